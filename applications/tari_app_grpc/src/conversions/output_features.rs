@@ -21,17 +21,182 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::tari_rpc as grpc;
-use std::convert::TryFrom;
-use tari_core::transactions::transaction::{OutputFeatures, OutputFlags};
+use std::convert::{TryFrom, TryInto};
+use tari_core::transactions::{
+    asset::AssetOutputFeatures,
+    htlc::HashTimeLock,
+    multisig::MultisigLock,
+    script::TariScript,
+    transaction::{OutputFeatures, OutputFeaturesVersion, OutputFlags, RangeProofType},
+    types::PublicKey,
+};
+use tari_crypto::tari_utilities::ByteArray;
 
 impl TryFrom<grpc::OutputFeatures> for OutputFeatures {
     type Error = String;
 
     fn try_from(features: grpc::OutputFeatures) -> Result<Self, Self::Error> {
+        let version = u8::try_from(features.version)
+            .map_err(|_| format!("Unsupported output features version {}", features.version))
+            .map(OutputFeaturesVersion::from)?;
+        if version < OutputFeaturesVersion::V1 && !features.coinbase_extra.is_empty() {
+            return Err(format!(
+                "Output features version {} cannot carry a coinbase extra",
+                version
+            ));
+        }
+        if version < OutputFeaturesVersion::V2 && features.htlc.is_some() {
+            return Err(format!(
+                "Output features version {} cannot carry a hash time lock",
+                version
+            ));
+        }
+        if version < OutputFeaturesVersion::V3 && features.multisig.is_some() {
+            return Err(format!(
+                "Output features version {} cannot carry a multisig lock",
+                version
+            ));
+        }
+        if version < OutputFeaturesVersion::V4 && !features.script.is_empty() {
+            return Err(format!("Output features version {} cannot carry a script", version));
+        }
+        let script = if features.script.is_empty() {
+            None
+        } else {
+            Some(TariScript::from_bytes(&features.script).map_err(|err| err.to_string())?)
+        };
+        let range_proof_type = u8::try_from(features.range_proof_type)
+            .map_err(|_| format!("Unsupported range proof type {}", features.range_proof_type))
+            .and_then(RangeProofType::try_from)?;
+        if version < OutputFeaturesVersion::V5 && range_proof_type != RangeProofType::BulletProof {
+            return Err(format!(
+                "Output features version {} cannot carry a {} range proof",
+                version, range_proof_type
+            ));
+        }
+
         Ok(Self {
+            version,
             flags: OutputFlags::from_bits(features.flags as u8)
                 .ok_or_else(|| "Invalid or unrecognised output flags".to_string())?,
             maturity: features.maturity,
+            relative_maturity: features.relative_maturity,
+            asset: features.asset.map(TryInto::try_into).transpose()?,
+            coinbase_extra: features.coinbase_extra,
+            htlc: features.htlc.map(TryInto::try_into).transpose()?,
+            multisig: features.multisig.map(TryInto::try_into).transpose()?,
+            script,
+            range_proof_type,
+        })
+    }
+}
+
+impl From<OutputFeatures> for grpc::OutputFeatures {
+    fn from(features: OutputFeatures) -> Self {
+        Self {
+            flags: features.flags.bits() as u32,
+            maturity: features.maturity,
+            relative_maturity: features.relative_maturity,
+            asset: features.asset.map(Into::into),
+            version: features.version.as_u8() as u32,
+            coinbase_extra: features.coinbase_extra,
+            htlc: features.htlc.map(Into::into),
+            multisig: features.multisig.map(Into::into),
+            script: features.script.map(|script| script.to_bytes()).unwrap_or_default(),
+            range_proof_type: features.range_proof_type.as_u8() as u32,
+        }
+    }
+}
+
+impl TryFrom<grpc::AssetOutputFeatures> for AssetOutputFeatures {
+    type Error = String;
+
+    fn try_from(asset: grpc::AssetOutputFeatures) -> Result<Self, Self::Error> {
+        let issuer = PublicKey::from_bytes(&asset.issuer).map_err(|_| "Could not get asset issuer".to_string())?;
+        let issuer_sig = asset.issuer_sig.map(TryInto::try_into).transpose()?;
+
+        Ok(Self {
+            issuer,
+            name: asset.name,
+            token_id: asset.token_id,
+            issuer_sig,
         })
     }
 }
+
+impl From<AssetOutputFeatures> for grpc::AssetOutputFeatures {
+    fn from(asset: AssetOutputFeatures) -> Self {
+        Self {
+            issuer: asset.issuer.to_vec(),
+            name: asset.name,
+            token_id: asset.token_id,
+            issuer_sig: asset.issuer_sig.map(|sig| grpc::Signature {
+                public_nonce: sig.get_public_nonce().to_vec(),
+                signature: sig.get_signature().to_vec(),
+            }),
+        }
+    }
+}
+
+impl TryFrom<grpc::HashTimeLock> for HashTimeLock {
+    type Error = String;
+
+    fn try_from(htlc: grpc::HashTimeLock) -> Result<Self, Self::Error> {
+        if htlc.hash_lock.len() != 32 {
+            return Err(format!(
+                "Hash lock must be 32 bytes, got {} bytes",
+                htlc.hash_lock.len()
+            ));
+        }
+        let mut hash_lock = [0u8; 32];
+        hash_lock.copy_from_slice(&htlc.hash_lock);
+        let claim_public_key =
+            PublicKey::from_bytes(&htlc.claim_public_key).map_err(|_| "Could not get HTLC claim key".to_string())?;
+        let refund_public_key =
+            PublicKey::from_bytes(&htlc.refund_public_key).map_err(|_| "Could not get HTLC refund key".to_string())?;
+
+        Ok(Self {
+            hash_lock,
+            claim_public_key,
+            refund_public_key,
+            refund_height: htlc.refund_height,
+        })
+    }
+}
+
+impl From<HashTimeLock> for grpc::HashTimeLock {
+    fn from(htlc: HashTimeLock) -> Self {
+        Self {
+            hash_lock: htlc.hash_lock.to_vec(),
+            claim_public_key: htlc.claim_public_key.to_vec(),
+            refund_public_key: htlc.refund_public_key.to_vec(),
+            refund_height: htlc.refund_height,
+        }
+    }
+}
+
+impl TryFrom<grpc::MultisigLock> for MultisigLock {
+    type Error = String;
+
+    fn try_from(lock: grpc::MultisigLock) -> Result<Self, Self::Error> {
+        let threshold =
+            u8::try_from(lock.threshold).map_err(|_| format!("Invalid multisig threshold {}", lock.threshold))?;
+        let public_keys = lock
+            .public_keys
+            .iter()
+            .map(|k| PublicKey::from_bytes(k).map_err(|_| "Could not get multisig participant key".to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let lock = Self { threshold, public_keys };
+        lock.validate().map_err(|err| err.to_string())?;
+        Ok(lock)
+    }
+}
+
+impl From<MultisigLock> for grpc::MultisigLock {
+    fn from(lock: MultisigLock) -> Self {
+        Self {
+            threshold: lock.threshold as u32,
+            public_keys: lock.public_keys.iter().map(|k| k.to_vec()).collect(),
+        }
+    }
+}
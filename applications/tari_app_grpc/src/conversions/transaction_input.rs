@@ -22,7 +22,14 @@
 
 use crate::tari_rpc as grpc;
 use std::convert::{TryFrom, TryInto};
-use tari_core::transactions::{transaction::TransactionInput, types::Commitment};
+use tari_core::transactions::{
+    covenant::Covenant,
+    htlc::HtlcWitness,
+    multisig::MultisigWitness,
+    script::ScriptWitness,
+    transaction::TransactionInput,
+    types::Commitment,
+};
 use tari_crypto::tari_utilities::{ByteArray, Hashable};
 
 impl TryFrom<grpc::TransactionInput> for TransactionInput {
@@ -37,7 +44,23 @@ impl TryFrom<grpc::TransactionInput> for TransactionInput {
         let commitment = Commitment::from_bytes(&input.commitment)
             .map_err(|err| format!("Could not convert input commitment:{}", err))?;
 
-        Ok(Self { features, commitment })
+        let covenant =
+            Covenant::from_bytes(&input.covenant).map_err(|err| format!("Could not convert input covenant:{}", err))?;
+
+        let htlc_witness = input.htlc_witness.map(TryInto::try_into).transpose()?;
+
+        let multisig_witness = input.multisig_witness.map(TryInto::try_into).transpose()?;
+
+        let script_witness = input.script_witness.map(TryInto::try_into).transpose()?;
+
+        Ok(Self {
+            features,
+            commitment,
+            covenant,
+            htlc_witness,
+            multisig_witness,
+            script_witness,
+        })
     }
 }
 
@@ -45,12 +68,117 @@ impl From<TransactionInput> for grpc::TransactionInput {
     fn from(input: TransactionInput) -> Self {
         let hash = input.hash();
         Self {
-            features: Some(grpc::OutputFeatures {
-                flags: input.features.flags.bits() as u32,
-                maturity: input.features.maturity,
-            }),
+            features: Some(input.features.clone().into()),
             commitment: Vec::from(input.commitment.as_bytes()),
             hash,
+            covenant: input.covenant.to_bytes(),
+            htlc_witness: input.htlc_witness.map(Into::into),
+            multisig_witness: input.multisig_witness.map(Into::into),
+            script_witness: input.script_witness.map(Into::into),
+        }
+    }
+}
+
+impl TryFrom<grpc::HtlcWitness> for HtlcWitness {
+    type Error = String;
+
+    fn try_from(witness: grpc::HtlcWitness) -> Result<Self, Self::Error> {
+        let kernel_excess = Commitment::from_bytes(&witness.kernel_excess)
+            .map_err(|err| format!("Could not convert HTLC kernel excess:{}", err))?;
+        let signature = witness
+            .signature
+            .map(TryInto::try_into)
+            .ok_or_else(|| "HTLC witness signature not provided".to_string())??;
+
+        if witness.preimage.is_empty() {
+            Ok(HtlcWitness::Refund {
+                kernel_excess,
+                signature,
+            })
+        } else {
+            Ok(HtlcWitness::Claim {
+                preimage: witness.preimage,
+                kernel_excess,
+                signature,
+            })
+        }
+    }
+}
+
+impl From<HtlcWitness> for grpc::HtlcWitness {
+    fn from(witness: HtlcWitness) -> Self {
+        let (preimage, kernel_excess, signature) = match witness {
+            HtlcWitness::Claim {
+                preimage,
+                kernel_excess,
+                signature,
+            } => (preimage, kernel_excess, signature),
+            HtlcWitness::Refund {
+                kernel_excess,
+                signature,
+            } => (Vec::new(), kernel_excess, signature),
+        };
+        Self {
+            preimage,
+            kernel_excess: kernel_excess.to_vec(),
+            signature: Some(grpc::Signature {
+                public_nonce: signature.get_public_nonce().to_vec(),
+                signature: signature.get_signature().to_vec(),
+            }),
+        }
+    }
+}
+
+impl TryFrom<grpc::MultisigWitness> for MultisigWitness {
+    type Error = String;
+
+    fn try_from(witness: grpc::MultisigWitness) -> Result<Self, Self::Error> {
+        let kernel_excess = Commitment::from_bytes(&witness.kernel_excess)
+            .map_err(|err| format!("Could not convert multisig kernel excess:{}", err))?;
+        let signature = witness
+            .signature
+            .map(TryInto::try_into)
+            .ok_or_else(|| "Multisig witness signature not provided".to_string())??;
+
+        Ok(Self {
+            signers: witness.signers,
+            kernel_excess,
+            signature,
+        })
+    }
+}
+
+impl From<MultisigWitness> for grpc::MultisigWitness {
+    fn from(witness: MultisigWitness) -> Self {
+        Self {
+            signers: witness.signers,
+            kernel_excess: witness.kernel_excess.to_vec(),
+            signature: Some(grpc::Signature {
+                public_nonce: witness.signature.get_public_nonce().to_vec(),
+                signature: witness.signature.get_signature().to_vec(),
+            }),
+        }
+    }
+}
+
+impl TryFrom<grpc::ScriptWitness> for ScriptWitness {
+    type Error = String;
+
+    fn try_from(witness: grpc::ScriptWitness) -> Result<Self, Self::Error> {
+        let kernel_excess = Commitment::from_bytes(&witness.kernel_excess)
+            .map_err(|err| format!("Could not convert script kernel excess:{}", err))?;
+        let stack = ScriptWitness::stack_from_bytes(&witness.stack)
+            .map_err(|err| format!("Could not convert script witness stack:{}", err))?;
+
+        Ok(Self { kernel_excess, stack })
+    }
+}
+
+impl From<ScriptWitness> for grpc::ScriptWitness {
+    fn from(witness: ScriptWitness) -> Self {
+        Self {
+            stack: witness.stack_to_bytes(),
+            kernel_excess: witness.kernel_excess.to_vec(),
         }
     }
 }
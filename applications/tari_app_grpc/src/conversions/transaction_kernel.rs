@@ -49,6 +49,7 @@ impl TryFrom<grpc::TransactionKernel> for TransactionKernel {
             excess_sig,
             fee: MicroTari::from(kernel.fee),
             lock_height: kernel.lock_height,
+            burn_amount: MicroTari::from(kernel.burn_amount),
         })
     }
 }
@@ -61,6 +62,7 @@ impl From<TransactionKernel> for grpc::TransactionKernel {
             features: kernel.features.bits() as u32,
             fee: kernel.fee.0,
             lock_height: kernel.lock_height,
+            burn_amount: kernel.burn_amount.0,
             excess: Vec::from(kernel.excess.as_bytes()),
             excess_sig: Some(grpc::Signature {
                 public_nonce: Vec::from(kernel.excess_sig.get_public_nonce().as_bytes()),
@@ -24,6 +24,7 @@ use crate::tari_rpc as grpc;
 use std::convert::{TryFrom, TryInto};
 use tari_core::transactions::{
     bullet_rangeproofs::BulletRangeProof,
+    covenant::Covenant,
     transaction::TransactionOutput,
     types::Commitment,
 };
@@ -40,9 +41,12 @@ impl TryFrom<grpc::TransactionOutput> for TransactionOutput {
 
         let commitment = Commitment::from_bytes(&output.commitment)
             .map_err(|err| format!("Invalid output commitment: {}", err.to_string()))?;
+        let covenant = Covenant::from_bytes(&output.covenant)
+            .map_err(|err| format!("Invalid output covenant: {}", err.to_string()))?;
         Ok(Self {
             features,
             commitment,
+            covenant,
             proof: BulletRangeProof(output.range_proof),
         })
     }
@@ -53,12 +57,10 @@ impl From<TransactionOutput> for grpc::TransactionOutput {
         let hash = output.hash();
         grpc::TransactionOutput {
             hash,
-            features: Some(grpc::OutputFeatures {
-                flags: output.features.flags.bits() as u32,
-                maturity: output.features.maturity,
-            }),
+            features: Some(output.features.clone().into()),
             commitment: Vec::from(output.commitment.as_bytes()),
             range_proof: Vec::from(output.proof.as_bytes()),
+            covenant: output.covenant.to_bytes(),
         }
     }
 }
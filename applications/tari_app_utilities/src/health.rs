@@ -0,0 +1,121 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+
+//! Liveness and readiness reporting shared by the applications. The status is served over HTTP on `/health` and
+//! `/ready`, so that the applications can be supervised by systemd, container orchestrators and load balancers.
+
+use futures::future;
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body,
+    Method,
+    Request,
+    Response,
+    Server,
+    StatusCode,
+};
+use log::*;
+use std::{
+    convert::Infallible,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+pub const LOG_TARGET: &str = "tari::application::health";
+
+/// The health of an application. `/health` reports whether the application is alive, i.e. not shutting down, and
+/// `/ready` whether it is also ready to do its work, e.g. the base node is synced.
+#[derive(Clone, Default)]
+pub struct HealthStatus {
+    ready: Arc<AtomicBool>,
+    shutting_down: Arc<AtomicBool>,
+}
+
+impl HealthStatus {
+    pub fn set_ready(&self, ready: bool) {
+        self.ready.store(ready, Ordering::SeqCst);
+    }
+
+    /// Record that the application has started to shut down. It is neither alive nor ready from then on.
+    pub fn set_shutting_down(&self) {
+        self.shutting_down.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_live(&self) -> bool {
+        !self.shutting_down.load(Ordering::SeqCst)
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.is_live() && self.ready.load(Ordering::SeqCst)
+    }
+
+    /// The response to a health check at `path`, or `None` if `path` is not a health check. This allows applications
+    /// that already serve HTTP to serve the health checks as well.
+    pub fn respond(&self, path: &str) -> Option<Response<Body>> {
+        let (healthy, unhealthy_message) = match path {
+            "/health" => (self.is_live(), "shutting down"),
+            "/ready" => (self.is_ready(), "not ready"),
+            _ => return None,
+        };
+        let (status, message) = if healthy {
+            (StatusCode::OK, "ok")
+        } else {
+            (StatusCode::SERVICE_UNAVAILABLE, unhealthy_message)
+        };
+        Some(
+            Response::builder()
+                .status(status)
+                .body(Body::from(message))
+                .expect("Response is valid"),
+        )
+    }
+
+    fn respond_to(&self, request: &Request<Body>) -> Response<Body> {
+        match (request.method(), self.respond(request.uri().path())) {
+            (&Method::GET, Some(response)) => response,
+            _ => Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .body(Body::from("Not found"))
+                .expect("Response is valid"),
+        }
+    }
+}
+
+/// Serve the health checks of `status` over HTTP at `addr`
+pub async fn serve(addr: SocketAddr, status: HealthStatus) -> Result<(), hyper::Error> {
+    let service = make_service_fn(move |_conn| {
+        let status = status.clone();
+        future::ready(Ok::<_, Infallible>(service_fn(move |request| {
+            future::ready(Ok::<_, Infallible>(status.respond_to(&request)))
+        })))
+    });
+    let server = Server::try_bind(&addr)?.serve(service);
+    info!(
+        target: LOG_TARGET,
+        "Serving health checks on http://{}/health and /ready", addr
+    );
+    server.await
+}
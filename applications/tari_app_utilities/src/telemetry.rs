@@ -0,0 +1,69 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+
+//! Optional export of `tracing` spans to an OpenTelemetry collector over OTLP, so that the key flows of an application
+//! (e.g. the transaction send protocol, block sync and RPC calls) can be traced end-to-end.
+
+use crate::utilities::ExitCodes;
+use log::*;
+use opentelemetry::{
+    sdk::{trace, Resource},
+    KeyValue,
+};
+use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+pub const LOG_TARGET: &str = "tari::application::telemetry";
+
+/// Keeps the OTLP exporter installed. Pending spans are flushed when this is dropped, so it should be held for the
+/// lifetime of the application.
+pub struct TelemetryGuard {
+    _uninstall: opentelemetry_otlp::Uninstall,
+}
+
+/// Install an OTLP exporter for the spans of this application if an `otlp_endpoint` is configured. Spans are tagged
+/// with the given `service_name`.
+pub fn init_telemetry(service_name: &str, otlp_endpoint: Option<&str>) -> Result<Option<TelemetryGuard>, ExitCodes> {
+    let endpoint = match otlp_endpoint {
+        Some(endpoint) => endpoint,
+        None => return Ok(None),
+    };
+
+    let (tracer, uninstall) = opentelemetry_otlp::new_pipeline()
+        .with_endpoint(endpoint)
+        .with_trace_config(trace::config().with_resource(Resource::new(vec![KeyValue::new(
+            "service.name",
+            service_name.to_string(),
+        )])))
+        .install()
+        .map_err(|err| ExitCodes::ConfigError(format!("Could not install the OTLP exporter: {}", err)))?;
+
+    let subscriber = Registry::default().with(tracing_opentelemetry::layer().with_tracer(tracer));
+    tracing::subscriber::set_global_default(subscriber)
+        .map_err(|err| ExitCodes::ConfigError(format!("Could not install the tracing subscriber: {}", err)))?;
+
+    info!(
+        target: LOG_TARGET,
+        "Exporting traces for {} to the OTLP collector at {}", service_name, endpoint
+    );
+    Ok(Some(TelemetryGuard { _uninstall: uninstall }))
+}
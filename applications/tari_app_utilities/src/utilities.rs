@@ -21,7 +21,7 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::identity_management::load_from_json;
-use futures::future::Either;
+use futures::{future, future::Either};
 use log::*;
 use std::{net::SocketAddr, path::Path};
 use tari_common::{CommsTransport, GlobalConfig, SocksAuthentication, TorControlAuthentication};
@@ -176,6 +176,7 @@ pub fn setup_wallet_transport_type(config: &GlobalConfig) -> TransportType {
         CommsTransport::TorHiddenService {
             control_server_address,
             socks_address_override,
+            socks_auth,
             auth,
             ..
         } => {
@@ -212,7 +213,7 @@ pub fn setup_wallet_transport_type(config: &GlobalConfig) -> TransportType {
                 identity: identity.map(Box::new),
                 port_mapping,
                 socks_address_override,
-                socks_auth: socks::Authentication::None,
+                socks_auth: convert_socks_authentication(socks_auth),
             })
         },
         CommsTransport::Socks5 {
@@ -241,6 +242,7 @@ pub fn convert_socks_authentication(auth: SocksAuthentication) -> socks::Authent
         SocksAuthentication::UsernamePassword(username, password) => {
             socks::Authentication::Password(username, password)
         },
+        SocksAuthentication::StreamIsolation => socks::Authentication::StreamIsolation,
     }
 }
 
@@ -276,6 +278,31 @@ pub fn setup_runtime(config: &GlobalConfig) -> Result<Runtime, String> {
         .map_err(|e| format!("There was an error while building the node runtime. {}", e.to_string()))
 }
 
+/// Wait until the application is asked to terminate, i.e. it receives SIGTERM (e.g. from systemd or a container
+/// runtime) or SIGINT (Ctrl+C)
+pub async fn wait_for_termination() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                let sigterm = sigterm.recv();
+                let ctrl_c = tokio::signal::ctrl_c();
+                futures::pin_mut!(sigterm, ctrl_c);
+                future::select(sigterm, ctrl_c).await;
+                info!(target: LOG_TARGET, "Termination signal received");
+                return;
+            },
+            Err(err) => warn!(target: LOG_TARGET, "Could not listen for SIGTERM: {}", err),
+        }
+    }
+    if let Err(err) = tokio::signal::ctrl_c().await {
+        warn!(target: LOG_TARGET, "Could not listen for Ctrl+C: {}", err);
+        future::pending::<()>().await;
+    }
+    info!(target: LOG_TARGET, "Termination signal received");
+}
+
 /// Returns a CommsPublicKey from either a emoji id or a public key
 pub fn parse_emoji_id_or_public_key(key: &str) -> Option<CommsPublicKey> {
     EmojiId::str_to_pubkey(&key.trim().replace('|', ""))
@@ -0,0 +1,315 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Runs the wallet over Tor as set in the `[wallet.tor]` config. The wallet creates its hidden service through the
+//! Tor control port, launching Tor itself if nothing is listening on the control port and it is configured to, and
+//! otherwise falls back to only connecting out through the Tor SOCKS5 proxy. A launched Tor process authenticates its
+//! control port with a cookie file in its data directory, so no password is passed on its command line.
+
+use crate::utilities::{convert_socks_authentication, ExitCodes};
+use futures::{future, future::Either, pin_mut};
+use log::*;
+use std::{
+    fs,
+    net::SocketAddr,
+    process::Stdio,
+    time::{Duration, Instant},
+};
+use tari_common::{TorControlAuthentication, WalletTorConfig};
+use tari_comms::{multiaddr::Multiaddr, tor, transports::SocksConfig, utils::multiaddr::multiaddr_to_socketaddr};
+use tari_core::tari_utilities::hex;
+use tari_p2p::transport::{TorConfig, TransportType};
+use tari_shutdown::ShutdownSignal;
+use tokio::{net::TcpStream, process::Command, time::delay_for};
+
+const LOG_TARGET: &str = "tari::application::wallet_tor";
+
+/// How long a launched Tor process has to open its control port
+const TOR_LAUNCH_TIMEOUT: Duration = Duration::from_secs(30);
+/// The SOCKS5 port that Tor listens on by default
+const DEFAULT_SOCKS_ADDRESS: &str = "/ip4/127.0.0.1/tcp/9050";
+/// The name of the control port cookie file of a launched Tor process, in its data directory
+const COOKIE_FILE_NAME: &str = "control_auth_cookie";
+
+/// Creates the transport type of the wallet from its `[wallet.tor]` config. A Tor process launched by this function
+/// is stopped when the shutdown signal is triggered.
+pub async fn setup_wallet_tor_transport(
+    config: &WalletTorConfig,
+    shutdown_signal: ShutdownSignal,
+) -> Result<TransportType, ExitCodes>
+{
+    let control_address = multiaddr_to_socketaddr(&config.control_address).map_err(|e| {
+        ExitCodes::ConfigError(format!(
+            "Invalid Tor control address '{}': {}",
+            config.control_address, e
+        ))
+    })?;
+
+    if is_listening(control_address).await {
+        info!(target: LOG_TARGET, "Using the Tor control port at {}", control_address);
+        return Ok(hidden_service_transport(
+            config,
+            control_authentication(&config.control_auth),
+        ));
+    }
+
+    if config.launch {
+        launch_tor(config, control_address, shutdown_signal).await?;
+        if wait_until_listening(control_address, TOR_LAUNCH_TIMEOUT).await {
+            info!(
+                target: LOG_TARGET,
+                "Using the Tor control port at {} of the launched Tor process", control_address
+            );
+            let cookie = read_control_cookie(config)?;
+            return Ok(hidden_service_transport(config, tor::Authentication::Cookie(cookie)));
+        }
+        warn!(
+            target: LOG_TARGET,
+            "The launched Tor process did not open its control port at {} within {:.0?}",
+            control_address,
+            TOR_LAUNCH_TIMEOUT
+        );
+    }
+
+    if !config.socks5_fallback {
+        return Err(ExitCodes::NetworkError(format!(
+            "Could not connect to the Tor control port at {}",
+            control_address
+        )));
+    }
+
+    let proxy_address = socks_address(config);
+    warn!(
+        target: LOG_TARGET,
+        "Could not connect to the Tor control port at {}, so the wallet only connects out through the Tor SOCKS5 \
+         proxy at {} and cannot be reached by other wallets directly",
+        control_address,
+        proxy_address
+    );
+    Ok(TransportType::Socks {
+        socks_config: SocksConfig {
+            proxy_address,
+            authentication: convert_socks_authentication(config.socks_auth.clone()),
+        },
+        // Peers cannot reach this listener, which only exists because the comms stack requires one
+        listener_address: "/ip4/127.0.0.1/tcp/0".parse().unwrap(),
+    })
+}
+
+fn hidden_service_transport(config: &WalletTorConfig, control_server_auth: tor::Authentication) -> TransportType {
+    // The same onion port and an OS-assigned forwarding port as every other wallet, so that wallets cannot be told
+    // apart by their ports
+    let port_mapping = (18101u16, "127.0.0.1:0".parse::<SocketAddr>().unwrap()).into();
+    TransportType::Tor(TorConfig {
+        control_server_addr: config.control_address.clone(),
+        control_server_auth,
+        identity: None,
+        port_mapping,
+        socks_address_override: config.socks_address.clone(),
+        socks_auth: convert_socks_authentication(config.socks_auth.clone()),
+    })
+}
+
+fn control_authentication(control_auth: &TorControlAuthentication) -> tor::Authentication {
+    match control_auth.clone() {
+        TorControlAuthentication::None => tor::Authentication::None,
+        TorControlAuthentication::Password(password) => tor::Authentication::HashedPassword(password),
+    }
+}
+
+/// The hex encoded contents of the control port cookie file that a launched Tor process writes
+fn read_control_cookie(config: &WalletTorConfig) -> Result<String, ExitCodes> {
+    let cookie_path = config.data_dir.join(COOKIE_FILE_NAME);
+    let cookie = fs::read(&cookie_path).map_err(|e| {
+        ExitCodes::IOError(format!(
+            "Could not read the Tor control port cookie '{}': {}",
+            cookie_path.display(),
+            e
+        ))
+    })?;
+    Ok(hex::to_hex(&cookie))
+}
+
+fn socks_address(config: &WalletTorConfig) -> Multiaddr {
+    config
+        .socks_address
+        .clone()
+        .unwrap_or_else(|| DEFAULT_SOCKS_ADDRESS.parse().unwrap())
+}
+
+async fn is_listening(address: SocketAddr) -> bool {
+    TcpStream::connect(address).await.is_ok()
+}
+
+async fn wait_until_listening(address: SocketAddr, timeout: Duration) -> bool {
+    let started = Instant::now();
+    while started.elapsed() < timeout {
+        if is_listening(address).await {
+            return true;
+        }
+        delay_for(Duration::from_millis(500)).await;
+    }
+    false
+}
+
+/// Launch Tor with its control port on the control address, authenticated with a cookie file that only the user of the
+/// wallet can read, and stop it when the shutdown signal is triggered
+async fn launch_tor(
+    config: &WalletTorConfig,
+    control_address: SocketAddr,
+    shutdown_signal: ShutdownSignal,
+) -> Result<(), ExitCodes>
+{
+    fs::create_dir_all(&config.data_dir).map_err(|e| {
+        ExitCodes::IOError(format!(
+            "Could not create the Tor data directory '{}': {}",
+            config.data_dir.display(),
+            e
+        ))
+    })?;
+    let socks_socket_address = multiaddr_to_socketaddr(&socks_address(config))
+        .map_err(|e| ExitCodes::ConfigError(format!("Invalid Tor SOCKS5 address: {}", e)))?;
+
+    let mut command = Command::new(&config.tor_executable);
+    command
+        .arg("--DataDirectory")
+        .arg(&config.data_dir)
+        .arg("--ControlPort")
+        .arg(control_address.to_string())
+        .arg("--SocksPort")
+        .arg(socks_socket_address.to_string())
+        .arg("--CookieAuthentication")
+        .arg("1")
+        .arg("--CookieAuthFile")
+        .arg(config.data_dir.join(COOKIE_FILE_NAME));
+    if let TorControlAuthentication::Password(_) = config.control_auth {
+        debug!(
+            target: LOG_TARGET,
+            "The control port password only applies to a Tor that is already running, the launched Tor process uses \
+             cookie authentication"
+        );
+    }
+
+    info!(
+        target: LOG_TARGET,
+        "Launching '{}' with its control port at {} and SOCKS5 proxy at {}",
+        config.tor_executable.display(),
+        control_address,
+        socks_socket_address
+    );
+    let child = command
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| {
+            ExitCodes::IOError(format!(
+                "Could not launch Tor '{}': {}",
+                config.tor_executable.display(),
+                e
+            ))
+        })?;
+
+    tokio::spawn(async move {
+        pin_mut!(child);
+        pin_mut!(shutdown_signal);
+        match future::select(child, shutdown_signal).await {
+            Either::Left((status, _)) => warn!(target: LOG_TARGET, "The launched Tor process exited: {:?}", status),
+            // Dropping the child process kills it
+            Either::Right(_) => info!(target: LOG_TARGET, "Stopping the launched Tor process"),
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::PathBuf;
+    use tari_common::SocksAuthentication;
+    use tari_shutdown::Shutdown;
+
+    fn config(control_address: &str) -> WalletTorConfig {
+        WalletTorConfig {
+            control_address: control_address.parse().unwrap(),
+            control_auth: TorControlAuthentication::None,
+            socks_address: Some("/ip4/127.0.0.1/tcp/19050".parse().unwrap()),
+            socks_auth: SocksAuthentication::None,
+            launch: false,
+            tor_executable: PathBuf::from("tor"),
+            data_dir: PathBuf::from("tor"),
+            socks5_fallback: true,
+        }
+    }
+
+    #[tokio_macros::test]
+    async fn falls_back_to_socks5_without_a_control_port() {
+        let shutdown = Shutdown::new();
+        let transport = setup_wallet_tor_transport(&config("/ip4/127.0.0.1/tcp/1"), shutdown.to_signal())
+            .await
+            .unwrap();
+        match transport {
+            TransportType::Socks { socks_config, .. } => {
+                assert_eq!(socks_config.proxy_address.to_string(), "/ip4/127.0.0.1/tcp/19050")
+            },
+            t => panic!("Unexpected transport {:?}", t),
+        }
+
+        let mut no_fallback = config("/ip4/127.0.0.1/tcp/1");
+        no_fallback.socks5_fallback = false;
+        assert!(setup_wallet_tor_transport(&no_fallback, shutdown.to_signal())
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn reads_the_control_cookie_as_hex() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let mut config = config("/ip4/127.0.0.1/tcp/1");
+        config.data_dir = data_dir.path().to_path_buf();
+        assert!(read_control_cookie(&config).is_err());
+
+        fs::write(data_dir.path().join(COOKIE_FILE_NAME), &[0x01, 0xab, 0xff]).unwrap();
+        assert_eq!(read_control_cookie(&config).unwrap(), "01abff");
+    }
+
+    #[tokio_macros::test]
+    async fn uses_a_listening_control_port() {
+        let shutdown = Shutdown::new();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let transport = setup_wallet_tor_transport(
+            &config(&format!("/ip4/127.0.0.1/tcp/{}", address.port())),
+            shutdown.to_signal(),
+        )
+        .await
+        .unwrap();
+        match transport {
+            TransportType::Tor(tor_config) => assert_eq!(
+                tor_config.control_server_addr.to_string(),
+                format!("/ip4/127.0.0.1/tcp/{}", address.port())
+            ),
+            t => panic!("Unexpected transport {:?}", t),
+        }
+    }
+}
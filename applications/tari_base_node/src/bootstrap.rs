@@ -28,7 +28,6 @@ use tari_common::{CommsTransport, GlobalConfig, TorControlAuthentication};
 use tari_comms::{
     peer_manager::Peer,
     protocol::rpc::RpcServer,
-    socks,
     tor,
     tor::TorIdentity,
     transports::SocksConfig,
@@ -57,7 +56,9 @@ use tari_core::{
         MempoolServiceInitializer,
         MempoolSyncInitializer,
     },
+    tari_utilities::hex::from_hex,
     transactions::types::CryptoFactories,
+    validation::block_validators::AssumeValid,
 };
 use tari_p2p::{
     comms_connector::pubsub_connector,
@@ -113,6 +114,14 @@ where B: BlockchainBackend + 'static
 
         debug!(target: LOG_TARGET, "{} sync peer(s) configured", sync_peers.len());
 
+        let assume_valid = match (config.assume_valid_height, config.assume_valid_hash.as_ref()) {
+            (Some(height), Some(hash)) => Some(AssumeValid {
+                height,
+                hash: from_hex(hash).map_err(|e| anyhow!("Invalid assume_valid_hash '{}': {}", hash, e))?,
+            }),
+            _ => None,
+        };
+
         let rules = self.rules.clone();
 
         let mempool_sync = MempoolSyncInitializer::new(mempool_config, self.mempool.clone());
@@ -148,6 +157,7 @@ where B: BlockchainBackend + 'static
                 BaseNodeStateMachineConfig {
                     block_sync_config: BlockSyncConfig {
                         sync_peers,
+                        assume_valid,
                         ..Default::default()
                     },
                     horizon_sync_config: HorizonSyncConfig {
@@ -157,6 +167,7 @@ where B: BlockchainBackend + 'static
                     pruning_horizon: config.pruning_horizon,
                     orphan_db_clean_out_threshold: config.orphan_db_clean_out_threshold,
                     max_randomx_vms: config.max_randomx_vms,
+                    randomx_mode: config.randomx_mode,
                     blocks_behind_before_considered_lagging: self.config.blocks_behind_before_considered_lagging,
                     ..Default::default()
                 },
@@ -240,6 +251,12 @@ where B: BlockchainBackend + 'static
                 network: self.config.network.into(),
                 flood_ban_max_msg_count: self.config.flood_ban_max_msg_count,
                 saf_msg_validity: self.config.saf_expiry_duration,
+                num_neighbouring_nodes: self.config.dht_num_neighbouring_nodes,
+                num_random_nodes: self.config.dht_num_random_nodes,
+                propagation_factor: self.config.dht_propagation_factor,
+                broadcast_factor: self.config.dht_broadcast_factor,
+                closest_min_additional_peers: self.config.dht_closest_min_additional_peers,
+                discovery_max_send_attempts: self.config.dht_discovery_max_send_attempts,
                 ..Default::default()
             },
             allow_test_addresses: self.config.allow_test_addresses,
@@ -257,6 +274,11 @@ where B: BlockchainBackend + 'static
             dns_seeds: self.config.dns_seeds.clone(),
             dns_seeds_name_server: self.config.dns_seeds_name_server,
             dns_seeds_use_dnssec: self.config.dns_seeds_use_dnssec,
+            outbound_only: false,
+            auto_update_public_address: self.config.auto_update_public_address,
+            allow_unsigned_identities: self.config.allow_unsigned_peer_identities,
+            noise_rekey_interval: self.config.noise_rekey_interval.map(Duration::from_secs),
+            noise_rekey_max_bytes: self.config.noise_rekey_max_bytes,
         }
     }
 
@@ -288,6 +310,7 @@ where B: BlockchainBackend + 'static
             CommsTransport::TorHiddenService {
                 control_server_address,
                 socks_address_override,
+                socks_auth,
                 forward_address,
                 auth,
                 onion_port,
@@ -324,7 +347,7 @@ where B: BlockchainBackend + 'static
                     port_mapping: (onion_port, forward_addr).into(),
                     // TODO: make configurable
                     socks_address_override,
-                    socks_auth: socks::Authentication::None,
+                    socks_auth: utilities::convert_socks_authentication(socks_auth),
                 })
             },
             CommsTransport::Socks5 {
@@ -21,17 +21,20 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::bootstrap::BaseNodeBootstrapper;
+use anyhow::anyhow;
 use log::*;
-use std::sync::Arc;
+use std::{fs, sync::Arc};
 use tari_common::{DatabaseType, GlobalConfig};
 use tari_comms::{peer_manager::NodeIdentity, protocol::rpc::RpcServerHandle, CommsNode};
 use tari_comms_dht::Dht;
 use tari_core::{
     base_node::{state_machine_service::states::StatusInfo, LocalNodeCommsInterface, StateMachineHandle},
+    blocks::genesis_block_builder::{load_genesis_block, validate_genesis_block},
     chain_storage::{create_lmdb_database, BlockchainDatabase, BlockchainDatabaseConfig, LMDBDatabase, Validators},
-    consensus::ConsensusManagerBuilder,
+    consensus::{ConsensusManager, ConsensusManagerBuilder},
     mempool::{service::LocalMempoolService, Mempool, MempoolConfig},
     proof_of_work::randomx_factory::{RandomXConfig, RandomXFactory},
+    tari_utilities::hex::Hex,
     transactions::types::CryptoFactories,
     validation::{
         block_validators::{BodyOnlyValidator, OrphanBlockValidator},
@@ -56,6 +59,7 @@ const LOG_TARGET: &str = "c::bn::initialization";
 pub struct BaseNodeContext {
     config: Arc<GlobalConfig>,
     blockchain_db: BlockchainDatabase<LMDBDatabase>,
+    consensus_rules: ConsensusManager,
     base_node_comms: CommsNode,
     base_node_dht: Dht,
     base_node_handles: ServiceHandles,
@@ -121,6 +125,11 @@ impl BaseNodeContext {
         self.blockchain_db.clone()
     }
 
+    /// Returns the consensus rules of the network
+    pub fn consensus_rules(&self) -> ConsensusManager {
+        self.consensus_rules.clone()
+    }
+
     /// Return the state machine channel to provide info updates
     pub fn get_state_machine_info_channel(&self) -> watch::Receiver<StatusInfo> {
         self.base_node_handles
@@ -129,6 +138,40 @@ impl BaseNodeContext {
     }
 }
 
+/// Creates the consensus rules of the configured network, applying the custom emission schedule and genesis block if
+/// they are configured
+pub fn create_consensus_rules(config: &GlobalConfig) -> Result<ConsensusManager, anyhow::Error> {
+    let mut builder = ConsensusManagerBuilder::new(config.network.into());
+    if let Some(emission) = &config.emission_schedule {
+        builder = builder.with_emission_amounts(emission.initial.into(), &emission.decay, emission.tail.into());
+    }
+    let genesis_block = match &config.genesis_block_file {
+        Some(path) => {
+            let json = fs::read_to_string(path)
+                .map_err(|e| anyhow!("Could not read the genesis block file {}: {}", path.display(), e))?;
+            let file = load_genesis_block(&json)
+                .map_err(|e| anyhow!("Could not load the genesis block file {}: {}", path.display(), e))?;
+            let block = file
+                .to_chain_block()
+                .map_err(|e| anyhow!("Invalid genesis block in {}: {}", path.display(), e))?;
+            info!(target: LOG_TARGET, "Using the genesis block {}", block.hash().to_hex());
+            builder = builder.with_faucet_value(file.faucet_value).with_block(block);
+            Some(file.block)
+        },
+        None => None,
+    };
+    let rules = builder.build();
+    if let Some(block) = genesis_block {
+        validate_genesis_block(&block, &rules, &CryptoFactories::default()).map_err(|e| {
+            anyhow!(
+                "The genesis block file is not valid under the emission schedule of this node: {}",
+                e
+            )
+        })?;
+    }
+    Ok(rules)
+}
+
 /// Sets up and initializes the base node, creating the context and database
 /// ## Parameters
 /// `config` - The configuration for the base node
@@ -194,9 +237,9 @@ async fn build_node_context(
 {
     //---------------------------------- Blockchain --------------------------------------------//
 
-    let rules = ConsensusManagerBuilder::new(config.network.into()).build();
+    let rules = create_consensus_rules(&config)?;
     let factories = CryptoFactories::default();
-    let randomx_factory = RandomXFactory::new(RandomXConfig::default(), config.max_randomx_vms);
+    let randomx_factory = RandomXFactory::new(RandomXConfig::with_mode(config.randomx_mode), config.max_randomx_vms);
     let validators = Validators::new(
         BodyOnlyValidator::default(),
         HeaderValidator::new(rules.clone(), randomx_factory),
@@ -209,11 +252,12 @@ async fn build_node_context(
     };
     let blockchain_db = BlockchainDatabase::new(backend, &rules, validators, db_config, cleanup_orphans_at_startup)?;
     let mempool_validator = MempoolValidator::new(vec![
-        Box::new(TxInternalConsistencyValidator::new(factories.clone())),
         Box::new(TxInputAndMaturityValidator::new(blockchain_db.clone())),
         Box::new(TxConsensusValidator::new(blockchain_db.clone())),
     ]);
-    let mempool = Mempool::new(MempoolConfig::default(), Arc::new(mempool_validator));
+    // The internal consistency checks do not depend on the chain, so they are run outside of the mempool lock
+    let mempool = Mempool::new(MempoolConfig::default(), Arc::new(mempool_validator))
+        .with_stateless_validator(Arc::new(TxInternalConsistencyValidator::new(factories.clone())));
 
     //---------------------------------- Base Node  --------------------------------------------//
     debug!(target: LOG_TARGET, "Creating base node state machine.");
@@ -236,6 +280,7 @@ async fn build_node_context(
     Ok(BaseNodeContext {
         config,
         blockchain_db,
+        consensus_rules: rules,
         base_node_comms,
         base_node_dht,
         base_node_handles,
@@ -48,7 +48,7 @@ use tari_core::{
     },
     blocks::BlockHeader,
     chain_storage::{async_db::AsyncBlockchainDb, ChainHeader, LMDBDatabase},
-    consensus::{ConsensusManager, Network},
+    consensus::ConsensusManager,
     mempool::service::LocalMempoolService,
     proof_of_work::PowAlgorithm,
     tari_utilities::{hex::Hex, message_format::MessageFormat},
@@ -65,6 +65,7 @@ pub struct CommandHandler {
     executor: runtime::Handle,
     config: Arc<GlobalConfig>,
     blockchain_db: AsyncBlockchainDb<LMDBDatabase>,
+    consensus_rules: ConsensusManager,
     discovery_service: DhtDiscoveryRequester,
     dht_metrics_collector: MetricsCollectorHandle,
     rpc_server: RpcServerHandle,
@@ -82,6 +83,7 @@ impl CommandHandler {
             executor,
             config: ctx.config(),
             blockchain_db: ctx.blockchain_db().into(),
+            consensus_rules: ctx.consensus_rules(),
             discovery_service: ctx.base_node_dht().discovery_service_requester(),
             dht_metrics_collector: ctx.base_node_dht().metrics_collector(),
             rpc_server: ctx.rpc_server(),
@@ -918,6 +920,7 @@ impl CommandHandler {
     )
     {
         let db = self.blockchain_db.clone();
+        let consensus_rules = self.consensus_rules.clone();
         self.executor.spawn(async move {
             let mut output = try_or_print!(File::create(&filename));
 
@@ -933,8 +936,6 @@ impl CommandHandler {
 
             let start_height = cmp::max(start_height, 1);
             let mut prev_header = try_or_print!(db.fetch_chain_header(start_height - 1).await);
-            // TODO: hardcoded network #testnetreset
-            let consensus_rules = ConsensusManager::builder(Network::Stibbons).build();
 
             writeln!(
                 output,
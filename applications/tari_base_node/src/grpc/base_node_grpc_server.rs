@@ -23,11 +23,13 @@ use crate::grpc::{
     blocks::{block_fees, block_heights, block_size, GET_BLOCKS_MAX_HEIGHTS, GET_BLOCKS_PAGE_SIZE},
     helpers::{mean, median},
 };
+use futures::StreamExt;
 use log::*;
 use std::{
     cmp,
     convert::{TryFrom, TryInto},
     sync::Arc,
+    time::Duration,
 };
 
 use tari_app_grpc::{
@@ -37,20 +39,20 @@ use tari_app_grpc::{
 use tari_comms::PeerManager;
 use tari_core::{
     base_node::{
-        comms_interface::Broadcast,
+        comms_interface::{BlockEvent, Broadcast},
         state_machine_service::states::BlockSyncInfo,
         LocalNodeCommsInterface,
         StateMachineHandle,
     },
     blocks::{Block, BlockHeader, NewBlockTemplate},
-    consensus::{ConsensusManager, ConsensusManagerBuilder, Network},
+    consensus::ConsensusManager,
     crypto::tari_utilities::hex::Hex,
     mempool::{service::LocalMempoolService, TxStorageResponse},
     proof_of_work::PowAlgorithm,
     transactions::{transaction::Transaction, types::Signature},
 };
 use tari_crypto::tari_utilities::{message_format::MessageFormat, Hashable};
-use tokio::{sync::mpsc, task};
+use tokio::{sync::mpsc, task, time};
 use tonic::{Request, Response, Status};
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -71,11 +73,15 @@ const LIST_HEADERS_MAX_NUM_HEADERS: u64 = 10_000;
 const LIST_HEADERS_PAGE_SIZE: usize = 10;
 // The `num_headers` value if none is provided.
 const LIST_HEADERS_DEFAULT_NUM_HEADERS: u64 = 10;
+// The number of block templates that can be queued for a `StreamNewBlockTemplates` client. A slow client that falls
+// this far behind is only holding stale templates.
+const STREAM_NEW_BLOCK_TEMPLATES_BUFFER_SIZE: usize = 2;
+// The minimum time between new block templates that are produced because the mempool changed
+const STREAM_NEW_BLOCK_TEMPLATES_MEMPOOL_INTERVAL: Duration = Duration::from_secs(1);
 
 pub struct BaseNodeGrpcServer {
     node_service: LocalNodeCommsInterface,
     mempool_service: LocalMempoolService,
-    network: Network,
     state_machine_handle: StateMachineHandle,
     peer_manager: Arc<PeerManager>,
     consensus_rules: ConsensusManager,
@@ -85,7 +91,7 @@ impl BaseNodeGrpcServer {
     pub fn new(
         local_node: LocalNodeCommsInterface,
         local_mempool: LocalMempoolService,
-        network: Network,
+        consensus_rules: ConsensusManager,
         state_machine_handle: StateMachineHandle,
         peer_manager: Arc<PeerManager>,
     ) -> Self
@@ -93,8 +99,7 @@ impl BaseNodeGrpcServer {
         Self {
             node_service: local_node,
             mempool_service: local_mempool,
-            consensus_rules: ConsensusManager::builder(network).build(),
-            network,
+            consensus_rules,
             state_machine_handle,
             peer_manager,
         }
@@ -109,6 +114,51 @@ pub async fn get_heights(
     block_heights(handler, request.start_height, request.end_height, request.from_tip).await
 }
 
+fn parse_pow_algo(algo: Option<tari_rpc::PowAlgo>) -> Result<PowAlgorithm, Status> {
+    (algo
+        .ok_or_else(|| Status::invalid_argument("No valid pow algo selected".to_string()))?
+        .pow_algo as u64)
+        .try_into()
+        .map_err(|_| Status::invalid_argument("No valid pow algo selected".to_string()))
+}
+
+async fn fetch_new_block_template(
+    handler: &mut LocalNodeCommsInterface,
+    algo: PowAlgorithm,
+    max_weight: u64,
+) -> Result<NewBlockTemplate, Status>
+{
+    handler.get_new_block_template(algo, max_weight).await.map_err(|e| {
+        warn!(
+            target: LOG_TARGET,
+            "Could not get new block template: {}",
+            e.to_string()
+        );
+        Status::internal(e.to_string())
+    })
+}
+
+fn new_block_template_response(
+    new_template: NewBlockTemplate,
+    algo: PowAlgorithm,
+    state_machine_handle: &StateMachineHandle,
+) -> tari_rpc::NewBlockTemplateResponse
+{
+    let status_watch = state_machine_handle.get_status_info_watch();
+    let pow = algo as i32;
+    tari_rpc::NewBlockTemplateResponse {
+        miner_data: Some(tari_rpc::MinerData {
+            reward: new_template.reward.into(),
+            target_difficulty: new_template.target_difficulty.as_u64(),
+            total_fees: new_template.total_fees.into(),
+            algo: Some(tari_rpc::PowAlgo { pow_algo: pow }),
+        }),
+        new_block_template: Some(new_template.into()),
+
+        initial_sync_achieved: (*status_watch.borrow()).bootstrapped,
+    }
+}
+
 #[tonic::async_trait]
 impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
     type FetchMatchingUtxosStream = mpsc::Receiver<Result<tari_rpc::FetchMatchingUtxosResponse, Status>>;
@@ -119,6 +169,7 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
     type GetTokensInCirculationStream = mpsc::Receiver<Result<tari_rpc::ValueAtHeightResponse, Status>>;
     type ListHeadersStream = mpsc::Receiver<Result<tari_rpc::BlockHeader, Status>>;
     type SearchKernelsStream = mpsc::Receiver<Result<tari_rpc::HistoricalBlock, Status>>;
+    type StreamNewBlockTemplatesStream = mpsc::Receiver<Result<tari_rpc::NewBlockTemplateResponse, Status>>;
 
     async fn get_network_difficulty(
         &self,
@@ -401,43 +452,87 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
         let request = request.into_inner();
         debug!(target: LOG_TARGET, "Incoming GRPC request for get new block template");
         trace!(target: LOG_TARGET, "Request {:?}", request);
-        let algo: PowAlgorithm = ((request.algo)
-            .ok_or_else(|| Status::invalid_argument("No valid pow algo selected".to_string()))?
-            .pow_algo as u64)
-            .try_into()
-            .map_err(|_| Status::invalid_argument("No valid pow algo selected".to_string()))?;
+        let algo = parse_pow_algo(request.algo)?;
         let mut handler = self.node_service.clone();
 
-        let new_template = handler
-            .get_new_block_template(algo, request.max_weight)
-            .await
-            .map_err(|e| {
-                warn!(
-                    target: LOG_TARGET,
-                    "Could not get new block template: {}",
-                    e.to_string()
-                );
-                Status::internal(e.to_string())
-            })?;
-
-        let status_watch = self.state_machine_handle.get_status_info_watch();
-        let pow = algo as i32;
-        let response = tari_rpc::NewBlockTemplateResponse {
-            miner_data: Some(tari_rpc::MinerData {
-                reward: new_template.reward.into(),
-                target_difficulty: new_template.target_difficulty.as_u64(),
-                total_fees: new_template.total_fees.into(),
-                algo: Some(tari_rpc::PowAlgo { pow_algo: pow }),
-            }),
-            new_block_template: Some(new_template.into()),
-
-            initial_sync_achieved: (*status_watch.borrow()).bootstrapped,
-        };
+        let new_template = fetch_new_block_template(&mut handler, algo, request.max_weight).await?;
+        let response = new_block_template_response(new_template, algo, &self.state_machine_handle);
 
         debug!(target: LOG_TARGET, "Sending GetNewBlockTemplate response to client");
         Ok(Response::new(response))
     }
 
+    async fn stream_new_block_templates(
+        &self,
+        request: Request<tari_rpc::NewBlockTemplateRequest>,
+    ) -> Result<Response<Self::StreamNewBlockTemplatesStream>, Status>
+    {
+        let request = request.into_inner();
+        debug!(target: LOG_TARGET, "Incoming GRPC request for StreamNewBlockTemplates");
+        trace!(target: LOG_TARGET, "Request {:?}", request);
+        let algo = parse_pow_algo(request.algo)?;
+        let max_weight = request.max_weight;
+        let mut handler = self.node_service.clone();
+        let state_machine_handle = self.state_machine_handle.clone();
+        // Subscribe before fetching the first template so that no change in between is missed
+        let mut block_events = handler.get_block_event_stream().fuse();
+        let mut mempool_events = self.mempool_service.get_mempool_state_event_stream().fuse();
+        let (mut tx, rx) = mpsc::channel(STREAM_NEW_BLOCK_TEMPLATES_BUFFER_SIZE);
+
+        task::spawn(async move {
+            let mut mempool_refresh = time::interval(STREAM_NEW_BLOCK_TEMPLATES_MEMPOOL_INTERVAL).fuse();
+            let mut mempool_changed = false;
+            let mut last_template: Option<NewBlockTemplate> = None;
+            let mut refresh = true;
+            loop {
+                if refresh {
+                    refresh = false;
+                    let template = match fetch_new_block_template(&mut handler, algo, max_weight).await {
+                        Ok(template) => template,
+                        Err(status) => {
+                            let _ = tx.send(Err(status)).await;
+                            return;
+                        },
+                    };
+                    // The timestamp is only set when the block is constructed, so an unchanged template is equal to
+                    // the previous one and the miner can carry on with its current work
+                    if last_template.as_ref() != Some(&template) {
+                        last_template = Some(template.clone());
+                        let response = new_block_template_response(template, algo, &state_machine_handle);
+                        if tx.send(Ok(response)).await.is_err() {
+                            debug!(target: LOG_TARGET, "StreamNewBlockTemplates client disconnected");
+                            return;
+                        }
+                    }
+                }
+
+                futures::select! {
+                    event = block_events.next() => match event {
+                        Some(Ok(event)) => refresh = event.changes_tip(),
+                        // Events were missed, so the tip may have changed
+                        Some(Err(_)) => refresh = true,
+                        None => return,
+                    },
+                    event = mempool_events.next() => match event {
+                        Some(_) => mempool_changed = true,
+                        None => return,
+                    },
+                    // Mempool changes come in bursts, so they are coalesced into at most one new template per interval
+                    _ = mempool_refresh.select_next_some() => {
+                        refresh = mempool_changed;
+                        mempool_changed = false;
+                    },
+                }
+            }
+        });
+
+        debug!(
+            target: LOG_TARGET,
+            "Sending StreamNewBlockTemplates response stream to client"
+        );
+        Ok(Response::new(rx))
+    }
+
     async fn get_new_block(
         &self,
         request: Request<tari_rpc::NewBlockTemplate>,
@@ -531,7 +626,8 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
             },
             TxStorageResponse::NotStored |
             TxStorageResponse::NotStoredOrphan |
-            TxStorageResponse::NotStoredTimeLocked => tari_rpc::SubmitTransactionResponse {
+            TxStorageResponse::NotStoredTimeLocked |
+            TxStorageResponse::NotStoredFeeTooLow => tari_rpc::SubmitTransactionResponse {
                 result: tari_rpc::SubmitTransactionResult::Rejected.into(),
             },
         };
@@ -598,7 +694,8 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
             },
             TxStorageResponse::NotStored |
             TxStorageResponse::NotStoredOrphan |
-            TxStorageResponse::NotStoredTimeLocked => tari_rpc::TransactionStateResponse {
+            TxStorageResponse::NotStoredTimeLocked |
+            TxStorageResponse::NotStoredFeeTooLow => tari_rpc::TransactionStateResponse {
                 result: tari_rpc::TransactionLocation::NotStored.into(),
             },
         };
@@ -880,11 +977,18 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
     ) -> Result<Response<tari_rpc::ConsensusConstants>, Status>
     {
         debug!(target: LOG_TARGET, "Incoming GRPC request for GetConstants",);
+        let mut handler = self.node_service.clone();
+        let meta = handler
+            .get_metadata()
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        // The constants that apply to the next block, so that wallets use the active transaction weight formula
+        let constants = self
+            .consensus_rules
+            .consensus_constants(meta.height_of_longest_chain() + 1)
+            .clone();
         debug!(target: LOG_TARGET, "Sending GetConstants response to client");
-        // TODO: Switch to request height
-        Ok(Response::new(
-            self.network.create_consensus_constants().pop().unwrap().into(),
-        ))
+        Ok(Response::new(constants.into()))
     }
 
     async fn get_block_size(
@@ -918,7 +1022,7 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
         heights = heights
             .drain(..cmp::min(heights.len(), GET_TOKENS_IN_CIRCULATION_MAX_HEIGHTS))
             .collect();
-        let consensus_manager = ConsensusManagerBuilder::new(self.network).build();
+        let consensus_manager = self.consensus_rules.clone();
 
         let (mut tx, rx) = mpsc::channel(GET_TOKENS_IN_CIRCULATION_PAGE_SIZE);
         task::spawn(async move {
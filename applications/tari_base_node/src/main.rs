@@ -95,26 +95,35 @@ mod grpc;
 mod parser;
 mod recovery;
 mod status_line;
+mod stratum;
 mod utils;
 
-use crate::command_handler::CommandHandler;
+use crate::{
+    builder::BaseNodeContext,
+    command_handler::CommandHandler,
+    stratum::{StratumConfig, StratumServer},
+};
 use futures::{pin_mut, FutureExt};
 use log::*;
 use parser::Parser;
 use rustyline::{config::OutputStreamType, error::ReadlineError, CompletionType, Config, EditMode, Editor};
 use std::{
+    future::Future,
     net::SocketAddr,
     process,
     sync::Arc,
     time::{Duration, Instant},
 };
 use tari_app_utilities::{
+    health::{self, HealthStatus},
     identity_management::setup_node_identity,
     initialization::init_configuration,
-    utilities::{setup_runtime, ExitCodes},
+    telemetry::init_telemetry,
+    utilities::{setup_runtime, wait_for_termination, ExitCodes},
 };
-use tari_common::{configuration::bootstrap::ApplicationType, ConfigBootstrap, GlobalConfig};
+use tari_common::{configuration::bootstrap::ApplicationType, ConfigBootstrap, DatabaseType, GlobalConfig};
 use tari_comms::peer_manager::PeerFeatures;
+use tari_core::chain_storage::compact_lmdb_database;
 use tari_shutdown::{Shutdown, ShutdownSignal};
 use tokio::{runtime, task, time};
 use tonic::transport::Server;
@@ -139,6 +148,8 @@ fn main_inner() -> Result<(), ExitCodes> {
 
     debug!(target: LOG_TARGET, "Using configuration: {:?}", node_config);
 
+    let _telemetry = init_telemetry("tari_base_node", node_config.base_node_otlp_endpoint.as_deref())?;
+
     // Set up the Tokio runtime
     let mut rt = setup_runtime(&node_config).map_err(|e| {
         error!(target: LOG_TARGET, "{}", e);
@@ -186,6 +197,14 @@ async fn run_node(node_config: Arc<GlobalConfig>, bootstrap: ConfigBootstrap) ->
         return Ok(());
     }
 
+    if bootstrap.compact_db {
+        if let DatabaseType::LMDB(path) = &node_config.db_type {
+            info!(target: LOG_TARGET, "Compacting the blockchain database");
+            compact_lmdb_database(path, node_config.db_config.clone())
+                .map_err(|e| ExitCodes::IOError(format!("Could not compact the blockchain database: {}", e)))?;
+        }
+    }
+
     // Build, node, build!
     let ctx = builder::configure_and_initialize_node(
         node_config.clone(),
@@ -204,7 +223,7 @@ async fn run_node(node_config: Arc<GlobalConfig>, bootstrap: ConfigBootstrap) ->
         let grpc = crate::grpc::base_node_grpc_server::BaseNodeGrpcServer::new(
             ctx.local_node(),
             ctx.local_mempool(),
-            node_config.network.into(),
+            ctx.consensus_rules(),
             ctx.state_machine(),
             ctx.base_node_comms().peer_manager(),
         );
@@ -212,11 +231,27 @@ async fn run_node(node_config: Arc<GlobalConfig>, bootstrap: ConfigBootstrap) ->
         task::spawn(run_grpc(grpc, node_config.grpc_base_node_address, shutdown.to_signal()));
     }
 
+    if node_config.stratum_enabled {
+        let stratum = StratumServer::new(StratumConfig::from_global_config(&node_config), ctx.local_node());
+        task::spawn(run_stratum(stratum, shutdown.to_signal()));
+    }
+
+    if let Some(health_address) = node_config.base_node_health_address {
+        let health = HealthStatus::default();
+        task::spawn(track_health(health.clone(), &ctx, shutdown.to_signal()));
+        task::spawn(async move {
+            if let Err(err) = health::serve(health_address, health).await {
+                error!(target: LOG_TARGET, "Health check server encountered an error: {}", err);
+            }
+        });
+    }
+
     // Run, node, run!
     // TODO: We are not starting a background process/daemon. Either we should do that or call this mode
     //       `--non-interactive`
     if bootstrap.daemon_mode {
         println!("Node started in daemon mode (pid = {})", process::id());
+        task::spawn(shutdown_on_termination(shutdown));
     } else {
         let command_handler = Arc::new(CommandHandler::new(runtime::Handle::current(), &ctx));
         let parser = Parser::new(command_handler);
@@ -236,6 +271,42 @@ async fn run_node(node_config: Arc<GlobalConfig>, bootstrap: ConfigBootstrap) ->
     Ok(())
 }
 
+/// Reports the base node as ready while it is synced with the network, and as not alive once it starts shutting down
+fn track_health(
+    health: HealthStatus,
+    ctx: &BaseNodeContext,
+    mut shutdown_signal: ShutdownSignal,
+) -> impl Future<Output = ()>
+{
+    let mut status_watch = ctx.state_machine().get_status_info_watch();
+    async move {
+        loop {
+            futures::select! {
+                status = status_watch.recv().fuse() => match status {
+                    Some(status) => health.set_ready(status.bootstrapped),
+                    None => break,
+                },
+                _ = shutdown_signal => break,
+            }
+        }
+        health.set_shutting_down();
+    }
+}
+
+/// Shuts the node down in an orderly way when SIGTERM or Ctrl+C is received, e.g. from systemd or a container runtime
+async fn shutdown_on_termination(mut shutdown: Shutdown) {
+    wait_for_termination().await;
+    println!("The node is shutting down because a termination signal was received...");
+    let _ = shutdown.trigger();
+}
+
+/// Runs the stratum server
+async fn run_stratum(stratum: StratumServer, interrupt_signal: ShutdownSignal) {
+    if let Err(err) = stratum.run(interrupt_signal).await {
+        error!(target: LOG_TARGET, "Stratum server encountered an error: {}", err);
+    }
+}
+
 /// Runs the gRPC server
 async fn run_grpc(
     grpc: crate::grpc::base_node_grpc_server::BaseNodeGrpcServer,
@@ -306,6 +377,9 @@ async fn cli_loop(parser: Parser, mut shutdown: Shutdown) {
     let read_command_fut = read_command(rustyline).fuse();
     pin_mut!(read_command_fut);
 
+    let termination = wait_for_termination().fuse();
+    pin_mut!(termination);
+
     let mut shutdown_signal = shutdown.to_signal();
     let start_time = Instant::now();
     loop {
@@ -337,6 +411,10 @@ async fn cli_loop(parser: Parser, mut shutdown: Shutdown) {
             _ = interval => {
                command_handler.status();
             },
+            _ = termination => {
+                println!("The node is shutting down because a termination signal was received...");
+                break;
+            },
             _ = shutdown_signal => {
                 break;
             }
@@ -21,6 +21,7 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 //
 
+use crate::builder::create_consensus_rules;
 use anyhow::anyhow;
 use log::*;
 use std::{
@@ -41,7 +42,7 @@ use tari_core::{
         BlockchainDatabaseConfig,
         Validators,
     },
-    consensus::{ConsensusManagerBuilder, Network as NetworkType},
+    consensus::ConsensusManager,
     proof_of_work::randomx_factory::{RandomXConfig, RandomXFactory},
     transactions::types::CryptoFactories,
     validation::{
@@ -91,9 +92,12 @@ pub async fn run_recovery(node_config: &GlobalConfig) -> Result<(), anyhow::Erro
             return Err(anyhow!("Recovery mode is only available for LMDB"));
         },
     };
-    let rules = ConsensusManagerBuilder::new(node_config.network.into()).build();
+    let rules = create_consensus_rules(node_config)?;
     let factories = CryptoFactories::default();
-    let randomx_factory = RandomXFactory::new(RandomXConfig::default(), node_config.max_randomx_vms);
+    let randomx_factory = RandomXFactory::new(
+        RandomXConfig::with_mode(node_config.randomx_mode),
+        node_config.max_randomx_vms,
+    );
     let validators = Validators::new(
         BodyOnlyValidator::default(),
         HeaderValidator::new(rules.clone(), randomx_factory),
@@ -105,7 +109,7 @@ pub async fn run_recovery(node_config: &GlobalConfig) -> Result<(), anyhow::Erro
         pruning_interval: node_config.pruned_mode_cleanup_interval,
     };
     let db = BlockchainDatabase::new(main_db, &rules, validators, db_config, true)?;
-    do_recovery(db.into(), temp_db).await?;
+    do_recovery(db.into(), temp_db, &rules).await?;
 
     info!(
         target: LOG_TARGET,
@@ -130,17 +134,18 @@ pub async fn run_recovery(node_config: &GlobalConfig) -> Result<(), anyhow::Erro
 async fn do_recovery<D: BlockchainBackend + 'static>(
     db: AsyncBlockchainDb<D>,
     temp_db: D,
+    rules: &ConsensusManager,
 ) -> Result<(), anyhow::Error>
 {
-    // We dont care about the values, here, so we just use mock validators, and a mainnet CM.
-    let rules = ConsensusManagerBuilder::new(NetworkType::LocalNet).build();
+    // We dont care about the values, here, so we just use mock validators, but the blocks are read with the consensus
+    // rules of the configured network.
     let validators = Validators::new(
         MockValidator::new(true),
         MockValidator::new(true),
         MockValidator::new(true),
     );
     let temp_db_backend =
-        BlockchainDatabase::new(temp_db, &rules, validators, BlockchainDatabaseConfig::default(), false)?;
+        BlockchainDatabase::new(temp_db, rules, validators, BlockchainDatabaseConfig::default(), false)?;
     let max_height = temp_db_backend
         .get_chain_metadata()
         .map_err(|e| anyhow!("Could not get max chain height: {}", e))?
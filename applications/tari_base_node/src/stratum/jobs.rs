@@ -0,0 +1,250 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use super::{error::StratumError, protocol::JobParams};
+use futures::StreamExt;
+use log::*;
+use std::{
+    cmp::min,
+    collections::VecDeque,
+    convert::TryFrom,
+    net::SocketAddr,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+use tari_app_grpc::tari_rpc::{wallet_client::WalletClient, GetCoinbaseRequest};
+use tari_core::{
+    base_node::comms_interface::LocalNodeCommsInterface,
+    blocks::{Block, NewBlockTemplate},
+    crypto::tari_utilities::hex::to_hex,
+    proof_of_work::{sha3_difficulty, sha3_mining_blob, Difficulty, PowAlgorithm},
+    transactions::transaction::Transaction,
+};
+use tari_shutdown::ShutdownSignal;
+use tokio::{sync::watch, time};
+use tonic::transport::Channel;
+
+const LOG_TARGET: &str = "tari::base_node::stratum";
+
+/// How often the job is refreshed while the tip does not change, so that miners pick up new mempool transactions
+const JOB_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+/// The number of recent jobs for which shares are still accepted
+const MAX_RECENT_JOBS: usize = 8;
+
+/// A block template that miners are given to solve
+#[derive(Debug)]
+pub struct Job {
+    pub id: String,
+    pub block: Block,
+    pub target_difficulty: Difficulty,
+    blob_before_nonce: String,
+    blob_after_nonce: String,
+}
+
+impl Job {
+    pub fn new(id: u64, block: Block, target_difficulty: Difficulty) -> Self {
+        let (before, after) = sha3_mining_blob(&block.header);
+        Self {
+            id: id.to_string(),
+            block,
+            target_difficulty,
+            blob_before_nonce: to_hex(&before),
+            blob_after_nonce: to_hex(&after),
+        }
+    }
+
+    pub fn height(&self) -> u64 {
+        self.block.header.height
+    }
+
+    /// The job as sent to a miner with the given share difficulty. A miner is never asked for shares that are harder
+    /// than a block.
+    pub fn to_params(&self, share_difficulty: u64) -> JobParams {
+        JobParams {
+            job_id: self.id.clone(),
+            height: self.height(),
+            blob_before_nonce: self.blob_before_nonce.clone(),
+            blob_after_nonce: self.blob_after_nonce.clone(),
+            target: min(share_difficulty, self.target_difficulty.as_u64()),
+        }
+    }
+
+    /// The difficulty achieved with the given nonce
+    pub fn difficulty_with_nonce(&self, nonce: u64) -> Difficulty {
+        let mut header = self.block.header.clone();
+        header.nonce = nonce;
+        sha3_difficulty(&header)
+    }
+
+    /// The block solved with the given nonce
+    pub fn block_with_nonce(&self, nonce: u64) -> Block {
+        let mut block = self.block.clone();
+        block.header.nonce = nonce;
+        block
+    }
+}
+
+/// The most recent jobs, newest last
+#[derive(Clone, Default)]
+pub struct Jobs {
+    recent: Arc<RwLock<VecDeque<Arc<Job>>>>,
+}
+
+impl Jobs {
+    pub fn current(&self) -> Option<Arc<Job>> {
+        self.recent.read().expect("jobs lock poisoned").back().cloned()
+    }
+
+    pub fn get(&self, id: &str) -> Option<Arc<Job>> {
+        self.recent
+            .read()
+            .expect("jobs lock poisoned")
+            .iter()
+            .rev()
+            .find(|job| job.id == id)
+            .cloned()
+    }
+
+    fn push(&self, job: Arc<Job>) {
+        let mut recent = self.recent.write().expect("jobs lock poisoned");
+        // Jobs for a previous height can no longer produce a block on the tip
+        recent.retain(|j| j.height() >= job.height());
+        recent.push_back(job);
+        while recent.len() > MAX_RECENT_JOBS {
+            recent.pop_front();
+        }
+    }
+}
+
+/// Makes a new job whenever the tip changes, and refreshes it periodically
+pub struct JobProvider {
+    node: LocalNodeCommsInterface,
+    wallet_address: SocketAddr,
+    wallet: Option<WalletClient<Channel>>,
+    jobs: Jobs,
+    job_sender: watch::Sender<Option<Arc<Job>>>,
+    last_job_id: u64,
+}
+
+impl JobProvider {
+    pub fn new(
+        node: LocalNodeCommsInterface,
+        wallet_address: SocketAddr,
+        jobs: Jobs,
+        job_sender: watch::Sender<Option<Arc<Job>>>,
+    ) -> Self
+    {
+        Self {
+            node,
+            wallet_address,
+            wallet: None,
+            jobs,
+            job_sender,
+            last_job_id: 0,
+        }
+    }
+
+    pub async fn run(mut self, mut shutdown: ShutdownSignal) {
+        let mut block_events = self.node.get_block_event_stream().fuse();
+        let mut refresh = time::interval(JOB_REFRESH_INTERVAL).fuse();
+        loop {
+            futures::select! {
+                event = block_events.select_next_some() => {
+                    if let Ok(event) = event {
+                        if event.changes_tip() {
+                            self.refresh().await;
+                        }
+                    }
+                },
+                _ = refresh.select_next_some() => self.refresh().await,
+                _ = shutdown => {
+                    info!(target: LOG_TARGET, "Stratum job provider is shutting down");
+                    break;
+                },
+            }
+        }
+    }
+
+    async fn refresh(&mut self) {
+        match self.new_job().await {
+            Ok(job) => {
+                debug!(
+                    target: LOG_TARGET,
+                    "New stratum job {} at height {} with target difficulty {}",
+                    job.id,
+                    job.height(),
+                    job.target_difficulty
+                );
+                let job = Arc::new(job);
+                self.jobs.push(job.clone());
+                if self.job_sender.broadcast(Some(job)).is_err() {
+                    debug!(target: LOG_TARGET, "No stratum sessions are listening for jobs");
+                }
+            },
+            Err(err) => warn!(target: LOG_TARGET, "Could not create a stratum job: {}", err),
+        }
+    }
+
+    async fn new_job(&mut self) -> Result<Job, StratumError> {
+        let mut template = self.node.get_new_block_template(PowAlgorithm::Sha3, 0).await?;
+        let coinbase = self.request_coinbase(&template).await?;
+        let (_, mut outputs, mut kernels) = coinbase.body.dissolve();
+        template.body.add_outputs(&mut outputs);
+        template.body.add_kernels(&mut kernels);
+        template.body.sort();
+        let target_difficulty = template.target_difficulty;
+        let block = self.node.get_new_block(template).await?;
+        self.last_job_id += 1;
+        Ok(Job::new(self.last_job_id, block, target_difficulty))
+    }
+
+    async fn request_coinbase(&mut self, template: &NewBlockTemplate) -> Result<Transaction, StratumError> {
+        let mut wallet = self.wallet_client().await?;
+        let request = GetCoinbaseRequest {
+            reward: template.reward.into(),
+            fee: template.total_fees.into(),
+            height: template.header.height,
+            extra: vec![],
+        };
+        let response = match wallet.get_coinbase(request).await {
+            Ok(response) => response.into_inner(),
+            Err(status) => {
+                // Reconnect on the next request
+                self.wallet = None;
+                return Err(status.into());
+            },
+        };
+        let transaction = response
+            .transaction
+            .ok_or_else(|| StratumError::InvalidCoinbase("No transaction returned".to_string()))?;
+        Transaction::try_from(transaction).map_err(StratumError::InvalidCoinbase)
+    }
+
+    async fn wallet_client(&mut self) -> Result<WalletClient<Channel>, StratumError> {
+        if let Some(client) = &self.wallet {
+            return Ok(client.clone());
+        }
+        let client = WalletClient::connect(format!("http://{}", self.wallet_address)).await?;
+        self.wallet = Some(client.clone());
+        Ok(client)
+    }
+}
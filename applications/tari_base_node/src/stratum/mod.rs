@@ -0,0 +1,50 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! A stratum server that serves SHA3 mining jobs to connected miners.
+//!
+//! Miners connect over TCP and exchange newline-delimited JSON-RPC messages, in the style of the stratum protocol used
+//! by Monero pools:
+//!
+//! - `login` `{"login": <worker name>, "pass": <ignored>, "agent": <miner software>}` replies with the session id and
+//!   the current job.
+//! - `getjob` `{"id": <session id>}` replies with the current job.
+//! - `submit` `{"id": <session id>, "job_id": <job id>, "nonce": <hex of the little-endian nonce>}` submits a share.
+//! - `keepalived` `{"id": <session id>}` keeps an idle connection open.
+//!
+//! The server pushes a `job` notification whenever a new job is available or the miner's share difficulty changes. A
+//! job carries the bytes of the SHA3 mining hash that come before and after the nonce, so a miner hashes
+//! `blob_before_nonce || nonce || blob_after_nonce` without having to know the block header layout, and a share is a
+//! nonce that meets the job's `target` difficulty.
+//!
+//! Jobs are made from the node's block templates, with a coinbase requested from the console wallet. The share
+//! difficulty of each miner is adjusted so that it finds a share about every `stratum_target_share_interval` seconds.
+//! A share that also meets the network difficulty is submitted to the node as a block.
+
+mod error;
+mod jobs;
+mod protocol;
+mod server;
+mod session;
+mod share_difficulty;
+
+pub use server::{StratumConfig, StratumServer};
@@ -0,0 +1,183 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const METHOD_LOGIN: &str = "login";
+pub const METHOD_GET_JOB: &str = "getjob";
+pub const METHOD_SUBMIT: &str = "submit";
+pub const METHOD_KEEP_ALIVE: &str = "keepalived";
+pub const METHOD_JOB: &str = "job";
+
+pub const ERROR_INVALID_REQUEST: i64 = -32600;
+pub const ERROR_METHOD_NOT_FOUND: i64 = -32601;
+pub const ERROR_INVALID_PARAMS: i64 = -32602;
+pub const ERROR_UNAUTHORIZED: i64 = -1;
+pub const ERROR_NO_JOB: i64 = -2;
+pub const ERROR_REJECTED_SHARE: i64 = -3;
+
+#[derive(Debug, Deserialize)]
+pub struct Request {
+    #[serde(default)]
+    pub id: Value,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Response {
+    pub id: Value,
+    pub jsonrpc: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+impl Response {
+    pub fn result(id: Value, result: Value) -> Self {
+        Self {
+            id,
+            jsonrpc: "2.0",
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    pub fn error<T: Into<String>>(id: Value, code: i64, message: T) -> Self {
+        Self {
+            id,
+            jsonrpc: "2.0",
+            result: None,
+            error: Some(RpcError {
+                code,
+                message: message.into(),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Notification<T> {
+    pub jsonrpc: &'static str,
+    pub method: &'static str,
+    pub params: T,
+}
+
+impl<T> Notification<T> {
+    pub fn new(method: &'static str, params: T) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            method,
+            params,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LoginParams {
+    pub login: String,
+    #[serde(default)]
+    pub agent: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SessionParams {
+    pub id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubmitParams {
+    pub id: String,
+    pub job_id: String,
+    pub nonce: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct JobParams {
+    pub job_id: String,
+    pub height: u64,
+    /// Hex of the mining hash bytes that come before the nonce
+    pub blob_before_nonce: String,
+    /// Hex of the mining hash bytes that come after the nonce
+    pub blob_after_nonce: String,
+    /// The share difficulty
+    pub target: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LoginResult {
+    pub id: String,
+    pub job: Option<JobParams>,
+    pub status: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatusResult {
+    pub status: &'static str,
+}
+
+/// Parses a nonce given as the hex of its little-endian bytes
+pub fn parse_nonce(nonce: &str) -> Option<u64> {
+    if nonce.len() != 16 || !nonce.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let mut bytes = [0u8; 8];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&nonce[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(u64::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn nonce_is_little_endian_hex() {
+        assert_eq!(parse_nonce("0100000000000000"), Some(1));
+        assert_eq!(parse_nonce("efcdab8967452301"), Some(0x0123_4567_89ab_cdef));
+        assert_eq!(parse_nonce("01"), None);
+        assert_eq!(parse_nonce("zz00000000000000"), None);
+        assert_eq!(parse_nonce("+100000000000000"), None);
+    }
+
+    #[test]
+    fn parse_submit_request() {
+        let request: Request = serde_json::from_str(
+            r#"{"id":3,"method":"submit","params":{"id":"1","job_id":"7","nonce":"0100000000000000"}}"#,
+        )
+        .unwrap();
+        assert_eq!(request.method, METHOD_SUBMIT);
+        let params: SubmitParams = serde_json::from_value(request.params).unwrap();
+        assert_eq!(params.job_id, "7");
+        assert_eq!(parse_nonce(&params.nonce), Some(1));
+    }
+}
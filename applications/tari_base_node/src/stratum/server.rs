@@ -0,0 +1,110 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use super::{
+    error::StratumError,
+    jobs::{JobProvider, Jobs},
+    session::Session,
+};
+use futures::FutureExt;
+use log::*;
+use std::{net::SocketAddr, time::Duration};
+use tari_common::GlobalConfig;
+use tari_core::base_node::comms_interface::LocalNodeCommsInterface;
+use tari_shutdown::ShutdownSignal;
+use tokio::{net::TcpListener, sync::watch, task};
+
+const LOG_TARGET: &str = "tari::base_node::stratum";
+
+#[derive(Debug, Clone)]
+pub struct StratumConfig {
+    pub listener_address: SocketAddr,
+    /// The console wallet that provides the coinbase for each job
+    pub wallet_grpc_address: SocketAddr,
+    pub initial_share_difficulty: u64,
+    pub target_share_interval: Duration,
+}
+
+impl StratumConfig {
+    pub fn from_global_config(config: &GlobalConfig) -> Self {
+        Self {
+            listener_address: config.stratum_address,
+            wallet_grpc_address: config.grpc_console_wallet_address,
+            initial_share_difficulty: config.stratum_initial_share_difficulty,
+            target_share_interval: Duration::from_secs(config.stratum_target_share_interval.max(1)),
+        }
+    }
+}
+
+pub struct StratumServer {
+    config: StratumConfig,
+    node: LocalNodeCommsInterface,
+}
+
+impl StratumServer {
+    pub fn new(config: StratumConfig, node: LocalNodeCommsInterface) -> Self {
+        Self { config, node }
+    }
+
+    /// Accepts miner connections until shutdown
+    pub async fn run(self, mut shutdown: ShutdownSignal) -> Result<(), StratumError> {
+        let mut listener = TcpListener::bind(self.config.listener_address).await?;
+        info!(
+            target: LOG_TARGET,
+            "Stratum server listening on {}", self.config.listener_address
+        );
+
+        let jobs = Jobs::default();
+        let (job_sender, job_receiver) = watch::channel(None);
+        let provider = JobProvider::new(
+            self.node.clone(),
+            self.config.wallet_grpc_address,
+            jobs.clone(),
+            job_sender,
+        );
+        task::spawn(provider.run(shutdown.clone()));
+
+        let mut last_session_id = 0u64;
+        loop {
+            futures::select! {
+                connection = listener.accept().fuse() => match connection {
+                    Ok((stream, address)) => {
+                        last_session_id += 1;
+                        let session = Session::new(
+                            last_session_id,
+                            address,
+                            self.node.clone(),
+                            jobs.clone(),
+                            self.config.initial_share_difficulty,
+                            self.config.target_share_interval,
+                        );
+                        task::spawn(session.run(stream, job_receiver.clone(), shutdown.clone()));
+                    },
+                    Err(err) => warn!(target: LOG_TARGET, "Could not accept stratum connection: {}", err),
+                },
+                _ = shutdown => break,
+            }
+        }
+        info!(target: LOG_TARGET, "Stratum server stopped");
+        Ok(())
+    }
+}
@@ -0,0 +1,338 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use super::{
+    error::StratumError,
+    jobs::{Job, Jobs},
+    protocol::*,
+    share_difficulty::ShareDifficulty,
+};
+use futures::StreamExt;
+use log::*;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::{
+    cmp::min,
+    collections::HashSet,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tari_core::{
+    base_node::comms_interface::{Broadcast, LocalNodeCommsInterface},
+    crypto::tari_utilities::hex::Hex,
+    proof_of_work::Difficulty,
+};
+use tari_shutdown::ShutdownSignal;
+use tokio::{
+    io::{self, AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+    sync::watch,
+    time,
+};
+
+const LOG_TARGET: &str = "tari::base_node::stratum";
+
+/// The longest message a miner may send
+const MAX_MESSAGE_LENGTH: usize = 4096;
+
+#[derive(Debug, Default)]
+struct ShareStats {
+    accepted: u64,
+    rejected: u64,
+    blocks: u64,
+}
+
+/// The connection of a single miner
+pub struct Session {
+    id: String,
+    address: SocketAddr,
+    node: LocalNodeCommsInterface,
+    jobs: Jobs,
+    worker: Option<String>,
+    share_difficulty: ShareDifficulty,
+    target_share_interval: Duration,
+    submitted: HashSet<(String, u64)>,
+    stats: ShareStats,
+    outbox: Vec<String>,
+}
+
+impl Session {
+    pub fn new(
+        id: u64,
+        address: SocketAddr,
+        node: LocalNodeCommsInterface,
+        jobs: Jobs,
+        initial_share_difficulty: u64,
+        target_share_interval: Duration,
+    ) -> Self
+    {
+        Self {
+            id: id.to_string(),
+            address,
+            node,
+            jobs,
+            worker: None,
+            share_difficulty: ShareDifficulty::new(initial_share_difficulty, target_share_interval, Instant::now()),
+            target_share_interval,
+            submitted: HashSet::new(),
+            stats: ShareStats::default(),
+            outbox: Vec::new(),
+        }
+    }
+
+    pub async fn run(
+        mut self,
+        stream: TcpStream,
+        job_receiver: watch::Receiver<Option<Arc<Job>>>,
+        mut shutdown: ShutdownSignal,
+    )
+    {
+        debug!(
+            target: LOG_TARGET,
+            "Stratum session {} opened from {}", self.id, self.address
+        );
+        let (reader, mut writer) = io::split(stream);
+        let mut lines = BufReader::new(reader).lines().fuse();
+        let mut job_receiver = job_receiver.fuse();
+        let mut overdue_check = time::interval(self.target_share_interval).fuse();
+        loop {
+            futures::select! {
+                line = lines.next() => match line {
+                    Some(Ok(line)) if line.len() > MAX_MESSAGE_LENGTH => {
+                        debug!(target: LOG_TARGET, "Stratum session {} sent an oversized message", self.id);
+                        break;
+                    },
+                    Some(Ok(line)) => self.handle_message(&line).await,
+                    Some(Err(err)) => {
+                        debug!(target: LOG_TARGET, "Stratum session {} read error: {}", self.id, err);
+                        break;
+                    },
+                    None => break,
+                },
+                job = job_receiver.select_next_some() => {
+                    if let Some(job) = job {
+                        self.handle_new_job(&job);
+                    }
+                },
+                _ = overdue_check.select_next_some() => {
+                    if self.share_difficulty.check_overdue(Instant::now()).is_some() {
+                        self.notify_current_job();
+                    }
+                },
+                _ = shutdown => break,
+            }
+
+            if let Err(err) = self.flush(&mut writer).await {
+                debug!(target: LOG_TARGET, "Stratum session {} write error: {}", self.id, err);
+                break;
+            }
+        }
+        info!(
+            target: LOG_TARGET,
+            "Stratum miner '{}' ({}) disconnected. Accepted shares: {}, rejected shares: {}, blocks found: {}",
+            self.worker.as_deref().unwrap_or("unknown"),
+            self.address,
+            self.stats.accepted,
+            self.stats.rejected,
+            self.stats.blocks
+        );
+    }
+
+    async fn flush<W: AsyncWriteExt + Unpin>(&mut self, writer: &mut W) -> Result<(), StratumError> {
+        for mut message in self.outbox.drain(..) {
+            message.push('\n');
+            writer.write_all(message.as_bytes()).await?;
+        }
+        writer.flush().await?;
+        Ok(())
+    }
+
+    fn send<T: Serialize>(&mut self, message: &T) {
+        match serde_json::to_string(message) {
+            Ok(message) => self.outbox.push(message),
+            Err(err) => error!(target: LOG_TARGET, "Could not serialize stratum message: {}", err),
+        }
+    }
+
+    fn logged_in(&self) -> bool {
+        self.worker.is_some()
+    }
+
+    async fn handle_message(&mut self, message: &str) {
+        let request = match serde_json::from_str::<Request>(message) {
+            Ok(request) => request,
+            Err(err) => {
+                self.send(&Response::error(
+                    Value::Null,
+                    ERROR_INVALID_REQUEST,
+                    format!("Invalid request: {}", err),
+                ));
+                return;
+            },
+        };
+        let id = request.id.clone();
+        let response = match request.method.as_str() {
+            METHOD_LOGIN => self.handle_login(request),
+            METHOD_GET_JOB => self.handle_get_job(request),
+            METHOD_SUBMIT => self.handle_submit(request).await,
+            METHOD_KEEP_ALIVE => self
+                .check_session(request.params)
+                .map(|_| json!(StatusResult { status: "KEEPALIVED" })),
+            method => Err((ERROR_METHOD_NOT_FOUND, format!("Unknown method '{}'", method))),
+        };
+        let response = match response {
+            Ok(result) => Response::result(id, result),
+            Err((code, message)) => Response::error(id, code, message),
+        };
+        self.send(&response);
+    }
+
+    fn check_session(&self, params: Value) -> Result<(), (i64, String)> {
+        let params: SessionParams = parse_params(params)?;
+        if !self.logged_in() || params.id != self.id {
+            return Err((ERROR_UNAUTHORIZED, "Unauthenticated".to_string()));
+        }
+        Ok(())
+    }
+
+    fn current_job_params(&self) -> Option<JobParams> {
+        self.jobs
+            .current()
+            .map(|job| job.to_params(self.share_difficulty.current()))
+    }
+
+    fn handle_login(&mut self, request: Request) -> Result<Value, (i64, String)> {
+        let params: LoginParams = parse_params(request.params)?;
+        info!(
+            target: LOG_TARGET,
+            "Stratum miner '{}' ({}) logged in using {}",
+            params.login,
+            self.address,
+            params.agent.as_deref().unwrap_or("an unknown miner")
+        );
+        self.worker = Some(params.login);
+        Ok(json!(LoginResult {
+            id: self.id.clone(),
+            job: self.current_job_params(),
+            status: "OK",
+        }))
+    }
+
+    fn handle_get_job(&mut self, request: Request) -> Result<Value, (i64, String)> {
+        self.check_session(request.params)?;
+        self.current_job_params()
+            .map(|job| json!(job))
+            .ok_or_else(|| (ERROR_NO_JOB, "No job available".to_string()))
+    }
+
+    async fn handle_submit(&mut self, request: Request) -> Result<Value, (i64, String)> {
+        let params: SubmitParams = parse_params(request.params)?;
+        if !self.logged_in() || params.id != self.id {
+            return Err((ERROR_UNAUTHORIZED, "Unauthenticated".to_string()));
+        }
+        let (job, nonce, difficulty) = match self.check_share(&params) {
+            Ok(share) => share,
+            Err(reason) => {
+                self.stats.rejected += 1;
+                debug!(
+                    target: LOG_TARGET,
+                    "Rejected share from stratum session {}: {}", self.id, reason
+                );
+                return Err((ERROR_REJECTED_SHARE, reason.to_string()));
+            },
+        };
+        self.stats.accepted += 1;
+
+        if difficulty >= job.target_difficulty {
+            self.submit_block(&job, nonce).await;
+        }
+        if self.share_difficulty.record_share(Instant::now()).is_some() {
+            self.notify_current_job();
+        }
+        Ok(json!(StatusResult { status: "OK" }))
+    }
+
+    fn check_share(&mut self, params: &SubmitParams) -> Result<(Arc<Job>, u64, Difficulty), &'static str> {
+        let job = self.jobs.get(&params.job_id).ok_or("Unknown or stale job")?;
+        let nonce = parse_nonce(&params.nonce).ok_or("Invalid nonce")?;
+        if !self.submitted.insert((job.id.clone(), nonce)) {
+            return Err("Duplicate share");
+        }
+        let required = min(self.share_difficulty.minimum_accepted(), job.target_difficulty.as_u64());
+        let difficulty = job.difficulty_with_nonce(nonce);
+        if difficulty.as_u64() < required {
+            return Err("Low difficulty share");
+        }
+        Ok((job, nonce, difficulty))
+    }
+
+    async fn submit_block(&mut self, job: &Job, nonce: u64) {
+        let worker = self.worker.as_deref().unwrap_or("unknown");
+        match self
+            .node
+            .submit_block(job.block_with_nonce(nonce), Broadcast::from(true))
+            .await
+        {
+            Ok(hash) => {
+                self.stats.blocks += 1;
+                info!(
+                    target: LOG_TARGET,
+                    "Stratum miner '{}' found block #{} ({})",
+                    worker,
+                    job.height(),
+                    hash.to_hex()
+                );
+            },
+            Err(err) => warn!(
+                target: LOG_TARGET,
+                "Block #{} found by stratum miner '{}' was not accepted: {}",
+                job.height(),
+                worker,
+                err
+            ),
+        }
+    }
+
+    fn handle_new_job(&mut self, job: &Job) {
+        // Shares for jobs that were dropped can no longer be submitted
+        let jobs = &self.jobs;
+        self.submitted.retain(|(id, _)| jobs.get(id).is_some());
+        if self.logged_in() {
+            let params = job.to_params(self.share_difficulty.current());
+            self.send(&Notification::new(METHOD_JOB, params));
+        }
+    }
+
+    fn notify_current_job(&mut self) {
+        if !self.logged_in() {
+            return;
+        }
+        if let Some(params) = self.current_job_params() {
+            self.send(&Notification::new(METHOD_JOB, params));
+        }
+    }
+}
+
+fn parse_params<T: serde::de::DeserializeOwned>(params: Value) -> Result<T, (i64, String)> {
+    serde_json::from_value(params).map_err(|err| (ERROR_INVALID_PARAMS, format!("Invalid params: {}", err)))
+}
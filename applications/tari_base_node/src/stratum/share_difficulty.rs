@@ -0,0 +1,162 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    cmp::{max, min},
+    time::{Duration, Instant},
+};
+
+/// The number of shares after which the difficulty is retargeted
+const RETARGET_SHARES: u32 = 8;
+/// The most the difficulty can change by in a single retarget
+const MAX_ADJUSTMENT_FACTOR: u64 = 4;
+const MIN_SHARE_DIFFICULTY: u64 = 1;
+
+/// Adjusts the share difficulty of a miner so that it finds a share about every `target_interval`.
+///
+/// The difficulty is retargeted after `RETARGET_SHARES` shares, or when a miner has not found that many shares in the
+/// time it should have taken to. Each retarget changes the difficulty by at most a factor of `MAX_ADJUSTMENT_FACTOR`.
+#[derive(Debug, Clone)]
+pub struct ShareDifficulty {
+    difficulty: u64,
+    previous: u64,
+    target_interval: Duration,
+    window_start: Instant,
+    window_shares: u32,
+}
+
+impl ShareDifficulty {
+    pub fn new(initial_difficulty: u64, target_interval: Duration, now: Instant) -> Self {
+        let difficulty = max(initial_difficulty, MIN_SHARE_DIFFICULTY);
+        Self {
+            difficulty,
+            previous: difficulty,
+            target_interval,
+            window_start: now,
+            window_shares: 0,
+        }
+    }
+
+    pub fn current(&self) -> u64 {
+        self.difficulty
+    }
+
+    /// The lowest difficulty a share may have. Shares that were found for the share difficulty before the last
+    /// retarget are still accepted.
+    pub fn minimum_accepted(&self) -> u64 {
+        min(self.difficulty, self.previous)
+    }
+
+    /// Records an accepted share and returns the new difficulty if it was retargeted
+    pub fn record_share(&mut self, now: Instant) -> Option<u64> {
+        self.window_shares += 1;
+        if self.window_shares < RETARGET_SHARES {
+            return None;
+        }
+        self.retarget(now)
+    }
+
+    /// Lowers the difficulty of a miner that has been finding too few shares. This should be called periodically,
+    /// because a miner whose difficulty is too high may never find another share. Returns the new difficulty if it was
+    /// retargeted.
+    pub fn check_overdue(&mut self, now: Instant) -> Option<u64> {
+        let window = self.target_interval * RETARGET_SHARES;
+        if now.duration_since(self.window_start) < window {
+            return None;
+        }
+        self.retarget(now)
+    }
+
+    fn retarget(&mut self, now: Instant) -> Option<u64> {
+        let elapsed = max(now.duration_since(self.window_start).as_millis(), 1);
+        let expected = self.target_interval.as_millis() * u128::from(self.window_shares);
+        let retargeted = (u128::from(self.difficulty) * expected / elapsed) as u64;
+        let lower = max(self.difficulty / MAX_ADJUSTMENT_FACTOR, MIN_SHARE_DIFFICULTY);
+        let upper = self.difficulty.saturating_mul(MAX_ADJUSTMENT_FACTOR);
+        let retargeted = min(max(retargeted, lower), upper);
+
+        self.window_start = now;
+        self.window_shares = 0;
+        if retargeted == self.difficulty {
+            return None;
+        }
+        self.previous = self.difficulty;
+        self.difficulty = retargeted;
+        Some(retargeted)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const INTERVAL: Duration = Duration::from_secs(10);
+
+    #[test]
+    fn steady_miner_keeps_its_difficulty() {
+        let start = Instant::now();
+        let mut diff = ShareDifficulty::new(1000, INTERVAL, start);
+        for i in 1..=RETARGET_SHARES {
+            assert_eq!(diff.record_share(start + INTERVAL * i), None);
+        }
+        assert_eq!(diff.current(), 1000);
+    }
+
+    #[test]
+    fn fast_miner_is_given_a_higher_difficulty() {
+        let start = Instant::now();
+        let mut diff = ShareDifficulty::new(1000, INTERVAL, start);
+        for i in 1..RETARGET_SHARES {
+            assert_eq!(diff.record_share(start + INTERVAL * i / 2), None);
+        }
+        assert_eq!(diff.record_share(start + INTERVAL * RETARGET_SHARES / 2), Some(2000));
+        assert_eq!(diff.minimum_accepted(), 1000);
+    }
+
+    #[test]
+    fn adjustment_is_limited() {
+        let start = Instant::now();
+        let mut diff = ShareDifficulty::new(1000, INTERVAL, start);
+        for _ in 1..RETARGET_SHARES {
+            diff.record_share(start);
+        }
+        assert_eq!(diff.record_share(start), Some(1000 * MAX_ADJUSTMENT_FACTOR));
+    }
+
+    #[test]
+    fn overdue_miner_is_given_a_lower_difficulty() {
+        let start = Instant::now();
+        let mut diff = ShareDifficulty::new(1000, INTERVAL, start);
+        assert_eq!(diff.check_overdue(start + INTERVAL), None);
+        for i in 1..=RETARGET_SHARES / 2 {
+            diff.record_share(start + INTERVAL * i);
+        }
+        // Half the shares in the time it should have taken to find `RETARGET_SHARES` of them
+        assert_eq!(diff.check_overdue(start + INTERVAL * RETARGET_SHARES), Some(500));
+        // No shares at all
+        let mut diff = ShareDifficulty::new(1000, INTERVAL, start);
+        assert_eq!(
+            diff.check_overdue(start + INTERVAL * RETARGET_SHARES),
+            Some(1000 / MAX_ADJUSTMENT_FACTOR)
+        );
+    }
+}
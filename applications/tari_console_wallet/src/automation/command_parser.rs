@@ -31,7 +31,16 @@ use std::{
 };
 use tari_app_utilities::utilities::parse_emoji_id_or_public_key;
 
-use tari_core::transactions::{tari_amount::MicroTari, types::PublicKey};
+use tari_core::{
+    tari_utilities::{
+        hex::{from_hex, Hex},
+        ByteArray,
+    },
+    transactions::{
+        tari_amount::MicroTari,
+        types::{Commitment, PrivateKey, PublicKey},
+    },
+};
 
 #[derive(Debug)]
 pub struct ParsedCommand {
@@ -50,6 +59,22 @@ impl Display for ParsedCommand {
             WalletCommand::Whois => "whois",
             WalletCommand::ExportUtxos => "export-utxos",
             WalletCommand::CountUtxos => "count-utxos",
+            WalletCommand::ExportViewKey => "export-view-key",
+            WalletCommand::ExportUnsignedTx => "export-unsigned-tx",
+            WalletCommand::SignFile => "sign-file",
+            WalletCommand::ImportSignedTx => "import-signed-tx",
+            WalletCommand::HtlcSecret => "htlc-secret",
+            WalletCommand::HtlcKey => "htlc-key",
+            WalletCommand::LockHtlc => "lock-htlc",
+            WalletCommand::ClaimHtlc => "claim-htlc",
+            WalletCommand::RefundHtlc => "refund-htlc",
+            WalletCommand::ListHtlcs => "list-htlcs",
+            WalletCommand::ClaimOneSidedPayments => "claim-one-sided-payments",
+            WalletCommand::MultisigFund => "multisig-fund",
+            WalletCommand::MultisigSpend => "multisig-spend",
+            WalletCommand::MultisigCosign => "multisig-cosign",
+            WalletCommand::ListMultisig => "list-multisig",
+            WalletCommand::ImportUtxo => "import-utxo",
         };
 
         let args = self
@@ -73,6 +98,8 @@ pub enum ParsedArgument {
     Date(DateTime<Utc>),
     OutputToCSVFile(String),
     CSVFileName(String),
+    Bytes(Vec<u8>),
+    Commitment(Commitment),
 }
 
 impl Display for ParsedArgument {
@@ -86,6 +113,8 @@ impl Display for ParsedArgument {
             ParsedArgument::Date(v) => write!(f, "{}", v.to_string()),
             ParsedArgument::OutputToCSVFile(v) => write!(f, "{}", v.to_string()),
             ParsedArgument::CSVFileName(v) => write!(f, "{}", v.to_string()),
+            ParsedArgument::Bytes(v) => write!(f, "{}", v.to_hex()),
+            ParsedArgument::Commitment(v) => write!(f, "{}", v.to_hex()),
         }
     }
 }
@@ -107,6 +136,22 @@ pub fn parse_command(command: &str) -> Result<ParsedCommand, ParseError> {
         Whois => parse_whois(args)?,
         ExportUtxos => parse_export_utxos(args)?, // todo: only show X number of utxos
         CountUtxos => Vec::new(),
+        ExportViewKey => Vec::new(),
+        ExportUnsignedTx => parse_export_unsigned_tx(args)?,
+        SignFile => parse_sign_file(args)?,
+        ImportSignedTx => parse_file_names(args, &["signed transaction file"])?,
+        HtlcSecret => Vec::new(),
+        HtlcKey => Vec::new(),
+        LockHtlc => parse_lock_htlc(args)?,
+        ClaimHtlc => parse_claim_htlc(args)?,
+        RefundHtlc => parse_refund_htlc(args)?,
+        ListHtlcs => Vec::new(),
+        ClaimOneSidedPayments => vec![ParsedArgument::Text(args.collect::<Vec<&str>>().join(" "))],
+        MultisigFund => parse_multisig_fund(args)?,
+        MultisigSpend => parse_multisig_spend(args)?,
+        MultisigCosign => parse_multisig_cosign(args)?,
+        ListMultisig => Vec::new(),
+        ImportUtxo => parse_import_utxo(args)?,
     };
 
     Ok(ParsedCommand { command, args })
@@ -243,6 +288,215 @@ fn parse_export_utxos(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>,
     Ok(parsed_args)
 }
 
+fn parse_export_unsigned_tx(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    let mut parsed_args = Vec::new();
+
+    // amount
+    let amount = args.next().ok_or_else(|| ParseError::Empty("amount".to_string()))?;
+    let amount = MicroTari::from_str(amount)?;
+    parsed_args.push(ParsedArgument::Amount(amount));
+
+    // public key/emoji id
+    let pubkey = args
+        .next()
+        .ok_or_else(|| ParseError::Empty("public key or emoji id".to_string()))?;
+    let pubkey = parse_emoji_id_or_public_key(pubkey).ok_or(ParseError::PublicKey)?;
+    parsed_args.push(ParsedArgument::PublicKey(pubkey));
+
+    // unsigned transaction file
+    let file = args
+        .next()
+        .ok_or_else(|| ParseError::Empty("unsigned transaction file".to_string()))?;
+    parsed_args.push(ParsedArgument::Text(file.to_string()));
+
+    // message
+    let message = args.collect::<Vec<&str>>().join(" ");
+    parsed_args.push(ParsedArgument::Text(message));
+
+    Ok(parsed_args)
+}
+
+fn parse_sign_file(args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    parse_file_names(args, &["unsigned transaction file", "signed transaction file"])
+}
+
+fn parse_file_names(mut args: SplitWhitespace, names: &[&str]) -> Result<Vec<ParsedArgument>, ParseError> {
+    names
+        .iter()
+        .map(|name| {
+            args.next()
+                .map(|file| ParsedArgument::Text(file.to_string()))
+                .ok_or_else(|| ParseError::Empty(name.to_string()))
+        })
+        .collect()
+}
+
+fn parse_hex(arg: Option<&str>, name: &str) -> Result<Vec<u8>, ParseError> {
+    let arg = arg.ok_or_else(|| ParseError::Empty(name.to_string()))?;
+    from_hex(arg).map_err(|_| ParseError::Hex(name.to_string()))
+}
+
+fn parse_commitment(arg: Option<&str>) -> Result<ParsedArgument, ParseError> {
+    let arg = arg.ok_or_else(|| ParseError::Empty("commitment".to_string()))?;
+    let commitment = Commitment::from_hex(arg).map_err(|_| ParseError::Hex("commitment".to_string()))?;
+    Ok(ParsedArgument::Commitment(commitment))
+}
+
+fn parse_lock_htlc(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    let mut parsed_args = Vec::new();
+
+    // amount
+    let amount = args.next().ok_or_else(|| ParseError::Empty("amount".to_string()))?;
+    let amount = MicroTari::from_str(amount)?;
+    parsed_args.push(ParsedArgument::Amount(amount));
+
+    // public key/emoji id of the claimer
+    let pubkey = args
+        .next()
+        .ok_or_else(|| ParseError::Empty("public key or emoji id".to_string()))?;
+    let pubkey = parse_emoji_id_or_public_key(pubkey).ok_or(ParseError::PublicKey)?;
+    parsed_args.push(ParsedArgument::PublicKey(pubkey));
+
+    // HTLC key of the claimer
+    let claim_key = args.next().ok_or_else(|| ParseError::Empty("HTLC key".to_string()))?;
+    let claim_key = PublicKey::from_hex(claim_key).map_err(|_| ParseError::Hex("HTLC key".to_string()))?;
+    parsed_args.push(ParsedArgument::PublicKey(claim_key));
+
+    // hash lock
+    let hash_lock = parse_hex(args.next(), "hash lock")?;
+    parsed_args.push(ParsedArgument::Bytes(hash_lock));
+
+    // refund height
+    let refund_height = args
+        .next()
+        .ok_or_else(|| ParseError::Empty("refund height".to_string()))?;
+    let refund_height = refund_height.parse::<u64>()?;
+    parsed_args.push(ParsedArgument::Int(refund_height));
+
+    // message
+    let message = args.collect::<Vec<&str>>().join(" ");
+    parsed_args.push(ParsedArgument::Text(message));
+
+    Ok(parsed_args)
+}
+
+fn parse_claim_htlc(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    let mut parsed_args = vec![parse_commitment(args.next())?];
+
+    let preimage = parse_hex(args.next(), "preimage")?;
+    parsed_args.push(ParsedArgument::Bytes(preimage));
+
+    // message
+    let message = args.collect::<Vec<&str>>().join(" ");
+    parsed_args.push(ParsedArgument::Text(message));
+
+    Ok(parsed_args)
+}
+
+fn parse_refund_htlc(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    let mut parsed_args = vec![parse_commitment(args.next())?];
+
+    // message
+    let message = args.collect::<Vec<&str>>().join(" ");
+    parsed_args.push(ParsedArgument::Text(message));
+
+    Ok(parsed_args)
+}
+
+fn parse_public_key(arg: Option<&str>) -> Result<ParsedArgument, ParseError> {
+    let arg = arg.ok_or_else(|| ParseError::Empty("public key or emoji id".to_string()))?;
+    let pubkey = parse_emoji_id_or_public_key(arg).ok_or(ParseError::PublicKey)?;
+    Ok(ParsedArgument::PublicKey(pubkey))
+}
+
+fn parse_multisig_fund(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    let mut parsed_args = Vec::new();
+
+    // amount
+    let amount = args.next().ok_or_else(|| ParseError::Empty("amount".to_string()))?;
+    let amount = MicroTari::from_str(amount)?;
+    parsed_args.push(ParsedArgument::Amount(amount));
+
+    // threshold
+    let threshold = args.next().ok_or_else(|| ParseError::Empty("threshold".to_string()))?;
+    let threshold = threshold.parse::<u64>()?;
+    parsed_args.push(ParsedArgument::Int(threshold));
+
+    // public keys/emoji ids of the other members, this wallet is always a member
+    let members = parse_public_keys(args)?;
+    if members.is_empty() {
+        return Err(ParseError::Empty("member public key or emoji id".to_string()));
+    }
+    // this wallet and the members, of which at least one has to sign
+    if threshold == 0 || threshold > members.len() as u64 + 1 {
+        return Err(ParseError::Invalid);
+    }
+    parsed_args.extend(members);
+
+    Ok(parsed_args)
+}
+
+fn parse_multisig_spend(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    let mut parsed_args = vec![parse_commitment(args.next())?];
+
+    // amount
+    let amount = args.next().ok_or_else(|| ParseError::Empty("amount".to_string()))?;
+    let amount = MicroTari::from_str(amount)?;
+    parsed_args.push(ParsedArgument::Amount(amount));
+
+    // public key/emoji id of the destination
+    parsed_args.push(parse_public_key(args.next())?);
+
+    // public keys/emoji ids of the cosigners
+    let cosigners = parse_public_keys(args)?;
+    if cosigners.is_empty() {
+        return Err(ParseError::Empty("cosigner public key or emoji id".to_string()));
+    }
+    parsed_args.extend(cosigners);
+
+    Ok(parsed_args)
+}
+
+fn parse_multisig_cosign(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    let mut parsed_args = vec![parse_commitment(args.next())?];
+
+    // amount
+    let amount = args.next().ok_or_else(|| ParseError::Empty("amount".to_string()))?;
+    let amount = MicroTari::from_str(amount)?;
+    parsed_args.push(ParsedArgument::Amount(amount));
+
+    // public key/emoji id of the destination
+    parsed_args.push(parse_public_key(args.next())?);
+
+    Ok(parsed_args)
+}
+
+fn parse_public_keys(args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    args.map(|arg| parse_public_key(Some(arg))).collect()
+}
+
+fn parse_import_utxo(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    let mut parsed_args = Vec::new();
+
+    // amount
+    let amount = args.next().ok_or_else(|| ParseError::Empty("amount".to_string()))?;
+    let amount = MicroTari::from_str(amount)?;
+    parsed_args.push(ParsedArgument::Amount(amount));
+
+    // spending key
+    let spending_key = args
+        .next()
+        .ok_or_else(|| ParseError::Empty("spending key".to_string()))?;
+    let spending_key = PrivateKey::from_hex(spending_key).map_err(|_| ParseError::Hex("spending key".to_string()))?;
+    parsed_args.push(ParsedArgument::Bytes(spending_key.to_vec()));
+
+    // message
+    let message = args.collect::<Vec<&str>>().join(" ");
+    parsed_args.push(ParsedArgument::Text(message));
+
+    Ok(parsed_args)
+}
+
 fn parse_coin_split(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
     let mut parsed_args = vec![];
 
@@ -265,8 +519,17 @@ mod test {
     use crate::automation::command_parser::{parse_command, ParsedArgument};
     use rand::rngs::OsRng;
     use std::str::FromStr;
-    use tari_core::transactions::{tari_amount::MicroTari, types::PublicKey};
-    use tari_crypto::keys::PublicKey as PublicKeyTrait;
+    use tari_core::{
+        tari_utilities::hex::Hex,
+        transactions::{
+            tari_amount::MicroTari,
+            types::{Commitment, PrivateKey, PublicKey},
+        },
+    };
+    use tari_crypto::{
+        keys::{PublicKey as PublicKeyTrait, SecretKey},
+        tari_utilities::ByteArray,
+    };
 
     #[test]
     fn test_parse_command() {
@@ -342,5 +605,118 @@ mod test {
         } else {
             panic!("Parsed csv file name is not the same as provided.");
         }
+
+        let command_str = format!("export-unsigned-tx 10T {} unsigned.json rent", public_key);
+        let parsed = parse_command(&command_str).unwrap();
+
+        if let ParsedArgument::Text(file) = parsed.args[2].clone() {
+            assert_eq!(file, "unsigned.json");
+        } else {
+            panic!("Parsed unsigned transaction file name is not the same as provided.");
+        }
+        if let ParsedArgument::Text(msg) = parsed.args[3].clone() {
+            assert_eq!(msg, "rent");
+        } else {
+            panic!("Parsed message is not the same as provided.");
+        }
+
+        let parsed = parse_command("sign-file unsigned.json signed.json").unwrap();
+        assert_eq!(parsed.args.len(), 2);
+
+        let parsed = parse_command("sign-file unsigned.json");
+        assert!(parsed.is_err());
+
+        let hash_lock = "ab".repeat(32);
+        let (_secret_key, claim_key) = PublicKey::random_keypair(&mut OsRng);
+        let command_str = format!("lock-htlc 10T {} {} {} 5000 swap", public_key, claim_key, hash_lock);
+        let parsed = parse_command(&command_str).unwrap();
+
+        if let ParsedArgument::PublicKey(key) = parsed.args[2].clone() {
+            assert_eq!(key, claim_key);
+        } else {
+            panic!("Parsed HTLC key is not the same as provided.");
+        }
+        if let ParsedArgument::Bytes(bytes) = parsed.args[3].clone() {
+            assert_eq!(bytes, vec![0xab; 32]);
+        } else {
+            panic!("Parsed hash lock is not the same as provided.");
+        }
+        if let ParsedArgument::Int(height) = parsed.args[4].clone() {
+            assert_eq!(height, 5000);
+        } else {
+            panic!("Parsed refund height is not the same as provided.");
+        }
+
+        let command_str = format!("lock-htlc 10T {} {} not-hex 5000", public_key, claim_key);
+        assert!(parse_command(&command_str).is_err());
+        let command_str = format!("lock-htlc 10T {} {} 5000", public_key, hash_lock);
+        assert!(parse_command(&command_str).is_err());
+
+        let commitment = Commitment::from_public_key(&public_key).to_hex();
+        let command_str = format!("claim-htlc {} {}", commitment, hash_lock);
+        let parsed = parse_command(&command_str).unwrap();
+
+        if let ParsedArgument::Commitment(c) = parsed.args[0].clone() {
+            assert_eq!(c.to_hex(), commitment);
+        } else {
+            panic!("Parsed commitment is not the same as provided.");
+        }
+        assert!(parse_command(&format!("claim-htlc {}", commitment)).is_err());
+
+        let parsed = parse_command(&format!("refund-htlc {} too slow", commitment)).unwrap();
+        if let ParsedArgument::Text(msg) = parsed.args[1].clone() {
+            assert_eq!(msg, "too slow");
+        } else {
+            panic!("Parsed message is not the same as provided.");
+        }
+
+        let (_secret_key, other_key) = PublicKey::random_keypair(&mut OsRng);
+        let command_str = format!("multisig-fund 10T 2 {} {}", public_key, other_key);
+        let parsed = parse_command(&command_str).unwrap();
+
+        if let ParsedArgument::Int(threshold) = parsed.args[1].clone() {
+            assert_eq!(threshold, 2);
+        } else {
+            panic!("Parsed threshold is not the same as provided.");
+        }
+        assert_eq!(parsed.args.len(), 4);
+
+        // the threshold cannot exceed the number of members including this wallet
+        let command_str = format!("multisig-fund 10T 3 {}", public_key);
+        assert!(parse_command(&command_str).is_err());
+        assert!(parse_command("multisig-fund 10T 1").is_err());
+
+        let command_str = format!("multisig-spend {} 5T {} {}", commitment, other_key, public_key);
+        let parsed = parse_command(&command_str).unwrap();
+
+        if let ParsedArgument::PublicKey(dest) = parsed.args[2].clone() {
+            assert_eq!(dest, other_key);
+        } else {
+            panic!("Parsed destination is not the same as provided.");
+        }
+        assert_eq!(parsed.args.len(), 4);
+
+        let command_str = format!("multisig-spend {} 5T {}", commitment, other_key);
+        assert!(parse_command(&command_str).is_err());
+
+        let command_str = format!("multisig-cosign {} 5T {}", commitment, other_key);
+        let parsed = parse_command(&command_str).unwrap();
+        assert_eq!(parsed.args.len(), 3);
+
+        let spending_key = PrivateKey::random(&mut OsRng);
+        let command_str = format!("import-utxo 1T {} faucet", spending_key.to_hex());
+        let parsed = parse_command(&command_str).unwrap();
+
+        if let ParsedArgument::Bytes(bytes) = parsed.args[1].clone() {
+            assert_eq!(bytes, spending_key.to_vec());
+        } else {
+            panic!("Parsed spending key is not the same as provided.");
+        }
+        if let ParsedArgument::Text(msg) = parsed.args[2].clone() {
+            assert_eq!(msg, "faucet");
+        } else {
+            panic!("Parsed message is not the same as provided.");
+        }
+        assert!(parse_command("import-utxo 1T not-a-key").is_err());
     }
 }
@@ -23,29 +23,43 @@
 use super::error::CommandError;
 use crate::automation::command_parser::{ParsedArgument, ParsedCommand};
 use chrono::{DateTime, Utc};
-use futures::{FutureExt, StreamExt};
+use futures::{stream::Fuse, FutureExt, StreamExt};
 use log::*;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::json;
 use std::{
+    convert::TryInto,
     fs::File,
-    io::{LineWriter, Write},
+    io::{self, LineWriter, Write},
     str::FromStr,
     time::{Duration, Instant},
 };
 use strum_macros::{Display, EnumIter, EnumString};
 use tari_common::GlobalConfig;
-use tari_comms::connectivity::{ConnectivityEvent, ConnectivityRequester};
+use tari_comms::{
+    connectivity::{ConnectivityEvent, ConnectivityRequester},
+    types::CommsPublicKey,
+};
 use tari_comms_dht::{envelope::NodeDestination, DhtDiscoveryRequester};
 use tari_core::{
-    tari_utilities::hex::Hex,
+    consensus::ConsensusConstantsBuilder,
+    tari_utilities::{hex::Hex, ByteArray},
     transactions::{
+        fee::Fee,
+        htlc::{generate_preimage, hash_preimage, HTLC_PREIMAGE_LENGTH},
+        one_sided::is_one_sided_payment_script,
         tari_amount::{uT, MicroTari, Tari},
         transaction::OutputFeatures,
+        types::{Commitment, PrivateKey},
     },
 };
-use tari_crypto::ristretto::pedersen::PedersenCommitmentFactory;
+use tari_crypto::{commitment::HomomorphicCommitmentFactory, ristretto::pedersen::PedersenCommitmentFactory};
 use tari_wallet::{
     output_manager_service::{handle::OutputManagerHandle, TxId},
-    transaction_service::handle::{TransactionEvent, TransactionServiceHandle},
+    transaction_service::{
+        handle::{TransactionEvent, TransactionEventReceiver, TransactionServiceHandle},
+        offline_signing::{select_watched_outputs, SignedTransactionBundle, UnsignedTransactionBundle},
+    },
     util::emoji::EmojiId,
     WalletSqlite,
 };
@@ -68,6 +82,31 @@ pub enum WalletCommand {
     Whois,
     ExportUtxos,
     CountUtxos,
+    ExportViewKey,
+    ExportUnsignedTx,
+    SignFile,
+    ImportSignedTx,
+    HtlcSecret,
+    HtlcKey,
+    LockHtlc,
+    ClaimHtlc,
+    RefundHtlc,
+    ListHtlcs,
+    ClaimOneSidedPayments,
+    MultisigFund,
+    MultisigSpend,
+    MultisigCosign,
+    ListMultisig,
+    ImportUtxo,
+}
+
+/// The format in which the command runner reports the results of the commands
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputFormat {
+    /// Human readable text
+    Text,
+    /// One JSON object per command on stdout, for use in scripts. Progress messages are not printed.
+    Json,
 }
 
 #[derive(Debug, EnumString, PartialEq, Clone)]
@@ -144,32 +183,44 @@ pub async fn coin_split(
     Ok(tx_id)
 }
 
-async fn wait_for_comms(connectivity_requester: &ConnectivityRequester) -> Result<bool, CommandError> {
+async fn wait_for_comms(
+    connectivity_requester: &ConnectivityRequester,
+    format: OutputFormat,
+) -> Result<bool, CommandError>
+{
     let mut connectivity = connectivity_requester.get_event_subscription().fuse();
-    print!("Waiting for connectivity... ");
+    if format == OutputFormat::Text {
+        print!("Waiting for connectivity... ");
+    }
     let mut timeout = delay_for(Duration::from_secs(30)).fuse();
     loop {
         futures::select! {
             result = connectivity.select_next_some() => {
                 if let Ok(msg) = result {
                     if let ConnectivityEvent::PeerConnected(_) = (*msg).clone() {
-                        println!("✅");
+                        if format == OutputFormat::Text {
+                            println!("✅");
+                        }
                         return Ok(true);
                     }
                 }
             },
             () = timeout => {
-                println!("❌");
+                if format == OutputFormat::Text {
+                    println!("❌");
+                }
                 return Err(CommandError::Comms("Timed out".to_string()));
             }
         }
     }
 }
 
+/// Discover a peer on the network. A failed discovery is reported in the result rather than as an error.
 pub async fn discover_peer(
     mut dht_service: DhtDiscoveryRequester,
     args: Vec<ParsedArgument>,
-) -> Result<(), CommandError>
+    format: OutputFormat,
+) -> Result<serde_json::Value, CommandError>
 {
     use ParsedArgument::*;
     let dest_public_key = match args[0].clone() {
@@ -177,28 +228,46 @@ pub async fn discover_peer(
         _ => Err(CommandError::Argument),
     }?;
 
+    let public_key_hex = dest_public_key.to_hex();
     let start = Instant::now();
-    println!("🌎 Peer discovery started.");
+    if format == OutputFormat::Text {
+        println!("🌎 Peer discovery started.");
+    }
     match dht_service
         .discover_peer(dest_public_key.clone(), NodeDestination::PublicKey(dest_public_key))
         .await
     {
         Ok(peer) => {
-            println!("⚡️ Discovery succeeded in {}ms.", start.elapsed().as_millis());
-            println!("{}", peer);
+            let elapsed_ms = start.elapsed().as_millis() as u64;
+            if format == OutputFormat::Text {
+                println!("⚡️ Discovery succeeded in {}ms.", elapsed_ms);
+                println!("{}", peer);
+            }
+            Ok(json!({
+                "public_key": public_key_hex,
+                "discovered": true,
+                "elapsed_ms": elapsed_ms,
+                "peer": peer.to_string(),
+            }))
         },
         Err(err) => {
-            println!("💀 Discovery failed: '{:?}'", err);
+            if format == OutputFormat::Text {
+                println!("💀 Discovery failed: '{:?}'", err);
+            }
+            Ok(json!({
+                "public_key": public_key_hex,
+                "discovered": false,
+                "error": err.to_string(),
+            }))
         },
     }
-
-    Ok(())
 }
 
 pub async fn make_it_rain(
     handle: Handle,
     wallet_transaction_service: TransactionServiceHandle,
     args: Vec<ParsedArgument>,
+    format: OutputFormat,
 ) -> Result<Vec<TxId>, CommandError>
 {
     use ParsedArgument::*;
@@ -241,10 +310,12 @@ pub async fn make_it_rain(
     // Wait until specified test start time
     let now = Utc::now();
     let delay_ms = if start_time > now {
-        println!(
-            "`make-it-rain` scheduled to start at {}: msg \"{}\"",
-            start_time, message
-        );
+        if format == OutputFormat::Text {
+            println!(
+                "`make-it-rain` scheduled to start at {}: msg \"{}\"",
+                start_time, message
+            );
+        }
         (start_time - now).num_milliseconds() as u64
     } else {
         0
@@ -293,16 +364,19 @@ pub async fn monitor_transactions(
     transaction_service: TransactionServiceHandle,
     tx_ids: Vec<TxId>,
     wait_stage: TransactionStage,
+    format: OutputFormat,
 ) -> Vec<SentTransaction>
 {
     let mut event_stream = transaction_service.get_event_stream_fused();
     let mut results = Vec::new();
     debug!(target: LOG_TARGET, "monitor transactions wait_stage: {:?}", wait_stage);
-    println!(
-        "Monitoring {} sent transactions to {:?} stage...",
-        tx_ids.len(),
-        wait_stage
-    );
+    if format == OutputFormat::Text {
+        println!(
+            "Monitoring {} sent transactions to {:?} stage...",
+            tx_ids.len(),
+            wait_stage
+        );
+    }
 
     loop {
         match event_stream.next().await {
@@ -409,11 +483,449 @@ pub async fn monitor_transactions(
     results
 }
 
+fn text_arg(args: &[ParsedArgument], index: usize) -> Result<String, CommandError> {
+    match args.get(index) {
+        Some(ParsedArgument::Text(text)) => Ok(text.clone()),
+        _ => Err(CommandError::Argument),
+    }
+}
+
+fn write_transaction_file<T: Serialize>(file: &str, value: &T) -> Result<(), CommandError> {
+    let file = File::create(file).map_err(|e| CommandError::TransactionFile(e.to_string()))?;
+    serde_json::to_writer_pretty(file, value).map_err(|e| CommandError::TransactionFile(e.to_string()))
+}
+
+fn read_transaction_file<T: DeserializeOwned>(file: &str) -> Result<T, CommandError> {
+    let file = File::open(file).map_err(|e| CommandError::TransactionFile(e.to_string()))?;
+    serde_json::from_reader(file).map_err(|e| CommandError::TransactionFile(e.to_string()))
+}
+
+/// Write a one-sided payment spending unspent watched outputs to a file, for the offline wallet that owns them to sign.
+/// Returns the file name and the number of outputs spent.
+pub async fn export_unsigned_transaction(
+    wallet: &WalletSqlite,
+    args: &[ParsedArgument],
+) -> Result<(String, usize), CommandError>
+{
+    // todo: consolidate "fee per gram" in codebase
+    let fee_per_gram = 25 * uT;
+
+    use ParsedArgument::*;
+    let amount = match args[0].clone() {
+        Amount(mtari) => Ok(mtari),
+        _ => Err(CommandError::Argument),
+    }?;
+    let recipient = match args[1].clone() {
+        PublicKey(key) => Ok(key),
+        _ => Err(CommandError::Argument),
+    }?;
+    let file = text_arg(args, 2)?;
+    let message = text_arg(args, 3)?;
+
+    let watched_outputs = wallet.db.get_watched_outputs().await?;
+    let fee_calc = Fee::new(
+        *ConsensusConstantsBuilder::new(wallet.network)
+            .build()
+            .transaction_weight(),
+    );
+    let inputs = select_watched_outputs(&watched_outputs, amount, fee_per_gram, &fee_calc)
+        .ok_or(CommandError::InsufficientWatchedFunds(amount))?;
+    let num_inputs = inputs.len();
+
+    let bundle = UnsignedTransactionBundle {
+        recipient,
+        amount,
+        fee_per_gram,
+        message,
+        inputs,
+    };
+    write_transaction_file(&file, &bundle)?;
+    Ok((file, num_inputs))
+}
+
+/// Show the payment in an unsigned transaction file and ask whether to sign it, as the file comes from another wallet.
+/// The prompt goes to stderr so that it does not mix with JSON output.
+fn confirm_signing(bundle: &UnsignedTransactionBundle) -> Result<bool, CommandError> {
+    eprintln!("Amount      : {}", bundle.amount);
+    eprintln!("Recipient   : {}", bundle.recipient);
+    eprintln!("Emoji id    : {}", EmojiId::from_pubkey(&bundle.recipient));
+    eprintln!("Fee per gram: {}", bundle.fee_per_gram);
+    eprintln!("Inputs      : {}", bundle.inputs.len());
+    eprintln!("Message     : {}", bundle.message);
+    eprint!("Sign this payment? (y/N) ");
+    io::stderr()
+        .flush()
+        .map_err(|e| CommandError::TransactionFile(e.to_string()))?;
+
+    let mut answer = String::new();
+    io::stdin()
+        .read_line(&mut answer)
+        .map_err(|e| CommandError::TransactionFile(e.to_string()))?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Sign the unsigned transaction in the first file with the spending keys of this wallet and write the result to the
+/// second file, once the user has confirmed the amount and recipient. Returns the file name and the id of the
+/// transaction.
+pub async fn sign_file(
+    output_service: &mut OutputManagerHandle,
+    args: &[ParsedArgument],
+) -> Result<(String, TxId), CommandError>
+{
+    let bundle: UnsignedTransactionBundle = read_transaction_file(&text_arg(args, 0)?)?;
+    let file = text_arg(args, 1)?;
+    if !confirm_signing(&bundle)? {
+        return Err(CommandError::SigningDeclined);
+    }
+
+    let (tx_id, _, transaction, _) = output_service
+        .sign_one_sided_transaction(
+            bundle.inputs,
+            bundle.recipient.clone(),
+            bundle.amount,
+            bundle.fee_per_gram,
+            bundle.message.clone(),
+        )
+        .await?;
+    let signed = SignedTransactionBundle {
+        tx_id,
+        recipient: bundle.recipient,
+        amount: bundle.amount,
+        message: bundle.message,
+        transaction,
+    };
+    write_transaction_file(&file, &signed)?;
+    Ok((file, tx_id))
+}
+
+/// Broadcast the transaction in a file written by `sign_file`
+pub async fn import_signed_transaction(
+    mut transaction_service: TransactionServiceHandle,
+    args: &[ParsedArgument],
+) -> Result<TxId, CommandError>
+{
+    let bundle: SignedTransactionBundle = read_transaction_file(&text_arg(args, 0)?)?;
+    transaction_service
+        .submit_one_sided_transaction(
+            bundle.tx_id,
+            bundle.recipient,
+            bundle.transaction,
+            bundle.amount,
+            bundle.message,
+        )
+        .await
+        .map_err(CommandError::Transaction)
+}
+
+/// Lock an amount in a hash time-locked output for the claimer, who can claim it with the preimage of the hash lock and
+/// its HTLC key until this wallet refunds it from the refund height onwards
+pub async fn lock_htlc(
+    mut transaction_service: TransactionServiceHandle,
+    args: &[ParsedArgument],
+) -> Result<TxId, CommandError>
+{
+    // todo: consolidate "fee per gram" in codebase
+    let fee_per_gram = 25 * uT;
+
+    use ParsedArgument::*;
+    let amount = match args[0].clone() {
+        Amount(mtari) => Ok(mtari),
+        _ => Err(CommandError::Argument),
+    }?;
+    let claimer = match args[1].clone() {
+        PublicKey(key) => Ok(key),
+        _ => Err(CommandError::Argument),
+    }?;
+    let claim_key = match args[2].clone() {
+        PublicKey(key) => Ok(key),
+        _ => Err(CommandError::Argument),
+    }?;
+    let hash_lock = match &args[3] {
+        Bytes(bytes) => bytes
+            .as_slice()
+            .try_into()
+            .map_err(|_| CommandError::HashLockLength(HTLC_PREIMAGE_LENGTH)),
+        _ => Err(CommandError::Argument),
+    }?;
+    let refund_height = match args[4] {
+        Int(height) => Ok(height),
+        _ => Err(CommandError::Argument),
+    }?;
+    let message = text_arg(args, 5)?;
+
+    transaction_service
+        .lock_htlc(
+            claimer,
+            claim_key,
+            amount,
+            hash_lock,
+            refund_height,
+            fee_per_gram,
+            message,
+        )
+        .await
+        .map_err(CommandError::Transaction)
+}
+
+/// Claim (with a preimage) or refund (without one) the hash time-locked output with the given commitment
+pub async fn spend_htlc(
+    mut transaction_service: TransactionServiceHandle,
+    args: &[ParsedArgument],
+    claim: bool,
+) -> Result<TxId, CommandError>
+{
+    // todo: consolidate "fee per gram" in codebase
+    let fee_per_gram = 25 * uT;
+
+    use ParsedArgument::*;
+    let commitment = match args[0].clone() {
+        Commitment(commitment) => Ok(commitment),
+        _ => Err(CommandError::Argument),
+    }?;
+    if claim {
+        let preimage = match args[1].clone() {
+            Bytes(bytes) => Ok(bytes),
+            _ => Err(CommandError::Argument),
+        }?;
+        let message = text_arg(args, 2)?;
+        transaction_service
+            .claim_htlc(commitment, preimage, fee_per_gram, message)
+            .await
+            .map_err(CommandError::Transaction)
+    } else {
+        let message = text_arg(args, 1)?;
+        transaction_service
+            .refund_htlc(commitment, fee_per_gram, message)
+            .await
+            .map_err(CommandError::Transaction)
+    }
+}
+
+/// Claim every unspent one-sided payment output received by this wallet, moving the funds to a normal output. Returns
+/// the commitment of each claimed output with the transaction that spent it.
+pub async fn claim_one_sided_payments(
+    mut output_service: OutputManagerHandle,
+    mut transaction_service: TransactionServiceHandle,
+    args: &[ParsedArgument],
+) -> Result<Vec<(Commitment, TxId)>, CommandError>
+{
+    // todo: consolidate "fee per gram" in codebase
+    let fee_per_gram = 25 * uT;
+
+    let message = text_arg(args, 0)?;
+    let factory = PedersenCommitmentFactory::default();
+    let commitments = output_service
+        .get_unspent_outputs()
+        .await?
+        .into_iter()
+        .filter(|utxo| utxo.features.script.as_ref().map_or(false, is_one_sided_payment_script))
+        .map(|utxo| factory.commit_value(&utxo.spending_key, utxo.value.into()))
+        .collect::<Vec<_>>();
+
+    let mut claimed = Vec::with_capacity(commitments.len());
+    for commitment in commitments {
+        let tx_id = transaction_service
+            .claim_one_sided_payment(commitment.clone(), fee_per_gram, message.clone())
+            .await
+            .map_err(CommandError::Transaction)?;
+        claimed.push((commitment, tx_id));
+    }
+    Ok(claimed)
+}
+
+fn public_key_args(args: &[ParsedArgument]) -> Result<Vec<CommsPublicKey>, CommandError> {
+    args.iter()
+        .map(|arg| match arg {
+            ParsedArgument::PublicKey(key) => Ok(key.clone()),
+            _ => Err(CommandError::Argument),
+        })
+        .collect()
+}
+
+/// Wait until the multisig session started by this wallet has produced its transaction. The session is cancelled if
+/// it does not finish within `wait`, as the other participants may never reply.
+async fn wait_for_multisig_session(
+    mut transaction_service: TransactionServiceHandle,
+    mut event_stream: Fuse<TransactionEventReceiver>,
+    session_id: u64,
+    wait: Duration,
+) -> Result<TxId, CommandError>
+{
+    let result = timeout(wait, async {
+        while let Some(event_result) = event_stream.next().await {
+            match event_result {
+                Ok(event) => match &*event {
+                    TransactionEvent::MultisigSessionCompleted(id, tx_id) if *id == session_id => return Ok(*tx_id),
+                    TransactionEvent::MultisigSessionFailed(id, reason) if *id == session_id => {
+                        return Err(CommandError::MultisigSession(session_id, reason.clone()))
+                    },
+                    _ => {},
+                },
+                Err(e) => warn!(target: LOG_TARGET, "RecvError in wait_for_multisig_session: {:?}", e),
+            }
+        }
+        Err(CommandError::MultisigSession(
+            session_id,
+            "the event stream has closed".to_string(),
+        ))
+    })
+    .await;
+
+    match result {
+        Ok(result) => result,
+        Err(_) => {
+            transaction_service.cancel_multisig_session(session_id).await?;
+            Err(CommandError::MultisigTimeout(session_id))
+        },
+    }
+}
+
+/// Lock an amount in a multisig output shared with the given members and wait for the funding transaction
+pub async fn multisig_fund(
+    mut transaction_service: TransactionServiceHandle,
+    args: &[ParsedArgument],
+    wait: Duration,
+) -> Result<(u64, TxId), CommandError>
+{
+    // todo: consolidate "fee per gram" in codebase
+    let fee_per_gram = 25 * uT;
+
+    use ParsedArgument::*;
+    let amount = match args[0].clone() {
+        Amount(mtari) => Ok(mtari),
+        _ => Err(CommandError::Argument),
+    }?;
+    let threshold = match args[1] {
+        Int(threshold) => threshold.try_into().map_err(|_| CommandError::Argument),
+        _ => Err(CommandError::Argument),
+    }?;
+    let members = public_key_args(&args[2..])?;
+
+    // subscribe before the session starts so that its result cannot be missed
+    let event_stream = transaction_service.get_event_stream_fused();
+    let session_id = transaction_service
+        .fund_multisig(amount, threshold, members, fee_per_gram, "Multisig funding".to_string())
+        .await?;
+    let tx_id = wait_for_multisig_session(transaction_service, event_stream, session_id, wait).await?;
+    Ok((session_id, tx_id))
+}
+
+/// Pay from a multisig output with the given cosigners and wait for the spending transaction
+pub async fn multisig_spend(
+    mut transaction_service: TransactionServiceHandle,
+    args: &[ParsedArgument],
+    wait: Duration,
+) -> Result<(u64, TxId), CommandError>
+{
+    // todo: consolidate "fee per gram" in codebase
+    let fee_per_gram = 25 * uT;
+
+    use ParsedArgument::*;
+    let commitment = match args[0].clone() {
+        Commitment(commitment) => Ok(commitment),
+        _ => Err(CommandError::Argument),
+    }?;
+    let amount = match args[1].clone() {
+        Amount(mtari) => Ok(mtari),
+        _ => Err(CommandError::Argument),
+    }?;
+    let dest = match args[2].clone() {
+        PublicKey(key) => Ok(key),
+        _ => Err(CommandError::Argument),
+    }?;
+    let cosigners = public_key_args(&args[3..])?;
+
+    let event_stream = transaction_service.get_event_stream_fused();
+    let session_id = transaction_service
+        .spend_multisig(
+            commitment,
+            amount,
+            dest,
+            cosigners,
+            fee_per_gram,
+            "Multisig payment".to_string(),
+        )
+        .await?;
+    let tx_id = wait_for_multisig_session(transaction_service, event_stream, session_id, wait).await?;
+    Ok((session_id, tx_id))
+}
+
+/// Approve co-signing a payment from a multisig output and wait until this wallet has sent its partial signature
+pub async fn multisig_cosign(
+    mut transaction_service: TransactionServiceHandle,
+    args: &[ParsedArgument],
+    wait: Duration,
+) -> Result<u64, CommandError>
+{
+    use ParsedArgument::*;
+    let commitment = match args[0].clone() {
+        Commitment(commitment) => Ok(commitment),
+        _ => Err(CommandError::Argument),
+    }?;
+    let amount = match args[1].clone() {
+        Amount(mtari) => Ok(mtari),
+        _ => Err(CommandError::Argument),
+    }?;
+    let dest = match args[2].clone() {
+        PublicKey(key) => Ok(key),
+        _ => Err(CommandError::Argument),
+    }?;
+
+    let mut event_stream = transaction_service.get_event_stream_fused();
+    transaction_service.cosign_multisig(commitment, amount, dest).await?;
+    let signed = timeout(wait, async {
+        while let Some(event_result) = event_stream.next().await {
+            if let Ok(event) = event_result {
+                if let TransactionEvent::MultisigPartialSignatureSent(session_id) = &*event {
+                    return Some(*session_id);
+                }
+            }
+        }
+        None
+    })
+    .await;
+
+    match signed {
+        Ok(Some(session_id)) => Ok(session_id),
+        _ => Err(CommandError::MultisigCosignTimeout),
+    }
+}
+
+/// Import an output created outside of the wallet, e.g. by a faucet, from its value and spending key. Returns the id
+/// of the transaction that records the import.
+pub async fn import_utxo(wallet: &mut WalletSqlite, args: &[ParsedArgument]) -> Result<TxId, CommandError> {
+    use ParsedArgument::*;
+    let amount = match args[0].clone() {
+        Amount(mtari) => Ok(mtari),
+        _ => Err(CommandError::Argument),
+    }?;
+    let spending_key = match &args[1] {
+        Bytes(bytes) => PrivateKey::from_bytes(bytes).map_err(|_| CommandError::Argument),
+        _ => Err(CommandError::Argument),
+    }?;
+    let message = text_arg(args, 2)?;
+
+    let source_public_key = wallet.comms.node_identity().public_key().clone();
+    let tx_id = wallet
+        .import_utxo(
+            amount,
+            &spending_key,
+            &source_public_key,
+            OutputFeatures::default(),
+            message,
+        )
+        .await?;
+    Ok(tx_id)
+}
+
+/// Run the wallet commands in order, stopping at the first command that fails. Transactions sent by the commands are
+/// then monitored until they reach the configured wait stage.
 pub async fn command_runner(
     handle: Handle,
     commands: Vec<ParsedCommand>,
-    wallet: WalletSqlite,
+    mut wallet: WalletSqlite,
     config: GlobalConfig,
+    format: OutputFormat,
 ) -> Result<(), CommandError>
 {
     let wait_stage = TransactionStage::from_str(&config.wallet_command_send_wait_stage)
@@ -424,107 +936,447 @@ pub async fn command_runner(
     let dht_service = wallet.dht_service.discovery_service_requester().clone();
     let connectivity_requester = wallet.comms.connectivity();
     let mut online = false;
+    let watch_only = wallet.db.get_watch_only_keys().await?.is_some();
 
     let mut tx_ids = Vec::new();
+    // multisig sessions wait for the other participants for as long as sent transactions are monitored
+    let multisig_wait = Duration::from_secs(config.wallet_command_send_wait_timeout);
 
-    println!("==============");
-    println!("Command Runner");
-    println!("==============");
+    let text = format == OutputFormat::Text;
+    if text {
+        println!("==============");
+        println!("Command Runner");
+        println!("==============");
+    }
     use WalletCommand::*;
     for (idx, parsed) in commands.into_iter().enumerate() {
-        println!("\n{}. {}\n", idx + 1, parsed);
+        if text {
+            println!("\n{}. {}\n", idx + 1, parsed);
+        }
+        let command = parsed.command.to_string();
 
-        match parsed.command {
-            GetBalance => match output_service.clone().get_balance().await {
-                Ok(balance) => {
-                    println!("{}", balance);
+        let result = async {
+            if watch_only &&
+                matches!(
+                    parsed.command,
+                    SendTari |
+                        MakeItRain |
+                        CoinSplit |
+                        SignFile |
+                        HtlcKey |
+                        LockHtlc |
+                        ClaimHtlc |
+                        RefundHtlc |
+                        ClaimOneSidedPayments |
+                        MultisigFund |
+                        MultisigSpend |
+                        MultisigCosign |
+                        ImportUtxo
+                )
+            {
+                return Err(CommandError::WatchOnly);
+            }
+            if !watch_only && parsed.command == ExportUnsignedTx {
+                return Err(CommandError::NotWatchOnly);
+            }
+            let value = match parsed.command {
+                GetBalance if watch_only => {
+                    let balance: MicroTari = wallet
+                        .db
+                        .get_watched_outputs()
+                        .await?
+                        .iter()
+                        .filter(|o| o.spent_at.is_none())
+                        .map(|o| o.value)
+                        .sum();
+                    if text {
+                        println!("Watched balance: {}", balance);
+                    }
+                    json!({ "watched_balance": balance.0 })
                 },
-                Err(e) => eprintln!("GetBalance error! {}", e),
-            },
-            DiscoverPeer => {
-                if !online {
-                    online = wait_for_comms(&connectivity_requester).await?;
-                }
-                discover_peer(dht_service.clone(), parsed.args).await?
-            },
-            SendTari => {
-                let tx_id = send_tari(transaction_service.clone(), parsed.args).await?;
-                debug!(target: LOG_TARGET, "send-tari tx_id {}", tx_id);
-                tx_ids.push(tx_id);
-            },
-            MakeItRain => {
-                let rain_ids = make_it_rain(handle.clone(), transaction_service.clone(), parsed.args).await?;
-                tx_ids.extend(rain_ids);
-            },
-            CoinSplit => {
-                let tx_id = coin_split(&parsed.args, &mut output_service, &mut transaction_service.clone()).await?;
-                tx_ids.push(tx_id);
-                println!("Coin split succeeded");
-            },
-            Whois => {
-                let public_key = match parsed.args[0].clone() {
-                    ParsedArgument::PublicKey(key) => Ok(Box::new(key)),
-                    _ => Err(CommandError::Argument),
-                }?;
-                let emoji_id = EmojiId::from_pubkey(&public_key);
-
-                println!("Public Key: {}", public_key.to_hex());
-                println!("Emoji ID  : {}", emoji_id);
-            },
-            ExportUtxos => {
-                let utxos = output_service.get_unspent_outputs().await?;
-                let count = utxos.len();
-                let sum: MicroTari = utxos.iter().map(|utxo| utxo.value).sum();
-                if parsed.args.is_empty() {
-                    for (i, utxo) in utxos.iter().enumerate() {
-                        println!("{}. Value: {} {}", i + 1, utxo.value, utxo.features);
+                GetBalance => {
+                    let balance = output_service.clone().get_balance().await?;
+                    if text {
+                        println!("{}", balance);
                     }
-                } else if let ParsedArgument::CSVFileName(file) = parsed.args[1].clone() {
-                    let factory = PedersenCommitmentFactory::default();
-                    let file = File::create(file).map_err(|e| CommandError::CSVFile(e.to_string()))?;
-                    let mut csv_file = LineWriter::new(file);
-                    writeln!(
-                        csv_file,
-                        r##""#","Value (uT)","Spending Key","Commitment","Flags","Maturity""##
-                    )
-                    .map_err(|e| CommandError::CSVFile(e.to_string()))?;
-                    for (i, utxo) in utxos.iter().enumerate() {
+                    json!({
+                        "available_balance": balance.available_balance.0,
+                        "time_locked_balance": balance.time_locked_balance.map(|b| b.0),
+                        "pending_incoming_balance": balance.pending_incoming_balance.0,
+                        "pending_outgoing_balance": balance.pending_outgoing_balance.0,
+                    })
+                },
+                DiscoverPeer => {
+                    if !online {
+                        online = wait_for_comms(&connectivity_requester, format).await?;
+                    }
+                    discover_peer(dht_service.clone(), parsed.args, format).await?
+                },
+                SendTari => {
+                    let tx_id = send_tari(transaction_service.clone(), parsed.args).await?;
+                    debug!(target: LOG_TARGET, "send-tari tx_id {}", tx_id);
+                    tx_ids.push(tx_id);
+                    json!({ "tx_id": tx_id })
+                },
+                MakeItRain => {
+                    let rain_ids =
+                        make_it_rain(handle.clone(), transaction_service.clone(), parsed.args, format).await?;
+                    tx_ids.extend(rain_ids.iter().cloned());
+                    json!({ "tx_ids": rain_ids })
+                },
+                CoinSplit => {
+                    let tx_id = coin_split(&parsed.args, &mut output_service, &mut transaction_service.clone()).await?;
+                    tx_ids.push(tx_id);
+                    if text {
+                        println!("Coin split succeeded");
+                    }
+                    json!({ "tx_id": tx_id })
+                },
+                Whois => {
+                    let public_key = match parsed.args[0].clone() {
+                        ParsedArgument::PublicKey(key) => Ok(Box::new(key)),
+                        _ => Err(CommandError::Argument),
+                    }?;
+                    let emoji_id = EmojiId::from_pubkey(&public_key);
+
+                    if text {
+                        println!("Public Key: {}", public_key.to_hex());
+                        println!("Emoji ID  : {}", emoji_id);
+                    }
+                    json!({
+                        "public_key": public_key.to_hex(),
+                        "emoji_id": emoji_id.to_string(),
+                    })
+                },
+                ExportUtxos => {
+                    let utxos = output_service.get_unspent_outputs().await?;
+                    let count = utxos.len();
+                    let sum: MicroTari = utxos.iter().map(|utxo| utxo.value).sum();
+                    let mut value = json!({
+                        "count": count,
+                        "total_value": sum.0,
+                    });
+                    if parsed.args.is_empty() {
+                        if text {
+                            for (i, utxo) in utxos.iter().enumerate() {
+                                println!("{}. Value: {} {}", i + 1, utxo.value, utxo.features);
+                            }
+                        }
+                        value["utxos"] = utxos
+                            .iter()
+                            .map(|utxo| {
+                                json!({
+                                    "value": utxo.value.0,
+                                    "flags": format!("{:?}", utxo.features.flags),
+                                    "maturity": utxo.features.maturity,
+                                })
+                            })
+                            .collect();
+                    } else if let ParsedArgument::CSVFileName(file) = parsed.args[1].clone() {
+                        value["file"] = json!(file);
+                        let factory = PedersenCommitmentFactory::default();
+                        let file = File::create(file).map_err(|e| CommandError::CSVFile(e.to_string()))?;
+                        let mut csv_file = LineWriter::new(file);
                         writeln!(
                             csv_file,
-                            r##""{}","{}","{}","{}","{:?}","{}""##,
-                            i + 1,
-                            utxo.value.0,
-                            utxo.spending_key.to_hex(),
-                            utxo.as_transaction_input(&factory, OutputFeatures::default())
-                                .commitment
-                                .to_hex(),
-                            utxo.features.flags,
-                            utxo.features.maturity,
+                            r##""#","Value (uT)","Spending Key","Commitment","Flags","Maturity""##
                         )
                         .map_err(|e| CommandError::CSVFile(e.to_string()))?;
+                        for (i, utxo) in utxos.iter().enumerate() {
+                            writeln!(
+                                csv_file,
+                                r##""{}","{}","{}","{}","{:?}","{}""##,
+                                i + 1,
+                                utxo.value.0,
+                                utxo.spending_key.to_hex(),
+                                utxo.as_transaction_input(&factory, OutputFeatures::default())
+                                    .commitment
+                                    .to_hex(),
+                                utxo.features.flags,
+                                utxo.features.maturity,
+                            )
+                            .map_err(|e| CommandError::CSVFile(e.to_string()))?;
+                        }
+                    }
+                    if text {
+                        println!("Total number of UTXOs: {}", count);
+                        println!("Total value of UTXOs: {}", sum);
+                    }
+                    value
+                },
+                CountUtxos => {
+                    let utxos = output_service.get_unspent_outputs().await?;
+                    let count = utxos.len();
+                    let values: Vec<MicroTari> = utxos.iter().map(|utxo| utxo.value).collect();
+                    let sum: MicroTari = values.iter().sum();
+                    let min = values.iter().min();
+                    let max = values.iter().max();
+                    let average = if count > 0 {
+                        Some(f64::from(sum) / count as f64)
+                    } else {
+                        None
+                    };
+                    if text {
+                        println!("Total number of UTXOs: {}", count);
+                        println!("Total value of UTXOs : {}", sum);
+                        if let Some(min) = min {
+                            println!("Minimum value UTXO   : {}", min);
+                        }
+                        if let Some(average) = average {
+                            println!("Average value UTXO   : {}", Tari::from(average / 1_000_000f64));
+                        }
+                        if let Some(max) = max {
+                            println!("Maximum value UTXO   : {}", max);
+                        }
+                    }
+                    json!({
+                        "count": count,
+                        "total_value": sum.0,
+                        "min_value": min.map(|v| v.0),
+                        "average_value": average,
+                        "max_value": max.map(|v| v.0),
+                    })
+                },
+                ExportViewKey => {
+                    let view_key = output_service.get_rewind_public_keys().await?.to_string();
+                    if text {
+                        println!("View key: {}", view_key);
+                        println!(
+                            "Start a watch-only wallet with --watch-only <view key> to follow this wallet's balance."
+                        );
+                    }
+                    json!({ "view_key": view_key })
+                },
+                ExportUnsignedTx => {
+                    let (file, inputs) = export_unsigned_transaction(&wallet, &parsed.args).await?;
+                    if text {
+                        println!(
+                            "Wrote an unsigned transaction spending {} watched outputs to {}",
+                            inputs, file
+                        );
+                        println!("Sign it with sign-file in the offline wallet, then import-signed-tx the result.");
+                    }
+                    json!({ "file": file, "inputs": inputs })
+                },
+                SignFile => {
+                    let (file, tx_id) = sign_file(&mut output_service, &parsed.args).await?;
+                    if text {
+                        println!("Wrote the signed transaction {} to {}", tx_id, file);
+                    }
+                    json!({ "file": file, "tx_id": tx_id })
+                },
+                ImportSignedTx => {
+                    let tx_id = import_signed_transaction(transaction_service.clone(), &parsed.args).await?;
+                    tx_ids.push(tx_id);
+                    if text {
+                        println!("Broadcasting the signed transaction {}", tx_id);
+                    }
+                    json!({ "tx_id": tx_id })
+                },
+                HtlcSecret => {
+                    let preimage = generate_preimage();
+                    let hash_lock = hash_preimage(&preimage);
+                    if text {
+                        println!("Preimage : {}", preimage.to_vec().to_hex());
+                        println!("Hash lock: {}", hash_lock.to_vec().to_hex());
+                        println!("Keep the preimage secret until the swap is claimed, it is not stored in the wallet.");
+                    }
+                    json!({ "preimage": preimage.to_vec().to_hex(), "hash_lock": hash_lock.to_vec().to_hex() })
+                },
+                HtlcKey => {
+                    let key = output_service.get_htlc_public_key().await?;
+                    if text {
+                        println!("HTLC key: {}", key.to_hex());
+                        println!("Give this key to the party that locks a swap for this wallet to claim.");
                     }
+                    json!({ "htlc_key": key.to_hex() })
+                },
+                LockHtlc => {
+                    let tx_id = lock_htlc(transaction_service.clone(), &parsed.args).await?;
+                    tx_ids.push(tx_id);
+                    if text {
+                        println!("Locked the swap in transaction {}", tx_id);
+                    }
+                    json!({ "tx_id": tx_id })
+                },
+                ClaimHtlc | RefundHtlc => {
+                    let claim = parsed.command == ClaimHtlc;
+                    let tx_id = spend_htlc(transaction_service.clone(), &parsed.args, claim).await?;
+                    tx_ids.push(tx_id);
+                    if text {
+                        let action = if claim { "Claimed" } else { "Refunded" };
+                        println!("{} the swap in transaction {}", action, tx_id);
+                    }
+                    json!({ "tx_id": tx_id })
+                },
+                ListHtlcs => {
+                    let public_key = output_service.get_htlc_public_key().await?;
+                    let factory = PedersenCommitmentFactory::default();
+                    let htlcs = output_service
+                        .get_htlc_outputs()
+                        .await?
+                        .into_iter()
+                        .filter_map(|utxo| {
+                            let commitment = utxo
+                                .as_transaction_input(&factory, OutputFeatures::default())
+                                .commitment;
+                            utxo.features.htlc.map(|htlc| (utxo.value, commitment, htlc))
+                        })
+                        .collect::<Vec<_>>();
+                    if text {
+                        for (i, (value, commitment, htlc)) in htlcs.iter().enumerate() {
+                            let role = if htlc.claim_public_key == public_key {
+                                "claim"
+                            } else {
+                                "refund"
+                            };
+                            println!(
+                                "{}. Value: {} Commitment: {} Hash lock: {} Refund height: {} ({})",
+                                i + 1,
+                                value,
+                                commitment.to_hex(),
+                                htlc.hash_lock.to_vec().to_hex(),
+                                htlc.refund_height,
+                                role
+                            );
+                        }
+                        println!("Total number of HTLCs: {}", htlcs.len());
+                    }
+                    let htlcs = htlcs
+                        .iter()
+                        .map(|(value, commitment, htlc)| {
+                            json!({
+                                "value": value.0,
+                                "commitment": commitment.to_hex(),
+                                "hash_lock": htlc.hash_lock.to_vec().to_hex(),
+                                "refund_height": htlc.refund_height,
+                                "claimable": htlc.claim_public_key == public_key,
+                            })
+                        })
+                        .collect::<Vec<_>>();
+                    json!({ "htlcs": htlcs })
+                },
+                ClaimOneSidedPayments => {
+                    let claimed =
+                        claim_one_sided_payments(output_service.clone(), transaction_service.clone(), &parsed.args)
+                            .await?;
+                    if text {
+                        for (commitment, tx_id) in claimed.iter() {
+                            println!("Claimed the payment {} in transaction {}", commitment.to_hex(), tx_id);
+                        }
+                        println!("Total number of claimed payments: {}", claimed.len());
+                    }
+                    let claimed = claimed
+                        .into_iter()
+                        .map(|(commitment, tx_id)| {
+                            tx_ids.push(tx_id);
+                            json!({ "commitment": commitment.to_hex(), "tx_id": tx_id })
+                        })
+                        .collect::<Vec<_>>();
+                    json!({ "claimed": claimed })
+                },
+                MultisigFund => {
+                    if !online {
+                        online = wait_for_comms(&connectivity_requester, format).await?;
+                    }
+                    let (session_id, tx_id) =
+                        multisig_fund(transaction_service.clone(), &parsed.args, multisig_wait).await?;
+                    tx_ids.push(tx_id);
+                    if text {
+                        println!("Funded the multisig output in transaction {}", tx_id);
+                    }
+                    json!({ "session_id": session_id, "tx_id": tx_id })
+                },
+                MultisigSpend => {
+                    if !online {
+                        online = wait_for_comms(&connectivity_requester, format).await?;
+                    }
+                    let (session_id, tx_id) =
+                        multisig_spend(transaction_service.clone(), &parsed.args, multisig_wait).await?;
+                    tx_ids.push(tx_id);
+                    if text {
+                        println!("Spent the multisig output in transaction {}", tx_id);
+                    }
+                    json!({ "session_id": session_id, "tx_id": tx_id })
+                },
+                MultisigCosign => {
+                    if !online {
+                        online = wait_for_comms(&connectivity_requester, format).await?;
+                    }
+                    if text {
+                        println!("Waiting for the sign request...");
+                    }
+                    let session_id = multisig_cosign(transaction_service.clone(), &parsed.args, multisig_wait).await?;
+                    if text {
+                        println!("Sent the partial signature for session {}", session_id);
+                    }
+                    json!({ "session_id": session_id })
+                },
+                ListMultisig => {
+                    let factory = PedersenCommitmentFactory::default();
+                    let outputs = output_service
+                        .get_multisig_outputs()
+                        .await?
+                        .into_iter()
+                        .filter_map(|utxo| {
+                            let commitment = utxo
+                                .as_transaction_input(&factory, OutputFeatures::default())
+                                .commitment;
+                            utxo.features.multisig.map(|lock| (utxo.value, commitment, lock))
+                        })
+                        .collect::<Vec<_>>();
+                    if text {
+                        for (i, (value, commitment, lock)) in outputs.iter().enumerate() {
+                            println!(
+                                "{}. Value: {} Commitment: {} ({}-of-{})",
+                                i + 1,
+                                value,
+                                commitment.to_hex(),
+                                lock.threshold,
+                                lock.public_keys.len()
+                            );
+                            for key in &lock.public_keys {
+                                println!("   Signer key: {}", key.to_hex());
+                            }
+                        }
+                        println!("Total number of multisig outputs: {}", outputs.len());
+                    }
+                    let outputs = outputs
+                        .iter()
+                        .map(|(value, commitment, lock)| {
+                            json!({
+                                "value": value.0,
+                                "commitment": commitment.to_hex(),
+                                "threshold": lock.threshold,
+                                "public_keys": lock.public_keys.iter().map(|k| k.to_hex()).collect::<Vec<_>>(),
+                            })
+                        })
+                        .collect::<Vec<_>>();
+                    json!({ "multisig_outputs": outputs })
+                },
+                ImportUtxo => {
+                    let tx_id = import_utxo(&mut wallet, &parsed.args).await?;
+                    if text {
+                        println!("Imported the output in transaction {}", tx_id);
+                    }
+                    json!({ "tx_id": tx_id })
+                },
+            };
+            Result::<_, CommandError>::Ok(value)
+        }
+        .await;
+
+        match result {
+            Ok(value) => {
+                if !text {
+                    println!("{}", json!({ "command": command, "result": value }));
                 }
-                println!("Total number of UTXOs: {}", count);
-                println!("Total value of UTXOs: {}", sum);
             },
-            CountUtxos => {
-                let utxos = output_service.get_unspent_outputs().await?;
-                let count = utxos.len();
-                let values: Vec<MicroTari> = utxos.iter().map(|utxo| utxo.value).collect();
-                let sum: MicroTari = values.iter().sum();
-                println!("Total number of UTXOs: {}", count);
-                println!("Total value of UTXOs : {}", sum);
-                if let Some(min) = values.iter().min() {
-                    println!("Minimum value UTXO   : {}", min);
-                }
-                if count > 0 {
-                    let average = f64::from(sum) / count as f64;
-                    let average = Tari::from(average / 1_000_000f64);
-                    println!("Average value UTXO   : {}", average);
-                }
-                if let Some(max) = values.iter().max() {
-                    println!("Maximum value UTXO   : {}", max);
+            Err(err) => {
+                if !text {
+                    println!("{}", json!({ "command": command, "error": err.to_string() }));
                 }
+                return Err(err);
             },
         }
     }
@@ -538,7 +1390,7 @@ pub async fn command_runner(
         );
         match timeout(
             duration,
-            monitor_transactions(transaction_service.clone(), tx_ids, wait_stage.clone()),
+            monitor_transactions(transaction_service.clone(), tx_ids, wait_stage.clone(), format),
         )
         .await
         {
@@ -547,14 +1399,40 @@ pub async fn command_runner(
                     target: LOG_TARGET,
                     "monitor_transactions done to stage {:?} with tx_ids: {:?}", wait_stage, txs
                 );
-                println!("Done! All transactions monitored to {:?} stage.", wait_stage);
+                if text {
+                    println!("Done! All transactions monitored to {:?} stage.", wait_stage);
+                } else {
+                    let transactions = txs
+                        .iter()
+                        .map(|tx| json!({ "tx_id": tx.id, "stage": format!("{:?}", tx.stage) }))
+                        .collect::<Vec<_>>();
+                    println!(
+                        "{}",
+                        json!({
+                            "wait_stage": format!("{:?}", wait_stage),
+                            "completed": true,
+                            "transactions": transactions,
+                        })
+                    );
+                }
             },
             Err(_e) => {
-                println!(
-                    "The configured timeout ({:#?}s) was reached before all transactions reached the {:?} stage. See \
-                     the logs for more info.",
-                    duration, wait_stage
-                );
+                if text {
+                    println!(
+                        "The configured timeout ({:#?}s) was reached before all transactions reached the {:?} stage. \
+                         See the logs for more info.",
+                        duration, wait_stage
+                    );
+                } else {
+                    println!(
+                        "{}",
+                        json!({
+                            "wait_stage": format!("{:?}", wait_stage),
+                            "completed": false,
+                        })
+                    );
+                }
+                return Err(CommandError::TransactionTimeout(format!("{:?}", wait_stage)));
             },
         }
     } else {
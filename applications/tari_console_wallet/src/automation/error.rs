@@ -25,8 +25,9 @@ use std::num::{ParseFloatError, ParseIntError};
 use chrono_english::DateError;
 use log::*;
 use tari_app_utilities::utilities::ExitCodes;
-use tari_core::transactions::tari_amount::MicroTariError;
+use tari_core::transactions::tari_amount::{MicroTari, MicroTariError};
 use tari_wallet::{
+    error::{WalletError, WalletStorageError},
     output_manager_service::error::OutputManagerError,
     transaction_service::error::TransactionServiceError,
 };
@@ -51,6 +52,30 @@ pub enum CommandError {
     Comms(String),
     #[error("CSV file error `{0}`")]
     CSVFile(String),
+    #[error("Timed out before all transactions reached the `{0}` stage")]
+    TransactionTimeout(String),
+    #[error("Wallet storage error `{0}`")]
+    WalletStorage(#[from] WalletStorageError),
+    #[error("Wallet error `{0}`")]
+    Wallet(#[from] WalletError),
+    #[error("A watch-only wallet cannot send transactions")]
+    WatchOnly,
+    #[error("Only a watch-only wallet can export an unsigned transaction")]
+    NotWatchOnly,
+    #[error("There are not enough unspent watched outputs to pay `{0}` and the fee")]
+    InsufficientWatchedFunds(MicroTari),
+    #[error("Transaction file error `{0}`")]
+    TransactionFile(String),
+    #[error("The transaction was not signed")]
+    SigningDeclined,
+    #[error("The hash lock must be {0} bytes")]
+    HashLockLength(usize),
+    #[error("Multisig session `{0}` failed: {1}")]
+    MultisigSession(u64, String),
+    #[error("Timed out waiting for the other participants in multisig session `{0}`, the session was cancelled")]
+    MultisigTimeout(u64),
+    #[error("Timed out waiting for a matching multisig sign request")]
+    MultisigCosignTimeout,
 }
 
 impl From<CommandError> for ExitCodes {
@@ -76,6 +101,8 @@ pub enum ParseError {
     Int(#[from] ParseIntError),
     #[error("Failed to parse date. {0}")]
     Date(#[from] DateError),
+    #[error("Failed to parse the {0} as hex.")]
+    Hex(String),
     #[error("Invalid combination of arguments.")]
     Invalid,
     #[error("Parsing not yet implemented for {0}.")]
@@ -103,7 +103,12 @@ impl wallet_server::Wallet for WalletGrpcServer {
 
         let mut tx_service = self.get_transaction_service();
         let response = tx_service
-            .generate_coinbase_transaction(request.reward.into(), request.fee.into(), request.height)
+            .generate_coinbase_transaction_with_extra(
+                request.reward.into(),
+                request.fee.into(),
+                request.height,
+                request.extra,
+            )
             .await;
 
         match response {
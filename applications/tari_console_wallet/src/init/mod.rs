@@ -20,16 +20,28 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+mod profile;
+pub use profile::{profile_config, select_profile, validate_profile_name, Profiles};
+
 use crate::{
-    utils::db::get_custom_base_node_peer_from_db,
+    utils::db::{get_custom_base_node_peer_from_db, get_saved_base_nodes_from_db},
     wallet_modes::{PeerConfig, WalletMode},
 };
 use log::*;
 use rand::rngs::OsRng;
 use rpassword::prompt_password_stdout;
 use rustyline::Editor;
-use std::{fs, path::PathBuf, str::FromStr, sync::Arc};
-use tari_app_utilities::utilities::{setup_wallet_transport_type, ExitCodes};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+use tari_app_utilities::{
+    utilities::{setup_wallet_transport_type, ExitCodes},
+    wallet_tor::setup_wallet_tor_transport,
+};
 use tari_common::{ConfigBootstrap, GlobalConfig, Network};
 use tari_comms::{
     peer_manager::{Peer, PeerFeatures},
@@ -38,7 +50,10 @@ use tari_comms::{
 use tari_comms_dht::{DbConnectionUrl, DhtConfig};
 use tari_core::{
     consensus::Network as NetworkType,
-    transactions::types::{CryptoFactories, PrivateKey},
+    transactions::{
+        tari_amount::MicroTari,
+        types::{CryptoFactories, PrivateKey},
+    },
 };
 use tari_crypto::keys::SecretKey;
 use tari_p2p::{
@@ -53,6 +68,8 @@ use tari_wallet::{
     error::{WalletError, WalletStorageError},
     output_manager_service::{
         config::OutputManagerServiceConfig,
+        handle::PublicRewindKeys,
+        key_manager_backend::KeyManagerBackendType,
         protocols::txo_validation_protocol::TxoValidationType,
         storage::{
             database::{
@@ -63,8 +80,10 @@ use tari_wallet::{
             },
             sqlite_db::OutputManagerSqliteDatabase,
         },
+        utxo_selection::UtxoSelectionPolicy,
     },
     storage::{
+        backup::restore_backup,
         database::{DbKey, DbKeyValuePair, DbValue, WalletBackend, WriteOperation},
         sqlite_utilities::initialize_sqlite_database_backends,
     },
@@ -73,6 +92,7 @@ use tari_wallet::{
         tasks::start_transaction_validation_and_broadcast_protocols::start_transaction_validation_and_broadcast_protocols,
     },
     types::ValidationRetryStrategy,
+    util::encryption::KdfParameters,
     wallet::WalletConfig,
     Wallet,
     WalletSqlite,
@@ -96,6 +116,21 @@ pub fn get_or_prompt_password(
     arg_password: Option<String>,
     config_password: Option<String>,
 ) -> Result<Option<String>, ExitCodes>
+{
+    if let Some(password) = get_password(arg_password, config_password)? {
+        return Ok(Some(password));
+    }
+
+    let password = prompt_password("Wallet password: ")?;
+
+    Ok(Some(password))
+}
+
+/// Gets the password provided by command line argument, environment variable or config, without prompting for it.
+pub fn get_password(
+    arg_password: Option<String>,
+    config_password: Option<String>,
+) -> Result<Option<String>, ExitCodes>
 {
     if arg_password.is_some() {
         return Ok(arg_password);
@@ -110,13 +145,7 @@ pub fn get_or_prompt_password(
         return Ok(env_password);
     }
 
-    if config_password.is_some() {
-        return Ok(config_password);
-    }
-
-    let password = prompt_password("Wallet password: ")?;
-
-    Ok(Some(password))
+    Ok(config_password)
 }
 
 fn prompt_password(prompt: &str) -> Result<String, ExitCodes> {
@@ -140,7 +169,10 @@ pub async fn change_password(
     shutdown_signal: ShutdownSignal,
 ) -> Result<(), ExitCodes>
 {
-    let mut wallet = init_wallet(config, arg_password, None, None, shutdown_signal).await?;
+    // The current password is needed to roll back to it if re-encrypting the database fails part way
+    let current = get_or_prompt_password(arg_password, config.console_wallet_password.clone())?
+        .ok_or_else(|| ExitCodes::InputError("The current wallet password is required".to_string()))?;
+    let mut wallet = init_wallet(config, Some(current.clone()), None, None, false, shutdown_signal).await?;
 
     let passphrase = prompt_password("New wallet password: ")?;
     let confirmed = prompt_password("Confirm new password: ")?;
@@ -149,19 +181,85 @@ pub async fn change_password(
         return Err(ExitCodes::InputError("Passwords don't match!".to_string()));
     }
 
-    wallet.remove_encryption().await?;
-
-    wallet.apply_encryption(passphrase).await?;
+    wallet.change_passphrase(current, passphrase).await?;
 
     println!("Wallet password changed successfully.");
 
     Ok(())
 }
 
+/// The configured costs of deriving the cipher of the wallet database from its password, falling back to the defaults.
+/// Costs below the minimums the wallet stores a KDF with are a config error.
+fn passphrase_kdf_parameters(config: &GlobalConfig) -> Result<KdfParameters, ExitCodes> {
+    let default = KdfParameters::default();
+    let parameters = KdfParameters {
+        memory_kib: config.wallet_passphrase_kdf_memory_kib.unwrap_or(default.memory_kib),
+        iterations: config.wallet_passphrase_kdf_iterations.unwrap_or(default.iterations),
+        parallelism: config.wallet_passphrase_kdf_parallelism.unwrap_or(default.parallelism),
+    };
+    parameters
+        .validate()
+        .map_err(|e| ExitCodes::ConfigError(format!("wallet.passphrase_kdf: {}", e)))?;
+    Ok(parameters)
+}
+
+/// Writes an encrypted backup of the wallet database to `backup_path`. The backup is encrypted with the password
+/// provided on the command line, or else a backup password that is prompted for.
+pub async fn backup_wallet(
+    wallet: &WalletSqlite,
+    backup_path: PathBuf,
+    arg_password: Option<String>,
+) -> Result<(), ExitCodes>
+{
+    let passphrase = match arg_password {
+        Some(password) => password,
+        None => {
+            let passphrase = prompt_password("Backup password: ")?;
+            let confirmed = prompt_password("Confirm backup password: ")?;
+            if passphrase != confirmed {
+                return Err(ExitCodes::InputError("Passwords don't match!".to_string()));
+            }
+            passphrase
+        },
+    };
+
+    let checksum = wallet.create_backup(backup_path.clone(), passphrase).await?;
+    println!("Wallet backup written to {}", backup_path.display());
+    println!("Checksum: {}", checksum);
+
+    Ok(())
+}
+
+/// Restores the wallet database from the encrypted backup at `backup_path`. There must not be a wallet database yet.
+pub fn restore_wallet_backup(
+    config: &GlobalConfig,
+    backup_path: &Path,
+    arg_password: Option<String>,
+) -> Result<(), ExitCodes>
+{
+    let passphrase = match arg_password {
+        Some(password) => password,
+        None => prompt_password("Backup password: ")?,
+    };
+
+    fs::create_dir_all(
+        &config
+            .console_wallet_db_file
+            .parent()
+            .expect("console_wallet_db_file cannot be set to a root directory"),
+    )
+    .map_err(|e| ExitCodes::WalletError(format!("Error creating Wallet folder. {}", e)))?;
+    restore_backup(backup_path, &passphrase, &config.console_wallet_db_file)?;
+    println!("Wallet restored from {}", backup_path.display());
+
+    Ok(())
+}
+
 /// Populates the PeerConfig struct from:
 /// 1. The custom peer in the wallet if it exists
 /// 2. The service peers defined in config they exist
 /// 3. The peer seeds defined in config
+/// 4. The named base nodes saved in the wallet
 pub async fn get_base_node_peer_config(
     config: &GlobalConfig,
     wallet: &mut WalletSqlite,
@@ -188,7 +286,11 @@ pub async fn get_base_node_peer_config(
         .collect::<Result<Vec<_>, _>>()
         .map_err(|err| ExitCodes::ConfigError(format!("Malformed seed peer: {}", err)))?;
 
-    let peer_config = PeerConfig::new(base_node_custom, base_node_peers, peer_seeds);
+    // saved
+    let base_node_saved = get_saved_base_nodes_from_db(wallet).await;
+
+    let peer_config =
+        PeerConfig::new(base_node_custom, base_node_peers, peer_seeds).with_saved_base_nodes(base_node_saved);
     debug!(target: LOG_TARGET, "base node peer config: {:?}", peer_config);
 
     Ok(peer_config)
@@ -260,12 +362,14 @@ pub fn get_notify_script(bootstrap: &ConfigBootstrap, config: &GlobalConfig) ->
     Ok(notify_script)
 }
 
-/// Set up the app environment and state for use by the UI
+/// Set up the app environment and state for use by the UI. The seed words of a new watch-only wallet are not shown,
+/// as they could only recover a wallet without the view key that it watches.
 pub async fn init_wallet(
     config: &GlobalConfig,
     arg_password: Option<String>,
     seed_words_file_name: Option<PathBuf>,
     master_key: Option<PrivateKey>,
+    watch_only: bool,
     shutdown_signal: ShutdownSignal,
 ) -> Result<WalletSqlite, ExitCodes>
 {
@@ -283,7 +387,8 @@ pub async fn init_wallet(
 
     // test encryption by initializing with no passphrase...
     let db_path = config.console_wallet_db_file.clone();
-    let result = initialize_sqlite_database_backends(db_path.clone(), None);
+    let kdf_parameters = passphrase_kdf_parameters(config)?;
+    let result = initialize_sqlite_database_backends(db_path.clone(), None, kdf_parameters);
     let (backends, wallet_encrypted) = match result {
         Ok(backends) => {
             // wallet is not encrypted
@@ -292,7 +397,7 @@ pub async fn init_wallet(
         Err(WalletStorageError::NoPasswordError) => {
             // get supplied or prompt password
             let passphrase = get_or_prompt_password(arg_password.clone(), config.console_wallet_password.clone())?;
-            let backends = initialize_sqlite_database_backends(db_path, passphrase)?;
+            let backends = initialize_sqlite_database_backends(db_path, passphrase, kdf_parameters)?;
 
             (backends, true)
         },
@@ -328,7 +433,10 @@ pub async fn init_wallet(
         },
     };
 
-    let transport_type = setup_wallet_transport_type(&config);
+    let transport_type = match &config.wallet_tor {
+        Some(tor_config) => setup_wallet_tor_transport(tor_config, shutdown_signal.clone()).await?,
+        None => setup_wallet_transport_type(&config),
+    };
     let transport_type = match transport_type {
         Tor(mut tor_config) => {
             tor_config.identity = match wallet_backend
@@ -359,6 +467,12 @@ pub async fn init_wallet(
             network: config.network.into(),
             flood_ban_max_msg_count: config.flood_ban_max_msg_count,
             saf_msg_validity: config.saf_expiry_duration,
+            num_neighbouring_nodes: config.dht_num_neighbouring_nodes,
+            num_random_nodes: config.dht_num_random_nodes,
+            propagation_factor: config.dht_propagation_factor,
+            broadcast_factor: config.dht_broadcast_factor,
+            closest_min_additional_peers: config.dht_closest_min_additional_peers,
+            discovery_max_send_attempts: config.dht_discovery_max_send_attempts,
             ..Default::default()
         },
         // TODO: This should be false unless testing locally - make this configurable
@@ -369,6 +483,11 @@ pub async fn init_wallet(
         peer_seeds: Default::default(),
         dns_seeds: Default::default(),
         dns_seeds_use_dnssec: true,
+        outbound_only: config.wallet_outbound_only,
+        auto_update_public_address: false,
+        allow_unsigned_identities: config.allow_unsigned_peer_identities,
+        noise_rekey_interval: config.noise_rekey_interval.map(Duration::from_secs),
+        noise_rekey_max_bytes: config.noise_rekey_max_bytes,
     };
 
     let network = match &config.network {
@@ -384,6 +503,9 @@ pub async fn init_wallet(
         config.wallet_base_node_service_request_max_age,
     );
 
+    let key_manager_backend = KeyManagerBackendType::from_str(&config.wallet_key_manager_backend)
+        .map_err(|e| ExitCodes::ConfigError(format!("wallet.key_manager_backend: {}", e)))?;
+
     let factories = CryptoFactories::default();
     let mut wallet_config = WalletConfig::new(
         comms_config.clone(),
@@ -397,11 +519,21 @@ pub async fn init_wallet(
                 config.transaction_routing_mechanism.clone(),
             ),
             num_confirmations_required: config.transaction_num_confirmations_required,
+            transaction_resend_period: config.transaction_resend_period,
+            transaction_resend_limit: config.transaction_resend_limit,
+            broadcast_retry_backoff_factor: config.transaction_broadcast_retry_backoff_factor,
+            broadcast_retry_max_delay: config.transaction_broadcast_retry_max_delay,
             ..Default::default()
         }),
         Some(OutputManagerServiceConfig {
             base_node_query_timeout: config.base_node_query_timeout,
             prevent_fee_gt_amount: config.prevent_fee_gt_amount,
+            utxo_selection_policy: UtxoSelectionPolicy::from(config.utxo_selection_strategy.clone()),
+            dust_consolidation_threshold: config.wallet_dust_consolidation_threshold.map(MicroTari::from),
+            dust_consolidation_interval: config.wallet_dust_consolidation_interval,
+            dust_consolidation_fee_per_gram: MicroTari::from(config.wallet_dust_consolidation_fee_per_gram),
+            dust_consolidation_min_outputs: config.wallet_dust_consolidation_min_outputs,
+            key_manager_backend,
             ..Default::default()
         }),
         network,
@@ -410,6 +542,7 @@ pub async fn init_wallet(
         Some(config.buffer_rate_limit_base_node_wallet),
     );
     wallet_config.buffer_size = std::cmp::max(BASE_NODE_BUFFER_MIN_SIZE, config.buffer_size_base_node);
+    wallet_config.passphrase_kdf = kdf_parameters;
 
     let recovery = set_master_key(&output_manager_backend, master_key).await?;
 
@@ -461,10 +594,10 @@ pub async fn init_wallet(
 
         debug!(target: LOG_TARGET, "Wallet encrypted.");
 
-        if interactive && !recovery {
+        if interactive && !recovery && !watch_only {
             confirm_seed_words(&mut wallet).await?;
         }
-        if let Some(file_name) = seed_words_file_name {
+        if let Some(file_name) = seed_words_file_name.filter(|_| !watch_only) {
             let seed_words = wallet.output_manager_service.get_seed_words().await?.join(" ");
             let _ = fs::write(file_name, seed_words)
                 .map_err(|e| ExitCodes::WalletError(format!("Problem writing seed words to file: {}", e)));
@@ -500,6 +633,39 @@ async fn set_master_key(
     }
 }
 
+/// Stores the view key of a new watch-only wallet, and checks that a view key given for an existing wallet is the one
+/// that it watches. Recovering a wallet from seed words would restore its spend keys, so it cannot be watch-only.
+pub async fn set_watch_only_keys(
+    view_key: Option<&str>,
+    boot_mode: WalletBoot,
+    wallet: &WalletSqlite,
+) -> Result<(), ExitCodes>
+{
+    let view_key = match view_key {
+        Some(view_key) => PublicRewindKeys::from_str(view_key).map_err(|e| ExitCodes::InputError(e.to_string()))?,
+        None => return Ok(()),
+    };
+
+    match (boot_mode, wallet.db.get_watch_only_keys().await?) {
+        (WalletBoot::New, _) => {
+            wallet.db.set_watch_only_keys(view_key).await?;
+            info!(target: LOG_TARGET, "Created a watch-only wallet");
+            Ok(())
+        },
+        (WalletBoot::Recovery, _) => Err(ExitCodes::InputError(
+            "A recovered wallet cannot be watch-only.".to_string(),
+        )),
+        (WalletBoot::Existing, Some(keys)) if keys == view_key => Ok(()),
+        (WalletBoot::Existing, Some(_)) => Err(ExitCodes::InputError(
+            "The wallet watches a different view key. Use another profile to watch this one.".to_string(),
+        )),
+        (WalletBoot::Existing, None) => Err(ExitCodes::InputError(
+            "The wallet already exists and is not watch-only. Use another profile to create a watch-only wallet."
+                .to_string(),
+        )),
+    }
+}
+
 /// Starts the wallet by setting the base node peer, and restarting the transaction and broadcast protocols.
 pub async fn start_wallet(
     wallet: &mut WalletSqlite,
@@ -654,8 +820,8 @@ pub(crate) fn boot(bootstrap: &ConfigBootstrap, config: &GlobalConfig) -> Result
         // normal startup of existing wallet
         Ok(WalletBoot::Existing)
     } else {
-        // automation/wallet created with --password
-        if bootstrap.password.is_some() {
+        // automation/wallet created with --password, or a watch-only wallet, which cannot be recovered from seed words
+        if bootstrap.password.is_some() || bootstrap.watch_only.is_some() {
             return Ok(WalletBoot::New);
         }
 
@@ -0,0 +1,163 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use rustyline::Editor;
+use std::{fs, path::PathBuf};
+use tari_app_utilities::utilities::ExitCodes;
+use tari_common::{ConfigBootstrap, GlobalConfig};
+
+/// The profile of the wallet database that is configured in the config file
+pub const DEFAULT_PROFILE: &str = "default";
+/// The directory, under the data directory, that holds a directory for each of the other profiles
+const PROFILES_DIR: &str = "wallet_profiles";
+
+/// The wallet profiles that can be opened, and the one that is open
+#[derive(Clone, Debug)]
+pub struct Profiles {
+    pub current: String,
+    pub available: Vec<String>,
+}
+
+impl Profiles {
+    pub fn new(config: &GlobalConfig, current: String) -> Self {
+        let mut available = list_profiles(config);
+        if !available.contains(&current) {
+            available.push(current.clone());
+        }
+        Self { current, available }
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.current == DEFAULT_PROFILE
+    }
+}
+
+/// A profile name is used as a directory name, so only letters, digits, `-` and `_` are allowed
+pub fn validate_profile_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("A profile name cannot be empty".to_string());
+    }
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(format!(
+            "Invalid profile name `{}`: only letters, digits, `-` and `_` are allowed",
+            name
+        ));
+    }
+    Ok(())
+}
+
+fn profile_dir(config: &GlobalConfig, name: &str) -> PathBuf {
+    config.data_dir.join(PROFILES_DIR).join(name)
+}
+
+/// The default profile followed by the profiles that have a wallet database, in alphabetical order
+pub fn list_profiles(config: &GlobalConfig) -> Vec<String> {
+    let db_file_name = config.console_wallet_db_file.file_name().map(|n| n.to_owned());
+    let mut profiles = fs::read_dir(config.data_dir.join(PROFILES_DIR))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| {
+                    db_file_name
+                        .as_ref()
+                        .map(|n| entry.path().join(n).exists())
+                        .unwrap_or(false)
+                })
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter(|name| name != DEFAULT_PROFILE && validate_profile_name(name).is_ok())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    profiles.sort();
+    profiles.insert(0, DEFAULT_PROFILE.to_string());
+    profiles
+}
+
+/// The config with the wallet database, peer database and DHT database of the profile. The default profile uses the
+/// configured locations, every other profile keeps its databases in its own directory.
+pub fn profile_config(config: &GlobalConfig, name: &str) -> Result<GlobalConfig, ExitCodes> {
+    validate_profile_name(name).map_err(ExitCodes::InputError)?;
+    if name == DEFAULT_PROFILE {
+        return Ok(config.clone());
+    }
+    let dir = profile_dir(config, name);
+    let db_file_name = config.console_wallet_db_file.file_name().ok_or_else(|| {
+        ExitCodes::ConfigError("console_wallet_db_file cannot be set to a root directory".to_string())
+    })?;
+    let mut profile_config = config.clone();
+    profile_config.console_wallet_db_file = dir.join(db_file_name);
+    profile_config.console_wallet_peer_db_path = dir.join("peer_db");
+    // The DHT database is kept in the data directory
+    profile_config.data_dir = dir;
+    Ok(profile_config)
+}
+
+/// The profile given on the command line, or the one the user picks when more than one profile exists. Only the
+/// default profile is used when the wallet is not run interactively.
+pub fn select_profile(bootstrap: &ConfigBootstrap, config: &GlobalConfig) -> Result<String, ExitCodes> {
+    if let Some(name) = bootstrap.profile.as_ref() {
+        validate_profile_name(name).map_err(ExitCodes::InputError)?;
+        return Ok(name.clone());
+    }
+    let profiles = list_profiles(config);
+    let interactive = !bootstrap.daemon_mode && bootstrap.command.is_none() && bootstrap.input_file.is_none();
+    if profiles.len() == 1 || !interactive {
+        return Ok(DEFAULT_PROFILE.to_string());
+    }
+
+    let mut rl = Editor::<()>::new();
+    loop {
+        println!("Select a wallet profile:");
+        for (i, name) in profiles.iter().enumerate() {
+            println!("{}. {}", i + 1, name);
+        }
+        println!("Or type the name of a new profile to create it.");
+        let line = rl.readline(">> ").map_err(|e| ExitCodes::IOError(e.to_string()))?;
+        let line = line.trim();
+        if let Some(name) = line
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| i.checked_sub(1))
+            .and_then(|i| profiles.get(i))
+        {
+            return Ok(name.clone());
+        }
+        match validate_profile_name(line) {
+            Ok(()) => return Ok(line.to_string()),
+            Err(e) => println!("{}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn profile_names_must_be_usable_as_directory_names() {
+        assert!(validate_profile_name("business").is_ok());
+        assert!(validate_profile_name("personal_2021-a").is_ok());
+        assert!(validate_profile_name("").is_err());
+        assert!(validate_profile_name("../default").is_err());
+        assert!(validate_profile_name("my wallet").is_err());
+    }
+}
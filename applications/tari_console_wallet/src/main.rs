@@ -6,25 +6,38 @@
 #![deny(unreachable_patterns)]
 #![deny(unknown_lints)]
 #![recursion_limit = "1024"]
-use crate::recovery::get_private_key_from_seed_words;
+use crate::{automation::commands::OutputFormat, recovery::get_private_key_from_seed_words};
 use init::{
+    backup_wallet,
     boot,
     change_password,
     get_base_node_peer_config,
     get_notify_script,
+    get_password,
     init_wallet,
+    profile_config,
+    restore_wallet_backup,
+    select_profile,
+    set_watch_only_keys,
     start_wallet,
     tari_splash_screen,
     wallet_mode,
+    Profiles,
     WalletBoot,
 };
 use log::*;
 use recovery::prompt_private_key_from_seed_words;
 use std::process;
-use tari_app_utilities::{initialization::init_configuration, utilities::ExitCodes};
-use tari_common::{configuration::bootstrap::ApplicationType, ConfigBootstrap};
+use tari_app_utilities::{
+    health::{self, HealthStatus},
+    initialization::init_configuration,
+    telemetry::init_telemetry,
+    utilities::ExitCodes,
+};
+use tari_common::{configuration::bootstrap::ApplicationType, ConfigBootstrap, GlobalConfig};
 use tari_core::transactions::types::PrivateKey;
 use tari_shutdown::Shutdown;
+use tokio::runtime::Runtime;
 use wallet_modes::{command_mode, grpc_mode, recovery_mode, script_mode, tui_mode, WalletMode};
 
 pub const LOG_TARGET: &str = "wallet::console_wallet::main";
@@ -67,24 +80,99 @@ fn main_inner() -> Result<(), ExitCodes> {
     debug!(target: LOG_TARGET, "Using configuration: {:?}", config);
     debug!(target: LOG_TARGET, "Using bootstrap: {:?}", bootstrap);
 
+    let _telemetry = init_telemetry("tari_console_wallet", config.wallet_otlp_endpoint.as_deref())?;
+
+    let health = HealthStatus::default();
+    if let Some(health_address) = config.wallet_health_address {
+        let health = health.clone();
+        runtime.spawn(async move {
+            if let Err(err) = health::serve(health_address, health).await {
+                error!(target: LOG_TARGET, "Health check server encountered an error: {}", err);
+            }
+        });
+    }
+
     // get command line password if provided
     let arg_password = bootstrap.password.clone();
 
-    if arg_password.is_none() {
+    // JSON output is meant to be read by other programs, so nothing else may be printed to stdout
+    let output_format = if bootstrap.json_output {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Text
+    };
+
+    if arg_password.is_none() && output_format == OutputFormat::Text {
         tari_splash_screen("Console Wallet");
     }
 
-    // check for recovery based on existence of wallet file
-    let mut boot_mode = boot(&bootstrap, &config)?;
-
-    let master_key: Option<PrivateKey> = get_master_key(boot_mode, &bootstrap)?;
-
     if bootstrap.init {
         info!(target: LOG_TARGET, "Default configuration created. Done.");
     }
 
-    // get command line password if provided
-    let arg_password = bootstrap.password.clone();
+    let mut bootstrap = bootstrap;
+    let mut profile = select_profile(&bootstrap, &config)?;
+    loop {
+        let profiles = Profiles::new(&config, profile.clone());
+        let profile_config = profile_config(&config, &profile)?;
+        match run_wallet(
+            &mut runtime,
+            &bootstrap,
+            profile_config,
+            profiles,
+            &health,
+            output_format,
+        )? {
+            Some(next_profile) => {
+                // The options for creating, recovering and unlocking a wallet only apply to the profile that was
+                // opened first
+                bootstrap.recovery = false;
+                bootstrap.seed_words = None;
+                bootstrap.seed_words_file_name = None;
+                bootstrap.watch_only = None;
+                bootstrap.backup = None;
+                bootstrap.restore_backup = None;
+                bootstrap.password = None;
+                profile = next_profile;
+            },
+            None => return Ok(()),
+        }
+    }
+}
+
+/// Open the wallet of a profile and run it in the selected mode until it is shut down, returning the profile to open
+/// next if the user switched profiles
+fn run_wallet(
+    runtime: &mut Runtime,
+    bootstrap: &ConfigBootstrap,
+    config: GlobalConfig,
+    profiles: Profiles,
+    health: &HealthStatus,
+    output_format: OutputFormat,
+) -> Result<Option<String>, ExitCodes>
+{
+    if let Some(backup_path) = bootstrap.restore_backup.as_ref() {
+        restore_wallet_backup(&config, backup_path, bootstrap.password.clone())?;
+    }
+
+    // check for recovery based on existence of wallet file
+    let mut boot_mode = boot(bootstrap, &config)?;
+
+    let master_key: Option<PrivateKey> = get_master_key(boot_mode, bootstrap)?;
+
+    // get command line password if provided. It cannot be prompted for in daemon mode, so it must be provided then.
+    let arg_password = if bootstrap.daemon_mode {
+        let password = get_password(bootstrap.password.clone(), config.console_wallet_password.clone())?;
+        Some(password.ok_or_else(|| {
+            ExitCodes::InputError(
+                "A wallet password is required in daemon mode. Provide it with --password, the TARI_WALLET_PASSWORD \
+                 environment variable or the wallet password setting in the config file."
+                    .to_string(),
+            )
+        })?)
+    } else {
+        bootstrap.password.clone()
+    };
     let seed_words_file_name = bootstrap.seed_words_file_name.clone();
 
     let mut shutdown = Shutdown::new();
@@ -92,7 +180,9 @@ fn main_inner() -> Result<(), ExitCodes> {
 
     if bootstrap.change_password {
         info!(target: LOG_TARGET, "Change password requested.");
-        return runtime.block_on(change_password(&config, arg_password, shutdown_signal));
+        return runtime
+            .block_on(change_password(&config, arg_password, shutdown_signal))
+            .map(|_| None);
     }
 
     // initialize wallet
@@ -101,9 +191,19 @@ fn main_inner() -> Result<(), ExitCodes> {
         arg_password,
         seed_words_file_name,
         master_key,
+        bootstrap.watch_only.is_some(),
         shutdown_signal,
     ))?;
 
+    runtime.block_on(set_watch_only_keys(bootstrap.watch_only.as_deref(), boot_mode, &wallet))?;
+
+    if let Some(backup_path) = bootstrap.backup.clone() {
+        info!(target: LOG_TARGET, "Wallet backup requested.");
+        return runtime
+            .block_on(backup_wallet(&wallet, backup_path, bootstrap.password.clone()))
+            .map(|_| None);
+    }
+
     // Check if there is an in progress recovery in the wallet's database
     if runtime.block_on(wallet.is_recovery_in_progress())? {
         println!("A Wallet Recovery was found to be in progress, continuing.");
@@ -114,13 +214,14 @@ fn main_inner() -> Result<(), ExitCodes> {
     let base_node_config = runtime.block_on(get_base_node_peer_config(&config, &mut wallet))?;
     let base_node = base_node_config.get_base_node_peer()?;
 
-    let wallet_mode = wallet_mode(&bootstrap, boot_mode);
+    let wallet_mode = wallet_mode(bootstrap, boot_mode);
 
     // start wallet
     runtime.block_on(start_wallet(&mut wallet, &base_node, &wallet_mode))?;
+    health.set_ready(true);
 
     // optional path to notify script
-    let notify_script = get_notify_script(&bootstrap, &config)?;
+    let notify_script = get_notify_script(bootstrap, &config)?;
 
     debug!(target: LOG_TARGET, "Starting app");
 
@@ -133,10 +234,14 @@ fn main_inner() -> Result<(), ExitCodes> {
             base_node,
             base_node_config,
             notify_script,
+            &bootstrap.log_config,
+            profiles,
         ),
-        WalletMode::Grpc => grpc_mode(handle, wallet.clone(), config),
-        WalletMode::Script(path) => script_mode(handle, path, wallet.clone(), config),
-        WalletMode::Command(command) => command_mode(handle, command, wallet.clone(), config),
+        WalletMode::Grpc => grpc_mode(handle, wallet.clone(), config).map(|_| None),
+        WalletMode::Script(path) => script_mode(handle, path, wallet.clone(), config, output_format).map(|_| None),
+        WalletMode::Command(command) => {
+            command_mode(handle, command, wallet.clone(), config, output_format).map(|_| None)
+        },
         WalletMode::Recovery => recovery_mode(
             handle,
             config,
@@ -144,20 +249,31 @@ fn main_inner() -> Result<(), ExitCodes> {
             base_node,
             base_node_config,
             notify_script,
-            &bootstrap,
+            bootstrap,
+            profiles,
         ),
         WalletMode::Invalid => Err(ExitCodes::InputError(
             "Invalid wallet mode - are you trying too many command options at once?".to_string(),
         )),
     };
 
-    print!("\nShutting down wallet... ");
+    // The application stays alive while the wallet of the next profile is opened
+    if matches!(result, Ok(Some(_))) {
+        health.set_ready(false);
+    } else {
+        health.set_shutting_down();
+    }
+    if output_format == OutputFormat::Text {
+        print!("\nShutting down wallet... ");
+    }
     if shutdown.trigger().is_ok() {
         runtime.block_on(wallet.wait_until_shutdown());
     } else {
         error!(target: LOG_TARGET, "No listeners for the shutdown signal!");
     }
-    println!("Done.");
+    if output_format == OutputFormat::Text {
+        println!("Done.");
+    }
 
     result
 }
@@ -21,6 +21,7 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use log::*;
+use serde_json::{Map, Value};
 use std::{
     io::Error,
     path::PathBuf,
@@ -42,63 +43,58 @@ use tokio::runtime::Handle;
 pub const LOG_TARGET: &str = "wallet::notifier";
 const RECEIVED: &str = "received";
 const SENT: &str = "sent";
+const BROADCAST: &str = "broadcast";
 const CONFIRMATION: &str = "confirmation";
 const MINED: &str = "mined";
 const CANCELLED: &str = "cancelled";
 
+/// The named values describing a transaction event, in the order they are passed to the notify script
+type Fields = Vec<(&'static str, String)>;
+
 #[derive(Clone)]
 pub struct Notifier {
-    path: Option<PathBuf>,
+    targets: NotifyTargets,
     handle: Handle,
     wallet: WalletSqlite,
 }
 
 impl Notifier {
-    pub fn new(path: Option<PathBuf>, handle: Handle, wallet: WalletSqlite) -> Self {
-        Self { path, handle, wallet }
+    pub fn new(path: Option<PathBuf>, webhook: Option<String>, handle: Handle, wallet: WalletSqlite) -> Self {
+        Self {
+            targets: NotifyTargets { path, webhook },
+            handle,
+            wallet,
+        }
     }
 
     /// Trigger a notification that a negotiated transaction was received.
     pub fn transaction_received(&self, tx_id: TxId) {
         debug!(target: LOG_TARGET, "transaction_received tx_id: {}", tx_id);
+        self.notify_completed(tx_id, RECEIVED);
+    }
 
-        if let Some(program) = self.path.clone() {
-            let mut transaction_service = self.wallet.transaction_service.clone();
-
-            self.handle.spawn(async move {
-                match transaction_service.get_completed_transaction(tx_id).await {
-                    Ok(tx) => {
-                        let args = args_from_complete(&tx, RECEIVED, None);
-                        let result = Command::new(program).args(&args).output();
-                        log(result);
-                    },
-                    Err(e) => error!(target: LOG_TARGET, "Transaction service error: {}", e),
-                }
-            });
-        } else {
-            trace!(target: LOG_TARGET, "No script defined, not running.");
-        }
+    /// Trigger a notification that a transaction was broadcast to the base node.
+    pub fn transaction_broadcast(&self, tx_id: TxId) {
+        debug!(target: LOG_TARGET, "transaction_broadcast tx_id: {}", tx_id);
+        self.notify_completed(tx_id, BROADCAST);
     }
 
     /// Trigger a notification that a transaction was mined with a given number of confirmations.
     pub fn transaction_mined_unconfirmed(&self, tx_id: TxId, confirmations: u64) {
         debug!(target: LOG_TARGET, "transaction_mined_unconfirmed tx_id: {}", tx_id);
 
-        if let Some(program) = self.path.clone() {
+        if let Some(targets) = self.enabled_targets() {
             let mut transaction_service = self.wallet.transaction_service.clone();
 
             self.handle.spawn(async move {
                 match transaction_service.get_completed_transaction(tx_id).await {
                     Ok(tx) => {
-                        let args = args_from_complete(&tx, CONFIRMATION, Some(confirmations));
-                        let result = Command::new(program).args(&args).output();
-                        log(result);
+                        let fields = fields_from_complete(&tx, CONFIRMATION, Some(confirmations));
+                        targets.notify(fields).await;
                     },
                     Err(e) => error!(target: LOG_TARGET, "Transaction service error: {}", e),
                 }
             });
-        } else {
-            trace!(target: LOG_TARGET, "No script defined, not running.");
         }
     }
 
@@ -106,7 +102,7 @@ impl Notifier {
     pub fn transaction_mined(&self, tx_id: TxId) {
         debug!(target: LOG_TARGET, "transaction_mined tx_id: {}", tx_id);
 
-        if let Some(program) = self.path.clone() {
+        if let Some(targets) = self.enabled_targets() {
             let mut transaction_service = self.wallet.transaction_service.clone();
 
             self.handle.spawn(async move {
@@ -119,15 +115,11 @@ impl Notifier {
                                 None
                             },
                         };
-                        let args = args_from_complete(&tx, MINED, confirmations);
-                        let result = Command::new(program).args(&args).output();
-                        log(result);
+                        targets.notify(fields_from_complete(&tx, MINED, confirmations)).await;
                     },
                     Err(e) => error!(target: LOG_TARGET, "Transaction service error: {}", e),
                 }
             });
-        } else {
-            trace!(target: LOG_TARGET, "No script defined, not running.");
         }
     }
 
@@ -135,16 +127,14 @@ impl Notifier {
     pub fn transaction_sent(&self, tx_id: TxId) {
         debug!(target: LOG_TARGET, "transaction_sent tx_id: {}", tx_id);
 
-        if let Some(program) = self.path.clone() {
+        if let Some(targets) = self.enabled_targets() {
             let mut transaction_service = self.wallet.transaction_service.clone();
 
             self.handle.spawn(async move {
                 match transaction_service.get_pending_outbound_transactions().await {
                     Ok(txs) => {
                         if let Some(tx) = txs.get(&tx_id) {
-                            let args = args_from_outbound(tx, SENT);
-                            let result = Command::new(program).args(&args).output();
-                            log(result);
+                            targets.notify(fields_from_outbound(tx, SENT)).await;
                         } else {
                             error!(target: LOG_TARGET, "Not found in pending outbound set tx_id: {}", tx_id);
                         }
@@ -152,8 +142,6 @@ impl Notifier {
                     Err(e) => error!(target: LOG_TARGET, "Transaction service error: {}", e),
                 }
             });
-        } else {
-            trace!(target: LOG_TARGET, "No script defined, not running.");
         }
     }
 
@@ -161,20 +149,19 @@ impl Notifier {
     pub fn transaction_cancelled(&self, tx_id: TxId) {
         debug!(target: LOG_TARGET, "transaction_cancelled tx_id: {}", tx_id);
 
-        if let Some(program) = self.path.clone() {
+        if let Some(targets) = self.enabled_targets() {
             let mut transaction_service = self.wallet.transaction_service.clone();
 
             self.handle.spawn(async move {
                 match transaction_service.get_any_transaction(tx_id).await {
                     Ok(option_tx) => {
                         if let Some(wallet_tx) = option_tx {
-                            let args = match wallet_tx {
-                                WalletTransaction::Completed(tx) => args_from_complete(&tx, CANCELLED, None),
-                                WalletTransaction::PendingInbound(tx) => args_from_inbound(&tx, CANCELLED),
-                                WalletTransaction::PendingOutbound(tx) => args_from_outbound(&tx, CANCELLED),
+                            let fields = match wallet_tx {
+                                WalletTransaction::Completed(tx) => fields_from_complete(&tx, CANCELLED, None),
+                                WalletTransaction::PendingInbound(tx) => fields_from_inbound(&tx, CANCELLED),
+                                WalletTransaction::PendingOutbound(tx) => fields_from_outbound(&tx, CANCELLED),
                             };
-                            let result = Command::new(program).args(&args).output();
-                            log(result);
+                            targets.notify(fields).await;
                         } else {
                             error!(target: LOG_TARGET, "Transaction not found tx_id: {}", tx_id);
                         }
@@ -182,10 +169,72 @@ impl Notifier {
                     Err(e) => error!(target: LOG_TARGET, "Transaction service error: {}", e),
                 }
             });
-        } else {
-            trace!(target: LOG_TARGET, "No script defined, not running.");
         }
     }
+
+    fn notify_completed(&self, tx_id: TxId, event: &'static str) {
+        if let Some(targets) = self.enabled_targets() {
+            let mut transaction_service = self.wallet.transaction_service.clone();
+
+            self.handle.spawn(async move {
+                match transaction_service.get_completed_transaction(tx_id).await {
+                    Ok(tx) => targets.notify(fields_from_complete(&tx, event, None)).await,
+                    Err(e) => error!(target: LOG_TARGET, "Transaction service error: {}", e),
+                }
+            });
+        }
+    }
+
+    fn enabled_targets(&self) -> Option<NotifyTargets> {
+        if self.targets.path.is_none() && self.targets.webhook.is_none() {
+            trace!(target: LOG_TARGET, "No script or webhook defined, not notifying.");
+            return None;
+        }
+        Some(self.targets.clone())
+    }
+}
+
+/// Where notifications are sent: the notify script and the webhook URL, either of which may be configured
+#[derive(Clone)]
+struct NotifyTargets {
+    path: Option<PathBuf>,
+    webhook: Option<String>,
+}
+
+impl NotifyTargets {
+    /// Run the notify script with the values of `fields` as its arguments, and post `fields` to the webhook as a JSON
+    /// object
+    async fn notify(self, fields: Fields) {
+        if let Some(program) = self.path {
+            let args = fields.iter().map(|(_, value)| value.clone()).collect::<Vec<_>>();
+            let result = Command::new(program).args(&args).output();
+            log(result);
+        }
+        if let Some(url) = self.webhook {
+            let result = reqwest::Client::new()
+                .post(&url)
+                .json(&webhook_body(fields))
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            match result {
+                Ok(response) => debug!(
+                    target: LOG_TARGET,
+                    "Notify webhook succeeded with status code: {}",
+                    response.status()
+                ),
+                Err(e) => error!(target: LOG_TARGET, "Notify webhook failed! Error: {}", e),
+            }
+        }
+    }
+}
+
+fn webhook_body(fields: Fields) -> Value {
+    let body = fields
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), Value::String(value)))
+        .collect::<Map<_, _>>();
+    Value::Object(body)
 }
 
 fn log(result: Result<Output, Error>) {
@@ -203,7 +252,7 @@ fn log(result: Result<Output, Error>) {
     }
 }
 
-fn args_from_complete(tx: &CompletedTransaction, event: &str, confirmations: Option<u64>) -> Vec<String> {
+fn fields_from_complete(tx: &CompletedTransaction, event: &str, confirmations: Option<u64>) -> Fields {
     trace!(target: LOG_TARGET, "Getting args from completed tx {:?}", tx);
     let amount = format!("{}", tx.amount);
     let status = format!("{}", tx.status);
@@ -228,49 +277,49 @@ fn args_from_complete(tx: &CompletedTransaction, event: &str, confirmations: Opt
     };
 
     vec![
-        String::from(event),
-        amount,
-        tx.tx_id.to_string(),
-        tx.message.clone(),
-        tx.source_public_key.to_hex(),
-        tx.destination_public_key.to_hex(),
-        status,
-        excess,
-        public_nonce,
-        signature,
-        confirmations,
-        direction,
+        ("event", String::from(event)),
+        ("amount", amount),
+        ("tx_id", tx.tx_id.to_string()),
+        ("message", tx.message.clone()),
+        ("source_public_key", tx.source_public_key.to_hex()),
+        ("destination_public_key", tx.destination_public_key.to_hex()),
+        ("status", status),
+        ("excess", excess),
+        ("public_nonce", public_nonce),
+        ("signature", signature),
+        ("confirmations", confirmations),
+        ("direction", direction),
     ]
 }
 
-fn args_from_outbound(tx: &OutboundTransaction, event: &str) -> Vec<String> {
+fn fields_from_outbound(tx: &OutboundTransaction, event: &str) -> Fields {
     trace!(target: LOG_TARGET, "Getting args from outbound tx {:?}", tx);
     let amount = format!("{}", tx.amount);
     let status = format!("{}", tx.status);
 
     vec![
-        String::from(event),
-        amount,
-        tx.tx_id.to_string(),
-        tx.message.clone(),
-        tx.destination_public_key.to_hex(),
-        status,
-        "outbound".to_string(),
+        ("event", String::from(event)),
+        ("amount", amount),
+        ("tx_id", tx.tx_id.to_string()),
+        ("message", tx.message.clone()),
+        ("destination_public_key", tx.destination_public_key.to_hex()),
+        ("status", status),
+        ("direction", "outbound".to_string()),
     ]
 }
 
-fn args_from_inbound(tx: &InboundTransaction, event: &str) -> Vec<String> {
+fn fields_from_inbound(tx: &InboundTransaction, event: &str) -> Fields {
     trace!(target: LOG_TARGET, "Getting args from inbound tx {:?}", tx);
     let amount = format!("{}", tx.amount);
     let status = format!("{}", tx.status);
 
     vec![
-        String::from(event),
-        amount,
-        tx.tx_id.to_string(),
-        tx.message.clone(),
-        tx.source_public_key.to_hex(),
-        status,
-        "inbound".to_string(),
+        ("event", String::from(event)),
+        ("amount", amount),
+        ("tx_id", tx.tx_id.to_string()),
+        ("message", tx.message.clone()),
+        ("source_public_key", tx.source_public_key.to_hex()),
+        ("status", status),
+        ("direction", "inbound".to_string()),
     ]
 }
@@ -2,8 +2,8 @@
 # example notify script
 
 # 1.
-# For transaction received, mined(unconfirmed), and mined events:
-#  $1 = "received", "confirmation", or "mined"
+# For transaction received, broadcast, mined(unconfirmed), and mined events:
+#  $1 = "received", "broadcast", "confirmation", or "mined"
 #  $2 = amount,
 #  $3 = tx_id
 #  $4 = message
@@ -44,6 +44,8 @@ received)
     # msg="transaction $1 $notify_user \namount: **$2** \nmessage: **$4** \n*tx_id: ${3}* \n*excess: ${8}* \n[link](https://explore.tari.com/kernel/${9}/${10})"
     # curl -i -X POST -H 'Content-Type: application/json' -d '{"text": "'"${msg}"'"}' $webhook_url
     ;;
+broadcast) ;;
+
 confirmation) ;;
 
 mined) ;;
@@ -52,3 +54,7 @@ sent) ;;
 
 cancelled) ;;
 esac
+
+# The same arguments are posted, by name, to the `notify_webhook` URL of the config as a JSON object, so a webhook can
+# be used instead of a script. A desktop notification can be shown from here, e.g. with
+# notify-send "Tari" "transaction $1: $2"
@@ -72,15 +72,22 @@ pub fn get_private_key_from_seed_words(seed_words: Vec<String>) -> Result<Privat
     }
 }
 
+/// Create the task that recovers the wallet funds from the given base node peers, scanning with up to four of them at
+/// a time
+pub fn wallet_recovery_task(wallet: WalletSqlite, peer_seeds: Vec<CommsPublicKey>) -> WalletRecoveryTask {
+    WalletRecoveryTask::builder()
+        .with_peer_seeds(peer_seeds)
+        .with_retry_limit(10)
+        .with_max_concurrent_peers(4)
+        .build(wallet)
+}
+
 /// Recovers wallet funds by connecting to a given base node peer, downloading the transaction outputs stored in the
 /// blockchain, and attempting to rewind them. Any outputs that are successfully rewound are then imported into the
 /// wallet.
 pub async fn wallet_recovery(wallet: WalletSqlite, peer_seeds: Vec<CommsPublicKey>) -> Result<(), ExitCodes> {
     println!("\nPress Ctrl-C to stop the recovery process\n");
-    let mut recovery_task = WalletRecoveryTask::builder()
-        .with_peer_seeds(peer_seeds)
-        .with_retry_limit(10)
-        .build(wallet);
+    let mut recovery_task = wallet_recovery_task(wallet, peer_seeds);
 
     let mut event_stream = recovery_task.get_event_receiver().fuse();
 
@@ -113,6 +120,18 @@ pub async fn wallet_recovery(wallet: WalletSqlite, peer_seeds: Vec<CommsPublicKe
                     total
                 );
             },
+            Ok(WalletRecoveryEvent::ResumingFromCheckpoint { height, utxo_index }) => {
+                println!("Resuming the recovery from UTXO #{} (height {}).", utxo_index, height);
+            },
+            Ok(WalletRecoveryEvent::ScanningToHeight(height)) => {
+                println!("Scanning the blockchain up to height {}.", height);
+            },
+            Ok(WalletRecoveryEvent::OutputsRecovered(num_utxos, total_amount)) => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Recovered {} utxos worth {} so far.", num_utxos, total_amount
+                );
+            },
             Ok(WalletRecoveryEvent::RecoveryRoundFailed {
                 num_retries,
                 retry_limit,
@@ -21,26 +21,44 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::{
+    init::Profiles,
     notifier::Notifier,
     ui::{
         components::{
             base_node::BaseNode,
+            contacts_tab::ContactsTab,
+            help_overlay::HelpOverlay,
+            lock_screen::LockScreen,
+            log_tab::LogTab,
             menu::Menu,
+            network_stats_tab::NetworkStatsTab,
             network_tab::NetworkTab,
+            profile_switcher::ProfileSwitcher,
             receive_tab::ReceiveTab,
+            scheduled_payments_tab::ScheduledPaymentsTab,
             send_tab::SendTab,
+            settings_tab::SettingsTab,
+            status_bar::StatusBar,
+            swaps_tab::SwapsTab,
             tabs_container::TabsContainer,
             transactions_tab::TransactionsTab,
+            watched_outputs_tab::WatchedOutputsTab,
             Component,
         },
         state::AppState,
-        MAX_WIDTH,
+        KeyAction,
+        KeyMap,
+        LayoutMode,
+        Theme,
     },
+    utils::{clipboard::paste_from_clipboard, price_feed::PriceFeed, tor_status::TorStatusMonitor},
     wallet_modes::PeerConfig,
 };
+use log::*;
+use std::path::PathBuf;
 use tari_common::{GlobalConfig, Network};
 use tari_comms::peer_manager::Peer;
-use tari_wallet::WalletSqlite;
+use tari_wallet::{output_manager_service::handle::PublicRewindKeys, WalletSqlite};
 use tokio::runtime::Handle;
 use tui::{
     backend::Backend,
@@ -51,20 +69,30 @@ use tui::{
 pub const LOG_TARGET: &str = "wallet::ui::app";
 pub const CUSTOM_BASE_NODE_PUBLIC_KEY_KEY: &str = "console_wallet_custom_base_node_public_key";
 pub const CUSTOM_BASE_NODE_ADDRESS_KEY: &str = "console_wallet_custom_base_node_address";
+pub const SEED_BACKED_UP_KEY: &str = "console_wallet_seed_backed_up";
 
 pub struct App<B: Backend> {
     pub title: String,
     pub should_quit: bool,
+    /// The profile to open once the wallet of this one has been closed
+    pub switch_profile: Option<String>,
     // Cached state this will need to be cleaned up into a threadsafe container
     pub app_state: AppState,
     // Ui working state
     pub tabs: TabsContainer<B>,
     pub base_node_status: BaseNode,
+    pub status_bar: StatusBar,
     pub menu: Menu,
     pub notifier: Notifier,
+    pub key_map: KeyMap,
+    pub help_overlay: HelpOverlay,
+    pub lock_screen: LockScreen,
+    pub profile_switcher: ProfileSwitcher,
+    pub price_feed: Option<PriceFeed>,
 }
 
 impl<B: Backend> App<B> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         title: String,
         wallet: WalletSqlite,
@@ -73,8 +101,33 @@ impl<B: Backend> App<B> {
         base_node_config: PeerConfig,
         node_config: GlobalConfig,
         notifier: Notifier,
+        key_map: KeyMap,
+        theme: Theme,
+        log_files: Vec<PathBuf>,
+        profiles: Profiles,
+        watch_only_keys: Option<PublicRewindKeys>,
     ) -> Self
     {
+        let title = if profiles.is_default() {
+            title
+        } else {
+            format!("{} ({})", title, profiles.current)
+        };
+        let watch_only = watch_only_keys.is_some();
+        let title = if watch_only {
+            format!("{} [watch-only]", title)
+        } else {
+            title
+        };
+        let lock_screen = LockScreen::new(node_config.console_wallet_idle_lock_timeout);
+        let price_feed = node_config.console_wallet_price_feed_url.clone().map(|url| {
+            PriceFeed::new(
+                url,
+                node_config.console_wallet_price_feed_pointer.clone(),
+                node_config.console_wallet_price_feed_currency.clone(),
+            )
+        });
+        let status_bar = StatusBar::new(TorStatusMonitor::new(&node_config));
         let app_state = AppState::new(
             wallet.comms.node_identity().as_ref(),
             network,
@@ -82,25 +135,46 @@ impl<B: Backend> App<B> {
             base_node_selected.clone(),
             base_node_config,
             node_config,
+            theme,
+            watch_only_keys,
         );
 
-        let tabs = TabsContainer::<B>::new(title.clone())
+        let mut tabs = TabsContainer::<B>::new(title.clone());
+        if watch_only {
+            tabs = tabs.add("Watched".into(), Box::new(WatchedOutputsTab::new()));
+        }
+        let tabs = tabs
             .add("Transactions".into(), Box::new(TransactionsTab::new()))
             .add("Send".into(), Box::new(SendTab::new()))
+            .add("Contacts".into(), Box::new(ContactsTab::new()))
             .add("Receive".into(), Box::new(ReceiveTab::new()))
-            .add("Network".into(), Box::new(NetworkTab::new(base_node_selected)));
+            .add("Scheduled".into(), Box::new(ScheduledPaymentsTab::new()))
+            .add("Swaps".into(), Box::new(SwapsTab::new()))
+            .add("Network".into(), Box::new(NetworkTab::new(base_node_selected)))
+            .add("Stats".into(), Box::new(NetworkStatsTab::new()))
+            .add("Logs".into(), Box::new(LogTab::new(log_files)))
+            .add("Settings".into(), Box::new(SettingsTab::new()));
 
         let base_node_status = BaseNode::new();
         let menu = Menu::new();
+        let help_overlay = HelpOverlay::new(&key_map);
+        let profile_switcher = ProfileSwitcher::new(profiles);
 
         Self {
             title,
             should_quit: false,
+            switch_profile: None,
             app_state,
             tabs,
             base_node_status,
+            status_bar,
             menu,
             notifier,
+            key_map,
+            help_overlay,
+            lock_screen,
+            profile_switcher,
+            price_feed,
         }
     }
 
@@ -109,11 +183,59 @@ impl<B: Backend> App<B> {
             'q' | 'c' => {
                 self.should_quit = true;
             },
+            'v' => self.paste(),
+            'p' => {
+                if self.unlocked_input() && !self.tabs.is_editing() {
+                    self.help_overlay.hide();
+                    self.profile_switcher.show();
+                }
+            },
             _ => (),
         }
     }
 
+    /// Type the text on the clipboard into the field that is being edited, leaving out line breaks and other control
+    /// characters so that the paste cannot submit the field
+    fn paste(&mut self) {
+        if !self.unlocked_input() || !self.tabs.is_editing() {
+            return;
+        }
+        match paste_from_clipboard() {
+            Ok(text) => {
+                for c in text.chars().filter(|c| !c.is_control()) {
+                    self.tabs.on_key(&mut self.app_state, c);
+                }
+            },
+            Err(e) => warn!(target: LOG_TARGET, "Could not paste from the clipboard: {}", e),
+        }
+    }
+
     pub fn on_key(&mut self, c: char) {
+        if self.lock_screen.is_locked() {
+            Component::<B>::on_key(&mut self.lock_screen, &mut self.app_state, c);
+            return;
+        }
+        self.lock_screen.record_input();
+        if self.profile_switcher.is_visible() {
+            if let Some(profile) = self.profile_switcher.on_key(c) {
+                self.switch_profile = Some(profile);
+                self.should_quit = true;
+            }
+            return;
+        }
+        if !self.tabs.is_editing() {
+            if let Some(action) = self.key_map.action(c) {
+                self.on_key_action(action);
+                return;
+            }
+            if c == '?' {
+                self.toggle_help();
+                return;
+            }
+        }
+        if self.help_overlay.is_visible() {
+            return;
+        }
         match c {
             '\t' => {
                 self.tabs.next();
@@ -122,12 +244,55 @@ impl<B: Backend> App<B> {
         }
     }
 
+    /// Show the keys of the open tab as it is now, or close the list if it is open
+    fn toggle_help(&mut self) {
+        if self.help_overlay.is_visible() {
+            self.help_overlay.hide();
+        } else {
+            let tab_bindings = self.tabs.key_bindings();
+            self.help_overlay.show(self.tabs.selected_title(), tab_bindings);
+        }
+    }
+
+    fn on_key_action(&mut self, action: KeyAction) {
+        match action {
+            KeyAction::Up => self.on_up(),
+            KeyAction::Down => self.on_down(),
+            KeyAction::PreviousTab => self.on_left(),
+            KeyAction::NextTab => self.on_right(),
+            KeyAction::Back => self.on_esc(),
+            KeyAction::Quit => self.should_quit = true,
+        }
+    }
+
     pub fn on_up(&mut self) {
-        self.tabs.on_up(&mut self.app_state);
+        if self.unlocked_input() {
+            if self.profile_switcher.is_visible() {
+                self.profile_switcher.up();
+            } else if self.help_overlay.is_visible() {
+                self.help_overlay.scroll_up();
+            } else {
+                self.tabs.on_up(&mut self.app_state);
+            }
+        }
     }
 
     pub fn on_down(&mut self) {
-        self.tabs.on_down(&mut self.app_state);
+        if self.unlocked_input() {
+            if self.profile_switcher.is_visible() {
+                self.profile_switcher.down();
+            } else if self.help_overlay.is_visible() {
+                self.help_overlay.scroll_down();
+            } else {
+                self.tabs.on_down(&mut self.app_state);
+            }
+        }
+    }
+
+    pub fn on_click(&mut self, column: u16, row: u16) {
+        if self.unlocked_input() && !self.help_overlay.is_visible() && !self.profile_switcher.is_visible() {
+            self.tabs.on_click(&mut self.app_state, column, row);
+        }
     }
 
     pub fn on_f10(&mut self) {
@@ -135,43 +300,108 @@ impl<B: Backend> App<B> {
     }
 
     pub fn on_right(&mut self) {
-        self.tabs.next();
+        if self.unlocked_input() && !self.profile_switcher.is_visible() {
+            self.help_overlay.hide();
+            self.tabs.next();
+        }
     }
 
     pub fn on_left(&mut self) {
-        self.tabs.previous();
+        if self.unlocked_input() && !self.profile_switcher.is_visible() {
+            self.help_overlay.hide();
+            self.tabs.previous();
+        }
     }
 
     pub fn on_esc(&mut self) {
-        self.tabs.on_esc(&mut self.app_state);
+        if self.lock_screen.is_locked() {
+            Component::<B>::on_esc(&mut self.lock_screen, &mut self.app_state);
+        } else if self.profile_switcher.is_visible() {
+            self.lock_screen.record_input();
+            self.profile_switcher.on_esc();
+        } else if self.help_overlay.is_visible() {
+            self.lock_screen.record_input();
+            self.help_overlay.hide();
+        } else {
+            self.lock_screen.record_input();
+            self.tabs.on_esc(&mut self.app_state);
+        }
     }
 
     pub fn on_backspace(&mut self) {
-        self.tabs.on_backspace(&mut self.app_state);
+        if self.lock_screen.is_locked() {
+            Component::<B>::on_backspace(&mut self.lock_screen, &mut self.app_state);
+        } else if self.profile_switcher.is_visible() {
+            self.lock_screen.record_input();
+            self.profile_switcher.on_backspace();
+        } else {
+            self.lock_screen.record_input();
+            self.tabs.on_backspace(&mut self.app_state);
+        }
+    }
+
+    /// Restart the idle timer for input that only applies to the tabs, returning false if the wallet is locked and
+    /// the input must be ignored
+    fn unlocked_input(&mut self) -> bool {
+        if self.lock_screen.is_locked() {
+            return false;
+        }
+        self.lock_screen.record_input();
+        true
     }
 
     pub fn on_tick(&mut self) {
         Handle::current().block_on(self.app_state.update_cache());
+        if let Some(price) = self.price_feed.as_mut().and_then(PriceFeed::poll) {
+            self.app_state.set_fiat_price(price);
+        }
         self.tabs.on_tick(&mut self.app_state);
+        Component::<B>::on_tick(&mut self.status_bar, &mut self.app_state);
+        Component::<B>::on_tick(&mut self.lock_screen, &mut self.app_state);
     }
 
     pub fn draw(&mut self, f: &mut Frame<'_, B>) {
-        let max_width_layout = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Length(MAX_WIDTH), Constraint::Min(0)].as_ref())
-            .split(f.size());
+        let area = f.size();
+        if self.lock_screen.is_locked() {
+            self.lock_screen.draw(f, area, &self.app_state);
+            return;
+        }
+        // On narrow terminals the tab titles and the base node status are stacked instead of side by side
+        let layout_mode = LayoutMode::for_width(area.width);
+        let title_height = if layout_mode.is_narrow() { 6 } else { 3 };
         let title_chunks = Layout::default()
-            .constraints([Constraint::Length(3), Constraint::Min(0), Constraint::Length(2)].as_ref())
-            .split(max_width_layout[0]);
-        let title_halves = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
-            .split(title_chunks[0]);
+            .constraints(
+                [
+                    Constraint::Length(title_height),
+                    Constraint::Min(0),
+                    Constraint::Length(1),
+                    Constraint::Length(2),
+                ]
+                .as_ref(),
+            )
+            .split(area);
+        let title_halves = if layout_mode.is_narrow() {
+            Layout::default()
+                .constraints([Constraint::Length(3), Constraint::Length(3)].as_ref())
+                .split(title_chunks[0])
+        } else {
+            Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+                .split(title_chunks[0])
+        };
 
-        self.tabs.draw_titles(f, title_halves[0]);
+        self.tabs.draw_titles(f, title_halves[0], self.app_state.get_theme());
 
         self.base_node_status.draw(f, title_halves[1], &self.app_state);
         self.tabs.draw_content(f, title_chunks[1], &mut self.app_state);
-        self.menu.draw(f, title_chunks[2], &self.app_state);
+        if self.help_overlay.is_visible() {
+            self.help_overlay.draw(f, title_chunks[1], &self.app_state);
+        }
+        if self.profile_switcher.is_visible() {
+            self.profile_switcher.draw(f, title_chunks[1], &self.app_state);
+        }
+        self.status_bar.draw(f, title_chunks[2], &self.app_state);
+        self.menu.draw(f, title_chunks[3], &self.app_state);
     }
 }
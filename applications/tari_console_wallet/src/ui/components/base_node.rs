@@ -25,7 +25,6 @@ use tari_wallet::base_node_service::service::OnlineState;
 use tui::{
     backend::Backend,
     layout::Rect,
-    style::{Color, Modifier, Style},
     text::{Span, Spans},
     widgets::{Block, Borders, Paragraph},
     Frame,
@@ -42,67 +41,69 @@ impl BaseNode {
 impl<B: Backend> Component<B> for BaseNode {
     fn draw(&mut self, f: &mut Frame<B>, area: Rect, app_state: &AppState)
     where B: Backend {
+        let theme = app_state.get_theme();
         let base_node_state = app_state.get_base_node_state();
 
         let chain_info = match base_node_state.online {
             OnlineState::Connecting => Spans::from(vec![
-                Span::styled("Chain Tip:", Style::default().fg(Color::Magenta)),
+                Span::styled("Chain Tip:", theme.label),
                 Span::raw(" "),
-                Span::styled("Connecting...", Style::default().fg(Color::Reset)),
+                Span::styled("Connecting...", theme.text),
             ]),
             OnlineState::Offline => Spans::from(vec![
-                Span::styled("Chain Tip:", Style::default().fg(Color::Magenta)),
+                Span::styled("Chain Tip:", theme.label),
                 Span::raw(" "),
-                Span::styled("Offline", Style::default().fg(Color::Red)),
+                Span::styled("Offline", theme.error),
             ]),
             OnlineState::Online => {
                 if let Some(metadata) = base_node_state.clone().chain_metadata {
                     let tip = metadata.height_of_longest_chain();
 
                     let synced = base_node_state.is_synced.unwrap_or_default();
-                    let (tip_color, sync_text) = if synced {
-                        (Color::Green, "Synced.")
+                    let (tip_style, sync_text) = if synced {
+                        (theme.success, "Synced.")
                     } else {
-                        (Color::Yellow, "Syncing...")
+                        (theme.warning, "Syncing...")
                     };
 
                     let latency = base_node_state.latency.unwrap_or_default().as_millis();
-                    let latency_color = match latency {
-                        0 => Color::Gray, // offline? default duration is 0
-                        1..=800 => Color::Green,
-                        801..=1200 => Color::Yellow,
-                        _ => Color::Red,
+                    let latency_style = match latency {
+                        0 => theme.muted, // offline? default duration is 0
+                        1..=800 => theme.success,
+                        801..=1200 => theme.warning,
+                        _ => theme.error,
                     };
 
                     let tip_info = vec![
-                        Span::styled("Chain Tip:", Style::default().fg(Color::Magenta)),
+                        Span::styled("Chain Tip:", theme.label),
                         Span::raw(" "),
-                        Span::styled(format!("#{}", tip), Style::default().fg(tip_color)),
+                        Span::styled(format!("#{}", tip), tip_style),
                         Span::raw("  "),
-                        Span::styled(sync_text.to_string(), Style::default().fg(Color::DarkGray)),
+                        Span::styled(sync_text.to_string(), theme.muted),
                         Span::raw("  "),
-                        Span::styled("Latency", Style::default().fg(Color::DarkGray)),
+                        Span::styled("Latency", theme.muted),
                         Span::raw(" "),
-                        Span::styled(latency.to_string(), Style::default().fg(latency_color)),
-                        Span::styled(" ms", Style::default().fg(Color::DarkGray)),
+                        Span::styled(latency.to_string(), latency_style),
+                        Span::styled(" ms", theme.muted),
                     ];
 
                     Spans::from(tip_info)
                 } else {
                     Spans::from(vec![
-                        Span::styled("Chain Tip:", Style::default().fg(Color::Magenta)),
+                        Span::styled("Chain Tip:", theme.label),
                         Span::raw(" "),
-                        Span::styled("Error", Style::default().fg(Color::Red)),
+                        Span::styled("Error", theme.error),
                     ])
                 }
             },
         };
 
-        let chain_metadata_paragraph =
-            Paragraph::new(chain_info).block(Block::default().borders(Borders::ALL).title(Span::styled(
-                "Base Node Status:",
-                Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
-            )));
+        let chain_metadata_paragraph = Paragraph::new(chain_info).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.border)
+                .title(Span::styled("Base Node Status:", theme.title)),
+        );
         f.render_widget(chain_metadata_paragraph, area);
     }
 }
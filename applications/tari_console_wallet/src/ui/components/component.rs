@@ -4,12 +4,27 @@ use tui::{backend::Backend, layout::Rect, Frame};
 pub trait Component<B: Backend> {
     fn draw(&mut self, f: &mut Frame<B>, area: Rect, app_state: &AppState);
 
+    /// Whether the component is capturing text, such as an input field being edited. Key bindings are not applied
+    /// while it is, so that the bound characters can still be typed.
+    fn is_editing(&self) -> bool {
+        false
+    }
+
+    /// The keys that the component handles in its current state, as pairs of the key and what it does. These are
+    /// listed by the help overlay, so they should be kept in step with `on_key`.
+    fn key_bindings(&self) -> Vec<(&'static str, &'static str)> {
+        Vec::new()
+    }
+
     fn on_key(&mut self, _app_state: &mut AppState, _c: char) {}
 
     fn on_up(&mut self, _app_state: &mut AppState) {}
 
     fn on_down(&mut self, _app_state: &mut AppState) {}
 
+    /// A mouse click at the given terminal cell
+    fn on_click(&mut self, _app_state: &mut AppState, _column: u16, _row: u16) {}
+
     fn on_esc(&mut self, _app_state: &mut AppState) {}
     fn on_backspace(&mut self, _app_state: &mut AppState) {}
     fn on_tick(&mut self, _app_state: &mut AppState) {}
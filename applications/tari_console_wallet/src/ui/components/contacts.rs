@@ -0,0 +1,403 @@
+use crate::{
+    ui::{
+        components::KeyHandled,
+        state::AppState,
+        widgets::{centered_rect_absolute, draw_dialog, MultiColumnList, WindowedListState},
+        Theme,
+        UiContact,
+    },
+    utils::formatting::display_compressed_string,
+};
+use tari_wallet::contacts_service::handle::ContactOnlineStatus;
+use tokio::runtime::Handle;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, ListItem, Paragraph, Wrap},
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+/// The list of contacts and the dialogs to add, edit and delete them, which the Contacts and Send tabs share
+pub struct Contacts {
+    edit_contact_mode: ContactInputMode,
+    show_edit_contact: bool,
+    alias_field: String,
+    public_key_field: String,
+    // The public key of the contact that is being edited, or None when a new contact is being created
+    editing_public_key: Option<String>,
+    error_message: Option<String>,
+    list_state: WindowedListState,
+    confirm_delete: bool,
+}
+
+impl Contacts {
+    pub fn new() -> Self {
+        Self {
+            edit_contact_mode: ContactInputMode::None,
+            show_edit_contact: false,
+            alias_field: "".to_string(),
+            public_key_field: "".to_string(),
+            editing_public_key: None,
+            error_message: None,
+            list_state: WindowedListState::new(),
+            confirm_delete: false,
+        }
+    }
+
+    /// The selected contact, if there is one
+    pub fn selected<'a>(&self, app_state: &'a AppState) -> Option<&'a UiContact> {
+        self.list_state.selected().and_then(|i| app_state.get_contact(i))
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.list_state.select(None);
+    }
+
+    /// Draw the list of contacts. `instructions` follow the instructions for choosing, editing, deleting and creating
+    /// contacts.
+    pub fn draw_list<B>(&mut self, f: &mut Frame<B>, area: Rect, app_state: &AppState, instructions: Vec<Span>)
+    where B: Backend {
+        let theme = app_state.get_theme();
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Contacts", theme.title));
+        f.render_widget(block, area);
+        let list_areas = Layout::default()
+            .constraints([Constraint::Length(2), Constraint::Min(42)].as_ref())
+            .margin(1)
+            .split(area);
+
+        let mut spans = vec![
+            Span::raw(" Use "),
+            Span::styled("Up/Down Arrow Keys", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to choose a contact, "),
+            Span::styled("E", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to (e)dit and "),
+            Span::styled("D", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to (d)elete a contact, "),
+            Span::styled("N", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to create a (n)ew contact"),
+        ];
+        spans.extend(instructions);
+        let instructions = Paragraph::new(Spans::from(spans)).wrap(Wrap { trim: true });
+        f.render_widget(instructions, list_areas[0]);
+        self.list_state.set_num_items(app_state.get_contacts().len());
+        let mut list_state = self
+            .list_state
+            .get_list_state((list_areas[1].height as usize).saturating_sub(3));
+        let window = self.list_state.get_start_end();
+        let windowed_view = app_state.get_contacts_slice(window.0, window.1);
+
+        let mut status_items = Vec::new();
+        let mut column0_items = Vec::new();
+        let mut column1_items = Vec::new();
+        let mut column2_items = Vec::new();
+        let mut last_seen_items = Vec::new();
+        for c in windowed_view.iter() {
+            let status_style = match c.online_status {
+                ContactOnlineStatus::Online => theme.success,
+                ContactOnlineStatus::Offline => theme.error,
+                ContactOnlineStatus::NeverSeen => theme.muted,
+            };
+            status_items.push(ListItem::new(Span::styled("●", status_style)));
+            column0_items.push(ListItem::new(Span::raw(c.alias.clone())));
+            column1_items.push(ListItem::new(Span::raw(c.public_key.to_string())));
+            column2_items.push(ListItem::new(Span::raw(display_compressed_string(
+                c.emoji_id.clone(),
+                3,
+                3,
+            ))));
+            last_seen_items.push(ListItem::new(Span::raw(c.last_seen.clone())));
+        }
+        let column_list = MultiColumnList::new()
+            .highlight_style(theme.highlight)
+            .heading_style(theme.label)
+            .max_width(list_areas[1].width)
+            .add_column(None, Some(2), status_items)
+            .add_column(Some("Alias"), Some(12), column0_items)
+            .add_column(Some("Public Key"), Some(67), column1_items)
+            .add_column(Some("Emoji ID"), Some(16), column2_items)
+            .add_column(Some("Last Seen"), None, last_seen_items);
+        column_list.render(f, list_areas[1], &mut list_state);
+    }
+
+    /// Draw the add/edit contact, delete confirmation and error dialogs over `area`, if they are open
+    pub fn draw_dialogs<B>(&mut self, f: &mut Frame<B>, area: Rect, app_state: &AppState)
+    where B: Backend {
+        if self.show_edit_contact {
+            self.draw_edit_contact(f, area, app_state.get_theme());
+        }
+
+        if let Some(msg) = self.error_message.clone() {
+            draw_dialog(f, area, "Error!".to_string(), msg, app_state.get_theme().error, 120, 9);
+        }
+
+        if self.confirm_delete {
+            draw_dialog(
+                f,
+                area,
+                "Confirm Delete".to_string(),
+                "Are you sure you want to delete this contact?\n(Y)es / (N)o".to_string(),
+                app_state.get_theme().error,
+                120,
+                9,
+            );
+        }
+    }
+
+    fn draw_edit_contact<B>(&mut self, f: &mut Frame<B>, area: Rect, theme: &Theme)
+    where B: Backend {
+        let popup_area = centered_rect_absolute(120, 10, area);
+
+        f.render_widget(Clear, popup_area);
+
+        let title = if self.editing_public_key.is_some() {
+            "Edit Contact"
+        } else {
+            "Add Contact"
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled(title, theme.title));
+        f.render_widget(block, popup_area);
+        let vert_chunks = Layout::default()
+            .constraints([Constraint::Length(2), Constraint::Length(3), Constraint::Length(3)].as_ref())
+            .margin(1)
+            .split(popup_area);
+
+        let instructions = Paragraph::new(Spans::from(vec![
+            Span::raw("Press "),
+            Span::styled("L", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to edit "),
+            Span::styled("Alias", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" field, "),
+            Span::styled("K", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to edit "),
+            Span::styled("Public Key/Emoji ID", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" field, "),
+            Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to save Contact, "),
+            Span::styled("Esc", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to cancel."),
+        ]))
+        .block(Block::default());
+        f.render_widget(instructions, vert_chunks[0]);
+
+        let alias_input = Paragraph::new(self.alias_field.as_ref())
+            .style(match self.edit_contact_mode {
+                ContactInputMode::Alias => theme.label,
+                _ => Style::default(),
+            })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(theme.border)
+                    .title("A(l)ias:"),
+            );
+        f.render_widget(alias_input, vert_chunks[1]);
+
+        let pubkey_input = Paragraph::new(self.public_key_field.as_ref())
+            .style(match self.edit_contact_mode {
+                ContactInputMode::PubkeyEmojiId => theme.label,
+                _ => Style::default(),
+            })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(theme.border)
+                    .title("Public (K)ey / Emoji Id:"),
+            );
+        f.render_widget(pubkey_input, vert_chunks[2]);
+
+        match self.edit_contact_mode {
+            ContactInputMode::None => (),
+            ContactInputMode::Alias => f.set_cursor(
+                // Put cursor past the end of the input text
+                vert_chunks[1].x + self.alias_field.width() as u16 + 1,
+                // Move one line down, from the border to the input line
+                vert_chunks[1].y + 1,
+            ),
+            ContactInputMode::PubkeyEmojiId => f.set_cursor(
+                // Put cursor past the end of the input text
+                vert_chunks[2].x + self.public_key_field.width() as u16 + 1,
+                // Move one line down, from the border to the input line
+                vert_chunks[2].y + 1,
+            ),
+        }
+    }
+
+    fn open_edit_contact(&mut self, alias: String, public_key: String, editing_public_key: Option<String>) {
+        self.alias_field = alias;
+        self.public_key_field = public_key;
+        self.editing_public_key = editing_public_key;
+        self.show_edit_contact = true;
+        self.edit_contact_mode = ContactInputMode::Alias;
+    }
+
+    fn close_edit_contact(&mut self) {
+        self.alias_field = "".to_string();
+        self.public_key_field = "".to_string();
+        self.editing_public_key = None;
+        self.show_edit_contact = false;
+        self.edit_contact_mode = ContactInputMode::None;
+    }
+
+    fn save_contact(&mut self, app_state: &mut AppState) {
+        let alias = self.alias_field.clone();
+        let public_key = self.public_key_field.clone();
+        let result = match self.editing_public_key.take() {
+            Some(previous_public_key) => {
+                Handle::current().block_on(app_state.update_contact(previous_public_key, alias, public_key))
+            },
+            None => Handle::current().block_on(app_state.upsert_contact(alias, public_key)),
+        };
+        if result.is_err() {
+            self.error_message = Some("Invalid Public key or Emoji ID provided\n Press Enter to continue.".to_string());
+        }
+        self.close_edit_contact();
+    }
+
+    fn on_key_confirm_delete(&mut self, c: char, app_state: &mut AppState) -> KeyHandled {
+        if !self.confirm_delete {
+            return KeyHandled::NotHandled;
+        }
+        match c {
+            'y' => {
+                if let Some(c) = self.selected(app_state).cloned() {
+                    if Handle::current()
+                        .block_on(app_state.delete_contact(c.public_key))
+                        .is_err()
+                    {
+                        self.error_message =
+                            Some("Could not delete selected contact\nPress Enter to continue.".to_string());
+                    }
+                }
+                self.confirm_delete = false;
+            },
+            'n' => self.confirm_delete = false,
+            _ => (),
+        }
+        KeyHandled::Handled
+    }
+
+    fn on_key_edit_contact(&mut self, c: char, app_state: &mut AppState) -> KeyHandled {
+        if !self.show_edit_contact {
+            return KeyHandled::NotHandled;
+        }
+        match self.edit_contact_mode {
+            ContactInputMode::None => match c {
+                'l' => self.edit_contact_mode = ContactInputMode::Alias,
+                'k' => self.edit_contact_mode = ContactInputMode::PubkeyEmojiId,
+                '\n' => self.save_contact(app_state),
+                _ => (),
+            },
+            ContactInputMode::Alias => match c {
+                '\n' | '\t' => self.edit_contact_mode = ContactInputMode::PubkeyEmojiId,
+                c => self.alias_field.push(c),
+            },
+            ContactInputMode::PubkeyEmojiId => match c {
+                '\n' => self.save_contact(app_state),
+                c => self.public_key_field.push(c),
+            },
+        }
+        KeyHandled::Handled
+    }
+
+    /// Whether the add/edit contact dialog is open, which takes all keys
+    pub fn is_editing(&self) -> bool {
+        self.show_edit_contact
+    }
+
+    /// The key bindings for the contacts list, followed by `extra` bindings of the tab that shows it
+    pub fn key_bindings(&self, extra: &[(&'static str, &'static str)]) -> Vec<(&'static str, &'static str)> {
+        if self.confirm_delete {
+            return vec![("Y", "Delete the contact"), ("N", "Keep the contact")];
+        }
+        let mut bindings = vec![
+            ("Up/Down Arrow", "Select a contact"),
+            ("N", "Add a contact"),
+            ("E", "Edit the selected contact"),
+            ("D", "Delete the selected contact"),
+        ];
+        bindings.extend_from_slice(extra);
+        bindings
+    }
+
+    /// Handle the keys of the contacts list and its dialogs. Other keys are left to the tab that shows the list.
+    pub fn on_key(&mut self, c: char, app_state: &mut AppState) -> KeyHandled {
+        if self.error_message.is_some() {
+            if '\n' == c {
+                self.error_message = None;
+            }
+            return KeyHandled::Handled;
+        }
+
+        if self.on_key_confirm_delete(c, app_state) == KeyHandled::Handled {
+            return KeyHandled::Handled;
+        }
+
+        if self.on_key_edit_contact(c, app_state) == KeyHandled::Handled {
+            return KeyHandled::Handled;
+        }
+
+        match c {
+            'n' => self.open_edit_contact("".to_string(), "".to_string(), None),
+            'e' => {
+                if let Some(c) = self.selected(app_state).cloned() {
+                    self.open_edit_contact(c.alias, c.public_key.clone(), Some(c.public_key));
+                }
+            },
+            'd' => {
+                if self.list_state.selected().is_some() {
+                    self.confirm_delete = true;
+                }
+            },
+            _ => return KeyHandled::NotHandled,
+        }
+        KeyHandled::Handled
+    }
+
+    pub fn on_up(&mut self, app_state: &AppState) {
+        self.list_state.set_num_items(app_state.get_contacts().len());
+        self.list_state.previous();
+    }
+
+    pub fn on_down(&mut self, app_state: &AppState) {
+        self.list_state.set_num_items(app_state.get_contacts().len());
+        self.list_state.next();
+    }
+
+    /// Close the add/edit contact dialog, if it is open
+    pub fn on_esc(&mut self) -> KeyHandled {
+        if self.show_edit_contact {
+            self.close_edit_contact();
+            KeyHandled::Handled
+        } else {
+            KeyHandled::NotHandled
+        }
+    }
+
+    pub fn on_backspace(&mut self) {
+        match self.edit_contact_mode {
+            ContactInputMode::Alias => {
+                let _ = self.alias_field.pop();
+            },
+            ContactInputMode::PubkeyEmojiId => {
+                let _ = self.public_key_field.pop();
+            },
+            ContactInputMode::None => {},
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub enum ContactInputMode {
+    None,
+    Alias,
+    PubkeyEmojiId,
+}
@@ -0,0 +1,65 @@
+use crate::ui::{
+    components::{contacts::Contacts, Component, KeyHandled},
+    state::AppState,
+};
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Layout, Rect},
+    text::Span,
+    Frame,
+};
+
+pub struct ContactsTab {
+    contacts: Contacts,
+}
+
+impl ContactsTab {
+    pub fn new() -> Self {
+        Self {
+            contacts: Contacts::new(),
+        }
+    }
+}
+
+impl<B: Backend> Component<B> for ContactsTab {
+    fn draw(&mut self, f: &mut Frame<B>, area: Rect, app_state: &AppState) {
+        let areas = Layout::default()
+            .constraints([Constraint::Min(42)].as_ref())
+            .split(area);
+
+        self.contacts.draw_list(f, areas[0], app_state, vec![Span::raw(
+            ". Contacts can be selected in the Send tab.",
+        )]);
+        self.contacts.draw_dialogs(f, area, app_state);
+    }
+
+    fn is_editing(&self) -> bool {
+        self.contacts.is_editing()
+    }
+
+    fn key_bindings(&self) -> Vec<(&'static str, &'static str)> {
+        self.contacts.key_bindings(&[("Esc", "Clear the selection")])
+    }
+
+    fn on_key(&mut self, app_state: &mut AppState, c: char) {
+        let _ = self.contacts.on_key(c, app_state);
+    }
+
+    fn on_up(&mut self, app_state: &mut AppState) {
+        self.contacts.on_up(app_state);
+    }
+
+    fn on_down(&mut self, app_state: &mut AppState) {
+        self.contacts.on_down(app_state);
+    }
+
+    fn on_esc(&mut self, _: &mut AppState) {
+        if self.contacts.on_esc() == KeyHandled::NotHandled {
+            self.contacts.clear_selection();
+        }
+    }
+
+    fn on_backspace(&mut self, _app_state: &mut AppState) {
+        self.contacts.on_backspace();
+    }
+}
@@ -0,0 +1,146 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::ui::{components::Component, state::AppState, widgets::centered_rect_absolute, KeyAction, KeyMap};
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// The width of the key column
+const KEY_WIDTH: usize = 24;
+
+/// Lists the keys that can be pressed, both those that work everywhere and those of the tab that was open when the
+/// overlay was shown. The global keys come from the key map, the tab keys from `Component::key_bindings`.
+pub struct HelpOverlay {
+    global_bindings: Vec<(String, &'static str)>,
+    tab_title: String,
+    tab_bindings: Vec<(&'static str, &'static str)>,
+    visible: bool,
+    scroll: u16,
+}
+
+impl HelpOverlay {
+    pub fn new(key_map: &KeyMap) -> Self {
+        Self {
+            global_bindings: global_bindings(key_map),
+            tab_title: String::new(),
+            tab_bindings: Vec::new(),
+            visible: false,
+            scroll: 0,
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn show(&mut self, tab_title: &str, tab_bindings: Vec<(&'static str, &'static str)>) {
+        self.tab_title = tab_title.to_string();
+        self.tab_bindings = tab_bindings;
+        self.scroll = 0;
+        self.visible = true;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+
+    pub fn scroll_down(&mut self) {
+        self.scroll += 1;
+    }
+}
+
+impl<B: Backend> Component<B> for HelpOverlay {
+    fn draw(&mut self, f: &mut Frame<B>, area: Rect, app_state: &AppState) {
+        let theme = app_state.get_theme();
+        let heading = |text: String| Spans::from(Span::styled(text, theme.title));
+        let binding = |key: &str, description: &str| {
+            Spans::from(vec![
+                Span::styled(format!("{:<width$}", key, width = KEY_WIDTH), theme.key),
+                Span::raw(description.to_string()),
+            ])
+        };
+
+        let mut lines = vec![heading(format!("{} tab", self.tab_title))];
+        if self.tab_bindings.is_empty() {
+            lines.push(Spans::from(Span::raw("This tab has no keys of its own")));
+        }
+        lines.extend(self.tab_bindings.iter().map(|(k, d)| binding(k, d)));
+        lines.push(Spans::from(""));
+        lines.push(heading("Everywhere".to_string()));
+        lines.extend(self.global_bindings.iter().map(|(k, d)| binding(k, d)));
+
+        let popup_area = centered_rect_absolute(80, lines.len() as u16 + 2, area);
+        // Stop scrolling once the last line is in view
+        self.scroll = self
+            .scroll
+            .min((lines.len() as u16).saturating_sub(popup_area.height.saturating_sub(2)));
+        f.render_widget(Clear, popup_area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Keys (? or Esc to close)", theme.title));
+        f.render_widget(Paragraph::new(lines).block(block).scroll((self.scroll, 0)), popup_area);
+    }
+}
+
+/// The keys handled by the app rather than by a tab, including the characters the key map binds to each action
+fn global_bindings(key_map: &KeyMap) -> Vec<(String, &'static str)> {
+    let mut bindings = KeyAction::ALL
+        .iter()
+        .map(|action| {
+            let mut keys = vec![action.default_keys().to_string()];
+            keys.extend(key_map.keys(*action).into_iter().map(String::from));
+            (keys.join("/"), action.description())
+        })
+        .collect::<Vec<_>>();
+    bindings.push(("Ctrl-V".to_string(), "Paste into the field being edited"));
+    bindings.push(("Ctrl-P".to_string(), "Switch to another wallet profile"));
+    bindings.push(("?".to_string(), "Show this help"));
+    bindings
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn global_bindings_include_the_key_map() {
+        let config = [("up", "k"), ("quit", "x")]
+            .iter()
+            .map(|(a, k)| (a.to_string(), k.to_string()))
+            .collect::<HashMap<_, _>>();
+        let bindings = global_bindings(&KeyMap::from_config(&config).unwrap());
+        assert_eq!(bindings[0], ("Up Arrow/k".to_string(), "Move up"));
+        assert_eq!(bindings[1], ("Down Arrow".to_string(), "Move down"));
+        assert_eq!(bindings[5], ("F10/Ctrl-Q/x".to_string(), "Quit"));
+    }
+}
@@ -0,0 +1,186 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::ui::{components::Component, state::AppState, widgets::centered_rect_absolute, UiError};
+use std::time::{Duration, Instant};
+use tari_wallet::error::{WalletError, WalletStorageError};
+use tokio::runtime::Handle;
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// Hides the wallet once it has been idle for the configured time, until the wallet password is entered again
+pub struct LockScreen {
+    idle_timeout: Option<Duration>,
+    last_input: Instant,
+    locked: bool,
+    password_field: String,
+    error_message: Option<String>,
+}
+
+impl LockScreen {
+    pub fn new(idle_timeout: Option<Duration>) -> Self {
+        Self {
+            idle_timeout,
+            last_input: Instant::now(),
+            locked: false,
+            password_field: String::new(),
+            error_message: None,
+        }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    /// Record user input, which restarts the idle timer
+    pub fn record_input(&mut self) {
+        self.last_input = Instant::now();
+    }
+
+    fn lock_if_idle(&mut self, now: Instant) {
+        if let Some(timeout) = self.idle_timeout {
+            if !self.locked && now.duration_since(self.last_input) >= timeout {
+                self.locked = true;
+                self.password_field.clear();
+                self.error_message = None;
+            }
+        }
+    }
+
+    fn unlock(&mut self, app_state: &AppState) {
+        let password = self.password_field.split_off(0);
+        match Handle::current().block_on(app_state.verify_passphrase(password)) {
+            Ok(()) => {
+                self.locked = false;
+                self.error_message = None;
+                self.record_input();
+            },
+            Err(UiError::WalletError(WalletError::WalletStorageError(WalletStorageError::IncorrectPassword))) => {
+                self.error_message = Some("Incorrect password".to_string());
+            },
+            Err(e) => {
+                self.error_message = Some(format!("Could not verify the password: {}", e));
+            },
+        }
+    }
+}
+
+impl<B: Backend> Component<B> for LockScreen {
+    fn draw(&mut self, f: &mut Frame<B>, area: Rect, app_state: &AppState) {
+        let theme = app_state.get_theme();
+        f.render_widget(Clear, area);
+
+        let popup_area = centered_rect_absolute(60, 8, area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Wallet Locked", theme.title));
+        f.render_widget(block, popup_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(1)
+            .constraints([Constraint::Length(2), Constraint::Length(3), Constraint::Min(1)].as_ref())
+            .split(popup_area);
+
+        let instructions = Paragraph::new(Spans::from(vec![
+            Span::raw("Enter the wallet password and press "),
+            Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to unlock."),
+        ]))
+        .alignment(Alignment::Center);
+        f.render_widget(instructions, chunks[0]);
+
+        let masked = "*".repeat(self.password_field.chars().count());
+        let password_input = Paragraph::new(masked.as_str()).style(theme.label).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.border)
+                .title("Password:"),
+        );
+        f.render_widget(password_input, chunks[1]);
+        f.set_cursor(
+            // Put cursor past the end of the input text
+            chunks[1].x + masked.len() as u16 + 1,
+            // Move one line down, from the border to the input line
+            chunks[1].y + 1,
+        );
+
+        if let Some(msg) = self.error_message.as_ref() {
+            let error = Paragraph::new(Span::styled(msg.as_str(), theme.error)).alignment(Alignment::Center);
+            f.render_widget(error, chunks[2]);
+        }
+    }
+
+    fn is_editing(&self) -> bool {
+        self.locked
+    }
+
+    fn on_key(&mut self, app_state: &mut AppState, c: char) {
+        match c {
+            '\n' => self.unlock(app_state),
+            '\t' => {},
+            c => self.password_field.push(c),
+        }
+    }
+
+    fn on_esc(&mut self, _app_state: &mut AppState) {
+        self.password_field.clear();
+    }
+
+    fn on_backspace(&mut self, _app_state: &mut AppState) {
+        let _ = self.password_field.pop();
+    }
+
+    fn on_tick(&mut self, _app_state: &mut AppState) {
+        self.lock_if_idle(Instant::now());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn locks_after_the_idle_timeout() {
+        let mut lock_screen = LockScreen::new(Some(Duration::from_secs(60)));
+        let start = lock_screen.last_input;
+        lock_screen.lock_if_idle(start + Duration::from_secs(59));
+        assert!(!lock_screen.is_locked());
+        lock_screen.lock_if_idle(start + Duration::from_secs(60));
+        assert!(lock_screen.is_locked());
+    }
+
+    #[test]
+    fn never_locks_without_a_timeout() {
+        let mut lock_screen = LockScreen::new(None);
+        let start = lock_screen.last_input;
+        lock_screen.lock_if_idle(start + Duration::from_secs(24 * 60 * 60));
+        assert!(!lock_screen.is_locked());
+    }
+}
@@ -0,0 +1,341 @@
+use crate::ui::{components::Component, state::AppState, Theme};
+use log::{Level, LevelFilter};
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    path::PathBuf,
+};
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+/// The number of lines of each log file that are kept for scrolling back
+const MAX_LINES: usize = 1000;
+/// How much of the end of a log file is read when it is first shown
+const INITIAL_TAIL_BYTES: u64 = 64 * 1024;
+
+struct LogLine {
+    /// The level of the message, which continuation lines of a multi-line message share with its first line
+    level: Option<Level>,
+    text: String,
+}
+
+/// Follows the lines appended to a log file
+struct LogTail {
+    path: PathBuf,
+    position: Option<u64>,
+    /// The bytes of the last line read, until its line ending has been written
+    partial: Vec<u8>,
+    lines: VecDeque<LogLine>,
+}
+
+impl LogTail {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            position: None,
+            partial: Vec::new(),
+            lines: VecDeque::new(),
+        }
+    }
+
+    /// Read the lines written since the last poll, starting over if the file was rolled over
+    fn poll(&mut self) -> io::Result<()> {
+        let mut file = File::open(&self.path)?;
+        let len = file.metadata()?.len();
+        let (position, skip_first_line) = match self.position {
+            Some(position) if position <= len => (position, false),
+            Some(_) => {
+                self.partial.clear();
+                (0, false)
+            },
+            None => {
+                let position = len.saturating_sub(INITIAL_TAIL_BYTES);
+                (position, position > 0)
+            },
+        };
+        file.seek(SeekFrom::Start(position))?;
+        let mut buf = std::mem::take(&mut self.partial);
+        let num_read = file.read_to_end(&mut buf)?;
+        self.position = Some(position + num_read as u64);
+
+        let mut lines = buf.split(|b| *b == b'\n');
+        // The line the initial read started in is incomplete
+        if skip_first_line {
+            let _ = lines.next();
+        }
+        let mut lines = lines.collect::<Vec<_>>();
+        if let Some(last) = lines.pop() {
+            self.partial = last.to_vec();
+        }
+        for line in lines {
+            self.push_line(String::from_utf8_lossy(line).trim_end_matches('\r').to_string());
+        }
+        Ok(())
+    }
+
+    fn push_line(&mut self, text: String) {
+        let level = parse_level(&text).or_else(|| self.lines.back().and_then(|l| l.level));
+        self.lines.push_back(LogLine { level, text });
+        if self.lines.len() > MAX_LINES {
+            let _ = self.lines.pop_front();
+        }
+    }
+}
+
+pub struct LogTab {
+    tails: Vec<LogTail>,
+    selected_file: usize,
+    level_filter: LevelFilter,
+    paused: bool,
+    /// The number of lines that the view is scrolled up from the newest line
+    scroll: usize,
+    error_message: Option<String>,
+}
+
+impl LogTab {
+    pub fn new(log_files: Vec<PathBuf>) -> Self {
+        Self {
+            tails: log_files.into_iter().map(LogTail::new).collect(),
+            selected_file: 0,
+            level_filter: LevelFilter::Trace,
+            paused: false,
+            scroll: 0,
+            error_message: None,
+        }
+    }
+
+    fn draw_header<B>(&self, f: &mut Frame<B>, area: Rect, theme: &Theme)
+    where B: Backend {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Logs", theme.title));
+        f.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .constraints([Constraint::Length(1), Constraint::Length(1), Constraint::Min(1)].as_ref())
+            .margin(1)
+            .split(area);
+
+        let file = self
+            .tails
+            .get(self.selected_file)
+            .map(|t| t.path.display().to_string())
+            .unwrap_or_else(|| "No log files are configured".to_string());
+        let state = if self.paused {
+            Span::styled("Paused", theme.warning)
+        } else {
+            Span::styled("Following", theme.success)
+        };
+        let status = Paragraph::new(Spans::from(vec![
+            Span::styled("File: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(file),
+            Span::styled("  Level: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(self.level_filter.to_string()),
+            Span::raw("  "),
+            state,
+        ]));
+        f.render_widget(status, chunks[0]);
+
+        let instructions = Paragraph::new(Spans::from(vec![
+            Span::raw("Press "),
+            Span::styled("F", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" for the next log file, "),
+            Span::styled("L", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to change the level, "),
+            Span::styled("P", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to pause or resume, and "),
+            Span::styled("Up/Down Arrow", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to scroll."),
+        ]))
+        .wrap(Wrap { trim: true });
+        f.render_widget(instructions, chunks[1]);
+
+        if let Some(msg) = self.error_message.as_ref() {
+            f.render_widget(Paragraph::new(Span::styled(msg.as_str(), theme.error)), chunks[2]);
+        }
+    }
+
+    fn draw_lines<B>(&mut self, f: &mut Frame<B>, area: Rect, theme: &Theme)
+    where B: Backend {
+        let block = Block::default().borders(Borders::ALL).border_style(theme.border);
+        let height = block.inner(area).height as usize;
+
+        let level_filter = self.level_filter;
+        let lines = self
+            .tails
+            .get(self.selected_file)
+            .map(|t| {
+                t.lines
+                    .iter()
+                    .filter(|l| l.level.map(|level| level <= level_filter).unwrap_or(true))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        self.scroll = self.scroll.min(lines.len().saturating_sub(height));
+        let end = lines.len() - self.scroll;
+        let start = end.saturating_sub(height);
+
+        let text = lines[start..end]
+            .iter()
+            .map(|l| Spans::from(Span::styled(l.text.as_str(), level_style(l.level, theme))))
+            .collect::<Vec<_>>();
+        f.render_widget(Paragraph::new(text).block(block), area);
+    }
+
+    fn next_file(&mut self) {
+        if !self.tails.is_empty() {
+            self.selected_file = (self.selected_file + 1) % self.tails.len();
+            self.scroll = 0;
+            self.error_message = None;
+        }
+    }
+}
+
+impl<B: Backend> Component<B> for LogTab {
+    fn draw(&mut self, f: &mut Frame<B>, area: Rect, app_state: &AppState) {
+        let areas = Layout::default()
+            .constraints([Constraint::Length(5), Constraint::Min(5)].as_ref())
+            .split(area);
+
+        self.draw_header(f, areas[0], app_state.get_theme());
+        self.draw_lines(f, areas[1], app_state.get_theme());
+    }
+
+    fn key_bindings(&self) -> Vec<(&'static str, &'static str)> {
+        vec![
+            ("F", "Show the next log file"),
+            ("L", "Change the lowest level shown"),
+            ("P", "Pause or resume following the file"),
+            ("Up/Down Arrow", "Scroll"),
+        ]
+    }
+
+    fn on_key(&mut self, _app_state: &mut AppState, c: char) {
+        match c {
+            'f' => self.next_file(),
+            'l' => self.level_filter = next_level_filter(self.level_filter),
+            'p' => {
+                self.paused = !self.paused;
+                if !self.paused {
+                    self.scroll = 0;
+                }
+            },
+            _ => {},
+        }
+    }
+
+    /// Scrolling back pauses the view, so that the lines do not move while they are being read
+    fn on_up(&mut self, _app_state: &mut AppState) {
+        self.paused = true;
+        self.scroll += 1;
+    }
+
+    fn on_down(&mut self, _app_state: &mut AppState) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+
+    fn on_tick(&mut self, _app_state: &mut AppState) {
+        if self.paused {
+            return;
+        }
+        if let Some(tail) = self.tails.get_mut(self.selected_file) {
+            self.error_message = tail
+                .poll()
+                .err()
+                .map(|e| format!("Could not read {}: {}", tail.path.display(), e));
+        }
+    }
+}
+
+/// The level of a line written by either the pattern encoder of the default log configuration, where the level follows
+/// the `[target]`, or the JSON encoder
+fn parse_level(line: &str) -> Option<Level> {
+    const JSON_LEVEL: &str = "\"level\":\"";
+    if let Some(i) = line.find(JSON_LEVEL) {
+        let rest = &line[i + JSON_LEVEL.len()..];
+        return rest.split('"').next()?.parse().ok();
+    }
+    let rest = &line[line.find("] ")? + 2..];
+    rest.split_whitespace().next()?.parse().ok()
+}
+
+fn next_level_filter(filter: LevelFilter) -> LevelFilter {
+    match filter {
+        LevelFilter::Trace => LevelFilter::Debug,
+        LevelFilter::Debug => LevelFilter::Info,
+        LevelFilter::Info => LevelFilter::Warn,
+        LevelFilter::Warn => LevelFilter::Error,
+        LevelFilter::Error | LevelFilter::Off => LevelFilter::Trace,
+    }
+}
+
+fn level_style(level: Option<Level>, theme: &Theme) -> Style {
+    match level {
+        Some(Level::Error) => theme.error,
+        Some(Level::Warn) => theme.warning,
+        Some(Level::Debug) | Some(Level::Trace) => theme.muted,
+        _ => Style::default(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn levels_are_parsed_from_pattern_and_json_lines() {
+        assert_eq!(
+            parse_level("2021-03-01 10:00:00.123 [wallet::transaction_service] WARN  Failed to send"),
+            Some(Level::Warn)
+        );
+        assert_eq!(
+            parse_level(r#"{"time":"2021-03-01T10:00:00","level":"ERROR","target":"wallet","message":"oops"}"#),
+            Some(Level::Error)
+        );
+        assert_eq!(parse_level("    at some continuation line"), None);
+    }
+
+    #[test]
+    fn appended_lines_are_tailed() {
+        let path = std::env::temp_dir().join(format!("console_wallet_log_tab_{}.log", std::process::id()));
+        let mut file = File::create(&path).unwrap();
+        writeln!(
+            file,
+            "2021-03-01 10:00:00.000 [wallet] INFO  first\nsecond line of first"
+        )
+        .unwrap();
+
+        let mut tail = LogTail::new(path.clone());
+        tail.poll().unwrap();
+        assert_eq!(tail.lines.len(), 2);
+        assert_eq!(tail.lines[1].level, Some(Level::Info));
+
+        // A line is only shown once it is complete
+        write!(file, "2021-03-01 10:00:01.000 [wallet] ERROR third").unwrap();
+        tail.poll().unwrap();
+        assert_eq!(tail.lines.len(), 2);
+        writeln!(file).unwrap();
+        tail.poll().unwrap();
+        assert_eq!(tail.lines.len(), 3);
+        assert_eq!(tail.lines[2].level, Some(Level::Error));
+
+        // The file is read from the start again when it was rolled over
+        File::create(&path)
+            .unwrap()
+            .write_all(b"2021-03-01 10:00:02.000 [wallet] DEBUG new\n")
+            .unwrap();
+        tail.poll().unwrap();
+        assert_eq!(tail.lines.len(), 4);
+        assert_eq!(tail.lines[3].text, "2021-03-01 10:00:02.000 [wallet] DEBUG new");
+        let _ = std::fs::remove_file(path);
+    }
+}
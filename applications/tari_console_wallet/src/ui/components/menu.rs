@@ -1,8 +1,7 @@
-use crate::ui::{components::Component, state::AppState};
+use crate::ui::{components::Component, state::AppState, LayoutMode};
 use tui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
     text::{Span, Spans},
     widgets::{Block, Paragraph},
     Frame,
@@ -17,44 +16,38 @@ impl Menu {
 }
 
 impl<B: Backend> Component<B> for Menu {
-    fn draw(&mut self, f: &mut Frame<B>, area: Rect, _app_state: &AppState)
+    fn draw(&mut self, f: &mut Frame<B>, area: Rect, app_state: &AppState)
     where B: Backend {
-        let columns = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Ratio(4, 5), Constraint::Ratio(1, 5)].as_ref())
-            .split(area);
+        let theme = app_state.get_theme();
+        // The quit key goes below the others when they do not fit side by side
+        let columns = if LayoutMode::for_width(area.width).is_narrow() {
+            Layout::default()
+                .constraints([Constraint::Length(1), Constraint::Length(1)].as_ref())
+                .split(area)
+        } else {
+            Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Ratio(4, 5), Constraint::Ratio(1, 5)].as_ref())
+                .split(area)
+        };
 
         let others = Spans::from(vec![
-            Span::styled("LeftArrow", Style::default().fg(Color::Green)),
-            Span::styled(":", Style::default().fg(Color::White)),
-            Span::styled(
-                " PrevTab ",
-                Style::default()
-                    .fg(Color::Magenta)
-                    .bg(Color::LightGreen)
-                    .add_modifier(Modifier::BOLD),
-            ),
+            Span::styled("LeftArrow", theme.key),
+            Span::styled(":", theme.text),
+            Span::styled(" PrevTab ", theme.menu),
             Span::raw(" "),
-            Span::styled("Tab/RightArrow", Style::default().fg(Color::Green)),
-            Span::styled(":", Style::default().fg(Color::White)),
-            Span::styled(
-                " NextTab ",
-                Style::default()
-                    .fg(Color::Magenta)
-                    .bg(Color::LightGreen)
-                    .add_modifier(Modifier::BOLD),
-            ),
+            Span::styled("Tab/RightArrow", theme.key),
+            Span::styled(":", theme.text),
+            Span::styled(" NextTab ", theme.menu),
+            Span::raw(" "),
+            Span::styled("?", theme.key),
+            Span::styled(":", theme.text),
+            Span::styled(" Keys ", theme.menu),
         ]);
         let quit = Spans::from(vec![
-            Span::styled("F10/Ctrl-Q", Style::default().fg(Color::Green)),
-            Span::styled(":", Style::default().fg(Color::White)),
-            Span::styled(
-                " Quit    ",
-                Style::default()
-                    .fg(Color::Magenta)
-                    .bg(Color::LightGreen)
-                    .add_modifier(Modifier::BOLD),
-            ),
+            Span::styled("F10/Ctrl-Q", theme.key),
+            Span::styled(":", theme.text),
+            Span::styled(" Quit    ", theme.menu),
         ]);
 
         let paragraph1 = Paragraph::new(others).block(Block::default());
@@ -23,12 +23,26 @@
 pub mod balance;
 pub mod base_node;
 mod component;
+pub mod contacts;
+pub mod contacts_tab;
+pub mod help_overlay;
+pub mod lock_screen;
+pub mod log_tab;
 pub(crate) mod menu;
+pub mod network_stats_tab;
 pub mod network_tab;
+pub mod profile_switcher;
+pub mod qrcode;
 pub mod receive_tab;
+pub mod recovery_progress;
+pub mod scheduled_payments_tab;
 pub mod send_tab;
+pub mod settings_tab;
+pub mod status_bar;
+pub mod swaps_tab;
 pub mod tabs_container;
 pub mod transactions_tab;
+pub mod watched_outputs_tab;
 
 pub use self::component::*;
 
@@ -0,0 +1,212 @@
+use crate::ui::{
+    components::Component,
+    state::{AppState, NetworkStats},
+    widgets::{MultiColumnList, WindowedListState},
+    Theme,
+    MAX_WIDTH,
+};
+use std::time::{Duration, Instant};
+use tari_comms::connectivity::ConnectivityStatus;
+use tokio::runtime::Handle;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, ListItem, Paragraph, Wrap},
+    Frame,
+};
+
+/// How often the statistics are fetched, which is also how often the connected peers are pinged
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+pub struct NetworkStatsTab {
+    stats: Option<NetworkStats>,
+    last_refresh: Option<Instant>,
+    peer_list_state: WindowedListState,
+    error_message: Option<String>,
+}
+
+impl NetworkStatsTab {
+    pub fn new() -> Self {
+        Self {
+            stats: None,
+            last_refresh: None,
+            peer_list_state: WindowedListState::new(),
+            error_message: None,
+        }
+    }
+
+    fn draw_connectivity<B>(&self, f: &mut Frame<B>, area: Rect, theme: &Theme)
+    where B: Backend {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Connectivity", theme.title));
+        f.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .constraints([Constraint::Length(1), Constraint::Min(1)].as_ref())
+            .margin(1)
+            .split(area);
+
+        let status = match self.stats.as_ref().map(|s| s.connectivity_status) {
+            Some(status) => Span::styled(status_text(status), status_style(status, theme)),
+            None => Span::raw("Unknown"),
+        };
+        let status = Paragraph::new(Spans::from(vec![
+            Span::styled("DHT connectivity: ", Style::default().add_modifier(Modifier::BOLD)),
+            status,
+        ]));
+        f.render_widget(status, chunks[0]);
+
+        let message = match self.error_message.as_ref() {
+            Some(e) => Span::styled(e.as_str(), theme.error),
+            None => Span::raw(
+                "Latency is the average round trip time of liveness pings, which are sent to the connected peers \
+                 while this tab is open. Bandwidth is not metered by the comms layer.",
+            ),
+        };
+        f.render_widget(Paragraph::new(message).wrap(Wrap { trim: true }), chunks[1]);
+    }
+
+    fn draw_peers<B>(&mut self, f: &mut Frame<B>, area: Rect, theme: &Theme)
+    where B: Backend {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Connected Peers", theme.title));
+        f.render_widget(block, area);
+
+        let list_areas = Layout::default()
+            .constraints([Constraint::Min(1)].as_ref())
+            .margin(1)
+            .split(area);
+
+        let peers = self.stats.as_ref().map(|s| s.peers.as_slice()).unwrap_or_default();
+        self.peer_list_state.set_num_items(peers.len());
+        let mut list_state = self
+            .peer_list_state
+            .get_list_state((list_areas[0].height as usize).saturating_sub(1));
+        let (start, end) = self.peer_list_state.get_start_end();
+        let windowed_view = peers.get(start..end).unwrap_or_default();
+
+        let mut column0_items = Vec::with_capacity(windowed_view.len());
+        let mut column1_items = Vec::with_capacity(windowed_view.len());
+        let mut column2_items = Vec::with_capacity(windowed_view.len());
+        let mut column3_items = Vec::with_capacity(windowed_view.len());
+        let mut column4_items = Vec::with_capacity(windowed_view.len());
+        let mut column5_items = Vec::with_capacity(windowed_view.len());
+        let mut column6_items = Vec::with_capacity(windowed_view.len());
+        for p in windowed_view.iter() {
+            column0_items.push(ListItem::new(Span::raw(p.node_id.to_string())));
+            column1_items.push(ListItem::new(Span::raw(p.address.to_string())));
+            column2_items.push(ListItem::new(Span::raw(p.direction.to_string())));
+            column3_items.push(ListItem::new(Span::raw(format_duration(p.connected_for))));
+            column4_items.push(ListItem::new(Span::raw(p.num_substreams.to_string())));
+            column5_items.push(ListItem::new(Span::raw(
+                p.latency
+                    .map(|l| format!("{} ms", l.as_millis()))
+                    .unwrap_or_else(|| "-".to_string()),
+            )));
+            column6_items.push(ListItem::new(Span::raw(p.user_agent.clone())));
+        }
+        let column_list = MultiColumnList::new()
+            .highlight_style(theme.highlight)
+            .heading_style(theme.label)
+            .max_width(list_areas[0].width)
+            .add_column(Some("NodeID"), Some(27), column0_items)
+            .add_column(Some("Address"), Some(38), column1_items)
+            .add_column(Some("Direction"), Some(10), column2_items)
+            .add_column(Some("Connected"), Some(10), column3_items)
+            .add_column(Some("Substreams"), Some(11), column4_items)
+            .add_column(Some("Latency"), Some(9), column5_items)
+            .add_column(Some("User Agent"), Some(MAX_WIDTH.saturating_sub(105)), column6_items);
+        column_list.render(f, list_areas[0], &mut list_state);
+    }
+
+    fn refresh(&mut self, app_state: &AppState) {
+        self.last_refresh = Some(Instant::now());
+        match Handle::current().block_on(app_state.get_network_stats()) {
+            Ok(stats) => {
+                self.stats = Some(stats);
+                self.error_message = None;
+            },
+            Err(e) => self.error_message = Some(format!("Could not fetch the network statistics: {}", e)),
+        }
+    }
+}
+
+impl<B: Backend> Component<B> for NetworkStatsTab {
+    fn draw(&mut self, f: &mut Frame<B>, area: Rect, app_state: &AppState) {
+        let areas = Layout::default()
+            .constraints([Constraint::Length(5), Constraint::Min(10)].as_ref())
+            .split(area);
+
+        self.draw_connectivity(f, areas[0], app_state.get_theme());
+        self.draw_peers(f, areas[1], app_state.get_theme());
+    }
+
+    fn key_bindings(&self) -> Vec<(&'static str, &'static str)> {
+        vec![("Up/Down Arrow", "Select a connected peer")]
+    }
+
+    fn on_up(&mut self, _app_state: &mut AppState) {
+        self.peer_list_state.previous();
+    }
+
+    fn on_down(&mut self, _app_state: &mut AppState) {
+        self.peer_list_state.next();
+    }
+
+    fn on_tick(&mut self, app_state: &mut AppState) {
+        if self
+            .last_refresh
+            .map(|t| t.elapsed() >= REFRESH_INTERVAL)
+            .unwrap_or(true)
+        {
+            self.refresh(app_state);
+        }
+    }
+}
+
+fn status_text(status: ConnectivityStatus) -> String {
+    use ConnectivityStatus::*;
+    match status {
+        Initializing => "Initializing".to_string(),
+        Online(n) => format!("Online ({} connected nodes)", n),
+        Degraded(n) => format!("Degraded ({} connected nodes)", n),
+        Offline => "Offline".to_string(),
+    }
+}
+
+fn status_style(status: ConnectivityStatus, theme: &Theme) -> Style {
+    use ConnectivityStatus::*;
+    match status {
+        Initializing => theme.text,
+        Online(_) => theme.success,
+        Degraded(_) => theme.warning,
+        Offline => theme.error,
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{:02}:{:02}:{:02}", secs / 3600, (secs / 60) % 60, secs % 60)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn connectivity_status_includes_the_number_of_connected_nodes() {
+        assert_eq!(status_text(ConnectivityStatus::Initializing), "Initializing");
+        assert_eq!(status_text(ConnectivityStatus::Online(8)), "Online (8 connected nodes)");
+        assert_eq!(
+            status_text(ConnectivityStatus::Degraded(2)),
+            "Degraded (2 connected nodes)"
+        );
+        assert_eq!(status_text(ConnectivityStatus::Offline), "Offline");
+    }
+}
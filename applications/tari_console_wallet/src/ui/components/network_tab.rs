@@ -7,7 +7,7 @@ use crate::{
         widgets::{draw_dialog, MultiColumnList, WindowedListState},
         MAX_WIDTH,
     },
-    utils::formatting::display_address,
+    utils::{clipboard::copy_to_clipboard, formatting::display_address},
 };
 use log::*;
 use tari_comms::peer_manager::Peer;
@@ -16,7 +16,7 @@ use tokio::runtime::Handle;
 use tui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Span, Spans},
     widgets::{Block, Borders, ListItem, ListState, Paragraph, Wrap},
     Frame,
@@ -31,8 +31,12 @@ pub struct NetworkTab {
     previous_public_key_field: String,
     address_field: String,
     previous_address_field: String,
+    name_field: String,
+    /// Whether the base node being entered is added to the saved base nodes, rather than set as the custom base node
+    saving_base_node: bool,
     error_message: Option<String>,
     confirmation_dialog: bool,
+    remove_confirmation: Option<Peer>,
     base_node_list_state: WindowedListState,
     detailed_base_node: Option<Peer>,
 }
@@ -49,8 +53,11 @@ impl NetworkTab {
             previous_public_key_field: public_key,
             address_field: address.clone(),
             previous_address_field: address,
+            name_field: "".to_string(),
+            saving_base_node: false,
             error_message: None,
             confirmation_dialog: false,
+            remove_confirmation: None,
             base_node_list_state: WindowedListState::new(),
             detailed_base_node: Some(base_node_selected),
         }
@@ -58,10 +65,11 @@ impl NetworkTab {
 
     pub fn draw_base_node_selection<B>(&mut self, f: &mut Frame<B>, area: Rect, app_state: &AppState)
     where B: Backend {
-        let block = Block::default().borders(Borders::ALL).title(Span::styled(
-            "Base Node Selection",
-            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
-        ));
+        let theme = app_state.get_theme();
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Base Node Selection", theme.title));
         f.render_widget(block, area);
 
         let areas = Layout::default()
@@ -76,8 +84,13 @@ impl NetworkTab {
             Span::styled("Up/Down Arrow Keys", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw(" to select a new Base Node, "),
             Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw(" to set."),
+            Span::raw(" to set, "),
+            Span::styled("D", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to remove a saved Base Node, "),
+            Span::styled("Y", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to copy its public key."),
         ]))
+        .wrap(Wrap { trim: true })
         .block(Block::default());
         f.render_widget(instructions, areas[0]);
 
@@ -88,20 +101,11 @@ impl NetworkTab {
         let mut column0_items = Vec::with_capacity(capacity);
         let mut column1_items = Vec::with_capacity(capacity);
 
-        let styles: HashMap<bool, Style> = [
-            (true, Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
-            (false, Style::default().fg(Color::Reset)),
-        ]
-        .iter()
-        .cloned()
-        .collect();
+        let styles: HashMap<bool, Style> = [(true, theme.title), (false, theme.text)].iter().cloned().collect();
 
         for (peer_type, peer) in base_node_list {
             let selected = peer == selected_peer;
-            let style = styles
-                .get(&selected)
-                .unwrap_or(&Style::default().fg(Color::Reset))
-                .to_owned();
+            let style = styles.get(&selected).unwrap_or(&theme.text).to_owned();
             column0_items.push(ListItem::new(Span::styled(peer_type, style)));
             column1_items.push(ListItem::new(Span::styled(peer.public_key.to_string(), style)));
         }
@@ -112,20 +116,21 @@ impl NetworkTab {
             .get_list_state((areas[1].height as usize).saturating_sub(3));
 
         let column_list = MultiColumnList::new()
-            .highlight_style(Style::default().add_modifier(Modifier::BOLD).fg(Color::Magenta))
-            .heading_style(Style::default().fg(Color::Magenta))
-            .max_width(MAX_WIDTH)
+            .highlight_style(theme.highlight)
+            .heading_style(theme.label)
+            .max_width(areas[1].width)
             .add_column(Some("Type"), Some(28), column0_items)
             .add_column(Some("Public Key"), Some(65), column1_items);
         column_list.render(f, areas[1], &mut base_node_list_state);
     }
 
-    fn draw_detailed_base_node<B>(&self, f: &mut Frame<B>, area: Rect, _app_state: &AppState)
+    fn draw_detailed_base_node<B>(&self, f: &mut Frame<B>, area: Rect, app_state: &AppState)
     where B: Backend {
-        let block = Block::default().borders(Borders::ALL).title(Span::styled(
-            "Base Node Detail",
-            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
-        ));
+        let theme = app_state.get_theme();
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Base Node Detail", theme.title));
         f.render_widget(block, area);
 
         let columns = Layout::default()
@@ -139,9 +144,9 @@ impl NetworkTab {
             .constraints([Constraint::Length(1), Constraint::Length(1), Constraint::Length(1)].as_ref())
             .split(columns[0]);
 
-        let node_id = Span::styled("Node ID:", Style::default().fg(Color::Magenta));
-        let public_key = Span::styled("Public Key:", Style::default().fg(Color::Magenta));
-        let address = Span::styled("Address:", Style::default().fg(Color::Magenta));
+        let node_id = Span::styled("Node ID:", theme.label);
+        let public_key = Span::styled("Public Key:", theme.label);
+        let address = Span::styled("Address:", theme.label);
 
         let paragraph = Paragraph::new(node_id).wrap(Wrap { trim: true });
         f.render_widget(paragraph, label_layout[0]);
@@ -156,9 +161,9 @@ impl NetworkTab {
                 .constraints([Constraint::Length(1), Constraint::Length(1), Constraint::Length(1)].as_ref())
                 .split(columns[1]);
 
-            let node_id = Span::styled(format!("{}", peer.node_id), Style::default().fg(Color::White));
-            let public_key = Span::styled(peer.public_key.to_hex(), Style::default().fg(Color::White));
-            let address = Span::styled(display_address(peer), Style::default().fg(Color::White));
+            let node_id = Span::styled(format!("{}", peer.node_id), theme.text);
+            let public_key = Span::styled(peer.public_key.to_hex(), theme.text);
+            let address = Span::styled(display_address(peer), theme.text);
 
             let paragraph = Paragraph::new(node_id).wrap(Wrap { trim: true });
             f.render_widget(paragraph, content_layout[0]);
@@ -171,10 +176,11 @@ impl NetworkTab {
 
     pub fn draw_connected_peers_list<B>(&self, f: &mut Frame<B>, area: Rect, app_state: &AppState)
     where B: Backend {
-        let block = Block::default().borders(Borders::ALL).title(Span::styled(
-            "Connected Peers",
-            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
-        ));
+        let theme = app_state.get_theme();
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Connected Peers", theme.title));
         f.render_widget(block, area);
 
         let list_areas = Layout::default()
@@ -192,8 +198,8 @@ impl NetworkTab {
             column2_items.push(ListItem::new(Span::raw(p.user_agent.clone())));
         }
         let column_list = MultiColumnList::new()
-            .heading_style(Style::default().fg(Color::Magenta))
-            .max_width(MAX_WIDTH)
+            .heading_style(theme.label)
+            .max_width(list_areas[0].width)
             .add_column(Some("NodeID"), Some(27), column0_items)
             .add_column(Some("Public Key"), Some(65), column1_items)
             .add_column(Some("User Agent"), Some(MAX_WIDTH.saturating_sub(93)), column2_items);
@@ -202,14 +208,23 @@ impl NetworkTab {
 
     pub fn draw_base_node_peer<B>(&self, f: &mut Frame<B>, area: Rect, app_state: &AppState)
     where B: Backend {
-        let block = Block::default().borders(Borders::ALL).title(Span::styled(
-            "Base Node Peer",
-            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
-        ));
+        let theme = app_state.get_theme();
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Base Node Peer", theme.title));
         f.render_widget(block, area);
 
         let base_node_layout = Layout::default()
-            .constraints([Constraint::Length(2), Constraint::Length(3), Constraint::Length(3)].as_ref())
+            .constraints(
+                [
+                    Constraint::Length(2),
+                    Constraint::Length(3),
+                    Constraint::Length(3),
+                    Constraint::Length(3),
+                ]
+                .as_ref(),
+            )
             .margin(1)
             .split(area);
 
@@ -221,7 +236,11 @@ impl NetworkTab {
                 "Base Node Public Key and Address",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
-            Span::raw(" fields. "),
+            Span::raw(" fields. Press "),
+            Span::styled("A", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to add a named Base Node to switch to later. "),
+            Span::styled("Y", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" copies the public key. "),
         ];
 
         if app_state.get_custom_base_node().is_some() {
@@ -232,31 +251,105 @@ impl NetworkTab {
             ]);
         }
 
-        let instructions_p = Paragraph::new(Spans::from(instructions)).block(Block::default());
+        let instructions_p = Paragraph::new(Spans::from(instructions))
+            .wrap(Wrap { trim: true })
+            .block(Block::default());
         f.render_widget(instructions_p, base_node_layout[0]);
 
         let peer = app_state.get_selected_base_node();
+        let (name, style) = match self.base_node_edit_mode {
+            BaseNodeInputMode::Name => (self.name_field.clone(), theme.label),
+            BaseNodeInputMode::PublicKey | BaseNodeInputMode::Address if self.saving_base_node => {
+                (self.name_field.clone(), Style::default())
+            },
+            _ => (
+                app_state
+                    .get_saved_base_nodes()
+                    .iter()
+                    .find(|(_, p)| p == peer)
+                    .map(|(name, _)| name.clone())
+                    .unwrap_or_default(),
+                Style::default(),
+            ),
+        };
+
+        let name_input = Paragraph::new(name).style(style).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.border)
+                .title("Name:"),
+        );
+        f.render_widget(name_input, base_node_layout[1]);
+
         let (public_key, style) = match self.base_node_edit_mode {
-            BaseNodeInputMode::PublicKey => (self.public_key_field.clone(), Style::default().fg(Color::Magenta)),
-            BaseNodeInputMode::Address => (self.public_key_field.clone(), Style::default()),
+            BaseNodeInputMode::PublicKey => (self.public_key_field.clone(), theme.label),
+            BaseNodeInputMode::Address | BaseNodeInputMode::Name => (self.public_key_field.clone(), Style::default()),
             _ => (peer.public_key.to_hex(), Style::default()),
         };
 
-        let pubkey_input = Paragraph::new(public_key)
-            .style(style)
-            .block(Block::default().borders(Borders::ALL).title("(P)ublic Key:"));
-        f.render_widget(pubkey_input, base_node_layout[1]);
+        let pubkey_input = Paragraph::new(public_key).style(style).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.border)
+                .title("(P)ublic Key:"),
+        );
+        f.render_widget(pubkey_input, base_node_layout[2]);
 
         let (public_address, style) = match self.base_node_edit_mode {
-            BaseNodeInputMode::PublicKey => (self.address_field.clone(), Style::default()),
-            BaseNodeInputMode::Address => (self.address_field.clone(), Style::default().fg(Color::Magenta)),
+            BaseNodeInputMode::PublicKey | BaseNodeInputMode::Name => (self.address_field.clone(), Style::default()),
+            BaseNodeInputMode::Address => (self.address_field.clone(), theme.label),
             _ => (display_address(&peer), Style::default()),
         };
 
-        let address_input = Paragraph::new(public_address)
-            .style(style)
-            .block(Block::default().borders(Borders::ALL).title("Address:"));
-        f.render_widget(address_input, base_node_layout[2]);
+        let address_input = Paragraph::new(public_address).style(style).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.border)
+                .title("Address:"),
+        );
+        f.render_widget(address_input, base_node_layout[3]);
+    }
+
+    /// Put the base node fields back to the base node in use after they have been edited
+    fn restore_base_node_fields(&mut self) {
+        self.public_key_field = self.previous_public_key_field.clone();
+        self.address_field = self.previous_address_field.clone();
+        self.name_field = "".to_string();
+        self.saving_base_node = false;
+        self.base_node_edit_mode = BaseNodeInputMode::None;
+    }
+
+    fn save_base_node(&mut self, app_state: &mut AppState) {
+        if self.name_field.trim().is_empty() {
+            self.error_message = Some("The Base Node needs a name to be saved".to_string());
+        } else if let Err(e) = Handle::current().block_on(app_state.save_base_node(
+            self.name_field.trim().to_string(),
+            self.public_key_field.clone(),
+            self.address_field.clone(),
+        )) {
+            warn!(target: LOG_TARGET, "Could not save base node: {}", e);
+            self.error_message = Some(format!("Error saving Base Node:\n{}", e.to_string()));
+        }
+        self.restore_base_node_fields();
+    }
+
+    fn on_key_remove_dialog(&mut self, c: char, app_state: &mut AppState) -> KeyHandled {
+        if let Some(peer) = self.remove_confirmation.clone() {
+            if 'y' == c {
+                if let Err(e) = Handle::current().block_on(app_state.remove_saved_base_node(peer)) {
+                    warn!(target: LOG_TARGET, "Could not remove saved base node: {}", e);
+                    self.error_message = Some(format!("Error removing saved Base Node:\n{}", e.to_string()));
+                }
+                self.base_node_list_state.select(None);
+                self.base_node_edit_mode = BaseNodeInputMode::None;
+                self.detailed_base_node = Some(app_state.get_selected_base_node().clone());
+            }
+            if 'y' == c || 'n' == c {
+                self.remove_confirmation = None;
+            }
+            return KeyHandled::Handled;
+        }
+        KeyHandled::NotHandled
     }
 
     fn on_key_confirm_dialog(&mut self, c: char, app_state: &mut AppState) -> KeyHandled {
@@ -288,9 +381,25 @@ impl NetworkTab {
         KeyHandled::NotHandled
     }
 
+    fn copy_public_key(&mut self, peer: &Peer) {
+        if let Err(e) = copy_to_clipboard(peer.public_key.to_hex()) {
+            self.error_message = Some(format!("Error copying the public key:\n{}", e));
+        }
+    }
+
     fn on_key_base_node_edit(&mut self, c: char, app_state: &mut AppState) -> KeyHandled {
         if self.base_node_edit_mode != BaseNodeInputMode::None {
             match self.base_node_edit_mode {
+                BaseNodeInputMode::Name => match c {
+                    '\n' => {
+                        self.base_node_edit_mode = BaseNodeInputMode::PublicKey;
+                        return KeyHandled::Handled;
+                    },
+                    c => {
+                        self.name_field.push(c);
+                        return KeyHandled::Handled;
+                    },
+                },
                 BaseNodeInputMode::PublicKey => match c {
                     '\n' => {
                         self.previous_address_field = self.address_field.clone();
@@ -304,6 +413,10 @@ impl NetworkTab {
                     },
                 },
                 BaseNodeInputMode::Address => match c {
+                    '\n' if self.saving_base_node => {
+                        self.save_base_node(app_state);
+                        return KeyHandled::Handled;
+                    },
                     '\n' => {
                         match Handle::current().block_on(
                             app_state.set_custom_base_node(self.public_key_field.clone(), self.address_field.clone()),
@@ -333,7 +446,12 @@ impl NetworkTab {
                 BaseNodeInputMode::Selection => match c {
                     '\n' => {
                         if let Some(peer) = self.detailed_base_node.clone() {
-                            if let Err(e) = Handle::current().block_on(app_state.set_base_node_peer(peer)) {
+                            let result = if app_state.is_saved_base_node(&peer) {
+                                Handle::current().block_on(app_state.activate_saved_base_node(peer))
+                            } else {
+                                Handle::current().block_on(app_state.set_base_node_peer(peer))
+                            };
+                            if let Err(e) = result {
                                 warn!(target: LOG_TARGET, "Could not set new base node peer: {}", e);
                                 self.error_message =
                                     Some(format!("Error setting new Base Node Address:\n{}", e.to_string()));
@@ -344,6 +462,20 @@ impl NetworkTab {
                         self.base_node_edit_mode = BaseNodeInputMode::None;
                         return KeyHandled::Handled;
                     },
+                    'd' => {
+                        if let Some(peer) = self.detailed_base_node.clone() {
+                            if app_state.is_saved_base_node(&peer) {
+                                self.remove_confirmation = Some(peer);
+                            }
+                        }
+                        return KeyHandled::Handled;
+                    },
+                    'y' => {
+                        if let Some(peer) = self.detailed_base_node.as_ref() {
+                            self.copy_public_key(peer);
+                        }
+                        return KeyHandled::Handled;
+                    },
                     _ => return KeyHandled::Handled,
                 },
                 BaseNodeInputMode::None => (),
@@ -359,7 +491,7 @@ impl<B: Backend> Component<B> for NetworkTab {
             .constraints(
                 [
                     Constraint::Length(3),
-                    Constraint::Length(10),
+                    Constraint::Length(13),
                     Constraint::Length(8 + app_state.get_base_node_list().len() as u16),
                     Constraint::Length(5),
                     Constraint::Min(12),
@@ -375,7 +507,7 @@ impl<B: Backend> Component<B> for NetworkTab {
         self.draw_connected_peers_list(f, areas[4], app_state);
 
         if let Some(msg) = self.error_message.clone() {
-            draw_dialog(f, area, "Error!".to_string(), msg, Color::Red, 120, 9);
+            draw_dialog(f, area, "Error!".to_string(), msg, app_state.get_theme().error, 120, 9);
         }
 
         if self.confirmation_dialog {
@@ -384,11 +516,52 @@ impl<B: Backend> Component<B> for NetworkTab {
                 area,
                 "Confirm clearing custom Base Node".to_string(),
                 "Are you sure you want to clear the custom Base node?\n(Y)es / (N)o".to_string(),
-                Color::Red,
+                app_state.get_theme().error,
                 120,
                 9,
             );
         }
+
+        if self.remove_confirmation.is_some() {
+            draw_dialog(
+                f,
+                area,
+                "Confirm removing saved Base Node".to_string(),
+                "Are you sure you want to remove this saved Base Node?\n(Y)es / (N)o".to_string(),
+                app_state.get_theme().error,
+                120,
+                9,
+            );
+        }
+    }
+
+    fn is_editing(&self) -> bool {
+        matches!(
+            self.base_node_edit_mode,
+            BaseNodeInputMode::Name | BaseNodeInputMode::PublicKey | BaseNodeInputMode::Address
+        )
+    }
+
+    fn key_bindings(&self) -> Vec<(&'static str, &'static str)> {
+        if self.confirmation_dialog || self.remove_confirmation.is_some() {
+            return vec![("Y", "Confirm"), ("N", "Cancel")];
+        }
+        if matches!(self.base_node_edit_mode, BaseNodeInputMode::Selection) {
+            return vec![
+                ("Up/Down Arrow", "Select a base node"),
+                ("Enter", "Connect to the selected base node"),
+                ("D", "Remove the selected saved base node"),
+                ("Y", "Copy the public key of the selected base node"),
+                ("Esc", "Close the list"),
+            ];
+        }
+        vec![
+            ("B", "Choose one of the saved base nodes"),
+            ("P", "Set a custom base node"),
+            ("A", "Add and save a base node"),
+            ("C", "Clear the custom base node"),
+            ("Y", "Copy the public key of the base node"),
+        ]
     }
 
     fn on_key(&mut self, app_state: &mut AppState, c: char) {
@@ -400,6 +573,9 @@ impl<B: Backend> Component<B> for NetworkTab {
         if self.on_key_confirm_dialog(c, app_state) == KeyHandled::Handled {
             return;
         }
+        if self.on_key_remove_dialog(c, app_state) == KeyHandled::Handled {
+            return;
+        }
         if self.on_key_base_node_edit(c, app_state) == KeyHandled::Handled {
             return;
         }
@@ -410,13 +586,30 @@ impl<B: Backend> Component<B> for NetworkTab {
                 self.public_key_field = "".to_string();
                 self.base_node_edit_mode = BaseNodeInputMode::PublicKey;
             },
+            'a' => {
+                self.previous_public_key_field = self.public_key_field.clone();
+                self.public_key_field = "".to_string();
+                self.name_field = "".to_string();
+                self.saving_base_node = true;
+                self.base_node_edit_mode = BaseNodeInputMode::Name;
+            },
             'c' => {
                 if app_state.get_custom_base_node().is_some() {
                     self.confirmation_dialog = true;
                 }
             },
+            'y' => {
+                let peer = app_state.get_selected_base_node().clone();
+                self.copy_public_key(&peer);
+            },
             'b' => {
-                if app_state.get_custom_base_node().is_some() {
+                // A custom base node that has not been saved would be lost by switching, so it must be cleared first
+                let unsaved_custom = app_state
+                    .get_custom_base_node()
+                    .as_ref()
+                    .map(|peer| !app_state.is_saved_base_node(peer))
+                    .unwrap_or(false);
+                if unsaved_custom {
                     self.confirmation_dialog = true;
                 } else {
                     self.base_node_list_state
@@ -435,6 +628,9 @@ impl<B: Backend> Component<B> for NetworkTab {
 
     fn on_backspace(&mut self, _app_state: &mut AppState) {
         match self.base_node_edit_mode {
+            BaseNodeInputMode::Name => {
+                let _ = self.name_field.pop();
+            },
             BaseNodeInputMode::PublicKey => {
                 let _ = self.public_key_field.pop();
             },
@@ -447,10 +643,8 @@ impl<B: Backend> Component<B> for NetworkTab {
 
     fn on_esc(&mut self, app_state: &mut AppState) {
         match self.base_node_edit_mode {
-            BaseNodeInputMode::PublicKey | BaseNodeInputMode::Address => {
-                self.public_key_field = self.previous_public_key_field.clone();
-                self.address_field = self.previous_address_field.clone();
-                self.base_node_edit_mode = BaseNodeInputMode::None;
+            BaseNodeInputMode::Name | BaseNodeInputMode::PublicKey | BaseNodeInputMode::Address => {
+                self.restore_base_node_fields();
             },
             _ => {
                 self.base_node_list_state.select(None);
@@ -499,6 +693,7 @@ impl<B: Backend> Component<B> for NetworkTab {
 #[derive(PartialEq, Debug)]
 pub enum BaseNodeInputMode {
     None,
+    Name,
     PublicKey,
     Address,
     Selection,
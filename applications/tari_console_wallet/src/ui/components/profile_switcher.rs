@@ -0,0 +1,226 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::{
+    init::{validate_profile_name, Profiles},
+    ui::{components::Component, state::AppState, widgets::centered_rect_absolute},
+};
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+/// Lists the wallet profiles so that another one can be opened, or a new one created. Opening a profile closes the
+/// wallet of the open profile first, so the switcher only decides which profile is opened next.
+pub struct ProfileSwitcher {
+    profiles: Profiles,
+    selected: usize,
+    /// The name of the new profile while it is being typed
+    new_profile: Option<String>,
+    error_message: Option<String>,
+    visible: bool,
+}
+
+impl ProfileSwitcher {
+    pub fn new(profiles: Profiles) -> Self {
+        Self {
+            profiles,
+            selected: 0,
+            new_profile: None,
+            error_message: None,
+            visible: false,
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn show(&mut self) {
+        self.selected = self
+            .profiles
+            .available
+            .iter()
+            .position(|p| *p == self.profiles.current)
+            .unwrap_or(0);
+        self.new_profile = None;
+        self.error_message = None;
+        self.visible = true;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    pub fn up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn down(&mut self) {
+        self.selected = (self.selected + 1).min(self.profiles.available.len().saturating_sub(1));
+    }
+
+    /// Handle a key, returning the profile to open when one was chosen
+    pub fn on_key(&mut self, c: char) -> Option<String> {
+        if let Some(name) = self.new_profile.as_mut() {
+            if c != '\n' {
+                name.push(c);
+                return None;
+            }
+            let name = name.clone();
+            if let Err(e) = validate_profile_name(&name) {
+                self.error_message = Some(e);
+                return None;
+            }
+            return self.open(name);
+        }
+        match c {
+            '\n' => {
+                let name = self.profiles.available.get(self.selected).cloned()?;
+                self.open(name)
+            },
+            'n' => {
+                self.new_profile = Some(String::new());
+                self.error_message = None;
+                None
+            },
+            _ => None,
+        }
+    }
+
+    pub fn on_backspace(&mut self) {
+        if let Some(name) = self.new_profile.as_mut() {
+            let _ = name.pop();
+        }
+    }
+
+    /// Leave the typing of a new profile name, or close the switcher
+    pub fn on_esc(&mut self) {
+        if self.new_profile.is_some() {
+            self.new_profile = None;
+            self.error_message = None;
+        } else {
+            self.hide();
+        }
+    }
+
+    fn open(&mut self, name: String) -> Option<String> {
+        self.hide();
+        if name == self.profiles.current {
+            None
+        } else {
+            Some(name)
+        }
+    }
+}
+
+impl<B: Backend> Component<B> for ProfileSwitcher {
+    fn draw(&mut self, f: &mut Frame<B>, area: Rect, app_state: &AppState) {
+        let theme = app_state.get_theme();
+        let mut lines = self
+            .profiles
+            .available
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let text = if *name == self.profiles.current {
+                    format!("{} (open)", name)
+                } else {
+                    name.clone()
+                };
+                if i == self.selected && self.new_profile.is_none() {
+                    Spans::from(Span::styled(text, theme.highlight))
+                } else {
+                    Spans::from(Span::raw(text))
+                }
+            })
+            .collect::<Vec<_>>();
+        lines.push(Spans::from(""));
+        match self.new_profile.as_ref() {
+            Some(name) => {
+                lines.push(Spans::from(vec![
+                    Span::styled("New profile: ", theme.label),
+                    Span::raw(format!("{}_", name)),
+                ]));
+                lines.push(Spans::from(Span::raw("Enter to create and open it, Esc to cancel")));
+            },
+            None => lines.push(Spans::from(Span::raw(
+                "Enter to open the selected profile, N to create a new one, Esc to close",
+            ))),
+        }
+        if let Some(e) = self.error_message.as_ref() {
+            lines.push(Spans::from(Span::styled(e.as_str(), theme.error)));
+        }
+
+        let popup_area = centered_rect_absolute(60, lines.len() as u16 + 4, area);
+        f.render_widget(Clear, popup_area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Wallet Profiles", theme.title));
+        f.render_widget(Paragraph::new(lines).block(block).wrap(Wrap { trim: true }), popup_area);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn switcher() -> ProfileSwitcher {
+        let mut switcher = ProfileSwitcher::new(Profiles {
+            current: "default".to_string(),
+            available: vec!["default".to_string(), "business".to_string()],
+        });
+        switcher.show();
+        switcher
+    }
+
+    #[test]
+    fn the_selected_profile_is_opened() {
+        let mut switcher = switcher();
+        assert_eq!(switcher.on_key('\n'), None);
+        assert!(!switcher.is_visible());
+
+        switcher.show();
+        switcher.down();
+        assert_eq!(switcher.on_key('\n'), Some("business".to_string()));
+    }
+
+    #[test]
+    fn a_new_profile_name_is_validated() {
+        let mut switcher = switcher();
+        let _ = switcher.on_key('n');
+        for c in "my wallet".chars() {
+            let _ = switcher.on_key(c);
+        }
+        assert_eq!(switcher.on_key('\n'), None);
+        assert!(switcher.error_message.is_some());
+
+        for _ in 0.." wallet".len() {
+            switcher.on_backspace();
+        }
+        assert_eq!(switcher.on_key('\n'), Some("my".to_string()));
+    }
+}
@@ -0,0 +1,213 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use ::qrcode::{render::unicode, QrCode};
+use tari_common::Network;
+use tari_comms::types::CommsPublicKey;
+use tari_core::transactions::tari_amount::MicroTari;
+use tari_crypto::tari_utilities::hex::Hex;
+
+/// The `tari://` URI that a wallet can scan to pay `public_key`, optionally requesting a specific amount with a note
+/// for the payment
+pub fn payment_uri(
+    network: Network,
+    public_key: &CommsPublicKey,
+    amount: Option<MicroTari>,
+    note: Option<&str>,
+) -> String
+{
+    let mut query = Vec::new();
+    if let Some(amount) = amount {
+        query.push(format!("amount={}", amount.0));
+    }
+    if let Some(note) = note.filter(|n| !n.is_empty()) {
+        query.push(format!("note={}", percent_encode(note)));
+    }
+    let uri = format!("tari://{}/pubkey/{}", network, public_key.to_hex());
+    if query.is_empty() {
+        uri
+    } else {
+        format!("{}?{}", uri, query.join("&"))
+    }
+}
+
+/// What a payment URI asks to be paid
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaymentRequest {
+    pub public_key: CommsPublicKey,
+    pub amount: Option<MicroTari>,
+    pub note: Option<String>,
+}
+
+/// Parse a payment URI made by `payment_uri`. The network may be left out, as in `tari://<public key>?amount=..`, and
+/// query parameters other than the amount and note are ignored.
+pub fn parse_payment_uri(uri: &str) -> Result<PaymentRequest, String> {
+    let rest = uri
+        .strip_prefix("tari://")
+        .ok_or_else(|| "A payment URI starts with tari://".to_string())?;
+    let (path, query) = match rest.find('?') {
+        Some(i) => (&rest[..i], &rest[i + 1..]),
+        None => (rest, ""),
+    };
+    let public_key = path.trim_end_matches('/').rsplit('/').next().unwrap_or_default();
+    let public_key =
+        CommsPublicKey::from_hex(public_key).map_err(|_| "The payment URI has no valid public key".to_string())?;
+
+    let mut request = PaymentRequest {
+        public_key,
+        amount: None,
+        note: None,
+    };
+    for (key, value) in query.split('&').filter_map(|p| {
+        let mut kv = p.splitn(2, '=');
+        Some((kv.next()?, kv.next().unwrap_or_default()))
+    }) {
+        match key {
+            "amount" => {
+                let amount = value
+                    .parse::<u64>()
+                    .map_err(|_| "The amount of the payment URI should be an integer".to_string())?;
+                request.amount = Some(MicroTari::from(amount));
+            },
+            "note" => request.note = Some(percent_decode(value)?),
+            _ => {},
+        }
+    }
+    Ok(request)
+}
+
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn percent_decode(text: &str) -> Result<String, String> {
+    let invalid = || "The note of the payment URI is not encoded correctly".to_string();
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = text.get(i + 1..i + 3).ok_or_else(invalid)?;
+                decoded.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+                i += 3;
+            },
+            b'+' => {
+                decoded.push(b' ');
+                i += 1;
+            },
+            b => {
+                decoded.push(b);
+                i += 1;
+            },
+        }
+    }
+    String::from_utf8(decoded).map_err(|_| invalid())
+}
+
+/// Render `data` as a QR code made of unicode half blocks, so that every line of text holds two rows of modules.
+/// Returns `None` if the data does not fit in a QR code.
+pub fn render_qr_code(data: &str) -> Option<String> {
+    let code = QrCode::new(data).ok()?;
+    let image = code
+        .render::<unicode::Dense1x2>()
+        .dark_color(unicode::Dense1x2::Dark)
+        .light_color(unicode::Dense1x2::Light)
+        .build()
+        .lines()
+        .skip(1)
+        .fold("".to_string(), |acc, l| format!("{}{}\n", acc, l));
+    Some(image)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::OsRng;
+    use tari_core::transactions::{tari_amount::uT, types::PrivateKey};
+    use tari_crypto::keys::{PublicKey as PK, SecretKey as SK};
+
+    #[test]
+    fn payment_uri_includes_the_requested_amount() {
+        let public_key = CommsPublicKey::from_secret_key(&PrivateKey::random(&mut OsRng));
+        let uri = payment_uri(Network::Stibbons, &public_key, None, None);
+        assert_eq!(uri, format!("tari://stibbons/pubkey/{}", public_key.to_hex()));
+        let uri = payment_uri(Network::Stibbons, &public_key, Some(1234 * uT), None);
+        assert_eq!(
+            uri,
+            format!("tari://stibbons/pubkey/{}?amount=1234", public_key.to_hex())
+        );
+        let uri = payment_uri(Network::Stibbons, &public_key, Some(1234 * uT), Some("Coffee & cake"));
+        assert_eq!(
+            uri,
+            format!(
+                "tari://stibbons/pubkey/{}?amount=1234&note=Coffee%20%26%20cake",
+                public_key.to_hex()
+            )
+        );
+    }
+
+    #[test]
+    fn payment_uris_are_parsed() {
+        let public_key = CommsPublicKey::from_secret_key(&PrivateKey::random(&mut OsRng));
+        let uri = payment_uri(
+            Network::Stibbons,
+            &public_key,
+            Some(1234 * uT),
+            Some("Coffee & cake ☕"),
+        );
+        assert_eq!(
+            parse_payment_uri(&uri),
+            Ok(PaymentRequest {
+                public_key: public_key.clone(),
+                amount: Some(1234 * uT),
+                note: Some("Coffee & cake ☕".to_string()),
+            })
+        );
+
+        let uri = format!("tari://{}?note=Rent+for+May", public_key.to_hex());
+        assert_eq!(
+            parse_payment_uri(&uri),
+            Ok(PaymentRequest {
+                public_key,
+                amount: None,
+                note: Some("Rent for May".to_string()),
+            })
+        );
+
+        assert!(parse_payment_uri("tari://stibbons/pubkey/00").is_err());
+        assert!(parse_payment_uri("https://tari.com").is_err());
+    }
+
+    #[test]
+    fn qr_code_lines_have_the_same_width() {
+        let image = render_qr_code("tari://stibbons/pubkey/00").unwrap();
+        let widths = image.lines().map(|l| l.chars().count()).collect::<Vec<_>>();
+        assert!(!widths.is_empty());
+        assert!(widths.iter().all(|w| *w == widths[0]));
+    }
+}
@@ -1,26 +1,84 @@
-use crate::ui::{components::Component, state::AppState};
+use crate::{
+    ui::{
+        components::{
+            qrcode::{payment_uri, render_qr_code},
+            Component,
+        },
+        state::AppState,
+        LayoutMode,
+    },
+    utils::clipboard::copy_to_clipboard,
+};
+use tari_core::transactions::tari_amount::MicroTari;
 use tui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
-    text::Span,
-    widgets::{Block, Borders, Paragraph},
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph, Wrap},
     Frame,
 };
+use unicode_width::UnicodeWidthStr;
 
-pub struct ReceiveTab {}
+pub struct ReceiveTab {
+    amount_field: String,
+    note_field: String,
+    input_mode: RequestInputMode,
+    /// The payment URI and QR code for the requested amount and note, rendered when either is entered
+    payment_request: Option<(String, String)>,
+    /// The outcome of the last copy to the clipboard
+    clipboard_status: Option<Result<&'static str, String>>,
+}
 
 impl ReceiveTab {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            amount_field: "".to_string(),
+            note_field: "".to_string(),
+            input_mode: RequestInputMode::None,
+            payment_request: None,
+            clipboard_status: None,
+        }
+    }
+
+    fn copy(&mut self, what: &'static str, text: String) {
+        self.clipboard_status = Some(copy_to_clipboard(text).map(|_| what).map_err(|e| e.to_string()));
+    }
+
+    fn update_payment_request(&mut self, app_state: &AppState) {
+        if self.amount_field.is_empty() && self.note_field.is_empty() {
+            self.payment_request = None;
+            return;
+        }
+        let identity = app_state.get_identity();
+        let uri = payment_uri(
+            identity.network,
+            &identity.comms_public_key,
+            self.amount_field.parse::<u64>().ok().map(MicroTari::from),
+            Some(self.note_field.as_str()),
+        );
+        self.payment_request = render_qr_code(&uri).map(|qr_code| (uri, qr_code));
+    }
+
+    fn payment_uri(&self, app_state: &AppState) -> String {
+        match &self.payment_request {
+            Some((uri, _)) => uri.clone(),
+            None => payment_uri(
+                app_state.get_identity().network,
+                &app_state.get_identity().comms_public_key,
+                None,
+                None,
+            ),
+        }
     }
 
     fn draw_whoami<B>(&self, f: &mut Frame<B>, area: Rect, app_state: &AppState)
     where B: Backend {
-        let block = Block::default().borders(Borders::ALL).title(Span::styled(
-            "Who Am I?",
-            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
-        ));
+        let theme = app_state.get_theme();
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Who Am I?", theme.title));
         f.render_widget(block, area);
 
         let help_body_area = Layout::default()
@@ -28,15 +86,29 @@ impl ReceiveTab {
             .margin(1)
             .split(area);
 
-        let chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Length(48), Constraint::Min(1)].as_ref())
-            .margin(1)
-            .split(help_body_area[0]);
+        // The QR code goes below the fields when they do not fit beside it
+        let (qr_code_area, info_area) = if LayoutMode::for_width(area.width).is_narrow() {
+            let chunks = Layout::default()
+                .constraints([Constraint::Length(20), Constraint::Min(1)].as_ref())
+                .margin(1)
+                .split(help_body_area[0]);
+            (chunks[1], chunks[0])
+        } else {
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Length(56), Constraint::Min(1)].as_ref())
+                .margin(1)
+                .split(help_body_area[0]);
+            (chunks[0], chunks[1])
+        };
 
-        let qr_code = Paragraph::new(app_state.get_identity().qr_code.as_str()).block(Block::default());
+        let qr_code = match &self.payment_request {
+            Some((_, qr_code)) => qr_code.as_str(),
+            None => app_state.get_identity().qr_code.as_str(),
+        };
+        let qr_code = Paragraph::new(qr_code).block(Block::default());
 
-        f.render_widget(qr_code, chunks[0]);
+        f.render_widget(qr_code, qr_code_area);
 
         let info_chunks = Layout::default()
             .constraints(
@@ -45,17 +117,21 @@ impl ReceiveTab {
                     Constraint::Length(3),
                     Constraint::Length(3),
                     Constraint::Length(3),
+                    Constraint::Length(3),
+                    Constraint::Length(3),
+                    Constraint::Length(2),
                     Constraint::Min(1),
                 ]
                 .as_ref(),
             )
             .horizontal_margin(1)
-            .split(chunks[1]);
+            .split(info_area);
 
         // Public Key
         let block = Block::default()
             .borders(Borders::ALL)
-            .title(Span::styled("Public Key", Style::default().fg(Color::White)));
+            .border_style(theme.border)
+            .title(Span::styled("Public Key ((Y) to copy)", theme.text));
         f.render_widget(block, info_chunks[1]);
         let label_layout = Layout::default()
             .constraints([Constraint::Length(1)].as_ref())
@@ -67,7 +143,8 @@ impl ReceiveTab {
         // Public Address
         let block = Block::default()
             .borders(Borders::ALL)
-            .title(Span::styled("Public Address", Style::default().fg(Color::White)));
+            .border_style(theme.border)
+            .title(Span::styled("Public Address", theme.text));
         f.render_widget(block, info_chunks[2]);
         let label_layout = Layout::default()
             .constraints([Constraint::Length(1)].as_ref())
@@ -79,7 +156,8 @@ impl ReceiveTab {
         // Emoji ID
         let block = Block::default()
             .borders(Borders::ALL)
-            .title(Span::styled("Emoji ID", Style::default().fg(Color::White)));
+            .border_style(theme.border)
+            .title(Span::styled("(E)moji ID", theme.text));
         f.render_widget(block, info_chunks[3]);
         let label_layout = Layout::default()
             .constraints([Constraint::Length(1)].as_ref())
@@ -87,6 +165,78 @@ impl ReceiveTab {
             .split(info_chunks[3]);
         let emoji_id = Paragraph::new(app_state.get_identity().emoji_id.as_str());
         f.render_widget(emoji_id, label_layout[0]);
+
+        // Payment request
+        let request_layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)].as_ref())
+            .split(info_chunks[4]);
+        let amount_input = Paragraph::new(self.amount_field.as_ref())
+            .style(match self.input_mode {
+                RequestInputMode::Amount => theme.label,
+                _ => Style::default(),
+            })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(theme.border)
+                    .title("Request (A)mount (uT):"),
+            );
+        f.render_widget(amount_input, request_layout[0]);
+        let note_input = Paragraph::new(self.note_field.as_ref())
+            .style(match self.input_mode {
+                RequestInputMode::Note => theme.label,
+                _ => Style::default(),
+            })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(theme.border)
+                    .title("(N)ote, Esc to clear the request:"),
+            );
+        f.render_widget(note_input, request_layout[1]);
+        match self.input_mode {
+            RequestInputMode::None => (),
+            RequestInputMode::Amount => f.set_cursor(
+                // Put cursor past the end of the input text
+                request_layout[0].x + self.amount_field.width() as u16 + 1,
+                // Move one line down, from the border to the input line
+                request_layout[0].y + 1,
+            ),
+            RequestInputMode::Note => f.set_cursor(
+                request_layout[1].x + self.note_field.width() as u16 + 1,
+                request_layout[1].y + 1,
+            ),
+        }
+
+        // Payment URI
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Payment (U)RI", theme.text));
+        f.render_widget(block, info_chunks[5]);
+        let label_layout = Layout::default()
+            .constraints([Constraint::Length(1)].as_ref())
+            .margin(1)
+            .split(info_chunks[5]);
+        f.render_widget(Paragraph::new(self.payment_uri(app_state)), label_layout[0]);
+
+        let instructions = Paragraph::new(Spans::from(vec![
+            Span::styled("Scan to pay", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(", or paste the URI into the To field of a Send form."),
+        ]))
+        .wrap(Wrap { trim: true });
+        f.render_widget(instructions, info_chunks[6]);
+
+        let clipboard_status = match &self.clipboard_status {
+            Some(Ok(what)) => Span::styled(format!("{} copied to the clipboard", what), theme.success),
+            Some(Err(e)) => Span::styled(format!("Could not copy to the clipboard: {}", e), theme.error),
+            None => Span::raw(""),
+        };
+        f.render_widget(
+            Paragraph::new(clipboard_status).wrap(Wrap { trim: true }),
+            info_chunks[7],
+        );
     }
 }
 
@@ -99,13 +249,90 @@ impl<B: Backend> Component<B> for ReceiveTab {
         self.draw_whoami(f, areas[0], app_state);
     }
 
-    fn on_key(&mut self, _app_state: &mut AppState, _c: char) {}
+    fn is_editing(&self) -> bool {
+        self.input_mode != RequestInputMode::None
+    }
+
+    fn key_bindings(&self) -> Vec<(&'static str, &'static str)> {
+        vec![
+            ("A", "Enter an amount to request"),
+            ("N", "Enter a note for the payment request"),
+            ("Y", "Copy the public key"),
+            ("E", "Copy the emoji ID"),
+            ("U", "Copy the payment URI"),
+            ("Esc", "Clear the payment request"),
+        ]
+    }
+
+    fn on_key(&mut self, app_state: &mut AppState, c: char) {
+        match self.input_mode {
+            RequestInputMode::None => (),
+            RequestInputMode::Amount => {
+                match c {
+                    '\n' | '\t' => {
+                        self.input_mode = if c == '\t' {
+                            RequestInputMode::Note
+                        } else {
+                            RequestInputMode::None
+                        };
+                        self.update_payment_request(app_state);
+                    },
+                    c if c.is_numeric() => self.amount_field.push(c),
+                    _ => {},
+                }
+                return;
+            },
+            RequestInputMode::Note => {
+                match c {
+                    '\n' => {
+                        self.input_mode = RequestInputMode::None;
+                        self.update_payment_request(app_state);
+                    },
+                    c => self.note_field.push(c),
+                }
+                return;
+            },
+        }
+        match c {
+            'a' => self.input_mode = RequestInputMode::Amount,
+            'n' => self.input_mode = RequestInputMode::Note,
+            'y' => self.copy("Public key", app_state.get_identity().public_key.clone()),
+            'e' => self.copy("Emoji ID", app_state.get_identity().emoji_id.clone()),
+            'u' => {
+                let uri = self.payment_uri(app_state);
+                self.copy("Payment URI", uri);
+            },
+            _ => {},
+        }
+    }
 
     fn on_up(&mut self, _app_state: &mut AppState) {}
 
     fn on_down(&mut self, _app_state: &mut AppState) {}
 
-    fn on_esc(&mut self, _: &mut AppState) {}
+    fn on_esc(&mut self, _: &mut AppState) {
+        self.input_mode = RequestInputMode::None;
+        self.amount_field = "".to_string();
+        self.note_field = "".to_string();
+        self.payment_request = None;
+    }
+
+    fn on_backspace(&mut self, _app_state: &mut AppState) {
+        match self.input_mode {
+            RequestInputMode::None => (),
+            RequestInputMode::Amount => {
+                let _ = self.amount_field.pop();
+            },
+            RequestInputMode::Note => {
+                let _ = self.note_field.pop();
+            },
+        }
+    }
+}
 
-    fn on_backspace(&mut self, _app_state: &mut AppState) {}
+#[derive(PartialEq, Debug)]
+enum RequestInputMode {
+    None,
+    Amount,
+    Note,
 }
@@ -0,0 +1,291 @@
+use crate::ui::Theme;
+use std::time::{Duration, Instant};
+use tari_core::transactions::tari_amount::MicroTari;
+use tari_wallet::tasks::wallet_recovery::WalletRecoveryEvent;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Gauge, Paragraph, Wrap},
+    Frame,
+};
+
+/// The number of connection and sync errors that are listed on the screen
+const MAX_ERRORS_SHOWN: usize = 5;
+
+/// Full screen view of a wallet recovery in progress, driven by the events of the recovery task
+pub struct RecoveryProgress {
+    started: Instant,
+    status: String,
+    scanning_to_height: Option<u64>,
+    /// (current, total) utxo of the current recovery round
+    utxo_progress: Option<(u64, u64)>,
+    /// When, and at which utxo, the current recovery round started reporting progress
+    round_start: Option<(Instant, u64)>,
+    num_recovered: u64,
+    value_recovered: MicroTari,
+    errors: Vec<String>,
+    complete: bool,
+    theme: Theme,
+}
+
+impl RecoveryProgress {
+    pub fn new(theme: Theme) -> Self {
+        Self {
+            started: Instant::now(),
+            status: "Starting recovery".to_string(),
+            scanning_to_height: None,
+            utxo_progress: None,
+            round_start: None,
+            num_recovered: 0,
+            value_recovered: MicroTari::from(0),
+            errors: Vec::new(),
+            complete: false,
+            theme,
+        }
+    }
+
+    pub fn on_event(&mut self, event: WalletRecoveryEvent) {
+        use WalletRecoveryEvent::*;
+        match event {
+            ConnectingToBaseNode(peer) => {
+                self.status = format!("Connecting to base node {}", peer);
+            },
+            ConnectedToBaseNode(peer, latency) => {
+                self.status = format!("Connected to base node {} (latency = {:.2?})", peer, latency);
+            },
+            ConnectionFailedToBaseNode {
+                peer,
+                num_retries,
+                retry_limit,
+                error,
+            } => {
+                self.push_error(format!(
+                    "Base node connection error to {} (retries {} of {}: {})",
+                    peer, num_retries, retry_limit, error
+                ));
+            },
+            RecoveryRoundFailed {
+                num_retries,
+                retry_limit,
+            } => {
+                self.push_error(format!("Failed to sync. Attempt {} of {}", num_retries, retry_limit));
+            },
+            ResumingFromCheckpoint { height, utxo_index } => {
+                self.status = format!("Resuming the recovery from UTXO #{} (height {})", utxo_index, height);
+            },
+            ScanningToHeight(height) => {
+                self.scanning_to_height = Some(height);
+                self.round_start = None;
+            },
+            Progress(current, total) => {
+                if self.round_start.is_none() {
+                    self.round_start = Some((Instant::now(), current));
+                }
+                self.utxo_progress = Some((current, total));
+            },
+            OutputsRecovered(num_utxos, total_amount) => {
+                self.num_recovered = num_utxos;
+                self.value_recovered = total_amount;
+            },
+            Completed(num_scanned, num_utxos, total_amount, elapsed) => {
+                self.status = format!(
+                    "Recovery complete! Scanned {} utxos in {}. Press any key to continue.",
+                    num_scanned,
+                    format_duration(elapsed)
+                );
+                self.num_recovered = num_utxos;
+                self.value_recovered = total_amount;
+                self.complete = true;
+            },
+        }
+    }
+
+    fn push_error(&mut self, error: String) {
+        self.errors.push(error);
+        if self.errors.len() > MAX_ERRORS_SHOWN {
+            self.errors.remove(0);
+        }
+    }
+
+    /// The estimated time until the current recovery round completes, from how fast it has progressed so far
+    fn eta(&self) -> Option<Duration> {
+        let (start, start_utxo) = self.round_start?;
+        let (current, total) = self.utxo_progress?;
+        estimate_remaining(
+            start.elapsed(),
+            current.saturating_sub(start_utxo),
+            total.saturating_sub(current),
+        )
+    }
+
+    pub fn draw<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+        let theme = &self.theme;
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Wallet Recovery", theme.title));
+        f.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .constraints(
+                [
+                    Constraint::Length(2),
+                    Constraint::Length(3),
+                    Constraint::Length(7),
+                    Constraint::Min(1),
+                    Constraint::Length(1),
+                ]
+                .as_ref(),
+            )
+            .margin(1)
+            .split(area);
+
+        let status_style = if self.complete {
+            theme.success.add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().add_modifier(Modifier::BOLD)
+        };
+        let status = Paragraph::new(Span::styled(self.status.as_str(), status_style)).wrap(Wrap { trim: true });
+        f.render_widget(status, chunks[0]);
+
+        let (ratio, label) = match self.utxo_progress {
+            Some((current, total)) if total > 0 => {
+                let ratio = (current as f64 / total as f64).min(1.0);
+                (ratio, format!("{:.1}% ({} of {} utxos)", ratio * 100.0, current, total))
+            },
+            _ => (0.0, "Waiting for the base node".to_string()),
+        };
+        let gauge = Gauge::default()
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(theme.border)
+                    .title("Progress"),
+            )
+            .gauge_style(theme.success)
+            .ratio(ratio)
+            .label(label);
+        f.render_widget(gauge, chunks[1]);
+
+        let height = self
+            .scanning_to_height
+            .map(|h| h.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        let eta = if self.complete {
+            "-".to_string()
+        } else {
+            self.eta().map(format_duration).unwrap_or_else(|| "-".to_string())
+        };
+        let details = vec![
+            detail_line("Scanning to height: ", height),
+            detail_line("UTXOs recovered:    ", self.num_recovered.to_string()),
+            detail_line("Value recovered:    ", self.value_recovered.to_string()),
+            detail_line("Elapsed:            ", format_duration(self.started.elapsed())),
+            detail_line("Time remaining:     ", eta),
+        ];
+        let details = Paragraph::new(details).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.border)
+                .title("Details"),
+        );
+        f.render_widget(details, chunks[2]);
+
+        let errors = self
+            .errors
+            .iter()
+            .map(|e| Spans::from(Span::styled(e.as_str(), theme.error)))
+            .collect::<Vec<_>>();
+        let errors = Paragraph::new(errors)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(theme.border)
+                    .title("Errors"),
+            )
+            .wrap(Wrap { trim: true });
+        f.render_widget(errors, chunks[3]);
+
+        let help = Paragraph::new(Spans::from(vec![
+            Span::styled("Ctrl-C", theme.key),
+            Span::raw(": Stop the recovery. Restarting the wallet continues the recovery from where it stopped."),
+        ]));
+        f.render_widget(help, chunks[4]);
+    }
+}
+
+fn detail_line(label: &'static str, value: String) -> Spans<'static> {
+    Spans::from(vec![
+        Span::styled(label, Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(value),
+    ])
+}
+
+/// Extrapolate the time it takes to process `remaining` items after `done` items took `elapsed`
+fn estimate_remaining(elapsed: Duration, done: u64, remaining: u64) -> Option<Duration> {
+    if done == 0 {
+        return None;
+    }
+    Some(elapsed.mul_f64(remaining as f64 / done as f64))
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{:02}:{:02}:{:02}", secs / 3600, (secs / 60) % 60, secs % 60)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn remaining_time_is_extrapolated_from_the_rate_so_far() {
+        assert_eq!(estimate_remaining(Duration::from_secs(10), 0, 100), None);
+        assert_eq!(
+            estimate_remaining(Duration::from_secs(10), 50, 100),
+            Some(Duration::from_secs(20))
+        );
+        assert_eq!(
+            estimate_remaining(Duration::from_secs(10), 50, 0),
+            Some(Duration::from_secs(0))
+        );
+    }
+
+    #[test]
+    fn durations_are_shown_as_hours_minutes_and_seconds() {
+        assert_eq!(format_duration(Duration::from_secs(0)), "00:00:00");
+        assert_eq!(format_duration(Duration::from_millis(61_500)), "00:01:01");
+        assert_eq!(format_duration(Duration::from_secs(36 * 3600 + 5)), "36:00:05");
+    }
+
+    #[test]
+    fn events_update_the_totals() {
+        let mut progress = RecoveryProgress::new(Theme::default());
+        progress.on_event(WalletRecoveryEvent::ResumingFromCheckpoint {
+            height: 400,
+            utxo_index: 10,
+        });
+        assert_eq!(progress.status, "Resuming the recovery from UTXO #10 (height 400)");
+        progress.on_event(WalletRecoveryEvent::ScanningToHeight(1000));
+        progress.on_event(WalletRecoveryEvent::Progress(10, 500));
+        progress.on_event(WalletRecoveryEvent::OutputsRecovered(2, MicroTari::from(300)));
+        progress.on_event(WalletRecoveryEvent::Progress(20, 500));
+        assert_eq!(progress.scanning_to_height, Some(1000));
+        assert_eq!(progress.utxo_progress, Some((20, 500)));
+        assert_eq!(progress.round_start.map(|(_, utxo)| utxo), Some(10));
+        assert_eq!(progress.num_recovered, 2);
+        assert!(!progress.complete);
+
+        progress.on_event(WalletRecoveryEvent::Completed(
+            490,
+            3,
+            MicroTari::from(400),
+            Duration::from_secs(5),
+        ));
+        assert_eq!(progress.num_recovered, 3);
+        assert_eq!(progress.value_recovered, MicroTari::from(400));
+        assert!(progress.complete);
+    }
+}
@@ -0,0 +1,541 @@
+use crate::{
+    ui::{
+        components::{Component, KeyHandled},
+        state::AppState,
+        widgets::{centered_rect_absolute, draw_dialog, MultiColumnList, WindowedListState},
+        Theme,
+    },
+    utils::formatting::display_compressed_string,
+};
+use std::time::{Duration, Instant};
+use tari_crypto::tari_utilities::hex::Hex;
+use tari_wallet::{storage::database::ScheduledPayment, types::DEFAULT_FEE_PER_GRAM};
+use tokio::runtime::Handle;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, ListItem, Paragraph, Wrap},
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+/// How often the scheduled payments are fetched, so that the next payment times follow the submitted payments
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+const HOUR_SECS: u64 = 60 * 60;
+const DAY_SECS: u64 = 24 * HOUR_SECS;
+const WEEK_SECS: u64 = 7 * DAY_SECS;
+
+pub struct ScheduledPaymentsTab {
+    payments: Vec<ScheduledPayment>,
+    last_refresh: Option<Instant>,
+    payments_list_state: WindowedListState,
+    show_add_payment: bool,
+    input_mode: ScheduleInputMode,
+    to_field: String,
+    amount_field: String,
+    fee_field: String,
+    interval_field: String,
+    message_field: String,
+    confirm_delete: bool,
+    error_message: Option<String>,
+}
+
+impl ScheduledPaymentsTab {
+    pub fn new() -> Self {
+        Self {
+            payments: Vec::new(),
+            last_refresh: None,
+            payments_list_state: WindowedListState::new(),
+            show_add_payment: false,
+            input_mode: ScheduleInputMode::None,
+            to_field: "".to_string(),
+            amount_field: "".to_string(),
+            fee_field: u64::from(DEFAULT_FEE_PER_GRAM).to_string(),
+            interval_field: "".to_string(),
+            message_field: "".to_string(),
+            confirm_delete: false,
+            error_message: None,
+        }
+    }
+
+    fn draw_payments<B>(&mut self, f: &mut Frame<B>, area: Rect, theme: &Theme)
+    where B: Backend {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Scheduled Payments", theme.title));
+        f.render_widget(block, area);
+        let list_areas = Layout::default()
+            .constraints([Constraint::Length(2), Constraint::Min(4)].as_ref())
+            .margin(1)
+            .split(area);
+
+        let instructions = Paragraph::new(Spans::from(vec![
+            Span::raw(" Use "),
+            Span::styled("Up/Down Arrow Keys", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to choose a payment, "),
+            Span::styled("N", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to schedule a (n)ew recurring payment and "),
+            Span::styled("D", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(
+                " to (d)elete a payment. Due payments are sent while the wallet is online, payments that were missed \
+                 while it was offline are skipped.",
+            ),
+        ]))
+        .wrap(Wrap { trim: true });
+        f.render_widget(instructions, list_areas[0]);
+
+        self.payments_list_state.set_num_items(self.payments.len());
+        let mut list_state = self
+            .payments_list_state
+            .get_list_state((list_areas[1].height as usize).saturating_sub(1));
+        let (start, end) = self.payments_list_state.get_start_end();
+        let windowed_view = self.payments.get(start..end).unwrap_or_default();
+
+        let mut column0_items = Vec::with_capacity(windowed_view.len());
+        let mut column1_items = Vec::with_capacity(windowed_view.len());
+        let mut column2_items = Vec::with_capacity(windowed_view.len());
+        let mut column3_items = Vec::with_capacity(windowed_view.len());
+        let mut column4_items = Vec::with_capacity(windowed_view.len());
+        let mut column5_items = Vec::with_capacity(windowed_view.len());
+        for p in windowed_view.iter() {
+            column0_items.push(ListItem::new(Span::raw(display_compressed_string(
+                p.destination_public_key.to_hex(),
+                8,
+                8,
+            ))));
+            column1_items.push(ListItem::new(Span::styled(format!("{}", p.amount), theme.success)));
+            column2_items.push(ListItem::new(Span::raw(format_interval(p.interval))));
+            column3_items.push(ListItem::new(Span::raw(format!(
+                "{}",
+                p.next_payment.format("%Y-%m-%d %H:%M:%S")
+            ))));
+            column4_items.push(ListItem::new(Span::raw(
+                p.last_tx_id.map(|id| id.to_string()).unwrap_or_else(|| "-".to_string()),
+            )));
+            column5_items.push(ListItem::new(Span::raw(p.message.clone())));
+        }
+        let column_list = MultiColumnList::new()
+            .highlight_style(theme.highlight)
+            .heading_style(theme.label)
+            .max_width(list_areas[1].width)
+            .add_column(Some("Recipient"), Some(20), column0_items)
+            .add_column(Some("Amount"), Some(18), column1_items)
+            .add_column(Some("Every"), Some(10), column2_items)
+            .add_column(Some("Next Payment"), Some(21), column3_items)
+            .add_column(Some("Last TxID"), Some(22), column4_items)
+            .add_column(Some("Message"), None, column5_items);
+        column_list.render(f, list_areas[1], &mut list_state);
+    }
+
+    fn draw_add_payment<B>(&self, f: &mut Frame<B>, area: Rect, theme: &Theme)
+    where B: Backend {
+        let popup_area = centered_rect_absolute(120, 13, area);
+
+        f.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Schedule a Recurring Payment", theme.title));
+        f.render_widget(block, popup_area);
+        let vert_chunks = Layout::default()
+            .constraints(
+                [
+                    Constraint::Length(2),
+                    Constraint::Length(3),
+                    Constraint::Length(3),
+                    Constraint::Length(3),
+                ]
+                .as_ref(),
+            )
+            .margin(1)
+            .split(popup_area);
+
+        let instructions = Paragraph::new(Spans::from(vec![
+            Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" moves to the next field and schedules the payment from the last one, "),
+            Span::styled("Esc", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to cancel. The interval is a number of hours (h), days (d) or weeks (w), e.g. 2w."),
+        ]))
+        .wrap(Wrap { trim: true });
+        f.render_widget(instructions, vert_chunks[0]);
+
+        let amount_layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(
+                [
+                    Constraint::Percentage(40),
+                    Constraint::Percentage(30),
+                    Constraint::Percentage(30),
+                ]
+                .as_ref(),
+            )
+            .split(vert_chunks[2]);
+
+        let fields = [
+            (ScheduleInputMode::To, "To (Public Key or Emoji ID):", vert_chunks[1]),
+            (ScheduleInputMode::Amount, "Amount (uT):", amount_layout[0]),
+            (ScheduleInputMode::Fee, "Fee-per-gram (uT):", amount_layout[1]),
+            (ScheduleInputMode::Interval, "Every:", amount_layout[2]),
+            (ScheduleInputMode::Message, "Message:", vert_chunks[3]),
+        ];
+        for (mode, title, field_area) in fields.iter() {
+            let input = Paragraph::new(self.field(mode).unwrap_or_default())
+                .style(if *mode == self.input_mode {
+                    theme.label
+                } else {
+                    Style::default()
+                })
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(theme.border)
+                        .title(*title),
+                );
+            f.render_widget(input, *field_area);
+            if *mode == self.input_mode {
+                f.set_cursor(
+                    // Put cursor past the end of the input text
+                    field_area.x + self.field(mode).unwrap_or_default().width() as u16 + 1,
+                    // Move one line down, from the border to the input line
+                    field_area.y + 1,
+                );
+            }
+        }
+    }
+
+    fn field(&self, mode: &ScheduleInputMode) -> Option<&str> {
+        match mode {
+            ScheduleInputMode::None => None,
+            ScheduleInputMode::To => Some(&self.to_field),
+            ScheduleInputMode::Amount => Some(&self.amount_field),
+            ScheduleInputMode::Fee => Some(&self.fee_field),
+            ScheduleInputMode::Interval => Some(&self.interval_field),
+            ScheduleInputMode::Message => Some(&self.message_field),
+        }
+    }
+
+    fn field_mut(&mut self) -> Option<&mut String> {
+        match self.input_mode {
+            ScheduleInputMode::None => None,
+            ScheduleInputMode::To => Some(&mut self.to_field),
+            ScheduleInputMode::Amount => Some(&mut self.amount_field),
+            ScheduleInputMode::Fee => Some(&mut self.fee_field),
+            ScheduleInputMode::Interval => Some(&mut self.interval_field),
+            ScheduleInputMode::Message => Some(&mut self.message_field),
+        }
+    }
+
+    fn open_add_payment(&mut self) {
+        self.show_add_payment = true;
+        self.input_mode = ScheduleInputMode::To;
+    }
+
+    fn close_add_payment(&mut self) {
+        self.show_add_payment = false;
+        self.input_mode = ScheduleInputMode::None;
+        self.to_field = "".to_string();
+        self.amount_field = "".to_string();
+        self.fee_field = u64::from(DEFAULT_FEE_PER_GRAM).to_string();
+        self.interval_field = "".to_string();
+        self.message_field = "".to_string();
+    }
+
+    fn add_payment(&mut self, app_state: &mut AppState) {
+        let amount = match self.amount_field.parse::<u64>() {
+            Ok(a) => a,
+            Err(_) => {
+                self.error_message = Some("Amount should be an integer\nPress Enter to continue.".to_string());
+                return;
+            },
+        };
+        let fee_per_gram = match self.fee_field.parse::<u64>() {
+            Ok(f) => f,
+            Err(_) => {
+                self.error_message = Some("Fee-per-gram should be an integer\nPress Enter to continue.".to_string());
+                return;
+            },
+        };
+        let interval = match parse_interval(&self.interval_field) {
+            Some(i) => i,
+            None => {
+                self.error_message = Some(
+                    "The interval should be a number of hours, days or weeks, e.g. 12h, 30d or 2w\nPress Enter to \
+                     continue."
+                        .to_string(),
+                );
+                return;
+            },
+        };
+
+        match Handle::current().block_on(app_state.add_scheduled_payment(
+            self.to_field.clone(),
+            amount,
+            fee_per_gram,
+            self.message_field.clone(),
+            interval,
+        )) {
+            Ok(_) => {
+                self.close_add_payment();
+                self.refresh(app_state);
+            },
+            Err(e) => {
+                self.error_message = Some(format!(
+                    "Could not schedule the payment:\n{}\nPress Enter to continue.",
+                    e
+                ))
+            },
+        }
+    }
+
+    fn refresh(&mut self, app_state: &AppState) {
+        self.last_refresh = Some(Instant::now());
+        match Handle::current().block_on(app_state.get_scheduled_payments()) {
+            Ok(payments) => self.payments = payments,
+            Err(e) => {
+                self.error_message = Some(format!(
+                    "Could not fetch the scheduled payments:\n{}\nPress Enter to continue.",
+                    e
+                ))
+            },
+        }
+    }
+
+    fn on_key_confirm_delete(&mut self, c: char, app_state: &mut AppState) -> KeyHandled {
+        if !self.confirm_delete {
+            return KeyHandled::NotHandled;
+        }
+        match c {
+            'y' => {
+                if let Some(id) = self
+                    .payments_list_state
+                    .selected()
+                    .and_then(|i| self.payments.get(i))
+                    .map(|p| p.id)
+                {
+                    if let Err(e) = Handle::current().block_on(app_state.remove_scheduled_payment(id)) {
+                        self.error_message = Some(format!(
+                            "Could not delete the scheduled payment:\n{}\nPress Enter to continue.",
+                            e
+                        ));
+                    }
+                    self.payments_list_state.select(None);
+                    self.refresh(app_state);
+                }
+                self.confirm_delete = false;
+            },
+            'n' => self.confirm_delete = false,
+            _ => (),
+        }
+        KeyHandled::Handled
+    }
+
+    fn on_key_add_payment(&mut self, c: char, app_state: &mut AppState) -> KeyHandled {
+        if !self.show_add_payment {
+            return KeyHandled::NotHandled;
+        }
+        match c {
+            '\n' => match self.input_mode.next() {
+                Some(mode) => self.input_mode = mode,
+                None => self.add_payment(app_state),
+            },
+            c => {
+                if let Some(field) = self.field_mut() {
+                    field.push(c);
+                }
+            },
+        }
+        KeyHandled::Handled
+    }
+}
+
+impl<B: Backend> Component<B> for ScheduledPaymentsTab {
+    fn draw(&mut self, f: &mut Frame<B>, area: Rect, app_state: &AppState) {
+        self.draw_payments(f, area, app_state.get_theme());
+        if self.show_add_payment {
+            self.draw_add_payment(f, area, app_state.get_theme());
+        }
+
+        if let Some(msg) = self.error_message.clone() {
+            draw_dialog(f, area, "Error!".to_string(), msg, app_state.get_theme().error, 120, 9);
+        }
+
+        if self.confirm_delete {
+            draw_dialog(
+                f,
+                area,
+                "Confirm Delete".to_string(),
+                "Are you sure you want to stop this recurring payment?\n(Y)es / (N)o".to_string(),
+                app_state.get_theme().error,
+                120,
+                9,
+            );
+        }
+    }
+
+    fn is_editing(&self) -> bool {
+        self.show_add_payment
+    }
+
+    fn key_bindings(&self) -> Vec<(&'static str, &'static str)> {
+        if self.confirm_delete {
+            return vec![
+                ("Y", "Delete the scheduled payment"),
+                ("N", "Keep the scheduled payment"),
+            ];
+        }
+        if self.show_add_payment {
+            return vec![
+                ("Enter", "Next field, or schedule the payment from the last field"),
+                ("Esc", "Cancel"),
+            ];
+        }
+        vec![
+            ("Up/Down Arrow", "Select a scheduled payment"),
+            ("N", "Schedule a new recurring payment"),
+            ("D", "Delete the selected scheduled payment"),
+            ("Esc", "Clear the selection"),
+        ]
+    }
+
+    fn on_key(&mut self, app_state: &mut AppState, c: char) {
+        if self.error_message.is_some() {
+            if '\n' == c {
+                self.error_message = None;
+            }
+            return;
+        }
+
+        if self.on_key_confirm_delete(c, app_state) == KeyHandled::Handled {
+            return;
+        }
+
+        if self.on_key_add_payment(c, app_state) == KeyHandled::Handled {
+            return;
+        }
+
+        match c {
+            'n' => self.open_add_payment(),
+            'd' => {
+                if self.payments_list_state.selected().is_some() {
+                    self.confirm_delete = true;
+                }
+            },
+            _ => (),
+        }
+    }
+
+    fn on_up(&mut self, _app_state: &mut AppState) {
+        self.payments_list_state.set_num_items(self.payments.len());
+        self.payments_list_state.previous();
+    }
+
+    fn on_down(&mut self, _app_state: &mut AppState) {
+        self.payments_list_state.set_num_items(self.payments.len());
+        self.payments_list_state.next();
+    }
+
+    fn on_esc(&mut self, _app_state: &mut AppState) {
+        if self.show_add_payment {
+            self.close_add_payment();
+        } else {
+            self.payments_list_state.select(None);
+        }
+    }
+
+    fn on_backspace(&mut self, _app_state: &mut AppState) {
+        if let Some(field) = self.field_mut() {
+            let _ = field.pop();
+        }
+    }
+
+    fn on_tick(&mut self, app_state: &mut AppState) {
+        if self.show_add_payment || self.error_message.is_some() {
+            return;
+        }
+        if self
+            .last_refresh
+            .map(|t| t.elapsed() >= REFRESH_INTERVAL)
+            .unwrap_or(true)
+        {
+            self.refresh(app_state);
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub enum ScheduleInputMode {
+    None,
+    To,
+    Amount,
+    Fee,
+    Interval,
+    Message,
+}
+
+impl ScheduleInputMode {
+    /// The field that follows this one in the form, or None after the last field
+    fn next(&self) -> Option<Self> {
+        match self {
+            Self::None => Some(Self::To),
+            Self::To => Some(Self::Amount),
+            Self::Amount => Some(Self::Fee),
+            Self::Fee => Some(Self::Interval),
+            Self::Interval => Some(Self::Message),
+            Self::Message => None,
+        }
+    }
+}
+
+/// Parse an interval such as `12h`, `30d` or `2w`. A number without a unit is a number of days.
+fn parse_interval(s: &str) -> Option<Duration> {
+    let s = s.trim().to_lowercase();
+    let (number, unit_secs) = match s.chars().last()? {
+        'h' => (&s[..s.len() - 1], HOUR_SECS),
+        'd' => (&s[..s.len() - 1], DAY_SECS),
+        'w' => (&s[..s.len() - 1], WEEK_SECS),
+        _ => (s.as_str(), DAY_SECS),
+    };
+    match number.trim().parse::<u64>() {
+        Ok(n) if n > 0 => n.checked_mul(unit_secs).map(Duration::from_secs),
+        _ => None,
+    }
+}
+
+/// The interval in the largest unit that it is a whole number of
+fn format_interval(interval: Duration) -> String {
+    let secs = interval.as_secs();
+    if secs % WEEK_SECS == 0 {
+        format!("{}w", secs / WEEK_SECS)
+    } else if secs % DAY_SECS == 0 {
+        format!("{}d", secs / DAY_SECS)
+    } else if secs % HOUR_SECS == 0 {
+        format!("{}h", secs / HOUR_SECS)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn intervals_are_parsed_and_formatted() {
+        assert_eq!(parse_interval("12h"), Some(Duration::from_secs(12 * HOUR_SECS)));
+        assert_eq!(parse_interval(" 30D "), Some(Duration::from_secs(30 * DAY_SECS)));
+        assert_eq!(parse_interval("2w"), Some(Duration::from_secs(2 * WEEK_SECS)));
+        assert_eq!(parse_interval("7"), Some(Duration::from_secs(7 * DAY_SECS)));
+        assert_eq!(parse_interval("0d"), None);
+        assert_eq!(parse_interval("w"), None);
+        assert_eq!(parse_interval("monthly"), None);
+
+        assert_eq!(format_interval(Duration::from_secs(2 * WEEK_SECS)), "2w");
+        assert_eq!(format_interval(Duration::from_secs(10 * DAY_SECS)), "10d");
+        assert_eq!(format_interval(Duration::from_secs(36 * HOUR_SECS)), "36h");
+    }
+}
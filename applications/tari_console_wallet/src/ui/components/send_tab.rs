@@ -1,18 +1,25 @@
 use crate::{
     ui::{
-        components::{balance::Balance, Component, KeyHandled},
-        state::{AppState, UiTransactionSendStatus},
+        components::{balance::Balance, contacts::Contacts, qrcode::parse_payment_uri, Component, KeyHandled},
+        state::{AppState, UiOutput, UiTransactionSendStatus},
         widgets::{centered_rect_absolute, draw_dialog, MultiColumnList, WindowedListState},
-        MAX_WIDTH,
+        Theme,
+        UiContact,
     },
-    utils::formatting::display_compressed_string,
+    utils::{clipboard::copy_to_clipboard, formatting::recipient_preview},
+};
+use tari_core::transactions::tari_amount::MicroTari;
+use tari_crypto::tari_utilities::hex::Hex;
+use tari_wallet::{
+    contacts_service::handle::ContactOnlineStatus,
+    output_manager_service::service::{suggested_fee_per_gram, FeeEstimate},
+    types::DEFAULT_FEE_PER_GRAM,
 };
-use tari_wallet::types::DEFAULT_FEE_PER_GRAM;
 use tokio::{runtime::Handle, sync::watch};
 use tui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Span, Spans},
     widgets::{Block, Borders, Clear, ListItem, Paragraph, Wrap},
     Frame,
@@ -22,20 +29,32 @@ use unicode_width::UnicodeWidthStr;
 pub struct SendTab {
     balance: Balance,
     send_input_mode: SendInputMode,
-    edit_contact_mode: ContactInputMode,
+    contacts: Contacts,
     show_contacts: bool,
-    show_edit_contact: bool,
     to_field: String,
     amount_field: String,
+    batch_recipients: Vec<(String, u64)>,
     fee_field: String,
     message_field: String,
-    alias_field: String,
-    public_key_field: String,
     error_message: Option<String>,
     success_message: Option<String>,
-    contacts_list_state: WindowedListState,
     send_result_watch: Option<watch::Receiver<UiTransactionSendStatus>>,
     confirmation_dialog: Option<ConfirmationDialogType>,
+    show_coin_split: bool,
+    coin_split_input_mode: CoinSplitInputMode,
+    split_amount_field: String,
+    split_count_field: String,
+    split_fee_field: String,
+    show_utxos: bool,
+    utxos: Vec<UiOutput>,
+    utxo_list_state: WindowedListState,
+    /// The outputs that the next transaction spends, instead of the ones the wallet would select
+    pinned_outputs: Vec<UiOutput>,
+    /// Whether the next transaction is built by this wallet alone, without waiting for the recipient's wallet
+    one_sided: bool,
+    /// The fee estimate of the transaction in the form, and the total amount, number of outputs and fee field it was
+    /// estimated for
+    fee_estimate: Option<(u64, usize, String, Result<FeeEstimate, String>)>,
 }
 
 impl SendTab {
@@ -43,36 +62,52 @@ impl SendTab {
         Self {
             balance: Balance::new(),
             send_input_mode: SendInputMode::None,
-            edit_contact_mode: ContactInputMode::None,
+            contacts: Contacts::new(),
             show_contacts: false,
-            show_edit_contact: false,
             to_field: "".to_string(),
             amount_field: "".to_string(),
+            batch_recipients: Vec::new(),
             fee_field: u64::from(DEFAULT_FEE_PER_GRAM).to_string(),
             message_field: "".to_string(),
-            alias_field: "".to_string(),
-            public_key_field: "".to_string(),
             error_message: None,
             success_message: None,
-            contacts_list_state: WindowedListState::new(),
             send_result_watch: None,
             confirmation_dialog: None,
+            show_coin_split: false,
+            coin_split_input_mode: CoinSplitInputMode::None,
+            split_amount_field: "".to_string(),
+            split_count_field: "".to_string(),
+            split_fee_field: u64::from(DEFAULT_FEE_PER_GRAM).to_string(),
+            show_utxos: false,
+            utxos: Vec::new(),
+            utxo_list_state: WindowedListState::new(),
+            pinned_outputs: Vec::new(),
+            one_sided: false,
+            fee_estimate: None,
         }
     }
 
-    fn draw_send_form<B>(&self, f: &mut Frame<B>, area: Rect, _app_state: &AppState)
+    fn draw_send_form<B>(&self, f: &mut Frame<B>, area: Rect, app_state: &AppState)
     where B: Backend {
-        let block = Block::default().borders(Borders::ALL).title(Span::styled(
-            "Send Transaction",
-            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
-        ));
+        let theme = app_state.get_theme();
+        let title = if self.one_sided {
+            "Send One-sided Transaction"
+        } else {
+            "Send Transaction"
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled(title, theme.title));
         f.render_widget(block, area);
         let vert_chunks = Layout::default()
             .constraints(
                 [
                     Constraint::Length(2),
                     Constraint::Length(3),
+                    Constraint::Length(1),
                     Constraint::Length(3),
+                    Constraint::Length(1),
                     Constraint::Length(3),
                 ]
                 .as_ref(),
@@ -96,51 +131,102 @@ impl SendTab {
             Span::styled("C", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw(" to select a contact, "),
             Span::styled("S", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw(" to send transaction."),
+            Span::raw(" to send transaction, "),
+            Span::styled("R", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to add another (r)ecipient, "),
+            Span::styled("P", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to s(p)lit coins, "),
+            Span::styled("U", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to pick the (u)nspent outputs to spend, "),
+            Span::styled("O", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to toggle a (o)ne-sided payment to an offline recipient. "),
+            Span::styled("Ctrl-V", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" pastes into the field being edited."),
         ]))
+        .wrap(Wrap { trim: true })
         .block(Block::default());
         f.render_widget(instructions, vert_chunks[0]);
 
+        let to_title = if self.batch_recipients.is_empty() {
+            "(T)o (Public Key, Emoji ID or Payment URI) :".to_string()
+        } else {
+            format!(
+                "(T)o (Public Key or Emoji ID) - {} other recipient(s) added, Esc to clear :",
+                self.batch_recipients.len()
+            )
+        };
         let to_input = Paragraph::new(self.to_field.as_ref())
             .style(match self.send_input_mode {
-                SendInputMode::To => Style::default().fg(Color::Magenta),
+                SendInputMode::To => theme.label,
                 _ => Style::default(),
             })
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title("(T)o (Public Key or Emoji ID) :"),
+                    .border_style(theme.border)
+                    .title(to_title),
             );
         f.render_widget(to_input, vert_chunks[1]);
 
+        if !self.to_field.is_empty() {
+            let preview = if self.to_field.starts_with("tari://") {
+                parse_payment_uri(&self.to_field)
+                    .map(|_| "Payment request, press Enter to fill in the form from it".to_string())
+            } else {
+                recipient_preview(&self.to_field)
+            };
+            let preview = match preview {
+                Ok(preview) => Span::styled(preview, theme.success),
+                Err(problem) => Span::styled(problem, theme.error),
+            };
+            f.render_widget(Paragraph::new(preview), vert_chunks[2]);
+        }
+
         let amount_fee_layout = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
-            .split(vert_chunks[2]);
+            .split(vert_chunks[3]);
 
         let amount_input = Paragraph::new(self.amount_field.as_ref())
             .style(match self.send_input_mode {
-                SendInputMode::Amount => Style::default().fg(Color::Magenta),
+                SendInputMode::Amount => theme.label,
                 _ => Style::default(),
             })
-            .block(Block::default().borders(Borders::ALL).title("(A)mount (uT):"));
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(theme.border)
+                    .title(self.amount_title(app_state)),
+            );
         f.render_widget(amount_input, amount_fee_layout[0]);
 
         let fee_input = Paragraph::new(self.fee_field.as_ref())
             .style(match self.send_input_mode {
-                SendInputMode::Fee => Style::default().fg(Color::Magenta),
+                SendInputMode::Fee => theme.label,
                 _ => Style::default(),
             })
-            .block(Block::default().borders(Borders::ALL).title("(F)ee-per-gram (uT):"));
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(theme.border)
+                    .title("(F)ee-per-gram (uT):"),
+            );
         f.render_widget(fee_input, amount_fee_layout[1]);
 
+        f.render_widget(Paragraph::new(self.fee_estimate_line(app_state)), vert_chunks[4]);
+
         let message_input = Paragraph::new(self.message_field.as_ref())
             .style(match self.send_input_mode {
-                SendInputMode::Message => Style::default().fg(Color::Magenta),
+                SendInputMode::Message => theme.label,
                 _ => Style::default(),
             })
-            .block(Block::default().borders(Borders::ALL).title("(M)essage:"));
-        f.render_widget(message_input, vert_chunks[3]);
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(theme.border)
+                    .title("(M)essage:"),
+            );
+        f.render_widget(message_input, vert_chunks[5]);
 
         match self.send_input_mode {
             SendInputMode::None => (),
@@ -164,132 +250,405 @@ impl SendTab {
             ),
             SendInputMode::Message => f.set_cursor(
                 // Put cursor past the end of the input text
-                vert_chunks[3].x + self.message_field.width() as u16 + 1,
+                vert_chunks[5].x + self.message_field.width() as u16 + 1,
                 // Move one line down, from the border to the input line
-                vert_chunks[3].y + 1,
+                vert_chunks[5].y + 1,
             ),
         }
     }
 
-    fn draw_contacts<B>(&mut self, f: &mut Frame<B>, area: Rect, app_state: &AppState)
+    /// The total amount and number of outputs of the transaction in the form, including the batch recipients
+    fn form_amount_and_outputs(&self) -> (u64, usize) {
+        let (mut amount, mut num_outputs) = self
+            .batch_recipients
+            .iter()
+            .fold((0u64, 0), |(total, count), (_, amount)| (total + amount, count + 1));
+        if let Ok(v) = self.amount_field.parse::<u64>() {
+            amount += v;
+            num_outputs += 1;
+        }
+        (amount, num_outputs)
+    }
+
+    /// Re-estimate the fee if the amount, recipients or fee per gram in the form changed since the last estimate
+    fn refresh_fee_estimate(&mut self, app_state: &AppState) {
+        let (amount, num_outputs) = self.form_amount_and_outputs();
+        if amount == 0 {
+            self.fee_estimate = None;
+            return;
+        }
+        if let Some((a, n, fee, _)) = &self.fee_estimate {
+            if *a == amount && *n == num_outputs && *fee == self.fee_field {
+                return;
+            }
+        }
+        let fee_per_gram = self.fee_field.parse::<u64>().ok().map(MicroTari::from);
+        let estimate = Handle::current()
+            .block_on(app_state.estimate_fee(MicroTari::from(amount), num_outputs, fee_per_gram))
+            .map_err(|e| e.to_string());
+        self.fee_estimate = Some((amount, num_outputs, self.fee_field.clone(), estimate));
+    }
+
+    fn fee_estimate_line(&self, app_state: &AppState) -> Spans {
+        let theme = app_state.get_theme();
+        let suggested = match &self.fee_estimate {
+            Some((_, _, _, Ok(estimate))) => estimate.suggested_fee_per_gram,
+            _ => suggested_fee_per_gram(app_state.get_base_node_state().mempool_fee_stats.as_ref()),
+        };
+        let suggestion = Span::raw(format!("Suggested fee-per-gram: {} uT", u64::from(suggested)));
+        match &self.fee_estimate {
+            None => Spans::from(suggestion),
+            Some((_, _, _, Ok(estimate))) => Spans::from(vec![
+                Span::styled(
+                    format!(
+                        "Estimated fee: {} ({} input(s), {} output(s){}, weight {})",
+                        estimate.fee,
+                        estimate.num_inputs,
+                        estimate.num_outputs,
+                        if estimate.has_change_output {
+                            " incl. change"
+                        } else {
+                            ""
+                        },
+                        estimate.weight
+                    ),
+                    theme.success,
+                ),
+                Span::raw(" - "),
+                suggestion,
+            ]),
+            Some((_, _, _, Err(e))) => Spans::from(vec![
+                Span::styled(format!("Cannot estimate the fee: {}", e), theme.error),
+                Span::raw(" - "),
+                suggestion,
+            ]),
+        }
+    }
+
+    fn amount_title(&self, app_state: &AppState) -> String {
+        let title = if self.pinned_outputs.is_empty() {
+            "(A)mount (uT):".to_string()
+        } else {
+            format!(
+                "(A)mount (uT) - spending {} pinned output(s) of {} :",
+                self.pinned_outputs.len(),
+                self.pinned_total()
+            )
+        };
+        match (app_state.get_fiat_price(), self.amount_field.parse::<u64>()) {
+            (Some(price), Ok(amount)) => format!("{} {}", title, price.format_value(MicroTari::from(amount))),
+            _ => title,
+        }
+    }
+
+    fn pinned_total(&self) -> MicroTari {
+        self.pinned_outputs
+            .iter()
+            .fold(MicroTari::from(0), |total, o| total + o.value)
+    }
+
+    fn is_pinned(&self, output: &UiOutput) -> bool {
+        self.pinned_outputs.iter().any(|o| o.commitment == output.commitment)
+    }
+
+    fn draw_utxos<B>(&mut self, f: &mut Frame<B>, area: Rect, app_state: &AppState)
     where B: Backend {
-        let block = Block::default().borders(Borders::ALL).title(Span::styled(
-            "Contacts",
-            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
-        ));
+        let theme = app_state.get_theme();
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Unspent Outputs", theme.title));
         f.render_widget(block, area);
         let list_areas = Layout::default()
-            .constraints([Constraint::Length(1), Constraint::Min(42)].as_ref())
+            .constraints([Constraint::Length(2), Constraint::Min(42)].as_ref())
             .margin(1)
             .split(area);
 
         let instructions = Paragraph::new(Spans::from(vec![
             Span::raw(" Use "),
             Span::styled("Up/Down Arrow Keys", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw(" to choose a contact, "),
-            Span::styled("E", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw(" to (e)dit and "),
-            Span::styled("D", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw(" to (d)elete a contact, "),
-            Span::styled("N", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw(" to create a (n)ew contact, "),
+            Span::raw(" to choose an output and "),
+            Span::styled("Space", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" or "),
             Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw(" to select."),
+            Span::raw(" to pin it for the next transaction. "),
+            Span::raw(format!(
+                "{} output(s) of {} pinned.",
+                self.pinned_outputs.len(),
+                self.pinned_total()
+            )),
         ]))
         .wrap(Wrap { trim: true });
         f.render_widget(instructions, list_areas[0]);
-        self.contacts_list_state.set_num_items(app_state.get_contacts().len());
+
+        self.utxo_list_state.set_num_items(self.utxos.len());
         let mut list_state = self
-            .contacts_list_state
+            .utxo_list_state
             .get_list_state((list_areas[1].height as usize).saturating_sub(3));
-        let window = self.contacts_list_state.get_start_end();
-        let windowed_view = app_state.get_contacts_slice(window.0, window.1);
+        let (start, end) = self.utxo_list_state.get_start_end();
+        let tip_height = app_state
+            .get_base_node_state()
+            .chain_metadata
+            .as_ref()
+            .map(|m| m.height_of_longest_chain());
 
         let mut column0_items = Vec::new();
         let mut column1_items = Vec::new();
         let mut column2_items = Vec::new();
-        for c in windowed_view.iter() {
-            column0_items.push(ListItem::new(Span::raw(c.alias.clone())));
-            column1_items.push(ListItem::new(Span::raw(c.public_key.to_string())));
-            column2_items.push(ListItem::new(Span::raw(display_compressed_string(
-                c.emoji_id.clone(),
-                3,
-                3,
-            ))));
+        let mut column3_items = Vec::new();
+        for o in self.utxos.get(start..end).unwrap_or_default() {
+            let pinned = if self.is_pinned(o) { "[x]" } else { "[ ]" };
+            column0_items.push(ListItem::new(Span::raw(pinned)));
+            column1_items.push(ListItem::new(Span::raw(o.value.to_string())));
+            let maturity = match tip_height {
+                Some(height) if o.maturity > height => {
+                    ListItem::new(Span::styled(format!("{} (immature)", o.maturity), theme.error))
+                },
+                _ => ListItem::new(Span::raw(o.maturity.to_string())),
+            };
+            column2_items.push(maturity);
+            column3_items.push(ListItem::new(Span::raw(o.commitment.to_hex())));
         }
         let column_list = MultiColumnList::new()
-            .highlight_style(Style::default().add_modifier(Modifier::BOLD).fg(Color::Magenta))
-            .heading_style(Style::default().fg(Color::Magenta))
-            .max_width(MAX_WIDTH)
-            .add_column(Some("Alias"), Some(12), column0_items)
-            .add_column(Some("Public Key"), Some(67), column1_items)
-            .add_column(Some("Emoji ID"), None, column2_items);
+            .highlight_style(theme.highlight)
+            .heading_style(theme.label)
+            .max_width(list_areas[1].width)
+            .add_column(Some("Pinned"), Some(7), column0_items)
+            .add_column(Some("Value"), Some(22), column1_items)
+            .add_column(Some("Maturity"), Some(18), column2_items)
+            .add_column(Some("Commitment"), None, column3_items);
         column_list.render(f, list_areas[1], &mut list_state);
     }
 
-    fn draw_edit_contact<B>(&mut self, f: &mut Frame<B>, area: Rect, _app_state: &AppState)
+    /// Open the list of unspent outputs, unpinning any outputs that have been spent in the meantime
+    fn open_utxos(&mut self, app_state: &AppState) {
+        match Handle::current().block_on(app_state.get_unspent_outputs()) {
+            Ok(utxos) => {
+                self.pinned_outputs
+                    .retain(|p| utxos.iter().any(|o| o.commitment == p.commitment));
+                self.utxos = utxos;
+                self.utxo_list_state = WindowedListState::new();
+                self.show_contacts = false;
+                self.show_utxos = true;
+            },
+            Err(e) => {
+                self.error_message = Some(format!(
+                    "Could not load the unspent outputs:\n{}\nPress Enter to continue.",
+                    e
+                ))
+            },
+        }
+    }
+
+    fn on_key_show_utxos(&mut self, c: char) -> KeyHandled {
+        if self.show_utxos && (c == ' ' || c == '\n') {
+            if let Some(output) = self.utxo_list_state.selected().and_then(|i| self.utxos.get(i)).cloned() {
+                if self.is_pinned(&output) {
+                    self.pinned_outputs.retain(|o| o.commitment != output.commitment);
+                } else {
+                    self.pinned_outputs.push(output);
+                }
+            }
+            return KeyHandled::Handled;
+        }
+
+        KeyHandled::NotHandled
+    }
+
+    fn draw_coin_split<B>(&self, f: &mut Frame<B>, area: Rect, theme: &Theme)
     where B: Backend {
         let popup_area = centered_rect_absolute(120, 10, area);
 
         f.render_widget(Clear, popup_area);
 
-        let block = Block::default().borders(Borders::ALL).title(Span::styled(
-            "Add/Edit Contact",
-            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
-        ));
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Coin Split", theme.title));
         f.render_widget(block, popup_area);
         let vert_chunks = Layout::default()
-            .constraints([Constraint::Length(2), Constraint::Length(3), Constraint::Length(3)].as_ref())
+            .constraints([Constraint::Length(3), Constraint::Length(3)].as_ref())
             .margin(1)
             .split(popup_area);
 
         let instructions = Paragraph::new(Spans::from(vec![
-            Span::raw("Press "),
-            Span::styled("L", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("Split the unspent outputs of the wallet into smaller outputs. Press "),
+            Span::styled("A", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw(" to edit "),
-            Span::styled("Alias", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw(" field, "),
-            Span::styled("K", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled("Amount per Split", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(", "),
+            Span::styled("N", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw(" to edit "),
-            Span::styled("Public Key/Emoji ID", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw(" field, "),
-            Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw(" to save Contact."),
+            Span::styled("Number of Splits", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(", "),
+            Span::styled("F", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to edit "),
+            Span::styled("Fee-Per-Gram", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(", "),
+            Span::styled("S", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to split, "),
+            Span::styled("Esc", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to cancel."),
         ]))
-        .block(Block::default());
+        .wrap(Wrap { trim: true });
         f.render_widget(instructions, vert_chunks[0]);
 
-        let alias_input = Paragraph::new(self.alias_field.as_ref())
-            .style(match self.edit_contact_mode {
-                ContactInputMode::Alias => Style::default().fg(Color::Magenta),
+        let fields_layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(
+                [
+                    Constraint::Percentage(34),
+                    Constraint::Percentage(33),
+                    Constraint::Percentage(33),
+                ]
+                .as_ref(),
+            )
+            .split(vert_chunks[1]);
+
+        let amount_input = Paragraph::new(self.split_amount_field.as_ref())
+            .style(match self.coin_split_input_mode {
+                CoinSplitInputMode::Amount => theme.label,
                 _ => Style::default(),
             })
-            .block(Block::default().borders(Borders::ALL).title("A(l)ias:"));
-        f.render_widget(alias_input, vert_chunks[1]);
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(theme.border)
+                    .title("(A)mount per split (uT):"),
+            );
+        f.render_widget(amount_input, fields_layout[0]);
 
-        let pubkey_input = Paragraph::new(self.public_key_field.as_ref())
-            .style(match self.edit_contact_mode {
-                ContactInputMode::PubkeyEmojiId => Style::default().fg(Color::Magenta),
+        let count_input = Paragraph::new(self.split_count_field.as_ref())
+            .style(match self.coin_split_input_mode {
+                CoinSplitInputMode::Count => theme.label,
                 _ => Style::default(),
             })
-            .block(Block::default().borders(Borders::ALL).title("Public (K)ey / Emoji Id:"));
-        f.render_widget(pubkey_input, vert_chunks[2]);
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(theme.border)
+                    .title("(N)umber of splits:"),
+            );
+        f.render_widget(count_input, fields_layout[1]);
 
-        match self.edit_contact_mode {
-            ContactInputMode::None => (),
-            ContactInputMode::Alias => f.set_cursor(
-                // Put cursor past the end of the input text
-                vert_chunks[1].x + self.alias_field.width() as u16 + 1,
-                // Move one line down, from the border to the input line
-                vert_chunks[1].y + 1,
-            ),
-            ContactInputMode::PubkeyEmojiId => f.set_cursor(
-                // Put cursor past the end of the input text
-                vert_chunks[2].x + self.public_key_field.width() as u16 + 1,
-                // Move one line down, from the border to the input line
-                vert_chunks[2].y + 1,
-            ),
+        let fee_input = Paragraph::new(self.split_fee_field.as_ref())
+            .style(match self.coin_split_input_mode {
+                CoinSplitInputMode::Fee => theme.label,
+                _ => Style::default(),
+            })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(theme.border)
+                    .title("(F)ee-per-gram (uT):"),
+            );
+        f.render_widget(fee_input, fields_layout[2]);
+
+        let (field, chunk) = match self.coin_split_input_mode {
+            CoinSplitInputMode::None => return,
+            CoinSplitInputMode::Amount => (&self.split_amount_field, fields_layout[0]),
+            CoinSplitInputMode::Count => (&self.split_count_field, fields_layout[1]),
+            CoinSplitInputMode::Fee => (&self.split_fee_field, fields_layout[2]),
+        };
+        f.set_cursor(
+            // Put cursor past the end of the input text
+            chunk.x + field.width() as u16 + 1,
+            // Move one line down, from the border to the input line
+            chunk.y + 1,
+        );
+    }
+
+    fn close_coin_split(&mut self) {
+        self.show_coin_split = false;
+        self.coin_split_input_mode = CoinSplitInputMode::None;
+        self.split_amount_field = "".to_string();
+        self.split_count_field = "".to_string();
+        self.split_fee_field = u64::from(DEFAULT_FEE_PER_GRAM).to_string();
+    }
+
+    fn on_key_coin_split(&mut self, c: char) -> KeyHandled {
+        if !self.show_coin_split {
+            return KeyHandled::NotHandled;
+        }
+        match self.coin_split_input_mode {
+            CoinSplitInputMode::None => match c {
+                'a' => self.coin_split_input_mode = CoinSplitInputMode::Amount,
+                'n' => self.coin_split_input_mode = CoinSplitInputMode::Count,
+                'f' => self.coin_split_input_mode = CoinSplitInputMode::Fee,
+                's' => {
+                    if self.split_amount_field.parse::<u64>().is_err() ||
+                        self.split_count_field.parse::<usize>().is_err()
+                    {
+                        self.error_message = Some(
+                            "Amount per split and number of splits should be integers\nPress Enter to continue."
+                                .to_string(),
+                        );
+                    } else {
+                        self.confirmation_dialog = Some(ConfirmationDialogType::ConfirmCoinSplit);
+                    }
+                },
+                _ => (),
+            },
+            CoinSplitInputMode::Amount => match c {
+                '\n' => self.coin_split_input_mode = CoinSplitInputMode::Count,
+                c if c.is_numeric() => self.split_amount_field.push(c),
+                _ => (),
+            },
+            CoinSplitInputMode::Count => match c {
+                '\n' => self.coin_split_input_mode = CoinSplitInputMode::None,
+                c if c.is_numeric() => self.split_count_field.push(c),
+                _ => (),
+            },
+            CoinSplitInputMode::Fee => match c {
+                '\n' => self.coin_split_input_mode = CoinSplitInputMode::None,
+                c if c.is_numeric() => self.split_fee_field.push(c),
+                _ => (),
+            },
+        }
+        KeyHandled::Handled
+    }
+
+    /// Validate the To and Amount fields and move them to the list of batch recipients
+    /// Fill in the form from the payment URI in the To field, if there is one
+    fn apply_payment_uri(&mut self) -> Result<(), String> {
+        if !self.to_field.starts_with("tari://") {
+            return Ok(());
+        }
+        let request = parse_payment_uri(&self.to_field)?;
+        self.to_field = request.public_key.to_hex();
+        if let Some(amount) = request.amount {
+            self.amount_field = amount.0.to_string();
+        }
+        if let Some(note) = request.note {
+            self.message_field = note;
         }
+        Ok(())
+    }
+
+    fn add_batch_recipient(&mut self) -> Result<(), String> {
+        self.apply_payment_uri()
+            .map_err(|e| format!("{}\nPress Enter to continue.", e))?;
+        if self.amount_field.is_empty() || self.to_field.is_empty() {
+            return Err("Destination Public Key/Emoji ID and Amount required\nPress Enter to continue.".to_string());
+        }
+        let amount = self
+            .amount_field
+            .parse::<u64>()
+            .map_err(|_| "Amount should be an integer\nPress Enter to continue.".to_string())?;
+        self.batch_recipients.push((self.to_field.clone(), amount));
+        self.to_field = "".to_string();
+        self.amount_field = "".to_string();
+        Ok(())
+    }
+
+    /// The contact that the transaction is sent to, if it is known to be offline. An interactive transaction to an
+    /// offline recipient only completes once the recipient comes online and replies.
+    fn offline_recipient<'a>(&self, app_state: &'a AppState) -> Option<&'a UiContact> {
+        app_state
+            .get_contacts()
+            .iter()
+            .find(|c| c.public_key == self.to_field || c.emoji_id == self.to_field)
+            .filter(|c| c.online_status == ContactOnlineStatus::Offline)
     }
 
     fn on_key_confirmation_dialog(&mut self, c: char, app_state: &mut AppState) -> KeyHandled {
@@ -320,13 +679,25 @@ impl SendTab {
 
                             let (tx, rx) = watch::channel(UiTransactionSendStatus::Initiated);
 
-                            match Handle::current().block_on(app_state.send_transaction(
-                                self.to_field.clone(),
-                                amount,
-                                fee_per_gram,
-                                self.message_field.clone(),
-                                tx,
-                            )) {
+                            let result = if self.one_sided {
+                                Handle::current().block_on(app_state.send_one_sided_transaction(
+                                    self.to_field.clone(),
+                                    amount,
+                                    fee_per_gram,
+                                    self.message_field.clone(),
+                                    tx,
+                                ))
+                            } else {
+                                Handle::current().block_on(app_state.send_transaction(
+                                    self.to_field.clone(),
+                                    amount,
+                                    fee_per_gram,
+                                    self.message_field.clone(),
+                                    self.pinned_outputs.iter().map(|o| o.commitment.clone()).collect(),
+                                    tx,
+                                ))
+                            };
+                            match result {
                                 Err(e) => {
                                     self.error_message =
                                         Some(format!("Error sending transaction:\n{}\nPress Enter to continue.", e))
@@ -337,6 +708,9 @@ impl SendTab {
                                     self.fee_field = u64::from(DEFAULT_FEE_PER_GRAM).to_string();
                                     self.message_field = "".to_string();
                                     self.send_input_mode = SendInputMode::None;
+                                    self.pinned_outputs.clear();
+                                    self.show_utxos = false;
+                                    self.one_sided = false;
                                     self.send_result_watch = Some(rx);
                                 },
                             }
@@ -344,22 +718,70 @@ impl SendTab {
                             return KeyHandled::Handled;
                         }
                     },
-                    Some(ConfirmationDialogType::ConfirmDeleteContact) => {
-                        if 'y' == c {
-                            if let Some(c) = self
-                                .contacts_list_state
-                                .selected()
-                                .and_then(|i| app_state.get_contact(i))
-                                .cloned()
-                            {
-                                if let Err(_e) = Handle::current().block_on(app_state.delete_contact(c.public_key)) {
-                                    self.error_message =
-                                        Some("Could not delete selected contact\nPress Enter to continue.".to_string());
-                                }
-                            }
+                    Some(ConfirmationDialogType::ConfirmSendBatch) => {
+                        let fee_per_gram = if let Ok(v) = self.fee_field.parse::<u64>() {
+                            v
+                        } else {
+                            self.error_message =
+                                Some("Fee-per-gram should be an integer\nPress Enter to continue.".to_string());
                             self.confirmation_dialog = None;
                             return KeyHandled::Handled;
+                        };
+
+                        match Handle::current().block_on(app_state.send_transaction_batch(
+                            self.batch_recipients.clone(),
+                            fee_per_gram,
+                            self.message_field.clone(),
+                        )) {
+                            Err(e) => {
+                                self.error_message =
+                                    Some(format!("Error sending transaction:\n{}\nPress Enter to continue.", e))
+                            },
+                            Ok(tx_ids) => {
+                                self.batch_recipients.clear();
+                                self.fee_field = u64::from(DEFAULT_FEE_PER_GRAM).to_string();
+                                self.message_field = "".to_string();
+                                self.send_input_mode = SendInputMode::None;
+                                self.success_message = Some(format!(
+                                    "Transaction sent to {} recipients!\nPlease press Enter to continue",
+                                    tx_ids.len()
+                                ));
+                            },
                         }
+                        self.confirmation_dialog = None;
+                        return KeyHandled::Handled;
+                    },
+                    Some(ConfirmationDialogType::ConfirmCoinSplit) => {
+                        let amount_per_split = self.split_amount_field.parse::<u64>().unwrap_or_default();
+                        let num_splits = self.split_count_field.parse::<usize>().unwrap_or_default();
+                        let fee_per_gram = if let Ok(v) = self.split_fee_field.parse::<u64>() {
+                            v
+                        } else {
+                            self.error_message =
+                                Some("Fee-per-gram should be an integer\nPress Enter to continue.".to_string());
+                            self.confirmation_dialog = None;
+                            return KeyHandled::Handled;
+                        };
+
+                        match Handle::current().block_on(app_state.send_coin_split(
+                            amount_per_split,
+                            num_splits,
+                            fee_per_gram,
+                        )) {
+                            Err(e) => {
+                                self.error_message =
+                                    Some(format!("Error splitting coins:\n{}\nPress Enter to continue.", e))
+                            },
+                            Ok(tx_id) => {
+                                self.close_coin_split();
+                                self.success_message = Some(format!(
+                                    "Coin split transaction {} submitted!\nPlease press Enter to continue",
+                                    tx_id
+                                ));
+                            },
+                        }
+                        self.confirmation_dialog = None;
+                        return KeyHandled::Handled;
                     },
                 }
             }
@@ -374,6 +796,10 @@ impl SendTab {
                 SendInputMode::None => (),
                 SendInputMode::To => match c {
                     '\n' | '\t' => {
+                        if let Err(e) = self.apply_payment_uri() {
+                            self.error_message = Some(format!("{}\nPress Enter to continue.", e));
+                            return KeyHandled::Handled;
+                        }
                         self.send_input_mode = SendInputMode::Amount;
                     },
                     c => {
@@ -412,70 +838,29 @@ impl SendTab {
         KeyHandled::NotHandled
     }
 
-    fn on_key_edit_contact(&mut self, c: char, app_state: &mut AppState) -> KeyHandled {
-        if self.show_edit_contact && self.edit_contact_mode != ContactInputMode::None {
-            match self.edit_contact_mode {
-                ContactInputMode::None => return KeyHandled::Handled,
-                ContactInputMode::Alias => match c {
-                    '\n' | '\t' => {
-                        self.edit_contact_mode = ContactInputMode::PubkeyEmojiId;
-                        return KeyHandled::Handled;
-                    },
-                    c => {
-                        self.alias_field.push(c);
-                        return KeyHandled::Handled;
-                    },
-                },
-                ContactInputMode::PubkeyEmojiId => match c {
-                    '\n' => {
-                        self.edit_contact_mode = ContactInputMode::None;
-                        self.show_edit_contact = false;
-
-                        if let Err(_e) = Handle::current()
-                            .block_on(app_state.upsert_contact(self.alias_field.clone(), self.public_key_field.clone()))
-                        {
-                            self.error_message =
-                                Some("Invalid Public key or Emoji ID provided\n Press Enter to continue.".to_string());
-                        }
-
-                        self.alias_field = "".to_string();
-                        self.public_key_field = "".to_string();
-                        return KeyHandled::Handled;
-                    },
-                    c => {
-                        self.public_key_field.push(c);
-                        return KeyHandled::Handled;
-                    },
-                },
-            }
-        }
-
-        KeyHandled::NotHandled
-    }
-
     fn on_key_show_contacts(&mut self, c: char, app_state: &mut AppState) -> KeyHandled {
         if self.show_contacts {
+            if self.contacts.on_key(c, app_state) == KeyHandled::Handled {
+                return KeyHandled::Handled;
+            }
             match c {
-                'd' => {
-                    self.confirmation_dialog = Some(ConfirmationDialogType::ConfirmDeleteContact);
-                    return KeyHandled::Handled;
-                },
                 '\n' => {
-                    if let Some(c) = self
-                        .contacts_list_state
-                        .selected()
-                        .and_then(|i| app_state.get_contact(i))
-                        .cloned()
-                    {
+                    if let Some(c) = self.contacts.selected(app_state).cloned() {
                         self.to_field = c.public_key;
                         self.send_input_mode = SendInputMode::Amount;
                         self.show_contacts = false;
                     }
                     return KeyHandled::Handled;
                 },
-                'n' => {
-                    self.show_edit_contact = true;
-                    self.edit_contact_mode = ContactInputMode::Alias;
+                'y' => {
+                    if let Some(c) = self.contacts.selected(app_state) {
+                        if let Err(e) = copy_to_clipboard(c.public_key.clone()) {
+                            self.error_message = Some(format!(
+                                "Could not copy the public key:\n{}\nPlease press Enter to continue",
+                                e
+                            ));
+                        }
+                    }
                     return KeyHandled::Handled;
                 },
                 _ => (),
@@ -492,7 +877,7 @@ impl<B: Backend> Component<B> for SendTab {
             .constraints(
                 [
                     Constraint::Length(3),
-                    Constraint::Length(13),
+                    Constraint::Length(15),
                     Constraint::Min(42),
                     Constraint::Length(1),
                 ]
@@ -501,15 +886,40 @@ impl<B: Backend> Component<B> for SendTab {
             .split(area);
 
         self.balance.draw(f, areas[0], app_state);
+        if app_state.is_watch_only() {
+            let notice = Paragraph::new(
+                "This is a watch-only wallet. It only has the view key of the wallet that it watches, so it cannot \
+                 send funds.",
+            )
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(app_state.get_theme().border)
+                    .title(Span::styled("Send Transaction", app_state.get_theme().title)),
+            );
+            f.render_widget(notice, areas[1]);
+            return;
+        }
         self.draw_send_form(f, areas[1], app_state);
 
         if self.show_contacts {
-            self.draw_contacts(f, areas[2], app_state);
-            if self.show_edit_contact {
-                self.draw_edit_contact(f, area, app_state);
-            }
+            self.contacts.draw_list(f, areas[2], app_state, vec![
+                Span::raw(", "),
+                Span::styled("Y", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" to cop(y) its public key, "),
+                Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" to select."),
+            ]);
+            self.contacts.draw_dialogs(f, area, app_state);
+        } else if self.show_utxos {
+            self.draw_utxos(f, areas[2], app_state);
         };
 
+        if self.show_coin_split {
+            self.draw_coin_split(f, area, app_state.get_theme());
+        }
+
         let rx_option = self.send_result_watch.take();
         if let Some(rx) = rx_option {
             let status = match (*rx.borrow()).clone() {
@@ -535,7 +945,7 @@ impl<B: Backend> Component<B> for SendTab {
                 area,
                 "Please Wait".to_string(),
                 format!("Transaction Send Status: {}", status),
-                Color::Green,
+                app_state.get_theme().success,
                 120,
                 10,
             );
@@ -543,33 +953,85 @@ impl<B: Backend> Component<B> for SendTab {
         }
 
         if let Some(msg) = self.success_message.clone() {
-            draw_dialog(f, area, "Success!".to_string(), msg, Color::Green, 120, 9);
+            draw_dialog(
+                f,
+                area,
+                "Success!".to_string(),
+                msg,
+                app_state.get_theme().success,
+                120,
+                9,
+            );
         }
 
         if let Some(msg) = self.error_message.clone() {
-            draw_dialog(f, area, "Error!".to_string(), msg, Color::Red, 120, 9);
+            draw_dialog(f, area, "Error!".to_string(), msg, app_state.get_theme().error, 120, 9);
         }
 
         match self.confirmation_dialog {
             None => (),
             Some(ConfirmationDialogType::ConfirmSend) => {
+                let offline_warning = match self.offline_recipient(app_state) {
+                    Some(contact) if !self.one_sided => format!(
+                        "{} is offline (last seen {}), the transaction completes once they are back online.\n",
+                        contact.alias, contact.last_seen
+                    ),
+                    _ => String::new(),
+                };
+                let message = if self.one_sided {
+                    "Are you sure you want to send this one-sided transaction? It is broadcast straight away and \
+                     cannot be cancelled.\n(Y)es / (N)o"
+                        .to_string()
+                } else if self.pinned_outputs.is_empty() {
+                    format!(
+                        "{}Are you sure you want to send this transaction?\n(Y)es / (N)o",
+                        offline_warning
+                    )
+                } else {
+                    format!(
+                        "{}Are you sure you want to send this transaction, spending the {} pinned output(s)?\n(Y)es / \
+                         (N)o",
+                        offline_warning,
+                        self.pinned_outputs.len()
+                    )
+                };
                 draw_dialog(
                     f,
                     area,
                     "Confirm Sending Transaction".to_string(),
-                    "Are you sure you want to send this transaction?\n(Y)es / (N)o".to_string(),
-                    Color::Red,
+                    message,
+                    app_state.get_theme().error,
                     120,
                     9,
                 );
             },
-            Some(ConfirmationDialogType::ConfirmDeleteContact) => {
+            Some(ConfirmationDialogType::ConfirmSendBatch) => {
+                let total: u64 = self.batch_recipients.iter().map(|(_, amount)| amount).sum();
                 draw_dialog(
                     f,
                     area,
-                    "Confirm Delete".to_string(),
-                    "Are you sure you want to delete this contact?\n(Y)es / (N)o".to_string(),
-                    Color::Red,
+                    "Confirm Sending Transaction".to_string(),
+                    format!(
+                        "Are you sure you want to send {} uT to {} recipients?\nAll the recipients are paid in a \
+                         single transaction.\n(Y)es / (N)o",
+                        total,
+                        self.batch_recipients.len()
+                    ),
+                    app_state.get_theme().error,
+                    120,
+                    9,
+                );
+            },
+            Some(ConfirmationDialogType::ConfirmCoinSplit) => {
+                draw_dialog(
+                    f,
+                    area,
+                    "Confirm Coin Split".to_string(),
+                    format!(
+                        "Are you sure you want to split your coins into {} outputs of {} uT?\n(Y)es / (N)o",
+                        self.split_count_field, self.split_amount_field
+                    ),
+                    app_state.get_theme().error,
                     120,
                     9,
                 );
@@ -577,7 +1039,59 @@ impl<B: Backend> Component<B> for SendTab {
         }
     }
 
+    fn is_editing(&self) -> bool {
+        self.send_input_mode != SendInputMode::None ||
+            self.coin_split_input_mode != CoinSplitInputMode::None ||
+            (self.show_contacts && self.contacts.is_editing())
+    }
+
+    fn key_bindings(&self) -> Vec<(&'static str, &'static str)> {
+        if self.confirmation_dialog.is_some() {
+            return vec![("Y", "Confirm"), ("N", "Cancel")];
+        }
+        if self.show_coin_split {
+            return vec![
+                ("A", "Edit the amount of each output"),
+                ("N", "Edit the number of outputs"),
+                ("F", "Edit the fee per gram"),
+                ("S", "Split the coins"),
+                ("Esc", "Close the coin split"),
+            ];
+        }
+        if self.show_contacts {
+            return self.contacts.key_bindings(&[
+                ("Enter", "Send to the selected contact"),
+                ("Y", "Copy the public key of the selected contact"),
+                ("C/Esc", "Close the contacts"),
+            ]);
+        }
+        if self.show_utxos {
+            return vec![
+                ("Up/Down Arrow", "Select an output"),
+                ("Space/Enter", "Pin or unpin the selected output"),
+                ("U/Esc", "Close the outputs"),
+            ];
+        }
+        vec![
+            ("T", "Edit the recipient"),
+            ("A", "Edit the amount"),
+            ("F", "Edit the fee per gram"),
+            ("M", "Edit the message"),
+            ("R", "Add the recipient to the batch"),
+            ("S", "Send the transaction"),
+            ("C", "Show the contacts"),
+            ("U", "Show the unspent outputs to pin"),
+            ("P", "Split coins"),
+            ("O", "Toggle a one-sided payment"),
+            ("Esc", "Clear the batch of recipients"),
+        ]
+    }
+
     fn on_key(&mut self, app_state: &mut AppState, c: char) {
+        if app_state.is_watch_only() {
+            return;
+        }
+
         if self.error_message.is_some() && '\n' == c {
             self.error_message = None;
             return;
@@ -596,11 +1110,12 @@ impl<B: Backend> Component<B> for SendTab {
             return;
         }
 
-        if self.on_key_send_input(c) == KeyHandled::Handled {
+        if self.on_key_coin_split(c) == KeyHandled::Handled {
             return;
         }
 
-        if self.on_key_edit_contact(c, app_state) == KeyHandled::Handled {
+        if self.on_key_send_input(c) == KeyHandled::Handled {
+            self.refresh_fee_estimate(app_state);
             return;
         }
 
@@ -608,38 +1123,73 @@ impl<B: Backend> Component<B> for SendTab {
             return;
         }
 
+        if self.on_key_show_utxos(c) == KeyHandled::Handled {
+            return;
+        }
+
         match c {
             'c' => {
                 self.show_contacts = !self.show_contacts;
                 if self.show_contacts {
-                    self.show_edit_contact = false;
-                    self.edit_contact_mode = ContactInputMode::Alias;
-                    self.public_key_field = "".to_string();
+                    self.show_utxos = false;
                     self.amount_field = "".to_string();
                     self.message_field = "".to_string();
                     self.send_input_mode = SendInputMode::None;
                 }
             },
 
-            'e' => {
-                if let Some(c) = self
-                    .contacts_list_state
-                    .selected()
-                    .and_then(|i| app_state.get_contact(i))
-                {
-                    self.public_key_field = c.public_key.clone();
-                    self.alias_field = c.alias.clone();
-                    if self.show_contacts {
-                        self.show_edit_contact = true;
-                        self.edit_contact_mode = ContactInputMode::Alias;
-                    }
-                }
-            },
             't' => self.send_input_mode = SendInputMode::To,
             'a' => self.send_input_mode = SendInputMode::Amount,
             'f' => self.send_input_mode = SendInputMode::Fee,
             'm' => self.send_input_mode = SendInputMode::Message,
+            'u' => {
+                if self.show_utxos {
+                    self.show_utxos = false;
+                } else {
+                    self.open_utxos(app_state);
+                }
+            },
+            'o' => self.one_sided = !self.one_sided,
+            'p' => {
+                self.show_contacts = false;
+                self.send_input_mode = SendInputMode::None;
+                self.show_coin_split = true;
+                self.coin_split_input_mode = CoinSplitInputMode::Amount;
+            },
+            'r' => {
+                if let Err(e) = self.add_batch_recipient() {
+                    self.error_message = Some(e);
+                    return;
+                }
+                self.send_input_mode = SendInputMode::To;
+            },
+            's' if !self.batch_recipients.is_empty() => {
+                if self.one_sided {
+                    self.error_message =
+                        Some("A one-sided payment is sent to a single recipient\nPress Enter to continue.".to_string());
+                    return;
+                }
+                if !self.pinned_outputs.is_empty() {
+                    self.error_message = Some(
+                        "Pinned outputs can only be spent in a transaction to a single recipient\nPress Enter to \
+                         continue."
+                            .to_string(),
+                    );
+                    return;
+                }
+                if !self.to_field.is_empty() || !self.amount_field.is_empty() {
+                    if let Err(e) = self.add_batch_recipient() {
+                        self.error_message = Some(e);
+                        return;
+                    }
+                }
+                self.confirmation_dialog = Some(ConfirmationDialogType::ConfirmSendBatch);
+            },
             's' => {
+                if let Err(e) = self.apply_payment_uri() {
+                    self.error_message = Some(format!("{}\nPress Enter to continue.", e));
+                    return;
+                }
                 if self.amount_field.is_empty() || self.to_field.is_empty() {
                     self.error_message = Some(
                         "Destination Public Key/Emoji ID and Amount required\nPress Enter to continue.".to_string(),
@@ -650,38 +1200,70 @@ impl<B: Backend> Component<B> for SendTab {
                     self.error_message = Some("Amount should be an integer\nPress Enter to continue.".to_string());
                     return;
                 };
+                if self.one_sided && !self.pinned_outputs.is_empty() {
+                    self.error_message = Some(
+                        "Pinned outputs cannot be spent in a one-sided payment\nPress Enter to continue.".to_string(),
+                    );
+                    return;
+                }
 
                 self.confirmation_dialog = Some(ConfirmationDialogType::ConfirmSend);
             },
             _ => {},
         }
+        self.refresh_fee_estimate(app_state);
     }
 
     fn on_up(&mut self, app_state: &mut AppState) {
-        self.contacts_list_state.set_num_items(app_state.get_contacts().len());
-        self.contacts_list_state.previous();
+        if self.show_utxos {
+            self.utxo_list_state.set_num_items(self.utxos.len());
+            self.utxo_list_state.previous();
+            return;
+        }
+        self.contacts.on_up(app_state);
     }
 
     fn on_down(&mut self, app_state: &mut AppState) {
-        self.contacts_list_state.set_num_items(app_state.get_contacts().len());
-        self.contacts_list_state.next();
+        if self.show_utxos {
+            self.utxo_list_state.set_num_items(self.utxos.len());
+            self.utxo_list_state.next();
+            return;
+        }
+        self.contacts.on_down(app_state);
     }
 
-    fn on_esc(&mut self, _: &mut AppState) {
+    fn on_esc(&mut self, app_state: &mut AppState) {
+        if self.show_coin_split {
+            self.close_coin_split();
+            return;
+        }
+        if self.show_utxos && self.send_input_mode == SendInputMode::None {
+            self.show_utxos = false;
+            return;
+        }
+        if self.show_contacts && self.contacts.on_esc() == KeyHandled::Handled {
+            return;
+        }
+        if self.send_input_mode == SendInputMode::None && !self.show_contacts {
+            self.batch_recipients.clear();
+            self.refresh_fee_estimate(app_state);
+        }
         self.send_input_mode = SendInputMode::None;
         self.show_contacts = false;
     }
 
-    fn on_backspace(&mut self, _app_state: &mut AppState) {
+    fn on_backspace(&mut self, app_state: &mut AppState) {
         match self.send_input_mode {
             SendInputMode::To => {
                 let _ = self.to_field.pop();
             },
             SendInputMode::Amount => {
                 let _ = self.amount_field.pop();
+                self.refresh_fee_estimate(app_state);
             },
             SendInputMode::Fee => {
                 let _ = self.fee_field.pop();
+                self.refresh_fee_estimate(app_state);
             },
             SendInputMode::Message => {
                 let _ = self.message_field.pop();
@@ -689,15 +1271,20 @@ impl<B: Backend> Component<B> for SendTab {
             SendInputMode::None => {},
         }
 
-        match self.edit_contact_mode {
-            ContactInputMode::Alias => {
-                let _ = self.alias_field.pop();
+        match self.coin_split_input_mode {
+            CoinSplitInputMode::Amount => {
+                let _ = self.split_amount_field.pop();
             },
-            ContactInputMode::PubkeyEmojiId => {
-                let _ = self.public_key_field.pop();
+            CoinSplitInputMode::Count => {
+                let _ = self.split_count_field.pop();
             },
-            ContactInputMode::None => {},
+            CoinSplitInputMode::Fee => {
+                let _ = self.split_fee_field.pop();
+            },
+            CoinSplitInputMode::None => {},
         }
+
+        self.contacts.on_backspace();
     }
 }
 
@@ -711,14 +1298,16 @@ pub enum SendInputMode {
 }
 
 #[derive(PartialEq, Debug)]
-pub enum ContactInputMode {
+pub enum CoinSplitInputMode {
     None,
-    Alias,
-    PubkeyEmojiId,
+    Amount,
+    Count,
+    Fee,
 }
 
 #[derive(PartialEq, Debug)]
 pub enum ConfirmationDialogType {
     ConfirmSend,
-    ConfirmDeleteContact,
+    ConfirmSendBatch,
+    ConfirmCoinSplit,
 }
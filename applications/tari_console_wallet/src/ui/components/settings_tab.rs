@@ -0,0 +1,438 @@
+use crate::ui::{components::Component, state::AppState, Theme};
+use rand::{rngs::OsRng, seq::index, Rng};
+use tokio::runtime::Handle;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+/// The number of seed words the user is asked to repeat to confirm the backup
+const QUIZ_WORD_COUNT: usize = 3;
+
+enum BackupStage {
+    /// The settings overview
+    None,
+    /// Showing the seed words so they can be written down
+    ShowWords(Vec<String>),
+    /// Asking for the words at `indices`, `position` being the one currently being asked for
+    Quiz {
+        words: Vec<String>,
+        indices: Vec<usize>,
+        position: usize,
+        answer: String,
+    },
+}
+
+/// The prompts of the password change, in the order they are asked
+const PASSWORD_PROMPTS: [&str; 3] = ["Current password", "New password", "Confirm new password"];
+
+/// The passwords entered so far while changing the wallet password
+#[derive(Default)]
+struct PasswordChange {
+    entries: Vec<String>,
+    input: String,
+}
+
+pub struct SettingsTab {
+    backup_stage: BackupStage,
+    password_change: Option<PasswordChange>,
+    seed_backed_up: Option<bool>,
+    error_message: Option<String>,
+    success_message: Option<String>,
+}
+
+impl SettingsTab {
+    pub fn new() -> Self {
+        Self {
+            backup_stage: BackupStage::None,
+            password_change: None,
+            seed_backed_up: None,
+            error_message: None,
+            success_message: None,
+        }
+    }
+
+    fn draw_settings<B>(&self, f: &mut Frame<B>, area: Rect, theme: &Theme)
+    where B: Backend {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Settings", theme.title));
+        f.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .constraints([Constraint::Length(2), Constraint::Length(2), Constraint::Min(1)].as_ref())
+            .margin(1)
+            .split(area);
+
+        let instructions = Paragraph::new(Spans::from(vec![
+            Span::raw("Press "),
+            Span::styled("B", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to back up your seed words, or "),
+            Span::styled("P", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to change the wallet password."),
+        ]))
+        .wrap(Wrap { trim: true });
+        f.render_widget(instructions, chunks[0]);
+
+        let status = match self.seed_backed_up {
+            Some(true) => Span::styled("Backed up", theme.success),
+            Some(false) => Span::styled("Not backed up", theme.error.add_modifier(Modifier::BOLD)),
+            None => Span::raw("Unknown"),
+        };
+        let status = Paragraph::new(Spans::from(vec![
+            Span::styled("Seed words: ", Style::default().add_modifier(Modifier::BOLD)),
+            status,
+        ]));
+        f.render_widget(status, chunks[1]);
+
+        let message = match (&self.error_message, &self.success_message) {
+            (Some(e), _) => Span::styled(e.as_str(), theme.error),
+            (None, Some(s)) => Span::styled(s.as_str(), theme.success),
+            (None, None) => Span::raw(""),
+        };
+        f.render_widget(Paragraph::new(message).wrap(Wrap { trim: true }), chunks[2]);
+    }
+
+    fn draw_seed_words<B>(&self, f: &mut Frame<B>, area: Rect, words: &[String], theme: &Theme)
+    where B: Backend {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Backup Seed Words", theme.title));
+        f.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .constraints([Constraint::Length(3), Constraint::Min(1)].as_ref())
+            .margin(1)
+            .split(area);
+
+        let instructions = Paragraph::new(vec![
+            Spans::from(Span::styled(
+                "These words can be used to recover your wallet and funds. Write them down and keep them safe.",
+                theme.warning.add_modifier(Modifier::BOLD),
+            )),
+            Spans::from(vec![
+                Span::raw("Press "),
+                Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" once you have written them down, or "),
+                Span::styled("Esc", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" to cancel."),
+            ]),
+        ])
+        .wrap(Wrap { trim: true });
+        f.render_widget(instructions, chunks[0]);
+
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Ratio(1, 4); 4].as_ref())
+            .split(chunks[1]);
+        let rows_per_column = ((words.len() + columns.len() - 1) / columns.len()).max(1);
+        for (c, (column, column_words)) in columns.iter().zip(words.chunks(rows_per_column)).enumerate() {
+            let lines = column_words
+                .iter()
+                .enumerate()
+                .map(|(i, w)| Spans::from(format!("{:>2}. {}", c * rows_per_column + i + 1, w)))
+                .collect::<Vec<_>>();
+            f.render_widget(Paragraph::new(lines), *column);
+        }
+    }
+
+    fn draw_quiz<B>(&self, f: &mut Frame<B>, area: Rect, index: usize, position: usize, answer: &str, theme: &Theme)
+    where B: Backend {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Confirm Seed Words", theme.title));
+        f.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .constraints([Constraint::Length(2), Constraint::Length(3), Constraint::Min(1)].as_ref())
+            .margin(1)
+            .split(area);
+
+        let instructions = Paragraph::new(Spans::from(vec![
+            Span::raw(format!(
+                "Enter word {} of your seed words ({} of {}) and press ",
+                index + 1,
+                position + 1,
+                QUIZ_WORD_COUNT
+            )),
+            Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(". "),
+            Span::styled("Esc", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" shows the words again."),
+        ]))
+        .wrap(Wrap { trim: true });
+        f.render_widget(instructions, chunks[0]);
+
+        let input = Paragraph::new(answer).style(theme.label).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.border)
+                .title(format!("Word {}:", index + 1)),
+        );
+        f.render_widget(input, chunks[1]);
+        f.set_cursor(
+            // Put cursor past the end of the input text
+            chunks[1].x + answer.width() as u16 + 1,
+            // Move one line down, from the border to the input line
+            chunks[1].y + 1,
+        );
+
+        if let Some(msg) = self.error_message.as_ref() {
+            f.render_widget(Paragraph::new(Span::styled(msg.as_str(), theme.error)), chunks[2]);
+        }
+    }
+
+    fn draw_password_change<B>(&self, f: &mut Frame<B>, area: Rect, change: &PasswordChange, theme: &Theme)
+    where B: Backend {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Change Wallet Password", theme.title));
+        f.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .constraints([Constraint::Length(2), Constraint::Length(3), Constraint::Min(1)].as_ref())
+            .margin(1)
+            .split(area);
+
+        let instructions = Paragraph::new(Spans::from(vec![
+            Span::raw("All of the wallet data is re-encrypted with the new password. Press "),
+            Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to continue or "),
+            Span::styled("Esc", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to cancel."),
+        ]))
+        .wrap(Wrap { trim: true });
+        f.render_widget(instructions, chunks[0]);
+
+        let masked = "*".repeat(change.input.chars().count());
+        let input = Paragraph::new(masked.as_str()).style(theme.label).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.border)
+                .title(format!("{}:", PASSWORD_PROMPTS[change.entries.len()])),
+        );
+        f.render_widget(input, chunks[1]);
+        f.set_cursor(
+            // Put cursor past the end of the input text
+            chunks[1].x + masked.width() as u16 + 1,
+            // Move one line down, from the border to the input line
+            chunks[1].y + 1,
+        );
+
+        if let Some(msg) = self.error_message.as_ref() {
+            f.render_widget(Paragraph::new(Span::styled(msg.as_str(), theme.error)), chunks[2]);
+        }
+    }
+
+    /// Move on to the next password prompt, changing the password once the new one has been confirmed
+    fn submit_password(&mut self, app_state: &mut AppState) {
+        let mut change = match self.password_change.take() {
+            Some(change) => change,
+            None => return,
+        };
+        self.error_message = None;
+        change.entries.push(std::mem::take(&mut change.input));
+        if change.entries.len() < PASSWORD_PROMPTS.len() {
+            self.password_change = Some(change);
+            return;
+        }
+
+        let (current, new, confirmed) = (&change.entries[0], &change.entries[1], &change.entries[2]);
+        if new != confirmed {
+            self.error_message = Some("The new passwords don't match, the password was not changed.".to_string());
+            return;
+        }
+        if new.is_empty() {
+            self.error_message = Some("The new password cannot be empty, the password was not changed.".to_string());
+            return;
+        }
+        match Handle::current().block_on(app_state.change_passphrase(current.clone(), new.clone())) {
+            Ok(()) => self.success_message = Some("The wallet password has been changed.".to_string()),
+            Err(e) => self.error_message = Some(format!("Could not change the wallet password: {}", e)),
+        }
+    }
+
+    fn start_backup(&mut self, app_state: &AppState) {
+        self.error_message = None;
+        self.success_message = None;
+        match Handle::current().block_on(app_state.get_seed_words()) {
+            Ok(words) => self.backup_stage = BackupStage::ShowWords(words),
+            Err(e) => self.error_message = Some(format!("Could not read the seed words: {}", e)),
+        }
+    }
+
+    fn check_answer(&mut self, app_state: &mut AppState) {
+        let confirmed = match &mut self.backup_stage {
+            BackupStage::Quiz {
+                words,
+                indices,
+                position,
+                answer,
+            } => {
+                if !is_correct_word(&words[indices[*position]], answer) {
+                    self.error_message = Some("That is not the right word, please try again.".to_string());
+                    answer.clear();
+                    return;
+                }
+                self.error_message = None;
+                answer.clear();
+                *position += 1;
+                *position == indices.len()
+            },
+            _ => return,
+        };
+        if confirmed {
+            self.backup_stage = BackupStage::None;
+            match Handle::current().block_on(app_state.set_seed_backed_up()) {
+                Ok(()) => {
+                    self.seed_backed_up = Some(true);
+                    self.success_message = Some("Your seed words backup has been confirmed.".to_string());
+                },
+                Err(e) => self.error_message = Some(format!("Could not save the backup status: {}", e)),
+            }
+        }
+    }
+}
+
+impl<B: Backend> Component<B> for SettingsTab {
+    fn draw(&mut self, f: &mut Frame<B>, area: Rect, app_state: &AppState) {
+        let theme = app_state.get_theme();
+        if let Some(change) = self.password_change.as_ref() {
+            self.draw_password_change(f, area, change, theme);
+            return;
+        }
+        match &self.backup_stage {
+            BackupStage::None => self.draw_settings(f, area, theme),
+            BackupStage::ShowWords(words) => self.draw_seed_words(f, area, words, theme),
+            BackupStage::Quiz {
+                indices,
+                position,
+                answer,
+                ..
+            } => self.draw_quiz(f, area, indices[*position], *position, answer, theme),
+        }
+    }
+
+    fn is_editing(&self) -> bool {
+        self.password_change.is_some() || matches!(self.backup_stage, BackupStage::Quiz { .. })
+    }
+
+    fn key_bindings(&self) -> Vec<(&'static str, &'static str)> {
+        if self.password_change.is_some() {
+            return vec![("Enter", "Continue"), ("Esc", "Cancel the password change")];
+        }
+        match self.backup_stage {
+            BackupStage::None => vec![("B", "Back up the seed words"), ("P", "Change the wallet password")],
+            BackupStage::ShowWords(_) => vec![("Enter", "Continue to the quiz"), ("Esc", "Stop the backup")],
+            BackupStage::Quiz { .. } => vec![("Enter", "Check the word"), ("Esc", "Show the seed words again")],
+        }
+    }
+
+    fn on_key(&mut self, app_state: &mut AppState, c: char) {
+        if let Some(change) = self.password_change.as_mut() {
+            match c {
+                '\n' => self.submit_password(app_state),
+                c => change.input.push(c),
+            }
+            return;
+        }
+        match &mut self.backup_stage {
+            BackupStage::None => match c {
+                'b' => self.start_backup(app_state),
+                'p' => {
+                    self.error_message = None;
+                    self.success_message = None;
+                    self.password_change = Some(PasswordChange::default());
+                },
+                _ => {},
+            },
+            BackupStage::ShowWords(words) => {
+                if c == '\n' {
+                    let words = std::mem::take(words);
+                    let indices = quiz_indices(&mut OsRng, words.len());
+                    self.backup_stage = BackupStage::Quiz {
+                        words,
+                        indices,
+                        position: 0,
+                        answer: String::new(),
+                    };
+                }
+            },
+            BackupStage::Quiz { answer, .. } => match c {
+                '\n' => self.check_answer(app_state),
+                c if !c.is_whitespace() => answer.push(c),
+                _ => {},
+            },
+        }
+    }
+
+    fn on_esc(&mut self, _app_state: &mut AppState) {
+        self.error_message = None;
+        if self.password_change.take().is_some() {
+            return;
+        }
+        self.backup_stage = match std::mem::replace(&mut self.backup_stage, BackupStage::None) {
+            BackupStage::Quiz { words, .. } => BackupStage::ShowWords(words),
+            _ => BackupStage::None,
+        };
+    }
+
+    fn on_backspace(&mut self, _app_state: &mut AppState) {
+        if let Some(change) = self.password_change.as_mut() {
+            let _ = change.input.pop();
+        } else if let BackupStage::Quiz { answer, .. } = &mut self.backup_stage {
+            let _ = answer.pop();
+        }
+    }
+
+    fn on_tick(&mut self, app_state: &mut AppState) {
+        if self.seed_backed_up.is_none() {
+            // Stays unknown, and is tried again on the next tick, if it cannot be read
+            self.seed_backed_up = Handle::current().block_on(app_state.is_seed_backed_up()).ok();
+        }
+    }
+}
+
+/// Pick the positions of the seed words to ask for, in ascending order
+fn quiz_indices<R: Rng>(rng: &mut R, word_count: usize) -> Vec<usize> {
+    let mut indices = index::sample(rng, word_count, QUIZ_WORD_COUNT.min(word_count)).into_vec();
+    indices.sort_unstable();
+    indices
+}
+
+fn is_correct_word(expected: &str, answer: &str) -> bool {
+    expected.eq_ignore_ascii_case(answer.trim())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn quiz_asks_for_distinct_words_in_order() {
+        for _ in 0..100 {
+            let indices = quiz_indices(&mut OsRng, 24);
+            assert_eq!(indices.len(), QUIZ_WORD_COUNT);
+            assert!(indices.windows(2).all(|w| w[0] < w[1]));
+            assert!(indices.iter().all(|i| *i < 24));
+        }
+        assert_eq!(quiz_indices(&mut OsRng, 2), vec![0, 1]);
+    }
+
+    #[test]
+    fn answers_ignore_case_and_surrounding_whitespace() {
+        assert!(is_correct_word("abandon", "abandon"));
+        assert!(is_correct_word("abandon", " Abandon "));
+        assert!(!is_correct_word("abandon", "ability"));
+        assert!(!is_correct_word("abandon", ""));
+    }
+}
@@ -0,0 +1,112 @@
+// Copyright 2020. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::{
+    ui::{components::Component, state::AppState, Theme},
+    utils::tor_status::{TorStatus, TorStatusMonitor},
+};
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::Style,
+    text::{Span, Spans},
+    widgets::Paragraph,
+    Frame,
+};
+
+/// A line at the bottom of every tab that summarises the sync and connectivity state of the wallet
+pub struct StatusBar {
+    tor_status: TorStatusMonitor,
+}
+
+impl StatusBar {
+    pub fn new(tor_status: TorStatusMonitor) -> Self {
+        Self { tor_status }
+    }
+}
+
+impl<B: Backend> Component<B> for StatusBar {
+    fn draw(&mut self, f: &mut Frame<B>, area: Rect, app_state: &AppState) {
+        let theme = app_state.get_theme();
+        let base_node_state = app_state.get_base_node_state();
+        let tip = base_node_state
+            .chain_metadata
+            .as_ref()
+            .map(|metadata| metadata.height_of_longest_chain());
+        let (sync_text, sync_style) = match base_node_state.is_synced {
+            Some(true) => ("synced", theme.success),
+            Some(false) => ("syncing", theme.warning),
+            None => ("unknown", theme.muted),
+        };
+        let validated = app_state.get_validated_height();
+
+        let mut spans = vec![
+            Span::styled("Base Node: ", theme.label),
+            Span::styled(format_height(tip), theme.text),
+            Span::raw(" "),
+            Span::styled(sync_text, sync_style),
+            Span::styled("  Validated: ", theme.label),
+            Span::styled(format_height(validated), validated_style(validated, tip, theme)),
+            Span::styled("  Peers: ", theme.label),
+            Span::styled(app_state.get_connected_peers().len().to_string(), theme.text),
+            Span::styled("  Tor: ", theme.label),
+            Span::styled(
+                self.tor_status.status().to_string(),
+                tor_style(self.tor_status.status(), theme),
+            ),
+        ];
+        if let Some(error) = app_state.get_last_error() {
+            spans.push(Span::styled("  Last Error: ", theme.label));
+            spans.push(Span::styled(
+                format!("{} {}", error.time.format("%H:%M:%S"), error.message),
+                theme.error,
+            ));
+        }
+        f.render_widget(Paragraph::new(Spans::from(spans)), area);
+    }
+
+    fn on_tick(&mut self, _app_state: &mut AppState) {
+        self.tor_status.poll();
+    }
+}
+
+fn format_height(height: Option<u64>) -> String {
+    height.map(|h| format!("#{}", h)).unwrap_or_else(|| "-".to_string())
+}
+
+/// The outputs are up to date when they were validated at the tip of the base node
+fn validated_style(validated: Option<u64>, tip: Option<u64>, theme: &Theme) -> Style {
+    match (validated, tip) {
+        (Some(validated), Some(tip)) if validated >= tip => theme.success,
+        (Some(_), _) => theme.warning,
+        (None, _) => theme.muted,
+    }
+}
+
+fn tor_style(status: &TorStatus, theme: &Theme) -> Style {
+    match status {
+        TorStatus::Bootstrapped => theme.success,
+        TorStatus::Bootstrapping(_, _) => theme.warning,
+        TorStatus::Unreachable => theme.error,
+        TorStatus::NotUsed | TorStatus::Unknown => theme.muted,
+    }
+}
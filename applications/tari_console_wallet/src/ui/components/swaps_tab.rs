@@ -0,0 +1,647 @@
+use crate::{
+    ui::{
+        components::{Component, KeyHandled},
+        state::{AppState, UiHtlc},
+        widgets::{centered_rect_absolute, draw_dialog, MultiColumnList, WindowedListState},
+        Theme,
+    },
+    utils::formatting::display_compressed_string,
+};
+use std::time::{Duration, Instant};
+use tari_core::transactions::htlc::{generate_preimage, hash_preimage};
+use tari_crypto::tari_utilities::hex::Hex;
+use tari_wallet::types::DEFAULT_FEE_PER_GRAM;
+use tokio::runtime::Handle;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, ListItem, Paragraph, Wrap},
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+/// How often the hash time-locked outputs are fetched, so that locks that are received or spent show up
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+pub struct SwapsTab {
+    htlcs: Vec<UiHtlc>,
+    htlc_key: Option<String>,
+    last_refresh: Option<Instant>,
+    htlcs_list_state: WindowedListState,
+    show_lock: bool,
+    show_claim: bool,
+    input_mode: SwapInputMode,
+    to_field: String,
+    claim_key_field: String,
+    amount_field: String,
+    fee_field: String,
+    hash_lock_field: String,
+    refund_height_field: String,
+    message_field: String,
+    preimage_field: String,
+    confirm_refund: bool,
+    secret_message: Option<String>,
+    result_message: Option<String>,
+    error_message: Option<String>,
+}
+
+impl SwapsTab {
+    pub fn new() -> Self {
+        Self {
+            htlcs: Vec::new(),
+            htlc_key: None,
+            last_refresh: None,
+            htlcs_list_state: WindowedListState::new(),
+            show_lock: false,
+            show_claim: false,
+            input_mode: SwapInputMode::None,
+            to_field: "".to_string(),
+            claim_key_field: "".to_string(),
+            amount_field: "".to_string(),
+            fee_field: u64::from(DEFAULT_FEE_PER_GRAM).to_string(),
+            hash_lock_field: "".to_string(),
+            refund_height_field: "".to_string(),
+            message_field: "".to_string(),
+            preimage_field: "".to_string(),
+            confirm_refund: false,
+            secret_message: None,
+            result_message: None,
+            error_message: None,
+        }
+    }
+
+    fn draw_htlcs<B>(&mut self, f: &mut Frame<B>, area: Rect, theme: &Theme)
+    where B: Backend {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Atomic Swaps", theme.title));
+        f.render_widget(block, area);
+        let list_areas = Layout::default()
+            .constraints([Constraint::Length(1), Constraint::Length(3), Constraint::Min(4)].as_ref())
+            .margin(1)
+            .split(area);
+
+        let htlc_key = Paragraph::new(Spans::from(vec![
+            Span::styled("HTLC key: ", theme.label),
+            Span::raw(self.htlc_key.clone().unwrap_or_default()),
+        ]));
+        f.render_widget(htlc_key, list_areas[0]);
+
+        let instructions = Paragraph::new(Spans::from(vec![
+            Span::raw(" Use "),
+            Span::styled("Up/Down Arrow Keys", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to choose a lock, "),
+            Span::styled("S", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to create a new (s)ecret, "),
+            Span::styled("N", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to lock funds for the other party of a (n)ew swap, "),
+            Span::styled("C", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to (c)laim a lock with its preimage and "),
+            Span::styled("R", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(
+                " to (r)efund a lock of this wallet once its refund height is reached. Give the other party the HTLC \
+                 key above to lock funds for this wallet.",
+            ),
+        ]))
+        .wrap(Wrap { trim: true });
+        f.render_widget(instructions, list_areas[1]);
+
+        self.htlcs_list_state.set_num_items(self.htlcs.len());
+        let mut list_state = self
+            .htlcs_list_state
+            .get_list_state((list_areas[2].height as usize).saturating_sub(1));
+        let (start, end) = self.htlcs_list_state.get_start_end();
+        let windowed_view = self.htlcs.get(start..end).unwrap_or_default();
+
+        let mut column0_items = Vec::with_capacity(windowed_view.len());
+        let mut column1_items = Vec::with_capacity(windowed_view.len());
+        let mut column2_items = Vec::with_capacity(windowed_view.len());
+        let mut column3_items = Vec::with_capacity(windowed_view.len());
+        let mut column4_items = Vec::with_capacity(windowed_view.len());
+        for h in windowed_view.iter() {
+            column0_items.push(ListItem::new(Span::raw(if h.claimable { "Claim" } else { "Refund" })));
+            column1_items.push(ListItem::new(Span::styled(format!("{}", h.value), theme.success)));
+            column2_items.push(ListItem::new(Span::raw(h.htlc.refund_height.to_string())));
+            column3_items.push(ListItem::new(Span::raw(display_compressed_string(
+                h.htlc.hash_lock.to_vec().to_hex(),
+                8,
+                8,
+            ))));
+            column4_items.push(ListItem::new(Span::raw(h.commitment.to_hex())));
+        }
+        let column_list = MultiColumnList::new()
+            .highlight_style(theme.highlight)
+            .heading_style(theme.label)
+            .max_width(list_areas[2].width)
+            .add_column(Some("Role"), Some(7), column0_items)
+            .add_column(Some("Value"), Some(18), column1_items)
+            .add_column(Some("Refund Height"), Some(14), column2_items)
+            .add_column(Some("Hash Lock"), Some(20), column3_items)
+            .add_column(Some("Commitment"), None, column4_items);
+        column_list.render(f, list_areas[2], &mut list_state);
+    }
+
+    fn draw_lock<B>(&self, f: &mut Frame<B>, area: Rect, theme: &Theme)
+    where B: Backend {
+        let popup_area = centered_rect_absolute(120, 19, area);
+
+        f.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Lock Funds for an Atomic Swap", theme.title));
+        f.render_widget(block, popup_area);
+        let vert_chunks = Layout::default()
+            .constraints(
+                [
+                    Constraint::Length(2),
+                    Constraint::Length(3),
+                    Constraint::Length(3),
+                    Constraint::Length(3),
+                    Constraint::Length(3),
+                    Constraint::Length(3),
+                ]
+                .as_ref(),
+            )
+            .margin(1)
+            .split(popup_area);
+
+        let instructions = Paragraph::new(Spans::from(vec![
+            Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" moves to the next field and locks the funds from the last one, "),
+            Span::styled("Esc", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to cancel. The refund height must be reached well before the lock on the other chain expires."),
+        ]))
+        .wrap(Wrap { trim: true });
+        f.render_widget(instructions, vert_chunks[0]);
+
+        let amount_layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(
+                [
+                    Constraint::Percentage(40),
+                    Constraint::Percentage(30),
+                    Constraint::Percentage(30),
+                ]
+                .as_ref(),
+            )
+            .split(vert_chunks[3]);
+
+        let fields = [
+            (SwapInputMode::To, "To (Public Key or Emoji ID):", vert_chunks[1]),
+            (SwapInputMode::ClaimKey, "HTLC Key of the Recipient:", vert_chunks[2]),
+            (SwapInputMode::Amount, "Amount (uT):", amount_layout[0]),
+            (SwapInputMode::Fee, "Fee-per-gram (uT):", amount_layout[1]),
+            (SwapInputMode::RefundHeight, "Refund Height:", amount_layout[2]),
+            (SwapInputMode::HashLock, "Hash Lock:", vert_chunks[4]),
+            (SwapInputMode::Message, "Message:", vert_chunks[5]),
+        ];
+        self.draw_fields(f, &fields, theme);
+    }
+
+    fn draw_claim<B>(&self, f: &mut Frame<B>, area: Rect, theme: &Theme)
+    where B: Backend {
+        let popup_area = centered_rect_absolute(120, 7, area);
+
+        f.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Claim an Atomic Swap", theme.title));
+        f.render_widget(block, popup_area);
+        let vert_chunks = Layout::default()
+            .constraints([Constraint::Length(2), Constraint::Length(3)].as_ref())
+            .margin(1)
+            .split(popup_area);
+
+        let instructions = Paragraph::new(Spans::from(vec![
+            Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" claims the lock, "),
+            Span::styled("Esc", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to cancel. Claiming reveals the preimage to the other party."),
+        ]))
+        .wrap(Wrap { trim: true });
+        f.render_widget(instructions, vert_chunks[0]);
+
+        let fields = [(SwapInputMode::Preimage, "Preimage:", vert_chunks[1])];
+        self.draw_fields(f, &fields, theme);
+    }
+
+    fn draw_fields<B>(&self, f: &mut Frame<B>, fields: &[(SwapInputMode, &str, Rect)], theme: &Theme)
+    where B: Backend {
+        for (mode, title, field_area) in fields.iter() {
+            let input = Paragraph::new(self.field(mode).unwrap_or_default())
+                .style(if *mode == self.input_mode {
+                    theme.label
+                } else {
+                    Style::default()
+                })
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(theme.border)
+                        .title(*title),
+                );
+            f.render_widget(input, *field_area);
+            if *mode == self.input_mode {
+                f.set_cursor(
+                    // Put cursor past the end of the input text
+                    field_area.x + self.field(mode).unwrap_or_default().width() as u16 + 1,
+                    // Move one line down, from the border to the input line
+                    field_area.y + 1,
+                );
+            }
+        }
+    }
+
+    fn field(&self, mode: &SwapInputMode) -> Option<&str> {
+        match mode {
+            SwapInputMode::None => None,
+            SwapInputMode::To => Some(&self.to_field),
+            SwapInputMode::ClaimKey => Some(&self.claim_key_field),
+            SwapInputMode::Amount => Some(&self.amount_field),
+            SwapInputMode::Fee => Some(&self.fee_field),
+            SwapInputMode::RefundHeight => Some(&self.refund_height_field),
+            SwapInputMode::HashLock => Some(&self.hash_lock_field),
+            SwapInputMode::Message => Some(&self.message_field),
+            SwapInputMode::Preimage => Some(&self.preimage_field),
+        }
+    }
+
+    fn field_mut(&mut self) -> Option<&mut String> {
+        match self.input_mode {
+            SwapInputMode::None => None,
+            SwapInputMode::To => Some(&mut self.to_field),
+            SwapInputMode::ClaimKey => Some(&mut self.claim_key_field),
+            SwapInputMode::Amount => Some(&mut self.amount_field),
+            SwapInputMode::Fee => Some(&mut self.fee_field),
+            SwapInputMode::RefundHeight => Some(&mut self.refund_height_field),
+            SwapInputMode::HashLock => Some(&mut self.hash_lock_field),
+            SwapInputMode::Message => Some(&mut self.message_field),
+            SwapInputMode::Preimage => Some(&mut self.preimage_field),
+        }
+    }
+
+    fn selected_htlc(&self) -> Option<&UiHtlc> {
+        self.htlcs_list_state.selected().and_then(|i| self.htlcs.get(i))
+    }
+
+    fn close_forms(&mut self) {
+        self.show_lock = false;
+        self.show_claim = false;
+        self.input_mode = SwapInputMode::None;
+        self.to_field = "".to_string();
+        self.claim_key_field = "".to_string();
+        self.amount_field = "".to_string();
+        self.fee_field = u64::from(DEFAULT_FEE_PER_GRAM).to_string();
+        self.hash_lock_field = "".to_string();
+        self.refund_height_field = "".to_string();
+        self.message_field = "".to_string();
+        self.preimage_field = "".to_string();
+    }
+
+    fn show_secret(&mut self) {
+        let preimage = generate_preimage();
+        let hash_lock = hash_preimage(&preimage);
+        self.secret_message = Some(format!(
+            "Preimage : {}\nHash lock: {}\nKeep the preimage secret until the swap is claimed, it is not stored in \
+             the wallet.\nPress Enter to continue.",
+            preimage.to_vec().to_hex(),
+            hash_lock.to_vec().to_hex()
+        ));
+    }
+
+    fn lock(&mut self, app_state: &mut AppState) {
+        let amount = match self.amount_field.parse::<u64>() {
+            Ok(a) => a,
+            Err(_) => {
+                self.error_message = Some("Amount should be an integer\nPress Enter to continue.".to_string());
+                return;
+            },
+        };
+        let fee_per_gram = match self.fee_field.parse::<u64>() {
+            Ok(f) => f,
+            Err(_) => {
+                self.error_message = Some("Fee-per-gram should be an integer\nPress Enter to continue.".to_string());
+                return;
+            },
+        };
+        let refund_height = match self.refund_height_field.parse::<u64>() {
+            Ok(h) => h,
+            Err(_) => {
+                self.error_message = Some("Refund height should be an integer\nPress Enter to continue.".to_string());
+                return;
+            },
+        };
+
+        match Handle::current().block_on(app_state.lock_htlc(
+            self.to_field.clone(),
+            self.claim_key_field.clone(),
+            amount,
+            self.hash_lock_field.clone(),
+            refund_height,
+            fee_per_gram,
+            self.message_field.clone(),
+        )) {
+            Ok(tx_id) => {
+                self.close_forms();
+                self.result_message = Some(format!(
+                    "Locked the swap in transaction {}\nPress Enter to continue.",
+                    tx_id
+                ));
+                self.refresh(app_state);
+            },
+            Err(e) => self.error_message = Some(format!("Could not lock the swap:\n{}\nPress Enter to continue.", e)),
+        }
+    }
+
+    fn claim(&mut self, app_state: &mut AppState) {
+        let commitment = match self.selected_htlc() {
+            Some(h) => h.commitment.clone(),
+            None => return,
+        };
+        match Handle::current().block_on(app_state.claim_htlc(
+            commitment,
+            self.preimage_field.clone(),
+            u64::from(DEFAULT_FEE_PER_GRAM),
+        )) {
+            Ok(tx_id) => {
+                self.close_forms();
+                self.result_message = Some(format!(
+                    "Claimed the swap in transaction {}\nPress Enter to continue.",
+                    tx_id
+                ));
+                self.htlcs_list_state.select(None);
+                self.refresh(app_state);
+            },
+            Err(e) => self.error_message = Some(format!("Could not claim the swap:\n{}\nPress Enter to continue.", e)),
+        }
+    }
+
+    fn refund(&mut self, app_state: &mut AppState) {
+        let commitment = match self.selected_htlc() {
+            Some(h) => h.commitment.clone(),
+            None => return,
+        };
+        match Handle::current().block_on(app_state.refund_htlc(commitment, u64::from(DEFAULT_FEE_PER_GRAM))) {
+            Ok(tx_id) => {
+                self.result_message = Some(format!(
+                    "Refunded the swap in transaction {}\nPress Enter to continue.",
+                    tx_id
+                ));
+                self.htlcs_list_state.select(None);
+                self.refresh(app_state);
+            },
+            Err(e) => self.error_message = Some(format!("Could not refund the swap:\n{}\nPress Enter to continue.", e)),
+        }
+    }
+
+    fn refresh(&mut self, app_state: &AppState) {
+        self.last_refresh = Some(Instant::now());
+        if self.htlc_key.is_none() {
+            match Handle::current().block_on(app_state.get_htlc_public_key()) {
+                Ok(key) => self.htlc_key = Some(key.to_hex()),
+                Err(e) => {
+                    self.error_message = Some(format!(
+                        "Could not fetch the HTLC key:\n{}\nPress Enter to continue.",
+                        e
+                    ));
+                    return;
+                },
+            }
+        }
+        match Handle::current().block_on(app_state.get_htlcs()) {
+            Ok(htlcs) => self.htlcs = htlcs,
+            Err(e) => {
+                self.error_message = Some(format!(
+                    "Could not fetch the hash time-locked outputs:\n{}\nPress Enter to continue.",
+                    e
+                ))
+            },
+        }
+    }
+
+    fn on_key_confirm_refund(&mut self, c: char, app_state: &mut AppState) -> KeyHandled {
+        if !self.confirm_refund {
+            return KeyHandled::NotHandled;
+        }
+        match c {
+            'y' => {
+                self.confirm_refund = false;
+                self.refund(app_state);
+            },
+            'n' => self.confirm_refund = false,
+            _ => (),
+        }
+        KeyHandled::Handled
+    }
+
+    fn on_key_form(&mut self, c: char, app_state: &mut AppState) -> KeyHandled {
+        if !self.show_lock && !self.show_claim {
+            return KeyHandled::NotHandled;
+        }
+        match c {
+            '\n' => match self.input_mode.next() {
+                Some(mode) => self.input_mode = mode,
+                None if self.show_claim => self.claim(app_state),
+                None => self.lock(app_state),
+            },
+            c => {
+                if let Some(field) = self.field_mut() {
+                    field.push(c);
+                }
+            },
+        }
+        KeyHandled::Handled
+    }
+}
+
+impl<B: Backend> Component<B> for SwapsTab {
+    fn draw(&mut self, f: &mut Frame<B>, area: Rect, app_state: &AppState) {
+        self.draw_htlcs(f, area, app_state.get_theme());
+        if self.show_lock {
+            self.draw_lock(f, area, app_state.get_theme());
+        }
+        if self.show_claim {
+            self.draw_claim(f, area, app_state.get_theme());
+        }
+
+        if let Some(msg) = self.secret_message.clone() {
+            draw_dialog(
+                f,
+                area,
+                "Swap Secret".to_string(),
+                msg,
+                app_state.get_theme().warning,
+                120,
+                10,
+            );
+        }
+
+        if let Some(msg) = self.result_message.clone() {
+            draw_dialog(
+                f,
+                area,
+                "Success!".to_string(),
+                msg,
+                app_state.get_theme().success,
+                120,
+                9,
+            );
+        }
+
+        if let Some(msg) = self.error_message.clone() {
+            draw_dialog(f, area, "Error!".to_string(), msg, app_state.get_theme().error, 120, 9);
+        }
+
+        if self.confirm_refund {
+            draw_dialog(
+                f,
+                area,
+                "Confirm Refund".to_string(),
+                "Are you sure you want to refund this swap?\n(Y)es / (N)o".to_string(),
+                app_state.get_theme().error,
+                120,
+                9,
+            );
+        }
+    }
+
+    fn is_editing(&self) -> bool {
+        self.show_lock || self.show_claim
+    }
+
+    fn key_bindings(&self) -> Vec<(&'static str, &'static str)> {
+        if self.confirm_refund {
+            return vec![("Y", "Refund the swap"), ("N", "Keep the swap locked")];
+        }
+        if self.show_lock {
+            return vec![
+                ("Enter", "Next field, or lock the funds from the last field"),
+                ("Esc", "Cancel"),
+            ];
+        }
+        if self.show_claim {
+            return vec![("Enter", "Claim the swap with the preimage"), ("Esc", "Cancel")];
+        }
+        vec![
+            ("Up/Down Arrow", "Select a hash time-locked output"),
+            ("S", "Create the secret of a new swap"),
+            ("N", "Lock funds for the other party of a new swap"),
+            ("C", "Claim the selected swap with its preimage"),
+            ("R", "Refund the selected swap of this wallet"),
+            ("Esc", "Clear the selection"),
+        ]
+    }
+
+    fn on_key(&mut self, app_state: &mut AppState, c: char) {
+        if self.error_message.is_some() || self.result_message.is_some() || self.secret_message.is_some() {
+            if '\n' == c {
+                self.error_message = None;
+                self.result_message = None;
+                self.secret_message = None;
+            }
+            return;
+        }
+
+        if self.on_key_confirm_refund(c, app_state) == KeyHandled::Handled {
+            return;
+        }
+
+        if self.on_key_form(c, app_state) == KeyHandled::Handled {
+            return;
+        }
+
+        match c {
+            's' => self.show_secret(),
+            'n' => {
+                self.show_lock = true;
+                self.input_mode = SwapInputMode::To;
+            },
+            'c' => {
+                if self.selected_htlc().map(|h| h.claimable).unwrap_or(false) {
+                    self.show_claim = true;
+                    self.input_mode = SwapInputMode::Preimage;
+                }
+            },
+            'r' => {
+                if self.selected_htlc().map(|h| !h.claimable).unwrap_or(false) {
+                    self.confirm_refund = true;
+                }
+            },
+            _ => (),
+        }
+    }
+
+    fn on_up(&mut self, _app_state: &mut AppState) {
+        self.htlcs_list_state.set_num_items(self.htlcs.len());
+        self.htlcs_list_state.previous();
+    }
+
+    fn on_down(&mut self, _app_state: &mut AppState) {
+        self.htlcs_list_state.set_num_items(self.htlcs.len());
+        self.htlcs_list_state.next();
+    }
+
+    fn on_esc(&mut self, _app_state: &mut AppState) {
+        if self.show_lock || self.show_claim {
+            self.close_forms();
+        } else {
+            self.htlcs_list_state.select(None);
+        }
+    }
+
+    fn on_backspace(&mut self, _app_state: &mut AppState) {
+        if let Some(field) = self.field_mut() {
+            let _ = field.pop();
+        }
+    }
+
+    fn on_tick(&mut self, app_state: &mut AppState) {
+        if self.is_editing() || self.error_message.is_some() {
+            return;
+        }
+        if self
+            .last_refresh
+            .map(|t| t.elapsed() >= REFRESH_INTERVAL)
+            .unwrap_or(true)
+        {
+            self.refresh(app_state);
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub enum SwapInputMode {
+    None,
+    To,
+    ClaimKey,
+    Amount,
+    Fee,
+    RefundHeight,
+    HashLock,
+    Message,
+    Preimage,
+}
+
+impl SwapInputMode {
+    /// The field that follows this one in its form, or None after the last field
+    fn next(&self) -> Option<Self> {
+        match self {
+            Self::None => Some(Self::To),
+            Self::To => Some(Self::ClaimKey),
+            Self::ClaimKey => Some(Self::Amount),
+            Self::Amount => Some(Self::Fee),
+            Self::Fee => Some(Self::RefundHeight),
+            Self::RefundHeight => Some(Self::HashLock),
+            Self::HashLock => Some(Self::Message),
+            Self::Message => None,
+            Self::Preimage => None,
+        }
+    }
+}
@@ -20,21 +20,23 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use crate::ui::{components::Component, state::AppState};
+use crate::ui::{components::Component, state::AppState, Theme};
 use tui::{
     backend::Backend,
     layout::Rect,
-    style::{Color, Modifier, Style},
     text::{Span, Spans},
     widgets::{Block, Borders, Tabs},
     Frame,
 };
+use unicode_width::UnicodeWidthStr;
 
 pub struct TabsContainer<B: Backend> {
     title: String,
     tabs: Vec<Box<dyn Component<B>>>,
     titles: Vec<String>,
     index: usize,
+    // Where the titles were last drawn, to find the tab that is clicked on
+    titles_area: Rect,
 }
 
 impl<B: Backend> TabsContainer<B> {
@@ -44,6 +46,7 @@ impl<B: Backend> TabsContainer<B> {
             tabs: vec![],
             titles: vec![],
             index: 0,
+            titles_area: Rect::default(),
         }
     }
 
@@ -65,18 +68,48 @@ impl<B: Backend> TabsContainer<B> {
         }
     }
 
-    pub fn draw_titles(&self, f: &mut Frame<B>, area: Rect) {
+    pub fn selected_title(&self) -> &str {
+        &self.titles[self.index]
+    }
+
+    /// The index of the tab whose title is drawn at the given cell, following the layout of the `Tabs` widget: the
+    /// titles are padded by a space on either side and separated by a one character divider.
+    fn tab_at(&self, column: u16, row: u16) -> Option<usize> {
+        let area = self.titles_area;
+        // Skip the borders of the block
+        if row != area.y + 1 || column <= area.x || column >= area.x + area.width.saturating_sub(1) {
+            return None;
+        }
+        let mut x = area.x + 1;
+        for (i, title) in self.titles.iter().enumerate() {
+            let end = x + title.width() as u16 + 2;
+            if column < end {
+                return Some(i);
+            }
+            // The divider
+            x = end + 1;
+            if column < x {
+                return None;
+            }
+        }
+        None
+    }
+
+    pub fn draw_titles(&mut self, f: &mut Frame<B>, area: Rect, theme: &Theme) {
+        self.titles_area = area;
         let titles = self
             .titles
             .iter()
-            .map(|t| Spans::from(Span::styled(t, Style::default().fg(Color::White))))
+            .map(|t| Spans::from(Span::styled(t, theme.text)))
             .collect();
         let tabs = Tabs::new(titles)
-            .block(Block::default().borders(Borders::ALL).title(Span::styled(
-                &self.title,
-                Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
-            )))
-            .highlight_style(Style::default().fg(Color::Magenta))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(theme.border)
+                    .title(Span::styled(&self.title, theme.title)),
+            )
+            .highlight_style(theme.highlight)
             .select(self.index);
         f.render_widget(tabs, area);
     }
@@ -93,6 +126,14 @@ impl<B: Backend> Component<B> for TabsContainer<B> {
         unimplemented!()
     }
 
+    fn is_editing(&self) -> bool {
+        self.tabs[self.index].is_editing()
+    }
+
+    fn key_bindings(&self) -> Vec<(&'static str, &'static str)> {
+        self.tabs[self.index].key_bindings()
+    }
+
     fn on_key(&mut self, app_state: &mut AppState, c: char) {
         self.tabs[self.index].on_key(app_state, c);
     }
@@ -105,6 +146,13 @@ impl<B: Backend> Component<B> for TabsContainer<B> {
         self.tabs[self.index].on_down(app_state);
     }
 
+    fn on_click(&mut self, app_state: &mut AppState, column: u16, row: u16) {
+        match self.tab_at(column, row) {
+            Some(index) => self.index = index,
+            None => self.tabs[self.index].on_click(app_state, column, row),
+        }
+    }
+
     fn on_esc(&mut self, app_state: &mut AppState) {
         self.tabs[self.index].on_esc(app_state);
     }
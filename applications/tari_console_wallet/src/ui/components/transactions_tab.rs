@@ -1,35 +1,60 @@
-use std::collections::HashMap;
+use std::path::PathBuf;
 
 use crate::ui::{
     components::{balance::Balance, Component},
-    state::AppState,
-    widgets::{draw_dialog, MultiColumnList, WindowedListState},
+    state::{AppState, TransactionFilter},
+    widgets::{centered_rect_absolute, draw_dialog, MultiColumnList, WindowedListState},
+    LayoutMode,
+    Theme,
     MAX_WIDTH,
+    SIDE_PANE_WIDTH,
 };
 use tari_crypto::tari_utilities::hex::Hex;
-use tari_wallet::transaction_service::storage::models::{
-    CompletedTransaction,
-    TransactionDirection,
-    TransactionStatus,
+use tari_wallet::{
+    output_manager_service::TxId,
+    transaction_service::{
+        handle::TransactionRetry,
+        storage::models::{CompletedTransaction, TransactionDirection, TransactionStatus},
+    },
 };
 use tokio::runtime::Handle;
 use tui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Span, Spans},
-    widgets::{Block, Borders, ListItem, Paragraph, Wrap},
+    widgets::{Block, Borders, Clear, ListItem, Paragraph, Wrap},
     Frame,
 };
+use unicode_width::UnicodeWidthStr;
 
 pub struct TransactionsTab {
     balance: Balance,
     selected_tx_list: SelectedTransactionList,
     pending_list_state: WindowedListState,
     completed_list_state: WindowedListState,
+    // Where the lists were last drawn, to find the transaction that is clicked on
+    pending_list_area: Rect,
+    completed_list_area: Rect,
     detailed_transaction: Option<CompletedTransaction>,
+    // The transaction shown in the expanded view, fetched from the wallet when the view is opened
+    expanded_transaction: Option<CompletedTransaction>,
+    // The path being entered in the export dialog, None when the dialog is closed
+    export_path: Option<String>,
+    // The query being entered in the search bar, None when the search bar is closed
+    search_query: Option<String>,
+    // The transaction whose labels are being edited and the comma separated labels being entered, None when the
+    // dialog is closed
+    label_input: Option<(TxId, String)>,
+    // The query of the filter that is currently applied to the transaction lists
+    active_search: Option<String>,
+    info_message: Option<String>,
     error_message: Option<String>,
-    confirmation_dialog: bool,
+    // The outcome of the last retry of a transaction, shown below the instructions
+    retry_status: Option<(String, Style)>,
+    // The pending transaction that is asked to be cancelled, kept so that the transaction that was confirmed is the
+    // one cancelled even if the pending list changes while the dialog is open
+    cancel_confirmation: Option<CompletedTransaction>,
 }
 
 impl TransactionsTab {
@@ -39,14 +64,42 @@ impl TransactionsTab {
             selected_tx_list: SelectedTransactionList::None,
             pending_list_state: WindowedListState::new(),
             completed_list_state: WindowedListState::new(),
+            pending_list_area: Rect::default(),
+            completed_list_area: Rect::default(),
             detailed_transaction: None,
+            expanded_transaction: None,
+            export_path: None,
+            search_query: None,
+            label_input: None,
+            active_search: None,
+            info_message: None,
             error_message: None,
-            confirmation_dialog: false,
+            retry_status: None,
+            cancel_confirmation: None,
+        }
+    }
+
+    /// Whether a dialog is drawn over the lists
+    fn is_popup_open(&self) -> bool {
+        self.export_path.is_some() ||
+            self.search_query.is_some() ||
+            self.label_input.is_some() ||
+            self.expanded_transaction.is_some() ||
+            self.info_message.is_some() ||
+            self.error_message.is_some() ||
+            self.cancel_confirmation.is_some()
+    }
+
+    fn list_title(&self, title: &str) -> String {
+        match self.active_search.as_ref() {
+            Some(query) => format!("{} - matching \"{}\"", title, query),
+            None => title.to_string(),
         }
     }
 
     fn draw_transaction_lists<B>(&mut self, f: &mut Frame<B>, area: Rect, app_state: &AppState)
     where B: Backend {
+        let theme = app_state.get_theme();
         let (pending_constraint, completed_constraint) = if app_state.get_pending_txs().is_empty() {
             self.selected_tx_list = SelectedTransactionList::CompletedTxs;
             (Constraint::Max(3), Constraint::Min(4))
@@ -59,15 +112,18 @@ impl TransactionsTab {
         let list_areas = Layout::default()
             .constraints([pending_constraint, completed_constraint].as_ref())
             .split(area);
+        self.pending_list_area = list_areas[0];
+        self.completed_list_area = list_areas[1];
 
         let style = if self.selected_tx_list == SelectedTransactionList::PendingTxs {
-            Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)
+            theme.highlight
         } else {
-            Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+            theme.title
         };
         let block = Block::default()
             .borders(Borders::ALL)
-            .title(Span::styled("(P)ending Transactions", style));
+            .border_style(theme.border)
+            .title(Span::styled(self.list_title("(P)ending Transactions"), style));
         f.render_widget(block, list_areas[0]);
 
         // Pending Transactions
@@ -78,54 +134,46 @@ impl TransactionsTab {
         let window = self.pending_list_state.get_start_end();
         let windowed_view = app_state.get_pending_txs_slice(window.0, window.1);
 
-        let text_colors: HashMap<bool, Color> = [(true, Color::DarkGray), (false, Color::Reset)]
-            .iter()
-            .cloned()
-            .collect();
-
         let mut column0_items = Vec::new();
         let mut column1_items = Vec::new();
         let mut column2_items = Vec::new();
         let mut column3_items = Vec::new();
         for t in windowed_view.iter() {
-            let text_color = text_colors.get(&t.cancelled).unwrap_or(&Color::Reset).to_owned();
+            let text_style = if t.cancelled { theme.muted } else { theme.text };
             if t.direction == TransactionDirection::Outbound {
                 column0_items.push(ListItem::new(Span::styled(
                     format!("{}", t.destination_public_key),
-                    Style::default().fg(text_color),
+                    text_style,
                 )));
                 let amount_style = if t.cancelled {
-                    Style::default().fg(Color::Red).add_modifier(Modifier::DIM)
+                    theme.error.add_modifier(Modifier::DIM)
                 } else {
-                    Style::default().fg(Color::Red)
+                    theme.error
                 };
                 column1_items.push(ListItem::new(Span::styled(format!("{}", t.amount), amount_style)));
             } else {
                 column0_items.push(ListItem::new(Span::styled(
                     format!("{}", t.source_public_key),
-                    Style::default().fg(text_color),
+                    text_style,
                 )));
                 let amount_style = if t.cancelled {
-                    Style::default().fg(Color::Green).add_modifier(Modifier::DIM)
+                    theme.success.add_modifier(Modifier::DIM)
                 } else {
-                    Style::default().fg(Color::Green)
+                    theme.success
                 };
                 column1_items.push(ListItem::new(Span::styled(format!("{}", t.amount), amount_style)));
             }
             column2_items.push(ListItem::new(Span::styled(
                 format!("{}", t.timestamp.format("%Y-%m-%d %H:%M:%S")),
-                Style::default().fg(text_color),
-            )));
-            column3_items.push(ListItem::new(Span::styled(
-                t.message.as_str(),
-                Style::default().fg(text_color),
+                text_style,
             )));
+            column3_items.push(ListItem::new(Span::styled(t.message.as_str(), text_style)));
         }
 
         let column_list = MultiColumnList::new()
-            .highlight_style(Style::default().add_modifier(Modifier::BOLD).fg(Color::Magenta))
-            .heading_style(Style::default().fg(Color::Magenta))
-            .max_width(MAX_WIDTH)
+            .highlight_style(theme.highlight)
+            .heading_style(theme.label)
+            .max_width(list_areas[0].width)
             .add_column(Some("Source/Destination Public Key"), Some(67), column0_items)
             .add_column(Some("Amount"), Some(18), column1_items)
             .add_column(Some("Timestamp"), Some(20), column2_items)
@@ -134,13 +182,14 @@ impl TransactionsTab {
 
         //  Completed Transactions
         let style = if self.selected_tx_list == SelectedTransactionList::CompletedTxs {
-            Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)
+            theme.highlight
         } else {
-            Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+            theme.title
         };
         let block = Block::default()
             .borders(Borders::ALL)
-            .title(Span::styled("Completed (T)ransactions", style));
+            .border_style(theme.border)
+            .title(Span::styled(self.list_title("Completed (T)ransactions"), style));
         f.render_widget(block, list_areas[1]);
 
         self.completed_list_state
@@ -157,33 +206,33 @@ impl TransactionsTab {
         let mut column3_items = Vec::new();
 
         for t in windowed_view.iter() {
-            let text_color = text_colors.get(&t.cancelled).unwrap_or(&Color::Reset).to_owned();
+            let text_style = if t.cancelled { theme.muted } else { theme.text };
             if t.direction == TransactionDirection::Outbound {
                 column0_items.push(ListItem::new(Span::styled(
                     format!("{}", t.destination_public_key),
-                    Style::default().fg(text_color),
+                    text_style,
                 )));
                 let amount_style = if t.cancelled {
-                    Style::default().fg(Color::Red).add_modifier(Modifier::DIM)
+                    theme.error.add_modifier(Modifier::DIM)
                 } else {
-                    Style::default().fg(Color::Red)
+                    theme.error
                 };
                 column1_items.push(ListItem::new(Span::styled(format!("{}", t.amount), amount_style)));
             } else {
                 column0_items.push(ListItem::new(Span::styled(
                     format!("{}", t.source_public_key),
-                    Style::default().fg(text_color),
+                    text_style,
                 )));
                 let amount_style = if t.cancelled {
-                    Style::default().fg(Color::Green).add_modifier(Modifier::DIM)
+                    theme.success.add_modifier(Modifier::DIM)
                 } else {
-                    Style::default().fg(Color::Green)
+                    theme.success
                 };
                 column1_items.push(ListItem::new(Span::styled(format!("{}", t.amount), amount_style)));
             }
             column2_items.push(ListItem::new(Span::styled(
                 format!("{}", t.timestamp.format("%Y-%m-%d %H:%M:%S")),
-                Style::default().fg(text_color),
+                text_style,
             )));
             let status = if t.cancelled {
                 "Cancelled".to_string()
@@ -192,13 +241,13 @@ impl TransactionsTab {
             } else {
                 t.status.to_string()
             };
-            column3_items.push(ListItem::new(Span::styled(status, Style::default().fg(text_color))));
+            column3_items.push(ListItem::new(Span::styled(status, text_style)));
         }
 
         let column_list = MultiColumnList::new()
-            .highlight_style(Style::default().add_modifier(Modifier::BOLD).fg(Color::Magenta))
-            .heading_style(Style::default().fg(Color::Magenta))
-            .max_width(MAX_WIDTH)
+            .highlight_style(theme.highlight)
+            .heading_style(theme.label)
+            .max_width(list_areas[1].width)
             .add_column(Some("Source/Destination Public Key"), Some(67), column0_items)
             .add_column(Some("Amount"), Some(18), column1_items)
             .add_column(Some("Timestamp"), Some(20), column2_items)
@@ -209,10 +258,11 @@ impl TransactionsTab {
 
     fn draw_detailed_transaction<B>(&self, f: &mut Frame<B>, area: Rect, app_state: &AppState)
     where B: Backend {
-        let block = Block::default().borders(Borders::ALL).title(Span::styled(
-            "Transaction Details",
-            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
-        ));
+        let theme = app_state.get_theme();
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Transaction Details", theme.title));
         f.render_widget(block, area);
 
         let columns = Layout::default()
@@ -243,18 +293,19 @@ impl TransactionsTab {
             )
             .split(columns[0]);
 
-        let tx_id = Span::styled("TxID:", Style::default().fg(Color::Magenta));
-        let source_public_key = Span::styled("Source Public Key:", Style::default().fg(Color::Magenta));
-        let destination_public_key = Span::styled("Destination Public Key:", Style::default().fg(Color::Magenta));
-        let direction = Span::styled("Direction:", Style::default().fg(Color::Magenta));
-        let amount = Span::styled("Amount:", Style::default().fg(Color::Magenta));
-        let fee = Span::styled("Fee:", Style::default().fg(Color::Magenta));
-        let status = Span::styled("Status:", Style::default().fg(Color::Magenta));
-        let message = Span::styled("Message:", Style::default().fg(Color::Magenta));
-        let timestamp = Span::styled("Timestamp:", Style::default().fg(Color::Magenta));
-        let excess = Span::styled("Excess:", Style::default().fg(Color::Magenta));
-        let confirmations = Span::styled("Confirmations:", Style::default().fg(Color::Magenta));
-        let mined_height = Span::styled("Mined Height:", Style::default().fg(Color::Magenta));
+        let tx_id = Span::styled("TxID:", theme.label);
+        let source_public_key = Span::styled("Source Public Key:", theme.label);
+        let destination_public_key = Span::styled("Destination Public Key:", theme.label);
+        let direction = Span::styled("Direction:", theme.label);
+        let amount = Span::styled("Amount:", theme.label);
+        let fee = Span::styled("Fee:", theme.label);
+        let status = Span::styled("Status:", theme.label);
+        let message = Span::styled("Message:", theme.label);
+        let timestamp = Span::styled("Timestamp:", theme.label);
+        let excess = Span::styled("Excess:", theme.label);
+        let confirmations = Span::styled("Confirmations:", theme.label);
+        let mined_height = Span::styled("Mined Height:", theme.label);
+        let labels = Span::styled("Labels:", theme.label);
         let paragraph = Paragraph::new(tx_id).wrap(Wrap { trim: true });
         f.render_widget(paragraph, label_layout[0]);
         let paragraph = Paragraph::new(source_public_key).wrap(Wrap { trim: true });
@@ -279,8 +330,9 @@ impl TransactionsTab {
         f.render_widget(paragraph, label_layout[10]);
         let paragraph = Paragraph::new(mined_height).wrap(Wrap { trim: true });
         f.render_widget(paragraph, label_layout[11]);
+        let paragraph = Paragraph::new(labels).wrap(Wrap { trim: true });
+        f.render_widget(paragraph, label_layout[12]);
         // Content:
-        let required_confirmations = app_state.get_required_confirmations();
         if let Some(tx) = self.detailed_transaction.as_ref() {
             let content_layout = Layout::default()
                 .constraints(
@@ -302,64 +354,41 @@ impl TransactionsTab {
                     .as_ref(),
                 )
                 .split(columns[1]);
-            let tx_id = Span::styled(format!("{}", tx.tx_id), Style::default().fg(Color::White));
+            let tx_id = Span::styled(format!("{}", tx.tx_id), theme.text);
 
             let source_public_key =
                 if tx.status == TransactionStatus::Pending && tx.direction == TransactionDirection::Outbound {
                     Span::raw("")
                 } else {
-                    Span::styled(format!("{}", tx.source_public_key), Style::default().fg(Color::White))
+                    Span::styled(format!("{}", tx.source_public_key), theme.text)
                 };
             let destination_public_key =
                 if tx.status == TransactionStatus::Pending && tx.direction == TransactionDirection::Inbound {
                     Span::raw("")
                 } else {
-                    Span::styled(
-                        format!("{}", tx.destination_public_key),
-                        Style::default().fg(Color::White),
-                    )
+                    Span::styled(format!("{}", tx.destination_public_key), theme.text)
                 };
-            let direction = Span::styled(format!("{}", tx.direction), Style::default().fg(Color::White));
-            let amount = Span::styled(format!("{}", tx.amount), Style::default().fg(Color::White));
-            let fee = Span::styled(format!("{}", tx.fee), Style::default().fg(Color::White));
-            let status_msg = if tx.cancelled {
-                "Cancelled".to_string()
-            } else if !tx.valid {
-                "Invalid".to_string()
-            } else {
-                tx.status.to_string()
-            };
-            let status = Span::styled(status_msg, Style::default().fg(Color::White));
-            let message = Span::styled(tx.message.as_str(), Style::default().fg(Color::White));
-            let timestamp = Span::styled(
-                format!("{}", tx.timestamp.format("%Y-%m-%d %H:%M:%S")),
-                Style::default().fg(Color::White),
-            );
+            let direction = Span::styled(format!("{}", tx.direction), theme.text);
+            let amount = Span::styled(format!("{}", tx.amount), theme.text);
+            let fee = Span::styled(format!("{}", tx.fee), theme.text);
+            let status = Span::styled(status_message(tx), theme.text);
+            let message = Span::styled(tx.message.as_str(), theme.text);
+            let timestamp = Span::styled(format!("{}", tx.timestamp.format("%Y-%m-%d %H:%M:%S")), theme.text);
             let excess_hex = if tx.transaction.body.kernels().is_empty() {
                 "".to_string()
             } else {
                 tx.transaction.body.kernels()[0].excess_sig.get_signature().to_hex()
             };
-            let excess = Span::styled(excess_hex.as_str(), Style::default().fg(Color::White));
-            let confirmation_count = app_state.get_confirmations(&tx.tx_id);
-            let confirmations_msg = if tx.status == TransactionStatus::MinedConfirmed && !tx.cancelled {
-                format!("{} required confirmations met", required_confirmations)
-            } else if tx.status == TransactionStatus::MinedUnconfirmed && !tx.cancelled {
-                if let Some(count) = confirmation_count {
-                    format!("{} of {} required confirmations met", count, required_confirmations)
-                } else {
-                    "N/A".to_string()
-                }
-            } else {
-                "N/A".to_string()
-            };
-            let confirmations = Span::styled(confirmations_msg.as_str(), Style::default().fg(Color::White));
+            let excess = Span::styled(excess_hex.as_str(), theme.text);
+            let confirmations_msg = confirmations_message(tx, app_state);
+            let confirmations = Span::styled(confirmations_msg.as_str(), theme.text);
             let mined_height = Span::styled(
                 tx.mined_height
                     .map(|m| m.to_string())
                     .unwrap_or_else(|| "N/A".to_string()),
-                Style::default().fg(Color::White),
+                theme.text,
             );
+            let labels = Span::styled(app_state.get_transaction_labels(&tx.tx_id).join(", "), theme.text);
 
             let paragraph = Paragraph::new(tx_id).wrap(Wrap { trim: true });
             f.render_widget(paragraph, content_layout[0]);
@@ -385,23 +414,448 @@ impl TransactionsTab {
             f.render_widget(paragraph, content_layout[10]);
             let paragraph = Paragraph::new(mined_height).wrap(Wrap { trim: true });
             f.render_widget(paragraph, content_layout[11]);
+            let paragraph = Paragraph::new(labels).wrap(Wrap { trim: true });
+            f.render_widget(paragraph, content_layout[12]);
+        }
+    }
+
+    fn draw_expanded_transaction<B>(&self, f: &mut Frame<B>, area: Rect, app_state: &AppState)
+    where B: Backend {
+        let theme = app_state.get_theme();
+        let tx = match self.expanded_transaction.as_ref() {
+            Some(tx) => tx,
+            None => return,
+        };
+        let popup_area = centered_rect_absolute(140, area.height.saturating_sub(4).max(10), area);
+        f.render_widget(Clear, popup_area);
+
+        let label = |text: &'static str| Span::styled(text, theme.label);
+        let value = |text: String| Span::styled(text, theme.text);
+
+        let mut lines = vec![
+            Spans::from(vec![label("TxID: "), value(tx.tx_id.to_string())]),
+            Spans::from(vec![label("Status: "), value(status_message(tx))]),
+            Spans::from(vec![
+                label("Cancellation Reason: "),
+                value(cancellation_reason(tx).unwrap_or("N/A").to_string()),
+            ]),
+            Spans::from(vec![label("Amount: "), value(tx.amount.to_string())]),
+            Spans::from(vec![label("Fee: "), value(tx.fee.to_string())]),
+            Spans::from(vec![label("Message: "), value(tx.message.clone())]),
+            Spans::from(vec![
+                label("Labels: "),
+                value(app_state.get_transaction_labels(&tx.tx_id).join(", ")),
+            ]),
+            Spans::from(vec![
+                label("Confirmations: "),
+                value(confirmations_message(tx, app_state)),
+            ]),
+            Spans::from(vec![
+                label("Mined Height: "),
+                value(
+                    tx.mined_height
+                        .map(|m| m.to_string())
+                        .unwrap_or_else(|| "N/A".to_string()),
+                ),
+            ]),
+            Spans::from(""),
+        ];
+
+        let body = &tx.transaction.body;
+        if body.kernels().is_empty() {
+            lines.push(Spans::from(vec![label("Kernel: "), value("N/A".to_string())]));
+        }
+        for kernel in body.kernels() {
+            lines.push(Spans::from(vec![
+                label("Kernel Excess: "),
+                value(kernel.excess.to_hex()),
+            ]));
+            lines.push(Spans::from(vec![
+                label("Excess Signature Nonce: "),
+                value(kernel.excess_sig.get_public_nonce().to_hex()),
+            ]));
+            lines.push(Spans::from(vec![
+                label("Excess Signature: "),
+                value(kernel.excess_sig.get_signature().to_hex()),
+            ]));
+            lines.push(Spans::from(vec![
+                label("Kernel Fee: "),
+                value(kernel.fee.to_string()),
+                label("  Lock Height: "),
+                value(kernel.lock_height.to_string()),
+            ]));
+        }
+        lines.push(Spans::from(""));
+        for input in body.inputs() {
+            lines.push(Spans::from(vec![
+                label("Input Commitment: "),
+                value(input.commitment.to_hex()),
+            ]));
+        }
+        for output in body.outputs() {
+            lines.push(Spans::from(vec![
+                label("Output Commitment: "),
+                value(output.commitment.to_hex()),
+                label("  Maturity: "),
+                value(output.features.maturity.to_string()),
+            ]));
+        }
+        if body.inputs().is_empty() && body.outputs().is_empty() {
+            lines.push(Spans::from(vec![
+                label("Commitments: "),
+                value("N/A until the transaction has been completed".to_string()),
+            ]));
+        }
+
+        let paragraph = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(theme.border)
+                    .title(Span::styled("Transaction Details (Esc or Enter to close)", theme.title)),
+            )
+            .wrap(Wrap { trim: false });
+        f.render_widget(paragraph, popup_area);
+    }
+
+    fn draw_export_dialog<B>(&self, f: &mut Frame<B>, area: Rect, theme: &Theme)
+    where B: Backend {
+        let path = match self.export_path.as_ref() {
+            Some(path) => path,
+            None => return,
+        };
+        let popup_area = centered_rect_absolute(100, 7, area);
+        f.render_widget(Clear, popup_area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Export Transaction History", theme.title));
+        f.render_widget(block, popup_area);
+        let vert_chunks = Layout::default()
+            .constraints([Constraint::Length(1), Constraint::Length(3)].as_ref())
+            .margin(1)
+            .split(popup_area);
+
+        let instructions = Paragraph::new(Spans::from(vec![
+            Span::raw("Enter the path of a "),
+            Span::styled(".csv", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" or "),
+            Span::styled(".json", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" file, "),
+            Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to export, "),
+            Span::styled("Esc", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to cancel."),
+        ]));
+        f.render_widget(instructions, vert_chunks[0]);
+
+        let input = Paragraph::new(path.as_str()).style(theme.label).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.border)
+                .title("File:"),
+        );
+        f.render_widget(input, vert_chunks[1]);
+        f.set_cursor(
+            // Put cursor past the end of the input text
+            vert_chunks[1].x + path.width() as u16 + 1,
+            // Move one line down, from the border to the input line
+            vert_chunks[1].y + 1,
+        );
+    }
+
+    fn draw_search_bar<B>(&self, f: &mut Frame<B>, area: Rect, theme: &Theme)
+    where B: Backend {
+        let query = match self.search_query.as_ref() {
+            Some(query) => query,
+            None => return,
+        };
+        let popup_area = centered_rect_absolute(100, 7, area);
+        f.render_widget(Clear, popup_area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Search Transactions", theme.title));
+        f.render_widget(block, popup_area);
+        let vert_chunks = Layout::default()
+            .constraints([Constraint::Length(1), Constraint::Length(3)].as_ref())
+            .margin(1)
+            .split(popup_area);
+
+        let instructions = Paragraph::new(Spans::from(vec![
+            Span::raw("Search the message, tx id or emoji ID, or use "),
+            Span::styled("id:", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(", "),
+            Span::styled("amount:min..max", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(", "),
+            Span::styled("status:", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(". Empty to clear, "),
+            Span::styled("Esc", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to cancel."),
+        ]));
+        f.render_widget(instructions, vert_chunks[0]);
+
+        let input = Paragraph::new(query.as_str()).style(theme.label).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.border)
+                .title("/"),
+        );
+        f.render_widget(input, vert_chunks[1]);
+        f.set_cursor(
+            // Put cursor past the end of the input text
+            vert_chunks[1].x + query.width() as u16 + 1,
+            // Move one line down, from the border to the input line
+            vert_chunks[1].y + 1,
+        );
+    }
+
+    fn draw_label_dialog<B>(&self, f: &mut Frame<B>, area: Rect, theme: &Theme)
+    where B: Backend {
+        let (tx_id, labels) = match self.label_input.as_ref() {
+            Some(input) => input,
+            None => return,
+        };
+        let popup_area = centered_rect_absolute(100, 7, area);
+        f.render_widget(Clear, popup_area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled(format!("Labels of Transaction {}", tx_id), theme.title));
+        f.render_widget(block, popup_area);
+        let vert_chunks = Layout::default()
+            .constraints([Constraint::Length(1), Constraint::Length(3)].as_ref())
+            .margin(1)
+            .split(popup_area);
+
+        let instructions = Paragraph::new(Spans::from(vec![
+            Span::raw("Enter labels separated by commas, "),
+            Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to save, "),
+            Span::styled("Esc", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to cancel. Empty to remove all labels."),
+        ]));
+        f.render_widget(instructions, vert_chunks[0]);
+
+        let input = Paragraph::new(labels.as_str()).style(theme.label).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.border)
+                .title("Labels:"),
+        );
+        f.render_widget(input, vert_chunks[1]);
+        f.set_cursor(
+            // Put cursor past the end of the input text
+            vert_chunks[1].x + labels.width() as u16 + 1,
+            // Move one line down, from the border to the input line
+            vert_chunks[1].y + 1,
+        );
+    }
+
+    fn edit_labels(&mut self, app_state: &AppState) {
+        if let Some(tx) = self.detailed_transaction.as_ref() {
+            self.label_input = Some((tx.tx_id, app_state.get_transaction_labels(&tx.tx_id).join(", ")));
+        }
+    }
+
+    fn save_labels(&mut self, tx_id: TxId, input: String, app_state: &mut AppState) {
+        if let Err(e) = Handle::current().block_on(app_state.set_transaction_labels(tx_id, parse_labels(&input))) {
+            self.error_message = Some(format!(
+                "Could not save the transaction labels.\n{}\nPress Enter to continue.",
+                e
+            ));
+        }
+    }
+
+    fn apply_search(&mut self, query: String, app_state: &mut AppState) {
+        let query = query.trim().to_string();
+        let filter = if query.is_empty() {
+            None
+        } else {
+            match query.parse::<TransactionFilter>() {
+                Ok(filter) => Some(filter),
+                Err(e) => {
+                    self.error_message = Some(format!("Invalid search: {}\nPress Enter to continue.", e));
+                    return;
+                },
+            }
+        };
+        self.active_search = filter.as_ref().map(|_| query);
+        Handle::current().block_on(app_state.set_transaction_filter(filter));
+        self.pending_list_state.select(None);
+        self.completed_list_state.select(None);
+        self.detailed_transaction = None;
+    }
+
+    /// Resend the selected pending outbound transaction, or rebroadcast the selected completed transaction that is not
+    /// mined yet
+    fn retry_transaction(&mut self, app_state: &AppState) {
+        let theme = app_state.get_theme();
+        let tx = match self.detailed_transaction.as_ref() {
+            Some(tx) => tx,
+            None => return,
+        };
+        self.retry_status = Some(
+            match Handle::current().block_on(app_state.retry_transaction(tx.tx_id)) {
+                Ok(TransactionRetry::Resent) => (
+                    format!("Transaction {} was sent to the recipient again", tx.tx_id),
+                    theme.success,
+                ),
+                Ok(TransactionRetry::BroadcastStarted) => (
+                    format!("Transaction {} is being broadcast to the base node", tx.tx_id),
+                    theme.success,
+                ),
+                Ok(TransactionRetry::BroadcastInProgress) => (
+                    format!(
+                        "Transaction {} is already being broadcast, and is resubmitted until it is mined",
+                        tx.tx_id
+                    ),
+                    theme.warning,
+                ),
+                Err(e) => (format!("Could not retry transaction {}: {}", tx.tx_id, e), theme.error),
+            },
+        );
+    }
+
+    fn export_transactions(&mut self, path: String, app_state: &AppState) {
+        match Handle::current().block_on(app_state.export_transactions(PathBuf::from(&path))) {
+            Ok(count) => {
+                self.info_message = Some(format!(
+                    "Exported {} transactions to {}\nPress Enter to continue.",
+                    count, path
+                ))
+            },
+            Err(e) => {
+                self.error_message = Some(format!(
+                    "Could not export the transactions.\n{}\nPress Enter to continue.",
+                    e
+                ))
+            },
+        }
+    }
+
+    /// Select the transaction drawn at the given cell of one of the lists
+    fn select_transaction_at(&mut self, app_state: &AppState, column: u16, row: u16) {
+        // The list items start below the top border and the column headings
+        let line_in = |area: Rect| {
+            if column > area.x &&
+                column < area.x + area.width.saturating_sub(1) &&
+                row >= area.y + 2 &&
+                row < area.y + area.height.saturating_sub(1)
+            {
+                Some((row - area.y - 2) as usize)
+            } else {
+                None
+            }
+        };
+        if let Some(index) = line_in(self.pending_list_area).and_then(|l| self.pending_list_state.item_at(l)) {
+            self.completed_list_state.select(None);
+            self.selected_tx_list = SelectedTransactionList::PendingTxs;
+            self.pending_list_state.select(Some(index));
+            self.detailed_transaction = app_state.get_pending_tx(index).cloned();
+        } else if let Some(index) = line_in(self.completed_list_area).and_then(|l| self.completed_list_state.item_at(l))
+        {
+            self.pending_list_state.select(None);
+            self.selected_tx_list = SelectedTransactionList::CompletedTxs;
+            self.completed_list_state.select(Some(index));
+            self.detailed_transaction = app_state.get_completed_tx(index).cloned();
+        }
+    }
+
+    fn expand_transaction(&mut self, app_state: &AppState) {
+        let tx_id = match self.detailed_transaction.as_ref() {
+            Some(tx) => tx.tx_id,
+            None => return,
+        };
+        match Handle::current().block_on(app_state.get_transaction_details(tx_id)) {
+            Ok(Some(tx)) => self.expanded_transaction = Some(tx),
+            Ok(None) => {
+                self.error_message = Some("The transaction could not be found.\nPress Enter to continue.".to_string())
+            },
+            Err(e) => {
+                self.error_message = Some(format!(
+                    "Could not fetch the transaction details.\n{}\nPress Enter to continue.",
+                    e
+                ))
+            },
+        }
+    }
+}
+
+/// Split comma separated input into labels, the transaction service drops empty and duplicate labels
+fn parse_labels(input: &str) -> Vec<String> {
+    input
+        .split(',')
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect()
+}
+
+fn status_message(tx: &CompletedTransaction) -> String {
+    if tx.cancelled {
+        "Cancelled".to_string()
+    } else if !tx.valid {
+        "Invalid".to_string()
+    } else {
+        tx.status.to_string()
+    }
+}
+
+fn confirmations_message(tx: &CompletedTransaction, app_state: &AppState) -> String {
+    let required_confirmations = app_state.get_required_confirmations();
+    if tx.status == TransactionStatus::MinedConfirmed && !tx.cancelled {
+        format!("{} required confirmations met", required_confirmations)
+    } else if tx.status == TransactionStatus::MinedUnconfirmed && !tx.cancelled {
+        if let Some(count) = app_state.get_confirmations(&tx.tx_id) {
+            format!("{} of {} required confirmations met", count, required_confirmations)
+        } else {
+            "N/A".to_string()
         }
+    } else {
+        "N/A".to_string()
+    }
+}
+
+/// The wallet does not store why a transaction was cancelled, so the reason is inferred from the state it was
+/// cancelled in.
+fn cancellation_reason(tx: &CompletedTransaction) -> Option<&'static str> {
+    if !tx.cancelled {
+        return None;
     }
+    let reason = if tx.status == TransactionStatus::Pending {
+        "Cancelled before it was completed, by the user or because the counterparty did not respond in time"
+    } else if tx.coinbase_block_height.is_some() {
+        "The coinbase was not mined, e.g. because another block was mined at its height"
+    } else {
+        "Rejected by the base node"
+    };
+    Some(reason)
 }
 
 impl<B: Backend> Component<B> for TransactionsTab {
     fn draw(&mut self, f: &mut Frame<B>, area: Rect, app_state: &AppState) {
-        let areas = Layout::default()
-            .constraints(
-                [
-                    Constraint::Length(3),
-                    Constraint::Length(1),
-                    Constraint::Min(10),
-                    Constraint::Length(14),
-                ]
-                .as_ref(),
-            )
-            .split(area);
+        let theme = app_state.get_theme();
+        let layout_mode = LayoutMode::for_width(area.width);
+        let mut constraints = vec![
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(10),
+        ];
+        if !layout_mode.is_wide() {
+            constraints.push(Constraint::Length(15));
+        }
+        let areas = Layout::default().constraints(constraints.as_ref()).split(area);
+        // On wide terminals the details of the selected transaction are shown beside the lists instead of below them
+        let (lists_area, details_area) = if layout_mode.is_wide() {
+            let panes = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Min(MAX_WIDTH), Constraint::Length(SIDE_PANE_WIDTH)].as_ref())
+                .split(areas[3]);
+            (panes[0], panes[1])
+        } else {
+            (areas[3], areas[4])
+        };
 
         self.balance.draw(f, areas[0], app_state);
 
@@ -419,60 +873,161 @@ impl<B: Backend> Component<B> for TransactionsTab {
             Style::default().add_modifier(Modifier::BOLD),
         ));
         span_vec.push(Span::raw(" selects a transaction, "));
+        span_vec.push(Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)));
+        span_vec.push(Span::raw(" shows its full details, "));
         span_vec.push(Span::styled("C", Style::default().add_modifier(Modifier::BOLD)));
         span_vec.push(Span::raw(" cancels a selected Pending Tx, "));
+        span_vec.push(Span::styled("R", Style::default().add_modifier(Modifier::BOLD)));
+        span_vec.push(Span::raw(" resends or rebroadcasts it, "));
+        span_vec.push(Span::styled("L", Style::default().add_modifier(Modifier::BOLD)));
+        span_vec.push(Span::raw(" edits its labels, "));
+        span_vec.push(Span::styled("/", Style::default().add_modifier(Modifier::BOLD)));
+        span_vec.push(Span::raw(" searches, "));
+        span_vec.push(Span::styled("X", Style::default().add_modifier(Modifier::BOLD)));
+        span_vec.push(Span::raw(" exports the history, "));
         span_vec.push(Span::styled("Esc", Style::default().add_modifier(Modifier::BOLD)));
         span_vec.push(Span::raw(" exits the list."));
 
         let instructions = Paragraph::new(Spans::from(span_vec)).wrap(Wrap { trim: true });
         f.render_widget(instructions, areas[1]);
 
-        self.draw_transaction_lists(f, areas[2], app_state);
-        self.draw_detailed_transaction(f, areas[3], app_state);
+        if let Some((msg, style)) = self.retry_status.as_ref() {
+            f.render_widget(Paragraph::new(Span::styled(msg.as_str(), *style)), areas[2]);
+        }
+
+        self.draw_transaction_lists(f, lists_area, app_state);
+        self.draw_detailed_transaction(f, details_area, app_state);
+        self.draw_expanded_transaction(f, area, app_state);
+        self.draw_export_dialog(f, area, theme);
+        self.draw_search_bar(f, area, theme);
+        self.draw_label_dialog(f, area, theme);
+
+        if let Some(msg) = self.info_message.clone() {
+            draw_dialog(f, area, "Export Complete".to_string(), msg, theme.success, 120, 9);
+        }
 
         if let Some(msg) = self.error_message.clone() {
-            draw_dialog(f, area, "Error!".to_string(), msg, Color::Red, 120, 9);
+            draw_dialog(f, area, "Error!".to_string(), msg, theme.error, 120, 9);
         }
 
-        if self.confirmation_dialog {
+        if let Some(tx) = self.cancel_confirmation.as_ref() {
+            let counterparty = if tx.direction == TransactionDirection::Outbound {
+                format!("to {}", tx.destination_public_key)
+            } else {
+                format!("from {}", tx.source_public_key)
+            };
             draw_dialog(
                 f,
                 area,
                 "Confirm Cancellation".to_string(),
-                "Are you sure you want to cancel this pending transaction? \n(Y)es / (N)o".to_string(),
-                Color::Red,
+                format!(
+                    "Are you sure you want to cancel the pending transaction of {} {}?\nIts funds are released once \
+                     it is cancelled.\n(Y)es / (N)o",
+                    tx.amount, counterparty
+                ),
+                theme.error,
                 120,
                 9,
             );
         }
     }
 
+    fn is_editing(&self) -> bool {
+        self.export_path.is_some() || self.search_query.is_some() || self.label_input.is_some()
+    }
+
+    fn key_bindings(&self) -> Vec<(&'static str, &'static str)> {
+        if self.cancel_confirmation.is_some() {
+            return vec![("Y", "Cancel the transaction"), ("N/Esc", "Keep the transaction")];
+        }
+        vec![
+            ("P", "Select the pending transactions"),
+            ("T", "Select the completed transactions"),
+            ("Up/Down Arrow", "Select a transaction in the list"),
+            ("Enter", "Show all the details of the selected transaction"),
+            ("C", "Cancel the selected pending transaction"),
+            ("R", "Resend or rebroadcast the selected transaction"),
+            ("L", "Edit the labels of the selected transaction"),
+            ("/", "Search the transactions"),
+            ("X", "Export the transactions to a CSV file"),
+            ("Esc", "Clear the selection"),
+        ]
+    }
+
     fn on_key(&mut self, app_state: &mut AppState, c: char) {
         if self.error_message.is_some() && '\n' == c {
             self.error_message = None;
             return;
         }
 
-        if self.confirmation_dialog {
-            if 'n' == c {
-                self.confirmation_dialog = false;
-                return;
-            } else if 'y' == c {
-                if self.selected_tx_list == SelectedTransactionList::PendingTxs {
-                    if let Some(i) = self.pending_list_state.selected() {
-                        if let Some(pending_tx) = app_state.get_pending_tx(i).cloned() {
-                            if let Err(e) = Handle::current().block_on(app_state.cancel_transaction(pending_tx.tx_id)) {
-                                self.error_message = Some(format!(
-                                    "Could not cancel pending transaction.\n{}\nPress Enter to continue.",
-                                    e
-                                ));
+        if self.info_message.is_some() && '\n' == c {
+            self.info_message = None;
+            return;
+        }
+
+        if let Some(path) = self.export_path.as_mut() {
+            if '\n' == c {
+                if let Some(path) = self.export_path.take() {
+                    self.export_transactions(path, app_state);
+                }
+            } else {
+                path.push(c);
+            }
+            return;
+        }
+
+        if let Some(query) = self.search_query.as_mut() {
+            if '\n' == c {
+                if let Some(query) = self.search_query.take() {
+                    self.apply_search(query, app_state);
+                }
+            } else {
+                query.push(c);
+            }
+            return;
+        }
+
+        if let Some((_, labels)) = self.label_input.as_mut() {
+            if '\n' == c {
+                if let Some((tx_id, labels)) = self.label_input.take() {
+                    self.save_labels(tx_id, labels, app_state);
+                }
+            } else {
+                labels.push(c);
+            }
+            return;
+        }
+
+        if self.expanded_transaction.is_some() {
+            if '\n' == c {
+                self.expanded_transaction = None;
+            }
+            return;
+        }
+
+        if let Some(tx) = self.cancel_confirmation.as_ref() {
+            match c {
+                'n' => self.cancel_confirmation = None,
+                'y' => {
+                    let tx_id = tx.tx_id;
+                    self.cancel_confirmation = None;
+                    match Handle::current().block_on(app_state.cancel_transaction(tx_id)) {
+                        Ok(_) => {
+                            if self.detailed_transaction.as_ref().map(|t| t.tx_id) == Some(tx_id) {
+                                self.detailed_transaction = None;
                             }
-                        }
+                        },
+                        Err(e) => {
+                            self.error_message = Some(format!(
+                                "Could not cancel pending transaction.\n{}\nPress Enter to continue.",
+                                e
+                            ))
+                        },
                     }
-                }
-                self.confirmation_dialog = false;
-                return;
+                },
+                _ => {},
             }
+            return;
         }
 
         match c {
@@ -503,26 +1058,36 @@ impl<B: Backend> Component<B> for TransactionsTab {
                 };
                 self.detailed_transaction = app_state.get_completed_tx(idx).cloned();
             },
+            'x' => self.export_path = Some("transactions.csv".to_string()),
+            'r' => self.retry_transaction(app_state),
+            'l' => self.edit_labels(app_state),
+            '/' => self.search_query = Some(self.active_search.clone().unwrap_or_default()),
             'c' => {
                 if self.selected_tx_list == SelectedTransactionList::PendingTxs {
-                    self.confirmation_dialog = true;
+                    self.cancel_confirmation = self
+                        .pending_list_state
+                        .selected()
+                        .and_then(|i| app_state.get_pending_tx(i).cloned());
                     return;
                 }
             },
-            '\n' => match self.selected_tx_list {
-                SelectedTransactionList::None => {},
-                SelectedTransactionList::PendingTxs => {
-                    self.detailed_transaction = match self.pending_list_state.selected() {
-                        None => None,
-                        Some(i) => app_state.get_pending_tx(i).cloned(),
-                    };
-                },
-                SelectedTransactionList::CompletedTxs => {
-                    self.detailed_transaction = match self.completed_list_state.selected() {
-                        None => None,
-                        Some(i) => app_state.get_completed_tx(i).cloned(),
-                    };
-                },
+            '\n' => {
+                match self.selected_tx_list {
+                    SelectedTransactionList::None => {},
+                    SelectedTransactionList::PendingTxs => {
+                        self.detailed_transaction = match self.pending_list_state.selected() {
+                            None => None,
+                            Some(i) => app_state.get_pending_tx(i).cloned(),
+                        };
+                    },
+                    SelectedTransactionList::CompletedTxs => {
+                        self.detailed_transaction = match self.completed_list_state.selected() {
+                            None => None,
+                            Some(i) => app_state.get_completed_tx(i).cloned(),
+                        };
+                    },
+                }
+                self.expand_transaction(app_state);
             },
             _ => {},
         }
@@ -574,12 +1139,51 @@ impl<B: Backend> Component<B> for TransactionsTab {
         }
     }
 
+    fn on_click(&mut self, app_state: &mut AppState, column: u16, row: u16) {
+        if self.is_popup_open() {
+            return;
+        }
+        self.select_transaction_at(app_state, column, row);
+    }
+
     fn on_esc(&mut self, _app_state: &mut AppState) {
+        if self.cancel_confirmation.is_some() {
+            self.cancel_confirmation = None;
+            return;
+        }
+        if self.export_path.is_some() {
+            self.export_path = None;
+            return;
+        }
+        if self.search_query.is_some() {
+            self.search_query = None;
+            return;
+        }
+        if self.label_input.is_some() {
+            self.label_input = None;
+            return;
+        }
+        if self.expanded_transaction.is_some() {
+            self.expanded_transaction = None;
+            return;
+        }
         self.selected_tx_list = SelectedTransactionList::None;
         self.pending_list_state.select(None);
         self.completed_list_state.select(None);
         self.detailed_transaction = None;
     }
+
+    fn on_backspace(&mut self, _app_state: &mut AppState) {
+        if let Some(path) = self.export_path.as_mut() {
+            let _ = path.pop();
+        }
+        if let Some(query) = self.search_query.as_mut() {
+            let _ = query.pop();
+        }
+        if let Some((_, labels)) = self.label_input.as_mut() {
+            let _ = labels.pop();
+        }
+    }
 }
 
 #[derive(PartialEq)]
@@ -588,3 +1192,17 @@ pub enum SelectedTransactionList {
     PendingTxs,
     CompletedTxs,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn labels_are_split_on_commas() {
+        assert_eq!(parse_labels(" rent, food ,,  "), vec![
+            "rent".to_string(),
+            "food".to_string()
+        ]);
+        assert!(parse_labels("  ").is_empty());
+    }
+}
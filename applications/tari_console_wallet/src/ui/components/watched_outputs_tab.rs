@@ -0,0 +1,116 @@
+use crate::ui::{
+    components::{balance::Balance, Component},
+    state::AppState,
+    widgets::{MultiColumnList, WindowedListState},
+    Theme,
+};
+use tari_crypto::tari_utilities::hex::Hex;
+use tari_wallet::storage::database::WatchedOutput;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, ListItem, Paragraph, Wrap},
+    Frame,
+};
+
+/// The outputs that a watch-only wallet found with its view key, newest first
+pub struct WatchedOutputsTab {
+    balance: Balance,
+    list_state: WindowedListState,
+}
+
+impl WatchedOutputsTab {
+    pub fn new() -> Self {
+        Self {
+            balance: Balance::new(),
+            list_state: WindowedListState::new(),
+        }
+    }
+
+    fn draw_outputs<B>(&mut self, f: &mut Frame<B>, area: Rect, outputs: &[WatchedOutput], theme: &Theme)
+    where B: Backend {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border)
+            .title(Span::styled("Watched Outputs", theme.title));
+        f.render_widget(block, area);
+
+        let areas = Layout::default()
+            .constraints([Constraint::Length(2), Constraint::Min(1)].as_ref())
+            .margin(1)
+            .split(area);
+
+        let unspent = outputs.iter().filter(|o| o.spent_at.is_none()).count();
+        let summary = Paragraph::new(Spans::from(vec![
+            Span::raw("The chain is scanned for new outputs every few minutes. "),
+            Span::styled(
+                format!("{} of {} outputs", unspent, outputs.len()),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(" found so far are unspent."),
+        ]))
+        .wrap(Wrap { trim: true });
+        f.render_widget(summary, areas[0]);
+
+        self.list_state.set_num_items(outputs.len());
+        let mut list_state = self
+            .list_state
+            .get_list_state((areas[1].height as usize).saturating_sub(1));
+        let (start, end) = self.list_state.get_start_end();
+        let windowed_view = outputs.get(start..end).unwrap_or_default();
+
+        let mut column0_items = Vec::with_capacity(windowed_view.len());
+        let mut column1_items = Vec::with_capacity(windowed_view.len());
+        let mut column2_items = Vec::with_capacity(windowed_view.len());
+        let mut column3_items = Vec::with_capacity(windowed_view.len());
+        for o in windowed_view.iter() {
+            let style = if o.spent_at.is_some() { theme.muted } else { theme.text };
+            column0_items.push(ListItem::new(Span::styled(o.value.to_string(), style)));
+            column1_items.push(ListItem::new(Span::styled(
+                o.detected_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                style,
+            )));
+            column2_items.push(ListItem::new(Span::styled(
+                o.spent_at
+                    .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+                    .unwrap_or_else(|| "Unspent".to_string()),
+                style,
+            )));
+            column3_items.push(ListItem::new(Span::styled(o.hash.to_hex(), style)));
+        }
+        let column_list = MultiColumnList::new()
+            .highlight_style(theme.highlight)
+            .heading_style(theme.label)
+            .max_width(areas[1].width)
+            .add_column(Some("Value"), Some(20), column0_items)
+            .add_column(Some("Found"), Some(21), column1_items)
+            .add_column(Some("Spent"), Some(21), column2_items)
+            .add_column(Some("Output Hash"), None, column3_items);
+        column_list.render(f, areas[1], &mut list_state);
+    }
+}
+
+impl<B: Backend> Component<B> for WatchedOutputsTab {
+    fn draw(&mut self, f: &mut Frame<B>, area: Rect, app_state: &AppState) {
+        let areas = Layout::default()
+            .constraints([Constraint::Length(3), Constraint::Min(5)].as_ref())
+            .split(area);
+
+        self.balance.draw(f, areas[0], app_state);
+        self.draw_outputs(f, areas[1], app_state.get_watched_outputs(), app_state.get_theme());
+    }
+
+    fn key_bindings(&self) -> Vec<(&'static str, &'static str)> {
+        vec![("Up/Down Arrow", "Select a watched output")]
+    }
+
+    fn on_up(&mut self, _app_state: &mut AppState) {
+        self.list_state.previous();
+    }
+
+    fn on_down(&mut self, _app_state: &mut AppState) {
+        self.list_state.next();
+    }
+}
@@ -0,0 +1,190 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{collections::HashMap, str::FromStr};
+use thiserror::Error;
+
+/// The UI actions that can be bound to a key in the `[wallet.ui.keys]` section of the config file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyAction {
+    Up,
+    Down,
+    PreviousTab,
+    NextTab,
+    Back,
+    Quit,
+}
+
+impl KeyAction {
+    pub const ALL: [KeyAction; 6] = [
+        KeyAction::Up,
+        KeyAction::Down,
+        KeyAction::PreviousTab,
+        KeyAction::NextTab,
+        KeyAction::Back,
+        KeyAction::Quit,
+    ];
+
+    /// The keys that perform the action whether or not it is bound in the key map
+    pub fn default_keys(self) -> &'static str {
+        match self {
+            KeyAction::Up => "Up Arrow",
+            KeyAction::Down => "Down Arrow",
+            KeyAction::PreviousTab => "Left Arrow",
+            KeyAction::NextTab => "Tab/Right Arrow",
+            KeyAction::Back => "Esc",
+            KeyAction::Quit => "F10/Ctrl-Q",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            KeyAction::Up => "Move up",
+            KeyAction::Down => "Move down",
+            KeyAction::PreviousTab => "Previous tab",
+            KeyAction::NextTab => "Next tab",
+            KeyAction::Back => "Close or go back",
+            KeyAction::Quit => "Quit",
+        }
+    }
+}
+
+impl FromStr for KeyAction {
+    type Err = KeyMapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "up" => Ok(KeyAction::Up),
+            "down" => Ok(KeyAction::Down),
+            "previous_tab" => Ok(KeyAction::PreviousTab),
+            "next_tab" => Ok(KeyAction::NextTab),
+            "back" => Ok(KeyAction::Back),
+            "quit" => Ok(KeyAction::Quit),
+            other => Err(KeyMapError::UnknownAction(other.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum KeyMapError {
+    #[error("Unknown key action `{0}`, expected one of up, down, previous_tab, next_tab, back or quit")]
+    UnknownAction(String),
+    #[error("The key for `{action}` should be a single character, got `{key}`")]
+    InvalidKey { action: String, key: String },
+    #[error("The key `{0}` is bound to more than one action")]
+    DuplicateKey(char),
+}
+
+/// Maps characters to UI actions, sitting between the terminal events and the components. The arrow keys, Tab and Esc
+/// always keep their meaning, the key map only binds additional characters.
+#[derive(Debug, Clone, Default)]
+pub struct KeyMap {
+    bindings: HashMap<char, KeyAction>,
+}
+
+impl KeyMap {
+    /// Build the key map from the action name to key name pairs of the config file
+    pub fn from_config(bindings: &HashMap<String, String>) -> Result<Self, KeyMapError> {
+        let mut key_map = KeyMap::default();
+        for (action, key) in bindings {
+            let key_action = action.parse::<KeyAction>()?;
+            let mut chars = key.chars();
+            let c = match (chars.next(), chars.next()) {
+                (Some(c), None) if !c.is_whitespace() => c,
+                _ => {
+                    return Err(KeyMapError::InvalidKey {
+                        action: action.clone(),
+                        key: key.clone(),
+                    })
+                },
+            };
+            if key_map.bindings.insert(c, key_action).is_some() {
+                return Err(KeyMapError::DuplicateKey(c));
+            }
+        }
+        Ok(key_map)
+    }
+
+    pub fn action(&self, c: char) -> Option<KeyAction> {
+        self.bindings.get(&c).copied()
+    }
+
+    /// The characters bound to the action, in order
+    pub fn keys(&self, action: KeyAction) -> Vec<char> {
+        let mut keys = self
+            .bindings
+            .iter()
+            .filter(|(_, a)| **a == action)
+            .map(|(c, _)| *c)
+            .collect::<Vec<_>>();
+        keys.sort_unstable();
+        keys
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn bindings(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(a, k)| (a.to_string(), k.to_string())).collect()
+    }
+
+    #[test]
+    fn vim_style_bindings() {
+        let key_map = KeyMap::from_config(&bindings(&[
+            ("up", "k"),
+            ("down", "j"),
+            ("previous_tab", "h"),
+            ("next_tab", "l"),
+        ]))
+        .unwrap();
+        assert_eq!(key_map.action('k'), Some(KeyAction::Up));
+        assert_eq!(key_map.action('j'), Some(KeyAction::Down));
+        assert_eq!(key_map.action('h'), Some(KeyAction::PreviousTab));
+        assert_eq!(key_map.action('l'), Some(KeyAction::NextTab));
+        assert_eq!(key_map.action('q'), None);
+        assert_eq!(KeyMap::default().action('k'), None);
+        assert_eq!(key_map.keys(KeyAction::Up), vec!['k']);
+        assert!(key_map.keys(KeyAction::Quit).is_empty());
+    }
+
+    #[test]
+    fn invalid_bindings_are_rejected() {
+        assert_eq!(
+            KeyMap::from_config(&bindings(&[("jump", "j")])).unwrap_err(),
+            KeyMapError::UnknownAction("jump".to_string())
+        );
+        assert_eq!(
+            KeyMap::from_config(&bindings(&[("up", "kk")])).unwrap_err(),
+            KeyMapError::InvalidKey {
+                action: "up".to_string(),
+                key: "kk".to_string()
+            }
+        );
+        assert!(KeyMap::from_config(&bindings(&[("up", "")])).is_err());
+        assert_eq!(
+            KeyMap::from_config(&bindings(&[("up", "k"), ("quit", "k")])).unwrap_err(),
+            KeyMapError::DuplicateKey('k')
+        );
+    }
+}
@@ -0,0 +1,76 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::ui::MAX_WIDTH;
+
+/// Terminals narrower than this get the stacked layout
+pub const NARROW_WIDTH: u16 = 100;
+/// The width of the pane that is shown beside a list on wide terminals
+pub const SIDE_PANE_WIDTH: u16 = 90;
+
+/// How the widgets of the UI are arranged for the width of the terminal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LayoutMode {
+    /// Widgets that are normally side by side are stacked
+    Narrow,
+    /// The layout that the tabs were designed for, at around `MAX_WIDTH`
+    Normal,
+    /// There is room for a pane beside the lists, e.g. for the details of the selected transaction
+    Wide,
+}
+
+impl LayoutMode {
+    pub fn for_width(width: u16) -> Self {
+        if width < NARROW_WIDTH {
+            LayoutMode::Narrow
+        } else if width < MAX_WIDTH + SIDE_PANE_WIDTH {
+            LayoutMode::Normal
+        } else {
+            LayoutMode::Wide
+        }
+    }
+
+    pub fn is_narrow(self) -> bool {
+        self == LayoutMode::Narrow
+    }
+
+    pub fn is_wide(self) -> bool {
+        self == LayoutMode::Wide
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn layout_mode_follows_the_terminal_width() {
+        assert_eq!(LayoutMode::for_width(80), LayoutMode::Narrow);
+        assert_eq!(LayoutMode::for_width(NARROW_WIDTH), LayoutMode::Normal);
+        assert_eq!(LayoutMode::for_width(MAX_WIDTH), LayoutMode::Normal);
+        assert_eq!(
+            LayoutMode::for_width(MAX_WIDTH + SIDE_PANE_WIDTH - 1),
+            LayoutMode::Normal
+        );
+        assert_eq!(LayoutMode::for_width(MAX_WIDTH + SIDE_PANE_WIDTH), LayoutMode::Wide);
+    }
+}
@@ -26,6 +26,9 @@ use tari_app_utilities::utilities::ExitCodes;
 
 mod app;
 mod components;
+mod keymap;
+mod layout;
+mod theme;
 mod widgets;
 
 pub mod state;
@@ -34,23 +37,35 @@ mod ui_contact;
 mod ui_error;
 
 pub use app::*;
+pub use keymap::*;
+pub use layout::*;
+pub use theme::*;
 pub use ui_contact::*;
 pub use ui_error::*;
 
-use crate::utils::events::{Event, EventStream};
+use crate::{
+    ui::components::recovery_progress::RecoveryProgress,
+    utils::events::{Event, EventStream},
+};
 use crossterm::{
-    event::{KeyCode, KeyModifiers},
+    event::{DisableMouseCapture, EnableMouseCapture, Event as CEvent, KeyCode, KeyModifiers, MouseEvent},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use log::*;
 use std::io::{stdout, Stdout, Write};
-use tokio::runtime::Handle;
+use tari_wallet::tasks::wallet_recovery::WalletRecoveryTask;
+use tokio::{
+    runtime::Handle,
+    sync::{broadcast, oneshot},
+};
 use tui::{backend::CrosstermBackend, Terminal};
 
+/// The width that the columns of the lists were laid out for
 pub const MAX_WIDTH: u16 = 133;
 
-pub fn run(app: App<CrosstermBackend<Stdout>>) -> Result<(), ExitCodes> {
+/// Run the interface until the user quits, returning the wallet profile to open next if they switched profiles
+pub fn run(app: App<CrosstermBackend<Stdout>>) -> Result<Option<String>, ExitCodes> {
     let mut app = app;
     Handle::current()
         .block_on(async {
@@ -66,15 +81,75 @@ pub fn run(app: App<CrosstermBackend<Stdout>>) -> Result<(), ExitCodes> {
         .map_err(|e| ExitCodes::WalletError(e.to_string()))?;
     crossterm_loop(app)
 }
-/// This is the main loop of the application UI using Crossterm based events
-fn crossterm_loop(mut app: App<CrosstermBackend<Stdout>>) -> Result<(), ExitCodes> {
+/// Show the progress of a wallet recovery on a full screen until it completes, fails or is stopped by the user
+pub fn run_recovery(mut recovery_task: WalletRecoveryTask, theme: Theme) -> Result<(), ExitCodes> {
+    let mut recovery_events = recovery_task.get_event_receiver();
+    let (result_tx, mut result_rx) = oneshot::channel();
+    Handle::current().spawn(async move {
+        let _ = result_tx.send(recovery_task.run().await);
+    });
+
     let events = CrosstermEvents::new();
+    let mut terminal = setup_terminal()?;
+    let mut progress = RecoveryProgress::new(theme);
+    let mut finished = false;
+    let result = loop {
+        loop {
+            match recovery_events.try_recv() {
+                Ok(event) => progress.on_event(event),
+                // Missed events are superseded by the ones that follow
+                Err(broadcast::TryRecvError::Lagged(_)) => continue,
+                Err(_) => break,
+            }
+        }
+        if !finished {
+            match result_rx.try_recv() {
+                Ok(Ok(())) => finished = true,
+                Ok(Err(e)) => break Err(ExitCodes::RecoveryError(e.to_string())),
+                Err(oneshot::error::TryRecvError::Closed) => {
+                    break Err(ExitCodes::RecoveryError(
+                        "The recovery task stopped unexpectedly".to_string(),
+                    ))
+                },
+                Err(oneshot::error::TryRecvError::Empty) => {},
+            }
+        }
+
+        terminal
+            .draw(|f| {
+                let area = f.size();
+                progress.draw(f, area)
+            })
+            .map_err(|e| {
+                error!(target: LOG_TARGET, "Error drawing interface. {}", e);
+                ExitCodes::InterfaceError
+            })?;
+        match events.next().map_err(|e| {
+            error!(target: LOG_TARGET, "Error reading input event: {}", e);
+            ExitCodes::InterfaceError
+        })? {
+            Event::Input(CEvent::Key(event)) => match (event.code, event.modifiers) {
+                (KeyCode::Char('c'), KeyModifiers::CONTROL) | (KeyCode::Char('q'), KeyModifiers::CONTROL) => {
+                    break Err(ExitCodes::RecoveryError("Recovery stopped by the user".to_string()));
+                },
+                // Any key leaves the summary once the recovery has completed
+                _ if finished => break Ok(()),
+                _ => {},
+            },
+            Event::Input(_) | Event::Tick => {},
+        }
+    };
+    restore_terminal(terminal)?;
+    result
+}
+
+fn setup_terminal() -> Result<Terminal<CrosstermBackend<Stdout>>, ExitCodes> {
     enable_raw_mode().map_err(|e| {
         error!(target: LOG_TARGET, "Error enabling Raw Mode {}", e);
         ExitCodes::InterfaceError
     })?;
     let mut stdout = stdout();
-    execute!(stdout, EnterAlternateScreen).map_err(|e| {
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture).map_err(|e| {
         error!(target: LOG_TARGET, "Error creating stdout context. {}", e);
         ExitCodes::InterfaceError
     })?;
@@ -91,6 +166,14 @@ fn crossterm_loop(mut app: App<CrosstermBackend<Stdout>>) -> Result<(), ExitCode
         ExitCodes::InterfaceError
     })?;
 
+    Ok(terminal)
+}
+
+/// This is the main loop of the application UI using Crossterm based events
+fn crossterm_loop(mut app: App<CrosstermBackend<Stdout>>) -> Result<Option<String>, ExitCodes> {
+    let events = CrosstermEvents::new();
+    let mut terminal = setup_terminal()?;
+
     loop {
         terminal.draw(|f| app.draw(f)).map_err(|e| {
             error!(target: LOG_TARGET, "Error drawing interface. {}", e);
@@ -100,7 +183,13 @@ fn crossterm_loop(mut app: App<CrosstermBackend<Stdout>>) -> Result<(), ExitCode
             error!(target: LOG_TARGET, "Error reading input event: {}", e);
             ExitCodes::InterfaceError
         })? {
-            Event::Input(event) => match (event.code, event.modifiers) {
+            Event::Input(CEvent::Mouse(event)) => match event {
+                MouseEvent::Down(_, column, row, _) => app.on_click(column, row),
+                MouseEvent::ScrollUp(..) => app.on_up(),
+                MouseEvent::ScrollDown(..) => app.on_down(),
+                _ => {},
+            },
+            Event::Input(CEvent::Key(event)) => match (event.code, event.modifiers) {
                 (KeyCode::Char(c), KeyModifiers::CONTROL) => app.on_control_key(c),
                 (KeyCode::Char(c), _) => app.on_key(c),
                 (KeyCode::Left, _) => app.on_left(),
@@ -114,6 +203,7 @@ fn crossterm_loop(mut app: App<CrosstermBackend<Stdout>>) -> Result<(), ExitCode
                 (KeyCode::F(10), _) => app.on_f10(),
                 _ => {},
             },
+            Event::Input(_) => {},
             Event::Tick => {
                 app.on_tick();
             },
@@ -123,6 +213,11 @@ fn crossterm_loop(mut app: App<CrosstermBackend<Stdout>>) -> Result<(), ExitCode
         }
     }
 
+    restore_terminal(terminal)?;
+    Ok(app.switch_profile.take())
+}
+
+fn restore_terminal(mut terminal: Terminal<CrosstermBackend<Stdout>>) -> Result<(), ExitCodes> {
     terminal.clear().map_err(|e| {
         error!(target: LOG_TARGET, "Error clearing interface. {}", e);
         ExitCodes::InterfaceError
@@ -132,7 +227,7 @@ fn crossterm_loop(mut app: App<CrosstermBackend<Stdout>>) -> Result<(), ExitCode
         error!(target: LOG_TARGET, "Error disabling Raw Mode {}", e);
         ExitCodes::InterfaceError
     })?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen).map_err(|e| {
+    execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture).map_err(|e| {
         error!(target: LOG_TARGET, "Error releasing stdout {}", e);
         ExitCodes::InterfaceError
     })?;
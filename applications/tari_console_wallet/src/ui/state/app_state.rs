@@ -1,58 +1,79 @@
 use crate::{
     notifier::Notifier,
     ui::{
-        state::wallet_event_monitor::WalletEventMonitor,
+        components::qrcode::{payment_uri, render_qr_code},
+        state::{
+            transaction_export::{write_transactions, ExportFormat},
+            transaction_filter::TransactionFilter,
+            wallet_event_monitor::WalletEventMonitor,
+        },
+        Theme,
         UiContact,
         UiError,
         CUSTOM_BASE_NODE_ADDRESS_KEY,
         CUSTOM_BASE_NODE_PUBLIC_KEY_KEY,
+        SEED_BACKED_UP_KEY,
     },
+    utils::{db::set_saved_base_nodes_in_db, price_feed::FiatPrice},
     wallet_modes::PeerConfig,
 };
+use chrono::{DateTime, Local, Utc};
 use futures::{stream::Fuse, StreamExt};
 use log::*;
-use qrcode::{render::unicode, QrCode};
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, convert::TryInto, fs::File, io::BufWriter, path::PathBuf, sync::Arc, time::Duration};
 use tari_common::{GlobalConfig, Network};
 use tari_comms::{
-    connectivity::ConnectivityEventRx,
+    connection_manager::ConnectionDirection,
+    connectivity::ConnectivityStatus,
     multiaddr::Multiaddr,
     peer_manager::{NodeId, Peer, PeerFeatures, PeerFlags},
     types::CommsPublicKey,
     NodeIdentity,
 };
 use tari_core::transactions::{
+    htlc::HashTimeLock,
     tari_amount::{uT, MicroTari},
-    types::PublicKey,
+    types::{Commitment, CommitmentFactory, PublicKey},
+};
+use tari_crypto::{
+    commitment::HomomorphicCommitmentFactory,
+    tari_utilities::hex::{from_hex, Hex},
 };
-use tari_crypto::tari_utilities::hex::Hex;
 use tari_shutdown::ShutdownSignal;
 use tari_wallet::{
-    base_node_service::{handle::BaseNodeEventReceiver, service::BaseNodeState},
+    base_node_service::service::BaseNodeState,
     contacts_service::storage::database::Contact,
+    events::WalletEventReceiver,
     output_manager_service::{
-        handle::OutputManagerEventReceiver,
+        handle::PublicRewindKeys,
         protocols::txo_validation_protocol::TxoValidationType,
-        service::Balance,
+        service::{Balance, FeeEstimate},
         TxId,
     },
+    storage::database::{ScheduledPayment, WatchedOutput},
+    tasks::watch_only_scan::{WatchOnlyScanEvent, WatchOnlyScanner},
     transaction_service::{
-        handle::{TransactionEvent, TransactionEventReceiver, TransactionServiceHandle},
+        handle::{TransactionEvent, TransactionRetry, TransactionServiceHandle},
         storage::models::{CompletedTransaction, TransactionStatus},
     },
     types::ValidationRetryStrategy,
     util::emoji::EmojiId,
     WalletSqlite,
 };
-use tokio::sync::{watch, RwLock};
+use tokio::sync::{broadcast, watch, RwLock};
 
 const LOG_TARGET: &str = "wallet::console_wallet::app_state";
+/// How often a watch-only wallet scans the chain for the outputs of the wallet that it watches
+const WATCH_ONLY_SCAN_INTERVAL: Duration = Duration::from_secs(300);
 
 #[derive(Clone)]
 pub struct AppState {
     inner: Arc<RwLock<AppStateInner>>,
     cached_data: AppStateData,
     node_config: GlobalConfig,
+    transaction_filter: Option<TransactionFilter>,
+    fiat_price: Option<FiatPrice>,
+    theme: Theme,
 }
 
 impl AppState {
@@ -63,19 +84,33 @@ impl AppState {
         base_node_selected: Peer,
         base_node_config: PeerConfig,
         node_config: GlobalConfig,
+        theme: Theme,
+        watch_only_keys: Option<PublicRewindKeys>,
     ) -> Self
     {
-        let inner = AppStateInner::new(node_identity, network, wallet, base_node_selected, base_node_config);
+        let inner = AppStateInner::new(
+            node_identity,
+            network,
+            wallet,
+            base_node_selected,
+            base_node_config,
+            watch_only_keys,
+        );
         let cached_data = inner.data.clone();
         Self {
             inner: Arc::new(RwLock::new(inner)),
             cached_data,
             node_config,
+            transaction_filter: None,
+            fiat_price: None,
+            theme,
         }
     }
 
+    /// Start following the events of the wallet services, and the scans of the chain if this is a watch-only wallet
     pub async fn start_event_monitor(&self, notifier: Notifier) {
-        let event_monitor = WalletEventMonitor::new(self.inner.clone());
+        let watch_only_scan_events = self.inner.read().await.start_watch_only_scanner();
+        let event_monitor = WalletEventMonitor::new(self.inner.clone(), watch_only_scan_events);
         tokio::spawn(event_monitor.run(notifier));
     }
 
@@ -83,7 +118,7 @@ impl AppState {
         let mut inner = self.inner.write().await;
         inner.refresh_full_transaction_state().await?;
         if let Some(data) = inner.get_updated_app_state() {
-            self.cached_data = data;
+            self.cached_data = apply_transaction_filter(data, &self.transaction_filter);
         }
         Ok(())
     }
@@ -92,7 +127,7 @@ impl AppState {
         let mut inner = self.inner.write().await;
         inner.refresh_contacts_state().await?;
         if let Some(data) = inner.get_updated_app_state() {
-            self.cached_data = data;
+            self.cached_data = apply_transaction_filter(data, &self.transaction_filter);
         }
         Ok(())
     }
@@ -101,72 +136,140 @@ impl AppState {
         let mut inner = self.inner.write().await;
         inner.refresh_connected_peers_state().await?;
         if let Some(data) = inner.get_updated_app_state() {
-            self.cached_data = data;
+            self.cached_data = apply_transaction_filter(data, &self.transaction_filter);
         }
         Ok(())
     }
 
+    /// Only keep the cached pending and completed transactions that match `filter`, or all of them if it is `None`
+    pub async fn set_transaction_filter(&mut self, filter: Option<TransactionFilter>) {
+        self.transaction_filter = filter;
+        let inner = self.inner.read().await;
+        self.cached_data = apply_transaction_filter(inner.data.clone(), &self.transaction_filter);
+    }
+
+    pub fn get_transaction_filter(&self) -> Option<&TransactionFilter> {
+        self.transaction_filter.as_ref()
+    }
+
     pub async fn update_cache(&mut self) {
         let mut inner = self.inner.write().await;
         if let Some(data) = inner.get_updated_app_state() {
-            self.cached_data = data;
+            self.cached_data = apply_transaction_filter(data, &self.transaction_filter);
         }
     }
 
     pub async fn upsert_contact(&mut self, alias: String, public_key_or_emoji_id: String) -> Result<(), UiError> {
         let mut inner = self.inner.write().await;
 
-        let public_key = match CommsPublicKey::from_hex(public_key_or_emoji_id.as_str()) {
-            Ok(pk) => pk,
-            Err(_) => {
-                EmojiId::str_to_pubkey(public_key_or_emoji_id.as_str()).map_err(|_| UiError::PublicKeyParseError)?
-            },
-        };
+        let public_key = parse_public_key_or_emoji_id(&public_key_or_emoji_id)?;
 
         let contact = Contact { alias, public_key };
         inner.wallet.contacts_service.upsert_contact(contact).await?;
 
         inner.refresh_contacts_state().await?;
         if let Some(data) = inner.get_updated_app_state() {
-            self.cached_data = data;
+            self.cached_data = apply_transaction_filter(data, &self.transaction_filter);
         }
         Ok(())
     }
 
-    pub async fn delete_contact(&mut self, public_key: String) -> Result<(), UiError> {
+    /// Save an edited contact. If the public key of the contact was changed, the contact stored under its previous
+    /// public key is removed.
+    pub async fn update_contact(
+        &mut self,
+        previous_public_key: String,
+        alias: String,
+        public_key_or_emoji_id: String,
+    ) -> Result<(), UiError>
+    {
         let mut inner = self.inner.write().await;
-        let public_key = match CommsPublicKey::from_hex(public_key.as_str()) {
-            Ok(pk) => pk,
-            Err(_) => EmojiId::str_to_pubkey(public_key.as_str()).map_err(|_| UiError::PublicKeyParseError)?,
+
+        let previous_public_key = parse_public_key_or_emoji_id(&previous_public_key)?;
+        let public_key = parse_public_key_or_emoji_id(&public_key_or_emoji_id)?;
+
+        let contact = Contact {
+            alias,
+            public_key: public_key.clone(),
         };
+        inner.wallet.contacts_service.upsert_contact(contact).await?;
+        if public_key != previous_public_key {
+            inner
+                .wallet
+                .contacts_service
+                .remove_contact(previous_public_key)
+                .await?;
+        }
+
+        inner.refresh_contacts_state().await?;
+        if let Some(data) = inner.get_updated_app_state() {
+            self.cached_data = apply_transaction_filter(data, &self.transaction_filter);
+        }
+        Ok(())
+    }
+
+    pub async fn delete_contact(&mut self, public_key: String) -> Result<(), UiError> {
+        let mut inner = self.inner.write().await;
+        let public_key = parse_public_key_or_emoji_id(&public_key)?;
 
         inner.wallet.contacts_service.remove_contact(public_key).await?;
 
         inner.refresh_contacts_state().await?;
         if let Some(data) = inner.get_updated_app_state() {
-            self.cached_data = data;
+            self.cached_data = apply_transaction_filter(data, &self.transaction_filter);
         }
         Ok(())
     }
 
+    /// Send a transaction to `public_key`. The outputs with the `inputs` commitments are spent if any are given,
+    /// otherwise the wallet selects the outputs to spend.
     pub async fn send_transaction(
         &mut self,
         public_key: String,
         amount: u64,
         fee_per_gram: u64,
         message: String,
+        inputs: Vec<Commitment>,
         result_tx: watch::Sender<UiTransactionSendStatus>,
     ) -> Result<(), UiError>
     {
         let inner = self.inner.write().await;
-        let public_key = match CommsPublicKey::from_hex(public_key.as_str()) {
-            Ok(pk) => pk,
-            Err(_) => EmojiId::str_to_pubkey(public_key.as_str()).map_err(|_| UiError::PublicKeyParseError)?,
-        };
+        inner.check_can_send()?;
+        let public_key = parse_public_key_or_emoji_id(&public_key)?;
 
         let fee_per_gram = fee_per_gram * uT;
         let tx_service_handle = inner.wallet.transaction_service.clone();
         tokio::spawn(send_transaction_task(
+            public_key,
+            MicroTari::from(amount),
+            message,
+            fee_per_gram,
+            inputs,
+            tx_service_handle,
+            result_tx,
+        ));
+
+        Ok(())
+    }
+
+    /// Pay `public_key` with a one-sided transaction, which is built and broadcast by this wallet alone so the
+    /// recipient does not have to be online
+    pub async fn send_one_sided_transaction(
+        &mut self,
+        public_key: String,
+        amount: u64,
+        fee_per_gram: u64,
+        message: String,
+        result_tx: watch::Sender<UiTransactionSendStatus>,
+    ) -> Result<(), UiError>
+    {
+        let inner = self.inner.write().await;
+        inner.check_can_send()?;
+        let public_key = parse_public_key_or_emoji_id(&public_key)?;
+
+        let fee_per_gram = fee_per_gram * uT;
+        let tx_service_handle = inner.wallet.transaction_service.clone();
+        tokio::spawn(send_one_sided_transaction_task(
             public_key,
             MicroTari::from(amount),
             message,
@@ -178,6 +281,90 @@ impl AppState {
         Ok(())
     }
 
+    /// Pay each (public key or emoji id, amount) pair in `recipients` in a single transaction, returning the TxIds of
+    /// the recipients in the same order. All the recipients are validated before anything is sent.
+    pub async fn send_transaction_batch(
+        &mut self,
+        recipients: Vec<(String, u64)>,
+        fee_per_gram: u64,
+        message: String,
+    ) -> Result<Vec<TxId>, UiError>
+    {
+        let inner = self.inner.write().await;
+        inner.check_can_send()?;
+        let recipients = recipients
+            .iter()
+            .map(|(public_key, amount)| Ok((parse_public_key_or_emoji_id(public_key)?, MicroTari::from(*amount))))
+            .collect::<Result<Vec<_>, UiError>>()?;
+
+        let mut tx_service_handle = inner.wallet.transaction_service.clone();
+        let tx_ids = tx_service_handle
+            .send_transaction_batch(recipients, fee_per_gram * uT, message)
+            .await?;
+
+        Ok(tx_ids)
+    }
+
+    /// Split the wallet's unspent outputs into `num_splits` outputs of `amount_per_split` each, e.g. to break up a
+    /// large coinbase output. The coin split transaction is submitted to the network like any other.
+    pub async fn send_coin_split(
+        &mut self,
+        amount_per_split: u64,
+        num_splits: usize,
+        fee_per_gram: u64,
+    ) -> Result<TxId, UiError>
+    {
+        let inner = self.inner.write().await;
+        inner.check_can_send()?;
+        let mut output_manager_handle = inner.wallet.output_manager_service.clone();
+        let mut tx_service_handle = inner.wallet.transaction_service.clone();
+
+        let (tx_id, tx, fee, amount) = output_manager_handle
+            .create_coin_split(amount_per_split * uT, num_splits, fee_per_gram * uT, None)
+            .await?;
+        tx_service_handle
+            .submit_transaction(tx_id, tx, fee, amount, "Coin split".into())
+            .await?;
+
+        Ok(tx_id)
+    }
+
+    /// The outputs of the wallet that can be picked to be spent in the next transaction, smallest first
+    pub async fn get_unspent_outputs(&self) -> Result<Vec<UiOutput>, UiError> {
+        let inner = self.inner.read().await;
+        let mut output_manager_service = inner.wallet.output_manager_service.clone();
+        let factory = CommitmentFactory::default();
+        let mut outputs = output_manager_service
+            .get_unspent_outputs()
+            .await?
+            .into_iter()
+            .filter(|o| o.features.asset.is_none())
+            .map(|o| UiOutput {
+                commitment: factory.commit_value(&o.spending_key, o.value.into()),
+                value: o.value,
+                maturity: o.features.maturity,
+            })
+            .collect::<Vec<_>>();
+        outputs.sort_by_key(|o| o.value);
+        Ok(outputs)
+    }
+
+    /// Estimate the fee of sending `amount` to `num_outputs` recipients. The fee per gram suggested by the base node is
+    /// used if none is given.
+    pub async fn estimate_fee(
+        &self,
+        amount: MicroTari,
+        num_outputs: usize,
+        fee_per_gram: Option<MicroTari>,
+    ) -> Result<FeeEstimate, UiError>
+    {
+        let inner = self.inner.read().await;
+        let mut tx_service_handle = inner.wallet.transaction_service.clone();
+        Ok(tx_service_handle
+            .estimate_fee(amount, num_outputs, fee_per_gram)
+            .await?)
+    }
+
     pub async fn cancel_transaction(&mut self, tx_id: TxId) -> Result<(), UiError> {
         let inner = self.inner.write().await;
         let mut tx_service_handle = inner.wallet.transaction_service.clone();
@@ -185,6 +372,287 @@ impl AppState {
         Ok(())
     }
 
+    /// Resend a stuck pending outbound transaction to its recipient, or rebroadcast a completed transaction that is not
+    /// mined yet
+    pub async fn retry_transaction(&self, tx_id: TxId) -> Result<TransactionRetry, UiError> {
+        let inner = self.inner.read().await;
+        let mut tx_service_handle = inner.wallet.transaction_service.clone();
+        Ok(tx_service_handle.retry_transaction(tx_id).await?)
+    }
+
+    /// Write the whole transaction history, including pending and cancelled transactions, to `path` in the format
+    /// given by its extension. Returns the number of transactions written.
+    pub async fn export_transactions(&self, path: PathBuf) -> Result<usize, UiError> {
+        let inner = self.inner.read().await;
+        let mut tx_service = inner.wallet.transaction_service.clone();
+
+        let mut transactions: Vec<CompletedTransaction> = Vec::new();
+        let pending_inbound = tx_service.get_pending_inbound_transactions().await?;
+        let cancelled_inbound = tx_service.get_cancelled_pending_inbound_transactions().await?;
+        transactions.extend(
+            pending_inbound
+                .into_iter()
+                .chain(cancelled_inbound)
+                .map(|(_, tx)| tx.into()),
+        );
+        let pending_outbound = tx_service.get_pending_outbound_transactions().await?;
+        let cancelled_outbound = tx_service.get_cancelled_pending_outbound_transactions().await?;
+        transactions.extend(
+            pending_outbound
+                .into_iter()
+                .chain(cancelled_outbound)
+                .map(|(_, tx)| tx.into()),
+        );
+        let completed = tx_service.get_completed_transactions().await?;
+        let cancelled_completed = tx_service.get_cancelled_completed_transactions().await?;
+        transactions.extend(completed.into_iter().chain(cancelled_completed).map(|(_, tx)| tx));
+        transactions.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+
+        let format = ExportFormat::from_path(&path);
+        let file = File::create(&path)?;
+        write_transactions(BufWriter::new(file), &transactions, format)?;
+        info!(
+            target: LOG_TARGET,
+            "Exported {} transactions to {}",
+            transactions.len(),
+            path.display()
+        );
+        Ok(transactions.len())
+    }
+
+    /// Fetch the current state of a transaction from the wallet, rather than the cached copy used by the lists
+    pub async fn get_transaction_details(&self, tx_id: TxId) -> Result<Option<CompletedTransaction>, UiError> {
+        let inner = self.inner.read().await;
+        let mut tx_service_handle = inner.wallet.transaction_service.clone();
+        let tx = tx_service_handle.get_any_transaction(tx_id).await?;
+        Ok(tx.map(CompletedTransaction::from))
+    }
+
+    /// Replace the labels of a transaction, an empty list removes them
+    pub async fn set_transaction_labels(&mut self, tx_id: TxId, labels: Vec<String>) -> Result<(), UiError> {
+        let mut inner = self.inner.write().await;
+        inner.set_transaction_labels(tx_id, labels).await?;
+        if let Some(data) = inner.get_updated_app_state() {
+            self.cached_data = apply_transaction_filter(data, &self.transaction_filter);
+        }
+        Ok(())
+    }
+
+    /// Check the password against the encryption of the wallet database
+    pub async fn verify_passphrase(&self, passphrase: String) -> Result<(), UiError> {
+        let inner = self.inner.read().await;
+        inner.wallet.verify_passphrase(passphrase).await?;
+        Ok(())
+    }
+
+    /// Re-encrypt the wallet database with the new password, leaving it encrypted with the current one if that fails
+    pub async fn change_passphrase(&mut self, current: String, new: String) -> Result<(), UiError> {
+        let mut inner = self.inner.write().await;
+        inner.wallet.change_passphrase(current, new).await?;
+        Ok(())
+    }
+
+    pub async fn get_seed_words(&self) -> Result<Vec<String>, UiError> {
+        let inner = self.inner.read().await;
+        let mut output_manager_service = inner.wallet.output_manager_service.clone();
+        Ok(output_manager_service.get_seed_words().await?)
+    }
+
+    /// Whether the user has confirmed that they wrote down the seed words
+    pub async fn is_seed_backed_up(&self) -> Result<bool, UiError> {
+        let inner = self.inner.read().await;
+        let backed_up = inner
+            .wallet
+            .db
+            .get_client_key_from_str::<bool>(SEED_BACKED_UP_KEY.to_string())
+            .await?;
+        Ok(backed_up.unwrap_or(false))
+    }
+
+    pub async fn set_seed_backed_up(&mut self) -> Result<(), UiError> {
+        let inner = self.inner.write().await;
+        inner
+            .wallet
+            .db
+            .set_client_key_value(SEED_BACKED_UP_KEY.to_string(), true.to_string())
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_scheduled_payments(&self) -> Result<Vec<ScheduledPayment>, UiError> {
+        let inner = self.inner.read().await;
+        let mut scheduler_service = inner.wallet.scheduler_service.clone();
+        Ok(scheduler_service.get_scheduled_payments().await?)
+    }
+
+    /// Schedule a payment to `public_key` that is sent every `interval`, with the first payment sent as soon as the
+    /// wallet is online
+    pub async fn add_scheduled_payment(
+        &mut self,
+        public_key: String,
+        amount: u64,
+        fee_per_gram: u64,
+        message: String,
+        interval: Duration,
+    ) -> Result<u64, UiError>
+    {
+        let inner = self.inner.write().await;
+        inner.check_can_send()?;
+        let public_key = parse_public_key_or_emoji_id(&public_key)?;
+        let mut scheduler_service = inner.wallet.scheduler_service.clone();
+        Ok(scheduler_service
+            .add_scheduled_payment(
+                public_key,
+                MicroTari::from(amount),
+                fee_per_gram * uT,
+                message,
+                interval,
+                Utc::now().naive_utc(),
+            )
+            .await?)
+    }
+
+    pub async fn remove_scheduled_payment(&mut self, id: u64) -> Result<(), UiError> {
+        let inner = self.inner.write().await;
+        let mut scheduler_service = inner.wallet.scheduler_service.clone();
+        Ok(scheduler_service.remove_scheduled_payment(id).await?)
+    }
+
+    /// The key with which this wallet claims and refunds hash time-locked outputs, which the other party of an atomic
+    /// swap locks its funds for
+    pub async fn get_htlc_public_key(&self) -> Result<PublicKey, UiError> {
+        let inner = self.inner.read().await;
+        let mut output_manager_service = inner.wallet.output_manager_service.clone();
+        Ok(output_manager_service.get_htlc_public_key().await?)
+    }
+
+    /// The unspent hash time-locked outputs of the atomic swaps of this wallet, both the ones it can claim and the ones
+    /// it can refund
+    pub async fn get_htlcs(&self) -> Result<Vec<UiHtlc>, UiError> {
+        let inner = self.inner.read().await;
+        let mut output_manager_service = inner.wallet.output_manager_service.clone();
+        let htlc_public_key = output_manager_service.get_htlc_public_key().await?;
+        let factory = CommitmentFactory::default();
+        let htlcs = output_manager_service
+            .get_htlc_outputs()
+            .await?
+            .into_iter()
+            .filter_map(|o| {
+                let commitment = factory.commit_value(&o.spending_key, o.value.into());
+                o.features.htlc.map(|htlc| UiHtlc {
+                    commitment,
+                    value: o.value,
+                    claimable: htlc.claim_public_key == htlc_public_key,
+                    htlc,
+                })
+            })
+            .collect();
+        Ok(htlcs)
+    }
+
+    /// Lock `amount` in a hash time-locked output for `public_key`, who can claim it with the preimage of `hash_lock`
+    /// and its HTLC key `claim_key` until this wallet refunds it from `refund_height` onwards
+    #[allow(clippy::too_many_arguments)]
+    pub async fn lock_htlc(
+        &mut self,
+        public_key: String,
+        claim_key: String,
+        amount: u64,
+        hash_lock: String,
+        refund_height: u64,
+        fee_per_gram: u64,
+        message: String,
+    ) -> Result<TxId, UiError>
+    {
+        let inner = self.inner.write().await;
+        inner.check_can_send()?;
+        let public_key = parse_public_key_or_emoji_id(&public_key)?;
+        let claim_key = PublicKey::from_hex(claim_key.trim()).map_err(|_| UiError::PublicKeyParseError)?;
+        let hash_lock: [u8; 32] = from_hex(hash_lock.trim())?
+            .as_slice()
+            .try_into()
+            .map_err(|_| UiError::HashLockParseError)?;
+        let mut tx_service_handle = inner.wallet.transaction_service.clone();
+        Ok(tx_service_handle
+            .lock_htlc(
+                public_key,
+                claim_key,
+                MicroTari::from(amount),
+                hash_lock,
+                refund_height,
+                fee_per_gram * uT,
+                message,
+            )
+            .await?)
+    }
+
+    /// Claim the hash time-locked output with the given commitment by revealing the preimage (hex) of its hash lock
+    pub async fn claim_htlc(
+        &mut self,
+        commitment: Commitment,
+        preimage: String,
+        fee_per_gram: u64,
+    ) -> Result<TxId, UiError>
+    {
+        let inner = self.inner.write().await;
+        inner.check_can_send()?;
+        let preimage = from_hex(preimage.trim())?;
+        let mut tx_service_handle = inner.wallet.transaction_service.clone();
+        Ok(tx_service_handle
+            .claim_htlc(commitment, preimage, fee_per_gram * uT, "Claimed swap".to_string())
+            .await?)
+    }
+
+    /// Refund the hash time-locked output with the given commitment, which this wallet locked. The refund is only
+    /// accepted by the base node once the refund height has been reached.
+    pub async fn refund_htlc(&mut self, commitment: Commitment, fee_per_gram: u64) -> Result<TxId, UiError> {
+        let inner = self.inner.write().await;
+        inner.check_can_send()?;
+        let mut tx_service_handle = inner.wallet.transaction_service.clone();
+        Ok(tx_service_handle
+            .refund_htlc(commitment, fee_per_gram * uT, "Refunded swap".to_string())
+            .await?)
+    }
+
+    /// The connectivity status of the wallet and the statistics of each active peer connection. Every connected peer
+    /// is also sent a liveness ping, so that its latency is known, or up to date, on the next call.
+    pub async fn get_network_stats(&self) -> Result<NetworkStats, UiError> {
+        let inner = self.inner.read().await;
+        let mut connectivity = inner.wallet.comms.connectivity();
+        let mut liveness = inner.wallet.liveness_service.clone();
+        let peer_manager = inner.wallet.comms.peer_manager();
+
+        let connectivity_status = connectivity.get_connectivity_status().await?;
+        let connections = connectivity.get_active_connections().await?;
+        let mut peers = Vec::with_capacity(connections.len());
+        for conn in connections.iter() {
+            let node_id = conn.peer_node_id().clone();
+            let latency = liveness.get_avg_latency(node_id.clone()).await?;
+            if let Err(e) = liveness.send_ping(node_id.clone()).await {
+                warn!(target: LOG_TARGET, "Could not ping peer {}: {}", node_id, e);
+            }
+            let user_agent = peer_manager
+                .find_by_node_id(&node_id)
+                .await
+                .map(|p| p.user_agent)
+                .unwrap_or_default();
+            peers.push(UiPeerStats {
+                node_id,
+                user_agent,
+                address: conn.address().clone(),
+                direction: conn.direction(),
+                connected_for: conn.age(),
+                num_substreams: conn.substream_count(),
+                latency: latency.map(|ms| Duration::from_millis(ms.into())),
+            });
+        }
+
+        Ok(NetworkStats {
+            connectivity_status,
+            peers,
+        })
+    }
+
     pub fn get_identity(&self) -> &MyIdentity {
         &self.cached_data.my_identity
     }
@@ -245,6 +713,14 @@ impl AppState {
         (&self.cached_data.confirmations).get(tx_id)
     }
 
+    pub fn get_transaction_labels(&self, tx_id: &TxId) -> &[String] {
+        self.cached_data
+            .transaction_labels
+            .get(tx_id)
+            .map(|l| l.as_slice())
+            .unwrap_or_default()
+    }
+
     pub fn get_completed_tx(&self, index: usize) -> Option<&CompletedTransaction> {
         if index < self.cached_data.completed_txs.len() {
             Some(&self.cached_data.completed_txs[index])
@@ -261,10 +737,47 @@ impl AppState {
         &self.cached_data.balance
     }
 
+    /// Whether the wallet only has the view key of another wallet, so that it can show its balance but not spend it
+    pub fn is_watch_only(&self) -> bool {
+        self.cached_data.watch_only
+    }
+
+    /// The outputs that the scans of a watch-only wallet found, the most recently found first
+    pub fn get_watched_outputs(&self) -> &[WatchedOutput] {
+        &self.cached_data.watched_outputs
+    }
+
+    /// The latest balance reported by the Output Manager Service, split into the amounts that make up the total
+    pub fn get_balance_breakdown(&self) -> BalanceBreakdown {
+        BalanceBreakdown::from(&self.cached_data.balance)
+    }
+
+    /// The latest price of Tari from the price feed, if one is configured and has been reached
+    pub fn get_fiat_price(&self) -> Option<&FiatPrice> {
+        self.fiat_price.as_ref()
+    }
+
+    pub fn set_fiat_price(&mut self, price: FiatPrice) {
+        self.fiat_price = Some(price);
+    }
+
+    pub fn get_theme(&self) -> &Theme {
+        &self.theme
+    }
+
     pub fn get_base_node_state(&self) -> &BaseNodeState {
         &self.cached_data.base_node_state
     }
 
+    /// The tip height of the base node when the outputs of the wallet were last validated
+    pub fn get_validated_height(&self) -> Option<u64> {
+        self.cached_data.validated_height
+    }
+
+    pub fn get_last_error(&self) -> Option<&BackgroundError> {
+        self.cached_data.last_error.as_ref()
+    }
+
     pub fn get_selected_base_node(&self) -> &Peer {
         &self.cached_data.base_node_selected
     }
@@ -281,6 +794,15 @@ impl AppState {
         &self.cached_data.base_node_list
     }
 
+    /// The named base nodes saved by the user
+    pub fn get_saved_base_nodes(&self) -> &Vec<(String, Peer)> {
+        &self.cached_data.base_node_saved
+    }
+
+    pub fn is_saved_base_node(&self, peer: &Peer) -> bool {
+        self.cached_data.base_node_saved.iter().any(|(_, p)| p == peer)
+    }
+
     pub async fn set_base_node_peer(&mut self, peer: Peer) -> Result<(), UiError> {
         let mut inner = self.inner.write().await;
         inner.set_base_node_peer(peer).await?;
@@ -288,24 +810,43 @@ impl AppState {
     }
 
     pub async fn set_custom_base_node(&mut self, public_key: String, address: String) -> Result<Peer, UiError> {
-        let pub_key = PublicKey::from_hex(public_key.as_str())?;
-        let addr = address.parse::<Multiaddr>().map_err(|_| UiError::AddressParseError)?;
-        let node_id = NodeId::from_key(&pub_key)?;
-        let peer = Peer::new(
-            pub_key,
-            node_id,
-            addr.into(),
-            PeerFlags::default(),
-            PeerFeatures::COMMUNICATION_NODE,
-            Default::default(),
-            Default::default(),
-        );
+        let peer = parse_base_node_peer(&public_key, &address)?;
 
         let mut inner = self.inner.write().await;
         inner.set_custom_base_node_peer(peer.clone()).await?;
         Ok(peer)
     }
 
+    /// Save a base node under `name` so it can be switched to later
+    pub async fn save_base_node(&mut self, name: String, public_key: String, address: String) -> Result<Peer, UiError> {
+        let peer = parse_base_node_peer(&public_key, &address)?;
+        {
+            let mut inner = self.inner.write().await;
+            inner.save_base_node(name, peer.clone()).await?;
+        }
+        self.update_cache().await;
+        Ok(peer)
+    }
+
+    pub async fn remove_saved_base_node(&mut self, peer: Peer) -> Result<(), UiError> {
+        {
+            let mut inner = self.inner.write().await;
+            inner.remove_saved_base_node(&peer).await?;
+        }
+        self.update_cache().await;
+        Ok(())
+    }
+
+    /// Switch to a saved base node. It stays selected when the wallet is restarted, like a custom base node.
+    pub async fn activate_saved_base_node(&mut self, peer: Peer) -> Result<(), UiError> {
+        {
+            let mut inner = self.inner.write().await;
+            inner.set_custom_base_node_peer(peer).await?;
+        }
+        self.update_cache().await;
+        Ok(())
+    }
+
     pub async fn clear_custom_base_node(&mut self) -> Result<(), UiError> {
         {
             let mut inner = self.inner.write().await;
@@ -324,6 +865,7 @@ pub struct AppStateInner {
     updated: bool,
     data: AppStateData,
     wallet: WalletSqlite,
+    watch_only_keys: Option<PublicRewindKeys>,
 }
 
 impl AppStateInner {
@@ -333,15 +875,39 @@ impl AppStateInner {
         wallet: WalletSqlite,
         base_node_selected: Peer,
         base_node_config: PeerConfig,
+        watch_only_keys: Option<PublicRewindKeys>,
     ) -> Self
     {
-        let data = AppStateData::new(node_identity, network, base_node_selected, base_node_config);
+        let mut data = AppStateData::new(node_identity, network, base_node_selected, base_node_config);
+        data.watch_only = watch_only_keys.is_some();
 
         AppStateInner {
             updated: false,
             data,
             wallet,
+            watch_only_keys,
+        }
+    }
+
+    /// Spawn the scanner of a watch-only wallet, returning the receiver of its events
+    fn start_watch_only_scanner(&self) -> Option<broadcast::Receiver<WatchOnlyScanEvent>> {
+        let keys = self.watch_only_keys.clone()?;
+        let mut scanner = WatchOnlyScanner::new(
+            self.wallet.clone(),
+            keys,
+            self.data.base_node_selected.public_key.clone(),
+            WATCH_ONLY_SCAN_INTERVAL,
+        );
+        let events = scanner.get_event_receiver();
+        tokio::spawn(scanner.run());
+        Some(events)
+    }
+
+    fn check_can_send(&self) -> Result<(), UiError> {
+        if self.data.watch_only {
+            return Err(UiError::WatchOnly);
         }
+        Ok(())
     }
 
     /// If there has been an update to the state since the last call to this function it will provide a cloned snapshot
@@ -409,6 +975,7 @@ impl AppStateInner {
         });
 
         self.data.completed_txs = completed_transactions;
+        self.data.transaction_labels = self.wallet.transaction_service.get_all_transaction_labels().await?;
         self.refresh_balance().await?;
         self.updated = true;
         Ok(())
@@ -488,14 +1055,35 @@ impl AppStateInner {
         Ok(())
     }
 
+    pub async fn set_transaction_labels(&mut self, tx_id: TxId, labels: Vec<String>) -> Result<(), UiError> {
+        let mut tx_service = self.wallet.transaction_service.clone();
+        tx_service.set_transaction_labels(tx_id, labels).await?;
+        // Read the labels back, as the service normalises them
+        let labels = tx_service.get_transaction_labels(tx_id).await?;
+        if labels.is_empty() {
+            self.data.transaction_labels.remove(&tx_id);
+        } else {
+            self.data.transaction_labels.insert(tx_id, labels);
+        }
+        self.updated = true;
+        Ok(())
+    }
+
     pub async fn refresh_contacts_state(&mut self) -> Result<(), UiError> {
+        let liveness = self.wallet.contacts_service.get_contacts_liveness().await?;
         let mut contacts: Vec<UiContact> = self
             .wallet
             .contacts_service
             .get_contacts()
             .await?
             .iter()
-            .map(|c| UiContact::from(c.clone()))
+            .map(|c| {
+                let contact = UiContact::from(c.clone());
+                match liveness.iter().find(|l| l.public_key == c.public_key) {
+                    Some(l) => contact.with_liveness(l),
+                    None => contact,
+                }
+            })
             .collect();
 
         contacts.sort_by(|a, b| {
@@ -525,7 +1113,12 @@ impl AppStateInner {
         Ok(())
     }
 
+    /// A watch-only wallet has no outputs of its own, so its available balance is the value of the unspent watched
+    /// outputs
     pub async fn refresh_balance(&mut self) -> Result<(), UiError> {
+        if self.data.watch_only {
+            return self.refresh_watched_outputs().await;
+        }
         let balance = self.wallet.output_manager_service.get_balance().await?;
         self.data.balance = balance;
         self.updated = true;
@@ -533,6 +1126,18 @@ impl AppStateInner {
         Ok(())
     }
 
+    pub async fn refresh_watched_outputs(&mut self) -> Result<(), UiError> {
+        let outputs = self.wallet.db.get_watched_outputs().await?;
+        self.data.balance = Balance {
+            available_balance: outputs.iter().filter(|o| o.spent_at.is_none()).map(|o| o.value).sum(),
+            ..Balance::zero()
+        };
+        self.data.watched_outputs = outputs;
+        self.updated = true;
+
+        Ok(())
+    }
+
     pub async fn refresh_base_node_state(&mut self, state: BaseNodeState) -> Result<(), UiError> {
         self.data.base_node_state = state;
         self.updated = true;
@@ -547,24 +1152,31 @@ impl AppStateInner {
         Ok(())
     }
 
-    pub fn get_shutdown_signal(&self) -> ShutdownSignal {
-        self.wallet.comms.shutdown_signal()
-    }
-
-    pub fn get_transaction_service_event_stream(&self) -> Fuse<TransactionEventReceiver> {
-        self.wallet.transaction_service.get_event_stream_fused()
+    /// Record the tip of the base node as the height that the outputs of the wallet were validated at
+    pub fn refresh_validated_height(&mut self) {
+        self.data.validated_height = self
+            .data
+            .base_node_state
+            .chain_metadata
+            .as_ref()
+            .map(|metadata| metadata.height_of_longest_chain());
+        self.updated = true;
     }
 
-    pub fn get_output_manager_service_event_stream(&self) -> Fuse<OutputManagerEventReceiver> {
-        self.wallet.output_manager_service.get_event_stream_fused()
+    pub fn set_last_error(&mut self, message: String) {
+        self.data.last_error = Some(BackgroundError {
+            time: Local::now(),
+            message,
+        });
+        self.updated = true;
     }
 
-    pub fn get_connectivity_event_stream(&self) -> Fuse<ConnectivityEventRx> {
-        self.wallet.comms.connectivity().get_event_subscription().fuse()
+    pub fn get_shutdown_signal(&self) -> ShutdownSignal {
+        self.wallet.comms.shutdown_signal()
     }
 
-    pub fn get_base_node_event_stream(&self) -> Fuse<BaseNodeEventReceiver> {
-        self.wallet.base_node_service.clone().get_event_stream_fused()
+    pub fn get_wallet_event_stream(&self) -> Fuse<WalletEventReceiver> {
+        self.wallet.get_event_stream_fused()
     }
 
     pub async fn set_base_node_peer(&mut self, peer: Peer) -> Result<(), UiError> {
@@ -586,11 +1198,18 @@ impl AppStateInner {
             .await
         {
             error!(target: LOG_TARGET, "Problem validating transactions: {}", e);
+            self.set_last_error(format!("Problem validating transactions: {}", e));
         }
         self.validate_outputs().await;
 
         self.data.base_node_previous = self.data.base_node_selected.clone();
         self.data.base_node_selected = peer.clone();
+        // A configured base node replaces a saved base node that was in use, which must then not be restored on the
+        // next start
+        if self.data.base_node_peer_custom.take().is_some() {
+            self.clear_custom_base_node_keys().await?;
+        }
+        self.data.refresh_base_node_list();
         self.updated = true;
 
         info!(
@@ -625,15 +1244,14 @@ impl AppStateInner {
             .await
         {
             error!(target: LOG_TARGET, "Problem validating transactions: {}", e);
+            self.set_last_error(format!("Problem validating transactions: {}", e));
         }
         self.validate_outputs().await;
 
         self.data.base_node_previous = self.data.base_node_selected.clone();
         self.data.base_node_selected = peer.clone();
         self.data.base_node_peer_custom = Some(peer.clone());
-        self.data
-            .base_node_list
-            .insert(0, ("Custom Base Node".to_string(), peer.clone()));
+        self.data.refresh_base_node_list();
         self.updated = true;
 
         // persist the custom node in wallet db
@@ -685,15 +1303,19 @@ impl AppStateInner {
             .await
         {
             error!(target: LOG_TARGET, "Problem validating transactions: {}", e);
+            self.set_last_error(format!("Problem validating transactions: {}", e));
         }
         self.validate_outputs().await;
 
         self.data.base_node_peer_custom = None;
         self.data.base_node_selected = previous;
-        self.data.base_node_list.remove(0);
+        self.data.refresh_base_node_list();
         self.updated = true;
 
-        // clear from wallet db
+        self.clear_custom_base_node_keys().await
+    }
+
+    async fn clear_custom_base_node_keys(&mut self) -> Result<(), UiError> {
         self.wallet
             .db
             .clear_client_value(CUSTOM_BASE_NODE_PUBLIC_KEY_KEY.to_string())
@@ -705,6 +1327,25 @@ impl AppStateInner {
         Ok(())
     }
 
+    pub async fn save_base_node(&mut self, name: String, peer: Peer) -> Result<(), UiError> {
+        if self.data.base_node_saved.iter().any(|(n, p)| n == &name || p == &peer) {
+            return Err(UiError::DuplicateBaseNode(name));
+        }
+        self.data.base_node_saved.push((name, peer));
+        set_saved_base_nodes_in_db(&self.wallet, &self.data.base_node_saved).await?;
+        self.data.refresh_base_node_list();
+        self.updated = true;
+        Ok(())
+    }
+
+    pub async fn remove_saved_base_node(&mut self, peer: &Peer) -> Result<(), UiError> {
+        self.data.base_node_saved.retain(|(_, p)| p != peer);
+        set_saved_base_nodes_in_db(&self.wallet, &self.data.base_node_saved).await?;
+        self.data.refresh_base_node_list();
+        self.updated = true;
+        Ok(())
+    }
+
     pub async fn validate_outputs(&mut self) {
         if let Err(e) = self
             .wallet
@@ -713,6 +1354,7 @@ impl AppStateInner {
             .await
         {
             error!(target: LOG_TARGET, "Problem validating UTXOs: {}", e);
+            self.set_last_error(format!("Problem validating UTXOs: {}", e));
         }
 
         if let Err(e) = self
@@ -722,6 +1364,7 @@ impl AppStateInner {
             .await
         {
             error!(target: LOG_TARGET, "Problem validating STXOs: {}", e);
+            self.set_last_error(format!("Problem validating STXOs: {}", e));
         }
 
         if let Err(e) = self
@@ -731,6 +1374,7 @@ impl AppStateInner {
             .await
         {
             error!(target: LOG_TARGET, "Problem validating Invalid TXOs: {}", e);
+            self.set_last_error(format!("Problem validating Invalid TXOs: {}", e));
         }
     }
 }
@@ -740,6 +1384,7 @@ struct AppStateData {
     pending_txs: Vec<CompletedTransaction>,
     completed_txs: Vec<CompletedTransaction>,
     confirmations: HashMap<TxId, u64>,
+    transaction_labels: HashMap<TxId, Vec<String>>,
     my_identity: MyIdentity,
     contacts: Vec<UiContact>,
     connected_peers: Vec<Peer>,
@@ -748,7 +1393,14 @@ struct AppStateData {
     base_node_selected: Peer,
     base_node_previous: Peer,
     base_node_list: Vec<(String, Peer)>,
+    /// The service peers and peer seeds from the config
+    base_node_configured: Vec<(String, Peer)>,
+    base_node_saved: Vec<(String, Peer)>,
     base_node_peer_custom: Option<Peer>,
+    validated_height: Option<u64>,
+    last_error: Option<BackgroundError>,
+    watch_only: bool,
+    watched_outputs: Vec<WatchedOutput>,
 }
 
 impl AppStateData {
@@ -760,27 +1412,21 @@ impl AppStateData {
     ) -> Self
     {
         let eid = EmojiId::from_pubkey(node_identity.public_key()).to_string();
-        let qr_link = format!("tari://{}/pubkey/{}", network, &node_identity.public_key().to_hex());
-        let code = QrCode::new(qr_link).unwrap();
-        let image = code
-            .render::<unicode::Dense1x2>()
-            .dark_color(unicode::Dense1x2::Dark)
-            .light_color(unicode::Dense1x2::Light)
-            .build()
-            .lines()
-            .skip(1)
-            .fold("".to_string(), |acc, l| format!("{}{}\n", acc, l));
+        let qr_link = payment_uri(network, node_identity.public_key(), None, None);
+        let image = render_qr_code(&qr_link).unwrap_or_default();
 
         let identity = MyIdentity {
             public_key: node_identity.public_key().to_string(),
             public_address: node_identity.public_address().to_string(),
             emoji_id: eid,
             qr_code: image,
+            network,
+            comms_public_key: node_identity.public_key().clone(),
         };
         let base_node_previous = base_node_selected.clone();
 
         // set up our base node list from config
-        let mut base_node_list = base_node_config
+        let mut base_node_configured = base_node_config
             .base_node_peers
             .iter()
             .map(|peer| ("Service Peer".to_string(), peer.clone()))
@@ -793,17 +1439,13 @@ impl AppStateData {
             .map(|peer| ("Peer Seed".to_string(), peer.clone()))
             .collect::<Vec<(String, Peer)>>();
 
-        base_node_list.extend(peer_seeds);
+        base_node_configured.extend(peer_seeds);
 
-        // and prepend the custom base node if it exists
-        if let Some(peer) = base_node_config.base_node_custom.clone() {
-            base_node_list.insert(0, ("Custom Base Node".to_string(), peer));
-        }
-
-        AppStateData {
+        let mut data = AppStateData {
             pending_txs: Vec::new(),
             completed_txs: Vec::new(),
             confirmations: HashMap::new(),
+            transaction_labels: HashMap::new(),
             my_identity: identity,
             contacts: Vec::new(),
             connected_peers: Vec::new(),
@@ -811,18 +1453,142 @@ impl AppStateData {
             base_node_state: BaseNodeState::default(),
             base_node_selected,
             base_node_previous,
-            base_node_list,
+            base_node_list: Vec::new(),
+            base_node_configured,
+            base_node_saved: base_node_config.base_node_saved,
             base_node_peer_custom: base_node_config.base_node_custom,
+            validated_height: None,
+            last_error: None,
+            watch_only: false,
+            watched_outputs: Vec::new(),
+        };
+        data.refresh_base_node_list();
+        data
+    }
+
+    fn refresh_base_node_list(&mut self) {
+        self.base_node_list = base_node_list(
+            self.base_node_peer_custom.as_ref(),
+            &self.base_node_saved,
+            &self.base_node_configured,
+        );
+    }
+}
+
+/// The base nodes that can be selected: the custom base node if it is not one of the saved base nodes, then the saved
+/// base nodes and finally the base nodes from the config
+fn base_node_list(
+    custom: Option<&Peer>,
+    saved: &[(String, Peer)],
+    configured: &[(String, Peer)],
+) -> Vec<(String, Peer)>
+{
+    let mut list = Vec::with_capacity(saved.len() + configured.len() + 1);
+    if let Some(peer) = custom {
+        if !saved.iter().any(|(_, p)| p == peer) {
+            list.push(("Custom Base Node".to_string(), peer.clone()));
+        }
+    }
+    list.extend(
+        saved
+            .iter()
+            .map(|(name, peer)| (format!("Saved: {}", name), peer.clone())),
+    );
+    list.extend(configured.iter().cloned());
+    list
+}
+
+/// The balance of the wallet, split by why the funds can or cannot be spent yet
+#[derive(Clone, Debug, PartialEq)]
+pub struct BalanceBreakdown {
+    /// Funds that can be spent right now
+    pub spendable: MicroTari,
+    /// Funds that are available but cannot be spent until their lock height or maturity is reached
+    pub time_locked: MicroTari,
+    /// Funds, including change, that are due to be received once pending transactions are confirmed
+    pub pending_incoming: MicroTari,
+    /// Funds that are encumbered in pending outbound transactions
+    pub pending_outgoing: MicroTari,
+    /// The balance once all pending transactions are confirmed
+    pub total: MicroTari,
+}
+
+impl From<&Balance> for BalanceBreakdown {
+    fn from(balance: &Balance) -> Self {
+        let time_locked = balance.time_locked_balance.unwrap_or_else(|| MicroTari::from(0));
+        let spendable = MicroTari::from(balance.available_balance.0.saturating_sub(time_locked.0));
+        Self {
+            spendable,
+            time_locked,
+            pending_incoming: balance.pending_incoming_balance,
+            pending_outgoing: balance.pending_outgoing_balance,
+            total: balance.available_balance + balance.pending_incoming_balance,
         }
     }
 }
 
+fn apply_transaction_filter(mut data: AppStateData, filter: &Option<TransactionFilter>) -> AppStateData {
+    if let Some(filter) = filter {
+        data.pending_txs.retain(|tx| filter.matches(tx));
+        data.completed_txs.retain(|tx| filter.matches(tx));
+    }
+    data
+}
+
+/// An error that happened while the wallet state was being refreshed in the background
+#[derive(Clone, Debug)]
+pub struct BackgroundError {
+    pub time: DateTime<Local>,
+    pub message: String,
+}
+
+/// An unspent output of the wallet, as listed for coin control
+#[derive(Clone, Debug)]
+pub struct UiOutput {
+    pub commitment: Commitment,
+    pub value: MicroTari,
+    /// The block height from which the output can be spent
+    pub maturity: u64,
+}
+
+/// A hash time-locked output of an atomic swap, as listed on the swaps tab
+#[derive(Clone, Debug)]
+pub struct UiHtlc {
+    pub commitment: Commitment,
+    pub value: MicroTari,
+    pub htlc: HashTimeLock,
+    /// Whether this wallet can claim the output, otherwise it locked the output and can refund it
+    pub claimable: bool,
+}
+
+/// The connectivity status of the wallet and its active peer connections, as shown on the network statistics tab
+#[derive(Clone, Debug)]
+pub struct NetworkStats {
+    pub connectivity_status: ConnectivityStatus,
+    pub peers: Vec<UiPeerStats>,
+}
+
+/// The statistics of an active connection to a peer
+#[derive(Clone, Debug)]
+pub struct UiPeerStats {
+    pub node_id: NodeId,
+    pub user_agent: String,
+    pub address: Multiaddr,
+    pub direction: ConnectionDirection,
+    pub connected_for: Duration,
+    pub num_substreams: usize,
+    /// The average round trip time of the liveness pings answered by the peer, if any have been answered yet
+    pub latency: Option<Duration>,
+}
+
 #[derive(Clone)]
 pub struct MyIdentity {
     pub public_key: String,
     pub public_address: String,
     pub emoji_id: String,
     pub qr_code: String,
+    pub network: Network,
+    pub comms_public_key: CommsPublicKey,
 }
 
 pub async fn send_transaction_task(
@@ -830,6 +1596,7 @@ pub async fn send_transaction_task(
     amount: MicroTari,
     message: String,
     fee_per_gram: MicroTari,
+    inputs: Vec<Commitment>,
     mut transaction_service_handle: TransactionServiceHandle,
     result_tx: watch::Sender<UiTransactionSendStatus>,
 )
@@ -838,10 +1605,16 @@ pub async fn send_transaction_task(
     let mut event_stream = transaction_service_handle.get_event_stream_fused();
     let mut send_direct_received_result = (false, false);
     let mut send_saf_received_result = (false, false);
-    match transaction_service_handle
-        .send_transaction(public_key, amount, fee_per_gram, message)
-        .await
-    {
+    let send_result = if inputs.is_empty() {
+        transaction_service_handle
+            .send_transaction(public_key, amount, fee_per_gram, message)
+            .await
+    } else {
+        transaction_service_handle
+            .send_transaction_with_inputs(public_key, inputs, amount, fee_per_gram, message)
+            .await
+    };
+    match send_result {
         Err(e) => {
             let _ = result_tx.broadcast(UiTransactionSendStatus::Error(UiError::from(e).to_string()));
         },
@@ -898,6 +1671,50 @@ pub async fn send_transaction_task(
     }
 }
 
+pub async fn send_one_sided_transaction_task(
+    public_key: CommsPublicKey,
+    amount: MicroTari,
+    message: String,
+    fee_per_gram: MicroTari,
+    mut transaction_service_handle: TransactionServiceHandle,
+    result_tx: watch::Sender<UiTransactionSendStatus>,
+)
+{
+    let _ = result_tx.broadcast(UiTransactionSendStatus::Initiated);
+    match transaction_service_handle
+        .send_one_sided_transaction(public_key, amount, fee_per_gram, message)
+        .await
+    {
+        Err(e) => {
+            let _ = result_tx.broadcast(UiTransactionSendStatus::Error(UiError::from(e).to_string()));
+        },
+        Ok(_) => {
+            let _ = result_tx.broadcast(UiTransactionSendStatus::TransactionComplete);
+        },
+    }
+}
+
+fn parse_base_node_peer(public_key: &str, address: &str) -> Result<Peer, UiError> {
+    let pub_key = PublicKey::from_hex(public_key)?;
+    let addr = address.parse::<Multiaddr>().map_err(|_| UiError::AddressParseError)?;
+    let node_id = NodeId::from_key(&pub_key)?;
+    Ok(Peer::new(
+        pub_key,
+        node_id,
+        addr.into(),
+        PeerFlags::default(),
+        PeerFeatures::COMMUNICATION_NODE,
+        Default::default(),
+        Default::default(),
+    ))
+}
+
+fn parse_public_key_or_emoji_id(public_key_or_emoji_id: &str) -> Result<CommsPublicKey, UiError> {
+    CommsPublicKey::from_hex(public_key_or_emoji_id)
+        .or_else(|_| EmojiId::str_to_pubkey(public_key_or_emoji_id))
+        .map_err(|_| UiError::PublicKeyParseError)
+}
+
 #[derive(Clone)]
 pub enum UiTransactionSendStatus {
     Initiated,
@@ -1,4 +1,6 @@
 mod app_state;
+mod transaction_export;
+mod transaction_filter;
 mod wallet_event_monitor;
 
-pub use self::app_state::*;
+pub use self::{app_state::*, transaction_export::ExportFormat, transaction_filter::TransactionFilter};
@@ -0,0 +1,185 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use serde_json::json;
+use std::{
+    io::{self, Write},
+    path::Path,
+};
+use tari_crypto::tari_utilities::hex::Hex;
+use tari_wallet::transaction_service::storage::models::{CompletedTransaction, TransactionDirection};
+
+const CSV_HEADER: [&str; 12] = [
+    "TxID",
+    "Timestamp (UTC)",
+    "Direction",
+    "Status",
+    "Cancelled",
+    "Amount (uT)",
+    "Fee (uT)",
+    "Counterparty",
+    "Source Public Key",
+    "Destination Public Key",
+    "Message",
+    "Mined Height",
+];
+
+/// The file formats that the transaction history can be exported to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    /// JSON for file names ending in `.json`, CSV otherwise
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => ExportFormat::Json,
+            _ => ExportFormat::Csv,
+        }
+    }
+}
+
+/// Write the transactions to `writer` in the given format, one record per transaction
+pub fn write_transactions<W: Write>(
+    mut writer: W,
+    transactions: &[CompletedTransaction],
+    format: ExportFormat,
+) -> io::Result<()>
+{
+    match format {
+        ExportFormat::Csv => {
+            writeln!(writer, "{}", csv_line(&CSV_HEADER))?;
+            for tx in transactions {
+                let fields = transaction_fields(tx);
+                writeln!(writer, "{}", csv_line(&fields))?;
+            }
+        },
+        ExportFormat::Json => {
+            let records = transactions
+                .iter()
+                .map(|tx| {
+                    json!({
+                        "tx_id": tx.tx_id,
+                        "timestamp": format_timestamp(tx),
+                        "direction": tx.direction.to_string(),
+                        "status": tx.status.to_string(),
+                        "cancelled": tx.cancelled,
+                        "amount": tx.amount.0,
+                        "fee": tx.fee.0,
+                        "counterparty": counterparty(tx),
+                        "source_public_key": tx.source_public_key.to_hex(),
+                        "destination_public_key": tx.destination_public_key.to_hex(),
+                        "message": tx.message,
+                        "mined_height": tx.mined_height,
+                    })
+                })
+                .collect::<Vec<_>>();
+            serde_json::to_writer_pretty(&mut writer, &records)?;
+            writeln!(writer)?;
+        },
+    }
+    writer.flush()
+}
+
+fn transaction_fields(tx: &CompletedTransaction) -> Vec<String> {
+    vec![
+        tx.tx_id.to_string(),
+        format_timestamp(tx),
+        tx.direction.to_string(),
+        tx.status.to_string(),
+        tx.cancelled.to_string(),
+        tx.amount.0.to_string(),
+        tx.fee.0.to_string(),
+        counterparty(tx),
+        tx.source_public_key.to_hex(),
+        tx.destination_public_key.to_hex(),
+        tx.message.clone(),
+        tx.mined_height.map(|h| h.to_string()).unwrap_or_default(),
+    ]
+}
+
+fn format_timestamp(tx: &CompletedTransaction) -> String {
+    tx.timestamp.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// The public key of the other party of the transaction
+fn counterparty(tx: &CompletedTransaction) -> String {
+    match tx.direction {
+        TransactionDirection::Inbound => tx.source_public_key.to_hex(),
+        TransactionDirection::Outbound => tx.destination_public_key.to_hex(),
+        TransactionDirection::Unknown => "".to_string(),
+    }
+}
+
+fn csv_line<S: AsRef<str>>(fields: &[S]) -> String {
+    fields
+        .iter()
+        .map(|f| format!("\"{}\"", f.as_ref().replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn format_is_chosen_by_extension() {
+        assert_eq!(
+            ExportFormat::from_path(&PathBuf::from("history.json")),
+            ExportFormat::Json
+        );
+        assert_eq!(
+            ExportFormat::from_path(&PathBuf::from("history.JSON")),
+            ExportFormat::Json
+        );
+        assert_eq!(
+            ExportFormat::from_path(&PathBuf::from("history.csv")),
+            ExportFormat::Csv
+        );
+        assert_eq!(ExportFormat::from_path(&PathBuf::from("history")), ExportFormat::Csv);
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_and_escaped() {
+        assert_eq!(
+            csv_line(&["1", "coffee, \"large\"", ""]),
+            r#""1","coffee, ""large""","""#
+        );
+    }
+
+    #[test]
+    fn empty_history_has_a_header() {
+        let mut csv = Vec::new();
+        write_transactions(&mut csv, &[], ExportFormat::Csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert!(csv.starts_with("\"TxID\",\"Timestamp (UTC)\""));
+        assert_eq!(csv.lines().count(), 1);
+
+        let mut json = Vec::new();
+        write_transactions(&mut json, &[], ExportFormat::Json).unwrap();
+        assert_eq!(String::from_utf8(json).unwrap().trim(), "[]");
+    }
+}
@@ -0,0 +1,210 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::str::FromStr;
+use tari_core::transactions::tari_amount::MicroTari;
+use tari_crypto::tari_utilities::hex::Hex;
+use tari_wallet::{
+    output_manager_service::TxId,
+    transaction_service::storage::models::CompletedTransaction,
+    util::emoji::EmojiId,
+};
+
+/// A search of the transaction lists, parsed from a query such as `coffee status:mined amount:1000..5000`.
+///
+/// The query is a list of space separated terms, all of which must match:
+/// - `id:<tx_id>` matches a single transaction
+/// - `amount:<min>..<max>` matches amounts (in uT) in the inclusive range, either bound can be left out, and
+///   `amount:<value>` matches an exact amount
+/// - `status:<status>` matches the start of the transaction status, e.g. `status:mined`, or `status:cancelled`
+/// - any other term matches part of the message, the tx_id or the public key or emoji ID of either party
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TransactionFilter {
+    terms: Vec<String>,
+    tx_id: Option<TxId>,
+    min_amount: Option<MicroTari>,
+    max_amount: Option<MicroTari>,
+    status: Option<String>,
+}
+
+impl TransactionFilter {
+    pub fn matches(&self, tx: &CompletedTransaction) -> bool {
+        if self.tx_id.map(|id| id != tx.tx_id).unwrap_or(false) {
+            return false;
+        }
+        if self.min_amount.map(|min| tx.amount < min).unwrap_or(false) ||
+            self.max_amount.map(|max| tx.amount > max).unwrap_or(false)
+        {
+            return false;
+        }
+        if let Some(status) = &self.status {
+            let matches_status = if status == "cancelled" {
+                tx.cancelled
+            } else {
+                normalize_status(&tx.status.to_string()).starts_with(status.as_str())
+            };
+            if !matches_status {
+                return false;
+            }
+        }
+        if self.terms.is_empty() {
+            return true;
+        }
+
+        let searchable = [
+            tx.message.to_lowercase(),
+            tx.tx_id.to_string(),
+            tx.source_public_key.to_hex(),
+            tx.destination_public_key.to_hex(),
+            EmojiId::from_pubkey(&tx.source_public_key).to_string(),
+            EmojiId::from_pubkey(&tx.destination_public_key).to_string(),
+        ];
+        self.terms
+            .iter()
+            .all(|term| searchable.iter().any(|field| field.contains(term.as_str())))
+    }
+}
+
+impl FromStr for TransactionFilter {
+    type Err = String;
+
+    fn from_str(query: &str) -> Result<Self, Self::Err> {
+        let mut filter = TransactionFilter::default();
+        for term in query.split_whitespace() {
+            let term = term.to_lowercase();
+            if let Some(id) = term.strip_prefix("id:") {
+                filter.tx_id = Some(id.parse().map_err(|_| format!("Invalid transaction id: {}", id))?);
+            } else if let Some(range) = term.strip_prefix("amount:") {
+                let (min, max) = parse_amount_range(range)?;
+                filter.min_amount = min;
+                filter.max_amount = max;
+            } else if let Some(status) = term.strip_prefix("status:") {
+                filter.status = Some(normalize_status(status));
+            } else {
+                filter.terms.push(term);
+            }
+        }
+        Ok(filter)
+    }
+}
+
+fn parse_amount_range(range: &str) -> Result<(Option<MicroTari>, Option<MicroTari>), String> {
+    let parse_bound = |bound: &str| -> Result<Option<MicroTari>, String> {
+        if bound.is_empty() {
+            return Ok(None);
+        }
+        bound
+            .parse::<u64>()
+            .map(|v| Some(MicroTari::from(v)))
+            .map_err(|_| format!("Invalid amount: {}", bound))
+    };
+    match range.find("..") {
+        Some(index) => Ok((parse_bound(&range[..index])?, parse_bound(&range[index + 2..])?)),
+        None => {
+            let amount = parse_bound(range)?;
+            Ok((amount, amount))
+        },
+    }
+}
+
+/// Lower case the status and strip the spaces, so that `minedconfirmed` matches `Mined Confirmed`
+fn normalize_status(status: &str) -> String {
+    status.to_lowercase().replace(' ', "")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::Utc;
+    use rand::rngs::OsRng;
+    use tari_core::transactions::{
+        tari_amount::uT,
+        transaction::Transaction,
+        types::{PrivateKey, PublicKey},
+    };
+    use tari_crypto::keys::{PublicKey as PK, SecretKey as SK};
+    use tari_wallet::transaction_service::storage::models::{TransactionDirection, TransactionStatus};
+
+    fn transaction(tx_id: TxId, amount: MicroTari, status: TransactionStatus, message: &str) -> CompletedTransaction {
+        CompletedTransaction::new(
+            tx_id,
+            PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+            PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+            amount,
+            uT * 100,
+            Transaction::new(vec![], vec![], vec![], PrivateKey::default()),
+            status,
+            message.to_string(),
+            Utc::now().naive_utc(),
+            TransactionDirection::Outbound,
+            None,
+        )
+    }
+
+    #[test]
+    fn terms_are_parsed() {
+        let filter = "Coffee id:42 amount:100..2000 status:Mined"
+            .parse::<TransactionFilter>()
+            .unwrap();
+        assert_eq!(filter.terms, vec!["coffee".to_string()]);
+        assert_eq!(filter.tx_id, Some(42));
+        assert_eq!(filter.min_amount, Some(100 * uT));
+        assert_eq!(filter.max_amount, Some(2000 * uT));
+        assert_eq!(filter.status, Some("mined".to_string()));
+
+        let filter = "amount:..500".parse::<TransactionFilter>().unwrap();
+        assert_eq!(filter.min_amount, None);
+        assert_eq!(filter.max_amount, Some(500 * uT));
+
+        assert!("id:abc".parse::<TransactionFilter>().is_err());
+        assert!("amount:1..x".parse::<TransactionFilter>().is_err());
+        assert_eq!("".parse::<TransactionFilter>().unwrap(), TransactionFilter::default());
+    }
+
+    #[test]
+    fn filter_matches_transactions() {
+        let coffee = transaction(1, 1500 * uT, TransactionStatus::MinedConfirmed, "Large coffee");
+        let rent = transaction(2, 90_000 * uT, TransactionStatus::Pending, "Rent");
+        let matching = |query: &str| {
+            let filter = query.parse::<TransactionFilter>().unwrap();
+            [&coffee, &rent]
+                .iter()
+                .filter(|tx| filter.matches(tx))
+                .map(|tx| tx.tx_id)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(matching(""), vec![1, 2]);
+        assert_eq!(matching("COFFEE"), vec![1]);
+        assert_eq!(matching("id:2"), vec![2]);
+        assert_eq!(matching("amount:1000..2000"), vec![1]);
+        assert_eq!(matching("amount:2000.."), vec![2]);
+        assert_eq!(matching("status:minedconfirmed"), vec![1]);
+        assert_eq!(matching("status:pending rent"), vec![2]);
+        assert_eq!(matching("status:cancelled"), Vec::<TxId>::new());
+        assert_eq!(matching(&rent.destination_public_key.to_hex()), vec![2]);
+        assert_eq!(
+            matching(&EmojiId::from_pubkey(&coffee.source_public_key).to_string()),
+            vec![1]
+        );
+    }
+}
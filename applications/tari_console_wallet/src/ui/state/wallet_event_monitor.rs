@@ -21,137 +21,101 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::{notifier::Notifier, ui::state::AppStateInner};
-use futures::stream::StreamExt;
+use futures::stream::{self, StreamExt};
 use log::*;
 use std::sync::Arc;
-use tari_comms::{connectivity::ConnectivityEvent, peer_manager::Peer};
+use tari_comms::peer_manager::Peer;
 use tari_wallet::{
     base_node_service::{handle::BaseNodeEvent, service::BaseNodeState},
+    events::WalletEvent,
     output_manager_service::{handle::OutputManagerEvent, TxId},
+    tasks::watch_only_scan::WatchOnlyScanEvent,
     transaction_service::handle::TransactionEvent,
 };
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 
 const LOG_TARGET: &str = "wallet::console_wallet::wallet_event_monitor";
 
 pub struct WalletEventMonitor {
     app_state_inner: Arc<RwLock<AppStateInner>>,
+    /// The events of the scanner of a watch-only wallet, if this is one
+    watch_only_scan_events: Option<broadcast::Receiver<WatchOnlyScanEvent>>,
 }
 
 impl WalletEventMonitor {
-    pub fn new(app_state_inner: Arc<RwLock<AppStateInner>>) -> Self {
-        Self { app_state_inner }
+    pub fn new(
+        app_state_inner: Arc<RwLock<AppStateInner>>,
+        watch_only_scan_events: Option<broadcast::Receiver<WatchOnlyScanEvent>>,
+    ) -> Self
+    {
+        Self {
+            app_state_inner,
+            watch_only_scan_events,
+        }
     }
 
     pub async fn run(mut self, notifier: Notifier) {
         let mut shutdown_signal = self.app_state_inner.read().await.get_shutdown_signal();
-        let mut transaction_service_events = self.app_state_inner.read().await.get_transaction_service_event_stream();
-
-        let mut output_manager_service_events = self
-            .app_state_inner
-            .read()
-            .await
-            .get_output_manager_service_event_stream();
-
-        let mut connectivity_events = self.app_state_inner.read().await.get_connectivity_event_stream();
+        let mut wallet_events = self.app_state_inner.read().await.get_wallet_event_stream();
 
-        let mut base_node_events = self.app_state_inner.read().await.get_base_node_event_stream();
+        let mut watch_only_scan_events = match self.watch_only_scan_events.take() {
+            Some(events) => events.boxed(),
+            None => stream::pending().boxed(),
+        }
+        .fuse();
 
         info!(target: LOG_TARGET, "Wallet Event Monitor starting");
         loop {
             futures::select! {
-                    result = transaction_service_events.select_next_some() => {
+                    result = wallet_events.select_next_some() => {
                         match result {
                             Ok(msg) => {
                                 trace!(target: LOG_TARGET, "Wallet Event Monitor received wallet event {:?}", msg);
-                                match (*msg).clone() {
-                                    TransactionEvent::ReceivedFinalizedTransaction(tx_id) => {
-                                        self.trigger_tx_state_refresh(tx_id).await;
-                                        notifier.transaction_received(tx_id);
-                                    },
-                                    TransactionEvent::TransactionMinedUnconfirmed(tx_id, confirmations) => {
-                                        self.trigger_confirmations_refresh(tx_id, confirmations).await;
-                                        self.trigger_tx_state_refresh(tx_id).await;
-                                        notifier.transaction_mined_unconfirmed(tx_id, confirmations);
-                                    },
-                                    TransactionEvent::TransactionMined(tx_id) => {
-                                        self.trigger_confirmations_cleanup(tx_id).await;
-                                        self.trigger_tx_state_refresh(tx_id).await;
-                                        notifier.transaction_mined(tx_id);
-                                    },
-                                    TransactionEvent::TransactionCancelled(tx_id) => {
-                                        self.trigger_tx_state_refresh(tx_id).await;
-                                        notifier.transaction_cancelled(tx_id);
+                                match &*msg {
+                                    WalletEvent::Transaction(event) => self.on_transaction_event(event, &notifier).await,
+                                    WalletEvent::Connectivity(event) => {
+                                        if event.is_peer_state_change() {
+                                            self.trigger_peer_state_refresh().await;
+                                        }
                                     },
-                                    TransactionEvent::ReceivedTransaction(tx_id) |
-                                    TransactionEvent::ReceivedTransactionReply(tx_id) |
-                                    TransactionEvent::TransactionBroadcast(tx_id) |
-                                    TransactionEvent::TransactionMinedRequestTimedOut(tx_id) => {
-                                        self.trigger_tx_state_refresh(tx_id).await;
+                                    WalletEvent::BaseNode(event) => match &**event {
+                                        BaseNodeEvent::BaseNodeStateChanged(state) => {
+                                            self.trigger_base_node_state_refresh(state.clone()).await;
+                                        },
+                                        BaseNodeEvent::BaseNodePeerSet(peer) => {
+                                            self.trigger_base_node_peer_refresh((**peer).clone()).await;
+                                        },
                                     },
-                                    TransactionEvent::TransactionDirectSendResult(tx_id, true) |
-                                    TransactionEvent::TransactionStoreForwardSendResult(tx_id, true) |
-                                    TransactionEvent::TransactionCompletedImmediately(tx_id) => {
-                                        self.trigger_tx_state_refresh(tx_id).await;
-                                        notifier.transaction_sent(tx_id);
+                                    WalletEvent::BaseNodeChanged(peer) => {
+                                        self.trigger_base_node_peer_refresh((**peer).clone()).await;
                                     },
-                                    TransactionEvent::TransactionValidationSuccess(_) => {
-                                        self.trigger_full_tx_state_refresh().await;
+                                    WalletEvent::OutputManager(event) => {
+                                        if let OutputManagerEvent::TxoValidationSuccess(_, _) = &**event {
+                                            self.trigger_balance_refresh().await;
+                                            self.trigger_validated_height_refresh().await;
+                                        }
                                     },
-                                    // Only the above variants trigger state refresh
-                                    _ => (),
+                                    WalletEvent::BalanceChanged(_) => self.trigger_balance_refresh().await,
+                                    WalletEvent::Contacts(_) => self.trigger_contacts_refresh().await,
                                 }
                             },
-                            Err(_) => debug!(target: LOG_TARGET, "Lagging read on Transaction Service event broadcast channel"),
+                            Err(_) => debug!(target: LOG_TARGET, "Lagging read on wallet event broadcast channel"),
                         }
                     },
-                    result = connectivity_events.select_next_some() => {
+                    result = watch_only_scan_events.select_next_some() => {
                         match result {
-                            Ok(msg) => {
-                                trace!(target: LOG_TARGET, "Wallet Event Monitor received wallet event {:?}", msg);
-                                match &*msg {
-                                    ConnectivityEvent::PeerDisconnected(_) |
-                                    ConnectivityEvent::ManagedPeerDisconnected(_) |
-                                    ConnectivityEvent::PeerConnected(_) |
-                                    ConnectivityEvent::PeerBanned(_) |
-                                    ConnectivityEvent::PeerOffline(_) |
-                                    ConnectivityEvent::PeerConnectionWillClose(_, _) => {
-                                        self.trigger_peer_state_refresh().await;
-                                    },
-                                    // Only the above variants trigger state refresh
-                                    _ => (),
-                                }
+                            Ok(WatchOnlyScanEvent::ScanCompleted(_, _)) => {
+                                self.trigger_watched_outputs_refresh().await;
                             },
-                            Err(_) => debug!(target: LOG_TARGET, "Lagging read on Connectivity event broadcast channel"),
-                        }
-                    },
-                    result = base_node_events.select_next_some() => {
-                        match result {
-                            Ok(msg) => {
-                                trace!(target: LOG_TARGET, "Wallet Event Monitor received base node event {:?}", msg);
-                                match (*msg).clone() {
-                                    BaseNodeEvent::BaseNodeStateChanged(state) => {
-                                        self.trigger_base_node_state_refresh(state).await;
-                                    }
-                                    BaseNodeEvent::BaseNodePeerSet(peer) => {
-                                        self.trigger_base_node_peer_refresh(*peer).await;
-                                    }
-                                }
+                            Ok(WatchOnlyScanEvent::ScanFailed(e)) => {
+                                self.app_state_inner
+                                    .write()
+                                    .await
+                                    .set_last_error(format!("The scan for watched outputs failed: {}", e));
                             },
-                            Err(_) => debug!(target: LOG_TARGET, "Lagging read on base node event broadcast channel"),
+                            Err(_) => debug!(target: LOG_TARGET, "Lagging read on watch-only scan event broadcast channel"),
                         }
                     },
-                    result = output_manager_service_events.select_next_some() => {
-                        match result {
-                            Ok(msg) => {
-                                trace!(target: LOG_TARGET, "Output Manager Service Callback Handler event {:?}", msg);
-                                if let OutputManagerEvent::TxoValidationSuccess(_,_) = &*msg {
-                                    self.trigger_balance_refresh().await;
-                                }
-                            },
-                            Err(_e) => error!(target: LOG_TARGET, "Error reading from Output Manager Service event broadcast channel"),
-                        }
-                },
                     complete => {
                         info!(target: LOG_TARGET, "Wallet Event Monitor is exiting because all tasks have completed");
                         break;
@@ -164,11 +128,55 @@ impl WalletEventMonitor {
         }
     }
 
+    async fn on_transaction_event(&mut self, event: &TransactionEvent, notifier: &Notifier) {
+        match *event {
+            TransactionEvent::ReceivedFinalizedTransaction(tx_id) => {
+                self.trigger_tx_state_refresh(tx_id).await;
+                notifier.transaction_received(tx_id);
+            },
+            TransactionEvent::TransactionMinedUnconfirmed(tx_id, confirmations) => {
+                self.trigger_confirmations_refresh(tx_id, confirmations).await;
+                self.trigger_tx_state_refresh(tx_id).await;
+                notifier.transaction_mined_unconfirmed(tx_id, confirmations);
+            },
+            TransactionEvent::TransactionMined(tx_id) => {
+                self.trigger_confirmations_cleanup(tx_id).await;
+                self.trigger_tx_state_refresh(tx_id).await;
+                notifier.transaction_mined(tx_id);
+            },
+            TransactionEvent::TransactionCancelled(tx_id) => {
+                self.trigger_tx_state_refresh(tx_id).await;
+                notifier.transaction_cancelled(tx_id);
+            },
+            TransactionEvent::TransactionBroadcast(tx_id) => {
+                self.trigger_tx_state_refresh(tx_id).await;
+                notifier.transaction_broadcast(tx_id);
+            },
+            TransactionEvent::ReceivedTransaction(tx_id) |
+            TransactionEvent::ReceivedTransactionReply(tx_id) |
+            TransactionEvent::TransactionMinedRequestTimedOut(tx_id) => {
+                self.trigger_tx_state_refresh(tx_id).await;
+            },
+            TransactionEvent::TransactionDirectSendResult(tx_id, true) |
+            TransactionEvent::TransactionStoreForwardSendResult(tx_id, true) |
+            TransactionEvent::TransactionCompletedImmediately(tx_id) => {
+                self.trigger_tx_state_refresh(tx_id).await;
+                notifier.transaction_sent(tx_id);
+            },
+            TransactionEvent::TransactionValidationSuccess(_) => {
+                self.trigger_full_tx_state_refresh().await;
+            },
+            // Only the above variants trigger state refresh
+            _ => (),
+        }
+    }
+
     async fn trigger_tx_state_refresh(&mut self, tx_id: TxId) {
         let mut inner = self.app_state_inner.write().await;
 
         if let Err(e) = inner.refresh_single_transaction_state(tx_id).await {
             warn!(target: LOG_TARGET, "Error refresh app_state: {}", e);
+            inner.set_last_error(format!("Could not refresh the wallet state: {}", e));
         }
     }
 
@@ -177,6 +185,7 @@ impl WalletEventMonitor {
 
         if let Err(e) = inner.refresh_single_confirmation_state(tx_id, confirmations).await {
             warn!(target: LOG_TARGET, "Error refresh app_state: {}", e);
+            inner.set_last_error(format!("Could not refresh the wallet state: {}", e));
         }
     }
 
@@ -185,6 +194,7 @@ impl WalletEventMonitor {
 
         if let Err(e) = inner.cleanup_single_confirmation_state(tx_id).await {
             warn!(target: LOG_TARGET, "Error refresh app_state: {}", e);
+            inner.set_last_error(format!("Could not refresh the wallet state: {}", e));
         }
     }
 
@@ -193,6 +203,7 @@ impl WalletEventMonitor {
 
         if let Err(e) = inner.refresh_full_transaction_state().await {
             warn!(target: LOG_TARGET, "Error refresh app_state: {}", e);
+            inner.set_last_error(format!("Could not refresh the wallet state: {}", e));
         }
     }
 
@@ -201,6 +212,7 @@ impl WalletEventMonitor {
 
         if let Err(e) = inner.refresh_connected_peers_state().await {
             warn!(target: LOG_TARGET, "Error refresh app_state: {}", e);
+            inner.set_last_error(format!("Could not refresh the wallet state: {}", e));
         }
     }
 
@@ -209,6 +221,7 @@ impl WalletEventMonitor {
 
         if let Err(e) = inner.refresh_base_node_state(state).await {
             warn!(target: LOG_TARGET, "Error refresh app_state: {}", e);
+            inner.set_last_error(format!("Could not refresh the wallet state: {}", e));
         }
     }
 
@@ -217,6 +230,7 @@ impl WalletEventMonitor {
 
         if let Err(e) = inner.refresh_base_node_peer(peer).await {
             warn!(target: LOG_TARGET, "Error refresh app_state: {}", e);
+            inner.set_last_error(format!("Could not refresh the wallet state: {}", e));
         }
     }
 
@@ -225,6 +239,31 @@ impl WalletEventMonitor {
 
         if let Err(e) = inner.refresh_balance().await {
             warn!(target: LOG_TARGET, "Error refresh app_state: {}", e);
+            inner.set_last_error(format!("Could not refresh the wallet state: {}", e));
+        }
+    }
+
+    async fn trigger_contacts_refresh(&mut self) {
+        let mut inner = self.app_state_inner.write().await;
+
+        if let Err(e) = inner.refresh_contacts_state().await {
+            warn!(target: LOG_TARGET, "Error refresh app_state: {}", e);
+            inner.set_last_error(format!("Could not refresh the wallet state: {}", e));
         }
     }
+
+    async fn trigger_watched_outputs_refresh(&mut self) {
+        let mut inner = self.app_state_inner.write().await;
+
+        if let Err(e) = inner.refresh_watched_outputs().await {
+            warn!(target: LOG_TARGET, "Error refresh app_state: {}", e);
+            inner.set_last_error(format!("Could not refresh the wallet state: {}", e));
+        }
+    }
+
+    async fn trigger_validated_height_refresh(&mut self) {
+        let mut inner = self.app_state_inner.write().await;
+
+        inner.refresh_validated_height();
+    }
 }
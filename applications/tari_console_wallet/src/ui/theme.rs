@@ -0,0 +1,241 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::collections::HashMap;
+use thiserror::Error;
+use tui::style::{Color, Modifier, Style};
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ThemeError {
+    #[error("Unknown theme preset `{0}`, expected one of dark, light or monochrome")]
+    UnknownPreset(String),
+    #[error(
+        "Unknown theme style `{0}`, expected one of preset, text, title, border, label, highlight, success, warning, \
+         error, muted, key or menu"
+    )]
+    UnknownStyle(String),
+    #[error(
+        "Invalid color `{color}` for `{style}`, expected a color name such as light_blue or a hex code such as #ff8800"
+    )]
+    InvalidColor { style: String, color: String },
+}
+
+/// The styles that the components of the UI draw with. A theme starts from one of the presets, which the
+/// `[wallet.ui.theme]` section of the config file can override the colors of.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    /// Values and other plain text
+    pub text: Style,
+    /// The titles of blocks and headings
+    pub title: Style,
+    /// The borders of blocks
+    pub border: Style,
+    /// Field labels, the column headings of lists and the field that is being edited
+    pub label: Style,
+    /// The selected row of a list
+    pub highlight: Style,
+    pub success: Style,
+    pub warning: Style,
+    pub error: Style,
+    /// Text that is less important, such as debug messages and hints
+    pub muted: Style,
+    /// The keys listed in the menu bar and help
+    pub key: Style,
+    /// The commands of the menu bar
+    pub menu: Style,
+}
+
+impl Theme {
+    /// The colors the wallet has always used, for terminals with a dark background
+    pub fn dark() -> Self {
+        Self {
+            text: Style::default().fg(Color::White),
+            title: Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+            border: Style::default(),
+            label: Style::default().fg(Color::Magenta),
+            highlight: Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+            success: Style::default().fg(Color::Green),
+            warning: Style::default().fg(Color::Yellow),
+            error: Style::default().fg(Color::Red),
+            muted: Style::default().fg(Color::DarkGray),
+            key: Style::default().fg(Color::Green),
+            menu: Style::default()
+                .fg(Color::Magenta)
+                .bg(Color::LightGreen)
+                .add_modifier(Modifier::BOLD),
+        }
+    }
+
+    /// Colors that can be read on a light background, which white and yellow text cannot
+    pub fn light() -> Self {
+        Self {
+            text: Style::default().fg(Color::Black),
+            title: Style::default().fg(Color::Black).add_modifier(Modifier::BOLD),
+            border: Style::default().fg(Color::DarkGray),
+            label: Style::default().fg(Color::Blue),
+            highlight: Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD),
+            success: Style::default().fg(Color::Green),
+            warning: Style::default().fg(Color::Magenta),
+            error: Style::default().fg(Color::Red),
+            muted: Style::default().fg(Color::Gray),
+            key: Style::default().fg(Color::Blue),
+            menu: Style::default()
+                .fg(Color::White)
+                .bg(Color::Blue)
+                .add_modifier(Modifier::BOLD),
+        }
+    }
+
+    /// No colors at all, only the terminal's own foreground with bold, reversed and dim text
+    pub fn monochrome() -> Self {
+        Self {
+            text: Style::default(),
+            title: Style::default().add_modifier(Modifier::BOLD),
+            border: Style::default(),
+            label: Style::default().add_modifier(Modifier::UNDERLINED),
+            highlight: Style::default().add_modifier(Modifier::REVERSED),
+            success: Style::default().add_modifier(Modifier::BOLD),
+            warning: Style::default().add_modifier(Modifier::BOLD),
+            error: Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            muted: Style::default().add_modifier(Modifier::DIM),
+            key: Style::default().add_modifier(Modifier::BOLD),
+            menu: Style::default().add_modifier(Modifier::REVERSED),
+        }
+    }
+
+    /// Build the theme from the style name to color name pairs of the config file, where `preset` picks the theme
+    /// that the colors are applied to
+    pub fn from_config(config: &HashMap<String, String>) -> Result<Self, ThemeError> {
+        let mut theme = match config.get("preset").map(String::as_str) {
+            None | Some("dark") => Theme::dark(),
+            Some("light") => Theme::light(),
+            Some("monochrome") => Theme::monochrome(),
+            Some(other) => return Err(ThemeError::UnknownPreset(other.to_string())),
+        };
+        for (style, color) in config.iter().filter(|(style, _)| *style != "preset") {
+            let target = match style.as_str() {
+                "text" => &mut theme.text,
+                "title" => &mut theme.title,
+                "border" => &mut theme.border,
+                "label" => &mut theme.label,
+                "highlight" => &mut theme.highlight,
+                "success" => &mut theme.success,
+                "warning" => &mut theme.warning,
+                "error" => &mut theme.error,
+                "muted" => &mut theme.muted,
+                "key" => &mut theme.key,
+                "menu" => &mut theme.menu,
+                other => return Err(ThemeError::UnknownStyle(other.to_string())),
+            };
+            let color = parse_color(color).ok_or_else(|| ThemeError::InvalidColor {
+                style: style.clone(),
+                color: color.clone(),
+            })?;
+            *target = target.fg(color);
+        }
+        Ok(theme)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::dark()
+    }
+}
+
+fn parse_color(s: &str) -> Option<Color> {
+    if let Some(hex) = s.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let rgb = u32::from_str_radix(hex, 16).ok()?;
+        return Some(Color::Rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8));
+    }
+    let color = match s.to_lowercase().replace('-', "_").as_str() {
+        "reset" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "dark_gray" | "dark_grey" => Color::DarkGray,
+        "light_red" => Color::LightRed,
+        "light_green" => Color::LightGreen,
+        "light_yellow" => Color::LightYellow,
+        "light_blue" => Color::LightBlue,
+        "light_magenta" => Color::LightMagenta,
+        "light_cyan" => Color::LightCyan,
+        "white" => Color::White,
+        _ => return None,
+    };
+    Some(color)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(s, c)| (s.to_string(), c.to_string())).collect()
+    }
+
+    #[test]
+    fn presets_and_overrides() {
+        assert_eq!(Theme::from_config(&config(&[])).unwrap(), Theme::dark());
+        assert_eq!(
+            Theme::from_config(&config(&[("preset", "monochrome")])).unwrap(),
+            Theme::monochrome()
+        );
+
+        let theme = Theme::from_config(&config(&[
+            ("preset", "light"),
+            ("border", "light-blue"),
+            ("error", "#ff8800"),
+        ]))
+        .unwrap();
+        assert_eq!(theme.border, Style::default().fg(Color::LightBlue));
+        assert_eq!(theme.error, Style::default().fg(Color::Rgb(0xff, 0x88, 0x00)));
+        assert_eq!(theme.highlight, Theme::light().highlight);
+    }
+
+    #[test]
+    fn invalid_themes_are_rejected() {
+        assert_eq!(
+            Theme::from_config(&config(&[("preset", "solarized")])).unwrap_err(),
+            ThemeError::UnknownPreset("solarized".to_string())
+        );
+        assert_eq!(
+            Theme::from_config(&config(&[("borders", "blue")])).unwrap_err(),
+            ThemeError::UnknownStyle("borders".to_string())
+        );
+        assert_eq!(
+            Theme::from_config(&config(&[("text", "#fff")])).unwrap_err(),
+            ThemeError::InvalidColor {
+                style: "text".to_string(),
+                color: "#fff".to_string()
+            }
+        );
+    }
+}
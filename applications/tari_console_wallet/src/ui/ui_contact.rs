@@ -1,10 +1,29 @@
-use tari_wallet::{contacts_service::storage::database::Contact, util::emoji::EmojiId};
+use tari_wallet::{
+    contacts_service::{
+        handle::{ContactOnlineStatus, ContactsLivenessData},
+        storage::database::Contact,
+    },
+    util::emoji::EmojiId,
+};
 
 #[derive(Debug, Clone)]
 pub struct UiContact {
     pub alias: String,
     pub public_key: String,
     pub emoji_id: String,
+    pub online_status: ContactOnlineStatus,
+    pub last_seen: String,
+}
+
+impl UiContact {
+    pub fn with_liveness(mut self, liveness: &ContactsLivenessData) -> Self {
+        self.online_status = liveness.online_status;
+        self.last_seen = match liveness.last_seen {
+            Some(last_seen) => last_seen.format("%Y-%m-%d %H:%M:%S").to_string(),
+            None => String::new(),
+        };
+        self
+    }
 }
 
 impl From<Contact> for UiContact {
@@ -13,6 +32,8 @@ impl From<Contact> for UiContact {
             alias: c.alias,
             public_key: c.public_key.to_string(),
             emoji_id: EmojiId::from_pubkey(&c.public_key).as_str().to_string(),
+            online_status: ContactOnlineStatus::NeverSeen,
+            last_seen: String::new(),
         }
     }
 }
@@ -1,9 +1,11 @@
 use tari_comms::{connectivity::ConnectivityError, peer_manager::node_id::NodeIdError};
 use tari_crypto::tari_utilities::hex::HexError;
+use tari_p2p::services::liveness::error::LivenessError;
 use tari_wallet::{
     contacts_service::error::ContactsServiceError,
     error::{WalletError, WalletStorageError},
     output_manager_service::error::OutputManagerError,
+    scheduler_service::error::SchedulerServiceError,
     transaction_service::error::TransactionServiceError,
 };
 use thiserror::Error;
@@ -17,8 +19,12 @@ pub enum UiError {
     #[error(transparent)]
     ContactsServiceError(#[from] ContactsServiceError),
     #[error(transparent)]
+    SchedulerServiceError(#[from] SchedulerServiceError),
+    #[error(transparent)]
     ConnectivityError(#[from] ConnectivityError),
     #[error(transparent)]
+    LivenessError(#[from] LivenessError),
+    #[error(transparent)]
     HexError(#[from] HexError),
     #[error(transparent)]
     NodeIdError(#[from] NodeIdError),
@@ -28,8 +34,20 @@ pub enum UiError {
     WalletStorageError(#[from] WalletStorageError),
     #[error("Could not convert string into Public Key")]
     PublicKeyParseError,
+    #[error("The hash lock must be 64 hex characters")]
+    HashLockParseError,
     #[error("Could not convert string into Net Address")]
     AddressParseError,
     #[error("Peer did not include an address")]
     NoAddressError,
+    #[error("A base node named `{0}` or with the same public key is already saved")]
+    DuplicateBaseNode(String),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error("Price feed error: `{0}`")]
+    PriceFeedError(String),
+    #[error("Clipboard error: `{0}`")]
+    ClipboardError(String),
+    #[error("A watch-only wallet cannot send funds")]
+    WatchOnly,
 }
@@ -69,6 +69,16 @@ impl WindowedListState {
         (self.start, self.end)
     }
 
+    /// The index of the item shown on the given line of the current window, if there is one
+    pub fn item_at(&self, line: usize) -> Option<usize> {
+        let index = self.start + line;
+        if index < self.end {
+            Some(index)
+        } else {
+            None
+        }
+    }
+
     pub fn next(&mut self) {
         if self.num_items != 0 {
             let i = match self.selected {
@@ -193,4 +203,18 @@ mod test {
         let window = list_state.get_start_end();
         assert_eq!(window, (5, 9));
     }
+
+    #[test]
+    fn test_item_at_line_of_window() {
+        let mut list_state = WindowedListState::new();
+        list_state.set_num_items(10);
+        for _ in 0..6 {
+            list_state.next();
+        }
+        let _state = list_state.get_list_state(4);
+        assert_eq!(list_state.get_start_end(), (2, 6));
+        assert_eq!(list_state.item_at(0), Some(2));
+        assert_eq!(list_state.item_at(3), Some(5));
+        assert_eq!(list_state.item_at(4), None);
+    }
 }
@@ -88,7 +88,7 @@ where T: Into<Vec<ListItem<'a>>>
         }
 
         if let Some(w) = self.max_width {
-            if w - 2 > sum_width {
+            if w.saturating_sub(2) > sum_width {
                 constraints.push(Constraint::Length(w - sum_width - 2));
             } else {
                 constraints.push(Constraint::Min(0));
@@ -23,7 +23,7 @@
 use tui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Span, Spans},
     widgets::{Block, Borders, Clear, Paragraph, Wrap},
     Frame,
@@ -63,7 +63,7 @@ pub fn draw_dialog<B>(
     full_area: Rect,
     title: String,
     message: String,
-    color: Color,
+    style: Style,
     width: u16,
     height: u16,
 ) where
@@ -73,20 +73,16 @@ pub fn draw_dialog<B>(
 
     f.render_widget(Clear, popup_area);
 
-    let block = Block::default().borders(Borders::ALL).title(Span::styled(
-        title.as_str(),
-        Style::default().fg(color).add_modifier(Modifier::BOLD),
-    ));
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(Span::styled(title.as_str(), style.add_modifier(Modifier::BOLD)));
     f.render_widget(block, popup_area);
 
     let lines = message.as_str().lines();
 
     let mut spans = Vec::new();
     for l in lines {
-        spans.push(Spans::from(Span::styled(
-            l,
-            Style::default().fg(color).add_modifier(Modifier::BOLD),
-        )));
+        spans.push(Spans::from(Span::styled(l, style.add_modifier(Modifier::BOLD))));
     }
     let center_area = centered_rect_absolute(
         width.min(full_area.width).saturating_sub(2),
@@ -95,7 +91,7 @@ pub fn draw_dialog<B>(
     );
 
     let text = Paragraph::new(spans)
-        .style(Style::default().fg(color))
+        .style(style)
         .block(Block::default())
         .alignment(Alignment::Center)
         .wrap(Wrap { trim: true });
@@ -0,0 +1,59 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::ui::UiError;
+use clipboard::{ClipboardContext, ClipboardProvider};
+use std::{cell::RefCell, error::Error};
+
+thread_local! {
+    /// The clipboard is opened once and kept open, because on X11 copied text is only available while the program that
+    /// copied it still owns the clipboard
+    static CLIPBOARD: RefCell<Option<ClipboardContext>> = RefCell::new(None);
+}
+
+/// Copy `text` to the system clipboard
+pub fn copy_to_clipboard(text: String) -> Result<(), UiError> {
+    with_clipboard(|clipboard| clipboard.set_contents(text))
+}
+
+/// The text on the system clipboard
+pub fn paste_from_clipboard() -> Result<String, UiError> {
+    with_clipboard(|clipboard| clipboard.get_contents())
+}
+
+fn with_clipboard<T, F>(f: F) -> Result<T, UiError>
+where F: FnOnce(&mut ClipboardContext) -> Result<T, Box<dyn Error>> {
+    CLIPBOARD.with(|cell| {
+        let mut clipboard = cell.borrow_mut();
+        if clipboard.is_none() {
+            *clipboard = Some(ClipboardContext::new().map_err(clipboard_error)?);
+        }
+        match clipboard.as_mut() {
+            Some(clipboard) => f(clipboard).map_err(clipboard_error),
+            None => Err(UiError::ClipboardError("The clipboard is not available".to_string())),
+        }
+    })
+}
+
+fn clipboard_error(e: Box<dyn Error>) -> UiError {
+    UiError::ClipboardError(e.to_string())
+}
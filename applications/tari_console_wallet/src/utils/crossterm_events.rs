@@ -21,7 +21,7 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::utils::events::{Event, EventStream};
-use crossterm::event::{self, Event as CEvent, KeyEvent};
+use crossterm::event::{self, Event as CEvent};
 use log::*;
 use std::{
     sync::mpsc,
@@ -32,9 +32,10 @@ use std::{
 pub const LOG_TARGET: &str = "wallet::app::crossterm_events";
 
 /// A small event handler that wrap Crossterm input and tick events. Each event
-/// type is handled in its own thread and returned to a common `Receiver`
+/// type is handled in its own thread and returned to a common `Receiver`. Key and mouse events are both forwarded as
+/// input, resize events are left to the next draw.
 pub struct CrosstermEvents {
-    rx: mpsc::Receiver<Event<KeyEvent>>,
+    rx: mpsc::Receiver<Event<CEvent>>,
     _input_handle: thread::JoinHandle<()>,
 }
 
@@ -68,10 +69,13 @@ impl CrosstermEvents {
                         .checked_sub(last_tick.elapsed())
                         .unwrap_or_else(|| Duration::from_millis(1)),
                 ) {
-                    Ok(true) => {
-                        if let Ok(CEvent::Key(key)) = event::read() {
-                            tx.send(Event::Input(key)).unwrap();
-                        }
+                    Ok(true) => match event::read() {
+                        Ok(input @ CEvent::Key(_)) | Ok(input @ CEvent::Mouse(_)) => {
+                            if tx.send(Event::Input(input)).is_err() {
+                                break;
+                            }
+                        },
+                        _ => {},
                     },
                     Ok(false) => {},
                     Err(e) => {
@@ -79,8 +83,11 @@ impl CrosstermEvents {
                     },
                 }
                 if last_tick.elapsed() >= config.tick_rate {
+                    // The receiver is dropped when the interface is closed, which stops the thread so that it no
+                    // longer reads the input that is meant for the prompts that follow
                     if let Err(e) = tx.send(Event::Tick) {
-                        warn!(target: LOG_TARGET, "Error sending Tick event on MPSC channel: {}", e);
+                        debug!(target: LOG_TARGET, "Stopping crossterm events: {}", e);
+                        break;
                     }
                     last_tick = Instant::now();
                 }
@@ -94,8 +101,8 @@ impl CrosstermEvents {
     }
 }
 
-impl EventStream<KeyEvent> for CrosstermEvents {
-    fn next(&self) -> Result<Event<KeyEvent>, mpsc::RecvError> {
+impl EventStream<CEvent> for CrosstermEvents {
+    fn next(&self) -> Result<Event<CEvent>, mpsc::RecvError> {
         self.rx.recv()
     }
 }
@@ -21,17 +21,19 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use log::*;
+use serde_json::{json, Value};
 use tari_comms::{
     multiaddr::Multiaddr,
     peer_manager::{NodeId, Peer, PeerFeatures, PeerFlags},
 };
 use tari_core::transactions::types::PublicKey;
 use tari_crypto::tari_utilities::hex::Hex;
-use tari_wallet::WalletSqlite;
+use tari_wallet::{error::WalletStorageError, WalletSqlite};
 
 pub const LOG_TARGET: &str = "wallet::utils::db";
 pub const CUSTOM_BASE_NODE_PUBLIC_KEY_KEY: &str = "console_wallet_custom_base_node_public_key";
 pub const CUSTOM_BASE_NODE_ADDRESS_KEY: &str = "console_wallet_custom_base_node_address";
+pub const SAVED_BASE_NODES_KEY: &str = "console_wallet_saved_base_nodes";
 
 /// This helper function will attempt to read a stored base node public key and address from the wallet database if
 /// possible. If both are found they are used to construct and return a Peer.
@@ -60,40 +62,94 @@ pub async fn get_custom_base_node_peer_from_db(wallet: &mut WalletSqlite) -> Opt
     };
 
     match (custom_base_node_peer_pubkey, custom_base_node_peer_address) {
-        (Some(public_key), Some(address)) => {
-            let pub_key_str = PublicKey::from_hex(public_key.as_str());
-            let addr_str = address.parse::<Multiaddr>();
-            let (pub_key, address) = match (pub_key_str, addr_str) {
-                (Ok(pk), Ok(addr)) => (pk, addr),
-                (_, _) => {
-                    debug!(
-                        target: LOG_TARGET,
-                        "Problem converting stored custom base node public key or address"
-                    );
-                    return None;
-                },
-            };
-
-            let node_id = match NodeId::from_key(&pub_key) {
-                Ok(n) => n,
-                Err(e) => {
-                    debug!(
-                        target: LOG_TARGET,
-                        "Problem converting stored base node public key to Node Id: {}", e
-                    );
-                    return None;
-                },
-            };
-            Some(Peer::new(
-                pub_key,
-                node_id,
-                address.into(),
-                PeerFlags::default(),
-                PeerFeatures::COMMUNICATION_NODE,
-                Default::default(),
-                Default::default(),
-            ))
-        },
+        (Some(public_key), Some(address)) => peer_from_db_values(&public_key, &address),
         (_, _) => None,
     }
 }
+
+/// Read the named base nodes that the user saved to switch between, in the order they were saved. Entries that
+/// cannot be converted into a Peer are skipped.
+pub async fn get_saved_base_nodes_from_db(wallet: &mut WalletSqlite) -> Vec<(String, Peer)> {
+    let saved = match wallet.db.get_client_key_value(SAVED_BASE_NODES_KEY.to_string()).await {
+        Ok(Some(val)) => val,
+        Ok(None) => return Vec::new(),
+        Err(e) => {
+            warn!(target: LOG_TARGET, "Problem reading from wallet database: {}", e);
+            return Vec::new();
+        },
+    };
+    let entries = match serde_json::from_str::<Vec<Value>>(&saved) {
+        Ok(entries) => entries,
+        Err(e) => {
+            warn!(target: LOG_TARGET, "Problem parsing the saved base nodes: {}", e);
+            return Vec::new();
+        },
+    };
+
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let name = entry.get("name")?.as_str()?;
+            let public_key = entry.get("public_key")?.as_str()?;
+            let address = entry.get("address")?.as_str()?;
+            peer_from_db_values(public_key, address).map(|peer| (name.to_string(), peer))
+        })
+        .collect()
+}
+
+/// Replace the named base nodes stored in the wallet database with `base_nodes`
+pub async fn set_saved_base_nodes_in_db(
+    wallet: &WalletSqlite,
+    base_nodes: &[(String, Peer)],
+) -> Result<(), WalletStorageError>
+{
+    let entries = base_nodes
+        .iter()
+        .map(|(name, peer)| {
+            json!({
+                "name": name,
+                "public_key": peer.public_key.to_hex(),
+                "address": peer.addresses.first().map(|a| a.to_string()).unwrap_or_default(),
+            })
+        })
+        .collect::<Vec<_>>();
+    wallet
+        .db
+        .set_client_key_value(SAVED_BASE_NODES_KEY.to_string(), serde_json::to_string(&entries)?)
+        .await
+}
+
+fn peer_from_db_values(public_key: &str, address: &str) -> Option<Peer> {
+    let pub_key_str = PublicKey::from_hex(public_key);
+    let addr_str = address.parse::<Multiaddr>();
+    let (pub_key, address) = match (pub_key_str, addr_str) {
+        (Ok(pk), Ok(addr)) => (pk, addr),
+        (_, _) => {
+            debug!(
+                target: LOG_TARGET,
+                "Problem converting stored custom base node public key or address"
+            );
+            return None;
+        },
+    };
+
+    let node_id = match NodeId::from_key(&pub_key) {
+        Ok(n) => n,
+        Err(e) => {
+            debug!(
+                target: LOG_TARGET,
+                "Problem converting stored base node public key to Node Id: {}", e
+            );
+            return None;
+        },
+    };
+    Some(Peer::new(
+        pub_key,
+        node_id,
+        address.into(),
+        PeerFlags::default(),
+        PeerFeatures::COMMUNICATION_NODE,
+        Default::default(),
+        Default::default(),
+    ))
+}
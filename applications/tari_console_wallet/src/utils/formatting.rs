@@ -20,7 +20,9 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use tari_comms::peer_manager::Peer;
+use tari_comms::{peer_manager::Peer, types::CommsPublicKey};
+use tari_crypto::tari_utilities::hex::Hex;
+use tari_wallet::util::emoji::{emoji_set, EmojiId};
 use unicode_segmentation::UnicodeSegmentation;
 
 /// Utility function to only display the first and last N characters of a long string. This function is aware of unicode
@@ -50,9 +52,33 @@ pub fn display_address(peer: &Peer) -> String {
     }
 }
 
+/// Preview a public key or emoji ID that is being typed: a valid public key is shown as its emoji ID and a valid emoji
+/// ID as its public key, so that the recipient can be checked, and otherwise what is wrong with the input is described
+pub fn recipient_preview(public_key_or_emoji_id: &str) -> Result<String, String> {
+    let input = public_key_or_emoji_id;
+    if input.chars().all(|c| c.is_ascii_hexdigit()) {
+        return match CommsPublicKey::from_hex(input) {
+            Ok(public_key) => Ok(format!("Emoji ID: {}", EmojiId::from_pubkey(&public_key))),
+            Err(_) if input.len() != 64 => Err(format!("{} of 64 hex characters", input.len())),
+            Err(_) => Err("Not a valid public key".to_string()),
+        };
+    }
+
+    let emoji = emoji_set();
+    let num_emoji = input.chars().filter(|c| emoji.contains(c)).count();
+    if num_emoji != input.chars().count() {
+        return Err("Not a public key or emoji ID".to_string());
+    }
+    match EmojiId::str_to_pubkey(input) {
+        Ok(public_key) => Ok(format!("Public Key: {}", public_key.to_hex())),
+        Err(_) if num_emoji != 33 => Err(format!("{} of 33 emoji", num_emoji)),
+        Err(_) => Err("Not a valid emoji ID, it may contain a typo".to_string()),
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::utils::formatting::display_compressed_string;
+    use crate::utils::formatting::{display_compressed_string, recipient_preview};
 
     #[test]
     fn test_compress_string() {
@@ -66,4 +92,33 @@ mod test {
             "🐾💎🎤...🐞🚜🌂🎩🎱📈".to_string()
         );
     }
+
+    #[test]
+    fn test_recipient_preview() {
+        let public_key = "70350e09c474809209824c6e6888707b7dd09959aa227343b5106382b856f73a";
+        let emoji_id = "🐎🍴🌷🌟💻🐖🐩🐾🌟🐬🎧🐌🏦🐳🐎🐝🐢🔋👕🎸👿🍒🐓🎉💔🌹🏆🐬💡🎳🚦🍹🎒";
+        assert_eq!(recipient_preview(public_key), Ok(format!("Emoji ID: {}", emoji_id)));
+        assert_eq!(recipient_preview(emoji_id), Ok(format!("Public Key: {}", public_key)));
+
+        assert_eq!(
+            recipient_preview(&public_key[..10]),
+            Err("10 of 64 hex characters".to_string())
+        );
+        let emoji = emoji_id.chars().collect::<Vec<_>>();
+        assert_eq!(
+            recipient_preview(&emoji[..32].iter().collect::<String>()),
+            Err("32 of 33 emoji".to_string())
+        );
+        // A mistyped emoji is caught by the checksum
+        let mut mistyped = emoji.clone();
+        mistyped[1] = emoji[0];
+        assert_eq!(
+            recipient_preview(&mistyped.iter().collect::<String>()),
+            Err("Not a valid emoji ID, it may contain a typo".to_string())
+        );
+        assert_eq!(
+            recipient_preview("not a key"),
+            Err("Not a public key or emoji ID".to_string())
+        );
+    }
 }
@@ -0,0 +1,96 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use log::*;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+const LOG_TARGET: &str = "wallet::console_wallet::logs";
+
+/// The files written to by the appenders of the log4rs configuration file at `log_config`. Only the `path` entries of
+/// the configuration are read, so log files that have been rolled over are not included.
+pub fn log_file_paths(log_config: &Path) -> Vec<PathBuf> {
+    match fs::read_to_string(log_config) {
+        Ok(config) => parse_log_file_paths(&config),
+        Err(e) => {
+            warn!(
+                target: LOG_TARGET,
+                "Could not read the log configuration {}: {}",
+                log_config.display(),
+                e
+            );
+            Vec::new()
+        },
+    }
+}
+
+fn parse_log_file_paths(config: &str) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    for line in config.lines() {
+        let value = match line.trim_start().strip_prefix("path:") {
+            Some(value) => value,
+            None => continue,
+        };
+        let value = value.split(" #").next().unwrap_or_default().trim();
+        let value = value.trim_matches(|c| c == '"' || c == '\'');
+        if !value.is_empty() {
+            let path = PathBuf::from(value);
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    paths
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn appender_paths_are_found_in_the_config() {
+        let config = r#"
+appenders:
+  stdout:
+    kind: console
+  network:
+    kind: rolling_file
+    path: "log/wallet/network.log"
+    policy:
+      roller:
+        pattern: "log/wallet/network.{}.log"
+  # path: "log/wallet/commented.log"
+  base_layer:
+    kind: file
+    path: log/wallet/base_layer.log # the wallet's own messages
+  other:
+    kind: file
+    path: 'log/wallet/network.log'
+"#;
+        assert_eq!(parse_log_file_paths(config), vec![
+            PathBuf::from("log/wallet/network.log"),
+            PathBuf::from("log/wallet/base_layer.log"),
+        ]);
+    }
+}
@@ -20,9 +20,13 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+pub mod clipboard;
 pub mod crossterm_events;
 pub mod db;
 pub mod events;
 pub mod formatting;
+pub mod logs;
+pub mod price_feed;
+pub mod tor_status;
 
 // pub mod termion_events;
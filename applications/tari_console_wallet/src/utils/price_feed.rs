@@ -0,0 +1,193 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::ui::UiError;
+use chrono::{DateTime, Local};
+use futures::FutureExt;
+use log::*;
+use serde_json::Value;
+use std::time::{Duration, Instant};
+use tari_core::transactions::tari_amount::MicroTari;
+use tokio::task::JoinHandle;
+
+const LOG_TARGET: &str = "wallet::console_wallet::price_feed";
+/// How often the price is fetched
+const REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+/// How long to wait before fetching the price again after a fetch failed
+const RETRY_INTERVAL: Duration = Duration::from_secs(60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The price of one Tari in a fiat currency
+#[derive(Clone, Debug)]
+pub struct FiatPrice {
+    pub price: f64,
+    pub currency: String,
+    pub fetched_at: DateTime<Local>,
+    /// Whether the last fetch of the price failed, in which case this price may be out of date
+    pub stale: bool,
+}
+
+impl FiatPrice {
+    /// The approximate value of `amount` in the fiat currency, e.g. `≈ 12.34 USD`
+    pub fn format_value(&self, amount: MicroTari) -> String {
+        let value = u64::from(amount) as f64 / 1_000_000.0 * self.price;
+        if self.stale {
+            format!(
+                "≈ {:.2} {} (as of {})",
+                value,
+                self.currency,
+                self.fetched_at.format("%H:%M")
+            )
+        } else {
+            format!("≈ {:.2} {}", value, self.currency)
+        }
+    }
+}
+
+/// Fetches the fiat price of Tari from an HTTP endpoint in the background. The last price that was fetched is kept
+/// while the endpoint cannot be reached.
+pub struct PriceFeed {
+    client: reqwest::Client,
+    url: String,
+    pointer: Option<String>,
+    currency: String,
+    fetch: Option<JoinHandle<Result<f64, UiError>>>,
+    next_fetch: Instant,
+    price: Option<FiatPrice>,
+}
+
+impl PriceFeed {
+    pub fn new(url: String, pointer: Option<String>, currency: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+            pointer,
+            currency,
+            fetch: None,
+            next_fetch: Instant::now(),
+            price: None,
+        }
+    }
+
+    /// Collect the result of the fetch in progress if it has finished, or start a fetch if one is due. Returns the
+    /// price when it has changed.
+    pub fn poll(&mut self) -> Option<FiatPrice> {
+        if let Some(fetch) = self.fetch.as_mut() {
+            let result = fetch.now_or_never()?;
+            self.fetch = None;
+            return self.update(result.unwrap_or_else(|e| Err(UiError::PriceFeedError(e.to_string()))));
+        }
+        if Instant::now() >= self.next_fetch {
+            self.fetch = Some(tokio::spawn(fetch_price(
+                self.client.clone(),
+                self.url.clone(),
+                self.pointer.clone(),
+            )));
+        }
+        None
+    }
+
+    fn update(&mut self, result: Result<f64, UiError>) -> Option<FiatPrice> {
+        match result {
+            Ok(price) => {
+                self.next_fetch = Instant::now() + REFRESH_INTERVAL;
+                self.price = Some(FiatPrice {
+                    price,
+                    currency: self.currency.clone(),
+                    fetched_at: Local::now(),
+                    stale: false,
+                });
+            },
+            Err(e) => {
+                warn!(target: LOG_TARGET, "Could not fetch the price from {}: {}", self.url, e);
+                self.next_fetch = Instant::now() + RETRY_INTERVAL;
+                self.price.as_mut()?.stale = true;
+            },
+        }
+        self.price.clone()
+    }
+}
+
+async fn fetch_price(client: reqwest::Client, url: String, pointer: Option<String>) -> Result<f64, UiError> {
+    let body = client
+        .get(&url)
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| UiError::PriceFeedError(e.to_string()))?
+        .text()
+        .await
+        .map_err(|e| UiError::PriceFeedError(e.to_string()))?;
+    parse_price(&body, pointer.as_deref())
+}
+
+/// The price in a response of the price feed, which is either a bare number or JSON with the price at `pointer`. The
+/// price can be a number or a string holding a number.
+fn parse_price(body: &str, pointer: Option<&str>) -> Result<f64, UiError> {
+    let response: Value =
+        serde_json::from_str(body.trim()).map_err(|e| UiError::PriceFeedError(format!("Invalid response: {}", e)))?;
+    let value = match pointer {
+        Some(pointer) => response
+            .pointer(pointer)
+            .ok_or_else(|| UiError::PriceFeedError(format!("The response has no `{}`", pointer)))?,
+        None => &response,
+    };
+    let price = match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    };
+    price
+        .filter(|p| p.is_finite() && *p >= 0.0)
+        .ok_or_else(|| UiError::PriceFeedError(format!("`{}` is not a price", value)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn prices_are_parsed_from_bare_numbers_and_json() {
+        let parses_to = |body, pointer, expected: f64| (parse_price(body, pointer).unwrap() - expected).abs() < 1e-9;
+        assert!(parses_to("0.0123\n", None, 0.0123));
+        assert!(parses_to(r#"{"tari":{"usd":0.5}}"#, Some("/tari/usd"), 0.5));
+        assert!(parses_to(r#"{"data":{"price":"1.25"}}"#, Some("/data/price"), 1.25));
+        assert!(parse_price(r#"{"tari":{"eur":0.5}}"#, Some("/tari/usd")).is_err());
+        assert!(parse_price("<html>Bad Gateway</html>", None).is_err());
+    }
+
+    #[test]
+    fn values_are_converted_from_micro_tari() {
+        let mut price = FiatPrice {
+            price: 0.5,
+            currency: "USD".to_string(),
+            fetched_at: Local::now(),
+            stale: false,
+        };
+        assert_eq!(price.format_value(MicroTari::from(2_500_000)), "≈ 1.25 USD");
+        price.stale = true;
+        assert!(price
+            .format_value(MicroTari::from(2_500_000))
+            .starts_with("≈ 1.25 USD (as of "));
+    }
+}
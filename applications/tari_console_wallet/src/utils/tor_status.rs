@@ -0,0 +1,182 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use futures::FutureExt;
+use log::*;
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+use tari_app_utilities::utilities::setup_wallet_transport_type;
+use tari_common::GlobalConfig;
+use tari_comms::{
+    multiaddr::Multiaddr,
+    tor::{Authentication, TorClientError, TorControlPortClient},
+};
+use tari_p2p::transport::TransportType;
+use tokio::{sync::broadcast, task::JoinHandle};
+
+const LOG_TARGET: &str = "wallet::console_wallet::tor_status";
+/// How often the status is queried while Tor is bootstrapping
+const BOOTSTRAP_INTERVAL: Duration = Duration::from_secs(5);
+/// How often the status is queried once Tor has bootstrapped
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How far the Tor instance that the wallet's hidden service runs on is in bootstrapping
+#[derive(Clone, Debug, PartialEq)]
+pub enum TorStatus {
+    /// The wallet is not using a Tor hidden service
+    NotUsed,
+    /// The control port has not been queried yet
+    Unknown,
+    /// The progress in percent with Tor's summary of the current bootstrap phase
+    Bootstrapping(u8, String),
+    Bootstrapped,
+    /// The control port could not be queried
+    Unreachable,
+}
+
+impl fmt::Display for TorStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TorStatus::NotUsed => write!(f, "Not used"),
+            TorStatus::Unknown => write!(f, "Unknown"),
+            TorStatus::Bootstrapping(progress, summary) => write!(f, "Bootstrapping {}% ({})", progress, summary),
+            TorStatus::Bootstrapped => write!(f, "Bootstrapped"),
+            TorStatus::Unreachable => write!(f, "Control port unreachable"),
+        }
+    }
+}
+
+/// Queries the bootstrap status from the Tor control port in the background
+pub struct TorStatusMonitor {
+    control_server: Option<(Multiaddr, Authentication)>,
+    query: Option<JoinHandle<Result<TorStatus, TorClientError>>>,
+    next_query: Instant,
+    status: TorStatus,
+}
+
+impl TorStatusMonitor {
+    pub fn new(config: &GlobalConfig) -> Self {
+        let control_server = match setup_wallet_transport_type(config) {
+            TransportType::Tor(tor_config) => Some((tor_config.control_server_addr, tor_config.control_server_auth)),
+            _ => None,
+        };
+        let status = if control_server.is_some() {
+            TorStatus::Unknown
+        } else {
+            TorStatus::NotUsed
+        };
+        Self {
+            control_server,
+            query: None,
+            next_query: Instant::now(),
+            status,
+        }
+    }
+
+    pub fn status(&self) -> &TorStatus {
+        &self.status
+    }
+
+    /// Collect the result of the query in progress if it has finished, or start a query if one is due
+    pub fn poll(&mut self) {
+        if let Some(query) = self.query.as_mut() {
+            let result = match query.now_or_never() {
+                Some(result) => result,
+                None => return,
+            };
+            self.query = None;
+            self.status = match result {
+                Ok(Ok(status)) => status,
+                Ok(Err(e)) => {
+                    warn!(target: LOG_TARGET, "Could not query the Tor bootstrap status: {}", e);
+                    TorStatus::Unreachable
+                },
+                Err(e) => {
+                    warn!(target: LOG_TARGET, "The Tor bootstrap status query failed: {}", e);
+                    TorStatus::Unreachable
+                },
+            };
+            let interval = match self.status {
+                TorStatus::Bootstrapped => REFRESH_INTERVAL,
+                _ => BOOTSTRAP_INTERVAL,
+            };
+            self.next_query = Instant::now() + interval;
+            return;
+        }
+        if let Some((addr, auth)) = self.control_server.as_ref() {
+            if Instant::now() >= self.next_query {
+                self.query = Some(tokio::spawn(query_status(addr.clone(), auth.clone())));
+            }
+        }
+    }
+}
+
+async fn query_status(addr: Multiaddr, auth: Authentication) -> Result<TorStatus, TorClientError> {
+    let (event_tx, _) = broadcast::channel(1);
+    let mut client = TorControlPortClient::connect(addr, event_tx).await?;
+    client.authenticate(&auth).await?;
+    let phase = client.get_info("status/bootstrap-phase").await?;
+    Ok(phase
+        .first()
+        .map(|phase| parse_bootstrap_phase(phase))
+        .unwrap_or(TorStatus::Unknown))
+}
+
+/// The status in a bootstrap phase reported by Tor, e.g. `NOTICE BOOTSTRAP PROGRESS=100 TAG=done SUMMARY="Done"`
+fn parse_bootstrap_phase(phase: &str) -> TorStatus {
+    let progress = phase
+        .split_whitespace()
+        .find_map(|field| field.strip_prefix("PROGRESS="))
+        .and_then(|progress| progress.parse::<u8>().ok());
+    let summary = phase
+        .find("SUMMARY=\"")
+        .map(|i| &phase[i + "SUMMARY=\"".len()..])
+        .and_then(|rest| rest.split('"').next())
+        .unwrap_or_default();
+    match progress {
+        Some(100) => TorStatus::Bootstrapped,
+        Some(progress) => TorStatus::Bootstrapping(progress, summary.to_string()),
+        None => TorStatus::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bootstrap_phases_are_parsed() {
+        assert_eq!(
+            parse_bootstrap_phase(r#"NOTICE BOOTSTRAP PROGRESS=100 TAG=done SUMMARY="Done""#),
+            TorStatus::Bootstrapped
+        );
+        assert_eq!(
+            parse_bootstrap_phase(
+                r#"NOTICE BOOTSTRAP PROGRESS=45 TAG=requesting_descriptors SUMMARY="Asking for relay descriptors""#
+            ),
+            TorStatus::Bootstrapping(45, "Asking for relay descriptors".to_string())
+        );
+        assert_eq!(parse_bootstrap_phase("garbage"), TorStatus::Unknown);
+    }
+}
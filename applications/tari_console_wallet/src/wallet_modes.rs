@@ -20,19 +20,31 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 use crate::{
-    automation::{command_parser::parse_command, commands::command_runner},
+    automation::{
+        command_parser::parse_command,
+        commands::{command_runner, OutputFormat},
+    },
     grpc::WalletGrpcServer,
+    init::Profiles,
     notifier::Notifier,
-    recovery::wallet_recovery,
+    recovery::{wallet_recovery, wallet_recovery_task},
     ui,
-    ui::App,
+    ui::{App, KeyMap, Theme},
+    utils::logs::log_file_paths,
 };
+use futures::{future, FutureExt};
 use log::*;
 use rand::{rngs::OsRng, seq::SliceRandom};
-use std::{fs, io::Stdout, net::SocketAddr, path::PathBuf};
-use tari_app_utilities::utilities::ExitCodes;
+use std::{
+    fs,
+    io::Stdout,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+};
+use tari_app_utilities::utilities::{wait_for_termination, ExitCodes};
 use tari_common::{ConfigBootstrap, GlobalConfig};
 use tari_comms::{peer_manager::Peer, types::CommsPublicKey};
+use tari_shutdown::ShutdownSignal;
 use tari_wallet::WalletSqlite;
 use tokio::runtime::Handle;
 use tonic::transport::Server;
@@ -55,6 +67,8 @@ pub struct PeerConfig {
     pub base_node_custom: Option<Peer>,
     pub base_node_peers: Vec<Peer>,
     pub peer_seeds: Vec<Peer>,
+    /// The named base nodes that the user saved in the wallet, to switch between
+    pub base_node_saved: Vec<(String, Peer)>,
 }
 
 impl PeerConfig {
@@ -64,9 +78,15 @@ impl PeerConfig {
             base_node_custom,
             base_node_peers,
             peer_seeds,
+            base_node_saved: Vec::new(),
         }
     }
 
+    pub fn with_saved_base_nodes(mut self, base_node_saved: Vec<(String, Peer)>) -> Self {
+        self.base_node_saved = base_node_saved;
+        self
+    }
+
     /// Get the prioritised base node peer from the PeerConfig.
     /// 1. Custom Base Node
     /// 2. First configured Base Node Peer
@@ -100,19 +120,30 @@ pub fn command_mode(
     command: String,
     wallet: WalletSqlite,
     config: GlobalConfig,
+    format: OutputFormat,
 ) -> Result<(), ExitCodes>
 {
     let commands = vec![parse_command(&command)?];
     info!("Starting wallet command mode");
-    handle.block_on(command_runner(handle.clone(), commands, wallet, config))?;
+    handle.block_on(command_runner(handle.clone(), commands, wallet, config, format))?;
     info!("Shutting down wallet command mode");
 
     Ok(())
 }
 
-pub fn script_mode(handle: Handle, path: PathBuf, wallet: WalletSqlite, config: GlobalConfig) -> Result<(), ExitCodes> {
+pub fn script_mode(
+    handle: Handle,
+    path: PathBuf,
+    wallet: WalletSqlite,
+    config: GlobalConfig,
+    format: OutputFormat,
+) -> Result<(), ExitCodes>
+{
     info!(target: LOG_TARGET, "Starting wallet script mode");
-    println!("Starting wallet script mode");
+    let text = format == OutputFormat::Text;
+    if text {
+        println!("Starting wallet script mode");
+    }
     let script = fs::read_to_string(path).map_err(|e| ExitCodes::InputError(e.to_string()))?;
 
     if script.is_empty() {
@@ -121,7 +152,9 @@ pub fn script_mode(handle: Handle, path: PathBuf, wallet: WalletSqlite, config:
 
     let mut commands = Vec::new();
 
-    println!("Parsing commands...");
+    if text {
+        println!("Parsing commands...");
+    }
     for command in script.lines() {
         // skip empty lines and 'comments' starting with #
         if !command.is_empty() && !command.starts_with('#') {
@@ -129,15 +162,18 @@ pub fn script_mode(handle: Handle, path: PathBuf, wallet: WalletSqlite, config:
             commands.push(parse_command(command)?);
         }
     }
-    println!("{} commands parsed successfully.", commands.len());
-
-    println!("Starting the command runner!");
-    handle.block_on(command_runner(handle.clone(), commands, wallet, config))?;
+    if text {
+        println!("{} commands parsed successfully.", commands.len());
+        println!("Starting the command runner!");
+    }
+    handle.block_on(command_runner(handle.clone(), commands, wallet, config, format))?;
 
     info!(target: LOG_TARGET, "Completed wallet script mode");
     Ok(())
 }
 
+/// Run the interface, returning the wallet profile to open next if the user switched profiles
+#[allow(clippy::too_many_arguments)]
 pub fn tui_mode(
     handle: Handle,
     node_config: GlobalConfig,
@@ -145,12 +181,30 @@ pub fn tui_mode(
     base_node_selected: Peer,
     base_node_config: PeerConfig,
     notify_script: Option<PathBuf>,
-) -> Result<(), ExitCodes>
+    log_config: &Path,
+    profiles: Profiles,
+) -> Result<Option<String>, ExitCodes>
 {
     let grpc = WalletGrpcServer::new(wallet.clone());
-    handle.spawn(run_grpc(grpc, node_config.grpc_console_wallet_address));
+    handle.spawn(run_grpc(
+        grpc,
+        node_config.grpc_console_wallet_address,
+        wallet.comms.shutdown_signal(),
+    ));
+
+    let key_map = KeyMap::from_config(&node_config.console_wallet_key_bindings)
+        .map_err(|e| ExitCodes::ConfigError(format!("Invalid key bindings in wallet.ui.keys: {}", e)))?;
+    let theme = Theme::from_config(&node_config.console_wallet_theme)
+        .map_err(|e| ExitCodes::ConfigError(format!("Invalid theme in wallet.ui.theme: {}", e)))?;
+
+    let notifier = Notifier::new(
+        notify_script,
+        node_config.console_wallet_notify_webhook.clone(),
+        handle.clone(),
+        wallet.clone(),
+    );
 
-    let notifier = Notifier::new(notify_script, handle.clone(), wallet.clone());
+    let watch_only_keys = handle.block_on(wallet.db.get_watch_only_keys())?;
 
     let app = App::<CrosstermBackend<Stdout>>::new(
         "Tari Console Wallet".into(),
@@ -160,20 +214,29 @@ pub fn tui_mode(
         base_node_config,
         node_config,
         notifier,
+        key_map,
+        theme,
+        log_file_paths(log_config),
+        profiles,
+        watch_only_keys,
     );
 
     info!(target: LOG_TARGET, "Starting app");
 
-    handle.enter(|| ui::run(app))?;
+    let next_profile = handle.enter(|| ui::run(app))?;
 
-    info!(
-        target: LOG_TARGET,
-        "Termination signal received from user. Shutting wallet down."
-    );
+    match next_profile.as_ref() {
+        Some(profile) => info!(target: LOG_TARGET, "Switching to the '{}' wallet profile", profile),
+        None => info!(
+            target: LOG_TARGET,
+            "Termination signal received from user. Shutting wallet down."
+        ),
+    }
 
-    Ok(())
+    Ok(next_profile)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn recovery_mode(
     handle: Handle,
     config: GlobalConfig,
@@ -182,7 +245,8 @@ pub fn recovery_mode(
     base_node_config: PeerConfig,
     notify_script: Option<PathBuf>,
     bootstrap: &ConfigBootstrap,
-) -> Result<(), ExitCodes>
+    profiles: Profiles,
+) -> Result<Option<String>, ExitCodes>
 {
     let peer_seed_public_keys: Vec<CommsPublicKey> = base_node_config
         .peer_seeds
@@ -190,7 +254,15 @@ pub fn recovery_mode(
         .map(|f| f.public_key.clone())
         .collect();
     println!("Starting recovery...");
-    match handle.block_on(wallet_recovery(wallet.clone(), peer_seed_public_keys)) {
+    let result = if bootstrap.daemon_mode {
+        handle.block_on(wallet_recovery(wallet.clone(), peer_seed_public_keys))
+    } else {
+        let theme = Theme::from_config(&config.console_wallet_theme)
+            .map_err(|e| ExitCodes::ConfigError(format!("Invalid theme in wallet.ui.theme: {}", e)))?;
+        let recovery_task = wallet_recovery_task(wallet.clone(), peer_seed_public_keys);
+        handle.enter(|| ui::run_recovery(recovery_task, theme))
+    };
+    match result {
         Ok(_) => println!("Wallet recovered!"),
         Err(e) => {
             error!(target: LOG_TARGET, "Recovery failed: {}", e);
@@ -204,7 +276,7 @@ pub fn recovery_mode(
     }
 
     if bootstrap.daemon_mode {
-        grpc_mode(handle, wallet, config)
+        grpc_mode(handle, wallet, config).map(|_| None)
     } else {
         println!("Starting TUI.");
         tui_mode(
@@ -214,26 +286,38 @@ pub fn recovery_mode(
             base_node_selected,
             base_node_config,
             notify_script,
+            &bootstrap.log_config,
+            profiles,
         )
     }
 }
 
 pub fn grpc_mode(handle: Handle, wallet: WalletSqlite, node_config: GlobalConfig) -> Result<(), ExitCodes> {
     println!("Starting grpc server");
+    let shutdown_signal = wallet.comms.shutdown_signal();
     let grpc = WalletGrpcServer::new(wallet);
     handle
-        .block_on(run_grpc(grpc, node_config.grpc_console_wallet_address))
+        .block_on(run_grpc(grpc, node_config.grpc_console_wallet_address, shutdown_signal))
         .map_err(ExitCodes::GrpcError)?;
     println!("Shutting down");
     Ok(())
 }
 
-async fn run_grpc(grpc: WalletGrpcServer, grpc_console_wallet_address: SocketAddr) -> Result<(), String> {
+/// Serve until a termination signal is received or the wallet shuts down. Stopping with the wallet frees the address
+/// for the wallet of the next profile when the user switches profiles.
+async fn run_grpc(
+    grpc: WalletGrpcServer,
+    grpc_console_wallet_address: SocketAddr,
+    wallet_shutdown: ShutdownSignal,
+) -> Result<(), String>
+{
     info!(target: LOG_TARGET, "Starting GRPC on {}", grpc_console_wallet_address);
 
+    let termination = Box::pin(wait_for_termination());
+    let shutdown = future::select(termination, wallet_shutdown).map(|_| ());
     Server::builder()
         .add_service(tari_app_grpc::tari_rpc::wallet_server::WalletServer::new(grpc))
-        .serve(grpc_console_wallet_address)
+        .serve_with_shutdown(grpc_console_wallet_address, shutdown)
         .await
         .map_err(|e| format!("GRPC server returned error:{}", e))?;
     info!(target: LOG_TARGET, "Stopping GRPC");
@@ -0,0 +1,76 @@
+#![cfg_attr(not(debug_assertions), deny(unused_variables))]
+#![cfg_attr(not(debug_assertions), deny(unused_imports))]
+#![cfg_attr(not(debug_assertions), deny(dead_code))]
+#![cfg_attr(not(debug_assertions), deny(unused_extern_crates))]
+#![deny(unused_must_use)]
+#![deny(unreachable_patterns)]
+#![deny(unknown_lints)]
+//! Builds the genesis block of a private network from a specification file:
+//!
+//! ```text
+//! tari_genesis_block_builder <spec.json> [output directory]
+//! ```
+//!
+//! The specification gives the timestamp of the block, its outputs and, optionally, its proof of work and the value of
+//! its coinbase:
+//!
+//! ```json
+//! {
+//!   "timestamp": 1620000000,
+//!   "pow_algo": "Sha3",
+//!   "target_difficulty": 1,
+//!   "block_reward": 5538846115,
+//!   "outputs": [{ "value": 5000000000, "maturity": 0 }]
+//! }
+//! ```
+//!
+//! The coinbase defaults to the `localnet` block reward at height 0. Networks with a custom `emission_schedule` must
+//! set `block_reward` to its initial reward, otherwise their base nodes reject the genesis block.
+//!
+//! The block is written to `genesis_block.json`, together with the faucet value (the total value of the outputs) that
+//! base nodes on `localnet` use when they load it with the `genesis_block_file` setting. The spending keys of the
+//! coinbase and the outputs are written to `genesis_keys.json`. The header values that identify the network are
+//! printed.
+
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::PathBuf,
+};
+use tari_core::{
+    blocks::genesis_block_builder::{build_genesis_block, GenesisBlockSpec},
+    consensus::{ConsensusManagerBuilder, Network},
+    transactions::types::CryptoFactories,
+};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let spec_file = args
+        .next()
+        .ok_or("Usage: tari_genesis_block_builder <spec.json> [output directory]")?;
+    let output_dir = args.next().map(PathBuf::from).unwrap_or_else(|| PathBuf::from("."));
+
+    let spec: GenesisBlockSpec = serde_json::from_str(&fs::read_to_string(&spec_file)?)?;
+    println!("Building a genesis block with {} outputs..", spec.outputs.len());
+    let rules = ConsensusManagerBuilder::new(Network::LocalNet).build();
+    let genesis = build_genesis_block(&spec, &rules, &CryptoFactories::default())?;
+
+    fs::create_dir_all(&output_dir)?;
+    let block_file = output_dir.join("genesis_block.json");
+    fs::write(&block_file, serde_json::to_string(&genesis.to_file())?)?;
+    let keys_file = output_dir.join("genesis_keys.json");
+    let mut keys = File::create(&keys_file)?;
+    for key in &genesis.keys {
+        keys.write_all(format!("{}\n", serde_json::to_string(key)?).as_bytes())?;
+    }
+
+    for (name, value) in genesis.constants() {
+        println!("{}: {}", name, value);
+    }
+    println!("Genesis block written to {}", block_file.display());
+    println!(
+        "Spending keys written to {}. Keep them safe, they control the genesis outputs.",
+        keys_file.display()
+    );
+    Ok(())
+}
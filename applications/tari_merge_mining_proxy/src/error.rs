@@ -52,6 +52,8 @@ pub enum MmProxyError {
     InvalidMonerodResponse(String),
     #[error("Failed to send request to monerod: {0}")]
     MonerodRequestFailed(reqwest::Error),
+    #[error("No monerod endpoints are configured")]
+    NoMonerodEndpoints,
     #[error("GRPC request failed with `{status}` {details}")]
     GrpcRequestError {
         #[source]
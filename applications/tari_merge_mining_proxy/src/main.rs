@@ -31,6 +31,7 @@
 mod block_template_data;
 mod common;
 mod error;
+mod monerod_endpoints;
 mod proxy;
 
 #[cfg(test)]
@@ -58,6 +59,7 @@ async fn main() -> Result<(), MmProxyError> {
              remain running and connect to these services on demand."
         );
     }
+    tokio::spawn(xmrig_service.clone().run_monerod_health_checks());
     let service = make_service_fn(|_conn| future::ready(Result::<_, Infallible>::Ok(xmrig_service.clone())));
 
     match Server::try_bind(&addr) {
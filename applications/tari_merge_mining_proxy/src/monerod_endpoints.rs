@@ -0,0 +1,242 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! The monerod endpoints that the proxy forwards requests to. Requests go to the active endpoint, and fail over to the
+//! others in turn when it cannot be reached. Periodic health checks take endpoints that are unreachable or lagging
+//! behind the others out of rotation, and prefer the endpoint with the lowest latency.
+
+use std::{
+    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+    time::Duration,
+};
+use tracing::{info, warn};
+
+const LOG_TARGET: &str = "tari_mm_proxy::monerod_endpoints";
+
+/// An endpoint that is more than this many blocks behind the highest endpoint is considered to be lagging
+pub const MAX_HEIGHT_LAG: u64 = 2;
+/// A healthy active endpoint is only replaced by an endpoint that is at least this many times faster, so that the
+/// proxy does not flap between endpoints with similar latencies
+const LATENCY_SWITCH_FACTOR: u32 = 2;
+
+/// The result of checking a single endpoint
+#[derive(Debug, Clone, PartialEq)]
+pub struct EndpointHealth {
+    pub height: u64,
+    pub latency: Duration,
+}
+
+#[derive(Debug, Clone)]
+struct Endpoint {
+    url: String,
+    healthy: bool,
+    latency: Option<Duration>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MonerodEndpoints {
+    endpoints: Arc<RwLock<Vec<Endpoint>>>,
+}
+
+impl MonerodEndpoints {
+    /// All endpoints start out healthy, and the first is active
+    pub fn new(urls: Vec<String>) -> Self {
+        let endpoints = urls
+            .into_iter()
+            .map(|url| Endpoint {
+                url,
+                healthy: true,
+                latency: None,
+            })
+            .collect();
+        Self {
+            endpoints: Arc::new(RwLock::new(endpoints)),
+        }
+    }
+
+    /// All endpoint URLs, in order of preference
+    pub fn urls(&self) -> Vec<String> {
+        self.read().iter().map(|e| e.url.clone()).collect()
+    }
+
+    /// The endpoint URLs in the order they should be tried. The active endpoint comes first, followed by the other
+    /// healthy endpoints and then the unhealthy ones.
+    pub fn failover_order(&self) -> Vec<String> {
+        failover_order(&self.read())
+    }
+
+    /// Take an endpoint out of rotation until it passes a health check or a request to it succeeds
+    pub fn mark_failed(&self, url: &str) {
+        let mut endpoints = self.write();
+        if let Some(endpoint) = endpoints.iter_mut().find(|e| e.url == url) {
+            if endpoint.healthy {
+                warn!(target: LOG_TARGET, "monerod at {} failed, failing over", url);
+            }
+            endpoint.healthy = false;
+        }
+    }
+
+    pub fn mark_succeeded(&self, url: &str, latency: Duration) {
+        let mut endpoints = self.write();
+        if let Some(endpoint) = endpoints.iter_mut().find(|e| e.url == url) {
+            if !endpoint.healthy {
+                info!(target: LOG_TARGET, "monerod at {} is back in rotation", url);
+            }
+            endpoint.healthy = true;
+            endpoint.latency = Some(latency);
+        }
+    }
+
+    /// Applies the result of checking every endpoint. `None` means that the endpoint could not be reached.
+    pub fn update_health(&self, results: &[(String, Option<EndpointHealth>)]) {
+        let mut endpoints = self.write();
+        let previous = failover_order(&endpoints).into_iter().next();
+        apply_health_checks(&mut endpoints, results);
+        let active = failover_order(&endpoints).into_iter().next();
+        if active != previous {
+            if let Some(active) = active {
+                info!(target: LOG_TARGET, "Switched the active monerod to {}", active);
+            }
+        }
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Vec<Endpoint>> {
+        self.endpoints.read().expect("monerod endpoints lock poisoned")
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Vec<Endpoint>> {
+        self.endpoints.write().expect("monerod endpoints lock poisoned")
+    }
+}
+
+fn failover_order(endpoints: &[Endpoint]) -> Vec<String> {
+    let (healthy, unhealthy): (Vec<_>, Vec<_>) = endpoints.iter().partition(|e| e.healthy);
+    healthy.into_iter().chain(unhealthy).map(|e| e.url.clone()).collect()
+}
+
+fn apply_health_checks(endpoints: &mut Vec<Endpoint>, results: &[(String, Option<EndpointHealth>)]) {
+    let active = endpoints.iter().find(|e| e.healthy).map(|e| e.url.clone());
+    let best_height = results
+        .iter()
+        .filter_map(|(_, health)| health.as_ref().map(|h| h.height))
+        .max()
+        .unwrap_or(0);
+    for endpoint in endpoints.iter_mut() {
+        let health = results
+            .iter()
+            .find(|(url, _)| *url == endpoint.url)
+            .and_then(|(_, health)| health.as_ref());
+        match health {
+            Some(health) => {
+                let lagging = health.height.saturating_add(MAX_HEIGHT_LAG) < best_height;
+                if lagging {
+                    warn!(
+                        target: LOG_TARGET,
+                        "monerod at {} is lagging at height {} (best height {})",
+                        endpoint.url,
+                        health.height,
+                        best_height
+                    );
+                }
+                endpoint.healthy = !lagging;
+                endpoint.latency = Some(health.latency);
+            },
+            None => endpoint.healthy = false,
+        }
+    }
+
+    // Healthy endpoints by latency. The sort is stable, so endpoints keep their configured order otherwise.
+    endpoints.sort_by_key(|e| (!e.healthy, e.latency.unwrap_or(Duration::from_secs(u64::MAX))));
+    let fastest = endpoints.first().and_then(|e| e.latency);
+    let keep_active = endpoints
+        .iter()
+        .position(|e| Some(&e.url) == active.as_ref())
+        .filter(|&i| {
+            let endpoint = &endpoints[i];
+            match (endpoint.latency, fastest) {
+                (Some(latency), Some(fastest)) => endpoint.healthy && latency <= fastest * LATENCY_SWITCH_FACTOR,
+                _ => endpoint.healthy,
+            }
+        });
+    if let Some(i) = keep_active {
+        let endpoint = endpoints.remove(i);
+        endpoints.insert(0, endpoint);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn endpoints() -> MonerodEndpoints {
+        MonerodEndpoints::new(vec!["a".to_string(), "b".to_string(), "c".to_string()])
+    }
+
+    fn healthy(height: u64, latency_ms: u64) -> Option<EndpointHealth> {
+        Some(EndpointHealth {
+            height,
+            latency: Duration::from_millis(latency_ms),
+        })
+    }
+
+    #[test]
+    fn it_fails_over_to_the_next_endpoint() {
+        let endpoints = endpoints();
+        assert_eq!(endpoints.failover_order(), vec!["a", "b", "c"]);
+        endpoints.mark_failed("a");
+        assert_eq!(endpoints.failover_order(), vec!["b", "c", "a"]);
+        endpoints.mark_failed("b");
+        assert_eq!(endpoints.failover_order(), vec!["c", "a", "b"]);
+        endpoints.mark_succeeded("a", Duration::from_millis(10));
+        assert_eq!(endpoints.failover_order(), vec!["a", "c", "b"]);
+    }
+
+    #[test]
+    fn it_takes_lagging_and_unreachable_endpoints_out_of_rotation() {
+        let endpoints = endpoints();
+        endpoints.update_health(&[
+            ("a".to_string(), healthy(100, 10)),
+            ("b".to_string(), None),
+            ("c".to_string(), healthy(100 + MAX_HEIGHT_LAG + 1, 50)),
+        ]);
+        assert_eq!(endpoints.failover_order(), vec!["c", "a", "b"]);
+    }
+
+    #[test]
+    fn it_prefers_a_much_faster_endpoint() {
+        let endpoints = endpoints();
+        // A slightly faster endpoint does not replace the active one
+        endpoints.update_health(&[
+            ("a".to_string(), healthy(100, 30)),
+            ("b".to_string(), healthy(100, 20)),
+            ("c".to_string(), healthy(100, 40)),
+        ]);
+        assert_eq!(endpoints.failover_order(), vec!["a", "b", "c"]);
+
+        endpoints.update_health(&[
+            ("a".to_string(), healthy(100, 100)),
+            ("b".to_string(), healthy(100, 20)),
+            ("c".to_string(), healthy(100, 40)),
+        ]);
+        assert_eq!(endpoints.failover_order(), vec!["b", "c", "a"]);
+    }
+}
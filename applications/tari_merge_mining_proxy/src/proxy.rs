@@ -24,13 +24,14 @@ use crate::{
     block_template_data::{BlockTemplateDataBuilder, BlockTemplateRepository},
     common::{json_rpc, merge_mining, monero_rpc::CoreRpcErrorCode, proxy, proxy::convert_json_to_hyper_json_response},
     error::MmProxyError,
+    monerod_endpoints::{EndpointHealth, MonerodEndpoints},
 };
 use bytes::Bytes;
-use futures::TryFutureExt;
+use futures::{future, TryFutureExt};
 use hyper::{service::Service, Body, Method, Request, Response, StatusCode, Uri};
 use json::json;
 use jsonrpc::error::StandardError;
-use reqwest::{header, ResponseBuilderExt, Url};
+use reqwest::{header, header::HeaderMap, ResponseBuilderExt, Url};
 use serde_json as json;
 use std::{
     cmp,
@@ -44,7 +45,7 @@ use std::{
         Arc,
     },
     task::{Context, Poll},
-    time::Instant,
+    time::{Duration, Instant},
 };
 use tari_app_grpc::{tari_rpc as grpc, tari_rpc::GetCoinbaseRequest};
 use tari_common::{GlobalConfig, Network};
@@ -60,11 +61,16 @@ const LOG_TARGET: &str = "tari_mm_proxy::proxy";
 pub(crate) const MMPROXY_AUX_KEY_NAME: &str = "_aux";
 /// The identifier used to identify the tari aux chain data
 const TARI_CHAIN_ID: &str = "xtr";
+/// How often monerod endpoints are checked when more than one is configured
+const MONEROD_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// A monerod that takes longer than this to answer a health check is considered unreachable
+const MONEROD_HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
 
 #[derive(Debug, Clone)]
 pub struct MergeMiningProxyConfig {
     pub network: Network,
-    pub monerod_url: String,
+    /// The monerod endpoints in order of preference
+    pub monerod_urls: Vec<String>,
     pub monerod_username: String,
     pub monerod_password: String,
     pub monerod_use_auth: bool,
@@ -79,7 +85,7 @@ impl From<GlobalConfig> for MergeMiningProxyConfig {
     fn from(config: GlobalConfig) -> Self {
         Self {
             network: config.network,
-            monerod_url: config.monerod_url,
+            monerod_urls: config.monerod_urls,
             monerod_username: config.monerod_username,
             monerod_password: config.monerod_password,
             monerod_use_auth: config.monerod_use_auth,
@@ -101,6 +107,7 @@ impl MergeMiningProxyService {
     pub fn new(config: MergeMiningProxyConfig, block_templates: BlockTemplateRepository) -> Self {
         Self {
             inner: InnerService {
+                monerod_endpoints: MonerodEndpoints::new(config.monerod_urls.clone()),
                 config,
                 block_templates,
                 http_client: reqwest::Client::new(),
@@ -127,46 +134,25 @@ impl MergeMiningProxyService {
 
         let _ = writeln!(w, "Connections:");
 
-        let _ = write!(w, "- monerod ({})... ", inner.config.monerod_url);
-        let monerod_uri = inner
-            .get_fully_qualified_monerod_url(&Uri::from_static("/json_rpc"))
-            .expect("Configuration error: Unable to parse monero_url");
-        let result = inner
-            .http_client
-            .request(Method::POST, monerod_uri)
-            .body(
-                json::to_string(&jsonrpc::Request {
-                    method: "get_version",
-                    params: &[],
-                    id: Default::default(),
-                    jsonrpc: None,
-                })
-                .expect("conversion to json should always succeed"),
-            )
-            .send()
-            .map_err(MmProxyError::MonerodRequestFailed)
-            .and_then(|resp| async {
-                resp.json::<jsonrpc::Response>()
-                    .await
-                    .map_err(MmProxyError::MonerodRequestFailed)
-            })
-            .await;
-
-        match result {
-            Ok(jsonrpc::Response { error: Some(error), .. }) => {
-                let _ = writeln!(w, "❌ ({})", error.message);
-                is_success = false;
-            },
-            Ok(jsonrpc::Response { result: Some(resp), .. }) => {
-                let _ = writeln!(w, "✅ (v{})", resp["version"].as_u64().unwrap_or(0));
-            },
-            Ok(_) => {
-                let _ = writeln!(w, "✅");
-            },
-            Err(err) => {
-                let _ = writeln!(w, "❌ ({})", err);
-                is_success = false;
-            },
+        for monerod_url in inner.monerod_endpoints.urls() {
+            let _ = write!(w, "- monerod ({})... ", monerod_url);
+            let result = inner.get_monerod_version(&monerod_url).await;
+            match result {
+                Ok(jsonrpc::Response { error: Some(error), .. }) => {
+                    let _ = writeln!(w, "❌ ({})", error.message);
+                    is_success = false;
+                },
+                Ok(jsonrpc::Response { result: Some(resp), .. }) => {
+                    let _ = writeln!(w, "✅ (v{})", resp["version"].as_u64().unwrap_or(0));
+                },
+                Ok(_) => {
+                    let _ = writeln!(w, "✅");
+                },
+                Err(err) => {
+                    let _ = writeln!(w, "❌ ({})", err);
+                    is_success = false;
+                },
+            }
         }
 
         let _ = write!(w, "- Tari base node GRPC ({})... ", inner.config.grpc_base_node_address);
@@ -197,6 +183,23 @@ impl MergeMiningProxyService {
 
         is_success
     }
+
+    /// Periodically checks every monerod endpoint, so that requests go to the fastest endpoint that is reachable and
+    /// not lagging. Returns immediately if only one endpoint is configured.
+    pub async fn run_monerod_health_checks(self) {
+        let inner = self.inner;
+        let urls = inner.monerod_endpoints.urls();
+        if urls.len() < 2 {
+            return;
+        }
+        let mut interval = tokio::time::interval(MONEROD_HEALTH_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let results = future::join_all(urls.iter().map(|url| inner.check_monerod_health(url))).await;
+            let results = urls.iter().cloned().zip(results).collect::<Vec<_>>();
+            inner.monerod_endpoints.update_health(&results);
+        }
+    }
 }
 
 impl Service<Request<Body>> for MergeMiningProxyService {
@@ -235,6 +238,7 @@ impl Service<Request<Body>> for MergeMiningProxyService {
 #[derive(Debug, Clone)]
 struct InnerService {
     config: MergeMiningProxyConfig,
+    monerod_endpoints: MonerodEndpoints,
     block_templates: BlockTemplateRepository,
     http_client: reqwest::Client,
     initial_sync_achieved: Arc<AtomicBool>,
@@ -513,6 +517,7 @@ impl InnerService {
                 reward: block_reward,
                 fee: total_fees,
                 height: tari_height,
+                extra: vec![],
             })
             .await
             .map_err(|status| MmProxyError::GrpcRequestError {
@@ -740,23 +745,13 @@ impl InnerService {
         Ok(client)
     }
 
-    fn get_fully_qualified_monerod_url(&self, uri: &Uri) -> Result<Url, MmProxyError> {
-        let uri = format!("{}{}", self.config.monerod_url, uri.path()).parse::<Url>()?;
+    fn get_fully_qualified_monerod_url(monerod_url: &str, uri: &Uri) -> Result<Url, MmProxyError> {
+        let uri = format!("{}{}", monerod_url, uri.path()).parse::<Url>()?;
         Ok(uri)
     }
 
-    /// Proxy a request received by this server to Monerod
-    async fn proxy_request_to_monerod(
-        &self,
-        request: Request<Bytes>,
-    ) -> Result<(Request<Bytes>, Response<json::Value>), MmProxyError>
-    {
-        let monerod_uri = self.get_fully_qualified_monerod_url(request.uri())?;
-
-        let mut builder = self
-            .http_client
-            .request(request.method().clone(), monerod_uri.clone())
-            .headers(request.headers().clone());
+    fn monerod_request(&self, method: Method, monerod_uri: &Url, headers: HeaderMap) -> reqwest::RequestBuilder {
+        let mut builder = self.http_client.request(method, monerod_uri.clone()).headers(headers);
 
         // Some public monerod setups (e.g. those that are reverse proxied by nginx) require the Host header.
         // The mmproxy is the direct client of monerod and so is responsible for setting this header.
@@ -771,14 +766,71 @@ impl InnerService {
             // Use HTTP basic auth. This is the only reason we are using `reqwest` over the standard hyper client.
             builder = builder.basic_auth(&self.config.monerod_username, Some(&self.config.monerod_password));
         }
+        builder
+    }
 
-        debug!(
-            target: LOG_TARGET,
-            "[monerod] request: {} {}",
-            request.method(),
-            monerod_uri,
-        );
+    async fn get_monerod_version(&self, monerod_url: &str) -> Result<jsonrpc::Response, MmProxyError> {
+        let monerod_uri = Self::get_fully_qualified_monerod_url(monerod_url, &Uri::from_static("/json_rpc"))?;
+        self.monerod_request(Method::POST, &monerod_uri, HeaderMap::new())
+            .body(
+                json::to_string(&jsonrpc::Request {
+                    method: "get_version",
+                    params: &[],
+                    id: Default::default(),
+                    jsonrpc: None,
+                })
+                .expect("conversion to json should always succeed"),
+            )
+            .send()
+            .map_err(MmProxyError::MonerodRequestFailed)
+            .and_then(|resp| async {
+                resp.json::<jsonrpc::Response>()
+                    .await
+                    .map_err(MmProxyError::MonerodRequestFailed)
+            })
+            .await
+    }
 
+    /// The height and latency of a monerod endpoint, or `None` if it could not be reached
+    async fn check_monerod_health(&self, monerod_url: &str) -> Option<EndpointHealth> {
+        let monerod_uri = Self::get_fully_qualified_monerod_url(monerod_url, &Uri::from_static("/get_height")).ok()?;
+        let start = Instant::now();
+        let result = self
+            .monerod_request(Method::GET, &monerod_uri, HeaderMap::new())
+            .timeout(MONEROD_HEALTH_CHECK_TIMEOUT)
+            .send()
+            .and_then(|resp| resp.json::<json::Value>())
+            .await;
+        match result {
+            Ok(resp) => match resp["height"].as_u64() {
+                Some(height) => Some(EndpointHealth {
+                    height,
+                    latency: start.elapsed(),
+                }),
+                None => {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Invalid get_height response from {}: {}", monerod_url, resp
+                    );
+                    None
+                },
+            },
+            Err(err) => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Health check of monerod at {} failed: {}", monerod_url, err
+                );
+                None
+            },
+        }
+    }
+
+    /// Proxy a request received by this server to Monerod
+    async fn proxy_request_to_monerod(
+        &self,
+        request: Request<Bytes>,
+    ) -> Result<(Request<Bytes>, Response<json::Value>), MmProxyError>
+    {
         let mut submit_block = false;
         let body: Bytes = request.body().clone();
         let json = json::from_slice::<json::Value>(&body[..]).unwrap_or_default();
@@ -817,17 +869,17 @@ impl InnerService {
             // very much against spamming the nodes unnecessarily.
             // NB!: This is by design, do not change this without understanding
             // it's implications.
+            let monerod_url = self
+                .monerod_endpoints
+                .failover_order()
+                .into_iter()
+                .next()
+                .ok_or(MmProxyError::NoMonerodEndpoints)?;
+            let monerod_uri = Self::get_fully_qualified_monerod_url(&monerod_url, request.uri())?;
             let accept_response = json_rpc::default_block_accept_response(json["id"].as_i64());
-            json_response =
-                convert_json_to_hyper_json_response(accept_response, StatusCode::OK, monerod_uri.clone()).await?;
+            json_response = convert_json_to_hyper_json_response(accept_response, StatusCode::OK, monerod_uri).await?;
         } else {
-            let resp = builder
-                // This is a cheap clone of the request body
-                .body(body)
-                .send()
-                .await
-                .map_err(MmProxyError::MonerodRequestFailed)?;
-            json_response = convert_reqwest_response_to_hyper_json_response(resp).await?
+            json_response = self.send_request_to_monerod(&request, body).await?;
         };
 
         let rpc_status = if json_response.body()["error"].is_null() {
@@ -846,6 +898,60 @@ impl InnerService {
         Ok((request, json_response))
     }
 
+    /// Sends the request to the active monerod, failing over to the other endpoints in turn if it cannot be reached or
+    /// returns a server error
+    async fn send_request_to_monerod(
+        &self,
+        request: &Request<Bytes>,
+        body: Bytes,
+    ) -> Result<Response<json::Value>, MmProxyError>
+    {
+        let mut last_error = None;
+        for monerod_url in self.monerod_endpoints.failover_order() {
+            let monerod_uri = Self::get_fully_qualified_monerod_url(&monerod_url, request.uri())?;
+            debug!(
+                target: LOG_TARGET,
+                "[monerod] request: {} {}",
+                request.method(),
+                monerod_uri,
+            );
+            let start = Instant::now();
+            let result = self
+                .monerod_request(request.method().clone(), &monerod_uri, request.headers().clone())
+                // This is a cheap clone of the request body
+                .body(body.clone())
+                .send()
+                .map_err(MmProxyError::MonerodRequestFailed)
+                .and_then(convert_reqwest_response_to_hyper_json_response)
+                .await;
+            match result {
+                Ok(resp) if !resp.status().is_server_error() => {
+                    self.monerod_endpoints.mark_succeeded(&monerod_url, start.elapsed());
+                    return Ok(resp);
+                },
+                Ok(resp) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "monerod at {} returned {}, trying the next endpoint",
+                        monerod_url,
+                        resp.status()
+                    );
+                    self.monerod_endpoints.mark_failed(&monerod_url);
+                    last_error = Some(MmProxyError::InvalidMonerodResponse(resp.status().to_string()));
+                },
+                Err(err) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Request to monerod at {} failed, trying the next endpoint: {}", monerod_url, err
+                    );
+                    self.monerod_endpoints.mark_failed(&monerod_url);
+                    last_error = Some(err);
+                },
+            }
+        }
+        Err(last_error.unwrap_or(MmProxyError::NoMonerodEndpoints))
+    }
+
     async fn get_proxy_response(
         &self,
         request: Request<Bytes>,
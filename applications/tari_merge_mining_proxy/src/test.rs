@@ -27,7 +27,7 @@ use tari_common::Network;
 fn default_test_config() -> MergeMiningProxyConfig {
     MergeMiningProxyConfig {
         network: Network::Rincewind,
-        monerod_url: "".to_string(),
+        monerod_urls: vec![],
         monerod_username: "".to_string(),
         monerod_password: "".to_string(),
         monerod_use_auth: false,
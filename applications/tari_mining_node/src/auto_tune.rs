@@ -0,0 +1,238 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+//! Automatic selection of the number of mining threads.
+//!
+//! Hashing more threads than the CPU can run at full speed can lower the total hashrate, e.g. when hyper-threads
+//! share execution units or the CPU clocks down under load. When auto-tuning is enabled, the miner benchmarks a
+//! range of thread counts on startup and mines with the fastest one. If the hashrate then stays well below the
+//! benchmarked hashrate for a while, which is what thermal throttling looks like, the benchmark is run again.
+
+use crate::{
+    difficulty::BlockHeaderSha3,
+    miner::{HashRates, Miner},
+};
+use futures::StreamExt;
+use log::*;
+use std::time::{Duration, Instant};
+use tari_core::{blocks::BlockHeader, proof_of_work::PowAlgorithm};
+
+// A thread count is only preferred over a smaller one if it is at least this much faster
+const TUNE_TOLERANCE: f64 = 0.02;
+// The hashrate is considered throttled when it drops below this share of the benchmarked hashrate
+const THROTTLE_THRESHOLD: f64 = 0.8;
+// The time the hashrate must stay throttled before the thread count is tuned again
+const THROTTLE_DURATION: Duration = Duration::from_secs(60);
+// A benchmark run waits at most this many benchmark durations for every thread to report
+const MAX_BENCHMARK_DURATION_FACTOR: u32 = 3;
+
+/// Decides how many mining threads to use
+pub struct ThreadTuner {
+    max_threads: usize,
+    benchmark_duration: Option<Duration>,
+    tuned: Option<TunedThreads>,
+    throttled_since: Option<Instant>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TunedThreads {
+    num_threads: usize,
+    hashrate: f64,
+}
+
+impl ThreadTuner {
+    /// Always use the given number of threads
+    pub fn fixed(num_threads: usize) -> Self {
+        Self {
+            max_threads: num_threads,
+            benchmark_duration: None,
+            tuned: None,
+            throttled_since: None,
+        }
+    }
+
+    /// Benchmark up to `max_threads` threads for `benchmark_duration` each and use the fastest
+    pub fn auto(max_threads: usize, benchmark_duration: Duration) -> Self {
+        Self {
+            max_threads,
+            benchmark_duration: Some(benchmark_duration),
+            tuned: None,
+            throttled_since: None,
+        }
+    }
+
+    pub fn num_threads(&self) -> usize {
+        self.tuned.map(|t| t.num_threads).unwrap_or(self.max_threads)
+    }
+
+    /// Whether `tune` must be called before mining
+    pub fn needs_tuning(&self) -> bool {
+        self.benchmark_duration.is_some() && self.tuned.is_none()
+    }
+
+    /// Benchmark the candidate thread counts and select the fastest
+    pub async fn tune(&mut self, cpu_utilization_percent: u8) {
+        let benchmark_duration = match self.benchmark_duration {
+            Some(d) => d,
+            None => return,
+        };
+        info!(
+            "Benchmarking up to {} mining threads for {:.0?} each",
+            self.max_threads, benchmark_duration
+        );
+        let mut results = Vec::new();
+        for num_threads in candidate_thread_counts(self.max_threads) {
+            let hashrate = benchmark(num_threads, benchmark_duration, cpu_utilization_percent).await;
+            info!("{} mining thread(s): {:.2}MH/s", num_threads, hashrate);
+            results.push((num_threads, hashrate));
+        }
+        let (num_threads, hashrate) = best_thread_count(&results).unwrap_or((self.max_threads, 0.0));
+        info!("Mining with {} thread(s) at {:.2}MH/s", num_threads, hashrate);
+        self.tuned = Some(TunedThreads { num_threads, hashrate });
+        self.throttled_since = None;
+    }
+
+    /// Record the current hashrate of all the mining threads. Returns true if the hashrate has been throttled for so
+    /// long that the thread count should be tuned again, in which case `needs_tuning` returns true until it is.
+    pub fn record_hashrates(&mut self, hash_rates: &HashRates) -> bool {
+        if !hash_rates.is_complete() {
+            return false;
+        }
+        self.record_hashrate_at(hash_rates.total(), Instant::now())
+    }
+
+    fn record_hashrate_at(&mut self, hashrate: f64, now: Instant) -> bool {
+        let tuned = match self.tuned {
+            Some(t) if t.hashrate > 0.0 => t,
+            _ => return false,
+        };
+        if hashrate >= tuned.hashrate * THROTTLE_THRESHOLD {
+            self.throttled_since = None;
+            return false;
+        }
+        let since = *self.throttled_since.get_or_insert(now);
+        if now.duration_since(since) < THROTTLE_DURATION {
+            return false;
+        }
+        warn!(
+            "Hashrate of {:.2}MH/s has been below the benchmarked {:.2}MH/s for {:.0?}. Tuning the number of mining \
+             threads again.",
+            hashrate, tuned.hashrate, THROTTLE_DURATION
+        );
+        self.tuned = None;
+        self.throttled_since = None;
+        true
+    }
+}
+
+/// Powers of two up to `max_threads`, and `max_threads` itself
+fn candidate_thread_counts(max_threads: usize) -> Vec<usize> {
+    let mut counts: Vec<usize> = (0..).map(|i| 1usize << i).take_while(|n| *n < max_threads).collect();
+    if max_threads > 0 {
+        counts.push(max_threads);
+    }
+    counts
+}
+
+/// The fastest thread count, preferring fewer threads when the difference is within `TUNE_TOLERANCE`
+fn best_thread_count(results: &[(usize, f64)]) -> Option<(usize, f64)> {
+    let mut sorted = results.to_vec();
+    sorted.sort_by_key(|(num_threads, _)| *num_threads);
+    sorted
+        .into_iter()
+        .fold(None, |best, (num_threads, hashrate)| match best {
+            Some((_, best_hashrate)) if hashrate <= best_hashrate * (1.0 + TUNE_TOLERANCE) => best,
+            _ => Some((num_threads, hashrate)),
+        })
+}
+
+/// Mine a header that can never be solved with the given number of threads and return the total hashrate in MH/s
+async fn benchmark(num_threads: usize, duration: Duration, cpu_utilization_percent: u8) -> f64 {
+    let mut miner = Miner::init_mining(benchmark_header(), u64::MAX, num_threads)
+        .with_cpu_utilization_percent(cpu_utilization_percent);
+    let mut hash_rates = HashRates::new(num_threads);
+    let start = Instant::now();
+    while let Some(report) = miner.next().await {
+        hash_rates.update(&report);
+        let elapsed = start.elapsed();
+        if (elapsed >= duration && hash_rates.is_complete()) || elapsed >= duration * MAX_BENCHMARK_DURATION_FACTOR {
+            break;
+        }
+    }
+    // Wait for the threads so that they do not slow down the next benchmark
+    miner.stop();
+    hash_rates.total()
+}
+
+fn benchmark_header() -> BlockHeaderSha3 {
+    let mut header = BlockHeader::new(0);
+    header.pow.pow_algo = PowAlgorithm::Sha3;
+    BlockHeaderSha3::new(header.into()).expect("Benchmark header is valid")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn candidates() {
+        assert_eq!(candidate_thread_counts(0), Vec::<usize>::new());
+        assert_eq!(candidate_thread_counts(1), vec![1]);
+        assert_eq!(candidate_thread_counts(4), vec![1, 2, 4]);
+        assert_eq!(candidate_thread_counts(6), vec![1, 2, 4, 6]);
+    }
+
+    #[test]
+    fn prefers_fewer_threads_unless_clearly_faster() {
+        assert_eq!(best_thread_count(&[]), None);
+        assert_eq!(
+            best_thread_count(&[(4, 4.05), (1, 1.0), (8, 4.1), (2, 2.0)]),
+            Some((4, 4.05))
+        );
+        assert_eq!(best_thread_count(&[(1, 1.0), (2, 2.0), (4, 3.8)]), Some((4, 3.8)));
+    }
+
+    #[test]
+    fn retunes_after_sustained_throttling() {
+        let mut tuner = ThreadTuner::auto(8, Duration::from_secs(1));
+        tuner.tuned = Some(TunedThreads {
+            num_threads: 4,
+            hashrate: 10.0,
+        });
+        let start = Instant::now();
+        assert!(!tuner.record_hashrate_at(5.0, start));
+        // Recovering resets the throttling timer
+        assert!(!tuner.record_hashrate_at(9.0, start + THROTTLE_DURATION / 2));
+        assert!(!tuner.record_hashrate_at(5.0, start + THROTTLE_DURATION));
+        assert!(!tuner.needs_tuning());
+        assert!(tuner.record_hashrate_at(5.0, start + THROTTLE_DURATION * 2));
+        assert!(tuner.needs_tuning());
+    }
+
+    #[test]
+    fn fixed_never_tunes() {
+        let mut tuner = ThreadTuner::fixed(3);
+        assert_eq!(tuner.num_threads(), 3);
+        assert!(!tuner.needs_tuning());
+        assert!(!tuner.record_hashrate_at(0.0, Instant::now()));
+    }
+}
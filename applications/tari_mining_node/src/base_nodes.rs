@@ -0,0 +1,85 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+/// The base nodes the miner can get block templates from, in order of preference. The miner mines with one base node
+/// at a time and fails over to the next when it becomes unreachable, stalls or rejects blocks.
+pub struct BaseNodes {
+    addresses: Vec<String>,
+    current: usize,
+}
+
+impl BaseNodes {
+    /// Create the list of base nodes. Addresses that appear more than once are only used once.
+    pub fn new(addresses: Vec<String>) -> Self {
+        let mut unique = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            if !unique.contains(&address) {
+                unique.push(address);
+            }
+        }
+        assert!(!unique.is_empty(), "At least one base node address is required");
+        Self {
+            addresses: unique,
+            current: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.addresses.len()
+    }
+
+    /// The address of the base node that is currently being mined with
+    pub fn current(&self) -> &str {
+        &self.addresses[self.current]
+    }
+
+    /// Switch to the next base node, wrapping around to the first one after the last
+    pub fn fail_over(&mut self) -> &str {
+        self.current = (self.current + 1) % self.addresses.len();
+        self.current()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fail_over_cycles_through_unique_addresses() {
+        let mut nodes = BaseNodes::new(vec![
+            "http://127.0.0.1:18142".to_string(),
+            "http://10.0.0.1:18142".to_string(),
+            "http://127.0.0.1:18142".to_string(),
+        ]);
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(nodes.current(), "http://127.0.0.1:18142");
+        assert_eq!(nodes.fail_over(), "http://10.0.0.1:18142");
+        assert_eq!(nodes.current(), "http://10.0.0.1:18142");
+        assert_eq!(nodes.fail_over(), "http://127.0.0.1:18142");
+    }
+
+    #[test]
+    fn single_node_fails_over_to_itself() {
+        let mut nodes = BaseNodes::new(vec!["http://127.0.0.1:18142".to_string()]);
+        assert_eq!(nodes.fail_over(), "http://127.0.0.1:18142");
+    }
+}
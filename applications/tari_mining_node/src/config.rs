@@ -27,28 +27,59 @@
 //! specific options:
 //! - base_node_grpc_address - is IPv4/IPv6 address including port
 //! number, by which Tari Base Node can be found
+//! - failover_base_node_grpc_addresses - base nodes to switch to, in order, when the current base node is
+//! unreachable, stalls or rejects a mined block
+//! - base_node_stall_timeout_sec - the current base node is considered stalled if it has not sent a new block template
+//! for this long
+//! - template_poll_interval_sec - how often to poll a base node that can not stream block templates for a new one
 //! - wallet_grpc_address - is IPv4/IPv6 address including port number,
 //! where Tari Wallet Node can be found
-//! - num_mining_threads - number of mining threads, defaults to number of cpu cores
+//! - num_mining_threads - number of mining threads, defaults to number of cpu cores. When auto-tuning, this is the
+//! maximum number of threads
+//! - auto_tune_threads - benchmark the number of mining threads on startup and use the fastest, defaults to false
+//! - auto_tune_benchmark_sec - how long to benchmark each number of threads for when auto-tuning
+//! - cpu_utilization_percent - share of time (1-100) each mining thread spends hashing, defaults to 100.
+//! Lower it to mine in the background
 //! - mine_on_tip_only - will start mining only when node is reporting bootstrapped state
 //! - validate_tip_timeout_sec - will check tip with node every N seconds to validate that still
 //! mining on a tip
+//! - stratum_address - host and port of a stratum server, such as a mining pool or the stratum server of a base
+//! node. When set, the miner mines the jobs of the stratum server instead of using the base node and wallet
+//! - stratum_login - the login used with the stratum server, e.g. a worker name or wallet address
+//! - status_http_address - IPv4/IPv6 address including port number on which mining statistics are served as JSON
+//! over HTTP, disabled by default
+//! - coinbase_extra - text included in the coinbase output of mined blocks, e.g. a pool identifier, defaults to none
+//! - otlp_endpoint - the OpenTelemetry collector that tracing spans are exported to over OTLP, disabled by default
 //! All miner options configured under `[mining_node]` section of
 //! Tari's `config.toml`.
 
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::{
+    net::{AddrParseError, SocketAddr},
+    time::Duration,
+};
 use tari_app_grpc::tari_rpc::{pow_algo::PowAlgos, NewBlockTemplateRequest, PowAlgo};
 use tari_common::{GlobalConfig, NetworkConfigPath};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct MinerConfig {
     pub base_node_grpc_address: Option<String>,
+    pub failover_base_node_grpc_addresses: Vec<String>,
+    pub base_node_stall_timeout_sec: u64,
+    pub template_poll_interval_sec: u64,
     pub wallet_grpc_address: Option<String>,
     pub num_mining_threads: usize,
+    pub auto_tune_threads: bool,
+    pub auto_tune_benchmark_sec: u64,
+    pub cpu_utilization_percent: u8,
     pub mine_on_tip_only: bool,
     pub proof_of_work_algo: ProofOfWork,
     pub validate_tip_timeout_sec: u64,
+    pub coinbase_extra: String,
+    pub status_http_address: Option<String>,
+    pub stratum_address: Option<String>,
+    pub stratum_login: String,
+    pub otlp_endpoint: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -66,11 +97,22 @@ impl Default for MinerConfig {
     fn default() -> Self {
         Self {
             base_node_grpc_address: None,
+            failover_base_node_grpc_addresses: Vec::new(),
+            base_node_stall_timeout_sec: 600,
+            template_poll_interval_sec: 10,
             wallet_grpc_address: None,
             num_mining_threads: num_cpus::get(),
+            auto_tune_threads: false,
+            auto_tune_benchmark_sec: 10,
+            cpu_utilization_percent: 100,
             mine_on_tip_only: true,
             proof_of_work_algo: ProofOfWork::Sha3,
             validate_tip_timeout_sec: 30,
+            coinbase_extra: String::new(),
+            status_http_address: None,
+            stratum_address: None,
+            stratum_login: String::new(),
+            otlp_endpoint: None,
         }
     }
 }
@@ -82,6 +124,13 @@ impl MinerConfig {
             .unwrap_or_else(|| format!("http://{}", global.grpc_base_node_address))
     }
 
+    /// The addresses of the base nodes in order of preference, starting with `base_node_addr`
+    pub fn base_node_addrs(&self, global: &GlobalConfig) -> Vec<String> {
+        let mut addrs = vec![self.base_node_addr(global)];
+        addrs.extend(self.failover_base_node_grpc_addresses.iter().cloned());
+        addrs
+    }
+
     pub fn wallet_addr(&self, global: &GlobalConfig) -> String {
         self.wallet_grpc_address
             .clone()
@@ -102,7 +151,24 @@ impl MinerConfig {
         Duration::from_secs(10)
     }
 
+    pub fn base_node_stall_timeout(&self) -> Duration {
+        Duration::from_secs(self.base_node_stall_timeout_sec)
+    }
+
+    pub fn template_poll_interval(&self) -> Duration {
+        Duration::from_secs(self.template_poll_interval_sec)
+    }
+
     pub fn validate_tip_timeout_sec(&self) -> Duration {
         Duration::from_secs(self.validate_tip_timeout_sec)
     }
+
+    /// The address of the mining statistics HTTP endpoint, if it is enabled
+    pub fn status_http_addr(&self) -> Result<Option<SocketAddr>, AddrParseError> {
+        self.status_http_address.as_deref().map(str::parse).transpose()
+    }
+
+    pub fn auto_tune_benchmark_duration(&self) -> Duration {
+        Duration::from_secs(self.auto_tune_benchmark_sec)
+    }
 }
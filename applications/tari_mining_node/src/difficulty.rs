@@ -27,10 +27,12 @@ use tari_core::large_ints::U256;
 
 pub type Difficulty = u64;
 
+#[derive(Clone)]
 pub struct BlockHeaderSha3 {
     header: BlockHeader,
     pow_bytes: Vec<u8>,
-    hash_before_timestamp: Sha3_256,
+    /// Not known for headers given as a mining blob, whose timestamp cannot be changed
+    hash_before_timestamp: Option<Sha3_256>,
     hash_before_nonce: Sha3_256,
     pub timestamp: u64,
     pub nonce: u64,
@@ -60,7 +62,7 @@ impl BlockHeaderSha3 {
 
         Ok(Self {
             pow_bytes: pow.to_bytes(),
-            hash_before_timestamp,
+            hash_before_timestamp: Some(hash_before_timestamp),
             hash_before_nonce,
             timestamp: timestamp.seconds as u64,
             nonce: header.nonce,
@@ -69,10 +71,30 @@ impl BlockHeaderSha3 {
         })
     }
 
+    /// Mine a header that is given as the bytes of its mining hash that come before and after the nonce, as stratum
+    /// jobs are. The header itself is not known, so only the nonce of a mined header can be used.
+    pub fn from_mining_blob(height: u64, before_nonce: &[u8], after_nonce: Vec<u8>) -> Self {
+        Self {
+            header: BlockHeader {
+                height,
+                ..Default::default()
+            },
+            pow_bytes: after_nonce,
+            hash_before_timestamp: None,
+            hash_before_nonce: Sha3_256::new().chain(before_nonce),
+            timestamp: 0,
+            nonce: 0,
+            hashes: 0,
+        }
+    }
+
+    /// Change the timestamp of the header. This does nothing for headers given as a mining blob.
     pub fn set_timestamp(&mut self, timestamp: u64) {
-        self.hash_before_nonce = self
-            .hash_before_timestamp
-            .clone()
+        let hash_before_timestamp = match self.hash_before_timestamp.as_ref() {
+            Some(hash) => hash.clone(),
+            None => return,
+        };
+        self.hash_before_nonce = hash_before_timestamp
             .chain(timestamp.to_le_bytes())
             .chain(&self.header.output_mr)
             .chain(&self.header.range_proof_mr)
@@ -81,11 +103,6 @@ impl BlockHeaderSha3 {
         self.timestamp = timestamp;
     }
 
-    pub fn random_nonce(&mut self) {
-        use rand::{rngs::OsRng, RngCore};
-        self.nonce = OsRng.next_u64();
-    }
-
     #[inline]
     pub fn inc_nonce(&mut self) {
         self.nonce = self.nonce.wrapping_add(1);
@@ -130,7 +147,10 @@ fn big_endian_difficulty(hash: &[u8]) -> Difficulty {
 pub mod test {
     use super::*;
     use chrono::{DateTime, NaiveDate, Utc};
-    use tari_core::{blocks::BlockHeader as CoreBlockHeader, proof_of_work::sha3_difficulty as core_sha3_difficulty};
+    use tari_core::{
+        blocks::BlockHeader as CoreBlockHeader,
+        proof_of_work::{sha3_difficulty as core_sha3_difficulty, sha3_mining_blob},
+    };
 
     pub fn get_header() -> (BlockHeader, CoreBlockHeader) {
         let mut header = CoreBlockHeader::new(0);
@@ -158,6 +178,27 @@ pub mod test {
         }
     }
 
+    #[test]
+    fn validate_mining_blob_difficulty() {
+        let (_, mut core_header) = get_header();
+        let (before, after) = sha3_mining_blob(&core_header);
+        let mut hasher = BlockHeaderSha3::from_mining_blob(core_header.height, &before, after);
+        hasher.nonce = 1;
+        core_header.nonce = 1;
+        for _ in 0..1000 {
+            assert_eq!(
+                hasher.difficulty(),
+                core_sha3_difficulty(&core_header).as_u64(),
+                "with nonce = {}",
+                core_header.nonce
+            );
+            core_header.nonce += 1;
+            hasher.inc_nonce();
+            // The timestamp of a mining blob is fixed
+            hasher.set_timestamp(core_header.timestamp.as_u64() + 1);
+        }
+    }
+
     #[test]
     fn validate_timestamp_difficulty() {
         let (mut header, mut core_header) = get_header();
@@ -20,6 +20,7 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 //
+use std::{fmt::Display, time::Duration};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -28,6 +29,10 @@ pub enum MinerError {
     GrpcStatus(#[from] tonic::Status),
     #[error("Connection error: {0}")]
     GrpcConnection(#[from] tonic::transport::Error),
+    #[error("Base node error: {0}")]
+    BaseNode(String),
+    #[error("Base node stalled: no new block template for {0:?}")]
+    BaseNodeStalled(Duration),
     #[error("Node not ready")]
     NodeNotReady,
     #[error("Block height {0} already mined")]
@@ -38,8 +43,16 @@ pub enum MinerError {
     BlockHeader(String),
     #[error("Conversion error: {0}")]
     Conversion(String),
+    #[error("Stratum error: {0}")]
+    Stratum(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
 }
 
 pub fn err_empty(name: &str) -> MinerError {
     MinerError::EmptyObject(name.to_string())
 }
+
+pub fn err_base_node<E: Display>(err: E) -> MinerError {
+    MinerError::BaseNode(err.to_string())
+}
@@ -21,31 +21,72 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 //
 use config::MinerConfig;
-use futures::stream::StreamExt;
+use futures::{future, future::Either, stream::StreamExt, FutureExt};
 use log::*;
-use tari_app_grpc::tari_rpc::{base_node_client::BaseNodeClient, wallet_client::WalletClient};
-use tari_app_utilities::{initialization::init_configuration, utilities::ExitCodes};
+use tari_app_grpc::tari_rpc::{
+    base_node_client::BaseNodeClient,
+    wallet_client::WalletClient,
+    NewBlockTemplateRequest,
+    NewBlockTemplateResponse,
+};
+use tari_app_utilities::{
+    health::HealthStatus,
+    initialization::init_configuration,
+    telemetry::init_telemetry,
+    utilities::{wait_for_termination, ExitCodes},
+};
 use tari_common::{configuration::bootstrap::ApplicationType, ConfigBootstrap, DefaultConfigLoader, GlobalConfig};
 use tari_core::blocks::BlockHeader;
-use tokio::{runtime::Runtime, time::delay_for};
-use tonic::transport::Channel;
+use tokio::{
+    runtime::Runtime,
+    time::{delay_for, timeout},
+};
+use tonic::{transport::Channel, Code, Streaming};
+use tracing::info_span;
+use tracing_futures::Instrument;
 use utils::{coinbase_request, extract_outputs_and_kernels};
 
+mod auto_tune;
+mod base_nodes;
 mod config;
 mod difficulty;
 mod errors;
 mod miner;
+mod stats;
+mod status_server;
+mod stratum;
 mod utils;
 
-use crate::miner::MiningReport;
-use errors::{err_empty, MinerError};
+use crate::{
+    auto_tune::ThreadTuner,
+    base_nodes::BaseNodes,
+    difficulty::BlockHeaderSha3,
+    miner::{HashRates, MiningReport},
+    stats::MinerStats,
+};
+use errors::{err_base_node, err_empty, MinerError};
 use miner::Miner;
-use std::{convert::TryFrom, time::Instant};
+use std::{
+    convert::TryFrom,
+    time::{Duration, Instant},
+};
 
 /// Application entry point
 fn main() {
     let mut rt = Runtime::new().expect("Failed to start tokio runtime");
-    match rt.block_on(main_inner()) {
+    let health = HealthStatus::default();
+    let result = rt.block_on(async {
+        let miner = Box::pin(main_inner(health.clone()));
+        match future::select(miner, Box::pin(wait_for_termination())).await {
+            Either::Left((result, _)) => result,
+            Either::Right(_) => {
+                info!("Mining node is shutting down because a termination signal was received");
+                health.set_shutting_down();
+                Ok(())
+            },
+        }
+    });
+    match result {
         Ok(_) => std::process::exit(0),
         Err(exit_code) => {
             eprintln!("Fatal error: {}", exit_code);
@@ -55,28 +96,96 @@ fn main() {
     }
 }
 
-async fn main_inner() -> Result<(), ExitCodes> {
+async fn main_inner(health: HealthStatus) -> Result<(), ExitCodes> {
     let (bootstrap, global, cfg) = init_configuration(ApplicationType::MiningNode)?;
     let mut config = <MinerConfig as DefaultConfigLoader>::load_from(&cfg).expect("Failed to load config");
     config.mine_on_tip_only = global.mine_on_tip_only;
     debug!("mine_on_tip_only is {}", config.mine_on_tip_only);
+    let _telemetry = init_telemetry("tari_mining_node", config.otlp_endpoint.as_deref())?;
+
+    let stats = MinerStats::default();
+    let status_addr = config
+        .status_http_addr()
+        .map_err(|err| ExitCodes::ConfigError(format!("Invalid status_http_address: {}", err)))?;
+    if let Some(addr) = status_addr {
+        let stats = stats.clone();
+        let health = health.clone();
+        tokio::spawn(async move {
+            if let Err(err) = status_server::serve(addr, stats, health).await {
+                error!("Mining statistics server failed: {}", err);
+            }
+        });
+    }
 
-    let (mut node_conn, mut wallet_conn) = connect(&config, &global).await.map_err(ExitCodes::grpc)?;
+    let mut tuner = if config.auto_tune_threads {
+        ThreadTuner::auto(config.num_mining_threads, config.auto_tune_benchmark_duration())
+    } else {
+        ThreadTuner::fixed(config.num_mining_threads)
+    };
+
+    if let Some(address) = config.stratum_address.as_deref() {
+        health.set_ready(true);
+        stratum::mine(address, &config, &mut tuner, &stats).await;
+        return Ok(());
+    }
+
+    let mut base_nodes = BaseNodes::new(config.base_node_addrs(&global));
+    let mut wallet_conn = connect_wallet(&config, &global).await.map_err(ExitCodes::grpc)?;
+    let (mut node_conn, mut templates) = connect_base_node(&mut base_nodes, &config, &stats)
+        .await
+        .map_err(ExitCodes::grpc)?;
 
     let mut blocks_found: u64 = 0;
     loop {
+        if tuner.needs_tuning() {
+            tuner.tune(config.cpu_utilization_percent).await;
+        }
+        health.set_ready(true);
         debug!("Starting new mining cycle");
-        match mining_cycle(&mut node_conn, &mut wallet_conn, &config, &bootstrap).await {
+        match mining_cycle(
+            &mut node_conn,
+            &mut wallet_conn,
+            &mut templates,
+            &mut tuner,
+            &stats,
+            &config,
+            &bootstrap,
+        )
+        .instrument(info_span!("mining_cycle"))
+        .await
+        {
+            err @ Err(MinerError::BaseNode(_)) | err @ Err(MinerError::BaseNodeStalled(_)) => {
+                // Switch to the next base node straight away, only holding off once none of them can be reached
+                health.set_ready(false);
+                error!("Base node {} failed: {:?}", base_nodes.current(), err);
+                fail_over(&mut base_nodes, &stats);
+                loop {
+                    match connect_base_node(&mut base_nodes, &config, &stats).await {
+                        Ok((nc, t)) => {
+                            node_conn = nc;
+                            templates = t;
+                            break;
+                        },
+                        Err(err) => {
+                            error!("Connection error: {:?}", err);
+                            debug!("Holding for {:?}", config.wait_timeout());
+                            delay_for(config.wait_timeout()).await;
+                        },
+                    }
+                }
+            },
             err @ Err(MinerError::GrpcConnection(_)) | err @ Err(MinerError::GrpcStatus(_)) => {
                 // Any GRPC error we will try to reconnect with a standard delay
+                health.set_ready(false);
                 error!("Connection error: {:?}", err);
                 loop {
                     debug!("Holding for {:?}", config.wait_timeout());
                     delay_for(config.wait_timeout()).await;
-                    match connect(&config, &global).await {
-                        Ok((nc, wc)) => {
+                    match connect(&mut base_nodes, &config, &global, &stats).await {
+                        Ok((nc, wc, t)) => {
                             node_conn = nc;
                             wallet_conn = wc;
+                            templates = t;
                             break;
                         },
                         Err(err) => {
@@ -109,32 +218,202 @@ async fn main_inner() -> Result<(), ExitCodes> {
 }
 
 async fn connect(
+    base_nodes: &mut BaseNodes,
     config: &MinerConfig,
     global: &GlobalConfig,
-) -> Result<(BaseNodeClient<Channel>, WalletClient<Channel>), MinerError>
+    stats: &MinerStats,
+) -> Result<(BaseNodeClient<Channel>, WalletClient<Channel>, BlockTemplates), MinerError>
 {
-    let base_node_addr = config.base_node_addr(&global);
-    info!("Connecting to base node at {}", base_node_addr);
-    let node_conn = BaseNodeClient::connect(base_node_addr.clone()).await?;
+    let wallet_conn = connect_wallet(config, global).await?;
+    let (node_conn, templates) = connect_base_node(base_nodes, config, stats).await?;
+    Ok((node_conn, wallet_conn, templates))
+}
+
+async fn connect_wallet(config: &MinerConfig, global: &GlobalConfig) -> Result<WalletClient<Channel>, MinerError> {
     let wallet_addr = config.wallet_addr(&global);
     info!("Connecting to wallet at {}", wallet_addr);
-    let wallet_conn = WalletClient::connect(wallet_addr.clone()).await?;
+    Ok(WalletClient::connect(wallet_addr).await?)
+}
+
+/// Connect to the current base node and subscribe to its block templates, or poll for them if the base node can not
+/// stream them. If that fails, each of the other base nodes is tried in turn.
+async fn connect_base_node(
+    base_nodes: &mut BaseNodes,
+    config: &MinerConfig,
+    stats: &MinerStats,
+) -> Result<(BaseNodeClient<Channel>, BlockTemplates), MinerError>
+{
+    let mut attempts = 0;
+    loop {
+        let base_node_addr = base_nodes.current().to_string();
+        info!("Connecting to base node at {}", base_node_addr);
+        match subscribe_to_base_node(&base_node_addr, config).await {
+            Ok(connection) => {
+                stats.set_base_node(&base_node_addr);
+                return Ok(connection);
+            },
+            Err(err) => {
+                attempts += 1;
+                if attempts >= base_nodes.len() {
+                    return Err(err);
+                }
+                warn!("Could not connect to base node at {}: {}", base_node_addr, err);
+                fail_over(base_nodes, stats);
+            },
+        }
+    }
+}
+
+async fn subscribe_to_base_node(
+    base_node_addr: &str,
+    config: &MinerConfig,
+) -> Result<(BaseNodeClient<Channel>, BlockTemplates), MinerError>
+{
+    let mut node_conn = BaseNodeClient::connect(base_node_addr.to_string())
+        .await
+        .map_err(err_base_node)?;
+    let templates = match node_conn.stream_new_block_templates(config.pow_algo_request()).await {
+        Ok(stream) => BlockTemplates::new(TemplateSource::Stream(stream.into_inner())),
+        Err(status) if status.code() == Code::Unimplemented => {
+            warn!(
+                "Base node at {} can not stream block templates, polling it every {:?} instead",
+                base_node_addr,
+                config.template_poll_interval()
+            );
+            BlockTemplates::new(TemplateSource::Poll {
+                client: node_conn.clone(),
+                request: config.pow_algo_request(),
+                interval: config.template_poll_interval(),
+                next_poll: Instant::now(),
+                last: None,
+            })
+        },
+        Err(status) => return Err(err_base_node(status)),
+    };
+    Ok((node_conn, templates))
+}
+
+/// Switch to the next base node, if there is more than one
+fn fail_over(base_nodes: &mut BaseNodes, stats: &MinerStats) {
+    if base_nodes.len() < 2 {
+        return;
+    }
+    let from = base_nodes.current().to_string();
+    let to = base_nodes.fail_over();
+    warn!("Failing over from base node {} to base node {}", from, to);
+    stats.record_base_node_failover();
+}
+
+/// Where new block templates come from
+enum TemplateSource {
+    /// The base node pushes a template whenever the chain tip or the mempool changes
+    Stream(Streaming<NewBlockTemplateResponse>),
+    /// Base nodes that can not stream templates are asked for one every `interval`, and a template is only new if it
+    /// differs from the last one
+    Poll {
+        client: BaseNodeClient<Channel>,
+        request: NewBlockTemplateRequest,
+        interval: Duration,
+        next_poll: Instant,
+        last: Option<NewBlockTemplateResponse>,
+    },
+}
+
+impl TemplateSource {
+    /// Wait for the next new template. This can be cancelled at any point without skipping a template, or polling
+    /// more often than every `interval`.
+    async fn next(&mut self) -> Result<NewBlockTemplateResponse, MinerError> {
+        match self {
+            TemplateSource::Stream(stream) => stream
+                .message()
+                .await
+                .map_err(err_base_node)?
+                .ok_or_else(template_stream_closed),
+            TemplateSource::Poll {
+                client,
+                request,
+                interval,
+                next_poll,
+                last,
+            } => loop {
+                delay_for(next_poll.saturating_duration_since(Instant::now())).await;
+                *next_poll = Instant::now() + *interval;
+                let template = client
+                    .get_new_block_template(request.clone())
+                    .await
+                    .map_err(err_base_node)?
+                    .into_inner();
+                if last.as_ref() != Some(&template) {
+                    *last = Some(template.clone());
+                    return Ok(template);
+                }
+            },
+        }
+    }
+}
+
+/// The block templates of the base node. A template that arrives while mining ends the mining cycle and is held back
+/// for the next one.
+struct BlockTemplates {
+    source: TemplateSource,
+    pending: Option<NewBlockTemplateResponse>,
+    last_received: Instant,
+}
+
+impl BlockTemplates {
+    fn new(source: TemplateSource) -> Self {
+        Self {
+            source,
+            pending: None,
+            last_received: Instant::now(),
+        }
+    }
 
-    Ok((node_conn, wallet_conn))
+    /// The template to mine next: the held back template if there is one, otherwise the next one the base node sends
+    async fn next(&mut self, stall_timeout: Duration) -> Result<NewBlockTemplateResponse, MinerError> {
+        match self.pending.take() {
+            Some(template) => Ok(template),
+            None => self.receive(stall_timeout).await,
+        }
+    }
+
+    /// Wait for the base node to send a new template. The base node is considered stalled if it has not sent a new one
+    /// for `stall_timeout`.
+    async fn receive(&mut self, stall_timeout: Duration) -> Result<NewBlockTemplateResponse, MinerError> {
+        let remaining = stall_timeout
+            .checked_sub(self.last_received.elapsed())
+            .unwrap_or_default();
+        let template = timeout(remaining, self.source.next())
+            .await
+            .map_err(|_| MinerError::BaseNodeStalled(stall_timeout))??;
+        self.last_received = Instant::now();
+        Ok(template)
+    }
+
+    /// Hold back a template to mine again in the next cycle, unless a newer one is already waiting
+    fn retry(&mut self, template: NewBlockTemplateResponse) {
+        if self.pending.is_none() {
+            self.pending = Some(template);
+        }
+    }
+}
+
+fn template_stream_closed() -> MinerError {
+    err_base_node("Base node closed the block template stream")
 }
 
 async fn mining_cycle(
     node_conn: &mut BaseNodeClient<Channel>,
     wallet_conn: &mut WalletClient<Channel>,
+    templates: &mut BlockTemplates,
+    tuner: &mut ThreadTuner,
+    stats: &MinerStats,
     config: &MinerConfig,
     bootstrap: &ConfigBootstrap,
 ) -> Result<bool, MinerError>
 {
     // 1. Receive new block template
-    let template = node_conn
-        .get_new_block_template(config.pow_algo_request())
-        .await?
-        .into_inner();
+    let template = templates.next(config.base_node_stall_timeout()).await?;
     let mut block_template = template
         .new_block_template
         .clone()
@@ -151,7 +430,7 @@ async fn mining_cycle(
     }
 
     // 2. Get coinbase from wallet and add it to new block template body
-    let request = coinbase_request(&template)?;
+    let request = coinbase_request(&template, &config.coinbase_extra)?;
     let coinbase = wallet_conn.get_coinbase(request).await?.into_inner();
     let (output, kernel) = extract_outputs_and_kernels(coinbase)?;
     let body = block_template
@@ -162,20 +441,46 @@ async fn mining_cycle(
     body.kernels.push(kernel);
     let target_difficulty = template
         .miner_data
+        .as_ref()
         .ok_or_else(|| err_empty("miner_data"))?
         .target_difficulty;
 
     // 3. Receive new block data
-    let block_result = node_conn.get_new_block(block_template).await?.into_inner();
+    let block_result = node_conn
+        .get_new_block(block_template)
+        .await
+        .map_err(err_base_node)?
+        .into_inner();
     let block = block_result.block.ok_or_else(|| err_empty("block"))?;
     let header = block.clone().header.ok_or_else(|| err_empty("block.header"))?;
 
     // 4. Initialize miner and start receiving mining statuses in the loop
-    let mut reports = Miner::init_mining(header.clone(), target_difficulty, config.num_mining_threads);
+    let num_threads = tuner.num_threads();
+    let hasher = BlockHeaderSha3::new(header.clone())?;
+    let mut reports = Miner::init_mining(hasher, target_difficulty, num_threads)
+        .with_cpu_utilization_percent(config.cpu_utilization_percent);
+    let mut hash_rates = HashRates::new(num_threads);
+    stats.start_template(header.height, target_difficulty, num_threads);
     let template_time = Instant::now();
     let mut reporting_timeout = Instant::now();
     let mut block_submitted = false;
-    while let Some(report) = reports.next().await {
+    loop {
+        let event = futures::select! {
+            report = reports.next().fuse() => Either::Left(report),
+            new_template = templates.receive(config.base_node_stall_timeout()).fuse() => Either::Right(new_template),
+        };
+        let report = match event {
+            Either::Left(Some(report)) => report,
+            Either::Left(None) => {
+                templates.retry(template);
+                break;
+            },
+            Either::Right(new_template) => {
+                templates.pending = Some(new_template?);
+                debug!("Received a new block template. Restarting ...");
+                break;
+            },
+        };
         if let Some(header) = report.header.clone() {
             let mut submit = true;
             if let Some(min_diff) = bootstrap.miner_min_diff {
@@ -206,14 +511,24 @@ async fn mining_cycle(
                 let mut mined_block = block.clone();
                 mined_block.header = Some(header);
                 // 5. Sending block to the node
-                node_conn.submit_block(mined_block).await?;
+                if let Err(status) = node_conn.submit_block(mined_block).await {
+                    stats.record_rejected_block();
+                    return Err(err_base_node(status));
+                }
+                stats.record_accepted_block();
                 block_submitted = true;
                 break;
             } else {
-                display_report(&report, config, template_time).await;
+                display_report(&report, &mut hash_rates, template_time).await;
             }
         } else {
-            display_report(&report, config, template_time).await;
+            display_report(&report, &mut hash_rates, template_time).await;
+        }
+        stats.record_report(&report, &hash_rates);
+        if tuner.record_hashrates(&hash_rates) {
+            // Restart mining with the same template once the number of threads has been tuned again
+            templates.retry(template);
+            break;
         }
         if config.mine_on_tip_only && reporting_timeout.elapsed() > config.validate_tip_timeout_sec() {
             validate_tip(node_conn, report.height).await?;
@@ -225,17 +540,18 @@ async fn mining_cycle(
     Ok(block_submitted)
 }
 
-async fn display_report(report: &MiningReport, config: &MinerConfig, template_time: Instant) {
-    let hashrate = report.hashes as f64 / report.elapsed.as_micros() as f64;
-    let estimated_time = report.target_difficulty as f64 / (hashrate * config.num_mining_threads as f64 * 1000000.0);
+async fn display_report(report: &MiningReport, hash_rates: &mut HashRates, template_time: Instant) {
+    let hashrate = hash_rates.update(report);
+    let total_hashrate = hash_rates.total();
+    let estimated_time = report.target_difficulty as f64 / (total_hashrate * 1000000.0);
     let remaining = estimated_time as i32 - template_time.elapsed().as_secs() as i32;
     debug!(
         "Miner {} reported {:.2}MH/s with total {:.2}MH/s over {} threads. Height: {}. Target: {}, Estimated block in \
          approx. {}m{}s (+/- Ave. {:.0}s)",
         report.miner,
         hashrate,
-        hashrate * config.num_mining_threads as f64,
-        config.num_mining_threads,
+        total_hashrate,
+        hash_rates.num_threads(),
         report.height,
         report.target_difficulty,
         remaining / 60,
@@ -248,7 +564,8 @@ async fn display_report(report: &MiningReport, config: &MinerConfig, template_ti
 async fn validate_tip(node_conn: &mut BaseNodeClient<Channel>, height: u64) -> Result<(), MinerError> {
     let tip = node_conn
         .get_tip_info(tari_app_grpc::tari_rpc::Empty {})
-        .await?
+        .await
+        .map_err(err_base_node)?
         .into_inner();
     if !tip.initial_sync_achieved || tip.metadata.is_none() {
         return Err(MinerError::NodeNotReady);
@@ -24,8 +24,13 @@ use super::difficulty::BlockHeaderSha3;
 use crossbeam::channel::{bounded, Select, Sender, TrySendError};
 use futures::Stream;
 use log::*;
+use rand::{rngs::OsRng, RngCore};
 use std::{
     pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
     task::{Context, Poll, Waker},
     thread,
     time::{Duration, Instant},
@@ -37,14 +42,81 @@ use thread::JoinHandle;
 // ~400_000 hashes per second
 const REPORTING_FREQUENCY: u64 = 3_000_000;
 
+// Number of nonces a mining thread claims at a time. Faster threads claim more ranges, so the nonce space is shared
+// out according to the speed of each thread. This is also how often a throttled thread rests.
+const NONCE_RANGE_SIZE: u64 = 100_000;
+
 // Thread's stack size, ideally we would fit all thread's data in the CPU L1 cache
 const STACK_SIZE: usize = 32_000;
 
+/// The nonce space shared by the mining threads of one block template. Threads claim consecutive ranges of
+/// `NONCE_RANGE_SIZE` nonces, so that no two threads hash the same nonce.
+#[derive(Clone)]
+pub struct NonceRanges {
+    next: Arc<AtomicU64>,
+}
+
+impl NonceRanges {
+    /// Start claiming ranges at a random nonce
+    pub fn random() -> Self {
+        Self::starting_at(OsRng.next_u64())
+    }
+
+    pub fn starting_at(nonce: u64) -> Self {
+        Self {
+            next: Arc::new(AtomicU64::new(nonce)),
+        }
+    }
+
+    /// Claim the next range of nonces and return the first nonce in it. The nonce space wraps around.
+    pub fn claim(&self) -> u64 {
+        self.next.fetch_add(NONCE_RANGE_SIZE, Ordering::Relaxed)
+    }
+}
+
+/// The latest hashrate of every mining thread
+#[derive(Debug, Default)]
+pub struct HashRates {
+    rates: Vec<f64>,
+}
+
+impl HashRates {
+    pub fn new(num_threads: usize) -> Self {
+        Self {
+            rates: vec![0.0; num_threads],
+        }
+    }
+
+    /// Record the hashrate in MH/s of the thread that sent the report and return it
+    pub fn update(&mut self, report: &MiningReport) -> f64 {
+        let rate = report.hashrate();
+        if let Some(r) = self.rates.get_mut(report.miner) {
+            *r = rate;
+        }
+        rate
+    }
+
+    pub fn num_threads(&self) -> usize {
+        self.rates.len()
+    }
+
+    /// The total hashrate in MH/s of all the threads
+    pub fn total(&self) -> f64 {
+        self.rates.iter().sum()
+    }
+
+    /// Whether every thread has reported, so that the total is not an underestimate
+    pub fn is_complete(&self) -> bool {
+        self.rates.iter().all(|r| *r > 0.0)
+    }
+}
+
 /// Miner will send regular reports from every mining threads
 #[derive(Debug)]
 pub struct MiningReport {
     pub miner: usize,
     pub target_difficulty: u64,
+    /// The difficulty of the mined header, or the best difficulty the thread has achieved so far if none was mined
     pub difficulty: u64,
     pub hashes: u64,
     pub elapsed: Duration,
@@ -54,29 +126,64 @@ pub struct MiningReport {
     pub last_nonce: u64,
 }
 
+impl MiningReport {
+    /// The average hashrate of the reporting thread in MH/s
+    pub fn hashrate(&self) -> f64 {
+        let micros = self.elapsed.as_micros();
+        if micros == 0 {
+            return 0.0;
+        }
+        self.hashes as f64 / micros as f64
+    }
+}
+
 /// Miner is starting number of mining threads and implements Stream for async reports polling
 /// Communication with async world is performed via channel and waker so should be quite efficient
+/// The mining threads stop after their current range of nonces when the miner is dropped
 pub struct Miner {
     threads: Vec<JoinHandle<()>>,
     channels: Vec<crossbeam::channel::Receiver<MiningReport>>,
     num_threads: usize,
-    header: BlockHeader,
+    hasher: BlockHeaderSha3,
     target_difficulty: u64,
+    cpu_utilization_percent: u8,
+    stop: Arc<AtomicBool>,
 }
 
 impl Miner {
-    pub fn init_mining(header: BlockHeader, target_difficulty: u64, num_threads: usize) -> Self {
+    pub fn init_mining(hasher: BlockHeaderSha3, target_difficulty: u64, num_threads: usize) -> Self {
         Self {
             threads: vec![],
             channels: vec![],
-            header,
+            hasher,
             num_threads,
             target_difficulty,
+            cpu_utilization_percent: 100,
+            stop: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Limit the share of time each mining thread spends hashing, so that mining can run in the background. Values
+    /// are clamped to 1..=100.
+    pub fn with_cpu_utilization_percent(mut self, percent: u8) -> Self {
+        self.cpu_utilization_percent = percent.max(1).min(100);
+        self
+    }
+
+    /// Stop the mining threads and wait for them to finish
+    pub fn stop(mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        for thread in self.threads.drain(..) {
+            if thread.join().is_err() {
+                error!("Mining thread panicked");
+            }
         }
     }
 
     // Start mining threads with async context waker
     fn start_threads(&mut self, ctx: &Context<'_>) {
+        let nonces = NonceRanges::random();
+        let cpu_utilization_percent = self.cpu_utilization_percent;
         let miners = (0..self.num_threads)
             .map(|i| {
                 (
@@ -88,11 +195,13 @@ impl Miner {
             })
             .map(|(thread, i)| {
                 let (tx, rx) = bounded(1);
-                let header = self.header.clone();
+                let hasher = self.hasher.clone();
                 let waker = ctx.waker().clone();
                 let difficulty = self.target_difficulty;
+                let nonces = nonces.clone();
+                let stop = self.stop.clone();
                 let handle = thread
-                    .spawn(move || mining_task(header, difficulty, tx, waker, i))
+                    .spawn(move || mining_task(hasher, difficulty, tx, waker, i, nonces, cpu_utilization_percent, stop))
                     .expect("Failed to create mining thread");
                 (handle, rx)
             });
@@ -103,6 +212,12 @@ impl Miner {
     }
 }
 
+impl Drop for Miner {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
 impl Stream for Miner {
     type Item = MiningReport;
 
@@ -154,49 +269,65 @@ impl Stream for Miner {
     }
 }
 
-/// Miner starts with a random nonce and iterates until it finds a header hash that meets the desired
-/// target
+/// Miner claims ranges of nonces and iterates over them until it finds a header hash that meets the desired target.
+/// If the CPU utilization is capped, the thread rests after every range in proportion to the time spent hashing it.
+#[allow(clippy::too_many_arguments)]
 pub fn mining_task(
-    header: BlockHeader,
+    mut hasher: BlockHeaderSha3,
     target_difficulty: u64,
     sender: Sender<MiningReport>,
     waker: Waker,
     miner: usize,
+    nonces: NonceRanges,
+    cpu_utilization_percent: u8,
+    stop: Arc<AtomicBool>,
 )
 {
     let start = Instant::now();
-    let mut hasher = BlockHeaderSha3::new(header).unwrap();
-    hasher.random_nonce();
+    let mut last_report = 0;
+    let mut best_difficulty = 0;
     // We're mining over here!
     info!("Mining thread {} started", miner);
     // Mining work
     loop {
-        let difficulty = hasher.difficulty();
-        if difficulty >= target_difficulty {
-            debug!(
-                "Miner {} found nonce {} with matching difficulty {}",
-                miner, hasher.nonce, difficulty
-            );
-            if let Err(err) = sender.try_send(MiningReport {
-                miner,
-                difficulty,
-                hashes: hasher.hashes,
-                elapsed: start.elapsed(),
-                height: hasher.height(),
-                last_nonce: hasher.nonce,
-                header: Some(hasher.into_header()),
-                target_difficulty,
-            }) {
-                error!("Miner {} failed to send report: {}", miner, err);
-            }
-            waker.wake();
+        if stop.load(Ordering::Relaxed) {
             info!("Mining thread {} stopped", miner);
             return;
         }
-        if hasher.nonce % REPORTING_FREQUENCY == 0 {
+        let range_start = Instant::now();
+        hasher.nonce = nonces.claim();
+        for _ in 0..NONCE_RANGE_SIZE {
+            let difficulty = hasher.difficulty();
+            best_difficulty = best_difficulty.max(difficulty);
+            if difficulty >= target_difficulty {
+                debug!(
+                    "Miner {} found nonce {} with matching difficulty {}",
+                    miner, hasher.nonce, difficulty
+                );
+                if let Err(err) = sender.try_send(MiningReport {
+                    miner,
+                    difficulty,
+                    hashes: hasher.hashes,
+                    elapsed: start.elapsed(),
+                    height: hasher.height(),
+                    last_nonce: hasher.nonce,
+                    header: Some(hasher.into_header()),
+                    target_difficulty,
+                }) {
+                    error!("Miner {} failed to send report: {}", miner, err);
+                }
+                waker.wake();
+                info!("Mining thread {} stopped", miner);
+                return;
+            }
+            hasher.inc_nonce();
+        }
+
+        if hasher.hashes - last_report >= REPORTING_FREQUENCY {
+            last_report = hasher.hashes;
             let res = sender.try_send(MiningReport {
                 miner,
-                difficulty,
+                difficulty: best_difficulty,
                 hashes: hasher.hashes,
                 elapsed: start.elapsed(),
                 header: None,
@@ -212,6 +343,42 @@ pub fn mining_task(
             }
             hasher.set_timestamp(timestamp().seconds as u64);
         }
-        hasher.inc_nonce();
+
+        if let Some(rest) = rest_duration(range_start.elapsed(), cpu_utilization_percent) {
+            thread::sleep(rest);
+        }
+    }
+}
+
+/// The time a thread that has been hashing for `busy` must rest to keep to the given CPU utilization
+fn rest_duration(busy: Duration, cpu_utilization_percent: u8) -> Option<Duration> {
+    if cpu_utilization_percent >= 100 {
+        return None;
+    }
+    let percent = u32::from(cpu_utilization_percent.max(1));
+    Some(busy * (100 - percent) / percent)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn nonce_ranges_do_not_overlap() {
+        let nonces = NonceRanges::starting_at(u64::MAX - NONCE_RANGE_SIZE + 1);
+        let other = nonces.clone();
+        assert_eq!(nonces.claim(), u64::MAX - NONCE_RANGE_SIZE + 1);
+        // The nonce space wraps around
+        assert_eq!(other.claim(), 1);
+        assert_eq!(nonces.claim(), NONCE_RANGE_SIZE + 1);
+    }
+
+    #[test]
+    fn rest_keeps_to_the_utilization_cap() {
+        let busy = Duration::from_millis(100);
+        assert_eq!(rest_duration(busy, 100), None);
+        assert_eq!(rest_duration(busy, 50), Some(Duration::from_millis(100)));
+        assert_eq!(rest_duration(busy, 25), Some(Duration::from_millis(300)));
+        assert_eq!(rest_duration(busy, 0), Some(Duration::from_millis(9900)));
     }
 }
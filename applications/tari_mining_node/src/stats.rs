@@ -0,0 +1,214 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+use crate::miner::{HashRates, MiningReport};
+use serde::Serialize;
+use std::{
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+/// Mining statistics shared between the mining loop, which records them, and the status server, which reports them
+#[derive(Clone)]
+pub struct MinerStats {
+    started: Instant,
+    inner: Arc<Mutex<StatsInner>>,
+}
+
+#[derive(Default)]
+struct StatsInner {
+    hashrate: f64,
+    num_threads: usize,
+    height: u64,
+    target_difficulty: u64,
+    best_difficulty: u64,
+    blocks_accepted: u64,
+    blocks_rejected: u64,
+    shares_accepted: u64,
+    shares_rejected: u64,
+    base_node: String,
+    base_node_failovers: u64,
+}
+
+/// A point-in-time view of the mining statistics, as served by the status endpoint
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatsSnapshot {
+    pub uptime_sec: u64,
+    /// Total hashrate of all mining threads in MH/s
+    pub hashrate: f64,
+    pub num_threads: usize,
+    /// Height of the block that is currently being mined
+    pub height: u64,
+    pub target_difficulty: u64,
+    /// The best difficulty achieved while mining the current block
+    pub best_difficulty: u64,
+    /// `best_difficulty` as a share of `target_difficulty`, i.e. how close the miner has come to finding the block
+    pub best_share_of_target: f64,
+    pub blocks_accepted: u64,
+    pub blocks_rejected: u64,
+    /// Shares accepted by the stratum server, when mining in stratum mode
+    pub shares_accepted: u64,
+    pub shares_rejected: u64,
+    /// Address of the base node that is currently being mined with
+    pub base_node: String,
+    /// Number of times the miner has switched to another base node
+    pub base_node_failovers: u64,
+}
+
+impl Default for MinerStats {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            inner: Arc::new(Mutex::new(StatsInner::default())),
+        }
+    }
+}
+
+impl MinerStats {
+    /// Start recording the statistics of a new block template
+    pub fn start_template(&self, height: u64, target_difficulty: u64, num_threads: usize) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.height = height;
+        inner.target_difficulty = target_difficulty;
+        inner.num_threads = num_threads;
+        inner.best_difficulty = 0;
+    }
+
+    /// Record a report from a mining thread, along with the hashrates of all the threads
+    pub fn record_report(&self, report: &MiningReport, hash_rates: &HashRates) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.hashrate = hash_rates.total();
+        inner.best_difficulty = inner.best_difficulty.max(report.difficulty);
+    }
+
+    pub fn record_accepted_block(&self) {
+        self.inner.lock().unwrap().blocks_accepted += 1;
+    }
+
+    pub fn record_rejected_block(&self) {
+        self.inner.lock().unwrap().blocks_rejected += 1;
+    }
+
+    pub fn record_accepted_share(&self) {
+        self.inner.lock().unwrap().shares_accepted += 1;
+    }
+
+    pub fn record_rejected_share(&self) {
+        self.inner.lock().unwrap().shares_rejected += 1;
+    }
+
+    /// Record that the miner is connected to the base node at `address`
+    pub fn set_base_node(&self, address: &str) {
+        self.inner.lock().unwrap().base_node = address.to_string();
+    }
+
+    pub fn record_base_node_failover(&self) {
+        self.inner.lock().unwrap().base_node_failovers += 1;
+    }
+
+    pub fn snapshot(&self) -> StatsSnapshot {
+        let inner = self.inner.lock().unwrap();
+        let best_share_of_target = if inner.target_difficulty == 0 {
+            0.0
+        } else {
+            inner.best_difficulty as f64 / inner.target_difficulty as f64
+        };
+        StatsSnapshot {
+            uptime_sec: self.started.elapsed().as_secs(),
+            hashrate: inner.hashrate,
+            num_threads: inner.num_threads,
+            height: inner.height,
+            target_difficulty: inner.target_difficulty,
+            best_difficulty: inner.best_difficulty,
+            best_share_of_target,
+            blocks_accepted: inner.blocks_accepted,
+            blocks_rejected: inner.blocks_rejected,
+            shares_accepted: inner.shares_accepted,
+            shares_rejected: inner.shares_rejected,
+            base_node: inner.base_node.clone(),
+            base_node_failovers: inner.base_node_failovers,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    fn report(miner: usize, difficulty: u64) -> MiningReport {
+        MiningReport {
+            miner,
+            target_difficulty: 1000,
+            difficulty,
+            hashes: 2_000_000,
+            elapsed: Duration::from_secs(1),
+            header: None,
+            height: 10,
+            last_nonce: 0,
+        }
+    }
+
+    #[test]
+    fn best_difficulty_is_tracked_per_template() {
+        let stats = MinerStats::default();
+        let mut hash_rates = HashRates::new(2);
+        stats.start_template(10, 1000, 2);
+        for report in &[report(0, 100), report(1, 250), report(0, 50)] {
+            hash_rates.update(report);
+            stats.record_report(report, &hash_rates);
+        }
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.height, 10);
+        assert_eq!(snapshot.num_threads, 2);
+        assert_eq!(snapshot.best_difficulty, 250);
+        assert!((snapshot.best_share_of_target - 0.25).abs() < f64::EPSILON);
+        assert!((snapshot.hashrate - 4.0).abs() < f64::EPSILON);
+
+        stats.start_template(11, 2000, 2);
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.height, 11);
+        assert_eq!(snapshot.best_difficulty, 0);
+        assert!((snapshot.hashrate - 4.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn blocks_shares_and_failovers_are_counted() {
+        let stats = MinerStats::default();
+        assert!((stats.snapshot().best_share_of_target - 0.0).abs() < f64::EPSILON);
+        stats.record_accepted_block();
+        stats.record_accepted_block();
+        stats.record_rejected_block();
+        stats.record_accepted_share();
+        stats.record_rejected_share();
+        stats.record_rejected_share();
+        stats.set_base_node("http://127.0.0.1:18142");
+        stats.record_base_node_failover();
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.blocks_accepted, 2);
+        assert_eq!(snapshot.blocks_rejected, 1);
+        assert_eq!(snapshot.shares_accepted, 1);
+        assert_eq!(snapshot.shares_rejected, 2);
+        assert_eq!(snapshot.base_node, "http://127.0.0.1:18142");
+        assert_eq!(snapshot.base_node_failovers, 1);
+    }
+}
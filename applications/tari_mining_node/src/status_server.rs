@@ -0,0 +1,77 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+use crate::stats::MinerStats;
+use futures::future;
+use hyper::{
+    header::CONTENT_TYPE,
+    service::{make_service_fn, service_fn},
+    Body,
+    Method,
+    Request,
+    Response,
+    Server,
+    StatusCode,
+};
+use log::*;
+use std::{convert::Infallible, net::SocketAddr};
+use tari_app_utilities::health::HealthStatus;
+
+/// Serve the mining statistics as JSON over HTTP at `addr`, so that they can be scraped by monitoring dashboards,
+/// along with the `/health` and `/ready` checks of the miner
+pub async fn serve(addr: SocketAddr, stats: MinerStats, health: HealthStatus) -> Result<(), hyper::Error> {
+    let service = make_service_fn(move |_conn| {
+        let stats = stats.clone();
+        let health = health.clone();
+        future::ready(Ok::<_, Infallible>(service_fn(move |request| {
+            future::ready(Ok::<_, Infallible>(respond(&request, &stats, &health)))
+        })))
+    });
+    let server = Server::try_bind(&addr)?.serve(service);
+    info!("Serving mining statistics on http://{}/stats", addr);
+    server.await
+}
+
+fn respond(request: &Request<Body>, stats: &MinerStats, health: &HealthStatus) -> Response<Body> {
+    if request.method() == Method::GET {
+        if let Some(response) = health.respond(request.uri().path()) {
+            return response;
+        }
+    }
+    match (request.method(), request.uri().path()) {
+        (&Method::GET, "/") | (&Method::GET, "/stats") => match serde_json::to_vec(&stats.snapshot()) {
+            Ok(json) => Response::builder()
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(json))
+                .expect("Response is valid"),
+            Err(err) => status_response(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+        },
+        _ => status_response(StatusCode::NOT_FOUND, "Not found".to_string()),
+    }
+}
+
+fn status_response(status: StatusCode, message: String) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .body(Body::from(message))
+        .expect("Response is valid")
+}
@@ -0,0 +1,101 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+use super::protocol::*;
+use crate::errors::MinerError;
+use log::*;
+use serde::Serialize;
+use tokio::{
+    io::{self, AsyncBufReadExt, AsyncWriteExt, BufReader, Lines, ReadHalf, WriteHalf},
+    net::TcpStream,
+};
+
+/// The longest message the stratum server may send
+const MAX_MESSAGE_LENGTH: usize = 4096;
+
+/// A connection to a stratum server
+pub struct StratumClient {
+    lines: Lines<BufReader<ReadHalf<TcpStream>>>,
+    writer: WriteHalf<TcpStream>,
+    next_id: u64,
+}
+
+impl StratumClient {
+    pub async fn connect(address: &str) -> Result<Self, MinerError> {
+        let stream = TcpStream::connect(address).await?;
+        let (reader, writer) = io::split(stream);
+        Ok(Self {
+            lines: BufReader::new(reader).lines(),
+            writer,
+            next_id: 1,
+        })
+    }
+
+    /// Log in to the server as `login`, returning the session id and the first job, if the server has one yet.
+    /// Jobs that are sent before the login response are returned in its place.
+    pub async fn login(&mut self, login: &str, agent: &str) -> Result<(String, Option<JobParams>), MinerError> {
+        let id = self.request(METHOD_LOGIN, LoginParams { login, agent }).await?;
+        let mut job = None;
+        loop {
+            match self.next_message().await? {
+                ServerMessage::Response(response_id, result) if response_id == id => {
+                    let result = result.map_err(|err| {
+                        MinerError::Stratum(format!("Login was rejected: {} ({})", err.message, err.code))
+                    })?;
+                    let result: LoginResult = serde_json::from_value(result)
+                        .map_err(|err| MinerError::Stratum(format!("Invalid login response: {}", err)))?;
+                    return Ok((result.id, result.job.or(job)));
+                },
+                ServerMessage::Job(params) => job = Some(params),
+                message => debug!("Ignoring stratum message received while logging in: {:?}", message),
+            }
+        }
+    }
+
+    /// Send a request to the server and return its id. The response is received as a message.
+    pub async fn request<T: Serialize>(&mut self, method: &str, params: T) -> Result<u64, MinerError> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let mut message = serde_json::to_string(&Request::new(id, method, params))
+            .map_err(|err| MinerError::Stratum(format!("Could not serialize stratum request: {}", err)))?;
+        message.push('\n');
+        self.writer.write_all(message.as_bytes()).await?;
+        self.writer.flush().await?;
+        Ok(id)
+    }
+
+    /// Wait for the next message from the server
+    pub async fn next_message(&mut self) -> Result<ServerMessage, MinerError> {
+        let line = self
+            .lines
+            .next_line()
+            .await?
+            .ok_or_else(|| MinerError::Stratum("Stratum server closed the connection".to_string()))?;
+        if line.len() > MAX_MESSAGE_LENGTH {
+            return Err(MinerError::Stratum(
+                "Stratum server sent an oversized message".to_string(),
+            ));
+        }
+        trace!("Received stratum message: {}", line);
+        ServerMessage::parse(&line)
+    }
+}
@@ -0,0 +1,234 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+//! Stratum client mode. Instead of building blocks from the base node's block templates over gRPC, the miner mines the
+//! jobs of a stratum server, such as a mining pool or the stratum server of a base node, and submits shares to it.
+
+mod client;
+mod protocol;
+
+use crate::{
+    auto_tune::ThreadTuner,
+    config::MinerConfig,
+    display_report,
+    errors::MinerError,
+    miner::{HashRates, Miner, MiningReport},
+    stats::MinerStats,
+};
+use client::StratumClient;
+use futures::{future, FutureExt, StreamExt};
+use log::*;
+use protocol::*;
+use std::{
+    cmp::min,
+    collections::HashSet,
+    time::{Duration, Instant},
+};
+use tokio::time::{self, delay_for};
+
+/// The delay before reconnecting after the first failure. It doubles with every further failure.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// How often the session is kept alive while no shares are being submitted
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(60);
+
+const AGENT: &str = concat!("tari_mining_node/", env!("CARGO_PKG_VERSION"));
+
+/// Mine the jobs of the stratum server at `address`, reconnecting whenever the connection fails
+pub async fn mine(address: &str, config: &MinerConfig, tuner: &mut ThreadTuner, stats: &MinerStats) {
+    let mut backoff = Backoff::default();
+    loop {
+        if let Err(err) = mine_session(address, config, tuner, stats, &mut backoff).await {
+            error!("Stratum error: {}", err);
+        }
+        let delay = backoff.next_delay();
+        info!("Reconnecting to stratum server at {} in {:?}", address, delay);
+        delay_for(delay).await;
+    }
+}
+
+enum Event {
+    Report(Option<MiningReport>),
+    Message(Result<ServerMessage, MinerError>),
+    KeepAlive,
+}
+
+async fn mine_session(
+    address: &str,
+    config: &MinerConfig,
+    tuner: &mut ThreadTuner,
+    stats: &MinerStats,
+    backoff: &mut Backoff,
+) -> Result<(), MinerError>
+{
+    info!("Connecting to stratum server at {}", address);
+    let mut client = StratumClient::connect(address).await?;
+    let (session_id, mut job) = client.login(&config.stratum_login, AGENT).await?;
+    info!(
+        "Logged in to stratum server at {} as '{}'",
+        address, config.stratum_login
+    );
+    backoff.reset();
+
+    let mut keep_alive = time::interval_at(time::Instant::now() + KEEP_ALIVE_INTERVAL, KEEP_ALIVE_INTERVAL);
+    let mut pending_shares = HashSet::new();
+    let mut hash_rates = HashRates::default();
+    let mut job_changed = true;
+    let mut job_time = Instant::now();
+    loop {
+        if tuner.needs_tuning() {
+            tuner.tune(config.cpu_utilization_percent).await;
+        }
+        let num_threads = tuner.num_threads();
+        if hash_rates.num_threads() != num_threads {
+            hash_rates = HashRates::new(num_threads);
+        }
+        let mut miner = match job.as_ref() {
+            Some(job) => {
+                if job_changed {
+                    stats.start_template(job.height, job.target, num_threads);
+                    job_time = Instant::now();
+                    job_changed = false;
+                }
+                Some(
+                    Miner::init_mining(job.hasher()?, job.target, num_threads)
+                        .with_cpu_utilization_percent(config.cpu_utilization_percent),
+                )
+            },
+            None => {
+                debug!("Waiting for a job from the stratum server");
+                None
+            },
+        };
+
+        // Mine until a share is found or the job changes
+        loop {
+            let event = futures::select! {
+                report = next_report(&mut miner).fuse() => Event::Report(report),
+                message = client.next_message().fuse() => Event::Message(message),
+                _ = keep_alive.tick().fuse() => Event::KeepAlive,
+            };
+            match event {
+                Event::Report(None) => break,
+                Event::Report(Some(report)) => {
+                    if report.header.is_some() {
+                        if let Some(job) = job.as_ref() {
+                            debug!(
+                                "Miner {} found a share for job {} with difficulty {}",
+                                report.miner, job.job_id, report.difficulty
+                            );
+                            let id = client
+                                .request(METHOD_SUBMIT, SubmitParams {
+                                    id: &session_id,
+                                    job_id: &job.job_id,
+                                    nonce: nonce_to_hex(report.last_nonce),
+                                })
+                                .await?;
+                            pending_shares.insert(id);
+                        }
+                        // Keep mining the job from a new nonce
+                        break;
+                    }
+                    display_report(&report, &mut hash_rates, job_time).await;
+                    stats.record_report(&report, &hash_rates);
+                    if tuner.record_hashrates(&hash_rates) {
+                        break;
+                    }
+                },
+                Event::Message(message) => match message? {
+                    ServerMessage::Job(new_job) => {
+                        debug!(
+                            "Received job {} for height {} with share difficulty {}",
+                            new_job.job_id, new_job.height, new_job.target
+                        );
+                        job = Some(new_job);
+                        job_changed = true;
+                        break;
+                    },
+                    ServerMessage::Response(id, result) => {
+                        if pending_shares.remove(&id) {
+                            match result {
+                                Ok(_) => stats.record_accepted_share(),
+                                Err(err) => {
+                                    warn!("Share was rejected: {} ({})", err.message, err.code);
+                                    stats.record_rejected_share();
+                                },
+                            }
+                        } else if let Err(err) = result {
+                            warn!("Stratum request failed: {} ({})", err.message, err.code);
+                        }
+                    },
+                    ServerMessage::Other(method) => debug!("Ignoring stratum notification '{}'", method),
+                },
+                Event::KeepAlive => {
+                    client
+                        .request(METHOD_KEEP_ALIVE, SessionParams { id: &session_id })
+                        .await?;
+                },
+            }
+        }
+    }
+}
+
+async fn next_report(miner: &mut Option<Miner>) -> Option<MiningReport> {
+    match miner {
+        Some(miner) => miner.next().await,
+        None => future::pending().await,
+    }
+}
+
+/// The delay between reconnection attempts, which grows while the server cannot be reached
+struct Backoff {
+    delay: Duration,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self { delay: INITIAL_BACKOFF }
+    }
+}
+
+impl Backoff {
+    fn reset(&mut self) {
+        self.delay = INITIAL_BACKOFF;
+    }
+
+    fn next_delay(&mut self) -> Duration {
+        let delay = self.delay;
+        self.delay = min(self.delay * 2, MAX_BACKOFF);
+        delay
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        let mut backoff = Backoff::default();
+        let delays = (0..8).map(|_| backoff.next_delay().as_secs()).collect::<Vec<_>>();
+        assert_eq!(delays, vec![1, 2, 4, 8, 16, 32, 60, 60]);
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), INITIAL_BACKOFF);
+    }
+}
@@ -0,0 +1,214 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+//! The messages of the stratum protocol served by the base node's stratum server and compatible pools. Requests and
+//! responses are JSON-RPC objects, one per line.
+
+use crate::{difficulty::BlockHeaderSha3, errors::MinerError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tari_core::tari_utilities::hex::{from_hex, to_hex};
+
+pub const METHOD_LOGIN: &str = "login";
+pub const METHOD_SUBMIT: &str = "submit";
+pub const METHOD_KEEP_ALIVE: &str = "keepalived";
+pub const METHOD_JOB: &str = "job";
+
+#[derive(Debug, Serialize)]
+pub struct Request<'a, T> {
+    pub id: u64,
+    pub jsonrpc: &'static str,
+    pub method: &'a str,
+    pub params: T,
+}
+
+impl<'a, T> Request<'a, T> {
+    pub fn new(id: u64, method: &'a str, params: T) -> Self {
+        Self {
+            id,
+            jsonrpc: "2.0",
+            method,
+            params,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct LoginParams<'a> {
+    pub login: &'a str,
+    pub agent: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionParams<'a> {
+    pub id: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubmitParams<'a> {
+    pub id: &'a str,
+    pub job_id: &'a str,
+    pub nonce: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct JobParams {
+    pub job_id: String,
+    pub height: u64,
+    /// Hex of the mining hash bytes that come before the nonce
+    pub blob_before_nonce: String,
+    /// Hex of the mining hash bytes that come after the nonce
+    pub blob_after_nonce: String,
+    /// The share difficulty
+    pub target: u64,
+}
+
+impl JobParams {
+    /// The hasher that mines this job
+    pub fn hasher(&self) -> Result<BlockHeaderSha3, MinerError> {
+        let before = from_hex(&self.blob_before_nonce).map_err(|err| invalid_job("blob_before_nonce", err))?;
+        let after = from_hex(&self.blob_after_nonce).map_err(|err| invalid_job("blob_after_nonce", err))?;
+        Ok(BlockHeaderSha3::from_mining_blob(self.height, &before, after))
+    }
+}
+
+fn invalid_job<E: std::fmt::Display>(field: &str, err: E) -> MinerError {
+    MinerError::Stratum(format!("Invalid {} in job: {}", field, err))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LoginResult {
+    pub id: String,
+    pub job: Option<JobParams>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+/// A message from the stratum server: either a response to one of our requests or a notification
+#[derive(Debug, Deserialize)]
+struct RawMessage {
+    #[serde(default)]
+    id: Value,
+    method: Option<String>,
+    #[serde(default)]
+    params: Value,
+    result: Option<Value>,
+    error: Option<RpcError>,
+}
+
+#[derive(Debug)]
+pub enum ServerMessage {
+    /// A new job to mine
+    Job(JobParams),
+    /// The response to the request with the given id
+    Response(u64, Result<Value, RpcError>),
+    /// A notification this miner does not handle
+    Other(String),
+}
+
+impl ServerMessage {
+    pub fn parse(message: &str) -> Result<Self, MinerError> {
+        let message: RawMessage = serde_json::from_str(message)
+            .map_err(|err| MinerError::Stratum(format!("Invalid message from stratum server: {}", err)))?;
+        if let Some(method) = message.method {
+            if method != METHOD_JOB {
+                return Ok(ServerMessage::Other(method));
+            }
+            let job = serde_json::from_value(message.params)
+                .map_err(|err| MinerError::Stratum(format!("Invalid job from stratum server: {}", err)))?;
+            return Ok(ServerMessage::Job(job));
+        }
+        let id = message
+            .id
+            .as_u64()
+            .ok_or_else(|| MinerError::Stratum("Stratum server sent a response without a valid id".to_string()))?;
+        let result = match message.error {
+            Some(error) => Err(error),
+            None => Ok(message.result.unwrap_or(Value::Null)),
+        };
+        Ok(ServerMessage::Response(id, result))
+    }
+}
+
+/// Formats a nonce as the hex of its little-endian bytes
+pub fn nonce_to_hex(nonce: u64) -> String {
+    to_hex(&nonce.to_le_bytes())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn nonce_is_little_endian_hex() {
+        assert_eq!(nonce_to_hex(1), "0100000000000000");
+        assert_eq!(nonce_to_hex(0x0123_4567_89ab_cdef), "efcdab8967452301");
+    }
+
+    #[test]
+    fn parse_job_notification() {
+        let message = ServerMessage::parse(
+            r#"{"jsonrpc":"2.0","method":"job","params":{"job_id":"7","height":12,"blob_before_nonce":"00ff","blob_after_nonce":"01","target":1000}}"#,
+        )
+        .unwrap();
+        match message {
+            ServerMessage::Job(job) => {
+                assert_eq!(job.job_id, "7");
+                assert_eq!(job.height, 12);
+                assert_eq!(job.target, 1000);
+                assert!(job.hasher().is_ok());
+            },
+            other => panic!("Unexpected message {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_responses() {
+        match ServerMessage::parse(r#"{"id":3,"jsonrpc":"2.0","result":{"status":"OK"}}"#).unwrap() {
+            ServerMessage::Response(3, Ok(result)) => assert_eq!(result["status"], "OK"),
+            other => panic!("Unexpected message {:?}", other),
+        }
+        match ServerMessage::parse(r#"{"id":4,"jsonrpc":"2.0","error":{"code":-3,"message":"Low difficulty share"}}"#)
+            .unwrap()
+        {
+            ServerMessage::Response(4, Err(error)) => assert_eq!(error.code, -3),
+            other => panic!("Unexpected message {:?}", other),
+        }
+        assert!(ServerMessage::parse(r#"{"jsonrpc":"2.0","result":null}"#).is_err());
+    }
+
+    #[test]
+    fn invalid_job_blob_is_rejected() {
+        let job = JobParams {
+            job_id: "1".to_string(),
+            height: 1,
+            blob_before_nonce: "zz".to_string(),
+            blob_after_nonce: "".to_string(),
+            target: 1,
+        };
+        assert!(job.hasher().is_err());
+    }
+}
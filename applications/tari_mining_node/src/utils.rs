@@ -29,8 +29,12 @@ use tari_app_grpc::tari_rpc::{
     TransactionOutput,
 };
 
-/// Convert NewBlockTemplateResponse to GetCoinbaseRequest
-pub fn coinbase_request(template_response: &NewBlockTemplateResponse) -> Result<GetCoinbaseRequest, MinerError> {
+/// Convert NewBlockTemplateResponse to GetCoinbaseRequest, asking for the given extra data in the coinbase output
+pub fn coinbase_request(
+    template_response: &NewBlockTemplateResponse,
+    extra: &str,
+) -> Result<GetCoinbaseRequest, MinerError>
+{
     let template = template_response
         .new_block_template
         .as_ref()
@@ -46,7 +50,12 @@ pub fn coinbase_request(template_response: &NewBlockTemplateResponse) -> Result<
         .as_ref()
         .ok_or_else(|| err_empty("template.header"))?
         .height;
-    Ok(GetCoinbaseRequest { height, fee, reward })
+    Ok(GetCoinbaseRequest {
+        height,
+        fee,
+        reward,
+        extra: extra.as_bytes().to_vec(),
+    })
 }
 
 pub fn extract_outputs_and_kernels(
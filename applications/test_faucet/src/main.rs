@@ -112,6 +112,7 @@ async fn write_keys(mut rx: mpsc::Receiver<(TransactionOutput, PrivateKey, Micro
         features: KernelFeatures::empty(),
         fee: MicroTari::from(0),
         lock_height: 0,
+        burn_amount: MicroTari::from(0),
         excess,
         excess_sig: sig,
     };
@@ -60,6 +60,19 @@ pub enum BlockEvent {
     BlockSyncRewind(Vec<Arc<ChainBlock>>),
 }
 
+impl BlockEvent {
+    /// Returns true if this event may have changed the tip of the best chain
+    pub fn changes_tip(&self) -> bool {
+        matches!(
+            self,
+            BlockEvent::ValidBlockAdded(_, BlockAddResult::Ok(_), _) |
+                BlockEvent::ValidBlockAdded(_, BlockAddResult::ChainReorg { .. }, _) |
+                BlockEvent::BlockSyncComplete(_) |
+                BlockEvent::BlockSyncRewind(_)
+        )
+    }
+}
+
 /// Used to notify if the block event is for a propagated block.
 #[derive(Debug, Clone, Copy)]
 pub struct Broadcast(bool);
@@ -358,10 +371,10 @@ where T: BlockchainBackend + 'static
                     request.max_weight
                 };
 
-                let transactions = async_mempool::retrieve(self.mempool.clone(), asking_weight)
+                // The selected transactions are aggregated so that outputs they spend from one another are cut through
+                let transactions = async_mempool::retrieve_aggregated(self.mempool.clone(), asking_weight)
                     .await?
                     .into_iter()
-                    .map(|tx| Arc::try_unwrap(tx).unwrap_or_else(|tx| (*tx).clone()))
                     .collect();
 
                 let height = header.height;
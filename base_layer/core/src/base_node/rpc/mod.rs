@@ -27,6 +27,7 @@ use crate::base_node::StateMachineHandle;
 use crate::proto::{
     base_node::{
         FetchMatchingUtxos,
+        FetchUtxosByCommitment,
         FetchUtxosResponse,
         Signatures,
         TipInfoResponse,
@@ -34,6 +35,7 @@ use crate::proto::{
         TxQueryResponse,
         TxSubmissionResponse,
     },
+    mempool::FeeStatsResponse,
     types::{Signature, Transaction},
 };
 #[cfg(feature = "base_node")]
@@ -72,6 +74,16 @@ pub trait BaseNodeWalletService: Send + Sync + 'static {
 
     #[rpc(method = 5)]
     async fn get_tip_info(&self, request: Request<()>) -> Result<Response<TipInfoResponse>, RpcStatus>;
+
+    #[rpc(method = 6)]
+    async fn get_mempool_fee_stats(&self, request: Request<()>) -> Result<Response<FeeStatsResponse>, RpcStatus>;
+
+    /// Fetch the unspent outputs with the given commitments. Commitments of spent or unknown outputs are omitted.
+    #[rpc(method = 7)]
+    async fn fetch_utxos_by_commitment(
+        &self,
+        request: Request<FetchUtxosByCommitment>,
+    ) -> Result<Response<FetchUtxosResponse>, RpcStatus>;
 }
 
 #[cfg(feature = "base_node")]
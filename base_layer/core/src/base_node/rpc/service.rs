@@ -27,6 +27,7 @@ use crate::{
     proto::{
         base_node::{
             FetchMatchingUtxos,
+            FetchUtxosByCommitment,
             FetchUtxosResponse,
             Signatures as SignaturesProto,
             TipInfoResponse,
@@ -37,14 +38,21 @@ use crate::{
             TxSubmissionRejectionReason,
             TxSubmissionResponse,
         },
+        mempool::FeeStatsResponse,
         types::{Signature as SignatureProto, Transaction as TransactionProto},
     },
-    transactions::{transaction::Transaction, types::Signature},
+    transactions::{
+        transaction::Transaction,
+        types::{Commitment, Signature},
+    },
 };
 use std::convert::TryFrom;
 use tari_comms::protocol::rpc::{Request, Response, RpcStatus};
+use tari_crypto::tari_utilities::{ByteArray, Hashable};
 
 const LOG_TARGET: &str = "c::base_node::rpc";
+/// The maximum number of commitments that can be looked up in one request
+const MAX_FETCH_UTXOS_BY_COMMITMENT: usize = 1000;
 
 pub struct BaseNodeWalletRpcService<B> {
     db: AsyncBlockchainDb<B>,
@@ -128,6 +136,7 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletRpcService<B> {
             TxStorageResponse::NotStoredOrphan |
             TxStorageResponse::NotStoredTimeLocked |
             TxStorageResponse::NotStoredAlreadySpent |
+            TxStorageResponse::NotStoredFeeTooLow |
             TxStorageResponse::NotStored => TxQueryResponse {
                 location: TxLocation::NotStored as i32,
                 block_hash: None,
@@ -181,6 +190,13 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletService for BaseNodeWalletRpc
                 rejection_reason: TxSubmissionRejectionReason::TimeLocked.into(),
                 is_synced,
             },
+            // The transaction spends an output that an unconfirmed transaction in the mempool also spends, and does not
+            // pay enough to replace it
+            TxStorageResponse::NotStoredFeeTooLow => TxSubmissionResponse {
+                accepted: false,
+                rejection_reason: TxSubmissionRejectionReason::DoubleSpend.into(),
+                is_synced,
+            },
 
             TxStorageResponse::NotStored => TxSubmissionResponse {
                 accepted: false,
@@ -303,10 +319,18 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletService for BaseNodeWalletRpc
                 }
             }
         }
+        let mined_heights = db
+            .fetch_utxo_mined_heights(res.iter().map(|o| o.hash()).collect())
+            .await
+            .map_err(RpcStatus::log_internal_error(LOG_TARGET))?
+            .into_iter()
+            .map(|height| height.ok_or_else(|| RpcStatus::general("Unspent output was removed during the request")))
+            .collect::<Result<Vec<_>, _>>()?;
 
         Ok(Response::new(FetchUtxosResponse {
             outputs: res.into_iter().map(Into::into).collect(),
             is_synced,
+            mined_heights,
         }))
     }
 
@@ -329,4 +353,57 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletService for BaseNodeWalletRpc
             is_synced,
         }))
     }
+
+    async fn get_mempool_fee_stats(&self, _request: Request<()>) -> Result<Response<FeeStatsResponse>, RpcStatus> {
+        let mut mempool = self.mempool();
+        let fee_stats = mempool
+            .get_fee_stats()
+            .await
+            .map_err(RpcStatus::log_internal_error(LOG_TARGET))?;
+
+        Ok(Response::new(fee_stats.into()))
+    }
+
+    async fn fetch_utxos_by_commitment(
+        &self,
+        request: Request<FetchUtxosByCommitment>,
+    ) -> Result<Response<FetchUtxosResponse>, RpcStatus>
+    {
+        let message = request.into_message();
+        if message.commitments.len() > MAX_FETCH_UTXOS_BY_COMMITMENT {
+            return Err(RpcStatus::bad_request(format!(
+                "Cannot fetch more than {} outputs at a time",
+                MAX_FETCH_UTXOS_BY_COMMITMENT
+            )));
+        }
+
+        let state_machine = self.state_machine();
+        let status_watch = state_machine.get_status_info_watch();
+        let is_synced = match (*status_watch.borrow()).state_info {
+            StateInfo::Listening(li) => li.is_synced(),
+            _ => false,
+        };
+
+        let db = self.db();
+        let mut outputs = Vec::with_capacity(message.commitments.len());
+        let mut mined_heights = Vec::with_capacity(message.commitments.len());
+        for bytes in message.commitments {
+            let commitment =
+                Commitment::from_bytes(&bytes).map_err(|_| RpcStatus::bad_request("Commitment was invalid"))?;
+            if let Some(info) = db
+                .fetch_utxo_by_commitment(commitment)
+                .await
+                .map_err(RpcStatus::log_internal_error(LOG_TARGET))?
+            {
+                outputs.push(info.output.into());
+                mined_heights.push(info.mined_height);
+            }
+        }
+
+        Ok(Response::new(FetchUtxosResponse {
+            outputs,
+            is_synced,
+            mined_heights,
+        }))
+    }
 }
@@ -41,6 +41,7 @@ use futures::{future, Future};
 use log::*;
 use std::sync::Arc;
 use tari_comms::{connectivity::ConnectivityRequester, PeerManager};
+use tari_crypto::tari_utilities::hex::Hex;
 use tari_service_framework::{ServiceInitializationError, ServiceInitializer, ServiceInitializerContext};
 use tokio::sync::{broadcast, watch};
 
@@ -101,8 +102,20 @@ where B: BlockchainBackend + 'static
             let connectivity = handles.expect_handle::<ConnectivityRequester>();
             let peer_manager = handles.expect_handle::<Arc<PeerManager>>();
 
-            let sync_validators = SyncValidators::full_consensus(rules.clone(), factories);
+            let sync_validators = match config.block_sync_config.assume_valid.clone() {
+                Some(assume_valid) => {
+                    info!(
+                        target: LOG_TARGET,
+                        "Range proofs will not be verified for synced blocks below #{} ({})",
+                        assume_valid.height,
+                        assume_valid.hash.to_hex()
+                    );
+                    SyncValidators::full_consensus_assume_valid(rules.clone(), factories, assume_valid)
+                },
+                None => SyncValidators::full_consensus(rules.clone(), factories),
+            };
             let max_randomx_vms = config.max_randomx_vms;
+            let randomx_config = RandomXConfig::with_mode(config.randomx_mode);
 
             let node = BaseNodeStateMachine::new(
                 db,
@@ -115,7 +128,7 @@ where B: BlockchainBackend + 'static
                 sync_validators,
                 status_event_sender,
                 state_event_publisher,
-                RandomXFactory::new(RandomXConfig::default(), max_randomx_vms),
+                RandomXFactory::new(randomx_config, max_randomx_vms),
                 rules,
                 handles.get_shutdown_signal(),
             );
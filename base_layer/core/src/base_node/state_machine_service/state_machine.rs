@@ -31,7 +31,7 @@ use crate::{
     },
     chain_storage::{async_db::AsyncBlockchainDb, BlockchainBackend},
     consensus::ConsensusManager,
-    proof_of_work::randomx_factory::RandomXFactory,
+    proof_of_work::randomx_factory::{RandomXFactory, RandomXMode},
 };
 use futures::{future, future::Either};
 use log::*;
@@ -51,6 +51,7 @@ pub struct BaseNodeStateMachineConfig {
     pub orphan_db_clean_out_threshold: usize,
     pub pruning_horizon: u64,
     pub max_randomx_vms: usize,
+    pub randomx_mode: RandomXMode,
     pub blocks_behind_before_considered_lagging: u64,
 }
 
@@ -40,8 +40,10 @@ use crate::{
         SyncUtxosResponse,
     },
     transactions::{
+        range_proof_batch::RangeProofBatchVerifier,
+        tari_amount::MicroTari,
         transaction::{TransactionKernel, TransactionOutput},
-        types::{HashDigest, RangeProofService},
+        types::{CommitmentFactory, HashDigest, PrivateKey, RangeProofService},
     },
 };
 use croaring::Bitmap;
@@ -50,7 +52,7 @@ use log::*;
 use std::convert::{TryFrom, TryInto};
 use tari_comms::PeerConnection;
 use tari_crypto::{
-    commitment::HomomorphicCommitment,
+    commitment::{HomomorphicCommitment, HomomorphicCommitmentFactory},
     tari_utilities::{hex::Hex, Hashable},
 };
 use tari_mmr::{MerkleMountainRange, MutableMmr};
@@ -447,10 +449,13 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
                     }
 
                     // Validate rangeproofs if the MMR matches
-                    for o in unpruned_outputs.drain(..) {
-                        o.verify_range_proof(self.prover)
-                            .map_err(|err| HorizonSyncError::InvalidRangeProof(o.hash().to_hex(), err.to_string()))?;
+                    if let Err(err) = RangeProofBatchVerifier::new(self.prover).verify(&unpruned_outputs) {
+                        return Err(HorizonSyncError::InvalidRangeProof(
+                            unpruned_outputs[err.index()].hash().to_hex(),
+                            err.to_string(),
+                        ));
                     }
+                    unpruned_outputs.clear();
 
                     txn.update_pruned_hash_set(MmrTree::Utxo, current_header.hash().clone(), pruned_output_set);
                     txn.update_pruned_hash_set(
@@ -508,6 +513,7 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
         let header = self.db().fetch_chain_header(self.horizon_sync_height).await?;
         let mut pruned_utxo_sum = HomomorphicCommitment::default();
         let mut pruned_kernel_sum = HomomorphicCommitment::default();
+        let mut total_burned = MicroTari::from(0);
 
         let mut prev_mmr = 0;
         let mut prev_kernel_mmr = 0;
@@ -566,6 +572,7 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
 
             for k in kernels {
                 pruned_kernel_sum = &k.excess + &pruned_kernel_sum;
+                total_burned += k.burn_amount;
             }
             prev_kernel_mmr = curr_header.header().kernel_mmr_size;
 
@@ -578,13 +585,16 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
             );
         }
 
+        // Burned funds have left circulation, so they are not represented in the UTXO set
+        let burned = CommitmentFactory::default().commit_value(&PrivateKey::default(), total_burned.into());
+        debug!(target: LOG_TARGET, "Total burned up to horizon: {}", total_burned);
         self.shared
             .sync_validators
             .final_horizon_state
             .validate(
                 header.height(),
                 &pruned_utxo_sum,
-                &pruned_kernel_sum,
+                &(&pruned_kernel_sum - &burned),
                 &*self.db().clone().into_inner().db_read_access()?,
             )
             .map_err(HorizonSyncError::FinalStateValidationFailed)?;
@@ -22,7 +22,7 @@
 
 use super::error::BlockSyncError;
 use crate::{
-    base_node::sync::{hooks::Hooks, rpc},
+    base_node::sync::{hooks::Hooks, rpc, BlockSyncConfig},
     chain_storage::{async_db::AsyncBlockchainDb, BlockchainBackend, ChainBlock},
     proto::base_node::SyncBlocksRequest,
     tari_utilities::{hex::Hex, Hashable},
@@ -43,10 +43,13 @@ use tari_comms::{
     PeerConnection,
 };
 use tokio::task;
+use tracing::info_span;
+use tracing_futures::Instrument;
 
 const LOG_TARGET: &str = "c::bn::block_sync";
 
 pub struct BlockSynchronizer<B> {
+    config: BlockSyncConfig,
     db: AsyncBlockchainDb<B>,
     connectivity: ConnectivityRequester,
     sync_peer: Option<PeerConnection>,
@@ -56,6 +59,7 @@ pub struct BlockSynchronizer<B> {
 
 impl<B: BlockchainBackend + 'static> BlockSynchronizer<B> {
     pub fn new(
+        config: BlockSyncConfig,
         db: AsyncBlockchainDb<B>,
         connectivity: ConnectivityRequester,
         sync_peer: Option<PeerConnection>,
@@ -63,6 +67,7 @@ impl<B: BlockchainBackend + 'static> BlockSynchronizer<B> {
     ) -> Self
     {
         Self {
+            config,
             db,
             connectivity,
             sync_peer,
@@ -88,7 +93,9 @@ impl<B: BlockchainBackend + 'static> BlockSynchronizer<B> {
             target: LOG_TARGET,
             "Attempting to synchronize blocks with `{}`", node_id
         );
-        self.attempt_block_sync(peer_conn).await?;
+        self.attempt_block_sync(peer_conn)
+            .instrument(info_span!("block_sync", peer_id = %node_id))
+            .await?;
 
         self.db.cleanup_orphans().await?;
         Ok(())
@@ -112,7 +119,11 @@ impl<B: BlockchainBackend + 'static> BlockSynchronizer<B> {
 
     async fn attempt_block_sync(&mut self, mut conn: PeerConnection) -> Result<(), BlockSyncError> {
         let mut client = conn
-            .connect_rpc_using_builder(rpc::BaseNodeSyncRpcClient::builder().with_deadline(Duration::from_secs(60)))
+            .connect_rpc_using_builder(
+                rpc::BaseNodeSyncRpcClient::builder()
+                    .with_deadline(Duration::from_secs(60))
+                    .with_stream_buffer_size(self.config.rpc_stream_buffer_size),
+            )
             .await?;
         self.synchronize_blocks(conn.peer_node_id(), &mut client).await?;
         Ok(())
@@ -20,6 +20,7 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use crate::validation::block_validators::AssumeValid;
 use std::time::Duration;
 use tari_comms::peer_manager::NodeId;
 
@@ -31,6 +32,11 @@ pub struct BlockSyncConfig {
     pub ban_period: Duration,
     pub short_ban_period: Duration,
     pub sync_peers: Vec<NodeId>,
+    /// Range proofs and covenants are not verified for synced blocks that are ancestors of this block
+    pub assume_valid: Option<AssumeValid>,
+    /// The number of streamed headers or blocks that are buffered before the sync peer is asked to wait for them to
+    /// be processed
+    pub rpc_stream_buffer_size: usize,
 }
 
 impl Default for BlockSyncConfig {
@@ -42,6 +48,8 @@ impl Default for BlockSyncConfig {
             ban_period: Duration::from_secs(30 * 60),
             short_ban_period: Duration::from_secs(60),
             sync_peers: Default::default(),
+            assume_valid: None,
+            rpc_stream_buffer_size: 50,
         }
     }
 }
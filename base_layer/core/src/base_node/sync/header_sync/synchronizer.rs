@@ -48,6 +48,8 @@ use tari_comms::{
     protocol::rpc::{RpcError, RpcHandshakeError},
     PeerConnection,
 };
+use tracing::info_span;
+use tracing_futures::Instrument;
 
 const LOG_TARGET: &str = "c::bn::header_sync";
 
@@ -107,7 +109,11 @@ impl<'a, B: BlockchainBackend + 'static> HeaderSynchronizer<'a, B> {
                 target: LOG_TARGET,
                 "Attempting to synchronize headers with `{}`", node_id
             );
-            match self.attempt_sync(peer_conn.clone()).await {
+            let result = self
+                .attempt_sync(peer_conn.clone())
+                .instrument(info_span!("header_sync", peer_id = %node_id))
+                .await;
+            match result {
                 Ok(()) => return Ok(peer_conn),
                 // Try another peer
                 Err(err @ BlockHeaderSyncError::NotInSync) => {
@@ -253,7 +259,11 @@ impl<'a, B: BlockchainBackend + 'static> HeaderSynchronizer<'a, B> {
 
     async fn attempt_sync(&mut self, mut conn: PeerConnection) -> Result<(), BlockHeaderSyncError> {
         let peer = conn.peer_node_id().clone();
-        let mut client = conn.connect_rpc::<rpc::BaseNodeSyncRpcClient>().await?;
+        let mut client = conn
+            .connect_rpc_using_builder(
+                rpc::BaseNodeSyncRpcClient::builder().with_stream_buffer_size(self.config.rpc_stream_buffer_size),
+            )
+            .await?;
         let latency = client.get_last_request_latency().await?;
         debug!(
             target: LOG_TARGET,
@@ -25,7 +25,7 @@ use crate::{
     consensus::ConsensusManager,
     transactions::types::CryptoFactories,
     validation::{
-        block_validators::BlockValidator,
+        block_validators::{AssumeValid, BlockValidator},
         CandidateBlockBodyValidation,
         ChainBalanceValidator,
         FinalHorizonStateValidation,
@@ -57,6 +57,20 @@ impl<B: BlockchainBackend + 'static> SyncValidators<B> {
             ChainBalanceValidator::<B>::new(rules, factories),
         )
     }
+
+    /// Full consensus validation, except that range proofs and covenants are not verified for the blocks buried below
+    /// the trusted block
+    pub fn full_consensus_assume_valid(
+        rules: ConsensusManager,
+        factories: CryptoFactories,
+        assume_valid: AssumeValid,
+    ) -> Self
+    {
+        Self::new(
+            BlockValidator::new(rules.clone(), factories.clone()).with_assume_valid(assume_valid),
+            ChainBalanceValidator::<B>::new(rules, factories),
+        )
+    }
 }
 
 impl<B: BlockchainBackend> fmt::Debug for SyncValidators<B> {
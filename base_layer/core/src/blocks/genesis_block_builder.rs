@@ -0,0 +1,456 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Builds the genesis block of a private network from a [GenesisBlockSpec], so that a network can be launched without
+//! editing the genesis blocks compiled into this crate. A base node on `localnet` loads the resulting
+//! [GenesisBlockFile] with [load_genesis_block].
+
+use crate::{
+    blocks::{Block, BlockHeader},
+    chain_storage::{BlockHeaderAccumulatedData, ChainBlock},
+    consensus::ConsensusManager,
+    proof_of_work::{sha3_difficulty, AchievedTargetDifficulty, Difficulty, PowAlgorithm, ProofOfWork},
+    transactions::{
+        aggregated_body::AggregateBody,
+        tari_amount::MicroTari,
+        transaction::{KernelFeatures, OutputFeatures, TransactionError, TransactionKernel, TransactionOutput},
+        transaction_protocol::{build_challenge, TransactionMetadata},
+        types::{Commitment, CryptoFactories, HashDigest, PrivateKey, PublicKey, Signature},
+    },
+};
+use croaring::Bitmap;
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tari_crypto::{
+    commitment::HomomorphicCommitmentFactory,
+    keys::{PublicKey as PK, SecretKey},
+    range_proof::{RangeProofError, RangeProofService},
+    signatures::SchnorrSignatureError,
+    tari_utilities::{hex::Hex, Hashable},
+};
+use tari_mmr::{error::MerkleMountainRangeError, MerkleMountainRange, MutableMmr};
+use thiserror::Error;
+
+/// The number of nonces that are tried before giving up on achieving the target difficulty of the genesis header
+const MAX_NONCE_ATTEMPTS: u64 = 100_000_000;
+
+#[derive(Debug, Error)]
+pub enum GenesisBlockError {
+    #[error("The genesis block must create at least one output")]
+    NoOutputs,
+    #[error("The genesis block can not be mined with {0:?}, only Sha3 is supported")]
+    UnsupportedPowAlgorithm(PowAlgorithm),
+    #[error("Could not find a nonce that achieves difficulty {0}")]
+    TargetDifficultyNotAchieved(Difficulty),
+    #[error("Could not calculate the MMR roots: {0}")]
+    MerkleMountainRangeError(#[from] MerkleMountainRangeError),
+    #[error("Could not create the range proof of a genesis output: {0}")]
+    RangeProofError(#[from] RangeProofError),
+    #[error("Could not sign a genesis kernel: {0}")]
+    SigningError(#[from] SchnorrSignatureError),
+    #[error("The genesis block does not balance with the consensus rules: {0}")]
+    InvalidBalance(#[from] TransactionError),
+    #[error("Could not parse the genesis block: {0}")]
+    SerializationError(#[from] serde_json::Error),
+}
+
+/// An output created by the genesis block
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenesisOutputSpec {
+    pub value: MicroTari,
+    /// The height at which the output becomes spendable
+    #[serde(default)]
+    pub maturity: u64,
+}
+
+/// The specification of a genesis block
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenesisBlockSpec {
+    /// The UNIX timestamp of the genesis block
+    pub timestamp: u64,
+    /// The proof of work algorithm of the genesis header. Default: Sha3
+    #[serde(default = "default_pow_algo")]
+    pub pow_algo: PowAlgorithm,
+    /// The difficulty that the genesis header must achieve. Default: 1
+    #[serde(default = "default_target_difficulty")]
+    pub target_difficulty: u64,
+    /// The value of the genesis coinbase. This must match the height 0 reward of the emission schedule that the
+    /// network's base nodes are configured with. Default: the reward of the given consensus rules
+    #[serde(default)]
+    pub block_reward: Option<MicroTari>,
+    /// The outputs created by the genesis block, in addition to its coinbase
+    pub outputs: Vec<GenesisOutputSpec>,
+}
+
+fn default_pow_algo() -> PowAlgorithm {
+    PowAlgorithm::Sha3
+}
+
+fn default_target_difficulty() -> u64 {
+    1
+}
+
+/// The spending key of a genesis output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenesisOutputKey {
+    pub key: String,
+    pub value: MicroTari,
+    pub commitment: String,
+}
+
+/// A genesis block with the spending keys of its outputs
+#[derive(Debug, Clone)]
+pub struct GenesisBlock {
+    pub block: Block,
+    pub keys: Vec<GenesisOutputKey>,
+    /// The total value of the outputs that are not the coinbase
+    pub faucet_value: MicroTari,
+    pub target_difficulty: u64,
+}
+
+impl GenesisBlock {
+    /// The values of the genesis header that identify the network, in the format they are given in the header.
+    pub fn constants(&self) -> Vec<(&'static str, String)> {
+        let header = &self.block.header;
+        vec![
+            ("hash", header.hash().to_hex()),
+            ("timestamp", header.timestamp.as_u64().to_string()),
+            ("output_mr", header.output_mr.to_hex()),
+            ("range_proof_mr", header.range_proof_mr.to_hex()),
+            ("output_mmr_size", header.output_mmr_size.to_string()),
+            ("kernel_mr", header.kernel_mr.to_hex()),
+            ("kernel_mmr_size", header.kernel_mmr_size.to_string()),
+            ("nonce", header.nonce.to_string()),
+            ("pow_algo", format!("{:?}", header.pow.pow_algo)),
+            ("faucet_value", self.faucet_value.0.to_string()),
+        ]
+    }
+
+    /// The file that base nodes load the genesis block from
+    pub fn to_file(&self) -> GenesisBlockFile {
+        GenesisBlockFile {
+            block: self.block.clone(),
+            faucet_value: self.faucet_value,
+            target_difficulty: self.target_difficulty,
+        }
+    }
+}
+
+/// A genesis block as it is written by the genesis block builder, with the values that base nodes need to validate it
+/// and to calculate its accumulated data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenesisBlockFile {
+    pub block: Block,
+    /// The total value of the outputs that are not the coinbase, the `faucet_value` of the network's consensus
+    /// constants
+    pub faucet_value: MicroTari,
+    pub target_difficulty: u64,
+}
+
+impl GenesisBlockFile {
+    /// Calculate the accumulated data of the genesis header from the difficulty that it achieves
+    pub fn to_chain_block(&self) -> Result<ChainBlock, GenesisBlockError> {
+        let header = &self.block.header;
+        let target = Difficulty::from(self.target_difficulty);
+        if header.pow.pow_algo != PowAlgorithm::Sha3 {
+            return Err(GenesisBlockError::UnsupportedPowAlgorithm(header.pow.pow_algo));
+        }
+        let achieved_target =
+            AchievedTargetDifficulty::try_construct(PowAlgorithm::Sha3, target, sha3_difficulty(header))
+                .ok_or(GenesisBlockError::TargetDifficultyNotAchieved(target))?;
+        let achieved = achieved_target.achieved();
+        let accumulated_data = BlockHeaderAccumulatedData {
+            hash: self.block.hash(),
+            total_kernel_offset: header.total_kernel_offset.clone(),
+            achieved_difficulty: achieved,
+            total_accumulated_difficulty: achieved.as_u64() as u128,
+            accumulated_monero_difficulty: 1.into(),
+            accumulated_blake_difficulty: achieved,
+            target_difficulty: achieved_target.target(),
+        };
+        // NOTE: Panic is impossible, accumulated_data hash is set from block
+        Ok(ChainBlock::try_construct(Arc::new(self.block.clone()), accumulated_data).unwrap())
+    }
+}
+
+/// Build a genesis block from the given specification. The coinbase and the outputs are created with fresh spending
+/// keys, and are balanced by a coinbase kernel and a faucet kernel.
+pub fn build_genesis_block(
+    spec: &GenesisBlockSpec,
+    rules: &ConsensusManager,
+    factories: &CryptoFactories,
+) -> Result<GenesisBlock, GenesisBlockError>
+{
+    if spec.outputs.is_empty() {
+        return Err(GenesisBlockError::NoOutputs);
+    }
+    if spec.pow_algo != PowAlgorithm::Sha3 {
+        return Err(GenesisBlockError::UnsupportedPowAlgorithm(spec.pow_algo));
+    }
+
+    let block_reward = spec.block_reward.unwrap_or_else(|| rules.get_block_reward_at(0));
+    let coinbase_lock_height = rules.consensus_constants(0).coinbase_lock_height();
+    let (coinbase, coinbase_key) = create_output(
+        block_reward,
+        OutputFeatures::create_coinbase(coinbase_lock_height),
+        factories,
+    )?;
+    let coinbase_kernel = create_kernel(KernelFeatures::create_coinbase(), coinbase_key.clone())?;
+    let mut keys = vec![GenesisOutputKey {
+        key: coinbase_key.to_hex(),
+        value: block_reward,
+        commitment: coinbase.commitment.to_hex(),
+    }];
+    let mut outputs = vec![coinbase];
+
+    let mut faucet_value = MicroTari(0);
+    let mut key_sum = PrivateKey::default();
+    for output_spec in &spec.outputs {
+        let (output, key) = create_output(
+            output_spec.value,
+            OutputFeatures::with_maturity(output_spec.maturity),
+            factories,
+        )?;
+        faucet_value += output_spec.value;
+        key_sum = key_sum + key.clone();
+        keys.push(GenesisOutputKey {
+            key: key.to_hex(),
+            value: output_spec.value,
+            commitment: output.commitment.to_hex(),
+        });
+        outputs.push(output);
+    }
+    let faucet_kernel = create_kernel(KernelFeatures::empty(), key_sum)?;
+    let mut body = AggregateBody::new(vec![], outputs, vec![coinbase_kernel, faucet_kernel]);
+    body.sort();
+
+    let mut header = BlockHeader::new(0);
+    header.timestamp = spec.timestamp.into();
+    header.pow = ProofOfWork {
+        pow_algo: spec.pow_algo,
+        pow_data: vec![],
+    };
+    update_mmr_roots(&mut header, &body)?;
+    mine_header(&mut header, Difficulty::from(spec.target_difficulty))?;
+
+    Ok(GenesisBlock {
+        block: Block { header, body },
+        keys,
+        faucet_value,
+        target_difficulty: spec.target_difficulty,
+    })
+}
+
+fn create_output(
+    value: MicroTari,
+    features: OutputFeatures,
+    factories: &CryptoFactories,
+) -> Result<(TransactionOutput, PrivateKey), GenesisBlockError>
+{
+    let key = PrivateKey::random(&mut OsRng);
+    let commitment = factories.commitment.commit_value(&key, value.into());
+    let proof = factories.range_proof.construct_proof(&key, value.into())?;
+    Ok((TransactionOutput::new(features, commitment, proof.into()), key))
+}
+
+// Create a zero fee kernel with the given excess key
+fn create_kernel(features: KernelFeatures, key: PrivateKey) -> Result<TransactionKernel, GenesisBlockError> {
+    let excess = PublicKey::from_secret_key(&key);
+    let nonce = PrivateKey::random(&mut OsRng);
+    let challenge = build_challenge(&PublicKey::from_secret_key(&nonce), &TransactionMetadata::default());
+    let excess_sig = Signature::sign(key, nonce, &challenge)?;
+    Ok(TransactionKernel {
+        features,
+        fee: MicroTari(0),
+        lock_height: 0,
+        burn_amount: MicroTari(0),
+        excess: Commitment::from_public_key(&excess),
+        excess_sig,
+    })
+}
+
+// Set the MMR roots and sizes of the header to those of the given body, calculated the way they are for any other
+// block
+fn update_mmr_roots(header: &mut BlockHeader, body: &AggregateBody) -> Result<(), GenesisBlockError> {
+    let mut kernel_mmr = MerkleMountainRange::<HashDigest, _>::new(Vec::new());
+    let mut output_mmr = MutableMmr::<HashDigest, _>::new(Vec::new(), Bitmap::create())?;
+    let mut proof_mmr = MerkleMountainRange::<HashDigest, _>::new(Vec::new());
+    for kernel in body.kernels() {
+        kernel_mmr.push(kernel.hash())?;
+    }
+    for output in body.outputs() {
+        output_mmr.push(output.hash())?;
+        proof_mmr.push(output.proof().hash())?;
+    }
+    header.kernel_mr = kernel_mmr.get_merkle_root()?;
+    header.kernel_mmr_size = kernel_mmr.get_leaf_count()? as u64;
+    header.output_mr = output_mmr.get_merkle_root()?;
+    header.range_proof_mr = proof_mmr.get_merkle_root()?;
+    header.output_mmr_size = proof_mmr.get_leaf_count()? as u64;
+    Ok(())
+}
+
+fn mine_header(header: &mut BlockHeader, target_difficulty: Difficulty) -> Result<(), GenesisBlockError> {
+    for nonce in 0..MAX_NONCE_ATTEMPTS {
+        header.nonce = nonce;
+        if sha3_difficulty(header) >= target_difficulty {
+            return Ok(());
+        }
+    }
+    Err(GenesisBlockError::TargetDifficultyNotAchieved(target_difficulty))
+}
+
+/// Parse a genesis block file from its JSON representation, as written by the genesis block builder
+pub fn load_genesis_block(json: &str) -> Result<GenesisBlockFile, GenesisBlockError> {
+    Ok(serde_json::from_str(json)?)
+}
+
+/// Check that the kernels of the genesis block are valid and that its outputs add up to the height 0 emission and the
+/// faucet value of the given consensus rules, which is what the chain balance validation of the node expects.
+pub fn validate_genesis_block(
+    block: &Block,
+    rules: &ConsensusManager,
+    factories: &CryptoFactories,
+) -> Result<(), GenesisBlockError>
+{
+    let total_value = rules.get_total_emission_at(0) + rules.consensus_constants(0).faucet_value();
+    block
+        .body
+        .validate_kernels_and_balance(&block.header.total_kernel_offset, total_value, factories)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::consensus::{ConsensusManagerBuilder, Network};
+
+    fn spec() -> GenesisBlockSpec {
+        serde_json::from_str(
+            r#"{
+                "timestamp": 1620000000,
+                "outputs": [{ "value": 1000000 }, { "value": 2000000, "maturity": 10 }]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn it_builds_a_valid_genesis_block() {
+        let factories = CryptoFactories::default();
+        let rules = ConsensusManagerBuilder::new(Network::LocalNet).build();
+        let genesis = build_genesis_block(&spec(), &rules, &factories).unwrap();
+        let block = &genesis.block;
+        assert_eq!(block.header.height, 0);
+        assert_eq!(block.header.timestamp.as_u64(), 1_620_000_000);
+        assert_eq!(block.header.output_mmr_size, 3);
+        assert_eq!(block.header.kernel_mmr_size, 2);
+        assert_eq!(genesis.keys.len(), 3);
+        assert_eq!(genesis.faucet_value, MicroTari(3_000_000));
+        assert!(block.body.outputs().iter().any(|o| o.features.maturity == 10));
+        assert_eq!(block.body.outputs().iter().filter(|o| o.is_coinbase()).count(), 1);
+        assert_eq!(
+            block
+                .body
+                .kernels()
+                .iter()
+                .filter(|k| k.features.contains(KernelFeatures::COINBASE_KERNEL))
+                .count(),
+            1
+        );
+        block
+            .body
+            .validate_internal_consistency(
+                &PrivateKey::default(),
+                rules.get_block_reward_at(0) + MicroTari(3_000_000),
+                &factories,
+            )
+            .unwrap();
+
+        // The base node applies the faucet value of the file to its consensus rules
+        let rules = ConsensusManagerBuilder::new(Network::LocalNet)
+            .with_faucet_value(genesis.faucet_value)
+            .build();
+        validate_genesis_block(block, &rules, &factories).unwrap();
+        let other_rules = ConsensusManagerBuilder::new(Network::LocalNet)
+            .with_faucet_value(MicroTari(1))
+            .build();
+        assert!(matches!(
+            validate_genesis_block(block, &other_rules, &factories),
+            Err(GenesisBlockError::InvalidBalance(_))
+        ));
+    }
+
+    #[test]
+    fn it_loads_the_genesis_block_file() {
+        let factories = CryptoFactories::default();
+        let rules = ConsensusManagerBuilder::new(Network::LocalNet).build();
+        let mut spec = spec();
+        spec.target_difficulty = 10;
+        spec.block_reward = Some(MicroTari(5_000));
+        let genesis = build_genesis_block(&spec, &rules, &factories).unwrap();
+        assert!(genesis.block.body.outputs().iter().any(|o| o.is_coinbase() &&
+            genesis
+                .keys
+                .iter()
+                .any(|k| k.value == MicroTari(5_000) && k.commitment == o.commitment.to_hex())));
+
+        let json = serde_json::to_string(&genesis.to_file()).unwrap();
+        let file = load_genesis_block(&json).unwrap();
+        assert_eq!(file.faucet_value, MicroTari(3_000_000));
+        let chain_block = file.to_chain_block().unwrap();
+        assert_eq!(chain_block.hash(), &genesis.block.hash());
+        let achieved = sha3_difficulty(&genesis.block.header);
+        let accumulated_data = chain_block.accumulated_data();
+        assert!(achieved >= Difficulty::from(10));
+        assert_eq!(accumulated_data.achieved_difficulty, achieved);
+        assert_eq!(accumulated_data.target_difficulty, Difficulty::from(10));
+        assert_eq!(accumulated_data.accumulated_blake_difficulty, achieved);
+        assert_eq!(accumulated_data.total_accumulated_difficulty, achieved.as_u64() as u128);
+
+        let mut unachieved = file;
+        unachieved.target_difficulty = achieved.as_u64() + 1;
+        assert!(matches!(
+            unachieved.to_chain_block(),
+            Err(GenesisBlockError::TargetDifficultyNotAchieved(_))
+        ));
+    }
+
+    #[test]
+    fn it_rejects_invalid_specs() {
+        let factories = CryptoFactories::default();
+        let rules = ConsensusManagerBuilder::new(Network::LocalNet).build();
+        let mut no_outputs = spec();
+        no_outputs.outputs.clear();
+        assert!(matches!(
+            build_genesis_block(&no_outputs, &rules, &factories),
+            Err(GenesisBlockError::NoOutputs)
+        ));
+        let mut monero = spec();
+        monero.pow_algo = PowAlgorithm::Monero;
+        assert!(matches!(
+            build_genesis_block(&monero, &rules, &factories),
+            Err(GenesisBlockError::UnsupportedPowAlgorithm(PowAlgorithm::Monero))
+        ));
+    }
+}
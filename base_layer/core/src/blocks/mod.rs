@@ -28,6 +28,8 @@ pub mod block_header;
 #[cfg(feature = "base_node")]
 pub mod genesis_block;
 #[cfg(feature = "base_node")]
+pub mod genesis_block_builder;
+#[cfg(feature = "base_node")]
 mod new_block_template;
 #[cfg(feature = "base_node")]
 mod new_blockheader_template;
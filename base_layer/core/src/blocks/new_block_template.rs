@@ -30,7 +30,7 @@ use std::fmt::{Display, Formatter};
 
 /// The new block template is used constructing a new partial block, allowing a miner to added the coinbase utxo and as
 /// a final step the Base node to add the MMR roots to the header.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct NewBlockTemplate {
     pub header: NewBlockHeaderTemplate,
     pub body: AggregateBody,
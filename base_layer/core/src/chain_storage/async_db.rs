@@ -37,6 +37,7 @@ use crate::{
         MmrTree,
         PrunedOutput,
         TargetDifficulties,
+        UtxoMinedInfo,
     },
     common::rolling_vec::RollingVec,
     proof_of_work::{PowAlgorithm, TargetDifficultyWindow},
@@ -140,8 +141,12 @@ impl<B: BlockchainBackend + 'static> AsyncBlockchainDb<B> {
     //---------------------------------- TXO --------------------------------------------//
     make_async_fn!(fetch_utxo(hash: HashOutput) -> Option<TransactionOutput>, "fetch_utxo");
 
+    make_async_fn!(fetch_utxo_by_commitment(commitment: Commitment) -> Option<UtxoMinedInfo>, "fetch_utxo_by_commitment");
+
     make_async_fn!(fetch_utxos(hashes: Vec<HashOutput>, is_spent_as_of: Option<HashOutput>) -> Vec<Option<(TransactionOutput, bool)>>, "fetch_utxos");
 
+    make_async_fn!(fetch_utxo_mined_heights(hashes: Vec<HashOutput>) -> Vec<Option<u64>>, "fetch_utxo_mined_heights");
+
     make_async_fn!(fetch_utxos_by_mmr_position(start: u64, end: u64, end_header_hash: HashOutput) -> (Vec<PrunedOutput>, Bitmap), "fetch_utxos_by_mmr_position");
 
     //---------------------------------- Kernel --------------------------------------------//
@@ -12,10 +12,12 @@ use crate::{
         DbValue,
         HorizonData,
         MmrTree,
+        UtxoMinedInfo,
     },
     transactions::{
+        asset::AssetId,
         transaction::{TransactionInput, TransactionKernel, TransactionOutput},
-        types::{HashOutput, Signature},
+        types::{Commitment, HashOutput, Signature},
     },
 };
 use croaring::Bitmap;
@@ -104,6 +106,13 @@ pub trait BlockchainBackend: Send + Sync {
     /// Fetch a specific output. Returns the output and the leaf index in the output MMR
     fn fetch_output(&self, output_hash: &HashOutput) -> Result<Option<(TransactionOutput, u32)>, ChainStorageError>;
 
+    /// Fetch the unpruned output with this commitment, along with where it was mined. The output may be spent.
+    fn fetch_output_by_commitment(&self, commitment: &Commitment) -> Result<Option<UtxoMinedInfo>, ChainStorageError>;
+
+    /// Returns true if an output carrying the given token of the asset has been added to the chain. Token zero is the
+    /// registration of the asset.
+    fn asset_token_exists(&self, asset_id: &AssetId, token_id: u64) -> Result<bool, ChainStorageError>;
+
     /// Fetch all outputs in a block
     fn fetch_outputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<PrunedOutput>, ChainStorageError>;
 
@@ -40,6 +40,7 @@ use crate::{
         MmrTree,
         OrNotFound,
         TargetDifficulties,
+        UtxoMinedInfo,
     },
     common::rolling_vec::RollingVec,
     consensus::{chain_strength_comparer::ChainStrengthComparer, ConsensusConstants, ConsensusManager},
@@ -63,7 +64,7 @@ use std::{
     time::Instant,
 };
 use tari_common_types::{chain_metadata::ChainMetadata, types::BlockHash};
-use tari_crypto::tari_utilities::{hex::Hex, ByteArray, Hashable};
+use tari_crypto::tari_utilities::{hex::Hex, Hashable};
 use tari_mmr::{MerkleMountainRange, MutableMmr};
 use uint::static_assertions::_core::ops::RangeBounds;
 
@@ -286,6 +287,29 @@ where B: BlockchainBackend
         Ok(db.fetch_output(&hash)?.map(|(out, _index)| out))
     }
 
+    /// Fetch the unspent output with the given commitment, along with the height and block it was mined in. Outputs
+    /// that are spent as of the tip are not returned. This is a single index lookup, wallets should prefer it to
+    /// scanning for their outputs.
+    pub fn fetch_utxo_by_commitment(&self, commitment: Commitment) -> Result<Option<UtxoMinedInfo>, ChainStorageError> {
+        let db = self.db_read_access()?;
+        let info = match db.fetch_output_by_commitment(&commitment)? {
+            Some(info) => info,
+            None => return Ok(None),
+        };
+        let tip_hash = db.fetch_chain_metadata()?.best_block().clone();
+        let data = db
+            .fetch_block_accumulated_data(&tip_hash)?
+            .ok_or_else(|| ChainStorageError::ValueNotFound {
+                entity: "BlockAccumulatedData".to_string(),
+                field: "header_hash".to_string(),
+                value: tip_hash.to_hex(),
+            })?;
+        if data.deleted().contains(info.mmr_position) {
+            return Ok(None);
+        }
+        Ok(Some(info))
+    }
+
     /// Return a list of matching utxos, with each being `None` if not found. If found, the transaction
     /// output, and a boolean indicating if the UTXO was spent as of the block hash specified or the tip if not
     /// specified.
@@ -316,6 +340,22 @@ where B: BlockchainBackend
         Ok(result)
     }
 
+    /// Return the height of the block that each of the given outputs was mined in, with each being `None` if the
+    /// output is not found
+    pub fn fetch_utxo_mined_heights(&self, hashes: Vec<HashOutput>) -> Result<Vec<Option<u64>>, ChainStorageError> {
+        let db = self.db_read_access()?;
+        let mut result = Vec::with_capacity(hashes.len());
+        for hash in hashes {
+            // The block that contains leaf `i` is the first block with an output MMR size of at least `i + 1`
+            let height = match db.fetch_output(&hash)? {
+                Some((_, mmr_index)) => Some(db.fetch_header_containing_utxo_mmr(u64::from(mmr_index) + 1)?.height()),
+                None => None,
+            };
+            result.push(height);
+        }
+        Ok(result)
+    }
+
     pub fn fetch_kernel_by_excess(
         &self,
         excess: &[u8],
@@ -1227,12 +1267,9 @@ fn fetch_block_with_utxo<T: BlockchainBackend>(
     commitment: Commitment,
 ) -> Result<Option<HistoricalBlock>, ChainStorageError>
 {
-    match db.fetch_output(&commitment.to_vec()) {
+    match db.fetch_output_by_commitment(&commitment) {
         Ok(output) => match output {
-            Some((_output, leaf)) => {
-                let header = db.fetch_header_containing_utxo_mmr(leaf as u64)?;
-                fetch_block_by_hash(db, header.hash().to_owned())
-            },
+            Some(info) => fetch_block_by_hash(db, info.header_hash),
             None => Ok(None),
         },
         Err(_) => Err(ChainStorageError::ValueNotFound {
@@ -45,6 +45,7 @@ use crate::{
             TransactionInputRowData,
             TransactionKernelRowData,
             TransactionOutputRowData,
+            LMDB_DB_ASSET_INDEX,
             LMDB_DB_BLOCK_ACCUMULATED_DATA,
             LMDB_DB_BLOCK_HASHES,
             LMDB_DB_HEADERS,
@@ -61,6 +62,7 @@ use crate::{
             LMDB_DB_ORPHAN_HEADER_ACCUMULATED_DATA,
             LMDB_DB_ORPHAN_PARENT_MAP_INDEX,
             LMDB_DB_TXOS_HASH_TO_INDEX,
+            LMDB_DB_TXO_COMMITMENT_INDEX,
             LMDB_DB_UTXOS,
             LMDB_DB_UTXO_MMR_SIZE_INDEX,
         },
@@ -70,10 +72,12 @@ use crate::{
         HorizonData,
         MmrTree,
         PrunedOutput,
+        UtxoMinedInfo,
     },
     crypto::tari_utilities::hex::to_hex,
     transactions::{
         aggregated_body::AggregateBody,
+        asset::AssetId,
         transaction::{TransactionInput, TransactionKernel, TransactionOutput},
         types::{Commitment, HashDigest, HashOutput, Signature},
     },
@@ -104,6 +108,8 @@ type DatabaseRef = Arc<Database<'static>>;
 
 pub const LOG_TARGET: &str = "c::cs::lmdb_db::lmdb_db";
 
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
 /// This is a lmdb-based blockchain database for persistent storage of the chain state.
 pub struct LMDBDatabase {
     env: Arc<Environment>,
@@ -116,6 +122,7 @@ pub struct LMDBDatabase {
     utxos_db: DatabaseRef,
     inputs_db: DatabaseRef,
     txos_hash_to_index_db: DatabaseRef,
+    txo_commitment_index: DatabaseRef,
     kernels_db: DatabaseRef,
     kernel_excess_index: DatabaseRef,
     kernel_excess_sig_index: DatabaseRef,
@@ -126,6 +133,7 @@ pub struct LMDBDatabase {
     orphan_header_accumulated_data_db: DatabaseRef,
     orphan_chain_tips_db: DatabaseRef,
     orphan_parent_map_index: DatabaseRef,
+    asset_index: DatabaseRef,
     _file_lock: Arc<File>,
 }
 
@@ -142,6 +150,7 @@ impl LMDBDatabase {
             utxos_db: get_database(&store, LMDB_DB_UTXOS)?,
             inputs_db: get_database(&store, LMDB_DB_INPUTS)?,
             txos_hash_to_index_db: get_database(&store, LMDB_DB_TXOS_HASH_TO_INDEX)?,
+            txo_commitment_index: get_database(&store, LMDB_DB_TXO_COMMITMENT_INDEX)?,
             kernels_db: get_database(&store, LMDB_DB_KERNELS)?,
             kernel_excess_index: get_database(&store, LMDB_DB_KERNEL_EXCESS_INDEX)?,
             kernel_excess_sig_index: get_database(&store, LMDB_DB_KERNEL_EXCESS_SIG_INDEX)?,
@@ -152,14 +161,64 @@ impl LMDBDatabase {
             monero_seed_height_db: get_database(&store, LMDB_DB_MONERO_SEED_HEIGHT)?,
             orphan_chain_tips_db: get_database(&store, LMDB_DB_ORPHAN_CHAIN_TIPS)?,
             orphan_parent_map_index: get_database(&store, LMDB_DB_ORPHAN_PARENT_MAP_INDEX)?,
+            asset_index: get_database(&store, LMDB_DB_ASSET_INDEX)?,
             env,
             env_config: store.env_config(),
             _file_lock: Arc::new(file_lock),
         };
+        res.build_txo_commitment_index_if_required()?;
 
         Ok(res)
     }
 
+    /// Databases created before the commitment index existed have an empty index. This populates it from the unpruned
+    /// outputs so that commitment lookups do not have to fall back to scanning the UTXO set.
+    fn build_txo_commitment_index_if_required(&self) -> Result<(), ChainStorageError> {
+        let mut entries = {
+            let txn = self.read_transaction()?;
+            if lmdb_len(&txn, &self.txo_commitment_index)? > 0 || lmdb_len(&txn, &self.utxos_db)? == 0 {
+                return Ok(());
+            }
+            lmdb_filter_map_values(&txn, &self.utxos_db, |row: TransactionOutputRowData| {
+                let TransactionOutputRowData {
+                    output,
+                    header_hash,
+                    mmr_position,
+                    ..
+                } = row;
+                Ok(output.map(|output| (output.commitment, header_hash, mmr_position)))
+            })?
+        };
+        if entries.is_empty() {
+            return Ok(());
+        }
+        // Where a commitment was reused, the most recent output must end up in the index
+        entries.sort_by_key(|(_, _, mmr_position)| *mmr_position);
+
+        info!(
+            target: LOG_TARGET,
+            "Building the output commitment index for {} unspent output(s)",
+            entries.len()
+        );
+        let txn = self.write_transaction()?;
+        for (commitment, header_hash, mmr_position) in entries {
+            let mined_height = self.fetch_height_from_hash(&txn, &header_hash).or_not_found(
+                "BlockHeader",
+                "hash",
+                header_hash.to_hex(),
+            )?;
+            lmdb_replace(
+                &txn,
+                &self.txo_commitment_index,
+                commitment.as_bytes(),
+                &(header_hash, mmr_position, mined_height),
+            )?;
+        }
+        txn.commit()
+            .map_err(|e| ChainStorageError::AccessError(e.to_string()))?;
+        Ok(())
+    }
+
     /// Try to establish a read lock on the LMDB database. If an exclusive write lock has been previously acquired, this
     /// method will block until that lock is released.
     fn read_transaction(&self) -> Result<ReadTransaction<'_>, ChainStorageError> {
@@ -328,11 +387,30 @@ impl LMDBDatabase {
         let mut output: TransactionOutputRowData =
             lmdb_get(txn, &self.utxos_db, key).or_not_found("TransactionOutput", "key", key.to_string())?;
         let result = output.output.take();
+        if let Some(pruned) = &result {
+            self.delete_txo_commitment_index_entry(txn, &pruned.commitment, output.mmr_position)?;
+        }
         // output.output is None
         lmdb_replace(txn, &self.utxos_db, key, &output)?;
         Ok(result)
     }
 
+    /// Removes the commitment index entry for the output at `mmr_position`. The entry is left alone if it has since
+    /// been taken over by a later output with the same commitment.
+    fn delete_txo_commitment_index_entry(
+        &self,
+        txn: &WriteTransaction<'_>,
+        commitment: &Commitment,
+        mmr_position: u32,
+    ) -> Result<(), ChainStorageError>
+    {
+        let indexed = lmdb_get::<_, (HashOutput, u32, u64)>(txn, &self.txo_commitment_index, commitment.as_bytes())?;
+        if matches!(indexed, Some((_, pos, _)) if pos == mmr_position) {
+            lmdb_delete(txn, &self.txo_commitment_index, commitment.as_bytes())?;
+        }
+        Ok(())
+    }
+
     fn insert_output(
         &self,
         txn: &WriteTransaction<'_>,
@@ -351,6 +429,23 @@ impl LMDBDatabase {
             &(mmr_position, key.clone()),
             "txos_hash_to_index_db",
         )?;
+        let mined_height =
+            self.fetch_height_from_hash(txn, &header_hash)
+                .or_not_found("BlockHeader", "hash", header_hash.to_hex())?;
+        lmdb_replace(
+            txn,
+            &*self.txo_commitment_index,
+            output.commitment.as_bytes(),
+            &(header_hash.clone(), mmr_position, mined_height),
+        )?;
+        if let Some(asset) = &output.features.asset {
+            lmdb_insert_dup(
+                txn,
+                &*self.asset_index,
+                &asset_index_key(&asset.asset_id(), asset.token_id),
+                &output_hash,
+            )?;
+        }
         lmdb_insert(
             txn,
             &*self.utxos_db,
@@ -676,6 +771,17 @@ impl LMDBDatabase {
         for utxo in rows {
             trace!(target: LOG_TARGET, "Deleting UTXO `{}`", to_hex(&utxo.hash));
             lmdb_delete(&write_txn, &self.txos_hash_to_index_db, utxo.hash.as_slice())?;
+            if let Some(output) = &utxo.output {
+                self.delete_txo_commitment_index_entry(&write_txn, &output.commitment, utxo.mmr_position)?;
+                if let Some(asset) = &output.features.asset {
+                    lmdb_delete_key_value(
+                        &write_txn,
+                        &self.asset_index,
+                        &asset_index_key(&asset.asset_id(), asset.token_id),
+                        &utxo.hash,
+                    )?;
+                }
+            }
         }
         debug!(target: LOG_TARGET, "Deleting kernels...");
         let kernels =
@@ -979,6 +1085,24 @@ impl LMDBDatabase {
             self.prune_output(&write_txn, &key)?;
         }
 
+        // The inputs of the block at the horizon spent the outputs pruned above, so they are pruned with them. What is
+        // left of a spent output is its hash, which is all that is needed to calculate the output MMR and its proofs.
+        let header_hash =
+            lmdb_get::<_, BlockHeaderAccumulatedData>(&write_txn, &self.header_accumulated_data_db, &horizon)
+                .or_not_found("BlockHeaderAccumulatedData", "height", horizon.to_string())?
+                .hash;
+        let pruned_inputs = lmdb_delete_keys_starting_with::<TransactionInputRowData>(
+            &write_txn,
+            &self.inputs_db,
+            &header_hash.to_hex(),
+        )?;
+        debug!(
+            target: LOG_TARGET,
+            "Pruned {} input(s) of block #{}",
+            pruned_inputs.len(),
+            horizon
+        );
+
         self.set_metadata(
             &write_txn,
             MetadataKey::PrunedHeight,
@@ -1045,12 +1169,47 @@ impl LMDBDatabase {
 }
 
 pub fn create_lmdb_database<P: AsRef<Path>>(path: P, config: LMDBConfig) -> Result<LMDBDatabase, ChainStorageError> {
-    let flags = db::CREATE;
     let _ = std::fs::create_dir_all(&path);
 
     let file_lock = acquire_exclusive_file_lock(&path.as_ref().to_path_buf())?;
 
-    let lmdb_store = LMDBBuilder::new()
+    let lmdb_store = create_lmdb_store(path, config)?;
+    LMDBDatabase::new(lmdb_store, file_lock)
+}
+
+/// Compacts the LMDB database at `path`. Pruning frees the space held by spent outputs and inputs inside the data file,
+/// and LMDB reuses that space, but it never shrinks the file. This rewrites the data file without its free pages. The
+/// database must not be open.
+pub fn compact_lmdb_database<P: AsRef<Path>>(path: P, config: LMDBConfig) -> Result<(), ChainStorageError> {
+    let path = path.as_ref();
+    let _file_lock = acquire_exclusive_file_lock(&path.to_path_buf())?;
+
+    let compact_path = path.join("temp_compact");
+    let _ = fs::remove_dir_all(&compact_path);
+    fs::create_dir_all(&compact_path)?;
+    let data_file = path.join("data.mdb");
+    let size_before = fs::metadata(&data_file)?.len();
+    {
+        // The environment is closed when the store is dropped
+        let lmdb_store = create_lmdb_store(path, config)?;
+        lmdb_store
+            .copy_compacted(&compact_path)
+            .map_err(|err| ChainStorageError::CriticalError(format!("Could not compact LMDB store:{}", err)))?;
+    }
+    fs::rename(compact_path.join("data.mdb"), &data_file)?;
+    fs::remove_dir_all(&compact_path)?;
+    info!(
+        target: LOG_TARGET,
+        "Compacted blockchain database from {} MB to {} MB",
+        size_before / BYTES_PER_MB,
+        fs::metadata(&data_file)?.len() / BYTES_PER_MB
+    );
+    Ok(())
+}
+
+fn create_lmdb_store<P: AsRef<Path>>(path: P, config: LMDBConfig) -> Result<LMDBStore, ChainStorageError> {
+    let flags = db::CREATE;
+    LMDBBuilder::new()
         .set_path(path)
         .set_env_config(config)
         .set_max_number_of_databases(20)
@@ -1062,6 +1221,7 @@ pub fn create_lmdb_database<P: AsRef<Path>>(path: P, config: LMDBConfig) -> Resu
         .add_database(LMDB_DB_UTXOS, flags)
         .add_database(LMDB_DB_INPUTS, flags)
         .add_database(LMDB_DB_TXOS_HASH_TO_INDEX, flags)
+        .add_database(LMDB_DB_TXO_COMMITMENT_INDEX, flags)
         .add_database(LMDB_DB_KERNELS, flags)
         .add_database(LMDB_DB_KERNEL_EXCESS_INDEX, flags)
         .add_database(LMDB_DB_KERNEL_EXCESS_SIG_INDEX, flags)
@@ -1072,9 +1232,9 @@ pub fn create_lmdb_database<P: AsRef<Path>>(path: P, config: LMDBConfig) -> Resu
         .add_database(LMDB_DB_MONERO_SEED_HEIGHT, flags)
         .add_database(LMDB_DB_ORPHAN_CHAIN_TIPS, flags)
         .add_database(LMDB_DB_ORPHAN_PARENT_MAP_INDEX, flags | db::DUPSORT)
+        .add_database(LMDB_DB_ASSET_INDEX, flags | db::DUPSORT)
         .build()
-        .map_err(|err| ChainStorageError::CriticalError(format!("Could not create LMDB store:{}", err)))?;
-    LMDBDatabase::new(lmdb_store, file_lock)
+        .map_err(|err| ChainStorageError::CriticalError(format!("Could not create LMDB store:{}", err)))
 }
 
 pub fn create_recovery_lmdb_database<P: AsRef<Path>>(path: P) -> Result<(), ChainStorageError> {
@@ -1094,6 +1254,14 @@ pub fn create_recovery_lmdb_database<P: AsRef<Path>>(path: P) -> Result<(), Chai
     Ok(())
 }
 
+/// The key of the asset index, the asset id followed by the big-endian token id
+fn asset_index_key(asset_id: &AssetId, token_id: u64) -> Vec<u8> {
+    let mut key = Vec::with_capacity(40);
+    key.extend_from_slice(asset_id.as_bytes());
+    key.extend_from_slice(&token_id.to_be_bytes());
+    key
+}
+
 fn acquire_exclusive_file_lock(db_path: &PathBuf) -> Result<File, ChainStorageError> {
     let lock_file_path = db_path.join(".chain_storage_file.lock");
 
@@ -1616,6 +1784,31 @@ impl BlockchainBackend for LMDBDatabase {
         }
     }
 
+    fn asset_token_exists(&self, asset_id: &AssetId, token_id: u64) -> Result<bool, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        lmdb_exists(&txn, &self.asset_index, asset_index_key(asset_id, token_id).as_slice())
+    }
+
+    fn fetch_output_by_commitment(&self, commitment: &Commitment) -> Result<Option<UtxoMinedInfo>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        let (header_hash, mmr_position, mined_height) =
+            match lmdb_get::<_, (HashOutput, u32, u64)>(&txn, &self.txo_commitment_index, commitment.as_bytes())? {
+                Some(entry) => entry,
+                None => return Ok(None),
+            };
+        let key = format!("{}-{:010}", header_hash.to_hex(), mmr_position);
+        Ok(
+            lmdb_get::<_, TransactionOutputRowData>(&txn, &self.utxos_db, key.as_str())?
+                .and_then(|row| row.output)
+                .map(|output| UtxoMinedInfo {
+                    output,
+                    mmr_position,
+                    mined_height,
+                    header_hash,
+                }),
+        )
+    }
+
     fn fetch_outputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<PrunedOutput>, ChainStorageError> {
         let txn = self.read_transaction()?;
         Ok(
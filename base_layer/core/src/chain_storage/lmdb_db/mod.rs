@@ -28,7 +28,7 @@ use crate::transactions::{
     transaction::{TransactionInput, TransactionKernel, TransactionOutput},
     types::HashOutput,
 };
-pub use lmdb_db::{create_lmdb_database, create_recovery_lmdb_database, LMDBDatabase};
+pub use lmdb_db::{compact_lmdb_database, create_lmdb_database, create_recovery_lmdb_database, LMDBDatabase};
 use serde::{Deserialize, Serialize};
 
 pub const LMDB_DB_METADATA: &str = "metadata";
@@ -39,6 +39,7 @@ pub const LMDB_DB_BLOCK_HASHES: &str = "block_hashes";
 pub const LMDB_DB_UTXOS: &str = "utxos";
 pub const LMDB_DB_INPUTS: &str = "inputs";
 pub const LMDB_DB_TXOS_HASH_TO_INDEX: &str = "txos_hash_to_index";
+pub const LMDB_DB_TXO_COMMITMENT_INDEX: &str = "txo_commitment_index";
 pub const LMDB_DB_KERNELS: &str = "kernels";
 pub const LMDB_DB_KERNEL_EXCESS_INDEX: &str = "kernel_excess_index";
 pub const LMDB_DB_KERNEL_EXCESS_SIG_INDEX: &str = "kernel_excess_sig_index";
@@ -49,6 +50,7 @@ pub const LMDB_DB_MONERO_SEED_HEIGHT: &str = "monero_seed_height";
 pub const LMDB_DB_ORPHAN_HEADER_ACCUMULATED_DATA: &str = "orphan_accumulated_data";
 pub const LMDB_DB_ORPHAN_CHAIN_TIPS: &str = "orphan_chain_tips";
 pub const LMDB_DB_ORPHAN_PARENT_MAP_INDEX: &str = "orphan_parent_map_index";
+pub const LMDB_DB_ASSET_INDEX: &str = "asset_index";
 
 #[derive(Serialize, Deserialize, Debug)]
 pub(crate) struct TransactionOutputRowData {
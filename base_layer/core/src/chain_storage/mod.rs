@@ -76,8 +76,12 @@ pub use horizon_data::HorizonData;
 mod pruned_output;
 pub use pruned_output::PrunedOutput;
 
+mod utxo_mined_info;
+pub use utxo_mined_info::UtxoMinedInfo;
+
 mod lmdb_db;
 pub use lmdb_db::{
+    compact_lmdb_database,
     create_lmdb_database,
     create_recovery_lmdb_database,
     LMDBDatabase,
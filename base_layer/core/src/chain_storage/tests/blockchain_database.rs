@@ -321,3 +321,29 @@ mod fetch_block_hashes_from_header_tip {
         assert_eq!(&hashes[5], genesis.hash());
     }
 }
+
+mod fetch_utxo_by_commitment {
+    use super::*;
+    use crate::transactions::types::{CommitmentFactory, PrivateKey};
+    use rand::rngs::OsRng;
+    use tari_crypto::{commitment::HomomorphicCommitmentFactory, keys::SecretKey};
+
+    #[test]
+    fn it_returns_the_mined_output() {
+        let db = setup();
+        let genesis = db.fetch_block(0).unwrap();
+        let output = genesis.block().body.outputs()[0].clone();
+        add_many_chained_blocks(2, &db);
+        let info = db.fetch_utxo_by_commitment(output.commitment.clone()).unwrap().unwrap();
+        assert_eq!(info.output, output);
+        assert_eq!(info.mined_height, 0);
+        assert_eq!(&info.header_hash, genesis.hash());
+    }
+
+    #[test]
+    fn it_returns_none_for_an_unknown_commitment() {
+        let db = setup();
+        let commitment = CommitmentFactory::default().commit_value(&PrivateKey::random(&mut OsRng), 100);
+        assert!(db.fetch_utxo_by_commitment(commitment).unwrap().is_none());
+    }
+}
@@ -21,12 +21,19 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::{
-    consensus::{network::Network, KERNEL_WEIGHT, WEIGHT_PER_OUTPUT},
-    proof_of_work::{Difficulty, PowAlgorithm},
-    transactions::tari_amount::{uT, MicroTari, T},
+    consensus::network::Network,
+    proof_of_work::{Difficulty, DifficultyAdjustmentAlgorithm, PowAlgorithm},
+    transactions::{
+        tari_amount::{uT, MicroTari, T},
+        transaction::OutputFeaturesVersion,
+        weight::TransactionWeight,
+    },
 };
 use chrono::{DateTime, Duration, Utc};
-use std::{collections::HashMap, ops::Add};
+use std::{
+    collections::HashMap,
+    ops::{Add, RangeInclusive},
+};
 use tari_crypto::tari_utilities::epoch_time::EpochTime;
 
 /// This is the inner struct used to control all consensus values.
@@ -44,6 +51,8 @@ pub struct ConsensusConstants {
     /// When doing difficulty adjustments and FTL calculations this is the amount of blocks we look at
     /// https://github.com/zawy12/difficulty-algorithms/issues/14
     difficulty_block_window: u64,
+    /// The algorithm used to adjust the target difficulty of each PoW algorithm
+    difficulty_adjustment_algorithm: DifficultyAdjustmentAlgorithm,
     /// Maximum transaction weight used for the construction of new blocks.
     max_block_transaction_weight: u64,
     /// This is how many blocks we use to count towards the median timestamp to ensure the block chain moves forward
@@ -51,7 +60,7 @@ pub struct ConsensusConstants {
     /// This is the initial emission curve amount
     pub(in crate::consensus) emission_initial: MicroTari,
     /// This is the emission curve delay for the int
-    pub(in crate::consensus) emission_decay: &'static [u64],
+    pub(in crate::consensus) emission_decay: Vec<u64>,
     /// This is the emission curve tail amount
     pub(in crate::consensus) emission_tail: MicroTari,
     /// This is the maximum age a monero merge mined seed can be reused
@@ -60,7 +69,15 @@ pub struct ConsensusConstants {
     /// Ideally this should count up to 100. If this does not you will reduce your target time.
     proof_of_work: HashMap<PowAlgorithm, PowAlgorithmConstants>,
     /// This is to keep track of the value inside of the genesis block
-    faucet_value: MicroTari,
+    pub(in crate::consensus) faucet_value: MicroTari,
+    /// The versions of output features that new outputs may use
+    output_features_version_range: RangeInclusive<OutputFeaturesVersion>,
+    /// The maximum size, in bytes, of the extra data a miner may include in the coinbase output
+    coinbase_extra_max_size: usize,
+    /// The formula used to calculate the weight of transactions
+    transaction_weight: TransactionWeight,
+    /// Whether new outputs may carry Bulletproof+ range proofs. Bulletproofs are always permitted.
+    bulletproof_plus_permitted: bool,
 }
 
 /// This is just a convenience  wrapper to put all the info into a hashmap per diff algo
@@ -85,7 +102,7 @@ impl ConsensusConstants {
 
     /// This gets the emission curve values as (initial, decay, tail)
     pub fn emission_amounts(&self) -> (MicroTari, &[u64], MicroTari) {
-        (self.emission_initial, self.emission_decay, self.emission_tail)
+        (self.emission_initial, &self.emission_decay, self.emission_tail)
     }
 
     /// The min height maturity a coinbase utxo must have.
@@ -119,6 +136,17 @@ impl ConsensusConstants {
         self.difficulty_block_window
     }
 
+    /// The algorithm used to adjust the target difficulty of each PoW algorithm.
+    pub fn difficulty_adjustment_algorithm(&self) -> &DifficultyAdjustmentAlgorithm {
+        &self.difficulty_adjustment_algorithm
+    }
+
+    /// The amount of blocks of the given PoW algorithm that the difficulty adjustment looks at.
+    pub fn get_difficulty_block_window_for(&self, pow_algo: PowAlgorithm) -> u64 {
+        self.difficulty_adjustment_algorithm
+            .block_window(pow_algo, self.difficulty_block_window)
+    }
+
     /// Maximum transaction weight used for the construction of new blocks.
     pub fn get_max_block_transaction_weight(&self) -> u64 {
         self.max_block_transaction_weight
@@ -126,7 +154,9 @@ impl ConsensusConstants {
 
     /// Maximum transaction weight used for the construction of new blocks. It leaves place for 1 kernel and 1 output
     pub fn get_max_block_weight_excluding_coinbase(&self) -> u64 {
-        self.max_block_transaction_weight - WEIGHT_PER_OUTPUT - KERNEL_WEIGHT
+        self.max_block_transaction_weight -
+            self.transaction_weight.output_weight() -
+            self.transaction_weight.kernel_weight()
     }
 
     /// The amount of PoW algorithms used by the Tari chain.
@@ -177,6 +207,27 @@ impl ConsensusConstants {
         }
     }
 
+    /// The versions of output features that new outputs may use
+    pub fn output_features_version_range(&self) -> &RangeInclusive<OutputFeaturesVersion> {
+        &self.output_features_version_range
+    }
+
+    /// The maximum size, in bytes, of the extra data a miner may include in the coinbase output
+    pub fn coinbase_extra_max_size(&self) -> usize {
+        self.coinbase_extra_max_size
+    }
+
+    /// The formula used to calculate the weight, and so the fee, of transactions
+    pub fn transaction_weight(&self) -> &TransactionWeight {
+        &self.transaction_weight
+    }
+
+    /// Whether new outputs may carry Bulletproof+ range proofs. The transition to Bulletproofs+ is made by a set of
+    /// constants, effective from the transition height, that permits them.
+    pub fn is_bulletproof_plus_permitted(&self) -> bool {
+        self.bulletproof_plus_permitted
+    }
+
     // This is the maximum age a monero merge mined seed can be reused
     pub fn max_randomx_seed_height(&self) -> u64 {
         self.max_randomx_seed_height
@@ -203,14 +254,19 @@ impl ConsensusConstants {
             blockchain_version: 1,
             future_time_limit: 540,
             difficulty_block_window,
+            difficulty_adjustment_algorithm: DifficultyAdjustmentAlgorithm::Lwma,
             max_block_transaction_weight: 19500,
             median_timestamp_count: 11,
             emission_initial: 5_538_846_115 * uT,
-            emission_decay: &EMISSION_DECAY,
+            emission_decay: EMISSION_DECAY.to_vec(),
             emission_tail: 100.into(),
             max_randomx_seed_height: std::u64::MAX,
             proof_of_work: algos,
             faucet_value: (5000 * 4000) * T,
+            output_features_version_range: OutputFeaturesVersion::V0..=OutputFeaturesVersion::V5,
+            coinbase_extra_max_size: 64,
+            transaction_weight: TransactionWeight::v2(),
+            bulletproof_plus_permitted: false,
         }]
     }
 
@@ -236,14 +292,19 @@ impl ConsensusConstants {
             blockchain_version: 1,
             future_time_limit: 540,
             difficulty_block_window,
+            difficulty_adjustment_algorithm: DifficultyAdjustmentAlgorithm::Lwma,
             max_block_transaction_weight: 19500,
             median_timestamp_count: 11,
             emission_initial: 5_538_846_115 * uT,
-            emission_decay: &EMISSION_DECAY,
+            emission_decay: EMISSION_DECAY.to_vec(),
             emission_tail: 100.into(),
             max_randomx_seed_height: std::u64::MAX,
             proof_of_work: algos,
             faucet_value: (5000 * 4000) * T,
+            output_features_version_range: OutputFeaturesVersion::V0..=OutputFeaturesVersion::V0,
+            coinbase_extra_max_size: 64,
+            transaction_weight: TransactionWeight::v1(),
+            bulletproof_plus_permitted: false,
         }]
     }
 
@@ -296,14 +357,19 @@ impl ConsensusConstants {
                 blockchain_version: 1,
                 future_time_limit: 540,
                 difficulty_block_window: 90,
+                difficulty_adjustment_algorithm: DifficultyAdjustmentAlgorithm::Lwma,
                 max_block_transaction_weight: 19500,
                 median_timestamp_count: 11,
                 emission_initial: 5_538_846_115 * uT,
-                emission_decay: &EMISSION_DECAY,
+                emission_decay: EMISSION_DECAY.to_vec(),
                 emission_tail: 100.into(),
                 max_randomx_seed_height: std::u64::MAX,
                 proof_of_work: algos,
                 faucet_value: (5000 * 4000) * T,
+                output_features_version_range: OutputFeaturesVersion::V0..=OutputFeaturesVersion::V0,
+                coinbase_extra_max_size: 64,
+                transaction_weight: TransactionWeight::v1(),
+                bulletproof_plus_permitted: false,
             },
             ConsensusConstants {
                 effective_from_height: 1400,
@@ -311,14 +377,19 @@ impl ConsensusConstants {
                 blockchain_version: 1,
                 future_time_limit: 540,
                 difficulty_block_window: 90,
+                difficulty_adjustment_algorithm: DifficultyAdjustmentAlgorithm::Lwma,
                 max_block_transaction_weight: 19500,
                 median_timestamp_count: 11,
                 emission_initial: 5_538_846_115 * uT,
-                emission_decay: &EMISSION_DECAY,
+                emission_decay: EMISSION_DECAY.to_vec(),
                 emission_tail: 100.into(),
                 max_randomx_seed_height: std::u64::MAX,
                 proof_of_work: algos2,
                 faucet_value: (5000 * 4000) * T,
+                output_features_version_range: OutputFeaturesVersion::V0..=OutputFeaturesVersion::V0,
+                coinbase_extra_max_size: 64,
+                transaction_weight: TransactionWeight::v1(),
+                bulletproof_plus_permitted: false,
             },
         ]
     }
@@ -345,14 +416,19 @@ impl ConsensusConstants {
             blockchain_version: 1,
             future_time_limit: 540,
             difficulty_block_window,
+            difficulty_adjustment_algorithm: DifficultyAdjustmentAlgorithm::Lwma,
             max_block_transaction_weight: 19500,
             median_timestamp_count: 11,
             emission_initial: 10_000_000.into(),
-            emission_decay: &EMISSION_DECAY,
+            emission_decay: EMISSION_DECAY.to_vec(),
             emission_tail: 100.into(),
             max_randomx_seed_height: std::u64::MAX,
             proof_of_work: algos,
             faucet_value: MicroTari::from(0),
+            output_features_version_range: OutputFeaturesVersion::V0..=OutputFeaturesVersion::V5,
+            coinbase_extra_max_size: 64,
+            transaction_weight: TransactionWeight::v2(),
+            bulletproof_plus_permitted: false,
         }]
     }
 }
@@ -377,6 +453,11 @@ impl ConsensusConstantsBuilder {
         self
     }
 
+    pub fn with_difficulty_adjustment_algorithm(mut self, algorithm: DifficultyAdjustmentAlgorithm) -> Self {
+        self.consensus.difficulty_adjustment_algorithm = algorithm;
+        self
+    }
+
     pub fn with_coinbase_lockheight(mut self, height: u64) -> Self {
         self.consensus.coinbase_lock_height = height;
         self
@@ -397,15 +478,29 @@ impl ConsensusConstantsBuilder {
         self
     }
 
-    pub fn with_emission_amounts(
-        mut self,
-        intial_amount: MicroTari,
-        decay: &'static [u64],
-        tail_amount: MicroTari,
-    ) -> Self
-    {
+    pub fn with_output_features_version_range(mut self, range: RangeInclusive<OutputFeaturesVersion>) -> Self {
+        self.consensus.output_features_version_range = range;
+        self
+    }
+
+    pub fn with_coinbase_extra_max_size(mut self, max_size: usize) -> Self {
+        self.consensus.coinbase_extra_max_size = max_size;
+        self
+    }
+
+    pub fn with_transaction_weight(mut self, weight: TransactionWeight) -> Self {
+        self.consensus.transaction_weight = weight;
+        self
+    }
+
+    pub fn with_bulletproof_plus_permitted(mut self, permitted: bool) -> Self {
+        self.consensus.bulletproof_plus_permitted = permitted;
+        self
+    }
+
+    pub fn with_emission_amounts(mut self, intial_amount: MicroTari, decay: &[u64], tail_amount: MicroTari) -> Self {
         self.consensus.emission_initial = intial_amount;
-        self.consensus.emission_decay = decay;
+        self.consensus.emission_decay = decay.to_vec();
         self.consensus.emission_tail = tail_amount;
         self
     }
@@ -0,0 +1,148 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use super::{
+    read_bytes,
+    read_fixed,
+    read_version,
+    write_bytes,
+    write_fixed,
+    write_version,
+    ConsensusDecoding,
+    ConsensusDecodingError,
+    ConsensusEncoding,
+};
+use crate::{
+    blocks::BlockHeader,
+    proof_of_work::{PowAlgorithm, ProofOfWork},
+    tari_utilities::epoch_time::EpochTime,
+    transactions::types::BlindingFactor,
+};
+use std::{
+    convert::TryFrom,
+    io::{self, Read, Write},
+};
+use tari_common_types::types::BLOCK_HASH_LENGTH;
+
+/// The current encoding version of block headers
+pub const BLOCK_HEADER_ENCODING_VERSION: u8 = 0;
+
+/// The maximum size of the supplemental proof of work data, e.g. the Monero merge mining data
+const MAX_POW_DATA_BYTES: usize = 16 * 1024;
+
+impl ConsensusEncoding for ProofOfWork {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let written = (self.pow_algo as u8).consensus_encode(writer)?;
+        Ok(written + write_bytes(writer, &self.pow_data)?)
+    }
+}
+
+impl ConsensusDecoding for ProofOfWork {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, ConsensusDecodingError> {
+        let algo = u8::consensus_decode(reader)?;
+        let pow_algo =
+            PowAlgorithm::try_from(u64::from(algo)).map_err(|e| ConsensusDecodingError::invalid("pow algorithm", e))?;
+        Ok(ProofOfWork {
+            pow_algo,
+            pow_data: read_bytes(reader, "pow data", MAX_POW_DATA_BYTES)?,
+        })
+    }
+}
+
+impl ConsensusEncoding for BlockHeader {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let mut written = write_version(writer, BLOCK_HEADER_ENCODING_VERSION)?;
+        written += self.version.consensus_encode(writer)?;
+        written += self.height.consensus_encode(writer)?;
+        written += write_fixed(writer, &self.prev_hash, BLOCK_HASH_LENGTH)?;
+        written += self.timestamp.as_u64().consensus_encode(writer)?;
+        written += write_fixed(writer, &self.output_mr, BLOCK_HASH_LENGTH)?;
+        written += write_fixed(writer, &self.range_proof_mr, BLOCK_HASH_LENGTH)?;
+        written += self.output_mmr_size.consensus_encode(writer)?;
+        written += write_fixed(writer, &self.kernel_mr, BLOCK_HASH_LENGTH)?;
+        written += self.kernel_mmr_size.consensus_encode(writer)?;
+        written += self.total_kernel_offset.consensus_encode(writer)?;
+        written += self.nonce.consensus_encode(writer)?;
+        written += self.pow.consensus_encode(writer)?;
+        Ok(written)
+    }
+}
+
+impl ConsensusDecoding for BlockHeader {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, ConsensusDecodingError> {
+        read_version(reader, "block header", BLOCK_HEADER_ENCODING_VERSION)?;
+        Ok(BlockHeader {
+            version: u16::consensus_decode(reader)?,
+            height: u64::consensus_decode(reader)?,
+            prev_hash: read_fixed(reader, BLOCK_HASH_LENGTH)?,
+            timestamp: EpochTime::from(u64::consensus_decode(reader)?),
+            output_mr: read_fixed(reader, BLOCK_HASH_LENGTH)?,
+            range_proof_mr: read_fixed(reader, BLOCK_HASH_LENGTH)?,
+            output_mmr_size: u64::consensus_decode(reader)?,
+            kernel_mr: read_fixed(reader, BLOCK_HASH_LENGTH)?,
+            kernel_mmr_size: u64::consensus_decode(reader)?,
+            total_kernel_offset: BlindingFactor::consensus_decode(reader)?,
+            nonce: u64::consensus_decode(reader)?,
+            pow: ProofOfWork::consensus_decode(reader)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::consensus::{ConsensusManagerBuilder, Network};
+    use tari_crypto::tari_utilities::Hashable;
+
+    #[test]
+    fn header_round_trip() {
+        let genesis = ConsensusManagerBuilder::new(Network::LocalNet)
+            .build()
+            .get_genesis_block();
+        let header = genesis.header().clone();
+        let bytes = header.to_consensus_bytes();
+        assert_eq!(bytes[0], BLOCK_HEADER_ENCODING_VERSION);
+        let decoded = BlockHeader::from_consensus_bytes(&bytes).unwrap();
+        assert_eq!(decoded.hash(), header.hash());
+    }
+
+    #[test]
+    fn it_rejects_malformed_headers() {
+        let genesis = ConsensusManagerBuilder::new(Network::LocalNet)
+            .build()
+            .get_genesis_block();
+        let mut bytes = genesis.header().to_consensus_bytes();
+
+        // Truncated
+        let err = BlockHeader::from_consensus_bytes(&bytes[..bytes.len() - 1]).unwrap_err();
+        assert!(matches!(err, ConsensusDecodingError::Io(_)));
+
+        bytes.push(0);
+        let err = BlockHeader::from_consensus_bytes(&bytes).unwrap_err();
+        assert!(matches!(err, ConsensusDecodingError::TrailingBytes(1)));
+
+        bytes.pop();
+        bytes[0] = BLOCK_HEADER_ENCODING_VERSION + 1;
+        let err = BlockHeader::from_consensus_bytes(&bytes).unwrap_err();
+        assert!(matches!(err, ConsensusDecodingError::UnsupportedVersion { .. }));
+    }
+}
@@ -0,0 +1,363 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! The consensus encoding is the canonical binary representation of block headers, transactions and kernels.
+//!
+//! Every top-level structure starts with an encoding version byte. A decoder rejects versions that it does not know,
+//! so a protocol upgrade introduces a new version rather than silently changing the meaning of existing bytes, and
+//! nodes can keep accepting the old version for as long as the consensus rules allow.
+//!
+//! Fields that may be absent are encoded as a presence byte followed, if present, by the length-prefixed encoding of
+//! the field. Decoding is strict: presence bytes other than 0 and 1, non-minimal length prefixes, a length prefix that
+//! does not match the encoded field, oversized fields and trailing bytes are all rejected, so every value has exactly
+//! one valid encoding.
+//!
+//! The size of output metadata that transaction weights charge for is the size of the consensus encoding of the output
+//! features and covenant, see `Fee::output_metadata_size`.
+
+#[cfg(feature = "base_node")]
+mod block_header;
+mod transaction;
+
+pub use transaction::{TRANSACTION_ENCODING_VERSION, TRANSACTION_KERNEL_ENCODING_VERSION};
+
+#[cfg(feature = "base_node")]
+pub use block_header::BLOCK_HEADER_ENCODING_VERSION;
+
+use std::io::{self, Read, Write};
+use thiserror::Error;
+
+/// The maximum size of a single length-prefixed optional field
+pub const MAX_OPTIONAL_FIELD_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Error)]
+pub enum ConsensusDecodingError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Unsupported {entity} encoding version {version}")]
+    UnsupportedVersion { entity: &'static str, version: u8 },
+    #[error("Non-canonical encoding: {0}")]
+    NonCanonical(String),
+    #[error("{entity} is {size} bytes which exceeds the maximum of {max} bytes")]
+    TooLarge {
+        entity: &'static str,
+        size: usize,
+        max: usize,
+    },
+    #[error("Invalid {entity}: {reason}")]
+    InvalidValue { entity: &'static str, reason: String },
+    #[error("{0} trailing byte(s) after the encoded value")]
+    TrailingBytes(usize),
+}
+
+impl ConsensusDecodingError {
+    pub(crate) fn invalid<E: std::fmt::Display>(entity: &'static str, err: E) -> Self {
+        ConsensusDecodingError::InvalidValue {
+            entity,
+            reason: err.to_string(),
+        }
+    }
+}
+
+pub trait ConsensusEncoding {
+    /// Writes the consensus encoding of this value and returns the number of bytes written
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, io::Error>;
+
+    /// Returns the consensus encoding of this value
+    fn to_consensus_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        self.consensus_encode(&mut buf)
+            .expect("writing to a Vec<u8> does not fail");
+        buf
+    }
+}
+
+pub trait ConsensusDecoding: Sized {
+    /// Reads a value from its consensus encoding
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, ConsensusDecodingError>;
+
+    /// Decodes a value that takes up all of `bytes`
+    fn from_consensus_bytes(bytes: &[u8]) -> Result<Self, ConsensusDecodingError> {
+        let mut reader = bytes;
+        let value = Self::consensus_decode(&mut reader)?;
+        if !reader.is_empty() {
+            return Err(ConsensusDecodingError::TrailingBytes(reader.len()));
+        }
+        Ok(value)
+    }
+}
+
+/// Writes the encoding version byte that starts every top-level structure
+pub(crate) fn write_version<W: Write>(writer: &mut W, version: u8) -> Result<usize, io::Error> {
+    version.consensus_encode(writer)
+}
+
+/// Reads the encoding version byte and rejects versions newer than `max_supported`
+pub(crate) fn read_version<R: Read>(
+    reader: &mut R,
+    entity: &'static str,
+    max_supported: u8,
+) -> Result<u8, ConsensusDecodingError>
+{
+    let version = u8::consensus_decode(reader)?;
+    if version > max_supported {
+        return Err(ConsensusDecodingError::UnsupportedVersion { entity, version });
+    }
+    Ok(version)
+}
+
+/// Writes `n` as a LEB128 variable length integer
+pub(crate) fn write_varint<W: Write>(writer: &mut W, mut n: u64) -> Result<usize, io::Error> {
+    let mut buf = [0u8; 10];
+    let mut len = 0;
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            buf[len] = byte;
+            len += 1;
+            break;
+        }
+        buf[len] = byte | 0x80;
+        len += 1;
+    }
+    writer.write_all(&buf[..len])?;
+    Ok(len)
+}
+
+/// Reads a LEB128 variable length integer. Encodings that are longer than necessary or overflow a u64 are rejected.
+pub(crate) fn read_varint<R: Read>(reader: &mut R) -> Result<u64, ConsensusDecodingError> {
+    let mut n = 0u64;
+    for i in 0..10 {
+        let byte = u8::consensus_decode(reader)?;
+        if i == 9 && byte > 0x01 {
+            return Err(ConsensusDecodingError::NonCanonical(
+                "varint overflows a u64".to_string(),
+            ));
+        }
+        n |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            if i > 0 && byte == 0 {
+                return Err(ConsensusDecodingError::NonCanonical(
+                    "varint has trailing zero bytes".to_string(),
+                ));
+            }
+            return Ok(n);
+        }
+    }
+    Err(ConsensusDecodingError::NonCanonical("varint is too long".to_string()))
+}
+
+/// Writes a length-prefixed byte string
+pub(crate) fn write_bytes<W: Write>(writer: &mut W, bytes: &[u8]) -> Result<usize, io::Error> {
+    let len = write_varint(writer, bytes.len() as u64)?;
+    writer.write_all(bytes)?;
+    Ok(len + bytes.len())
+}
+
+/// Reads a length-prefixed byte string of at most `max` bytes
+pub(crate) fn read_bytes<R: Read>(
+    reader: &mut R,
+    entity: &'static str,
+    max: usize,
+) -> Result<Vec<u8>, ConsensusDecodingError>
+{
+    let size = read_length(reader, entity, max)?;
+    let mut buf = vec![0u8; size];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+/// Writes a count-prefixed list
+pub(crate) fn write_vec<W: Write, T: ConsensusEncoding>(writer: &mut W, items: &[T]) -> Result<usize, io::Error> {
+    let mut written = write_varint(writer, items.len() as u64)?;
+    for item in items {
+        written += item.consensus_encode(writer)?;
+    }
+    Ok(written)
+}
+
+/// Reads a count-prefixed list of at most `max` items
+pub(crate) fn read_vec<R: Read, T: ConsensusDecoding>(
+    reader: &mut R,
+    entity: &'static str,
+    max: usize,
+) -> Result<Vec<T>, ConsensusDecodingError>
+{
+    let count = read_length(reader, entity, max)?;
+    let mut items = Vec::with_capacity(count.min(1024));
+    for _ in 0..count {
+        items.push(T::consensus_decode(reader)?);
+    }
+    Ok(items)
+}
+
+fn read_length<R: Read>(reader: &mut R, entity: &'static str, max: usize) -> Result<usize, ConsensusDecodingError> {
+    let size = read_varint(reader)?;
+    if size > max as u64 {
+        return Err(ConsensusDecodingError::TooLarge {
+            entity,
+            size: size as usize,
+            max,
+        });
+    }
+    Ok(size as usize)
+}
+
+/// Writes a fixed size field, such as a hash, which must be exactly `size` bytes long
+pub(crate) fn write_fixed<W: Write>(writer: &mut W, bytes: &[u8], size: usize) -> Result<usize, io::Error> {
+    if bytes.len() != size {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("expected a {} byte field but got {} bytes", size, bytes.len()),
+        ));
+    }
+    writer.write_all(bytes)?;
+    Ok(size)
+}
+
+pub(crate) fn read_fixed<R: Read>(reader: &mut R, size: usize) -> Result<Vec<u8>, ConsensusDecodingError> {
+    let mut buf = vec![0u8; size];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+impl ConsensusEncoding for u8 {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        writer.write_all(&[*self])?;
+        Ok(1)
+    }
+}
+
+impl ConsensusDecoding for u8 {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, ConsensusDecodingError> {
+        let mut buf = [0u8; 1];
+        reader.read_exact(&mut buf)?;
+        Ok(buf[0])
+    }
+}
+
+macro_rules! impl_consensus_encoding_for_int {
+    ($($t:ty),*) => {
+        $(
+            impl ConsensusEncoding for $t {
+                fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, io::Error> {
+                    let bytes = self.to_le_bytes();
+                    writer.write_all(&bytes)?;
+                    Ok(bytes.len())
+                }
+            }
+
+            impl ConsensusDecoding for $t {
+                fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, ConsensusDecodingError> {
+                    let mut buf = [0u8; std::mem::size_of::<$t>()];
+                    reader.read_exact(&mut buf)?;
+                    Ok(<$t>::from_le_bytes(buf))
+                }
+            }
+        )*
+    };
+}
+
+impl_consensus_encoding_for_int!(u16, u64);
+
+/// An optional field is a presence byte that is followed by the length-prefixed encoding of the value, if there is one
+impl<T: ConsensusEncoding> ConsensusEncoding for Option<T> {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        match self {
+            Some(value) => {
+                let written = 1u8.consensus_encode(writer)?;
+                Ok(written + write_bytes(writer, &value.to_consensus_bytes())?)
+            },
+            None => 0u8.consensus_encode(writer),
+        }
+    }
+}
+
+impl<T: ConsensusDecoding> ConsensusDecoding for Option<T> {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, ConsensusDecodingError> {
+        match u8::consensus_decode(reader)? {
+            0 => Ok(None),
+            1 => {
+                let bytes = read_bytes(reader, "optional field", MAX_OPTIONAL_FIELD_BYTES)?;
+                match T::from_consensus_bytes(&bytes) {
+                    Ok(value) => Ok(Some(value)),
+                    Err(ConsensusDecodingError::TrailingBytes(n)) => Err(ConsensusDecodingError::NonCanonical(
+                        format!("optional field is {} byte(s) longer than its value", n),
+                    )),
+                    Err(err) => Err(err),
+                }
+            },
+            b => Err(ConsensusDecodingError::NonCanonical(format!(
+                "invalid presence byte {} for an optional field",
+                b
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn varint_round_trip() {
+        for n in &[0, 1, 0x7f, 0x80, 0x3fff, 0x4000, u64::from(u32::MAX), u64::MAX] {
+            let mut buf = Vec::new();
+            let written = write_varint(&mut buf, *n).unwrap();
+            assert_eq!(written, buf.len());
+            assert_eq!(read_varint(&mut buf.as_slice()).unwrap(), *n);
+        }
+    }
+
+    #[test]
+    fn it_rejects_non_minimal_varints() {
+        let err = read_varint(&mut &[0x81u8, 0x00][..]).unwrap_err();
+        assert!(matches!(err, ConsensusDecodingError::NonCanonical(_)));
+        let err = read_varint(&mut &[0xffu8; 10][..]).unwrap_err();
+        assert!(matches!(err, ConsensusDecodingError::NonCanonical(_)));
+    }
+
+    #[test]
+    fn optional_fields() {
+        let value = Some(0x1234u16);
+        let bytes = value.to_consensus_bytes();
+        assert_eq!(bytes, vec![1, 2, 0x34, 0x12]);
+        assert_eq!(Option::<u16>::from_consensus_bytes(&bytes).unwrap(), value);
+        assert_eq!(
+            Option::<u16>::from_consensus_bytes(&None::<u16>.to_consensus_bytes()).unwrap(),
+            None
+        );
+
+        // The length prefix must match the value
+        let err = Option::<u16>::from_consensus_bytes(&[1, 3, 0x34, 0x12, 0]).unwrap_err();
+        assert!(matches!(err, ConsensusDecodingError::NonCanonical(_)));
+        let err = Option::<u16>::from_consensus_bytes(&[2]).unwrap_err();
+        assert!(matches!(err, ConsensusDecodingError::NonCanonical(_)));
+    }
+
+    #[test]
+    fn it_rejects_trailing_bytes() {
+        let err = u16::from_consensus_bytes(&[1, 2, 3]).unwrap_err();
+        assert!(matches!(err, ConsensusDecodingError::TrailingBytes(1)));
+    }
+}
@@ -0,0 +1,705 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use super::{
+    read_bytes,
+    read_fixed,
+    read_vec,
+    read_version,
+    write_bytes,
+    write_fixed,
+    write_vec,
+    write_version,
+    ConsensusDecoding,
+    ConsensusDecodingError,
+    ConsensusEncoding,
+};
+use crate::transactions::{
+    aggregated_body::AggregateBody,
+    asset::{AssetOutputFeatures, MAX_ASSET_NAME_BYTES},
+    covenant::{Covenant, MAX_COVENANT_BYTES},
+    htlc::{HashTimeLock, HtlcWitness, HTLC_PREIMAGE_LENGTH},
+    multisig::{MultisigLock, MultisigWitness, MAX_MULTISIG_PARTICIPANTS},
+    script::{ScriptWitness, TariScript, MAX_SCRIPT_BYTES, MAX_STACK_SIZE},
+    tari_amount::MicroTari,
+    transaction::{
+        KernelFeatures,
+        OutputFeatures,
+        OutputFeaturesVersion,
+        OutputFlags,
+        RangeProofType,
+        Transaction,
+        TransactionInput,
+        TransactionKernel,
+        TransactionOutput,
+        MAX_COINBASE_EXTRA_BYTES,
+    },
+    types::{Commitment, PrivateKey, PublicKey, RangeProof, Signature},
+};
+use std::{
+    convert::TryFrom,
+    io::{self, Read, Write},
+};
+use tari_crypto::tari_utilities::ByteArray;
+
+/// The current encoding version of transactions
+pub const TRANSACTION_ENCODING_VERSION: u8 = 0;
+/// The current encoding version of transaction kernels
+pub const TRANSACTION_KERNEL_ENCODING_VERSION: u8 = 0;
+
+/// The maximum size of an encoded range proof
+const MAX_RANGE_PROOF_BYTES: usize = 1024;
+/// The maximum number of inputs, outputs or kernels in a transaction body
+const MAX_BODY_ITEMS: usize = 100_000;
+const KEY_SIZE: usize = 32;
+/// The maximum size of an encoded script witness stack, every item being at most a tag and a signature
+const MAX_SCRIPT_STACK_BYTES: usize = MAX_STACK_SIZE * (1 + 2 * KEY_SIZE);
+
+macro_rules! impl_consensus_encoding_for_key {
+    ($($t:ty),*) => {
+        $(
+            impl ConsensusEncoding for $t {
+                fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, io::Error> {
+                    write_fixed(writer, self.as_bytes(), KEY_SIZE)
+                }
+            }
+
+            impl ConsensusDecoding for $t {
+                fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, ConsensusDecodingError> {
+                    let bytes = read_fixed(reader, KEY_SIZE)?;
+                    <$t>::from_bytes(&bytes).map_err(|e| ConsensusDecodingError::invalid(stringify!($t), e))
+                }
+            }
+        )*
+    };
+}
+
+impl_consensus_encoding_for_key!(PublicKey, PrivateKey, Commitment);
+
+impl ConsensusEncoding for Signature {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let written = self.get_public_nonce().consensus_encode(writer)?;
+        Ok(written + self.get_signature().consensus_encode(writer)?)
+    }
+}
+
+impl ConsensusDecoding for Signature {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, ConsensusDecodingError> {
+        let public_nonce = PublicKey::consensus_decode(reader)?;
+        let signature = PrivateKey::consensus_decode(reader)?;
+        Ok(Signature::new(public_nonce, signature))
+    }
+}
+
+impl ConsensusEncoding for MicroTari {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        self.0.consensus_encode(writer)
+    }
+}
+
+impl ConsensusDecoding for MicroTari {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, ConsensusDecodingError> {
+        Ok(MicroTari(u64::consensus_decode(reader)?))
+    }
+}
+
+impl ConsensusEncoding for AssetOutputFeatures {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let mut written = self.issuer.consensus_encode(writer)?;
+        written += write_bytes(writer, &self.name)?;
+        written += self.token_id.consensus_encode(writer)?;
+        written += self.issuer_sig.consensus_encode(writer)?;
+        Ok(written)
+    }
+}
+
+impl ConsensusDecoding for AssetOutputFeatures {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, ConsensusDecodingError> {
+        Ok(AssetOutputFeatures {
+            issuer: PublicKey::consensus_decode(reader)?,
+            name: read_bytes(reader, "asset name", MAX_ASSET_NAME_BYTES)?,
+            token_id: u64::consensus_decode(reader)?,
+            issuer_sig: Option::<Signature>::consensus_decode(reader)?,
+        })
+    }
+}
+
+impl ConsensusEncoding for HashTimeLock {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let mut written = write_fixed(writer, &self.hash_lock, 32)?;
+        written += self.claim_public_key.consensus_encode(writer)?;
+        written += self.refund_public_key.consensus_encode(writer)?;
+        written += self.refund_height.consensus_encode(writer)?;
+        Ok(written)
+    }
+}
+
+impl ConsensusDecoding for HashTimeLock {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, ConsensusDecodingError> {
+        let mut hash_lock = [0u8; 32];
+        hash_lock.copy_from_slice(&read_fixed(reader, 32)?);
+        Ok(HashTimeLock {
+            hash_lock,
+            claim_public_key: PublicKey::consensus_decode(reader)?,
+            refund_public_key: PublicKey::consensus_decode(reader)?,
+            refund_height: u64::consensus_decode(reader)?,
+        })
+    }
+}
+
+const HTLC_WITNESS_CLAIM: u8 = 0;
+const HTLC_WITNESS_REFUND: u8 = 1;
+
+impl ConsensusEncoding for HtlcWitness {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        match self {
+            HtlcWitness::Claim {
+                preimage,
+                kernel_excess,
+                signature,
+            } => {
+                let mut written = HTLC_WITNESS_CLAIM.consensus_encode(writer)?;
+                written += write_bytes(writer, preimage)?;
+                written += kernel_excess.consensus_encode(writer)?;
+                written += signature.consensus_encode(writer)?;
+                Ok(written)
+            },
+            HtlcWitness::Refund {
+                kernel_excess,
+                signature,
+            } => {
+                let mut written = HTLC_WITNESS_REFUND.consensus_encode(writer)?;
+                written += kernel_excess.consensus_encode(writer)?;
+                written += signature.consensus_encode(writer)?;
+                Ok(written)
+            },
+        }
+    }
+}
+
+impl ConsensusDecoding for HtlcWitness {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, ConsensusDecodingError> {
+        match u8::consensus_decode(reader)? {
+            HTLC_WITNESS_CLAIM => Ok(HtlcWitness::Claim {
+                preimage: read_bytes(reader, "HTLC preimage", HTLC_PREIMAGE_LENGTH)?,
+                kernel_excess: Commitment::consensus_decode(reader)?,
+                signature: Signature::consensus_decode(reader)?,
+            }),
+            HTLC_WITNESS_REFUND => Ok(HtlcWitness::Refund {
+                kernel_excess: Commitment::consensus_decode(reader)?,
+                signature: Signature::consensus_decode(reader)?,
+            }),
+            tag => Err(ConsensusDecodingError::invalid(
+                "HTLC witness",
+                format!("unknown spend path {}", tag),
+            )),
+        }
+    }
+}
+
+impl ConsensusEncoding for MultisigLock {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let mut written = self.threshold.consensus_encode(writer)?;
+        written += write_vec(writer, &self.public_keys)?;
+        Ok(written)
+    }
+}
+
+impl ConsensusDecoding for MultisigLock {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, ConsensusDecodingError> {
+        let lock = MultisigLock {
+            threshold: u8::consensus_decode(reader)?,
+            public_keys: read_vec(reader, "multisig participants", MAX_MULTISIG_PARTICIPANTS)?,
+        };
+        lock.validate()
+            .map_err(|err| ConsensusDecodingError::invalid("multisig lock", err))?;
+        Ok(lock)
+    }
+}
+
+impl ConsensusEncoding for MultisigWitness {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let mut written = write_bytes(writer, &self.signers)?;
+        written += self.kernel_excess.consensus_encode(writer)?;
+        written += self.signature.consensus_encode(writer)?;
+        Ok(written)
+    }
+}
+
+impl ConsensusDecoding for MultisigWitness {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, ConsensusDecodingError> {
+        Ok(MultisigWitness {
+            signers: read_bytes(reader, "multisig signers", MAX_MULTISIG_PARTICIPANTS)?,
+            kernel_excess: Commitment::consensus_decode(reader)?,
+            signature: Signature::consensus_decode(reader)?,
+        })
+    }
+}
+
+impl ConsensusEncoding for TariScript {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        write_bytes(writer, &self.to_bytes())
+    }
+}
+
+impl ConsensusDecoding for TariScript {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, ConsensusDecodingError> {
+        let bytes = read_bytes(reader, "script", MAX_SCRIPT_BYTES)?;
+        let script = TariScript::from_bytes(&bytes).map_err(|err| ConsensusDecodingError::invalid("script", err))?;
+        script
+            .validate()
+            .map_err(|err| ConsensusDecodingError::invalid("script", err))?;
+        Ok(script)
+    }
+}
+
+impl ConsensusEncoding for ScriptWitness {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let mut written = self.kernel_excess.consensus_encode(writer)?;
+        written += write_bytes(writer, &self.stack_to_bytes())?;
+        Ok(written)
+    }
+}
+
+impl ConsensusDecoding for ScriptWitness {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, ConsensusDecodingError> {
+        let kernel_excess = Commitment::consensus_decode(reader)?;
+        let bytes = read_bytes(reader, "script witness stack", MAX_SCRIPT_STACK_BYTES)?;
+        let stack = ScriptWitness::stack_from_bytes(&bytes)
+            .map_err(|err| ConsensusDecodingError::invalid("script witness stack", err))?;
+        Ok(ScriptWitness { kernel_excess, stack })
+    }
+}
+
+impl ConsensusEncoding for OutputFeatures {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let mut written = write_version(writer, self.version.as_u8())?;
+        written += self.flags.bits().consensus_encode(writer)?;
+        written += self.maturity.consensus_encode(writer)?;
+        written += self.relative_maturity.consensus_encode(writer)?;
+        written += self.asset.consensus_encode(writer)?;
+        // V0 features have no coinbase extra. Outputs that set one anyway are rejected by validation.
+        if self.version >= OutputFeaturesVersion::V1 {
+            written += write_bytes(writer, &self.coinbase_extra)?;
+        }
+        // Likewise, only V2 features and later have a hash time lock
+        if self.version >= OutputFeaturesVersion::V2 {
+            written += self.htlc.consensus_encode(writer)?;
+        }
+        if self.version >= OutputFeaturesVersion::V3 {
+            written += self.multisig.consensus_encode(writer)?;
+        }
+        if self.version >= OutputFeaturesVersion::V4 {
+            written += self.script.consensus_encode(writer)?;
+        }
+        if self.version >= OutputFeaturesVersion::V5 {
+            written += self.range_proof_type.as_u8().consensus_encode(writer)?;
+        }
+        Ok(written)
+    }
+}
+
+impl ConsensusDecoding for OutputFeatures {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, ConsensusDecodingError> {
+        let version = OutputFeaturesVersion::from(u8::consensus_decode(reader)?);
+        // Unlike the protobuf encoding, the fields of an unknown version can not be skipped
+        if !version.is_known() {
+            return Err(ConsensusDecodingError::UnsupportedVersion {
+                entity: "output features",
+                version: version.as_u8(),
+            });
+        }
+        let bits = u8::consensus_decode(reader)?;
+        let flags = OutputFlags::from_bits(bits)
+            .ok_or_else(|| ConsensusDecodingError::invalid("output flags", format!("unknown flags {:#010b}", bits)))?;
+        let maturity = u64::consensus_decode(reader)?;
+        let relative_maturity = u64::consensus_decode(reader)?;
+        let asset = Option::<AssetOutputFeatures>::consensus_decode(reader)?;
+        let coinbase_extra = if version >= OutputFeaturesVersion::V1 {
+            read_bytes(reader, "coinbase extra", MAX_COINBASE_EXTRA_BYTES)?
+        } else {
+            Vec::new()
+        };
+        let htlc = if version >= OutputFeaturesVersion::V2 {
+            Option::<HashTimeLock>::consensus_decode(reader)?
+        } else {
+            None
+        };
+        let multisig = if version >= OutputFeaturesVersion::V3 {
+            Option::<MultisigLock>::consensus_decode(reader)?
+        } else {
+            None
+        };
+        let script = if version >= OutputFeaturesVersion::V4 {
+            Option::<TariScript>::consensus_decode(reader)?
+        } else {
+            None
+        };
+        let range_proof_type = if version >= OutputFeaturesVersion::V5 {
+            let value = u8::consensus_decode(reader)?;
+            RangeProofType::try_from(value).map_err(|err| ConsensusDecodingError::invalid("range proof type", err))?
+        } else {
+            RangeProofType::BulletProof
+        };
+        Ok(OutputFeatures {
+            flags,
+            maturity,
+            relative_maturity,
+            asset,
+            version,
+            coinbase_extra,
+            htlc,
+            multisig,
+            script,
+            range_proof_type,
+        })
+    }
+}
+
+impl ConsensusEncoding for Covenant {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        write_bytes(writer, &self.to_bytes())
+    }
+}
+
+impl ConsensusDecoding for Covenant {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, ConsensusDecodingError> {
+        let bytes = read_bytes(reader, "covenant", MAX_COVENANT_BYTES)?;
+        Covenant::from_bytes(&bytes).map_err(|e| ConsensusDecodingError::invalid("covenant", e))
+    }
+}
+
+impl ConsensusEncoding for TransactionInput {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let mut written = self.features.consensus_encode(writer)?;
+        written += self.commitment.consensus_encode(writer)?;
+        written += self.covenant.consensus_encode(writer)?;
+        // Only inputs that spend hash time-locked outputs have a witness, which keeps the encoding of all other inputs
+        // unchanged. Inputs that carry a witness anyway are rejected by validation.
+        if self.features.is_hash_time_locked() {
+            written += self.htlc_witness.consensus_encode(writer)?;
+        }
+        // Likewise for inputs that spend multisig locked outputs
+        if self.features.is_multisig_locked() {
+            written += self.multisig_witness.consensus_encode(writer)?;
+        }
+        // And for inputs that spend script locked outputs
+        if self.features.is_script_locked() {
+            written += self.script_witness.consensus_encode(writer)?;
+        }
+        Ok(written)
+    }
+}
+
+impl ConsensusDecoding for TransactionInput {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, ConsensusDecodingError> {
+        let features = OutputFeatures::consensus_decode(reader)?;
+        let commitment = Commitment::consensus_decode(reader)?;
+        let covenant = Covenant::consensus_decode(reader)?;
+        let htlc_witness = if features.is_hash_time_locked() {
+            Option::<HtlcWitness>::consensus_decode(reader)?
+        } else {
+            None
+        };
+        let multisig_witness = if features.is_multisig_locked() {
+            Option::<MultisigWitness>::consensus_decode(reader)?
+        } else {
+            None
+        };
+        let script_witness = if features.is_script_locked() {
+            Option::<ScriptWitness>::consensus_decode(reader)?
+        } else {
+            None
+        };
+        Ok(TransactionInput {
+            features,
+            commitment,
+            covenant,
+            htlc_witness,
+            multisig_witness,
+            script_witness,
+        })
+    }
+}
+
+impl ConsensusEncoding for TransactionOutput {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let mut written = self.features.consensus_encode(writer)?;
+        written += self.commitment.consensus_encode(writer)?;
+        written += self.covenant.consensus_encode(writer)?;
+        written += write_bytes(writer, self.proof.as_bytes())?;
+        Ok(written)
+    }
+}
+
+impl ConsensusDecoding for TransactionOutput {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, ConsensusDecodingError> {
+        let features = OutputFeatures::consensus_decode(reader)?;
+        let commitment = Commitment::consensus_decode(reader)?;
+        let covenant = Covenant::consensus_decode(reader)?;
+        let proof = read_bytes(reader, "range proof", MAX_RANGE_PROOF_BYTES)?;
+        Ok(TransactionOutput {
+            features,
+            commitment,
+            covenant,
+            proof: RangeProof::from(proof),
+        })
+    }
+}
+
+/// Kernels carry the encoding version so that new kernel types can be introduced without changing transactions. The
+/// burn amount is an optional field that is only present on kernels that burn a non-zero amount.
+impl ConsensusEncoding for TransactionKernel {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let mut written = write_version(writer, TRANSACTION_KERNEL_ENCODING_VERSION)?;
+        written += self.features.bits().consensus_encode(writer)?;
+        written += self.fee.consensus_encode(writer)?;
+        written += self.lock_height.consensus_encode(writer)?;
+        let burn_amount = Some(self.burn_amount).filter(|amount| *amount > MicroTari(0));
+        written += burn_amount.consensus_encode(writer)?;
+        written += self.excess.consensus_encode(writer)?;
+        written += self.excess_sig.consensus_encode(writer)?;
+        Ok(written)
+    }
+}
+
+impl ConsensusDecoding for TransactionKernel {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, ConsensusDecodingError> {
+        read_version(reader, "transaction kernel", TRANSACTION_KERNEL_ENCODING_VERSION)?;
+        let bits = u8::consensus_decode(reader)?;
+        let features = KernelFeatures::from_bits(bits).ok_or_else(|| {
+            ConsensusDecodingError::invalid("kernel features", format!("unknown features {:#010b}", bits))
+        })?;
+        let fee = MicroTari::consensus_decode(reader)?;
+        let lock_height = u64::consensus_decode(reader)?;
+        let burn_amount = match Option::<MicroTari>::consensus_decode(reader)? {
+            Some(MicroTari(0)) => {
+                return Err(ConsensusDecodingError::NonCanonical(
+                    "a zero burn amount must be omitted".to_string(),
+                ))
+            },
+            Some(amount) => amount,
+            None => MicroTari(0),
+        };
+        Ok(TransactionKernel {
+            features,
+            fee,
+            lock_height,
+            burn_amount,
+            excess: Commitment::consensus_decode(reader)?,
+            excess_sig: Signature::consensus_decode(reader)?,
+        })
+    }
+}
+
+impl ConsensusEncoding for Transaction {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let mut written = write_version(writer, TRANSACTION_ENCODING_VERSION)?;
+        written += self.offset.consensus_encode(writer)?;
+        written += write_vec(writer, self.body.inputs())?;
+        written += write_vec(writer, self.body.outputs())?;
+        written += write_vec(writer, self.body.kernels())?;
+        Ok(written)
+    }
+}
+
+/// The inputs, outputs and kernels of a transaction must be sorted and unique, this is the only order in which they
+/// can be encoded
+impl ConsensusDecoding for Transaction {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, ConsensusDecodingError> {
+        read_version(reader, "transaction", TRANSACTION_ENCODING_VERSION)?;
+        let offset = PrivateKey::consensus_decode(reader)?;
+        let inputs: Vec<TransactionInput> = read_vec(reader, "transaction inputs", MAX_BODY_ITEMS)?;
+        check_sorted(&inputs, "inputs")?;
+        let outputs: Vec<TransactionOutput> = read_vec(reader, "transaction outputs", MAX_BODY_ITEMS)?;
+        check_sorted(&outputs, "outputs")?;
+        let kernels: Vec<TransactionKernel> = read_vec(reader, "transaction kernels", MAX_BODY_ITEMS)?;
+        check_sorted(&kernels, "kernels")?;
+        let body = AggregateBody::new_sorted_unchecked(inputs, outputs, kernels);
+        Ok(Transaction { offset, body })
+    }
+}
+
+fn check_sorted<T: Ord>(items: &[T], entity: &str) -> Result<(), ConsensusDecodingError> {
+    if items.windows(2).all(|pair| pair[0] < pair[1]) {
+        Ok(())
+    } else {
+        Err(ConsensusDecodingError::NonCanonical(format!(
+            "transaction {} are not sorted and unique",
+            entity
+        )))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::transactions::{
+        helpers::{create_multisig_witness, create_test_kernel, create_test_multisig_lock, create_tx},
+        htlc::{generate_preimage, hash_preimage},
+        script::{Opcode, StackItem},
+        tari_amount::T,
+        types::CryptoFactories,
+    };
+    use rand::rngs::OsRng;
+    use tari_crypto::keys::{PublicKey as PublicKeyTrait, SecretKey};
+
+    #[test]
+    fn transaction_round_trip() {
+        let factories = CryptoFactories::default();
+        let (tx, _, _) = create_tx(5000.into(), 15.into(), 1, 2, 1, 3);
+        let bytes = tx.to_consensus_bytes();
+        assert_eq!(bytes[0], TRANSACTION_ENCODING_VERSION);
+        let decoded = Transaction::from_consensus_bytes(&bytes).unwrap();
+        assert_eq!(decoded, tx);
+        assert!(decoded.body.is_sorted());
+        assert!(decoded.validate_internal_consistency(&factories, None).is_ok());
+    }
+
+    #[test]
+    fn it_rejects_unknown_versions() {
+        let (tx, _, _) = create_tx(5000.into(), 15.into(), 1, 2, 1, 3);
+        let mut bytes = tx.to_consensus_bytes();
+        bytes[0] = TRANSACTION_ENCODING_VERSION + 1;
+        let err = Transaction::from_consensus_bytes(&bytes).unwrap_err();
+        assert!(matches!(err, ConsensusDecodingError::UnsupportedVersion { .. }));
+    }
+
+    #[test]
+    fn coinbase_extra_round_trip() {
+        let features = OutputFeatures::create_coinbase_with_extra(10, b"pool".to_vec());
+        let bytes = features.to_consensus_bytes();
+        assert_eq!(OutputFeatures::from_consensus_bytes(&bytes).unwrap(), features);
+
+        // The extra is only part of V1 features
+        let v0 = OutputFeatures::create_coinbase(10);
+        assert_eq!(bytes.len(), v0.to_consensus_bytes().len() + 1 + 4);
+    }
+
+    #[test]
+    fn htlc_round_trip() {
+        let (k, p) = PublicKey::random_keypair(&mut OsRng);
+        let preimage = generate_preimage().to_vec();
+        let htlc = HashTimeLock::new(hash_preimage(&preimage), p.clone(), p, 100);
+        let features = OutputFeatures::with_hash_time_lock(htlc.clone());
+        let commitment = Commitment::default();
+        let witness = htlc
+            .sign_claim(
+                k,
+                PrivateKey::random(&mut OsRng),
+                preimage,
+                &commitment,
+                Commitment::default(),
+            )
+            .unwrap();
+        let input = TransactionInput::new(features, commitment).with_htlc_witness(witness);
+        let bytes = input.to_consensus_bytes();
+        assert_eq!(TransactionInput::from_consensus_bytes(&bytes).unwrap(), input);
+
+        // Inputs that do not spend a hash time-locked output have no witness
+        let plain = TransactionInput::new(OutputFeatures::default(), Commitment::default());
+        let bytes = plain.to_consensus_bytes();
+        assert_eq!(TransactionInput::from_consensus_bytes(&bytes).unwrap(), plain);
+    }
+
+    #[test]
+    fn multisig_round_trip() {
+        let (lock, secrets) = create_test_multisig_lock();
+        let features = OutputFeatures::with_multisig_lock(lock.clone());
+        let bytes = features.to_consensus_bytes();
+        assert_eq!(OutputFeatures::from_consensus_bytes(&bytes).unwrap(), features);
+
+        let commitment = Commitment::default();
+        let witness = create_multisig_witness(&lock, &secrets, vec![1, 2], &commitment, Commitment::default());
+        let input = TransactionInput::new(features, commitment).with_multisig_witness(witness);
+        let bytes = input.to_consensus_bytes();
+        assert_eq!(TransactionInput::from_consensus_bytes(&bytes).unwrap(), input);
+
+        // Malformed locks are rejected
+        let mut unsorted = lock;
+        unsorted.public_keys.reverse();
+        let bytes = OutputFeatures::with_multisig_lock(unsorted).to_consensus_bytes();
+        assert!(OutputFeatures::from_consensus_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn script_round_trip() {
+        let script = TariScript::new(vec![Opcode::CheckHeightVerify(100), Opcode::PushInt(1)]);
+        let features = OutputFeatures::with_script(script);
+        let bytes = features.to_consensus_bytes();
+        assert_eq!(OutputFeatures::from_consensus_bytes(&bytes).unwrap(), features);
+
+        let commitment = Commitment::default();
+        let witness = ScriptWitness {
+            kernel_excess: Commitment::default(),
+            stack: vec![StackItem::Number(5)],
+        };
+        let input = TransactionInput::new(features, commitment).with_script_witness(witness);
+        let bytes = input.to_consensus_bytes();
+        assert_eq!(TransactionInput::from_consensus_bytes(&bytes).unwrap(), input);
+
+        // Empty scripts are rejected
+        let bytes = OutputFeatures::with_script(TariScript::new(vec![])).to_consensus_bytes();
+        assert!(OutputFeatures::from_consensus_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn range_proof_type_round_trip() {
+        let features = OutputFeatures::with_range_proof_type(RangeProofType::BulletProofPlus);
+        let bytes = features.to_consensus_bytes();
+        assert_eq!(OutputFeatures::from_consensus_bytes(&bytes).unwrap(), features);
+
+        // Unknown range proof types are rejected
+        let mut bytes = bytes;
+        *bytes.last_mut().unwrap() = 2;
+        assert!(OutputFeatures::from_consensus_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn kernel_burn_amount_is_optional() {
+        let kernel = create_test_kernel(5.into(), 0);
+        let bytes = kernel.to_consensus_bytes();
+        assert_eq!(TransactionKernel::from_consensus_bytes(&bytes).unwrap(), kernel);
+
+        let mut burn = kernel;
+        burn.features = KernelFeatures::BURN_KERNEL;
+        burn.burn_amount = 100 * T;
+        let burn_bytes = burn.to_consensus_bytes();
+        // A presence byte, a length byte and the amount
+        assert_eq!(burn_bytes.len(), bytes.len() + 1 + 8);
+        assert_eq!(TransactionKernel::from_consensus_bytes(&burn_bytes).unwrap(), burn);
+    }
+
+    #[test]
+    fn it_rejects_unsorted_bodies() {
+        let mut kernels = vec![create_test_kernel(5.into(), 0), create_test_kernel(6.into(), 0)];
+        kernels.sort();
+        kernels.reverse();
+        let mut bytes = vec![TRANSACTION_ENCODING_VERSION];
+        PrivateKey::default().consensus_encode(&mut bytes).unwrap();
+        write_vec::<_, TransactionInput>(&mut bytes, &[]).unwrap();
+        write_vec::<_, TransactionOutput>(&mut bytes, &[]).unwrap();
+        write_vec(&mut bytes, &kernels).unwrap();
+        let err = Transaction::from_consensus_bytes(&bytes).unwrap_err();
+        assert!(matches!(err, ConsensusDecodingError::NonCanonical(_)));
+    }
+}
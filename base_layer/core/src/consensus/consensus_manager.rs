@@ -105,7 +105,7 @@ impl ConsensusManager {
     /// height
     pub(crate) fn new_target_difficulty(&self, pow_algo: PowAlgorithm, height: u64) -> TargetDifficultyWindow {
         let constants = self.consensus_constants(height);
-        let block_window = constants.get_difficulty_block_window();
+        let block_window = constants.get_difficulty_block_window_for(pow_algo);
 
         TargetDifficultyWindow::new(
             usize::try_from(block_window).expect("difficulty block window exceeds usize::MAX"),
@@ -151,6 +151,8 @@ pub struct ConsensusManagerBuilder {
     network: Network,
     gen_block: Option<ChainBlock>,
     chain_strength_comparer: Option<Box<dyn ChainStrengthComparer + Send + Sync>>,
+    emission_amounts: Option<(MicroTari, Vec<u64>, MicroTari)>,
+    faucet_value: Option<MicroTari>,
 }
 
 impl ConsensusManagerBuilder {
@@ -161,6 +163,8 @@ impl ConsensusManagerBuilder {
             network,
             gen_block: None,
             chain_strength_comparer: None,
+            emission_amounts: None,
+            faucet_value: None,
         }
     }
 
@@ -176,6 +180,21 @@ impl ConsensusManagerBuilder {
         self
     }
 
+    /// Overrides the emission curve (initial reward, decay and tail emission) of every set of consensus constants. This
+    /// is intended for custom and internal test networks, since nodes with different emission curves reject each
+    /// other's blocks.
+    pub fn with_emission_amounts(mut self, initial: MicroTari, decay: &[u64], tail: MicroTari) -> Self {
+        self.emission_amounts = Some((initial, decay.to_vec(), tail));
+        self
+    }
+
+    /// Overrides the value of the genesis block outputs that are not its coinbase in every set of consensus constants.
+    /// This must be set to the faucet value of a custom genesis block for the chain balance to validate.
+    pub fn with_faucet_value(mut self, faucet_value: MicroTari) -> Self {
+        self.faucet_value = Some(faucet_value);
+        self
+    }
+
     pub fn on_ties(mut self, chain_strength_comparer: Box<dyn ChainStrengthComparer + Send + Sync>) -> Self {
         self.chain_strength_comparer = Some(chain_strength_comparer);
         self
@@ -187,6 +206,18 @@ impl ConsensusManagerBuilder {
             self.consensus_constants = self.network.create_consensus_constants();
         }
         // TODO: Check that constants is not empty
+        if let Some((initial, decay, tail)) = self.emission_amounts.take() {
+            for constants in &mut self.consensus_constants {
+                constants.emission_initial = initial;
+                constants.emission_decay = decay.clone();
+                constants.emission_tail = tail;
+            }
+        }
+        if let Some(faucet_value) = self.faucet_value {
+            for constants in &mut self.consensus_constants {
+                constants.faucet_value = faucet_value;
+            }
+        }
 
         let emission = EmissionSchedule::new(
             self.consensus_constants[0].emission_initial,
@@ -35,7 +35,7 @@ pub trait Emission {
 #[derive(Debug, Clone)]
 pub struct EmissionSchedule {
     initial: MicroTari,
-    decay: &'static [u64],
+    decay: Vec<u64>,
     tail: MicroTari,
 }
 
@@ -64,12 +64,16 @@ impl EmissionSchedule {
     ///
     /// The shift right operation will overflow if shifting more than 63 bits. `new` will panic if any of the decay
     /// values are greater than or equal to 64.
-    pub fn new(initial: MicroTari, decay: &'static [u64], tail: MicroTari) -> EmissionSchedule {
+    pub fn new(initial: MicroTari, decay: &[u64], tail: MicroTari) -> EmissionSchedule {
         assert!(
             decay.iter().all(|i| *i < 64),
             "Decay value would overflow. All `decay` values must be less than 64"
         );
-        EmissionSchedule { initial, decay, tail }
+        EmissionSchedule {
+            initial,
+            decay: decay.to_vec(),
+            tail,
+        }
     }
 
     /// Return an iterator over the block reward and total supply. This is the most efficient way to iterate through
@@ -178,7 +182,11 @@ impl Emission for EmissionSchedule {
 #[cfg(test)]
 mod test {
     use crate::{
-        consensus::emission::{Emission, EmissionSchedule},
+        consensus::{
+            emission::{Emission, EmissionSchedule},
+            ConsensusManagerBuilder,
+            Network,
+        },
         transactions::tari_amount::{uT, MicroTari, T},
     };
 
@@ -193,6 +201,20 @@ mod test {
         assert_eq!(schedule.supply_at_block(100), MicroTari::from(1_009_994_950));
     }
 
+    #[test]
+    fn custom_emission_amounts() {
+        let rules = ConsensusManagerBuilder::new(Network::LocalNet)
+            .with_emission_amounts(MicroTari::from(10_000_100), &[2], MicroTari::from(100))
+            .build();
+        assert_eq!(rules.get_block_reward_at(0), MicroTari::from(10_000_100));
+        assert_eq!(rules.get_block_reward_at(1), MicroTari::from(7_500_075));
+        assert_eq!(rules.get_block_reward_at(100), MicroTari::from(100));
+        assert_eq!(
+            rules.consensus_constants(0).emission_amounts(),
+            (MicroTari::from(10_000_100), &[2u64][..], MicroTari::from(100))
+        );
+    }
+
     #[test]
     fn huge_block_number() {
         // let mut n = (std::i32::MAX - 1) as u64;
@@ -24,6 +24,8 @@
 pub(crate) mod chain_strength_comparer;
 #[cfg(any(feature = "base_node", feature = "transactions"))]
 pub mod consensus_constants;
+#[cfg(feature = "transactions")]
+pub mod consensus_encoding;
 #[cfg(feature = "base_node")]
 mod consensus_manager;
 #[cfg(any(feature = "base_node", feature = "transactions"))]
@@ -40,6 +42,8 @@ pub const KERNEL_WEIGHT: u64 = 3; // Constant weight per transaction; covers ker
 
 #[cfg(any(feature = "base_node", feature = "transactions"))]
 pub use consensus_constants::{ConsensusConstants, ConsensusConstantsBuilder};
+#[cfg(feature = "transactions")]
+pub use consensus_encoding::{ConsensusDecoding, ConsensusDecodingError, ConsensusEncoding};
 #[cfg(feature = "base_node")]
 pub use consensus_manager::{ConsensusManager, ConsensusManagerBuilder, ConsensusManagerError};
 #[cfg(any(feature = "base_node", feature = "transactions"))]
@@ -22,10 +22,10 @@
 
 use crate::{
     blocks::Block,
-    mempool::{error::MempoolError, Mempool, StateResponse, StatsResponse, TxStorageResponse},
+    mempool::{error::MempoolError, Mempool, MempoolFeeStats, StateResponse, StatsResponse, TxStorageResponse},
     transactions::{transaction::Transaction, types::Signature},
 };
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 macro_rules! make_async {
     ($fn:ident($($param1:ident:$ptype1:ty,$param2:ident:$ptype2:ty),+) -> $rtype:ty) => {
@@ -59,10 +59,14 @@ macro_rules! make_async {
 }
 
 make_async!(insert(tx: Arc<Transaction>) -> TxStorageResponse);
+make_async!(insert_replacing(tx: Arc<Transaction>) -> (TxStorageResponse, Vec<Signature>));
 make_async!(process_published_block(published_block: Arc<Block>) -> ());
 make_async!(process_reorg(removed_blocks: Vec<Arc<Block>>, new_blocks: Vec<Arc<Block>>) -> ());
 make_async!(snapshot() -> Vec<Arc<Transaction>>);
 make_async!(retrieve(total_weight: u64) -> Vec<Arc<Transaction>>);
+make_async!(retrieve_aggregated(total_weight: u64) -> Option<Transaction>);
+make_async!(retrieve_for_rebroadcast(min_age: Duration) -> Vec<Arc<Transaction>>);
 make_async!(has_tx_with_excess_sig(excess_sig: Signature) -> TxStorageResponse);
 make_async!(stats() -> StatsResponse);
+make_async!(fee_stats() -> MempoolFeeStats);
 make_async!(state() -> StateResponse);
@@ -30,6 +30,7 @@ use tari_common::{configuration::seconds, NetworkConfigPath};
 pub struct MempoolConfig {
     pub unconfirmed_pool: UnconfirmedPoolConfig,
     pub reorg_pool: ReorgPoolConfig,
+    pub replace_by_fee: ReplaceByFeeConfig,
 }
 
 impl Default for MempoolConfig {
@@ -37,10 +38,42 @@ impl Default for MempoolConfig {
         Self {
             unconfirmed_pool: UnconfirmedPoolConfig::default(),
             reorg_pool: ReorgPoolConfig::default(),
+            replace_by_fee: ReplaceByFeeConfig::default(),
         }
     }
 }
 
+/// Configuration of the policy applied to a transaction that spends an output that is already spent by a transaction
+/// in the unconfirmed pool.
+#[derive(Clone, Copy, Deserialize, Serialize)]
+pub struct ReplaceByFeeConfig {
+    /// When enabled, a conflicting transaction replaces the transactions it conflicts with if it pays a sufficiently
+    /// higher fee per gram, and is rejected otherwise. When disabled, conflicting transactions are all kept and at
+    /// most one of them will be mined. Default: false
+    pub enabled: bool,
+    /// The minimum increase in fee per gram, in percent of the highest fee per gram paid by the conflicting
+    /// transactions, that a transaction must pay to replace them. Default: 10
+    pub min_fee_per_gram_increase_percent: u64,
+}
+
+impl Default for ReplaceByFeeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_fee_per_gram_increase_percent: consts::MEMPOOL_REPLACE_BY_FEE_MIN_FEE_PER_GRAM_INCREASE_PERCENT,
+        }
+    }
+}
+
+impl ReplaceByFeeConfig {
+    /// Returns true if a transaction paying `fee_per_gram` may replace transactions paying at most
+    /// `conflicting_fee_per_gram`
+    pub fn is_replacement_allowed(&self, fee_per_gram: u64, conflicting_fee_per_gram: u64) -> bool {
+        let increase = conflicting_fee_per_gram.saturating_mul(self.min_fee_per_gram_increase_percent) / 100;
+        fee_per_gram > conflicting_fee_per_gram && fee_per_gram >= conflicting_fee_per_gram.saturating_add(increase)
+    }
+}
+
 impl NetworkConfigPath for MempoolConfig {
     fn main_key_prefix() -> &'static str {
         "mempool"
@@ -58,6 +91,18 @@ pub struct MempoolServiceConfig {
     pub initial_sync_num_peers: usize,
     /// The maximum number of transactions to sync in a single sync session Default: 10_000
     pub initial_sync_max_transactions: usize,
+    /// The maximum number of incoming transactions whose signatures, range proofs and scripts are checked
+    /// concurrently. Transactions are still inserted into the mempool in the order in which they were received.
+    /// Default: 4
+    pub num_validation_workers: usize,
+    /// Unconfirmed transactions that were last propagated longer than this ago, and are still valid, are propagated
+    /// again so that they eventually reach miners. Default: 10 minutes
+    #[serde(with = "seconds")]
+    pub tx_rebroadcast_age: Duration,
+    /// The interval at which the unconfirmed pool is checked for transactions that are due to be propagated again.
+    /// Default: 60 seconds
+    #[serde(with = "seconds")]
+    pub tx_rebroadcast_check_interval: Duration,
 }
 
 impl Default for MempoolServiceConfig {
@@ -66,6 +111,9 @@ impl Default for MempoolServiceConfig {
             request_timeout: consts::MEMPOOL_SERVICE_REQUEST_TIMEOUT,
             initial_sync_num_peers: 2,
             initial_sync_max_transactions: 10_000,
+            num_validation_workers: consts::MEMPOOL_SERVICE_NUM_VALIDATION_WORKERS,
+            tx_rebroadcast_age: consts::MEMPOOL_SERVICE_TX_REBROADCAST_AGE,
+            tx_rebroadcast_check_interval: consts::MEMPOOL_SERVICE_TX_REBROADCAST_CHECK_INTERVAL,
         }
     }
 }
@@ -81,6 +129,7 @@ mod test {
     use super::{
         consts::{MEMPOOL_REORG_POOL_CACHE_TTL, MEMPOOL_REORG_POOL_STORAGE_CAPACITY},
         MempoolConfig,
+        ReplaceByFeeConfig,
     };
     use config::Config;
     use tari_common::DefaultConfigLoader;
@@ -127,4 +176,18 @@ mod test {
             .expect("Could not set 'network'");
         assert!(MempoolConfig::load_from(&config).is_err());
     }
+
+    #[test]
+    pub fn test_replace_by_fee_threshold() {
+        let config = ReplaceByFeeConfig {
+            enabled: true,
+            min_fee_per_gram_increase_percent: 10,
+        };
+        assert!(!config.is_replacement_allowed(109, 100));
+        assert!(config.is_replacement_allowed(110, 100));
+        // A replacement must always pay more than the transactions it replaces
+        assert!(!config.is_replacement_allowed(0, 0));
+        assert!(config.is_replacement_allowed(1, 0));
+        assert!(!config.is_replacement_allowed(5, 5));
+    }
 }
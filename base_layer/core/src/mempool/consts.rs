@@ -33,5 +33,15 @@ pub const MEMPOOL_REORG_POOL_STORAGE_CAPACITY: usize = 5_000;
 /// The time-to-live duration used for transactions stored in the ReorgPool
 pub const MEMPOOL_REORG_POOL_CACHE_TTL: Duration = Duration::from_secs(300);
 
+/// The minimum increase in fee per gram, in percent, that a transaction must pay to replace the unconfirmed
+/// transactions it conflicts with
+pub const MEMPOOL_REPLACE_BY_FEE_MIN_FEE_PER_GRAM_INCREASE_PERCENT: u64 = 10;
+
 /// The allocated waiting time for a request waiting for service responses from the mempools of remote base nodes.
 pub const MEMPOOL_SERVICE_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+/// The maximum number of incoming transactions that are validated concurrently
+pub const MEMPOOL_SERVICE_NUM_VALIDATION_WORKERS: usize = 4;
+/// Unconfirmed transactions that were last propagated longer than this ago are propagated again
+pub const MEMPOOL_SERVICE_TX_REBROADCAST_AGE: Duration = Duration::from_secs(600);
+/// The interval at which the unconfirmed pool is checked for transactions that are due to be propagated again
+pub const MEMPOOL_SERVICE_TX_REBROADCAST_CHECK_INTERVAL: Duration = Duration::from_secs(60);
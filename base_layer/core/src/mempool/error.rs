@@ -46,4 +46,6 @@ pub enum MempoolError {
     BackendError(String),
     #[error("Internal reply channel error: `{0}`")]
     TransportChannelError(#[from] TransportChannelError),
+    #[error("The transaction validation pipeline has shut down")]
+    ValidationPipelineShutdown,
 }
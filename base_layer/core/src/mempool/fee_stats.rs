@@ -0,0 +1,263 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Error, Formatter},
+    time::{Duration, Instant},
+};
+
+/// The lower bounds, in µT per gram, of the fee bands that the weight of the unconfirmed pool is broken down into
+pub const FEE_PER_GRAM_BANDS: [u64; 9] = [0, 1, 2, 5, 10, 25, 50, 100, 250];
+/// The upper bounds of the bands that the ages of the unconfirmed transactions are broken down into. The last band
+/// holds all older transactions.
+pub const AGE_BANDS: [Duration; 4] = [
+    Duration::from_secs(60),
+    Duration::from_secs(5 * 60),
+    Duration::from_secs(30 * 60),
+    Duration::from_secs(2 * 60 * 60),
+];
+
+/// The fees per gram that the given percentages of the unconfirmed pool weight pay at most
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FeePerGramPercentiles {
+    pub p10: u64,
+    pub p25: u64,
+    pub p50: u64,
+    pub p75: u64,
+    pub p90: u64,
+}
+
+/// The unconfirmed transactions that pay a fee per gram in the range `[min_fee_per_gram, max_fee_per_gram)`
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FeeBand {
+    pub min_fee_per_gram: u64,
+    /// The exclusive upper bound of the band, `None` for the highest band
+    pub max_fee_per_gram: Option<u64>,
+    pub tx_count: usize,
+    pub weight: u64,
+}
+
+/// The number of unconfirmed transactions that have been in the pool for less than `max_age`, and at least as long as
+/// the `max_age` of the previous band
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AgeBand {
+    /// The exclusive upper bound of the band, `None` for the oldest band
+    pub max_age: Option<Duration>,
+    pub tx_count: usize,
+}
+
+/// Fee statistics of the unconfirmed pool, used to estimate the fee a transaction needs to pay to be mined
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MempoolFeeStats {
+    pub tx_count: usize,
+    pub total_weight: u64,
+    pub fee_per_gram_percentiles: FeePerGramPercentiles,
+    pub weight_by_fee_band: Vec<FeeBand>,
+    pub age_distribution: Vec<AgeBand>,
+}
+
+impl Display for MempoolFeeStats {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
+        let p = &self.fee_per_gram_percentiles;
+        write!(
+            fmt,
+            "Mempool fee stats: Transactions: {}, Weight: {}, Fee per gram percentiles: 10%: {}, 25%: {}, 50%: {}, \
+             75%: {}, 90%: {}",
+            self.tx_count, self.total_weight, p.p10, p.p25, p.p50, p.p75, p.p90
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+struct FeeLevel {
+    tx_count: usize,
+    weight: u64,
+}
+
+/// Keeps rolling fee statistics of a set of transactions, which is updated as transactions are added and removed, so
+/// that the statistics can be produced without visiting every transaction.
+#[derive(Debug, Default)]
+pub struct FeeStatsTracker {
+    fee_levels: BTreeMap<u64, FeeLevel>,
+    insert_times: BTreeMap<Instant, usize>,
+    tx_count: usize,
+    total_weight: u64,
+}
+
+impl FeeStatsTracker {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Record a transaction with the given fee per gram and weight that was inserted at `inserted_at`
+    pub fn add(&mut self, fee_per_gram: u64, weight: u64, inserted_at: Instant) {
+        let level = self.fee_levels.entry(fee_per_gram).or_default();
+        level.tx_count += 1;
+        level.weight += weight;
+        *self.insert_times.entry(inserted_at).or_default() += 1;
+        self.tx_count += 1;
+        self.total_weight += weight;
+    }
+
+    /// Forget a transaction that was previously recorded with the same values
+    pub fn remove(&mut self, fee_per_gram: u64, weight: u64, inserted_at: Instant) {
+        if let Some(level) = self.fee_levels.get_mut(&fee_per_gram) {
+            level.tx_count -= 1;
+            level.weight -= weight;
+            if level.tx_count == 0 {
+                self.fee_levels.remove(&fee_per_gram);
+            }
+        }
+        if let Some(count) = self.insert_times.get_mut(&inserted_at) {
+            *count -= 1;
+            if *count == 0 {
+                self.insert_times.remove(&inserted_at);
+            }
+        }
+        self.tx_count = self.tx_count.saturating_sub(1);
+        self.total_weight = self.total_weight.saturating_sub(weight);
+    }
+
+    /// Returns the lowest fee per gram such that at least `percent` percent of the recorded weight pays at most that
+    /// fee per gram, or zero if no transactions are recorded
+    pub fn fee_per_gram_percentile(&self, percent: u64) -> u64 {
+        let threshold = self.total_weight * percent;
+        let mut accumulated = 0;
+        for (fee_per_gram, level) in &self.fee_levels {
+            accumulated += level.weight;
+            if accumulated * 100 >= threshold {
+                return *fee_per_gram;
+            }
+        }
+        self.fee_levels.keys().next_back().copied().unwrap_or(0)
+    }
+
+    /// Returns the statistics of the recorded transactions, with transaction ages measured at `now`
+    pub fn stats(&self, now: Instant) -> MempoolFeeStats {
+        let mut weight_by_fee_band = FEE_PER_GRAM_BANDS
+            .iter()
+            .enumerate()
+            .map(|(i, min)| FeeBand {
+                min_fee_per_gram: *min,
+                max_fee_per_gram: FEE_PER_GRAM_BANDS.get(i + 1).copied(),
+                tx_count: 0,
+                weight: 0,
+            })
+            .collect::<Vec<_>>();
+        for (fee_per_gram, level) in &self.fee_levels {
+            let band = FEE_PER_GRAM_BANDS
+                .iter()
+                .rposition(|min| min <= fee_per_gram)
+                .unwrap_or(0);
+            weight_by_fee_band[band].tx_count += level.tx_count;
+            weight_by_fee_band[band].weight += level.weight;
+        }
+
+        let mut age_distribution = AGE_BANDS
+            .iter()
+            .map(|max| Some(*max))
+            .chain(Some(None))
+            .map(|max_age| AgeBand { max_age, tx_count: 0 })
+            .collect::<Vec<_>>();
+        for (inserted_at, count) in &self.insert_times {
+            let age = now.saturating_duration_since(*inserted_at);
+            let band = AGE_BANDS.iter().position(|max| age < *max).unwrap_or(AGE_BANDS.len());
+            age_distribution[band].tx_count += count;
+        }
+
+        MempoolFeeStats {
+            tx_count: self.tx_count,
+            total_weight: self.total_weight,
+            fee_per_gram_percentiles: FeePerGramPercentiles {
+                p10: self.fee_per_gram_percentile(10),
+                p25: self.fee_per_gram_percentile(25),
+                p50: self.fee_per_gram_percentile(50),
+                p75: self.fee_per_gram_percentile(75),
+                p90: self.fee_per_gram_percentile(90),
+            },
+            weight_by_fee_band,
+            age_distribution,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn empty_tracker() {
+        let stats = FeeStatsTracker::new().stats(Instant::now());
+        assert_eq!(stats.tx_count, 0);
+        assert_eq!(stats.fee_per_gram_percentiles, FeePerGramPercentiles::default());
+        assert_eq!(stats.weight_by_fee_band.len(), FEE_PER_GRAM_BANDS.len());
+        assert_eq!(stats.age_distribution.len(), AGE_BANDS.len() + 1);
+    }
+
+    #[test]
+    fn rolling_stats() {
+        let now = Instant::now();
+        let old = now - Duration::from_secs(10 * 60);
+        let mut tracker = FeeStatsTracker::new();
+        tracker.add(1, 100, old);
+        tracker.add(5, 200, now);
+        tracker.add(5, 300, now);
+        tracker.add(300, 400, now);
+
+        let stats = tracker.stats(now);
+        assert_eq!(stats.tx_count, 4);
+        assert_eq!(stats.total_weight, 1000);
+        assert_eq!(stats.fee_per_gram_percentiles, FeePerGramPercentiles {
+            p10: 1,
+            p25: 5,
+            p50: 5,
+            p75: 300,
+            p90: 300,
+        });
+        assert_eq!(stats.weight_by_fee_band[1], FeeBand {
+            min_fee_per_gram: 1,
+            max_fee_per_gram: Some(2),
+            tx_count: 1,
+            weight: 100,
+        });
+        assert_eq!(stats.weight_by_fee_band[3].weight, 500);
+        assert_eq!(stats.weight_by_fee_band[8], FeeBand {
+            min_fee_per_gram: 250,
+            max_fee_per_gram: None,
+            tx_count: 1,
+            weight: 400,
+        });
+        assert_eq!(stats.age_distribution[0].tx_count, 3);
+        assert_eq!(stats.age_distribution[2].tx_count, 1);
+
+        tracker.remove(300, 400, now);
+        tracker.remove(1, 100, old);
+        let stats = tracker.stats(now);
+        assert_eq!(stats.tx_count, 2);
+        assert_eq!(stats.total_weight, 500);
+        assert_eq!(stats.fee_per_gram_percentiles.p90, 5);
+        assert_eq!(stats.age_distribution[2].tx_count, 0);
+        assert_eq!(stats.weight_by_fee_band.iter().map(|b| b.weight).sum::<u64>(), 500);
+    }
+}
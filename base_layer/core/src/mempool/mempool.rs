@@ -24,6 +24,7 @@ use crate::{
     blocks::Block,
     mempool::{
         error::MempoolError,
+        fee_stats::MempoolFeeStats,
         mempool_storage::MempoolStorage,
         MempoolConfig,
         StateResponse,
@@ -31,9 +32,15 @@ use crate::{
         TxStorageResponse,
     },
     transactions::{transaction::Transaction, types::Signature},
-    validation::MempoolTransactionValidation,
+    validation::{MempoolTransactionValidation, ValidationError},
 };
-use std::sync::{Arc, RwLock};
+use log::*;
+use std::{
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+pub const LOG_TARGET: &str = "c::mp::mempool";
 
 /// The Mempool consists of an Unconfirmed Transaction Pool, Pending Pool, Orphan Pool and Reorg Pool and is responsible
 /// for managing and maintaining all unconfirmed transactions have not yet been included in a block, and transactions
@@ -41,6 +48,7 @@ use std::sync::{Arc, RwLock};
 #[derive(Clone)]
 pub struct Mempool {
     pool_storage: Arc<RwLock<MempoolStorage>>,
+    stateless_validator: Option<Arc<dyn MempoolTransactionValidation>>,
 }
 
 impl Mempool {
@@ -48,16 +56,52 @@ impl Mempool {
     pub fn new(config: MempoolConfig, validator: Arc<dyn MempoolTransactionValidation>) -> Self {
         Self {
             pool_storage: Arc::new(RwLock::new(MempoolStorage::new(config, validator))),
+            stateless_validator: None,
+        }
+    }
+
+    /// Set the validator for the checks that only depend on the transaction itself, such as the signature, range proof
+    /// and script checks. These checks run before the Mempool lock is taken, so independent transactions are validated
+    /// concurrently. The validator given to `new` then only has to check transactions against the blockchain.
+    pub fn with_stateless_validator(mut self, validator: Arc<dyn MempoolTransactionValidation>) -> Self {
+        self.stateless_validator = Some(validator);
+        self
+    }
+
+    /// Run the stateless checks on the transaction. This does not access the Mempool.
+    pub fn validate_stateless(&self, tx: &Transaction) -> Result<(), ValidationError> {
+        match &self.stateless_validator {
+            Some(validator) => validator.validate(tx),
+            None => Ok(()),
         }
     }
 
     /// Insert an unconfirmed transaction into the Mempool. The transaction *MUST* have passed through the validation
     /// pipeline already and will thus always be internally consistent by this stage
     pub fn insert(&self, tx: Arc<Transaction>) -> Result<TxStorageResponse, MempoolError> {
+        self.insert_replacing(tx).map(|(storage, _)| storage)
+    }
+
+    /// Insert an unconfirmed transaction into the Mempool, returning the excess signatures of the conflicting
+    /// transactions that it replaced along with the storage response.
+    pub fn insert_replacing(&self, tx: Arc<Transaction>) -> Result<(TxStorageResponse, Vec<Signature>), MempoolError> {
+        if let Err(e) = self.validate_stateless(&tx) {
+            warn!(target: LOG_TARGET, "Validation failed due to error:{}", e);
+            return Ok((TxStorageResponse::NotStored, Vec::new()));
+        }
+        self.insert_validated(tx)
+    }
+
+    // Insert a transaction that has already passed the stateless checks
+    pub(crate) fn insert_validated(
+        &self,
+        tx: Arc<Transaction>,
+    ) -> Result<(TxStorageResponse, Vec<Signature>), MempoolError>
+    {
         self.pool_storage
             .write()
             .map_err(|e| MempoolError::BackendError(e.to_string()))?
-            .insert(tx)
+            .insert_replacing(tx)
     }
 
     /// Update the Mempool based on the received published block.
@@ -100,6 +144,23 @@ impl Mempool {
             .retrieve(total_weight)
     }
 
+    /// Returns the transactions ranked by transaction priority up to a given weight, aggregated into a single
+    /// transaction with cut-through applied
+    pub fn retrieve_aggregated(&self, total_weight: u64) -> Result<Option<Transaction>, MempoolError> {
+        self.pool_storage
+            .read()
+            .map_err(|e| MempoolError::BackendError(e.to_string()))?
+            .retrieve_aggregated(total_weight)
+    }
+
+    /// Returns the unconfirmed transactions that are due to be propagated again and are still valid
+    pub fn retrieve_for_rebroadcast(&self, min_age: Duration) -> Result<Vec<Arc<Transaction>>, MempoolError> {
+        self.pool_storage
+            .write()
+            .map_err(|e| MempoolError::BackendError(e.to_string()))?
+            .retrieve_for_rebroadcast(min_age)
+    }
+
     /// Check if the specified transaction is stored in the Mempool.
     pub fn has_tx_with_excess_sig(&self, excess_sig: Signature) -> Result<TxStorageResponse, MempoolError> {
         self.pool_storage
@@ -108,6 +169,14 @@ impl Mempool {
             .has_tx_with_excess_sig(excess_sig)
     }
 
+    /// Returns the fee statistics of the unconfirmed transactions in the Mempool.
+    pub fn fee_stats(&self) -> Result<MempoolFeeStats, MempoolError> {
+        self.pool_storage
+            .read()
+            .map_err(|e| MempoolError::BackendError(e.to_string()))?
+            .fee_stats()
+    }
+
     /// Gathers and returns the stats of the Mempool.
     pub fn stats(&self) -> Result<StatsResponse, MempoolError> {
         self.pool_storage
@@ -24,9 +24,11 @@ use crate::{
     blocks::Block,
     mempool::{
         error::MempoolError,
+        fee_stats::MempoolFeeStats,
         reorg_pool::ReorgPool,
         unconfirmed_pool::UnconfirmedPool,
         MempoolConfig,
+        ReplaceByFeeConfig,
         StateResponse,
         StatsResponse,
         TxStorageResponse,
@@ -35,7 +37,7 @@ use crate::{
     validation::{MempoolTransactionValidation, ValidationError},
 };
 use log::*;
-use std::sync::Arc;
+use std::{cmp::max, sync::Arc, time::Duration};
 use tari_crypto::tari_utilities::{hex::Hex, Hashable};
 
 pub const LOG_TARGET: &str = "c::mp::mempool";
@@ -46,6 +48,7 @@ pub const LOG_TARGET: &str = "c::mp::mempool";
 pub struct MempoolStorage {
     unconfirmed_pool: UnconfirmedPool,
     reorg_pool: ReorgPool,
+    replace_by_fee: ReplaceByFeeConfig,
     validator: Arc<dyn MempoolTransactionValidation>,
 }
 
@@ -55,6 +58,7 @@ impl MempoolStorage {
         Self {
             unconfirmed_pool: UnconfirmedPool::new(config.unconfirmed_pool),
             reorg_pool: ReorgPool::new(config.reorg_pool),
+            replace_by_fee: config.replace_by_fee,
             validator: validators,
         }
     }
@@ -62,6 +66,16 @@ impl MempoolStorage {
     /// Insert an unconfirmed transaction into the Mempool. The transaction *MUST* have passed through the validation
     /// pipeline already and will thus always be internally consistent by this stage
     pub fn insert(&mut self, tx: Arc<Transaction>) -> Result<TxStorageResponse, MempoolError> {
+        self.insert_replacing(tx).map(|(storage, _)| storage)
+    }
+
+    /// Insert an unconfirmed transaction into the Mempool, as with `insert`. Also returns the excess signatures of the
+    /// conflicting transactions that the transaction replaced, if any.
+    pub fn insert_replacing(
+        &mut self,
+        tx: Arc<Transaction>,
+    ) -> Result<(TxStorageResponse, Vec<Signature>), MempoolError>
+    {
         debug!(
             target: LOG_TARGET,
             "Inserting tx into mempool: {}",
@@ -73,25 +87,121 @@ impl MempoolStorage {
         );
 
         match self.validator.validate(&tx) {
-            Ok(()) => {
-                self.unconfirmed_pool.insert(tx)?;
-                Ok(TxStorageResponse::UnconfirmedPool)
-            },
-            Err(ValidationError::UnknownInputs) => {
-                warn!(target: LOG_TARGET, "Validation failed due to unknown inputs");
-                Ok(TxStorageResponse::NotStoredOrphan)
-            },
+            Ok(()) => self.insert_valid(tx, Vec::new()),
+            Err(ValidationError::UnknownInputs) => self.insert_chained(tx),
             Err(ValidationError::ContainsSTxO) => {
                 warn!(target: LOG_TARGET, "Validation failed due to already spent output");
-                Ok(TxStorageResponse::NotStoredAlreadySpent)
+                Ok((TxStorageResponse::NotStoredAlreadySpent, Vec::new()))
             },
             Err(ValidationError::MaturityError) => {
                 warn!(target: LOG_TARGET, "Validation failed due to maturity error");
-                Ok(TxStorageResponse::NotStoredTimeLocked)
+                Ok((TxStorageResponse::NotStoredTimeLocked, Vec::new()))
             },
             Err(e) => {
                 warn!(target: LOG_TARGET, "Validation failed due to error:{}", e);
-                Ok(TxStorageResponse::NotStored)
+                Ok((TxStorageResponse::NotStored, Vec::new()))
+            },
+        }
+    }
+
+    // Validation only checks a transaction against the blockchain, so a valid transaction may still spend an output
+    // that a transaction in the unconfirmed pool spends. When replace-by-fee is enabled, the transaction is only
+    // inserted if it pays a sufficiently higher fee per gram than every transaction it conflicts with, and those
+    // transactions are then evicted along with the transactions that depend on them.
+    fn insert_valid(
+        &mut self,
+        tx: Arc<Transaction>,
+        depends_on: Vec<Signature>,
+    ) -> Result<(TxStorageResponse, Vec<Signature>), MempoolError>
+    {
+        let mut evicted = Vec::new();
+        if self.replace_by_fee.enabled {
+            let conflicts = self.unconfirmed_pool.conflicting_txs(&tx);
+            if let Some(conflicting_fee_per_gram) = conflicts.iter().map(|(_, fee_per_gram)| *fee_per_gram).max() {
+                let fee_per_gram = tx.body.get_total_fee().0 / max(tx.calculate_weight(), 1);
+                if !self
+                    .replace_by_fee
+                    .is_replacement_allowed(fee_per_gram, conflicting_fee_per_gram)
+                {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Transaction conflicts with {} unconfirmed transaction(s) and its fee per gram ({}) is too \
+                         low to replace them ({})",
+                        conflicts.len(),
+                        fee_per_gram,
+                        conflicting_fee_per_gram
+                    );
+                    return Ok((TxStorageResponse::NotStoredFeeTooLow, evicted));
+                }
+                evicted = self
+                    .unconfirmed_pool
+                    .discard_with_dependents(conflicts.into_iter().map(|(tx_key, _)| tx_key).collect())
+                    .iter()
+                    .filter_map(|evicted_tx| evicted_tx.first_kernel_excess_sig().cloned())
+                    .collect();
+                debug!(
+                    target: LOG_TARGET,
+                    "Transaction replaces {} conflicting unconfirmed transaction(s)",
+                    evicted.len()
+                );
+            }
+        }
+        self.unconfirmed_pool.insert_with_dependencies(tx, depends_on)?;
+        Ok((TxStorageResponse::UnconfirmedPool, evicted))
+    }
+
+    // A transaction that spends the outputs of other unconfirmed transactions fails validation, as its inputs are not
+    // in the UTXO set yet. It is accepted if it is valid once aggregated with the transactions it depends on, in
+    // which case the outputs it spends are cut through. Maturity, relative time-locks, covenants, scripts, hash time
+    // locks and multisig locks are checked against inputs, which do not survive cut-through, so the transaction may
+    // only spend outputs that have none of them.
+    fn insert_chained(&mut self, tx: Arc<Transaction>) -> Result<(TxStorageResponse, Vec<Signature>), MempoolError> {
+        let parents = self.unconfirmed_pool.unconfirmed_parents(&tx);
+        if parents.is_empty() {
+            warn!(target: LOG_TARGET, "Validation failed due to unknown inputs");
+            return Ok((TxStorageResponse::NotStoredOrphan, Vec::new()));
+        }
+        let ancestors = self.unconfirmed_pool.with_ancestors(&parents)?;
+        let spends_restricted_output = tx
+            .body
+            .inputs()
+            .iter()
+            .filter(|input| {
+                ancestors
+                    .iter()
+                    .any(|a| a.body.outputs().iter().any(|o| input.is_equal_to(o)))
+            })
+            .any(|input| input.has_spending_conditions());
+        if spends_restricted_output {
+            warn!(
+                target: LOG_TARGET,
+                "Transaction spends an unconfirmed output that has spending conditions"
+            );
+            return Ok((TxStorageResponse::NotStored, Vec::new()));
+        }
+
+        let aggregated = ancestors
+            .iter()
+            .fold((*tx).clone(), |aggregated, ancestor| aggregated + (**ancestor).clone());
+        match self.validator.validate(&aggregated) {
+            Ok(()) => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Transaction depends on {} unconfirmed transaction(s)",
+                    parents.len()
+                );
+                self.insert_valid(tx, parents)
+            },
+            Err(ValidationError::UnknownInputs) => {
+                warn!(target: LOG_TARGET, "Validation failed due to unknown inputs");
+                Ok((TxStorageResponse::NotStoredOrphan, Vec::new()))
+            },
+            Err(e) => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Validation of the aggregated transaction failed due to error:{}", e
+                );
+                Ok((TxStorageResponse::NotStored, Vec::new()))
             },
         }
     }
@@ -191,11 +301,87 @@ impl MempoolStorage {
     }
 
     /// Returns a list of transaction ranked by transaction priority up to a given weight.
-    /// Will only return transactions that will fit into a block
+    /// Will only return transactions that will fit into a block. A transaction that spends the outputs of other
+    /// unconfirmed transactions is only returned with, and after, those transactions.
     pub fn retrieve(&self, total_weight: u64) -> Result<Vec<Arc<Transaction>>, MempoolError> {
         Ok(self.unconfirmed_pool.highest_priority_txs(total_weight)?)
     }
 
+    /// Returns the transactions selected by [retrieve](MempoolStorage::retrieve) aggregated into a single transaction,
+    /// with the outputs that the transactions spend from one another cut through. A transaction that spends an output
+    /// with spending conditions of another selected transaction is left out, as cut-through would remove the input
+    /// that those conditions are checked against, and so are the transactions that depend on it. Returns None if no
+    /// transactions were selected.
+    pub fn retrieve_aggregated(&self, total_weight: u64) -> Result<Option<Transaction>, MempoolError> {
+        let mut selected_outputs = Vec::new();
+        let mut skipped_outputs = Vec::new();
+        let mut aggregated: Option<Transaction> = None;
+        for tx in self.retrieve(total_weight)? {
+            let spends_skipped = tx
+                .body
+                .inputs()
+                .iter()
+                .any(|input| skipped_outputs.iter().any(|o| input.is_equal_to(o)));
+            let spends_restricted =
+                tx.body.inputs().iter().any(|input| {
+                    input.has_spending_conditions() && selected_outputs.iter().any(|o| input.is_equal_to(o))
+                });
+            if spends_skipped || spends_restricted {
+                debug!(
+                    target: LOG_TARGET,
+                    "Leaving out a transaction that would cut through an output with spending conditions"
+                );
+                skipped_outputs.extend(tx.body.outputs().iter().cloned());
+                continue;
+            }
+            selected_outputs.extend(tx.body.outputs().iter().cloned());
+            let tx = Arc::try_unwrap(tx).unwrap_or_else(|tx| (*tx).clone());
+            aggregated = Some(match aggregated {
+                Some(aggregated) => aggregated.add_no_cut_through(tx),
+                None => tx,
+            });
+        }
+        Ok(aggregated.map(|mut tx| {
+            tx.body.do_cut_through();
+            tx
+        }))
+    }
+
+    /// Returns the unconfirmed transactions that were last propagated at least `min_age` ago and are still valid, so
+    /// that they can be propagated again. Transactions that are no longer valid are discarded, along with the
+    /// transactions that depend on them.
+    pub fn retrieve_for_rebroadcast(&mut self, min_age: Duration) -> Result<Vec<Arc<Transaction>>, MempoolError> {
+        let due = self.unconfirmed_pool.take_txs_due_for_rebroadcast(min_age);
+        let mut invalid = Vec::new();
+        for tx in &due {
+            // Chained transactions can not be validated on their own, they remain valid for as long as the
+            // transactions they depend on do.
+            if !self.unconfirmed_pool.unconfirmed_parents(tx).is_empty() {
+                continue;
+            }
+            match self.validator.validate(tx) {
+                Ok(()) => {},
+                Err(ValidationError::FatalStorageError(e)) => return Err(MempoolError::BackendError(e)),
+                Err(e) => {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Discarding transaction that is no longer valid: {}", e
+                    );
+                    invalid.extend(tx.first_kernel_excess_sig().cloned());
+                },
+            }
+        }
+        self.unconfirmed_pool.discard_with_dependents(invalid);
+        Ok(due
+            .into_iter()
+            .filter(|tx| {
+                tx.first_kernel_excess_sig()
+                    .map(|sig| self.unconfirmed_pool.has_tx_with_excess_sig(sig))
+                    .unwrap_or(false)
+            })
+            .collect())
+    }
+
     /// Check if the specified transaction is stored in the Mempool.
     pub fn has_tx_with_excess_sig(&self, excess_sig: Signature) -> Result<TxStorageResponse, MempoolError> {
         if self.unconfirmed_pool.has_tx_with_excess_sig(&excess_sig) {
@@ -217,6 +403,11 @@ impl MempoolStorage {
         Ok(self.unconfirmed_pool.calculate_weight() + self.reorg_pool.calculate_weight()?)
     }
 
+    /// Returns the fee statistics of the unconfirmed transactions in the Mempool.
+    pub fn fee_stats(&self) -> Result<MempoolFeeStats, MempoolError> {
+        Ok(self.unconfirmed_pool.fee_stats())
+    }
+
     /// Gathers and returns the stats of the Mempool.
     pub fn stats(&self) -> Result<StatsResponse, MempoolError> {
         Ok(StatsResponse {
@@ -239,7 +430,7 @@ impl MempoolStorage {
             .reorg_pool
             .snapshot()?
             .iter()
-            .map(|tx| tx.body.kernels()[0].excess_sig.clone())
+            .filter_map(|tx| tx.first_kernel_excess_sig().cloned())
             .collect::<Vec<_>>();
         Ok(StateResponse {
             unconfirmed_pool,
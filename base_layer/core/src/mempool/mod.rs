@@ -29,9 +29,12 @@ mod config;
 mod consts;
 #[cfg(feature = "base_node")]
 mod error;
+#[cfg_attr(not(feature = "base_node"), allow(dead_code))]
+mod fee_stats;
 #[cfg(feature = "base_node")]
 #[allow(clippy::module_inception)]
 mod mempool;
+pub use fee_stats::{AgeBand, FeeBand, FeePerGramPercentiles, MempoolFeeStats};
 #[cfg(feature = "base_node")]
 mod mempool_storage;
 #[cfg(feature = "base_node")]
@@ -46,6 +49,8 @@ pub use rpc::create_mempool_rpc_service;
 pub use rpc::{MempoolRpcClient, MempoolRpcServer, MempoolRpcService, MempoolService};
 #[cfg(feature = "base_node")]
 mod unconfirmed_pool;
+#[cfg(feature = "base_node")]
+mod validation_pipeline;
 
 // public modules
 #[cfg(feature = "base_node")]
@@ -53,7 +58,7 @@ pub mod async_mempool;
 
 // Public re-exports
 #[cfg(feature = "base_node")]
-pub use self::config::{MempoolConfig, MempoolServiceConfig};
+pub use self::config::{MempoolConfig, MempoolServiceConfig, ReplaceByFeeConfig};
 #[cfg(feature = "base_node")]
 pub use error::MempoolError;
 #[cfg(feature = "base_node")]
@@ -132,6 +137,7 @@ pub enum TxStorageResponse {
     NotStoredOrphan,
     NotStoredTimeLocked,
     NotStoredAlreadySpent,
+    NotStoredFeeTooLow,
     NotStored,
 }
 
@@ -149,6 +155,7 @@ impl Display for TxStorageResponse {
             TxStorageResponse::NotStoredOrphan => "Not stored orphan transaction",
             TxStorageResponse::NotStoredTimeLocked => "Not stored time locked transaction",
             TxStorageResponse::NotStoredAlreadySpent => "Not stored output already spent",
+            TxStorageResponse::NotStoredFeeTooLow => "Not stored fee too low to replace conflicting transaction",
             TxStorageResponse::NotStored => "Not stored",
         };
         fmt.write_str(&storage)
@@ -159,4 +166,10 @@ impl Display for TxStorageResponse {
 #[derive(Debug, Clone)]
 pub enum MempoolStateEvent {
     Updated,
+    /// The transaction with the given excess signature replaced the listed conflicting transactions, which were
+    /// evicted from the unconfirmed pool along with the transactions that depended on them
+    TransactionsReplaced {
+        replaced_by: Signature,
+        evicted: Vec<Signature>,
+    },
 }
@@ -20,8 +20,11 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use crate::{mempool::priority::PriorityError, transactions::transaction::Transaction};
-use std::{convert::TryFrom, sync::Arc};
+use crate::{
+    mempool::priority::PriorityError,
+    transactions::{transaction::Transaction, types::Signature},
+};
+use std::{cmp::max, convert::TryFrom, sync::Arc, time::Instant};
 use tari_crypto::tari_utilities::message_format::MessageFormat;
 
 /// Create a unique unspent transaction priority based on the transaction fee, maturity of the oldest input UTXO and the
@@ -58,16 +61,30 @@ pub struct PrioritizedTransaction {
     pub transaction: Arc<Transaction>,
     pub priority: FeePriority,
     pub weight: u64,
+    /// The fee per gram paid by the transaction, in µT
+    pub fee_per_gram: u64,
+    /// The excess signatures of the unconfirmed transactions that create the outputs spent by this transaction
+    pub depends_on: Vec<Signature>,
+    /// The time at which this transaction was received
+    pub inserted_at: Instant,
+    /// The time at which this transaction was last propagated to the network, or was received
+    pub last_propagated: Instant,
 }
 
 impl TryFrom<Transaction> for PrioritizedTransaction {
     type Error = PriorityError;
 
     fn try_from(transaction: Transaction) -> Result<Self, Self::Error> {
+        let weight = transaction.calculate_weight();
+        let now = Instant::now();
         Ok(Self {
             priority: FeePriority::try_from(&transaction)?,
-            weight: transaction.calculate_weight(),
+            weight,
+            fee_per_gram: transaction.body.get_total_fee().0 / max(weight, 1),
             transaction: Arc::new(transaction),
+            depends_on: Vec::new(),
+            inserted_at: now,
+            last_propagated: now,
         })
     }
 }
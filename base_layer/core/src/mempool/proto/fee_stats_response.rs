@@ -0,0 +1,128 @@
+// Copyright 2019, The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::mempool::{
+    proto::mempool::{
+        AgeBand as ProtoAgeBand,
+        FeeBand as ProtoFeeBand,
+        FeePerGramPercentiles as ProtoFeePerGramPercentiles,
+        FeeStatsResponse as ProtoFeeStatsResponse,
+    },
+    AgeBand,
+    FeeBand,
+    FeePerGramPercentiles,
+    MempoolFeeStats,
+};
+use std::{convert::TryFrom, time::Duration};
+
+impl TryFrom<ProtoFeeStatsResponse> for MempoolFeeStats {
+    type Error = String;
+
+    fn try_from(stats: ProtoFeeStatsResponse) -> Result<Self, Self::Error> {
+        Ok(Self {
+            tx_count: stats.tx_count as usize,
+            total_weight: stats.total_weight,
+            fee_per_gram_percentiles: stats
+                .fee_per_gram_percentiles
+                .map(Into::into)
+                .ok_or_else(|| "Fee per gram percentiles not provided".to_string())?,
+            weight_by_fee_band: stats.weight_by_fee_band.into_iter().map(Into::into).collect(),
+            age_distribution: stats.age_distribution.into_iter().map(Into::into).collect(),
+        })
+    }
+}
+
+impl From<MempoolFeeStats> for ProtoFeeStatsResponse {
+    fn from(stats: MempoolFeeStats) -> Self {
+        Self {
+            tx_count: stats.tx_count as u64,
+            total_weight: stats.total_weight,
+            fee_per_gram_percentiles: Some(stats.fee_per_gram_percentiles.into()),
+            weight_by_fee_band: stats.weight_by_fee_band.into_iter().map(Into::into).collect(),
+            age_distribution: stats.age_distribution.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<ProtoFeePerGramPercentiles> for FeePerGramPercentiles {
+    fn from(percentiles: ProtoFeePerGramPercentiles) -> Self {
+        Self {
+            p10: percentiles.p10,
+            p25: percentiles.p25,
+            p50: percentiles.p50,
+            p75: percentiles.p75,
+            p90: percentiles.p90,
+        }
+    }
+}
+
+impl From<FeePerGramPercentiles> for ProtoFeePerGramPercentiles {
+    fn from(percentiles: FeePerGramPercentiles) -> Self {
+        Self {
+            p10: percentiles.p10,
+            p25: percentiles.p25,
+            p50: percentiles.p50,
+            p75: percentiles.p75,
+            p90: percentiles.p90,
+        }
+    }
+}
+
+impl From<ProtoFeeBand> for FeeBand {
+    fn from(band: ProtoFeeBand) -> Self {
+        Self {
+            min_fee_per_gram: band.min_fee_per_gram,
+            max_fee_per_gram: band.max_fee_per_gram,
+            tx_count: band.tx_count as usize,
+            weight: band.weight,
+        }
+    }
+}
+
+impl From<FeeBand> for ProtoFeeBand {
+    fn from(band: FeeBand) -> Self {
+        Self {
+            min_fee_per_gram: band.min_fee_per_gram,
+            max_fee_per_gram: band.max_fee_per_gram,
+            tx_count: band.tx_count as u64,
+            weight: band.weight,
+        }
+    }
+}
+
+impl From<ProtoAgeBand> for AgeBand {
+    fn from(band: ProtoAgeBand) -> Self {
+        Self {
+            max_age: band.max_age_secs.map(Duration::from_secs),
+            tx_count: band.tx_count as usize,
+        }
+    }
+}
+
+impl From<AgeBand> for ProtoAgeBand {
+    fn from(band: AgeBand) -> Self {
+        Self {
+            max_age_secs: band.max_age.map(|age| age.as_secs()),
+            tx_count: band.tx_count as u64,
+        }
+    }
+}
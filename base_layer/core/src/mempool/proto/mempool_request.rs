@@ -41,6 +41,7 @@ impl TryInto<MempoolRequest> for ProtoMempoolRequest {
                 excess_sig.try_into().map_err(|err: ByteArrayError| err.to_string())?,
             ),
             SubmitTransaction(tx) => MempoolRequest::SubmitTransaction(tx.try_into()?),
+            GetFeeStats(_) => MempoolRequest::GetFeeStats,
         };
         Ok(request)
     }
@@ -54,6 +55,7 @@ impl From<MempoolRequest> for ProtoMempoolRequest {
             GetState => ProtoMempoolRequest::GetState(true),
             GetTxStateByExcessSig(excess_sig) => ProtoMempoolRequest::GetTxStateByExcessSig(excess_sig.into()),
             SubmitTransaction(tx) => ProtoMempoolRequest::SubmitTransaction(tx.into()),
+            GetFeeStats => ProtoMempoolRequest::GetFeeStats(true),
         }
     }
 }
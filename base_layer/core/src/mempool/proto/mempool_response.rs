@@ -43,6 +43,7 @@ impl TryInto<MempoolResponse> for ProtoMempoolResponse {
                     .ok_or_else(|| "Invalid or unrecognised `TxStorageResponse` enum".to_string())?;
                 MempoolResponse::TxStorage(tx_storage_response.try_into()?)
             },
+            FeeStats(fee_stats_response) => MempoolResponse::FeeStats(fee_stats_response.try_into()?),
         };
         Ok(response)
     }
@@ -72,6 +73,7 @@ impl From<MempoolResponse> for ProtoMempoolResponse {
                 let tx_storage_response: ProtoTxStorageResponse = tx_storage_response.into();
                 ProtoMempoolResponse::TxStorage(tx_storage_response.into())
             },
+            FeeStats(fee_stats_response) => ProtoMempoolResponse::FeeStats(fee_stats_response.into()),
         }
     }
 }
@@ -33,6 +33,7 @@ pub use mempool::{
 
 mod sync_protocol;
 // TODO: Clean up
+pub mod fee_stats_response;
 pub mod mempool_request;
 pub mod mempool_response;
 pub mod state_response;
@@ -47,6 +47,7 @@ impl From<TxStorageResponse> for proto::TxStorageResponse {
             NotStoredOrphan => proto::TxStorageResponse::NotStored,
             NotStoredTimeLocked => proto::TxStorageResponse::NotStored,
             NotStoredAlreadySpent => proto::TxStorageResponse::NotStored,
+            NotStoredFeeTooLow => proto::TxStorageResponse::NotStored,
         }
     }
 }
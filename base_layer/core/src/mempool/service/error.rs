@@ -47,4 +47,6 @@ pub enum MempoolServiceError {
     TransportChannelError(#[from] TransportChannelError),
     #[error("Failed to send broadcast message")]
     BroadcastFailed,
+    #[error("The transaction has no kernels")]
+    TransactionNoKernels,
 }
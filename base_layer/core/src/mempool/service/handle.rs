@@ -23,6 +23,7 @@
 use crate::{
     mempool::{
         service::{MempoolRequest, MempoolResponse},
+        MempoolFeeStats,
         MempoolServiceError,
         StateResponse,
         StatsResponse,
@@ -56,6 +57,13 @@ impl MempoolHandle {
         }
     }
 
+    pub async fn get_fee_stats(&mut self) -> Result<MempoolFeeStats, MempoolServiceError> {
+        match self.inner.call(MempoolRequest::GetFeeStats).await?? {
+            MempoolResponse::FeeStats(resp) => Ok(resp),
+            _ => panic!("Incorrect response"),
+        }
+    }
+
     pub async fn get_tx_state_by_excess_sig(
         &mut self,
         sig: Signature,
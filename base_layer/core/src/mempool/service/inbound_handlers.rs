@@ -26,14 +26,15 @@ use crate::{
     mempool::{
         async_mempool,
         service::{MempoolRequest, MempoolResponse, MempoolServiceError, OutboundMempoolServiceInterface},
+        validation_pipeline::TxValidationPipeline,
         Mempool,
         MempoolStateEvent,
         TxStorageResponse,
     },
-    transactions::transaction::Transaction,
+    transactions::{transaction::Transaction, types::Signature},
 };
 use log::*;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use tari_comms::peer_manager::NodeId;
 use tari_crypto::tari_utilities::hex::Hex;
 use tokio::sync::broadcast;
@@ -46,6 +47,7 @@ pub const LOG_TARGET: &str = "c::mp::service::inbound_handlers";
 pub struct MempoolInboundHandlers {
     event_publisher: broadcast::Sender<MempoolStateEvent>,
     mempool: Mempool,
+    validation_pipeline: TxValidationPipeline,
     outbound_nmi: OutboundMempoolServiceInterface,
 }
 
@@ -54,12 +56,14 @@ impl MempoolInboundHandlers {
     pub fn new(
         event_publisher: broadcast::Sender<MempoolStateEvent>,
         mempool: Mempool,
+        validation_pipeline: TxValidationPipeline,
         outbound_nmi: OutboundMempoolServiceInterface,
     ) -> Self
     {
         Self {
             event_publisher,
             mempool,
+            validation_pipeline,
             outbound_nmi,
         }
     }
@@ -82,10 +86,13 @@ impl MempoolInboundHandlers {
                 debug!(
                     target: LOG_TARGET,
                     "Transaction ({}) submitted using request.",
-                    tx.body.kernels()[0].excess_sig.get_signature().to_hex(),
+                    first_kernel_excess_sig(&tx)?.get_signature().to_hex(),
                 );
                 Ok(MempoolResponse::TxStorage(self.submit_transaction(tx, vec![]).await?))
             },
+            GetFeeStats => Ok(MempoolResponse::FeeStats(
+                async_mempool::fee_stats(self.mempool.clone()).await?,
+            )),
         }
     }
 
@@ -99,7 +106,7 @@ impl MempoolInboundHandlers {
         debug!(
             target: LOG_TARGET,
             "Transaction ({}) received from {}.",
-            tx.body.kernels()[0].excess_sig.get_signature().to_hex(),
+            first_kernel_excess_sig(&tx)?.get_signature().to_hex(),
             source_peer
                 .as_ref()
                 .map(|p| format!("remote peer: {}", p))
@@ -117,11 +124,10 @@ impl MempoolInboundHandlers {
     ) -> Result<TxStorageResponse, MempoolServiceError>
     {
         trace!(target: LOG_TARGET, "submit_transaction: {}.", tx);
-        let tx_storage =
-            async_mempool::has_tx_with_excess_sig(self.mempool.clone(), tx.body.kernels()[0].excess_sig.clone())
-                .await?;
+        let excess_sig = first_kernel_excess_sig(&tx)?;
+        let tx_storage = async_mempool::has_tx_with_excess_sig(self.mempool.clone(), excess_sig.clone()).await?;
 
-        let kernel_excess_sig = tx.body.kernels()[0].excess_sig.get_signature().to_hex();
+        let kernel_excess_sig = excess_sig.get_signature().to_hex();
         if tx_storage.is_stored() {
             debug!(
                 target: LOG_TARGET,
@@ -130,14 +136,27 @@ impl MempoolInboundHandlers {
             return Ok(tx_storage);
         }
 
-        match async_mempool::insert(self.mempool.clone(), Arc::new(tx.clone())).await {
-            Ok(tx_storage) => {
+        match self.validation_pipeline.submit(Arc::new(tx.clone())).await {
+            Ok((tx_storage, evicted)) => {
                 debug!(
                     target: LOG_TARGET,
                     "Transaction inserted into mempool: {}, pool: {}.", kernel_excess_sig, tx_storage
                 );
+                if !evicted.is_empty() {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Transaction {} replaced {} conflicting transaction(s).",
+                        kernel_excess_sig,
+                        evicted.len()
+                    );
+                    let _ = self.event_publisher.send(MempoolStateEvent::TransactionsReplaced {
+                        replaced_by: excess_sig,
+                        evicted,
+                    });
+                }
                 // propagate the tx if it was accepted to the unconfirmed pool
                 if matches!(tx_storage, TxStorageResponse::UnconfirmedPool) {
+                    let _ = self.event_publisher.send(MempoolStateEvent::Updated);
                     debug!(
                         target: LOG_TARGET,
                         "Propagate transaction ({}) to network.", kernel_excess_sig,
@@ -150,6 +169,23 @@ impl MempoolInboundHandlers {
         }
     }
 
+    /// Propagate the unconfirmed transactions that were last propagated at least `min_age` ago and are still valid.
+    /// This ensures that transactions submitted while this node had poor connectivity eventually reach miners.
+    pub async fn rebroadcast_aged_transactions(&mut self, min_age: Duration) -> Result<(), MempoolServiceError> {
+        let txs = async_mempool::retrieve_for_rebroadcast(self.mempool.clone(), min_age).await?;
+        if !txs.is_empty() {
+            debug!(
+                target: LOG_TARGET,
+                "Propagating {} aged transaction(s) to the network again.",
+                txs.len()
+            );
+        }
+        for tx in txs {
+            self.outbound_nmi.propagate_tx((*tx).clone(), vec![]).await?;
+        }
+        Ok(())
+    }
+
     /// Handle inbound block events from the local base node service.
     pub async fn handle_block_event(&mut self, block_event: &BlockEvent) -> Result<(), MempoolServiceError> {
         use BlockEvent::*;
@@ -190,3 +226,10 @@ impl MempoolInboundHandlers {
         Ok(())
     }
 }
+
+// The excess signature of the first kernel identifies a transaction in the mempool
+fn first_kernel_excess_sig(tx: &Transaction) -> Result<Signature, MempoolServiceError> {
+    tx.first_kernel_excess_sig()
+        .cloned()
+        .ok_or(MempoolServiceError::TransactionNoKernels)
+}
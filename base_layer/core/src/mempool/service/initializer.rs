@@ -32,6 +32,7 @@ use crate::{
             service::{MempoolService, MempoolStreams},
             MempoolHandle,
         },
+        validation_pipeline::TxValidationPipeline,
         MempoolServiceConfig,
     },
     proto,
@@ -158,20 +159,23 @@ impl ServiceInitializer for MempoolServiceInitializer {
         let local_mp_interface =
             LocalMempoolService::new(local_request_sender_service, mempool_state_event_publisher.clone());
         let config = self.config;
-        let inbound_handlers = MempoolInboundHandlers::new(
-            mempool_state_event_publisher,
-            self.mempool.clone(),
-            outbound_mp_interface.clone(),
-        );
+        let mempool = self.mempool.clone();
 
         // Register handle to OutboundMempoolServiceInterface before waiting for handles to be ready
-        context.register_handle(outbound_mp_interface);
+        context.register_handle(outbound_mp_interface.clone());
         context.register_handle(local_mp_interface);
 
         context.spawn_when_ready(move |handles| async move {
             let outbound_message_service = handles.expect_handle::<Dht>().outbound_requester();
             let state_machine = handles.expect_handle::<StateMachineHandle>();
             let base_node = handles.expect_handle::<LocalNodeCommsInterface>();
+            let validation_pipeline = TxValidationPipeline::spawn(mempool.clone(), config.num_validation_workers);
+            let inbound_handlers = MempoolInboundHandlers::new(
+                mempool_state_event_publisher,
+                mempool,
+                validation_pipeline,
+                outbound_mp_interface,
+            );
 
             let streams = MempoolStreams {
                 outbound_request_stream,
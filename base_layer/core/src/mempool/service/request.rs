@@ -34,6 +34,7 @@ pub enum MempoolRequest {
     GetState,
     GetTxStateByExcessSig(Signature),
     SubmitTransaction(Transaction),
+    GetFeeStats,
 }
 
 impl Display for MempoolRequest {
@@ -48,6 +49,7 @@ impl Display for MempoolRequest {
                 "SubmitTransaction ({})",
                 tx.body.kernels()[0].excess_sig.get_signature().to_hex()
             )),
+            MempoolRequest::GetFeeStats => f.write_str("GetFeeStats"),
         }
     }
 }
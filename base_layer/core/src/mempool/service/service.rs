@@ -47,7 +47,11 @@ use futures::{
 };
 use log::*;
 use rand::rngs::OsRng;
-use std::{convert::TryInto, sync::Arc, time::Duration};
+use std::{
+    convert::TryInto,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tari_common_types::waiting_requests::{generate_request_key, RequestKey, WaitingRequests};
 use tari_comms::peer_manager::NodeId;
 use tari_comms_dht::{
@@ -58,7 +62,7 @@ use tari_comms_dht::{
 use tari_crypto::tari_utilities::hex::Hex;
 use tari_p2p::{domain_message::DomainMessage, tari_message::TariMessageType};
 use tari_service_framework::{reply_channel, reply_channel::RequestContext};
-use tokio::task;
+use tokio::{task, time};
 
 const LOG_TARGET: &str = "c::mempool::service::service";
 
@@ -135,6 +139,12 @@ impl MempoolService {
             .expect("Mempool Service initialized without timeout_receiver_stream")
             .fuse();
         let mut request_receiver = streams.request_receiver;
+        let rebroadcast_check_interval = self.config.tx_rebroadcast_check_interval;
+        let mut rebroadcast_ticker = time::interval_at(
+            (Instant::now() + rebroadcast_check_interval).into(),
+            rebroadcast_check_interval,
+        )
+        .fuse();
 
         loop {
             futures::select! {
@@ -181,6 +191,11 @@ impl MempoolService {
                     }
                 },
 
+                // Periodically propagate aged transactions again
+                _ = rebroadcast_ticker.select_next_some() => {
+                    self.spawn_rebroadcast_aged_transactions();
+                },
+
                 // Timeout events for waiting requests
                 timeout_request_key = timeout_receiver_stream.select_next_some() => {
                     self.spawn_handle_request_timeout(timeout_request_key);
@@ -317,6 +332,21 @@ impl MempoolService {
         });
     }
 
+    fn spawn_rebroadcast_aged_transactions(&self) {
+        // Transactions are not propagated while busy with initial sync, as their validity can not be determined
+        if !(*self.state_machine.get_status_info_watch().borrow()).bootstrapped {
+            return;
+        }
+        let mut inbound_handlers = self.inbound_handlers.clone();
+        let min_age = self.config.tx_rebroadcast_age;
+        task::spawn(async move {
+            let result = inbound_handlers.rebroadcast_aged_transactions(min_age).await;
+            if let Err(e) = result {
+                error!(target: LOG_TARGET, "Failed to rebroadcast aged transactions: {:?}", e);
+            }
+        });
+    }
+
     fn spawn_handle_request_timeout(&self, timeout_request_key: u64) {
         let waiting_requests = self.waiting_requests.clone();
         task::spawn(async move {
@@ -22,6 +22,7 @@
 
 use crate::mempool::{
     service::{MempoolHandle, MempoolRequest, MempoolResponse},
+    MempoolFeeStats,
     MempoolServiceError,
     StateResponse,
     StatsResponse,
@@ -49,6 +50,7 @@ pub struct MempoolMockState {
     get_state: Arc<Mutex<StateResponse>>,
     get_tx_state_by_excess_sig: Arc<Mutex<TxStorageResponse>>,
     submit_transaction: Arc<Mutex<TxStorageResponse>>,
+    get_fee_stats: Arc<Mutex<MempoolFeeStats>>,
     calls: Arc<AtomicUsize>,
 }
 
@@ -67,6 +69,7 @@ impl Default for MempoolMockState {
             })),
             get_tx_state_by_excess_sig: Arc::new(Mutex::new(TxStorageResponse::NotStored)),
             submit_transaction: Arc::new(Mutex::new(TxStorageResponse::NotStored)),
+            get_fee_stats: Arc::new(Mutex::new(MempoolFeeStats::default())),
             calls: Arc::new(Default::default()),
         }
     }
@@ -89,6 +92,10 @@ impl MempoolMockState {
         *self.submit_transaction.lock().await = resp;
     }
 
+    pub async fn set_get_fee_stats_response(&self, stats: MempoolFeeStats) {
+        *self.get_fee_stats.lock().await = stats;
+    }
+
     fn inc_call_count(&self) {
         self.calls.fetch_add(1, Ordering::SeqCst);
     }
@@ -135,6 +142,7 @@ impl MempoolServiceMock {
             SubmitTransaction(_) => Ok(MempoolResponse::TxStorage(
                 self.state.submit_transaction.lock().await.clone(),
             )),
+            GetFeeStats => Ok(MempoolResponse::FeeStats(self.state.get_fee_stats.lock().await.clone())),
         }
     }
 }
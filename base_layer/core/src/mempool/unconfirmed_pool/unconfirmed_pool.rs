@@ -24,19 +24,21 @@ use crate::{
     blocks::Block,
     mempool::{
         consts::{MEMPOOL_UNCONFIRMED_POOL_STORAGE_CAPACITY, MEMPOOL_UNCONFIRMED_POOL_WEIGHT_TRANSACTION_SKIP_COUNT},
+        fee_stats::{FeeStatsTracker, MempoolFeeStats},
         priority::{FeePriority, PrioritizedTransaction},
         unconfirmed_pool::UnconfirmedPoolError,
     },
-    transactions::{transaction::Transaction, types::Signature},
+    transactions::{transaction::Transaction, types::Signature, weight::TransactionWeight},
 };
 use log::*;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap, HashSet},
     convert::TryFrom,
     sync::Arc,
+    time::{Duration, Instant},
 };
-use tari_crypto::tari_utilities::hex::Hex;
+use tari_crypto::tari_utilities::{hex::Hex, Hashable};
 
 pub const LOG_TARGET: &str = "c::mp::unconfirmed_pool::unconfirmed_pool_storage";
 
@@ -67,10 +69,14 @@ impl Default for UnconfirmedPoolConfig {
 /// priority. The txs_by_priority BTreeMap makes it easier to select the set of highest priority transactions that can
 /// be included in a block. The excess_sig of a transaction is used a key to uniquely identify a specific transaction in
 /// these containers.
+/// A transaction may spend the outputs of other transactions in the pool. It then depends on those transactions: it is
+/// only selected for a block together with them, so that their outputs are cut through, and it is discarded along with
+/// them.
 pub struct UnconfirmedPool {
     config: UnconfirmedPoolConfig,
     txs_by_signature: HashMap<Signature, PrioritizedTransaction>,
     txs_by_priority: BTreeMap<FeePriority, Signature>,
+    fee_stats: FeeStatsTracker,
 }
 
 impl UnconfirmedPool {
@@ -80,6 +86,7 @@ impl UnconfirmedPool {
             config,
             txs_by_signature: HashMap::new(),
             txs_by_priority: BTreeMap::new(),
+            fee_stats: FeeStatsTracker::new(),
         }
     }
 
@@ -88,17 +95,58 @@ impl UnconfirmedPool {
     }
 
     fn remove_lowest_priority_tx(&mut self) {
-        if let Some((priority, sig)) = self.txs_by_priority.iter().next().map(|(p, s)| (p.clone(), s.clone())) {
-            self.txs_by_signature.remove(&sig);
-            self.txs_by_priority.remove(&priority);
+        if let Some(sig) = self.txs_by_priority.values().next().cloned() {
+            self.discard_with_dependents(vec![sig]);
         }
     }
 
+    // Remove a single transaction from both containers
+    fn remove_tx(&mut self, tx_key: &Signature) -> Option<PrioritizedTransaction> {
+        let ptx = self.txs_by_signature.remove(tx_key)?;
+        self.txs_by_priority.remove(&ptx.priority);
+        self.fee_stats.remove(ptx.fee_per_gram, ptx.weight, ptx.inserted_at);
+        Some(ptx)
+    }
+
+    /// Remove the given transactions, and all of the transactions that depend on them, from the pool. Dependent
+    /// transactions spend outputs that will now never be mined, so they can not be kept.
+    pub fn discard_with_dependents(&mut self, tx_keys: Vec<Signature>) -> Vec<Arc<Transaction>> {
+        let mut removed_txs = Vec::new();
+        let mut discarded = tx_keys;
+        while !discarded.is_empty() {
+            let mut removed_keys = HashSet::new();
+            for tx_key in discarded {
+                if let Some(ptx) = self.remove_tx(&tx_key) {
+                    removed_txs.push(ptx.transaction);
+                    removed_keys.insert(tx_key);
+                }
+            }
+            discarded = self
+                .txs_by_signature
+                .iter()
+                .filter(|(_, ptx)| ptx.depends_on.iter().any(|k| removed_keys.contains(k)))
+                .map(|(k, _)| k.clone())
+                .collect();
+        }
+        removed_txs
+    }
+
     /// Insert a new transaction into the UnconfirmedPool. Low priority transactions will be removed to make space for
     /// higher priority transactions. The lowest priority transactions will be removed when the maximum capacity is
     /// reached and the new transaction has a higher priority than the currently stored lowest priority transaction.
-    #[allow(clippy::map_entry)]
     pub fn insert(&mut self, tx: Arc<Transaction>) -> Result<(), UnconfirmedPoolError> {
+        self.insert_with_dependencies(tx, Vec::new())
+    }
+
+    /// Insert a new transaction that spends the outputs of the unconfirmed transactions with the given excess
+    /// signatures. The transaction is not inserted if any of these transactions are no longer in the pool.
+    #[allow(clippy::map_entry)]
+    pub fn insert_with_dependencies(
+        &mut self,
+        tx: Arc<Transaction>,
+        depends_on: Vec<Signature>,
+    ) -> Result<(), UnconfirmedPoolError>
+    {
         let tx_key = tx
             .first_kernel_excess_sig()
             .ok_or_else(|| UnconfirmedPoolError::TransactionNoKernels)?;
@@ -109,15 +157,33 @@ impl UnconfirmedPool {
                 tx_key.get_signature().to_hex()
             );
             trace!(target: LOG_TARGET, "Transaction inserted: {}", tx);
-            let prioritized_tx = PrioritizedTransaction::try_from((*tx).clone())?;
+            let mut prioritized_tx = PrioritizedTransaction::try_from((*tx).clone())?;
+            prioritized_tx.depends_on = depends_on;
             if self.txs_by_signature.len() >= self.config.storage_capacity {
                 if prioritized_tx.priority < *self.lowest_priority() {
                     return Ok(());
                 }
                 self.remove_lowest_priority_tx();
             }
+            if !prioritized_tx
+                .depends_on
+                .iter()
+                .all(|k| self.txs_by_signature.contains_key(k))
+            {
+                debug!(
+                    target: LOG_TARGET,
+                    "Not inserting tx {} as a transaction it depends on is no longer in the unconfirmed pool",
+                    tx_key.get_signature().to_hex()
+                );
+                return Ok(());
+            }
             self.txs_by_priority
                 .insert(prioritized_tx.priority.clone(), tx_key.clone());
+            self.fee_stats.add(
+                prioritized_tx.fee_per_gram,
+                prioritized_tx.weight,
+                prioritized_tx.inserted_at,
+            );
             self.txs_by_signature.insert(tx_key.clone(), prioritized_tx);
         }
         Ok(())
@@ -137,21 +203,117 @@ impl UnconfirmedPool {
         self.txs_by_signature.contains_key(excess_sig)
     }
 
-    /// Returns a set of the highest priority unconfirmed transactions, that can be included in a block
+    /// Returns the excess signatures of the transactions in the pool that create outputs spent by the given transaction
+    pub fn unconfirmed_parents(&self, tx: &Transaction) -> Vec<Signature> {
+        self.txs_by_signature
+            .iter()
+            .filter(|(_, ptx)| {
+                ptx.transaction
+                    .body
+                    .outputs()
+                    .iter()
+                    .any(|o| tx.body.inputs().iter().any(|i| i.is_equal_to(o)))
+            })
+            .map(|(k, _)| k.clone())
+            .collect()
+    }
+
+    /// Returns the excess signatures and fees per gram of the other transactions in the pool that spend any of the
+    /// inputs of the given transaction
+    pub fn conflicting_txs(&self, tx: &Transaction) -> Vec<(Signature, u64)> {
+        let tx_key = tx.first_kernel_excess_sig();
+        self.txs_by_signature
+            .iter()
+            .filter(|(k, _)| Some(*k) != tx_key)
+            .filter(|(_, ptx)| {
+                ptx.transaction
+                    .body
+                    .inputs()
+                    .iter()
+                    .any(|input| tx.body.inputs().contains(input))
+            })
+            .map(|(k, ptx)| (k.clone(), ptx.fee_per_gram))
+            .collect()
+    }
+
+    /// Returns the given transactions along with all the unconfirmed transactions they depend on, directly or
+    /// indirectly. Transactions are ordered after the transactions that they depend on.
+    pub fn with_ancestors(&self, tx_keys: &[Signature]) -> Result<Vec<Arc<Transaction>>, UnconfirmedPoolError> {
+        let mut txs = Vec::new();
+        for tx_key in tx_keys {
+            self.collect_unselected_ancestors(tx_key, &HashSet::new(), &mut txs)?;
+        }
+        Ok(txs.into_iter().map(|(_, ptx)| ptx.transaction.clone()).collect())
+    }
+
+    // Appends the transaction to `candidates`, preceded by the transactions that it depends on, skipping any
+    // transaction that has already been selected or is already a candidate
+    fn collect_unselected_ancestors<'a>(
+        &'a self,
+        tx_key: &'a Signature,
+        selected: &HashSet<Signature>,
+        candidates: &mut Vec<(&'a Signature, &'a PrioritizedTransaction)>,
+    ) -> Result<(), UnconfirmedPoolError>
+    {
+        if selected.contains(tx_key) || candidates.iter().any(|(k, _)| *k == tx_key) {
+            return Ok(());
+        }
+        let ptx = self
+            .txs_by_signature
+            .get(tx_key)
+            .ok_or_else(|| UnconfirmedPoolError::StorageOutofSync)?;
+        for parent_key in &ptx.depends_on {
+            self.collect_unselected_ancestors(parent_key, selected, candidates)?;
+        }
+        candidates.push((tx_key, ptx));
+        Ok(())
+    }
+
+    /// Returns a set of the highest priority unconfirmed transactions, that can be included in a block. A transaction
+    /// that depends on other unconfirmed transactions is only selected together with them, and the transactions are
+    /// returned in an order where every transaction follows the transactions it depends on. Aggregating the returned
+    /// transactions cuts through the outputs they spend from one another, so the weight of those inputs and outputs
+    /// is not counted against `total_weight`.
     pub fn highest_priority_txs(&self, total_weight: u64) -> Result<Vec<Arc<Transaction>>, UnconfirmedPoolError> {
         let mut selected_txs: Vec<Arc<Transaction>> = Vec::new();
+        let mut selected_keys = HashSet::new();
+        let mut selected_outputs = HashSet::new();
+        let cut_through_weight = TransactionWeight::v1().calculate(0, 1, 1, 0);
         let mut curr_weight: u64 = 0;
         let mut curr_skip_count: usize = 0;
         for (_, tx_key) in self.txs_by_priority.iter().rev() {
-            let ptx = self
-                .txs_by_signature
-                .get(tx_key)
-                .ok_or_else(|| UnconfirmedPoolError::StorageOutofSync)?;
-
-            if curr_weight + ptx.weight <= total_weight {
-                if !UnconfirmedPool::find_duplicate_input(&selected_txs, &ptx.transaction) {
-                    curr_weight += ptx.weight;
-                    selected_txs.push(ptx.transaction.clone());
+            if selected_keys.contains(tx_key) {
+                continue;
+            }
+            let mut candidates = Vec::new();
+            self.collect_unselected_ancestors(tx_key, &selected_keys, &mut candidates)?;
+            let candidate_outputs = candidates
+                .iter()
+                .flat_map(|(_, ptx)| ptx.transaction.body.outputs().iter().map(Hashable::hash))
+                .collect::<HashSet<_>>();
+            let num_cut_through = candidates
+                .iter()
+                .flat_map(|(_, ptx)| ptx.transaction.body.inputs())
+                .map(Hashable::hash)
+                .filter(|hash| selected_outputs.contains(hash) || candidate_outputs.contains(hash))
+                .count() as u64;
+            let weight = candidates
+                .iter()
+                .map(|(_, ptx)| ptx.weight)
+                .sum::<u64>()
+                .saturating_sub(num_cut_through * cut_through_weight);
+
+            if curr_weight + weight <= total_weight {
+                if !candidates
+                    .iter()
+                    .any(|(_, ptx)| UnconfirmedPool::find_duplicate_input(&selected_txs, &ptx.transaction))
+                {
+                    curr_weight += weight;
+                    selected_outputs.extend(candidate_outputs);
+                    for (key, ptx) in candidates {
+                        selected_keys.insert(key.clone());
+                        selected_txs.push(ptx.transaction.clone());
+                    }
                 }
             } else {
                 // Check if some the next few txs with slightly lower priority wont fit in the remaining space.
@@ -179,15 +341,18 @@ impl UnconfirmedPool {
     /// Remove all published transactions from the UnconfirmedPool and discard all double spend transactions.
     /// Returns a list of all transactions that were removed the unconfirmed pool as a result of appearing in the block.
     fn discard_double_spends(&mut self, published_block: &Block) {
-        let mut removed_tx_keys = Vec::new();
-        for (tx_key, ptx) in self.txs_by_signature.iter() {
-            for input in ptx.transaction.body.inputs() {
-                if published_block.body.inputs().contains(input) {
-                    self.txs_by_priority.remove(&ptx.priority);
-                    removed_tx_keys.push(tx_key.clone());
-                }
-            }
-        }
+        let removed_tx_keys = self
+            .txs_by_signature
+            .iter()
+            .filter(|(_, ptx)| {
+                ptx.transaction
+                    .body
+                    .inputs()
+                    .iter()
+                    .any(|input| published_block.body.inputs().contains(input))
+            })
+            .map(|(tx_key, _)| tx_key.clone())
+            .collect::<Vec<_>>();
 
         for tx_key in &removed_tx_keys {
             trace!(
@@ -195,21 +360,27 @@ impl UnconfirmedPool {
                 "Removing double spends from unconfirmed pool: {:?}",
                 tx_key
             );
-            self.txs_by_signature.remove(&tx_key);
         }
+        self.discard_with_dependents(removed_tx_keys);
     }
 
     /// Remove all published transactions from the UnconfirmedPoolStorage and discard double spends
     pub fn remove_published_and_discard_double_spends(&mut self, published_block: &Block) -> Vec<Arc<Transaction>> {
         let mut removed_txs = Vec::new();
-        published_block.body.kernels().iter().for_each(|kernel| {
-            if let Some(ptx) = self.txs_by_signature.get(&kernel.excess_sig) {
-                self.txs_by_priority.remove(&ptx.priority);
-                if let Some(ptx) = self.txs_by_signature.remove(&kernel.excess_sig) {
-                    removed_txs.push(ptx.transaction);
-                }
+        let mut published_keys = HashSet::new();
+        for kernel in published_block.body.kernels() {
+            if let Some(ptx) = self.remove_tx(&kernel.excess_sig) {
+                removed_txs.push(ptx.transaction);
+                published_keys.insert(kernel.excess_sig.clone());
             }
-        });
+        }
+        // The outputs of the published transactions are now in the UTXO set, so transactions spending them no longer
+        // depend on them
+        if !published_keys.is_empty() {
+            for ptx in self.txs_by_signature.values_mut() {
+                ptx.depends_on.retain(|k| !published_keys.contains(k));
+            }
+        }
         // First remove published transactions before discarding double spends
         self.discard_double_spends(published_block);
 
@@ -219,25 +390,38 @@ impl UnconfirmedPool {
     /// Remove all unconfirmed transactions that have become time locked. This can happen when the chain height was
     /// reduced on some reorgs.
     pub fn remove_timelocked(&mut self, tip_height: u64) -> Vec<Arc<Transaction>> {
-        let mut removed_tx_keys: Vec<Signature> = Vec::new();
-        for (tx_key, ptx) in self.txs_by_signature.iter() {
-            if ptx.transaction.min_spendable_height() > tip_height + 1 {
-                self.txs_by_priority.remove(&ptx.priority);
-                removed_tx_keys.push(tx_key.clone());
-            }
-        }
-        let mut removed_txs: Vec<Arc<Transaction>> = Vec::new();
-        for tx_key in removed_tx_keys {
+        let removed_tx_keys = self
+            .txs_by_signature
+            .iter()
+            .filter(|(_, ptx)| ptx.transaction.min_spendable_height() > tip_height + 1)
+            .map(|(tx_key, _)| tx_key.clone())
+            .collect::<Vec<_>>();
+        for tx_key in &removed_tx_keys {
             trace!(
                 target: LOG_TARGET,
                 "Removing time locked transaction from unconfirmed pool: {:?}",
                 tx_key
             );
-            if let Some(ptx) = self.txs_by_signature.remove(&tx_key) {
-                removed_txs.push(ptx.transaction);
-            }
         }
-        removed_txs
+        self.discard_with_dependents(removed_tx_keys)
+    }
+
+    /// Returns the transactions that were last propagated at least `min_age` ago, and marks them as propagated now.
+    /// Transactions are returned after the unconfirmed transactions they depend on.
+    pub fn take_txs_due_for_rebroadcast(&mut self, min_age: Duration) -> Vec<Arc<Transaction>> {
+        let now = Instant::now();
+        let mut due = self
+            .txs_by_signature
+            .values_mut()
+            .filter(|ptx| now.duration_since(ptx.last_propagated) >= min_age)
+            .map(|ptx| {
+                ptx.last_propagated = now;
+                (ptx.depends_on.is_empty(), ptx.transaction.clone())
+            })
+            .collect::<Vec<_>>();
+        // Stable sort, independent transactions first
+        due.sort_by_key(|(independent, _)| !*independent);
+        due.into_iter().map(|(_, tx)| tx).collect()
     }
 
     /// Returns the total number of unconfirmed transactions stored in the UnconfirmedPool.
@@ -253,6 +437,11 @@ impl UnconfirmedPool {
             .collect()
     }
 
+    /// Returns the fee statistics of the transactions stored in the pool.
+    pub fn fee_stats(&self) -> MempoolFeeStats {
+        self.fee_stats.stats(Instant::now())
+    }
+
     /// Returns the total weight of all transactions stored in the pool.
     pub fn calculate_weight(&self) -> u64 {
         self.txs_by_signature
@@ -280,13 +469,14 @@ mod test {
         test_helpers::create_orphan_block,
         transactions::{
             fee::Fee,
-            helpers::TestParams,
+            helpers::{spend_utxos, TestParams},
             tari_amount::MicroTari,
             transaction::{KernelFeatures, UnblindedOutput},
             types::{CryptoFactories, HashDigest},
             SenderTransactionProtocol,
         },
         tx,
+        txn_schema,
     };
 
     #[test]
@@ -372,7 +562,7 @@ mod test {
         let double_spend_utxo = tx2.body.inputs().first().unwrap().clone();
         let double_spend_input = inputs.first().unwrap().clone();
 
-        let estimated_fee = Fee::calculate(20.into(), 1, 1, 1);
+        let estimated_fee = Fee::default().calculate(20.into(), 1, 1, 1, 0);
         let utxo = UnblindedOutput::new(INPUT_AMOUNT - estimated_fee, test_params.spend_key, None);
         stx_builder
             .with_input(double_spend_utxo, double_spend_input)
@@ -529,4 +719,157 @@ mod test {
 
         assert!(unconfirmed_pool.check_status());
     }
+
+    #[test]
+    fn test_chained_txs() {
+        let network = Network::LocalNet;
+        let consensus = ConsensusManagerBuilder::new(network).build();
+        let (parent, _, parent_outputs) = tx!(MicroTari(10_000), fee: MicroTari(20), inputs: 1, outputs: 1);
+        let (child, _, _) =
+            spend_utxos(txn_schema!(from: parent_outputs, to: vec![MicroTari(2_000)], fee: MicroTari(100)));
+        let parent = Arc::new(parent);
+        let child = Arc::new(child);
+        let parent_key = parent.first_kernel_excess_sig().unwrap().clone();
+
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
+            storage_capacity: 10,
+            weight_tx_skip_count: 3,
+        });
+        unconfirmed_pool.insert(parent.clone()).unwrap();
+        assert_eq!(unconfirmed_pool.unconfirmed_parents(&child), vec![parent_key.clone()]);
+        unconfirmed_pool
+            .insert_with_dependencies(child.clone(), vec![parent_key])
+            .unwrap();
+        assert_eq!(unconfirmed_pool.len(), 2);
+
+        // The child has the higher priority, but can only be selected together with its parent
+        let selected_txs = unconfirmed_pool.highest_priority_txs(child.calculate_weight()).unwrap();
+        assert_eq!(selected_txs, vec![parent.clone()]);
+        let desired_weight = parent.calculate_weight() + child.calculate_weight();
+        let selected_txs = unconfirmed_pool.highest_priority_txs(desired_weight).unwrap();
+        assert_eq!(selected_txs, vec![parent.clone(), child.clone()]);
+
+        // Aggregating the selected transactions cuts through the output spent by the child
+        let aggregated = (*parent).clone() + (*child).clone();
+        assert_eq!(aggregated.body.inputs().len(), 1);
+        assert_eq!(aggregated.body.outputs().len(), 2);
+        assert_eq!(aggregated.body.kernels().len(), 2);
+        assert!(aggregated
+            .validate_internal_consistency(&CryptoFactories::default(), None)
+            .is_ok());
+        // The weight of the cut through input and output is not counted when selecting the transactions
+        assert!(aggregated.calculate_weight() < desired_weight);
+        let selected_txs = unconfirmed_pool
+            .highest_priority_txs(aggregated.calculate_weight())
+            .unwrap();
+        assert_eq!(selected_txs, vec![parent.clone(), child.clone()]);
+
+        // Once the parent is published the child no longer depends on it
+        let published_block = create_orphan_block(0, vec![(*parent).clone()], &consensus);
+        let _ = unconfirmed_pool.remove_published_and_discard_double_spends(&published_block);
+        let selected_txs = unconfirmed_pool.highest_priority_txs(child.calculate_weight()).unwrap();
+        assert_eq!(selected_txs, vec![child]);
+
+        assert!(unconfirmed_pool.check_status());
+    }
+
+    #[test]
+    fn test_discard_dependent_txs() {
+        let network = Network::LocalNet;
+        let consensus = ConsensusManagerBuilder::new(network).build();
+        let (parent, _, parent_outputs) = tx!(MicroTari(10_000), fee: MicroTari(20), inputs: 1, outputs: 1);
+        let (child, _, _) = spend_utxos(txn_schema!(from: parent_outputs, to: vec![MicroTari(2_000)]));
+        let mut double_spend = tx!(MicroTari(5_000), fee: MicroTari(50), inputs: 1, outputs: 1).0;
+        double_spend.body.inputs_mut()[0] = parent.body.inputs()[0].clone();
+        let parent = Arc::new(parent);
+        let child = Arc::new(child);
+        let parent_key = parent.first_kernel_excess_sig().unwrap().clone();
+
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
+            storage_capacity: 10,
+            weight_tx_skip_count: 3,
+        });
+        unconfirmed_pool.insert(parent.clone()).unwrap();
+        unconfirmed_pool
+            .insert_with_dependencies(child.clone(), vec![parent_key])
+            .unwrap();
+
+        // Publishing a double spend of the parent's input discards the parent, and with it the child
+        let published_block = create_orphan_block(0, vec![double_spend], &consensus);
+        let _ = unconfirmed_pool.remove_published_and_discard_double_spends(&published_block);
+        assert_eq!(
+            unconfirmed_pool.has_tx_with_excess_sig(&parent.body.kernels()[0].excess_sig),
+            false
+        );
+        assert_eq!(
+            unconfirmed_pool.has_tx_with_excess_sig(&child.body.kernels()[0].excess_sig),
+            false
+        );
+
+        // A transaction is not inserted when a transaction it depends on is no longer in the pool
+        unconfirmed_pool
+            .insert_with_dependencies(child.clone(), vec![parent.body.kernels()[0].excess_sig.clone()])
+            .unwrap();
+        assert_eq!(unconfirmed_pool.len(), 0);
+
+        assert!(unconfirmed_pool.check_status());
+    }
+
+    #[test]
+    fn test_txs_due_for_rebroadcast() {
+        let (parent, _, parent_outputs) = tx!(MicroTari(10_000), fee: MicroTari(20), inputs: 1, outputs: 1);
+        let (child, _, _) = spend_utxos(txn_schema!(from: parent_outputs, to: vec![MicroTari(2_000)]));
+        let parent = Arc::new(parent);
+        let child = Arc::new(child);
+        let parent_key = parent.first_kernel_excess_sig().unwrap().clone();
+
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
+            storage_capacity: 10,
+            weight_tx_skip_count: 3,
+        });
+        unconfirmed_pool.insert(parent.clone()).unwrap();
+        unconfirmed_pool
+            .insert_with_dependencies(child.clone(), vec![parent_key])
+            .unwrap();
+
+        // Newly inserted transactions have only just been received
+        assert!(unconfirmed_pool
+            .take_txs_due_for_rebroadcast(Duration::from_secs(3600))
+            .is_empty());
+        // The parent is propagated before the transaction that spends its output
+        let due = unconfirmed_pool.take_txs_due_for_rebroadcast(Duration::from_secs(0));
+        assert_eq!(due, vec![parent, child]);
+        // Taken transactions are marked as propagated
+        assert!(unconfirmed_pool
+            .take_txs_due_for_rebroadcast(Duration::from_secs(3600))
+            .is_empty());
+        assert_eq!(unconfirmed_pool.len(), 2);
+    }
+
+    #[test]
+    fn test_fee_stats() {
+        let tx1 = Arc::new(tx!(MicroTari(5_000), fee: MicroTari(50), inputs: 2, outputs: 1).0);
+        let tx2 = Arc::new(tx!(MicroTari(5_000), fee: MicroTari(20), inputs: 4, outputs: 1).0);
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig::default());
+        unconfirmed_pool.insert_txs(vec![tx1.clone(), tx2.clone()]).unwrap();
+
+        let stats = unconfirmed_pool.fee_stats();
+        assert_eq!(stats.tx_count, 2);
+        assert_eq!(stats.total_weight, unconfirmed_pool.calculate_weight());
+        assert_eq!(stats.age_distribution.iter().map(|b| b.tx_count).sum::<usize>(), 2);
+
+        let published_block = create_orphan_block(
+            0,
+            vec![(*tx1).clone()],
+            &ConsensusManagerBuilder::new(Network::LocalNet).build(),
+        );
+        let _ = unconfirmed_pool.remove_published_and_discard_double_spends(&published_block);
+        let stats = unconfirmed_pool.fee_stats();
+        assert_eq!(stats.tx_count, 1);
+        assert_eq!(stats.total_weight, tx2.calculate_weight());
+        assert_eq!(
+            stats.fee_per_gram_percentiles.p50,
+            tx2.body.get_total_fee().0 / tx2.calculate_weight()
+        );
+    }
 }
@@ -0,0 +1,186 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::{
+    mempool::{error::MempoolError, Mempool, TxStorageResponse},
+    transactions::{transaction::Transaction, types::Signature},
+};
+use futures::{
+    channel::{mpsc, oneshot},
+    SinkExt,
+    StreamExt,
+};
+use log::*;
+use std::{cmp::max, sync::Arc};
+use tari_crypto::tari_utilities::hex::Hex;
+use tokio::task;
+
+const LOG_TARGET: &str = "c::mp::validation_pipeline";
+
+type SubmissionResult = Result<(TxStorageResponse, Vec<Signature>), MempoolError>;
+
+/// The ingress pipeline for transactions submitted to the Mempool.
+///
+/// The stateless checks of a transaction (signatures, range proofs and scripts) do not depend on the blockchain or on
+/// the other transactions in the Mempool. The pipeline runs these checks for up to `num_workers` transactions at a
+/// time on the blocking thread pool, without holding the Mempool lock. The validated transactions are then committed,
+/// which runs the checks against the blockchain and inserts them into the Mempool, one at a time and in the order in
+/// which they were submitted. A transaction is therefore never committed before a transaction that was submitted
+/// earlier, such as the transaction that creates the outputs it spends.
+#[derive(Clone)]
+pub struct TxValidationPipeline {
+    sender: mpsc::Sender<(Arc<Transaction>, oneshot::Sender<SubmissionResult>)>,
+}
+
+impl TxValidationPipeline {
+    /// Spawn a pipeline that commits transactions to the given Mempool
+    pub fn spawn(mempool: Mempool, num_workers: usize) -> Self {
+        let num_workers = max(num_workers, 1);
+        let (sender, receiver) = mpsc::channel(num_workers);
+        task::spawn(Self::run(mempool, receiver, num_workers));
+        Self { sender }
+    }
+
+    async fn run(
+        mempool: Mempool,
+        receiver: mpsc::Receiver<(Arc<Transaction>, oneshot::Sender<SubmissionResult>)>,
+        num_workers: usize,
+    )
+    {
+        receiver
+            .map(|(tx, reply)| {
+                let mempool = mempool.clone();
+                async move {
+                    let validated = task::spawn_blocking(move || mempool.validate_stateless(&tx).map(|_| tx)).await;
+                    (validated, reply)
+                }
+            })
+            // Up to `num_workers` transactions are validated concurrently, and the results are yielded in submission
+            // order
+            .buffered(num_workers)
+            .for_each(|(validated, reply)| {
+                let mempool = mempool.clone();
+                async move {
+                    let result = match validated {
+                        Ok(Ok(tx)) => task::spawn_blocking(move || mempool.insert_validated(tx))
+                            .await
+                            .map_err(|e| MempoolError::BlockingTaskSpawnError(e.to_string()))
+                            .and_then(|inner_result| inner_result),
+                        Ok(Err(e)) => {
+                            warn!(target: LOG_TARGET, "Validation failed due to error:{}", e);
+                            Ok((TxStorageResponse::NotStored, Vec::new()))
+                        },
+                        Err(e) => Err(MempoolError::BlockingTaskSpawnError(e.to_string())),
+                    };
+                    // The submitter may have stopped waiting for the result
+                    let _ = reply.send(result);
+                }
+            })
+            .await;
+        debug!(target: LOG_TARGET, "Transaction validation pipeline has shut down");
+    }
+
+    /// Validate the transaction and insert it into the Mempool once all of the transactions submitted before it have
+    /// been committed. Returns the storage response and the excess signatures of the conflicting transactions that the
+    /// transaction replaced, if any.
+    pub async fn submit(&mut self, tx: Arc<Transaction>) -> SubmissionResult {
+        trace!(
+            target: LOG_TARGET,
+            "Transaction ({}) submitted to the validation pipeline",
+            tx.first_kernel_excess_sig()
+                .map(|sig| sig.get_signature().to_hex())
+                .unwrap_or_else(|| "None".into())
+        );
+        let (reply_sender, reply_receiver) = oneshot::channel();
+        self.sender
+            .send((tx, reply_sender))
+            .await
+            .map_err(|_| MempoolError::ValidationPipelineShutdown)?;
+        reply_receiver
+            .await
+            .map_err(|_| MempoolError::ValidationPipelineShutdown)?
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        mempool::MempoolConfig,
+        transactions::tari_amount::MicroTari,
+        tx,
+        validation::{MempoolTransactionValidation, ValidationError},
+    };
+    use futures::future;
+    use std::{sync::Mutex, thread, time::Duration};
+
+    // Takes a long time to validate the given transaction
+    struct SlowValidator(Signature);
+
+    impl MempoolTransactionValidation for SlowValidator {
+        fn validate(&self, tx: &Transaction) -> Result<(), ValidationError> {
+            if tx.first_kernel_excess_sig() == Some(&self.0) {
+                thread::sleep(Duration::from_millis(500));
+            }
+            Ok(())
+        }
+    }
+
+    // Records the order in which transactions are committed
+    struct CommitRecorder(Arc<Mutex<Vec<Signature>>>);
+
+    impl MempoolTransactionValidation for CommitRecorder {
+        fn validate(&self, tx: &Transaction) -> Result<(), ValidationError> {
+            self.0.lock().unwrap().extend(tx.first_kernel_excess_sig().cloned());
+            Ok(())
+        }
+    }
+
+    #[tokio_macros::test]
+    async fn commits_in_submission_order() {
+        let txs = (0..4)
+            .map(|_| Arc::new(tx!(MicroTari(10_000), fee: MicroTari(20), inputs: 1, outputs: 1).0))
+            .collect::<Vec<_>>();
+        let tx_keys = txs
+            .iter()
+            .map(|tx| tx.first_kernel_excess_sig().unwrap().clone())
+            .collect::<Vec<_>>();
+
+        let committed = Arc::new(Mutex::new(Vec::new()));
+        let mempool = Mempool::new(MempoolConfig::default(), Arc::new(CommitRecorder(committed.clone())))
+            .with_stateless_validator(Arc::new(SlowValidator(tx_keys[0].clone())));
+        let pipeline = TxValidationPipeline::spawn(mempool.clone(), 4);
+
+        let results = future::join_all(txs.iter().map(|tx| {
+            let mut pipeline = pipeline.clone();
+            let tx = tx.clone();
+            async move { pipeline.submit(tx).await }
+        }))
+        .await;
+        for result in results {
+            assert_eq!(result.unwrap().0, TxStorageResponse::UnconfirmedPool);
+        }
+        // The first transaction is validated last, but is still committed first
+        assert_eq!(*committed.lock().unwrap(), tx_keys);
+        assert_eq!(mempool.stats().unwrap().unconfirmed_txs, 4);
+    }
+}
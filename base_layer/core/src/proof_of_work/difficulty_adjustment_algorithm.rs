@@ -0,0 +1,74 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::proof_of_work::PowAlgorithm;
+use std::collections::HashMap;
+
+/// The difficulty adjustment algorithm (DAA) that a network uses to calculate the target difficulty of each PoW
+/// algorithm. The target time of each PoW algorithm sets its share of the blocks, and so weights the algorithms
+/// against each other; the DAA sets how the target difficulty follows the observed solve times.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DifficultyAdjustmentAlgorithm {
+    /// LWMA-1 over the network's difficulty block window, for every PoW algorithm
+    Lwma,
+    /// LWMA-1 with a block window for each PoW algorithm. An algorithm that finds a small share of the blocks covers a
+    /// long stretch of time with the network's block window, and can use a shorter window to respond to hash rate
+    /// changes sooner. PoW algorithms that are not listed use the network's difficulty block window.
+    LwmaPerAlgorithm(HashMap<PowAlgorithm, u64>),
+}
+
+impl DifficultyAdjustmentAlgorithm {
+    /// The number of blocks of `pow_algo` over which the target difficulty is calculated, given the network's
+    /// difficulty block window
+    pub fn block_window(&self, pow_algo: PowAlgorithm, network_block_window: u64) -> u64 {
+        match self {
+            DifficultyAdjustmentAlgorithm::Lwma => network_block_window,
+            DifficultyAdjustmentAlgorithm::LwmaPerAlgorithm(windows) => {
+                windows.get(&pow_algo).copied().unwrap_or(network_block_window)
+            },
+        }
+    }
+}
+
+impl Default for DifficultyAdjustmentAlgorithm {
+    fn default() -> Self {
+        DifficultyAdjustmentAlgorithm::Lwma
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn block_window() {
+        assert_eq!(
+            DifficultyAdjustmentAlgorithm::Lwma.block_window(PowAlgorithm::Monero, 90),
+            90
+        );
+        let mut windows = HashMap::new();
+        windows.insert(PowAlgorithm::Monero, 60);
+        let daa = DifficultyAdjustmentAlgorithm::LwmaPerAlgorithm(windows);
+        assert_eq!(daa.block_window(PowAlgorithm::Monero, 90), 60);
+        assert_eq!(daa.block_window(PowAlgorithm::Sha3, 90), 90);
+    }
+}
@@ -164,6 +164,30 @@ mod test {
         assert_eq!(dif.get_difficulty(), None);
     }
 
+    #[test]
+    fn lwma_calculates_the_linearly_weighted_target() {
+        // With a 3 block window and a 60s target time the weights of the solve times are 1, 2 and 3, and the target is
+        // ceil(average difficulty * 60 * (1 + 2 + 3) / weighted solve times)
+        let target = |samples: &[(u64, u64)]| {
+            let mut dif = LinearWeightedMovingAverage::new(3, 60, 60 * 6);
+            for (timestamp, difficulty) in samples {
+                dif.add_back((*timestamp).into(), (*difficulty).into());
+            }
+            dif.get_difficulty().unwrap().as_u64()
+        };
+        // Solve times on target: 120 * 360 / (60 + 120 + 180) = 120, the average difficulty
+        assert_eq!(target(&[(0, 1), (60, 90), (120, 120), (180, 150)]), 120);
+        // Solve times of 30, 60 and 120s: ceil(100 * 360 / (30 + 120 + 360)) = ceil(70.59) = 71
+        assert_eq!(target(&[(0, 1), (30, 100), (90, 100), (210, 100)]), 71);
+        // The solve time of 880s is clamped to 360s: ceil(100 * 360 / (60 + 120 + 1080)) = ceil(28.57) = 29
+        assert_eq!(target(&[(0, 1), (60, 100), (120, 100), (1000, 100)]), 29);
+        // A timestamp before its predecessor counts as a 1s solve time, and the next solve time is measured from
+        // there: ceil(100 * 360 / (60 + 2 + 147)) = ceil(172.25) = 173
+        assert_eq!(target(&[(0, 1), (60, 100), (50, 100), (110, 100)]), 173);
+        // Only the most recent samples within the window are used
+        assert_eq!(target(&[(0, 1_000), (60, 1), (120, 90), (180, 120), (240, 150)]), 120);
+    }
+
     #[test]
     fn lwma_is_at_capacity() {
         // This is important to check because using a VecDeque can cause bugs unless the following is accounted for
@@ -25,6 +25,11 @@ pub(crate) mod difficulty;
 #[cfg(any(feature = "base_node", feature = "transactions"))]
 pub use difficulty::{Difficulty, DifficultyAdjustment};
 
+#[cfg(any(feature = "base_node", feature = "transactions"))]
+mod difficulty_adjustment_algorithm;
+#[cfg(any(feature = "base_node", feature = "transactions"))]
+pub use difficulty_adjustment_algorithm::DifficultyAdjustmentAlgorithm;
+
 #[cfg(any(feature = "base_node", feature = "transactions"))]
 mod error;
 #[cfg(any(feature = "base_node", feature = "transactions"))]
@@ -49,7 +54,7 @@ pub use proof_of_work_algorithm::PowAlgorithm;
 #[cfg(feature = "base_node")]
 mod sha3_pow;
 #[cfg(feature = "base_node")]
-pub use sha3_pow::sha3_difficulty;
+pub use sha3_pow::{sha3_difficulty, sha3_mining_blob};
 #[cfg(all(test, feature = "base_node"))]
 pub use sha3_pow::test as sha3_test;
 
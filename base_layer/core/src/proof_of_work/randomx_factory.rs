@@ -6,12 +6,23 @@ use std::{
     sync::{Arc, Mutex, RwLock},
     time::Instant,
 };
+pub use tari_common::configuration::RandomXMode;
 
 const LOG_TARGET: &str = "c::pow::randomx_factory";
 
-#[derive(Default)]
+#[derive(Debug, Clone, Copy, Default)]
 pub struct RandomXConfig {
     pub use_large_pages: bool,
+    pub mode: RandomXMode,
+}
+
+impl RandomXConfig {
+    pub fn with_mode(mode: RandomXMode) -> Self {
+        Self {
+            mode,
+            ..Default::default()
+        }
+    }
 }
 
 impl From<&RandomXConfig> for RandomXFlag {
@@ -20,6 +31,9 @@ impl From<&RandomXConfig> for RandomXFlag {
         if source.use_large_pages {
             result |= RandomXFlag::FLAG_LARGE_PAGES
         }
+        if source.mode == RandomXMode::Fast {
+            result |= RandomXFlag::FLAG_FULL_MEM
+        }
         result
     }
 }
@@ -27,17 +41,20 @@ impl From<&RandomXConfig> for RandomXFlag {
 #[derive(Clone)]
 pub struct RandomXVMInstance {
     // Note: If the cache and dataset drops, the vm will be wonky, so have to store all
-    // three for now
-    instance: Arc<Mutex<(RandomXVM, RandomXCache, RandomXDataset)>>,
+    // three for now. Only VMs in fast mode have a dataset.
+    instance: Arc<Mutex<(RandomXVM, RandomXCache, Option<RandomXDataset>)>>,
 }
 
 impl RandomXVMInstance {
     // Note: Can maybe even get more gains by creating a new VM and sharing the dataset and cache
-    pub fn new(key: &[u8]) -> Result<Self, RandomXError> {
-        let flags = RandomXFlag::get_recommended_flags();
+    pub fn new(key: &[u8], config: &RandomXConfig) -> Result<Self, RandomXError> {
+        let flags = RandomXFlag::from(config);
         let cache = RandomXCache::new(flags, key)?;
-        let dataset = RandomXDataset::new(flags, &cache, 0)?;
-        let vm = RandomXVM::new(flags, Some(&cache), Some(&dataset))?;
+        let dataset = match config.mode {
+            RandomXMode::Fast => Some(RandomXDataset::new(flags, &cache, 0)?),
+            RandomXMode::Light => None,
+        };
+        let vm = RandomXVM::new(flags, Some(&cache), dataset.as_ref())?;
 
         Ok(Self {
             instance: Arc::new(Mutex::new((vm, cache, dataset))),
@@ -52,6 +69,8 @@ impl RandomXVMInstance {
 unsafe impl Send for RandomXVMInstance {}
 unsafe impl Sync for RandomXVMInstance {}
 
+/// Creates the RandomX VMs that verify Monero merge mined blocks. VMs are cached by seed hash, so the cache and, in
+/// fast mode, the dataset for a seed hash are only built once.
 // Thread safe impl of the inner impl
 #[derive(Clone)]
 pub struct RandomXFactory {
@@ -82,16 +101,19 @@ impl RandomXFactory {
 }
 
 struct RandomXFactoryInner {
-    // config: RandomXConfig,
+    config: RandomXConfig,
     vms: HashMap<Vec<u8>, (Instant, RandomXVMInstance)>,
     max_vms: usize,
 }
 
 impl RandomXFactoryInner {
-    pub fn new(_config: RandomXConfig, max_vms: usize) -> Self {
-        debug!(target: LOG_TARGET, "RandomX factory started with {} max VMs", max_vms);
+    pub fn new(config: RandomXConfig, max_vms: usize) -> Self {
+        debug!(
+            target: LOG_TARGET,
+            "RandomX factory started with {} max VMs in {} mode", max_vms, config.mode
+        );
         Self {
-            // config,
+            config,
             vms: Default::default(),
             max_vms,
         }
@@ -118,12 +140,40 @@ impl RandomXFactoryInner {
             }
         }
 
-        // TODO: put config in.
-
-        let vm = RandomXVMInstance::new(&key)?;
+        let start = Instant::now();
+        let vm = RandomXVMInstance::new(&key, &self.config)?;
+        debug!(
+            target: LOG_TARGET,
+            "Created RandomX VM in {} mode in {:.2?}",
+            self.config.mode,
+            start.elapsed()
+        );
 
         self.vms.insert(Vec::from(key), (Instant::now(), vm.clone()));
 
         Ok(vm)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn light_mode_vms_are_cached_by_seed_hash() {
+        let factory = RandomXFactory::new(RandomXConfig::with_mode(RandomXMode::Light), 1);
+        let input = b"Tari merge mining";
+        let vm = factory.create(b"seed 1").unwrap();
+        let hash = vm.calculate_hash(input).unwrap();
+        let same_vm = factory.create(b"seed 1").unwrap();
+        assert!(Arc::ptr_eq(&vm.instance, &same_vm.instance));
+        assert_eq!(same_vm.calculate_hash(input).unwrap(), hash);
+
+        // The oldest VM is dropped when there are too many
+        let other_vm = factory.create(b"seed 2").unwrap();
+        assert_ne!(other_vm.calculate_hash(input).unwrap(), hash);
+        let recreated_vm = factory.create(b"seed 1").unwrap();
+        assert!(!Arc::ptr_eq(&vm.instance, &recreated_vm.instance));
+        assert_eq!(recreated_vm.calculate_hash(input).unwrap(), hash);
+    }
+}
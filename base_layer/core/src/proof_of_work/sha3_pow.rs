@@ -52,6 +52,21 @@ pub fn sha3_hash(header: &BlockHeader) -> Vec<u8> {
         .to_vec()
 }
 
+/// The bytes of the SHA3 mining hash that come before and after the nonce. A miner that does not know the header
+/// layout can mine the header by hashing `before || nonce (little-endian) || after`, as [sha3_hash] does.
+pub fn sha3_mining_blob(header: &BlockHeader) -> (Vec<u8>, Vec<u8>) {
+    let mut before = Vec::with_capacity(2 + 8 + 32 + 8 + 32 * 4);
+    before.extend_from_slice(&header.version.to_le_bytes());
+    before.extend_from_slice(&header.height.to_le_bytes());
+    before.extend_from_slice(header.prev_hash.as_bytes());
+    before.extend_from_slice(&header.timestamp.as_u64().to_le_bytes());
+    before.extend_from_slice(header.output_mr.as_bytes());
+    before.extend_from_slice(header.range_proof_mr.as_bytes());
+    before.extend_from_slice(header.kernel_mr.as_bytes());
+    before.extend_from_slice(header.total_kernel_offset.as_bytes());
+    (before, header.pow.to_bytes())
+}
+
 fn sha3_difficulty_with_hash(header: &BlockHeader) -> (Difficulty, Vec<u8>) {
     let hash = sha3_hash(header);
     let hash = Sha3_256::digest(&hash);
@@ -63,9 +78,14 @@ fn sha3_difficulty_with_hash(header: &BlockHeader) -> (Difficulty, Vec<u8>) {
 pub mod test {
     use crate::{
         blocks::BlockHeader,
-        proof_of_work::{sha3_pow::sha3_difficulty, Difficulty, PowAlgorithm},
+        proof_of_work::{
+            sha3_pow::{sha3_difficulty, sha3_hash, sha3_mining_blob},
+            Difficulty,
+            PowAlgorithm,
+        },
     };
     use chrono::{DateTime, NaiveDate, Utc};
+    use sha3::{Digest, Sha3_256};
 
     /// A simple example miner. It starts at nonce = 0 and iterates until it finds a header hash that meets the desired
     /// target block
@@ -92,4 +112,19 @@ pub mod test {
         header.nonce = 1;
         assert_eq!(sha3_difficulty(&header), Difficulty::from(2));
     }
+
+    #[test]
+    fn mining_blob_matches_hash() {
+        let mut header = get_header();
+        header.height = 42;
+        header.nonce = 0x0102_0304_0506_0708;
+        let (before, after) = sha3_mining_blob(&header);
+        let hash = Sha3_256::new()
+            .chain(&before)
+            .chain(header.nonce.to_le_bytes())
+            .chain(&after)
+            .finalize()
+            .to_vec();
+        assert_eq!(hash, sha3_hash(&header));
+    }
 }
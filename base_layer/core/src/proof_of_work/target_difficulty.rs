@@ -120,6 +120,8 @@ impl AchievedTargetDifficulty {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::proof_of_work::DifficultyAdjustmentAlgorithm;
+    use std::collections::HashMap;
 
     #[test]
     fn it_calculates_the_target_difficulty() {
@@ -135,4 +137,74 @@ mod test {
 
         assert_eq!(target_difficulties.calculate(1.into(), 400.into()), 100.into());
     }
+
+    // Header timestamps and achieved difficulties of a chain with a 60 second target time, followed by the target
+    // difficulty of LWMA-1 over a 5 block window after each header. The targets are worked out from the LWMA-1
+    // formula, target = ceil(average difficulty * target time * n(n+1)/2 / sum(i * solve time i)), for example:
+    // - after the 6th header the solve times are 60, 60, 60, 60 and 50s, so the target is ceil(101 * 60 * 15 / (60 +
+    //   120 + 180 + 240 + 250)) = ceil(106.94) = 107
+    // - after the 11th header the solve times are 30, 65, 70, 100 and 360s, the maximum block time, so the target is
+    //   ceil(100 * 60 * 15 / (30 + 130 + 210 + 400 + 1800)) = ceil(35.02) = 36
+    const RECORDED_HEADERS: [(u64, u64); 15] = [
+        (60, 100),
+        (120, 100),
+        (180, 100),
+        (240, 100),
+        (300, 100),
+        (350, 105),
+        (380, 128),
+        (445, 123),
+        (515, 116),
+        (615, 94),
+        (975, 39),
+        (976, 46),
+        (977, 55),
+        (978, 75),
+        (979, 148),
+    ];
+    const RECORDED_TARGETS: [u64; 15] = [1, 100, 100, 100, 100, 107, 136, 130, 120, 94, 36, 39, 47, 67, 175];
+
+    /// Replays the headers through a target difficulty window for the given DAA, returning the target difficulty
+    /// calculated after each header
+    fn replay(algorithm: &DifficultyAdjustmentAlgorithm, headers: &[(u64, u64)]) -> Vec<u64> {
+        let block_window = algorithm.block_window(PowAlgorithm::Sha3, 5) as usize;
+        let mut window = TargetDifficultyWindow::new(block_window, 60, 60 * 6);
+        headers
+            .iter()
+            .map(|(timestamp, difficulty)| {
+                window.add_back((*timestamp).into(), (*difficulty).into());
+                window.calculate(1.into(), u64::MAX.into()).as_u64()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lwma_matches_recorded_targets() {
+        assert_eq!(
+            replay(&DifficultyAdjustmentAlgorithm::Lwma, &RECORDED_HEADERS),
+            RECORDED_TARGETS.to_vec()
+        );
+    }
+
+    #[test]
+    fn lwma_per_algorithm_window() {
+        // Settings for other algorithms do not affect SHA3
+        let mut windows = HashMap::new();
+        windows.insert(PowAlgorithm::Monero, 2);
+        let targets = replay(
+            &DifficultyAdjustmentAlgorithm::LwmaPerAlgorithm(windows.clone()),
+            &RECORDED_HEADERS,
+        );
+        assert_eq!(targets, RECORDED_TARGETS.to_vec());
+
+        // A shorter window follows the long solve time of the 11th header more closely. After the 6th header the solve
+        // times are 60 and 50s, so the target is ceil(102.5 * 60 * 3 / (60 + 100)) = ceil(115.31) = 116.
+        windows.insert(PowAlgorithm::Sha3, 2);
+        let targets = replay(
+            &DifficultyAdjustmentAlgorithm::LwmaPerAlgorithm(windows),
+            &RECORDED_HEADERS,
+        );
+        assert_eq!(targets[..11], [1, 100, 100, 100, 100, 116, 191, 142, 105, 70, 15]);
+        assert!(targets[10] < RECORDED_TARGETS[10]);
+    }
 }
@@ -27,18 +27,25 @@ use crate::{
     tari_utilities::convert::try_convert_all,
     transactions::{
         aggregated_body::AggregateBody,
+        asset::AssetOutputFeatures,
         bullet_rangeproofs::BulletRangeProof,
+        covenant::Covenant,
+        htlc::{HashTimeLock, HtlcWitness},
+        multisig::{MultisigLock, MultisigWitness},
+        script::{ScriptWitness, TariScript},
         tari_amount::MicroTari,
         transaction::{
             KernelFeatures,
             OutputFeatures,
+            OutputFeaturesVersion,
             OutputFlags,
+            RangeProofType,
             Transaction,
             TransactionInput,
             TransactionKernel,
             TransactionOutput,
         },
-        types::{BlindingFactor, Commitment},
+        types::{BlindingFactor, Commitment, PublicKey},
     },
 };
 use std::convert::{TryFrom, TryInto};
@@ -71,6 +78,7 @@ impl TryFrom<proto::types::TransactionKernel> for TransactionKernel {
             excess_sig,
             fee: MicroTari::from(kernel.fee),
             lock_height: kernel.lock_height,
+            burn_amount: MicroTari::from(kernel.burn_amount),
         })
     }
 }
@@ -83,6 +91,7 @@ impl From<TransactionKernel> for proto::types::TransactionKernel {
             excess_sig: Some(kernel.excess_sig.into()),
             fee: kernel.fee.into(),
             lock_height: kernel.lock_height,
+            burn_amount: kernel.burn_amount.into(),
         }
     }
 }
@@ -104,7 +113,22 @@ impl TryFrom<proto::types::TransactionInput> for TransactionInput {
             .ok_or_else(|| "Transaction output commitment not provided".to_string())?
             .map_err(|err| err.to_string())?;
 
-        Ok(Self { features, commitment })
+        let covenant = Covenant::from_bytes(&input.covenant).map_err(|err| err.to_string())?;
+
+        let htlc_witness = input.htlc_witness.map(TryInto::try_into).transpose()?;
+
+        let multisig_witness = input.multisig_witness.map(TryInto::try_into).transpose()?;
+
+        let script_witness = input.script_witness.map(TryInto::try_into).transpose()?;
+
+        Ok(Self {
+            features,
+            commitment,
+            covenant,
+            htlc_witness,
+            multisig_witness,
+            script_witness,
+        })
     }
 }
 
@@ -113,6 +137,127 @@ impl From<TransactionInput> for proto::types::TransactionInput {
         Self {
             features: Some(output.features.into()),
             commitment: Some(output.commitment.into()),
+            covenant: output.covenant.to_bytes(),
+            htlc_witness: output.htlc_witness.map(Into::into),
+            multisig_witness: output.multisig_witness.map(Into::into),
+            script_witness: output.script_witness.map(Into::into),
+        }
+    }
+}
+
+//---------------------------------- ScriptWitness --------------------------------------------//
+
+impl TryFrom<proto::types::ScriptWitness> for ScriptWitness {
+    type Error = String;
+
+    fn try_from(witness: proto::types::ScriptWitness) -> Result<Self, Self::Error> {
+        let kernel_excess = witness
+            .kernel_excess
+            .map(|commit| Commitment::from_bytes(&commit.data))
+            .ok_or_else(|| "Script witness kernel excess not provided".to_string())?
+            .map_err(|err| err.to_string())?;
+        let stack = ScriptWitness::stack_from_bytes(&witness.stack).map_err(|err| err.to_string())?;
+
+        Ok(Self { kernel_excess, stack })
+    }
+}
+
+impl From<ScriptWitness> for proto::types::ScriptWitness {
+    fn from(witness: ScriptWitness) -> Self {
+        Self {
+            stack: witness.stack_to_bytes(),
+            kernel_excess: Some(witness.kernel_excess.into()),
+        }
+    }
+}
+
+//---------------------------------- MultisigWitness --------------------------------------------//
+
+impl TryFrom<proto::types::MultisigWitness> for MultisigWitness {
+    type Error = String;
+
+    fn try_from(witness: proto::types::MultisigWitness) -> Result<Self, Self::Error> {
+        let kernel_excess = witness
+            .kernel_excess
+            .map(|commit| Commitment::from_bytes(&commit.data))
+            .ok_or_else(|| "Multisig witness kernel excess not provided".to_string())?
+            .map_err(|err| err.to_string())?;
+        let signature = witness
+            .signature
+            .map(TryInto::try_into)
+            .ok_or_else(|| "Multisig witness signature not provided".to_string())?
+            .map_err(|err: ByteArrayError| err.to_string())?;
+
+        Ok(Self {
+            signers: witness.signers,
+            kernel_excess,
+            signature,
+        })
+    }
+}
+
+impl From<MultisigWitness> for proto::types::MultisigWitness {
+    fn from(witness: MultisigWitness) -> Self {
+        Self {
+            signers: witness.signers,
+            kernel_excess: Some(witness.kernel_excess.into()),
+            signature: Some(witness.signature.into()),
+        }
+    }
+}
+
+//---------------------------------- HtlcWitness --------------------------------------------//
+
+impl TryFrom<proto::types::HtlcWitness> for HtlcWitness {
+    type Error = String;
+
+    fn try_from(witness: proto::types::HtlcWitness) -> Result<Self, Self::Error> {
+        let kernel_excess = witness
+            .kernel_excess
+            .map(|commit| Commitment::from_bytes(&commit.data))
+            .ok_or_else(|| "HTLC witness kernel excess not provided".to_string())?
+            .map_err(|err| err.to_string())?;
+        let signature = witness
+            .signature
+            .map(TryInto::try_into)
+            .ok_or_else(|| "HTLC witness signature not provided".to_string())?
+            .map_err(|err: ByteArrayError| err.to_string())?;
+
+        if witness.preimage.is_empty() {
+            Ok(HtlcWitness::Refund {
+                kernel_excess,
+                signature,
+            })
+        } else {
+            Ok(HtlcWitness::Claim {
+                preimage: witness.preimage,
+                kernel_excess,
+                signature,
+            })
+        }
+    }
+}
+
+impl From<HtlcWitness> for proto::types::HtlcWitness {
+    fn from(witness: HtlcWitness) -> Self {
+        match witness {
+            HtlcWitness::Claim {
+                preimage,
+                kernel_excess,
+                signature,
+            } => Self {
+                preimage,
+                kernel_excess: Some(kernel_excess.into()),
+                signature: Some(signature.into()),
+            },
+            HtlcWitness::Refund {
+                kernel_excess,
+                signature,
+            } => Self {
+                preimage: Vec::new(),
+                kernel_excess: Some(kernel_excess.into()),
+                signature: Some(signature.into()),
+            },
         }
     }
 }
@@ -134,9 +279,12 @@ impl TryFrom<proto::types::TransactionOutput> for TransactionOutput {
             .ok_or_else(|| "Transaction output commitment not provided".to_string())?
             .map_err(|err| err.to_string())?;
 
+        let covenant = Covenant::from_bytes(&output.covenant).map_err(|err| err.to_string())?;
+
         Ok(Self {
             features,
             commitment,
+            covenant,
             proof: BulletRangeProof(output.range_proof),
         })
     }
@@ -148,6 +296,7 @@ impl From<TransactionOutput> for proto::types::TransactionOutput {
             features: Some(output.features.into()),
             commitment: Some(output.commitment.into()),
             range_proof: output.proof.to_vec(),
+            covenant: output.covenant.to_bytes(),
         }
     }
 }
@@ -158,10 +307,57 @@ impl TryFrom<proto::types::OutputFeatures> for OutputFeatures {
     type Error = String;
 
     fn try_from(features: proto::types::OutputFeatures) -> Result<Self, Self::Error> {
+        let version = u8::try_from(features.version)
+            .map_err(|_| format!("Unsupported output features version {}", features.version))
+            .map(OutputFeaturesVersion::from)?;
+        if version < OutputFeaturesVersion::V1 && !features.coinbase_extra.is_empty() {
+            return Err(format!(
+                "Output features version {} cannot carry a coinbase extra",
+                version
+            ));
+        }
+        if version < OutputFeaturesVersion::V2 && features.htlc.is_some() {
+            return Err(format!(
+                "Output features version {} cannot carry a hash time lock",
+                version
+            ));
+        }
+        if version < OutputFeaturesVersion::V3 && features.multisig.is_some() {
+            return Err(format!(
+                "Output features version {} cannot carry a multisig lock",
+                version
+            ));
+        }
+        if version < OutputFeaturesVersion::V4 && !features.script.is_empty() {
+            return Err(format!("Output features version {} cannot carry a script", version));
+        }
+        let script = if features.script.is_empty() {
+            None
+        } else {
+            Some(TariScript::from_bytes(&features.script).map_err(|err| err.to_string())?)
+        };
+        let range_proof_type = u8::try_from(features.range_proof_type)
+            .map_err(|_| format!("Unsupported range proof type {}", features.range_proof_type))
+            .and_then(RangeProofType::try_from)?;
+        if version < OutputFeaturesVersion::V5 && range_proof_type != RangeProofType::BulletProof {
+            return Err(format!(
+                "Output features version {} cannot carry a {} range proof",
+                version, range_proof_type
+            ));
+        }
+
         Ok(Self {
+            version,
             flags: OutputFlags::from_bits(features.flags as u8)
                 .ok_or_else(|| "Invalid or unrecognised output flags".to_string())?,
             maturity: features.maturity,
+            relative_maturity: features.relative_maturity,
+            asset: features.asset.map(TryInto::try_into).transpose()?,
+            coinbase_extra: features.coinbase_extra,
+            htlc: features.htlc.map(TryInto::try_into).transpose()?,
+            multisig: features.multisig.map(TryInto::try_into).transpose()?,
+            script,
+            range_proof_type,
         })
     }
 }
@@ -171,6 +367,110 @@ impl From<OutputFeatures> for proto::types::OutputFeatures {
         Self {
             flags: features.flags.bits() as u32,
             maturity: features.maturity,
+            relative_maturity: features.relative_maturity,
+            asset: features.asset.map(Into::into),
+            version: features.version.as_u8() as u32,
+            coinbase_extra: features.coinbase_extra,
+            htlc: features.htlc.map(Into::into),
+            multisig: features.multisig.map(Into::into),
+            script: features.script.map(|script| script.to_bytes()).unwrap_or_default(),
+            range_proof_type: features.range_proof_type.as_u8() as u32,
+        }
+    }
+}
+
+//---------------------------------- AssetOutputFeatures --------------------------------------------//
+
+impl TryFrom<proto::types::AssetOutputFeatures> for AssetOutputFeatures {
+    type Error = String;
+
+    fn try_from(asset: proto::types::AssetOutputFeatures) -> Result<Self, Self::Error> {
+        let issuer = PublicKey::from_bytes(&asset.issuer).map_err(|err| err.to_string())?;
+        let issuer_sig = asset
+            .issuer_sig
+            .map(TryInto::try_into)
+            .transpose()
+            .map_err(|err: ByteArrayError| err.to_string())?;
+
+        Ok(Self {
+            issuer,
+            name: asset.name,
+            token_id: asset.token_id,
+            issuer_sig,
+        })
+    }
+}
+
+impl From<AssetOutputFeatures> for proto::types::AssetOutputFeatures {
+    fn from(asset: AssetOutputFeatures) -> Self {
+        Self {
+            issuer: asset.issuer.to_vec(),
+            name: asset.name,
+            token_id: asset.token_id,
+            issuer_sig: asset.issuer_sig.map(Into::into),
+        }
+    }
+}
+
+//---------------------------------- HashTimeLock --------------------------------------------//
+
+impl TryFrom<proto::types::HashTimeLock> for HashTimeLock {
+    type Error = String;
+
+    fn try_from(htlc: proto::types::HashTimeLock) -> Result<Self, Self::Error> {
+        if htlc.hash_lock.len() != 32 {
+            return Err(format!(
+                "Hash lock must be 32 bytes, got {} bytes",
+                htlc.hash_lock.len()
+            ));
+        }
+        let mut hash_lock = [0u8; 32];
+        hash_lock.copy_from_slice(&htlc.hash_lock);
+
+        Ok(Self {
+            hash_lock,
+            claim_public_key: PublicKey::from_bytes(&htlc.claim_public_key).map_err(|err| err.to_string())?,
+            refund_public_key: PublicKey::from_bytes(&htlc.refund_public_key).map_err(|err| err.to_string())?,
+            refund_height: htlc.refund_height,
+        })
+    }
+}
+
+impl From<HashTimeLock> for proto::types::HashTimeLock {
+    fn from(htlc: HashTimeLock) -> Self {
+        Self {
+            hash_lock: htlc.hash_lock.to_vec(),
+            claim_public_key: htlc.claim_public_key.to_vec(),
+            refund_public_key: htlc.refund_public_key.to_vec(),
+            refund_height: htlc.refund_height,
+        }
+    }
+}
+
+//---------------------------------- MultisigLock --------------------------------------------//
+
+impl TryFrom<proto::types::MultisigLock> for MultisigLock {
+    type Error = String;
+
+    fn try_from(lock: proto::types::MultisigLock) -> Result<Self, Self::Error> {
+        let threshold =
+            u8::try_from(lock.threshold).map_err(|_| format!("Invalid multisig threshold {}", lock.threshold))?;
+        let public_keys = lock
+            .public_keys
+            .iter()
+            .map(|k| PublicKey::from_bytes(k).map_err(|err| err.to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let lock = Self { threshold, public_keys };
+        lock.validate().map_err(|err| err.to_string())?;
+        Ok(lock)
+    }
+}
+
+impl From<MultisigLock> for proto::types::MultisigLock {
+    fn from(lock: MultisigLock) -> Self {
+        Self {
+            threshold: lock.threshold as u32,
+            public_keys: lock.public_keys.iter().map(|k| k.to_vec()).collect(),
         }
     }
 }
@@ -39,6 +39,7 @@ use crate::{
         LMDBDatabase,
         MmrTree,
         PrunedOutput,
+        UtxoMinedInfo,
         Validators,
     },
     consensus::{
@@ -49,8 +50,9 @@ use crate::{
         Network,
     },
     transactions::{
+        asset::AssetId,
         transaction::{TransactionInput, TransactionKernel, TransactionOutput},
-        types::{CryptoFactories, HashOutput, Signature},
+        types::{Commitment, CryptoFactories, HashOutput, Signature},
     },
     validation::{
         block_validators::{BodyOnlyValidator, OrphanBlockValidator},
@@ -251,6 +253,14 @@ impl BlockchainBackend for TempDatabase {
         self.db.fetch_output(output_hash)
     }
 
+    fn fetch_output_by_commitment(&self, commitment: &Commitment) -> Result<Option<UtxoMinedInfo>, ChainStorageError> {
+        self.db.fetch_output_by_commitment(commitment)
+    }
+
+    fn asset_token_exists(&self, asset_id: &AssetId, token_id: u64) -> Result<bool, ChainStorageError> {
+        self.db.asset_token_exists(asset_id, token_id)
+    }
+
     fn fetch_outputs_in_block(&self, header_hash: &HashOutput) -> Result<Vec<PrunedOutput>, ChainStorageError> {
         self.db.fetch_outputs_in_block(header_hash)
     }
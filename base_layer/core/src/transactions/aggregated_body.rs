@@ -20,18 +20,24 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 use crate::transactions::{
+    covenant,
     fee::Fee,
+    range_proof_batch::{RangeProofBatchError, RangeProofBatchVerifier},
     tari_amount::*,
     transaction::*,
     types::{BlindingFactor, Commitment, CommitmentFactory, CryptoFactories, PrivateKey, RangeProofService},
+    weight::TransactionWeight,
 };
 use log::*;
 use serde::{Deserialize, Serialize};
-use std::fmt::{Display, Error, Formatter};
+use std::{
+    collections::HashSet,
+    fmt::{Display, Error, Formatter},
+};
 use tari_crypto::{
     commitment::HomomorphicCommitmentFactory,
     ristretto::pedersen::PedersenCommitment,
-    tari_utilities::hex::Hex,
+    tari_utilities::{hex::Hex, Hashable},
 };
 
 pub const LOG_TARGET: &str = "c::tx::aggregated_body";
@@ -75,6 +81,21 @@ impl AggregateBody {
         }
     }
 
+    /// Create a new aggregate body from inputs, outputs and kernels that the caller has checked are already sorted
+    pub(crate) fn new_sorted_unchecked(
+        inputs: Vec<TransactionInput>,
+        outputs: Vec<TransactionOutput>,
+        kernels: Vec<TransactionKernel>,
+    ) -> AggregateBody
+    {
+        AggregateBody {
+            sorted: true,
+            inputs,
+            outputs,
+            kernels,
+        }
+    }
+
     /// Provide read-only access to the input list
     pub fn inputs(&self) -> &Vec<TransactionInput> {
         &self.inputs
@@ -242,6 +263,26 @@ impl AggregateBody {
         fee
     }
 
+    /// The total amount permanently destroyed by the burn kernels in this body
+    pub fn get_total_burned(&self) -> MicroTari {
+        let mut burned = MicroTari::from(0);
+        for kernel in &self.kernels {
+            burned += kernel.burn_amount;
+        }
+        burned
+    }
+
+    /// This function will check that every kernel carries a burn amount if and only if it is a burn kernel
+    pub fn check_burn_kernels(&self) -> Result<(), TransactionError> {
+        for kernel in self.kernels() {
+            kernel.check_burn().map_err(|e| {
+                warn!(target: LOG_TARGET, "Kernel ({}) has an invalid burn: {:?}", kernel, e);
+                e
+            })?;
+        }
+        Ok(())
+    }
+
     /// This function will check spent kernel rules like tx lock height etc
     pub fn check_kernel_rules(&self, height: u64) -> Result<(), TransactionError> {
         for kernel in self.kernels() {
@@ -312,10 +353,12 @@ impl AggregateBody {
         Ok(())
     }
 
-    /// This function will check all stxo to ensure that feature flags where followed
+    /// This function will check all stxo to ensure that feature flags where followed. Refunded hash time-locked
+    /// outputs must also have reached their refund height, and the scripts of script locked outputs must succeed at
+    /// the given height.
     pub fn check_stxo_rules(&self, height: u64) -> Result<(), TransactionError> {
         for input in self.inputs() {
-            if input.features.maturity > height {
+            if input.min_spendable_height() > height {
                 warn!(
                     target: LOG_TARGET,
                     "Input found that has not yet matured to spending height: {}", input
@@ -323,6 +366,307 @@ impl AggregateBody {
                 return Err(TransactionError::InputMaturity);
             }
         }
+        self.check_script_spends(height)
+    }
+
+    /// This function will run the script of every input that spends a script locked output against its witness, as if
+    /// the body is mined at the given height. The presence of the witnesses and the kernels they commit to are checked
+    /// by [check_script_rules](Self::check_script_rules).
+    pub fn check_script_spends(&self, height: u64) -> Result<(), TransactionError> {
+        for input in self.inputs() {
+            if let (Some(script), Some(witness)) = (&input.features.script, &input.script_witness) {
+                script
+                    .verify_witness(witness, &input.commitment, height)
+                    .map_err(|err| {
+                        warn!(
+                            target: LOG_TARGET,
+                            "Input found whose script fails at height {} ({}): {}", height, err, input
+                        );
+                        TransactionError::InvalidScript(err.to_string())
+                    })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// This function will check that the covenants of all outputs are well formed and that the covenant of every
+    /// input is satisfied by an output of its own. An output cannot satisfy the covenants of two inputs, so a body that
+    /// merges several transactions needs as many satisfying outputs as the transactions do on their own.
+    pub fn check_covenants(&self) -> Result<(), TransactionError> {
+        for output in self.outputs() {
+            output
+                .covenant
+                .check_size()
+                .map_err(|err| TransactionError::InvalidCovenant(err.to_string()))?;
+        }
+        let covenants = self.inputs().iter().map(|i| &i.covenant).collect::<Vec<_>>();
+        if let Err(index) = covenant::assign_outputs(&covenants, self.outputs()) {
+            let input = &self.inputs()[index];
+            warn!(
+                target: LOG_TARGET,
+                "Input found whose covenant {} is not satisfied by an output of its own: {}", input.covenant, input
+            );
+            return Err(TransactionError::CovenantNotSatisfied(input.commitment.to_hex()));
+        }
+        Ok(())
+    }
+
+    /// This function will check the asset rules for every output that carries asset features:
+    /// 1. Registration and mint outputs carry asset features that are signed by the asset issuer
+    /// 1. Any other asset output receives a token that is spent by an input of this body
+    /// 1. No token is received by more than one output
+    /// 1. Every token spent by an input is received by an output, so tokens (and asset registrations) cannot be burned
+    ///
+    /// Since every transaction conserves its tokens on its own, merging transactions cannot let the input of one
+    /// satisfy the transfer of another. Whether an issuance is valid with respect to the chain is checked by the
+    /// validators against the asset index of the blockchain database.
+    pub fn check_asset_rules(&self) -> Result<(), TransactionError> {
+        let mut tokens = HashSet::new();
+        for output in self.outputs() {
+            let features = &output.features;
+            let is_issuance = features.is_asset_registration() || features.is_mint();
+            let asset = match &features.asset {
+                Some(asset) => asset,
+                None if is_issuance => {
+                    return Err(TransactionError::InvalidAsset(
+                        "Registration and mint outputs must carry asset features".into(),
+                    ))
+                },
+                None => continue,
+            };
+            asset
+                .check_size()
+                .map_err(|err| TransactionError::InvalidAsset(err.to_string()))?;
+            if features.is_asset_registration() && features.is_mint() {
+                return Err(TransactionError::InvalidAsset(
+                    "An output cannot both register an asset and mint a token".into(),
+                ));
+            }
+            if features.is_asset_registration() && asset.token_id != 0 {
+                return Err(TransactionError::InvalidAsset(
+                    "An asset registration cannot carry a token".into(),
+                ));
+            }
+            if features.is_mint() && asset.token_id == 0 {
+                return Err(TransactionError::InvalidAsset("Token id zero cannot be minted".into()));
+            }
+
+            if is_issuance {
+                asset
+                    .verify_issuer_signature(&output.commitment)
+                    .map_err(|err| TransactionError::InvalidAsset(err.to_string()))?;
+            } else {
+                let asset_id = asset.asset_id();
+                let is_spent_by_input = self.inputs().iter().any(|input| {
+                    input
+                        .features
+                        .asset
+                        .as_ref()
+                        .map_or(false, |a| a.asset_id() == asset_id && a.token_id == asset.token_id)
+                });
+                if !is_spent_by_input {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Output receives {} which is not spent by any input: {}", asset, output
+                    );
+                    return Err(TransactionError::InvalidAsset(format!(
+                        "{} is not spent by any input",
+                        asset
+                    )));
+                }
+            }
+
+            if !tokens.insert((asset.asset_id(), asset.token_id)) {
+                return Err(TransactionError::InvalidAsset(format!(
+                    "{} is received by more than one output",
+                    asset
+                )));
+            }
+        }
+        for input in self.inputs() {
+            if let Some(asset) = &input.features.asset {
+                if !tokens.contains(&(asset.asset_id(), asset.token_id)) {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Input spends {} which is not received by any output: {}", asset, input
+                    );
+                    return Err(TransactionError::InvalidAsset(format!(
+                        "{} is spent without being received by an output",
+                        asset
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// This function will check the hash time-locked contract rules:
+    /// 1. Hash time-locked outputs use V2 features or later
+    /// 1. Every input that spends a hash time-locked output carries a valid witness, and no other input carries one
+    /// 1. The witness commits to a kernel of this body
+    ///
+    /// The refund height of refunded outputs is checked by [check_stxo_rules](Self::check_stxo_rules)
+    pub fn check_htlc_rules(&self) -> Result<(), TransactionError> {
+        for output in self.outputs().iter().filter(|o| o.features.is_hash_time_locked()) {
+            if output.features.version < OutputFeaturesVersion::V2 {
+                return Err(TransactionError::InvalidHtlc(format!(
+                    "Output features version {} cannot carry a hash time lock",
+                    output.features.version
+                )));
+            }
+        }
+        for input in self.inputs() {
+            let (htlc, witness) = match (&input.features.htlc, &input.htlc_witness) {
+                (None, None) => continue,
+                (Some(htlc), Some(witness)) => (htlc, witness),
+                (Some(_), None) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Input found that spends a hash time-locked output without a witness: {}", input
+                    );
+                    return Err(TransactionError::InvalidHtlc(format!(
+                        "Input {} has no witness",
+                        input.commitment.to_hex()
+                    )));
+                },
+                (None, Some(_)) => {
+                    return Err(TransactionError::InvalidHtlc(format!(
+                        "Input {} is not hash time-locked but carries a witness",
+                        input.commitment.to_hex()
+                    )));
+                },
+            };
+            htlc.verify_witness(witness, &input.commitment).map_err(|err| {
+                warn!(
+                    target: LOG_TARGET,
+                    "Input found with an invalid HTLC witness ({}): {}", err, input
+                );
+                TransactionError::InvalidHtlc(err.to_string())
+            })?;
+            if !self.kernels().iter().any(|k| &k.excess == witness.kernel_excess()) {
+                return Err(TransactionError::InvalidHtlc(format!(
+                    "The witness of input {} does not commit to a kernel of the transaction",
+                    input.commitment.to_hex()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// This function will check the multisig rules:
+    /// 1. Multisig locked outputs use V3 features or later, and are not also hash time-locked
+    /// 1. Every input that spends a multisig locked output carries a valid witness, and no other input carries one
+    /// 1. The witness commits to a kernel of this body
+    pub fn check_multisig_rules(&self) -> Result<(), TransactionError> {
+        for output in self.outputs().iter().filter(|o| o.features.is_multisig_locked()) {
+            if output.features.version < OutputFeaturesVersion::V3 {
+                return Err(TransactionError::InvalidMultisig(format!(
+                    "Output features version {} cannot carry a multisig lock",
+                    output.features.version
+                )));
+            }
+            if output.features.is_hash_time_locked() {
+                return Err(TransactionError::InvalidMultisig(
+                    "An output cannot be both multisig and hash time-locked".to_string(),
+                ));
+            }
+        }
+        for input in self.inputs() {
+            let (lock, witness) = match (&input.features.multisig, &input.multisig_witness) {
+                (None, None) => continue,
+                (Some(lock), Some(witness)) => (lock, witness),
+                (Some(_), None) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Input found that spends a multisig locked output without a witness: {}", input
+                    );
+                    return Err(TransactionError::InvalidMultisig(format!(
+                        "Input {} has no witness",
+                        input.commitment.to_hex()
+                    )));
+                },
+                (None, Some(_)) => {
+                    return Err(TransactionError::InvalidMultisig(format!(
+                        "Input {} is not multisig locked but carries a witness",
+                        input.commitment.to_hex()
+                    )));
+                },
+            };
+            lock.validate()
+                .and_then(|_| lock.verify_witness(witness, &input.commitment))
+                .map_err(|err| {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Input found with an invalid multisig witness ({}): {}", err, input
+                    );
+                    TransactionError::InvalidMultisig(err.to_string())
+                })?;
+            if !self.kernels().iter().any(|k| k.excess == witness.kernel_excess) {
+                return Err(TransactionError::InvalidMultisig(format!(
+                    "The witness of input {} does not commit to a kernel of the transaction",
+                    input.commitment.to_hex()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// This function will check the script rules:
+    /// 1. Script locked outputs use V4 features or later, are not also locked in another way and have a well formed
+    /// script
+    /// 1. Every input that spends a script locked output carries a witness, and no other input carries one
+    /// 1. The witness commits to a kernel of this body
+    ///
+    /// The scripts are run by [check_script_spends](Self::check_script_spends), as they depend on the block height
+    pub fn check_script_rules(&self) -> Result<(), TransactionError> {
+        for output in self.outputs() {
+            let script = match &output.features.script {
+                Some(script) => script,
+                None => continue,
+            };
+            if output.features.version < OutputFeaturesVersion::V4 {
+                return Err(TransactionError::InvalidScript(format!(
+                    "Output features version {} cannot carry a script",
+                    output.features.version
+                )));
+            }
+            if output.features.is_hash_time_locked() || output.features.is_multisig_locked() {
+                return Err(TransactionError::InvalidScript(
+                    "A script locked output cannot also be hash time-locked or multisig locked".to_string(),
+                ));
+            }
+            script
+                .validate()
+                .map_err(|err| TransactionError::InvalidScript(err.to_string()))?;
+        }
+        for input in self.inputs() {
+            let witness = match (&input.features.script, &input.script_witness) {
+                (None, None) => continue,
+                (Some(_), Some(witness)) => witness,
+                (Some(_), None) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Input found that spends a script locked output without a witness: {}", input
+                    );
+                    return Err(TransactionError::InvalidScript(format!(
+                        "Input {} has no witness",
+                        input.commitment.to_hex()
+                    )));
+                },
+                (None, Some(_)) => {
+                    return Err(TransactionError::InvalidScript(format!(
+                        "Input {} is not script locked but carries a witness",
+                        input.commitment.to_hex()
+                    )));
+                },
+            };
+            if !self.kernels().iter().any(|k| k.excess == witness.kernel_excess) {
+                return Err(TransactionError::InvalidScript(format!(
+                    "The witness of input {} does not commit to a kernel of the transaction",
+                    input.commitment.to_hex()
+                )));
+            }
+        }
         Ok(())
     }
 
@@ -330,6 +674,12 @@ impl AggregateBody {
     /// 1. The sum of inputs, outputs and fees equal the (public excess value + offset)
     /// 1. The signature signs the canonical message with the private excess
     /// 1. Range proofs of the outputs are valid
+    /// 1. The covenants of all inputs are satisfied
+    /// 1. Asset tokens are only created by their issuer
+    /// 1. Hash time-locked outputs are only spent with a valid witness
+    /// 1. Multisig locked outputs are only spent with a valid witness of enough participants
+    /// 1. Script locked outputs are only spent with a witness that commits to a kernel of the transaction
+    /// 1. Burn amounts are only set on burn kernels
     ///
     /// This function does NOT check that inputs come from the UTXO set
     /// The reward is the total amount of Tari rewarded for this block (block reward + total fees), this should be 0
@@ -340,12 +690,31 @@ impl AggregateBody {
         total_reward: MicroTari,
         factories: &CryptoFactories,
     ) -> Result<(), TransactionError>
+    {
+        self.validate_kernels_and_balance(offset, total_reward, factories)?;
+        self.validate_range_proofs(&factories.range_proof)?;
+        self.check_covenants()?;
+        self.check_asset_rules()?;
+        self.check_htlc_rules()?;
+        self.check_multisig_rules()?;
+        self.check_script_rules()
+    }
+
+    /// Validate only the kernels of this body and that they balance its inputs and outputs. This is the subset of
+    /// [validate_internal_consistency](Self::validate_internal_consistency) that still applies to blocks that are
+    /// assumed to be valid, it does not verify range proofs, covenants or asset issuer signatures.
+    pub fn validate_kernels_and_balance(
+        &self,
+        offset: &BlindingFactor,
+        total_reward: MicroTari,
+        factories: &CryptoFactories,
+    ) -> Result<(), TransactionError>
     {
         let total_offset = factories.commitment.commit_value(&offset, total_reward.0);
 
         self.verify_kernel_signatures()?;
-        self.validate_kernel_sum(total_offset, &factories.commitment)?;
-        self.validate_range_proofs(&factories.range_proof)
+        self.check_burn_kernels()?;
+        self.validate_kernel_sum(total_offset, &factories.commitment)
     }
 
     pub fn dissolve(self) -> (Vec<TransactionInput>, Vec<TransactionOutput>, Vec<TransactionKernel>) {
@@ -359,16 +728,17 @@ impl AggregateBody {
         sum_outputs - sum_inputs
     }
 
-    /// Calculate the sum of the kernels, taking into account the provided offset, and their constituent fees
+    /// Calculate the sum of the kernels, taking into account the provided offset, and their constituent fees. Burned
+    /// amounts leave the inputs without appearing in any output, so they are balanced in the same way as fees.
     fn sum_kernels(&self, offset_with_fee: PedersenCommitment) -> KernelSum {
-        // Sum all kernel excesses and fees
+        // Sum all kernel excesses, fees and burned amounts
         self.kernels.iter().fold(
             KernelSum {
                 fees: MicroTari(0),
                 sum: offset_with_fee,
             },
             |acc, val| KernelSum {
-                fees: acc.fees + val.fee,
+                fees: acc.fees + val.fee + val.burn_amount,
                 sum: &acc.sum + &val.excess,
             },
         )
@@ -407,19 +777,39 @@ impl AggregateBody {
 
     fn validate_range_proofs(&self, range_proof_service: &RangeProofService) -> Result<(), TransactionError> {
         trace!(target: LOG_TARGET, "Checking range proofs");
-        for o in &self.outputs {
-            if !o.verify_range_proof(&range_proof_service)? {
-                return Err(TransactionError::ValidationError(
-                    "Range proof could not be verified".into(),
-                ));
-            }
-        }
-        Ok(())
+        RangeProofBatchVerifier::new(range_proof_service)
+            .verify(&self.outputs)
+            .map_err(|err| {
+                let output = &self.outputs[err.index()];
+                debug!(
+                    target: LOG_TARGET,
+                    "Range proof of output {} could not be verified: {}",
+                    output.hash().to_hex(),
+                    err
+                );
+                match err {
+                    RangeProofBatchError::UnsupportedRangeProofType { range_proof_type, .. } => {
+                        TransactionError::UnsupportedRangeProofType(range_proof_type)
+                    },
+                    RangeProofBatchError::InvalidRangeProof(_) => {
+                        TransactionError::ValidationError("Range proof could not be verified".into())
+                    },
+                }
+            })
     }
 
-    /// Returns the byte size or weight of a body
+    /// Returns the byte size or weight of a body with the original weight formula. The weight that a body is validated
+    /// with is given by `ConsensusConstants::transaction_weight`.
     pub fn calculate_weight(&self) -> u64 {
-        Fee::calculate_weight(self.kernels().len(), self.inputs().len(), self.outputs().len())
+        TransactionWeight::v1().calculate(self.kernels().len(), self.inputs().len(), self.outputs().len(), 0)
+    }
+
+    /// Returns the total size in bytes of the metadata, i.e. the features and covenants, of the outputs in this body
+    pub fn calculate_output_metadata_size(&self) -> usize {
+        self.outputs
+            .iter()
+            .map(|o| Fee::output_metadata_size(&o.features, &o.covenant))
+            .sum()
     }
 
     pub fn is_sorted(&self) -> bool {
@@ -0,0 +1,251 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Primitives for issuing assets other than Tari. An asset is identified by the public key of its issuer and a short
+//! issuer-chosen name. The issuer registers the asset with an output flagged `ASSET_REGISTRATION`, and mints tokens
+//! of the asset with outputs flagged `MINT_NON_FUNGIBLE`. Both must carry the issuer's signature over the output
+//! commitment. Tokens are transferred by spending the output that carries them into a new output with the same asset
+//! features. Token outputs still commit to an ordinary (hidden) Tari value, so the usual balance rules apply to them.
+//!
+//! Tokens cannot be burned: every transaction must pass each token it spends, including the registration, on to a new
+//! output. The latest output of every asset and token is therefore always unspent, so even pruned nodes can check
+//! against their asset index that an asset is registered only once and a token is minted only once.
+
+use crate::transactions::types::{Challenge, Commitment, HashDigest, MessageHash, PrivateKey, PublicKey, Signature};
+use digest::Digest;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    fmt::{Display, Formatter},
+    hash::{Hash, Hasher},
+};
+use tari_crypto::tari_utilities::{hex::Hex, ByteArray};
+use thiserror::Error;
+
+/// The maximum size, in bytes, of an asset name
+pub const MAX_ASSET_NAME_BYTES: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum AssetError {
+    #[error("Asset name is {size} bytes which exceeds the maximum of {max} bytes")]
+    NameTooLong { size: usize, max: usize },
+    #[error("The issuer signature is missing")]
+    MissingIssuerSignature,
+    #[error("The issuer signature is invalid")]
+    InvalidIssuerSignature,
+    #[error("Could not sign the asset output: {0}")]
+    SigningError(String),
+}
+
+/// The unique identifier of an asset, H(issuer public key | name)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct AssetId([u8; 32]);
+
+impl AssetId {
+    pub fn new(issuer: &PublicKey, name: &[u8]) -> Self {
+        let hash = HashDigest::new().chain(issuer.as_bytes()).chain(name).result();
+        let mut id = [0u8; 32];
+        id.copy_from_slice(&hash[..32]);
+        AssetId(id)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Display for AssetId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0.to_vec().to_hex())
+    }
+}
+
+/// The asset-specific part of an output's features
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetOutputFeatures {
+    /// The public key of the issuer. Only the holder of the corresponding secret key can register the asset and mint
+    /// its tokens.
+    pub issuer: PublicKey,
+    /// The issuer-chosen name of the asset, used to distinguish the assets of a single issuer
+    pub name: Vec<u8>,
+    /// The token carried by this output. The registration output carries no token and uses zero.
+    pub token_id: u64,
+    /// The issuer's authorisation for a registration or mint output. Not required when transferring a token.
+    pub issuer_sig: Option<Signature>,
+}
+
+impl AssetOutputFeatures {
+    pub fn new(issuer: PublicKey, name: Vec<u8>, token_id: u64) -> Self {
+        Self {
+            issuer,
+            name,
+            token_id,
+            issuer_sig: None,
+        }
+    }
+
+    pub fn asset_id(&self) -> AssetId {
+        AssetId::new(&self.issuer, &self.name)
+    }
+
+    /// The challenge that the issuer signs to authorise the output with the given commitment
+    pub fn authorisation_challenge(&self, commitment: &Commitment) -> MessageHash {
+        Challenge::new()
+            .chain(self.asset_id().as_bytes())
+            .chain(&self.token_id.to_le_bytes())
+            .chain(commitment.as_bytes())
+            .result()
+            .to_vec()
+    }
+
+    /// Sign the output with the given commitment with the issuer's secret key, authorising its registration or mint
+    pub fn sign(
+        mut self,
+        issuer_secret: PrivateKey,
+        nonce: PrivateKey,
+        commitment: &Commitment,
+    ) -> Result<Self, AssetError>
+    {
+        let challenge = self.authorisation_challenge(commitment);
+        let sig =
+            Signature::sign(issuer_secret, nonce, &challenge).map_err(|e| AssetError::SigningError(e.to_string()))?;
+        self.issuer_sig = Some(sig);
+        Ok(self)
+    }
+
+    /// Verify that the issuer authorised the output with the given commitment
+    pub fn verify_issuer_signature(&self, commitment: &Commitment) -> Result<(), AssetError> {
+        let sig = self.issuer_sig.as_ref().ok_or(AssetError::MissingIssuerSignature)?;
+        let challenge = self.authorisation_challenge(commitment);
+        if sig.verify_challenge(&self.issuer, &challenge) {
+            Ok(())
+        } else {
+            Err(AssetError::InvalidIssuerSignature)
+        }
+    }
+
+    pub fn check_size(&self) -> Result<(), AssetError> {
+        if self.name.len() > MAX_ASSET_NAME_BYTES {
+            return Err(AssetError::NameTooLong {
+                size: self.name.len(),
+                max: MAX_ASSET_NAME_BYTES,
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns the canonical byte representation of these features
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(32 + 1 + self.name.len() + 8 + 1 + 64);
+        buf.extend_from_slice(self.issuer.as_bytes());
+        buf.push(self.name.len() as u8);
+        buf.extend_from_slice(&self.name);
+        buf.extend_from_slice(&self.token_id.to_le_bytes());
+        match &self.issuer_sig {
+            Some(sig) => {
+                buf.push(1);
+                buf.extend_from_slice(sig.get_public_nonce().as_bytes());
+                buf.extend_from_slice(sig.get_signature().as_bytes());
+            },
+            None => buf.push(0),
+        }
+        buf
+    }
+}
+
+impl Hash for AssetOutputFeatures {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_bytes().hash(state)
+    }
+}
+
+impl Display for AssetOutputFeatures {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Asset {} token {}", self.asset_id(), self.token_id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::transactions::types::CommitmentFactory;
+    use rand::rngs::OsRng;
+    use tari_crypto::{
+        commitment::HomomorphicCommitmentFactory,
+        keys::{PublicKey as PublicKeyTrait, SecretKey},
+    };
+
+    fn random_commitment() -> Commitment {
+        CommitmentFactory::default().commit_value(&PrivateKey::random(&mut OsRng), 100)
+    }
+
+    #[test]
+    fn asset_id_depends_on_issuer_and_name() {
+        let (_, issuer) = PublicKey::random_keypair(&mut OsRng);
+        let (_, other) = PublicKey::random_keypair(&mut OsRng);
+        assert_eq!(AssetId::new(&issuer, b"gold"), AssetId::new(&issuer, b"gold"));
+        assert_ne!(AssetId::new(&issuer, b"gold"), AssetId::new(&issuer, b"silver"));
+        assert_ne!(AssetId::new(&issuer, b"gold"), AssetId::new(&other, b"gold"));
+    }
+
+    #[test]
+    fn issuer_signature() {
+        let (k, issuer) = PublicKey::random_keypair(&mut OsRng);
+        let commitment = random_commitment();
+        let features = AssetOutputFeatures::new(issuer, b"gold".to_vec(), 1);
+        assert_eq!(
+            features.verify_issuer_signature(&commitment),
+            Err(AssetError::MissingIssuerSignature)
+        );
+        let features = features
+            .sign(k.clone(), PrivateKey::random(&mut OsRng), &commitment)
+            .unwrap();
+        assert!(features.verify_issuer_signature(&commitment).is_ok());
+        // The authorisation is bound to the output commitment
+        assert_eq!(
+            features.verify_issuer_signature(&random_commitment()),
+            Err(AssetError::InvalidIssuerSignature)
+        );
+        // ...and to the token
+        let mut other_token = features.clone();
+        other_token.token_id = 2;
+        assert_eq!(
+            other_token.verify_issuer_signature(&commitment),
+            Err(AssetError::InvalidIssuerSignature)
+        );
+    }
+
+    #[test]
+    fn name_size_limit() {
+        let (_, issuer) = PublicKey::random_keypair(&mut OsRng);
+        let features = AssetOutputFeatures::new(issuer.clone(), vec![0u8; MAX_ASSET_NAME_BYTES], 0);
+        assert!(features.check_size().is_ok());
+        let features = AssetOutputFeatures::new(issuer, vec![0u8; MAX_ASSET_NAME_BYTES + 1], 0);
+        assert_eq!(
+            features.check_size(),
+            Err(AssetError::NameTooLong {
+                size: MAX_ASSET_NAME_BYTES + 1,
+                max: MAX_ASSET_NAME_BYTES
+            })
+        );
+    }
+}
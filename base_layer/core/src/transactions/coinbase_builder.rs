@@ -29,6 +29,7 @@ use crate::{
             KernelBuilder,
             KernelFeatures,
             OutputFeatures,
+            OutputFeaturesVersion,
             Transaction,
             TransactionBuilder,
             UnblindedOutput,
@@ -54,6 +55,10 @@ pub enum CoinbaseBuildError {
     BuildError(String),
     #[error("Some inconsistent data was given to the builder. This transaction is not valid")]
     InvalidTransaction,
+    #[error("The coinbase extra is {size} bytes which exceeds the maximum of {max} bytes")]
+    ExtraTooLarge { size: usize, max: usize },
+    #[error("The consensus rules do not allow {0} output features, which a coinbase extra requires")]
+    UnsupportedFeaturesVersion(OutputFeaturesVersion),
 }
 
 pub struct CoinbaseBuilder {
@@ -63,6 +68,7 @@ pub struct CoinbaseBuilder {
     spend_key: Option<PrivateKey>,
     private_nonce: Option<PrivateKey>,
     rewind_data: Option<RewindData>,
+    extra: Vec<u8>,
 }
 
 impl CoinbaseBuilder {
@@ -76,6 +82,7 @@ impl CoinbaseBuilder {
             spend_key: None,
             private_nonce: None,
             rewind_data: None,
+            extra: Vec::new(),
         }
     }
 
@@ -109,6 +116,13 @@ impl CoinbaseBuilder {
         self
     }
 
+    /// Include arbitrary data, such as a mining pool identifier, in the coinbase output. It may be no larger than the
+    /// consensus rules permit.
+    pub fn with_extra(mut self, extra: Vec<u8>) -> Self {
+        self.extra = extra;
+        self
+    }
+
     /// Try and construct a Coinbase Transaction. The block reward is taken from the emission curve for the current
     /// block height. The other parameters (keys, nonces etc.) are provided by the caller. Other data is
     /// automatically set: Coinbase transactions have an offset of zero, no fees, the `COINBASE_OUTPUT` flags are set
@@ -151,7 +165,20 @@ impl CoinbaseBuilder {
         let nonce = self.private_nonce.ok_or_else(|| CoinbaseBuildError::MissingNonce)?;
         let public_nonce = PublicKey::from_secret_key(&nonce);
         let key = self.spend_key.ok_or_else(|| CoinbaseBuildError::MissingSpendKey)?;
-        let output_features = OutputFeatures::create_coinbase(height + constants.coinbase_lock_height());
+        if self.extra.len() > constants.coinbase_extra_max_size() {
+            return Err(CoinbaseBuildError::ExtraTooLarge {
+                size: self.extra.len(),
+                max: constants.coinbase_extra_max_size(),
+            });
+        }
+        let output_features =
+            OutputFeatures::create_coinbase_with_extra(height + constants.coinbase_lock_height(), self.extra);
+        if !constants
+            .output_features_version_range()
+            .contains(&output_features.version)
+        {
+            return Err(CoinbaseBuildError::UnsupportedFeaturesVersion(output_features.version));
+        }
         let excess = self.factories.commitment.commit_value(&key, 0);
         let kernel_features = KernelFeatures::create_coinbase();
         let metadata = TransactionMetadata::default();
@@ -198,7 +225,14 @@ mod test {
             coinbase_builder::CoinbaseBuildError,
             helpers::TestParams,
             tari_amount::uT,
-            transaction::{KernelFeatures, OutputFeatures, OutputFlags, TransactionError, UnblindedOutput},
+            transaction::{
+                KernelFeatures,
+                OutputFeatures,
+                OutputFeaturesVersion,
+                OutputFlags,
+                TransactionError,
+                UnblindedOutput,
+            },
             transaction_protocol::RewindData,
             types::{BlindingFactor, CryptoFactories, PrivateKey},
             CoinbaseBuilder,
@@ -284,6 +318,68 @@ mod test {
         );
     }
 
+    #[test]
+    #[allow(clippy::erasing_op)]
+    fn coinbase_extra() {
+        let p = TestParams::new();
+        let (builder, rules, factories) = get_builder();
+        let constants = rules.consensus_constants(42);
+        let (tx, _) = builder
+            .with_block_height(42)
+            .with_fees(0 * uT)
+            .with_nonce(p.nonce.clone())
+            .with_spend_key(p.spend_key.clone())
+            .with_extra(b"pool".to_vec())
+            .build(constants, rules.emission_schedule())
+            .unwrap();
+        assert_eq!(tx.body.outputs()[0].features.coinbase_extra, b"pool".to_vec());
+        assert_eq!(tx.body.outputs()[0].features.version, OutputFeaturesVersion::V1);
+
+        let max = constants.coinbase_extra_max_size();
+        let builder = CoinbaseBuilder::new(factories)
+            .with_block_height(42)
+            .with_fees(0 * uT)
+            .with_nonce(p.nonce)
+            .with_spend_key(p.spend_key)
+            .with_extra(vec![0u8; max + 1]);
+        assert_eq!(
+            builder.build(constants, rules.emission_schedule()).unwrap_err(),
+            CoinbaseBuildError::ExtraTooLarge { size: max + 1, max }
+        );
+    }
+
+    #[test]
+    #[allow(clippy::erasing_op)]
+    fn coinbase_extra_requires_v1_features() {
+        let p = TestParams::new();
+        let factories = CryptoFactories::default();
+        let rules = ConsensusManagerBuilder::new(Network::Stibbons).build();
+        let constants = rules.consensus_constants(42);
+        assert!(!constants
+            .output_features_version_range()
+            .contains(&OutputFeaturesVersion::V1));
+        let builder = CoinbaseBuilder::new(factories.clone())
+            .with_block_height(42)
+            .with_fees(0 * uT)
+            .with_nonce(p.nonce.clone())
+            .with_spend_key(p.spend_key.clone())
+            .with_extra(b"pool".to_vec());
+        assert_eq!(
+            builder.build(constants, rules.emission_schedule()).unwrap_err(),
+            CoinbaseBuildError::UnsupportedFeaturesVersion(OutputFeaturesVersion::V1)
+        );
+
+        // Without an extra, the coinbase has V0 features
+        let (tx, _) = CoinbaseBuilder::new(factories)
+            .with_block_height(42)
+            .with_fees(0 * uT)
+            .with_nonce(p.nonce)
+            .with_spend_key(p.spend_key)
+            .build(constants, rules.emission_schedule())
+            .unwrap();
+        assert_eq!(tx.body.outputs()[0].features.version, OutputFeaturesVersion::V0);
+    }
+
     #[test]
     fn valid_coinbase_with_rewindable_output() {
         let rewind_key = PrivateKey::random(&mut OsRng);
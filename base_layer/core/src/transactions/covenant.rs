@@ -0,0 +1,382 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Covenants allow the owner of an output to restrict how that output may be spent. A covenant is a list of rules
+//! that is committed to in the output hash. When the output is spent, the spending transaction must contain at least
+//! one output that satisfies every rule of the covenant attached to the input. This makes constructs such as
+//! side-chain pegs and vaults possible, since funds can be forced to stay under a particular set of output features.
+//!
+//! Transactions can be merged, and a block body does not record where one transaction ends and the next begins. So
+//! that the output of one transaction cannot satisfy the covenant of an input of another, every covenant is assigned
+//! its own output (see [assign_outputs]). A body that merges valid transactions always has such an assignment, since
+//! every transaction brings its own satisfying outputs.
+
+use crate::transactions::transaction::{OutputFlags, TransactionOutput};
+use serde::{Deserialize, Serialize};
+use std::{
+    convert::TryFrom,
+    fmt,
+    fmt::{Display, Formatter},
+};
+use thiserror::Error;
+
+/// The maximum size, in bytes, of an encoded covenant
+pub const MAX_COVENANT_BYTES: usize = 256;
+
+const RULE_REQUIRED_FLAGS: u8 = 0x01;
+const RULE_MIN_MATURITY: u8 = 0x02;
+const RULE_PRESERVE_COVENANT: u8 = 0x03;
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum CovenantError {
+    #[error("Unexpected end of covenant bytes")]
+    UnexpectedEndOfBytes,
+    #[error("Unknown covenant rule tag: {0:#04x}")]
+    UnknownRuleTag(u8),
+    #[error("Invalid output flags in covenant: {0:#010b}")]
+    InvalidOutputFlags(u8),
+    #[error("Covenant is {size} bytes which exceeds the maximum of {max} bytes")]
+    TooLarge { size: usize, max: usize },
+}
+
+/// A single rule of a covenant. Each rule constrains an output of the spending transaction.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum CovenantRule {
+    /// The output must have all of the given feature flags set
+    RequiredFlags(OutputFlags),
+    /// The output must have a maturity of at least the given height
+    MinMaturity(u64),
+    /// The output must carry the same covenant as the output being spent
+    PreserveCovenant,
+}
+
+impl CovenantRule {
+    fn is_satisfied_by(&self, covenant: &Covenant, output: &TransactionOutput) -> bool {
+        use CovenantRule::*;
+        match self {
+            RequiredFlags(flags) => output.features.flags.contains(*flags),
+            MinMaturity(maturity) => output.features.maturity >= *maturity,
+            PreserveCovenant => output.covenant == *covenant,
+        }
+    }
+
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        use CovenantRule::*;
+        match self {
+            RequiredFlags(flags) => {
+                buf.push(RULE_REQUIRED_FLAGS);
+                buf.push(flags.bits());
+            },
+            MinMaturity(maturity) => {
+                buf.push(RULE_MIN_MATURITY);
+                buf.extend_from_slice(&maturity.to_le_bytes());
+            },
+            PreserveCovenant => {
+                buf.push(RULE_PRESERVE_COVENANT);
+            },
+        }
+    }
+
+    fn read_from(bytes: &mut &[u8]) -> Result<Self, CovenantError> {
+        let tag = read_bytes(bytes, 1)?[0];
+        match tag {
+            RULE_REQUIRED_FLAGS => {
+                let bits = read_bytes(bytes, 1)?[0];
+                let flags = OutputFlags::from_bits(bits).ok_or_else(|| CovenantError::InvalidOutputFlags(bits))?;
+                Ok(CovenantRule::RequiredFlags(flags))
+            },
+            RULE_MIN_MATURITY => {
+                let mut buf = [0u8; 8];
+                buf.copy_from_slice(read_bytes(bytes, 8)?);
+                Ok(CovenantRule::MinMaturity(u64::from_le_bytes(buf)))
+            },
+            RULE_PRESERVE_COVENANT => Ok(CovenantRule::PreserveCovenant),
+            tag => Err(CovenantError::UnknownRuleTag(tag)),
+        }
+    }
+}
+
+fn read_bytes<'a>(bytes: &mut &'a [u8], len: usize) -> Result<&'a [u8], CovenantError> {
+    if bytes.len() < len {
+        return Err(CovenantError::UnexpectedEndOfBytes);
+    }
+    let (head, tail) = bytes.split_at(len);
+    *bytes = tail;
+    Ok(head)
+}
+
+impl Display for CovenantRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        use CovenantRule::*;
+        match self {
+            RequiredFlags(flags) => write!(f, "required_flags({:?})", flags),
+            MinMaturity(maturity) => write!(f, "min_maturity({})", maturity),
+            PreserveCovenant => write!(f, "preserve_covenant"),
+        }
+    }
+}
+
+/// A set of rules restricting the outputs of a transaction that spends the output carrying the covenant. An empty
+/// covenant places no restrictions on the spending transaction.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+pub struct Covenant {
+    rules: Vec<CovenantRule>,
+}
+
+impl Covenant {
+    /// Create a new empty covenant
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add a rule to this covenant
+    pub fn with_rule(mut self, rule: CovenantRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn rules(&self) -> &[CovenantRule] {
+        &self.rules
+    }
+
+    /// Returns true if this covenant has no rules, otherwise false
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Returns the canonical byte encoding of this covenant. An empty covenant encodes to zero bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        for rule in &self.rules {
+            rule.write_to(&mut buf);
+        }
+        buf
+    }
+
+    /// Decode a covenant from its canonical byte encoding
+    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, CovenantError> {
+        if bytes.len() > MAX_COVENANT_BYTES {
+            return Err(CovenantError::TooLarge {
+                size: bytes.len(),
+                max: MAX_COVENANT_BYTES,
+            });
+        }
+        let mut rules = Vec::new();
+        while !bytes.is_empty() {
+            rules.push(CovenantRule::read_from(&mut bytes)?);
+        }
+        Ok(Self { rules })
+    }
+
+    /// Checks that the encoded covenant does not exceed `MAX_COVENANT_BYTES`
+    pub fn check_size(&self) -> Result<(), CovenantError> {
+        let size = self.to_bytes().len();
+        if size > MAX_COVENANT_BYTES {
+            return Err(CovenantError::TooLarge {
+                size,
+                max: MAX_COVENANT_BYTES,
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns true if the given output satisfies every rule in this covenant
+    pub fn is_satisfied_by_output(&self, output: &TransactionOutput) -> bool {
+        self.rules.iter().all(|rule| rule.is_satisfied_by(self, output))
+    }
+
+    /// Returns true if this covenant is empty, or if at least one of the given outputs satisfies every rule in this
+    /// covenant
+    pub fn is_satisfied_by<'a, I: IntoIterator<Item = &'a TransactionOutput>>(&self, outputs: I) -> bool {
+        self.is_empty() || outputs.into_iter().any(|output| self.is_satisfied_by_output(output))
+    }
+}
+
+/// Assigns a distinct output to every non-empty covenant, such that each output satisfies the covenant it is assigned
+/// to. Returns the index of a covenant for which no assignment exists.
+pub fn assign_outputs(covenants: &[&Covenant], outputs: &[TransactionOutput]) -> Result<(), usize> {
+    let candidates = covenants
+        .iter()
+        .map(|covenant| {
+            if covenant.is_empty() {
+                return Vec::new();
+            }
+            outputs
+                .iter()
+                .enumerate()
+                .filter(|(_, output)| covenant.is_satisfied_by_output(output))
+                .map(|(i, _)| i)
+                .collect()
+        })
+        .collect::<Vec<Vec<usize>>>();
+
+    let mut assigned_to = vec![None; outputs.len()];
+    for (i, covenant) in covenants.iter().enumerate() {
+        if covenant.is_empty() {
+            continue;
+        }
+        let mut visited = vec![false; outputs.len()];
+        if !try_assign(i, &candidates, &mut assigned_to, &mut visited) {
+            return Err(i);
+        }
+    }
+    Ok(())
+}
+
+/// Finds an output for the given covenant, moving previously assigned covenants to other outputs if needed
+fn try_assign(
+    covenant: usize,
+    candidates: &[Vec<usize>],
+    assigned_to: &mut [Option<usize>],
+    visited: &mut [bool],
+) -> bool
+{
+    for &output in &candidates[covenant] {
+        if visited[output] {
+            continue;
+        }
+        visited[output] = true;
+        let is_available = match assigned_to[output] {
+            None => true,
+            Some(other) => try_assign(other, candidates, assigned_to, visited),
+        };
+        if is_available {
+            assigned_to[output] = Some(covenant);
+            return true;
+        }
+    }
+    false
+}
+
+impl TryFrom<&[u8]> for Covenant {
+    type Error = CovenantError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Covenant::from_bytes(bytes)
+    }
+}
+
+impl Display for Covenant {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let rules = self.rules.iter().map(ToString::to_string).collect::<Vec<_>>();
+        write!(f, "[{}]", rules.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::transactions::transaction::OutputFeatures;
+
+    fn output_with(flags: OutputFlags, maturity: u64, covenant: Covenant) -> TransactionOutput {
+        TransactionOutput {
+            features: OutputFeatures {
+                flags,
+                maturity,
+                ..Default::default()
+            },
+            covenant,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn encoding_roundtrip() {
+        let covenant = Covenant::new()
+            .with_rule(CovenantRule::RequiredFlags(OutputFlags::COINBASE_OUTPUT))
+            .with_rule(CovenantRule::MinMaturity(1234))
+            .with_rule(CovenantRule::PreserveCovenant);
+        let bytes = covenant.to_bytes();
+        assert_eq!(bytes.len(), 2 + 9 + 1);
+        assert_eq!(Covenant::from_bytes(&bytes).unwrap(), covenant);
+
+        assert!(Covenant::new().to_bytes().is_empty());
+        assert!(Covenant::from_bytes(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn decoding_invalid_bytes() {
+        assert_eq!(Covenant::from_bytes(&[0xff]), Err(CovenantError::UnknownRuleTag(0xff)));
+        assert_eq!(
+            Covenant::from_bytes(&[RULE_MIN_MATURITY, 1, 2]),
+            Err(CovenantError::UnexpectedEndOfBytes)
+        );
+        assert_eq!(
+            Covenant::from_bytes(&[RULE_REQUIRED_FLAGS, 0b1000_0000]),
+            Err(CovenantError::InvalidOutputFlags(0b1000_0000))
+        );
+        let too_large = vec![RULE_PRESERVE_COVENANT; MAX_COVENANT_BYTES + 1];
+        assert!(matches!(
+            Covenant::from_bytes(&too_large),
+            Err(CovenantError::TooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn satisfied_by_outputs() {
+        let covenant = Covenant::new()
+            .with_rule(CovenantRule::MinMaturity(100))
+            .with_rule(CovenantRule::PreserveCovenant);
+
+        let no_covenant = output_with(OutputFlags::empty(), 100, Covenant::new());
+        let immature = output_with(OutputFlags::empty(), 99, covenant.clone());
+        let valid = output_with(OutputFlags::empty(), 100, covenant.clone());
+
+        let none: &[TransactionOutput] = &[];
+        assert!(!covenant.is_satisfied_by(none));
+        assert!(!covenant.is_satisfied_by(&[no_covenant.clone(), immature.clone()]));
+        assert!(covenant.is_satisfied_by(&[no_covenant.clone(), immature, valid]));
+        assert!(Covenant::new().is_satisfied_by(&[no_covenant]));
+        assert!(Covenant::new().is_satisfied_by(none));
+    }
+
+    #[test]
+    fn outputs_are_assigned_to_one_covenant() {
+        let vault = Covenant::new().with_rule(CovenantRule::PreserveCovenant);
+        let mature = Covenant::new().with_rule(CovenantRule::MinMaturity(100));
+        let vault_output = output_with(OutputFlags::empty(), 200, vault.clone());
+        let mature_output = output_with(OutputFlags::empty(), 100, Covenant::new());
+
+        // One output cannot satisfy two covenants
+        assert_eq!(assign_outputs(&[&vault, &vault], &[vault_output.clone()]), Err(1));
+        assert_eq!(
+            assign_outputs(&[&vault, &vault], &[vault_output.clone(), vault_output.clone()]),
+            Ok(())
+        );
+        // The vault output satisfies both covenants, so it must go to the vault for the mature output to be used
+        assert_eq!(
+            assign_outputs(&[&mature, &vault], &[vault_output.clone(), mature_output.clone()]),
+            Ok(())
+        );
+        assert_eq!(assign_outputs(&[&mature, &vault], &[vault_output]), Err(1));
+        // Empty covenants need no output
+        let empty = Covenant::new();
+        assert_eq!(assign_outputs(&[&empty, &mature], &[mature_output]), Ok(()));
+    }
+
+    #[test]
+    fn required_flags() {
+        let covenant = Covenant::new().with_rule(CovenantRule::RequiredFlags(OutputFlags::COINBASE_OUTPUT));
+        assert!(!covenant.is_satisfied_by_output(&output_with(OutputFlags::empty(), 0, Covenant::new())));
+        assert!(covenant.is_satisfied_by_output(&output_with(OutputFlags::COINBASE_OUTPUT, 0, Covenant::new())));
+    }
+}
@@ -21,28 +21,62 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::{
-    consensus::{KERNEL_WEIGHT, WEIGHT_PER_INPUT, WEIGHT_PER_OUTPUT},
-    transactions::{tari_amount::*, transaction::MINIMUM_TRANSACTION_FEE},
+    consensus::ConsensusEncoding,
+    transactions::{
+        covenant::Covenant,
+        tari_amount::*,
+        transaction::{OutputFeatures, MINIMUM_TRANSACTION_FEE},
+        weight::TransactionWeight,
+    },
 };
 
-pub struct Fee {}
+/// Calculates transaction fees with a transaction weight formula. Use the formula of the consensus constants at the
+/// height the transaction is expected to be mined at, which is what transaction validation weighs it with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Fee(TransactionWeight);
 
 impl Fee {
+    pub const fn new(weighting: TransactionWeight) -> Self {
+        Self(weighting)
+    }
+
     /// Computes the absolute transaction fee given the fee-per-gram, and the size of the transaction
-    pub fn calculate(fee_per_gram: MicroTari, num_kernels: usize, num_inputs: usize, num_outputs: usize) -> MicroTari {
-        (Fee::calculate_weight(num_kernels, num_inputs, num_outputs) * u64::from(fee_per_gram)).into()
+    pub fn calculate(
+        &self,
+        fee_per_gram: MicroTari,
+        num_kernels: usize,
+        num_inputs: usize,
+        num_outputs: usize,
+        output_metadata_bytes: usize,
+    ) -> MicroTari
+    {
+        self.0.calculate_fee(
+            fee_per_gram,
+            num_kernels,
+            num_inputs,
+            num_outputs,
+            output_metadata_bytes,
+        )
     }
 
     /// Computes the absolute transaction fee using `calculate`, but the resulting fee will always be at least the
     /// minimum network transaction fee.
     pub fn calculate_with_minimum(
+        &self,
         fee_per_gram: MicroTari,
         num_kernels: usize,
         num_inputs: usize,
         num_outputs: usize,
+        output_metadata_bytes: usize,
     ) -> MicroTari
     {
-        let fee = Fee::calculate(fee_per_gram, num_kernels, num_inputs, num_outputs);
+        let fee = self.calculate(
+            fee_per_gram,
+            num_kernels,
+            num_inputs,
+            num_outputs,
+            output_metadata_bytes,
+        );
         if fee < MINIMUM_TRANSACTION_FEE {
             MINIMUM_TRANSACTION_FEE
         } else {
@@ -50,10 +84,39 @@ impl Fee {
         }
     }
 
-    /// Calculate the weight of a transaction based on the number of inputs and outputs
-    pub fn calculate_weight(num_kernels: usize, num_inputs: usize, num_outputs: usize) -> u64 {
-        KERNEL_WEIGHT * num_kernels as u64 +
-            WEIGHT_PER_INPUT * num_inputs as u64 +
-            WEIGHT_PER_OUTPUT * num_outputs as u64
+    /// Calculate the weight of a transaction based on the number of kernels, inputs and outputs and the size of the
+    /// output metadata
+    pub fn calculate_weight(
+        &self,
+        num_kernels: usize,
+        num_inputs: usize,
+        num_outputs: usize,
+        output_metadata_bytes: usize,
+    ) -> u64
+    {
+        self.0
+            .calculate(num_kernels, num_inputs, num_outputs, output_metadata_bytes)
+    }
+
+    /// The size of the metadata of an output, which is the length of the consensus encoding of its features and
+    /// covenant
+    pub fn output_metadata_size(features: &OutputFeatures, covenant: &Covenant) -> usize {
+        features.to_consensus_bytes().len() + covenant.to_consensus_bytes().len()
+    }
+
+    /// The size of the metadata of `num_outputs` outputs with default features and an empty covenant, which is what
+    /// change and recipient outputs are created with
+    pub fn default_output_metadata_size(num_outputs: usize) -> usize {
+        num_outputs * Self::output_metadata_size(&OutputFeatures::default(), &Covenant::default())
+    }
+
+    pub fn weighting(&self) -> &TransactionWeight {
+        &self.0
+    }
+}
+
+impl From<TransactionWeight> for Fee {
+    fn from(weighting: TransactionWeight) -> Self {
+        Self(weighting)
     }
 }
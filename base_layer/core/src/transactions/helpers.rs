@@ -22,6 +22,7 @@
 
 use crate::transactions::{
     fee::Fee,
+    multisig::{MultisigLock, MultisigWitness},
     tari_amount::MicroTari,
     transaction::{
         KernelBuilder,
@@ -47,6 +48,51 @@ use tari_crypto::{
     range_proof::RangeProofService,
 };
 
+/// Create a 2-of-3 multisig lock, returning the secret keys of its participants in lock order
+pub fn create_test_multisig_lock() -> (MultisigLock, Vec<PrivateKey>) {
+    let mut secrets = (0..3).map(|_| PrivateKey::random(&mut OsRng)).collect::<Vec<_>>();
+    let lock = MultisigLock::new(2, secrets.iter().map(PublicKey::from_secret_key).collect()).unwrap();
+    secrets.sort_by_key(|k| lock.index_of(&PublicKey::from_secret_key(k)));
+    (lock, secrets)
+}
+
+/// Run the multisig signing protocol between the given signers of `lock` in one go
+pub fn create_multisig_witness(
+    lock: &MultisigLock,
+    secrets: &[PrivateKey],
+    signers: Vec<u8>,
+    commitment: &Commitment,
+    kernel_excess: Commitment,
+) -> MultisigWitness
+{
+    let nonces = signers
+        .iter()
+        .map(|_| PrivateKey::random(&mut OsRng))
+        .collect::<Vec<_>>();
+    let aggregate_nonce = nonces
+        .iter()
+        .skip(1)
+        .fold(PublicKey::from_secret_key(&nonces[0]), |sum, r| {
+            &sum + &PublicKey::from_secret_key(r)
+        });
+    let partials = signers
+        .iter()
+        .zip(nonces.iter())
+        .map(|(&i, nonce)| {
+            lock.partial_sign(
+                &secrets[i as usize],
+                nonce,
+                &signers,
+                &aggregate_nonce,
+                commitment,
+                &kernel_excess,
+            )
+            .unwrap()
+        })
+        .collect::<Vec<_>>();
+    lock.aggregate(signers, &partials, commitment, kernel_excess).unwrap()
+}
+
 pub fn make_input<R: Rng + CryptoRng>(
     rng: &mut R,
     val: MicroTari,
@@ -109,7 +155,11 @@ pub fn create_random_signature(fee: MicroTari, lock_height: u64) -> (PublicKey,
 /// Generate a random transaction signature, returning the public key (excess) and the signature.
 pub fn create_signature(k: PrivateKey, fee: MicroTari, lock_height: u64) -> Signature {
     let r = PrivateKey::random(&mut OsRng);
-    let tx_meta = TransactionMetadata { fee, lock_height };
+    let tx_meta = TransactionMetadata {
+        fee,
+        lock_height,
+        ..Default::default()
+    };
     let e = build_challenge(&PublicKey::from_secret_key(&r), &tx_meta);
     Signature::sign(k, r, &e).unwrap()
 }
@@ -124,7 +174,11 @@ pub fn create_random_signature_from_s_key(
     let _rng = rand::thread_rng();
     let r = PrivateKey::random(&mut OsRng);
     let p = PK::from_secret_key(&s_key);
-    let tx_meta = TransactionMetadata { fee, lock_height };
+    let tx_meta = TransactionMetadata {
+        fee,
+        lock_height,
+        ..Default::default()
+    };
     let e = build_challenge(&PublicKey::from_secret_key(&r), &tx_meta);
     (p, Signature::sign(s_key, r, &e).unwrap())
 }
@@ -255,7 +309,7 @@ pub fn create_tx(
     unblinded_inputs.push(input.clone());
     stx_builder.with_input(utxo, input);
 
-    let estimated_fee = Fee::calculate(fee_per_gram, 1, input_count, output_count);
+    let estimated_fee = Fee::default().calculate(fee_per_gram, 1, input_count, output_count, 0);
     let amount_per_output = (amount - estimated_fee) / output_count as u64;
     let amount_for_last_output = (amount - estimated_fee) - amount_per_output * (output_count as u64 - 1);
     for i in 0..output_count {
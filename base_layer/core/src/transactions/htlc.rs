@@ -0,0 +1,372 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Hash time-locked contracts (HTLCs) are the building block of cross-chain atomic swaps. A hash time-locked output
+//! can be spent in one of two ways:
+//! 1. The claim path: the claimant reveals the preimage of the hash lock and signs with the claim key. The preimage
+//!    is then public, so the counterparty can use it to claim the matching contract on the other chain.
+//! 2. The refund path: once the chain reaches the refund height, the original owner signs with the refund key.
+//!
+//! The hash lock is the SHA-256 hash of a 32 byte preimage, so that it is compatible with the HTLCs of other chains.
+//! The witness that unlocks the output is carried by the spending input. Its signature commits to the input and to
+//! the excess of a kernel of the spending transaction, so the witness cannot be moved to a different transaction.
+
+use crate::transactions::types::{Challenge, Commitment, MessageHash, PrivateKey, PublicKey, Signature};
+use digest::Digest;
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::{
+    fmt,
+    fmt::{Display, Formatter},
+    hash::{Hash, Hasher},
+};
+use tari_crypto::tari_utilities::{hex::Hex, ByteArray};
+use thiserror::Error;
+
+/// The size, in bytes, of a hash lock preimage
+pub const HTLC_PREIMAGE_LENGTH: usize = 32;
+/// The weight that the witness of a hash time-locked input adds to the transaction spending it: a signature check, and
+/// on the claim path also a hash of the preimage. Both paths weigh the same, so that a spend can be priced before the
+/// path is known.
+pub const HTLC_WITNESS_WEIGHT: u64 = 2;
+
+const CLAIM_PATH: u8 = 0x01;
+const REFUND_PATH: u8 = 0x02;
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum HtlcError {
+    #[error("The preimage must be 32 bytes, got {0} bytes")]
+    InvalidPreimageLength(usize),
+    #[error("The preimage does not match the hash lock")]
+    PreimageMismatch,
+    #[error("The witness signature is invalid")]
+    InvalidSignature,
+    #[error("Could not sign the witness: {0}")]
+    SigningError(String),
+}
+
+/// Returns the hash lock of the given preimage, i.e. its SHA-256 hash
+pub fn hash_preimage(preimage: &[u8]) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&Sha256::digest(preimage));
+    hash
+}
+
+/// Generate a random preimage for a new hash lock
+pub fn generate_preimage() -> [u8; HTLC_PREIMAGE_LENGTH] {
+    let mut preimage = [0u8; HTLC_PREIMAGE_LENGTH];
+    OsRng.fill_bytes(&mut preimage);
+    preimage
+}
+
+/// The hash time lock part of an output's features
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HashTimeLock {
+    /// The SHA-256 hash of the preimage that the claimant must reveal
+    pub hash_lock: [u8; 32],
+    /// The public key of the party that may claim the output by revealing the preimage
+    pub claim_public_key: PublicKey,
+    /// The public key of the party that may reclaim the output once the refund height is reached
+    pub refund_public_key: PublicKey,
+    /// The height from which the output may be refunded
+    pub refund_height: u64,
+}
+
+impl HashTimeLock {
+    pub fn new(
+        hash_lock: [u8; 32],
+        claim_public_key: PublicKey,
+        refund_public_key: PublicKey,
+        refund_height: u64,
+    ) -> Self
+    {
+        Self {
+            hash_lock,
+            claim_public_key,
+            refund_public_key,
+            refund_height,
+        }
+    }
+
+    /// Checks that the given preimage unlocks this hash lock
+    pub fn check_preimage(&self, preimage: &[u8]) -> Result<(), HtlcError> {
+        if preimage.len() != HTLC_PREIMAGE_LENGTH {
+            return Err(HtlcError::InvalidPreimageLength(preimage.len()));
+        }
+        if hash_preimage(preimage) != self.hash_lock {
+            return Err(HtlcError::PreimageMismatch);
+        }
+        Ok(())
+    }
+
+    /// The challenge that is signed to spend the output with the given commitment in a transaction containing a
+    /// kernel with the given excess
+    fn spend_challenge(&self, path: u8, commitment: &Commitment, kernel_excess: &Commitment) -> MessageHash {
+        Challenge::new()
+            .chain(&[path])
+            .chain(&self.to_bytes())
+            .chain(commitment.as_bytes())
+            .chain(kernel_excess.as_bytes())
+            .result()
+            .to_vec()
+    }
+
+    /// Create the witness that claims the output with the given commitment by revealing the preimage, signed with the
+    /// claim secret key
+    pub fn sign_claim(
+        &self,
+        claim_secret: PrivateKey,
+        nonce: PrivateKey,
+        preimage: Vec<u8>,
+        commitment: &Commitment,
+        kernel_excess: Commitment,
+    ) -> Result<HtlcWitness, HtlcError>
+    {
+        self.check_preimage(&preimage)?;
+        let challenge = self.spend_challenge(CLAIM_PATH, commitment, &kernel_excess);
+        let signature =
+            Signature::sign(claim_secret, nonce, &challenge).map_err(|e| HtlcError::SigningError(e.to_string()))?;
+        Ok(HtlcWitness::Claim {
+            preimage,
+            kernel_excess,
+            signature,
+        })
+    }
+
+    /// Create the witness that refunds the output with the given commitment, signed with the refund secret key
+    pub fn sign_refund(
+        &self,
+        refund_secret: PrivateKey,
+        nonce: PrivateKey,
+        commitment: &Commitment,
+        kernel_excess: Commitment,
+    ) -> Result<HtlcWitness, HtlcError>
+    {
+        let challenge = self.spend_challenge(REFUND_PATH, commitment, &kernel_excess);
+        let signature =
+            Signature::sign(refund_secret, nonce, &challenge).map_err(|e| HtlcError::SigningError(e.to_string()))?;
+        Ok(HtlcWitness::Refund {
+            kernel_excess,
+            signature,
+        })
+    }
+
+    /// Verify that the witness unlocks the output with the given commitment. This does not check the refund height or
+    /// that the kernel the witness commits to is part of the spending transaction.
+    pub fn verify_witness(&self, witness: &HtlcWitness, commitment: &Commitment) -> Result<(), HtlcError> {
+        let (public_key, challenge, signature) = match witness {
+            HtlcWitness::Claim {
+                preimage,
+                kernel_excess,
+                signature,
+            } => {
+                self.check_preimage(preimage)?;
+                let challenge = self.spend_challenge(CLAIM_PATH, commitment, kernel_excess);
+                (&self.claim_public_key, challenge, signature)
+            },
+            HtlcWitness::Refund {
+                kernel_excess,
+                signature,
+            } => {
+                let challenge = self.spend_challenge(REFUND_PATH, commitment, kernel_excess);
+                (&self.refund_public_key, challenge, signature)
+            },
+        };
+        if signature.verify_challenge(public_key, &challenge) {
+            Ok(())
+        } else {
+            Err(HtlcError::InvalidSignature)
+        }
+    }
+
+    /// Returns the canonical byte representation of this hash time lock
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(32 + 32 + 32 + 8);
+        buf.extend_from_slice(&self.hash_lock);
+        buf.extend_from_slice(self.claim_public_key.as_bytes());
+        buf.extend_from_slice(self.refund_public_key.as_bytes());
+        buf.extend_from_slice(&self.refund_height.to_le_bytes());
+        buf
+    }
+}
+
+impl Hash for HashTimeLock {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_bytes().hash(state)
+    }
+}
+
+impl Display for HashTimeLock {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "HTLC hash lock {} refundable from height {}",
+            self.hash_lock.to_vec().to_hex(),
+            self.refund_height
+        )
+    }
+}
+
+/// The data that unlocks a hash time-locked output, carried by the input that spends it
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum HtlcWitness {
+    /// Spends the output with the preimage of the hash lock and a signature by the claim key
+    Claim {
+        preimage: Vec<u8>,
+        kernel_excess: Commitment,
+        signature: Signature,
+    },
+    /// Spends the output after the refund height with a signature by the refund key
+    Refund {
+        kernel_excess: Commitment,
+        signature: Signature,
+    },
+}
+
+impl HtlcWitness {
+    /// The excess of the kernel of the spending transaction that the witness signature commits to
+    pub fn kernel_excess(&self) -> &Commitment {
+        match self {
+            HtlcWitness::Claim { kernel_excess, .. } => kernel_excess,
+            HtlcWitness::Refund { kernel_excess, .. } => kernel_excess,
+        }
+    }
+
+    /// The revealed preimage, if this witness claims the output
+    pub fn preimage(&self) -> Option<&[u8]> {
+        match self {
+            HtlcWitness::Claim { preimage, .. } => Some(preimage),
+            HtlcWitness::Refund { .. } => None,
+        }
+    }
+
+    pub fn is_refund(&self) -> bool {
+        matches!(self, HtlcWitness::Refund { .. })
+    }
+}
+
+impl Display for HtlcWitness {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            HtlcWitness::Claim { preimage, .. } => write!(f, "HTLC claim with preimage {}", preimage.to_hex()),
+            HtlcWitness::Refund { .. } => write!(f, "HTLC refund"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::transactions::types::CommitmentFactory;
+    use tari_crypto::{
+        commitment::HomomorphicCommitmentFactory,
+        keys::{PublicKey as PublicKeyTrait, SecretKey},
+    };
+
+    fn random_commitment() -> Commitment {
+        CommitmentFactory::default().commit_value(&PrivateKey::random(&mut OsRng), 100)
+    }
+
+    fn nonce() -> PrivateKey {
+        PrivateKey::random(&mut OsRng)
+    }
+
+    #[test]
+    fn it_checks_the_preimage() {
+        let preimage = generate_preimage();
+        let (_, claim) = PublicKey::random_keypair(&mut OsRng);
+        let (_, refund) = PublicKey::random_keypair(&mut OsRng);
+        let htlc = HashTimeLock::new(hash_preimage(&preimage), claim, refund, 100);
+        assert!(htlc.check_preimage(&preimage).is_ok());
+        assert_eq!(htlc.check_preimage(&[0u8; 32]), Err(HtlcError::PreimageMismatch));
+        assert_eq!(
+            htlc.check_preimage(&preimage[..31]),
+            Err(HtlcError::InvalidPreimageLength(31))
+        );
+    }
+
+    #[test]
+    fn claim_witness() {
+        let preimage = generate_preimage().to_vec();
+        let (k_claim, claim) = PublicKey::random_keypair(&mut OsRng);
+        let (k_refund, refund) = PublicKey::random_keypair(&mut OsRng);
+        let htlc = HashTimeLock::new(hash_preimage(&preimage), claim, refund, 100);
+        let commitment = random_commitment();
+        let excess = random_commitment();
+
+        // Only the claimant can claim, and only with the preimage
+        assert_eq!(
+            htlc.sign_claim(k_claim.clone(), nonce(), vec![1u8; 32], &commitment, excess.clone()),
+            Err(HtlcError::PreimageMismatch)
+        );
+        let witness = htlc
+            .sign_claim(k_refund, nonce(), preimage.clone(), &commitment, excess.clone())
+            .unwrap();
+        assert_eq!(
+            htlc.verify_witness(&witness, &commitment),
+            Err(HtlcError::InvalidSignature)
+        );
+        let witness = htlc
+            .sign_claim(k_claim, nonce(), preimage.clone(), &commitment, excess)
+            .unwrap();
+        assert!(htlc.verify_witness(&witness, &commitment).is_ok());
+        assert_eq!(witness.preimage(), Some(preimage.as_slice()));
+
+        // The witness is bound to the output being spent
+        assert_eq!(
+            htlc.verify_witness(&witness, &random_commitment()),
+            Err(HtlcError::InvalidSignature)
+        );
+        // ...and to the kernel of the spending transaction
+        let mut moved = witness;
+        if let HtlcWitness::Claim { kernel_excess, .. } = &mut moved {
+            *kernel_excess = random_commitment();
+        }
+        assert_eq!(
+            htlc.verify_witness(&moved, &commitment),
+            Err(HtlcError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn refund_witness() {
+        let (k_claim, claim) = PublicKey::random_keypair(&mut OsRng);
+        let (k_refund, refund) = PublicKey::random_keypair(&mut OsRng);
+        let htlc = HashTimeLock::new(hash_preimage(&generate_preimage()), claim, refund, 100);
+        let commitment = random_commitment();
+
+        let witness = htlc
+            .sign_refund(k_claim, nonce(), &commitment, random_commitment())
+            .unwrap();
+        assert_eq!(
+            htlc.verify_witness(&witness, &commitment),
+            Err(HtlcError::InvalidSignature)
+        );
+        let witness = htlc
+            .sign_refund(k_refund, nonce(), &commitment, random_commitment())
+            .unwrap();
+        assert!(htlc.verify_witness(&witness, &commitment).is_ok());
+        assert!(witness.is_refund());
+        assert_eq!(witness.preimage(), None);
+    }
+}
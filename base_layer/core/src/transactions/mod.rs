@@ -1,11 +1,19 @@
 pub mod aggregated_body;
+pub mod asset;
 pub mod bullet_rangeproofs;
+pub mod covenant;
 pub mod fee;
+pub mod htlc;
+pub mod multisig;
+pub mod one_sided;
+pub mod range_proof_batch;
+pub mod script;
 pub mod tari_amount;
 pub mod transaction;
 #[allow(clippy::op_ref)]
 pub mod transaction_protocol;
 pub mod types;
+pub mod weight;
 // Re-export commonly used structs
 pub use transaction_protocol::{recipient::ReceiverTransactionProtocol, sender::SenderTransactionProtocol};
 // Re-export the crypto crate to make exposing traits etc easier for clients of this crate
@@ -0,0 +1,547 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! m-of-n multisig outputs, e.g. 2-of-3 treasury outputs. A multisig locked output can only be spent with a signature
+//! by `threshold` of its participants. The signers combine their keys and nonces into a single Schnorr signature. The
+//! witness still lists the signers and its verification sums their keys, so from version 2 of the
+//! [transaction weight](crate::transactions::weight::TransactionWeight) it is weighed per signer:
+//! 1. Every participant key is weighted with a coefficient that is derived from all the keys of the lock. This stops a
+//!    participant from choosing its key such that it cancels out the keys of the others.
+//! 2. The signers first exchange [commitments](nonce_commitment) to their public nonces, and only then the nonces
+//!    themselves. No signer can choose its nonce after seeing those of the others, which would let it forge signatures
+//!    by combining many concurrent signing sessions. Each signer then signs the joint challenge with its weighted key.
+//! 3. The partial signatures add up to a signature that verifies against the sum of the weighted keys of the signers.
+//!
+//! As for hash time-locked outputs, the witness that unlocks the output is carried by the spending input. Its signature
+//! commits to the input and to the excess of a kernel of the spending transaction, so the witness cannot be moved to a
+//! different transaction.
+
+use crate::transactions::types::{Challenge, Commitment, MessageHash, PrivateKey, PublicKey, Signature};
+use digest::Digest;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    fmt::{Display, Formatter},
+    hash::{Hash, Hasher},
+};
+use tari_crypto::{keys::PublicKey as PublicKeyTrait, tari_utilities::ByteArray};
+use thiserror::Error;
+
+/// The maximum number of participants of a multisig lock
+pub const MAX_MULTISIG_PARTICIPANTS: usize = 16;
+
+const KEY_COEFFICIENT_TAG: &[u8] = b"multisig_key_coefficient";
+const NONCE_COMMITMENT_TAG: &[u8] = b"multisig_nonce_commitment";
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum MultisigError {
+    #[error("The threshold must be between 1 and the number of participants ({participants}), got {threshold}")]
+    InvalidThreshold { threshold: u8, participants: usize },
+    #[error("A multisig lock can have at most 16 participants, got {0}")]
+    TooManyParticipants(usize),
+    #[error("The participant keys must be sorted and unique")]
+    InvalidParticipants,
+    #[error("The key is not a participant of the multisig lock")]
+    NotAParticipant,
+    #[error("Expected {expected} signers, got {got}")]
+    InvalidSignerCount { expected: usize, got: usize },
+    #[error("The signers must be distinct participants, in ascending order")]
+    InvalidSigners,
+    #[error("The signature is invalid")]
+    InvalidSignature,
+    #[error("Could not sign the witness: {0}")]
+    SigningError(String),
+}
+
+/// The hash commitment to the public nonce of the signer with the given key, which the signer shares before revealing
+/// the nonce
+pub fn nonce_commitment(public_key: &PublicKey, public_nonce: &PublicKey) -> Vec<u8> {
+    Challenge::new()
+        .chain(NONCE_COMMITMENT_TAG)
+        .chain(public_key.as_bytes())
+        .chain(public_nonce.as_bytes())
+        .result()
+        .to_vec()
+}
+
+/// The multisig lock part of an output's features
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultisigLock {
+    /// The number of participants that have to sign to spend the output
+    pub threshold: u8,
+    /// The public keys of the participants, sorted in ascending order. Signers are referred to by their index in this
+    /// list.
+    pub public_keys: Vec<PublicKey>,
+}
+
+impl MultisigLock {
+    /// Create a `threshold`-of-n lock for the given participant keys, in any order
+    pub fn new(threshold: u8, mut public_keys: Vec<PublicKey>) -> Result<Self, MultisigError> {
+        public_keys.sort();
+        let lock = Self { threshold, public_keys };
+        lock.validate()?;
+        Ok(lock)
+    }
+
+    /// Checks that the lock is well formed. Locks created with [new](Self::new) always are, but locks received from
+    /// other parties may not be.
+    pub fn validate(&self) -> Result<(), MultisigError> {
+        let participants = self.public_keys.len();
+        if participants > MAX_MULTISIG_PARTICIPANTS {
+            return Err(MultisigError::TooManyParticipants(participants));
+        }
+        if self.threshold == 0 || self.threshold as usize > participants {
+            return Err(MultisigError::InvalidThreshold {
+                threshold: self.threshold,
+                participants,
+            });
+        }
+        if self.public_keys.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(MultisigError::InvalidParticipants);
+        }
+        Ok(())
+    }
+
+    /// The index of the participant with the given key
+    pub fn index_of(&self, public_key: &PublicKey) -> Option<u8> {
+        self.public_keys.iter().position(|k| k == public_key).map(|i| i as u8)
+    }
+
+    /// The coefficient that weighs the key of the participant with the given index
+    fn key_coefficient(&self, index: u8) -> Result<PrivateKey, MultisigError> {
+        let public_key = self
+            .public_keys
+            .get(index as usize)
+            .ok_or(MultisigError::InvalidSigners)?;
+        let hash = Challenge::new()
+            .chain(KEY_COEFFICIENT_TAG)
+            .chain(&self.to_bytes())
+            .chain(public_key.as_bytes())
+            .result();
+        PrivateKey::from_bytes(&hash).map_err(|e| MultisigError::SigningError(e.to_string()))
+    }
+
+    /// The weighted key of the participant with the given index
+    fn weighted_public_key(&self, index: u8) -> Result<PublicKey, MultisigError> {
+        let public_key = self
+            .public_keys
+            .get(index as usize)
+            .ok_or(MultisigError::InvalidSigners)?;
+        Ok(&self.key_coefficient(index)? * public_key)
+    }
+
+    /// Checks that `signers` are exactly `threshold` distinct participants, in ascending order
+    fn check_signers(&self, signers: &[u8]) -> Result<(), MultisigError> {
+        if signers.len() != self.threshold as usize {
+            return Err(MultisigError::InvalidSignerCount {
+                expected: self.threshold as usize,
+                got: signers.len(),
+            });
+        }
+        if signers.windows(2).any(|w| w[0] >= w[1]) || signers.iter().any(|&i| i as usize >= self.public_keys.len()) {
+            return Err(MultisigError::InvalidSigners);
+        }
+        Ok(())
+    }
+
+    /// The key that the signature of the given signers verifies against, i.e. the sum of their weighted keys
+    pub fn aggregate_public_key(&self, signers: &[u8]) -> Result<PublicKey, MultisigError> {
+        self.check_signers(signers)?;
+        let mut keys = signers.iter().map(|&i| self.weighted_public_key(i));
+        let first = keys.next().ok_or(MultisigError::InvalidSigners)??;
+        keys.try_fold(first, |sum, key| Ok(&sum + &key?))
+    }
+
+    /// The challenge that the signers sign to spend the output with the given commitment in a transaction containing a
+    /// kernel with the given excess
+    fn spend_challenge(
+        &self,
+        aggregate_public_key: &PublicKey,
+        aggregate_nonce: &PublicKey,
+        commitment: &Commitment,
+        kernel_excess: &Commitment,
+    ) -> MessageHash
+    {
+        Challenge::new()
+            .chain(&self.to_bytes())
+            .chain(aggregate_public_key.as_bytes())
+            .chain(aggregate_nonce.as_bytes())
+            .chain(commitment.as_bytes())
+            .chain(kernel_excess.as_bytes())
+            .result()
+            .to_vec()
+    }
+
+    /// Create the partial signature of one of the `signers`. `nonce` is the secret nonce of the signer, whose public
+    /// nonce has been shared with the other signers, and `aggregate_nonce` is the sum of the public nonces of all the
+    /// signers. A nonce must never be used for more than one partial signature.
+    pub fn partial_sign(
+        &self,
+        secret_key: &PrivateKey,
+        nonce: &PrivateKey,
+        signers: &[u8],
+        aggregate_nonce: &PublicKey,
+        commitment: &Commitment,
+        kernel_excess: &Commitment,
+    ) -> Result<Signature, MultisigError>
+    {
+        let index = self
+            .index_of(&PublicKey::from_secret_key(secret_key))
+            .filter(|i| signers.contains(i))
+            .ok_or(MultisigError::NotAParticipant)?;
+        let aggregate_public_key = self.aggregate_public_key(signers)?;
+        let challenge = self.spend_challenge(&aggregate_public_key, aggregate_nonce, commitment, kernel_excess);
+        let weighted_secret = &self.key_coefficient(index)? * secret_key;
+        Signature::sign(weighted_secret, nonce.clone(), &challenge)
+            .map_err(|e| MultisigError::SigningError(e.to_string()))
+    }
+
+    /// Verify the partial signature of the signer with the given key, so that a misbehaving signer can be identified
+    /// before the partial signatures are combined
+    pub fn verify_partial_signature(
+        &self,
+        signature: &Signature,
+        public_key: &PublicKey,
+        signers: &[u8],
+        aggregate_nonce: &PublicKey,
+        commitment: &Commitment,
+        kernel_excess: &Commitment,
+    ) -> Result<(), MultisigError>
+    {
+        let index = self
+            .index_of(public_key)
+            .filter(|i| signers.contains(i))
+            .ok_or(MultisigError::NotAParticipant)?;
+        let aggregate_public_key = self.aggregate_public_key(signers)?;
+        let challenge = self.spend_challenge(&aggregate_public_key, aggregate_nonce, commitment, kernel_excess);
+        if signature.verify_challenge(&self.weighted_public_key(index)?, &challenge) {
+            Ok(())
+        } else {
+            Err(MultisigError::InvalidSignature)
+        }
+    }
+
+    /// Combine the partial signatures of the `signers` into the witness that spends the output with the given
+    /// commitment
+    pub fn aggregate(
+        &self,
+        signers: Vec<u8>,
+        partial_signatures: &[Signature],
+        commitment: &Commitment,
+        kernel_excess: Commitment,
+    ) -> Result<MultisigWitness, MultisigError>
+    {
+        if partial_signatures.len() != signers.len() {
+            return Err(MultisigError::InvalidSignerCount {
+                expected: signers.len(),
+                got: partial_signatures.len(),
+            });
+        }
+        let mut signatures = partial_signatures.iter();
+        let first = signatures.next().ok_or(MultisigError::InvalidSigners)?.clone();
+        let signature = signatures.fold(first, |sum, s| &sum + s);
+        let witness = MultisigWitness {
+            signers,
+            kernel_excess,
+            signature,
+        };
+        self.verify_witness(&witness, commitment)?;
+        Ok(witness)
+    }
+
+    /// Verify that the witness unlocks the output with the given commitment. This does not check that the kernel the
+    /// witness commits to is part of the spending transaction.
+    pub fn verify_witness(&self, witness: &MultisigWitness, commitment: &Commitment) -> Result<(), MultisigError> {
+        let aggregate_public_key = self.aggregate_public_key(&witness.signers)?;
+        let challenge = self.spend_challenge(
+            &aggregate_public_key,
+            witness.signature.get_public_nonce(),
+            commitment,
+            &witness.kernel_excess,
+        );
+        if witness.signature.verify_challenge(&aggregate_public_key, &challenge) {
+            Ok(())
+        } else {
+            Err(MultisigError::InvalidSignature)
+        }
+    }
+
+    /// Returns the canonical byte representation of this multisig lock
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(2 + 32 * self.public_keys.len());
+        buf.push(self.threshold);
+        buf.push(self.public_keys.len() as u8);
+        for public_key in &self.public_keys {
+            buf.extend_from_slice(public_key.as_bytes());
+        }
+        buf
+    }
+}
+
+impl Hash for MultisigLock {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_bytes().hash(state)
+    }
+}
+
+impl Display for MultisigLock {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-of-{} multisig", self.threshold, self.public_keys.len())
+    }
+}
+
+/// The data that unlocks a multisig locked output, carried by the input that spends it
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct MultisigWitness {
+    /// The indices of the participants that signed, in ascending order
+    pub signers: Vec<u8>,
+    /// The excess of the kernel of the spending transaction that the signature commits to
+    pub kernel_excess: Commitment,
+    /// The sum of the partial signatures of the signers
+    pub signature: Signature,
+}
+
+impl Display for MultisigWitness {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Multisig witness signed by participants {:?}", self.signers)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::transactions::{helpers::create_test_multisig_lock, types::CommitmentFactory};
+    use rand::rngs::OsRng;
+    use tari_crypto::{commitment::HomomorphicCommitmentFactory, keys::SecretKey};
+
+    fn random_commitment() -> Commitment {
+        CommitmentFactory::default().commit_value(&PrivateKey::random(&mut OsRng), 100)
+    }
+
+    /// Runs the signing protocol for the given signers
+    fn sign(
+        lock: &MultisigLock,
+        secrets: &[PrivateKey],
+        signers: Vec<u8>,
+        commitment: &Commitment,
+        kernel_excess: &Commitment,
+    ) -> Result<MultisigWitness, MultisigError>
+    {
+        let nonces = signers
+            .iter()
+            .map(|_| PrivateKey::random(&mut OsRng))
+            .collect::<Vec<_>>();
+        let public_nonces = nonces.iter().map(PublicKey::from_secret_key).collect::<Vec<_>>();
+        let aggregate_nonce = public_nonces[1..]
+            .iter()
+            .fold(public_nonces[0].clone(), |sum, r| &sum + r);
+        let partials = signers
+            .iter()
+            .zip(nonces.iter())
+            .map(|(&i, nonce)| {
+                lock.partial_sign(
+                    &secrets[i as usize],
+                    nonce,
+                    &signers,
+                    &aggregate_nonce,
+                    commitment,
+                    kernel_excess,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        for (&i, partial) in signers.iter().zip(partials.iter()) {
+            lock.verify_partial_signature(
+                partial,
+                &lock.public_keys[i as usize],
+                &signers,
+                &aggregate_nonce,
+                commitment,
+                kernel_excess,
+            )?;
+        }
+        lock.aggregate(signers, &partials, commitment, kernel_excess.clone())
+    }
+
+    #[test]
+    fn it_validates_the_lock() {
+        let keys = (0..3)
+            .map(|_| PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)))
+            .collect::<Vec<_>>();
+        assert!(MultisigLock::new(2, keys.clone()).is_ok());
+        assert_eq!(
+            MultisigLock::new(0, keys.clone()),
+            Err(MultisigError::InvalidThreshold {
+                threshold: 0,
+                participants: 3
+            })
+        );
+        assert!(MultisigLock::new(4, keys.clone()).is_err());
+        let mut duplicated = keys.clone();
+        duplicated.push(keys[0].clone());
+        assert_eq!(
+            MultisigLock::new(2, duplicated),
+            Err(MultisigError::InvalidParticipants)
+        );
+        let too_many = (0..17)
+            .map(|_| PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)))
+            .collect();
+        assert_eq!(
+            MultisigLock::new(2, too_many),
+            Err(MultisigError::TooManyParticipants(17))
+        );
+        let mut unsorted = MultisigLock::new(2, keys).unwrap();
+        unsorted.public_keys.reverse();
+        assert_eq!(unsorted.validate(), Err(MultisigError::InvalidParticipants));
+    }
+
+    #[test]
+    fn any_two_of_three_can_spend() {
+        let (lock, secrets) = create_test_multisig_lock();
+        let commitment = random_commitment();
+        let excess = random_commitment();
+        for signers in &[vec![0u8, 1], vec![0, 2], vec![1, 2]] {
+            let witness = sign(&lock, &secrets, signers.clone(), &commitment, &excess).unwrap();
+            assert!(lock.verify_witness(&witness, &commitment).is_ok());
+        }
+    }
+
+    #[test]
+    fn signers_must_meet_the_threshold() {
+        let (lock, secrets) = create_test_multisig_lock();
+        let commitment = random_commitment();
+        let excess = random_commitment();
+        assert_eq!(
+            sign(&lock, &secrets, vec![0], &commitment, &excess),
+            Err(MultisigError::InvalidSignerCount { expected: 2, got: 1 })
+        );
+        assert_eq!(
+            sign(&lock, &secrets, vec![1, 0], &commitment, &excess),
+            Err(MultisigError::InvalidSigners)
+        );
+
+        // A key outside of the lock cannot sign
+        let outsider = PrivateKey::random(&mut OsRng);
+        let nonce = PrivateKey::random(&mut OsRng);
+        assert_eq!(
+            lock.partial_sign(
+                &outsider,
+                &nonce,
+                &[0, 1],
+                &PublicKey::from_secret_key(&nonce),
+                &commitment,
+                &excess
+            ),
+            Err(MultisigError::NotAParticipant)
+        );
+        // ...and neither can a participant that is not one of the signers
+        assert_eq!(
+            lock.partial_sign(
+                &secrets[2],
+                &nonce,
+                &[0, 1],
+                &PublicKey::from_secret_key(&nonce),
+                &commitment,
+                &excess
+            ),
+            Err(MultisigError::NotAParticipant)
+        );
+    }
+
+    #[test]
+    fn it_detects_a_bad_partial_signature() {
+        let (lock, secrets) = create_test_multisig_lock();
+        let commitment = random_commitment();
+        let excess = random_commitment();
+        let nonces = [PrivateKey::random(&mut OsRng), PrivateKey::random(&mut OsRng)];
+        let aggregate_nonce = &PublicKey::from_secret_key(&nonces[0]) + &PublicKey::from_secret_key(&nonces[1]);
+        let partial = lock
+            .partial_sign(&secrets[0], &nonces[0], &[0, 1], &aggregate_nonce, &commitment, &excess)
+            .unwrap();
+        assert!(lock
+            .verify_partial_signature(
+                &partial,
+                &lock.public_keys[0],
+                &[0, 1],
+                &aggregate_nonce,
+                &commitment,
+                &excess
+            )
+            .is_ok());
+        // The partial signature of one signer does not pass for the other
+        assert_eq!(
+            lock.verify_partial_signature(
+                &partial,
+                &lock.public_keys[1],
+                &[0, 1],
+                &aggregate_nonce,
+                &commitment,
+                &excess
+            ),
+            Err(MultisigError::InvalidSignature)
+        );
+        // A single partial signature does not unlock the output
+        assert_eq!(
+            lock.aggregate(vec![0, 1], &[partial.clone(), partial], &commitment, excess),
+            Err(MultisigError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn the_witness_is_bound_to_the_spend() {
+        let (lock, secrets) = create_test_multisig_lock();
+        let commitment = random_commitment();
+        let excess = random_commitment();
+        let witness = sign(&lock, &secrets, vec![0, 2], &commitment, &excess).unwrap();
+
+        // The witness is bound to the output being spent
+        assert_eq!(
+            lock.verify_witness(&witness, &random_commitment()),
+            Err(MultisigError::InvalidSignature)
+        );
+        // ...to the kernel of the spending transaction
+        let mut moved = witness.clone();
+        moved.kernel_excess = random_commitment();
+        assert_eq!(
+            lock.verify_witness(&moved, &commitment),
+            Err(MultisigError::InvalidSignature)
+        );
+        // ...and to the signers
+        let mut relabelled = witness;
+        relabelled.signers = vec![0, 1];
+        assert_eq!(
+            lock.verify_witness(&relabelled, &commitment),
+            Err(MultisigError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn the_nonce_commitment_binds_the_signer_and_nonce() {
+        let (lock, _) = create_test_multisig_lock();
+        let public_nonce = PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng));
+        let commitment = nonce_commitment(&lock.public_keys[0], &public_nonce);
+
+        assert_eq!(nonce_commitment(&lock.public_keys[0], &public_nonce), commitment);
+        assert_ne!(nonce_commitment(&lock.public_keys[1], &public_nonce), commitment);
+        let other_nonce = PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng));
+        assert_ne!(nonce_commitment(&lock.public_keys[0], &other_nonce), commitment);
+    }
+}
@@ -0,0 +1,309 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! One-sided payments, which pay a recipient without the recipient taking part in building the transaction. The
+//! sender derives all the keys of the recipient's output from a Diffie-Hellman secret that it shares with the
+//! recipient: the product of a new ephemeral key of the sender and the recipient's public key. As the public part of
+//! the ephemeral key is carried by the output itself, nothing secret has to be sent to the recipient, who finds its
+//! payments by checking the outputs with its secret key:
+//! 1. The blinding factor of the output and the keys that its range proof is rewound with are hashes of the secret, so
+//!    the recipient recovers the value of the output by rewinding it.
+//! 2. The sender knows the blinding factor as well, so the output is also locked by a script to a one-time key of the
+//!    recipient, the recipient's public key offset by another hash of the secret. Only the recipient knows the secret
+//!    part of that key, so only the recipient can spend the output: it claims the payment by spending it to an output
+//!    of its own.
+//!
+//! The script starts by pushing the ephemeral public key, then drops it again before checking the signature of the
+//! one-time key.
+
+use crate::transactions::{
+    script::{Opcode, TariScript},
+    tari_amount::MicroTari,
+    transaction::{OutputFeatures, TransactionOutput, UnblindedOutput},
+    transaction_protocol::RewindData,
+    types::{Challenge, CryptoFactories, PrivateKey, PublicKey},
+};
+use digest::Digest;
+use tari_crypto::{
+    commitment::HomomorphicCommitmentFactory,
+    keys::PublicKey as PublicKeyTrait,
+    range_proof::REWIND_USER_MESSAGE_LENGTH,
+    tari_utilities::ByteArray,
+};
+use thiserror::Error;
+
+const SPENDING_KEY_TAG: &[u8] = b"one_sided_spending_key";
+const REWIND_KEY_TAG: &[u8] = b"one_sided_rewind_key";
+const REWIND_BLINDING_KEY_TAG: &[u8] = b"one_sided_rewind_blinding_key";
+const PROOF_MESSAGE_TAG: &[u8] = b"one_sided_proof_message";
+const ONE_TIME_KEY_TAG: &[u8] = b"one_sided_one_time_key";
+
+/// The number of stack items of the witness that claims a one-sided payment output, the signature of its one-time key
+pub const CLAIM_WITNESS_STACK_ITEMS: usize = 1;
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum OneSidedPaymentError {
+    #[error("The output is not a one-sided payment")]
+    NotAOneSidedPayment,
+    #[error("The one-sided payment is not paid to this key")]
+    NotOwned,
+    #[error("The range proof of the one-sided payment cannot be rewound: {0}")]
+    RewindFailed(String),
+    #[error("Could not derive the keys of the one-sided payment: {0}")]
+    KeyDerivationError(String),
+}
+
+/// The keys of the output of a one-sided payment, which both its sender and its recipient can derive
+#[derive(Debug, Clone)]
+pub struct OneSidedPayment {
+    ephemeral_public_key: PublicKey,
+    one_time_public_key: PublicKey,
+    one_time_key_offset: PrivateKey,
+    spending_key: PrivateKey,
+    rewind_data: RewindData,
+}
+
+impl OneSidedPayment {
+    /// Derive the keys of a payment to `recipient` with a new ephemeral secret key of the sender. The ephemeral key
+    /// must not be used for anything else.
+    pub fn new(ephemeral_secret_key: &PrivateKey, recipient: &PublicKey) -> Result<Self, OneSidedPaymentError> {
+        let shared_secret = ephemeral_secret_key * recipient;
+        Self::from_shared_secret(
+            &shared_secret,
+            PublicKey::from_secret_key(ephemeral_secret_key),
+            recipient,
+        )
+    }
+
+    /// Derive the keys of the payment locked by the given script, for the recipient with the given secret key. Fails
+    /// if the script is not the script of a one-sided payment to that key.
+    pub fn from_script(script: &TariScript, secret_key: &PrivateKey) -> Result<Self, OneSidedPaymentError> {
+        let (ephemeral_public_key, one_time_public_key) = parse_script(script)?;
+        let shared_secret = secret_key * &ephemeral_public_key;
+        let payment = Self::from_shared_secret(
+            &shared_secret,
+            ephemeral_public_key,
+            &PublicKey::from_secret_key(secret_key),
+        )?;
+        if payment.one_time_public_key != one_time_public_key {
+            return Err(OneSidedPaymentError::NotOwned);
+        }
+        Ok(payment)
+    }
+
+    fn from_shared_secret(
+        shared_secret: &PublicKey,
+        ephemeral_public_key: PublicKey,
+        recipient: &PublicKey,
+    ) -> Result<Self, OneSidedPaymentError>
+    {
+        let one_time_key_offset = derive_key(ONE_TIME_KEY_TAG, shared_secret)?;
+        let one_time_public_key = recipient + &PublicKey::from_secret_key(&one_time_key_offset);
+        let mut proof_message = [0u8; REWIND_USER_MESSAGE_LENGTH];
+        proof_message.copy_from_slice(&hash(PROOF_MESSAGE_TAG, shared_secret)[..REWIND_USER_MESSAGE_LENGTH]);
+        Ok(Self {
+            ephemeral_public_key,
+            one_time_public_key,
+            one_time_key_offset,
+            spending_key: derive_key(SPENDING_KEY_TAG, shared_secret)?,
+            rewind_data: RewindData {
+                rewind_key: derive_key(REWIND_KEY_TAG, shared_secret)?,
+                rewind_blinding_key: derive_key(REWIND_BLINDING_KEY_TAG, shared_secret)?,
+                proof_message,
+            },
+        })
+    }
+
+    /// The script that locks the output to the one-time key of the recipient
+    pub fn script(&self) -> TariScript {
+        TariScript::new(vec![
+            Opcode::PushPubKey(self.ephemeral_public_key.clone()),
+            Opcode::Drop,
+            Opcode::CheckMultiSig(1, vec![self.one_time_public_key.clone()]),
+        ])
+    }
+
+    /// The features of the output, which is locked by the [script](Self::script)
+    pub fn output_features(&self) -> OutputFeatures {
+        OutputFeatures::with_script(self.script())
+    }
+
+    /// The blinding factor of the output
+    pub fn spending_key(&self) -> &PrivateKey {
+        &self.spending_key
+    }
+
+    /// The keys that the range proof of the output is rewound with
+    pub fn rewind_data(&self) -> &RewindData {
+        &self.rewind_data
+    }
+
+    /// The output paying `amount`, with the [features](Self::output_features) of the payment
+    pub fn output(&self, amount: MicroTari) -> UnblindedOutput {
+        UnblindedOutput::new(amount, self.spending_key.clone(), Some(self.output_features()))
+    }
+
+    /// The secret part of the one-time key that the output is locked to, given the secret key of the recipient. The
+    /// witness that claims the output is signed with it.
+    pub fn one_time_secret_key(&self, secret_key: &PrivateKey) -> PrivateKey {
+        secret_key + &self.one_time_key_offset
+    }
+
+    /// Rewind the range proof of the output to recover its value, checking that the output is the output of this
+    /// payment
+    pub fn recover_output(
+        &self,
+        output: &TransactionOutput,
+        factories: &CryptoFactories,
+    ) -> Result<UnblindedOutput, OneSidedPaymentError>
+    {
+        if output.features.script.as_ref() != Some(&self.script()) {
+            return Err(OneSidedPaymentError::NotAOneSidedPayment);
+        }
+        let rewound = output
+            .full_rewind_range_proof(
+                &factories.range_proof,
+                &self.rewind_data.rewind_key,
+                &self.rewind_data.rewind_blinding_key,
+            )
+            .map_err(|e| OneSidedPaymentError::RewindFailed(e.to_string()))?;
+        if rewound.blinding_factor != self.spending_key ||
+            rewound.proof_message != self.rewind_data.proof_message ||
+            factories
+                .commitment
+                .commit_value(&self.spending_key, rewound.committed_value.into()) !=
+                output.commitment
+        {
+            return Err(OneSidedPaymentError::RewindFailed(
+                "the output does not match the keys of the payment".to_string(),
+            ));
+        }
+        Ok(UnblindedOutput::new(
+            rewound.committed_value,
+            self.spending_key.clone(),
+            Some(output.features.clone()),
+        ))
+    }
+}
+
+/// Returns true if the script has the form of a one-sided payment script, whoever it is paid to
+pub fn is_one_sided_payment_script(script: &TariScript) -> bool {
+    parse_script(script).is_ok()
+}
+
+/// The ephemeral public key and the one-time key of a one-sided payment script
+fn parse_script(script: &TariScript) -> Result<(PublicKey, PublicKey), OneSidedPaymentError> {
+    match script.opcodes() {
+        [Opcode::PushPubKey(ephemeral_public_key), Opcode::Drop, Opcode::CheckMultiSig(1, keys)] if keys.len() == 1 => {
+            Ok((ephemeral_public_key.clone(), keys[0].clone()))
+        },
+        _ => Err(OneSidedPaymentError::NotAOneSidedPayment),
+    }
+}
+
+fn hash(tag: &[u8], shared_secret: &PublicKey) -> Vec<u8> {
+    Challenge::new()
+        .chain(tag)
+        .chain(shared_secret.as_bytes())
+        .result()
+        .to_vec()
+}
+
+fn derive_key(tag: &[u8], shared_secret: &PublicKey) -> Result<PrivateKey, OneSidedPaymentError> {
+    PrivateKey::from_bytes(&hash(tag, shared_secret))
+        .map_err(|e| OneSidedPaymentError::KeyDerivationError(e.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::transactions::{
+        script::{ScriptWitness, StackItem},
+        types::CommitmentFactory,
+    };
+    use rand::rngs::OsRng;
+    use tari_crypto::keys::SecretKey;
+
+    #[test]
+    fn the_recipient_derives_the_keys_of_the_sender() {
+        let (secret_key, public_key) = PublicKey::random_keypair(&mut OsRng);
+        let ephemeral_secret_key = PrivateKey::random(&mut OsRng);
+        let sent = OneSidedPayment::new(&ephemeral_secret_key, &public_key).unwrap();
+        assert!(is_one_sided_payment_script(&sent.script()));
+        let decoded = TariScript::from_bytes(&sent.script().to_bytes()).unwrap();
+        assert_eq!(
+            OneSidedPayment::from_script(&decoded, &secret_key).unwrap().script(),
+            decoded
+        );
+
+        let received = OneSidedPayment::from_script(&sent.script(), &secret_key).unwrap();
+        assert_eq!(received.spending_key(), sent.spending_key());
+        assert_eq!(received.rewind_data().rewind_key, sent.rewind_data().rewind_key);
+        assert_eq!(received.script(), sent.script());
+
+        // Only the recipient can spend the output
+        let other_secret_key = PrivateKey::random(&mut OsRng);
+        assert_eq!(
+            OneSidedPayment::from_script(&sent.script(), &other_secret_key).unwrap_err(),
+            OneSidedPaymentError::NotOwned
+        );
+        let commitment = CommitmentFactory::default().commit_value(sent.spending_key(), 100);
+        let kernel_excess = CommitmentFactory::default().commit_value(&PrivateKey::random(&mut OsRng), 0);
+        let signature = sent
+            .script()
+            .sign(
+                received.one_time_secret_key(&secret_key),
+                PrivateKey::random(&mut OsRng),
+                &commitment,
+                &kernel_excess,
+            )
+            .unwrap();
+        let witness = ScriptWitness {
+            kernel_excess,
+            stack: vec![StackItem::Signature(signature)],
+        };
+        assert!(sent.script().verify_witness(&witness, &commitment, 0).is_ok());
+    }
+
+    #[test]
+    fn the_recipient_recovers_the_output() {
+        let factories = CryptoFactories::default();
+        let (secret_key, public_key) = PublicKey::random_keypair(&mut OsRng);
+        let sent = OneSidedPayment::new(&PrivateKey::random(&mut OsRng), &public_key).unwrap();
+        let output = sent
+            .output(MicroTari::from(1_000))
+            .as_rewindable_transaction_output(&factories, sent.rewind_data())
+            .unwrap();
+
+        let received = OneSidedPayment::from_script(output.features.script.as_ref().unwrap(), &secret_key).unwrap();
+        let recovered = received.recover_output(&output, &factories).unwrap();
+        assert_eq!(recovered.value, MicroTari::from(1_000));
+        assert_eq!(&recovered.spending_key, sent.spending_key());
+
+        // An output that is not rewindable with the keys of the payment is rejected
+        let plain = sent
+            .output(MicroTari::from(1_000))
+            .as_transaction_output(&factories)
+            .unwrap();
+        assert!(received.recover_output(&plain, &factories).is_err());
+    }
+}
@@ -0,0 +1,198 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Verification of the range proofs of many outputs at once, e.g. all the outputs of a block. Range proof verification
+//! dominates the cost of validating a block, so outputs should be verified through a [RangeProofBatchVerifier] rather
+//! than one at a time.
+//!
+//! The outputs are split into batches of [RANGE_PROOF_BATCH_SIZE], and the batches are verified in parallel across all
+//! available cores. The version of `tari_crypto` in use does not expose an aggregated Bulletproof verifier, so the
+//! proofs within a batch are verified one after another. Verifying whole batches on each core keeps the scheduling
+//! overhead low, and a batch is the unit at which an aggregated verifier will be used once one is available.
+
+use crate::transactions::{
+    transaction::{RangeProofType, TransactionOutput},
+    types::RangeProofService,
+};
+use rayon::prelude::*;
+use tari_crypto::range_proof::RangeProofService as RangeProofServiceTrait;
+use thiserror::Error;
+
+/// The number of range proofs in a batch
+pub const RANGE_PROOF_BATCH_SIZE: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum RangeProofBatchError {
+    #[error("The range proof of output {0} is invalid")]
+    InvalidRangeProof(usize),
+    #[error("The range proof of output {index} is a {range_proof_type} range proof, which cannot be verified")]
+    UnsupportedRangeProofType {
+        index: usize,
+        range_proof_type: RangeProofType,
+    },
+}
+
+impl RangeProofBatchError {
+    /// The index of the output whose range proof failed verification
+    pub fn index(&self) -> usize {
+        match self {
+            RangeProofBatchError::InvalidRangeProof(index) => *index,
+            RangeProofBatchError::UnsupportedRangeProofType { index, .. } => *index,
+        }
+    }
+
+    fn offset_by(self, offset: usize) -> Self {
+        match self {
+            RangeProofBatchError::InvalidRangeProof(index) => RangeProofBatchError::InvalidRangeProof(index + offset),
+            RangeProofBatchError::UnsupportedRangeProofType {
+                index,
+                range_proof_type,
+            } => RangeProofBatchError::UnsupportedRangeProofType {
+                index: index + offset,
+                range_proof_type,
+            },
+        }
+    }
+}
+
+/// Verifies the range proofs of a list of outputs in parallel batches
+pub struct RangeProofBatchVerifier<'a> {
+    prover: &'a RangeProofService,
+    batch_size: usize,
+}
+
+impl<'a> RangeProofBatchVerifier<'a> {
+    pub fn new(prover: &'a RangeProofService) -> Self {
+        Self {
+            prover,
+            batch_size: RANGE_PROOF_BATCH_SIZE,
+        }
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Verify the range proofs of all the outputs. On failure, the error identifies the first output, in the order
+    /// given, whose range proof could not be verified.
+    pub fn verify(&self, outputs: &[TransactionOutput]) -> Result<(), RangeProofBatchError> {
+        let batch_size = self.batch_size;
+        match outputs
+            .par_chunks(batch_size)
+            .enumerate()
+            .find_map_first(|(i, batch)| self.verify_batch(batch).err().map(|e| e.offset_by(i * batch_size)))
+        {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// Verify the range proofs of a single batch of outputs on the current thread
+    pub fn verify_batch(&self, outputs: &[TransactionOutput]) -> Result<(), RangeProofBatchError> {
+        for (index, output) in outputs.iter().enumerate() {
+            match output.features.range_proof_type {
+                RangeProofType::BulletProof => {
+                    if !self.prover.verify(&output.proof.0, &output.commitment) {
+                        return Err(RangeProofBatchError::InvalidRangeProof(index));
+                    }
+                },
+                range_proof_type => {
+                    return Err(RangeProofBatchError::UnsupportedRangeProofType {
+                        index,
+                        range_proof_type,
+                    })
+                },
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::transactions::{
+        tari_amount::MicroTari,
+        transaction::{OutputFeatures, UnblindedOutput},
+        types::{CryptoFactories, PrivateKey, RangeProof},
+    };
+    use rand::rngs::OsRng;
+    use tari_crypto::{commitment::HomomorphicCommitmentFactory, keys::SecretKey, tari_utilities::ByteArray};
+
+    fn valid_output(factories: &CryptoFactories, value: u64) -> TransactionOutput {
+        UnblindedOutput::new(MicroTari::from(value), PrivateKey::random(&mut OsRng), None)
+            .as_transaction_output(factories)
+            .unwrap()
+    }
+
+    fn invalid_output(factories: &CryptoFactories) -> TransactionOutput {
+        // The proof is for a different value than the commitment
+        let k = PrivateKey::random(&mut OsRng);
+        let commitment = factories.commitment.commit_value(&k, 10);
+        let proof = factories.range_proof.construct_proof(&k, 11).unwrap();
+        TransactionOutput::new(
+            OutputFeatures::default(),
+            commitment,
+            RangeProof::from_bytes(&proof).unwrap(),
+        )
+    }
+
+    #[test]
+    fn it_verifies_all_outputs() {
+        let factories = CryptoFactories::new(32);
+        let outputs = (0..5).map(|v| valid_output(&factories, v)).collect::<Vec<_>>();
+        let verifier = RangeProofBatchVerifier::new(&factories.range_proof).with_batch_size(2);
+        assert_eq!(verifier.verify(&outputs), Ok(()));
+        assert_eq!(verifier.verify(&[]), Ok(()));
+    }
+
+    #[test]
+    fn it_reports_the_first_invalid_output() {
+        let factories = CryptoFactories::new(32);
+        let mut outputs = (0..5).map(|v| valid_output(&factories, v)).collect::<Vec<_>>();
+        outputs[3] = invalid_output(&factories);
+        outputs.push(invalid_output(&factories));
+        for batch_size in 1..=outputs.len() {
+            let verifier = RangeProofBatchVerifier::new(&factories.range_proof).with_batch_size(batch_size);
+            assert_eq!(
+                verifier.verify(&outputs),
+                Err(RangeProofBatchError::InvalidRangeProof(3))
+            );
+        }
+    }
+
+    #[test]
+    fn it_rejects_unsupported_range_proofs() {
+        let factories = CryptoFactories::new(32);
+        let mut outputs = vec![valid_output(&factories, 1), valid_output(&factories, 2)];
+        outputs[1].features = OutputFeatures::with_range_proof_type(RangeProofType::BulletProofPlus);
+        let err = RangeProofBatchVerifier::new(&factories.range_proof)
+            .verify(&outputs)
+            .unwrap_err();
+        assert_eq!(err, RangeProofBatchError::UnsupportedRangeProofType {
+            index: 1,
+            range_proof_type: RangeProofType::BulletProofPlus
+        });
+        assert_eq!(err.index(), 1);
+    }
+}
@@ -0,0 +1,796 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! TariScript is a small stack based script language that locks an output with spending conditions that the hash time
+//! and multisig locks cannot express, such as an m-of-n lock that can only be spent once a height is reached. A script
+//! is a list of opcodes that is committed to in the features of the output.
+//!
+//! As for the other locks, the witness that unlocks the output is carried by the spending input. It holds the items
+//! that the script starts with on its stack, and the excess of a kernel of the spending transaction. The signatures on
+//! the stack sign a challenge that commits to the script, the output being spent and that kernel, so the witness
+//! cannot be moved to a different transaction.
+//!
+//! A script is executed at the height of the block that spends the output. It succeeds if every opcode succeeds and it
+//! leaves exactly one item on the stack, a non-zero number.
+
+use crate::transactions::{
+    multisig::MAX_MULTISIG_PARTICIPANTS,
+    types::{Challenge, Commitment, MessageHash, PrivateKey, PublicKey, Signature},
+};
+use digest::Digest;
+use serde::{Deserialize, Serialize};
+use std::{
+    convert::TryFrom,
+    fmt,
+    fmt::{Display, Formatter},
+    hash::{Hash, Hasher},
+};
+use tari_crypto::tari_utilities::{hex::Hex, ByteArray};
+use thiserror::Error;
+
+/// The maximum size, in bytes, of an encoded script
+pub const MAX_SCRIPT_BYTES: usize = 1024;
+/// The maximum number of items on the stack of a script, including the items of the witness
+pub const MAX_STACK_SIZE: usize = 32;
+
+/// The weight of every opcode of a script
+const OPCODE_WEIGHT: u64 = 1;
+/// The additional weight of every key that a multisig opcode checks signatures against
+const SIGNATURE_CHECK_WEIGHT: u64 = 1;
+
+const SPEND_CHALLENGE_TAG: &[u8] = b"tari_script_spend";
+
+const OP_PUSH_INT: u8 = 0x01;
+const OP_DROP: u8 = 0x02;
+const OP_DUP: u8 = 0x03;
+const OP_VERIFY: u8 = 0x04;
+const OP_GE_ZERO: u8 = 0x05;
+const OP_PUSH_PUBKEY: u8 = 0x06;
+const OP_CHECK_HEIGHT_VERIFY: u8 = 0x10;
+const OP_CHECK_HEIGHT: u8 = 0x11;
+const OP_COMPARE_HEIGHT_VERIFY: u8 = 0x12;
+const OP_COMPARE_HEIGHT: u8 = 0x13;
+const OP_CHECK_MULTI_SIG: u8 = 0x20;
+const OP_CHECK_MULTI_SIG_VERIFY: u8 = 0x21;
+
+const ITEM_NUMBER: u8 = 0x01;
+const ITEM_SIGNATURE: u8 = 0x02;
+const ITEM_PUBLIC_KEY: u8 = 0x03;
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ScriptError {
+    #[error("Unexpected end of script bytes")]
+    UnexpectedEndOfBytes,
+    #[error("Unknown opcode: {0:#04x}")]
+    UnknownOpcode(u8),
+    #[error("Unknown stack item tag: {0:#04x}")]
+    UnknownItemTag(u8),
+    #[error("Invalid key or signature: {0}")]
+    InvalidEncoding(String),
+    #[error("Script is {size} bytes which exceeds the maximum of {max} bytes")]
+    TooLarge { size: usize, max: usize },
+    #[error("The script has no opcodes")]
+    EmptyScript,
+    #[error(
+        "A multisig opcode takes between 1 and {} keys and a threshold between 1 and the number of keys",
+        MAX_MULTISIG_PARTICIPANTS
+    )]
+    InvalidMultisig,
+    #[error("The stack is empty")]
+    StackUnderflow,
+    #[error("The stack exceeds the maximum of {} items", MAX_STACK_SIZE)]
+    StackOverflow,
+    #[error("The item on the stack has the wrong type")]
+    IncompatibleTypes,
+    #[error("A height does not fit in a script number")]
+    Overflow,
+    #[error("A verify opcode failed")]
+    VerifyFailed,
+    #[error("The script did not leave a single non-zero number on the stack")]
+    ScriptFailed,
+    #[error("Could not sign the witness: {0}")]
+    SigningError(String),
+}
+
+/// An item on the stack of an executing script
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum StackItem {
+    Number(i64),
+    Signature(Signature),
+    PublicKey(PublicKey),
+}
+
+impl StackItem {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        match self {
+            StackItem::Number(n) => {
+                buf.push(ITEM_NUMBER);
+                buf.extend_from_slice(&n.to_le_bytes());
+            },
+            StackItem::Signature(signature) => {
+                buf.push(ITEM_SIGNATURE);
+                buf.extend_from_slice(signature.get_public_nonce().as_bytes());
+                buf.extend_from_slice(signature.get_signature().as_bytes());
+            },
+            StackItem::PublicKey(key) => {
+                buf.push(ITEM_PUBLIC_KEY);
+                buf.extend_from_slice(key.as_bytes());
+            },
+        }
+    }
+
+    fn read_from(bytes: &mut &[u8]) -> Result<Self, ScriptError> {
+        match read_bytes(bytes, 1)?[0] {
+            ITEM_NUMBER => Ok(StackItem::Number(read_i64(bytes)?)),
+            ITEM_SIGNATURE => {
+                let public_nonce = read_public_key(bytes)?;
+                let signature = PrivateKey::from_bytes(read_bytes(bytes, 32)?)
+                    .map_err(|err| ScriptError::InvalidEncoding(err.to_string()))?;
+                Ok(StackItem::Signature(Signature::new(public_nonce, signature)))
+            },
+            ITEM_PUBLIC_KEY => Ok(StackItem::PublicKey(read_public_key(bytes)?)),
+            tag => Err(ScriptError::UnknownItemTag(tag)),
+        }
+    }
+}
+
+impl Display for StackItem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            StackItem::Number(n) => write!(f, "{}", n),
+            StackItem::Signature(signature) => write!(f, "sig({})", signature.get_public_nonce().to_hex()),
+            StackItem::PublicKey(key) => write!(f, "key({})", key.to_hex()),
+        }
+    }
+}
+
+/// A single instruction of a script
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Opcode {
+    /// Push the number onto the stack
+    PushInt(i64),
+    /// Remove the top item of the stack
+    Drop,
+    /// Push a copy of the top item of the stack
+    Dup,
+    /// Pop a number and fail if it is zero
+    Verify,
+    /// Pop a number and push 1 if it is zero or positive, otherwise 0
+    GeZero,
+    /// Push the public key onto the stack
+    PushPubKey(PublicKey),
+    /// Fail unless the block height is at least the given height
+    CheckHeightVerify(u64),
+    /// Push the block height minus the given height
+    CheckHeight(u64),
+    /// Pop a height and fail unless the block height is at least that height
+    CompareHeightVerify,
+    /// Pop a height and push the block height minus that height
+    CompareHeight,
+    /// Pop `threshold` signatures and push 1 if they are signatures of the spend challenge by distinct keys of the
+    /// given keys, in the same order as the keys, otherwise push 0. The signatures are pushed by the witness in key
+    /// order, so the first signature is the deepest on the stack.
+    CheckMultiSig(u8, Vec<PublicKey>),
+    /// As `CheckMultiSig`, but fail instead of pushing 0 and push nothing on success
+    CheckMultiSigVerify(u8, Vec<PublicKey>),
+}
+
+impl Opcode {
+    /// The weight that this opcode adds to the output that carries it
+    fn weight(&self) -> u64 {
+        match self {
+            Opcode::CheckMultiSig(_, keys) | Opcode::CheckMultiSigVerify(_, keys) => {
+                OPCODE_WEIGHT + SIGNATURE_CHECK_WEIGHT * keys.len() as u64
+            },
+            _ => OPCODE_WEIGHT,
+        }
+    }
+
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        use Opcode::*;
+        match self {
+            PushInt(n) => {
+                buf.push(OP_PUSH_INT);
+                buf.extend_from_slice(&n.to_le_bytes());
+            },
+            Drop => buf.push(OP_DROP),
+            Dup => buf.push(OP_DUP),
+            Verify => buf.push(OP_VERIFY),
+            GeZero => buf.push(OP_GE_ZERO),
+            PushPubKey(key) => {
+                buf.push(OP_PUSH_PUBKEY);
+                buf.extend_from_slice(key.as_bytes());
+            },
+            CheckHeightVerify(height) => {
+                buf.push(OP_CHECK_HEIGHT_VERIFY);
+                buf.extend_from_slice(&height.to_le_bytes());
+            },
+            CheckHeight(height) => {
+                buf.push(OP_CHECK_HEIGHT);
+                buf.extend_from_slice(&height.to_le_bytes());
+            },
+            CompareHeightVerify => buf.push(OP_COMPARE_HEIGHT_VERIFY),
+            CompareHeight => buf.push(OP_COMPARE_HEIGHT),
+            CheckMultiSig(threshold, keys) | CheckMultiSigVerify(threshold, keys) => {
+                buf.push(if let CheckMultiSig(..) = self {
+                    OP_CHECK_MULTI_SIG
+                } else {
+                    OP_CHECK_MULTI_SIG_VERIFY
+                });
+                buf.push(*threshold);
+                buf.push(keys.len() as u8);
+                for key in keys {
+                    buf.extend_from_slice(key.as_bytes());
+                }
+            },
+        }
+    }
+
+    fn read_from(bytes: &mut &[u8]) -> Result<Self, ScriptError> {
+        use Opcode::*;
+        let opcode = match read_bytes(bytes, 1)?[0] {
+            OP_PUSH_INT => PushInt(read_i64(bytes)?),
+            OP_DROP => Drop,
+            OP_DUP => Dup,
+            OP_VERIFY => Verify,
+            OP_GE_ZERO => GeZero,
+            OP_PUSH_PUBKEY => PushPubKey(read_public_key(bytes)?),
+            OP_CHECK_HEIGHT_VERIFY => CheckHeightVerify(read_u64(bytes)?),
+            OP_CHECK_HEIGHT => CheckHeight(read_u64(bytes)?),
+            OP_COMPARE_HEIGHT_VERIFY => CompareHeightVerify,
+            OP_COMPARE_HEIGHT => CompareHeight,
+            code @ OP_CHECK_MULTI_SIG | code @ OP_CHECK_MULTI_SIG_VERIFY => {
+                let threshold = read_bytes(bytes, 1)?[0];
+                let num_keys = read_bytes(bytes, 1)?[0];
+                let keys = (0..num_keys)
+                    .map(|_| read_public_key(bytes))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if code == OP_CHECK_MULTI_SIG {
+                    CheckMultiSig(threshold, keys)
+                } else {
+                    CheckMultiSigVerify(threshold, keys)
+                }
+            },
+            code => return Err(ScriptError::UnknownOpcode(code)),
+        };
+        Ok(opcode)
+    }
+}
+
+impl Display for Opcode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        use Opcode::*;
+        match self {
+            PushInt(n) => write!(f, "PushInt({})", n),
+            Drop => write!(f, "Drop"),
+            Dup => write!(f, "Dup"),
+            Verify => write!(f, "Verify"),
+            GeZero => write!(f, "GeZero"),
+            PushPubKey(key) => write!(f, "PushPubKey({})", key.to_hex()),
+            CheckHeightVerify(height) => write!(f, "CheckHeightVerify({})", height),
+            CheckHeight(height) => write!(f, "CheckHeight({})", height),
+            CompareHeightVerify => write!(f, "CompareHeightVerify"),
+            CompareHeight => write!(f, "CompareHeight"),
+            CheckMultiSig(threshold, keys) => write!(f, "CheckMultiSig({}-of-{})", threshold, keys.len()),
+            CheckMultiSigVerify(threshold, keys) => write!(f, "CheckMultiSigVerify({}-of-{})", threshold, keys.len()),
+        }
+    }
+}
+
+fn read_bytes<'a>(bytes: &mut &'a [u8], len: usize) -> Result<&'a [u8], ScriptError> {
+    if bytes.len() < len {
+        return Err(ScriptError::UnexpectedEndOfBytes);
+    }
+    let (head, tail) = bytes.split_at(len);
+    *bytes = tail;
+    Ok(head)
+}
+
+fn read_u64(bytes: &mut &[u8]) -> Result<u64, ScriptError> {
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(read_bytes(bytes, 8)?);
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn read_i64(bytes: &mut &[u8]) -> Result<i64, ScriptError> {
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(read_bytes(bytes, 8)?);
+    Ok(i64::from_le_bytes(buf))
+}
+
+fn read_public_key(bytes: &mut &[u8]) -> Result<PublicKey, ScriptError> {
+    PublicKey::from_bytes(read_bytes(bytes, 32)?).map_err(|err| ScriptError::InvalidEncoding(err.to_string()))
+}
+
+/// The data that unlocks a script locked output, carried by the input that spends it
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ScriptWitness {
+    /// The excess of the kernel of the spending transaction that the signatures on the stack commit to
+    pub kernel_excess: Commitment,
+    /// The items that the script starts with on its stack, the last item being the top of the stack
+    pub stack: Vec<StackItem>,
+}
+
+impl ScriptWitness {
+    /// Returns the canonical byte encoding of the stack of this witness
+    pub fn stack_to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        for item in &self.stack {
+            item.write_to(&mut buf);
+        }
+        buf
+    }
+
+    /// Decode a stack from its canonical byte encoding
+    pub fn stack_from_bytes(mut bytes: &[u8]) -> Result<Vec<StackItem>, ScriptError> {
+        let mut stack = Vec::new();
+        while !bytes.is_empty() {
+            if stack.len() == MAX_STACK_SIZE {
+                return Err(ScriptError::StackOverflow);
+            }
+            stack.push(StackItem::read_from(&mut bytes)?);
+        }
+        Ok(stack)
+    }
+}
+
+impl Display for ScriptWitness {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Script witness with {} stack items", self.stack.len())
+    }
+}
+
+/// A script that locks an output
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TariScript {
+    opcodes: Vec<Opcode>,
+}
+
+impl TariScript {
+    pub fn new(opcodes: Vec<Opcode>) -> Self {
+        Self { opcodes }
+    }
+
+    pub fn opcodes(&self) -> &[Opcode] {
+        &self.opcodes
+    }
+
+    /// The weight that this script adds to the output that carries it
+    pub fn weight(&self) -> u64 {
+        self.opcodes.iter().map(Opcode::weight).sum()
+    }
+
+    /// Checks that the script is not empty, does not exceed `MAX_SCRIPT_BYTES` and that its multisig opcodes are well
+    /// formed
+    pub fn validate(&self) -> Result<(), ScriptError> {
+        if self.opcodes.is_empty() {
+            return Err(ScriptError::EmptyScript);
+        }
+        let size = self.to_bytes().len();
+        if size > MAX_SCRIPT_BYTES {
+            return Err(ScriptError::TooLarge {
+                size,
+                max: MAX_SCRIPT_BYTES,
+            });
+        }
+        for opcode in &self.opcodes {
+            if let Opcode::CheckMultiSig(threshold, keys) | Opcode::CheckMultiSigVerify(threshold, keys) = opcode {
+                if keys.is_empty() ||
+                    keys.len() > MAX_MULTISIG_PARTICIPANTS ||
+                    *threshold == 0 ||
+                    *threshold as usize > keys.len()
+                {
+                    return Err(ScriptError::InvalidMultisig);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The challenge that is signed to spend the output with the given commitment in a transaction containing a
+    /// kernel with the given excess
+    pub fn spend_challenge(&self, commitment: &Commitment, kernel_excess: &Commitment) -> MessageHash {
+        Challenge::new()
+            .chain(SPEND_CHALLENGE_TAG)
+            .chain(&self.to_bytes())
+            .chain(commitment.as_bytes())
+            .chain(kernel_excess.as_bytes())
+            .result()
+            .to_vec()
+    }
+
+    /// Create a signature for the witness that spends the output with the given commitment in a transaction
+    /// containing a kernel with the given excess
+    pub fn sign(
+        &self,
+        secret_key: PrivateKey,
+        nonce: PrivateKey,
+        commitment: &Commitment,
+        kernel_excess: &Commitment,
+    ) -> Result<Signature, ScriptError>
+    {
+        let challenge = self.spend_challenge(commitment, kernel_excess);
+        Signature::sign(secret_key, nonce, &challenge).map_err(|e| ScriptError::SigningError(e.to_string()))
+    }
+
+    /// Verify that the witness unlocks the output with the given commitment in a block at the given height. This does
+    /// not check that the kernel the witness commits to is part of the spending transaction.
+    pub fn verify_witness(
+        &self,
+        witness: &ScriptWitness,
+        commitment: &Commitment,
+        block_height: u64,
+    ) -> Result<(), ScriptError>
+    {
+        self.validate()?;
+        if witness.stack.len() > MAX_STACK_SIZE {
+            return Err(ScriptError::StackOverflow);
+        }
+        let challenge = self.spend_challenge(commitment, &witness.kernel_excess);
+        let mut stack = witness.stack.clone();
+        for opcode in &self.opcodes {
+            execute_opcode(opcode, &mut stack, &challenge, block_height)?;
+        }
+        match stack.as_slice() {
+            [StackItem::Number(n)] if *n != 0 => Ok(()),
+            _ => Err(ScriptError::ScriptFailed),
+        }
+    }
+
+    /// Returns the canonical byte encoding of this script
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        for opcode in &self.opcodes {
+            opcode.write_to(&mut buf);
+        }
+        buf
+    }
+
+    /// Decode a script from its canonical byte encoding
+    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, ScriptError> {
+        if bytes.len() > MAX_SCRIPT_BYTES {
+            return Err(ScriptError::TooLarge {
+                size: bytes.len(),
+                max: MAX_SCRIPT_BYTES,
+            });
+        }
+        let mut opcodes = Vec::new();
+        while !bytes.is_empty() {
+            opcodes.push(Opcode::read_from(&mut bytes)?);
+        }
+        Ok(Self { opcodes })
+    }
+}
+
+fn execute_opcode(
+    opcode: &Opcode,
+    stack: &mut Vec<StackItem>,
+    challenge: &[u8],
+    block_height: u64,
+) -> Result<(), ScriptError>
+{
+    use Opcode::*;
+    match opcode {
+        PushInt(n) => push(stack, StackItem::Number(*n)),
+        Drop => pop(stack).map(|_| ()),
+        Dup => {
+            let top = stack.last().cloned().ok_or(ScriptError::StackUnderflow)?;
+            push(stack, top)
+        },
+        Verify => match pop_number(stack)? {
+            0 => Err(ScriptError::VerifyFailed),
+            _ => Ok(()),
+        },
+        GeZero => {
+            let n = pop_number(stack)?;
+            push(stack, StackItem::Number(if n >= 0 { 1 } else { 0 }))
+        },
+        PushPubKey(key) => push(stack, StackItem::PublicKey(key.clone())),
+        CheckHeightVerify(height) => {
+            if block_height >= *height {
+                Ok(())
+            } else {
+                Err(ScriptError::VerifyFailed)
+            }
+        },
+        CheckHeight(height) => {
+            let difference = height_difference(block_height, *height)?;
+            push(stack, StackItem::Number(difference))
+        },
+        CompareHeightVerify => {
+            let height = pop_number(stack)?;
+            if i128::from(block_height) >= i128::from(height) {
+                Ok(())
+            } else {
+                Err(ScriptError::VerifyFailed)
+            }
+        },
+        CompareHeight => {
+            let height = u64::try_from(pop_number(stack)?).map_err(|_| ScriptError::Overflow)?;
+            let difference = height_difference(block_height, height)?;
+            push(stack, StackItem::Number(difference))
+        },
+        CheckMultiSig(threshold, keys) => {
+            let is_valid = check_multisig(stack, *threshold, keys, challenge)?;
+            push(stack, StackItem::Number(if is_valid { 1 } else { 0 }))
+        },
+        CheckMultiSigVerify(threshold, keys) => {
+            if check_multisig(stack, *threshold, keys, challenge)? {
+                Ok(())
+            } else {
+                Err(ScriptError::VerifyFailed)
+            }
+        },
+    }
+}
+
+fn push(stack: &mut Vec<StackItem>, item: StackItem) -> Result<(), ScriptError> {
+    if stack.len() >= MAX_STACK_SIZE {
+        return Err(ScriptError::StackOverflow);
+    }
+    stack.push(item);
+    Ok(())
+}
+
+fn pop(stack: &mut Vec<StackItem>) -> Result<StackItem, ScriptError> {
+    stack.pop().ok_or(ScriptError::StackUnderflow)
+}
+
+fn pop_number(stack: &mut Vec<StackItem>) -> Result<i64, ScriptError> {
+    match pop(stack)? {
+        StackItem::Number(n) => Ok(n),
+        _ => Err(ScriptError::IncompatibleTypes),
+    }
+}
+
+fn height_difference(block_height: u64, height: u64) -> Result<i64, ScriptError> {
+    i64::try_from(i128::from(block_height) - i128::from(height)).map_err(|_| ScriptError::Overflow)
+}
+
+/// Pops `threshold` signatures off the stack and returns true if each was created by a different key of `keys`, in the
+/// order of the keys
+fn check_multisig(
+    stack: &mut Vec<StackItem>,
+    threshold: u8,
+    keys: &[PublicKey],
+    challenge: &[u8],
+) -> Result<bool, ScriptError>
+{
+    let mut signatures = Vec::with_capacity(threshold as usize);
+    for _ in 0..threshold {
+        match pop(stack)? {
+            StackItem::Signature(signature) => signatures.push(signature),
+            _ => return Err(ScriptError::IncompatibleTypes),
+        }
+    }
+    signatures.reverse();
+
+    let mut keys = keys.iter();
+    let is_valid = signatures
+        .iter()
+        .all(|signature| keys.any(|key| signature.verify_challenge(key, challenge)));
+    Ok(is_valid)
+}
+
+impl Hash for TariScript {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_bytes().hash(state)
+    }
+}
+
+impl Display for TariScript {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let opcodes = self.opcodes.iter().map(ToString::to_string).collect::<Vec<_>>();
+        write!(f, "Script [{}]", opcodes.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::transactions::types::CommitmentFactory;
+    use rand::rngs::OsRng;
+    use tari_crypto::{
+        commitment::HomomorphicCommitmentFactory,
+        keys::{PublicKey as PublicKeyTrait, SecretKey},
+    };
+
+    fn keys(n: usize) -> (Vec<PrivateKey>, Vec<PublicKey>) {
+        (0..n).map(|_| PublicKey::random_keypair(&mut OsRng)).unzip()
+    }
+
+    fn random_commitment() -> Commitment {
+        CommitmentFactory::default().commit_value(&PrivateKey::random(&mut OsRng), 100)
+    }
+
+    fn witness(script: &TariScript, secrets: &[&PrivateKey], commitment: &Commitment) -> ScriptWitness {
+        let kernel_excess = random_commitment();
+        let stack = secrets
+            .iter()
+            .map(|&k| {
+                let signature = script
+                    .sign(k.clone(), PrivateKey::random(&mut OsRng), commitment, &kernel_excess)
+                    .unwrap();
+                StackItem::Signature(signature)
+            })
+            .collect();
+        ScriptWitness { kernel_excess, stack }
+    }
+
+    #[test]
+    fn encoding_round_trip() {
+        let (_, public_keys) = keys(3);
+        let script = TariScript::new(vec![
+            Opcode::PushInt(-7),
+            Opcode::Dup,
+            Opcode::Drop,
+            Opcode::GeZero,
+            Opcode::PushPubKey(public_keys[0].clone()),
+            Opcode::Verify,
+            Opcode::CheckHeightVerify(10),
+            Opcode::CheckHeight(20),
+            Opcode::CompareHeightVerify,
+            Opcode::CompareHeight,
+            Opcode::CheckMultiSigVerify(1, public_keys.clone()),
+            Opcode::CheckMultiSig(2, public_keys),
+        ]);
+        assert_eq!(TariScript::from_bytes(&script.to_bytes()).unwrap(), script);
+
+        let commitment = random_commitment();
+        let (secrets, _) = keys(1);
+        let mut witness = witness(&script, &[&secrets[0]], &commitment);
+        witness.stack.push(StackItem::Number(42));
+        witness
+            .stack
+            .push(StackItem::PublicKey(PublicKey::from_secret_key(&secrets[0])));
+        assert_eq!(
+            ScriptWitness::stack_from_bytes(&witness.stack_to_bytes()).unwrap(),
+            witness.stack
+        );
+
+        assert_eq!(TariScript::from_bytes(&[0xff]), Err(ScriptError::UnknownOpcode(0xff)));
+        assert_eq!(
+            TariScript::from_bytes(&[OP_PUSH_INT, 1]),
+            Err(ScriptError::UnexpectedEndOfBytes)
+        );
+    }
+
+    #[test]
+    fn height_opcodes() {
+        let commitment = random_commitment();
+        let empty = ScriptWitness {
+            kernel_excess: random_commitment(),
+            stack: vec![],
+        };
+
+        let script = TariScript::new(vec![Opcode::CheckHeightVerify(100), Opcode::PushInt(1)]);
+        assert_eq!(
+            script.verify_witness(&empty, &commitment, 99),
+            Err(ScriptError::VerifyFailed)
+        );
+        assert_eq!(script.verify_witness(&empty, &commitment, 100), Ok(()));
+
+        let script = TariScript::new(vec![Opcode::CheckHeight(50), Opcode::GeZero]);
+        assert_eq!(script.verify_witness(&empty, &commitment, 50), Ok(()));
+        // GeZero leaves a zero on the stack, which fails the script
+        assert_eq!(
+            script.verify_witness(&empty, &commitment, 49),
+            Err(ScriptError::ScriptFailed)
+        );
+
+        // The height can come from the witness
+        let script = TariScript::new(vec![Opcode::CompareHeightVerify, Opcode::PushInt(1)]);
+        let mut with_height = empty.clone();
+        with_height.stack.push(StackItem::Number(10));
+        assert_eq!(script.verify_witness(&with_height, &commitment, 10), Ok(()));
+        assert_eq!(
+            script.verify_witness(&with_height, &commitment, 9),
+            Err(ScriptError::VerifyFailed)
+        );
+        assert_eq!(
+            script.verify_witness(&empty, &commitment, 10),
+            Err(ScriptError::StackUnderflow)
+        );
+    }
+
+    #[test]
+    fn check_multisig() {
+        let (secrets, public_keys) = keys(3);
+        let script = TariScript::new(vec![Opcode::CheckMultiSig(2, public_keys)]);
+        let commitment = random_commitment();
+
+        let valid = witness(&script, &[&secrets[0], &secrets[2]], &commitment);
+        assert_eq!(script.verify_witness(&valid, &commitment, 0), Ok(()));
+
+        // The signatures must be in the order of the keys
+        let reversed = witness(&script, &[&secrets[2], &secrets[0]], &commitment);
+        assert_eq!(
+            script.verify_witness(&reversed, &commitment, 0),
+            Err(ScriptError::ScriptFailed)
+        );
+
+        // A key cannot sign twice
+        let repeated = witness(&script, &[&secrets[1], &secrets[1]], &commitment);
+        assert_eq!(
+            script.verify_witness(&repeated, &commitment, 0),
+            Err(ScriptError::ScriptFailed)
+        );
+
+        let too_few = witness(&script, &[&secrets[1]], &commitment);
+        assert_eq!(
+            script.verify_witness(&too_few, &commitment, 0),
+            Err(ScriptError::StackUnderflow)
+        );
+    }
+
+    #[test]
+    fn signatures_are_bound_to_the_output_and_kernel() {
+        let (secrets, public_keys) = keys(1);
+        let script = TariScript::new(vec![Opcode::CheckMultiSigVerify(1, public_keys), Opcode::PushInt(1)]);
+        let commitment = random_commitment();
+        let witness = witness(&script, &[&secrets[0]], &commitment);
+        assert_eq!(script.verify_witness(&witness, &commitment, 0), Ok(()));
+
+        assert_eq!(
+            script.verify_witness(&witness, &random_commitment(), 0),
+            Err(ScriptError::VerifyFailed)
+        );
+        let mut moved = witness;
+        moved.kernel_excess = random_commitment();
+        assert_eq!(
+            script.verify_witness(&moved, &commitment, 0),
+            Err(ScriptError::VerifyFailed)
+        );
+    }
+
+    #[test]
+    fn multisig_after_height() {
+        let (secrets, public_keys) = keys(3);
+        let script = TariScript::new(vec![
+            Opcode::CheckHeightVerify(1000),
+            Opcode::CheckMultiSig(2, public_keys),
+        ]);
+        let commitment = random_commitment();
+        let witness = witness(&script, &[&secrets[1], &secrets[2]], &commitment);
+        assert_eq!(
+            script.verify_witness(&witness, &commitment, 999),
+            Err(ScriptError::VerifyFailed)
+        );
+        assert_eq!(script.verify_witness(&witness, &commitment, 1000), Ok(()));
+    }
+
+    #[test]
+    fn validation() {
+        assert_eq!(TariScript::new(vec![]).validate(), Err(ScriptError::EmptyScript));
+        let (_, public_keys) = keys(2);
+        assert_eq!(
+            TariScript::new(vec![Opcode::CheckMultiSig(3, public_keys.clone())]).validate(),
+            Err(ScriptError::InvalidMultisig)
+        );
+        assert_eq!(
+            TariScript::new(vec![Opcode::CheckMultiSig(0, public_keys)]).validate(),
+            Err(ScriptError::InvalidMultisig)
+        );
+        let too_large = TariScript::new(vec![Opcode::PushInt(1); MAX_SCRIPT_BYTES / 9 + 1]);
+        assert!(matches!(too_large.validate(), Err(ScriptError::TooLarge { .. })));
+    }
+}
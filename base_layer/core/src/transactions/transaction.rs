@@ -25,6 +25,11 @@
 
 use crate::transactions::{
     aggregated_body::AggregateBody,
+    asset::AssetOutputFeatures,
+    covenant::Covenant,
+    htlc::{HashTimeLock, HtlcWitness},
+    multisig::{MultisigLock, MultisigWitness},
+    script::{ScriptWitness, TariScript},
     tari_amount::{uT, MicroTari},
     transaction_protocol::{build_challenge, RewindData, TransactionMetadata},
     types::{
@@ -44,6 +49,7 @@ use digest::Input;
 use serde::{Deserialize, Serialize};
 use std::{
     cmp::{max, min, Ordering},
+    convert::TryFrom,
     fmt,
     fmt::{Display, Formatter},
     hash::{Hash, Hasher},
@@ -67,6 +73,8 @@ pub const MAX_TRANSACTION_INPUTS: usize = 12_500;
 pub const MAX_TRANSACTION_OUTPUTS: usize = 500;
 pub const MAX_TRANSACTION_RECIPIENTS: usize = 15;
 pub const MINIMUM_TRANSACTION_FEE: MicroTari = MicroTari(100);
+/// The largest coinbase extra that can be encoded. The consensus rules may set a lower limit.
+pub const MAX_COINBASE_EXTRA_BYTES: usize = 256;
 
 //--------------------------------------        Output features   --------------------------------------------------//
 
@@ -77,6 +85,8 @@ bitflags! {
     pub struct KernelFeatures: u8 {
         /// Coinbase transaction
         const COINBASE_KERNEL = 1u8;
+        /// Burn transaction. The kernel commits to an amount that is permanently removed from circulation
+        const BURN_KERNEL = 2u8;
     }
 }
 
@@ -84,6 +94,103 @@ impl KernelFeatures {
     pub fn create_coinbase() -> KernelFeatures {
         KernelFeatures::COINBASE_KERNEL
     }
+
+    pub fn create_burn() -> KernelFeatures {
+        KernelFeatures::BURN_KERNEL
+    }
+}
+
+/// The version of the layout of `OutputFeatures`. New fields are added to the features in a new version, so that
+/// nodes can tell which fields an output is expected to have, and the consensus rules can set which versions are
+/// permitted at a given height. Versions that are newer than this node knows about are still parsed, with the fields
+/// of the latest known version, so that introducing a version does not break the parsing of older nodes; the
+/// consensus rules reject the versions that they do not permit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+pub struct OutputFeaturesVersion(u8);
+
+impl OutputFeaturesVersion {
+    /// The latest version that this node knows the layout of
+    pub const LATEST: OutputFeaturesVersion = OutputFeaturesVersion::V5;
+    /// Flags, maturity, relative maturity and asset
+    pub const V0: OutputFeaturesVersion = OutputFeaturesVersion(0);
+    /// Adds the coinbase extra
+    pub const V1: OutputFeaturesVersion = OutputFeaturesVersion(1);
+    /// Adds the hash time lock
+    pub const V2: OutputFeaturesVersion = OutputFeaturesVersion(2);
+    /// Adds the multisig lock
+    pub const V3: OutputFeaturesVersion = OutputFeaturesVersion(3);
+    /// Adds the script
+    pub const V4: OutputFeaturesVersion = OutputFeaturesVersion(4);
+    /// Adds the range proof type
+    pub const V5: OutputFeaturesVersion = OutputFeaturesVersion(5);
+
+    /// The version used for new outputs
+    pub fn get_current_version() -> Self {
+        OutputFeaturesVersion::V0
+    }
+
+    pub fn as_u8(self) -> u8 {
+        self.0
+    }
+
+    /// Returns true if this node knows the layout of this version
+    pub fn is_known(self) -> bool {
+        self <= OutputFeaturesVersion::LATEST
+    }
+}
+
+impl From<u8> for OutputFeaturesVersion {
+    fn from(value: u8) -> Self {
+        OutputFeaturesVersion(value)
+    }
+}
+
+impl Display for OutputFeaturesVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "V{}", self.as_u8())
+    }
+}
+
+/// The kind of range proof carried by an output. Outputs with features before V5 always carry a Bulletproof.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum RangeProofType {
+    BulletProof = 0,
+    /// Requires V5 features, and is only permitted from the height at which the consensus rules activate it
+    BulletProofPlus = 1,
+}
+
+impl RangeProofType {
+    pub fn as_u8(self) -> u8 {
+        self as u8
+    }
+}
+
+impl Default for RangeProofType {
+    fn default() -> Self {
+        RangeProofType::BulletProof
+    }
+}
+
+impl TryFrom<u8> for RangeProofType {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(RangeProofType::BulletProof),
+            1 => Ok(RangeProofType::BulletProofPlus),
+            v => Err(format!("Unsupported range proof type {}", v)),
+        }
+    }
+}
+
+impl Display for RangeProofType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RangeProofType::BulletProof => f.write_str("Bulletproof"),
+            RangeProofType::BulletProofPlus => f.write_str("Bulletproof+"),
+        }
+    }
 }
 
 /// Options for UTXO's
@@ -94,12 +201,96 @@ pub struct OutputFeatures {
     /// the maturity of the specific UTXO. This is the min lock height at which an UTXO can be spend. Coinbase UTXO
     /// require a min maturity of the Coinbase_lock_height, this should be checked on receiving new blocks.
     pub maturity: u64,
+    /// The number of blocks after the block in which this UTXO was mined before it can be spent. Zero means that the
+    /// UTXO is not relatively time-locked.
+    #[serde(default)]
+    pub relative_maturity: u64,
+    /// The asset carried by this output, if it is not a plain Tari output
+    #[serde(default)]
+    pub asset: Option<AssetOutputFeatures>,
+    /// The version of these features. Features serialized before versioning was introduced are V0.
+    #[serde(default)]
+    pub version: OutputFeaturesVersion,
+    /// Arbitrary data chosen by the miner, e.g. to identify a mining pool. Only coinbase outputs with V1 features or
+    /// later may carry it.
+    #[serde(default)]
+    pub coinbase_extra: Vec<u8>,
+    /// The hash time lock of this output, if it is part of an atomic swap. Requires V2 features or later.
+    #[serde(default)]
+    pub htlc: Option<HashTimeLock>,
+    /// The m-of-n lock of this output, if it is spent by several parties. Requires V3 features or later.
+    #[serde(default)]
+    pub multisig: Option<MultisigLock>,
+    /// The script that locks this output, if its spending conditions cannot be expressed by the other locks. Requires
+    /// V4 features or later.
+    #[serde(default)]
+    pub script: Option<TariScript>,
+    /// The kind of range proof carried by the output. Anything other than a Bulletproof requires V5 features or later.
+    #[serde(default)]
+    pub range_proof_type: RangeProofType,
 }
 
 impl OutputFeatures {
+    /// Returns the canonical byte representation of these features. The relative maturity and asset are only appended
+    /// when they are set so that the bytes (and therefore the hashes) of plain outputs are unchanged. For the same
+    /// reason V0 features carry no version byte, while later versions start with it and always contain every field.
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut buf = Vec::new();
-        bincode::serialize_into(&mut buf, self).unwrap(); // this should not fail
+        if self.version > OutputFeaturesVersion::V0 {
+            buf.push(self.version.as_u8());
+        }
+        bincode::serialize_into(&mut buf, &(&self.flags, self.maturity)).unwrap(); // this should not fail
+        if self.version == OutputFeaturesVersion::V0 {
+            if self.relative_maturity > 0 || self.asset.is_some() {
+                buf.extend_from_slice(&self.relative_maturity.to_le_bytes());
+            }
+            if let Some(asset) = &self.asset {
+                buf.extend_from_slice(&asset.to_bytes());
+            }
+            return buf;
+        }
+        buf.extend_from_slice(&self.relative_maturity.to_le_bytes());
+        match &self.asset {
+            Some(asset) => {
+                buf.push(1);
+                buf.extend_from_slice(&asset.to_bytes());
+            },
+            None => buf.push(0),
+        }
+        buf.extend_from_slice(&(self.coinbase_extra.len() as u64).to_le_bytes());
+        buf.extend_from_slice(&self.coinbase_extra);
+        if self.version >= OutputFeaturesVersion::V2 {
+            match &self.htlc {
+                Some(htlc) => {
+                    buf.push(1);
+                    buf.extend_from_slice(&htlc.to_bytes());
+                },
+                None => buf.push(0),
+            }
+        }
+        if self.version >= OutputFeaturesVersion::V3 {
+            match &self.multisig {
+                Some(multisig) => {
+                    buf.push(1);
+                    buf.extend_from_slice(&multisig.to_bytes());
+                },
+                None => buf.push(0),
+            }
+        }
+        if self.version >= OutputFeaturesVersion::V4 {
+            match &self.script {
+                Some(script) => {
+                    let script = script.to_bytes();
+                    buf.push(1);
+                    buf.extend_from_slice(&(script.len() as u64).to_le_bytes());
+                    buf.extend_from_slice(&script);
+                },
+                None => buf.push(0),
+            }
+        }
+        if self.version >= OutputFeaturesVersion::V5 {
+            buf.push(self.range_proof_type.as_u8());
+        }
         buf
     }
 
@@ -107,9 +298,124 @@ impl OutputFeatures {
         OutputFeatures {
             flags: OutputFlags::COINBASE_OUTPUT,
             maturity: maturity_height,
+            ..OutputFeatures::default()
+        }
+    }
+
+    /// Create the `OutputFeatures` of a coinbase output that carries the given extra data. The extra data requires V1
+    /// features, so coinbases without it keep the current version.
+    pub fn create_coinbase_with_extra(maturity_height: u64, coinbase_extra: Vec<u8>) -> OutputFeatures {
+        if coinbase_extra.is_empty() {
+            return OutputFeatures::create_coinbase(maturity_height);
+        }
+        OutputFeatures {
+            version: OutputFeaturesVersion::V1,
+            coinbase_extra,
+            ..OutputFeatures::create_coinbase(maturity_height)
+        }
+    }
+
+    /// Create the `OutputFeatures` of the output that registers an asset. `asset` must be signed by the issuer.
+    pub fn create_asset_registration(asset: AssetOutputFeatures) -> OutputFeatures {
+        OutputFeatures {
+            flags: OutputFlags::ASSET_REGISTRATION,
+            asset: Some(asset),
+            ..OutputFeatures::default()
+        }
+    }
+
+    /// Create the `OutputFeatures` of an output that mints a new token. `asset` must be signed by the issuer.
+    pub fn create_mint_non_fungible(asset: AssetOutputFeatures) -> OutputFeatures {
+        OutputFeatures {
+            flags: OutputFlags::MINT_NON_FUNGIBLE,
+            asset: Some(asset),
+            ..OutputFeatures::default()
         }
     }
 
+    /// Create the `OutputFeatures` of an output that receives an existing token, i.e. a transfer
+    pub fn with_asset(mut asset: AssetOutputFeatures) -> OutputFeatures {
+        asset.issuer_sig = None;
+        OutputFeatures {
+            asset: Some(asset),
+            ..OutputFeatures::default()
+        }
+    }
+
+    /// Create the `OutputFeatures` of an output that is locked by the given hash time lock and all other values at
+    /// their default setting. The hash time lock requires V2 features.
+    pub fn with_hash_time_lock(htlc: HashTimeLock) -> OutputFeatures {
+        OutputFeatures {
+            version: OutputFeaturesVersion::V2,
+            htlc: Some(htlc),
+            ..OutputFeatures::default()
+        }
+    }
+
+    pub fn is_hash_time_locked(&self) -> bool {
+        self.htlc.is_some()
+    }
+
+    /// Create the `OutputFeatures` of an output that is locked by the given multisig lock and all other values at their
+    /// default setting. The multisig lock requires V3 features.
+    pub fn with_multisig_lock(multisig: MultisigLock) -> OutputFeatures {
+        OutputFeatures {
+            version: OutputFeaturesVersion::V3,
+            multisig: Some(multisig),
+            ..OutputFeatures::default()
+        }
+    }
+
+    pub fn is_multisig_locked(&self) -> bool {
+        self.multisig.is_some()
+    }
+
+    /// Create the `OutputFeatures` of an output that is locked by the given script and all other values at their
+    /// default setting. The script requires V4 features.
+    pub fn with_script(script: TariScript) -> OutputFeatures {
+        OutputFeatures {
+            version: OutputFeaturesVersion::V4,
+            script: Some(script),
+            ..OutputFeatures::default()
+        }
+    }
+
+    pub fn is_script_locked(&self) -> bool {
+        self.script.is_some()
+    }
+
+    /// Create the `OutputFeatures` of an output that carries the given kind of range proof and all other values at
+    /// their default setting. Recording the range proof type requires V5 features.
+    pub fn with_range_proof_type(range_proof_type: RangeProofType) -> OutputFeatures {
+        OutputFeatures {
+            version: OutputFeaturesVersion::V5,
+            range_proof_type,
+            ..OutputFeatures::default()
+        }
+    }
+
+    pub fn is_asset_registration(&self) -> bool {
+        self.flags.contains(OutputFlags::ASSET_REGISTRATION)
+    }
+
+    pub fn is_mint(&self) -> bool {
+        self.flags.contains(OutputFlags::MINT_NON_FUNGIBLE)
+    }
+
+    /// Create an `OutputFeatures` that can only be spent `relative_maturity` blocks after the output is mined and all
+    /// other values at their default setting
+    pub fn with_relative_maturity(relative_maturity: u64) -> OutputFeatures {
+        OutputFeatures {
+            relative_maturity,
+            ..OutputFeatures::default()
+        }
+    }
+
+    /// Returns true if an output with these features, mined at `mined_height`, may be spent in a block at `height`
+    pub fn is_mature_at(&self, mined_height: u64, height: u64) -> bool {
+        self.maturity <= height && mined_height.saturating_add(self.relative_maturity) <= height
+    }
+
     /// Create an `OutputFeatures` with the given maturity and all other values at their default setting
     pub fn with_maturity(maturity: u64) -> OutputFeatures {
         OutputFeatures {
@@ -124,6 +430,14 @@ impl Default for OutputFeatures {
         OutputFeatures {
             flags: OutputFlags::empty(),
             maturity: 0,
+            relative_maturity: 0,
+            asset: None,
+            version: OutputFeaturesVersion::get_current_version(),
+            coinbase_extra: Vec::new(),
+            htlc: None,
+            multisig: None,
+            script: None,
+            range_proof_type: RangeProofType::BulletProof,
         }
     }
 }
@@ -144,9 +458,28 @@ impl Display for OutputFeatures {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "OutputFeatures: Flags = {:?}, Maturity = {}",
-            self.flags, self.maturity
-        )
+            "OutputFeatures: Version = {}, Flags = {:?}, Maturity = {}, Relative maturity = {}",
+            self.version, self.flags, self.maturity, self.relative_maturity
+        )?;
+        if let Some(asset) = &self.asset {
+            write!(f, ", {}", asset)?;
+        }
+        if !self.coinbase_extra.is_empty() {
+            write!(f, ", Coinbase extra = {}", self.coinbase_extra.to_hex())?;
+        }
+        if let Some(htlc) = &self.htlc {
+            write!(f, ", {}", htlc)?;
+        }
+        if let Some(multisig) = &self.multisig {
+            write!(f, ", {}", multisig)?;
+        }
+        if let Some(script) = &self.script {
+            write!(f, ", {}", script)?;
+        }
+        if self.range_proof_type != RangeProofType::BulletProof {
+            write!(f, ", Range proof = {}", self.range_proof_type)?;
+        }
+        Ok(())
     }
 }
 
@@ -155,6 +488,10 @@ bitflags! {
     pub struct OutputFlags: u8 {
         /// Output is a coinbase output, must not be spent until maturity
         const COINBASE_OUTPUT = 0b0000_0001;
+        /// Output registers a new asset. Must carry asset features signed by the issuer.
+        const ASSET_REGISTRATION = 0b0000_0010;
+        /// Output mints a new token of an asset. Must carry asset features signed by the issuer.
+        const MINT_NON_FUNGIBLE = 0b0000_0100;
     }
 }
 
@@ -182,6 +519,20 @@ pub enum TransactionError {
     NoCoinbase,
     #[error("Input maturity not reached")]
     InputMaturity,
+    #[error("Invalid covenant: {0}")]
+    InvalidCovenant(String),
+    #[error("Covenant of input {0} is not satisfied by an output of its own")]
+    CovenantNotSatisfied(String),
+    #[error("Invalid asset output: {0}")]
+    InvalidAsset(String),
+    #[error("Invalid hash time-locked contract: {0}")]
+    InvalidHtlc(String),
+    #[error("Invalid multisig spend: {0}")]
+    InvalidMultisig(String),
+    #[error("Invalid script spend: {0}")]
+    InvalidScript(String),
+    #[error("{0} range proofs are not supported by this node")]
+    UnsupportedRangeProofType(RangeProofType),
 }
 
 //-----------------------------------------     UnblindedOutput   ----------------------------------------------------//
@@ -193,6 +544,7 @@ pub struct UnblindedOutput {
     pub value: MicroTari,
     pub spending_key: BlindingFactor,
     pub features: OutputFeatures,
+    pub covenant: Covenant,
 }
 
 impl UnblindedOutput {
@@ -202,13 +554,27 @@ impl UnblindedOutput {
             value,
             spending_key,
             features: features.unwrap_or_default(),
+            covenant: Covenant::default(),
         }
     }
 
+    /// Attach a covenant that restricts how the resulting output may be spent
+    pub fn with_covenant(mut self, covenant: Covenant) -> Self {
+        self.covenant = covenant;
+        self
+    }
+
     /// Commits an UnblindedOutput into a Transaction input
     pub fn as_transaction_input(&self, factory: &CommitmentFactory, features: OutputFeatures) -> TransactionInput {
         let commitment = factory.commit(&self.spending_key, &self.value.into());
-        TransactionInput { commitment, features }
+        TransactionInput {
+            commitment,
+            features,
+            covenant: self.covenant.clone(),
+            htlc_witness: None,
+            multisig_witness: None,
+            script_witness: None,
+        }
     }
 
     pub fn as_transaction_output(&self, factories: &CryptoFactories) -> Result<TransactionOutput, TransactionError> {
@@ -216,6 +582,7 @@ impl UnblindedOutput {
         let output = TransactionOutput {
             features: self.features.clone(),
             commitment,
+            covenant: self.covenant.clone(),
             proof: RangeProof::from_bytes(
                 &factories
                     .range_proof
@@ -254,6 +621,7 @@ impl UnblindedOutput {
         let output = TransactionOutput {
             features: self.features.clone(),
             commitment,
+            covenant: self.covenant.clone(),
             proof,
         };
         // A range proof can be constructed for an invalid value so we should confirm that the proof can be verified.
@@ -304,13 +672,77 @@ pub struct TransactionInput {
     pub features: OutputFeatures,
     /// The commitment referencing the output being spent.
     pub commitment: Commitment,
+    /// The covenant of the output being spent. The spending transaction must contain an output satisfying it.
+    #[serde(default)]
+    pub covenant: Covenant,
+    /// Unlocks the output being spent if it is hash time-locked. It is not part of the input hash.
+    #[serde(default)]
+    pub htlc_witness: Option<HtlcWitness>,
+    /// Unlocks the output being spent if it is multisig locked. It is not part of the input hash.
+    #[serde(default)]
+    pub multisig_witness: Option<MultisigWitness>,
+    /// Unlocks the output being spent if it is script locked. It is not part of the input hash.
+    #[serde(default)]
+    pub script_witness: Option<ScriptWitness>,
 }
 
 /// An input for a transaction that spends an existing output
 impl TransactionInput {
     /// Create a new Transaction Input
     pub fn new(features: OutputFeatures, commitment: Commitment) -> TransactionInput {
-        TransactionInput { features, commitment }
+        TransactionInput {
+            features,
+            commitment,
+            covenant: Covenant::default(),
+            htlc_witness: None,
+            multisig_witness: None,
+            script_witness: None,
+        }
+    }
+
+    /// Set the covenant of the output being spent
+    pub fn with_covenant(mut self, covenant: Covenant) -> Self {
+        self.covenant = covenant;
+        self
+    }
+
+    /// Attach the witness that unlocks the hash time-locked output being spent
+    pub fn with_htlc_witness(mut self, witness: HtlcWitness) -> Self {
+        self.htlc_witness = Some(witness);
+        self
+    }
+
+    /// Attach the witness that unlocks the multisig locked output being spent
+    pub fn with_multisig_witness(mut self, witness: MultisigWitness) -> Self {
+        self.multisig_witness = Some(witness);
+        self
+    }
+
+    /// Attach the witness that unlocks the script locked output being spent
+    pub fn with_script_witness(mut self, witness: ScriptWitness) -> Self {
+        self.script_witness = Some(witness);
+        self
+    }
+
+    /// The height from which this input may be spent. This is the maturity of the output being spent, or its refund
+    /// height if it is a hash time-locked output that is refunded.
+    pub fn min_spendable_height(&self) -> u64 {
+        match (&self.features.htlc, &self.htlc_witness) {
+            (Some(htlc), Some(witness)) if witness.is_refund() => max(self.features.maturity, htlc.refund_height),
+            _ => self.features.maturity,
+        }
+    }
+
+    /// Whether the output being spent has conditions that are only checked against the spending input: a maturity, a
+    /// relative time-lock, a covenant, a script, or a hash time or multisig lock. Such an input must not be cut through
+    /// with the output it spends, as its conditions would then never be checked.
+    pub fn has_spending_conditions(&self) -> bool {
+        self.features.maturity > 0 ||
+            self.features.relative_maturity > 0 ||
+            !self.covenant.is_empty() ||
+            self.features.is_script_locked() ||
+            self.features.is_hash_time_locked() ||
+            self.features.is_multisig_locked()
     }
 
     /// Accessor method for the commitment contained in an input
@@ -323,10 +755,10 @@ impl TransactionInput {
         factory.open(&input.spending_key, &input.value.into(), &self.commitment)
     }
 
-    /// This will check if the input and the output is the same commitment by looking at the commitment and features.
-    /// This will ignore the output rangeproof
+    /// This will check if the input and the output is the same commitment by looking at the commitment, features and
+    /// covenant. This will ignore the output rangeproof
     pub fn is_equal_to(&self, output: &TransactionOutput) -> bool {
-        self.commitment == output.commitment && self.features == output.features
+        self.commitment == output.commitment && self.features == output.features && self.covenant == output.covenant
     }
 }
 
@@ -335,6 +767,10 @@ impl From<TransactionOutput> for TransactionInput {
         TransactionInput {
             features: item.features,
             commitment: item.commitment,
+            covenant: item.covenant,
+            htlc_witness: None,
+            multisig_witness: None,
+            script_witness: None,
         }
     }
 }
@@ -342,11 +778,7 @@ impl From<TransactionOutput> for TransactionInput {
 /// Implement the canonical hashing function for TransactionInput for use in ordering
 impl Hashable for TransactionInput {
     fn hash(&self) -> Vec<u8> {
-        HashDigest::new()
-            .chain(self.features.to_bytes())
-            .chain(self.commitment.as_bytes())
-            .result()
-            .to_vec()
+        hash_output(&self.features, &self.commitment, &self.covenant)
     }
 }
 
@@ -367,6 +799,9 @@ pub struct TransactionOutput {
     pub features: OutputFeatures,
     /// The homomorphic commitment representing the output amount
     pub commitment: Commitment,
+    /// Restrictions on the transaction that spends this output
+    #[serde(default)]
+    pub covenant: Covenant,
     /// A proof that the commitment is in the right range
     pub proof: RangeProof,
 }
@@ -378,10 +813,17 @@ impl TransactionOutput {
         TransactionOutput {
             features,
             commitment,
+            covenant: Covenant::default(),
             proof,
         }
     }
 
+    /// Attach a covenant that restricts how this output may be spent
+    pub fn with_covenant(mut self, covenant: Covenant) -> Self {
+        self.covenant = covenant;
+        self
+    }
+
     /// Accessor method for the commitment contained in an output
     pub fn commitment(&self) -> &Commitment {
         &self.commitment
@@ -392,9 +834,21 @@ impl TransactionOutput {
         &self.proof
     }
 
-    /// Verify that range proof is valid
+    /// Verify that range proof is valid. The proof is verified according to the range proof type in the features.
     pub fn verify_range_proof(&self, prover: &RangeProofService) -> Result<bool, TransactionError> {
-        Ok(prover.verify(&self.proof.0, &self.commitment))
+        match self.features.range_proof_type {
+            RangeProofType::BulletProof => Ok(prover.verify(&self.proof.0, &self.commitment)),
+            // The version of tari_crypto in use has no Bulletproof+ verifier, so these outputs cannot be accepted
+            // until it does, even once the consensus rules permit them
+            t @ RangeProofType::BulletProofPlus => Err(TransactionError::UnsupportedRangeProofType(t)),
+        }
+    }
+
+    fn check_range_proof_is_bulletproof(&self) -> Result<(), TransactionError> {
+        match self.features.range_proof_type {
+            RangeProofType::BulletProof => Ok(()),
+            t => Err(TransactionError::UnsupportedRangeProofType(t)),
+        }
     }
 
     /// Attempt to rewind the range proof to reveal the proof message and committed value
@@ -405,6 +859,7 @@ impl TransactionOutput {
         rewind_blinding_public_key: &PublicKey,
     ) -> Result<RewindResult, TransactionError>
     {
+        self.check_range_proof_is_bulletproof()?;
         Ok(prover
             .rewind_proof_value_only(
                 &self.proof.0,
@@ -423,16 +878,17 @@ impl TransactionOutput {
         rewind_blinding_key: &PrivateKey,
     ) -> Result<FullRewindResult, TransactionError>
     {
+        self.check_range_proof_is_bulletproof()?;
         Ok(prover
             .rewind_proof_commitment_data(&self.proof.0, &&self.commitment, rewind_key, rewind_blinding_key)?
             .into())
     }
 
-    /// This will check if the input and the output is the same commitment by looking at the commitment and features.
-    /// This will ignore the output rangeproof
+    /// This will check if the input and the output is the same commitment by looking at the commitment, features and
+    /// covenant. This will ignore the output rangeproof
     #[inline]
     pub fn is_equal_to(&self, output: &TransactionInput) -> bool {
-        self.commitment == output.commitment && self.features == output.features
+        self.commitment == output.commitment && self.features == output.features && self.covenant == output.covenant
     }
 
     /// Returns true if the output is a coinbase, otherwise false
@@ -449,12 +905,21 @@ impl TransactionOutput {
 /// c) TransactionInputs will now have the same hash as UTXOs, which makes locating STXOs easier when doing reorgs
 impl Hashable for TransactionOutput {
     fn hash(&self) -> Vec<u8> {
-        HashDigest::new()
-            .chain(self.features.to_bytes())
-            .chain(self.commitment.as_bytes())
-            // .chain(range proof) // See docs as to why we exclude this
-            .result()
-            .to_vec()
+        // .chain(range proof) // See docs as to why we exclude this
+        hash_output(&self.features, &self.commitment, &self.covenant)
+    }
+}
+
+/// The hash shared by an output and the input that spends it. The covenant is only committed to when it is not empty
+/// so that the hashes of outputs without a covenant are unchanged.
+fn hash_output(features: &OutputFeatures, commitment: &Commitment, covenant: &Covenant) -> Vec<u8> {
+    let hasher = HashDigest::new()
+        .chain(features.to_bytes())
+        .chain(commitment.as_bytes());
+    if covenant.is_empty() {
+        hasher.result().to_vec()
+    } else {
+        hasher.chain(covenant.to_bytes()).result().to_vec()
     }
 }
 
@@ -558,6 +1023,10 @@ pub struct TransactionKernel {
     /// This kernel is not valid earlier than lock_height blocks
     /// The max lock_height of all *inputs* to this transaction
     pub lock_height: u64,
+    /// The amount destroyed by this kernel. Only non-zero for burn kernels. Burned value is not claimable by the
+    /// miner and is committed to in the excess signature, so it is provably removed from circulation.
+    #[serde(default)]
+    pub burn_amount: MicroTari,
     /// Remainder of the sum of all transaction commitments (minus an offset). If the transaction is well-formed,
     /// amounts plus fee will sum to zero, and the excess is hence a valid public key.
     pub excess: Commitment,
@@ -571,6 +1040,7 @@ pub struct KernelBuilder {
     features: KernelFeatures,
     fee: MicroTari,
     lock_height: u64,
+    burn_amount: MicroTari,
     excess: Option<Commitment>,
     excess_sig: Option<Signature>,
 }
@@ -600,6 +1070,12 @@ impl KernelBuilder {
         self
     }
 
+    /// Build a transaction kernel that burns the provided amount
+    pub fn with_burn_amount(mut self, burn_amount: MicroTari) -> KernelBuilder {
+        self.burn_amount = burn_amount;
+        self
+    }
+
     /// Add the excess (sum of public spend keys minus the offset)
     pub fn with_excess(mut self, excess: &Commitment) -> KernelBuilder {
         self.excess = Some(excess.clone());
@@ -620,6 +1096,7 @@ impl KernelBuilder {
             features: self.features,
             fee: self.fee,
             lock_height: self.lock_height,
+            burn_amount: self.burn_amount,
             excess: self.excess.unwrap(),
             excess_sig: self.excess_sig.unwrap(),
         })
@@ -632,6 +1109,7 @@ impl Default for KernelBuilder {
             features: KernelFeatures::empty(),
             fee: MicroTari::from(0),
             lock_height: 0,
+            burn_amount: MicroTari::from(0),
             excess: None,
             excess_sig: None,
         }
@@ -639,12 +1117,30 @@ impl Default for KernelBuilder {
 }
 
 impl TransactionKernel {
+    pub fn is_burn(&self) -> bool {
+        self.features.contains(KernelFeatures::BURN_KERNEL)
+    }
+
+    /// A kernel is well-formed if it carries a burn amount if and only if it is flagged as a burn kernel
+    pub fn check_burn(&self) -> Result<(), TransactionError> {
+        match (self.is_burn(), self.burn_amount > MicroTari::from(0)) {
+            (true, true) | (false, false) => Ok(()),
+            (true, false) => Err(TransactionError::ValidationError(
+                "Burn kernel must burn a non-zero amount".into(),
+            )),
+            (false, true) => Err(TransactionError::ValidationError(
+                "Only burn kernels may carry a burn amount".into(),
+            )),
+        }
+    }
+
     pub fn verify_signature(&self) -> Result<(), TransactionError> {
         let excess = self.excess.as_public_key();
         let r = self.excess_sig.get_public_nonce();
         let m = TransactionMetadata {
             lock_height: self.lock_height,
             fee: self.fee,
+            burn_amount: self.burn_amount,
         };
         let c = build_challenge(r, &m);
         if self.excess_sig.verify_challenge(excess, &c) {
@@ -657,12 +1153,17 @@ impl TransactionKernel {
 
 impl Hashable for TransactionKernel {
     /// Produce a canonical hash for a transaction kernel. The hash is given by
-    /// $$ H(feature_bits | fee | lock_height | P_excess | R_sum | s_sum)
+    /// $$ H(feature_bits | fee | lock_height | [burn_amount] | P_excess | R_sum | s_sum)
+    /// The burn amount is only included for kernels that burn funds, which keeps the hashes of existing kernels stable.
     fn hash(&self) -> Vec<u8> {
-        HashDigest::new()
+        let mut hasher = HashDigest::new()
             .chain(&[self.features.bits])
             .chain(u64::from(self.fee).to_le_bytes())
-            .chain(self.lock_height.to_le_bytes())
+            .chain(self.lock_height.to_le_bytes());
+        if self.is_burn() || self.burn_amount > MicroTari::from(0) {
+            hasher = hasher.chain(u64::from(self.burn_amount).to_le_bytes());
+        }
+        hasher
             .chain(self.excess.as_bytes())
             .chain(self.excess_sig.get_public_nonce().as_bytes())
             .chain(self.excess_sig.get_signature().as_bytes())
@@ -674,9 +1175,10 @@ impl Hashable for TransactionKernel {
 impl Display for TransactionKernel {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
         let msg = format!(
-            "Fee: {}\nLock height: {}\nFeatures: {:?}\nExcess: {}\nExcess signature: {}\n",
+            "Fee: {}\nLock height: {}\nBurn amount: {}\nFeatures: {:?}\nExcess: {}\nExcess signature: {}\n",
             self.fee,
             self.lock_height,
+            self.burn_amount,
             self.features,
             self.excess.to_hex(),
             self.excess_sig
@@ -691,6 +1193,7 @@ impl Display for TransactionKernel {
 /// and returns the summed commitments and the total fees
 pub struct KernelSum {
     pub sum: Commitment,
+    /// The total of the fees and burned amounts of the kernels
     pub fees: MicroTari,
 }
 
@@ -762,12 +1265,13 @@ impl Transaction {
         })
     }
 
-    /// Returns the maximum maturity of the input UTXOs
+    /// Returns the maximum maturity of the input UTXOs, including the refund heights of refunded hash time-locked
+    /// outputs
     pub fn max_input_maturity(&self) -> u64 {
         self.body
             .inputs()
             .iter()
-            .fold(0, |max_maturity, input| max(max_maturity, input.features.maturity))
+            .fold(0, |max_maturity, input| max(max_maturity, input.min_spendable_height()))
     }
 
     /// Returns the maximum timelock of the kernels inside of the transaction
@@ -864,12 +1368,18 @@ impl TransactionBuilder {
         self
     }
 
-    /// Set the kernel of a transaction. Currently only one kernel is allowed per transaction
+    /// Set the kernel of a transaction, replacing any kernels added before
     pub fn with_kernel(&mut self, kernel: TransactionKernel) -> &mut Self {
         self.body.set_kernel(kernel);
         self
     }
 
+    /// Add a kernel to an existing transaction
+    pub fn add_kernel(&mut self, kernel: TransactionKernel) -> &mut Self {
+        self.body.add_kernel(kernel);
+        self
+    }
+
     pub fn with_reward(&mut self, reward: MicroTari) -> &mut Self {
         self.reward = Some(reward);
         self
@@ -907,7 +1417,10 @@ mod test {
     use super::*;
     use crate::{
         transactions::{
-            helpers::{create_test_kernel, create_tx, spend_utxos},
+            covenant::CovenantRule,
+            helpers::{create_multisig_witness, create_test_kernel, create_test_multisig_lock, create_tx, spend_utxos},
+            htlc::{generate_preimage, hash_preimage},
+            script::{Opcode, StackItem},
             tari_amount::T,
             transaction::OutputFeatures,
             types::{BlindingFactor, PrivateKey, PublicKey, RangeProof},
@@ -966,6 +1479,30 @@ mod test {
         assert_eq!(tx_output3.verify_range_proof(&factories.range_proof).unwrap(), false);
     }
 
+    #[test]
+    fn range_proof_type() {
+        let factories = CryptoFactories::new(32);
+        let unblinded = UnblindedOutput::new(100.into(), BlindingFactor::random(&mut OsRng), None);
+        let output = unblinded.as_transaction_output(&factories).unwrap();
+        assert_eq!(output.features.range_proof_type, RangeProofType::BulletProof);
+
+        // The range proof type is committed to in the output hash, so it cannot be changed to pick another verifier
+        let mut plus = output.clone();
+        plus.features = OutputFeatures::with_range_proof_type(RangeProofType::BulletProofPlus);
+        let mut bulletproof = output;
+        bulletproof.features = OutputFeatures::with_range_proof_type(RangeProofType::BulletProof);
+        assert_ne!(plus.hash(), bulletproof.hash());
+
+        // Proofs are verified according to their type
+        assert!(bulletproof.verify_range_proof(&factories.range_proof).unwrap());
+        assert_eq!(
+            plus.verify_range_proof(&factories.range_proof),
+            Err(TransactionError::UnsupportedRangeProofType(
+                RangeProofType::BulletProofPlus
+            ))
+        );
+    }
+
     #[test]
     fn kernel_hash() {
         let s = PrivateKey::from_hex("6c6eebc5a9c02e1f3c16a69ba4331f9f63d0718401dea10adc4f9d3b879a2c09").unwrap();
@@ -1046,6 +1583,357 @@ mod test {
         assert_eq!(tx.min_spendable_height(), 10);
     }
 
+    #[test]
+    fn relative_maturity() {
+        let features = OutputFeatures::with_relative_maturity(10);
+        assert!(!features.is_mature_at(5, 14));
+        assert!(features.is_mature_at(5, 15));
+        let features = OutputFeatures {
+            maturity: 20,
+            ..features
+        };
+        assert!(!features.is_mature_at(5, 19));
+        assert!(features.is_mature_at(5, 20));
+
+        // Outputs without a relative time-lock keep their original hash
+        assert_eq!(OutputFeatures::default().to_bytes().len(), 9);
+        assert_eq!(OutputFeatures::with_relative_maturity(10).to_bytes().len(), 17);
+    }
+
+    #[test]
+    fn output_features_version() {
+        assert_eq!(OutputFeatures::default().version, OutputFeaturesVersion::V0);
+        assert_eq!(OutputFeaturesVersion::from(0), OutputFeaturesVersion::V0);
+        assert_eq!(OutputFeaturesVersion::from(5), OutputFeaturesVersion::V5);
+        assert!(OutputFeaturesVersion::V5.is_known());
+        assert!(!OutputFeaturesVersion::from(6).is_known());
+        assert!(OutputFeaturesVersion::from(6) > OutputFeaturesVersion::LATEST);
+
+        // Features that were serialized before the version was added are V0
+        let features: OutputFeatures =
+            serde_json::from_str(r#"{"flags":{"bits":1},"maturity":5,"relative_maturity":0,"asset":null}"#).unwrap();
+        assert_eq!(features.version, OutputFeaturesVersion::V0);
+        assert_eq!(features, OutputFeatures::create_coinbase(5));
+    }
+
+    #[test]
+    fn coinbase_extra() {
+        // Coinbases without extra data are unchanged
+        assert_eq!(
+            OutputFeatures::create_coinbase_with_extra(5, vec![]),
+            OutputFeatures::create_coinbase(5)
+        );
+
+        let features = OutputFeatures::create_coinbase_with_extra(5, b"pool".to_vec());
+        assert_eq!(features.version, OutputFeaturesVersion::V1);
+        assert!(features.flags.contains(OutputFlags::COINBASE_OUTPUT));
+        // The version, flags and maturity, relative maturity, asset presence, extra length and extra
+        assert_eq!(features.to_bytes().len(), 1 + 9 + 8 + 1 + 8 + 4);
+        let other = OutputFeatures::create_coinbase_with_extra(5, b"pooL".to_vec());
+        assert_ne!(features.to_bytes(), other.to_bytes());
+    }
+
+    #[test]
+    fn check_covenants() {
+        let factories = CryptoFactories::new(32);
+        let covenant = Covenant::new()
+            .with_rule(CovenantRule::MinMaturity(10))
+            .with_rule(CovenantRule::PreserveCovenant);
+        let k = BlindingFactor::random(&mut OsRng);
+        let input = TransactionInput::new(OutputFeatures::default(), factories.commitment.commit_value(&k, 100))
+            .with_covenant(covenant.clone());
+        let mut tx = Transaction::new(vec![input.clone()], Vec::new(), Vec::new(), 0.into());
+        assert!(matches!(
+            tx.body.check_covenants(),
+            Err(TransactionError::CovenantNotSatisfied(_))
+        ));
+
+        let mut output = TransactionOutput::default().with_covenant(covenant.clone());
+        tx.body.add_output(output.clone());
+        assert!(tx.body.check_covenants().is_err());
+
+        output.features.maturity = 10;
+        tx.body.add_output(output);
+        assert_eq!(tx.body.check_covenants(), Ok(()));
+
+        // The covenant is committed to in the hash, so it cannot be stripped by the spender
+        assert_ne!(
+            input.hash(),
+            TransactionInput::new(input.features.clone(), input.commitment.clone()).hash()
+        );
+        let plain = TransactionOutput::default();
+        assert_eq!(plain.hash(), TransactionInput::from(plain.clone()).hash());
+    }
+
+    #[test]
+    fn covenants_are_not_satisfied_across_merged_transactions() {
+        let factories = CryptoFactories::new(32);
+        let vault = Covenant::new().with_rule(CovenantRule::PreserveCovenant);
+        let vault_input = || {
+            let k = BlindingFactor::random(&mut OsRng);
+            TransactionInput::new(OutputFeatures::default(), factories.commitment.commit_value(&k, 100))
+                .with_covenant(vault.clone())
+        };
+        let vault_output = TransactionOutput::default().with_covenant(vault.clone());
+
+        // Keeps the funds in the vault
+        let honest = Transaction::new(vec![vault_input()], vec![vault_output.clone()], Vec::new(), 0.into());
+        assert_eq!(honest.body.check_covenants(), Ok(()));
+        // Takes the funds out of the vault
+        let thief = Transaction::new(
+            vec![vault_input()],
+            vec![TransactionOutput::default()],
+            Vec::new(),
+            0.into(),
+        );
+        assert!(thief.body.check_covenants().is_err());
+
+        // Merging the two must not let the output of the honest transaction satisfy the covenant of the thief
+        let merged = honest.clone().add_no_cut_through(thief);
+        assert!(matches!(
+            merged.body.check_covenants(),
+            Err(TransactionError::CovenantNotSatisfied(_))
+        ));
+
+        // Merging valid transactions keeps them valid
+        let other = Transaction::new(vec![vault_input()], vec![vault_output], Vec::new(), 0.into());
+        let merged = honest.add_no_cut_through(other);
+        assert_eq!(merged.body.check_covenants(), Ok(()));
+    }
+
+    #[test]
+    fn check_asset_rules() {
+        let factories = CryptoFactories::new(32);
+        let (issuer_k, issuer) = PublicKey::random_keypair(&mut OsRng);
+        let commitment = factories
+            .commitment
+            .commit_value(&BlindingFactor::random(&mut OsRng), 100);
+        let asset = AssetOutputFeatures::new(issuer, b"gold".to_vec(), 1);
+
+        // Minting requires the issuer's signature
+        let mut mint = TransactionOutput::default();
+        mint.commitment = commitment.clone();
+        mint.features = OutputFeatures::create_mint_non_fungible(asset.clone());
+        let mut tx = Transaction::new(Vec::new(), vec![mint.clone()], Vec::new(), 0.into());
+        assert!(matches!(
+            tx.body.check_asset_rules(),
+            Err(TransactionError::InvalidAsset(_))
+        ));
+        mint.features = OutputFeatures::create_mint_non_fungible(
+            asset
+                .clone()
+                .sign(issuer_k, PrivateKey::random(&mut OsRng), &commitment)
+                .unwrap(),
+        );
+        tx = Transaction::new(Vec::new(), vec![mint.clone()], Vec::new(), 0.into());
+        assert_eq!(tx.body.check_asset_rules(), Ok(()));
+        // The same token cannot be minted twice
+        tx.body.add_output(mint.clone());
+        assert!(tx.body.check_asset_rules().is_err());
+
+        // A token can only be received if it is spent by an input
+        let mut transfer = TransactionOutput::default();
+        transfer.features = OutputFeatures::with_asset(asset);
+        let tx = Transaction::new(Vec::new(), vec![transfer.clone()], Vec::new(), 0.into());
+        assert!(tx.body.check_asset_rules().is_err());
+        let input = TransactionInput::from(mint.clone());
+        let tx = Transaction::new(vec![input.clone()], vec![transfer.clone()], Vec::new(), 0.into());
+        assert_eq!(tx.body.check_asset_rules(), Ok(()));
+        // A token cannot be burned
+        let burn = Transaction::new(vec![input], vec![TransactionOutput::default()], Vec::new(), 0.into());
+        assert!(matches!(
+            burn.body.check_asset_rules(),
+            Err(TransactionError::InvalidAsset(_))
+        ));
+        // Merging a transfer with a forged one must not let the input of the first pay for the second
+        let forged = Transaction::new(Vec::new(), vec![transfer], Vec::new(), 0.into());
+        assert!(forged.body.check_asset_rules().is_err());
+        let merged = tx.add_no_cut_through(forged);
+        assert!(matches!(
+            merged.body.check_asset_rules(),
+            Err(TransactionError::InvalidAsset(_))
+        ));
+
+        // Asset features are committed to in the output hash
+        let mut plain = mint.clone();
+        plain.features = OutputFeatures::default();
+        assert_ne!(mint.hash(), plain.hash());
+    }
+
+    #[test]
+    fn check_htlc_rules() {
+        let factories = CryptoFactories::new(32);
+        let (claim_k, claim) = PublicKey::random_keypair(&mut OsRng);
+        let (refund_k, refund) = PublicKey::random_keypair(&mut OsRng);
+        let preimage = generate_preimage().to_vec();
+        let htlc = HashTimeLock::new(hash_preimage(&preimage), claim, refund, 50);
+        let commitment = factories
+            .commitment
+            .commit_value(&BlindingFactor::random(&mut OsRng), 100);
+        let input = TransactionInput::new(OutputFeatures::with_hash_time_lock(htlc.clone()), commitment.clone());
+        let kernel = create_test_kernel(5.into(), 0);
+
+        // A hash time-locked output cannot be spent without a witness
+        let tx = Transaction::new(vec![input.clone()], Vec::new(), vec![kernel.clone()], 0.into());
+        assert!(matches!(
+            tx.body.check_htlc_rules(),
+            Err(TransactionError::InvalidHtlc(_))
+        ));
+
+        let claim_witness = htlc
+            .sign_claim(
+                claim_k,
+                PrivateKey::random(&mut OsRng),
+                preimage,
+                &commitment,
+                kernel.excess.clone(),
+            )
+            .unwrap();
+        let claimed = input.clone().with_htlc_witness(claim_witness.clone());
+        let tx = Transaction::new(vec![claimed.clone()], Vec::new(), vec![kernel.clone()], 0.into());
+        assert_eq!(tx.body.check_htlc_rules(), Ok(()));
+        assert_eq!(tx.body.check_stxo_rules(0), Ok(()));
+        // The witness is bound to the kernel of the spending transaction
+        let other = create_test_kernel(5.into(), 0);
+        let tx = Transaction::new(vec![claimed.clone()], Vec::new(), vec![other], 0.into());
+        assert!(tx.body.check_htlc_rules().is_err());
+        // ...but it is not part of the input hash
+        assert_eq!(claimed.hash(), input.hash());
+
+        // A refund is only possible from the refund height
+        let refund_witness = htlc
+            .sign_refund(
+                refund_k,
+                PrivateKey::random(&mut OsRng),
+                &commitment,
+                kernel.excess.clone(),
+            )
+            .unwrap();
+        let refunded = input.with_htlc_witness(refund_witness);
+        let tx = Transaction::new(vec![refunded], Vec::new(), vec![kernel.clone()], 0.into());
+        assert_eq!(tx.body.check_htlc_rules(), Ok(()));
+        assert_eq!(tx.body.check_stxo_rules(49), Err(TransactionError::InputMaturity));
+        assert_eq!(tx.body.check_stxo_rules(50), Ok(()));
+        assert_eq!(tx.min_spendable_height(), 50);
+
+        // Only hash time-locked inputs may carry a witness
+        let plain = TransactionInput::new(OutputFeatures::default(), commitment).with_htlc_witness(claim_witness);
+        let tx = Transaction::new(vec![plain], Vec::new(), vec![kernel], 0.into());
+        assert!(tx.body.check_htlc_rules().is_err());
+
+        // Hash time-locked outputs require V2 features
+        let mut output = TransactionOutput::default();
+        output.features = OutputFeatures::with_hash_time_lock(htlc);
+        let mut tx = Transaction::new(Vec::new(), vec![output.clone()], Vec::new(), 0.into());
+        assert_eq!(tx.body.check_htlc_rules(), Ok(()));
+        output.features.version = OutputFeaturesVersion::V1;
+        tx = Transaction::new(Vec::new(), vec![output], Vec::new(), 0.into());
+        assert!(tx.body.check_htlc_rules().is_err());
+    }
+
+    #[test]
+    fn check_multisig_rules() {
+        let factories = CryptoFactories::new(32);
+        let (lock, secrets) = create_test_multisig_lock();
+        let commitment = factories
+            .commitment
+            .commit_value(&BlindingFactor::random(&mut OsRng), 100);
+        let input = TransactionInput::new(OutputFeatures::with_multisig_lock(lock.clone()), commitment.clone());
+        let kernel = create_test_kernel(5.into(), 0);
+
+        // A multisig locked output cannot be spent without a witness
+        let tx = Transaction::new(vec![input.clone()], Vec::new(), vec![kernel.clone()], 0.into());
+        assert!(matches!(
+            tx.body.check_multisig_rules(),
+            Err(TransactionError::InvalidMultisig(_))
+        ));
+
+        let witness = create_multisig_witness(&lock, &secrets, vec![0, 2], &commitment, kernel.excess.clone());
+        let signed = input.clone().with_multisig_witness(witness.clone());
+        let tx = Transaction::new(vec![signed.clone()], Vec::new(), vec![kernel.clone()], 0.into());
+        assert_eq!(tx.body.check_multisig_rules(), Ok(()));
+        // The witness is bound to the kernel of the spending transaction
+        let other = create_test_kernel(5.into(), 0);
+        let tx = Transaction::new(vec![signed.clone()], Vec::new(), vec![other], 0.into());
+        assert!(tx.body.check_multisig_rules().is_err());
+        // ...but it is not part of the input hash
+        assert_eq!(signed.hash(), input.hash());
+
+        // Only multisig locked inputs may carry a witness
+        let plain = TransactionInput::new(OutputFeatures::default(), commitment).with_multisig_witness(witness);
+        let tx = Transaction::new(vec![plain], Vec::new(), vec![kernel], 0.into());
+        assert!(tx.body.check_multisig_rules().is_err());
+
+        // Multisig locked outputs require V3 features
+        let mut output = TransactionOutput::default();
+        output.features = OutputFeatures::with_multisig_lock(lock);
+        let tx = Transaction::new(Vec::new(), vec![output.clone()], Vec::new(), 0.into());
+        assert_eq!(tx.body.check_multisig_rules(), Ok(()));
+        output.features.version = OutputFeaturesVersion::V2;
+        let tx = Transaction::new(Vec::new(), vec![output], Vec::new(), 0.into());
+        assert!(tx.body.check_multisig_rules().is_err());
+    }
+
+    #[test]
+    fn check_script_rules() {
+        let factories = CryptoFactories::new(32);
+        let (k, p) = PublicKey::random_keypair(&mut OsRng);
+        let script = TariScript::new(vec![Opcode::CheckHeightVerify(10), Opcode::CheckMultiSig(1, vec![p])]);
+        let commitment = factories
+            .commitment
+            .commit_value(&BlindingFactor::random(&mut OsRng), 100);
+        let input = TransactionInput::new(OutputFeatures::with_script(script.clone()), commitment.clone());
+        let kernel = create_test_kernel(5.into(), 0);
+
+        // A script locked output cannot be spent without a witness
+        let tx = Transaction::new(vec![input.clone()], Vec::new(), vec![kernel.clone()], 0.into());
+        assert!(matches!(
+            tx.body.check_script_rules(),
+            Err(TransactionError::InvalidScript(_))
+        ));
+
+        let signature = script
+            .sign(k, PrivateKey::random(&mut OsRng), &commitment, &kernel.excess)
+            .unwrap();
+        let witness = ScriptWitness {
+            kernel_excess: kernel.excess.clone(),
+            stack: vec![StackItem::Signature(signature)],
+        };
+        let signed = input.clone().with_script_witness(witness.clone());
+        let tx = Transaction::new(vec![signed.clone()], Vec::new(), vec![kernel.clone()], 0.into());
+        assert_eq!(tx.body.check_script_rules(), Ok(()));
+        // The script runs at the height of the spending block
+        assert!(matches!(
+            tx.body.check_stxo_rules(9),
+            Err(TransactionError::InvalidScript(_))
+        ));
+        assert_eq!(tx.body.check_stxo_rules(10), Ok(()));
+        // The witness is bound to the kernel of the spending transaction
+        let other = create_test_kernel(5.into(), 0);
+        let tx = Transaction::new(vec![signed.clone()], Vec::new(), vec![other], 0.into());
+        assert!(tx.body.check_script_rules().is_err());
+        // ...but it is not part of the input hash
+        assert_eq!(signed.hash(), input.hash());
+
+        // Only script locked inputs may carry a witness
+        let plain = TransactionInput::new(OutputFeatures::default(), commitment).with_script_witness(witness);
+        let tx = Transaction::new(vec![plain], Vec::new(), vec![kernel], 0.into());
+        assert!(tx.body.check_script_rules().is_err());
+
+        // Script locked outputs require V4 features and cannot carry another lock
+        let mut output = TransactionOutput::default();
+        output.features = OutputFeatures::with_script(script);
+        let tx = Transaction::new(Vec::new(), vec![output.clone()], Vec::new(), 0.into());
+        assert_eq!(tx.body.check_script_rules(), Ok(()));
+        output.features.version = OutputFeaturesVersion::V3;
+        let tx = Transaction::new(Vec::new(), vec![output.clone()], Vec::new(), 0.into());
+        assert!(tx.body.check_script_rules().is_err());
+        output.features.version = OutputFeaturesVersion::V4;
+        output.features.multisig = Some(create_test_multisig_lock().0);
+        let tx = Transaction::new(Vec::new(), vec![output], Vec::new(), 0.into());
+        assert!(tx.body.check_script_rules().is_err());
+    }
+
     #[test]
     fn test_validate_internal_consistency() {
         let (tx, _, _) = create_tx(5000.into(), 15.into(), 1, 2, 1, 4);
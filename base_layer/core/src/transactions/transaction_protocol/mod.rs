@@ -133,6 +133,9 @@ pub struct TransactionMetadata {
     pub fee: MicroTari,
     /// The earliest block this transaction can be mined
     pub lock_height: u64,
+    /// The amount permanently destroyed by this transaction. Zero for regular transactions.
+    #[serde(default)]
+    pub burn_amount: MicroTari,
 }
 
 #[derive(Debug, Clone)]
@@ -144,10 +147,13 @@ pub struct RewindData {
 
 /// Convenience function that calculates the challenge for the Schnorr signatures
 pub fn build_challenge(sum_public_nonces: &PublicKey, metadata: &TransactionMetadata) -> MessageHash {
-    Challenge::new()
+    let mut challenge = Challenge::new()
         .chain(sum_public_nonces.as_bytes())
         .chain(&u64::from(metadata.fee).to_le_bytes())
-        .chain(&metadata.lock_height.to_le_bytes())
-        .result()
-        .to_vec()
+        .chain(&metadata.lock_height.to_le_bytes());
+    // Committing to the burn amount prevents burned value from being redirected once the kernel is signed
+    if metadata.burn_amount > MicroTari::from(0) {
+        challenge = challenge.chain(&u64::from(metadata.burn_amount).to_le_bytes());
+    }
+    challenge.result().to_vec()
 }
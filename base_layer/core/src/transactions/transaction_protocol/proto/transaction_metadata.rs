@@ -29,6 +29,7 @@ impl From<proto::TransactionMetadata> for TransactionMetadata {
         Self {
             fee: metadata.fee.into(),
             lock_height: metadata.lock_height,
+            burn_amount: metadata.burn_amount.into(),
         }
     }
 }
@@ -40,6 +41,8 @@ impl From<TransactionMetadata> for proto::TransactionMetadata {
             fee: metadata.fee.into(),
             // The earliest block this transaction can be mined
             lock_height: metadata.lock_height,
+            // The amount permanently destroyed by this transaction
+            burn_amount: metadata.burn_amount.into(),
         }
     }
 }
@@ -230,6 +230,7 @@ mod test {
         let m = TransactionMetadata {
             fee: MicroTari(125),
             lock_height: 0,
+            burn_amount: MicroTari(0),
         };
         let msg = SingleRoundSenderData {
             tx_id: 15,
@@ -276,6 +277,7 @@ mod test {
         let m = TransactionMetadata {
             fee: MicroTari(125),
             lock_height: 0,
+            burn_amount: MicroTari(0),
         };
         let msg = SingleRoundSenderData {
             tx_id: 15,
@@ -21,6 +21,9 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::transactions::{
+    htlc::HtlcWitness,
+    multisig::MultisigWitness,
+    script::ScriptWitness,
     tari_amount::*,
     transaction::{
         KernelBuilder,
@@ -40,7 +43,7 @@ use crate::transactions::{
         TransactionMetadata,
         TransactionProtocolError as TPE,
     },
-    types::{BlindingFactor, CryptoFactories, PrivateKey, PublicKey, RangeProofService, Signature},
+    types::{BlindingFactor, Commitment, CryptoFactories, PrivateKey, PublicKey, RangeProofService, Signature},
 };
 use digest::Digest;
 use serde::{Deserialize, Serialize};
@@ -76,6 +79,28 @@ pub(super) struct RawTransactionInfo {
     pub recipient_info: RecipientInfo,
     pub signatures: Vec<Signature>,
     pub message: String,
+    // A kernel for each recipient when there are several, empty otherwise
+    #[serde(default)]
+    pub recipient_kernels: Vec<RecipientKernelInfo>,
+}
+
+/// The sender's part of the kernel that pays one of several recipients. A transaction with several recipients has a
+/// kernel for each of them, so that each recipient completes the single-round protocol on its own, without learning
+/// about the others. The sender's excess is split between the kernels and each kernel is signed with its own nonce.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub(super) struct RecipientKernelInfo {
+    pub tx_id: u64,
+    pub amount: MicroTari,
+    // The share of the transaction fee that this kernel pays
+    pub metadata: TransactionMetadata,
+    // The sender's part of the offset blinding factor
+    pub excess_blinding_factor: BlindingFactor,
+    pub public_excess: PublicKey,
+    pub private_nonce: PrivateKey,
+    pub public_nonce: PublicKey,
+    // Set once the recipient has replied
+    pub recipient_public_spend_key: Option<PublicKey>,
+    pub signatures: Vec<Signature>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
@@ -197,6 +222,37 @@ impl SenderTransactionProtocol {
         }
     }
 
+    /// Return the TxIds of all the recipients, in the order of their amounts. The first one is the TxId of the
+    /// transaction, see [get_tx_id](Self::get_tx_id).
+    pub fn get_tx_ids(&self) -> Result<Vec<u64>, TPE> {
+        match &self.state {
+            SenderState::Finalizing(info) |
+            SenderState::SingleRoundMessageReady(info) |
+            SenderState::CollectingSingleSignature(info) => Ok(info.ids.clone()),
+            _ => Err(TPE::InvalidStateError),
+        }
+    }
+
+    /// Return the TxIds of the recipients whose signed data has not been added yet
+    pub fn get_pending_recipient_tx_ids(&self) -> Result<Vec<u64>, TPE> {
+        match &self.state {
+            SenderState::SingleRoundMessageReady(info) | SenderState::CollectingSingleSignature(info) => {
+                if info.recipient_kernels.is_empty() {
+                    Ok(vec![info.ids[0]])
+                } else {
+                    Ok(info
+                        .recipient_kernels
+                        .iter()
+                        .filter(|k| k.recipient_public_spend_key.is_none())
+                        .map(|k| k.tx_id)
+                        .collect())
+                }
+            },
+            SenderState::Finalizing(_) => Ok(Vec::new()),
+            _ => Err(TPE::InvalidStateError),
+        }
+    }
+
     pub fn get_total_amount(&self) -> Result<MicroTari, TPE> {
         match &self.state {
             SenderState::Initializing(info) |
@@ -244,6 +300,69 @@ impl SenderTransactionProtocol {
         }
     }
 
+    /// This function will return the excess of the kernel that `finalize` is going to build. It is only known once all
+    /// recipients have replied, i.e. in the Finalizing state.
+    pub fn get_kernel_excess(&self) -> Result<Commitment, TPE> {
+        match &self.state {
+            SenderState::Finalizing(info) if !info.recipient_kernels.is_empty() => Err(TPE::UnsupportedError(
+                "A transaction with several recipients has a kernel for each of them".into(),
+            )),
+            SenderState::Finalizing(info) => Ok(PedersenCommitment::from_public_key(&info.public_excess)),
+            _ => Err(TPE::InvalidStateError),
+        }
+    }
+
+    /// Attach the witness that spends the hash time-locked input with the given commitment. The witness commits to the
+    /// kernel excess, see [get_kernel_excess](Self::get_kernel_excess), so this can only be done before `finalize`.
+    pub fn add_htlc_witness(&mut self, commitment: &Commitment, witness: HtlcWitness) -> Result<(), TPE> {
+        match &mut self.state {
+            SenderState::Finalizing(info) => {
+                let input = info
+                    .inputs
+                    .iter_mut()
+                    .find(|i| &i.commitment == commitment && i.features.is_hash_time_locked())
+                    .ok_or_else(|| TPE::ValidationError("No hash time-locked input with this commitment".into()))?;
+                input.htlc_witness = Some(witness);
+                Ok(())
+            },
+            _ => Err(TPE::InvalidStateError),
+        }
+    }
+
+    /// Attach the witness that spends the multisig locked input with the given commitment. Like
+    /// [add_htlc_witness](Self::add_htlc_witness), this can only be done before `finalize`.
+    pub fn add_multisig_witness(&mut self, commitment: &Commitment, witness: MultisigWitness) -> Result<(), TPE> {
+        match &mut self.state {
+            SenderState::Finalizing(info) => {
+                let input = info
+                    .inputs
+                    .iter_mut()
+                    .find(|i| &i.commitment == commitment && i.features.is_multisig_locked())
+                    .ok_or_else(|| TPE::ValidationError("No multisig locked input with this commitment".into()))?;
+                input.multisig_witness = Some(witness);
+                Ok(())
+            },
+            _ => Err(TPE::InvalidStateError),
+        }
+    }
+
+    /// Attach the witness that spends the script locked input with the given commitment. Like
+    /// [add_htlc_witness](Self::add_htlc_witness), this can only be done before `finalize`.
+    pub fn add_script_witness(&mut self, commitment: &Commitment, witness: ScriptWitness) -> Result<(), TPE> {
+        match &mut self.state {
+            SenderState::Finalizing(info) => {
+                let input = info
+                    .inputs
+                    .iter_mut()
+                    .find(|i| &i.commitment == commitment && i.features.is_script_locked())
+                    .ok_or_else(|| TPE::ValidationError("No script locked input with this commitment".into()))?;
+                input.script_witness = Some(witness);
+                Ok(())
+            },
+            _ => Err(TPE::InvalidStateError),
+        }
+    }
+
     /// Build the sender's message for the single-round protocol (one recipient) and move to next State
     pub fn build_single_round_message(&mut self) -> Result<SingleRoundSenderData, TPE> {
         match &self.state {
@@ -256,9 +375,29 @@ impl SenderTransactionProtocol {
         }
     }
 
-    /// Return the single round sender message
+    /// Build the sender's messages for the single-round protocol, one for each recipient, and move to next State
+    pub fn build_single_round_messages(&mut self) -> Result<Vec<SingleRoundSenderData>, TPE> {
+        match &self.state {
+            SenderState::SingleRoundMessageReady(info) => {
+                let result = self.get_single_round_messages()?;
+                self.state = SenderState::CollectingSingleSignature(info.clone());
+                Ok(result)
+            },
+            _ => Err(TPE::InvalidStateError),
+        }
+    }
+
+    /// Return the single round sender message. Use [get_single_round_messages](Self::get_single_round_messages) if
+    /// the transaction has several recipients.
     pub fn get_single_round_message(&self) -> Result<SingleRoundSenderData, TPE> {
         match &self.state {
+            SenderState::SingleRoundMessageReady(info) | SenderState::CollectingSingleSignature(info)
+                if !info.recipient_kernels.is_empty() =>
+            {
+                Err(TPE::UnsupportedError(
+                    "The transaction has several recipients, each with a message of its own".into(),
+                ))
+            },
             SenderState::SingleRoundMessageReady(info) | SenderState::CollectingSingleSignature(info) => {
                 Ok(SingleRoundSenderData {
                     tx_id: info.ids[0],
@@ -273,7 +412,31 @@ impl SenderTransactionProtocol {
         }
     }
 
-    /// Add the signed transaction from the recipient and move to the next state
+    /// Return the single round sender message of each recipient, in the order of their amounts
+    pub fn get_single_round_messages(&self) -> Result<Vec<SingleRoundSenderData>, TPE> {
+        match &self.state {
+            SenderState::SingleRoundMessageReady(info) | SenderState::CollectingSingleSignature(info) => {
+                if info.recipient_kernels.is_empty() {
+                    return Ok(vec![self.get_single_round_message()?]);
+                }
+                Ok(info
+                    .recipient_kernels
+                    .iter()
+                    .map(|k| SingleRoundSenderData {
+                        tx_id: k.tx_id,
+                        amount: k.amount,
+                        public_nonce: k.public_nonce.clone(),
+                        public_excess: k.public_excess.clone(),
+                        metadata: k.metadata.clone(),
+                        message: info.message.clone(),
+                    })
+                    .collect())
+            },
+            _ => Err(TPE::InvalidStateError),
+        }
+    }
+
+    /// Add the signed transaction from a recipient. Once every recipient's data has been added, move to the next state
     pub fn add_single_recipient_info(
         &mut self,
         rec: RecipientSignedMessage,
@@ -287,13 +450,38 @@ impl SenderTransactionProtocol {
                         "Recipient output range proof failed to verify".into(),
                     ));
                 }
-                // Consolidate transaction info
+                if info.recipient_kernels.is_empty() {
+                    // Consolidate transaction info
+                    info.outputs.push(rec.output);
+                    // nonce is in the signature, so we'll add those together later
+                    info.public_excess = &info.public_excess + &rec.public_spend_key;
+                    info.public_nonce_sum = &info.public_nonce_sum + rec.partial_signature.get_public_nonce();
+                    info.signatures.push(rec.partial_signature);
+                    self.state = SenderState::Finalizing(info.clone());
+                    return Ok(());
+                }
+                let index = info
+                    .recipient_kernels
+                    .iter()
+                    .position(|k| k.tx_id == rec.tx_id)
+                    .ok_or_else(|| TPE::ValidationError(format!("No recipient has TxId {}", rec.tx_id)))?;
+                if info.recipient_kernels[index].recipient_public_spend_key.is_some() {
+                    return Err(TPE::ValidationError(format!(
+                        "The recipient with TxId {} has already replied",
+                        rec.tx_id
+                    )));
+                }
                 info.outputs.push(rec.output);
-                // nonce is in the signature, so we'll add those together later
-                info.public_excess = &info.public_excess + &rec.public_spend_key;
-                info.public_nonce_sum = &info.public_nonce_sum + rec.partial_signature.get_public_nonce();
-                info.signatures.push(rec.partial_signature);
-                self.state = SenderState::Finalizing(info.clone());
+                let kernel = &mut info.recipient_kernels[index];
+                kernel.recipient_public_spend_key = Some(rec.public_spend_key);
+                kernel.signatures.push(rec.partial_signature);
+                if info
+                    .recipient_kernels
+                    .iter()
+                    .all(|k| k.recipient_public_spend_key.is_some())
+                {
+                    self.state = SenderState::Finalizing(info.clone());
+                }
                 Ok(())
             },
             _ => Err(TPE::InvalidStateError),
@@ -316,13 +504,38 @@ impl SenderTransactionProtocol {
             tx_builder.add_output(o.clone());
         }
         tx_builder.add_offset(info.offset.clone());
+        if !info.recipient_kernels.is_empty() {
+            for k in &info.recipient_kernels {
+                let recipient_public_spend_key = k.recipient_public_spend_key.as_ref().ok_or_else(|| {
+                    TPE::IncompleteStateError(format!("The recipient with TxId {} has not replied", k.tx_id))
+                })?;
+                let excess = PedersenCommitment::from_public_key(&(&k.public_excess + recipient_public_spend_key));
+                let mut s_agg = k.signatures[0].clone();
+                k.signatures.iter().skip(1).for_each(|s| s_agg = &s_agg + s);
+                let kernel = KernelBuilder::new()
+                    .with_fee(k.metadata.fee)
+                    .with_features(features)
+                    .with_lock_height(k.metadata.lock_height)
+                    .with_excess(&excess)
+                    .with_signature(&s_agg)
+                    .build()?;
+                tx_builder.add_kernel(kernel);
+            }
+            return tx_builder.build(factories).map_err(TPE::from);
+        }
         let mut s_agg = info.signatures[0].clone();
         info.signatures.iter().skip(1).for_each(|s| s_agg = &s_agg + s);
         let excess = PedersenCommitment::from_public_key(&info.public_excess);
+        let features = if info.metadata.burn_amount > MicroTari(0) {
+            features | KernelFeatures::BURN_KERNEL
+        } else {
+            features
+        };
         let kernel = KernelBuilder::new()
             .with_fee(info.metadata.fee)
             .with_features(features)
             .with_lock_height(info.metadata.lock_height)
+            .with_burn_amount(info.metadata.burn_amount)
             .with_excess(&excess)
             .with_signature(&s_agg)
             .build()?;
@@ -348,12 +561,20 @@ impl SenderTransactionProtocol {
             if info.inputs.is_empty() {
                 return Err(TPE::ValidationError("A transaction cannot have zero inputs".into()));
             }
-            if info.signatures.len() != 1 + info.num_recipients {
+            if info.recipient_kernels.is_empty() && info.signatures.len() != 1 + info.num_recipients {
                 return Err(TPE::ValidationError(format!(
                     "Incorrect number of signatures ({})",
                     info.signatures.len()
                 )));
             }
+            // Each kernel is signed by the sender and its recipient
+            if let Some(k) = info.recipient_kernels.iter().find(|k| k.signatures.len() != 2) {
+                return Err(TPE::ValidationError(format!(
+                    "Incorrect number of signatures ({}) for the recipient with TxId {}",
+                    k.signatures.len(),
+                    k.tx_id
+                )));
+            }
             Ok(())
         } else {
             Err(TPE::InvalidStateError)
@@ -363,6 +584,19 @@ impl SenderTransactionProtocol {
     /// Produce the sender's partial signature
     fn sign(&mut self) -> Result<(), TPE> {
         match &mut self.state {
+            SenderState::Finalizing(info) if !info.recipient_kernels.is_empty() => {
+                for k in info.recipient_kernels.iter_mut() {
+                    let recipient_signature = k.signatures.first().ok_or_else(|| {
+                        TPE::IncompleteStateError(format!("The recipient with TxId {} has not replied", k.tx_id))
+                    })?;
+                    let public_nonce_sum = &k.public_nonce + recipient_signature.get_public_nonce();
+                    let e = build_challenge(&public_nonce_sum, &k.metadata);
+                    let s = Signature::sign(k.excess_blinding_factor.clone(), k.private_nonce.clone(), &e)
+                        .map_err(TPE::SigningError)?;
+                    k.signatures.push(s);
+                }
+                Ok(())
+            },
             SenderState::Finalizing(info) => {
                 let e = build_challenge(&info.public_nonce_sum, &info.metadata);
                 let k = info.offset_blinding_factor.clone();
@@ -473,9 +707,9 @@ pub fn calculate_tx_id<D: Digest>(pub_nonce: &PublicKey, index: usize) -> u64 {
 pub(super) enum SenderState {
     /// Transitional state that kicks of the relevant transaction protocol
     Initializing(Box<RawTransactionInfo>),
-    /// The message for the recipient in a single-round scheme is ready
+    /// The message for each recipient in a single-round scheme is ready
     SingleRoundMessageReady(Box<RawTransactionInfo>),
-    /// Waiting for the signed transaction data in the single-round protocol
+    /// Waiting for the signed transaction data of each recipient in the single-round protocol
     CollectingSingleSignature(Box<RawTransactionInfo>),
     /// The final transaction state is being validated - it will automatically transition to Failed or Finalized from
     /// here
@@ -493,10 +727,7 @@ impl SenderState {
         match self {
             SenderState::Initializing(info) => match info.num_recipients {
                 0 => Ok(SenderState::Finalizing(info)),
-                1 => Ok(SenderState::SingleRoundMessageReady(info)),
-                _ => Ok(SenderState::Failed(TPE::UnsupportedError(
-                    "Multiple recipients are not supported yet".into(),
-                ))),
+                _ => Ok(SenderState::SingleRoundMessageReady(info)),
             },
             _ => Err(TPE::InvalidTransitionError),
         }
@@ -548,7 +779,8 @@ impl fmt::Display for SenderState {
 mod test {
     use crate::transactions::{
         fee::Fee,
-        helpers::{make_input, TestParams},
+        helpers::{create_multisig_witness, create_test_multisig_lock, make_input, TestParams},
+        htlc::{generate_preimage, hash_preimage, HashTimeLock, HtlcWitness},
         tari_amount::*,
         transaction::{KernelFeatures, OutputFeatures, UnblindedOutput},
         transaction_protocol::{
@@ -592,6 +824,146 @@ mod test {
         assert_eq!(tx.offset, p.offset);
     }
 
+    #[test]
+    fn spend_htlc() {
+        let factories = CryptoFactories::default();
+        let p = TestParams::new();
+        let (claim_secret, claim_public_key) = PublicKey::random_keypair(&mut OsRng);
+        let refund_public_key = PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng));
+        let preimage = generate_preimage();
+        let htlc = HashTimeLock::new(hash_preimage(&preimage), claim_public_key, refund_public_key, 100);
+        let utxo = UnblindedOutput::new(
+            MicroTari(5000),
+            PrivateKey::random(&mut OsRng),
+            Some(OutputFeatures::with_hash_time_lock(htlc.clone())),
+        );
+        let input = utxo.as_transaction_input(&factories.commitment, utxo.features.clone());
+        let commitment = input.commitment.clone();
+        let mut builder = SenderTransactionProtocol::builder(0);
+        builder
+            .with_lock_height(0)
+            .with_fee_per_gram(MicroTari(10))
+            .with_offset(p.offset.clone())
+            .with_private_nonce(p.nonce.clone())
+            .with_input(input, utxo)
+            .with_output(UnblindedOutput::new(
+                MicroTari(5000) - Fee::default().calculate(MicroTari(10), 1, 1, 1, 0),
+                p.spend_key.clone(),
+                None,
+            ));
+        let mut sender = builder.build::<Blake256>(&factories).unwrap();
+        assert!(sender.is_finalizing());
+
+        // Without the witness the transaction is rejected
+        let mut unsigned = sender.clone();
+        assert!(unsigned.finalize(KernelFeatures::empty(), &factories).is_err());
+
+        let excess = sender.get_kernel_excess().unwrap();
+        let witness = htlc
+            .sign_claim(
+                claim_secret,
+                PrivateKey::random(&mut OsRng),
+                preimage.to_vec(),
+                &commitment,
+                excess.clone(),
+            )
+            .unwrap();
+        sender.add_htlc_witness(&commitment, witness).unwrap();
+        sender.finalize(KernelFeatures::empty(), &factories).unwrap();
+        let tx = sender.get_transaction().unwrap();
+        assert_eq!(tx.body.kernels()[0].excess, excess);
+        assert!(tx.body.inputs()[0].htlc_witness.is_some());
+    }
+
+    #[test]
+    fn spend_multisig() {
+        let factories = CryptoFactories::default();
+        let p = TestParams::new();
+        let (lock, secrets) = create_test_multisig_lock();
+        let utxo = UnblindedOutput::new(
+            MicroTari(5000),
+            PrivateKey::random(&mut OsRng),
+            Some(OutputFeatures::with_multisig_lock(lock.clone())),
+        );
+        let input = utxo.as_transaction_input(&factories.commitment, utxo.features.clone());
+        let commitment = input.commitment.clone();
+        let mut builder = SenderTransactionProtocol::builder(0);
+        builder
+            .with_lock_height(0)
+            .with_fee_per_gram(MicroTari(10))
+            .with_offset(p.offset.clone())
+            .with_private_nonce(p.nonce.clone())
+            .with_input(input, utxo)
+            .with_output(UnblindedOutput::new(
+                MicroTari(5000) - Fee::default().calculate(MicroTari(10), 1, 1, 1, 0),
+                p.spend_key.clone(),
+                None,
+            ));
+        let mut sender = builder.build::<Blake256>(&factories).unwrap();
+        assert!(sender.is_finalizing());
+
+        // Without the witness the transaction is rejected
+        let mut unsigned = sender.clone();
+        assert!(unsigned.finalize(KernelFeatures::empty(), &factories).is_err());
+
+        let excess = sender.get_kernel_excess().unwrap();
+        let witness = create_multisig_witness(&lock, &secrets, vec![0, 1], &commitment, excess.clone());
+        // The witness of a multisig input cannot be attached as a hash time lock witness, or vice versa
+        assert!(sender
+            .add_htlc_witness(&commitment, HtlcWitness::Refund {
+                kernel_excess: excess.clone(),
+                signature: witness.signature.clone(),
+            })
+            .is_err());
+        sender.add_multisig_witness(&commitment, witness).unwrap();
+        sender.finalize(KernelFeatures::empty(), &factories).unwrap();
+        let tx = sender.get_transaction().unwrap();
+        assert_eq!(tx.body.kernels()[0].excess, excess);
+        assert!(tx.body.inputs()[0].multisig_witness.is_some());
+    }
+
+    #[test]
+    fn burn_funds() {
+        let factories = CryptoFactories::default();
+        let p = TestParams::new();
+        let (utxo, input) = make_input(&mut OsRng, MicroTari(5000), &factories.commitment);
+        let mut builder = SenderTransactionProtocol::builder(0);
+        builder
+            .with_lock_height(0)
+            .with_fee_per_gram(MicroTari(10))
+            .with_offset(p.offset.clone())
+            .with_private_nonce(p.nonce.clone())
+            .with_change_secret(p.change_key.clone())
+            .with_input(utxo, input)
+            .with_burn_amount(MicroTari(1000));
+        let mut sender = builder.build::<Blake256>(&factories).unwrap();
+        assert!(sender.is_finalizing());
+        sender.finalize(KernelFeatures::empty(), &factories).unwrap();
+        let tx = sender.get_transaction().unwrap().clone();
+        let kernel = tx.body.kernels()[0].clone();
+        assert!(kernel.is_burn());
+        assert_eq!(kernel.burn_amount, MicroTari(1000));
+        assert_eq!(tx.body.get_total_burned(), MicroTari(1000));
+        assert_eq!(tx.body.outputs().len(), 1, "Only the change output should be created");
+        assert!(tx.validate_internal_consistency(&factories, None).is_ok());
+
+        // The burned value cannot be redirected to the fee without invalidating the kernel signature
+        let mut tampered = tx.clone();
+        let mut stolen = kernel.clone();
+        stolen.fee = kernel.fee + kernel.burn_amount;
+        stolen.burn_amount = MicroTari(0);
+        stolen.features = KernelFeatures::empty();
+        tampered.body.set_kernel(stolen);
+        assert!(tampered.validate_internal_consistency(&factories, None).is_err());
+
+        // A burn kernel must burn something
+        let mut tampered = tx;
+        let mut empty_burn = kernel;
+        empty_burn.burn_amount = MicroTari(0);
+        tampered.body.set_kernel(empty_burn);
+        assert!(tampered.body.check_burn_kernels().is_err());
+    }
+
     #[test]
     fn single_recipient_no_change() {
         let factories = CryptoFactories::default();
@@ -601,7 +973,7 @@ mod test {
         let b = TestParams::new();
         let (utxo, input) = make_input(&mut OsRng, MicroTari(1200), &factories.commitment);
         let mut builder = SenderTransactionProtocol::builder(1);
-        let fee = Fee::calculate(MicroTari(20), 1, 1, 1);
+        let fee = Fee::default().calculate(MicroTari(20), 1, 1, 1, 0);
         builder
             .with_lock_height(0)
             .with_fee_per_gram(MicroTari(20))
@@ -659,7 +1031,7 @@ mod test {
         let b = TestParams::new();
         let (utxo, input) = make_input(&mut OsRng, MicroTari(25000), &factories.commitment);
         let mut builder = SenderTransactionProtocol::builder(1);
-        let fee = Fee::calculate(MicroTari(20), 1, 1, 2);
+        let fee = Fee::default().calculate(MicroTari(20), 1, 1, 2, 0);
         builder
             .with_lock_height(0)
             .with_fee_per_gram(MicroTari(20))
@@ -724,6 +1096,125 @@ mod test {
         assert!(tx.clone().validate_internal_consistency(&factories, None).is_ok());
     }
 
+    #[test]
+    fn multi_recipients() {
+        let factories = CryptoFactories::default();
+        // Alice's parameters
+        let a = TestParams::new();
+        // Bob's and Carol's parameters
+        let b = TestParams::new();
+        let c = TestParams::new();
+        let (utxo, input) = make_input(&mut OsRng, MicroTari(25000), &factories.commitment);
+        let mut builder = SenderTransactionProtocol::builder(2);
+        let fee = Fee::default().calculate(MicroTari(20), 2, 1, 3, 0);
+        builder
+            .with_lock_height(0)
+            .with_fee_per_gram(MicroTari(20))
+            .with_offset(a.offset.clone())
+            .with_private_nonce(a.nonce.clone())
+            .with_change_secret(a.change_key.clone())
+            .with_input(utxo, input)
+            .with_amount(0, MicroTari(5000))
+            .with_amount(1, MicroTari(3000));
+        let mut alice = builder.build::<Blake256>(&factories).unwrap();
+        assert!(alice.is_single_round_message_ready());
+        assert!(alice.get_single_round_message().is_err());
+        let msgs = alice.build_single_round_messages().unwrap();
+        assert_eq!(msgs.len(), 2);
+        assert_eq!(msgs[0].amount, MicroTari(5000));
+        assert_eq!(msgs[1].amount, MicroTari(3000));
+        assert_eq!(msgs[0].metadata.fee + msgs[1].metadata.fee, fee);
+        assert_eq!(
+            alice.get_tx_ids().unwrap(),
+            msgs.iter().map(|m| m.tx_id).collect::<Vec<_>>()
+        );
+        assert!(alice.is_collecting_single_signature());
+
+        // Test serializing the current state to be sent and resuming from that serialized data
+        let ser = alice.save_pending_transaction_to_be_sent().unwrap();
+        let mut alice = SenderTransactionProtocol::load_pending_transaction_to_be_sent(ser).unwrap();
+
+        // Each recipient gets its own message and replies as if it were the only recipient
+        let bob_info = SingleReceiverTransactionProtocol::create(
+            &msgs[0],
+            b.nonce,
+            b.spend_key,
+            OutputFeatures::default(),
+            &factories,
+            None,
+        )
+        .unwrap();
+        let carol_info = SingleReceiverTransactionProtocol::create(
+            &msgs[1],
+            c.nonce,
+            c.spend_key,
+            OutputFeatures::default(),
+            &factories,
+            None,
+        )
+        .unwrap();
+        alice
+            .add_single_recipient_info(carol_info.clone(), &factories.range_proof)
+            .unwrap();
+        // Still waiting for Bob, and Carol can't reply twice
+        assert!(alice.is_collecting_single_signature());
+        assert_eq!(alice.get_pending_recipient_tx_ids().unwrap(), vec![msgs[0].tx_id]);
+        assert!(alice
+            .add_single_recipient_info(carol_info, &factories.range_proof)
+            .is_err());
+        alice
+            .add_single_recipient_info(bob_info, &factories.range_proof)
+            .unwrap();
+        assert!(alice.is_finalizing());
+        alice.finalize(KernelFeatures::empty(), &factories).unwrap();
+
+        let tx = alice.get_transaction().unwrap();
+        assert_eq!(tx.offset, a.offset);
+        assert_eq!(tx.body.kernels().len(), 2);
+        assert_eq!(tx.body.get_total_fee(), fee);
+        assert_eq!(tx.body.inputs().len(), 1);
+        assert_eq!(tx.body.outputs().len(), 3);
+        assert!(tx.clone().validate_internal_consistency(&factories, None).is_ok());
+    }
+
+    #[test]
+    fn multi_recipients_reject_unknown_reply() {
+        let factories = CryptoFactories::default();
+        let a = TestParams::new();
+        let b = TestParams::new();
+        let (utxo, input) = make_input(&mut OsRng, MicroTari(25000), &factories.commitment);
+        let mut builder = SenderTransactionProtocol::builder(2);
+        builder
+            .with_lock_height(0)
+            .with_fee_per_gram(MicroTari(20))
+            .with_offset(a.offset.clone())
+            .with_private_nonce(a.nonce.clone())
+            .with_change_secret(a.change_key.clone())
+            .with_input(utxo, input)
+            .with_amount(0, MicroTari(5000))
+            .with_amount(1, MicroTari(3000));
+        let mut alice = builder.build::<Blake256>(&factories).unwrap();
+        let mut msg = alice.build_single_round_messages().unwrap().remove(0);
+        msg.tx_id = msg.tx_id.wrapping_add(1);
+        let bob_info = SingleReceiverTransactionProtocol::create(
+            &msg,
+            b.nonce,
+            b.spend_key,
+            OutputFeatures::default(),
+            &factories,
+            None,
+        )
+        .unwrap();
+        match alice.add_single_recipient_info(bob_info, &factories.range_proof) {
+            Ok(_) => panic!("A reply with an unknown TxId should be rejected"),
+            Err(e) => assert_eq!(
+                e,
+                TransactionProtocolError::ValidationError(format!("No recipient has TxId {}", msg.tx_id))
+            ),
+        }
+        assert_eq!(alice.get_pending_recipient_tx_ids().unwrap().len(), 2);
+    }
+
     #[test]
     fn single_recipient_range_proof_fail() {
         let factories = CryptoFactories::new(32);
@@ -169,6 +169,7 @@ mod test {
         let m = TransactionMetadata {
             fee: MicroTari(100),
             lock_height: 0,
+            burn_amount: MicroTari(0),
         };
         let info = SingleRoundSenderData {
             tx_id: 500,
@@ -21,9 +21,11 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::transactions::{
+    covenant::Covenant,
     fee::Fee,
     tari_amount::*,
     transaction::{
+        OutputFeatures,
         TransactionInput,
         TransactionOutput,
         UnblindedOutput,
@@ -33,20 +35,25 @@ use crate::transactions::{
     },
     transaction_protocol::{
         recipient::RecipientInfo,
-        sender::{calculate_tx_id, RawTransactionInfo, SenderState, SenderTransactionProtocol},
+        sender::{calculate_tx_id, RawTransactionInfo, RecipientKernelInfo, SenderState, SenderTransactionProtocol},
         RewindData,
         TransactionMetadata,
     },
     types::{BlindingFactor, CryptoFactories, PrivateKey, PublicKey},
+    weight::TransactionWeight,
 };
 use digest::Digest;
 use log::*;
+use rand::rngs::OsRng;
 use std::{
     cmp::max,
     collections::HashMap,
     fmt::{Debug, Error, Formatter},
 };
-use tari_crypto::{keys::PublicKey as PublicKeyTrait, tari_utilities::fixed_set::FixedSet};
+use tari_crypto::{
+    keys::{PublicKey as PublicKeyTrait, SecretKey as SecretKeyTrait},
+    tari_utilities::fixed_set::FixedSet,
+};
 
 pub const LOG_TARGET: &str = "c::tx::tx_protocol::tx_initializer";
 
@@ -64,10 +71,15 @@ pub struct SenderTransactionInitializer {
     amounts: FixedSet<MicroTari>,
     lock_height: Option<u64>,
     fee_per_gram: Option<MicroTari>,
+    fee: Fee,
+    witness_weight: u64,
+    burn_amount: MicroTari,
     inputs: Vec<TransactionInput>,
     unblinded_inputs: Vec<UnblindedOutput>,
     outputs: Vec<UnblindedOutput>,
+    output_rewind_data: Vec<Option<RewindData>>,
     change_secret: Option<BlindingFactor>,
+    change_covenant: Covenant,
     rewind_data: Option<RewindData>,
     offset: Option<BlindingFactor>,
     excess_blinding_factor: BlindingFactor,
@@ -94,10 +106,15 @@ impl SenderTransactionInitializer {
             amounts: FixedSet::new(num_recipients),
             lock_height: None,
             fee_per_gram: None,
+            fee: Fee::default(),
+            witness_weight: 0,
+            burn_amount: MicroTari(0),
             inputs: Vec::new(),
             unblinded_inputs: Vec::new(),
             outputs: Vec::new(),
+            output_rewind_data: Vec::new(),
             change_secret: None,
+            change_covenant: Covenant::default(),
             rewind_data: None,
             offset: None,
             private_nonce: None,
@@ -114,12 +131,32 @@ impl SenderTransactionInitializer {
         self
     }
 
+    /// Set the weight formula that the fee is calculated with. This should be the formula of the consensus constants
+    /// that the transaction will be validated with. Default: the original formula, `TransactionWeight::v1`
+    pub fn with_transaction_weight(&mut self, weighting: TransactionWeight) -> &mut Self {
+        self.fee = Fee::new(weighting);
+        self
+    }
+
+    /// Set the weight of the witnesses that are attached to the inputs after the transaction is built, such as the
+    /// witness of a hash time-locked input, so that the fee pays for them. Default: 0
+    pub fn with_witness_weight(&mut self, witness_weight: u64) -> &mut Self {
+        self.witness_weight = witness_weight;
+        self
+    }
+
     /// Set the amount to pay to the ith recipient. This method will silently fail if `receiver_index` >= num_receivers.
     pub fn with_amount(&mut self, receiver_index: usize, amount: MicroTari) -> &mut Self {
         self.amounts.set_item(receiver_index, amount);
         self
     }
 
+    /// Set the amount to be permanently destroyed by this transaction. A non-zero amount results in a burn kernel.
+    pub fn with_burn_amount(&mut self, burn_amount: MicroTari) -> &mut Self {
+        self.burn_amount = burn_amount;
+        self
+    }
+
     /// Sets the minimum block height that this transaction will be mined.
     pub fn with_lock_height(&mut self, lock_height: u64) -> &mut Self {
         self.lock_height = Some(lock_height);
@@ -145,6 +182,16 @@ impl SenderTransactionInitializer {
     pub fn with_output(&mut self, output: UnblindedOutput) -> &mut Self {
         self.excess_blinding_factor = &self.excess_blinding_factor + &output.spending_key;
         self.outputs.push(output);
+        self.output_rewind_data.push(None);
+        self
+    }
+
+    /// Adds an output with a range proof that can be rewound with the given rewind keys, to recover its value and
+    /// proof message
+    pub fn with_rewindable_output(&mut self, output: UnblindedOutput, rewind_data: RewindData) -> &mut Self {
+        self.excess_blinding_factor = &self.excess_blinding_factor + &output.spending_key;
+        self.outputs.push(output);
+        self.output_rewind_data.push(Some(rewind_data));
         self
     }
 
@@ -168,6 +215,13 @@ impl SenderTransactionInitializer {
         self
     }
 
+    /// Provide a covenant for the change output. This is required when spending an input whose covenant must be
+    /// preserved, e.g. funds held in a vault or side-chain peg.
+    pub fn with_change_covenant(&mut self, covenant: Covenant) -> &mut Self {
+        self.change_covenant = covenant;
+        self
+    }
+
     /// Provide the private nonce that will be used for the sender's partial signature for the transaction.
     pub fn with_private_nonce(&mut self, nonce: PrivateKey) -> &mut Self {
         self.private_nonce = Some(nonce);
@@ -190,6 +244,8 @@ impl SenderTransactionInitializer {
     /// fee, including the additional change output (if any) is returned along with the amount of change.
     /// The change output **always has default output features**.
     fn add_change_if_required(&mut self) -> Result<(MicroTari, MicroTari, Option<UnblindedOutput>), String> {
+        // Each recipient has a kernel of its own
+        let num_kernels = max(1, self.num_recipients);
         // The number of outputs excluding a possible residual change output
         let num_outputs = self.outputs.len() + self.num_recipients;
         let num_inputs = self.inputs.len();
@@ -197,11 +253,34 @@ impl SenderTransactionInitializer {
         let total_to_self = self.outputs.iter().map(|o| o.value).sum::<MicroTari>();
         let total_amount = self.amounts.sum().ok_or_else(|| "Not all amounts have been provided")?;
         let fee_per_gram = self.fee_per_gram.ok_or_else(|| "Fee per gram was not provided")?;
-        let fee_without_change = Fee::calculate(fee_per_gram, 1, num_inputs, num_outputs);
-        let fee_with_change = Fee::calculate(fee_per_gram, 1, num_inputs, num_outputs + 1);
+        // The recipients create their outputs with default features and covenants
+        let metadata_without_change = self
+            .outputs
+            .iter()
+            .map(|o| Fee::output_metadata_size(&o.features, &o.covenant))
+            .sum::<usize>() +
+            Fee::default_output_metadata_size(self.num_recipients);
+        let metadata_with_change =
+            metadata_without_change + Fee::output_metadata_size(&OutputFeatures::default(), &self.change_covenant);
+        let witness_fee = self.witness_weight * fee_per_gram;
+        let fee_without_change = self.fee.calculate(
+            fee_per_gram,
+            num_kernels,
+            num_inputs,
+            num_outputs,
+            metadata_without_change,
+        ) + witness_fee;
+        let fee_with_change = self.fee.calculate(
+            fee_per_gram,
+            num_kernels,
+            num_inputs,
+            num_outputs + 1,
+            metadata_with_change,
+        ) + witness_fee;
         let extra_fee = fee_with_change - fee_without_change;
         // Subtract with a check on going negative
-        let change_amount = total_being_spent.checked_sub(total_to_self + total_amount + fee_without_change);
+        let change_amount =
+            total_being_spent.checked_sub(total_to_self + total_amount + self.burn_amount + fee_without_change);
         match change_amount {
             None => Err("You are spending more than you're providing".into()),
             Some(MicroTari(0)) => Ok((fee_without_change, MicroTari(0), None)),
@@ -217,7 +296,8 @@ impl SenderTransactionInitializer {
                             .change_secret
                             .as_ref()
                             .ok_or_else(|| "Change spending key was not provided")?;
-                        let change_unblinded_output = UnblindedOutput::new(v, change_key.clone(), None);
+                        let change_unblinded_output = UnblindedOutput::new(v, change_key.clone(), None)
+                            .with_covenant(self.change_covenant.clone());
                         Ok((fee_with_change, v, Some(change_unblinded_output)))
                     },
                 }
@@ -268,6 +348,10 @@ impl SenderTransactionInitializer {
         if self.inputs.len() > MAX_TRANSACTION_INPUTS {
             return self.build_err("Too many inputs in transaction");
         }
+        // A burn kernel can not also pay a recipient
+        if self.num_recipients > 1 && self.burn_amount > MicroTari(0) {
+            return self.build_err("Funds can not be burned in a transaction with several recipients");
+        }
         // Calculate the fee based on whether we need to add a residual change output or not
         let (total_fee, change, change_output) = match self.add_change_if_required() {
             Ok((fee, change, output)) => (fee, change, output),
@@ -285,7 +369,11 @@ impl SenderTransactionInitializer {
         let mut outputs = match self
             .outputs
             .iter()
-            .map(|o| o.as_transaction_output(factories))
+            .zip(self.output_rewind_data.iter())
+            .map(|(o, rewind_data)| match rewind_data {
+                Some(rewind_data) => o.as_rewindable_transaction_output(factories, rewind_data),
+                None => o.as_transaction_output(factories),
+            })
             .collect::<Result<Vec<TransactionOutput>, _>>()
         {
             Ok(o) => o,
@@ -314,6 +402,7 @@ impl SenderTransactionInitializer {
                 }
             };
             self.outputs.push(change_unblinded_output);
+            self.output_rewind_data.push(self.rewind_data.clone());
             outputs.push(change_output);
         }
 
@@ -358,6 +447,49 @@ impl SenderTransactionInitializer {
             }
         }
 
+        // With several recipients, each recipient's kernel is signed with a part of the sender's excess and a nonce of
+        // its own, and pays a share of the fee. The first kernel makes up the remainders.
+        let recipient_kernels = if self.num_recipients > 1 {
+            let num_kernels = self.num_recipients as u64;
+            let excess_parts = (1..self.num_recipients)
+                .map(|_| PrivateKey::random(&mut OsRng))
+                .collect::<Vec<_>>();
+            let first_excess = excess_parts
+                .iter()
+                .fold(offset_blinding_factor.clone(), |excess, part| &excess - part);
+            let fee_share = MicroTari(total_fee.0 / num_kernels);
+            let first_fee_share = MicroTari(fee_share.0 + total_fee.0 % num_kernels);
+            let lock_height = self.lock_height.unwrap();
+            ids.iter()
+                .zip(self.amounts.clone().into_vec())
+                .enumerate()
+                .map(|(i, (tx_id, amount))| {
+                    let (excess_blinding_factor, fee, private_nonce) = if i == 0 {
+                        (first_excess.clone(), first_fee_share, nonce.clone())
+                    } else {
+                        (excess_parts[i - 1].clone(), fee_share, PrivateKey::random(&mut OsRng))
+                    };
+                    RecipientKernelInfo {
+                        tx_id: *tx_id,
+                        amount,
+                        metadata: TransactionMetadata {
+                            fee,
+                            lock_height,
+                            burn_amount: MicroTari(0),
+                        },
+                        public_excess: PublicKey::from_secret_key(&excess_blinding_factor),
+                        excess_blinding_factor,
+                        public_nonce: PublicKey::from_secret_key(&private_nonce),
+                        private_nonce,
+                        recipient_public_spend_key: None,
+                        signatures: Vec::new(),
+                    }
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
         // Everything is here. Let's send some Tari!
         let sender_info = RawTransactionInfo {
             num_recipients: self.num_recipients,
@@ -368,6 +500,7 @@ impl SenderTransactionInitializer {
             metadata: TransactionMetadata {
                 fee: total_fee,
                 lock_height: self.lock_height.unwrap(),
+                burn_amount: self.burn_amount,
             },
             inputs: self.inputs,
             outputs,
@@ -380,6 +513,7 @@ impl SenderTransactionInitializer {
             recipient_info,
             signatures: Vec::new(),
             message: self.message.unwrap_or_else(|| "".to_string()),
+            recipient_kernels,
         };
 
         let state = SenderState::Initializing(Box::new(sender_info));
@@ -401,12 +535,9 @@ mod test {
             helpers::{make_input, TestParams},
             tari_amount::*,
             transaction::{UnblindedOutput, MAX_TRANSACTION_INPUTS},
-            transaction_protocol::{
-                sender::SenderState,
-                transaction_initializer::SenderTransactionInitializer,
-                TransactionProtocolError,
-            },
+            transaction_protocol::{sender::SenderState, transaction_initializer::SenderTransactionInitializer},
             types::CryptoFactories,
+            weight::TransactionWeight,
         },
     };
     use rand::rngs::OsRng;
@@ -435,7 +566,7 @@ mod test {
         let (utxo, input) = make_input(&mut OsRng, MicroTari(5_000), &factories.commitment);
         builder.with_input(utxo, input);
         builder.with_fee_per_gram(MicroTari(20));
-        let expected_fee = Fee::calculate(MicroTari(20), 1, 1, 2);
+        let expected_fee = Fee::default().calculate(MicroTari(20), 1, 1, 2, 0);
         // We needed a change input, so this should fail
         let err = builder.build::<Blake256>(&factories).unwrap_err();
         assert_eq!(err.message, "Change spending key was not provided");
@@ -465,7 +596,7 @@ mod test {
         let factories = CryptoFactories::default();
         let p = TestParams::new();
         let (utxo, input) = make_input(&mut OsRng, MicroTari(500), &factories.commitment);
-        let expected_fee = Fee::calculate(MicroTari(20), 1, 1, 1);
+        let expected_fee = Fee::default().calculate(MicroTari(20), 1, 1, 1, 0);
         let output = UnblindedOutput::new(MicroTari(500) - expected_fee, p.spend_key, None);
         // Start the builder
         let mut builder = SenderTransactionInitializer::new(0);
@@ -493,6 +624,61 @@ mod test {
         }
     }
 
+    /// The fee is weighed with the given formula, including the metadata of the outputs
+    #[test]
+    fn fee_uses_the_transaction_weight() {
+        let factories = CryptoFactories::default();
+        let p = TestParams::new();
+        let (utxo, input) = make_input(&mut OsRng, MicroTari(5_000), &factories.commitment);
+        let output = UnblindedOutput::new(MicroTari(500), p.spend_key, None);
+        let metadata_size = Fee::default_output_metadata_size(2);
+        let weighting = TransactionWeight::new(2, 1, 1, 1, 1);
+        let expected_fee = Fee::new(weighting).calculate(MicroTari(20), 1, 1, 2, metadata_size);
+        assert_eq!(expected_fee, MicroTari(20 * (4 + metadata_size as u64)));
+        let mut builder = SenderTransactionInitializer::new(0);
+        builder
+            .with_lock_height(0)
+            .with_offset(p.offset)
+            .with_private_nonce(p.nonce)
+            .with_output(output)
+            .with_input(utxo, input)
+            .with_change_secret(p.change_key)
+            .with_fee_per_gram(MicroTari(20))
+            .with_transaction_weight(weighting);
+        let result = builder.build::<Blake256>(&factories).unwrap();
+        if let SenderState::Finalizing(info) = result.state {
+            assert_eq!(info.metadata.fee, expected_fee, "Fee");
+            assert_eq!(info.outputs.len(), 2, "There should be 2 outputs");
+        } else {
+            panic!("There were no recipients, so we should be finalizing");
+        }
+    }
+
+    #[test]
+    fn fee_pays_for_the_witness_weight() {
+        let factories = CryptoFactories::default();
+        let p = TestParams::new();
+        let (utxo, input) = make_input(&mut OsRng, MicroTari(5_000), &factories.commitment);
+        let output = UnblindedOutput::new(MicroTari(500), p.spend_key, None);
+        let expected_fee = Fee::default().calculate(MicroTari(20), 1, 1, 2, 0) + MicroTari(20 * 5);
+        let mut builder = SenderTransactionInitializer::new(0);
+        builder
+            .with_lock_height(0)
+            .with_offset(p.offset)
+            .with_private_nonce(p.nonce)
+            .with_output(output)
+            .with_input(utxo, input)
+            .with_change_secret(p.change_key)
+            .with_fee_per_gram(MicroTari(20))
+            .with_witness_weight(5);
+        let result = builder.build::<Blake256>(&factories).unwrap();
+        if let SenderState::Finalizing(info) = result.state {
+            assert_eq!(info.metadata.fee, expected_fee, "Fee");
+        } else {
+            panic!("There were no recipients, so we should be finalizing");
+        }
+    }
+
     /// Hit the edge case where our change isn't enough to cover the cost of an extra output
     #[test]
     #[allow(clippy::identity_op)]
@@ -616,13 +802,50 @@ mod test {
             .with_fee_per_gram(MicroTari(20));
         let result = builder.build::<Blake256>(&factories).unwrap();
         // Peek inside and check the results
-        if let SenderState::Failed(TransactionProtocolError::UnsupportedError(s)) = result.state {
-            assert_eq!(s, "Multiple recipients are not supported yet")
+        if let SenderState::SingleRoundMessageReady(info) = result.state {
+            // Each recipient has a kernel, the change output is counted with the outputs
+            let expected_fee = Fee::default().calculate(MicroTari(20), 2, 1, 4, 0);
+            assert_eq!(info.num_recipients, 2, "Number of receivers");
+            assert_eq!(info.metadata.fee, expected_fee, "Fee");
+            assert_eq!(info.recipient_kernels.len(), 2, "Kernels");
+            let kernel_fees = info.recipient_kernels.iter().map(|k| k.metadata.fee).sum::<MicroTari>();
+            assert_eq!(kernel_fees, expected_fee, "Kernel fees");
+            let tx_ids = info.recipient_kernels.iter().map(|k| k.tx_id).collect::<Vec<_>>();
+            assert_eq!(tx_ids, info.ids, "TxIds");
+            assert_eq!(info.recipient_kernels[0].amount, MicroTari(1200));
+            assert_eq!(info.recipient_kernels[1].amount, MicroTari(1100));
+            assert_ne!(
+                info.recipient_kernels[0].public_nonce, info.recipient_kernels[1].public_nonce,
+                "Each kernel has its own nonce"
+            );
         } else {
-            panic!("We should not allow multiple recipients at this time");
+            panic!("There should be a single round message for each recipient");
         }
     }
 
+    #[test]
+    fn multi_recipients_cannot_burn() {
+        let factories = CryptoFactories::default();
+        let p = TestParams::new();
+        let (utxo, input) = make_input(&mut OsRng, MicroTari(100_000), &factories.commitment);
+        let mut builder = SenderTransactionInitializer::new(2);
+        builder
+            .with_lock_height(0)
+            .with_offset(p.offset)
+            .with_amount(0, MicroTari(1200))
+            .with_amount(1, MicroTari(1100))
+            .with_burn_amount(MicroTari(1000))
+            .with_private_nonce(p.nonce)
+            .with_input(utxo, input)
+            .with_change_secret(p.change_key)
+            .with_fee_per_gram(MicroTari(20));
+        let err = builder.build::<Blake256>(&factories).unwrap_err();
+        assert_eq!(
+            err.message,
+            "Funds can not be burned in a transaction with several recipients"
+        );
+    }
+
     #[test]
     fn single_recipient() {
         // Create some inputs
@@ -631,7 +854,7 @@ mod test {
         let (utxo1, input1) = make_input(&mut OsRng, MicroTari(2000), &factories.commitment);
         let (utxo2, input2) = make_input(&mut OsRng, MicroTari(3000), &factories.commitment);
         let weight = MicroTari(30);
-        let expected_fee = Fee::calculate(weight, 1, 2, 3);
+        let expected_fee = Fee::default().calculate(weight, 1, 2, 3, 0);
         let output = UnblindedOutput::new(MicroTari(1500) - expected_fee, p.spend_key, None);
         // Start the builder
         let mut builder = SenderTransactionInitializer::new(1);
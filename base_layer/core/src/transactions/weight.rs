@@ -0,0 +1,344 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::{
+    consensus::{KERNEL_WEIGHT, WEIGHT_PER_INPUT, WEIGHT_PER_OUTPUT},
+    transactions::{
+        aggregated_body::AggregateBody,
+        htlc::HTLC_WITNESS_WEIGHT,
+        script::TariScript,
+        tari_amount::MicroTari,
+        transaction::TransactionInput,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Error, Formatter};
+
+/// The weight of each signer of a multisig witness, from version 2 of the formula
+pub const MULTISIG_SIGNER_WEIGHT: u64 = 1;
+/// The weight of each item on the stack of a script witness, from version 2 of the formula
+pub const SCRIPT_STACK_ITEM_WEIGHT: u64 = 1;
+
+/// A versioned formula for the weight of a transaction, from which its fee is calculated. The consensus constants of
+/// each network set the formula that is active from their effective height, so the fee policy can change at an
+/// activation height without changing how older blocks are weighed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionWeight {
+    version: u16,
+    kernel_weight: u64,
+    input_weight: u64,
+    output_weight: u64,
+    /// The number of bytes of output metadata (output features and covenants) that weigh one gram. Zero if output
+    /// metadata is not weighed.
+    metadata_bytes_per_gram: u64,
+    /// The weight of each signer of the multisig witness of an input. Zero if multisig witnesses are not weighed.
+    #[serde(default)]
+    multisig_signer_weight: u64,
+    /// The weight of each stack item of the script witness of an input. Zero if script witnesses are not weighed.
+    #[serde(default)]
+    script_stack_item_weight: u64,
+}
+
+impl TransactionWeight {
+    /// The original formula, which weighs kernels, inputs and outputs but not their metadata
+    pub const fn v1() -> Self {
+        Self {
+            version: 1,
+            kernel_weight: KERNEL_WEIGHT,
+            input_weight: WEIGHT_PER_INPUT,
+            output_weight: WEIGHT_PER_OUTPUT,
+            metadata_bytes_per_gram: 0,
+            multisig_signer_weight: 0,
+            script_stack_item_weight: 0,
+        }
+    }
+
+    /// The original formula, which also weighs multisig witnesses per signer and script witnesses per stack item
+    pub const fn v2() -> Self {
+        Self {
+            version: 2,
+            kernel_weight: KERNEL_WEIGHT,
+            input_weight: WEIGHT_PER_INPUT,
+            output_weight: WEIGHT_PER_OUTPUT,
+            metadata_bytes_per_gram: 0,
+            multisig_signer_weight: MULTISIG_SIGNER_WEIGHT,
+            script_stack_item_weight: SCRIPT_STACK_ITEM_WEIGHT,
+        }
+    }
+
+    /// A formula with the given parameters, for use by later versions of the consensus constants
+    pub const fn new(
+        version: u16,
+        kernel_weight: u64,
+        input_weight: u64,
+        output_weight: u64,
+        metadata_bytes_per_gram: u64,
+    ) -> Self
+    {
+        Self {
+            version,
+            kernel_weight,
+            input_weight,
+            output_weight,
+            metadata_bytes_per_gram,
+            multisig_signer_weight: 0,
+            script_stack_item_weight: 0,
+        }
+    }
+
+    /// This formula, weighing multisig and script witnesses with the given weights per signer and per stack item
+    pub const fn with_witness_weights(self, multisig_signer_weight: u64, script_stack_item_weight: u64) -> Self {
+        Self {
+            multisig_signer_weight,
+            script_stack_item_weight,
+            ..self
+        }
+    }
+
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+
+    pub fn kernel_weight(&self) -> u64 {
+        self.kernel_weight
+    }
+
+    pub fn input_weight(&self) -> u64 {
+        self.input_weight
+    }
+
+    pub fn output_weight(&self) -> u64 {
+        self.output_weight
+    }
+
+    pub fn metadata_bytes_per_gram(&self) -> u64 {
+        self.metadata_bytes_per_gram
+    }
+
+    pub fn multisig_signer_weight(&self) -> u64 {
+        self.multisig_signer_weight
+    }
+
+    pub fn script_stack_item_weight(&self) -> u64 {
+        self.script_stack_item_weight
+    }
+
+    /// The weight of a multisig witness with the given number of signers
+    pub fn multisig_witness_weight(&self, num_signers: usize) -> u64 {
+        self.multisig_signer_weight * num_signers as u64
+    }
+
+    /// The weight of a script witness with the given number of stack items
+    pub fn script_witness_weight(&self, num_stack_items: usize) -> u64 {
+        self.script_stack_item_weight * num_stack_items as u64
+    }
+
+    /// The weight of the witnesses attached to the input
+    pub fn input_witness_weight(&self, input: &TransactionInput) -> u64 {
+        let htlc_weight = if input.htlc_witness.is_some() {
+            HTLC_WITNESS_WEIGHT
+        } else {
+            0
+        };
+        let multisig_weight = input
+            .multisig_witness
+            .as_ref()
+            .map(|w| self.multisig_witness_weight(w.signers.len()))
+            .unwrap_or(0);
+        let script_weight = input
+            .script_witness
+            .as_ref()
+            .map(|w| self.script_witness_weight(w.stack.len()))
+            .unwrap_or(0);
+        htlc_weight + multisig_weight + script_weight
+    }
+
+    /// Calculate the weight of a transaction with the given number of kernels, inputs and outputs, and the given total
+    /// size of output metadata in bytes
+    pub fn calculate(&self, num_kernels: usize, num_inputs: usize, num_outputs: usize, metadata_bytes: usize) -> u64 {
+        let metadata_weight = if self.metadata_bytes_per_gram == 0 {
+            0
+        } else {
+            (metadata_bytes as u64 + self.metadata_bytes_per_gram - 1) / self.metadata_bytes_per_gram
+        };
+        self.kernel_weight * num_kernels as u64 +
+            self.input_weight * num_inputs as u64 +
+            self.output_weight * num_outputs as u64 +
+            metadata_weight
+    }
+
+    /// Calculate the weight of the given body. The scripts of script locked outputs and the witnesses of hash
+    /// time-locked inputs add their own weight in every version of the formula, multisig and script witnesses from
+    /// version 2.
+    pub fn calculate_body(&self, body: &AggregateBody) -> u64 {
+        let metadata_bytes = if self.metadata_bytes_per_gram == 0 {
+            0
+        } else {
+            body.calculate_output_metadata_size()
+        };
+        let script_weight: u64 = body
+            .outputs()
+            .iter()
+            .filter_map(|o| o.features.script.as_ref())
+            .map(TariScript::weight)
+            .sum();
+        let witness_weight: u64 = body.inputs().iter().map(|i| self.input_witness_weight(i)).sum();
+        self.calculate(
+            body.kernels().len(),
+            body.inputs().len(),
+            body.outputs().len(),
+            metadata_bytes,
+        ) + script_weight +
+            witness_weight
+    }
+
+    /// Calculate the fee of a transaction with the given fee per gram
+    pub fn calculate_fee(
+        &self,
+        fee_per_gram: MicroTari,
+        num_kernels: usize,
+        num_inputs: usize,
+        num_outputs: usize,
+        metadata_bytes: usize,
+    ) -> MicroTari
+    {
+        (self.calculate(num_kernels, num_inputs, num_outputs, metadata_bytes) * u64::from(fee_per_gram)).into()
+    }
+}
+
+impl Default for TransactionWeight {
+    fn default() -> Self {
+        Self::v1()
+    }
+}
+
+impl Display for TransactionWeight {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(
+            f,
+            "V{} (kernel: {}, input: {}, output: {}, metadata bytes per gram: {}, multisig signer: {}, script stack \
+             item: {})",
+            self.version,
+            self.kernel_weight,
+            self.input_weight,
+            self.output_weight,
+            self.metadata_bytes_per_gram,
+            self.multisig_signer_weight,
+            self.script_stack_item_weight
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::transactions::{
+        htlc::HashTimeLock,
+        multisig::MultisigWitness,
+        script::{Opcode, ScriptWitness, StackItem},
+        transaction::{OutputFeatures, TransactionOutput},
+        types::{Commitment, PrivateKey, PublicKey, RangeProof, Signature},
+    };
+    use rand::rngs::OsRng;
+    use tari_crypto::keys::{PublicKey as PublicKeyTrait, SecretKey};
+
+    #[test]
+    fn v1_matches_the_original_formula() {
+        let weight = TransactionWeight::v1();
+        assert_eq!(
+            weight.calculate(2, 3, 4, 500),
+            2 * KERNEL_WEIGHT + 3 * WEIGHT_PER_INPUT + 4 * WEIGHT_PER_OUTPUT
+        );
+        assert_eq!(
+            weight.calculate_fee(MicroTari(25), 1, 1, 2, 0),
+            MicroTari(25 * (KERNEL_WEIGHT + WEIGHT_PER_INPUT + 2 * WEIGHT_PER_OUTPUT))
+        );
+    }
+
+    #[test]
+    fn metadata_is_weighed_per_started_gram() {
+        let weight = TransactionWeight::new(2, 3, 1, 13, 16);
+        assert_eq!(weight.calculate(1, 1, 1, 0), 17);
+        assert_eq!(weight.calculate(1, 1, 1, 1), 18);
+        assert_eq!(weight.calculate(1, 1, 1, 16), 18);
+        assert_eq!(weight.calculate(1, 1, 1, 17), 19);
+    }
+
+    #[test]
+    fn scripts_are_weighed() {
+        let keys = vec![PublicKey::default(); 3];
+        let script = TariScript::new(vec![Opcode::CheckHeightVerify(10), Opcode::CheckMultiSig(2, keys)]);
+        assert_eq!(script.weight(), 1 + (1 + 3));
+        let output = TransactionOutput::new(
+            OutputFeatures::with_script(script),
+            Commitment::default(),
+            RangeProof::default(),
+        );
+        let body = AggregateBody::new(vec![], vec![output], vec![]);
+        let weight = TransactionWeight::v1();
+        assert_eq!(weight.calculate_body(&body), WEIGHT_PER_OUTPUT + 5);
+    }
+
+    #[test]
+    fn htlc_witnesses_are_weighed() {
+        let (k, public_key) = PublicKey::random_keypair(&mut OsRng);
+        let htlc = HashTimeLock::new([0u8; 32], public_key.clone(), public_key, 10);
+        let witness = htlc
+            .sign_refund(
+                k,
+                PrivateKey::random(&mut OsRng),
+                &Commitment::default(),
+                Commitment::default(),
+            )
+            .unwrap();
+        let input = TransactionInput::new(OutputFeatures::default(), Commitment::default()).with_htlc_witness(witness);
+        let body = AggregateBody::new(vec![input], vec![], vec![]);
+        let weight = TransactionWeight::v1();
+        assert_eq!(weight.calculate_body(&body), WEIGHT_PER_INPUT + HTLC_WITNESS_WEIGHT);
+    }
+
+    #[test]
+    fn multisig_and_script_witnesses_are_weighed_from_v2() {
+        let signature = Signature::new(PublicKey::default(), PrivateKey::default());
+        let multisig_input = TransactionInput::new(OutputFeatures::default(), Commitment::default())
+            .with_multisig_witness(MultisigWitness {
+                signers: vec![0, 1, 2],
+                kernel_excess: Commitment::default(),
+                signature: signature.clone(),
+            });
+        let script_input = TransactionInput::new(OutputFeatures::default(), Commitment::default()).with_script_witness(
+            ScriptWitness {
+                kernel_excess: Commitment::default(),
+                stack: vec![StackItem::Signature(signature), StackItem::Number(1)],
+            },
+        );
+        let body = AggregateBody::new(vec![multisig_input, script_input], vec![], vec![]);
+
+        assert_eq!(TransactionWeight::v1().calculate_body(&body), 2 * WEIGHT_PER_INPUT);
+        assert_eq!(
+            TransactionWeight::v2().calculate_body(&body),
+            2 * WEIGHT_PER_INPUT + 3 * MULTISIG_SIGNER_WEIGHT + 2 * SCRIPT_STACK_ITEM_WEIGHT
+        );
+        let weight = TransactionWeight::new(3, 1, 1, 1, 0).with_witness_weights(10, 100);
+        assert_eq!(weight.calculate_body(&body), 2 + 3 * 10 + 2 * 100);
+    }
+}
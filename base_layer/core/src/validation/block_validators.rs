@@ -22,7 +22,7 @@
 use crate::{
     blocks::{Block, BlockValidationError},
     chain_storage,
-    chain_storage::{BlockchainBackend, ChainBlock, MmrTree},
+    chain_storage::{BlockchainBackend, ChainBlock, MmrTree, Optional},
     consensus::ConsensusManager,
     transactions::{
         aggregated_body::AggregateBody,
@@ -32,9 +32,15 @@ use crate::{
     validation::{
         helpers::{
             check_accounting_balance,
+            check_asset_issuance,
             check_block_weight,
+            check_coinbase_extra,
             check_coinbase_output,
             check_cut_through,
+            check_input_relative_maturity,
+            check_kernel_balance,
+            check_output_features_versions,
+            check_range_proof_types,
             is_all_unique_and_sorted,
         },
         traits::PostOrphanBodyValidation,
@@ -45,6 +51,7 @@ use crate::{
 };
 use log::*;
 use std::marker::PhantomData;
+use tari_common_types::types::BlockHash;
 use tari_crypto::{
     commitment::HomomorphicCommitmentFactory,
     tari_utilities::{hash::Hashable, hex::Hex},
@@ -68,6 +75,9 @@ impl OrphanBlockValidator {
 impl OrphanValidation for OrphanBlockValidator {
     /// The consensus checks that are done (in order of cheapest to verify to most expensive):
     /// 1. Is the block weight of the block under the prescribed limit?
+    /// 1. Do all outputs use a permitted version of the output features?
+    /// 1. Do all outputs carry a permitted kind of range proof?
+    /// 1. Is the coinbase extra, if any, permitted?
     /// 1. Does it contain only unique inputs and outputs?
     /// 1. Where all the rules for the spent outputs followed?
     /// 1. Was cut through applied in the block?
@@ -86,8 +96,19 @@ impl OrphanValidation for OrphanBlockValidator {
         };
         trace!(target: LOG_TARGET, "Validating {}", block_id);
 
-        check_block_weight(&block, &self.rules.consensus_constants(block.header.height))?;
+        let constants = self.rules.consensus_constants(block.header.height);
+        check_block_weight(&block, constants)?;
         trace!(target: LOG_TARGET, "SV - Block weight is ok for {} ", &block_id);
+        check_output_features_versions(&block.body, constants)?;
+        trace!(
+            target: LOG_TARGET,
+            "SV - Output features versions are ok for {} ",
+            &block_id
+        );
+        check_range_proof_types(&block.body, constants)?;
+        trace!(target: LOG_TARGET, "SV - Range proof types are ok for {} ", &block_id);
+        check_coinbase_extra(&block.body, constants)?;
+        trace!(target: LOG_TARGET, "SV - Coinbase extra is ok for {} ", &block_id);
 
         trace!(
             target: LOG_TARGET,
@@ -127,12 +148,14 @@ impl<B: BlockchainBackend> PostOrphanBodyValidation<B> for BodyOnlyValidator {
     /// The consensus checks that are done (in order of cheapest to verify to most expensive):
     /// 1. Does the block satisfy the stateless checks?
     /// 1. Are all inputs currently in the UTXO set?
+    /// 1. Have the relative time-locks of all inputs passed?
     /// 1. Are all inputs and outputs not in the STXO set?
     /// 1. Are the block header MMR roots valid?
     fn validate_body_for_valid_orphan(&self, block: &ChainBlock, backend: &B) -> Result<(), ValidationError> {
         let block_id = format!("block #{} ({})", block.header().height, block.hash().to_hex());
         check_inputs_are_utxos(&block.block(), backend)?;
         check_not_duplicate_txos(&block.block(), backend)?;
+        check_asset_issuance(&block.block().body, backend)?;
         trace!(
             target: LOG_TARGET,
             "Block validation: All inputs and outputs are valid for {}",
@@ -161,7 +184,8 @@ fn check_sorting_and_duplicates(body: &AggregateBody) -> Result<(), ValidationEr
     Ok(())
 }
 
-/// This function checks that all inputs in the blocks are valid UTXO's to be spend
+/// This function checks that all inputs in the blocks are valid UTXO's to be spend and that their relative
+/// time-locks have passed
 fn check_inputs_are_utxos<B: BlockchainBackend>(block: &Block, db: &B) -> Result<(), ValidationError> {
     let data = db
         .fetch_block_accumulated_data(&block.header.prev_hash)?
@@ -176,6 +200,7 @@ fn check_inputs_are_utxos<B: BlockchainBackend>(block: &Block, db: &B) -> Result
                 );
                 return Err(ValidationError::ContainsSTxO);
             }
+            check_input_relative_maturity(db, input, index, block.header.height)?;
         } else {
             warn!(
                 target: LOG_TARGET,
@@ -264,12 +289,21 @@ fn check_mmr_roots<B: BlockchainBackend>(block: &Block, db: &B) -> Result<(), Va
     Ok(())
 }
 
+/// A trusted block. Blocks at or below its height that are ancestors of it are assumed to have valid range proofs and
+/// covenants, so only their kernels, balance and MMR roots are checked.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssumeValid {
+    pub height: u64,
+    pub hash: BlockHash,
+}
+
 /// This validator checks whether a block satisfies consensus rules.
 /// It implements two validators: one for the `BlockHeader` and one for `Block`. The `Block` validator ONLY validates
 /// the block body using the header. It is assumed that the `BlockHeader` has already been validated.
 pub struct BlockValidator<B: BlockchainBackend> {
     rules: ConsensusManager,
     factories: CryptoFactories,
+    assume_valid: Option<AssumeValid>,
     phantom_data: PhantomData<B>,
 }
 
@@ -278,10 +312,41 @@ impl<B: BlockchainBackend> BlockValidator<B> {
         Self {
             rules,
             factories,
+            assume_valid: None,
             phantom_data: Default::default(),
         }
     }
 
+    /// Skip range proof and covenant verification for the ancestors of the given trusted block
+    pub fn with_assume_valid(mut self, assume_valid: AssumeValid) -> Self {
+        self.assume_valid = Some(assume_valid);
+        self
+    }
+
+    /// Returns true if the block is buried below the trusted block. The block body is validated against a header that
+    /// is already in the header chain, so this is the case if the header chain contains both the block and the
+    /// trusted block.
+    fn is_assumed_valid(&self, block: &Block, db: &B) -> Result<bool, ValidationError> {
+        let assume_valid = match self.assume_valid.as_ref() {
+            Some(assume_valid) if block.header.height <= assume_valid.height => assume_valid,
+            _ => return Ok(false),
+        };
+        let is_trusted_chain = db
+            .fetch_chain_header_by_height(assume_valid.height)
+            .optional()?
+            .map(|header| *header.hash() == assume_valid.hash)
+            .unwrap_or(false);
+        if !is_trusted_chain {
+            return Ok(false);
+        }
+        let is_in_chain = db
+            .fetch_chain_header_by_height(block.header.height)
+            .optional()?
+            .map(|header| *header.hash() == block.hash())
+            .unwrap_or(false);
+        Ok(is_in_chain)
+    }
+
     /// This function checks that all inputs in the blocks are valid UTXO's to be spend
     fn check_inputs(&self, block: &Block) -> Result<(), ValidationError> {
         let inputs = block.body.inputs();
@@ -310,6 +375,8 @@ impl<B: BlockchainBackend> BlockValidator<B> {
                 return Err(BlockValidationError::NoCutThrough.into());
             }
         }
+        // Script locked inputs must be unlocked at the height of this block
+        block.body.check_script_spends(block.header.height)?;
         Ok(())
     }
 
@@ -437,7 +504,9 @@ impl<B: BlockchainBackend> BlockValidator<B> {
 
 impl<B: BlockchainBackend> CandidateBlockBodyValidation<B> for BlockValidator<B> {
     /// The following consensus checks are done:
-    /// 1. Does the block satisfy the stateless checks?
+    /// 1. Does the block satisfy the stateless checks? Range proofs and covenants are not verified if the block is
+    /// assumed to be valid.
+    /// 1. Are the asset issuances valid with respect to the chain?
     /// 1. Are the block header MMR roots valid?
     fn validate_body(&self, block: &Block, backend: &B) -> Result<(), ValidationError> {
         let block_id = format!("block #{}", block.header.height);
@@ -450,13 +519,23 @@ impl<B: BlockchainBackend> CandidateBlockBodyValidation<B> for BlockValidator<B>
         self.check_inputs(block)?;
         self.check_outputs(block)?;
 
-        check_accounting_balance(block, &self.rules, &self.factories)?;
-        trace!(target: LOG_TARGET, "SV - accounting balance correct for {}", &block_id);
+        if self.is_assumed_valid(block, backend)? {
+            check_kernel_balance(block, &self.rules, &self.factories)?;
+            trace!(
+                target: LOG_TARGET,
+                "SV - kernel balance correct for {}, range proofs are assumed valid",
+                &block_id
+            );
+        } else {
+            check_accounting_balance(block, &self.rules, &self.factories)?;
+            trace!(target: LOG_TARGET, "SV - accounting balance correct for {}", &block_id);
+        }
         debug!(
             target: LOG_TARGET,
             "{} has PASSED stateless VALIDATION check.", &block_id
         );
 
+        check_asset_issuance(&block.body, backend)?;
         self.check_mmr_roots(backend, &block)?;
         trace!(
             target: LOG_TARGET,
@@ -468,3 +547,35 @@ impl<B: BlockchainBackend> CandidateBlockBodyValidation<B> for BlockValidator<B>
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        consensus::{ConsensusManagerBuilder, Network},
+        test_helpers::blockchain::{create_store_with_consensus, TempDatabase},
+    };
+
+    #[test]
+    fn it_only_assumes_ancestors_of_the_trusted_block_are_valid() {
+        let rules = ConsensusManagerBuilder::new(Network::LocalNet).build();
+        let db = create_store_with_consensus(&rules);
+        let genesis = rules.get_genesis_block();
+        let backend = db.db_read_access().unwrap();
+        let validator = BlockValidator::<TempDatabase>::new(rules.clone(), CryptoFactories::default());
+        assert!(!validator.is_assumed_valid(genesis.block(), &*backend).unwrap());
+
+        let validator = validator.with_assume_valid(AssumeValid {
+            height: 0,
+            hash: genesis.hash().clone(),
+        });
+        assert!(validator.is_assumed_valid(genesis.block(), &*backend).unwrap());
+
+        let validator =
+            BlockValidator::<TempDatabase>::new(rules, CryptoFactories::default()).with_assume_valid(AssumeValid {
+                height: 0,
+                hash: vec![0u8; 32],
+            });
+        assert!(!validator.is_assumed_valid(genesis.block(), &*backend).unwrap());
+    }
+}
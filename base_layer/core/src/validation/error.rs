@@ -24,7 +24,7 @@ use crate::{
     blocks::{block_header::BlockHeaderValidationError, BlockValidationError},
     chain_storage::ChainStorageError,
     proof_of_work::{monero_rx::MergeMineError, PowError},
-    transactions::transaction::TransactionError,
+    transactions::transaction::{OutputFeaturesVersion, RangeProofType, TransactionError},
 };
 use thiserror::Error;
 
@@ -77,6 +77,14 @@ pub enum ValidationError {
     MergeMineError(#[from] MergeMineError),
     #[error("Maximum transaction weight exceeded")]
     MaxTransactionWeightExceeded,
+    #[error("Output features version {0} is not permitted by the consensus rules")]
+    OutputFeaturesVersionNotPermitted(OutputFeaturesVersion),
+    #[error("{0} range proofs are not permitted by the consensus rules")]
+    RangeProofTypeNotPermitted(RangeProofType),
+    #[error("Only coinbase outputs may carry a coinbase extra")]
+    CoinbaseExtraNotPermitted,
+    #[error("Coinbase extra is {size} bytes which exceeds the maximum of {max} bytes")]
+    CoinbaseExtraTooLarge { size: usize, max: usize },
 }
 
 // ChainStorageError has a ValidationError variant, so to prevent a cyclic dependency we use a string representation in
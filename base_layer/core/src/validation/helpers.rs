@@ -38,7 +38,11 @@ use crate::{
         PowAlgorithm,
         PowError,
     },
-    transactions::types::CryptoFactories,
+    transactions::{
+        aggregated_body::AggregateBody,
+        transaction::{OutputFeaturesVersion, OutputFlags, RangeProofType, TransactionError, TransactionInput},
+        types::CryptoFactories,
+    },
     validation::ValidationError,
 };
 use log::*;
@@ -185,7 +189,7 @@ pub fn check_target_difficulty(
 
 pub fn check_block_weight(block: &Block, consensus_constants: &ConsensusConstants) -> Result<(), ValidationError> {
     // The genesis block has a larger weight than other blocks may have so we have to exclude it here
-    let block_weight = block.body.calculate_weight();
+    let block_weight = consensus_constants.transaction_weight().calculate_body(&block.body);
     if block_weight <= consensus_constants.get_max_block_transaction_weight() || block.header.height == 0 {
         trace!(
             target: LOG_TARGET,
@@ -201,6 +205,90 @@ pub fn check_block_weight(block: &Block, consensus_constants: &ConsensusConstant
     }
 }
 
+/// This function checks that the features of every output use a version that the consensus rules permit
+pub fn check_output_features_versions(
+    body: &AggregateBody,
+    consensus_constants: &ConsensusConstants,
+) -> Result<(), ValidationError>
+{
+    let permitted = consensus_constants.output_features_version_range();
+    if let Some(output) = body.outputs().iter().find(|o| !permitted.contains(&o.features.version)) {
+        warn!(
+            target: LOG_TARGET,
+            "Output {} uses features version {}, which is not permitted",
+            output.hash().to_hex(),
+            output.features.version
+        );
+        return Err(ValidationError::OutputFeaturesVersionNotPermitted(
+            output.features.version,
+        ));
+    }
+    Ok(())
+}
+
+/// This function checks that every output carries a kind of range proof that the consensus rules permit. Range
+/// proofs other than Bulletproofs also require V5 features, which `check_output_features_versions` checks.
+pub fn check_range_proof_types(
+    body: &AggregateBody,
+    consensus_constants: &ConsensusConstants,
+) -> Result<(), ValidationError>
+{
+    for output in body.outputs() {
+        let range_proof_type = output.features.range_proof_type;
+        let is_permitted = match range_proof_type {
+            RangeProofType::BulletProof => true,
+            RangeProofType::BulletProofPlus => {
+                consensus_constants.is_bulletproof_plus_permitted() &&
+                    output.features.version >= OutputFeaturesVersion::V5
+            },
+        };
+        if !is_permitted {
+            warn!(
+                target: LOG_TARGET,
+                "Output {} carries a {} range proof, which is not permitted",
+                output.hash().to_hex(),
+                range_proof_type
+            );
+            return Err(ValidationError::RangeProofTypeNotPermitted(range_proof_type));
+        }
+    }
+    Ok(())
+}
+
+/// This function checks that only coinbase outputs with V1 features or later carry a coinbase extra, and that it is
+/// no larger than the consensus rules permit
+pub fn check_coinbase_extra(
+    body: &AggregateBody,
+    consensus_constants: &ConsensusConstants,
+) -> Result<(), ValidationError>
+{
+    let max = consensus_constants.coinbase_extra_max_size();
+    for output in body.outputs().iter().filter(|o| !o.features.coinbase_extra.is_empty()) {
+        let features = &output.features;
+        if !features.flags.contains(OutputFlags::COINBASE_OUTPUT) || features.version < OutputFeaturesVersion::V1 {
+            warn!(
+                target: LOG_TARGET,
+                "Output {} carries a coinbase extra but is not a V1 coinbase",
+                output.hash().to_hex()
+            );
+            return Err(ValidationError::CoinbaseExtraNotPermitted);
+        }
+        if features.coinbase_extra.len() > max {
+            warn!(
+                target: LOG_TARGET,
+                "Coinbase {} carries a {} byte extra",
+                output.hash().to_hex(),
+                features.coinbase_extra.len()
+            );
+            return Err(ValidationError::CoinbaseExtraTooLarge {
+                size: features.coinbase_extra.len(),
+                max,
+            });
+        }
+    }
+    Ok(())
+}
+
 pub fn check_accounting_balance(
     block: &Block,
     rules: &ConsensusManager,
@@ -227,6 +315,33 @@ pub fn check_accounting_balance(
         })
 }
 
+/// Checks the kernel signatures and kernel sum of the block, without verifying its range proofs or covenants. Only
+/// used for blocks that are buried below a trusted block.
+pub fn check_kernel_balance(
+    block: &Block,
+    rules: &ConsensusManager,
+    factories: &CryptoFactories,
+) -> Result<(), ValidationError>
+{
+    if block.header.height == 0 {
+        return Ok(());
+    }
+    let offset = &block.header.total_kernel_offset;
+    let total_coinbase = rules.calculate_coinbase_and_fees(block);
+    block
+        .body
+        .validate_kernels_and_balance(&offset, total_coinbase, factories)
+        .map_err(|err| {
+            warn!(
+                target: LOG_TARGET,
+                "Kernel validation failed on block:{}:{}",
+                block.hash().to_hex(),
+                err
+            );
+            ValidationError::TransactionError(err)
+        })
+}
+
 pub fn check_coinbase_output(
     block: &Block,
     rules: &ConsensusManager,
@@ -260,6 +375,70 @@ pub fn check_cut_through(block: &Block) -> Result<(), ValidationError> {
     Ok(())
 }
 
+/// This function checks that the relative time-lock of the given input, if any, has passed at `height`. The
+/// `leaf_index` is the position of the output being spent in the output MMR.
+pub fn check_input_relative_maturity<B: BlockchainBackend>(
+    db: &B,
+    input: &TransactionInput,
+    leaf_index: u32,
+    height: u64,
+) -> Result<(), ValidationError>
+{
+    if input.features.relative_maturity == 0 {
+        return Ok(());
+    }
+    // The output MMR size index is keyed on the number of outputs after each block, so the block that contains leaf
+    // `i` is the first block with an output MMR size of at least `i + 1`
+    let mined_height = db.fetch_header_containing_utxo_mmr(u64::from(leaf_index) + 1)?.height();
+    if !input.features.is_mature_at(mined_height, height) {
+        warn!(
+            target: LOG_TARGET,
+            "Input {} mined at height {} is relatively time-locked until height {}",
+            input,
+            mined_height,
+            mined_height.saturating_add(input.features.relative_maturity)
+        );
+        return Err(ValidationError::MaturityError);
+    }
+    Ok(())
+}
+
+/// This function checks the asset issuances in the body against the asset index of the chain:
+/// 1. An asset can only be registered once
+/// 1. A token can only be minted once, and only for an asset that is registered on chain or in the same body
+///
+/// The asset rules of the body itself are checked by `AggregateBody::check_asset_rules`.
+pub fn check_asset_issuance<B: BlockchainBackend>(body: &AggregateBody, db: &B) -> Result<(), ValidationError> {
+    for output in body.outputs() {
+        let asset = match &output.features.asset {
+            Some(asset) => asset,
+            None => continue,
+        };
+        let asset_id = asset.asset_id();
+        if output.features.is_asset_registration() && db.asset_token_exists(&asset_id, 0)? {
+            warn!(
+                target: LOG_TARGET,
+                "Output registers {} which is already registered", asset_id
+            );
+            return Err(TransactionError::InvalidAsset(format!("Asset {} is already registered", asset_id)).into());
+        }
+        if output.features.is_mint() {
+            if db.asset_token_exists(&asset_id, asset.token_id)? {
+                warn!(target: LOG_TARGET, "Output mints {} which is already minted", asset);
+                return Err(TransactionError::InvalidAsset(format!("{} is already minted", asset)).into());
+            }
+            let is_registered_in_body = body.outputs().iter().any(|o| {
+                o.features.is_asset_registration() && o.features.asset.as_ref().map(|a| a.asset_id()) == Some(asset_id)
+            });
+            if !is_registered_in_body && !db.asset_token_exists(&asset_id, 0)? {
+                warn!(target: LOG_TARGET, "Output mints {} of an unregistered asset", asset);
+                return Err(TransactionError::InvalidAsset(format!("Asset {} is not registered", asset_id)).into());
+            }
+        }
+    }
+    Ok(())
+}
+
 pub fn is_all_unique_and_sorted<I: AsRef<[T]>, T: PartialOrd>(items: I) -> bool {
     let items = items.as_ref();
     if items.is_empty() {
@@ -313,6 +492,145 @@ mod test {
         }
     }
 
+    mod check_coinbase_extra {
+        use super::*;
+        use crate::{
+            consensus::{ConsensusConstantsBuilder, Network},
+            transactions::transaction::{OutputFeatures, TransactionOutput},
+        };
+
+        fn body_with(features: OutputFeatures) -> AggregateBody {
+            let output = TransactionOutput {
+                features,
+                ..Default::default()
+            };
+            AggregateBody::new(vec![], vec![output], vec![])
+        }
+
+        #[test]
+        fn it_limits_the_size() {
+            let constants = ConsensusConstantsBuilder::new(Network::LocalNet)
+                .with_coinbase_extra_max_size(4)
+                .build();
+            let body = body_with(OutputFeatures::create_coinbase_with_extra(10, b"pool".to_vec()));
+            assert!(check_coinbase_extra(&body, &constants).is_ok());
+            let body = body_with(OutputFeatures::create_coinbase_with_extra(10, b"pools".to_vec()));
+            assert!(matches!(
+                check_coinbase_extra(&body, &constants),
+                Err(ValidationError::CoinbaseExtraTooLarge { size: 5, max: 4 })
+            ));
+        }
+
+        #[test]
+        fn it_only_permits_v1_coinbases() {
+            let constants = ConsensusConstantsBuilder::new(Network::LocalNet).build();
+            let mut features = OutputFeatures::create_coinbase_with_extra(10, b"pool".to_vec());
+            features.flags = OutputFlags::empty();
+            assert!(matches!(
+                check_coinbase_extra(&body_with(features), &constants),
+                Err(ValidationError::CoinbaseExtraNotPermitted)
+            ));
+            let mut features = OutputFeatures::create_coinbase_with_extra(10, b"pool".to_vec());
+            features.version = OutputFeaturesVersion::V0;
+            assert!(matches!(
+                check_coinbase_extra(&body_with(features), &constants),
+                Err(ValidationError::CoinbaseExtraNotPermitted)
+            ));
+        }
+    }
+
+    mod check_output_features_versions {
+        use super::*;
+        use crate::{
+            consensus::{ConsensusConstantsBuilder, Network},
+            proto,
+            transactions::transaction::{OutputFeatures, TransactionOutput},
+        };
+        use std::convert::TryFrom;
+
+        #[test]
+        fn it_rejects_unknown_versions_that_were_decoded() {
+            let constants = ConsensusConstantsBuilder::new(Network::LocalNet)
+                .with_output_features_version_range(OutputFeaturesVersion::V0..=OutputFeaturesVersion::LATEST)
+                .build();
+
+            // Features with a version that this node does not know about can still be parsed
+            let features = OutputFeatures::try_from(proto::types::OutputFeatures {
+                version: u32::from(OutputFeaturesVersion::LATEST.as_u8()) + 1,
+                maturity: 10,
+                ..Default::default()
+            })
+            .unwrap();
+            assert!(!features.version.is_known());
+            assert_eq!(features.maturity, 10);
+
+            // but are not permitted by the consensus rules
+            let output = TransactionOutput {
+                features,
+                ..Default::default()
+            };
+            let body = AggregateBody::new(vec![], vec![output.clone()], vec![]);
+            assert!(matches!(
+                check_output_features_versions(&body, &constants),
+                Err(ValidationError::OutputFeaturesVersionNotPermitted(v)) if v == output.features.version
+            ));
+
+            let output = TransactionOutput {
+                features: OutputFeatures::with_maturity(10),
+                ..Default::default()
+            };
+            let body = AggregateBody::new(vec![], vec![output], vec![]);
+            assert!(check_output_features_versions(&body, &constants).is_ok());
+        }
+    }
+
+    mod check_range_proof_types {
+        use super::*;
+        use crate::{
+            consensus::{ConsensusConstantsBuilder, Network},
+            transactions::transaction::{OutputFeatures, TransactionOutput},
+        };
+
+        fn body_with(features: OutputFeatures) -> AggregateBody {
+            let output = TransactionOutput {
+                features,
+                ..Default::default()
+            };
+            AggregateBody::new(vec![], vec![output], vec![])
+        }
+
+        #[test]
+        fn it_only_permits_bulletproofs_plus_after_the_transition() {
+            let body = body_with(OutputFeatures::with_range_proof_type(RangeProofType::BulletProofPlus));
+            let before = ConsensusConstantsBuilder::new(Network::LocalNet).build();
+            assert!(matches!(
+                check_range_proof_types(&body, &before),
+                Err(ValidationError::RangeProofTypeNotPermitted(
+                    RangeProofType::BulletProofPlus
+                ))
+            ));
+            let after = ConsensusConstantsBuilder::new(Network::LocalNet)
+                .with_bulletproof_plus_permitted(true)
+                .build();
+            assert!(check_range_proof_types(&body, &after).is_ok());
+
+            // Bulletproofs are always permitted
+            let body = body_with(OutputFeatures::with_range_proof_type(RangeProofType::BulletProof));
+            assert!(check_range_proof_types(&body, &before).is_ok());
+            assert!(check_range_proof_types(&body, &after).is_ok());
+        }
+
+        #[test]
+        fn it_requires_v5_features() {
+            let constants = ConsensusConstantsBuilder::new(Network::LocalNet)
+                .with_bulletproof_plus_permitted(true)
+                .build();
+            let mut features = OutputFeatures::with_range_proof_type(RangeProofType::BulletProofPlus);
+            features.version = OutputFeaturesVersion::V4;
+            assert!(check_range_proof_types(&body_with(features), &constants).is_err());
+        }
+    }
+
     #[cfg(test)]
     #[allow(clippy::module_inception)]
     mod test {
@@ -22,9 +22,25 @@
 
 use crate::{
     blocks::BlockHeader,
+    chain_storage::BlockchainDatabase,
     consensus::{ConsensusManagerBuilder, Network},
-    test_helpers::{blockchain::create_store_with_consensus, create_chain_header},
-    validation::header_iter::HeaderIter,
+    test_helpers::{
+        blockchain::{create_new_blockchain, create_store_with_consensus, TempDatabase},
+        create_block,
+        create_chain_header,
+    },
+    transactions::{
+        asset::AssetOutputFeatures,
+        transaction::{OutputFeatures, Transaction, TransactionError, TransactionOutput},
+        types::{CommitmentFactory, PrivateKey, PublicKey},
+    },
+    validation::{header_iter::HeaderIter, helpers::check_asset_issuance, ValidationError},
+};
+use rand::rngs::OsRng;
+use std::sync::Arc;
+use tari_crypto::{
+    commitment::HomomorphicCommitmentFactory,
+    keys::{PublicKey as PublicKeyTrait, SecretKey},
 };
 
 #[test]
@@ -146,7 +162,7 @@ fn chain_balance_validation() {
     // // txn.spend_utxo(coinbase_hash);
     //
     // let output = UnblindedOutput::new(coinbase_value, coinbase_key, None);
-    // let fee = Fee::calculate(25 * uT, 1, 1, 2);
+    // let fee = Fee::default().calculate(25 * uT, 1, 1, 2, 0);
     // let schema = txn_schema!(from: vec![output], to: vec![coinbase_value - fee], fee: 25 * uT);
     // let (tx, _, params) = spend_utxos(schema);
     //
@@ -189,7 +205,7 @@ fn chain_balance_validation() {
     // // txn.spend_utxo(faucet_hash);
     //
     // let output = UnblindedOutput::new(faucet_value, faucet_key, None);
-    // let fee = Fee::calculate(25 * uT, 1, 1, 2);
+    // let fee = Fee::default().calculate(25 * uT, 1, 1, 2, 0);
     // let schema = txn_schema!(from: vec![output], to: vec![faucet_value - fee], fee: 25 * uT);
     // let (tx, _, params) = spend_utxos(schema);
     //
@@ -254,3 +270,69 @@ fn chain_balance_validation() {
     // validator.validate(&header4).unwrap_err();
     unimplemented!();
 }
+
+fn issuance_output(
+    features: fn(AssetOutputFeatures) -> OutputFeatures,
+    asset: &AssetOutputFeatures,
+    issuer_k: &PrivateKey,
+) -> TransactionOutput
+{
+    let mut output = TransactionOutput::default();
+    output.commitment = CommitmentFactory::default().commit_value(&PrivateKey::random(&mut OsRng), 100);
+    let asset = asset
+        .clone()
+        .sign(issuer_k.clone(), PrivateKey::random(&mut OsRng), &output.commitment)
+        .unwrap();
+    output.features = features(asset);
+    output
+}
+
+fn add_block_with_outputs(db: &BlockchainDatabase<TempDatabase>, outputs: Vec<TransactionOutput>) {
+    let tip = db.fetch_tip_header().unwrap();
+    let tx = Transaction::new(Vec::new(), outputs, Vec::new(), 0.into());
+    let mut block = create_block(1, tip.height() + 1, vec![tx]);
+    block.header.prev_hash = tip.hash().clone();
+    block.header.output_mmr_size = tip.header().output_mmr_size + block.body.outputs().len() as u64;
+    block.header.kernel_mmr_size = tip.header().kernel_mmr_size + block.body.kernels().len() as u64;
+    db.add_block(Arc::new(block)).unwrap().assert_added();
+}
+
+#[test]
+fn asset_issuance_is_checked_against_the_chain() {
+    let db = create_new_blockchain();
+    let (issuer_k, issuer) = PublicKey::random_keypair(&mut OsRng);
+    let registration = AssetOutputFeatures::new(issuer.clone(), b"gold".to_vec(), 0);
+    let token = AssetOutputFeatures::new(issuer, b"gold".to_vec(), 1);
+    let check = |outputs: Vec<TransactionOutput>| {
+        let tx = Transaction::new(Vec::new(), outputs, Vec::new(), 0.into());
+        check_asset_issuance(&tx.body, &*db.db_read_access().unwrap())
+    };
+    let is_invalid_asset = |res: Result<(), ValidationError>| {
+        matches!(
+            res,
+            Err(ValidationError::TransactionError(TransactionError::InvalidAsset(_)))
+        )
+    };
+
+    // A token cannot be minted before its asset is registered...
+    let mint = issuance_output(OutputFeatures::create_mint_non_fungible, &token, &issuer_k);
+    assert!(is_invalid_asset(check(vec![mint.clone()])));
+    // ...unless the registration is in the same body
+    let register = issuance_output(OutputFeatures::create_asset_registration, &registration, &issuer_k);
+    assert!(check(vec![register.clone(), mint.clone()]).is_ok());
+
+    add_block_with_outputs(&db, vec![register]);
+    assert!(check(vec![mint.clone()]).is_ok());
+    // An asset can only be registered once
+    let register_again = issuance_output(OutputFeatures::create_asset_registration, &registration, &issuer_k);
+    assert!(is_invalid_asset(check(vec![register_again])));
+
+    // A token can only be minted once
+    add_block_with_outputs(&db, vec![mint]);
+    let mint_again = issuance_output(OutputFeatures::create_mint_non_fungible, &token, &issuer_k);
+    assert!(is_invalid_asset(check(vec![mint_again.clone()])));
+
+    // Rewinding the chain removes the token from the index
+    db.rewind_to_height(1).unwrap();
+    assert!(check(vec![mint_again]).is_ok());
+}
@@ -24,7 +24,17 @@ use crate::{
     chain_storage::{BlockchainBackend, BlockchainDatabase, MmrTree},
     tari_utilities::hex::Hex,
     transactions::{transaction::Transaction, types::CryptoFactories},
-    validation::{MempoolTransactionValidation, ValidationError},
+    validation::{
+        helpers::{
+            check_asset_issuance,
+            check_coinbase_extra,
+            check_input_relative_maturity,
+            check_output_features_versions,
+            check_range_proof_types,
+        },
+        MempoolTransactionValidation,
+        ValidationError,
+    },
 };
 use log::*;
 use tari_crypto::tari_utilities::hash::Hashable;
@@ -59,6 +69,9 @@ impl MempoolTransactionValidation for TxInternalConsistencyValidator {
 /// This validator will check the transaction against the current consensus rules.
 ///
 /// 1. The transaction weight should not exceed the maximum weight for 1 block
+/// 1. The outputs must use a permitted version of the output features
+/// 1. The outputs must carry a permitted kind of range proof
+/// 1. The outputs must not carry a coinbase extra, unless they are a permitted coinbase
 #[derive(Clone)]
 pub struct TxConsensusValidator<B> {
     db: BlockchainDatabase<B>,
@@ -74,16 +87,22 @@ impl<B: BlockchainBackend> MempoolTransactionValidation for TxConsensusValidator
     fn validate(&self, tx: &Transaction) -> Result<(), ValidationError> {
         let consensus_constants = self.db.consensus_constants()?;
         // validate maximum tx weight
-        if tx.calculate_weight() > consensus_constants.get_max_block_weight_excluding_coinbase() {
+        let weight = consensus_constants.transaction_weight().calculate_body(&tx.body);
+        if weight > consensus_constants.get_max_block_weight_excluding_coinbase() {
             return Err(ValidationError::MaxTransactionWeightExceeded);
         }
+        check_output_features_versions(&tx.body, consensus_constants)?;
+        check_range_proof_types(&tx.body, consensus_constants)?;
+        check_coinbase_extra(&tx.body, consensus_constants)?;
 
         Ok(())
     }
 }
 
 /// This validator assumes that the transaction was already validated and it will skip this step. It will only check, in
-/// order,: All inputs exist in the backend, All timelocks (kernel lock heights and output maturities) have passed
+/// order,: All inputs exist in the backend, All timelocks (kernel lock heights, output maturities and relative output
+/// maturities) have passed, The scripts of all script locked inputs succeed, The asset issuances are valid with respect
+/// to the chain
 #[derive(Clone)]
 pub struct TxInputAndMaturityValidator<B> {
     db: BlockchainDatabase<B>,
@@ -102,6 +121,10 @@ impl<B: BlockchainBackend> MempoolTransactionValidation for TxInputAndMaturityVa
 
         let tip_height = db.fetch_chain_metadata()?.height_of_longest_chain();
         verify_timelocks(tx, tip_height)?;
+        verify_relative_timelocks(tx, &*db, tip_height)?;
+        // Like the timelocks, the scripts must succeed in the next block
+        tx.body.check_script_spends(tip_height + 1)?;
+        check_asset_issuance(&tx.body, &*db)?;
         verify_no_duplicated_inputs_outputs(tx)?;
         Ok(())
     }
@@ -116,6 +139,23 @@ fn verify_timelocks(tx: &Transaction, current_height: u64) -> Result<(), Validat
     Ok(())
 }
 
+// This function checks that the relative time-locks of all the inputs in the provided transaction pass, i.e. that
+// the transaction could be mined in the next block
+fn verify_relative_timelocks<B: BlockchainBackend>(
+    tx: &Transaction,
+    db: &B,
+    current_height: u64,
+) -> Result<(), ValidationError>
+{
+    for input in tx.body.inputs().iter().filter(|i| i.features.relative_maturity > 0) {
+        let index = db
+            .fetch_mmr_leaf_index(MmrTree::Utxo, &input.hash())?
+            .ok_or_else(|| ValidationError::UnknownInputs)?;
+        check_input_relative_maturity(db, input, index, current_height + 1)?;
+    }
+    Ok(())
+}
+
 // This function checks that the inputs and outputs do not exist in the STxO set.
 fn verify_not_stxos<B: BlockchainBackend>(tx: &Transaction, db: &B) -> Result<(), ValidationError> {
     // `ChainMetadata::best_block` must always have the hash of the tip block.
@@ -65,9 +65,9 @@ use tari_core::{
     },
     chain_storage::ChainBlock,
     consensus::{ConsensusManager, ConsensusManagerBuilder, Network},
-    crypto::tari_utilities::Hashable,
+    crypto::tari_utilities::{ByteArray, Hashable},
     proto::{
-        base_node::{FetchMatchingUtxos, Signatures as SignaturesProto},
+        base_node::{FetchMatchingUtxos, FetchUtxosByCommitment, Signatures as SignaturesProto},
         types::{Signature as SignatureProto, Transaction as TransactionProto},
     },
     test_helpers::blockchain::TempDatabase,
@@ -228,7 +228,7 @@ fn test_base_node_wallet_rpc() {
     assert_eq!(resp.rejection_reason, TxSubmissionRejectionReason::AlreadyMined);
 
     // Now create a different tx that uses the same input as Tx1 to produce a DoubleSpend rejection
-    let (txs1b, _utxos1) = schema_to_transaction(&[txn_schema!(from: vec![utxo0], to: vec![2 * T, 1 * T])]);
+    let (txs1b, _utxos1) = schema_to_transaction(&[txn_schema!(from: vec![utxo0.clone()], to: vec![2 * T, 1 * T])]);
     let tx1b = (*txs1b[0]).clone();
 
     // Now if we submit Tx1 is should return as rejected as AlreadyMined
@@ -313,4 +313,28 @@ fn test_base_node_wallet_rpc() {
             .iter()
             .any(|u| u.as_transaction_output(&factories).unwrap().commitment == output.commitment));
     }
+
+    // utxo0 was spent in block 1 and utxos2 were never mined
+    let mut req_utxos = vec![utxo0];
+    req_utxos.extend(utxos1.clone());
+    req_utxos.push(utxos2[0].clone());
+    let msg = FetchUtxosByCommitment {
+        commitments: req_utxos
+            .iter()
+            .map(|uo| uo.as_transaction_output(&factories).unwrap().commitment.to_vec())
+            .collect(),
+    };
+    let req = request_mock.request_with_context(Default::default(), msg);
+    let response = runtime
+        .block_on(service.fetch_utxos_by_commitment(req))
+        .unwrap()
+        .into_message();
+    assert_eq!(response.outputs.len(), utxos1.len());
+    assert_eq!(response.mined_heights, vec![1; utxos1.len()]);
+    for output_proto in response.outputs.iter() {
+        let output = TransactionOutput::try_from(output_proto.clone()).unwrap();
+        assert!(utxos1
+            .iter()
+            .any(|u| u.as_transaction_output(&factories).unwrap().commitment == output.commitment));
+    }
 }
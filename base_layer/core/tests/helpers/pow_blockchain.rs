@@ -112,7 +112,7 @@ pub fn calculate_accumulated_difficulty(
 ) -> Difficulty
 {
     let mut lwma = LinearWeightedMovingAverage::new(
-        consensus_constants.get_difficulty_block_window() as usize,
+        consensus_constants.get_difficulty_block_window_for(pow_algo) as usize,
         consensus_constants.get_diff_target_block_interval(pow_algo),
         consensus_constants.get_difficulty_max_block_interval(pow_algo),
     );
@@ -268,7 +268,7 @@
 //         // Spend coinbases before horizon height
 //         {
 //             let supply = consensus_manager.emission_schedule().supply_at_block(4);
-//             let fee = Fee::calculate(25 * uT, 5, 5, 2);
+//             let fee = Fee::default().calculate(25 * uT, 5, 5, 2, 0);
 //             let schema = txn_schema!(from: outputs, to: vec![supply - fee], fee: 25 * uT);
 //             let (tx, _, _) = spend_utxos(schema);
 //
@@ -303,7 +303,7 @@
 //         // Spend the other coinbases (why not?)
 //         {
 //             let supply = consensus_manager.emission_schedule().supply_at_block(4);
-//             let fee = Fee::calculate(25 * uT, 5, 5, 2);
+//             let fee = Fee::default().calculate(25 * uT, 5, 5, 2, 0);
 //             let schema = txn_schema!(from: outputs, to: vec![supply - fee], fee: 25 * uT);
 //             let (tx, _, _) = spend_utxos(schema);
 //
@@ -431,7 +431,7 @@
 //
 //         // Spend faucet UTXO
 //         {
-//             let fee = Fee::calculate(25 * uT, 1, 1, 2);
+//             let fee = Fee::default().calculate(25 * uT, 1, 1, 2, 0);
 //             let output = UnblindedOutput::new(faucet_value, faucet_key, None);
 //             let schema = txn_schema!(from: vec![output], to: vec![faucet_value - fee], fee: 25 * uT);
 //             let (tx, _, _) = spend_utxos(schema);
@@ -37,6 +37,7 @@ use helpers::{
 };
 use tari_crypto::keys::PublicKey as PublicKeyTrait;
 // use crate::helpers::database::create_store;
+use rand::rngs::OsRng;
 use std::{ops::Deref, sync::Arc, time::Duration};
 use tari_comms_dht::domain_message::OutboundDomainMessage;
 use tari_core::{
@@ -47,12 +48,20 @@ use tari_core::{
     },
     chain_storage::BlockchainDatabaseConfig,
     consensus::{ConsensusConstantsBuilder, ConsensusManagerBuilder, Network},
-    mempool::{Mempool, MempoolConfig, MempoolServiceConfig, MempoolServiceError, TxStorageResponse},
+    mempool::{
+        Mempool,
+        MempoolConfig,
+        MempoolServiceConfig,
+        MempoolServiceError,
+        ReplaceByFeeConfig,
+        TxStorageResponse,
+    },
     proof_of_work::Difficulty,
     proto,
     transactions::{
         fee::Fee,
         helpers::{schema_to_transaction, spend_utxos, TestParams},
+        script::{Opcode, TariScript},
         tari_amount::{uT, MicroTari, T},
         transaction::{KernelBuilder, OutputFeatures, Transaction, TransactionOutput, UnblindedOutput},
         transaction_protocol::{build_challenge, TransactionMetadata},
@@ -330,6 +339,171 @@ fn test_retrieve() {
     assert!(retrieved_txs.contains(&tx2[1]));
 }
 
+#[test]
+#[allow(clippy::identity_op)]
+fn test_chained_transactions() {
+    let network = Network::LocalNet;
+    let (mut store, mut blocks, mut outputs, consensus_manager) = create_new_blockchain(network);
+    let mempool_validator = TxInputAndMaturityValidator::new(store.clone());
+    let mempool = Mempool::new(MempoolConfig::default(), Arc::new(mempool_validator));
+    let txs = vec![txn_schema!(from: vec![outputs[0][0].clone()], to: vec![2 * T, 2 * T])];
+    generate_new_block(&mut store, &mut blocks, &mut outputs, txs, &consensus_manager).unwrap();
+
+    let (parent, parent_outputs, _) = spend_utxos(txn_schema!(
+        from: vec![outputs[1][0].clone()],
+        to: vec![1 * T],
+        fee: 20 * uT
+    ));
+    let parent = Arc::new(parent);
+    let (child, _, _) = spend_utxos(txn_schema!(
+        from: vec![parent_outputs[0].clone()],
+        to: vec![500_000 * uT],
+        fee: 50 * uT
+    ));
+    let child = Arc::new(child);
+
+    // The child can only be accepted once the transaction that creates the output it spends is in the mempool
+    assert_eq!(
+        mempool.insert(child.clone()).unwrap(),
+        TxStorageResponse::NotStoredOrphan
+    );
+    assert_eq!(
+        mempool.insert(parent.clone()).unwrap(),
+        TxStorageResponse::UnconfirmedPool
+    );
+    assert_eq!(
+        mempool.insert(child.clone()).unwrap(),
+        TxStorageResponse::UnconfirmedPool
+    );
+
+    let weight = parent.calculate_weight() + child.calculate_weight();
+    let retrieved_txs = mempool.retrieve(weight).unwrap();
+    assert_eq!(retrieved_txs, vec![parent.clone(), child.clone()]);
+
+    // Retrieving the transactions aggregated cuts through the output spent by the child
+    let spent = &child.body.inputs()[0];
+    let aggregated = mempool.retrieve_aggregated(weight).unwrap().unwrap();
+    assert_eq!(aggregated.body.kernels().len(), 2);
+    assert_eq!(aggregated.body.inputs(), parent.body.inputs());
+    assert!(!aggregated.body.outputs().iter().any(|o| spent.is_equal_to(o)));
+    assert_eq!(
+        aggregated.calculate_weight(),
+        weight - Fee::default().calculate_weight(0, 1, 1, 0)
+    );
+    assert!(mempool.retrieve_aggregated(0).unwrap().is_none());
+
+    // Both transactions are mined in the same block, with the output spent by the child cut through
+    generate_block(
+        &store,
+        &mut blocks,
+        vec![parent.deref().clone(), child.deref().clone()],
+        &consensus_manager,
+    )
+    .unwrap();
+    assert!(!blocks[2].block().body.inputs().contains(spent));
+    assert!(!blocks[2].block().body.outputs().iter().any(|o| spent.is_equal_to(o)));
+    mempool.process_published_block(blocks[2].to_arc_block()).unwrap();
+    assert_eq!(mempool.stats().unwrap().unconfirmed_txs, 0);
+}
+
+#[test]
+#[allow(clippy::identity_op)]
+fn test_chained_transactions_spending_script_locked_outputs() {
+    let network = Network::LocalNet;
+    let (mut store, mut blocks, mut outputs, consensus_manager) = create_new_blockchain(network);
+    let mempool_validator = TxInputAndMaturityValidator::new(store.clone());
+    let mempool = Mempool::new(MempoolConfig::default(), Arc::new(mempool_validator));
+    let txs = vec![txn_schema!(from: vec![outputs[0][0].clone()], to: vec![2 * T, 2 * T])];
+    generate_new_block(&mut store, &mut blocks, &mut outputs, txs, &consensus_manager).unwrap();
+
+    let (_, public_key) = PublicKey::random_keypair(&mut OsRng);
+    let script = TariScript::new(vec![Opcode::CheckMultiSig(1, vec![public_key])]);
+    let (parent, parent_outputs, _) = spend_utxos(txn_schema!(
+        from: vec![outputs[1][0].clone()],
+        to: vec![1 * T],
+        fee: 20 * uT,
+        lock: 0,
+        OutputFeatures::with_script(script)
+    ));
+    let parent = Arc::new(parent);
+    // The child does not carry a script witness, which would go unnoticed if the input was cut through
+    let (child, _, _) = spend_utxos(txn_schema!(
+        from: vec![parent_outputs[0].clone()],
+        to: vec![500_000 * uT],
+        fee: 50 * uT
+    ));
+    let child = Arc::new(child);
+
+    assert_eq!(
+        mempool.insert(parent.clone()).unwrap(),
+        TxStorageResponse::UnconfirmedPool
+    );
+    assert_eq!(mempool.insert(child).unwrap(), TxStorageResponse::NotStored);
+    assert_eq!(mempool.stats().unwrap().unconfirmed_txs, 1);
+}
+
+#[test]
+#[allow(clippy::identity_op)]
+fn test_replace_by_fee() {
+    let network = Network::LocalNet;
+    let (mut store, mut blocks, mut outputs, consensus_manager) = create_new_blockchain(network);
+    let mempool_validator = TxInputAndMaturityValidator::new(store.clone());
+    let mempool_config = MempoolConfig {
+        replace_by_fee: ReplaceByFeeConfig {
+            enabled: true,
+            min_fee_per_gram_increase_percent: 10,
+        },
+        ..Default::default()
+    };
+    let mempool = Mempool::new(mempool_config, Arc::new(mempool_validator));
+    let txs = vec![txn_schema!(from: vec![outputs[0][0].clone()], to: vec![2 * T, 2 * T])];
+    generate_new_block(&mut store, &mut blocks, &mut outputs, txs, &consensus_manager).unwrap();
+
+    let (original, original_outputs, _) = spend_utxos(txn_schema!(
+        from: vec![outputs[1][0].clone()],
+        to: vec![1 * T],
+        fee: 20 * uT
+    ));
+    let original = Arc::new(original);
+    let (child, _, _) = spend_utxos(txn_schema!(
+        from: vec![original_outputs[0].clone()],
+        to: vec![500_000 * uT],
+        fee: 20 * uT
+    ));
+    let child = Arc::new(child);
+    let (underpaying, _, _) = spend_utxos(txn_schema!(
+        from: vec![outputs[1][0].clone()],
+        to: vec![1 * T],
+        fee: 21 * uT
+    ));
+    let underpaying = Arc::new(underpaying);
+    let (replacement, _, _) = spend_utxos(txn_schema!(
+        from: vec![outputs[1][0].clone()],
+        to: vec![1 * T],
+        fee: 25 * uT
+    ));
+    let replacement = Arc::new(replacement);
+
+    mempool.insert(original.clone()).unwrap();
+    mempool.insert(child.clone()).unwrap();
+    assert_eq!(mempool.stats().unwrap().unconfirmed_txs, 2);
+
+    // A conflicting transaction that does not pay a sufficiently higher fee per gram is rejected
+    assert_eq!(
+        mempool.insert_replacing(underpaying).unwrap(),
+        (TxStorageResponse::NotStoredFeeTooLow, vec![])
+    );
+    assert_eq!(mempool.stats().unwrap().unconfirmed_txs, 2);
+
+    // The replacement evicts the transaction it conflicts with, along with the transaction that depends on it
+    let (storage, evicted) = mempool.insert_replacing(replacement.clone()).unwrap();
+    assert_eq!(storage, TxStorageResponse::UnconfirmedPool);
+    assert_eq!(evicted.len(), 2);
+    assert!(evicted.contains(&original.body.kernels()[0].excess_sig));
+    assert!(evicted.contains(&child.body.kernels()[0].excess_sig));
+    assert_eq!(mempool.snapshot().unwrap(), vec![replacement]);
+}
+
 #[test]
 #[allow(clippy::identity_op)]
 fn test_reorg() {
@@ -661,7 +835,7 @@ fn consensus_validation() {
     let sum_inputs_blinding_factors = input.spending_key.clone();
     let inputs = vec![input.as_transaction_input(&factories.commitment, OutputFeatures::default())];
 
-    let fee = Fee::calculate(fee_per_gram.into(), 1, input_count, output_count);
+    let fee = Fee::default().calculate(fee_per_gram.into(), 1, input_count, output_count, 0);
     let amount_per_output = (amount - fee) / output_count as u64;
     let amount_for_last_output = (amount - fee) - amount_per_output * (output_count as u64 - 1);
     let mut unblinded_outputs = Vec::with_capacity(output_count);
@@ -687,7 +861,11 @@ fn consensus_validation() {
         .collect::<Result<Vec<TransactionOutput>, _>>()
         .unwrap();
 
-    let tx_meta = TransactionMetadata { fee, lock_height: 0 };
+    let tx_meta = TransactionMetadata {
+        fee,
+        lock_height: 0,
+        ..Default::default()
+    };
 
     let nonce = test_params.nonce.clone();
     let public_nonce = PublicKey::from_secret_key(&nonce);
@@ -20,6 +20,22 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+//! Deterministic derivation of the keys of a wallet from its master key.
+//!
+//! Keys are derived on labelled branches, one per purpose, so that keys used for different purposes never coincide and
+//! a wallet recovered from its master key can derive every key it used before. The `index`th key of a branch is
+//!
+//! `key = D(hex(master_key) || label || decimal(index))`
+//!
+//! where `D` is the digest of the key manager (Blake256 in the wallet), `hex` is lowercase hexadecimal, the index is
+//! written in decimal without padding and the three strings are concatenated as UTF-8. The digest is converted into a
+//! key with `ByteArray::from_bytes`, which for Ristretto keys reduces the 32 bytes, read as a little-endian integer,
+//! modulo the group order. The labels of the branches are listed in [KeyManagerBranch].
+//!
+//! The keys of multisig and hash time-locked scripts are the keys of their branches. The keys of one-sided payments
+//! are not on the derivation path: they are derived from a Diffie-Hellman secret of the sender's ephemeral key and the
+//! recipient's node identity key, as described in the README of this crate.
+
 use crate::mnemonic;
 use digest::Digest;
 use rand::{CryptoRng, Rng};
@@ -40,6 +56,38 @@ pub enum KeyManagerError {
     MnemonicError(#[from] mnemonic::MnemonicError),
 }
 
+/// The branches of the key derivation path and the purposes of their keys
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyManagerBranch {
+    /// The spending keys of the outputs the wallet receives and of its change, from index 1 onwards. The label is
+    /// empty for compatibility with the keys of wallets created before branches were introduced.
+    Spend,
+    /// The spending keys of coinbase outputs, indexed by the height of the block they are mined in
+    Coinbase,
+    /// The key with which the range proofs of the wallet's outputs can be rewound, at index 0
+    RecoveryViewOnly,
+    /// The blinding key of the rewindable range proofs, at index 0
+    RecoveryBlinding,
+    /// The key with which the wallet takes part in multisig locks, at index 0
+    Multisig,
+    /// The key with which the wallet claims or refunds the hash time-locked outputs of its atomic swaps, at index 0
+    Htlc,
+}
+
+impl KeyManagerBranch {
+    /// The label that is mixed into the keys of this branch
+    pub fn label(self) -> &'static str {
+        match self {
+            KeyManagerBranch::Spend => "",
+            KeyManagerBranch::Coinbase => "coinbase",
+            KeyManagerBranch::RecoveryViewOnly => "recovery_viewonly",
+            KeyManagerBranch::RecoveryBlinding => "recovery_blinding",
+            KeyManagerBranch::Multisig => "multisig",
+            KeyManagerBranch::Htlc => "htlc",
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct DerivedKey<K>
 where K: SecretKey
@@ -118,9 +166,20 @@ where
         }
     }
 
-    /// Derive a new private key from master key: derived_key=SHA256(master_key||branch_seed||index)
+    /// Constructs a KeyManager for the given branch of the master key
+    pub fn from_branch(master_key: K, branch: KeyManagerBranch, primary_key_index: u64) -> KeyManager<K, D> {
+        Self::from(master_key, branch.label().to_string(), primary_key_index)
+    }
+
+    /// Derive the private key at the index of the branch of this key manager:
+    /// derived_key=D(master_key||branch_seed||index), see the module documentation
     pub fn derive_key(&self, key_index: u64) -> Result<DerivedKey<K>, ByteArrayError> {
-        let concatenated = format!("{}{}", self.master_key.to_hex(), key_index.to_string());
+        let concatenated = format!(
+            "{}{}{}",
+            self.master_key.to_hex(),
+            self.branch_seed,
+            key_index.to_string()
+        );
         match K::from_bytes(D::digest(&concatenated.into_bytes()).as_slice()) {
             Ok(k) => Ok(DerivedKey { k, key_index }),
             Err(e) => Err(e),
@@ -144,7 +203,7 @@ mod test {
     use rand::rngs::OsRng;
     use sha2::Sha256;
     use std::fs::remove_file;
-    use tari_crypto::ristretto::RistrettoSecretKey;
+    use tari_crypto::{ristretto::RistrettoSecretKey, tari_utilities::ByteArray};
 
     #[test]
     fn test_new_keymanager() {
@@ -216,6 +275,55 @@ mod test {
         assert_eq!(next_key2.key_index, desired_key_index2);
     }
 
+    #[test]
+    fn test_derive_key_per_branch() {
+        let km = KeyManager::<RistrettoSecretKey, Sha256>::new(&mut OsRng);
+        let master_key = km.master_key.clone();
+        let branches = [
+            KeyManagerBranch::Spend,
+            KeyManagerBranch::Coinbase,
+            KeyManagerBranch::RecoveryViewOnly,
+            KeyManagerBranch::RecoveryBlinding,
+            KeyManagerBranch::Multisig,
+            KeyManagerBranch::Htlc,
+        ];
+        let keys = branches
+            .iter()
+            .map(|branch| {
+                KeyManager::<RistrettoSecretKey, Sha256>::from_branch(master_key.clone(), *branch, 0)
+                    .derive_key(1)
+                    .unwrap()
+                    .k
+            })
+            .collect::<Vec<_>>();
+        for (i, key) in keys.iter().enumerate() {
+            assert!(keys.iter().skip(i + 1).all(|other| other != key));
+        }
+
+        let coinbase = KeyManager::<RistrettoSecretKey, Sha256>::from_branch(master_key, KeyManagerBranch::Coinbase, 0);
+        assert_eq!(coinbase.derive_key(1).unwrap().k, keys[1]);
+        assert_ne!(coinbase.derive_key(2).unwrap().k, keys[1]);
+    }
+
+    #[test]
+    fn test_derivation_path() {
+        let km = KeyManager::<RistrettoSecretKey, Sha256>::new(&mut OsRng);
+        let coinbase =
+            KeyManager::<RistrettoSecretKey, Sha256>::from_branch(km.master_key.clone(), KeyManagerBranch::Coinbase, 0);
+        let expected =
+            |path: String| RistrettoSecretKey::from_bytes(Sha256::digest(path.as_bytes()).as_slice()).unwrap();
+
+        // The keys of the spend branch are the same as before branches were introduced
+        assert_eq!(
+            km.derive_key(7).unwrap().k,
+            expected(format!("{}7", km.master_key.to_hex()))
+        );
+        assert_eq!(
+            coinbase.derive_key(42).unwrap().k,
+            expected(format!("{}coinbase42", km.master_key.to_hex()))
+        );
+    }
+
     #[test]
     fn test_to_file_and_from_file() {
         let desired_km = KeyManager::<RistrettoSecretKey, Sha256>::new(&mut OsRng);
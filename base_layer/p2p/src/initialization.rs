@@ -146,6 +146,18 @@ pub struct CommsConfig {
     pub dns_seeds_name_server: SocketAddr,
     /// All DNS seed records must pass DNSSEC validation
     pub dns_seeds_use_dnssec: bool,
+    /// Set to true to run comms in outbound-only mode. No listener is started and this node will not advertise an
+    /// address to peers. This is intended for wallets that are not reachable from the internet (e.g. behind NAT).
+    pub outbound_only: bool,
+    /// Set to true to update this node's advertised public address when the majority of peers that it connects to
+    /// report observing a different public IP address.
+    pub auto_update_public_address: bool,
+    /// Set to true to accept the unsigned identities of peers that run a version that does not sign its identity yet
+    pub allow_unsigned_identities: bool,
+    /// Rotate the session keys of each connection once this much time has elapsed since the last rotation
+    pub noise_rekey_interval: Option<Duration>,
+    /// Rotate the session keys of each connection once this many bytes have been sent since the last rotation
+    pub noise_rekey_max_bytes: Option<u64>,
 }
 
 /// Initialize Tari Comms configured for tests
@@ -264,6 +276,17 @@ pub async fn spawn_comms_using_transport(
                 .spawn_with_transport(transport)
                 .await?
         },
+        TransportType::Tor(tor_config) if comms.is_outbound_only() => {
+            debug!(
+                target: LOG_TARGET,
+                "Building outbound-only TOR comms stack ({})", tor_config
+            );
+            // Outbound-only nodes do not create a hidden service, the tor control port is only used to discover the
+            // SOCKS address
+            let mut hidden_service_ctl = initialize_hidden_service(tor_config).await?;
+            let transport = hidden_service_ctl.initialize_transport().await?;
+            comms.spawn_with_transport(transport).await?
+        },
         TransportType::Tor(tor_config) => {
             debug!(target: LOG_TARGET, "Building TOR comms stack ({})", tor_config);
             let mut hidden_service_ctl = initialize_hidden_service(tor_config).await?;
@@ -334,6 +357,17 @@ where
     let listener_liveness_allowlist_cidrs = parse_cidrs(&config.listener_liveness_allowlist_cidrs)
         .map_err(CommsInitializationError::InvalidLivenessCidrs)?;
 
+    let mut builder = builder;
+    if config.outbound_only {
+        builder = builder.outbound_only();
+    }
+    if config.auto_update_public_address {
+        builder = builder.auto_update_public_address();
+    }
+    builder = builder
+        .allow_unsigned_identities(config.allow_unsigned_identities)
+        .with_noise_rekey(config.noise_rekey_interval, config.noise_rekey_max_bytes);
+
     let mut comms = builder
         .with_listener_liveness_max_sessions(config.listener_liveness_max_sessions)
         .with_listener_liveness_allowlist_cidrs(listener_liveness_allowlist_cidrs)
@@ -138,6 +138,15 @@ impl LivenessHandle {
         }
     }
 
+    /// Retrieve the average latency, in milliseconds, of the pongs received from the given node ID. `None` is returned
+    /// if no pong has been received from the node.
+    pub async fn get_avg_latency(&mut self, node_id: NodeId) -> Result<Option<u32>, LivenessError> {
+        match self.handle.call(LivenessRequest::GetAvgLatency(node_id)).await?? {
+            LivenessResponse::AvgLatency(latency) => Ok(latency),
+            _ => Err(LivenessError::UnexpectedApiResponse),
+        }
+    }
+
     /// Set metadata entry for the pong message
     pub async fn set_metadata_entry(&mut self, key: MetadataKey, value: Vec<u8>) -> Result<(), LivenessError> {
         match self
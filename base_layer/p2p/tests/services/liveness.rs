@@ -159,4 +159,15 @@ async fn end_to_end() {
     assert_eq!(pongcount1, 8);
     assert_eq!(pingcount2, 8);
     assert_eq!(pongcount2, 10);
+
+    assert!(liveness1
+        .get_avg_latency(node_2_identity.node_id().clone())
+        .await
+        .unwrap()
+        .is_some());
+    assert!(liveness1
+        .get_avg_latency(make_node_identity().node_id().clone())
+        .await
+        .unwrap()
+        .is_none());
 }
@@ -0,0 +1,4 @@
+ALTER TABLE outputs
+    ADD COLUMN relative_maturity INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE outputs
+    ADD COLUMN mined_height INTEGER NULL;
@@ -0,0 +1,22 @@
+PRAGMA foreign_keys=off;
+ALTER TABLE outputs RENAME TO outputs_old;
+CREATE TABLE outputs (
+    id INTEGER NOT NULL PRIMARY KEY,
+    commitment BLOB NULL DEFAULT NULL,
+    spending_key BLOB NOT NULL,
+    value INTEGER NOT NULL,
+    flags INTEGER NOT NULL,
+    maturity INTEGER NOT NULL,
+    status INTEGER NOT NULL,
+    tx_id INTEGER NULL,
+    hash BLOB NULL DEFAULT NULL,
+    relative_maturity INTEGER NOT NULL DEFAULT 0,
+    mined_height INTEGER NULL,
+    asset TEXT NULL,
+    CONSTRAINT unique_commitment UNIQUE (commitment)
+);
+INSERT INTO outputs (id, commitment, spending_key, value, flags, maturity, status, tx_id, hash, relative_maturity, mined_height, asset)
+SELECT id, commitment, spending_key, value, flags, maturity, status, tx_id, hash, relative_maturity, mined_height, asset
+FROM outputs_old;
+DROP TABLE outputs_old;
+PRAGMA foreign_keys=on;
@@ -0,0 +1,10 @@
+CREATE TABLE scheduled_payments (
+    id INTEGER PRIMARY KEY NOT NULL,
+    destination_public_key BLOB NOT NULL,
+    amount INTEGER NOT NULL,
+    fee_per_gram INTEGER NOT NULL,
+    message TEXT NOT NULL,
+    interval_secs INTEGER NOT NULL,
+    next_payment DATETIME NOT NULL,
+    last_tx_id INTEGER NULL
+);
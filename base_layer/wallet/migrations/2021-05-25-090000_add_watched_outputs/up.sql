@@ -0,0 +1,6 @@
+CREATE TABLE watched_outputs (
+    hash BLOB PRIMARY KEY NOT NULL,
+    value INTEGER NOT NULL,
+    detected_at DATETIME NOT NULL,
+    spent_at DATETIME NULL
+);
@@ -0,0 +1,2 @@
+ALTER TABLE watched_outputs
+    DROP COLUMN output;
@@ -0,0 +1,2 @@
+ALTER TABLE watched_outputs
+    ADD COLUMN output TEXT NULL;
@@ -0,0 +1,5 @@
+CREATE TABLE transaction_labels (
+    tx_id INTEGER NOT NULL,
+    label TEXT NOT NULL,
+    PRIMARY KEY (tx_id, label)
+);
@@ -0,0 +1,2 @@
+ALTER TABLE outputs
+    ADD COLUMN script TEXT NULL;
@@ -26,6 +26,7 @@ use std::sync::Arc;
 use tari_comms::peer_manager::Peer;
 
 use tari_common_types::chain_metadata::ChainMetadata;
+use tari_core::mempool::MempoolFeeStats;
 use tari_service_framework::reply_channel::SenderService;
 use tokio::sync::broadcast;
 use tower::Service;
@@ -36,12 +37,14 @@ pub type BaseNodeEventReceiver = broadcast::Receiver<Arc<BaseNodeEvent>>;
 #[derive(Debug)]
 pub enum BaseNodeServiceRequest {
     GetChainMetadata,
+    GetMempoolFeeStats,
     SetBaseNodePeer(Box<Peer>),
 }
 /// API Response enum
 #[derive(Debug)]
 pub enum BaseNodeServiceResponse {
     ChainMetadata(Option<ChainMetadata>),
+    MempoolFeeStats(Option<MempoolFeeStats>),
     BaseNodePeerSet,
 }
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
@@ -81,6 +84,14 @@ impl BaseNodeServiceHandle {
         }
     }
 
+    /// Returns the last fee statistics received from the base node's mempool, if any
+    pub async fn get_mempool_fee_stats(&mut self) -> Result<Option<MempoolFeeStats>, BaseNodeServiceError> {
+        match self.handle.call(BaseNodeServiceRequest::GetMempoolFeeStats).await?? {
+            BaseNodeServiceResponse::MempoolFeeStats(stats) => Ok(stats),
+            _ => Err(BaseNodeServiceError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn set_base_node_peer(&mut self, peer: Peer) -> Result<(), BaseNodeServiceError> {
         match self
             .handle
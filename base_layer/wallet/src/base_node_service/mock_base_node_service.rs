@@ -28,6 +28,7 @@ use crate::base_node_service::{
 use futures::StreamExt;
 use tari_common_types::chain_metadata::ChainMetadata;
 use tari_comms::peer_manager::Peer;
+use tari_core::mempool::MempoolFeeStats;
 use tari_service_framework::reply_channel::Receiver;
 use tari_shutdown::ShutdownSignal;
 
@@ -93,6 +94,7 @@ impl MockBaseNodeService {
             latency: None,
             online,
             base_node_peer: self.state.base_node_peer.clone(),
+            mempool_fee_stats: None,
         }
     }
 
@@ -105,9 +107,15 @@ impl MockBaseNodeService {
             latency: None,
             online: OnlineState::Online,
             base_node_peer: None,
+            mempool_fee_stats: None,
         }
     }
 
+    /// Set the mempool fee statistics the mock reports, None if the base node did not provide them
+    pub fn set_mempool_fee_stats(&mut self, stats: Option<MempoolFeeStats>) {
+        self.state.mempool_fee_stats = stats;
+    }
+
     fn set_base_node_peer(&mut self, peer: Peer) {
         self.state.base_node_peer = Some(peer);
     }
@@ -126,6 +134,9 @@ impl MockBaseNodeService {
             BaseNodeServiceRequest::GetChainMetadata => Ok(BaseNodeServiceResponse::ChainMetadata(
                 self.state.chain_metadata.clone(),
             )),
+            BaseNodeServiceRequest::GetMempoolFeeStats => Ok(BaseNodeServiceResponse::MempoolFeeStats(
+                self.state.mempool_fee_stats.clone(),
+            )),
         }
     }
 }
@@ -39,7 +39,7 @@ use tari_comms::{
     protocol::rpc::RpcError,
     PeerConnection,
 };
-use tari_core::base_node::rpc::BaseNodeWalletRpcClient;
+use tari_core::{base_node::rpc::BaseNodeWalletRpcClient, mempool::MempoolFeeStats};
 use tari_shutdown::ShutdownSignal;
 use tokio::{
     stream::StreamExt,
@@ -213,6 +213,17 @@ impl<T: WalletBackend + 'static> BaseNodeMonitor<T> {
 
             self.db.set_chain_metadata(chain_metadata.clone()).await?;
 
+            // Base nodes that predate mempool fee stats reject the request, which should not take them offline
+            let mempool_fee_stats = match client.get_mempool_fee_stats().await {
+                Ok(stats) => MempoolFeeStats::try_from(stats)
+                    .map_err(|e| debug!(target: LOG_TARGET, "Invalid mempool fee stats: {}", e))
+                    .ok(),
+                Err(e) => {
+                    debug!(target: LOG_TARGET, "Base node did not provide mempool fee stats: {}", e);
+                    None
+                },
+            };
+
             self.map_state(move |state| BaseNodeState {
                 chain_metadata: Some(chain_metadata),
                 is_synced: Some(is_synced),
@@ -220,6 +231,7 @@ impl<T: WalletBackend + 'static> BaseNodeMonitor<T> {
                 latency,
                 online: OnlineState::Online,
                 base_node_peer: state.base_node_peer.clone(),
+                mempool_fee_stats,
             })
             .await;
 
@@ -257,6 +269,7 @@ impl<T: WalletBackend + 'static> BaseNodeMonitor<T> {
             latency: None,
             online: OnlineState::Connecting,
             base_node_peer: state.base_node_peer.clone(),
+            mempool_fee_stats: None,
         })
         .await;
     }
@@ -269,6 +282,7 @@ impl<T: WalletBackend + 'static> BaseNodeMonitor<T> {
             latency: None,
             online: OnlineState::Offline,
             base_node_peer: state.base_node_peer.clone(),
+            mempool_fee_stats: None,
         })
         .await;
     }
@@ -35,6 +35,7 @@ use log::*;
 use std::{sync::Arc, time::Duration};
 use tari_common_types::chain_metadata::ChainMetadata;
 use tari_comms::{connectivity::ConnectivityRequester, peer_manager::Peer};
+use tari_core::mempool::MempoolFeeStats;
 use tari_service_framework::reply_channel::Receiver;
 use tari_shutdown::ShutdownSignal;
 use tokio::sync::RwLock;
@@ -50,6 +51,8 @@ pub struct BaseNodeState {
     pub latency: Option<Duration>,
     pub online: OnlineState,
     pub base_node_peer: Option<Peer>,
+    /// The fee statistics of the base node's mempool, if the base node provides them
+    pub mempool_fee_stats: Option<MempoolFeeStats>,
 }
 
 /// Connection state of the Base Node
@@ -69,6 +72,7 @@ impl Default for BaseNodeState {
             latency: None,
             online: OnlineState::Connecting,
             base_node_peer: None,
+            mempool_fee_stats: None,
         }
     }
 }
@@ -195,6 +199,9 @@ where T: WalletBackend + 'static
                     Ok(BaseNodeServiceResponse::ChainMetadata(metadata))
                 },
             },
+            BaseNodeServiceRequest::GetMempoolFeeStats => Ok(BaseNodeServiceResponse::MempoolFeeStats(
+                self.get_state().await.mempool_fee_stats,
+            )),
         }
     }
 
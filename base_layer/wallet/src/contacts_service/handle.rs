@@ -21,16 +21,73 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::contacts_service::{error::ContactsServiceError, storage::database::Contact};
-use tari_comms::types::CommsPublicKey;
+use chrono::NaiveDateTime;
+use futures::{stream::Fuse, StreamExt};
+use std::{fmt, sync::Arc};
+use tari_comms::{peer_manager::NodeId, types::CommsPublicKey};
 use tari_service_framework::reply_channel::SenderService;
+use tokio::sync::broadcast;
 use tower::Service;
 
+pub type ContactsLivenessEventSender = broadcast::Sender<Arc<ContactsLivenessEvent>>;
+pub type ContactsLivenessEventReceiver = broadcast::Receiver<Arc<ContactsLivenessEvent>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContactOnlineStatus {
+    /// The contact answered a ping, or pinged this wallet, within the online period
+    Online,
+    /// The contact has been seen, but not within the online period
+    Offline,
+    /// The contact has not been seen since the wallet started
+    NeverSeen,
+}
+
+impl fmt::Display for ContactOnlineStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContactOnlineStatus::Online => write!(f, "Online"),
+            ContactOnlineStatus::Offline => write!(f, "Offline"),
+            ContactOnlineStatus::NeverSeen => write!(f, "Never seen"),
+        }
+    }
+}
+
+/// The liveness of a contact, as seen by the pings that the contacts service sends it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContactsLivenessData {
+    pub public_key: CommsPublicKey,
+    pub node_id: NodeId,
+    /// The latency, in milliseconds, of the last pong received from the contact
+    pub latency: Option<u32>,
+    pub last_seen: Option<NaiveDateTime>,
+    pub online_status: ContactOnlineStatus,
+}
+
+impl ContactsLivenessData {
+    pub fn new(public_key: CommsPublicKey) -> Self {
+        Self {
+            node_id: NodeId::from_public_key(&public_key),
+            public_key,
+            latency: None,
+            last_seen: None,
+            online_status: ContactOnlineStatus::NeverSeen,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContactsLivenessEvent {
+    /// The contact was seen, or its online status changed
+    StatusUpdated(Box<ContactsLivenessData>),
+}
+
 #[derive(Debug)]
 pub enum ContactsServiceRequest {
     GetContact(CommsPublicKey),
     UpsertContact(Contact),
     RemoveContact(CommsPublicKey),
     GetContacts,
+    GetContactsLiveness,
 }
 
 #[derive(Debug)]
@@ -39,17 +96,28 @@ pub enum ContactsServiceResponse {
     ContactRemoved(Contact),
     Contact(Contact),
     Contacts(Vec<Contact>),
+    ContactsLiveness(Vec<ContactsLivenessData>),
 }
 
 #[derive(Clone)]
 pub struct ContactsServiceHandle {
     handle: SenderService<ContactsServiceRequest, Result<ContactsServiceResponse, ContactsServiceError>>,
+    liveness_events: ContactsLivenessEventSender,
 }
 impl ContactsServiceHandle {
     pub fn new(
         handle: SenderService<ContactsServiceRequest, Result<ContactsServiceResponse, ContactsServiceError>>,
-    ) -> Self {
-        Self { handle }
+        liveness_events: ContactsLivenessEventSender,
+    ) -> Self
+    {
+        Self {
+            handle,
+            liveness_events,
+        }
+    }
+
+    pub fn get_liveness_event_stream_fused(&self) -> Fuse<ContactsLivenessEventReceiver> {
+        self.liveness_events.subscribe().fuse()
     }
 
     pub async fn get_contact(&mut self, pub_key: CommsPublicKey) -> Result<Contact, ContactsServiceError> {
@@ -66,6 +134,14 @@ impl ContactsServiceHandle {
         }
     }
 
+    /// The liveness of every contact, in the order of `get_contacts`
+    pub async fn get_contacts_liveness(&mut self) -> Result<Vec<ContactsLivenessData>, ContactsServiceError> {
+        match self.handle.call(ContactsServiceRequest::GetContactsLiveness).await?? {
+            ContactsServiceResponse::ContactsLiveness(l) => Ok(l),
+            _ => Err(ContactsServiceError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn upsert_contact(&mut self, contact: Contact) -> Result<(), ContactsServiceError> {
         match self
             .handle
@@ -20,38 +20,46 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+pub mod config;
 pub mod error;
 pub mod handle;
 pub mod service;
 pub mod storage;
 
 use crate::contacts_service::{
+    config::ContactsServiceConfig,
     handle::ContactsServiceHandle,
     service::ContactsService,
     storage::database::{ContactsBackend, ContactsDatabase},
 };
 use futures::{future, Future};
 use log::*;
+use tari_p2p::services::liveness::LivenessHandle;
 use tari_service_framework::{
     reply_channel,
     ServiceInitializationError,
     ServiceInitializer,
     ServiceInitializerContext,
 };
+use tokio::sync::broadcast;
 
 const LOG_TARGET: &str = "wallet::contacts_service::initializer";
 
 pub struct ContactsServiceInitializer<T>
 where T: ContactsBackend
 {
+    config: ContactsServiceConfig,
     backend: Option<T>,
 }
 
 impl<T> ContactsServiceInitializer<T>
 where T: ContactsBackend
 {
-    pub fn new(backend: T) -> Self {
-        Self { backend: Some(backend) }
+    pub fn new(config: ContactsServiceConfig, backend: T) -> Self {
+        Self {
+            config,
+            backend: Some(backend),
+        }
     }
 }
 
@@ -63,7 +71,9 @@ where T: ContactsBackend + 'static
     fn initialize(&mut self, context: ServiceInitializerContext) -> Self::Future {
         let (sender, receiver) = reply_channel::unbounded();
 
-        let contacts_handle = ContactsServiceHandle::new(sender);
+        let (liveness_events, _) = broadcast::channel(100);
+
+        let contacts_handle = ContactsServiceHandle::new(sender, liveness_events.clone());
 
         // Register handle before waiting for handles to be ready
         context.register_handle(contacts_handle);
@@ -74,10 +84,20 @@ where T: ContactsBackend + 'static
             .expect("Cannot start Contacts Service without setting a storage backend");
 
         let shutdown_signal = context.get_shutdown_signal();
+        let config = self.config.clone();
 
         context.spawn_when_ready(move |handles| async move {
-            let service =
-                ContactsService::new(receiver, ContactsDatabase::new(backend), handles.get_shutdown_signal()).start();
+            let liveness = handles.expect_handle::<LivenessHandle>();
+
+            let service = ContactsService::new(
+                receiver,
+                ContactsDatabase::new(backend),
+                handles.get_shutdown_signal(),
+                config,
+                liveness,
+                liveness_events,
+            )
+            .start();
             futures::pin_mut!(service);
             future::select(service, shutdown_signal).await;
             info!(target: LOG_TARGET, "Contacts service shutdown");
@@ -21,14 +21,27 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::contacts_service::{
+    config::ContactsServiceConfig,
     error::ContactsServiceError,
-    handle::{ContactsServiceRequest, ContactsServiceResponse},
+    handle::{
+        ContactOnlineStatus,
+        ContactsLivenessData,
+        ContactsLivenessEvent,
+        ContactsLivenessEventSender,
+        ContactsServiceRequest,
+        ContactsServiceResponse,
+    },
     storage::database::{ContactsBackend, ContactsDatabase},
 };
+use chrono::{Duration as ChronoDuration, NaiveDateTime, Utc};
 use futures::{pin_mut, StreamExt};
 use log::*;
+use std::{collections::HashMap, sync::Arc};
+use tari_comms::peer_manager::NodeId;
+use tari_p2p::services::liveness::{LivenessEvent, LivenessHandle, PingPongEvent};
 use tari_service_framework::reply_channel;
 use tari_shutdown::ShutdownSignal;
+use tokio::time;
 
 const LOG_TARGET: &str = "wallet:contacts_service";
 
@@ -39,6 +52,11 @@ where T: ContactsBackend + 'static
     request_stream:
         Option<reply_channel::Receiver<ContactsServiceRequest, Result<ContactsServiceResponse, ContactsServiceError>>>,
     shutdown_signal: Option<ShutdownSignal>,
+    config: ContactsServiceConfig,
+    liveness: LivenessHandle,
+    liveness_events: ContactsLivenessEventSender,
+    /// The liveness of the contacts, by their node id so that pings and pongs can be matched to them
+    liveness_data: HashMap<NodeId, ContactsLivenessData>,
 }
 
 impl<T> ContactsService<T>
@@ -52,12 +70,19 @@ where T: ContactsBackend + 'static
 
         db: ContactsDatabase<T>,
         shutdown_signal: ShutdownSignal,
+        config: ContactsServiceConfig,
+        liveness: LivenessHandle,
+        liveness_events: ContactsLivenessEventSender,
     ) -> Self
     {
         Self {
             db,
             request_stream: Some(request_stream),
             shutdown_signal: Some(shutdown_signal),
+            config,
+            liveness,
+            liveness_events,
+            liveness_data: HashMap::new(),
         }
     }
 
@@ -75,6 +100,9 @@ where T: ContactsBackend + 'static
             .expect("Output Manager Service initialized without shutdown signal");
         pin_mut!(shutdown);
 
+        let mut liveness_event_stream = self.liveness.get_event_stream().fuse();
+        let mut liveness_ticker = time::interval(self.config.liveness_check_interval).fuse();
+
         info!(target: LOG_TARGET, "Contacts Service started");
         loop {
             futures::select! {
@@ -89,6 +117,15 @@ where T: ContactsBackend + 'static
                         e
                     });
                 },
+                _ = liveness_ticker.select_next_some() => {
+                    if let Err(e) = self.ping_contacts().await {
+                        warn!(target: LOG_TARGET, "Could not ping the contacts: {}", e);
+                    }
+                },
+                event = liveness_event_stream.select_next_some() => match event {
+                    Ok(event) => self.handle_liveness_event(&event),
+                    Err(e) => debug!(target: LOG_TARGET, "Lagging read on liveness events: {}", e),
+                },
                 _ = shutdown => {
                     info!(target: LOG_TARGET, "Contacts service shutting down because it received the shutdown signal");
                     break;
@@ -114,6 +151,10 @@ where T: ContactsBackend + 'static
             },
             ContactsServiceRequest::UpsertContact(c) => {
                 self.db.upsert_contact(c.clone()).await?;
+                let node_id = NodeId::from_public_key(&c.public_key);
+                self.liveness_data
+                    .entry(node_id)
+                    .or_insert_with(|| ContactsLivenessData::new(c.public_key.clone()));
                 info!(
                     target: LOG_TARGET,
                     "Contact Saved: \nAlias: {}\nPubKey: {} ", c.alias, c.public_key
@@ -122,6 +163,7 @@ where T: ContactsBackend + 'static
             },
             ContactsServiceRequest::RemoveContact(pk) => {
                 let result = self.db.remove_contact(pk).await?;
+                self.liveness_data.remove(&NodeId::from_public_key(&result.public_key));
                 info!(
                     target: LOG_TARGET,
                     "Contact Removed: \nAlias: {}\nPubKey: {} ", result.alias, result.public_key
@@ -131,6 +173,97 @@ where T: ContactsBackend + 'static
             ContactsServiceRequest::GetContacts => {
                 Ok(self.db.get_contacts().await.map(ContactsServiceResponse::Contacts)?)
             },
+            ContactsServiceRequest::GetContactsLiveness => {
+                let contacts = self.db.get_contacts().await?;
+                let liveness = contacts
+                    .into_iter()
+                    .map(|c| {
+                        self.liveness_data
+                            .get(&NodeId::from_public_key(&c.public_key))
+                            .cloned()
+                            .unwrap_or_else(|| ContactsLivenessData::new(c.public_key))
+                    })
+                    .collect();
+                Ok(ContactsServiceResponse::ContactsLiveness(liveness))
+            },
+        }
+    }
+
+    /// Marks the contacts that have not been seen within the online period as offline, and pings every contact
+    async fn ping_contacts(&mut self) -> Result<(), ContactsServiceError> {
+        let contacts = self.db.get_contacts().await?;
+        let mut liveness_data = HashMap::with_capacity(contacts.len());
+        for contact in contacts {
+            let node_id = NodeId::from_public_key(&contact.public_key);
+            let data = self
+                .liveness_data
+                .remove(&node_id)
+                .unwrap_or_else(|| ContactsLivenessData::new(contact.public_key));
+            liveness_data.insert(node_id, data);
+        }
+        self.liveness_data = liveness_data;
+
+        let now = Utc::now().naive_utc();
+        let online_period =
+            ChronoDuration::from_std(self.config.online_period).unwrap_or_else(|_| ChronoDuration::max_value());
+        for data in self.liveness_data.values_mut() {
+            let online_status = online_status(data.last_seen, now, online_period);
+            if data.online_status != online_status {
+                data.online_status = online_status;
+                let _ = self
+                    .liveness_events
+                    .send(Arc::new(ContactsLivenessEvent::StatusUpdated(Box::new(data.clone()))));
+            }
+        }
+
+        for node_id in self.liveness_data.keys() {
+            if let Err(e) = self.liveness.send_ping(node_id.clone()).await {
+                debug!(target: LOG_TARGET, "Could not ping contact {}: {}", node_id, e);
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_liveness_event(&mut self, event: &LivenessEvent) {
+        match event {
+            LivenessEvent::ReceivedPong(event) => self.on_contact_seen(event),
+            // A contact that pings this wallet is online as well
+            LivenessEvent::ReceivedPing(event) => self.on_contact_seen(event),
+            LivenessEvent::PingRoundBroadcast(_) => (),
+        }
+    }
+
+    fn on_contact_seen(&mut self, event: &PingPongEvent) {
+        let data = match self.liveness_data.get_mut(&event.node_id) {
+            Some(data) => data,
+            None => return,
+        };
+        data.last_seen = Some(Utc::now().naive_utc());
+        if event.latency.is_some() {
+            data.latency = event.latency;
         }
+        data.online_status = ContactOnlineStatus::Online;
+        trace!(
+            target: LOG_TARGET,
+            "Contact {} seen (latency: {:?})",
+            data.public_key,
+            data.latency
+        );
+        let _ = self
+            .liveness_events
+            .send(Arc::new(ContactsLivenessEvent::StatusUpdated(Box::new(data.clone()))));
+    }
+}
+
+fn online_status(
+    last_seen: Option<NaiveDateTime>,
+    now: NaiveDateTime,
+    online_period: ChronoDuration,
+) -> ContactOnlineStatus
+{
+    match last_seen {
+        None => ContactOnlineStatus::NeverSeen,
+        Some(last_seen) if now - last_seen <= online_period => ContactOnlineStatus::Online,
+        Some(_) => ContactOnlineStatus::Offline,
     }
 }
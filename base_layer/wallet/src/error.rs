@@ -73,6 +73,8 @@ pub enum WalletError {
     NodeIdError(#[from] NodeIdError),
     #[error("Error performing wallet recovery: '{0}'")]
     WalletRecoveryError(String),
+    #[error("Error scanning for the outputs of a watch-only wallet: '{0}'")]
+    WatchOnlyScanError(String),
     #[error("Shutdown Signal Received")]
     Shutdown,
 }
@@ -131,4 +133,12 @@ pub enum WalletStorageError {
     NoPasswordError,
     #[error("Incorrect password provided for encrypted wallet")]
     IncorrectPassword,
+    #[error("Invalid wallet backup: `{0}`")]
+    InvalidBackup(String),
+    #[error("Passphrase key derivation error: `{0}`")]
+    KdfError(String),
+    #[error("Invalid passphrase KDF parameters: {0}")]
+    InvalidKdfParameters(String),
+    #[error("Could not re-encrypt the wallet database: `{0}`")]
+    CipherRotationError(String),
 }
@@ -0,0 +1,190 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! A single stream of the events of all the wallet services, so that applications do not need to follow the event
+//! stream of each service separately.
+
+use crate::{
+    base_node_service::handle::{BaseNodeEvent, BaseNodeEventReceiver},
+    contacts_service::handle::{ContactsLivenessEvent, ContactsLivenessEventReceiver},
+    output_manager_service::{
+        handle::{OutputManagerEvent, OutputManagerEventReceiver, OutputManagerHandle},
+        service::Balance,
+    },
+    transaction_service::handle::{TransactionEvent, TransactionEventReceiver},
+};
+use futures::{stream::Fuse, StreamExt};
+use log::*;
+use std::sync::Arc;
+use tari_comms::{
+    connectivity::{ConnectivityEvent, ConnectivityEventRx},
+    peer_manager::Peer,
+};
+use tari_shutdown::ShutdownSignal;
+use tokio::sync::broadcast;
+
+const LOG_TARGET: &str = "wallet::events";
+
+/// The number of events that a subscriber can fall behind by before it starts missing events
+pub const WALLET_EVENT_BUFFER_SIZE: usize = 100;
+
+pub type WalletEventSender = broadcast::Sender<Arc<WalletEvent>>;
+pub type WalletEventReceiver = broadcast::Receiver<Arc<WalletEvent>>;
+
+/// A change to the state of the wallet
+#[derive(Debug, Clone)]
+pub enum WalletEvent {
+    /// A step in the lifecycle of a transaction, or of the validation of the transactions
+    Transaction(Arc<TransactionEvent>),
+    /// A step in the validation of the outputs of the wallet
+    OutputManager(Arc<OutputManagerEvent>),
+    /// The base node was changed, or its chain state was updated
+    BaseNode(Arc<BaseNodeEvent>),
+    /// Every wallet service was switched to this base node, and the connection to the previous one was closed
+    BaseNodeChanged(Box<Peer>),
+    /// A peer connected or disconnected, or the connectivity of the wallet changed
+    Connectivity(Arc<ConnectivityEvent>),
+    /// The balance of the wallet is different from when it was last published
+    BalanceChanged(Balance),
+    /// A contact was seen, or its online status changed
+    Contacts(Arc<ContactsLivenessEvent>),
+}
+
+/// Republishes the events of the wallet services as [WalletEvent]s, and publishes the balance of the wallet whenever a
+/// transaction or output event changed it.
+pub struct WalletEventPublisher {
+    transaction_events: Fuse<TransactionEventReceiver>,
+    output_manager_events: Fuse<OutputManagerEventReceiver>,
+    base_node_events: Fuse<BaseNodeEventReceiver>,
+    connectivity_events: Fuse<ConnectivityEventRx>,
+    contacts_liveness_events: Fuse<ContactsLivenessEventReceiver>,
+    output_manager_service: OutputManagerHandle,
+    event_publisher: WalletEventSender,
+    last_balance: Option<Balance>,
+    shutdown_signal: ShutdownSignal,
+}
+
+impl WalletEventPublisher {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        transaction_events: Fuse<TransactionEventReceiver>,
+        output_manager_events: Fuse<OutputManagerEventReceiver>,
+        base_node_events: Fuse<BaseNodeEventReceiver>,
+        connectivity_events: Fuse<ConnectivityEventRx>,
+        contacts_liveness_events: Fuse<ContactsLivenessEventReceiver>,
+        output_manager_service: OutputManagerHandle,
+        event_publisher: WalletEventSender,
+        shutdown_signal: ShutdownSignal,
+    ) -> Self
+    {
+        Self {
+            transaction_events,
+            output_manager_events,
+            base_node_events,
+            connectivity_events,
+            contacts_liveness_events,
+            output_manager_service,
+            event_publisher,
+            last_balance: None,
+            shutdown_signal,
+        }
+    }
+
+    pub async fn run(self) {
+        let Self {
+            mut transaction_events,
+            mut output_manager_events,
+            mut base_node_events,
+            mut connectivity_events,
+            mut contacts_liveness_events,
+            mut output_manager_service,
+            event_publisher,
+            mut last_balance,
+            mut shutdown_signal,
+        } = self;
+
+        info!(target: LOG_TARGET, "Wallet event publisher starting");
+        loop {
+            futures::select! {
+                result = transaction_events.select_next_some() => match result {
+                    Ok(event) => {
+                        publish(&event_publisher, WalletEvent::Transaction(event));
+                        publish_balance_if_changed(&mut output_manager_service, &mut last_balance, &event_publisher).await;
+                    },
+                    Err(e) => debug!(target: LOG_TARGET, "Lagging read on transaction service events: {}", e),
+                },
+                result = output_manager_events.select_next_some() => match result {
+                    Ok(event) => {
+                        publish(&event_publisher, WalletEvent::OutputManager(event));
+                        publish_balance_if_changed(&mut output_manager_service, &mut last_balance, &event_publisher).await;
+                    },
+                    Err(e) => debug!(target: LOG_TARGET, "Lagging read on output manager service events: {}", e),
+                },
+                result = base_node_events.select_next_some() => match result {
+                    Ok(event) => publish(&event_publisher, WalletEvent::BaseNode(event)),
+                    Err(e) => debug!(target: LOG_TARGET, "Lagging read on base node service events: {}", e),
+                },
+                result = connectivity_events.select_next_some() => match result {
+                    Ok(event) => publish(&event_publisher, WalletEvent::Connectivity(event)),
+                    Err(e) => debug!(target: LOG_TARGET, "Lagging read on connectivity events: {}", e),
+                },
+                result = contacts_liveness_events.select_next_some() => match result {
+                    Ok(event) => publish(&event_publisher, WalletEvent::Contacts(event)),
+                    Err(e) => debug!(target: LOG_TARGET, "Lagging read on contacts liveness events: {}", e),
+                },
+                complete => {
+                    info!(target: LOG_TARGET, "Wallet event publisher exiting because all the event streams ended");
+                    break;
+                },
+                _ = shutdown_signal => {
+                    info!(target: LOG_TARGET, "Wallet event publisher shutting down because the shutdown signal was received");
+                    break;
+                },
+            }
+        }
+    }
+}
+
+fn publish(event_publisher: &WalletEventSender, event: WalletEvent) {
+    trace!(target: LOG_TARGET, "Publishing wallet event {:?}", event);
+    // Sending only fails when there are no subscribers, which is not an error
+    let _ = event_publisher.send(Arc::new(event));
+}
+
+async fn publish_balance_if_changed(
+    output_manager_service: &mut OutputManagerHandle,
+    last_balance: &mut Option<Balance>,
+    event_publisher: &WalletEventSender,
+)
+{
+    let balance = match output_manager_service.get_balance().await {
+        Ok(balance) => balance,
+        Err(e) => {
+            warn!(target: LOG_TARGET, "Could not fetch the balance of the wallet: {}", e);
+            return;
+        },
+    };
+    if last_balance.as_ref() != Some(&balance) {
+        *last_balance = Some(balance.clone());
+        publish(event_publisher, WalletEvent::BalanceChanged(balance));
+    }
+}
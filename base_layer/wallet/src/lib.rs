@@ -14,7 +14,9 @@ mod macros;
 pub mod base_node_service;
 pub mod contacts_service;
 pub mod error;
+pub mod events;
 pub mod output_manager_service;
+pub mod scheduler_service;
 pub mod storage;
 pub mod test_utils;
 pub mod transaction_service;
@@ -20,7 +20,9 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use crate::output_manager_service::{key_manager_backend::KeyManagerBackendType, utxo_selection::UtxoSelectionPolicy};
 use std::time::Duration;
+use tari_core::transactions::tari_amount::MicroTari;
 
 #[derive(Clone, Debug)]
 pub struct OutputManagerServiceConfig {
@@ -28,6 +30,18 @@ pub struct OutputManagerServiceConfig {
     pub max_utxo_query_size: usize,
     pub prevent_fee_gt_amount: bool,
     pub peer_dial_retry_timeout: Duration,
+    /// The UTXO selection strategy used when a send does not specify one
+    pub utxo_selection_policy: UtxoSelectionPolicy,
+    /// Outputs below this value are periodically consolidated into a single output, None disables the consolidation
+    pub dust_consolidation_threshold: Option<MicroTari>,
+    /// How often to check for dust outputs to consolidate
+    pub dust_consolidation_interval: Duration,
+    /// The fee per gram paid by a consolidation. It only runs while the mempool is not paying more than this.
+    pub dust_consolidation_fee_per_gram: MicroTari,
+    /// The least number of dust outputs that are worth consolidating
+    pub dust_consolidation_min_outputs: usize,
+    /// Where the master key is kept and the keys are derived
+    pub key_manager_backend: KeyManagerBackendType,
 }
 
 impl Default for OutputManagerServiceConfig {
@@ -37,6 +51,12 @@ impl Default for OutputManagerServiceConfig {
             max_utxo_query_size: 5000,
             prevent_fee_gt_amount: true,
             peer_dial_retry_timeout: Duration::from_secs(20),
+            utxo_selection_policy: UtxoSelectionPolicy::default(),
+            dust_consolidation_threshold: None,
+            dust_consolidation_interval: Duration::from_secs(6 * 60 * 60),
+            dust_consolidation_fee_per_gram: MicroTari::from(5),
+            dust_consolidation_min_outputs: 10,
+            key_manager_backend: KeyManagerBackendType::default(),
         }
     }
 }
@@ -20,11 +20,17 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use crate::{base_node_service::error::BaseNodeServiceError, output_manager_service::storage::database::DbKey};
+use crate::{
+    base_node_service::error::BaseNodeServiceError,
+    output_manager_service::{key_manager_backend::KeyManagerBackendError, storage::database::DbKey},
+};
 use diesel::result::Error as DieselError;
 use tari_comms::{peer_manager::node_id::NodeIdError, protocol::rpc::RpcError};
 use tari_comms_dht::outbound::DhtOutboundError;
 use tari_core::transactions::{
+    htlc::HtlcError,
+    multisig::MultisigError,
+    one_sided::OneSidedPaymentError,
     transaction::TransactionError,
     transaction_protocol::TransactionProtocolError,
     CoinbaseBuildError,
@@ -53,6 +59,8 @@ pub enum OutputManagerError {
     MnemonicError(#[from] MnemonicError),
     #[error("Key manager error: `{0}`")]
     KeyManagerError(#[from] KeyManagerError),
+    #[error("Key manager backend error: `{0}`")]
+    KeyManagerBackendError(#[from] KeyManagerBackendError),
     #[error("Transaction error: `{0}`")]
     TransactionError(#[from] TransactionError),
     #[error("DHT outbound error: `{0}`")]
@@ -65,8 +73,14 @@ pub enum OutputManagerError {
     NotEnoughFunds,
     #[error("Funds are still pending. Unable to fulfil transaction right now.")]
     FundsPending,
+    #[error("The output `{0}` selected for spending is not an unspent output or cannot be spent yet")]
+    SelectedOutputNotSpendable(String),
     #[error("Output already exists")]
     DuplicateOutput,
+    #[error("The output cannot be imported: {0}")]
+    InvalidImportedOutput(String),
+    #[error("The output with commitment `{0}` to be signed for does not belong to this wallet")]
+    OutputNotOwned(String),
     #[error("Error sending a message to the public API")]
     ApiSendFailed,
     #[error("Error receiving a message from the public API")]
@@ -101,6 +115,14 @@ pub enum OutputManagerError {
     RpcError(#[from] RpcError),
     #[error("Node ID error: `{0}`")]
     NodeIdError(#[from] NodeIdError),
+    #[error("Multisig error: `{0}`")]
+    MultisigError(#[from] MultisigError),
+    #[error("One-sided payment error: `{0}`")]
+    OneSidedPaymentError(#[from] OneSidedPaymentError),
+    #[error("No multisig nonce was created for signing session `{0}`, or it has been used already")]
+    MultisigNonceNotFound(u64),
+    #[error("HTLC error: `{0}`")]
+    HtlcError(#[from] HtlcError),
 }
 
 #[derive(Debug, Error, PartialEq)]
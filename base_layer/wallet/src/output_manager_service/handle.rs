@@ -24,24 +24,28 @@ use crate::{
     output_manager_service::{
         error::OutputManagerError,
         protocols::txo_validation_protocol::TxoValidationType,
-        service::Balance,
+        service::{Balance, FeeEstimate},
         storage::database::PendingTransactionOutputs,
+        utxo_selection::UtxoSelectionPolicy,
         TxId,
     },
     types::ValidationRetryStrategy,
 };
 use aes_gcm::Aes256Gcm;
 use futures::{stream::Fuse, StreamExt};
-use std::{collections::HashMap, fmt, sync::Arc, time::Duration};
+use std::{collections::HashMap, fmt, str::FromStr, sync::Arc, time::Duration};
 use tari_comms::types::CommsPublicKey;
 use tari_core::transactions::{
+    htlc::{HashTimeLock, HtlcWitness},
+    multisig::MultisigLock,
     tari_amount::MicroTari,
-    transaction::{Transaction, TransactionInput, TransactionOutput, UnblindedOutput},
+    transaction::{OutputFeatures, Transaction, TransactionInput, TransactionOutput, UnblindedOutput},
     transaction_protocol::sender::TransactionSenderMessage,
-    types::PublicKey,
+    types::{Commitment, PrivateKey, PublicKey, Signature},
     ReceiverTransactionProtocol,
     SenderTransactionProtocol,
 };
+use tari_crypto::tari_utilities::hex::Hex;
 use tari_service_framework::reply_channel::SenderService;
 use tokio::sync::broadcast;
 use tower::Service;
@@ -50,26 +54,48 @@ use tower::Service;
 pub enum OutputManagerRequest {
     GetBalance,
     AddOutput(UnblindedOutput),
+    ImportOutput((PrivateKey, MicroTari, OutputFeatures)),
     GetRecipientTransaction(TransactionSenderMessage),
-    GetCoinbaseTransaction((u64, MicroTari, MicroTari, u64)),
+    GetCoinbaseTransaction((u64, MicroTari, MicroTari, u64, Vec<u8>)),
     ConfirmPendingTransaction(u64),
     ConfirmTransaction((u64, Vec<TransactionInput>, Vec<TransactionOutput>)),
     PrepareToSendTransaction((MicroTari, MicroTari, Option<u64>, String)),
+    PrepareToSendTransactionWithInputs((Vec<Commitment>, MicroTari, MicroTari, Option<u64>, String)),
+    PrepareToSendTransactionWithStrategy((UtxoSelectionPolicy, MicroTari, MicroTari, Option<u64>, String)),
+    PrepareToSendTransactionToMany((Vec<MicroTari>, MicroTari, Option<u64>, String)),
     CreatePayToSelfTransaction((MicroTari, MicroTari, Option<u64>, String)),
+    CreateBurnTransaction((MicroTari, MicroTari, String)),
+    CreateOneSidedTransaction((PublicKey, MicroTari, MicroTari, Option<u64>, String)),
+    AcceptOneSidedOutput((TxId, UnblindedOutput)),
+    SignOneSidedTransaction((Vec<TransactionOutput>, PublicKey, MicroTari, MicroTari, String)),
+    PrepareOneSidedPaymentClaim((Commitment, MicroTari, String)),
+    CreateHtlcTransaction((MicroTari, HashTimeLock, MicroTari, String)),
+    PrepareHtlcSpend((Commitment, MicroTari, String)),
+    GetHtlcPublicKey,
+    SignHtlcWitness(Box<HtlcSigningRequest>),
+    CreateMultisigTransaction((MicroTari, MultisigLock, MicroTari, String)),
+    PrepareMultisigSpend((Commitment, PublicKey, MicroTari, MicroTari, String)),
+    GetMultisigPublicKey,
+    CreateMultisigNonce(u64),
+    SignMultisig(Box<MultisigSigningRequest>),
     CancelTransaction(u64),
     TimeoutTransactions(Duration),
     GetPendingTransactions,
     GetSpentOutputs,
     GetUnspentOutputs,
+    GetAssetOutputs,
+    GetHtlcOutputs,
+    GetMultisigOutputs,
     GetInvalidOutputs,
     GetSeedWords,
     SetBaseNodePublicKey(CommsPublicKey),
     ValidateUtxos(TxoValidationType, ValidationRetryStrategy),
     CreateCoinSplit((MicroTari, usize, MicroTari, Option<u64>)),
+    ConsolidateDust,
     ApplyEncryption(Box<Aes256Gcm>),
     RemoveEncryption,
     GetPublicRewindKeys,
-    FeeEstimate((MicroTari, MicroTari, u64, u64)),
+    EstimateFee((MicroTari, usize, Option<MicroTari>)),
     RewindOutputs(Vec<TransactionOutput>),
 }
 
@@ -79,41 +105,142 @@ impl fmt::Display for OutputManagerRequest {
         match self {
             GetBalance => write!(f, "GetBalance"),
             AddOutput(v) => write!(f, "AddOutput ({})", v.value),
+            ImportOutput((_, v, _)) => write!(f, "ImportOutput ({})", v),
             GetRecipientTransaction(_) => write!(f, "GetRecipientTransaction"),
             ConfirmTransaction(v) => write!(f, "ConfirmTransaction ({})", v.0),
             ConfirmPendingTransaction(v) => write!(f, "ConfirmPendingTransaction ({})", v),
             PrepareToSendTransaction((_, _, _, msg)) => write!(f, "PrepareToSendTransaction ({})", msg),
+            PrepareToSendTransactionWithInputs((inputs, _, _, _, msg)) => write!(
+                f,
+                "PrepareToSendTransactionWithInputs ({} inputs, {})",
+                inputs.len(),
+                msg
+            ),
+            PrepareToSendTransactionWithStrategy((policy, _, _, _, msg)) => {
+                write!(f, "PrepareToSendTransactionWithStrategy ({}, {})", policy, msg)
+            },
+            PrepareToSendTransactionToMany((amounts, _, _, msg)) => write!(
+                f,
+                "PrepareToSendTransactionToMany ({} recipients, {})",
+                amounts.len(),
+                msg
+            ),
             CreatePayToSelfTransaction((_, _, _, msg)) => write!(f, "CreatePayToSelfTransaction ({})", msg),
+            CreateBurnTransaction((amount, _, msg)) => write!(f, "CreateBurnTransaction ({}, {})", amount, msg),
+            CreateOneSidedTransaction((_, amount, _, _, msg)) => {
+                write!(f, "CreateOneSidedTransaction ({}, {})", amount, msg)
+            },
+            AcceptOneSidedOutput((tx_id, output)) => write!(f, "AcceptOneSidedOutput ({}, {})", tx_id, output.value),
+            SignOneSidedTransaction((inputs, _, amount, _, msg)) => write!(
+                f,
+                "SignOneSidedTransaction ({} inputs, {}, {})",
+                inputs.len(),
+                amount,
+                msg
+            ),
+            CreateHtlcTransaction((amount, htlc, _, msg)) => {
+                write!(f, "CreateHtlcTransaction ({}, {}, {})", amount, htlc, msg)
+            },
+            PrepareHtlcSpend((commitment, _, msg)) => write!(f, "PrepareHtlcSpend ({}, {})", commitment.to_hex(), msg),
+            GetHtlcPublicKey => write!(f, "GetHtlcPublicKey"),
+            SignHtlcWitness(request) => write!(f, "SignHtlcWitness ({})", request.commitment.to_hex()),
+            PrepareOneSidedPaymentClaim((commitment, _, msg)) => {
+                write!(f, "PrepareOneSidedPaymentClaim ({}, {})", commitment.to_hex(), msg)
+            },
+            CreateMultisigTransaction((amount, lock, _, msg)) => {
+                write!(f, "CreateMultisigTransaction ({}, {}, {})", amount, lock, msg)
+            },
+            PrepareMultisigSpend((commitment, _, amount, _, msg)) => {
+                write!(f, "PrepareMultisigSpend ({}, {}, {})", commitment.to_hex(), amount, msg)
+            },
+            GetMultisigPublicKey => write!(f, "GetMultisigPublicKey"),
+            CreateMultisigNonce(session_id) => write!(f, "CreateMultisigNonce ({})", session_id),
+            SignMultisig(request) => write!(
+                f,
+                "SignMultisig ({}, {})",
+                request.session_id,
+                request.commitment.to_hex()
+            ),
             CancelTransaction(v) => write!(f, "CancelTransaction ({})", v),
             TimeoutTransactions(d) => write!(f, "TimeoutTransactions ({}s)", d.as_secs()),
             GetPendingTransactions => write!(f, "GetPendingTransactions"),
             GetSpentOutputs => write!(f, "GetSpentOutputs"),
             GetUnspentOutputs => write!(f, "GetUnspentOutputs"),
+            GetAssetOutputs => write!(f, "GetAssetOutputs"),
+            GetHtlcOutputs => write!(f, "GetHtlcOutputs"),
+            GetMultisigOutputs => write!(f, "GetMultisigOutputs"),
             GetInvalidOutputs => write!(f, "GetInvalidOutputs"),
             GetSeedWords => write!(f, "GetSeedWords"),
             SetBaseNodePublicKey(k) => write!(f, "SetBaseNodePublicKey ({})", k),
             ValidateUtxos(validation_type, retry) => write!(f, "{} ({:?})", validation_type, retry),
             CreateCoinSplit(v) => write!(f, "CreateCoinSplit ({})", v.0),
+            ConsolidateDust => write!(f, "ConsolidateDust"),
             ApplyEncryption(_) => write!(f, "ApplyEncryption"),
             RemoveEncryption => write!(f, "RemoveEncryption"),
             GetCoinbaseTransaction(_) => write!(f, "GetCoinbaseTransaction"),
             GetPublicRewindKeys => write!(f, "GetPublicRewindKeys"),
-            FeeEstimate(_) => write!(f, "FeeEstimate"),
+            EstimateFee((amount, num_outputs, _)) => write!(f, "EstimateFee ({}, {} outputs)", amount, num_outputs),
             RewindOutputs(_) => write!(f, "RewindAndImportOutputs"),
         }
     }
 }
 
+/// The data this wallet needs to sign the witness that claims or refunds a hash time-locked output
+#[derive(Debug, Clone)]
+pub struct HtlcSigningRequest {
+    pub htlc: HashTimeLock,
+    /// The preimage of the hash lock to claim the output with, or None to refund it
+    pub preimage: Option<Vec<u8>>,
+    /// The commitment of the hash time-locked output being spent
+    pub commitment: Commitment,
+    /// The excess of the kernel of the spending transaction
+    pub kernel_excess: Commitment,
+}
+
+/// The data this wallet needs to add its partial signature to the spend of a multisig locked output
+#[derive(Debug, Clone)]
+pub struct MultisigSigningRequest {
+    /// The signing session, whose nonce must have been created with `create_multisig_nonce`
+    pub session_id: u64,
+    pub lock: MultisigLock,
+    /// The indices of the participants that sign, which must include this wallet
+    pub signers: Vec<u8>,
+    /// The sum of the public nonces of all the signers
+    pub aggregate_nonce: PublicKey,
+    /// The commitment of the multisig locked output being spent
+    pub commitment: Commitment,
+    /// The excess of the kernel of the spending transaction
+    pub kernel_excess: Commitment,
+}
+
+/// A transaction spending a multisig locked output that still needs the multisig witness before it can be finalized
+#[derive(Debug, Clone)]
+pub struct MultisigSpend {
+    pub stp: SenderTransactionProtocol,
+    /// The one-sided payment output paying the recipient
+    pub recipient_output: UnblindedOutput,
+    /// The change, which is locked by the same multisig lock as the output being spent
+    pub change_output: Option<UnblindedOutput>,
+    /// The ephemeral secret key of the one-sided payment to the recipient
+    pub ephemeral_secret_key: PrivateKey,
+    /// The offset of the transaction
+    pub offset: PrivateKey,
+}
+
 /// API Reply enum
 #[derive(Debug, Clone)]
 pub enum OutputManagerResponse {
     Balance(Balance),
     OutputAdded,
+    OutputImported(Commitment),
     RecipientTransactionGenerated(ReceiverTransactionProtocol),
     CoinbaseTransaction(Transaction),
     OutputConfirmed,
     PendingTransactionConfirmed,
     PayToSelfTransaction((TxId, MicroTari, Transaction)),
+    BurnTransaction((TxId, MicroTari, Transaction)),
+    OneSidedTransaction((TxId, MicroTari, Transaction, UnblindedOutput)),
+    OneSidedOutputAccepted,
     TransactionConfirmed,
     TransactionToSend(SenderTransactionProtocol),
     TransactionCancelled,
@@ -121,15 +248,25 @@ pub enum OutputManagerResponse {
     PendingTransactions(HashMap<u64, PendingTransactionOutputs>),
     SpentOutputs(Vec<UnblindedOutput>),
     UnspentOutputs(Vec<UnblindedOutput>),
+    AssetOutputs(Vec<UnblindedOutput>),
+    HtlcOutputs(Vec<UnblindedOutput>),
+    HtlcPublicKey(PublicKey),
+    HtlcWitness(HtlcWitness),
+    MultisigOutputs(Vec<UnblindedOutput>),
+    MultisigSpend(Box<MultisigSpend>),
+    MultisigPublicKey(PublicKey),
+    MultisigNonce(PublicKey),
+    MultisigPartialSignature(Signature),
     InvalidOutputs(Vec<UnblindedOutput>),
     SeedWords(Vec<String>),
     BaseNodePublicKeySet,
     UtxoValidationStarted(u64),
     Transaction((u64, Transaction, MicroTari, MicroTari)),
+    DustConsolidated(Option<TxId>),
     EncryptionApplied,
     EncryptionRemoved,
     PublicRewindKeys(Box<PublicRewindKeys>),
-    FeeEstimate(MicroTari),
+    EstimatedFee(FeeEstimate),
     RewindOutputs(Vec<UnblindedOutput>),
 }
 
@@ -147,10 +284,57 @@ pub enum OutputManagerEvent {
     Error(String),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct PublicRewindKeys {
     pub rewind_public_key: PublicKey,
     pub rewind_blinding_public_key: PublicKey,
+    /// The public key that both rewind keys were before keys were derived per branch, which outputs received back then
+    /// can only be rewound with
+    pub legacy_rewind_public_key: Option<PublicKey>,
+}
+
+/// The view key of a wallet is its two public rewind keys in hex, one after the other, followed by its legacy rewind
+/// key if it has one. The keys reveal which outputs belong to the wallet and their values, but not their blinding
+/// factors, so they cannot be used to spend them.
+impl fmt::Display for PublicRewindKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            self.rewind_public_key.to_hex(),
+            self.rewind_blinding_public_key.to_hex()
+        )?;
+        if let Some(legacy_rewind_public_key) = &self.legacy_rewind_public_key {
+            write!(f, "{}", legacy_rewind_public_key.to_hex())?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for PublicRewindKeys {
+    type Err = OutputManagerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        // Each public key is 32 bytes, and view keys exported before the legacy rewind key was added to them only have
+        // the first two
+        if (s.len() != 128 && s.len() != 192) || !s.is_ascii() {
+            return Err(OutputManagerError::ConversionError(
+                "A view key must be 128 or 192 hex characters".to_string(),
+            ));
+        }
+        let from_hex =
+            |key: &str| PublicKey::from_hex(key).map_err(|e| OutputManagerError::ConversionError(e.to_string()));
+        let legacy_rewind_public_key = match s.get(128..) {
+            Some(key) if !key.is_empty() => Some(from_hex(key)?),
+            _ => None,
+        };
+        Ok(Self {
+            rewind_public_key: from_hex(&s[..64])?,
+            rewind_blinding_public_key: from_hex(&s[64..128])?,
+            legacy_rewind_public_key,
+        })
+    }
 }
 
 #[derive(Clone)]
@@ -182,6 +366,25 @@ impl OutputManagerHandle {
         }
     }
 
+    /// Import an output that was created outside of the transaction protocols, e.g. by a faucet or in the genesis
+    /// block, from its spending key. Returns the commitment of the output.
+    pub async fn import_output(
+        &mut self,
+        spending_key: PrivateKey,
+        value: MicroTari,
+        features: OutputFeatures,
+    ) -> Result<Commitment, OutputManagerError>
+    {
+        match self
+            .handle
+            .call(OutputManagerRequest::ImportOutput((spending_key, value, features)))
+            .await??
+        {
+            OutputManagerResponse::OutputImported(commitment) => Ok(commitment),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn get_balance(&mut self) -> Result<Balance, OutputManagerError> {
         match self.handle.call(OutputManagerRequest::GetBalance).await?? {
             OutputManagerResponse::Balance(b) => Ok(b),
@@ -211,6 +414,20 @@ impl OutputManagerHandle {
         fees: MicroTari,
         block_height: u64,
     ) -> Result<Transaction, OutputManagerError>
+    {
+        self.get_coinbase_transaction_with_extra(tx_id, reward, fees, block_height, Vec::new())
+            .await
+    }
+
+    /// Get a coinbase transaction whose output carries the given extra data, e.g. a mining pool identifier
+    pub async fn get_coinbase_transaction_with_extra(
+        &mut self,
+        tx_id: TxId,
+        reward: MicroTari,
+        fees: MicroTari,
+        block_height: u64,
+        extra: Vec<u8>,
+    ) -> Result<Transaction, OutputManagerError>
     {
         match self
             .handle
@@ -219,6 +436,7 @@ impl OutputManagerHandle {
                 reward,
                 fees,
                 block_height,
+                extra,
             )))
             .await??
         {
@@ -250,27 +468,101 @@ impl OutputManagerHandle {
         }
     }
 
-    /// Get a fee estimate for an amount of MicroTari, at a specified fee per gram and given number of kernels and
-    /// outputs.
-    pub async fn fee_estimate(
+    /// Prepare a Sender Transaction Protocol that pays each of the `amounts` to a recipient of its own, in a single
+    /// transaction. The TxIds of the recipients are those of the protocol, in the same order as the amounts.
+    pub async fn prepare_transaction_to_send_to_many(
+        &mut self,
+        amounts: Vec<MicroTari>,
+        fee_per_gram: MicroTari,
+        lock_height: Option<u64>,
+        message: String,
+    ) -> Result<SenderTransactionProtocol, OutputManagerError>
+    {
+        match self
+            .handle
+            .call(OutputManagerRequest::PrepareToSendTransactionToMany((
+                amounts,
+                fee_per_gram,
+                lock_height,
+                message,
+            )))
+            .await??
+        {
+            OutputManagerResponse::TransactionToSend(stp) => Ok(stp),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Prepare a Sender Transaction Protocol that spends exactly the unspent outputs with the given commitments,
+    /// instead of letting the service select the outputs. If required a change output will be produced.
+    pub async fn prepare_transaction_to_send_with_inputs(
+        &mut self,
+        inputs: Vec<Commitment>,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        lock_height: Option<u64>,
+        message: String,
+    ) -> Result<SenderTransactionProtocol, OutputManagerError>
+    {
+        match self
+            .handle
+            .call(OutputManagerRequest::PrepareToSendTransactionWithInputs((
+                inputs,
+                amount,
+                fee_per_gram,
+                lock_height,
+                message,
+            )))
+            .await??
+        {
+            OutputManagerResponse::TransactionToSend(stp) => Ok(stp),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Prepare a Sender Transaction Protocol whose inputs are chosen with the given UTXO selection strategy instead of
+    /// the configured one. If required a change output will be produced.
+    pub async fn prepare_transaction_to_send_with_strategy(
         &mut self,
+        policy: UtxoSelectionPolicy,
         amount: MicroTari,
         fee_per_gram: MicroTari,
-        num_kernels: u64,
-        num_outputs: u64,
-    ) -> Result<MicroTari, OutputManagerError>
+        lock_height: Option<u64>,
+        message: String,
+    ) -> Result<SenderTransactionProtocol, OutputManagerError>
     {
         match self
             .handle
-            .call(OutputManagerRequest::FeeEstimate((
+            .call(OutputManagerRequest::PrepareToSendTransactionWithStrategy((
+                policy,
                 amount,
                 fee_per_gram,
-                num_kernels,
-                num_outputs,
+                lock_height,
+                message,
             )))
             .await??
         {
-            OutputManagerResponse::FeeEstimate(fee) => Ok(fee),
+            OutputManagerResponse::TransactionToSend(stp) => Ok(stp),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Estimate the fee of sending an amount of MicroTari to the given number of outputs, accounting for input
+    /// selection, the change output and output metadata. The fee per gram suggested by the base node is used if none
+    /// is given.
+    pub async fn estimate_fee(
+        &mut self,
+        amount: MicroTari,
+        num_outputs: usize,
+        fee_per_gram: Option<MicroTari>,
+    ) -> Result<FeeEstimate, OutputManagerError>
+    {
+        match self
+            .handle
+            .call(OutputManagerRequest::EstimateFee((amount, num_outputs, fee_per_gram)))
+            .await??
+        {
+            OutputManagerResponse::EstimatedFee(estimate) => Ok(estimate),
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
     }
@@ -353,6 +645,22 @@ impl OutputManagerHandle {
         }
     }
 
+    /// Returns the unspent outputs that carry asset tokens. These outputs are never selected to fund transactions.
+    pub async fn get_asset_outputs(&mut self) -> Result<Vec<UnblindedOutput>, OutputManagerError> {
+        match self.handle.call(OutputManagerRequest::GetAssetOutputs).await?? {
+            OutputManagerResponse::AssetOutputs(s) => Ok(s),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Returns the unspent hash time-locked outputs, both the ones locked by this wallet and the ones it can claim
+    pub async fn get_htlc_outputs(&mut self) -> Result<Vec<UnblindedOutput>, OutputManagerError> {
+        match self.handle.call(OutputManagerRequest::GetHtlcOutputs).await?? {
+            OutputManagerResponse::HtlcOutputs(s) => Ok(s),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn get_invalid_outputs(&mut self) -> Result<Vec<UnblindedOutput>, OutputManagerError> {
         match self.handle.call(OutputManagerRequest::GetInvalidOutputs).await?? {
             OutputManagerResponse::InvalidOutputs(s) => Ok(s),
@@ -426,6 +734,15 @@ impl OutputManagerHandle {
         }
     }
 
+    /// Consolidate the dust outputs now instead of waiting for the background job. Returns the TxId of the
+    /// consolidation transaction, or None if there was nothing to consolidate.
+    pub async fn consolidate_dust(&mut self) -> Result<Option<TxId>, OutputManagerError> {
+        match self.handle.call(OutputManagerRequest::ConsolidateDust).await?? {
+            OutputManagerResponse::DustConsolidated(tx_id) => Ok(tx_id),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn apply_encryption(&mut self, cipher: Aes256Gcm) -> Result<(), OutputManagerError> {
         match self
             .handle
@@ -477,4 +794,287 @@ impl OutputManagerHandle {
             _ => Err(OutputManagerError::UnexpectedApiResponse),
         }
     }
+
+    /// Create a transaction that permanently destroys `amount` of this wallet's funds. Returns the transaction id, the
+    /// fee and the finalized transaction, ready to be submitted.
+    pub async fn create_burn_transaction(
+        &mut self,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<(TxId, MicroTari, Transaction), OutputManagerError>
+    {
+        match self
+            .handle
+            .call(OutputManagerRequest::CreateBurnTransaction((
+                amount,
+                fee_per_gram,
+                message,
+            )))
+            .await??
+        {
+            OutputManagerResponse::BurnTransaction(outputs) => Ok(outputs),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Create a transaction that pays `amount` to `recipient` without the recipient taking part. Returns the
+    /// transaction id, the fee, the finalized transaction and the output paying the recipient. The recipient derives
+    /// the keys of that output with its own secret key, so they are not sent to it.
+    pub async fn create_one_sided_transaction(
+        &mut self,
+        recipient: PublicKey,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        lock_height: Option<u64>,
+        message: String,
+    ) -> Result<(TxId, MicroTari, Transaction, UnblindedOutput), OutputManagerError>
+    {
+        match self
+            .handle
+            .call(OutputManagerRequest::CreateOneSidedTransaction((
+                recipient,
+                amount,
+                fee_per_gram,
+                lock_height,
+                message,
+            )))
+            .await??
+        {
+            OutputManagerResponse::OneSidedTransaction(outputs) => Ok(outputs),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Add the output of a one-sided payment made to this wallet as pending, until its transaction is mined
+    pub async fn accept_one_sided_output(
+        &mut self,
+        tx_id: TxId,
+        output: UnblindedOutput,
+    ) -> Result<(), OutputManagerError>
+    {
+        match self
+            .handle
+            .call(OutputManagerRequest::AcceptOneSidedOutput((tx_id, output)))
+            .await??
+        {
+            OutputManagerResponse::OneSidedOutputAccepted => Ok(()),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Create a one-sided transaction like `create_one_sided_transaction`, but spending the given outputs instead of
+    /// the outputs in the database. The outputs are rewound to recover their spending keys, so this wallet can sign
+    /// for outputs that it has never seen, e.g. while it is kept offline.
+    pub async fn sign_one_sided_transaction(
+        &mut self,
+        inputs: Vec<TransactionOutput>,
+        recipient: PublicKey,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<(TxId, MicroTari, Transaction, UnblindedOutput), OutputManagerError>
+    {
+        match self
+            .handle
+            .call(OutputManagerRequest::SignOneSidedTransaction((
+                inputs,
+                recipient,
+                amount,
+                fee_per_gram,
+                message,
+            )))
+            .await??
+        {
+            OutputManagerResponse::OneSidedTransaction(outputs) => Ok(outputs),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Create a one-sided transaction that locks `amount` in an output with the given hash time lock. The output is
+    /// returned to be handed to the claimer, and it is also kept by this wallet to be refunded.
+    pub async fn create_htlc_transaction(
+        &mut self,
+        amount: MicroTari,
+        htlc: HashTimeLock,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<(TxId, MicroTari, Transaction, UnblindedOutput), OutputManagerError>
+    {
+        match self
+            .handle
+            .call(OutputManagerRequest::CreateHtlcTransaction((
+                amount,
+                htlc,
+                fee_per_gram,
+                message,
+            )))
+            .await??
+        {
+            OutputManagerResponse::OneSidedTransaction(outputs) => Ok(outputs),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Create a one-sided transaction that locks `amount` in an output with the given multisig lock, such as a treasury
+    /// output. The output is returned to be handed to the other participants, and it is also kept by this wallet.
+    pub async fn create_multisig_transaction(
+        &mut self,
+        amount: MicroTari,
+        lock: MultisigLock,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<(TxId, MicroTari, Transaction, UnblindedOutput), OutputManagerError>
+    {
+        match self
+            .handle
+            .call(OutputManagerRequest::CreateMultisigTransaction((
+                amount,
+                lock,
+                fee_per_gram,
+                message,
+            )))
+            .await??
+        {
+            OutputManagerResponse::OneSidedTransaction(outputs) => Ok(outputs),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Prepare a transaction that pays `amount` to `destination` out of the multisig locked output with the given
+    /// commitment. The multisig witness has to be attached before the transaction is finalized.
+    pub async fn prepare_multisig_spend(
+        &mut self,
+        commitment: Commitment,
+        destination: PublicKey,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<MultisigSpend, OutputManagerError>
+    {
+        match self
+            .handle
+            .call(OutputManagerRequest::PrepareMultisigSpend((
+                commitment,
+                destination,
+                amount,
+                fee_per_gram,
+                message,
+            )))
+            .await??
+        {
+            OutputManagerResponse::MultisigSpend(spend) => Ok(*spend),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Returns the unspent multisig locked outputs that this wallet is a participant of
+    pub async fn get_multisig_outputs(&mut self) -> Result<Vec<UnblindedOutput>, OutputManagerError> {
+        match self.handle.call(OutputManagerRequest::GetMultisigOutputs).await?? {
+            OutputManagerResponse::MultisigOutputs(s) => Ok(s),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Returns the key with which this wallet takes part in multisig locks
+    pub async fn get_multisig_public_key(&mut self) -> Result<PublicKey, OutputManagerError> {
+        match self.handle.call(OutputManagerRequest::GetMultisigPublicKey).await?? {
+            OutputManagerResponse::MultisigPublicKey(k) => Ok(k),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Create the secret nonce of this wallet for a multisig signing session and return its public nonce, to be shared
+    /// with the other signers
+    pub async fn create_multisig_nonce(&mut self, session_id: u64) -> Result<PublicKey, OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::CreateMultisigNonce(session_id))
+            .await??
+        {
+            OutputManagerResponse::MultisigNonce(k) => Ok(k),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Create the partial signature of this wallet for a multisig signing session. The nonce of the session is used up.
+    pub async fn sign_multisig(&mut self, request: MultisigSigningRequest) -> Result<Signature, OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::SignMultisig(Box::new(request)))
+            .await??
+        {
+            OutputManagerResponse::MultisigPartialSignature(s) => Ok(s),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Returns the key with which this wallet claims or refunds hash time-locked outputs. The claim key of a lock that
+    /// pays this wallet must be this key.
+    pub async fn get_htlc_public_key(&mut self) -> Result<PublicKey, OutputManagerError> {
+        match self.handle.call(OutputManagerRequest::GetHtlcPublicKey).await?? {
+            OutputManagerResponse::HtlcPublicKey(k) => Ok(k),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Sign the witness that claims, with a preimage, or refunds a hash time-locked output with the HTLC key of this
+    /// wallet
+    pub async fn sign_htlc_witness(&mut self, request: HtlcSigningRequest) -> Result<HtlcWitness, OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::SignHtlcWitness(Box::new(request)))
+            .await??
+        {
+            OutputManagerResponse::HtlcWitness(w) => Ok(w),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Prepare a transaction that spends the hash time-locked output with the given commitment back to this wallet.
+    /// The witness of the claim or refund path has to be attached before the transaction is finalized.
+    pub async fn prepare_htlc_spend(
+        &mut self,
+        commitment: Commitment,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<SenderTransactionProtocol, OutputManagerError>
+    {
+        match self
+            .handle
+            .call(OutputManagerRequest::PrepareHtlcSpend((
+                commitment,
+                fee_per_gram,
+                message,
+            )))
+            .await??
+        {
+            OutputManagerResponse::TransactionToSend(stp) => Ok(stp),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Prepare a transaction that claims the one-sided payment output with the given commitment by spending it to this
+    /// wallet. The witness signed with the one-time key of the payment has to be attached before the transaction is
+    /// finalized.
+    pub async fn prepare_one_sided_payment_claim(
+        &mut self,
+        commitment: Commitment,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<SenderTransactionProtocol, OutputManagerError>
+    {
+        match self
+            .handle
+            .call(OutputManagerRequest::PrepareOneSidedPaymentClaim((
+                commitment,
+                fee_per_gram,
+                message,
+            )))
+            .await??
+        {
+            OutputManagerResponse::TransactionToSend(stp) => Ok(stp),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
 }
@@ -0,0 +1,293 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::output_manager_service::key_manager_backend::{KeyManagerBackend, KeyManagerBackendError};
+use hidapi::{HidApi, HidDevice};
+use std::{convert::TryFrom, sync::Mutex};
+use tari_core::transactions::{tari_amount::MicroTari, types::PrivateKey};
+use tari_crypto::tari_utilities::ByteArray;
+use tari_key_manager::key_manager::KeyManagerBranch;
+
+const LEDGER_VENDOR_ID: u16 = 0x2c97;
+const LEDGER_USAGE_PAGE: u16 = 0xffa0;
+
+const HID_PACKET_SIZE: usize = 64;
+const HID_CHANNEL: u16 = 0x0101;
+const HID_TAG_APDU: u8 = 0x05;
+/// Channel, tag and sequence number
+const HID_HEADER_SIZE: usize = 5;
+
+const CLA: u8 = 0x80;
+const INS_DERIVE_KEY: u8 = 0x02;
+const INS_CONFIRM_SEND: u8 = 0x03;
+const SW_OK: u16 = 0x9000;
+const SW_DENIED: u16 = 0x6985;
+/// The fee and the number of amounts take up the first 9 bytes of the 255 that an APDU can carry
+const MAX_CONFIRM_SEND_AMOUNTS: usize = (255 - 9) / 8;
+
+/// Exchanges APDUs with a Ledger device
+pub trait LedgerTransport: Send + Sync {
+    /// Send the command APDU and return the response data and status word
+    fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, KeyManagerBackendError>;
+}
+
+/// The USB HID transport of a Ledger device
+pub struct HidTransport {
+    device: Mutex<HidDevice>,
+}
+
+impl HidTransport {
+    /// Open the first Ledger device that is connected
+    pub fn open() -> Result<Self, KeyManagerBackendError> {
+        let api = HidApi::new().map_err(|e| KeyManagerBackendError::DeviceError(e.to_string()))?;
+        let device = api
+            .device_list()
+            .find(|info| info.vendor_id() == LEDGER_VENDOR_ID && info.usage_page() == LEDGER_USAGE_PAGE)
+            .ok_or_else(|| KeyManagerBackendError::DeviceError("No Ledger device is connected".to_string()))?
+            .open_device(&api)
+            .map_err(|e| KeyManagerBackendError::DeviceError(e.to_string()))?;
+        Ok(Self {
+            device: Mutex::new(device),
+        })
+    }
+}
+
+impl LedgerTransport for HidTransport {
+    fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, KeyManagerBackendError> {
+        let device = self
+            .device
+            .lock()
+            .map_err(|_| KeyManagerBackendError::DeviceError("The device lock is poisoned".to_string()))?;
+        for packet in frame_apdu(apdu)? {
+            // hidapi expects the report id in front of the packet
+            let mut report = Vec::with_capacity(HID_PACKET_SIZE + 1);
+            report.push(0x00);
+            report.extend_from_slice(&packet);
+            device
+                .write(&report)
+                .map_err(|e| KeyManagerBackendError::DeviceError(e.to_string()))?;
+        }
+
+        let mut assembler = ResponseAssembler::default();
+        loop {
+            let mut packet = [0u8; HID_PACKET_SIZE];
+            // Blocks until the device answers, which can take as long as the user takes to confirm on it
+            let read = device
+                .read(&mut packet)
+                .map_err(|e| KeyManagerBackendError::DeviceError(e.to_string()))?;
+            if let Some(response) = assembler.push(&packet[..read])? {
+                return Ok(response);
+            }
+        }
+    }
+}
+
+/// Split an APDU into the HID packets it is sent in. The first packet carries the length of the APDU.
+fn frame_apdu(apdu: &[u8]) -> Result<Vec<[u8; HID_PACKET_SIZE]>, KeyManagerBackendError> {
+    let length = u16::try_from(apdu.len())
+        .map_err(|_| KeyManagerBackendError::DeviceError("The APDU is too long".to_string()))?;
+    let mut data = Vec::with_capacity(apdu.len() + 2);
+    data.extend_from_slice(&length.to_be_bytes());
+    data.extend_from_slice(apdu);
+
+    let mut packets = Vec::new();
+    for (sequence, chunk) in data.chunks(HID_PACKET_SIZE - HID_HEADER_SIZE).enumerate() {
+        let mut packet = [0u8; HID_PACKET_SIZE];
+        packet[0..2].copy_from_slice(&HID_CHANNEL.to_be_bytes());
+        packet[2] = HID_TAG_APDU;
+        packet[3..5].copy_from_slice(&(sequence as u16).to_be_bytes());
+        packet[HID_HEADER_SIZE..HID_HEADER_SIZE + chunk.len()].copy_from_slice(chunk);
+        packets.push(packet);
+    }
+    Ok(packets)
+}
+
+/// Reassembles a response from the HID packets it is received in
+#[derive(Default)]
+struct ResponseAssembler {
+    sequence: u16,
+    expected_length: usize,
+    response: Vec<u8>,
+}
+
+impl ResponseAssembler {
+    /// Add the next packet, returning the response once all of it has been received
+    fn push(&mut self, packet: &[u8]) -> Result<Option<Vec<u8>>, KeyManagerBackendError> {
+        if packet.len() < HID_HEADER_SIZE ||
+            packet[0..2] != HID_CHANNEL.to_be_bytes()[..] ||
+            packet[2] != HID_TAG_APDU ||
+            packet[3..5] != self.sequence.to_be_bytes()[..]
+        {
+            return Err(KeyManagerBackendError::DeviceError(
+                "Unexpected response packet".to_string(),
+            ));
+        }
+        let mut payload = &packet[HID_HEADER_SIZE..];
+        if self.sequence == 0 {
+            if payload.len() < 2 {
+                return Err(KeyManagerBackendError::DeviceError(
+                    "Unexpected response packet".to_string(),
+                ));
+            }
+            self.expected_length = u16::from_be_bytes([payload[0], payload[1]]) as usize;
+            payload = &payload[2..];
+        }
+        self.sequence += 1;
+        self.response.extend_from_slice(payload);
+        if self.response.len() < self.expected_length {
+            return Ok(None);
+        }
+        self.response.truncate(self.expected_length);
+        Ok(Some(std::mem::take(&mut self.response)))
+    }
+}
+
+/// Keeps the master key on a Ledger device running the Tari app. The device derives the key at an index of a branch
+/// the same way [KeyManager](tari_key_manager::key_manager::KeyManager) does and shows every send for confirmation.
+pub struct LedgerKeyManager {
+    transport: Box<dyn LedgerTransport>,
+}
+
+impl LedgerKeyManager {
+    pub fn new(transport: Box<dyn LedgerTransport>) -> Self {
+        Self { transport }
+    }
+
+    /// Use the first Ledger device that is connected
+    pub fn connect() -> Result<Self, KeyManagerBackendError> {
+        Ok(Self::new(Box::new(HidTransport::open()?)))
+    }
+
+    /// Send a command and return its response data, or an error if the status word is not OK
+    fn send_command(&self, ins: u8, data: &[u8]) -> Result<Vec<u8>, KeyManagerBackendError> {
+        let data_length = u8::try_from(data.len())
+            .map_err(|_| KeyManagerBackendError::DeviceError("The command data is too long".to_string()))?;
+        let mut apdu = vec![CLA, ins, 0x00, 0x00, data_length];
+        apdu.extend_from_slice(data);
+
+        let mut response = self.transport.exchange(&apdu)?;
+        if response.len() < 2 {
+            return Err(KeyManagerBackendError::DeviceError(
+                "The response has no status word".to_string(),
+            ));
+        }
+        let status = response.split_off(response.len() - 2);
+        match u16::from_be_bytes([status[0], status[1]]) {
+            SW_OK => Ok(response),
+            SW_DENIED => Err(KeyManagerBackendError::SendRejected),
+            status => Err(KeyManagerBackendError::DeviceError(format!(
+                "The device returned status {:04x}",
+                status
+            ))),
+        }
+    }
+}
+
+impl KeyManagerBackend for LedgerKeyManager {
+    fn derive_key(&self, branch: KeyManagerBranch, index: u64) -> Result<PrivateKey, KeyManagerBackendError> {
+        let mut data = index.to_be_bytes().to_vec();
+        data.extend_from_slice(branch.label().as_bytes());
+        let key = self.send_command(INS_DERIVE_KEY, &data)?;
+        Ok(PrivateKey::from_bytes(&key)?)
+    }
+
+    fn seed_words(&self) -> Result<Vec<String>, KeyManagerBackendError> {
+        Err(KeyManagerBackendError::SeedWordsUnavailable)
+    }
+
+    fn confirm_send(&self, amounts: &[MicroTari], fee: MicroTari) -> Result<(), KeyManagerBackendError> {
+        if amounts.len() > MAX_CONFIRM_SEND_AMOUNTS {
+            return Err(KeyManagerBackendError::DeviceError(format!(
+                "The device can confirm sends to at most {} recipients",
+                MAX_CONFIRM_SEND_AMOUNTS
+            )));
+        }
+        let mut data = u64::from(fee).to_be_bytes().to_vec();
+        data.push(amounts.len() as u8);
+        for amount in amounts {
+            data.extend_from_slice(&u64::from(*amount).to_be_bytes());
+        }
+        self.send_command(INS_CONFIRM_SEND, &data)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct MockTransport {
+        status: u16,
+        data: Vec<u8>,
+    }
+
+    impl LedgerTransport for MockTransport {
+        fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, KeyManagerBackendError> {
+            assert_eq!(apdu[0], CLA);
+            assert_eq!(apdu[4] as usize, apdu.len() - 5);
+            let mut response = self.data.clone();
+            response.extend_from_slice(&self.status.to_be_bytes());
+            Ok(response)
+        }
+    }
+
+    #[test]
+    fn test_hid_framing_round_trip() {
+        let apdu = (0..150u8).collect::<Vec<_>>();
+        let packets = frame_apdu(&apdu).unwrap();
+        assert_eq!(packets.len(), 3);
+
+        let mut assembler = ResponseAssembler::default();
+        assert!(assembler.push(&packets[0]).unwrap().is_none());
+        assert!(assembler.push(&packets[1]).unwrap().is_none());
+        assert_eq!(assembler.push(&packets[2]).unwrap().unwrap(), apdu);
+
+        let mut assembler = ResponseAssembler::default();
+        assert!(assembler.push(&packets[1]).is_err());
+    }
+
+    #[test]
+    fn test_ledger_status_words() {
+        let key = vec![1u8; 32];
+        let ledger = LedgerKeyManager::new(Box::new(MockTransport {
+            status: SW_OK,
+            data: key.clone(),
+        }));
+        assert_eq!(
+            ledger.derive_key(KeyManagerBranch::Spend, 1).unwrap(),
+            PrivateKey::from_bytes(&key).unwrap()
+        );
+        assert!(matches!(
+            ledger.seed_words(),
+            Err(KeyManagerBackendError::SeedWordsUnavailable)
+        ));
+
+        let ledger = LedgerKeyManager::new(Box::new(MockTransport {
+            status: SW_DENIED,
+            data: vec![],
+        }));
+        assert!(matches!(
+            ledger.confirm_send(&[MicroTari::from(100)], MicroTari::from(5)),
+            Err(KeyManagerBackendError::SendRejected)
+        ));
+    }
+}
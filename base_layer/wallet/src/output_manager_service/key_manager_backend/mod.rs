@@ -0,0 +1,121 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! The key manager backend holds the wallet's master key and derives the keys of the branches in the
+//! [KeyManagerBranch] path from it. The default [SoftwareKeyManager] keeps the master key in the wallet database. With
+//! the `ledger` feature the [LedgerKeyManager] keeps it on a Ledger device instead: the seed words never leave the
+//! device and every send has to be confirmed on its screen. The derived spending keys are still handed to the wallet,
+//! since they are the blinding factors of the commitments and the range proofs are built in the wallet.
+
+#[cfg(feature = "ledger")]
+mod ledger;
+mod software;
+
+#[cfg(feature = "ledger")]
+pub use ledger::{HidTransport, LedgerKeyManager, LedgerTransport};
+pub use software::SoftwareKeyManager;
+
+use std::str::FromStr;
+use tari_core::transactions::{tari_amount::MicroTari, types::PrivateKey};
+use tari_crypto::tari_utilities::ByteArrayError;
+use tari_key_manager::{key_manager::KeyManagerBranch, mnemonic::MnemonicError};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum KeyManagerBackendError {
+    #[error("Byte array error: `{0}`")]
+    ByteArrayError(#[from] ByteArrayError),
+    #[error("Mnemonic error: `{0}`")]
+    MnemonicError(#[from] MnemonicError),
+    #[error("The seed words are kept on the hardware wallet and can only be shown there")]
+    SeedWordsUnavailable,
+    #[error("The transaction was rejected on the hardware wallet")]
+    SendRejected,
+    #[error("Hardware wallet error: `{0}`")]
+    DeviceError(String),
+    #[error("Invalid key manager backend: {0}")]
+    InvalidBackend(String),
+}
+
+/// The kinds of key manager backend the wallet can be configured with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyManagerBackendType {
+    /// The master key is kept in the wallet database, see [SoftwareKeyManager]
+    Software,
+    /// The master key is kept on the first Ledger device found, see [LedgerKeyManager]
+    #[cfg(feature = "ledger")]
+    Ledger,
+}
+
+impl Default for KeyManagerBackendType {
+    fn default() -> Self {
+        KeyManagerBackendType::Software
+    }
+}
+
+impl FromStr for KeyManagerBackendType {
+    type Err = KeyManagerBackendError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "software" => Ok(KeyManagerBackendType::Software),
+            #[cfg(feature = "ledger")]
+            "ledger" => Ok(KeyManagerBackendType::Ledger),
+            #[cfg(not(feature = "ledger"))]
+            "ledger" => Err(KeyManagerBackendError::InvalidBackend(
+                "this wallet was built without the `ledger` feature".to_string(),
+            )),
+            other => Err(KeyManagerBackendError::InvalidBackend(format!(
+                "`{}` is not one of \"Software\" or \"Ledger\"",
+                other
+            ))),
+        }
+    }
+}
+
+/// Derives the wallet's keys and approves its sends
+pub trait KeyManagerBackend: Send + Sync {
+    /// The private key at `index` of the given branch
+    fn derive_key(&self, branch: KeyManagerBranch, index: u64) -> Result<PrivateKey, KeyManagerBackendError>;
+
+    /// The seed words the master key can be restored from
+    fn seed_words(&self) -> Result<Vec<String>, KeyManagerBackendError>;
+
+    /// Approve a send of the given amounts, and the fee paid for it, before the wallet encumbers any outputs for it.
+    /// Returns [KeyManagerBackendError::SendRejected] if the user did not approve it.
+    fn confirm_send(&self, amounts: &[MicroTari], fee: MicroTari) -> Result<(), KeyManagerBackendError>;
+}
+
+/// Create the configured key manager backend. The master key and spend branch seed of the wallet database are only
+/// used by the software backend.
+pub fn create_key_manager_backend(
+    backend_type: KeyManagerBackendType,
+    master_key: PrivateKey,
+    spend_branch_seed: String,
+) -> Result<Box<dyn KeyManagerBackend>, KeyManagerBackendError>
+{
+    match backend_type {
+        KeyManagerBackendType::Software => Ok(Box::new(SoftwareKeyManager::new(master_key, spend_branch_seed))),
+        #[cfg(feature = "ledger")]
+        KeyManagerBackendType::Ledger => Ok(Box::new(LedgerKeyManager::connect()?)),
+    }
+}
@@ -0,0 +1,107 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::{
+    output_manager_service::key_manager_backend::{KeyManagerBackend, KeyManagerBackendError},
+    types::KeyDigest,
+};
+use tari_core::transactions::{tari_amount::MicroTari, types::PrivateKey};
+use tari_key_manager::{
+    key_manager::{KeyManager, KeyManagerBranch},
+    mnemonic::{from_secret_key, MnemonicLanguage},
+};
+
+/// Derives the keys in the wallet from the master key stored in its database
+pub struct SoftwareKeyManager {
+    master_key: PrivateKey,
+    /// The label of the spend branch, which is stored with the master key
+    spend_branch_seed: String,
+}
+
+impl SoftwareKeyManager {
+    pub fn new(master_key: PrivateKey, spend_branch_seed: String) -> Self {
+        Self {
+            master_key,
+            spend_branch_seed,
+        }
+    }
+}
+
+impl KeyManagerBackend for SoftwareKeyManager {
+    fn derive_key(&self, branch: KeyManagerBranch, index: u64) -> Result<PrivateKey, KeyManagerBackendError> {
+        let branch_seed = match branch {
+            KeyManagerBranch::Spend => self.spend_branch_seed.clone(),
+            branch => branch.label().to_string(),
+        };
+        let key_manager = KeyManager::<PrivateKey, KeyDigest>::from(self.master_key.clone(), branch_seed, 0);
+        Ok(key_manager.derive_key(index)?.k)
+    }
+
+    fn seed_words(&self) -> Result<Vec<String>, KeyManagerBackendError> {
+        Ok(from_secret_key(&self.master_key, &MnemonicLanguage::English)?)
+    }
+
+    fn confirm_send(&self, _amounts: &[MicroTari], _fee: MicroTari) -> Result<(), KeyManagerBackendError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::OsRng;
+    use tari_crypto::keys::SecretKey;
+    use tari_key_manager::mnemonic::Mnemonic;
+
+    #[test]
+    fn test_software_key_manager_matches_key_manager() {
+        let master_key = PrivateKey::random(&mut OsRng);
+        let backend = SoftwareKeyManager::new(master_key.clone(), "".to_string());
+
+        let mut key_manager = KeyManager::<PrivateKey, KeyDigest>::from(master_key.clone(), "".to_string(), 0);
+        for index in 1..5 {
+            assert_eq!(
+                backend.derive_key(KeyManagerBranch::Spend, index).unwrap(),
+                key_manager.next_key().unwrap().k
+            );
+        }
+        for branch in &[
+            KeyManagerBranch::Coinbase,
+            KeyManagerBranch::RecoveryViewOnly,
+            KeyManagerBranch::RecoveryBlinding,
+            KeyManagerBranch::Multisig,
+            KeyManagerBranch::Htlc,
+        ] {
+            let key_manager = KeyManager::<PrivateKey, KeyDigest>::from_branch(master_key.clone(), *branch, 0);
+            assert_eq!(
+                backend.derive_key(*branch, 7).unwrap(),
+                key_manager.derive_key(7).unwrap().k
+            );
+        }
+
+        let seed_words = backend.seed_words().unwrap();
+        assert_eq!(PrivateKey::from_mnemonic(&seed_words).unwrap(), master_key);
+        assert!(backend
+            .confirm_send(&[MicroTari::from(100)], MicroTari::from(5))
+            .is_ok());
+    }
+}
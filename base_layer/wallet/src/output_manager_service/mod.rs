@@ -48,10 +48,12 @@ use tokio::sync::broadcast;
 pub mod config;
 pub mod error;
 pub mod handle;
+pub mod key_manager_backend;
 pub mod protocols;
 #[allow(unused_assignments)]
 pub mod service;
 pub mod storage;
+pub mod utxo_selection;
 
 const LOG_TARGET: &str = "wallet::output_manager_service::initializer";
 
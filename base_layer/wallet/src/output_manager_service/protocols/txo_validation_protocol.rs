@@ -428,6 +428,12 @@ where TBackend: OutputManagerBackend + 'static
             })?;
             returned_outputs.push(output);
         }
+        // Base nodes that do not report mined heights return none at all
+        let mined_heights = if batch_response.mined_heights.len() == returned_outputs.len() {
+            batch_response.mined_heights.as_slice()
+        } else {
+            &[]
+        };
 
         // complete validation
         match self.validation_type {
@@ -447,11 +453,30 @@ where TBackend: OutputManagerBackend + 'static
                     }
                 }
 
-                // Go through all the returned UTXOs and if they are in the hashmap remove them
-                for output in returned_outputs.iter() {
+                // Go through all the returned UTXOs and if they are in the hashmap remove them. Record the height
+                // that relatively time-locked outputs were mined at, so that coin selection knows when they become
+                // spendable. This is the only source of the height for imported and recovered outputs.
+                for (i, output) in returned_outputs.iter().enumerate() {
                     let response_hash = output.hash();
 
-                    let _ = output_hashes.remove(&response_hash);
+                    let uo = match output_hashes.remove(&response_hash) {
+                        Some(uo) => uo,
+                        None => continue,
+                    };
+                    if let Some(&mined_height) = mined_heights.get(i) {
+                        if uo.unblinded_output.features.relative_maturity > 0 && uo.mined_height != Some(mined_height) {
+                            self.resources
+                                .db
+                                .set_output_mined_height(uo.commitment.clone(), mined_height)
+                                .await
+                                .map_err(|e| {
+                                    OutputManagerProtocolError::new(
+                                        self.id,
+                                        OutputManagerError::OutputManagerStorageError(e),
+                                    )
+                                })?;
+                        }
+                    }
                 }
 
                 // If there are any remaining Unspent Outputs we will move them to the invalid collection
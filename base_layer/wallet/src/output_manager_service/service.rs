@@ -25,31 +25,40 @@ use crate::{
     output_manager_service::{
         config::OutputManagerServiceConfig,
         error::{OutputManagerError, OutputManagerProtocolError},
-        handle::{OutputManagerEventSender, OutputManagerRequest, OutputManagerResponse, PublicRewindKeys},
+        handle::{
+            HtlcSigningRequest,
+            MultisigSigningRequest,
+            MultisigSpend,
+            OutputManagerEventSender,
+            OutputManagerRequest,
+            OutputManagerResponse,
+            PublicRewindKeys,
+        },
+        key_manager_backend::{create_key_manager_backend, KeyManagerBackend},
         protocols::txo_validation_protocol::{TxoValidationProtocol, TxoValidationType},
         storage::{
             database::{KeyManagerState, OutputManagerBackend, OutputManagerDatabase, PendingTransactionOutputs},
             models::DbUnblindedOutput,
         },
+        utxo_selection::{SelectionTarget, UtxoSelectionPolicy},
         TxId,
     },
     transaction_service::handle::TransactionServiceHandle,
-    types::{HashDigest, KeyDigest, ValidationRetryStrategy},
+    types::{HashDigest, ValidationRetryStrategy, DEFAULT_FEE_PER_GRAM},
 };
 use futures::{pin_mut, stream::FuturesUnordered, StreamExt};
 use log::*;
 use rand::{rngs::OsRng, RngCore};
-use std::{
-    cmp::Ordering,
-    collections::HashMap,
-    fmt::{self, Display},
-    time::Duration,
-};
+use std::{cmp, collections::HashMap, fmt, time::Duration};
 use tari_comms::{connectivity::ConnectivityRequester, types::CommsPublicKey};
 use tari_core::{
     consensus::ConsensusConstants,
+    mempool::MempoolFeeStats,
     transactions::{
         fee::Fee,
+        htlc::{HashTimeLock, HtlcWitness, HTLC_WITNESS_WEIGHT},
+        multisig::MultisigLock,
+        one_sided::{OneSidedPayment, CLAIM_WITNESS_STACK_ITEMS},
         tari_amount::MicroTari,
         transaction::{
             KernelFeatures,
@@ -58,9 +67,10 @@ use tari_core::{
             TransactionInput,
             TransactionOutput,
             UnblindedOutput,
+            MINIMUM_TRANSACTION_FEE,
         },
         transaction_protocol::{sender::TransactionSenderMessage, RewindData},
-        types::{CryptoFactories, PrivateKey, PublicKey},
+        types::{Commitment, CryptoFactories, PrivateKey, PublicKey, Signature},
         CoinbaseBuilder,
         ReceiverTransactionProtocol,
         SenderTransactionProtocol,
@@ -69,25 +79,25 @@ use tari_core::{
 use tari_crypto::{
     keys::{PublicKey as PublicKeyTrait, SecretKey as SecretKeyTrait},
     range_proof::REWIND_USER_MESSAGE_LENGTH,
-    tari_utilities::hex::Hex,
-};
-use tari_key_manager::{
-    key_manager::KeyManager,
-    mnemonic::{from_secret_key, MnemonicLanguage},
+    tari_utilities::{hex::Hex, ByteArray},
 };
+use tari_key_manager::key_manager::KeyManagerBranch;
 use tari_service_framework::reply_channel;
 use tari_shutdown::ShutdownSignal;
 use tokio::{
     sync::{broadcast, Mutex},
     task::JoinHandle,
+    time::{self, Instant},
 };
 
 const LOG_TARGET: &str = "wallet::output_manager_service";
 const LOG_TARGET_STRESS: &str = "stress_test::output_manager_service";
 
-const KEY_MANAGER_COINBASE_BRANCH_KEY: &str = "coinbase";
-const KEY_MANAGER_RECOVERY_VIEWONLY_BRANCH_KEY: &str = "recovery_viewonly";
-const KEY_MANAGER_RECOVERY_BLINDING_BRANCH_KEY: &str = "recovery_blinding";
+/// The most dust outputs spent by a single consolidation transaction
+const MAX_DUST_CONSOLIDATION_INPUTS: usize = 500;
+
+/// How far past the current key index the spending keys of recovered outputs are looked for
+const RECOVERY_KEY_INDEX_LOOKAHEAD: u64 = 1000;
 
 /// This service will manage a wallet's available outputs and the key manager that produces the keys for these outputs.
 /// The service will assemble transactions to be sent from the wallets available outputs and provide keys to receive
@@ -97,8 +107,17 @@ pub struct OutputManagerService<TBackend>
 where TBackend: OutputManagerBackend + 'static
 {
     resources: OutputManagerResources<TBackend>,
-    key_manager: Mutex<KeyManager<PrivateKey, KeyDigest>>,
-    coinbase_key_manager: Mutex<KeyManager<PrivateKey, KeyDigest>>,
+    key_manager: Box<dyn KeyManagerBackend>,
+    /// The index of the last spending key handed out from the spend branch
+    spend_key_index: Mutex<u64>,
+    /// The key this wallet uses as its share of the multisig locks it takes part in
+    multisig_secret_key: PrivateKey,
+    /// Single-use signing nonces of the multisig signing sessions in progress, by session id
+    multisig_nonces: HashMap<u64, PrivateKey>,
+    /// The key this wallet claims and refunds hash time-locked outputs with
+    htlc_secret_key: PrivateKey,
+    /// The rewind keys of outputs received before keys were derived per branch
+    legacy_rewind_data: RewindData,
     request_stream:
         Option<reply_channel::Receiver<OutputManagerRequest, Result<OutputManagerResponse, OutputManagerError>>>,
     base_node_update_publisher: broadcast::Sender<CommsPublicKey>,
@@ -139,31 +158,24 @@ where TBackend: OutputManagerBackend + 'static
             Some(km) => km,
         };
 
-        let coinbase_key_manager = KeyManager::<PrivateKey, KeyDigest>::from(
-            key_manager_state.master_key.clone(),
-            KEY_MANAGER_COINBASE_BRANCH_KEY.to_string(),
-            0,
-        );
-
-        let key_manager = KeyManager::<PrivateKey, KeyDigest>::from(
-            key_manager_state.master_key.clone(),
-            key_manager_state.branch_seed,
-            key_manager_state.primary_key_index,
-        );
-
-        let rewind_key_manager = KeyManager::<PrivateKey, KeyDigest>::from(
-            key_manager_state.master_key.clone(),
-            KEY_MANAGER_RECOVERY_VIEWONLY_BRANCH_KEY.to_string(),
-            0,
-        );
-        let rewind_key = rewind_key_manager.derive_key(0)?.k;
-
-        let rewind_blinding_key_manager = KeyManager::<PrivateKey, KeyDigest>::from(
+        let key_manager = create_key_manager_backend(
+            config.key_manager_backend,
             key_manager_state.master_key,
-            KEY_MANAGER_RECOVERY_BLINDING_BRANCH_KEY.to_string(),
-            0,
-        );
-        let rewind_blinding_key = rewind_blinding_key_manager.derive_key(0)?.k;
+            key_manager_state.branch_seed,
+        )?;
+        let rewind_key = key_manager.derive_key(KeyManagerBranch::RecoveryViewOnly, 0)?;
+        let multisig_secret_key = key_manager.derive_key(KeyManagerBranch::Multisig, 0)?;
+        let htlc_secret_key = key_manager.derive_key(KeyManagerBranch::Htlc, 0)?;
+        let rewind_blinding_key = key_manager.derive_key(KeyManagerBranch::RecoveryBlinding, 0)?;
+
+        // Before keys were derived per branch both rewind keys were the key at index 0 of the master key, which outputs
+        // received back then can only be rewound with
+        let legacy_rewind_key = key_manager.derive_key(KeyManagerBranch::Spend, 0)?;
+        let legacy_rewind_data = RewindData {
+            rewind_key: legacy_rewind_key.clone(),
+            rewind_blinding_key: legacy_rewind_key,
+            proof_message: [0u8; REWIND_USER_MESSAGE_LENGTH],
+        };
 
         let rewind_data = RewindData {
             rewind_key,
@@ -192,8 +204,12 @@ where TBackend: OutputManagerBackend + 'static
 
         Ok(OutputManagerService {
             resources,
-            key_manager: Mutex::new(key_manager),
-            coinbase_key_manager: Mutex::new(coinbase_key_manager),
+            key_manager,
+            spend_key_index: Mutex::new(key_manager_state.primary_key_index),
+            multisig_secret_key,
+            multisig_nonces: HashMap::new(),
+            htlc_secret_key,
+            legacy_rewind_data,
             request_stream: Some(request_stream),
             base_node_update_publisher,
             base_node_service,
@@ -213,6 +229,13 @@ where TBackend: OutputManagerBackend + 'static
         let mut txo_validation_handles: FuturesUnordered<JoinHandle<Result<u64, OutputManagerProtocolError>>> =
             FuturesUnordered::new();
 
+        let dust_consolidation_interval = self.resources.config.dust_consolidation_interval;
+        let mut dust_consolidation_ticks = time::interval_at(
+            Instant::now() + dust_consolidation_interval,
+            dust_consolidation_interval,
+        )
+        .fuse();
+
         info!(target: LOG_TARGET, "Output Manager Service started");
         loop {
             futures::select! {
@@ -235,6 +258,13 @@ where TBackend: OutputManagerBackend + 'static
                         Err(e) => error!(target: LOG_TARGET, "Error resolving TXO Validation protocol: {:?}", e),
                     };
                 }
+                _ = dust_consolidation_ticks.select_next_some() => {
+                    if self.resources.config.dust_consolidation_threshold.is_some() {
+                        if let Err(e) = self.consolidate_dust().await {
+                            warn!(target: LOG_TARGET, "Error consolidating dust outputs: {}", e);
+                        }
+                    }
+                }
                 _ = shutdown => {
                     info!(target: LOG_TARGET, "Output manager service shutting down because it received the shutdown signal");
                     break;
@@ -261,6 +291,27 @@ where TBackend: OutputManagerBackend + 'static
             OutputManagerRequest::AddOutput(uo) => {
                 self.add_output(uo).await.map(|_| OutputManagerResponse::OutputAdded)
             },
+            OutputManagerRequest::ImportOutput((spending_key, value, features)) => {
+                let is_relatively_time_locked = features.relative_maturity > 0;
+                let commitment = self.import_output(spending_key, value, features).await?;
+                // The output is only spendable once its mined height is known, which the validation fetches from the
+                // base node
+                if is_relatively_time_locked {
+                    if let Err(e) = self.validate_outputs(
+                        TxoValidationType::Unspent,
+                        ValidationRetryStrategy::UntilSuccess,
+                        txo_validation_handles,
+                    ) {
+                        warn!(
+                            target: LOG_TARGET,
+                            "Could not start the validation of imported output {}: {}",
+                            commitment.to_hex(),
+                            e
+                        );
+                    }
+                }
+                Ok(OutputManagerResponse::OutputImported(commitment))
+            },
             OutputManagerRequest::GetBalance => {
                 let current_chain_tip = match self.base_node_service.get_chain_metadata().await {
                     Ok(metadata) => metadata.map(|m| m.height_of_longest_chain()),
@@ -274,22 +325,104 @@ where TBackend: OutputManagerBackend + 'static
                 .get_recipient_transaction(tsm)
                 .await
                 .map(OutputManagerResponse::RecipientTransactionGenerated),
-            OutputManagerRequest::GetCoinbaseTransaction((tx_id, reward, fees, block_height)) => self
-                .get_coinbase_transaction(tx_id, reward, fees, block_height)
+            OutputManagerRequest::GetCoinbaseTransaction((tx_id, reward, fees, block_height, extra)) => self
+                .get_coinbase_transaction(tx_id, reward, fees, block_height, extra)
                 .await
                 .map(OutputManagerResponse::CoinbaseTransaction),
             OutputManagerRequest::PrepareToSendTransaction((amount, fee_per_gram, lock_height, message)) => self
-                .prepare_transaction_to_send(amount, fee_per_gram, lock_height, message)
+                .prepare_transaction_to_send(None, None, amount, fee_per_gram, lock_height, message)
+                .await
+                .map(OutputManagerResponse::TransactionToSend),
+            OutputManagerRequest::PrepareToSendTransactionWithInputs((
+                inputs,
+                amount,
+                fee_per_gram,
+                lock_height,
+                message,
+            )) => self
+                .prepare_transaction_to_send(Some(inputs), None, amount, fee_per_gram, lock_height, message)
+                .await
+                .map(OutputManagerResponse::TransactionToSend),
+            OutputManagerRequest::PrepareToSendTransactionWithStrategy((
+                policy,
+                amount,
+                fee_per_gram,
+                lock_height,
+                message,
+            )) => self
+                .prepare_transaction_to_send(None, Some(policy), amount, fee_per_gram, lock_height, message)
+                .await
+                .map(OutputManagerResponse::TransactionToSend),
+            OutputManagerRequest::PrepareToSendTransactionToMany((amounts, fee_per_gram, lock_height, message)) => self
+                .prepare_transaction_to_send_to_many(None, None, amounts, fee_per_gram, lock_height, message)
                 .await
                 .map(OutputManagerResponse::TransactionToSend),
             OutputManagerRequest::CreatePayToSelfTransaction((amount, fee_per_gram, lock_height, message)) => self
                 .create_pay_to_self_transaction(amount, fee_per_gram, lock_height, message)
                 .await
                 .map(OutputManagerResponse::PayToSelfTransaction),
-            OutputManagerRequest::FeeEstimate((amount, fee_per_gram, num_kernels, num_outputs)) => self
-                .fee_estimate(amount, fee_per_gram, num_kernels, num_outputs)
+            OutputManagerRequest::CreateBurnTransaction((amount, fee_per_gram, message)) => self
+                .create_burn_transaction(amount, fee_per_gram, message)
+                .await
+                .map(OutputManagerResponse::BurnTransaction),
+            OutputManagerRequest::CreateOneSidedTransaction((
+                recipient,
+                amount,
+                fee_per_gram,
+                lock_height,
+                message,
+            )) => self
+                .create_one_sided_transaction(recipient, amount, fee_per_gram, lock_height, message)
+                .await
+                .map(OutputManagerResponse::OneSidedTransaction),
+            OutputManagerRequest::SignOneSidedTransaction((inputs, recipient, amount, fee_per_gram, message)) => self
+                .sign_one_sided_transaction(inputs, recipient, amount, fee_per_gram, message)
+                .await
+                .map(OutputManagerResponse::OneSidedTransaction),
+            OutputManagerRequest::PrepareOneSidedPaymentClaim((commitment, fee_per_gram, message)) => self
+                .prepare_one_sided_payment_claim(commitment, fee_per_gram, message)
+                .await
+                .map(OutputManagerResponse::TransactionToSend),
+            OutputManagerRequest::CreateHtlcTransaction((amount, htlc, fee_per_gram, message)) => self
+                .create_htlc_transaction(amount, htlc, fee_per_gram, message)
+                .await
+                .map(OutputManagerResponse::OneSidedTransaction),
+            OutputManagerRequest::PrepareHtlcSpend((commitment, fee_per_gram, message)) => self
+                .prepare_htlc_spend(commitment, fee_per_gram, message)
+                .await
+                .map(OutputManagerResponse::TransactionToSend),
+            OutputManagerRequest::CreateMultisigTransaction((amount, lock, fee_per_gram, message)) => self
+                .create_multisig_transaction(amount, lock, fee_per_gram, message)
+                .await
+                .map(OutputManagerResponse::OneSidedTransaction),
+            OutputManagerRequest::PrepareMultisigSpend((commitment, destination, amount, fee_per_gram, message)) => {
+                self.prepare_multisig_spend(commitment, destination, amount, fee_per_gram, message)
+                    .await
+                    .map(|spend| OutputManagerResponse::MultisigSpend(Box::new(spend)))
+            },
+            OutputManagerRequest::GetHtlcPublicKey => Ok(OutputManagerResponse::HtlcPublicKey(
+                PublicKey::from_secret_key(&self.htlc_secret_key),
+            )),
+            OutputManagerRequest::SignHtlcWitness(request) => {
+                self.sign_htlc_witness(*request).map(OutputManagerResponse::HtlcWitness)
+            },
+            OutputManagerRequest::GetMultisigPublicKey => Ok(OutputManagerResponse::MultisigPublicKey(
+                PublicKey::from_secret_key(&self.multisig_secret_key),
+            )),
+            OutputManagerRequest::CreateMultisigNonce(session_id) => Ok(OutputManagerResponse::MultisigNonce(
+                self.create_multisig_nonce(session_id),
+            )),
+            OutputManagerRequest::SignMultisig(request) => self
+                .sign_multisig(*request)
+                .map(OutputManagerResponse::MultisigPartialSignature),
+            OutputManagerRequest::AcceptOneSidedOutput((tx_id, output)) => self
+                .accept_one_sided_output(tx_id, output)
+                .await
+                .map(|_| OutputManagerResponse::OneSidedOutputAccepted),
+            OutputManagerRequest::EstimateFee((amount, num_outputs, fee_per_gram)) => self
+                .estimate_fee(amount, num_outputs, fee_per_gram)
                 .await
-                .map(OutputManagerResponse::FeeEstimate),
+                .map(OutputManagerResponse::EstimatedFee),
             OutputManagerRequest::ConfirmPendingTransaction(tx_id) => self
                 .confirm_encumberance(tx_id)
                 .await
@@ -328,6 +461,36 @@ where TBackend: OutputManagerBackend + 'static
                     .collect();
                 Ok(OutputManagerResponse::UnspentOutputs(outputs))
             },
+            OutputManagerRequest::GetAssetOutputs => {
+                let outputs = self
+                    .fetch_unspent_outputs()
+                    .await?
+                    .into_iter()
+                    .filter(|o| o.unblinded_output.features.asset.is_some())
+                    .map(|v| v.into())
+                    .collect();
+                Ok(OutputManagerResponse::AssetOutputs(outputs))
+            },
+            OutputManagerRequest::GetHtlcOutputs => {
+                let outputs = self
+                    .fetch_unspent_outputs()
+                    .await?
+                    .into_iter()
+                    .filter(|o| o.unblinded_output.features.is_hash_time_locked())
+                    .map(|v| v.into())
+                    .collect();
+                Ok(OutputManagerResponse::HtlcOutputs(outputs))
+            },
+            OutputManagerRequest::GetMultisigOutputs => {
+                let outputs = self
+                    .fetch_unspent_outputs()
+                    .await?
+                    .into_iter()
+                    .filter(|o| o.unblinded_output.features.is_multisig_locked())
+                    .map(|v| v.into())
+                    .collect();
+                Ok(OutputManagerResponse::MultisigOutputs(outputs))
+            },
             OutputManagerRequest::GetSeedWords => self.get_seed_words().await.map(OutputManagerResponse::SeedWords),
             OutputManagerRequest::SetBaseNodePublicKey(pk) => self
                 .set_base_node_public_key(pk)
@@ -349,6 +512,10 @@ where TBackend: OutputManagerBackend + 'static
                 .create_coin_split(amount_per_split, split_count, fee_per_gram, lock_height)
                 .await
                 .map(OutputManagerResponse::Transaction),
+            OutputManagerRequest::ConsolidateDust => self
+                .consolidate_dust()
+                .await
+                .map(OutputManagerResponse::DustConsolidated),
             OutputManagerRequest::ApplyEncryption(cipher) => self
                 .resources
                 .db
@@ -429,6 +596,41 @@ where TBackend: OutputManagerBackend + 'static
         Ok(self.resources.db.add_unspent_output(output).await?)
     }
 
+    /// Add an output created outside of the transaction protocols to the unspent outputs list. Outputs that need more
+    /// than their spending key to be spent are rejected, as the wallet would not be able to spend them.
+    pub async fn import_output(
+        &mut self,
+        spending_key: PrivateKey,
+        value: MicroTari,
+        features: OutputFeatures,
+    ) -> Result<Commitment, OutputManagerError>
+    {
+        if value == MicroTari::from(0) {
+            return Err(OutputManagerError::InvalidImportedOutput(
+                "the value is zero".to_string(),
+            ));
+        }
+        if features.is_hash_time_locked() || features.is_multisig_locked() || features.is_script_locked() {
+            return Err(OutputManagerError::InvalidImportedOutput(
+                "locked outputs cannot be spent with the spending key alone".to_string(),
+            ));
+        }
+
+        let output = DbUnblindedOutput::from_unblinded_output(
+            UnblindedOutput::new(value, spending_key, Some(features)),
+            &self.resources.factories,
+        )?;
+        let commitment = output.commitment.clone();
+        self.resources.db.add_unspent_output(output).await?;
+        info!(
+            target: LOG_TARGET,
+            "Imported output (Commitment: {}) of value {}",
+            commitment.to_hex(),
+            value
+        );
+        Ok(commitment)
+    }
+
     async fn get_balance(&self, current_chain_tip: Option<u64>) -> Result<Balance, OutputManagerError> {
         let balance = self.resources.db.get_balance(current_chain_tip).await?;
         trace!(target: LOG_TARGET, "Balance: {:?}", balance);
@@ -478,13 +680,15 @@ where TBackend: OutputManagerBackend + 'static
     /// Request a Coinbase transaction for a specific block height. All existing pending transactions with
     /// this blockheight will be cancelled.
     /// The key will be derived from the coinbase specific keychain using the blockheight as an index. The coinbase
-    /// keychain is based on the wallets master_key and the "coinbase" branch.
+    /// keychain is based on the wallets master_key and the "coinbase" branch. The coinbase output carries the given
+    /// extra data, if any.
     async fn get_coinbase_transaction(
         &mut self,
         tx_id: TxId,
         reward: MicroTari,
         fees: MicroTari,
         block_height: u64,
+        extra: Vec<u8>,
     ) -> Result<Transaction, OutputManagerError>
     {
         self.resources
@@ -501,6 +705,7 @@ where TBackend: OutputManagerBackend + 'static
             .with_spend_key(key.clone())
             .with_nonce(nonce)
             .with_rewind_data(self.resources.rewind_data.clone())
+            .with_extra(extra.clone())
             .build_with_reward(&self.resources.consensus_constants, reward)?;
 
         self.resources
@@ -509,8 +714,9 @@ where TBackend: OutputManagerBackend + 'static
                 tx_id,
                 reward + fees,
                 key,
-                OutputFeatures::create_coinbase(
+                OutputFeatures::create_coinbase_with_extra(
                     block_height + self.resources.consensus_constants.coinbase_lock_height(),
+                    extra,
                 ),
                 &self.resources.factories,
                 Some(block_height),
@@ -553,6 +759,8 @@ where TBackend: OutputManagerBackend + 'static
             .db
             .confirm_pending_transaction_outputs(pending_transaction.tx_id)
             .await?;
+        self.record_mined_height(&pending_transaction.outputs_to_be_received)
+            .await?;
 
         debug!(
             target: LOG_TARGET,
@@ -562,64 +770,138 @@ where TBackend: OutputManagerBackend + 'static
         Ok(())
     }
 
-    /// Get a fee estimate for an amount of MicroTari, at a specified fee per gram and given number of kernels and
-    /// outputs.
-    async fn fee_estimate(
+    /// Estimate the fee of sending `amount` to `num_outputs` recipients, selecting the inputs and deciding on a change
+    /// output the same way sending would. The fee is weighed with the consensus weight formula, including the
+    /// metadata of the outputs and a kernel for each recipient. If no fee per gram is given, the one suggested by the
+    /// base node's mempool is used.
+    async fn estimate_fee(
         &mut self,
         amount: MicroTari,
-        fee_per_gram: MicroTari,
-        num_kernels: u64,
-        num_outputs: u64,
-    ) -> Result<MicroTari, OutputManagerError>
+        num_outputs: usize,
+        fee_per_gram: Option<MicroTari>,
+    ) -> Result<FeeEstimate, OutputManagerError>
     {
+        let mempool_fee_stats = self.base_node_service.get_mempool_fee_stats().await?;
+        let suggested_fee_per_gram = suggested_fee_per_gram(mempool_fee_stats.as_ref());
+        let fee_per_gram = fee_per_gram.unwrap_or(suggested_fee_per_gram);
         debug!(
             target: LOG_TARGET,
-            "Getting fee estimate. Amount: {}. Fee per gram: {}. Num kernels: {}. Num outputs: {}",
+            "Estimating fee. Amount: {}. Num outputs: {}. Fee per gram: {} (suggested {})",
             amount,
+            num_outputs,
             fee_per_gram,
-            num_kernels,
-            num_outputs
+            suggested_fee_per_gram
         );
 
-        let (utxos, _, _) = self
-            .select_utxos(amount, fee_per_gram, num_outputs as usize, None)
+        let num_kernels = cmp::max(1, num_outputs);
+        let (utxos, has_change_output, _) = self
+            .select_utxos(amount, fee_per_gram, num_kernels, num_outputs, None)
             .await?;
-        debug!(target: LOG_TARGET, "{} utxos selected.", utxos.len());
+        let num_outputs = num_outputs + if has_change_output { 1 } else { 0 };
+        // Priced with the same fee calculator that sending builds the transaction with
+        let fee_calc = self.get_fee_calc();
+        let metadata_bytes = Fee::default_output_metadata_size(num_outputs);
+        let weight = fee_calc.calculate_weight(num_kernels, utxos.len(), num_outputs, metadata_bytes);
+        let fee = fee_calc.calculate_with_minimum(fee_per_gram, num_kernels, utxos.len(), num_outputs, metadata_bytes);
+        debug!(target: LOG_TARGET, "Fee estimated: {} for weight {}", fee, weight);
+
+        Ok(FeeEstimate {
+            fee,
+            fee_per_gram,
+            suggested_fee_per_gram,
+            num_inputs: utxos.len(),
+            num_outputs,
+            has_change_output,
+            weight,
+        })
+    }
 
-        let fee = Fee::calculate_with_minimum(fee_per_gram, num_kernels as usize, utxos.len(), num_outputs as usize);
+    /// The fee calculator of the weight formula that the consensus rules validate transactions with
+    fn get_fee_calc(&self) -> Fee {
+        Fee::new(*self.resources.consensus_constants.transaction_weight())
+    }
 
-        debug!(target: LOG_TARGET, "Fee calculated: {}", fee);
-        Ok(fee)
+    /// The fee of a transaction with a single kernel and outputs with default features and covenants
+    fn calculate_fee(&self, fee_per_gram: MicroTari, num_inputs: usize, num_outputs: usize) -> MicroTari {
+        self.get_fee_calc().calculate(
+            fee_per_gram,
+            1,
+            num_inputs,
+            num_outputs,
+            Fee::default_output_metadata_size(num_outputs),
+        )
     }
 
     /// Prepare a Sender Transaction Protocol for the amount and fee_per_gram specified. If required a change output
-    /// will be produced.
+    /// will be produced. The outputs with the `inputs` commitments are spent if given, otherwise the outputs are
+    /// selected by the service with the `policy` strategy, or the configured one.
     pub async fn prepare_transaction_to_send(
         &mut self,
+        inputs: Option<Vec<Commitment>>,
+        policy: Option<UtxoSelectionPolicy>,
         amount: MicroTari,
         fee_per_gram: MicroTari,
         lock_height: Option<u64>,
         message: String,
     ) -> Result<SenderTransactionProtocol, OutputManagerError>
     {
+        self.prepare_transaction_to_send_to_many(inputs, policy, vec![amount], fee_per_gram, lock_height, message)
+            .await
+    }
+
+    /// Prepare a Sender Transaction Protocol that pays each of the `amounts` to a recipient of its own, in a single
+    /// transaction with a kernel for each recipient. The outputs are chosen, and the change worked out, as for
+    /// [prepare_transaction_to_send](Self::prepare_transaction_to_send). The outputs are encumbered under the TxId of
+    /// the first recipient.
+    pub async fn prepare_transaction_to_send_to_many(
+        &mut self,
+        inputs: Option<Vec<Commitment>>,
+        policy: Option<UtxoSelectionPolicy>,
+        amounts: Vec<MicroTari>,
+        fee_per_gram: MicroTari,
+        lock_height: Option<u64>,
+        message: String,
+    ) -> Result<SenderTransactionProtocol, OutputManagerError>
+    {
+        let num_recipients = amounts.len();
+        let amount = amounts.iter().sum::<MicroTari>();
         debug!(
             target: LOG_TARGET,
-            "Preparing to send transaction. Amount: {}. Fee per gram: {}. ", amount, fee_per_gram,
+            "Preparing to send transaction. Amount: {}. Recipients: {}. Fee per gram: {}. ",
+            amount,
+            num_recipients,
+            fee_per_gram,
         );
-        let (outputs, _, total) = self.select_utxos(amount, fee_per_gram, 1, None).await?;
+        if num_recipients == 0 {
+            return Err(OutputManagerError::BuildError(
+                "A transaction to send needs at least one recipient".to_string(),
+            ));
+        }
+        let (outputs, total) = match inputs {
+            Some(inputs) => self.select_specified_utxos(inputs, amount, fee_per_gram).await?,
+            None => {
+                let (outputs, _, total) = self
+                    .select_utxos(amount, fee_per_gram, num_recipients, num_recipients, policy)
+                    .await?;
+                (outputs, total)
+            },
+        };
 
         let offset = PrivateKey::random(&mut OsRng);
         let nonce = PrivateKey::random(&mut OsRng);
 
-        let mut builder = SenderTransactionProtocol::builder(1);
+        let mut builder = SenderTransactionProtocol::builder(num_recipients);
         builder
+            .with_transaction_weight(*self.resources.consensus_constants.transaction_weight())
             .with_lock_height(lock_height.unwrap_or(0))
             .with_fee_per_gram(fee_per_gram)
             .with_offset(offset.clone())
             .with_private_nonce(nonce.clone())
-            .with_amount(0, amount)
             .with_message(message)
             .with_prevent_fee_gt_amount(self.resources.config.prevent_fee_gt_amount);
+        for (i, amount) in amounts.iter().enumerate() {
+            builder.with_amount(i, *amount);
+        }
 
         for uo in outputs.iter() {
             builder.with_input(
@@ -636,7 +918,13 @@ where TBackend: OutputManagerBackend + 'static
             amount,
             outputs.len()
         );
-        let fee_without_change = Fee::calculate(fee_per_gram, 1, outputs.len(), 1);
+        let fee_without_change = self.get_fee_calc().calculate(
+            fee_per_gram,
+            num_recipients,
+            outputs.len(),
+            num_recipients,
+            Fee::default_output_metadata_size(num_recipients),
+        );
         let mut change_key: Option<PrivateKey> = None;
         // If the input values > the amount to be sent + fee_without_change then we will need to include a change
         // output
@@ -659,6 +947,8 @@ where TBackend: OutputManagerBackend + 'static
             )?);
         }
 
+        self.key_manager.confirm_send(&amounts, stp.get_fee_amount()?)?;
+
         let tx_id = stp.get_tx_id()?;
         // The Transaction Protocol built successfully so we will pull the unspent outputs out of the unspent list and
         // store them until the transaction times out OR is confirmed
@@ -684,7 +974,7 @@ where TBackend: OutputManagerBackend + 'static
         message: String,
     ) -> Result<(TxId, MicroTari, Transaction), OutputManagerError>
     {
-        let (inputs, _, total) = self.select_utxos(amount, fee_per_gram, 1, None).await?;
+        let (inputs, _, total) = self.select_utxos(amount, fee_per_gram, 1, 1, None).await?;
 
         let offset = PrivateKey::random(&mut OsRng);
         let nonce = PrivateKey::random(&mut OsRng);
@@ -692,6 +982,7 @@ where TBackend: OutputManagerBackend + 'static
         // Create builder with no recipients (other than ourselves)
         let mut builder = SenderTransactionProtocol::builder(0);
         builder
+            .with_transaction_weight(*self.resources.consensus_constants.transaction_weight())
             .with_lock_height(lock_height.unwrap_or(0))
             .with_fee_per_gram(fee_per_gram)
             .with_offset(offset.clone())
@@ -721,7 +1012,7 @@ where TBackend: OutputManagerBackend + 'static
         let mut outputs = vec![utxo];
         let mut change_key = None;
 
-        let fee = Fee::calculate(fee_per_gram, 1, inputs.len(), 1);
+        let fee = self.calculate_fee(fee_per_gram, inputs.len(), 1);
         let change_value = total.saturating_sub(amount).saturating_sub(fee);
         if change_value > 0.into() {
             let key = self.get_next_spend_key().await?;
@@ -761,209 +1052,893 @@ where TBackend: OutputManagerBackend + 'static
         Ok((tx_id, fee, tx))
     }
 
-    /// Confirm that a transaction has finished being negotiated between parties so the short-term encumberance can be
-    /// made official
-    async fn confirm_encumberance(&mut self, tx_id: u64) -> Result<(), OutputManagerError> {
-        self.resources.db.confirm_encumbered_outputs(tx_id).await?;
-
-        Ok(())
-    }
-
-    /// Confirm that a received or sent transaction and its outputs have been detected on the base chain. The inputs and
-    /// outputs are checked to see that they match what the stored PendingTransaction contains. This will
-    /// be called by the Transaction Service which monitors the base chain.
-    async fn confirm_transaction(
+    async fn create_burn_transaction(
         &mut self,
-        tx_id: u64,
-        inputs: &[TransactionInput],
-        outputs: &[TransactionOutput],
-    ) -> Result<(), OutputManagerError>
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<(TxId, MicroTari, Transaction), OutputManagerError>
     {
-        let pending_transaction = self.resources.db.fetch_pending_transaction_outputs(tx_id).await?;
+        // A burn has no outputs other than (possibly) our change
+        let (inputs, _, total) = self.select_utxos(amount, fee_per_gram, 1, 0, None).await?;
 
-        // Check that outputs to be spent can all be found in the provided transaction inputs
-        for output_to_spend in pending_transaction.outputs_to_be_spent.iter() {
-            let input_to_check = output_to_spend
-                .unblinded_output
-                .as_transaction_input(&self.resources.factories.commitment, OutputFeatures::default());
+        let offset = PrivateKey::random(&mut OsRng);
+        let nonce = PrivateKey::random(&mut OsRng);
 
-            if inputs.iter().all(|input| input.commitment != input_to_check.commitment) {
-                return Err(OutputManagerError::IncompleteTransaction(
-                    "outputs to spend are missing",
-                ));
-            }
+        let mut builder = SenderTransactionProtocol::builder(0);
+        builder
+            .with_transaction_weight(*self.resources.consensus_constants.transaction_weight())
+            .with_lock_height(0)
+            .with_fee_per_gram(fee_per_gram)
+            .with_offset(offset)
+            .with_private_nonce(nonce)
+            .with_burn_amount(amount)
+            .with_message(message);
+
+        for uo in &inputs {
+            builder.with_input(
+                uo.unblinded_output.as_transaction_input(
+                    &self.resources.factories.commitment,
+                    uo.unblinded_output.features.clone(),
+                ),
+                uo.unblinded_output.clone(),
+            );
         }
 
-        // Check that outputs to be received can all be found in the provided transaction outputs
-        for output_to_receive in pending_transaction.outputs_to_be_received.iter() {
-            let output_to_check = output_to_receive
-                .unblinded_output
-                .as_transaction_input(&self.resources.factories.commitment, OutputFeatures::default());
+        let mut outputs = Vec::new();
+        let mut change_key = None;
 
-            if outputs
-                .iter()
-                .all(|output| output.commitment != output_to_check.commitment)
-            {
-                return Err(OutputManagerError::IncompleteTransaction(
-                    "outputs to receive are missing",
-                ));
-            }
+        let fee = self.calculate_fee(fee_per_gram, inputs.len(), 1);
+        let change_value = total.saturating_sub(amount).saturating_sub(fee);
+        if change_value > 0.into() {
+            let key = self.get_next_spend_key().await?;
+            change_key = Some(key.clone());
+            builder.with_rewindable_change_secret(key, self.resources.rewind_data.clone());
         }
 
-        self.resources
-            .db
-            .confirm_pending_transaction_outputs(pending_transaction.tx_id)
-            .await?;
+        let mut stp = builder
+            .build::<HashDigest>(&self.resources.factories)
+            .map_err(|e| OutputManagerError::BuildError(e.message))?;
 
-        trace!(target: LOG_TARGET, "Confirm transaction (TxId: {})", tx_id);
+        if let Some(key) = change_key {
+            let change_amount = stp.get_change_amount()?;
+            let change_output = DbUnblindedOutput::rewindable_from_unblinded_output(
+                UnblindedOutput::new(change_amount, key, None),
+                &self.resources.factories,
+                &self.resources.rewind_data,
+            )?;
 
-        Ok(())
-    }
+            outputs.push(change_output);
+        }
 
-    /// Cancel a pending transaction and place the encumbered outputs back into the unspent pool
-    pub async fn cancel_transaction(&mut self, tx_id: u64) -> Result<(), OutputManagerError> {
-        debug!(
+        self.key_manager.confirm_send(&[amount], stp.get_fee_amount()?)?;
+
+        let tx_id = stp.get_tx_id()?;
+        trace!(target: LOG_TARGET, "Encumber burn transaction ({}) outputs.", tx_id);
+        self.resources.db.encumber_outputs(tx_id, inputs, outputs).await?;
+        self.confirm_encumberance(tx_id).await?;
+        let fee = stp.get_fee_amount()?;
+        trace!(
             target: LOG_TARGET,
-            "Cancelling pending transaction outputs for TxId: {}", tx_id
+            "Finalize burn transaction ({}) of {}.",
+            tx_id,
+            amount
         );
-        Ok(self.resources.db.cancel_pending_transaction_outputs(tx_id).await?)
-    }
+        stp.finalize(KernelFeatures::create_burn(), &self.resources.factories)?;
+        let tx = stp.take_transaction()?;
 
-    /// Go through the pending transaction and if any have existed longer than the specified duration, cancel them
-    async fn timeout_pending_transactions(&mut self, period: Duration) -> Result<(), OutputManagerError> {
-        Ok(self.resources.db.timeout_pending_transaction_outputs(period).await?)
+        Ok((tx_id, fee, tx))
     }
 
-    /// Select which unspent transaction outputs to use to send a transaction of the specified amount. Use the specified
-    /// selection strategy to choose the outputs. It also determines if a change output is required.
-    async fn select_utxos(
+    /// Create a transaction that pays `amount` to a one-sided payment output of `recipient`, see
+    /// [OneSidedPayment]. Only the change is expected back; the recipient's output is returned so that its value can
+    /// be recorded, but the recipient derives its keys itself.
+    async fn create_one_sided_transaction(
         &mut self,
+        recipient: PublicKey,
         amount: MicroTari,
         fee_per_gram: MicroTari,
-        output_count: usize,
-        strategy: Option<UTXOSelectionStrategy>,
-    ) -> Result<(Vec<DbUnblindedOutput>, bool, MicroTari), OutputManagerError>
+        lock_height: Option<u64>,
+        message: String,
+    ) -> Result<(TxId, MicroTari, Transaction, UnblindedOutput), OutputManagerError>
     {
-        debug!(
+        let (inputs, _, _) = self.select_utxos(amount, fee_per_gram, 1, 1, None).await?;
+        let unblinded_inputs = inputs.iter().map(|uo| uo.unblinded_output.clone()).collect::<Vec<_>>();
+        let payment = OneSidedPayment::new(&PrivateKey::random(&mut OsRng), &recipient)?;
+        let recipient_output = payment.output(amount);
+        let (mut stp, change_output) = self
+            .build_one_sided_transaction(
+                &unblinded_inputs,
+                recipient_output.clone(),
+                Some(payment.rewind_data().clone()),
+                fee_per_gram,
+                lock_height,
+                message,
+            )
+            .await?;
+        let outputs = change_output.into_iter().collect::<Vec<_>>();
+
+        self.key_manager.confirm_send(&[amount], stp.get_fee_amount()?)?;
+
+        let tx_id = stp.get_tx_id()?;
+        trace!(
             target: LOG_TARGET,
-            "select_utxos amount: {}, fee_per_gram: {}, output_count: {}, strategy: {:?}",
-            amount,
-            fee_per_gram,
-            output_count,
-            strategy
+            "Encumber one-sided transaction ({}) outputs.",
+            tx_id
         );
-        let mut utxos = Vec::new();
-        let mut utxos_total_value = MicroTari::from(0);
-        let mut fee_without_change = MicroTari::from(0);
-        let mut fee_with_change = MicroTari::from(0);
+        self.resources.db.encumber_outputs(tx_id, inputs, outputs).await?;
+        self.confirm_encumberance(tx_id).await?;
+        let fee = stp.get_fee_amount()?;
+        trace!(target: LOG_TARGET, "Finalize one-sided transaction ({}).", tx_id);
+        stp.finalize(KernelFeatures::empty(), &self.resources.factories)?;
+        let tx = stp.take_transaction()?;
 
-        let uo = self.resources.db.fetch_sorted_unspent_outputs().await?;
+        Ok((tx_id, fee, tx, recipient_output))
+    }
 
-        // Attempt to get the chain tip height
-        let chain_metadata = self.base_node_service.get_chain_metadata().await?;
-        let (connected, tip_height) = match &chain_metadata {
-            Some(metadata) => (true, metadata.height_of_longest_chain()),
-            None => (false, 0),
-        };
+    /// Sign a one-sided transaction that spends `outputs`, which are not in the database. Their spending keys are
+    /// recovered by rewinding their range proofs, so every one of them has to belong to this wallet. The outputs are
+    /// not encumbered as they are not known to the database.
+    async fn sign_one_sided_transaction(
+        &mut self,
+        outputs: Vec<TransactionOutput>,
+        recipient: PublicKey,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<(TxId, MicroTari, Transaction, UnblindedOutput), OutputManagerError>
+    {
+        let mut inputs = Vec::with_capacity(outputs.len());
+        for output in outputs {
+            let commitment = output.commitment.to_hex();
+            match self.rewind_outputs(vec![output]).await?.pop() {
+                Some(input) => inputs.push(input),
+                None => return Err(OutputManagerError::OutputNotOwned(commitment)),
+            }
+        }
 
-        // If no strategy was specified and no metadata is available, then make sure to use MaturitythenSmallest
-        let strategy = match (strategy, connected) {
-            (Some(s), _) => Some(s),
-            (None, false) => Some(UTXOSelectionStrategy::MaturityThenSmallest),
-            (None, true) => None, // use the selection heuristic next
-        };
+        let total: MicroTari = inputs.iter().map(|uo| uo.value).sum();
+        if total < amount + self.calculate_fee(fee_per_gram, inputs.len(), 2) {
+            return Err(OutputManagerError::NotEnoughFunds);
+        }
 
-        // If we know the chain height then filter out unspendable UTXOs
-        let num_utxos = uo.len();
-        let uo = if connected {
-            let mature_utxos = uo
-                .into_iter()
-                .filter(|u| u.unblinded_output.features.maturity <= tip_height)
-                .collect::<Vec<DbUnblindedOutput>>();
+        let payment = OneSidedPayment::new(&PrivateKey::random(&mut OsRng), &recipient)?;
+        let recipient_output = payment.output(amount);
+        let (mut stp, _) = self
+            .build_one_sided_transaction(
+                &inputs,
+                recipient_output.clone(),
+                Some(payment.rewind_data().clone()),
+                fee_per_gram,
+                None,
+                message,
+            )
+            .await?;
+        let tx_id = stp.get_tx_id()?;
+        let fee = stp.get_fee_amount()?;
+        trace!(target: LOG_TARGET, "Finalize signed one-sided transaction ({}).", tx_id);
+        stp.finalize(KernelFeatures::empty(), &self.resources.factories)?;
+        let tx = stp.take_transaction()?;
 
-            trace!(
-                target: LOG_TARGET,
-                "Some UTXOs have not matured yet at height {}, filtered {} UTXOs",
-                tip_height,
-                num_utxos - mature_utxos.len()
+        Ok((tx_id, fee, tx, recipient_output))
+    }
+
+    /// Create a one-sided transaction that locks `amount` in a hash time-locked output. The output is also stored as
+    /// an output to be received by this wallet, so that it can be refunded once the refund height is reached. It is
+    /// never selected to fund other transactions.
+    async fn create_htlc_transaction(
+        &mut self,
+        amount: MicroTari,
+        htlc: HashTimeLock,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<(TxId, MicroTari, Transaction, UnblindedOutput), OutputManagerError>
+    {
+        let (inputs, _, _) = self.select_utxos(amount, fee_per_gram, 1, 1, None).await?;
+        let unblinded_inputs = inputs.iter().map(|uo| uo.unblinded_output.clone()).collect::<Vec<_>>();
+        let htlc_output = UnblindedOutput::new(
+            amount,
+            PrivateKey::random(&mut OsRng),
+            Some(OutputFeatures::with_hash_time_lock(htlc)),
+        );
+        let (mut stp, change_output) = self
+            .build_one_sided_transaction(
+                &unblinded_inputs,
+                htlc_output.clone(),
+                None,
+                fee_per_gram,
+                None,
+                message,
+            )
+            .await?;
+        let mut outputs = change_output.into_iter().collect::<Vec<_>>();
+        outputs.push(DbUnblindedOutput::from_unblinded_output(
+            htlc_output.clone(),
+            &self.resources.factories,
+        )?);
+
+        self.key_manager.confirm_send(&[amount], stp.get_fee_amount()?)?;
+
+        let tx_id = stp.get_tx_id()?;
+        trace!(target: LOG_TARGET, "Encumber HTLC transaction ({}) outputs.", tx_id);
+        self.resources.db.encumber_outputs(tx_id, inputs, outputs).await?;
+        self.confirm_encumberance(tx_id).await?;
+        let fee = stp.get_fee_amount()?;
+        trace!(target: LOG_TARGET, "Finalize HTLC transaction ({}).", tx_id);
+        stp.finalize(KernelFeatures::empty(), &self.resources.factories)?;
+        let tx = stp.take_transaction()?;
+
+        Ok((tx_id, fee, tx, htlc_output))
+    }
+
+    /// Create a one-sided transaction that locks `amount` in an m-of-n multisig output. The output is stored as an
+    /// output to be received by this wallet so that it can be tracked, but it is never selected to fund other
+    /// transactions, as spending it needs the signatures of `threshold` participants.
+    async fn create_multisig_transaction(
+        &mut self,
+        amount: MicroTari,
+        lock: MultisigLock,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<(TxId, MicroTari, Transaction, UnblindedOutput), OutputManagerError>
+    {
+        lock.validate()?;
+        let (inputs, _, _) = self.select_utxos(amount, fee_per_gram, 1, 1, None).await?;
+        let unblinded_inputs = inputs.iter().map(|uo| uo.unblinded_output.clone()).collect::<Vec<_>>();
+        let multisig_output = UnblindedOutput::new(
+            amount,
+            PrivateKey::random(&mut OsRng),
+            Some(OutputFeatures::with_multisig_lock(lock)),
+        );
+        let (mut stp, change_output) = self
+            .build_one_sided_transaction(
+                &unblinded_inputs,
+                multisig_output.clone(),
+                None,
+                fee_per_gram,
+                None,
+                message,
+            )
+            .await?;
+        let mut outputs = change_output.into_iter().collect::<Vec<_>>();
+        outputs.push(DbUnblindedOutput::from_unblinded_output(
+            multisig_output.clone(),
+            &self.resources.factories,
+        )?);
+
+        self.key_manager.confirm_send(&[amount], stp.get_fee_amount()?)?;
+
+        let tx_id = stp.get_tx_id()?;
+        trace!(target: LOG_TARGET, "Encumber multisig transaction ({}) outputs.", tx_id);
+        self.resources.db.encumber_outputs(tx_id, inputs, outputs).await?;
+        self.confirm_encumberance(tx_id).await?;
+        let fee = stp.get_fee_amount()?;
+        trace!(target: LOG_TARGET, "Finalize multisig transaction ({}).", tx_id);
+        stp.finalize(KernelFeatures::empty(), &self.resources.factories)?;
+        let tx = stp.take_transaction()?;
+
+        Ok((tx_id, fee, tx, multisig_output))
+    }
+
+    /// Build a transaction spending `inputs` that pays the recipient's output, made rewindable if rewind data is given,
+    /// and any change to a rewindable output of this wallet
+    async fn build_one_sided_transaction(
+        &mut self,
+        inputs: &[UnblindedOutput],
+        recipient_output: UnblindedOutput,
+        recipient_rewind_data: Option<RewindData>,
+        fee_per_gram: MicroTari,
+        lock_height: Option<u64>,
+        message: String,
+    ) -> Result<(SenderTransactionProtocol, Option<DbUnblindedOutput>), OutputManagerError>
+    {
+        let amount = recipient_output.value;
+        let offset = PrivateKey::random(&mut OsRng);
+        let nonce = PrivateKey::random(&mut OsRng);
+
+        // The recipient's output is added by us, so the builder has no recipients to wait for
+        let mut builder = SenderTransactionProtocol::builder(0);
+        builder
+            .with_transaction_weight(*self.resources.consensus_constants.transaction_weight())
+            .with_lock_height(lock_height.unwrap_or(0))
+            .with_fee_per_gram(fee_per_gram)
+            .with_offset(offset)
+            .with_private_nonce(nonce)
+            .with_amount(0, amount)
+            .with_message(message)
+            .with_prevent_fee_gt_amount(self.resources.config.prevent_fee_gt_amount);
+
+        for uo in inputs {
+            builder.with_input(
+                uo.as_transaction_input(&self.resources.factories.commitment, uo.features.clone()),
+                uo.clone(),
             );
+        }
 
-            mature_utxos
-        } else {
-            uo
+        match recipient_rewind_data {
+            Some(rewind_data) => builder.with_rewindable_output(recipient_output, rewind_data),
+            None => builder.with_output(recipient_output),
         };
 
-        // Heuristic for selection strategy: Default to MaturityThenSmallest, but if the amount is greater than
-        // the largest UTXO, use Largest UTXOs first.
-        let strategy = match (strategy, uo.is_empty()) {
-            (Some(s), _) => s,
-            (None, true) => UTXOSelectionStrategy::Smallest,
-            (None, false) => {
-                let largest_utxo = &uo[uo.len() - 1];
-                if amount > largest_utxo.unblinded_output.value {
-                    UTXOSelectionStrategy::Largest
-                } else {
-                    UTXOSelectionStrategy::MaturityThenSmallest
-                }
+        let mut change_key = None;
+
+        let total: MicroTari = inputs.iter().map(|uo| uo.value).sum();
+        let fee = self.calculate_fee(fee_per_gram, inputs.len(), 2);
+        let change_value = total.saturating_sub(amount).saturating_sub(fee);
+        if change_value > 0.into() {
+            let key = self.get_next_spend_key().await?;
+            change_key = Some(key.clone());
+            builder.with_rewindable_change_secret(key, self.resources.rewind_data.clone());
+        }
+
+        let stp = builder
+            .build::<HashDigest>(&self.resources.factories)
+            .map_err(|e| OutputManagerError::BuildError(e.message))?;
+
+        let change_output = match change_key {
+            Some(key) => {
+                let change_amount = stp.get_change_amount()?;
+                Some(DbUnblindedOutput::rewindable_from_unblinded_output(
+                    UnblindedOutput::new(change_amount, key, None),
+                    &self.resources.factories,
+                    &self.resources.rewind_data,
+                )?)
             },
+            None => None,
         };
-        debug!(target: LOG_TARGET, "select_utxos selection strategy: {}", strategy);
-
-        let uo = match strategy {
-            UTXOSelectionStrategy::Smallest => uo,
-            UTXOSelectionStrategy::MaturityThenSmallest => {
-                let mut uo = uo;
-                uo.sort_by(|a, b| {
-                    match a
-                        .unblinded_output
-                        .features
-                        .maturity
-                        .cmp(&b.unblinded_output.features.maturity)
-                    {
-                        Ordering::Equal => a.unblinded_output.value.cmp(&b.unblinded_output.value),
-                        Ordering::Less => Ordering::Less,
-                        Ordering::Greater => Ordering::Greater,
-                    }
-                });
-                uo
+
+        Ok((stp, change_output))
+    }
+
+    /// Add the output of a one-sided payment as an output to be received by the transaction, so that it becomes
+    /// spendable once the transaction is confirmed
+    async fn accept_one_sided_output(
+        &mut self,
+        tx_id: TxId,
+        output: UnblindedOutput,
+    ) -> Result<(), OutputManagerError>
+    {
+        self.resources
+            .db
+            .accept_incoming_pending_transaction(
+                tx_id,
+                output.value,
+                output.spending_key,
+                output.features,
+                &self.resources.factories,
+                None,
+            )
+            .await?;
+
+        self.confirm_encumberance(tx_id).await?;
+
+        Ok(())
+    }
+
+    /// Prepare a transaction that spends the unspent hash time-locked output with the given commitment to a new output
+    /// of this wallet, less the fee. The returned protocol is in the Finalizing state, as it still needs the witness
+    /// of the claim or refund path to be attached before it is finalized.
+    async fn prepare_htlc_spend(
+        &mut self,
+        commitment: Commitment,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<SenderTransactionProtocol, OutputManagerError>
+    {
+        self.prepare_locked_output_spend(
+            commitment,
+            OutputFeatures::is_hash_time_locked,
+            HTLC_WITNESS_WEIGHT,
+            fee_per_gram,
+            message,
+        )
+        .await
+    }
+
+    /// Prepare a transaction that claims the unspent one-sided payment output with the given commitment, by spending
+    /// it to a new output of this wallet. Like [prepare_htlc_spend](Self::prepare_htlc_spend), the witness that
+    /// unlocks the script of the output has to be attached before the transaction is finalized.
+    async fn prepare_one_sided_payment_claim(
+        &mut self,
+        commitment: Commitment,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<SenderTransactionProtocol, OutputManagerError>
+    {
+        let witness_weight = self
+            .get_fee_calc()
+            .weighting()
+            .script_witness_weight(CLAIM_WITNESS_STACK_ITEMS);
+        self.prepare_locked_output_spend(
+            commitment,
+            OutputFeatures::is_script_locked,
+            witness_weight,
+            fee_per_gram,
+            message,
+        )
+        .await
+    }
+
+    /// Prepare a transaction that spends the unspent output with the given commitment, whose features must have the
+    /// given kind of lock, to a new output of this wallet. The fee also pays for the weight of the witness that is
+    /// attached to the input afterwards.
+    async fn prepare_locked_output_spend(
+        &mut self,
+        commitment: Commitment,
+        is_locked: fn(&OutputFeatures) -> bool,
+        witness_weight: u64,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<SenderTransactionProtocol, OutputManagerError>
+    {
+        let input = self
+            .fetch_unspent_outputs()
+            .await?
+            .into_iter()
+            .find(|o| o.commitment == commitment && is_locked(&o.unblinded_output.features))
+            .ok_or_else(|| OutputManagerError::SelectedOutputNotSpendable(commitment.to_hex()))?;
+
+        let fee = self.calculate_fee(fee_per_gram, 1, 1) + witness_weight * fee_per_gram;
+        if input.unblinded_output.value <= fee {
+            return Err(OutputManagerError::NotEnoughFunds);
+        }
+        let amount = input.unblinded_output.value - fee;
+
+        let offset = PrivateKey::random(&mut OsRng);
+        let nonce = PrivateKey::random(&mut OsRng);
+
+        let mut builder = SenderTransactionProtocol::builder(0);
+        builder
+            .with_transaction_weight(*self.resources.consensus_constants.transaction_weight())
+            .with_lock_height(0)
+            .with_fee_per_gram(fee_per_gram)
+            .with_witness_weight(witness_weight)
+            .with_offset(offset)
+            .with_private_nonce(nonce)
+            .with_message(message)
+            .with_input(
+                input.unblinded_output.as_transaction_input(
+                    &self.resources.factories.commitment,
+                    input.unblinded_output.features.clone(),
+                ),
+                input.unblinded_output.clone(),
+            );
+
+        let spend_key = self.get_next_spend_key().await?;
+        let utxo = DbUnblindedOutput::rewindable_from_unblinded_output(
+            UnblindedOutput::new(amount, spend_key, None),
+            &self.resources.factories,
+            &self.resources.rewind_data,
+        )?;
+        builder.with_output(utxo.unblinded_output.clone());
+
+        let stp = builder
+            .build::<HashDigest>(&self.resources.factories)
+            .map_err(|e| OutputManagerError::BuildError(e.message))?;
+
+        let tx_id = stp.get_tx_id()?;
+        trace!(
+            target: LOG_TARGET,
+            "Encumber locked output spend transaction ({}) outputs.",
+            tx_id
+        );
+        self.resources
+            .db
+            .encumber_outputs(tx_id, vec![input], vec![utxo])
+            .await?;
+        self.confirm_encumberance(tx_id).await?;
+
+        Ok(stp)
+    }
+
+    /// Prepare a transaction that spends the unspent multisig output with the given commitment, paying `amount` to a
+    /// one-sided payment output of `destination` and any change back to the same multisig lock. The
+    /// returned protocol is in the Finalizing state, as it still needs the aggregated signature of the signers to be
+    /// attached before it is finalized.
+    async fn prepare_multisig_spend(
+        &mut self,
+        commitment: Commitment,
+        destination: PublicKey,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<MultisigSpend, OutputManagerError>
+    {
+        let input = self
+            .fetch_unspent_outputs()
+            .await?
+            .into_iter()
+            .find(|o| o.commitment == commitment && o.unblinded_output.features.is_multisig_locked())
+            .ok_or_else(|| OutputManagerError::SelectedOutputNotSpendable(commitment.to_hex()))?;
+
+        let value = input.unblinded_output.value;
+        // The witness is signed by `threshold` of the participants
+        let num_signers = input
+            .unblinded_output
+            .features
+            .multisig
+            .as_ref()
+            .map(|lock| lock.threshold as usize)
+            .unwrap_or(0);
+        let witness_weight = self.get_fee_calc().weighting().multisig_witness_weight(num_signers);
+        let witness_fee = witness_weight * fee_per_gram;
+        let fee_without_change = self.calculate_fee(fee_per_gram, 1, 1) + witness_fee;
+        if value < amount + fee_without_change {
+            return Err(OutputManagerError::NotEnoughFunds);
+        }
+        let fee_with_change = self.calculate_fee(fee_per_gram, 1, 2) + witness_fee;
+
+        let offset = PrivateKey::random(&mut OsRng);
+        let nonce = PrivateKey::random(&mut OsRng);
+
+        let mut builder = SenderTransactionProtocol::builder(0);
+        builder
+            .with_transaction_weight(*self.resources.consensus_constants.transaction_weight())
+            .with_lock_height(0)
+            .with_fee_per_gram(fee_per_gram)
+            .with_witness_weight(witness_weight)
+            .with_offset(offset.clone())
+            .with_private_nonce(nonce)
+            .with_message(message)
+            .with_input(
+                input.unblinded_output.as_transaction_input(
+                    &self.resources.factories.commitment,
+                    input.unblinded_output.features.clone(),
+                ),
+                input.unblinded_output.clone(),
+            );
+
+        let ephemeral_secret_key = PrivateKey::random(&mut OsRng);
+        let payment = OneSidedPayment::new(&ephemeral_secret_key, &destination)?;
+        let recipient_output = payment.output(amount);
+        builder.with_rewindable_output(recipient_output.clone(), payment.rewind_data().clone());
+
+        // Change stays locked to the same participants, the remainder of a dust-sized change is left to the fee
+        let change_output = if value > amount + fee_with_change {
+            let change = UnblindedOutput::new(
+                value - amount - fee_with_change,
+                PrivateKey::random(&mut OsRng),
+                Some(input.unblinded_output.features.clone()),
+            );
+            builder.with_output(change.clone());
+            Some(change)
+        } else {
+            None
+        };
+
+        let stp = builder
+            .build::<HashDigest>(&self.resources.factories)
+            .map_err(|e| OutputManagerError::BuildError(e.message))?;
+
+        let tx_id = stp.get_tx_id()?;
+        trace!(
+            target: LOG_TARGET,
+            "Encumber multisig spend transaction ({}) outputs.",
+            tx_id
+        );
+        let outputs = match change_output.as_ref() {
+            Some(change) => vec![DbUnblindedOutput::from_unblinded_output(
+                change.clone(),
+                &self.resources.factories,
+            )?],
+            None => Vec::new(),
+        };
+        self.resources.db.encumber_outputs(tx_id, vec![input], outputs).await?;
+        self.confirm_encumberance(tx_id).await?;
+
+        Ok(MultisigSpend {
+            stp,
+            recipient_output,
+            change_output,
+            ephemeral_secret_key,
+            offset,
+        })
+    }
+
+    /// Sign the witness that spends a hash time-locked output with the HTLC key of this wallet, on the claim path if a
+    /// preimage is given and on the refund path otherwise
+    fn sign_htlc_witness(&self, request: HtlcSigningRequest) -> Result<HtlcWitness, OutputManagerError> {
+        let secret_key = self.htlc_secret_key.clone();
+        let nonce = PrivateKey::random(&mut OsRng);
+        let witness = match request.preimage {
+            Some(preimage) => {
+                request
+                    .htlc
+                    .sign_claim(secret_key, nonce, preimage, &request.commitment, request.kernel_excess)?
             },
-            UTXOSelectionStrategy::Largest => uo.into_iter().rev().collect(),
+            None => request
+                .htlc
+                .sign_refund(secret_key, nonce, &request.commitment, request.kernel_excess)?,
         };
-        trace!(target: LOG_TARGET, "We found {} UTXOs to select from", uo.len());
 
-        let mut require_change_output = false;
-        for o in uo.iter() {
-            utxos.push(o.clone());
-            utxos_total_value += o.unblinded_output.value;
-            // The assumption here is that the only output will be the payment output and change if required
-            fee_without_change = Fee::calculate(fee_per_gram, 1, utxos.len(), output_count);
-            if utxos_total_value == amount + fee_without_change {
-                break;
+        Ok(witness)
+    }
+
+    /// Create the signing nonce of this wallet for a multisig signing session, replacing any earlier nonce of the same
+    /// session, and return its public part to be shared with the other signers
+    fn create_multisig_nonce(&mut self, session_id: u64) -> PublicKey {
+        let nonce = PrivateKey::random(&mut OsRng);
+        let public_nonce = PublicKey::from_secret_key(&nonce);
+        self.multisig_nonces.insert(session_id, nonce);
+        public_nonce
+    }
+
+    /// Produce this wallet's partial signature for a multisig signing session. The session's nonce is consumed, so that
+    /// it can never be used to sign a second challenge.
+    fn sign_multisig(&mut self, request: MultisigSigningRequest) -> Result<Signature, OutputManagerError> {
+        let nonce = self
+            .multisig_nonces
+            .remove(&request.session_id)
+            .ok_or(OutputManagerError::MultisigNonceNotFound(request.session_id))?;
+        let signature = request.lock.partial_sign(
+            &self.multisig_secret_key,
+            &nonce,
+            &request.signers,
+            &request.aggregate_nonce,
+            &request.commitment,
+            &request.kernel_excess,
+        )?;
+
+        Ok(signature)
+    }
+
+    /// Confirm that a transaction has finished being negotiated between parties so the short-term encumberance can be
+    /// made official
+    async fn confirm_encumberance(&mut self, tx_id: u64) -> Result<(), OutputManagerError> {
+        self.resources.db.confirm_encumbered_outputs(tx_id).await?;
+
+        Ok(())
+    }
+
+    /// Confirm that a received or sent transaction and its outputs have been detected on the base chain. The inputs and
+    /// outputs are checked to see that they match what the stored PendingTransaction contains. This will
+    /// be called by the Transaction Service which monitors the base chain.
+    async fn confirm_transaction(
+        &mut self,
+        tx_id: u64,
+        inputs: &[TransactionInput],
+        outputs: &[TransactionOutput],
+    ) -> Result<(), OutputManagerError>
+    {
+        let pending_transaction = self.resources.db.fetch_pending_transaction_outputs(tx_id).await?;
+
+        // Check that outputs to be spent can all be found in the provided transaction inputs
+        for output_to_spend in pending_transaction.outputs_to_be_spent.iter() {
+            let input_to_check = output_to_spend
+                .unblinded_output
+                .as_transaction_input(&self.resources.factories.commitment, OutputFeatures::default());
+
+            if inputs.iter().all(|input| input.commitment != input_to_check.commitment) {
+                return Err(OutputManagerError::IncompleteTransaction(
+                    "outputs to spend are missing",
+                ));
             }
-            fee_with_change = Fee::calculate(fee_per_gram, 1, utxos.len(), output_count + 1);
-            if utxos_total_value >= amount + fee_with_change {
-                require_change_output = true;
-                break;
+        }
+
+        // Check that outputs to be received can all be found in the provided transaction outputs
+        for output_to_receive in pending_transaction.outputs_to_be_received.iter() {
+            let output_to_check = output_to_receive
+                .unblinded_output
+                .as_transaction_input(&self.resources.factories.commitment, OutputFeatures::default());
+
+            if outputs
+                .iter()
+                .all(|output| output.commitment != output_to_check.commitment)
+            {
+                return Err(OutputManagerError::IncompleteTransaction(
+                    "outputs to receive are missing",
+                ));
             }
         }
 
-        let current_chain_tip = chain_metadata.map(|cm| cm.height_of_longest_chain());
-        let balance = self.get_balance(current_chain_tip).await?;
-        let pending_incoming = balance.pending_incoming_balance;
+        self.resources
+            .db
+            .confirm_pending_transaction_outputs(pending_transaction.tx_id)
+            .await?;
+        self.record_mined_height(&pending_transaction.outputs_to_be_received)
+            .await?;
 
-        let perfect_utxo_selection = utxos_total_value == amount + fee_without_change;
-        let not_enough_spendable = utxos_total_value < amount + fee_with_change;
-        let enough_with_pending = utxos_total_value + pending_incoming >= amount + fee_with_change;
+        trace!(target: LOG_TARGET, "Confirm transaction (TxId: {})", tx_id);
+
+        Ok(())
+    }
 
-        if !perfect_utxo_selection && not_enough_spendable {
-            if enough_with_pending {
+    /// Record the mined height of newly confirmed outputs that are relatively time-locked, so that coin selection knows
+    /// when they become spendable. Outputs are confirmed once they have been detected on the base chain, so the current
+    /// chain tip is used as a conservative upper bound for the height at which they were mined. The UTXO validation
+    /// replaces it with the height reported by the base node.
+    async fn record_mined_height(&mut self, outputs: &[DbUnblindedOutput]) -> Result<(), OutputManagerError> {
+        if outputs
+            .iter()
+            .all(|o| o.unblinded_output.features.relative_maturity == 0)
+        {
+            return Ok(());
+        }
+        let tip_height = match self.base_node_service.get_chain_metadata().await? {
+            Some(metadata) => metadata.height_of_longest_chain(),
+            None => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Chain tip is unknown, relatively time-locked outputs will not be selected for spending"
+                );
+                return Ok(());
+            },
+        };
+        for output in outputs
+            .iter()
+            .filter(|o| o.unblinded_output.features.relative_maturity > 0)
+        {
+            self.resources
+                .db
+                .set_output_mined_height(output.commitment.clone(), tip_height)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Cancel a pending transaction and place the encumbered outputs back into the unspent pool
+    pub async fn cancel_transaction(&mut self, tx_id: u64) -> Result<(), OutputManagerError> {
+        debug!(
+            target: LOG_TARGET,
+            "Cancelling pending transaction outputs for TxId: {}", tx_id
+        );
+        Ok(self.resources.db.cancel_pending_transaction_outputs(tx_id).await?)
+    }
+
+    /// Go through the pending transaction and if any have existed longer than the specified duration, cancel them
+    async fn timeout_pending_transactions(&mut self, period: Duration) -> Result<(), OutputManagerError> {
+        Ok(self.resources.db.timeout_pending_transaction_outputs(period).await?)
+    }
+
+    /// Look up the unspent outputs with the given commitments so they can be spent in a transaction of the specified
+    /// amount, checking that they are spendable and cover the amount and fee.
+    async fn select_specified_utxos(
+        &mut self,
+        inputs: Vec<Commitment>,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+    ) -> Result<(Vec<DbUnblindedOutput>, MicroTari), OutputManagerError>
+    {
+        debug!(
+            target: LOG_TARGET,
+            "select_specified_utxos inputs: {}, amount: {}, fee_per_gram: {}",
+            inputs.len(),
+            amount,
+            fee_per_gram
+        );
+        let mut unspent = self.resources.db.fetch_sorted_unspent_outputs().await?;
+        let tip_height = self
+            .base_node_service
+            .get_chain_metadata()
+            .await?
+            .map(|metadata| metadata.height_of_longest_chain());
+
+        let mut utxos = Vec::with_capacity(inputs.len());
+        for commitment in inputs {
+            let position = unspent
+                .iter()
+                .position(|o| o.commitment == commitment)
+                .ok_or_else(|| OutputManagerError::SelectedOutputNotSpendable(commitment.to_hex()))?;
+            let output = unspent.swap_remove(position);
+            // Hash time-locked, multisig and script locked outputs can only be spent with a witness, see
+            // `prepare_htlc_spend`, `prepare_multisig_spend` and `prepare_one_sided_payment_claim`
+            if output.unblinded_output.features.is_hash_time_locked() ||
+                output.unblinded_output.features.is_multisig_locked() ||
+                output.unblinded_output.features.is_script_locked() ||
+                tip_height
+                    .map(|height| !output.is_spendable_at(height))
+                    .unwrap_or(false)
+            {
+                return Err(OutputManagerError::SelectedOutputNotSpendable(commitment.to_hex()));
+            }
+            utxos.push(output);
+        }
+
+        let total = utxos
+            .iter()
+            .fold(MicroTari::from(0), |total, o| total + o.unblinded_output.value);
+        let fee_without_change = self.calculate_fee(fee_per_gram, utxos.len(), 1);
+        let fee_with_change = self.calculate_fee(fee_per_gram, utxos.len(), 2);
+        if total != amount + fee_without_change && total < amount + fee_with_change {
+            return Err(OutputManagerError::NotEnoughFunds);
+        }
+
+        Ok((utxos, total))
+    }
+
+    /// Select which unspent transaction outputs to use to send a transaction of the specified amount. Use the specified
+    /// selection policy, or the configured one if none is given, to choose the outputs. It also determines if a change
+    /// output is required.
+    async fn select_utxos(
+        &mut self,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        num_kernels: usize,
+        output_count: usize,
+        policy: Option<UtxoSelectionPolicy>,
+    ) -> Result<(Vec<DbUnblindedOutput>, bool, MicroTari), OutputManagerError>
+    {
+        let policy = policy.unwrap_or(self.resources.config.utxo_selection_policy);
+        debug!(
+            target: LOG_TARGET,
+            "select_utxos amount: {}, fee_per_gram: {}, num_kernels: {}, output_count: {}, policy: {}",
+            amount,
+            fee_per_gram,
+            num_kernels,
+            output_count,
+            policy
+        );
+
+        // Outputs carrying asset tokens or locked in an atomic swap must only be spent deliberately, never to fund a
+        // transaction
+        let uo = self
+            .resources
+            .db
+            .fetch_sorted_unspent_outputs()
+            .await?
+            .into_iter()
+            .filter(|o| {
+                o.unblinded_output.features.asset.is_none() &&
+                    o.unblinded_output.features.htlc.is_none() &&
+                    o.unblinded_output.features.multisig.is_none() &&
+                    o.unblinded_output.features.script.is_none()
+            })
+            .collect::<Vec<_>>();
+
+        // Attempt to get the chain tip height
+        let chain_metadata = self.base_node_service.get_chain_metadata().await?;
+        let tip_height = chain_metadata.as_ref().map(|cm| cm.height_of_longest_chain());
+
+        // If we know the chain height then filter out unspendable UTXOs
+        let uo = match tip_height {
+            Some(tip_height) => {
+                let num_utxos = uo.len();
+                let mature_utxos = uo
+                    .into_iter()
+                    .filter(|u| u.is_spendable_at(tip_height))
+                    .collect::<Vec<DbUnblindedOutput>>();
+
+                trace!(
+                    target: LOG_TARGET,
+                    "Some UTXOs have not matured yet at height {}, filtered {} UTXOs",
+                    tip_height,
+                    num_utxos - mature_utxos.len()
+                );
+
+                mature_utxos
+            },
+            None => uo,
+        };
+        trace!(target: LOG_TARGET, "We found {} UTXOs to select from", uo.len());
+
+        let target = SelectionTarget {
+            amount,
+            fee_per_gram,
+            fee_calc: self.get_fee_calc(),
+            num_kernels,
+            num_outputs: output_count,
+            tip_height,
+        };
+        let strategy = policy.strategy();
+        let selection = strategy.select(uo, &target);
+        debug!(
+            target: LOG_TARGET,
+            "select_utxos selection strategy {} selected {} UTXOs",
+            strategy.name(),
+            selection.utxos.len()
+        );
+
+        if !selection.is_sufficient(&target) {
+            let balance = self.get_balance(tip_height).await?;
+            let fee_with_change = target.fee_with_change(selection.utxos.len());
+            if selection.total + balance.pending_incoming_balance >= amount + fee_with_change {
                 return Err(OutputManagerError::FundsPending);
             } else {
                 return Err(OutputManagerError::NotEnoughFunds);
             }
         }
 
-        Ok((utxos, require_change_output, utxos_total_value))
+        Ok((selection.utxos, selection.requires_change_output, selection.total))
     }
 
     /// Set the base node public key to the list that will be used to check the status of UTXO's on the base chain. If
@@ -1027,21 +2002,23 @@ where TBackend: OutputManagerBackend + 'static
             .select_utxos(
                 total_split_amount,
                 fee_per_gram,
+                1,
                 output_count,
-                Some(UTXOSelectionStrategy::Largest),
+                Some(UtxoSelectionPolicy::LargestFirst),
             )
             .await?;
         let input_count = inputs.len();
         if require_change_output {
             output_count = split_count + 1
         };
-        let fee = Fee::calculate(fee_per_gram, 1, input_count, output_count);
+        let fee = self.calculate_fee(fee_per_gram, input_count, output_count);
 
         trace!(target: LOG_TARGET, "Construct coin split transaction.");
         let offset = PrivateKey::random(&mut OsRng);
         let nonce = PrivateKey::random(&mut OsRng);
         let mut builder = SenderTransactionProtocol::builder(0);
         builder
+            .with_transaction_weight(*self.resources.consensus_constants.transaction_weight())
             .with_lock_height(lock_height.unwrap_or(0))
             .with_fee_per_gram(fee_per_gram)
             .with_offset(offset.clone())
@@ -1099,12 +2076,162 @@ where TBackend: OutputManagerBackend + 'static
         Ok((tx_id, tx, fee, utxos_total_value))
     }
 
+    /// Spend the spendable outputs below the configured dust threshold into a single output paid to this wallet and
+    /// hand the transaction to the Transaction Service to be broadcast. Nothing is consolidated unless the
+    /// consolidation is enabled, there are enough dust outputs that are worth more than the fee to spend them and the
+    /// mempool is not paying more than the configured fee per gram.
+    async fn consolidate_dust(&mut self) -> Result<Option<TxId>, OutputManagerError> {
+        let (tx_id, tx, fee, amount) = match self.create_dust_consolidation().await? {
+            Some(consolidation) => consolidation,
+            None => return Ok(None),
+        };
+        info!(
+            target: LOG_TARGET,
+            "Consolidating dust outputs worth {} with a fee of {} in transaction ({})", amount, fee, tx_id
+        );
+
+        // The Transaction Service calls back into this service while submitting, so the reply can't be awaited here
+        let mut transaction_service = self.resources.transaction_service.clone();
+        tokio::spawn(async move {
+            if let Err(e) = transaction_service
+                .submit_transaction(tx_id, tx, fee, amount, "Dust consolidation".to_string())
+                .await
+            {
+                warn!(
+                    target: LOG_TARGET,
+                    "Could not submit dust consolidation transaction ({}): {}", tx_id, e
+                );
+            }
+        });
+
+        Ok(Some(tx_id))
+    }
+
+    /// Create the dust consolidation transaction, returns (tx_id, tx, fee, utxos_total_value)
+    async fn create_dust_consolidation(
+        &mut self,
+    ) -> Result<Option<(u64, Transaction, MicroTari, MicroTari)>, OutputManagerError> {
+        let threshold = match self.resources.config.dust_consolidation_threshold {
+            Some(threshold) => threshold,
+            None => return Ok(None),
+        };
+        let fee_per_gram = self.resources.config.dust_consolidation_fee_per_gram;
+
+        let tip_height = match self.base_node_service.get_chain_metadata().await? {
+            Some(metadata) => metadata.height_of_longest_chain(),
+            None => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Not consolidating dust without the chain tip height"
+                );
+                return Ok(None);
+            },
+        };
+        match self.base_node_service.get_mempool_fee_stats().await? {
+            Some(stats) if stats.tx_count == 0 || stats.fee_per_gram_percentiles.p75 <= u64::from(fee_per_gram) => {},
+            stats => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Not consolidating dust, the mempool fees are not low enough or unknown: {:?}", stats
+                );
+                return Ok(None);
+            },
+        }
+
+        // Only outputs that are worth more than the fee of spending them are consolidated
+        let input_fee = self.calculate_fee(fee_per_gram, 2, 1) - self.calculate_fee(fee_per_gram, 1, 1);
+        let inputs = self
+            .resources
+            .db
+            .fetch_sorted_unspent_outputs()
+            .await?
+            .into_iter()
+            .filter(|o| {
+                let value = o.unblinded_output.value;
+                o.unblinded_output.features.asset.is_none() &&
+                    o.unblinded_output.features.htlc.is_none() &&
+                    o.unblinded_output.features.multisig.is_none() &&
+                    o.unblinded_output.features.script.is_none() &&
+                    o.is_spendable_at(tip_height) &&
+                    value < threshold &&
+                    value > input_fee
+            })
+            .rev()
+            .take(MAX_DUST_CONSOLIDATION_INPUTS)
+            .collect::<Vec<_>>();
+        if inputs.len() < cmp::max(self.resources.config.dust_consolidation_min_outputs, 2) {
+            trace!(
+                target: LOG_TARGET,
+                "Found {} dust outputs, not enough to consolidate",
+                inputs.len()
+            );
+            return Ok(None);
+        }
+
+        let utxos_total_value = inputs
+            .iter()
+            .fold(MicroTari::from(0), |total, o| total + o.unblinded_output.value);
+        let fee = self.calculate_fee(fee_per_gram, inputs.len(), 1);
+        if fee < MINIMUM_TRANSACTION_FEE {
+            trace!(
+                target: LOG_TARGET,
+                "Not consolidating dust, the fee {} is below the minimum transaction fee",
+                fee
+            );
+            return Ok(None);
+        }
+        let output_value = match utxos_total_value.checked_sub(fee) {
+            Some(value) if value > MicroTari::from(0) => value,
+            _ => return Ok(None),
+        };
+
+        trace!(target: LOG_TARGET, "Construct dust consolidation transaction.");
+        let offset = PrivateKey::random(&mut OsRng);
+        let nonce = PrivateKey::random(&mut OsRng);
+        let mut builder = SenderTransactionProtocol::builder(0);
+        builder
+            .with_transaction_weight(*self.resources.consensus_constants.transaction_weight())
+            .with_lock_height(0)
+            .with_fee_per_gram(fee_per_gram)
+            .with_offset(offset)
+            .with_private_nonce(nonce);
+        for uo in inputs.iter() {
+            builder.with_input(
+                uo.unblinded_output.as_transaction_input(
+                    &self.resources.factories.commitment,
+                    uo.unblinded_output.features.clone(),
+                ),
+                uo.unblinded_output.clone(),
+            );
+        }
+        let spend_key = self.get_next_spend_key().await?;
+        let utxo = DbUnblindedOutput::rewindable_from_unblinded_output(
+            UnblindedOutput::new(output_value, spend_key, None),
+            &self.resources.factories,
+            &self.resources.rewind_data,
+        )?;
+        builder.with_output(utxo.unblinded_output.clone());
+
+        let factories = CryptoFactories::default();
+        let mut stp = builder
+            .build::<HashDigest>(&self.resources.factories)
+            .map_err(|e| OutputManagerError::BuildError(e.message))?;
+        let tx_id = stp.get_tx_id()?;
+        trace!(
+            target: LOG_TARGET,
+            "Encumber dust consolidation transaction ({}) outputs.",
+            tx_id
+        );
+        self.resources.db.encumber_outputs(tx_id, inputs, vec![utxo]).await?;
+        self.confirm_encumberance(tx_id).await?;
+        stp.finalize(KernelFeatures::empty(), &factories)?;
+        let tx = stp.take_transaction()?;
+        Ok(Some((tx_id, tx, fee, utxos_total_value)))
+    }
+
     /// Return the Seed words for the current Master Key set in the Key Manager
     pub async fn get_seed_words(&self) -> Result<Vec<String>, OutputManagerError> {
-        Ok(from_secret_key(
-            self.key_manager.lock().await.master_key(),
-            &MnemonicLanguage::English,
-        )?)
+        Ok(self.key_manager.seed_words()?)
     }
 
     /// Return the public rewind keys
@@ -1112,6 +2239,7 @@ where TBackend: OutputManagerBackend + 'static
         PublicRewindKeys {
             rewind_public_key: PublicKey::from_secret_key(&self.resources.rewind_data.rewind_key),
             rewind_blinding_public_key: PublicKey::from_secret_key(&self.resources.rewind_data.rewind_blinding_key),
+            legacy_rewind_public_key: Some(PublicKey::from_secret_key(&self.legacy_rewind_data.rewind_key)),
         }
     }
 
@@ -1122,6 +2250,7 @@ where TBackend: OutputManagerBackend + 'static
     ) -> Result<Vec<UnblindedOutput>, OutputManagerError>
     {
         let rewind_data = &self.resources.rewind_data;
+        let legacy_rewind_data = &self.legacy_rewind_data;
 
         let rewound_outputs: Vec<UnblindedOutput> = outputs
             .into_iter()
@@ -1132,6 +2261,13 @@ where TBackend: OutputManagerBackend + 'static
                         &rewind_data.rewind_key,
                         &rewind_data.rewind_blinding_key,
                     )
+                    .or_else(|_| {
+                        output.full_rewind_range_proof(
+                            &self.resources.factories.range_proof,
+                            &legacy_rewind_data.rewind_key,
+                            &legacy_rewind_data.rewind_blinding_key,
+                        )
+                    })
                     .ok()
                     .map(|v| (v, output.features))
             })
@@ -1151,44 +2287,66 @@ where TBackend: OutputManagerBackend + 'static
                 output.features,
             );
         }
+        self.skip_recovered_spend_keys(&rewound_outputs).await?;
 
         Ok(rewound_outputs)
     }
 
+    /// Move the key index of the spend branch past the keys of the recovered outputs, so that a wallet restored from
+    /// its seed words does not hand out the spending keys of the outputs it already holds again
+    async fn skip_recovered_spend_keys(&self, outputs: &[UnblindedOutput]) -> Result<(), OutputManagerError> {
+        if outputs.is_empty() {
+            return Ok(());
+        }
+        let mut spend_key_index = self.spend_key_index.lock().await;
+        let mut spend_keys = HashMap::new();
+        for index in 1..=*spend_key_index + RECOVERY_KEY_INDEX_LOOKAHEAD {
+            spend_keys.insert(
+                self.key_manager.derive_key(KeyManagerBranch::Spend, index)?.to_vec(),
+                index,
+            );
+        }
+        let highest_index = outputs
+            .iter()
+            .filter_map(|output| spend_keys.get(&output.spending_key.to_vec()))
+            .max()
+            .copied();
+
+        if let Some(index) = highest_index.filter(|index| *index > *spend_key_index) {
+            debug!(
+                target: LOG_TARGET,
+                "Recovered outputs use spend keys up to index {}, continuing from there", index
+            );
+            let key_manager_state = self
+                .resources
+                .db
+                .get_key_manager_state()
+                .await?
+                .ok_or_else(|| OutputManagerError::ServiceError("The key manager state is not set".to_string()))?;
+            self.resources
+                .db
+                .set_key_manager_state(KeyManagerState {
+                    primary_key_index: index,
+                    ..key_manager_state
+                })
+                .await?;
+            *spend_key_index = index;
+        }
+        Ok(())
+    }
+
     async fn get_next_spend_key(&self) -> Result<PrivateKey, OutputManagerError> {
-        let mut km = self.key_manager.lock().await;
-        let key = km.next_key()?;
+        let mut spend_key_index = self.spend_key_index.lock().await;
+        let key = self
+            .key_manager
+            .derive_key(KeyManagerBranch::Spend, *spend_key_index + 1)?;
         self.resources.db.increment_key_index().await?;
-        Ok(key.k)
+        *spend_key_index += 1;
+        Ok(key)
     }
 
     async fn get_coinbase_key_for_height(&self, height: u64) -> Result<PrivateKey, OutputManagerError> {
-        let km = self.coinbase_key_manager.lock().await;
-        let key = km.derive_key(height)?;
-        Ok(key.k)
-    }
-}
-
-/// Different UTXO selection strategies for choosing which UTXO's are used to fulfill a transaction
-/// TODO Investigate and implement more optimal strategies
-#[derive(Debug)]
-pub enum UTXOSelectionStrategy {
-    // Start from the smallest UTXOs and work your way up until the amount is covered. Main benefit
-    // is removing small UTXOs from the blockchain, con is that it costs more in fees
-    Smallest,
-    // Start from oldest maturity to reduce the likelihood of grabbing locked up UTXOs
-    MaturityThenSmallest,
-    // A strategy that selects the largest UTXOs first. Preferred when the amount is large
-    Largest,
-}
-
-impl Display for UTXOSelectionStrategy {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            UTXOSelectionStrategy::Smallest => write!(f, "Smallest"),
-            UTXOSelectionStrategy::MaturityThenSmallest => write!(f, "MaturityThenSmallest"),
-            UTXOSelectionStrategy::Largest => write!(f, "Largest"),
-        }
+        Ok(self.key_manager.derive_key(KeyManagerBranch::Coinbase, height)?)
     }
 }
 
@@ -1225,6 +2383,40 @@ impl fmt::Display for Balance {
     }
 }
 
+/// The estimated fee of a transaction, and the shape of the transaction it was estimated for
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeEstimate {
+    pub fee: MicroTari,
+    /// The fee per gram that the fee was estimated with
+    pub fee_per_gram: MicroTari,
+    /// The fee per gram suggested by the state of the base node's mempool
+    pub suggested_fee_per_gram: MicroTari,
+    pub num_inputs: usize,
+    /// The number of outputs, including the change output if one is needed
+    pub num_outputs: usize,
+    pub has_change_output: bool,
+    pub weight: u64,
+}
+
+impl fmt::Display for FeeEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Fee: {} at {}/g ({} inputs, {} outputs, weight {})",
+            self.fee, self.fee_per_gram, self.num_inputs, self.num_outputs, self.weight
+        )
+    }
+}
+
+/// Suggest a fee per gram that pays at least as much as 75% of the weight of the unconfirmed pool. When the pool is
+/// empty or its state is unknown, the wallet default is suggested.
+pub fn suggested_fee_per_gram(mempool_fee_stats: Option<&MempoolFeeStats>) -> MicroTari {
+    match mempool_fee_stats {
+        Some(stats) if stats.tx_count > 0 => MicroTari(cmp::max(stats.fee_per_gram_percentiles.p75, 1)),
+        _ => DEFAULT_FEE_PER_GRAM,
+    }
+}
+
 /// This struct is a collection of the common resources that a async task in the service requires.
 #[derive(Clone)]
 pub struct OutputManagerResources<TBackend>
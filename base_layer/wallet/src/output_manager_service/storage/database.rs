@@ -93,6 +93,12 @@ pub trait OutputManagerBackend: Send + Sync + Clone {
     fn apply_encryption(&self, cipher: Aes256Gcm) -> Result<(), OutputManagerStorageError>;
     /// Remove encryption from the backend.
     fn remove_encryption(&self) -> Result<(), OutputManagerStorageError>;
+    /// Record the height at which the output with the given commitment was mined
+    fn set_output_mined_height(
+        &self,
+        commitment: &Commitment,
+        mined_height: u64,
+    ) -> Result<(), OutputManagerStorageError>;
     /// Update a Spent output to be Unspent
     fn update_spent_output_to_unspent(
         &self,
@@ -255,8 +261,16 @@ where T: OutputManagerBackend + 'static
 
         if let DbValue::UnspentOutputs(uo) = unspent_outputs {
             if let DbValue::AllPendingTransactionOutputs(pto) = pending_txs {
+                // Outputs carrying asset tokens, locked in an atomic swap, shared in a multisig lock or locked by a
+                // script, like one-sided payments that are yet to be claimed, are not available to fund transactions
                 let available_balance = uo
                     .iter()
+                    .filter(|x| {
+                        x.unblinded_output.features.asset.is_none() &&
+                            x.unblinded_output.features.htlc.is_none() &&
+                            x.unblinded_output.features.multisig.is_none() &&
+                            x.unblinded_output.features.script.is_none()
+                    })
                     .fold(MicroTari::from(0), |acc, x| acc + x.unblinded_output.value);
                 let time_locked_balance = if let Some(tip) = current_chain_tip {
                     let time_locked_outputs = tokio::task::spawn_blocking(move || {
@@ -283,14 +297,25 @@ where T: OutputManagerBackend + 'static
                 let mut pending_incoming = MicroTari::from(0);
                 let mut pending_outgoing = MicroTari::from(0);
 
+                // Locked outputs are not part of the balance while they are pending either
                 for v in pto.values() {
                     pending_incoming += v
                         .outputs_to_be_received
                         .iter()
+                        .filter(|x| {
+                            x.unblinded_output.features.htlc.is_none() &&
+                                x.unblinded_output.features.multisig.is_none() &&
+                                x.unblinded_output.features.script.is_none()
+                        })
                         .fold(MicroTari::from(0), |acc, x| acc + x.unblinded_output.value);
                     pending_outgoing += v
                         .outputs_to_be_spent
                         .iter()
+                        .filter(|x| {
+                            x.unblinded_output.features.htlc.is_none() &&
+                                x.unblinded_output.features.multisig.is_none() &&
+                                x.unblinded_output.features.script.is_none()
+                        })
                         .fold(MicroTari::from(0), |acc, x| acc + x.unblinded_output.value);
                 }
 
@@ -587,6 +612,19 @@ where T: OutputManagerBackend + 'static
             .and_then(|inner_result| inner_result)
     }
 
+    pub async fn set_output_mined_height(
+        &self,
+        commitment: Commitment,
+        mined_height: u64,
+    ) -> Result<(), OutputManagerStorageError>
+    {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || db_clone.set_output_mined_height(&commitment, mined_height))
+            .await
+            .map_err(|err| OutputManagerStorageError::BlockingTaskSpawnError(err.to_string()))
+            .and_then(|inner_result| inner_result)
+    }
+
     pub async fn update_spent_output_to_unspent(
         &self,
         commitment: Commitment,
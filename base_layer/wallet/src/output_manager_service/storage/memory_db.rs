@@ -120,7 +120,7 @@ impl OutputManagerBackend for OutputManagerMemoryDatabase {
                 db.unspent_outputs
                     .iter()
                     .filter_map(|o| {
-                        if (*o).output.unblinded_output.features.maturity > *tip {
+                        if !(*o).output.is_spendable_at(*tip) {
                             Some(DbUnblindedOutput::from((*o).clone()))
                         } else {
                             None
@@ -401,6 +401,24 @@ impl OutputManagerBackend for OutputManagerMemoryDatabase {
         }
     }
 
+    fn set_output_mined_height(
+        &self,
+        commitment: &Commitment,
+        mined_height: u64,
+    ) -> Result<(), OutputManagerStorageError>
+    {
+        let mut db = acquire_write_lock!(self.db);
+        let db = &mut *db;
+        let output = db
+            .unspent_outputs
+            .iter_mut()
+            .chain(db.spent_outputs.iter_mut())
+            .find(|v| v.output.commitment == *commitment)
+            .ok_or_else(|| OutputManagerStorageError::ValuesNotFound)?;
+        output.output.mined_height = Some(mined_height);
+        Ok(())
+    }
+
     fn update_spent_output_to_unspent(
         &self,
         commitment: &Commitment,
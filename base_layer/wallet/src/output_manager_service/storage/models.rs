@@ -36,6 +36,8 @@ pub struct DbUnblindedOutput {
     pub commitment: Commitment,
     pub unblinded_output: UnblindedOutput,
     pub hash: HashOutput,
+    /// The height at which this output was (at the latest) mined, if known
+    pub mined_height: Option<u64>,
 }
 
 impl DbUnblindedOutput {
@@ -49,6 +51,7 @@ impl DbUnblindedOutput {
             hash: tx_out.hash(),
             commitment: tx_out.commitment,
             unblinded_output: output,
+            mined_height: None,
         })
     }
 
@@ -63,8 +66,21 @@ impl DbUnblindedOutput {
             hash: tx_out.hash(),
             commitment: tx_out.commitment,
             unblinded_output: output,
+            mined_height: None,
         })
     }
+
+    /// Returns true if this output can be spent in a block at the given height. An output with a relative time-lock
+    /// is only considered spendable once the height at which it was mined is known.
+    pub fn is_spendable_at(&self, height: u64) -> bool {
+        let features = &self.unblinded_output.features;
+        if features.relative_maturity == 0 {
+            return features.maturity <= height;
+        }
+        self.mined_height
+            .map(|mined_height| features.is_mature_at(mined_height, height))
+            .unwrap_or(false)
+    }
 }
 
 impl From<DbUnblindedOutput> for UnblindedOutput {
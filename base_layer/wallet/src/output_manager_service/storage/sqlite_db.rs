@@ -56,7 +56,7 @@ use tari_core::{
     tari_utilities::hash::Hashable,
     transactions::{
         tari_amount::MicroTari,
-        transaction::{OutputFeatures, OutputFlags, UnblindedOutput},
+        transaction::{OutputFeatures, OutputFeaturesVersion, OutputFlags, RangeProofType, UnblindedOutput},
         types::{Commitment, CryptoFactories, PrivateKey},
     },
 };
@@ -79,9 +79,15 @@ pub struct OutputManagerSqliteDatabase {
 
 impl OutputManagerSqliteDatabase {
     pub fn new(database_connection: WalletDbConnection, cipher: Option<Aes256Gcm>) -> Self {
+        Self::with_shared_cipher(database_connection, Arc::new(RwLock::new(cipher)))
+    }
+
+    /// A backend that uses the cipher of the wallet backend, which encrypts and re-encrypts the values of all of the
+    /// services in the database at once
+    pub fn with_shared_cipher(database_connection: WalletDbConnection, cipher: Arc<RwLock<Option<Aes256Gcm>>>) -> Self {
         Self {
             database_connection,
-            cipher: Arc::new(RwLock::new(cipher)),
+            cipher,
         }
     }
 
@@ -372,6 +378,7 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
                                 status: Some(OutputStatus::Unspent),
                                 tx_id: None,
                                 spending_key: None,
+                                mined_height: None,
                             },
                             &(*conn),
                         )?;
@@ -381,6 +388,7 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
                                 status: Some(OutputStatus::Spent),
                                 tx_id: None,
                                 spending_key: None,
+                                mined_height: None,
                             },
                             &(*conn),
                         )?;
@@ -426,6 +434,7 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
                     status: Some(OutputStatus::EncumberedToBeSpent),
                     tx_id: Some(tx_id),
                     spending_key: None,
+                    mined_height: None,
                 },
                 &(*conn),
             )?;
@@ -489,6 +498,7 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
                                 status: Some(OutputStatus::CancelledInbound),
                                 tx_id: None,
                                 spending_key: None,
+                                mined_height: None,
                             },
                             &(*conn),
                         )?;
@@ -498,6 +508,7 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
                                 status: Some(OutputStatus::Unspent),
                                 tx_id: None,
                                 spending_key: None,
+                                mined_height: None,
                             },
                             &(*conn),
                         )?;
@@ -553,6 +564,7 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
                 status: Some(OutputStatus::Invalid),
                 tx_id: None,
                 spending_key: None,
+                mined_height: None,
             },
             &(*conn),
         )?;
@@ -572,6 +584,27 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
                 status: Some(OutputStatus::Unspent),
                 tx_id: None,
                 spending_key: None,
+                mined_height: None,
+            },
+            &(*conn),
+        )?;
+        Ok(())
+    }
+
+    fn set_output_mined_height(
+        &self,
+        commitment: &Commitment,
+        mined_height: u64,
+    ) -> Result<(), OutputManagerStorageError>
+    {
+        let conn = self.database_connection.acquire_lock();
+        let output = OutputSql::find_by_commitment(&commitment.to_vec(), &conn)?;
+        let _ = output.update(
+            UpdateOutput {
+                status: None,
+                tx_id: None,
+                spending_key: None,
+                mined_height: Some(mined_height),
             },
             &(*conn),
         )?;
@@ -595,6 +628,7 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
                 status: Some(OutputStatus::Unspent),
                 tx_id: None,
                 spending_key: None,
+                mined_height: None,
             },
             &(*conn),
         )?;
@@ -616,75 +650,107 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
     }
 
     fn apply_encryption(&self, cipher: Aes256Gcm) -> Result<(), OutputManagerStorageError> {
+        let conn = self.database_connection.acquire_lock();
         let mut current_cipher = acquire_write_lock!(self.cipher);
 
         if (*current_cipher).is_some() {
             return Err(OutputManagerStorageError::AlreadyEncrypted);
         }
 
+        conn.transaction::<_, OutputManagerStorageError, _>(|| encrypt_keys(&cipher, &conn))?;
+
+        (*current_cipher) = Some(cipher);
+
+        Ok(())
+    }
+
+    fn remove_encryption(&self) -> Result<(), OutputManagerStorageError> {
         let conn = self.database_connection.acquire_lock();
-        let mut outputs = OutputSql::index(&conn)?;
+        let mut current_cipher = acquire_write_lock!(self.cipher);
+        let cipher = if let Some(cipher) = (*current_cipher).clone().take() {
+            cipher
+        } else {
+            return Ok(());
+        };
+        conn.transaction::<_, OutputManagerStorageError, _>(|| decrypt_keys(&cipher, &conn))?;
 
-        // If the db is already encrypted then the very first output we try to encrypt will fail.
-        for o in outputs.iter_mut() {
-            // Test if this output is encrypted or not to avoid a double encryption.
-            let _ = PrivateKey::from_vec(&o.spending_key).map_err(|_| {
-                error!(
-                    target: LOG_TARGET,
-                    "Could not create PrivateKey from stored bytes, They might already be encrypted"
-                );
-                OutputManagerStorageError::AlreadyEncrypted
-            })?;
-            o.encrypt(&cipher)
-                .map_err(|_| OutputManagerStorageError::AeadError("Encryption Error".to_string()))?;
-            o.update_encryption(&conn)?;
-        }
+        // Now that all the decryption has been completed we can safely remove the cipher fully
+        let _ = (*current_cipher).take();
+        Ok(())
+    }
+}
 
-        let mut key_manager_state = KeyManagerStateSql::get_state(&conn)?;
+/// Encrypt the spending keys of the outputs and the master key of the key manager, which must not be encrypted yet
+pub(crate) fn encrypt_keys(cipher: &Aes256Gcm, conn: &SqliteConnection) -> Result<(), OutputManagerStorageError> {
+    let mut outputs = OutputSql::index(conn)?;
 
-        let _ = PrivateKey::from_vec(&key_manager_state.master_key).map_err(|_| {
+    // If the db is already encrypted then the very first output we try to encrypt will fail.
+    for o in outputs.iter_mut() {
+        // Test if this output is encrypted or not to avoid a double encryption.
+        let _ = PrivateKey::from_vec(&o.spending_key).map_err(|_| {
             error!(
                 target: LOG_TARGET,
                 "Could not create PrivateKey from stored bytes, They might already be encrypted"
             );
             OutputManagerStorageError::AlreadyEncrypted
         })?;
-
-        key_manager_state
-            .encrypt(&cipher)
+        o.encrypt(cipher)
             .map_err(|_| OutputManagerStorageError::AeadError("Encryption Error".to_string()))?;
-        key_manager_state.set_state(&conn)?;
+        o.update_encryption(conn)?;
+    }
 
-        (*current_cipher) = Some(cipher);
+    let mut key_manager_state = match key_manager_state_if_initialized(conn)? {
+        Some(state) => state,
+        None => return Ok(()),
+    };
 
-        Ok(())
-    }
+    let _ = PrivateKey::from_vec(&key_manager_state.master_key).map_err(|_| {
+        error!(
+            target: LOG_TARGET,
+            "Could not create PrivateKey from stored bytes, They might already be encrypted"
+        );
+        OutputManagerStorageError::AlreadyEncrypted
+    })?;
 
-    fn remove_encryption(&self) -> Result<(), OutputManagerStorageError> {
-        let mut current_cipher = acquire_write_lock!(self.cipher);
-        let cipher = if let Some(cipher) = (*current_cipher).clone().take() {
-            cipher
-        } else {
-            return Ok(());
-        };
-        let conn = self.database_connection.acquire_lock();
-        let mut outputs = OutputSql::index(&conn)?;
+    key_manager_state
+        .encrypt(cipher)
+        .map_err(|_| OutputManagerStorageError::AeadError("Encryption Error".to_string()))?;
+    key_manager_state.set_state(conn)?;
 
-        for o in outputs.iter_mut() {
-            o.decrypt(&cipher)
-                .map_err(|_| OutputManagerStorageError::AeadError("Encryption Error".to_string()))?;
-            o.update_encryption(&conn)?;
-        }
+    Ok(())
+}
 
-        let mut key_manager_state = KeyManagerStateSql::get_state(&conn)?;
-        key_manager_state
-            .decrypt(&cipher)
+/// Decrypt the spending keys of the outputs and the master key of the key manager
+pub(crate) fn decrypt_keys(cipher: &Aes256Gcm, conn: &SqliteConnection) -> Result<(), OutputManagerStorageError> {
+    let mut outputs = OutputSql::index(conn)?;
+
+    for o in outputs.iter_mut() {
+        o.decrypt(cipher)
             .map_err(|_| OutputManagerStorageError::AeadError("Encryption Error".to_string()))?;
-        key_manager_state.set_state(&conn)?;
+        o.update_encryption(conn)?;
+    }
 
-        // Now that all the decryption has been completed we can safely remove the cipher fully
-        let _ = (*current_cipher).take();
-        Ok(())
+    let mut key_manager_state = match key_manager_state_if_initialized(conn)? {
+        Some(state) => state,
+        None => return Ok(()),
+    };
+    key_manager_state
+        .decrypt(cipher)
+        .map_err(|_| OutputManagerStorageError::AeadError("Encryption Error".to_string()))?;
+    key_manager_state.set_state(conn)?;
+
+    Ok(())
+}
+
+/// The key manager state is only stored once the service has started, which the rest of the database can be encrypted
+/// before
+fn key_manager_state_if_initialized(
+    conn: &SqliteConnection,
+) -> Result<Option<KeyManagerStateSql>, OutputManagerStorageError> {
+    match KeyManagerStateSql::get_state(conn) {
+        Ok(state) => Ok(Some(state)),
+        Err(OutputManagerStorageError::KeyManagerNotInitialized) => Ok(None),
+        Err(e) => Err(e),
     }
 }
 
@@ -755,6 +821,14 @@ struct NewOutputSql {
     status: i32,
     tx_id: Option<i64>,
     hash: Option<Vec<u8>>,
+    relative_maturity: i64,
+    mined_height: Option<i64>,
+    asset: Option<String>,
+    features_version: i32,
+    coinbase_extra: Option<Vec<u8>>,
+    htlc: Option<String>,
+    multisig: Option<String>,
+    script: Option<String>,
 }
 
 impl NewOutputSql {
@@ -768,6 +842,34 @@ impl NewOutputSql {
             status: status as i32,
             tx_id: tx_id.map(|i| i as i64),
             hash: Some(output.hash),
+            relative_maturity: output.unblinded_output.features.relative_maturity as i64,
+            mined_height: output.mined_height.map(|h| h as i64),
+            asset: output
+                .unblinded_output
+                .features
+                .asset
+                .as_ref()
+                .and_then(|a| serde_json::to_string(a).ok()),
+            features_version: output.unblinded_output.features.version.as_u8() as i32,
+            coinbase_extra: Some(output.unblinded_output.features.coinbase_extra.clone()).filter(|e| !e.is_empty()),
+            htlc: output
+                .unblinded_output
+                .features
+                .htlc
+                .as_ref()
+                .and_then(|h| serde_json::to_string(h).ok()),
+            multisig: output
+                .unblinded_output
+                .features
+                .multisig
+                .as_ref()
+                .and_then(|m| serde_json::to_string(m).ok()),
+            script: output
+                .unblinded_output
+                .features
+                .script
+                .as_ref()
+                .and_then(|s| serde_json::to_string(s).ok()),
         }
     }
 
@@ -802,6 +904,14 @@ struct OutputSql {
     status: i32,
     tx_id: Option<i64>,
     hash: Option<Vec<u8>>,
+    relative_maturity: i64,
+    mined_height: Option<i64>,
+    asset: Option<String>,
+    features_version: i32,
+    coinbase_extra: Option<Vec<u8>>,
+    htlc: Option<String>,
+    multisig: Option<String>,
+    script: Option<String>,
 }
 
 impl OutputSql {
@@ -819,12 +929,21 @@ impl OutputSql {
         Ok(outputs::table.filter(outputs::status.eq(status as i32)).load(conn)?)
     }
 
-    /// Return all unspent outputs that have a maturity above the provided chain tip
+    /// Return all unspent outputs that have a maturity or relative maturity above the provided chain tip
     pub fn index_time_locked(tip: u64, conn: &SqliteConnection) -> Result<Vec<OutputSql>, OutputManagerStorageError> {
-        Ok(outputs::table
+        let outputs = outputs::table
             .filter(outputs::status.eq(OutputStatus::Unspent as i32))
-            .filter(outputs::maturity.gt(tip as i64))
-            .load(conn)?)
+            .filter(outputs::maturity.gt(tip as i64).or(outputs::relative_maturity.gt(0)))
+            .load::<OutputSql>(conn)?;
+        Ok(outputs
+            .into_iter()
+            .filter(|o| {
+                o.maturity > tip as i64 ||
+                    o.mined_height
+                        .map(|h| h.saturating_add(o.relative_maturity) > tip as i64)
+                        .unwrap_or(true)
+            })
+            .collect())
     }
 
     /// Find a particular Output, if it exists
@@ -933,6 +1052,7 @@ impl OutputSql {
                 status: None,
                 tx_id: None,
                 spending_key: Some(self.spending_key.clone()),
+                mined_height: None,
             },
             conn,
         )?;
@@ -955,9 +1075,38 @@ impl TryFrom<OutputSql> for DbUnblindedOutput {
                 OutputManagerStorageError::ConversionError
             })?,
             Some(OutputFeatures {
+                version: OutputFeaturesVersion::from(o.features_version as u8),
                 flags: OutputFlags::from_bits(o.flags as u8)
                     .ok_or_else(|| OutputManagerStorageError::ConversionError)?,
                 maturity: o.maturity as u64,
+                relative_maturity: o.relative_maturity as u64,
+                asset: o
+                    .asset
+                    .as_deref()
+                    .map(serde_json::from_str)
+                    .transpose()
+                    .map_err(|_| OutputManagerStorageError::ConversionError)?,
+                coinbase_extra: o.coinbase_extra.unwrap_or_default(),
+                htlc: o
+                    .htlc
+                    .as_deref()
+                    .map(serde_json::from_str)
+                    .transpose()
+                    .map_err(|_| OutputManagerStorageError::ConversionError)?,
+                multisig: o
+                    .multisig
+                    .as_deref()
+                    .map(serde_json::from_str)
+                    .transpose()
+                    .map_err(|_| OutputManagerStorageError::ConversionError)?,
+                script: o
+                    .script
+                    .as_deref()
+                    .map(serde_json::from_str)
+                    .transpose()
+                    .map_err(|_| OutputManagerStorageError::ConversionError)?,
+                // The wallet only creates and rewinds Bulletproofs, so it holds no outputs with any other range proof
+                range_proof_type: RangeProofType::BulletProof,
             }),
         );
         let hash = match o.hash {
@@ -981,6 +1130,7 @@ impl TryFrom<OutputSql> for DbUnblindedOutput {
             commitment,
             unblinded_output,
             hash,
+            mined_height: o.mined_height.map(|h| h as u64),
         })
     }
 }
@@ -1008,6 +1158,14 @@ impl From<OutputSql> for NewOutputSql {
             status: o.status,
             tx_id: o.tx_id,
             hash: o.hash,
+            relative_maturity: o.relative_maturity,
+            mined_height: o.mined_height,
+            asset: o.asset,
+            features_version: o.features_version,
+            coinbase_extra: o.coinbase_extra,
+            htlc: o.htlc,
+            multisig: o.multisig,
+            script: o.script,
         }
     }
 }
@@ -1023,6 +1181,7 @@ pub struct UpdateOutput {
     status: Option<OutputStatus>,
     tx_id: Option<TxId>,
     spending_key: Option<Vec<u8>>,
+    mined_height: Option<u64>,
 }
 
 #[derive(AsChangeset)]
@@ -1031,6 +1190,7 @@ pub struct UpdateOutputSql {
     status: Option<i32>,
     tx_id: Option<i64>,
     spending_key: Option<Vec<u8>>,
+    mined_height: Option<i64>,
 }
 
 #[derive(AsChangeset)]
@@ -1048,6 +1208,7 @@ impl From<UpdateOutput> for UpdateOutputSql {
             status: u.status.map(|t| t as i32),
             tx_id: u.tx_id.map(|t| t as i64),
             spending_key: u.spending_key,
+            mined_height: u.mined_height.map(|h| h as i64),
         }
     }
 }
@@ -1432,6 +1593,7 @@ mod test {
                     status: Some(OutputStatus::Unspent),
                     tx_id: Some(44u64),
                     spending_key: None,
+                    mined_height: None,
                 },
                 &conn,
             )
@@ -1444,6 +1606,7 @@ mod test {
                     status: Some(OutputStatus::EncumberedToBeReceived),
                     tx_id: Some(44u64),
                     spending_key: None,
+                    mined_height: None,
                 },
                 &conn,
             )
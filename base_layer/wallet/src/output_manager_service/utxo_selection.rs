@@ -0,0 +1,454 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Strategies used by the Output Manager to choose which unspent outputs fund a transaction.
+//!
+//! A strategy receives the spendable candidates and a [SelectionTarget] and returns the outputs to spend, the total
+//! value of the selection and whether a change output is required. The strategy used for a send is chosen with a
+//! [UtxoSelectionPolicy], either from the Output Manager config or per send.
+
+use crate::output_manager_service::storage::models::DbUnblindedOutput;
+use log::*;
+use std::{cmp::Ordering, fmt};
+use tari_core::transactions::{fee::Fee, tari_amount::MicroTari};
+
+const LOG_TARGET: &str = "wallet::output_manager_service::utxo_selection";
+
+/// What a selection needs to pay for: the amount sent to the recipient outputs and the fee of the transaction
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SelectionTarget {
+    pub amount: MicroTari,
+    pub fee_per_gram: MicroTari,
+    /// The fee calculator of the consensus weight formula
+    pub fee_calc: Fee,
+    /// The number of kernels of the transaction, one for each recipient
+    pub num_kernels: usize,
+    /// The number of outputs of the transaction, excluding a possible change output
+    pub num_outputs: usize,
+    /// The current chain tip height, None if the wallet is not connected to a base node
+    pub tip_height: Option<u64>,
+}
+
+impl SelectionTarget {
+    /// The fee of a transaction spending `num_inputs` inputs without a change output
+    pub fn fee_without_change(&self, num_inputs: usize) -> MicroTari {
+        self.fee_calc.calculate(
+            self.fee_per_gram,
+            self.num_kernels,
+            num_inputs,
+            self.num_outputs,
+            Fee::default_output_metadata_size(self.num_outputs),
+        )
+    }
+
+    /// The fee of a transaction spending `num_inputs` inputs with a change output
+    pub fn fee_with_change(&self, num_inputs: usize) -> MicroTari {
+        self.fee_calc.calculate(
+            self.fee_per_gram,
+            self.num_kernels,
+            num_inputs,
+            self.num_outputs + 1,
+            Fee::default_output_metadata_size(self.num_outputs + 1),
+        )
+    }
+}
+
+/// The outputs chosen by a strategy
+#[derive(Debug, Clone)]
+pub struct SelectedUtxos {
+    pub utxos: Vec<DbUnblindedOutput>,
+    pub total: MicroTari,
+    pub requires_change_output: bool,
+}
+
+impl SelectedUtxos {
+    /// Whether the selection pays for the target. A selection without a change output may exceed the amount and fee,
+    /// the excess is then added to the fee.
+    pub fn is_sufficient(&self, target: &SelectionTarget) -> bool {
+        let num_inputs = self.utxos.len();
+        if self.requires_change_output {
+            self.total >= target.amount + target.fee_with_change(num_inputs)
+        } else {
+            num_inputs > 0 && self.total >= target.amount + target.fee_without_change(num_inputs)
+        }
+    }
+}
+
+/// A way of choosing which unspent outputs are spent to pay for a transaction
+pub trait UtxoSelectionStrategy: Send + Sync {
+    /// A short name for logging
+    fn name(&self) -> &'static str;
+
+    /// Choose the outputs out of `candidates` to pay for `target`. The candidates are already known to be spendable.
+    /// If the candidates can't cover the target the returned selection is not sufficient, see
+    /// [SelectedUtxos::is_sufficient].
+    fn select(&self, candidates: Vec<DbUnblindedOutput>, target: &SelectionTarget) -> SelectedUtxos;
+}
+
+/// Accumulate the outputs in the given order until they pay for the target, with or without a change output
+fn select_in_order(candidates: Vec<DbUnblindedOutput>, target: &SelectionTarget) -> SelectedUtxos {
+    let mut utxos = Vec::new();
+    let mut total = MicroTari::from(0);
+    let mut requires_change_output = false;
+    for o in candidates {
+        total += o.unblinded_output.value;
+        utxos.push(o);
+        if total == target.amount + target.fee_without_change(utxos.len()) {
+            break;
+        }
+        if total >= target.amount + target.fee_with_change(utxos.len()) {
+            requires_change_output = true;
+            break;
+        }
+    }
+
+    SelectedUtxos {
+        utxos,
+        total,
+        requires_change_output,
+    }
+}
+
+fn sort_by_value(candidates: &mut [DbUnblindedOutput]) {
+    candidates.sort_by(|a, b| a.unblinded_output.value.cmp(&b.unblinded_output.value));
+}
+
+/// Spend the largest outputs first, which keeps the number of inputs, and so the fee, as low as possible
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LargestFirst;
+
+impl UtxoSelectionStrategy for LargestFirst {
+    fn name(&self) -> &'static str {
+        "LargestFirst"
+    }
+
+    fn select(&self, mut candidates: Vec<DbUnblindedOutput>, target: &SelectionTarget) -> SelectedUtxos {
+        sort_by_value(&mut candidates);
+        candidates.reverse();
+        select_in_order(candidates, target)
+    }
+}
+
+/// Spend the smallest outputs first. This consolidates dust outputs at the cost of a higher fee.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SmallestFirst;
+
+impl UtxoSelectionStrategy for SmallestFirst {
+    fn name(&self) -> &'static str {
+        "SmallestFirst"
+    }
+
+    fn select(&self, mut candidates: Vec<DbUnblindedOutput>, target: &SelectionTarget) -> SelectedUtxos {
+        sort_by_value(&mut candidates);
+        select_in_order(candidates, target)
+    }
+}
+
+/// Spend the outputs with the oldest maturity first, then the smallest, to reduce the likelihood of grabbing outputs
+/// that are still time-locked
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MaturityThenSmallest;
+
+impl UtxoSelectionStrategy for MaturityThenSmallest {
+    fn name(&self) -> &'static str {
+        "MaturityThenSmallest"
+    }
+
+    fn select(&self, mut candidates: Vec<DbUnblindedOutput>, target: &SelectionTarget) -> SelectedUtxos {
+        candidates.sort_by(|a, b| {
+            match a
+                .unblinded_output
+                .features
+                .maturity
+                .cmp(&b.unblinded_output.features.maturity)
+            {
+                Ordering::Equal => a.unblinded_output.value.cmp(&b.unblinded_output.value),
+                ordering => ordering,
+            }
+        });
+        select_in_order(candidates, target)
+    }
+}
+
+/// Look for one or two outputs that pay for the target without leaving enough for a change output. A transaction
+/// without change does not link a new output of the sender to the payment. If no such outputs exist the largest
+/// outputs are spent as with [LargestFirst].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AvoidChange;
+
+impl AvoidChange {
+    /// The index of the smallest candidate that pays for the target on its own without change
+    fn find_single(candidates: &[DbUnblindedOutput], target: &SelectionTarget) -> Option<usize> {
+        let min = target.amount + target.fee_without_change(1);
+        let max = target.amount + target.fee_with_change(1);
+        candidates.iter().position(|o| {
+            let value = o.unblinded_output.value;
+            value >= min && value < max
+        })
+    }
+
+    /// The indices of the pair of candidates with the smallest total that pays for the target without change
+    fn find_pair(candidates: &[DbUnblindedOutput], target: &SelectionTarget) -> Option<(usize, usize)> {
+        if candidates.len() < 2 {
+            return None;
+        }
+        let min = target.amount + target.fee_without_change(2);
+        let max = target.amount + target.fee_with_change(2);
+        let mut best: Option<(usize, usize, MicroTari)> = None;
+        let (mut low, mut high) = (0, candidates.len() - 1);
+        while low < high {
+            let total = candidates[low].unblinded_output.value + candidates[high].unblinded_output.value;
+            if total < min {
+                low += 1;
+            } else if total >= max {
+                high -= 1;
+            } else {
+                if best.map(|(_, _, best_total)| total < best_total).unwrap_or(true) {
+                    best = Some((low, high, total));
+                }
+                high -= 1;
+            }
+        }
+        best.map(|(low, high, _)| (low, high))
+    }
+}
+
+impl UtxoSelectionStrategy for AvoidChange {
+    fn name(&self) -> &'static str {
+        "AvoidChange"
+    }
+
+    fn select(&self, mut candidates: Vec<DbUnblindedOutput>, target: &SelectionTarget) -> SelectedUtxos {
+        sort_by_value(&mut candidates);
+
+        let chosen = match Self::find_single(&candidates, target) {
+            Some(index) => vec![index],
+            None => match Self::find_pair(&candidates, target) {
+                Some((low, high)) => vec![high, low],
+                None => {
+                    debug!(
+                        target: LOG_TARGET,
+                        "No selection without change found for {}, spending the largest outputs first", target.amount
+                    );
+                    return LargestFirst.select(candidates, target);
+                },
+            },
+        };
+
+        // Remove the higher index first so the lower index stays valid
+        let utxos = chosen
+            .into_iter()
+            .map(|index| candidates.swap_remove(index))
+            .collect::<Vec<_>>();
+        let total = utxos
+            .iter()
+            .fold(MicroTari::from(0), |total, o| total + o.unblinded_output.value);
+        SelectedUtxos {
+            utxos,
+            total,
+            requires_change_output: false,
+        }
+    }
+}
+
+/// The default heuristic: spend the largest outputs first if the amount is greater than the largest output, otherwise
+/// spend the outputs with the oldest maturity first
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultStrategy;
+
+impl UtxoSelectionStrategy for DefaultStrategy {
+    fn name(&self) -> &'static str {
+        "Default"
+    }
+
+    fn select(&self, candidates: Vec<DbUnblindedOutput>, target: &SelectionTarget) -> SelectedUtxos {
+        // Without the chain height it is unknown which outputs are mature, so prefer the oldest maturity
+        if target.tip_height.is_none() {
+            return MaturityThenSmallest.select(candidates, target);
+        }
+        let largest = candidates.iter().map(|o| o.unblinded_output.value).max();
+        match largest {
+            Some(largest) if target.amount > largest => LargestFirst.select(candidates, target),
+            _ => MaturityThenSmallest.select(candidates, target),
+        }
+    }
+}
+
+/// The selection strategies that can be chosen in the config or for a single send
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum UtxoSelectionPolicy {
+    Default,
+    LargestFirst,
+    SmallestFirst,
+    AvoidChange,
+}
+
+impl UtxoSelectionPolicy {
+    pub fn strategy(self) -> Box<dyn UtxoSelectionStrategy> {
+        match self {
+            Self::Default => Box::new(DefaultStrategy),
+            Self::LargestFirst => Box::new(LargestFirst),
+            Self::SmallestFirst => Box::new(SmallestFirst),
+            Self::AvoidChange => Box::new(AvoidChange),
+        }
+    }
+}
+
+impl fmt::Display for UtxoSelectionPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Default => f.write_str("'Default'"),
+            Self::LargestFirst => f.write_str("'LargestFirst'"),
+            Self::SmallestFirst => f.write_str("'SmallestFirst'"),
+            Self::AvoidChange => f.write_str("'AvoidChange'"),
+        }
+    }
+}
+
+impl From<String> for UtxoSelectionPolicy {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "Default" => Self::Default,
+            "LargestFirst" => Self::LargestFirst,
+            "SmallestFirst" => Self::SmallestFirst,
+            "AvoidChange" => Self::AvoidChange,
+            _ => {
+                warn!(
+                    target: LOG_TARGET,
+                    "UTXO selection strategy config setting not recognized, using default value {}",
+                    Self::Default
+                );
+                Self::Default
+            },
+        }
+    }
+}
+
+impl Default for UtxoSelectionPolicy {
+    fn default() -> Self {
+        Self::Default
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::OsRng;
+    use tari_core::transactions::{
+        transaction::UnblindedOutput,
+        types::{CryptoFactories, PrivateKey},
+    };
+    use tari_crypto::keys::SecretKey;
+
+    fn make_outputs(values: &[u64]) -> Vec<DbUnblindedOutput> {
+        let factories = CryptoFactories::default();
+        values
+            .iter()
+            .map(|value| {
+                let output = UnblindedOutput::new(MicroTari::from(*value), PrivateKey::random(&mut OsRng), None);
+                DbUnblindedOutput::from_unblinded_output(output, &factories).unwrap()
+            })
+            .collect()
+    }
+
+    fn values(selection: &SelectedUtxos) -> Vec<u64> {
+        selection
+            .utxos
+            .iter()
+            .map(|o| o.unblinded_output.value.into())
+            .collect()
+    }
+
+    fn target(amount: u64) -> SelectionTarget {
+        SelectionTarget {
+            amount: MicroTari::from(amount),
+            fee_per_gram: MicroTari::from(1),
+            fee_calc: Fee::default(),
+            num_kernels: 1,
+            num_outputs: 1,
+            tip_height: Some(100),
+        }
+    }
+
+    #[test]
+    fn largest_and_smallest_first() {
+        let target = target(5_000);
+        let candidates = make_outputs(&[1_000, 4_000, 2_000, 8_000]);
+
+        let selection = LargestFirst.select(candidates.clone(), &target);
+        assert_eq!(values(&selection), vec![8_000]);
+        assert!(selection.requires_change_output);
+        assert!(selection.is_sufficient(&target));
+
+        let selection = SmallestFirst.select(candidates, &target);
+        assert_eq!(values(&selection), vec![1_000, 2_000, 4_000]);
+        assert!(selection.requires_change_output);
+        assert!(selection.is_sufficient(&target));
+
+        let selection = SmallestFirst.select(make_outputs(&[1_000, 2_000]), &target);
+        assert!(!selection.is_sufficient(&target));
+    }
+
+    #[test]
+    fn avoid_change_finds_change_free_selections() {
+        let target = target(5_000);
+        let single = target.amount + target.fee_without_change(1);
+        let pair = target.amount + target.fee_without_change(2);
+
+        // A single output that covers the amount and fee exactly is preferred
+        let candidates = make_outputs(&[1_000, u64::from(single), 9_000]);
+        let selection = AvoidChange.select(candidates, &target);
+        assert_eq!(values(&selection), vec![u64::from(single)]);
+        assert!(!selection.requires_change_output);
+        assert!(selection.is_sufficient(&target));
+
+        // Otherwise a pair whose excess is too small for a change output
+        let candidates = make_outputs(&[3_000, 9_000, u64::from(pair) - 3_000 + 1, 100]);
+        let selection = AvoidChange.select(candidates, &target);
+        assert_eq!(values(&selection), vec![3_000, u64::from(pair) - 3_000 + 1]);
+        assert!(!selection.requires_change_output);
+        assert!(selection.is_sufficient(&target));
+
+        // And the largest outputs when neither exists
+        let candidates = make_outputs(&[3_000, 9_000, 20_000]);
+        let selection = AvoidChange.select(candidates, &target);
+        assert_eq!(values(&selection), vec![20_000]);
+        assert!(selection.requires_change_output);
+    }
+
+    #[test]
+    fn policy_from_config_string() {
+        assert_eq!(
+            UtxoSelectionPolicy::from("AvoidChange".to_string()),
+            UtxoSelectionPolicy::AvoidChange
+        );
+        assert_eq!(
+            UtxoSelectionPolicy::from("SmallestFirst".to_string()),
+            UtxoSelectionPolicy::SmallestFirst
+        );
+        assert_eq!(
+            UtxoSelectionPolicy::from("Nonsense".to_string()),
+            UtxoSelectionPolicy::Default
+        );
+        assert_eq!(UtxoSelectionPolicy::AvoidChange.strategy().name(), "AvoidChange");
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use super::error::SchedulerServiceError;
+use crate::{output_manager_service::TxId, storage::database::ScheduledPayment};
+use chrono::NaiveDateTime;
+use futures::{stream::Fuse, StreamExt};
+use std::{sync::Arc, time::Duration};
+use tari_comms::types::CommsPublicKey;
+use tari_core::transactions::tari_amount::MicroTari;
+use tari_service_framework::reply_channel::SenderService;
+use tokio::sync::broadcast;
+use tower::Service;
+
+pub type SchedulerEventSender = broadcast::Sender<Arc<SchedulerEvent>>;
+pub type SchedulerEventReceiver = broadcast::Receiver<Arc<SchedulerEvent>>;
+
+/// API Request enum
+#[derive(Debug)]
+pub enum SchedulerServiceRequest {
+    /// Schedule a payment of (destination, amount, fee per gram, message) every interval, starting at the given time
+    AddScheduledPayment((CommsPublicKey, MicroTari, MicroTari, String, Duration, NaiveDateTime)),
+    GetScheduledPayments,
+    RemoveScheduledPayment(u64),
+}
+
+/// API Response enum
+#[derive(Debug)]
+pub enum SchedulerServiceResponse {
+    ScheduledPaymentAdded(u64),
+    ScheduledPayments(Vec<ScheduledPayment>),
+    ScheduledPaymentRemoved,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SchedulerEvent {
+    /// The transaction for a scheduled payment was submitted to the transaction service
+    ScheduledPaymentSubmitted(u64, TxId),
+    /// A due scheduled payment could not be sent, it will be retried on the next check
+    ScheduledPaymentFailed(u64, String),
+}
+
+/// The Scheduler Service Handle is a struct that contains the interfaces used to manage the recurring payments of the
+/// wallet
+#[derive(Clone)]
+pub struct SchedulerServiceHandle {
+    handle: SenderService<SchedulerServiceRequest, Result<SchedulerServiceResponse, SchedulerServiceError>>,
+    event_stream_sender: SchedulerEventSender,
+}
+
+impl SchedulerServiceHandle {
+    pub fn new(
+        handle: SenderService<SchedulerServiceRequest, Result<SchedulerServiceResponse, SchedulerServiceError>>,
+        event_stream_sender: SchedulerEventSender,
+    ) -> Self
+    {
+        Self {
+            handle,
+            event_stream_sender,
+        }
+    }
+
+    pub fn get_event_stream_fused(&self) -> Fuse<SchedulerEventReceiver> {
+        self.event_stream_sender.subscribe().fuse()
+    }
+
+    /// Schedule a recurring payment, returning the id of the scheduled payment. The first payment is sent once
+    /// `first_payment` has passed and the wallet is online.
+    pub async fn add_scheduled_payment(
+        &mut self,
+        dest_pubkey: CommsPublicKey,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+        interval: Duration,
+        first_payment: NaiveDateTime,
+    ) -> Result<u64, SchedulerServiceError>
+    {
+        match self
+            .handle
+            .call(SchedulerServiceRequest::AddScheduledPayment((
+                dest_pubkey,
+                amount,
+                fee_per_gram,
+                message,
+                interval,
+                first_payment,
+            )))
+            .await??
+        {
+            SchedulerServiceResponse::ScheduledPaymentAdded(id) => Ok(id),
+            _ => Err(SchedulerServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Returns the scheduled payments, ordered by when the next payment is due
+    pub async fn get_scheduled_payments(&mut self) -> Result<Vec<ScheduledPayment>, SchedulerServiceError> {
+        match self
+            .handle
+            .call(SchedulerServiceRequest::GetScheduledPayments)
+            .await??
+        {
+            SchedulerServiceResponse::ScheduledPayments(payments) => Ok(payments),
+            _ => Err(SchedulerServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn remove_scheduled_payment(&mut self, id: u64) -> Result<(), SchedulerServiceError> {
+        match self
+            .handle
+            .call(SchedulerServiceRequest::RemoveScheduledPayment(id))
+            .await??
+        {
+            SchedulerServiceResponse::ScheduledPaymentRemoved => Ok(()),
+            _ => Err(SchedulerServiceError::UnexpectedApiResponse),
+        }
+    }
+}
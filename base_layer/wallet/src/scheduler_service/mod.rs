@@ -0,0 +1,102 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+pub mod config;
+pub mod error;
+pub mod handle;
+pub mod service;
+
+use crate::{
+    scheduler_service::{config::SchedulerServiceConfig, handle::SchedulerServiceHandle, service::SchedulerService},
+    storage::database::{WalletBackend, WalletDatabase},
+    transaction_service::handle::TransactionServiceHandle,
+};
+use futures::{future, Future};
+use log::*;
+use tari_comms::connectivity::ConnectivityRequester;
+use tari_service_framework::{
+    reply_channel,
+    ServiceInitializationError,
+    ServiceInitializer,
+    ServiceInitializerContext,
+};
+use tokio::sync::broadcast;
+
+const LOG_TARGET: &str = "wallet::scheduler_service";
+
+pub struct SchedulerServiceInitializer<T>
+where T: WalletBackend + 'static
+{
+    config: SchedulerServiceConfig,
+    db: WalletDatabase<T>,
+}
+
+impl<T> SchedulerServiceInitializer<T>
+where T: WalletBackend + 'static
+{
+    pub fn new(config: SchedulerServiceConfig, db: WalletDatabase<T>) -> Self {
+        Self { config, db }
+    }
+}
+
+impl<T> ServiceInitializer for SchedulerServiceInitializer<T>
+where T: WalletBackend + 'static
+{
+    type Future = impl Future<Output = Result<(), ServiceInitializationError>>;
+
+    fn initialize(&mut self, context: ServiceInitializerContext) -> Self::Future {
+        info!(target: LOG_TARGET, "Wallet scheduler service initializing.");
+
+        let (sender, request_stream) = reply_channel::unbounded();
+
+        let (event_publisher, _) = broadcast::channel(200);
+
+        let scheduler_service_handle = SchedulerServiceHandle::new(sender, event_publisher.clone());
+
+        // Register handle before waiting for handles to be ready
+        context.register_handle(scheduler_service_handle);
+
+        let config = self.config.clone();
+        let db = self.db.clone();
+
+        context.spawn_when_ready(move |handles| async move {
+            let transaction_service = handles.expect_handle::<TransactionServiceHandle>();
+            let connectivity_manager = handles.expect_handle::<ConnectivityRequester>();
+
+            let service = SchedulerService::new(
+                config,
+                request_stream,
+                db,
+                transaction_service,
+                connectivity_manager,
+                event_publisher,
+                handles.get_shutdown_signal(),
+            )
+            .start();
+            futures::pin_mut!(service);
+            let _ = service.await;
+            info!(target: LOG_TARGET, "Wallet Scheduler Service shutdown");
+        });
+
+        future::ready(Ok(()))
+    }
+}
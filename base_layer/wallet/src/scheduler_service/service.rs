@@ -0,0 +1,321 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use super::{
+    config::SchedulerServiceConfig,
+    error::SchedulerServiceError,
+    handle::{SchedulerEvent, SchedulerEventSender, SchedulerServiceRequest, SchedulerServiceResponse},
+};
+use crate::{
+    storage::database::{ScheduledPayment, WalletBackend, WalletDatabase},
+    transaction_service::handle::TransactionServiceHandle,
+};
+use chrono::{Duration as ChronoDuration, NaiveDateTime, Utc};
+use futures::{pin_mut, StreamExt};
+use log::*;
+use rand::{rngs::OsRng, RngCore};
+use std::{sync::Arc, time::Duration};
+use tari_comms::{connectivity::ConnectivityRequester, types::CommsPublicKey};
+use tari_core::transactions::tari_amount::MicroTari;
+use tari_service_framework::reply_channel::Receiver;
+use tari_shutdown::ShutdownSignal;
+use tokio::time;
+
+const LOG_TARGET: &str = "wallet::scheduler_service::service";
+
+/// The scheduler service keeps the recurring payments of the wallet in the wallet database and submits each payment to
+/// the transaction service when it is due and the wallet is online.
+pub struct SchedulerService<T>
+where T: WalletBackend + 'static
+{
+    config: SchedulerServiceConfig,
+    request_stream: Option<Receiver<SchedulerServiceRequest, Result<SchedulerServiceResponse, SchedulerServiceError>>>,
+    db: WalletDatabase<T>,
+    transaction_service: TransactionServiceHandle,
+    connectivity_manager: ConnectivityRequester,
+    event_publisher: SchedulerEventSender,
+    shutdown_signal: Option<ShutdownSignal>,
+}
+
+impl<T> SchedulerService<T>
+where T: WalletBackend + 'static
+{
+    pub fn new(
+        config: SchedulerServiceConfig,
+        request_stream: Receiver<SchedulerServiceRequest, Result<SchedulerServiceResponse, SchedulerServiceError>>,
+        db: WalletDatabase<T>,
+        transaction_service: TransactionServiceHandle,
+        connectivity_manager: ConnectivityRequester,
+        event_publisher: SchedulerEventSender,
+        shutdown_signal: ShutdownSignal,
+    ) -> Self
+    {
+        Self {
+            config,
+            request_stream: Some(request_stream),
+            db,
+            transaction_service,
+            connectivity_manager,
+            event_publisher,
+            shutdown_signal: Some(shutdown_signal),
+        }
+    }
+
+    /// Starts the service.
+    pub async fn start(mut self) -> Result<(), SchedulerServiceError> {
+        let mut shutdown = self
+            .shutdown_signal
+            .take()
+            .expect("Wallet Scheduler Service initialized without shutdown signal");
+
+        let request_stream = self
+            .request_stream
+            .take()
+            .expect("Wallet Scheduler Service initialized without request_stream")
+            .fuse();
+        pin_mut!(request_stream);
+
+        let mut check_ticker = time::interval(self.config.check_interval).fuse();
+
+        info!(target: LOG_TARGET, "Wallet Scheduler Service started");
+        loop {
+            futures::select! {
+                request_context = request_stream.select_next_some() => {
+                    let (request, reply_tx) = request_context.split();
+                    let response = self.handle_request(request).await.map_err(|e| {
+                        error!(target: LOG_TARGET, "Error handling request: {:?}", e);
+                        e
+                    });
+                    let _ = reply_tx.send(response).map_err(|e| {
+                        warn!(target: LOG_TARGET, "Failed to send reply");
+                        e
+                    });
+                },
+                _ = check_ticker.select_next_some() => {
+                    if let Err(e) = self.send_due_payments().await {
+                        warn!(target: LOG_TARGET, "Could not send the due scheduled payments: {}", e);
+                    }
+                },
+                _ = shutdown => {
+                    info!(
+                        target: LOG_TARGET,
+                        "Wallet Scheduler Service shutting down because the shutdown signal was received"
+                    );
+                    break;
+                },
+                complete => {
+                    info!(target: LOG_TARGET, "Wallet Scheduler Service shutting down");
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// This handler is called when requests arrive from the various streams
+    async fn handle_request(
+        &mut self,
+        request: SchedulerServiceRequest,
+    ) -> Result<SchedulerServiceResponse, SchedulerServiceError>
+    {
+        debug!(
+            target: LOG_TARGET,
+            "Handling Wallet Scheduler Service Request: {:?}", request
+        );
+        match request {
+            SchedulerServiceRequest::AddScheduledPayment((
+                dest_pubkey,
+                amount,
+                fee_per_gram,
+                message,
+                interval,
+                first_payment,
+            )) => self
+                .add_scheduled_payment(dest_pubkey, amount, fee_per_gram, message, interval, first_payment)
+                .await
+                .map(SchedulerServiceResponse::ScheduledPaymentAdded),
+            SchedulerServiceRequest::GetScheduledPayments => {
+                let mut payments = self.db.get_scheduled_payments().await?;
+                payments.sort_by_key(|p| p.next_payment);
+                Ok(SchedulerServiceResponse::ScheduledPayments(payments))
+            },
+            SchedulerServiceRequest::RemoveScheduledPayment(id) => {
+                if !self.db.remove_scheduled_payment(id).await? {
+                    return Err(SchedulerServiceError::ScheduledPaymentNotFound(id));
+                }
+                info!(target: LOG_TARGET, "Scheduled payment {} removed", id);
+                Ok(SchedulerServiceResponse::ScheduledPaymentRemoved)
+            },
+        }
+    }
+
+    async fn add_scheduled_payment(
+        &mut self,
+        dest_pubkey: CommsPublicKey,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+        interval: Duration,
+        first_payment: NaiveDateTime,
+    ) -> Result<u64, SchedulerServiceError>
+    {
+        if amount == MicroTari::from(0) {
+            return Err(SchedulerServiceError::ZeroAmount);
+        }
+        if interval < self.config.minimum_payment_interval {
+            return Err(SchedulerServiceError::IntervalTooShort(
+                self.config.minimum_payment_interval.as_secs(),
+            ));
+        }
+
+        let payment = ScheduledPayment {
+            id: OsRng.next_u64(),
+            destination_public_key: dest_pubkey,
+            amount,
+            fee_per_gram,
+            message,
+            interval,
+            next_payment: first_payment,
+            last_tx_id: None,
+        };
+        let id = payment.id;
+        self.db.set_scheduled_payment(payment).await?;
+        info!(
+            target: LOG_TARGET,
+            "Scheduled payment {} of {} every {}s added",
+            id,
+            amount,
+            interval.as_secs()
+        );
+
+        Ok(id)
+    }
+
+    /// Submit every payment that is due. Nothing is sent while the wallet is offline, the due payments are sent on the
+    /// first check after it comes back online.
+    async fn send_due_payments(&mut self) -> Result<(), SchedulerServiceError> {
+        let status = self.connectivity_manager.get_connectivity_status().await?;
+        if !status.is_online() && !status.is_degraded() {
+            trace!(
+                target: LOG_TARGET,
+                "Not sending scheduled payments because the wallet is not online ({})",
+                status
+            );
+            return Ok(());
+        }
+
+        let now = Utc::now().naive_utc();
+        let due_payments = self
+            .db
+            .get_scheduled_payments()
+            .await?
+            .into_iter()
+            .filter(|p| p.next_payment <= now);
+
+        for mut payment in due_payments {
+            match self
+                .transaction_service
+                .send_transaction(
+                    payment.destination_public_key.clone(),
+                    payment.amount,
+                    payment.fee_per_gram,
+                    payment.message.clone(),
+                )
+                .await
+            {
+                Ok(tx_id) => {
+                    info!(
+                        target: LOG_TARGET,
+                        "Scheduled payment {} submitted as transaction {}", payment.id, tx_id
+                    );
+                    payment.last_tx_id = Some(tx_id);
+                    payment.next_payment = next_payment_after(&payment, now);
+                    self.db.set_scheduled_payment(payment.clone()).await?;
+                    self.publish_event(SchedulerEvent::ScheduledPaymentSubmitted(payment.id, tx_id));
+                },
+                Err(e) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Scheduled payment {} could not be sent: {}", payment.id, e
+                    );
+                    self.publish_event(SchedulerEvent::ScheduledPaymentFailed(payment.id, e.to_string()));
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    fn publish_event(&self, event: SchedulerEvent) {
+        trace!(target: LOG_TARGET, "Publishing event: {:?}", event);
+        let _ = self.event_publisher.send(Arc::new(event)).map_err(|_| {
+            trace!(
+                target: LOG_TARGET,
+                "Could not publish SchedulerEvent as there are no subscribers"
+            )
+        });
+    }
+}
+
+/// The first time in the schedule of the payment that is after `now`. Payments that were missed while the wallet was
+/// offline are skipped rather than sent all at once.
+fn next_payment_after(payment: &ScheduledPayment, now: NaiveDateTime) -> NaiveDateTime {
+    let interval = (payment.interval.as_secs() as i64).max(1);
+    let elapsed = (now - payment.next_payment).num_seconds().max(0);
+    payment.next_payment + ChronoDuration::seconds((elapsed / interval + 1) * interval)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tari_comms::types::CommsSecretKey;
+    use tari_crypto::keys::{PublicKey, SecretKey};
+
+    #[test]
+    fn missed_payments_are_skipped() {
+        let start = NaiveDateTime::from_timestamp(1_600_000_000, 0);
+        let payment = ScheduledPayment {
+            id: 1,
+            destination_public_key: CommsPublicKey::from_secret_key(&CommsSecretKey::random(&mut OsRng)),
+            amount: MicroTari::from(1000),
+            fee_per_gram: MicroTari::from(20),
+            message: String::new(),
+            interval: Duration::from_secs(3600),
+            next_payment: start,
+            last_tx_id: None,
+        };
+
+        assert_eq!(
+            next_payment_after(&payment, start),
+            start + ChronoDuration::seconds(3600)
+        );
+        assert_eq!(
+            next_payment_after(&payment, start + ChronoDuration::seconds(3599)),
+            start + ChronoDuration::seconds(3600)
+        );
+        // The wallet was offline for three and a half periods
+        assert_eq!(
+            next_payment_after(&payment, start + ChronoDuration::seconds(3 * 3600 + 1800)),
+            start + ChronoDuration::seconds(4 * 3600)
+        );
+    }
+}
@@ -86,6 +86,14 @@ table! {
         status -> Integer,
         tx_id -> Nullable<BigInt>,
         hash -> Nullable<Binary>,
+        relative_maturity -> BigInt,
+        mined_height -> Nullable<BigInt>,
+        asset -> Nullable<Text>,
+        features_version -> Integer,
+        coinbase_extra -> Nullable<Binary>,
+        htlc -> Nullable<Text>,
+        multisig -> Nullable<Text>,
+        script -> Nullable<Text>,
     }
 }
 
@@ -98,6 +106,26 @@ table! {
     }
 }
 
+table! {
+    scheduled_payments (id) {
+        id -> BigInt,
+        destination_public_key -> Binary,
+        amount -> BigInt,
+        fee_per_gram -> BigInt,
+        message -> Text,
+        interval_secs -> BigInt,
+        next_payment -> Timestamp,
+        last_tx_id -> Nullable<BigInt>,
+    }
+}
+
+table! {
+    transaction_labels (tx_id, label) {
+        tx_id -> BigInt,
+        label -> Text,
+    }
+}
+
 table! {
     wallet_settings (key) {
         key -> Text,
@@ -105,6 +133,16 @@ table! {
     }
 }
 
+table! {
+    watched_outputs (hash) {
+        hash -> Binary,
+        value -> BigInt,
+        detected_at -> Timestamp,
+        spent_at -> Nullable<Timestamp>,
+        output -> Nullable<Text>,
+    }
+}
+
 allow_tables_to_appear_in_same_query!(
     client_key_values,
     completed_transactions,
@@ -114,5 +152,8 @@ allow_tables_to_appear_in_same_query!(
     outbound_transactions,
     outputs,
     pending_transaction_outputs,
+    scheduled_payments,
+    transaction_labels,
     wallet_settings,
+    watched_outputs,
 );
@@ -0,0 +1,243 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Encrypted backups of the wallet database. A backup file holds a magic number, the length of the passphrase KDF as
+//! a big-endian u16, the KDF and the database encrypted under the cipher the KDF derives from the backup passphrase,
+//! in that order. The AEAD tag of the ciphertext is what detects a wrong passphrase or a corrupted file.
+
+use crate::{
+    error::WalletStorageError,
+    storage::{database::WalletBackend, sqlite_utilities::run_migration_and_create_sqlite_connection},
+    util::encryption::{decrypt_bytes_integral_nonce, encrypt_bytes_integral_nonce, KdfParameters, PassphraseKdf},
+};
+use digest::Digest;
+use log::*;
+use std::{
+    convert::{TryFrom, TryInto},
+    fs,
+    path::Path,
+};
+use tari_crypto::{common::Blake256, tari_utilities::hex::to_hex};
+
+const LOG_TARGET: &str = "wallet::storage::backup";
+
+const BACKUP_MAGIC: &[u8] = b"TARI_WALLET_BACKUP_2";
+const KDF_LENGTH_BYTES: usize = 2;
+
+/// Write a backup of the database of `db` to `backup_path`, encrypted under a cipher derived from `passphrase` with
+/// Argon2id and a new salt, then read it back to check that it restores. Returns the hex Blake256 checksum of the
+/// backup file.
+pub fn create_backup<T: WalletBackend>(
+    db: &T,
+    backup_path: &Path,
+    passphrase: &str,
+    kdf_parameters: KdfParameters,
+) -> Result<String, WalletStorageError>
+{
+    kdf_parameters.validate()?;
+
+    let snapshot = db.snapshot()?;
+
+    let kdf = PassphraseKdf::argon2id(kdf_parameters);
+    let kdf_header = kdf.to_string().into_bytes();
+    let kdf_length = u16::try_from(kdf_header.len())
+        .map_err(|_| WalletStorageError::InvalidBackup("The passphrase KDF is too long".to_string()))?;
+    let mut backup = BACKUP_MAGIC.to_vec();
+    backup.extend_from_slice(&kdf_length.to_be_bytes());
+    backup.extend(kdf_header);
+    backup.extend(
+        encrypt_bytes_integral_nonce(&kdf.cipher(passphrase)?, snapshot)
+            .map_err(|e| WalletStorageError::AeadError(format!("Encryption Error:{}", e)))?,
+    );
+    fs::write(backup_path, &backup)?;
+
+    let written = fs::read(backup_path)?;
+    decode_backup(&written, passphrase)?;
+    let checksum = to_hex(Blake256::digest(&written).as_slice());
+    info!(
+        target: LOG_TARGET,
+        "Wallet backup written to {} with checksum {}",
+        backup_path.display(),
+        checksum
+    );
+    Ok(checksum)
+}
+
+/// Restore the backup at `backup_path` to a new wallet database at `db_path`, after decrypting it
+pub fn restore_backup(backup_path: &Path, passphrase: &str, db_path: &Path) -> Result<(), WalletStorageError> {
+    if db_path.exists() {
+        return Err(WalletStorageError::FileError(format!(
+            "Cannot restore a backup over the existing wallet database {}",
+            db_path.display()
+        )));
+    }
+    let database = decode_backup(&fs::read(backup_path)?, passphrase)?;
+    fs::write(db_path, database)?;
+
+    // Bring the restored database up to date with the migrations, which also checks that it is a wallet database
+    if let Err(e) = run_migration_and_create_sqlite_connection(db_path) {
+        let _ = fs::remove_file(db_path);
+        return Err(e);
+    }
+    info!(
+        target: LOG_TARGET,
+        "Wallet backup {} restored to {}",
+        backup_path.display(),
+        db_path.display()
+    );
+    Ok(())
+}
+
+/// Decrypt the database in a backup under the cipher its KDF derives from `passphrase`
+fn decode_backup(backup: &[u8], passphrase: &str) -> Result<Vec<u8>, WalletStorageError> {
+    let not_a_backup = || WalletStorageError::InvalidBackup("Not a wallet backup file".to_string());
+    if backup.len() < BACKUP_MAGIC.len() + KDF_LENGTH_BYTES || !backup.starts_with(BACKUP_MAGIC) {
+        return Err(not_a_backup());
+    }
+    let (kdf_length, rest) = backup[BACKUP_MAGIC.len()..].split_at(KDF_LENGTH_BYTES);
+    let kdf_length = u16::from_be_bytes(kdf_length.try_into().map_err(|_| not_a_backup())?) as usize;
+    if rest.len() < kdf_length {
+        return Err(not_a_backup());
+    }
+    let (kdf, ciphertext) = rest.split_at(kdf_length);
+    let kdf = std::str::from_utf8(kdf)
+        .map_err(|_| not_a_backup())?
+        .parse::<PassphraseKdf>()
+        .map_err(|_| WalletStorageError::InvalidBackup("The passphrase KDF of the backup is invalid".to_string()))?;
+    match &kdf {
+        PassphraseKdf::Blake256 => {
+            return Err(WalletStorageError::InvalidBackup(
+                "A backup must derive its cipher with Argon2id".to_string(),
+            ))
+        },
+        PassphraseKdf::Argon2id { parameters, .. } => parameters.validate()?,
+    }
+    decrypt_bytes_integral_nonce(&kdf.cipher(passphrase)?, ciphertext.to_vec())
+        .map_err(|_| WalletStorageError::IncorrectPassword)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        storage::{
+            database::{DbKey, DbKeyValuePair, DbValue, WriteOperation},
+            sqlite_db::WalletSqliteDatabase,
+        },
+        util::encryption::{passphrase_cipher, MIN_KDF_MEMORY_KIB},
+    };
+    use aes_gcm::Aes256Gcm;
+    use tempfile::tempdir;
+
+    const TEST_KDF_PARAMETERS: KdfParameters = KdfParameters {
+        memory_kib: MIN_KDF_MEMORY_KIB,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    #[test]
+    fn a_backup_restores_to_the_same_database() {
+        let tempdir = tempdir().unwrap();
+        let db_path = tempdir.path().join("wallet.sqlite3");
+        let backup_path = tempdir.path().join("wallet.backup");
+        let restored_path = tempdir.path().join("restored.sqlite3");
+
+        let connection = run_migration_and_create_sqlite_connection(&db_path).unwrap();
+        let db = WalletSqliteDatabase::new(connection, None).unwrap();
+        db.write(WriteOperation::Insert(DbKeyValuePair::ClientKeyValue(
+            "key".to_string(),
+            "value".to_string(),
+        )))
+        .unwrap();
+
+        let checksum = create_backup(&db, &backup_path, "backup password", TEST_KDF_PARAMETERS).unwrap();
+        let backup = fs::read(&backup_path).unwrap();
+        assert_eq!(checksum, to_hex(Blake256::digest(&backup).as_slice()));
+        assert!(backup.starts_with(BACKUP_MAGIC));
+        // The plaintext snapshot taken next to the database has been removed
+        assert!(fs::read_dir(tempdir.path()).unwrap().all(|entry| !entry
+            .unwrap()
+            .file_name()
+            .to_string_lossy()
+            .starts_with(".wallet-snapshot")));
+
+        assert!(matches!(
+            restore_backup(&backup_path, "wrong password", &restored_path),
+            Err(WalletStorageError::IncorrectPassword)
+        ));
+        assert!(matches!(
+            restore_backup(&backup_path, "backup password", &db_path),
+            Err(WalletStorageError::FileError(_))
+        ));
+        restore_backup(&backup_path, "backup password", &restored_path).unwrap();
+
+        let connection = run_migration_and_create_sqlite_connection(&restored_path).unwrap();
+        let restored = WalletSqliteDatabase::new(connection, None).unwrap();
+        match restored.fetch(&DbKey::ClientKey("key".to_string())).unwrap() {
+            Some(DbValue::ClientValue(v)) => assert_eq!(v, "value"),
+            _ => panic!("The restored database should hold the client value"),
+        }
+    }
+
+    #[test]
+    fn a_corrupted_backup_is_rejected() {
+        let tempdir = tempdir().unwrap();
+        let backup_path = tempdir.path().join("wallet.backup");
+        let db_path = tempdir.path().join("wallet.sqlite3");
+
+        fs::write(&backup_path, b"not a backup").unwrap();
+        assert!(matches!(
+            restore_backup(&backup_path, "password", &db_path),
+            Err(WalletStorageError::InvalidBackup(_))
+        ));
+
+        // The AEAD tag detects a changed ciphertext, and the KDF must not be the unsalted hash
+        let database = b"database".to_vec();
+        let kdf = PassphraseKdf::argon2id(TEST_KDF_PARAMETERS);
+        let encode = |kdf: &PassphraseKdf, cipher: Aes256Gcm| {
+            let kdf = kdf.to_string().into_bytes();
+            let mut backup = BACKUP_MAGIC.to_vec();
+            backup.extend_from_slice(&(kdf.len() as u16).to_be_bytes());
+            backup.extend(kdf);
+            backup.extend(encrypt_bytes_integral_nonce(&cipher, database.clone()).unwrap());
+            backup
+        };
+        let mut backup = encode(&kdf, kdf.cipher("password").unwrap());
+        assert_eq!(decode_backup(&backup, "password").unwrap(), database);
+        let last = backup.len() - 1;
+        backup[last] ^= 1;
+        assert!(matches!(
+            decode_backup(&backup, "password"),
+            Err(WalletStorageError::IncorrectPassword)
+        ));
+        let backup = encode(&PassphraseKdf::Blake256, passphrase_cipher("password"));
+        assert!(matches!(
+            decode_backup(&backup, "password"),
+            Err(WalletStorageError::InvalidBackup(_))
+        ));
+        let backup = BACKUP_MAGIC.iter().chain(&[0u8, 200]).cloned().collect::<Vec<_>>();
+        assert!(matches!(
+            decode_backup(&backup, "password"),
+            Err(WalletStorageError::InvalidBackup(_))
+        ));
+    }
+}
@@ -20,12 +20,20 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use crate::error::WalletStorageError;
+use crate::{
+    error::WalletStorageError,
+    output_manager_service::{handle::PublicRewindKeys, TxId},
+    storage::backup,
+    util::encryption::{KdfParameters, PassphraseKdf},
+};
 use aes_gcm::Aes256Gcm;
+use chrono::NaiveDateTime;
 use log::*;
 use std::{
     fmt::{Display, Error, Formatter},
+    path::PathBuf,
     sync::Arc,
+    time::Duration,
 };
 use tari_common_types::chain_metadata::ChainMetadata;
 use tari_comms::{
@@ -34,6 +42,7 @@ use tari_comms::{
     tor::TorIdentity,
     types::{CommsPublicKey, CommsSecretKey},
 };
+use tari_core::transactions::{tari_amount::MicroTari, transaction::TransactionOutput};
 
 const LOG_TARGET: &str = "wallet::database";
 
@@ -43,10 +52,20 @@ pub trait WalletBackend: Send + Sync + Clone {
     fn fetch(&self, key: &DbKey) -> Result<Option<DbValue>, WalletStorageError>;
     /// Modify the state the of the backend with a write operation
     fn write(&self, op: WriteOperation) -> Result<Option<DbValue>, WalletStorageError>;
-    /// Apply encryption to the backend.
+    /// Apply encryption to the backend, including the values of the other services that share the database, in a
+    /// single db transaction.
     fn apply_encryption(&self, cipher: Aes256Gcm) -> Result<(), WalletStorageError>;
-    /// Remove encryption from the backend.
+    /// Remove encryption from the backend, including the values of the other services that share the database, in a
+    /// single db transaction.
     fn remove_encryption(&self) -> Result<(), WalletStorageError>;
+    /// Re-encrypt every encrypted value in the database with the provided cipher instead of the current one,
+    /// including the values of the other services that share the database, and store the KDF the cipher was derived
+    /// with, in a single db transaction.
+    fn rotate_cipher(&self, cipher: Aes256Gcm, kdf: PassphraseKdf) -> Result<(), WalletStorageError>;
+    /// Check that the provided cipher is the one the backend is encrypted with.
+    fn verify_cipher(&self, cipher: &Aes256Gcm) -> Result<(), WalletStorageError>;
+    /// Take a consistent copy of the whole database. Any file the copy is written to on the way is removed again.
+    fn snapshot(&self) -> Result<Vec<u8>, WalletStorageError>;
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -59,6 +78,11 @@ pub enum DbKey {
     TorId,
     BaseNodeChainMetadata,
     ClientKey(String),
+    ScheduledPayment(u64),
+    ScheduledPayments,
+    WatchOnlyKeys,
+    WatchedOutputs,
+    PassphraseKdf,
 }
 
 pub enum DbValue {
@@ -71,6 +95,11 @@ pub enum DbValue {
     ClientValue(String),
     ValueCleared,
     BaseNodeChainMetadata(ChainMetadata),
+    ScheduledPayment(Box<ScheduledPayment>),
+    ScheduledPayments(Vec<ScheduledPayment>),
+    WatchOnlyKeys(Box<PublicRewindKeys>),
+    WatchedOutputs(Vec<WatchedOutput>),
+    PassphraseKdf(PassphraseKdf),
 }
 
 #[derive(Clone)]
@@ -80,6 +109,40 @@ pub enum DbKeyValuePair {
     Identity(Box<NodeIdentity>),
     TorId(TorIdentity),
     BaseNodeChainMetadata(ChainMetadata),
+    ScheduledPayment(Box<ScheduledPayment>),
+    WatchOnlyKeys(Box<PublicRewindKeys>),
+    WatchedOutput(Box<WatchedOutput>),
+    PassphraseKdf(PassphraseKdf),
+}
+
+/// A payment that is sent to the same recipient every `interval`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScheduledPayment {
+    pub id: u64,
+    pub destination_public_key: CommsPublicKey,
+    pub amount: MicroTari,
+    pub fee_per_gram: MicroTari,
+    pub message: String,
+    pub interval: Duration,
+    /// When the next payment is due
+    pub next_payment: NaiveDateTime,
+    /// The transaction of the last payment that was submitted
+    pub last_tx_id: Option<TxId>,
+}
+
+/// An output that a watch-only wallet found on the chain with its view key
+#[derive(Clone, Debug, PartialEq)]
+pub struct WatchedOutput {
+    /// The hash of the output, with which the base node is asked whether the output is still unspent
+    pub hash: Vec<u8>,
+    pub value: MicroTari,
+    /// When the output was found by a scan
+    pub detected_at: NaiveDateTime,
+    /// When a scan found that the output had been spent
+    pub spent_at: Option<NaiveDateTime>,
+    /// The output as it is on the chain, which the wallet that owns it needs to sign a transaction spending it while
+    /// it is offline. Outputs found before the outputs were kept do not have it.
+    pub output: Option<TransactionOutput>,
 }
 
 pub enum WriteOperation {
@@ -188,6 +251,31 @@ where T: WalletBackend + 'static
             .and_then(|inner_result| inner_result)
     }
 
+    /// Write an encrypted backup of the database to `backup_path`, returning the hex checksum of the backup file
+    pub async fn create_backup(
+        &self,
+        backup_path: PathBuf,
+        passphrase: String,
+        kdf_parameters: KdfParameters,
+    ) -> Result<String, WalletStorageError>
+    {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || {
+            backup::create_backup(&*db_clone, &backup_path, &passphrase, kdf_parameters)
+        })
+        .await
+        .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))
+        .and_then(|inner_result| inner_result)
+    }
+
+    pub async fn rotate_cipher(&self, cipher: Aes256Gcm, kdf: PassphraseKdf) -> Result<(), WalletStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || db_clone.rotate_cipher(cipher, kdf))
+            .await
+            .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))
+            .and_then(|inner_result| inner_result)
+    }
+
     pub async fn remove_encryption(&self) -> Result<(), WalletStorageError> {
         let db_clone = self.db.clone();
         tokio::task::spawn_blocking(move || db_clone.remove_encryption())
@@ -196,6 +284,16 @@ where T: WalletBackend + 'static
             .and_then(|inner_result| inner_result)
     }
 
+    /// Check that the provided cipher is the one the database is encrypted with, returning
+    /// `WalletStorageError::IncorrectPassword` if it is not.
+    pub async fn verify_cipher(&self, cipher: Aes256Gcm) -> Result<(), WalletStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || db_clone.verify_cipher(&cipher))
+            .await
+            .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))
+            .and_then(|inner_result| inner_result)
+    }
+
     pub async fn set_client_key_value(&self, key: String, value: String) -> Result<(), WalletStorageError> {
         let db_clone = self.db.clone();
 
@@ -246,6 +344,141 @@ where T: WalletBackend + 'static
         }
     }
 
+    pub async fn get_scheduled_payments(&self) -> Result<Vec<ScheduledPayment>, WalletStorageError> {
+        let db_clone = self.db.clone();
+
+        let c = tokio::task::spawn_blocking(move || match db_clone.fetch(&DbKey::ScheduledPayments) {
+            Ok(None) => Ok(Vec::new()),
+            Ok(Some(DbValue::ScheduledPayments(payments))) => Ok(payments),
+            Ok(Some(other)) => unexpected_result(DbKey::ScheduledPayments, other),
+            Err(e) => log_error(DbKey::ScheduledPayments, e),
+        })
+        .await
+        .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(c)
+    }
+
+    pub async fn get_scheduled_payment(&self, id: u64) -> Result<Option<ScheduledPayment>, WalletStorageError> {
+        let db_clone = self.db.clone();
+
+        let c = tokio::task::spawn_blocking(move || match db_clone.fetch(&DbKey::ScheduledPayment(id)) {
+            Ok(None) => Ok(None),
+            Ok(Some(DbValue::ScheduledPayment(payment))) => Ok(Some(*payment)),
+            Ok(Some(other)) => unexpected_result(DbKey::ScheduledPayment(id), other),
+            Err(e) => log_error(DbKey::ScheduledPayment(id), e),
+        })
+        .await
+        .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(c)
+    }
+
+    /// Insert a scheduled payment, or replace the stored one with the same id
+    pub async fn set_scheduled_payment(&self, payment: ScheduledPayment) -> Result<(), WalletStorageError> {
+        let db_clone = self.db.clone();
+
+        tokio::task::spawn_blocking(move || {
+            db_clone.write(WriteOperation::Insert(DbKeyValuePair::ScheduledPayment(Box::new(
+                payment,
+            ))))
+        })
+        .await
+        .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(())
+    }
+
+    pub async fn remove_scheduled_payment(&self, id: u64) -> Result<bool, WalletStorageError> {
+        let db_clone = self.db.clone();
+
+        let c = tokio::task::spawn_blocking(move || {
+            match db_clone.write(WriteOperation::Remove(DbKey::ScheduledPayment(id))) {
+                Ok(None) => Ok(false),
+                Ok(Some(DbValue::ValueCleared)) => Ok(true),
+                Ok(Some(other)) => unexpected_result(DbKey::ScheduledPayment(id), other),
+                Err(e) => log_error(DbKey::ScheduledPayment(id), e),
+            }
+        })
+        .await
+        .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(c)
+    }
+
+    /// The view key of the wallet that this wallet watches, if this is a watch-only wallet
+    pub async fn get_watch_only_keys(&self) -> Result<Option<PublicRewindKeys>, WalletStorageError> {
+        let db_clone = self.db.clone();
+
+        let c = tokio::task::spawn_blocking(move || match db_clone.fetch(&DbKey::WatchOnlyKeys) {
+            Ok(None) => Ok(None),
+            Ok(Some(DbValue::WatchOnlyKeys(keys))) => Ok(Some(*keys)),
+            Ok(Some(other)) => unexpected_result(DbKey::WatchOnlyKeys, other),
+            Err(e) => log_error(DbKey::WatchOnlyKeys, e),
+        })
+        .await
+        .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(c)
+    }
+
+    pub async fn set_watch_only_keys(&self, keys: PublicRewindKeys) -> Result<(), WalletStorageError> {
+        let db_clone = self.db.clone();
+
+        tokio::task::spawn_blocking(move || {
+            db_clone.write(WriteOperation::Insert(DbKeyValuePair::WatchOnlyKeys(Box::new(keys))))
+        })
+        .await
+        .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(())
+    }
+
+    /// The outputs found by the scans of a watch-only wallet, the most recently found first
+    pub async fn get_watched_outputs(&self) -> Result<Vec<WatchedOutput>, WalletStorageError> {
+        let db_clone = self.db.clone();
+
+        let c = tokio::task::spawn_blocking(move || match db_clone.fetch(&DbKey::WatchedOutputs) {
+            Ok(None) => Ok(Vec::new()),
+            Ok(Some(DbValue::WatchedOutputs(outputs))) => Ok(outputs),
+            Ok(Some(other)) => unexpected_result(DbKey::WatchedOutputs, other),
+            Err(e) => log_error(DbKey::WatchedOutputs, e),
+        })
+        .await
+        .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(c)
+    }
+
+    /// Insert a watched output, or replace the stored one with the same hash
+    pub async fn set_watched_output(&self, output: WatchedOutput) -> Result<(), WalletStorageError> {
+        let db_clone = self.db.clone();
+
+        tokio::task::spawn_blocking(move || {
+            db_clone.write(WriteOperation::Insert(DbKeyValuePair::WatchedOutput(Box::new(output))))
+        })
+        .await
+        .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(())
+    }
+
+    /// How the cipher of the database was derived from its passphrase, if that has been stored
+    pub async fn get_passphrase_kdf(&self) -> Result<Option<PassphraseKdf>, WalletStorageError> {
+        let db_clone = self.db.clone();
+
+        let c = tokio::task::spawn_blocking(move || match db_clone.fetch(&DbKey::PassphraseKdf) {
+            Ok(None) => Ok(None),
+            Ok(Some(DbValue::PassphraseKdf(kdf))) => Ok(Some(kdf)),
+            Ok(Some(other)) => unexpected_result(DbKey::PassphraseKdf, other),
+            Err(e) => log_error(DbKey::PassphraseKdf, e),
+        })
+        .await
+        .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(c)
+    }
+
+    pub async fn set_passphrase_kdf(&self, kdf: PassphraseKdf) -> Result<(), WalletStorageError> {
+        let db_clone = self.db.clone();
+
+        tokio::task::spawn_blocking(move || db_clone.write(WriteOperation::Insert(DbKeyValuePair::PassphraseKdf(kdf))))
+            .await
+            .map_err(|err| WalletStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(())
+    }
+
     pub async fn clear_client_value(&self, key: String) -> Result<bool, WalletStorageError> {
         let db_clone = self.db.clone();
 
@@ -274,6 +507,11 @@ impl Display for DbKey {
             DbKey::TorId => f.write_str(&"TorId".to_string()),
             DbKey::ClientKey(k) => f.write_str(&format!("ClientKey: {:?}", k)),
             DbKey::BaseNodeChainMetadata => f.write_str(&"Last seen Chain metadata from base node".to_string()),
+            DbKey::ScheduledPayment(id) => f.write_str(&format!("ScheduledPayment: {}", id)),
+            DbKey::ScheduledPayments => f.write_str(&"ScheduledPayments".to_string()),
+            DbKey::WatchOnlyKeys => f.write_str(&"WatchOnlyKeys".to_string()),
+            DbKey::WatchedOutputs => f.write_str(&"WatchedOutputs".to_string()),
+            DbKey::PassphraseKdf => f.write_str(&"PassphraseKdf".to_string()),
         }
     }
 }
@@ -290,6 +528,11 @@ impl Display for DbValue {
             DbValue::TorId(v) => f.write_str(&format!("Tor ID: {}", v)),
             DbValue::Identity(v) => f.write_str(&format!("Node Identity: {}", v)),
             DbValue::BaseNodeChainMetadata(v) => f.write_str(&format!("Last seen Chain metadata from base node:{}", v)),
+            DbValue::ScheduledPayment(v) => f.write_str(&format!("ScheduledPayment: {}", v.id)),
+            DbValue::ScheduledPayments(v) => f.write_str(&format!("ScheduledPayments: {} payments", v.len())),
+            DbValue::WatchOnlyKeys(v) => f.write_str(&format!("WatchOnlyKeys: {}", v)),
+            DbValue::WatchedOutputs(v) => f.write_str(&format!("WatchedOutputs: {} outputs", v.len())),
+            DbValue::PassphraseKdf(v) => f.write_str(&format!("PassphraseKdf: {}", v)),
         }
     }
 }
@@ -312,15 +555,21 @@ fn unexpected_result<T>(req: DbKey, res: DbValue) -> Result<T, WalletStorageErro
 
 #[cfg(test)]
 mod test {
-    use crate::storage::{
-        database::{WalletBackend, WalletDatabase},
-        memory_db::WalletMemoryDatabase,
-        sqlite_db::WalletSqliteDatabase,
-        sqlite_utilities::run_migration_and_create_sqlite_connection,
+    use crate::{
+        output_manager_service::handle::PublicRewindKeys,
+        storage::{
+            database::{ScheduledPayment, WalletBackend, WalletDatabase, WatchedOutput},
+            memory_db::WalletMemoryDatabase,
+            sqlite_db::WalletSqliteDatabase,
+            sqlite_utilities::run_migration_and_create_sqlite_connection,
+        },
     };
+    use chrono::{Duration as ChronoDuration, NaiveDateTime};
     use rand::rngs::OsRng;
-    use tari_comms::types::CommsSecretKey;
-    use tari_crypto::keys::SecretKey;
+    use std::time::Duration;
+    use tari_comms::types::{CommsPublicKey, CommsSecretKey};
+    use tari_core::transactions::tari_amount::MicroTari;
+    use tari_crypto::keys::{PublicKey, SecretKey};
     use tari_test_utils::random::string;
     use tempfile::tempdir;
     use tokio::runtime::Runtime;
@@ -377,6 +626,53 @@ mod test {
         assert!(!runtime
             .block_on(db.clear_client_value(client_key_values[0].0.clone()))
             .unwrap());
+
+        // Test scheduled payments
+        assert!(runtime.block_on(db.get_scheduled_payments()).unwrap().is_empty());
+        let mut payment = ScheduledPayment {
+            id: 1,
+            destination_public_key: CommsPublicKey::from_secret_key(&CommsSecretKey::random(&mut OsRng)),
+            amount: MicroTari::from(1000),
+            fee_per_gram: MicroTari::from(20),
+            message: "Rent".to_string(),
+            interval: Duration::from_secs(86400),
+            next_payment: NaiveDateTime::from_timestamp(1_600_000_000, 0),
+            last_tx_id: None,
+        };
+        runtime.block_on(db.set_scheduled_payment(payment.clone())).unwrap();
+        payment.last_tx_id = Some(42);
+        payment.next_payment += ChronoDuration::seconds(86400);
+        runtime.block_on(db.set_scheduled_payment(payment.clone())).unwrap();
+        assert_eq!(runtime.block_on(db.get_scheduled_payment(1)).unwrap().unwrap(), payment);
+        assert_eq!(runtime.block_on(db.get_scheduled_payments()).unwrap(), vec![payment]);
+        assert!(runtime.block_on(db.get_scheduled_payment(2)).unwrap().is_none());
+
+        assert!(!runtime.block_on(db.remove_scheduled_payment(2)).unwrap());
+        assert!(runtime.block_on(db.remove_scheduled_payment(1)).unwrap());
+        assert!(runtime.block_on(db.get_scheduled_payments()).unwrap().is_empty());
+
+        // Test watch-only keys and watched outputs
+        assert!(runtime.block_on(db.get_watch_only_keys()).unwrap().is_none());
+        let keys = PublicRewindKeys {
+            rewind_public_key: CommsPublicKey::from_secret_key(&CommsSecretKey::random(&mut OsRng)),
+            rewind_blinding_public_key: CommsPublicKey::from_secret_key(&CommsSecretKey::random(&mut OsRng)),
+            legacy_rewind_public_key: Some(CommsPublicKey::from_secret_key(&CommsSecretKey::random(&mut OsRng))),
+        };
+        runtime.block_on(db.set_watch_only_keys(keys.clone())).unwrap();
+        assert_eq!(runtime.block_on(db.get_watch_only_keys()).unwrap(), Some(keys));
+
+        assert!(runtime.block_on(db.get_watched_outputs()).unwrap().is_empty());
+        let mut output = WatchedOutput {
+            hash: vec![1u8; 32],
+            value: MicroTari::from(5000),
+            detected_at: NaiveDateTime::from_timestamp(1_600_000_000, 0),
+            spent_at: None,
+            output: None,
+        };
+        runtime.block_on(db.set_watched_output(output.clone())).unwrap();
+        output.spent_at = Some(NaiveDateTime::from_timestamp(1_600_086_400, 0));
+        runtime.block_on(db.set_watched_output(output.clone())).unwrap();
+        assert_eq!(runtime.block_on(db.get_watched_outputs()).unwrap(), vec![output]);
     }
 
     #[test]
@@ -22,7 +22,17 @@
 
 use crate::{
     error::WalletStorageError,
-    storage::database::{DbKey, DbKeyValuePair, DbValue, WalletBackend, WriteOperation},
+    output_manager_service::handle::PublicRewindKeys,
+    storage::database::{
+        DbKey,
+        DbKeyValuePair,
+        DbValue,
+        ScheduledPayment,
+        WalletBackend,
+        WatchedOutput,
+        WriteOperation,
+    },
+    util::encryption::PassphraseKdf,
 };
 use aes_gcm::Aes256Gcm;
 use std::{
@@ -47,6 +57,10 @@ pub struct InnerDatabase {
     identity: Option<NodeIdentity>,
     tor_id: Option<TorIdentity>,
     chain_metadata: Option<ChainMetadata>,
+    scheduled_payments: HashMap<u64, ScheduledPayment>,
+    watch_only_keys: Option<PublicRewindKeys>,
+    watched_outputs: HashMap<Vec<u8>, WatchedOutput>,
+    passphrase_kdf: Option<PassphraseKdf>,
 }
 
 impl InnerDatabase {
@@ -59,6 +73,10 @@ impl InnerDatabase {
             identity: None,
             tor_id: None,
             chain_metadata: None,
+            scheduled_payments: HashMap::new(),
+            watch_only_keys: None,
+            passphrase_kdf: None,
+            watched_outputs: HashMap::new(),
         }
     }
 }
@@ -97,6 +115,23 @@ impl WalletBackend for WalletMemoryDatabase {
             DbKey::Identity => db.identity.clone().map(DbValue::Identity),
             DbKey::TorId => db.tor_id.clone().map(DbValue::TorId),
             DbKey::BaseNodeChainMetadata => db.chain_metadata.clone().map(DbValue::BaseNodeChainMetadata),
+            DbKey::ScheduledPayment(id) => db
+                .scheduled_payments
+                .get(id)
+                .map(|p| DbValue::ScheduledPayment(Box::new(p.clone()))),
+            DbKey::ScheduledPayments => Some(DbValue::ScheduledPayments(
+                db.scheduled_payments.values().cloned().collect(),
+            )),
+            DbKey::WatchOnlyKeys => db
+                .watch_only_keys
+                .clone()
+                .map(|keys| DbValue::WatchOnlyKeys(Box::new(keys))),
+            DbKey::WatchedOutputs => {
+                let mut outputs = db.watched_outputs.values().cloned().collect::<Vec<_>>();
+                outputs.sort_by(|a, b| b.detected_at.cmp(&a.detected_at));
+                Some(DbValue::WatchedOutputs(outputs))
+            },
+            DbKey::PassphraseKdf => db.passphrase_kdf.clone().map(DbValue::PassphraseKdf),
         };
 
         Ok(result)
@@ -121,6 +156,18 @@ impl WalletBackend for WalletMemoryDatabase {
                 DbKeyValuePair::BaseNodeChainMetadata(metadata) => {
                     db.chain_metadata = Some(metadata);
                 },
+                DbKeyValuePair::ScheduledPayment(payment) => {
+                    db.scheduled_payments.insert(payment.id, *payment);
+                },
+                DbKeyValuePair::WatchOnlyKeys(keys) => {
+                    db.watch_only_keys = Some(*keys);
+                },
+                DbKeyValuePair::WatchedOutput(output) => {
+                    db.watched_outputs.insert(output.hash.clone(), *output);
+                },
+                DbKeyValuePair::PassphraseKdf(kdf) => {
+                    db.passphrase_kdf = Some(kdf);
+                },
             },
             WriteOperation::Remove(k) => match k {
                 DbKey::CommsSecretKey => {
@@ -151,6 +198,16 @@ impl WalletBackend for WalletMemoryDatabase {
                 DbKey::TorId => {
                     db.tor_id = None;
                 },
+                DbKey::ScheduledPayment(id) => {
+                    if db.scheduled_payments.remove(&id).is_some() {
+                        return Ok(Some(DbValue::ValueCleared));
+                    } else {
+                        return Ok(None);
+                    }
+                },
+                DbKey::ScheduledPayments | DbKey::WatchOnlyKeys | DbKey::WatchedOutputs | DbKey::PassphraseKdf => {
+                    return Err(WalletStorageError::OperationNotSupported);
+                },
             },
         }
 
@@ -164,4 +221,16 @@ impl WalletBackend for WalletMemoryDatabase {
     fn remove_encryption(&self) -> Result<(), WalletStorageError> {
         Ok(())
     }
+
+    fn rotate_cipher(&self, _: Aes256Gcm, _: PassphraseKdf) -> Result<(), WalletStorageError> {
+        Ok(())
+    }
+
+    fn snapshot(&self) -> Result<Vec<u8>, WalletStorageError> {
+        Err(WalletStorageError::OperationNotSupported)
+    }
+
+    fn verify_cipher(&self, _: &Aes256Gcm) -> Result<(), WalletStorageError> {
+        Ok(())
+    }
 }
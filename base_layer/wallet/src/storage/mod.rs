@@ -27,6 +27,7 @@
 //          diesel migration run --database-url test.sqlite3
 // After running this, make sure that the diesel update did not change BigInt to Integer
 
+pub mod backup;
 pub mod database;
 pub mod memory_db;
 pub mod sqlite_db;
@@ -22,23 +22,35 @@
 
 use crate::{
     error::WalletStorageError,
-    schema::{client_key_values, wallet_settings},
+    output_manager_service::handle::PublicRewindKeys,
+    schema::{client_key_values, scheduled_payments, wallet_settings, watched_outputs},
     storage::{
-        database::{DbKey, DbKeyValuePair, DbValue, WalletBackend, WriteOperation},
-        sqlite_utilities::WalletDbConnection,
+        database::{DbKey, DbKeyValuePair, DbValue, ScheduledPayment, WalletBackend, WatchedOutput, WriteOperation},
+        sqlite_utilities::{decrypt_database, encrypt_database, rotate_database_cipher, WalletDbConnection},
+    },
+    util::encryption::{
+        decrypt_bytes_integral_nonce,
+        encrypt_bytes_integral_nonce,
+        Encryptable,
+        PassphraseKdf,
+        AES_NONCE_BYTES,
     },
-    util::encryption::{decrypt_bytes_integral_nonce, encrypt_bytes_integral_nonce, Encryptable, AES_NONCE_BYTES},
 };
 use aes_gcm::{
     aead::{generic_array::GenericArray, Aead},
     Aes256Gcm,
     Error as AeadError,
 };
+use chrono::NaiveDateTime;
 use diesel::{prelude::*, SqliteConnection};
 use log::*;
 use std::{
+    convert::TryFrom,
+    fs,
+    path::Path,
     str::{from_utf8, FromStr},
     sync::{Arc, RwLock},
+    time::Duration,
 };
 use tari_common_types::chain_metadata::ChainMetadata;
 use tari_comms::{
@@ -47,6 +59,7 @@ use tari_comms::{
     tor::TorIdentity,
     types::{CommsPublicKey, CommsSecretKey},
 };
+use tari_core::transactions::tari_amount::MicroTari;
 use tari_crypto::{
     keys::PublicKey,
     tari_utilities::{
@@ -167,6 +180,11 @@ impl WalletSqliteDatabase {
         })
     }
 
+    /// The cipher of this backend, for the backends of the other services to share
+    pub(crate) fn cipher(&self) -> Arc<RwLock<Option<Aes256Gcm>>> {
+        self.cipher.clone()
+    }
+
     fn set_comms_private_key(
         &self,
         secret_key: &CommsSecretKey,
@@ -357,6 +375,18 @@ impl WalletSqliteDatabase {
 
                 return Ok(value_to_return.map(|v| DbValue::ClientValue(v.value)));
             },
+            DbKeyValuePair::ScheduledPayment(payment) => {
+                ScheduledPaymentSql::from(*payment).set(&conn)?;
+            },
+            DbKeyValuePair::WatchOnlyKeys(keys) => {
+                WalletSettingSql::new(DbKey::WatchOnlyKeys.to_string(), keys.to_string()).set(&conn)?;
+            },
+            DbKeyValuePair::WatchedOutput(output) => {
+                WatchedOutputSql::try_from(*output)?.set(&conn)?;
+            },
+            DbKeyValuePair::PassphraseKdf(kdf) => {
+                set_passphrase_kdf(&kdf, &conn)?;
+            },
         }
         Ok(None)
     }
@@ -388,6 +418,14 @@ impl WalletSqliteDatabase {
             DbKey::TorId => {
                 let _ = WalletSettingSql::clear(DbKey::TorId.to_string(), &conn)?;
             },
+            DbKey::ScheduledPayment(id) => {
+                if ScheduledPaymentSql::clear(id, &conn)? {
+                    return Ok(Some(DbValue::ValueCleared));
+                }
+            },
+            DbKey::ScheduledPayments | DbKey::WatchOnlyKeys | DbKey::WatchedOutputs | DbKey::PassphraseKdf => {
+                return Err(WalletStorageError::OperationNotSupported);
+            },
         };
         Ok(None)
     }
@@ -435,6 +473,30 @@ impl WalletBackend for WalletSqliteDatabase {
             DbKey::TorId => self.get_tor_id(&conn)?,
             DbKey::CommsFeatures => self.get_comms_features(&conn)?.map(DbValue::CommsFeatures),
             DbKey::BaseNodeChainMetadata => self.get_chain_metadata(&conn)?.map(DbValue::BaseNodeChainMetadata),
+            DbKey::ScheduledPayment(id) => match ScheduledPaymentSql::get(*id, &conn)? {
+                None => None,
+                Some(p) => Some(DbValue::ScheduledPayment(Box::new(ScheduledPayment::try_from(p)?))),
+            },
+            DbKey::ScheduledPayments => Some(DbValue::ScheduledPayments(
+                ScheduledPaymentSql::index(&conn)?
+                    .into_iter()
+                    .map(ScheduledPayment::try_from)
+                    .collect::<Result<Vec<_>, _>>()?,
+            )),
+            DbKey::WatchOnlyKeys => match WalletSettingSql::get(key.to_string(), &conn)? {
+                None => None,
+                Some(keys) => Some(DbValue::WatchOnlyKeys(Box::new(
+                    PublicRewindKeys::from_str(&keys)
+                        .map_err(|e| WalletStorageError::ConversionError(e.to_string()))?,
+                ))),
+            },
+            DbKey::WatchedOutputs => Some(DbValue::WatchedOutputs(
+                WatchedOutputSql::index(&conn)?
+                    .into_iter()
+                    .map(WatchedOutput::try_from)
+                    .collect::<Result<Vec<_>, _>>()?,
+            )),
+            DbKey::PassphraseKdf => get_passphrase_kdf(&conn)?.map(DbValue::PassphraseKdf),
         };
 
         Ok(result)
@@ -448,39 +510,15 @@ impl WalletBackend for WalletSqliteDatabase {
     }
 
     fn apply_encryption(&self, cipher: Aes256Gcm) -> Result<(), WalletStorageError> {
+        // The connection is locked before the cipher, as the writers of every backend do, and is held until the cipher
+        // is set, so nothing is written unencrypted once the database has been encrypted
+        let conn = self.database_connection.acquire_lock();
         let mut current_cipher = acquire_write_lock!(self.cipher);
         if current_cipher.is_some() {
             return Err(WalletStorageError::AlreadyEncrypted);
         }
 
-        let conn = self.database_connection.acquire_lock();
-        let secret_key_str = match WalletSettingSql::get(DbKey::CommsSecretKey.to_string(), &conn)? {
-            None => return Err(WalletStorageError::ValueNotFound(DbKey::CommsSecretKey)),
-            Some(sk) => sk,
-        };
-        // If this fails then the database is already encrypted.
-        let secret_key = CommsSecretKey::from_hex(&secret_key_str).map_err(|_| WalletStorageError::AlreadyEncrypted)?;
-        let ciphertext_integral_nonce = encrypt_bytes_integral_nonce(&cipher, secret_key.to_vec())
-            .map_err(|e| WalletStorageError::AeadError(format!("Encryption Error:{}", e.to_string())))?;
-        WalletSettingSql::new(DbKey::CommsSecretKey.to_string(), ciphertext_integral_nonce.to_hex()).set(&conn)?;
-
-        // Encrypt all the client values
-        let mut client_key_values = ClientKeyValueSql::index(&conn)?;
-        for ckv in client_key_values.iter_mut() {
-            ckv.encrypt(&cipher)
-                .map_err(|e| WalletStorageError::AeadError(format!("Encryption Error:{}", e.to_string())))?;
-            ckv.set(&conn)?;
-        }
-
-        // Encrypt tor_id if present
-        let tor_id = WalletSettingSql::get(DbKey::TorId.to_string(), &conn)?;
-        if let Some(v) = tor_id {
-            let tor = TorIdentity::from_json(&v).map_err(|e| WalletStorageError::ConversionError(e.to_string()))?;
-            let bytes = bincode::serialize(&tor).map_err(|e| WalletStorageError::ConversionError(e.to_string()))?;
-            let ciphertext_integral_nonce = encrypt_bytes_integral_nonce(&cipher, bytes)
-                .map_err(|e| WalletStorageError::AeadError(format!("Encryption Error:{}", e.to_string())))?;
-            WalletSettingSql::new(DbKey::TorId.to_string(), ciphertext_integral_nonce.to_hex()).set(&conn)?;
-        }
+        conn.transaction::<_, WalletStorageError, _>(|| encrypt_database(&cipher, &conn))?;
 
         (*current_cipher) = Some(cipher);
 
@@ -488,49 +526,158 @@ impl WalletBackend for WalletSqliteDatabase {
     }
 
     fn remove_encryption(&self) -> Result<(), WalletStorageError> {
+        let conn = self.database_connection.acquire_lock();
         let mut current_cipher = acquire_write_lock!(self.cipher);
         let cipher = if let Some(cipher) = (*current_cipher).clone().take() {
             cipher
         } else {
             return Ok(());
         };
-        let conn = self.database_connection.acquire_lock();
-        let secret_key_str = match WalletSettingSql::get(DbKey::CommsSecretKey.to_string(), &conn)? {
-            None => return Err(WalletStorageError::ValueNotFound(DbKey::CommsSecretKey)),
-            Some(sk) => sk,
-        };
+        conn.transaction::<_, WalletStorageError, _>(|| decrypt_database(&cipher, &conn))?;
 
-        let secret_key_bytes = decrypt_bytes_integral_nonce(&cipher, from_hex(secret_key_str.as_str())?)
-            .map_err(|e| WalletStorageError::AeadError(format!("Decryption Error:{}", e.to_string())))?;
-        let decrypted_key = CommsSecretKey::from_bytes(secret_key_bytes.as_slice())?;
-        WalletSettingSql::new(DbKey::CommsSecretKey.to_string(), decrypted_key.to_hex()).set(&conn)?;
+        // Now that all the decryption has been completed we can safely remove the cipher fully
+        let _ = (*current_cipher).take();
 
-        // Decrypt all the client values
-        let mut client_key_values = ClientKeyValueSql::index(&conn)?;
-        for ckv in client_key_values.iter_mut() {
-            ckv.decrypt(&cipher)
-                .map_err(|e| WalletStorageError::AeadError(format!("Decryption Error:{}", e.to_string())))?;
-            ckv.set(&conn)?;
-        }
+        Ok(())
+    }
 
-        // remove tor id encryption if present
-        let key_str = WalletSettingSql::get(DbKey::TorId.to_string(), &conn)?;
-        if let Some(v) = key_str {
-            let decrypted_key_bytes = decrypt_bytes_integral_nonce(&cipher, from_hex(v.as_str())?)
-                .map_err(|e| WalletStorageError::AeadError(format!("Decryption Error:{}", e.to_string())))?;
-            let tor_id: TorIdentity = bincode::deserialize(&decrypted_key_bytes)
-                .map_err(|e| WalletStorageError::ConversionError(e.to_string()))?;
-            let tor_string = tor_id
-                .to_json()
-                .map_err(|e| WalletStorageError::ConversionError(e.to_string()))?;
-            WalletSettingSql::new(DbKey::TorId.to_string(), tor_string).set(&conn)?;
-        }
+    fn rotate_cipher(&self, cipher: Aes256Gcm, kdf: PassphraseKdf) -> Result<(), WalletStorageError> {
+        // Nothing can be written with the old cipher once the database has been re-encrypted, as the connection is
+        // held until the cipher that the backends share has been swapped
+        let conn = self.database_connection.acquire_lock();
+        let mut current_cipher = acquire_write_lock!(self.cipher);
+        let current = current_cipher
+            .as_ref()
+            .ok_or_else(|| WalletStorageError::CipherRotationError("The database is not encrypted".to_string()))?;
+        rotate_database_cipher(&conn, current, &cipher, &kdf)?;
 
-        // Now that all the decryption has been completed we can safely remove the cipher fully
-        let _ = (*current_cipher).take();
+        (*current_cipher) = Some(cipher);
 
         Ok(())
     }
+
+    fn verify_cipher(&self, cipher: &Aes256Gcm) -> Result<(), WalletStorageError> {
+        // The same check that is done on startup: decrypting the stored CommsSecretKey with the cipher must produce
+        // the stored CommsPublicKey
+        WalletSqliteDatabase::new(self.database_connection.clone(), Some(cipher.clone())).map(|_| ())
+    }
+
+    fn snapshot(&self) -> Result<Vec<u8>, WalletStorageError> {
+        // The services share this connection, so holding its lock keeps them from writing while the copy is made.
+        // VACUUM INTO writes the copy in a single read transaction, so it is consistent in any case.
+        let conn = self.database_connection.acquire_lock();
+
+        // The copy is not encrypted as a whole, so it is kept next to the database rather than wherever the backup is
+        // going. The temporary file is removed when it is dropped, on every path out of here.
+        let database_file = diesel::sql_query("SELECT file FROM pragma_database_list WHERE name = 'main'")
+            .get_result::<DatabaseFile>(&*conn)?
+            .file;
+        let database_dir = Path::new(&database_file)
+            .parent()
+            .filter(|_| !database_file.is_empty())
+            .ok_or(WalletStorageError::OperationNotSupported)?;
+        let snapshot_file = tempfile::Builder::new()
+            .prefix(".wallet-snapshot")
+            .tempfile_in(database_dir)?;
+        let snapshot_path = snapshot_file
+            .path()
+            .to_str()
+            .ok_or_else(|| WalletStorageError::InvalidUnicodePath)?;
+        diesel::sql_query("VACUUM INTO ?")
+            .bind::<diesel::sql_types::Text, _>(snapshot_path)
+            .execute(&*conn)?;
+        Ok(fs::read(snapshot_file.path())?)
+    }
+}
+
+/// The file of a database, as listed by `pragma_database_list`
+#[derive(QueryableByName)]
+struct DatabaseFile {
+    #[sql_type = "diesel::sql_types::Text"]
+    file: String,
+}
+
+/// The KDF is not encrypted, as it is needed to derive the cipher before anything can be decrypted
+pub fn get_passphrase_kdf(conn: &SqliteConnection) -> Result<Option<PassphraseKdf>, WalletStorageError> {
+    WalletSettingSql::get(DbKey::PassphraseKdf.to_string(), conn)?
+        .map(|kdf| PassphraseKdf::from_str(&kdf))
+        .transpose()
+}
+
+pub(crate) fn set_passphrase_kdf(kdf: &PassphraseKdf, conn: &SqliteConnection) -> Result<(), WalletStorageError> {
+    if let PassphraseKdf::Argon2id { parameters, .. } = kdf {
+        parameters.validate()?;
+    }
+    WalletSettingSql::new(DbKey::PassphraseKdf.to_string(), kdf.to_string()).set(conn)
+}
+
+/// Encrypt the comms secret key, the client values and the tor id, which must not be encrypted yet
+pub(crate) fn encrypt_settings(cipher: &Aes256Gcm, conn: &SqliteConnection) -> Result<(), WalletStorageError> {
+    let secret_key_str = match WalletSettingSql::get(DbKey::CommsSecretKey.to_string(), conn)? {
+        None => return Err(WalletStorageError::ValueNotFound(DbKey::CommsSecretKey)),
+        Some(sk) => sk,
+    };
+    // If this fails then the database is already encrypted.
+    let secret_key = CommsSecretKey::from_hex(&secret_key_str).map_err(|_| WalletStorageError::AlreadyEncrypted)?;
+    let ciphertext_integral_nonce = encrypt_bytes_integral_nonce(cipher, secret_key.to_vec())
+        .map_err(|e| WalletStorageError::AeadError(format!("Encryption Error:{}", e.to_string())))?;
+    WalletSettingSql::new(DbKey::CommsSecretKey.to_string(), ciphertext_integral_nonce.to_hex()).set(conn)?;
+
+    // Encrypt all the client values
+    let mut client_key_values = ClientKeyValueSql::index(conn)?;
+    for ckv in client_key_values.iter_mut() {
+        ckv.encrypt(cipher)
+            .map_err(|e| WalletStorageError::AeadError(format!("Encryption Error:{}", e.to_string())))?;
+        ckv.set(conn)?;
+    }
+
+    // Encrypt tor_id if present
+    let tor_id = WalletSettingSql::get(DbKey::TorId.to_string(), conn)?;
+    if let Some(v) = tor_id {
+        let tor = TorIdentity::from_json(&v).map_err(|e| WalletStorageError::ConversionError(e.to_string()))?;
+        let bytes = bincode::serialize(&tor).map_err(|e| WalletStorageError::ConversionError(e.to_string()))?;
+        let ciphertext_integral_nonce = encrypt_bytes_integral_nonce(cipher, bytes)
+            .map_err(|e| WalletStorageError::AeadError(format!("Encryption Error:{}", e.to_string())))?;
+        WalletSettingSql::new(DbKey::TorId.to_string(), ciphertext_integral_nonce.to_hex()).set(conn)?;
+    }
+
+    Ok(())
+}
+
+/// Decrypt the comms secret key, the client values and the tor id
+pub(crate) fn decrypt_settings(cipher: &Aes256Gcm, conn: &SqliteConnection) -> Result<(), WalletStorageError> {
+    let secret_key_str = match WalletSettingSql::get(DbKey::CommsSecretKey.to_string(), conn)? {
+        None => return Err(WalletStorageError::ValueNotFound(DbKey::CommsSecretKey)),
+        Some(sk) => sk,
+    };
+
+    let secret_key_bytes = decrypt_bytes_integral_nonce(cipher, from_hex(secret_key_str.as_str())?)
+        .map_err(|e| WalletStorageError::AeadError(format!("Decryption Error:{}", e.to_string())))?;
+    let decrypted_key = CommsSecretKey::from_bytes(secret_key_bytes.as_slice())?;
+    WalletSettingSql::new(DbKey::CommsSecretKey.to_string(), decrypted_key.to_hex()).set(conn)?;
+
+    // Decrypt all the client values
+    let mut client_key_values = ClientKeyValueSql::index(conn)?;
+    for ckv in client_key_values.iter_mut() {
+        ckv.decrypt(cipher)
+            .map_err(|e| WalletStorageError::AeadError(format!("Decryption Error:{}", e.to_string())))?;
+        ckv.set(conn)?;
+    }
+
+    // remove tor id encryption if present
+    let key_str = WalletSettingSql::get(DbKey::TorId.to_string(), conn)?;
+    if let Some(v) = key_str {
+        let decrypted_key_bytes = decrypt_bytes_integral_nonce(cipher, from_hex(v.as_str())?)
+            .map_err(|e| WalletStorageError::AeadError(format!("Decryption Error:{}", e.to_string())))?;
+        let tor_id: TorIdentity = bincode::deserialize(&decrypted_key_bytes)
+            .map_err(|e| WalletStorageError::ConversionError(e.to_string()))?;
+        let tor_string = tor_id
+            .to_json()
+            .map_err(|e| WalletStorageError::ConversionError(e.to_string()))?;
+        WalletSettingSql::new(DbKey::TorId.to_string(), tor_string).set(conn)?;
+    }
+
+    Ok(())
 }
 
 /// A Sql version of the wallet setting key-value table
@@ -635,12 +782,151 @@ impl Encryptable<Aes256Gcm> for ClientKeyValueSql {
     }
 }
 
+/// A Sql version of a scheduled payment
+#[derive(Clone, Debug, Queryable, Insertable, PartialEq)]
+#[table_name = "scheduled_payments"]
+struct ScheduledPaymentSql {
+    id: i64,
+    destination_public_key: Vec<u8>,
+    amount: i64,
+    fee_per_gram: i64,
+    message: String,
+    interval_secs: i64,
+    next_payment: NaiveDateTime,
+    last_tx_id: Option<i64>,
+}
+
+impl ScheduledPaymentSql {
+    pub fn index(conn: &SqliteConnection) -> Result<Vec<Self>, WalletStorageError> {
+        Ok(scheduled_payments::table
+            .order_by(scheduled_payments::next_payment.asc())
+            .load::<ScheduledPaymentSql>(conn)?)
+    }
+
+    pub fn set(&self, conn: &SqliteConnection) -> Result<(), WalletStorageError> {
+        diesel::replace_into(scheduled_payments::table)
+            .values(self)
+            .execute(conn)?;
+
+        Ok(())
+    }
+
+    pub fn get(id: u64, conn: &SqliteConnection) -> Result<Option<Self>, WalletStorageError> {
+        scheduled_payments::table
+            .filter(scheduled_payments::id.eq(id as i64))
+            .first::<ScheduledPaymentSql>(conn)
+            .map(Some)
+            .or_else(|err| match err {
+                diesel::result::Error::NotFound => Ok(None),
+                err => Err(err.into()),
+            })
+    }
+
+    pub fn clear(id: u64, conn: &SqliteConnection) -> Result<bool, WalletStorageError> {
+        let num_deleted =
+            diesel::delete(scheduled_payments::table.filter(scheduled_payments::id.eq(id as i64))).execute(conn)?;
+
+        Ok(num_deleted > 0)
+    }
+}
+
+impl From<ScheduledPayment> for ScheduledPaymentSql {
+    fn from(p: ScheduledPayment) -> Self {
+        Self {
+            id: p.id as i64,
+            destination_public_key: p.destination_public_key.to_vec(),
+            amount: u64::from(p.amount) as i64,
+            fee_per_gram: u64::from(p.fee_per_gram) as i64,
+            message: p.message,
+            interval_secs: p.interval.as_secs() as i64,
+            next_payment: p.next_payment,
+            last_tx_id: p.last_tx_id.map(|id| id as i64),
+        }
+    }
+}
+
+impl TryFrom<ScheduledPaymentSql> for ScheduledPayment {
+    type Error = WalletStorageError;
+
+    fn try_from(p: ScheduledPaymentSql) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: p.id as u64,
+            destination_public_key: CommsPublicKey::from_vec(&p.destination_public_key)
+                .map_err(|e| WalletStorageError::ConversionError(e.to_string()))?,
+            amount: MicroTari::from(p.amount as u64),
+            fee_per_gram: MicroTari::from(p.fee_per_gram as u64),
+            message: p.message,
+            interval: Duration::from_secs(p.interval_secs as u64),
+            next_payment: p.next_payment,
+            last_tx_id: p.last_tx_id.map(|id| id as u64),
+        })
+    }
+}
+
+/// A Sql version of a watched output
+#[derive(Clone, Debug, Queryable, Insertable, PartialEq)]
+#[table_name = "watched_outputs"]
+struct WatchedOutputSql {
+    hash: Vec<u8>,
+    value: i64,
+    detected_at: NaiveDateTime,
+    spent_at: Option<NaiveDateTime>,
+    output: Option<String>,
+}
+
+impl WatchedOutputSql {
+    pub fn index(conn: &SqliteConnection) -> Result<Vec<Self>, WalletStorageError> {
+        Ok(watched_outputs::table
+            .order_by(watched_outputs::detected_at.desc())
+            .load::<WatchedOutputSql>(conn)?)
+    }
+
+    pub fn set(&self, conn: &SqliteConnection) -> Result<(), WalletStorageError> {
+        diesel::replace_into(watched_outputs::table)
+            .values(self)
+            .execute(conn)?;
+
+        Ok(())
+    }
+}
+
+impl TryFrom<WatchedOutput> for WatchedOutputSql {
+    type Error = WalletStorageError;
+
+    fn try_from(o: WatchedOutput) -> Result<Self, Self::Error> {
+        Ok(Self {
+            hash: o.hash,
+            value: u64::from(o.value) as i64,
+            detected_at: o.detected_at,
+            spent_at: o.spent_at,
+            output: o.output.as_ref().map(serde_json::to_string).transpose()?,
+        })
+    }
+}
+
+impl TryFrom<WatchedOutputSql> for WatchedOutput {
+    type Error = WalletStorageError;
+
+    fn try_from(o: WatchedOutputSql) -> Result<Self, Self::Error> {
+        Ok(Self {
+            hash: o.hash,
+            value: MicroTari::from(o.value as u64),
+            detected_at: o.detected_at,
+            spent_at: o.spent_at,
+            output: o.output.as_deref().map(serde_json::from_str).transpose()?,
+        })
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::storage::{
-        database::{DbKey, DbValue, WalletBackend},
-        sqlite_db::{ClientKeyValueSql, WalletSettingSql, WalletSqliteDatabase},
-        sqlite_utilities::run_migration_and_create_sqlite_connection,
+    use crate::{
+        error::WalletStorageError,
+        storage::{
+            database::{DbKey, DbValue, WalletBackend},
+            sqlite_db::{ClientKeyValueSql, WalletSettingSql, WalletSqliteDatabase},
+            sqlite_utilities::run_migration_and_create_sqlite_connection,
+        },
     };
     use aes_gcm::{
         aead::{generic_array::GenericArray, Aead, NewAead},
@@ -795,7 +1081,13 @@ mod test {
 
         let key = GenericArray::from_slice(b"an example very very secret key.");
         let cipher = Aes256Gcm::new(key);
-        db.apply_encryption(cipher).unwrap();
+        db.apply_encryption(cipher.clone()).unwrap();
+        db.verify_cipher(&cipher).unwrap();
+        let wrong_key = GenericArray::from_slice(b"an example very very wrong key!!");
+        assert!(matches!(
+            db.verify_cipher(&Aes256Gcm::new(wrong_key)),
+            Err(WalletStorageError::IncorrectPassword)
+        ));
         let read_secret_key2 = match db.fetch(&DbKey::CommsSecretKey).unwrap().unwrap() {
             DbValue::CommsSecretKey(sk) => sk,
             _ => {
@@ -23,16 +23,16 @@
 use crate::{
     contacts_service::storage::sqlite_db::ContactsServiceSqliteDatabase,
     error::WalletStorageError,
-    output_manager_service::storage::sqlite_db::OutputManagerSqliteDatabase,
-    storage::{database::WalletDatabase, sqlite_db::WalletSqliteDatabase},
-    transaction_service::storage::sqlite_db::TransactionServiceSqliteDatabase,
-};
-use aes_gcm::{
-    aead::{generic_array::GenericArray, NewAead},
-    Aes256Gcm,
+    output_manager_service::storage::sqlite_db::{self as output_manager_sqlite_db, OutputManagerSqliteDatabase},
+    storage::{
+        database::WalletDatabase,
+        sqlite_db::{self as wallet_sqlite_db, WalletSqliteDatabase},
+    },
+    transaction_service::storage::sqlite_db::{self as transaction_sqlite_db, TransactionServiceSqliteDatabase},
+    util::encryption::{KdfParameters, PassphraseKdf},
 };
+use aes_gcm::Aes256Gcm;
 use diesel::{Connection, SqliteConnection};
-use digest::Digest;
 use fs2::FileExt;
 use log::*;
 use std::{
@@ -40,7 +40,6 @@ use std::{
     path::{Path, PathBuf},
     sync::{Arc, Mutex, MutexGuard},
 };
-use tari_crypto::common::Blake256;
 
 const LOG_TARGET: &str = "wallet::storage:sqlite_utilities";
 
@@ -136,9 +135,13 @@ pub fn acquire_exclusive_file_lock(db_path: &PathBuf) -> Result<File, WalletStor
     Ok(file)
 }
 
+/// Open the backends of the wallet database. The cipher is derived from `passphrase` with the KDF that is stored in the
+/// database, and if that KDF is weaker than `kdf_parameters` the database is encrypted again with a cipher derived
+/// with them.
 pub fn initialize_sqlite_database_backends(
     db_path: PathBuf,
     passphrase: Option<String>,
+    kdf_parameters: KdfParameters,
 ) -> Result<
     (
         WalletSqliteDatabase,
@@ -149,12 +152,6 @@ pub fn initialize_sqlite_database_backends(
     WalletStorageError,
 >
 {
-    let cipher = passphrase.map(|passphrase_str| {
-        let passphrase_hash = Blake256::new().chain(passphrase_str.as_bytes()).result();
-        let key = GenericArray::from_slice(passphrase_hash.as_slice());
-        Aes256Gcm::new(key)
-    });
-
     let connection = run_migration_and_create_sqlite_connection(&db_path).map_err(|e| {
         error!(
             target: LOG_TARGET,
@@ -163,9 +160,18 @@ pub fn initialize_sqlite_database_backends(
         e
     })?;
 
-    let wallet_backend = WalletSqliteDatabase::new(connection.clone(), cipher.clone())?;
-    let transaction_backend = TransactionServiceSqliteDatabase::new(connection.clone(), cipher.clone());
-    let output_manager_backend = OutputManagerSqliteDatabase::new(connection.clone(), cipher);
+    let cipher = match passphrase {
+        Some(passphrase) => Some(derive_database_cipher(&connection, &passphrase, &kdf_parameters)?),
+        None => None,
+    };
+
+    // The backends share a single cipher, so that it is swapped for all of them at once when the database is encrypted
+    // or re-encrypted
+    let wallet_backend = WalletSqliteDatabase::new(connection.clone(), cipher)?;
+    let transaction_backend =
+        TransactionServiceSqliteDatabase::with_shared_cipher(connection.clone(), wallet_backend.cipher());
+    let output_manager_backend =
+        OutputManagerSqliteDatabase::with_shared_cipher(connection.clone(), wallet_backend.cipher());
     let contacts_backend = ContactsServiceSqliteDatabase::new(connection);
 
     Ok((
@@ -175,3 +181,219 @@ pub fn initialize_sqlite_database_backends(
         contacts_backend,
     ))
 }
+
+/// Derive the cipher of the encrypted database from `passphrase`, upgrading the KDF to `kdf_parameters` first if the
+/// stored one is weaker
+fn derive_database_cipher(
+    connection: &WalletDbConnection,
+    passphrase: &str,
+    kdf_parameters: &KdfParameters,
+) -> Result<Aes256Gcm, WalletStorageError>
+{
+    let kdf = wallet_sqlite_db::get_passphrase_kdf(&connection.acquire_lock())?.unwrap_or(PassphraseKdf::Blake256);
+    let cipher = kdf.cipher(passphrase)?;
+    if !kdf.is_weaker_than(kdf_parameters) {
+        return Ok(cipher);
+    }
+
+    // Fails if the passphrase is not the right one, so the database is only ever re-encrypted from its real cipher
+    let _ = WalletSqliteDatabase::new(connection.clone(), Some(cipher.clone()))?;
+
+    info!(
+        target: LOG_TARGET,
+        "Upgrading the wallet passphrase KDF from {} to Argon2id {:?}", kdf, kdf_parameters
+    );
+    let new_kdf = PassphraseKdf::argon2id(*kdf_parameters);
+    let new_cipher = new_kdf.cipher(passphrase)?;
+    rotate_database_cipher(&connection.acquire_lock(), &cipher, &new_cipher, &new_kdf)?;
+
+    Ok(new_cipher)
+}
+
+/// Encrypt all of the values in the database that are stored encrypted, which must not be encrypted yet
+pub(crate) fn encrypt_database(cipher: &Aes256Gcm, conn: &SqliteConnection) -> Result<(), WalletStorageError> {
+    wallet_sqlite_db::encrypt_settings(cipher, conn)?;
+    output_manager_sqlite_db::encrypt_keys(cipher, conn).map_err(|e| WalletStorageError::AeadError(e.to_string()))?;
+    transaction_sqlite_db::encrypt_transactions(cipher, conn).map_err(|e| WalletStorageError::AeadError(e.to_string()))
+}
+
+/// Decrypt all of the encrypted values in the database
+pub(crate) fn decrypt_database(cipher: &Aes256Gcm, conn: &SqliteConnection) -> Result<(), WalletStorageError> {
+    wallet_sqlite_db::decrypt_settings(cipher, conn)?;
+    output_manager_sqlite_db::decrypt_keys(cipher, conn).map_err(|e| WalletStorageError::AeadError(e.to_string()))?;
+    transaction_sqlite_db::decrypt_transactions(cipher, conn).map_err(|e| WalletStorageError::AeadError(e.to_string()))
+}
+
+/// Re-encrypt all of the encrypted values in the database with `new_cipher`, and store the KDF that it was derived
+/// with, in a single db transaction. The caller holds the lock on the connection, so that it can swap the cipher of
+/// the backends before anything else is written.
+pub(crate) fn rotate_database_cipher(
+    conn: &SqliteConnection,
+    current_cipher: &Aes256Gcm,
+    new_cipher: &Aes256Gcm,
+    new_kdf: &PassphraseKdf,
+) -> Result<(), WalletStorageError>
+{
+    conn.transaction::<_, WalletStorageError, _>(|| {
+        decrypt_database(current_cipher, conn)
+            .and_then(|_| encrypt_database(new_cipher, conn))
+            .map_err(|e| WalletStorageError::CipherRotationError(e.to_string()))?;
+        wallet_sqlite_db::set_passphrase_kdf(new_kdf, conn)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        error::WalletStorageError,
+        output_manager_service::{
+            error::OutputManagerStorageError,
+            storage::database::{
+                DbKey as OutputManagerDbKey,
+                DbKeyValuePair as OutputManagerDbKeyValuePair,
+                DbValue as OutputManagerDbValue,
+                KeyManagerState,
+                OutputManagerBackend,
+                WriteOperation as OutputManagerWriteOperation,
+            },
+        },
+        storage::{
+            database::{DbKey, DbKeyValuePair, DbValue, WalletBackend, WriteOperation},
+            sqlite_utilities::initialize_sqlite_database_backends,
+        },
+        transaction_service::{error::TransactionStorageError, storage::database::TransactionBackend},
+        util::encryption::{passphrase_cipher, KdfParameters, PassphraseKdf, MIN_KDF_MEMORY_KIB},
+    };
+    use rand::rngs::OsRng;
+    use tari_comms::types::CommsSecretKey;
+    use tari_core::transactions::types::PrivateKey;
+    use tari_crypto::keys::SecretKey;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_weak_kdf_is_upgraded_on_unlock() {
+        let db_tempdir = tempdir().unwrap();
+        let db_path = db_tempdir.path().join("wallet.sqlite3");
+        let parameters = KdfParameters {
+            memory_kib: MIN_KDF_MEMORY_KIB,
+            iterations: 1,
+            parallelism: 1,
+        };
+
+        // A wallet that was encrypted before the KDF was stored
+        let secret_key = CommsSecretKey::random(&mut OsRng);
+        {
+            let (wallet_backend, transaction_backend, output_manager_backend, _) =
+                initialize_sqlite_database_backends(db_path.clone(), None, parameters).unwrap();
+            wallet_backend
+                .write(WriteOperation::Insert(DbKeyValuePair::CommsSecretKey(
+                    secret_key.clone(),
+                )))
+                .unwrap();
+            let cipher = passphrase_cipher("passphrase");
+            wallet_backend.apply_encryption(cipher.clone()).unwrap();
+            // The services share the cipher of the wallet backend, so their values are already encrypted
+            assert!(matches!(
+                output_manager_backend.apply_encryption(cipher.clone()),
+                Err(OutputManagerStorageError::AlreadyEncrypted)
+            ));
+            assert!(matches!(
+                transaction_backend.apply_encryption(cipher),
+                Err(TransactionStorageError::AlreadyEncrypted)
+            ));
+        }
+
+        // The KDF is left alone when the passphrase is wrong
+        assert!(matches!(
+            initialize_sqlite_database_backends(db_path.clone(), Some("wrong".to_string()), parameters),
+            Err(WalletStorageError::IncorrectPassword)
+        ));
+
+        for _ in 0..2 {
+            let (wallet_backend, _, _, _) =
+                initialize_sqlite_database_backends(db_path.clone(), Some("passphrase".to_string()), parameters)
+                    .unwrap();
+            match wallet_backend.fetch(&DbKey::PassphraseKdf).unwrap() {
+                Some(DbValue::PassphraseKdf(PassphraseKdf::Argon2id { parameters: p, .. })) => {
+                    assert_eq!(p, parameters)
+                },
+                _ => panic!("The KDF should have been upgraded to Argon2id"),
+            }
+            match wallet_backend.fetch(&DbKey::CommsSecretKey).unwrap() {
+                Some(DbValue::CommsSecretKey(sk)) => assert_eq!(sk, secret_key),
+                _ => panic!("Should be a Comms Secret Key"),
+            }
+        }
+
+        // Stronger parameters upgrade the KDF again
+        let stronger = KdfParameters {
+            memory_kib: MIN_KDF_MEMORY_KIB * 2,
+            ..parameters
+        };
+        let (wallet_backend, _, _, _) =
+            initialize_sqlite_database_backends(db_path, Some("passphrase".to_string()), stronger).unwrap();
+        match wallet_backend.fetch(&DbKey::PassphraseKdf).unwrap() {
+            Some(DbValue::PassphraseKdf(PassphraseKdf::Argon2id { parameters: p, .. })) => assert_eq!(p, stronger),
+            _ => panic!("The KDF should have been upgraded to Argon2id"),
+        }
+    }
+
+    #[test]
+    fn test_rotate_cipher_changes_the_passphrase() {
+        let db_tempdir = tempdir().unwrap();
+        let db_path = db_tempdir.path().join("wallet.sqlite3");
+        let parameters = KdfParameters {
+            memory_kib: MIN_KDF_MEMORY_KIB,
+            iterations: 1,
+            parallelism: 1,
+        };
+
+        let secret_key = CommsSecretKey::random(&mut OsRng);
+        let master_key = PrivateKey::random(&mut OsRng);
+        {
+            let (wallet_backend, _, output_manager_backend, _) =
+                initialize_sqlite_database_backends(db_path.clone(), None, parameters).unwrap();
+            wallet_backend
+                .write(WriteOperation::Insert(DbKeyValuePair::CommsSecretKey(
+                    secret_key.clone(),
+                )))
+                .unwrap();
+            wallet_backend.apply_encryption(passphrase_cipher("old")).unwrap();
+
+            let kdf = PassphraseKdf::argon2id(parameters);
+            wallet_backend.rotate_cipher(kdf.cipher("new").unwrap(), kdf).unwrap();
+            match wallet_backend.fetch(&DbKey::CommsSecretKey).unwrap() {
+                Some(DbValue::CommsSecretKey(sk)) => assert_eq!(sk, secret_key),
+                _ => panic!("Should be a Comms Secret Key"),
+            }
+            // A service writing after the rotation uses the new cipher
+            output_manager_backend
+                .write(OutputManagerWriteOperation::Insert(
+                    OutputManagerDbKeyValuePair::KeyManagerState(KeyManagerState {
+                        master_key: master_key.clone(),
+                        branch_seed: "".to_string(),
+                        primary_key_index: 0,
+                    }),
+                ))
+                .unwrap();
+        }
+
+        assert!(matches!(
+            initialize_sqlite_database_backends(db_path.clone(), Some("old".to_string()), parameters),
+            Err(WalletStorageError::IncorrectPassword)
+        ));
+        let (wallet_backend, _, output_manager_backend, _) =
+            initialize_sqlite_database_backends(db_path, Some("new".to_string()), parameters).unwrap();
+        match wallet_backend.fetch(&DbKey::CommsSecretKey).unwrap() {
+            Some(DbValue::CommsSecretKey(sk)) => assert_eq!(sk, secret_key),
+            _ => panic!("Should be a Comms Secret Key"),
+        }
+        match output_manager_backend
+            .fetch(&OutputManagerDbKey::KeyManagerState)
+            .unwrap()
+        {
+            Some(OutputManagerDbValue::KeyManagerState(state)) => assert_eq!(state.master_key, master_key),
+            _ => panic!("Should be the key manager state"),
+        }
+    }
+}
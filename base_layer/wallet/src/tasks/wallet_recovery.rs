@@ -26,11 +26,13 @@ use crate::{
     WalletSqlite,
 };
 use chrono::Utc;
-use futures::StreamExt;
+use futures::{channel::mpsc, future, SinkExt, StreamExt};
 use log::*;
 use std::{
+    collections::VecDeque,
     convert::TryFrom,
     str::FromStr,
+    sync::Arc,
     time::{Duration, Instant},
 };
 use tari_comms::{peer_manager::NodeId, types::CommsPublicKey, PeerConnection};
@@ -43,19 +45,31 @@ use tari_core::{
     tari_utilities::Hashable,
     transactions::{tari_amount::MicroTari, transaction::TransactionOutput},
 };
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, Mutex};
 
 pub const LOG_TARGET: &str = "wallet::recovery";
 
 pub const RECOVERY_HEIGHT_KEY: &str = "recovery/height-progress";
+const RECOVERY_HEADER_HASH_KEY: &str = "recovery/header-hash";
 const RECOVERY_NUM_UTXOS_KEY: &str = "recovery/num-utxos";
 const RECOVERY_UTXO_INDEX_KEY: &str = "recovery/utxos-index";
 const RECOVERY_TOTAL_AMOUNT_KEY: &str = "recovery/total-amount";
 
+/// How many blocks are scanned again when the scan position is not on the chain of the base node any more
+const RECOVERY_REORG_RESCAN_DEPTH: u64 = 100;
+/// The number of block ranges per sync peer that a recovery round is split into when scanning with several sync peers
+const SCAN_RANGES_PER_PEER: usize = 4;
+/// The fewest blocks in a scan range, so that short rounds near the chain tip are not split up needlessly
+const MIN_BLOCKS_PER_SCAN_RANGE: u64 = 100;
+/// The number of streamed UTXOs that are buffered before the sync peer is asked to wait for them to be scanned
+pub const DEFAULT_UTXO_STREAM_BUFFER_SIZE: usize = 100;
+
 #[derive(Debug, Default, Clone)]
 pub struct WalletRecoveryTaskBuilder {
     retry_limit: usize,
     peer_seeds: Vec<CommsPublicKey>,
+    max_concurrent_peers: usize,
+    stream_buffer_size: Option<usize>,
 }
 
 impl WalletRecoveryTaskBuilder {
@@ -71,8 +85,29 @@ impl WalletRecoveryTaskBuilder {
         self
     }
 
+    /// Set the maximum number of sync peers that are scanned concurrently. Each recovery round is split into block
+    /// ranges that are handed out to the connected peers, so that a slow peer does not hold up the whole recovery.
+    /// Defaults to a single peer.
+    pub fn with_max_concurrent_peers(&mut self, max_concurrent_peers: usize) -> &mut Self {
+        self.max_concurrent_peers = max_concurrent_peers;
+        self
+    }
+
+    /// Set the number of streamed UTXOs that are buffered before the sync peer is asked to wait for them to be
+    /// scanned. Defaults to [DEFAULT_UTXO_STREAM_BUFFER_SIZE].
+    pub fn with_stream_buffer_size(&mut self, stream_buffer_size: usize) -> &mut Self {
+        self.stream_buffer_size = Some(stream_buffer_size);
+        self
+    }
+
     pub fn build(&mut self, wallet: WalletSqlite) -> WalletRecoveryTask {
-        WalletRecoveryTask::new(wallet, self.peer_seeds.drain(..).collect(), self.retry_limit)
+        WalletRecoveryTask::new(
+            wallet,
+            self.peer_seeds.drain(..).collect(),
+            self.retry_limit,
+            self.max_concurrent_peers.max(1),
+            self.stream_buffer_size.unwrap_or(DEFAULT_UTXO_STREAM_BUFFER_SIZE),
+        )
     }
 }
 
@@ -83,10 +118,19 @@ pub struct WalletRecoveryTask {
     num_retries: usize,
     peer_seeds: Vec<CommsPublicKey>,
     peer_index: usize,
+    max_concurrent_peers: usize,
+    stream_buffer_size: usize,
 }
 
 impl WalletRecoveryTask {
-    fn new(wallet: WalletSqlite, peer_seeds: Vec<CommsPublicKey>, retry_limit: usize) -> Self {
+    fn new(
+        wallet: WalletSqlite,
+        peer_seeds: Vec<CommsPublicKey>,
+        retry_limit: usize,
+        max_concurrent_peers: usize,
+        stream_buffer_size: usize,
+    ) -> Self
+    {
         let (event_sender, _) = broadcast::channel(100);
         Self {
             wallet,
@@ -95,6 +139,8 @@ impl WalletRecoveryTask {
             retry_limit,
             peer_index: 0,
             num_retries: 0,
+            max_concurrent_peers,
+            stream_buffer_size,
         }
     }
 
@@ -106,47 +152,53 @@ impl WalletRecoveryTask {
         self.event_sender.subscribe()
     }
 
-    fn get_next_peer(&mut self) -> Option<NodeId> {
-        let peer = self.peer_seeds.get(self.peer_index).map(NodeId::from_public_key);
-        self.peer_index += 1;
-        peer
+    fn get_next_peers(&mut self) -> Vec<NodeId> {
+        let peers = self
+            .peer_seeds
+            .iter()
+            .skip(self.peer_index)
+            .take(self.max_concurrent_peers)
+            .map(NodeId::from_public_key)
+            .collect::<Vec<_>>();
+        self.peer_index += self.max_concurrent_peers;
+        peers
     }
 
     pub async fn run(mut self) -> Result<(), WalletError> {
         loop {
-            match self.get_next_peer() {
-                Some(peer) => match self.attempt_sync(peer.clone()).await {
-                    Ok((total_scanned, final_utxo_pos, elapsed)) => {
-                        info!(target: LOG_TARGET, "Recovery successful to UTXO #{}", final_utxo_pos);
-                        self.finalize(total_scanned, final_utxo_pos, elapsed).await?;
-                        return Ok(());
-                    },
-                    Err(e) => {
-                        warn!(
-                            target: LOG_TARGET,
-                            "Failed to sync wallet from base node {}: {}", peer, e
-                        );
+            let peers = self.get_next_peers();
+            if peers.is_empty() {
+                self.publish_event(WalletRecoveryEvent::RecoveryRoundFailed {
+                    num_retries: self.num_retries,
+                    retry_limit: self.retry_limit,
+                });
 
-                        continue;
-                    },
-                },
-                None => {
-                    self.publish_event(WalletRecoveryEvent::RecoveryRoundFailed {
-                        num_retries: self.num_retries,
-                        retry_limit: self.retry_limit,
-                    });
-
-                    if self.num_retries >= self.retry_limit {
-                        return Err(WalletError::WalletRecoveryError(format!(
-                            "Failed to recover wallet after {} attempt(s) using all {} sync peer(s). Aborting...",
-                            self.num_retries,
-                            self.peer_seeds.len()
-                        )));
-                    }
+                if self.num_retries >= self.retry_limit {
+                    return Err(WalletError::WalletRecoveryError(format!(
+                        "Failed to recover wallet after {} attempt(s) using all {} sync peer(s). Aborting...",
+                        self.num_retries,
+                        self.peer_seeds.len()
+                    )));
+                }
 
-                    self.num_retries += 1;
-                    // Reset peer index to try connect to the first peer again
-                    self.peer_index = 0;
+                self.num_retries += 1;
+                // Reset peer index to try connect to the first peer again
+                self.peer_index = 0;
+                continue;
+            }
+
+            let peer_list = peers.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
+            match self.attempt_sync(peers).await {
+                Ok((total_scanned, final_utxo_pos, elapsed)) => {
+                    info!(target: LOG_TARGET, "Recovery successful to UTXO #{}", final_utxo_pos);
+                    self.finalize(total_scanned, final_utxo_pos, elapsed).await?;
+                    return Ok(());
+                },
+                Err(e) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Failed to sync wallet from base node(s) {}: {}", peer_list, e
+                    );
                 },
             }
         }
@@ -160,6 +212,7 @@ impl WalletRecoveryTask {
             .unwrap_or_else(|| 0.into());
 
         self.clear_metadata(RecoveryMetadataKey::Height).await?;
+        self.clear_metadata(RecoveryMetadataKey::HeaderHash).await?;
         self.clear_metadata(RecoveryMetadataKey::NumUtxos).await?;
         self.clear_metadata(RecoveryMetadataKey::TotalAmount).await?;
         self.clear_metadata(RecoveryMetadataKey::UtxoIndex).await?;
@@ -192,28 +245,30 @@ impl WalletRecoveryTask {
         }
     }
 
-    async fn attempt_sync(&mut self, peer: NodeId) -> Result<(u64, u64, Duration), WalletError> {
-        let mut connection = self.connect_to_peer(peer.clone()).await?;
-
-        let mut client = connection
-            .connect_rpc_using_builder(BaseNodeSyncRpcClient::builder().with_deadline(Duration::from_secs(60)))
-            .await
-            .map_err(to_wallet_recovery_error)?;
+    async fn attempt_sync(&mut self, peers: Vec<NodeId>) -> Result<(u64, u64, Duration), WalletError> {
+        let mut clients = Vec::with_capacity(peers.len());
+        for peer in peers {
+            match self.connect_sync_client(peer.clone()).await {
+                Ok(client) => clients.push((peer, client)),
+                Err(e) => warn!(target: LOG_TARGET, "Failed to connect to base node {}: {}", peer, e),
+            }
+        }
 
-        let latency = client
-            .get_last_request_latency()
-            .await
-            .map_err(to_wallet_recovery_error)?;
-        self.publish_event(WalletRecoveryEvent::ConnectedToBaseNode(
-            peer.clone(),
-            latency.unwrap_or_default(),
-        ));
+        {
+            let (peer, client) = clients.first_mut().ok_or_else(|| {
+                WalletError::WalletRecoveryError("Could not connect to any of the sync peers".to_string())
+            })?;
+            self.check_scan_position(client, peer).await?;
+        }
 
         let timer = Instant::now();
         let mut total_scanned = 0u64;
         loop {
             let start_index = self.get_start_utxo_mmr_pos().await?;
-            let tip_header = self.get_chain_tip_header(&mut client, &peer).await?;
+            let (peer, client) = clients.first_mut().ok_or_else(|| {
+                WalletError::WalletRecoveryError("All sync peers failed during the recovery round".to_string())
+            })?;
+            let tip_header = self.get_chain_tip_header(client, peer).await?;
             let output_mmr_size = tip_header.output_mmr_size;
             debug!(
                 target: LOG_TARGET,
@@ -236,7 +291,20 @@ impl WalletRecoveryTask {
                 return Ok((total_scanned, start_index, timer.elapsed()));
             }
 
-            let num_scanned = self.recover_utxos(&mut client, start_index, tip_header).await?;
+            let ranges = if clients.len() > 1 {
+                let (peer, client) = &mut clients[0];
+                self.split_scan_range(client, peer, start_index, tip_header, clients.len())
+                    .await?
+            } else {
+                vec![ScanRange {
+                    start: start_index,
+                    end_header: tip_header,
+                }]
+            };
+
+            let (num_scanned, healthy_clients) = self.recover_utxos(clients, start_index, ranges).await;
+            clients = healthy_clients;
+            let num_scanned = num_scanned?;
             debug!(
                 target: LOG_TARGET,
                 "Round completed UTXO #{} in {:.2?} ({} scanned)",
@@ -248,6 +316,30 @@ impl WalletRecoveryTask {
         }
     }
 
+    async fn connect_sync_client(&mut self, peer: NodeId) -> Result<BaseNodeSyncRpcClient, WalletError> {
+        let mut connection = self.connect_to_peer(peer.clone()).await?;
+
+        let mut client = connection
+            .connect_rpc_using_builder(
+                BaseNodeSyncRpcClient::builder()
+                    .with_deadline(Duration::from_secs(60))
+                    .with_stream_buffer_size(self.stream_buffer_size),
+            )
+            .await
+            .map_err(to_wallet_recovery_error)?;
+
+        let latency = client
+            .get_last_request_latency()
+            .await
+            .map_err(to_wallet_recovery_error)?;
+        self.publish_event(WalletRecoveryEvent::ConnectedToBaseNode(
+            peer,
+            latency.unwrap_or_default(),
+        ));
+
+        Ok(client)
+    }
+
     async fn get_chain_tip_header(
         &self,
         client: &mut BaseNodeSyncRpcClient,
@@ -271,6 +363,73 @@ impl WalletRecoveryTask {
         Ok(end_header)
     }
 
+    /// The scan position is the header that the recovery is scanning up to and the index of the last scanned UTXO below
+    /// it. A recovery that was interrupted, or that continues with another base node, resumes from the scan position as
+    /// long as its header is on the chain of the base node. Otherwise the chain has been reorganised since, and the
+    /// last `RECOVERY_REORG_RESCAN_DEPTH` blocks before the scan position are scanned again.
+    async fn check_scan_position(&self, client: &mut BaseNodeSyncRpcClient, peer: &NodeId) -> Result<(), WalletError> {
+        let height = self.get_metadata::<u64>(RecoveryMetadataKey::Height).await?;
+        let header_hash = self.get_metadata::<String>(RecoveryMetadataKey::HeaderHash).await?;
+        let utxo_index = self.get_metadata::<u64>(RecoveryMetadataKey::UtxoIndex).await?;
+        let (height, header_hash, utxo_index) = match (height, header_hash, utxo_index) {
+            (Some(height), Some(header_hash), Some(utxo_index)) => (height, header_hash, utxo_index),
+            _ => return Ok(()),
+        };
+
+        let header = self.get_header_by_height(client, peer, height).await?;
+        if header.hash().to_hex() == header_hash {
+            info!(
+                target: LOG_TARGET,
+                "Resuming recovery from UTXO #{} (height {})", utxo_index, height
+            );
+            self.publish_event(WalletRecoveryEvent::ResumingFromCheckpoint { height, utxo_index });
+            return Ok(());
+        }
+
+        let rescan_height = height.saturating_sub(RECOVERY_REORG_RESCAN_DEPTH);
+        let rescan_header = self.get_header_by_height(client, peer, rescan_height).await?;
+        let rescan_utxo_index = utxo_index.min(rescan_header.output_mmr_size.saturating_sub(1));
+        warn!(
+            target: LOG_TARGET,
+            "The recovery scan position at height {} is not on the chain of base node {}, scanning again from UTXO \
+             #{} (height {})",
+            height,
+            peer,
+            rescan_utxo_index,
+            rescan_height
+        );
+        self.set_scan_position(&rescan_header, rescan_utxo_index).await?;
+        self.publish_event(WalletRecoveryEvent::ResumingFromCheckpoint {
+            height: rescan_height,
+            utxo_index: rescan_utxo_index,
+        });
+        Ok(())
+    }
+
+    async fn get_header_by_height(
+        &self,
+        client: &mut BaseNodeSyncRpcClient,
+        peer: &NodeId,
+        height: u64,
+    ) -> Result<BlockHeader, WalletError>
+    {
+        let header = client.get_header_by_height(height).await.map_err(|e| {
+            WalletError::WalletRecoveryError(format!(
+                "Base node {} did not return the header at height {}: {}",
+                peer, height, e
+            ))
+        })?;
+        BlockHeader::try_from(header).map_err(to_wallet_recovery_error)
+    }
+
+    async fn set_scan_position(&self, header: &BlockHeader, utxo_index: u64) -> Result<(), WalletError> {
+        self.set_metadata(RecoveryMetadataKey::Height, header.height).await?;
+        self.set_metadata(RecoveryMetadataKey::HeaderHash, header.hash().to_hex())
+            .await?;
+        self.set_metadata(RecoveryMetadataKey::UtxoIndex, utxo_index).await?;
+        Ok(())
+    }
+
     async fn get_start_utxo_mmr_pos(&self) -> Result<u64, WalletError> {
         let previous_sync_height = self
             .get_metadata::<u64>(RecoveryMetadataKey::Height)
@@ -294,138 +453,274 @@ impl WalletRecoveryTask {
         Ok(previous_utxo_index.unwrap_or(0u64))
     }
 
+    /// Splits the UTXOs from `start_index` up to the tip header into block ranges that are scanned concurrently. A few
+    /// ranges are created per sync peer so that the faster peers pick up more of them.
+    async fn split_scan_range(
+        &self,
+        client: &mut BaseNodeSyncRpcClient,
+        peer: &NodeId,
+        start_index: u64,
+        tip_header: BlockHeader,
+        num_peers: usize,
+    ) -> Result<Vec<ScanRange>, WalletError>
+    {
+        let start_height = self
+            .find_height_of_utxo(client, peer, start_index, tip_header.height)
+            .await?;
+        let end_heights = split_scan_heights(start_height, tip_header.height, num_peers * SCAN_RANGES_PER_PEER);
+
+        let mut ranges = Vec::with_capacity(end_heights.len());
+        let mut range_start = start_index;
+        for height in end_heights {
+            let end_header = if height == tip_header.height {
+                tip_header.clone()
+            } else {
+                self.get_header_by_height(client, peer, height).await?
+            };
+            // Skip ranges of blocks without any outputs
+            if end_header.output_mmr_size > range_start {
+                let next_range_start = end_header.output_mmr_size;
+                ranges.push(ScanRange {
+                    start: range_start,
+                    end_header,
+                });
+                range_start = next_range_start;
+            }
+        }
+
+        debug!(
+            target: LOG_TARGET,
+            "Split UTXOs #{} to #{} (heights {} to {}) into {} scan range(s)",
+            start_index,
+            tip_header.output_mmr_size - 1,
+            start_height,
+            tip_header.height,
+            ranges.len()
+        );
+        Ok(ranges)
+    }
+
+    /// Returns the height of the block that contains the UTXO at the given index
+    async fn find_height_of_utxo(
+        &self,
+        client: &mut BaseNodeSyncRpcClient,
+        peer: &NodeId,
+        utxo_index: u64,
+        tip_height: u64,
+    ) -> Result<u64, WalletError>
+    {
+        let mut low = 0;
+        let mut high = tip_height;
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let header = self.get_header_by_height(client, peer, mid).await?;
+            if header.output_mmr_size > utxo_index {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        Ok(low)
+    }
+
+    /// Scans the ranges concurrently, one stream per sync peer, and imports the recovered outputs as they arrive. The
+    /// clients of the peers that did not fail are returned so that they can be used for the next round.
     async fn recover_utxos(
         &mut self,
-        client: &mut BaseNodeSyncRpcClient,
+        clients: Vec<(NodeId, BaseNodeSyncRpcClient)>,
+        start_mmr_leaf_index: u64,
+        ranges: Vec<ScanRange>,
+    ) -> (Result<u64, WalletError>, Vec<(NodeId, BaseNodeSyncRpcClient)>)
+    {
+        let queue = Arc::new(Mutex::new(ranges.iter().cloned().enumerate().collect::<VecDeque<_>>()));
+        let (sender, receiver) = mpsc::channel(clients.len() * 2);
+        let scanners = future::join_all(
+            clients
+                .into_iter()
+                .map(|(peer, client)| scan_ranges(peer, client, queue.clone(), sender.clone())),
+        );
+        // Drop our sender so that the receiver ends once all scanners have finished
+        drop(sender);
+
+        let (result, clients) = future::join(
+            self.import_scanned_outputs(receiver, start_mmr_leaf_index, &ranges),
+            scanners,
+        )
+        .await;
+        (result, clients.into_iter().flatten().collect())
+    }
+
+    async fn import_scanned_outputs(
+        &mut self,
+        mut receiver: mpsc::Receiver<ScanMessage>,
         start_mmr_leaf_index: u64,
-        end_header: BlockHeader,
+        ranges: &[ScanRange],
     ) -> Result<u64, WalletError>
     {
+        let end_header = &ranges
+            .last()
+            .ok_or_else(|| WalletError::WalletRecoveryError("There are no UTXOs to scan".to_string()))?
+            .end_header;
+        let end_utxo_index = end_header.output_mmr_size - 1;
         info!(
             target: LOG_TARGET,
-            "Wallet recovery attempting to recover from UTXO #{} to #{} (height {})",
+            "Wallet recovery attempting to recover from UTXO #{} to #{} (height {}) in {} range(s)",
             start_mmr_leaf_index,
             end_header.output_mmr_size,
-            end_header.height
+            end_header.height,
+            ranges.len()
         );
 
-        let end_header_hash = end_header.hash();
-        let end_header_size = end_header.output_mmr_size;
-
         let mut num_recovered = 0u64;
         let mut total_amount = MicroTari::from(0);
         let mut total_scanned = 0;
 
-        self.publish_event(WalletRecoveryEvent::Progress(start_mmr_leaf_index, end_header_size - 1));
-        let request = SyncUtxosRequest {
-            start: start_mmr_leaf_index,
-            end_header_hash,
-            include_pruned_utxos: false,
-            include_deleted_bitmaps: false,
-        };
+        let current_num_utxos = self.get_metadata(RecoveryMetadataKey::NumUtxos).await?.unwrap_or(0u64);
+        let current_total_amount = self
+            .get_metadata::<MicroTari>(RecoveryMetadataKey::TotalAmount)
+            .await?
+            .unwrap_or_else(|| 0.into());
 
-        let utxo_stream = client.sync_utxos(request).await.map_err(to_wallet_recovery_error)?;
-        // We download in chunks just because rewind_outputs works with multiple outputs (and could parallelized
-        // rewinding)
-        let mut utxo_stream = utxo_stream.chunks(10);
-        let mut last_utxo_index = 0u64;
-        let mut iteration_count = 0u64;
-        while let Some(response) = utxo_stream.next().await {
-            let response: Vec<proto::base_node::SyncUtxosResponse> = response
-                .into_iter()
-                .map(|v| v.map_err(to_wallet_recovery_error))
-                .collect::<Result<Vec<_>, _>>()?;
-
-            let current_utxo_index = response
-                // Assumes correct ordering which is otherwise not required for this protocol
-                .last()
-                .ok_or_else(|| {
-                    WalletError::WalletRecoveryError("Invalid response from base node: response was empty".to_string())
-                })?
-                .mmr_index;
-            if current_utxo_index < last_utxo_index {
-                return Err(WalletError::WalletRecoveryError(
-                    "Invalid response from base node: mmr index must be non-decreasing".to_string(),
-                ));
-            }
-            last_utxo_index = current_utxo_index;
+        self.publish_event(WalletRecoveryEvent::ScanningToHeight(end_header.height));
+        self.publish_event(WalletRecoveryEvent::OutputsRecovered(
+            current_num_utxos,
+            current_total_amount,
+        ));
+        self.publish_event(WalletRecoveryEvent::Progress(start_mmr_leaf_index, end_utxo_index));
 
-            let outputs = response
-                .into_iter()
-                .filter_map(|utxo| {
-                    utxo.into_utxo()
-                        .and_then(|o| o.utxo)
-                        .and_then(|utxo| utxo.into_transaction_output())
-                        .map(|output| TransactionOutput::try_from(output).map_err(to_wallet_recovery_error))
-                })
-                .collect::<Result<Vec<_>, _>>()?;
-
-            total_scanned += outputs.len();
-            // Reduce the number of db hits by only persisting progress every N iterations
-            const COMMIT_EVERY_N: u64 = 100;
-            if iteration_count % COMMIT_EVERY_N == 0 || current_utxo_index >= end_header_size - 1 {
-                self.publish_event(WalletRecoveryEvent::Progress(current_utxo_index, end_header_size - 1));
-                self.set_metadata(RecoveryMetadataKey::UtxoIndex, current_utxo_index)
-                    .await?;
-            }
+        // The outputs below the start index are on the chain of the first range's end header as well, so the scan
+        // position can move to it before the round starts
+        self.set_scan_position(&ranges[0].end_header, start_mmr_leaf_index)
+            .await?;
 
-            iteration_count += 1;
-            let unblinded_outputs = self.wallet.output_manager_service.rewind_outputs(outputs).await?;
-            if unblinded_outputs.is_empty() {
-                continue;
-            }
+        let mut progress = ScanProgress::new(start_mmr_leaf_index, ranges);
+        let mut iteration_count = 0u64;
+        while let Some(message) = receiver.next().await {
+            match message {
+                ScanMessage::Outputs {
+                    range_id,
+                    last_utxo_index,
+                    outputs,
+                } => {
+                    total_scanned += outputs.len();
+                    let (num_imported, amount_imported) = self.import_outputs(outputs).await?;
+                    if num_imported > 0 {
+                        num_recovered += num_imported;
+                        total_amount += amount_imported;
+                        self.publish_event(WalletRecoveryEvent::OutputsRecovered(
+                            current_num_utxos + num_recovered,
+                            current_total_amount + total_amount,
+                        ));
+                    }
+                    progress.set_scanned_to(range_id, last_utxo_index);
 
-            let source_public_key = self.wallet.comms.node_identity_ref().public_key().clone();
-
-            for uo in unblinded_outputs {
-                match self
-                    .wallet
-                    .import_utxo(
-                        uo.value,
-                        &uo.spending_key,
-                        &source_public_key,
-                        uo.features,
-                        format!("Recovered on {}.", Utc::now().naive_utc()),
-                    )
-                    .await
-                {
-                    Ok(_) => {
-                        num_recovered = num_recovered.saturating_add(1);
-                        total_amount += uo.value;
-                    },
-                    Err(WalletError::OutputManagerError(OutputManagerError::OutputManagerStorageError(
-                        OutputManagerStorageError::DuplicateOutput,
-                    ))) => warn!(target: LOG_TARGET, "Recovered output already in database"),
-                    Err(e) => return Err(e),
-                }
+                    // Reduce the number of db hits by only persisting progress every N iterations. Progress is only
+                    // persisted once the outputs of the chunk have been imported, so that resuming never skips an
+                    // output.
+                    const COMMIT_EVERY_N: u64 = 100;
+                    iteration_count += 1;
+                    if iteration_count % COMMIT_EVERY_N != 0 {
+                        continue;
+                    }
+                },
+                ScanMessage::RangeCompleted(range_id) => progress.set_completed(range_id),
+                ScanMessage::PeerFailed { peer, error } => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Base node {} failed to return its scan range, handing it to the other sync peers: {}",
+                        peer,
+                        error
+                    );
+                    continue;
+                },
             }
-        }
 
-        self.set_metadata(RecoveryMetadataKey::Height, end_header.height)
+            self.publish_event(WalletRecoveryEvent::Progress(
+                progress.num_scanned_to(end_utxo_index),
+                end_utxo_index,
+            ));
+            let (header, utxo_index) = progress.scan_position();
+            self.set_progress(
+                header,
+                utxo_index,
+                current_num_utxos + num_recovered,
+                current_total_amount + total_amount,
+            )
             .await?;
+        }
 
-        let current_num_utxos = self.get_metadata(RecoveryMetadataKey::NumUtxos).await?.unwrap_or(0u64);
-        self.set_metadata(RecoveryMetadataKey::NumUtxos, current_num_utxos + num_recovered)
-            .await?;
-
-        let current_total_amount = self
-            .get_metadata::<MicroTari>(RecoveryMetadataKey::TotalAmount)
-            .await?
-            .unwrap_or_else(|| 0.into());
-
-        self.set_metadata(RecoveryMetadataKey::UtxoIndex, last_utxo_index)
-            .await?;
-        self.set_metadata(
-            RecoveryMetadataKey::TotalAmount,
-            (current_total_amount + total_amount).as_u64(),
+        let (header, utxo_index) = progress.scan_position();
+        self.set_progress(
+            header,
+            utxo_index,
+            current_num_utxos + num_recovered,
+            current_total_amount + total_amount,
         )
         .await?;
 
-        self.publish_event(WalletRecoveryEvent::Progress(end_header_size - 1, end_header_size - 1));
+        if !progress.is_complete() {
+            return Err(WalletError::WalletRecoveryError(format!(
+                "All sync peers failed before the recovery round was completed (scanned up to UTXO #{})",
+                utxo_index
+            )));
+        }
+
+        self.publish_event(WalletRecoveryEvent::Progress(end_utxo_index, end_utxo_index));
 
         Ok(total_scanned as u64)
     }
 
+    /// Rewinds the outputs and imports the ones that belong to this wallet, returning the number and value of the
+    /// imported outputs
+    async fn import_outputs(&mut self, outputs: Vec<TransactionOutput>) -> Result<(u64, MicroTari), WalletError> {
+        let unblinded_outputs = self.wallet.output_manager_service.rewind_outputs(outputs).await?;
+        let source_public_key = self.wallet.comms.node_identity_ref().public_key().clone();
+
+        let mut num_imported = 0u64;
+        let mut amount_imported = MicroTari::from(0);
+        for uo in unblinded_outputs {
+            match self
+                .wallet
+                .import_utxo(
+                    uo.value,
+                    &uo.spending_key,
+                    &source_public_key,
+                    uo.features,
+                    format!("Recovered on {}.", Utc::now().naive_utc()),
+                )
+                .await
+            {
+                Ok(_) => {
+                    num_imported = num_imported.saturating_add(1);
+                    amount_imported += uo.value;
+                },
+                Err(WalletError::OutputManagerError(OutputManagerError::OutputManagerStorageError(
+                    OutputManagerStorageError::DuplicateOutput,
+                ))) => warn!(target: LOG_TARGET, "Recovered output already in database"),
+                Err(WalletError::OutputManagerError(OutputManagerError::InvalidImportedOutput(reason))) => {
+                    warn!(target: LOG_TARGET, "Recovered output not imported: {}", reason)
+                },
+                Err(e) => return Err(e),
+            }
+        }
+        Ok((num_imported, amount_imported))
+    }
+
+    async fn set_progress(
+        &self,
+        header: &BlockHeader,
+        utxo_index: u64,
+        num_recovered: u64,
+        total_amount: MicroTari,
+    ) -> Result<(), WalletError>
+    {
+        self.set_metadata(RecoveryMetadataKey::NumUtxos, num_recovered).await?;
+        self.set_metadata(RecoveryMetadataKey::TotalAmount, total_amount.as_u64())
+            .await?;
+        self.set_scan_position(header, utxo_index).await?;
+        Ok(())
+    }
+
     async fn set_metadata<T: ToString>(&self, key: RecoveryMetadataKey, value: T) -> Result<(), WalletError> {
         self.wallet
             .db
@@ -463,6 +758,7 @@ enum RecoveryMetadataKey {
     NumUtxos,
     UtxoIndex,
     Height,
+    HeaderHash,
 }
 
 impl RecoveryMetadataKey {
@@ -473,6 +769,7 @@ impl RecoveryMetadataKey {
             NumUtxos => RECOVERY_NUM_UTXOS_KEY,
             UtxoIndex => RECOVERY_UTXO_INDEX_KEY,
             Height => RECOVERY_HEIGHT_KEY,
+            HeaderHash => RECOVERY_HEADER_HASH_KEY,
         }
     }
 }
@@ -491,13 +788,272 @@ pub enum WalletRecoveryEvent {
         num_retries: usize,
         retry_limit: usize,
     },
+    /// The recovery continues from the scan position of an earlier recovery, at this block height and UTXO index
+    ResumingFromCheckpoint {
+        height: u64,
+        utxo_index: u64,
+    },
     /// Progress of the recovery process (current_block, current_chain_height)
     Progress(u64, u64),
+    /// The recovery round is scanning the outputs of the chain up to this block height
+    ScanningToHeight(u64),
+    /// Outputs recovered so far (Num of Recovered outputs, Value of recovered outputs)
+    OutputsRecovered(u64, MicroTari),
     /// Completed Recovery (Number scanned, Num of Recovered outputs, Value of recovered outputs, Time taken)
     Completed(u64, u64, MicroTari, Duration),
 }
 
+/// A range of UTXOs that is scanned by one sync peer, from the start index up to the outputs of the end header
+#[derive(Debug, Clone)]
+struct ScanRange {
+    start: u64,
+    end_header: BlockHeader,
+}
+
+impl ScanRange {
+    fn end_index(&self) -> u64 {
+        self.end_header.output_mmr_size - 1
+    }
+}
+
+enum ScanMessage {
+    Outputs {
+        range_id: usize,
+        last_utxo_index: u64,
+        outputs: Vec<TransactionOutput>,
+    },
+    RangeCompleted(usize),
+    PeerFailed {
+        peer: NodeId,
+        error: String,
+    },
+}
+
+/// Tracks how far each range of a recovery round has been imported. The ranges complete in any order, but the scan
+/// position only moves over the ranges that have been imported without a gap, so that resuming never skips an output.
+struct ScanProgress<'a> {
+    start_index: u64,
+    ranges: &'a [ScanRange],
+    scanned_to: Vec<Option<u64>>,
+    completed: Vec<bool>,
+}
+
+impl<'a> ScanProgress<'a> {
+    fn new(start_index: u64, ranges: &'a [ScanRange]) -> Self {
+        Self {
+            start_index,
+            ranges,
+            scanned_to: vec![None; ranges.len()],
+            completed: vec![false; ranges.len()],
+        }
+    }
+
+    fn set_scanned_to(&mut self, range_id: usize, utxo_index: u64) {
+        let scanned_to = &mut self.scanned_to[range_id];
+        *scanned_to = Some(scanned_to.map_or(utxo_index, |i| i.max(utxo_index)));
+    }
+
+    fn set_completed(&mut self, range_id: usize) {
+        self.completed[range_id] = true;
+    }
+
+    fn is_complete(&self) -> bool {
+        self.completed.iter().all(|c| *c)
+    }
+
+    /// The number of UTXOs scanned across all ranges, counted from the start index
+    fn num_scanned_to(&self, end_index: u64) -> u64 {
+        let num_scanned = self
+            .ranges
+            .iter()
+            .enumerate()
+            .map(|(i, range)| match (self.completed[i], self.scanned_to[i]) {
+                (true, _) => range.end_index() + 1 - range.start,
+                (false, Some(index)) => index + 1 - range.start,
+                (false, None) => 0,
+            })
+            .sum::<u64>();
+        (self.start_index + num_scanned).min(end_index)
+    }
+
+    /// The header and UTXO index up to which every output has been imported
+    fn scan_position(&self) -> (&'a BlockHeader, u64) {
+        let ranges = self.ranges;
+        match self.completed.iter().position(|c| !c) {
+            Some(0) => (&ranges[0].end_header, self.scanned_to[0].unwrap_or(self.start_index)),
+            Some(i) => match self.scanned_to[i] {
+                Some(index) => (&ranges[i].end_header, index),
+                None => (&ranges[i - 1].end_header, ranges[i - 1].end_index()),
+            },
+            None => {
+                let last = &ranges[ranges.len() - 1];
+                (&last.end_header, last.end_index())
+            },
+        }
+    }
+}
+
+/// Splits the blocks from `start_height` to `end_height` into at most `num_ranges` ranges of roughly equal length,
+/// none shorter than `MIN_BLOCKS_PER_SCAN_RANGE` unless there is only one. Returns the height of the last block of each
+/// range.
+fn split_scan_heights(start_height: u64, end_height: u64, num_ranges: usize) -> Vec<u64> {
+    let num_blocks = end_height.saturating_sub(start_height) + 1;
+    let num_ranges = (num_ranges as u64).min(num_blocks / MIN_BLOCKS_PER_SCAN_RANGE).max(1);
+    (1..=num_ranges)
+        .map(|i| start_height + num_blocks * i / num_ranges - 1)
+        .collect()
+}
+
+/// Scans ranges from the queue over the given sync peer until the queue is empty. When the peer fails, its range is put
+/// back on the queue for the other sync peers, and its client is not returned.
+async fn scan_ranges(
+    peer: NodeId,
+    mut client: BaseNodeSyncRpcClient,
+    queue: Arc<Mutex<VecDeque<(usize, ScanRange)>>>,
+    mut sender: mpsc::Sender<ScanMessage>,
+) -> Option<(NodeId, BaseNodeSyncRpcClient)>
+{
+    loop {
+        let next_range = queue.lock().await.pop_front();
+        let (range_id, range) = match next_range {
+            Some(r) => r,
+            None => return Some((peer, client)),
+        };
+
+        match scan_range(&mut client, range_id, &range, &mut sender).await {
+            Ok(()) => {
+                if sender.send(ScanMessage::RangeCompleted(range_id)).await.is_err() {
+                    // The recovery round has ended
+                    return Some((peer, client));
+                }
+            },
+            Err(e) => {
+                queue.lock().await.push_front((range_id, range));
+                let _ = sender
+                    .send(ScanMessage::PeerFailed {
+                        peer,
+                        error: e.to_string(),
+                    })
+                    .await;
+                return None;
+            },
+        }
+    }
+}
+
+async fn scan_range(
+    client: &mut BaseNodeSyncRpcClient,
+    range_id: usize,
+    range: &ScanRange,
+    sender: &mut mpsc::Sender<ScanMessage>,
+) -> Result<(), WalletError>
+{
+    let request = SyncUtxosRequest {
+        start: range.start,
+        end_header_hash: range.end_header.hash(),
+        include_pruned_utxos: false,
+        include_deleted_bitmaps: false,
+    };
+
+    let utxo_stream = client.sync_utxos(request).await.map_err(to_wallet_recovery_error)?;
+    // We download in chunks just because rewind_outputs works with multiple outputs (and could parallelized
+    // rewinding)
+    let mut utxo_stream = utxo_stream.chunks(10);
+    let mut last_utxo_index = range.start;
+    while let Some(response) = utxo_stream.next().await {
+        let response: Vec<proto::base_node::SyncUtxosResponse> = response
+            .into_iter()
+            .map(|v| v.map_err(to_wallet_recovery_error))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let current_utxo_index = response
+            // Assumes correct ordering which is otherwise not required for this protocol
+            .last()
+            .ok_or_else(|| {
+                WalletError::WalletRecoveryError("Invalid response from base node: response was empty".to_string())
+            })?
+            .mmr_index;
+        if current_utxo_index < last_utxo_index {
+            return Err(WalletError::WalletRecoveryError(
+                "Invalid response from base node: mmr index must be non-decreasing".to_string(),
+            ));
+        }
+        if current_utxo_index > range.end_index() {
+            return Err(WalletError::WalletRecoveryError(
+                "Invalid response from base node: mmr index is beyond the end header".to_string(),
+            ));
+        }
+        last_utxo_index = current_utxo_index;
+
+        let outputs = response
+            .into_iter()
+            .filter_map(|utxo| {
+                utxo.into_utxo()
+                    .and_then(|o| o.utxo)
+                    .and_then(|utxo| utxo.into_transaction_output())
+                    .map(|output| TransactionOutput::try_from(output).map_err(to_wallet_recovery_error))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let message = ScanMessage::Outputs {
+            range_id,
+            last_utxo_index,
+            outputs,
+        };
+        if sender.send(message).await.is_err() {
+            // The recovery round has ended
+            return Ok(());
+        }
+    }
+
+    Ok(())
+}
+
 // TODO: Replace this with WalletRecoveryError error object
 fn to_wallet_recovery_error<T: ToString>(err: T) -> WalletError {
     WalletError::WalletRecoveryError(err.to_string())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn scan_range(start: u64, height: u64, output_mmr_size: u64) -> ScanRange {
+        let mut end_header = BlockHeader::new(0);
+        end_header.height = height;
+        end_header.output_mmr_size = output_mmr_size;
+        ScanRange { start, end_header }
+    }
+
+    #[test]
+    fn scan_heights_are_split_into_contiguous_ranges() {
+        assert_eq!(split_scan_heights(0, 999, 4), vec![249, 499, 749, 999]);
+        assert_eq!(split_scan_heights(10, 409, 8), vec![109, 209, 309, 409]);
+        assert_eq!(split_scan_heights(500, 549, 8), vec![549]);
+        assert_eq!(split_scan_heights(0, 0, 4), vec![0]);
+    }
+
+    #[test]
+    fn scan_position_only_moves_over_ranges_imported_without_a_gap() {
+        let ranges = vec![scan_range(5, 10, 20), scan_range(20, 20, 40), scan_range(40, 30, 60)];
+        let mut progress = ScanProgress::new(5, &ranges);
+        assert_eq!(progress.scan_position(), (&ranges[0].end_header, 5));
+
+        // Later ranges completing first do not move the scan position
+        progress.set_completed(2);
+        progress.set_scanned_to(1, 30);
+        assert_eq!(progress.scan_position(), (&ranges[0].end_header, 5));
+        assert_eq!(progress.num_scanned_to(59), 5 + 11 + 20);
+
+        progress.set_scanned_to(0, 12);
+        assert_eq!(progress.scan_position(), (&ranges[0].end_header, 12));
+
+        progress.set_completed(0);
+        assert_eq!(progress.scan_position(), (&ranges[1].end_header, 30));
+
+        progress.set_completed(1);
+        assert!(progress.is_complete());
+        assert_eq!(progress.scan_position(), (&ranges[2].end_header, 59));
+        assert_eq!(progress.num_scanned_to(59), 59);
+    }
+}
@@ -0,0 +1,403 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::{
+    base_node_service::handle::BaseNodeEvent,
+    error::WalletError,
+    output_manager_service::handle::PublicRewindKeys,
+    storage::database::WatchedOutput,
+    tasks::wallet_recovery::DEFAULT_UTXO_STREAM_BUFFER_SIZE,
+    WalletSqlite,
+};
+use chrono::Utc;
+use futures::{FutureExt, StreamExt};
+use log::*;
+use std::{collections::HashSet, convert::TryFrom, time::Duration};
+use tari_comms::{peer_manager::NodeId, types::CommsPublicKey, PeerConnection};
+use tari_core::{
+    base_node::{rpc::BaseNodeWalletRpcClient, sync::rpc::BaseNodeSyncRpcClient},
+    blocks::BlockHeader,
+    proto::base_node::{FetchMatchingUtxos, SyncUtxosRequest},
+    tari_utilities::Hashable,
+    transactions::{tari_amount::MicroTari, transaction::TransactionOutput, types::RangeProofService},
+};
+use tokio::{sync::broadcast, time};
+
+pub const LOG_TARGET: &str = "wallet::watch_only_scan";
+
+/// The index of the next UTXO to scan, which is stored as a client value so that a scan resumes where the last one
+/// stopped
+const WATCH_ONLY_UTXO_INDEX_KEY: &str = "watch_only/utxo-index";
+/// The number of watched outputs that are asked about in one request when checking which have been spent
+const SPENT_QUERY_BATCH_SIZE: usize = 100;
+
+#[derive(Debug, Clone)]
+pub enum WatchOnlyScanEvent {
+    /// A scan completed (Num of outputs found, Num of outputs found to be spent)
+    ScanCompleted(usize, usize),
+    ScanFailed(String),
+}
+
+/// Periodically scans the chain for the outputs that can be rewound with the view key of a watch-only wallet, and
+/// checks which of the watched outputs have been spent since.
+///
+/// The view key only reveals the value of an output, so the outputs are kept in their own table rather than handed
+/// to the output manager, which could not spend them. Outputs that the watched wallet received before its keys were
+/// derived per branch are found with the legacy rewind key of the view key, if it has one.
+pub struct WatchOnlyScanner {
+    wallet: WalletSqlite,
+    keys: PublicRewindKeys,
+    base_node_public_key: CommsPublicKey,
+    scan_interval: Duration,
+    event_sender: broadcast::Sender<WatchOnlyScanEvent>,
+}
+
+impl WatchOnlyScanner {
+    pub fn new(
+        wallet: WalletSqlite,
+        keys: PublicRewindKeys,
+        base_node_public_key: CommsPublicKey,
+        scan_interval: Duration,
+    ) -> Self
+    {
+        let (event_sender, _) = broadcast::channel(20);
+        Self {
+            wallet,
+            keys,
+            base_node_public_key,
+            scan_interval,
+            event_sender,
+        }
+    }
+
+    pub fn get_event_receiver(&mut self) -> broadcast::Receiver<WatchOnlyScanEvent> {
+        self.event_sender.subscribe()
+    }
+
+    /// Scan every `scan_interval` until the wallet shuts down, following the base node peer that is set on the wallet
+    pub async fn run(mut self) {
+        let mut shutdown = self.wallet.comms.shutdown_signal();
+        let mut base_node_events = self.wallet.base_node_service.get_event_stream_fused();
+        let mut ticks = time::interval(self.scan_interval).fuse();
+
+        loop {
+            futures::select! {
+                _ = ticks.select_next_some() => {
+                    let event = match self.scan().await {
+                        Ok((num_found, num_spent)) => WatchOnlyScanEvent::ScanCompleted(num_found, num_spent),
+                        Err(e) => {
+                            warn!(target: LOG_TARGET, "Watch-only scan failed: {}", e);
+                            WatchOnlyScanEvent::ScanFailed(e.to_string())
+                        },
+                    };
+                    let _ = self.event_sender.send(event);
+                },
+                event = base_node_events.select_next_some() => {
+                    if let Ok(event) = event {
+                        if let BaseNodeEvent::BaseNodePeerSet(peer) = &*event {
+                            self.base_node_public_key = peer.public_key.clone();
+                        }
+                    }
+                },
+                _ = shutdown => {
+                    info!(target: LOG_TARGET, "Watch-only scanner shutting down");
+                    break;
+                },
+            }
+        }
+    }
+
+    async fn scan(&mut self) -> Result<(usize, usize), WalletError> {
+        let mut connection = self.connect_to_base_node().await?;
+        let num_found = self.scan_for_outputs(&mut connection).await?;
+        let num_spent = self.check_spent_outputs(&mut connection).await?;
+        debug!(
+            target: LOG_TARGET,
+            "Watch-only scan found {} outputs and {} spent outputs", num_found, num_spent
+        );
+        Ok((num_found, num_spent))
+    }
+
+    async fn connect_to_base_node(&self) -> Result<PeerConnection, WalletError> {
+        let node_id = NodeId::from_public_key(&self.base_node_public_key);
+        Ok(self.wallet.comms.connectivity().dial_peer(node_id).await?)
+    }
+
+    async fn scan_for_outputs(&self, connection: &mut PeerConnection) -> Result<usize, WalletError> {
+        let mut client = connection
+            .connect_rpc_using_builder(
+                BaseNodeSyncRpcClient::builder()
+                    .with_deadline(Duration::from_secs(60))
+                    .with_stream_buffer_size(DEFAULT_UTXO_STREAM_BUFFER_SIZE),
+            )
+            .await
+            .map_err(to_watch_only_scan_error)?;
+
+        let chain_metadata = client.get_chain_metadata().await.map_err(to_watch_only_scan_error)?;
+        let tip_header = client
+            .get_header_by_height(chain_metadata.height_of_longest_chain())
+            .await
+            .map_err(to_watch_only_scan_error)?;
+        let tip_header = BlockHeader::try_from(tip_header).map_err(to_watch_only_scan_error)?;
+
+        let start_index = self
+            .wallet
+            .db
+            .get_client_key_from_str::<u64>(WATCH_ONLY_UTXO_INDEX_KEY.to_string())
+            .await?
+            .unwrap_or(0);
+        // The start index could be past the tip if the base node changed to one that is behind the last one
+        if start_index >= tip_header.output_mmr_size.saturating_sub(1) {
+            return Ok(0);
+        }
+
+        let request = SyncUtxosRequest {
+            start: start_index,
+            end_header_hash: tip_header.hash(),
+            include_pruned_utxos: false,
+            include_deleted_bitmaps: false,
+        };
+        let mut utxo_stream = client.sync_utxos(request).await.map_err(to_watch_only_scan_error)?;
+
+        let known_hashes = self
+            .wallet
+            .db
+            .get_watched_outputs()
+            .await?
+            .into_iter()
+            .map(|o| o.hash)
+            .collect::<HashSet<_>>();
+        let mut last_utxo_index = start_index;
+        let mut outputs = Vec::new();
+        while let Some(response) = utxo_stream.next().await {
+            let response = response.map_err(to_watch_only_scan_error)?;
+            last_utxo_index = last_utxo_index.max(response.mmr_index);
+            if let Some(output) = response
+                .into_utxo()
+                .and_then(|o| o.utxo)
+                .and_then(|utxo| utxo.into_transaction_output())
+            {
+                outputs.push(TransactionOutput::try_from(output).map_err(to_watch_only_scan_error)?);
+            }
+        }
+
+        let mut num_found = 0;
+        for (output, value) in find_owned_outputs(&outputs, &self.keys, &self.wallet.factories.range_proof) {
+            let hash = output.hash();
+            if known_hashes.contains(&hash) {
+                continue;
+            }
+            self.wallet
+                .db
+                .set_watched_output(WatchedOutput {
+                    hash,
+                    value,
+                    detected_at: Utc::now().naive_utc(),
+                    spent_at: None,
+                    output: Some(output.clone()),
+                })
+                .await?;
+            num_found += 1;
+        }
+        self.wallet
+            .db
+            .set_client_key_value(WATCH_ONLY_UTXO_INDEX_KEY.to_string(), last_utxo_index.to_string())
+            .await?;
+
+        Ok(num_found)
+    }
+
+    /// Mark the unspent watched outputs that the base node no longer has in its UTXO set as spent
+    async fn check_spent_outputs(&self, connection: &mut PeerConnection) -> Result<usize, WalletError> {
+        let unspent = self
+            .wallet
+            .db
+            .get_watched_outputs()
+            .await?
+            .into_iter()
+            .filter(|o| o.spent_at.is_none())
+            .collect::<Vec<_>>();
+        if unspent.is_empty() {
+            return Ok(0);
+        }
+
+        let mut client = connection
+            .connect_rpc_using_builder(BaseNodeWalletRpcClient::builder().with_deadline(Duration::from_secs(60)))
+            .await
+            .map_err(to_watch_only_scan_error)?;
+
+        let mut num_spent = 0;
+        for batch in unspent.chunks(SPENT_QUERY_BATCH_SIZE) {
+            let request = FetchMatchingUtxos {
+                output_hashes: batch.iter().map(|o| o.hash.clone()).collect(),
+            };
+            let response = client
+                .fetch_matching_utxos(request)
+                .await
+                .map_err(to_watch_only_scan_error)?;
+            // A base node that is still syncing could be missing outputs that are not spent
+            if !response.is_synced {
+                return Ok(num_spent);
+            }
+
+            let returned_hashes = response
+                .outputs
+                .into_iter()
+                .map(|o| TransactionOutput::try_from(o).map(|o| o.hash()))
+                .collect::<Result<HashSet<_>, _>>()
+                .map_err(to_watch_only_scan_error)?;
+            for output in batch.iter().filter(|o| !returned_hashes.contains(&o.hash)) {
+                let mut output = output.clone();
+                output.spent_at = Some(Utc::now().naive_utc());
+                self.wallet.db.set_watched_output(output).await?;
+                num_spent += 1;
+            }
+        }
+
+        Ok(num_spent)
+    }
+}
+
+/// The outputs whose range proofs can be rewound with the view key, with their values
+fn find_owned_outputs<'a>(
+    outputs: &'a [TransactionOutput],
+    keys: &PublicRewindKeys,
+    range_proof: &RangeProofService,
+) -> Vec<(&'a TransactionOutput, MicroTari)>
+{
+    outputs
+        .iter()
+        .filter_map(|output| {
+            output
+                .rewind_range_proof_value_only(range_proof, &keys.rewind_public_key, &keys.rewind_blinding_public_key)
+                .or_else(|e| match &keys.legacy_rewind_public_key {
+                    // Both rewind keys were the same key before keys were derived per branch
+                    Some(legacy_key) => output.rewind_range_proof_value_only(range_proof, legacy_key, legacy_key),
+                    None => Err(e),
+                })
+                .ok()
+                .map(|result| (output, result.committed_value))
+        })
+        .collect()
+}
+
+fn to_watch_only_scan_error<T: ToString>(err: T) -> WalletError {
+    WalletError::WatchOnlyScanError(err.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::OsRng;
+    use std::str::FromStr;
+    use tari_core::transactions::{
+        transaction::UnblindedOutput,
+        transaction_protocol::RewindData,
+        types::{CryptoFactories, PrivateKey, PublicKey},
+    };
+    use tari_crypto::{
+        keys::{PublicKey as PublicKeyTrait, SecretKey},
+        range_proof::REWIND_USER_MESSAGE_LENGTH,
+    };
+
+    fn random_rewind_data() -> RewindData {
+        RewindData {
+            rewind_key: PrivateKey::random(&mut OsRng),
+            rewind_blinding_key: PrivateKey::random(&mut OsRng),
+            proof_message: [0u8; REWIND_USER_MESSAGE_LENGTH],
+        }
+    }
+
+    #[test]
+    fn only_outputs_that_rewind_with_the_view_key_are_found() {
+        let factories = CryptoFactories::new(32);
+        let rewind_data = random_rewind_data();
+        let keys = PublicRewindKeys {
+            rewind_public_key: PublicKey::from_secret_key(&rewind_data.rewind_key),
+            rewind_blinding_public_key: PublicKey::from_secret_key(&rewind_data.rewind_blinding_key),
+            legacy_rewind_public_key: None,
+        };
+
+        let owned = UnblindedOutput::new(MicroTari::from(1234), PrivateKey::random(&mut OsRng), None)
+            .as_rewindable_transaction_output(&factories, &rewind_data)
+            .unwrap();
+        let other = UnblindedOutput::new(MicroTari::from(5678), PrivateKey::random(&mut OsRng), None)
+            .as_transaction_output(&factories)
+            .unwrap();
+
+        let outputs = [owned.clone(), other];
+        let found = find_owned_outputs(&outputs, &keys, &factories.range_proof);
+        assert_eq!(found, vec![(&owned, MicroTari::from(1234))]);
+    }
+
+    #[test]
+    fn outputs_that_rewind_with_the_legacy_key_are_found() {
+        let factories = CryptoFactories::new(32);
+        let rewind_data = random_rewind_data();
+        let legacy_key = PrivateKey::random(&mut OsRng);
+        let legacy_rewind_data = RewindData {
+            rewind_key: legacy_key.clone(),
+            rewind_blinding_key: legacy_key.clone(),
+            proof_message: [0u8; REWIND_USER_MESSAGE_LENGTH],
+        };
+        let mut keys = PublicRewindKeys {
+            rewind_public_key: PublicKey::from_secret_key(&rewind_data.rewind_key),
+            rewind_blinding_public_key: PublicKey::from_secret_key(&rewind_data.rewind_blinding_key),
+            legacy_rewind_public_key: None,
+        };
+
+        let owned = UnblindedOutput::new(MicroTari::from(1234), PrivateKey::random(&mut OsRng), None)
+            .as_rewindable_transaction_output(&factories, &rewind_data)
+            .unwrap();
+        let legacy = UnblindedOutput::new(MicroTari::from(5678), PrivateKey::random(&mut OsRng), None)
+            .as_rewindable_transaction_output(&factories, &legacy_rewind_data)
+            .unwrap();
+        let outputs = [owned.clone(), legacy.clone()];
+
+        let found = find_owned_outputs(&outputs, &keys, &factories.range_proof);
+        assert_eq!(found, vec![(&owned, MicroTari::from(1234))]);
+
+        keys.legacy_rewind_public_key = Some(PublicKey::from_secret_key(&legacy_key));
+        let found = find_owned_outputs(&outputs, &keys, &factories.range_proof);
+        assert_eq!(found, vec![
+            (&owned, MicroTari::from(1234)),
+            (&legacy, MicroTari::from(5678))
+        ]);
+    }
+
+    #[test]
+    fn view_keys_with_and_without_the_legacy_key_round_trip() {
+        let mut keys = PublicRewindKeys {
+            rewind_public_key: PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+            rewind_blinding_public_key: PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+            legacy_rewind_public_key: None,
+        };
+        assert_eq!(keys.to_string().len(), 128);
+        assert_eq!(PublicRewindKeys::from_str(&keys.to_string()).unwrap(), keys);
+
+        keys.legacy_rewind_public_key = Some(PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)));
+        assert_eq!(keys.to_string().len(), 192);
+        assert_eq!(PublicRewindKeys::from_str(&keys.to_string()).unwrap(), keys);
+
+        assert!(PublicRewindKeys::from_str(&keys.to_string()[..160]).is_err());
+    }
+}
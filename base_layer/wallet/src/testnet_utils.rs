@@ -160,6 +160,11 @@ pub async fn create_wallet(
         dns_seeds_name_server: "1.1.1.1:53".parse().unwrap(),
         dns_seeds_use_dnssec: false,
         peer_seeds: Default::default(),
+        outbound_only: false,
+        auto_update_public_address: false,
+        allow_unsigned_identities: true,
+        noise_rekey_interval: None,
+        noise_rekey_max_bytes: None,
     };
 
     let config = WalletConfig::new(comms_config, factories, None, None, Network::Stibbons, None, None, None);
@@ -32,12 +32,21 @@ pub struct TransactionServiceConfig {
     pub direct_send_timeout: Duration,
     pub broadcast_send_timeout: Duration,
     pub low_power_polling_timeout: Duration,
+    /// How long a pending transaction message waits for a reply before it is resent to its recipient
     pub transaction_resend_period: Duration,
+    /// The number of times a pending transaction message is resent, or None to resend it until the transaction is
+    /// cancelled. Resends requested through the transaction service are always sent.
+    pub transaction_resend_limit: Option<u32>,
     pub resend_response_cooldown: Duration,
     pub pending_transaction_cancellation_timeout: Duration,
     pub num_confirmations_required: u64,
     pub max_tx_query_batch_size: usize,
     pub transaction_routing_mechanism: TransactionRoutingMechanism,
+    /// The factor by which the delay before a transaction broadcast retries grows with every consecutive failure to
+    /// reach the base node. A factor of 1 retries after the broadcast monitoring interval every time.
+    pub broadcast_retry_backoff_factor: f32,
+    /// The longest delay before a transaction broadcast retries
+    pub broadcast_retry_max_delay: Duration,
 }
 
 impl Default for TransactionServiceConfig {
@@ -49,15 +58,35 @@ impl Default for TransactionServiceConfig {
             broadcast_send_timeout: Duration::from_secs(60),
             low_power_polling_timeout: Duration::from_secs(300),
             transaction_resend_period: Duration::from_secs(3600),
+            transaction_resend_limit: None,
             resend_response_cooldown: Duration::from_secs(300),
             pending_transaction_cancellation_timeout: Duration::from_secs(259200), // 3 Days
             num_confirmations_required: 3,
             max_tx_query_batch_size: 5000,
             transaction_routing_mechanism: TransactionRoutingMechanism::default(),
+            broadcast_retry_backoff_factor: 1.0,
+            broadcast_retry_max_delay: Duration::from_secs(600),
         }
     }
 }
 
+impl TransactionServiceConfig {
+    /// Whether a pending transaction message that has been sent `send_count` times can be resent
+    pub fn can_resend(&self, send_count: u32) -> bool {
+        self.transaction_resend_limit.map_or(true, |limit| send_count <= limit)
+    }
+
+    /// The delay before a transaction broadcast retries after `num_failures` consecutive failures to reach the base
+    /// node, starting from the broadcast monitoring interval `base_delay`
+    pub fn broadcast_retry_delay(&self, base_delay: Duration, num_failures: u32) -> Duration {
+        if num_failures == 0 || self.broadcast_retry_backoff_factor <= 1.0 {
+            return base_delay;
+        }
+        let delay = base_delay.as_secs_f64() * f64::from(self.broadcast_retry_backoff_factor).powi(num_failures as i32);
+        Duration::from_secs_f64(delay.min(self.broadcast_retry_max_delay.as_secs_f64())).max(base_delay)
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum TransactionRoutingMechanism {
     DirectOnly,
@@ -98,3 +127,40 @@ impl Default for TransactionRoutingMechanism {
         Self::DirectAndStoreAndForward
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn resend_limit() {
+        let mut config = TransactionServiceConfig::default();
+        assert!(config.can_resend(1000));
+
+        config.transaction_resend_limit = Some(2);
+        assert!(config.can_resend(1));
+        assert!(config.can_resend(2));
+        assert!(!config.can_resend(3));
+
+        config.transaction_resend_limit = Some(0);
+        assert!(!config.can_resend(1));
+    }
+
+    #[test]
+    fn broadcast_retry_backoff() {
+        let base = Duration::from_secs(30);
+        let mut config = TransactionServiceConfig::default();
+        assert_eq!(config.broadcast_retry_delay(base, 5), base);
+
+        config.broadcast_retry_backoff_factor = 2.0;
+        config.broadcast_retry_max_delay = Duration::from_secs(200);
+        assert_eq!(config.broadcast_retry_delay(base, 0), base);
+        assert_eq!(config.broadcast_retry_delay(base, 1), Duration::from_secs(60));
+        assert_eq!(config.broadcast_retry_delay(base, 2), Duration::from_secs(120));
+        assert_eq!(config.broadcast_retry_delay(base, 3), Duration::from_secs(200));
+        assert_eq!(config.broadcast_retry_delay(base, 1000), Duration::from_secs(200));
+
+        config.broadcast_retry_max_delay = Duration::from_secs(10);
+        assert_eq!(config.broadcast_retry_delay(base, 3), base);
+    }
+}
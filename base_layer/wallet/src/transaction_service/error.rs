@@ -29,7 +29,14 @@ use futures::channel::oneshot::Canceled;
 use serde_json::Error as SerdeJsonError;
 use tari_comms::{peer_manager::node_id::NodeIdError, protocol::rpc::RpcError};
 use tari_comms_dht::outbound::DhtOutboundError;
-use tari_core::transactions::{transaction::TransactionError, transaction_protocol::TransactionProtocolError};
+use tari_core::transactions::{
+    htlc::HtlcError,
+    multisig::MultisigError,
+    one_sided::OneSidedPaymentError,
+    script::ScriptError,
+    transaction::TransactionError,
+    transaction_protocol::TransactionProtocolError,
+};
 use tari_p2p::services::liveness::error::LivenessError;
 use tari_service_framework::reply_channel::TransportChannelError;
 use thiserror::Error;
@@ -76,6 +83,8 @@ pub enum TransactionServiceError {
     DiscoveryProcessFailed(TxId),
     #[error("Invalid Completed Transaction provided")]
     InvalidCompletedTransaction,
+    #[error("Only pending outbound transactions and completed transactions that are not mined can be retried")]
+    TransactionCannotBeRetried,
     #[error("No Base Node public keys are provided for Base chain broadcast and monitoring")]
     NoBaseNodeKeysProvided,
     #[error("Error sending data to Protocol via register channels")]
@@ -98,6 +107,32 @@ pub enum TransactionServiceError {
     TransportChannelError(#[from] TransportChannelError),
     #[error("Transaction storage error: `{0}`")]
     TransactionStorageError(#[from] TransactionStorageError),
+    #[error("A batch send requires at least one recipient")]
+    EmptyRecipientBatch,
+    #[error("A batch with several recipients cannot pay this wallet itself")]
+    PayToSelfInBatch,
+    #[error("Selected outputs cannot be spent in a transaction to this wallet itself")]
+    SelectedOutputsForPayToSelf,
+    #[error("A one-sided payment cannot be sent to this wallet itself")]
+    OneSidedPaymentToSelf,
+    #[error("No unspent one-sided payment with commitment `{0}` can be claimed by this wallet")]
+    OneSidedPaymentNotFound(String),
+    #[error("One-sided payment error: `{0}`")]
+    OneSidedPaymentError(#[from] OneSidedPaymentError),
+    #[error("Script error: `{0}`")]
+    ScriptError(#[from] ScriptError),
+    #[error("No unspent hash time-locked output with commitment `{0}` can be spent by this wallet")]
+    HtlcNotFound(String),
+    #[error("Hash time-locked contract error: `{0}`")]
+    HtlcError(#[from] HtlcError),
+    #[error("No unspent multisig output with commitment `{0}` can be spent by this wallet")]
+    MultisigOutputNotFound(String),
+    #[error("Multisig error: `{0}`")]
+    MultisigError(#[from] MultisigError),
+    #[error("Multisig session `{0}` does not exist or has ended")]
+    MultisigSessionNotFound(u64),
+    #[error("Multisig session `{0}` received an unexpected message: `{1}`")]
+    InvalidMultisigMessage(u64, String),
     #[error("Invalid message error: `{0}`")]
     InvalidMessageError(String),
     #[cfg(feature = "test_harness")]
@@ -21,17 +21,23 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::{
-    output_manager_service::TxId,
+    output_manager_service::{service::FeeEstimate, utxo_selection::UtxoSelectionPolicy, TxId},
     transaction_service::{
+        config::TransactionRoutingMechanism,
         error::TransactionServiceError,
         storage::models::{CompletedTransaction, InboundTransaction, OutboundTransaction, WalletTransaction},
     },
 };
 use aes_gcm::Aes256Gcm;
 use futures::{stream::Fuse, StreamExt};
-use std::{collections::HashMap, fmt, sync::Arc};
+use std::{collections::HashMap, fmt, sync::Arc, time::Duration};
 use tari_comms::types::CommsPublicKey;
-use tari_core::transactions::{tari_amount::MicroTari, transaction::Transaction};
+use tari_core::transactions::{
+    tari_amount::MicroTari,
+    transaction::Transaction,
+    types::{Commitment, PublicKey},
+};
+use tari_crypto::tari_utilities::hex::Hex;
 use tari_service_framework::reply_channel::SenderService;
 use tokio::sync::broadcast;
 use tower::Service;
@@ -53,19 +59,50 @@ pub enum TransactionServiceRequest {
     GetAnyTransaction(TxId),
     SetBaseNodePublicKey(CommsPublicKey),
     SendTransaction((CommsPublicKey, MicroTari, MicroTari, String)),
+    SendTransactionWithInputs((CommsPublicKey, Vec<Commitment>, MicroTari, MicroTari, String)),
+    SendTransactionWithStrategy((CommsPublicKey, UtxoSelectionPolicy, MicroTari, MicroTari, String)),
+    SendTransactionBatch((Vec<(CommsPublicKey, MicroTari)>, MicroTari, String)),
+    BurnTari((MicroTari, MicroTari, String)),
+    SendOneSidedTransaction((CommsPublicKey, MicroTari, MicroTari, String)),
+    SubmitOneSidedTransaction((TxId, CommsPublicKey, Box<Transaction>, MicroTari, String)),
+    ClaimOneSidedPayment((Commitment, MicroTari, String)),
+    LockHtlc((CommsPublicKey, PublicKey, MicroTari, [u8; 32], u64, MicroTari, String)),
+    ClaimHtlc((Commitment, Vec<u8>, MicroTari, String)),
+    RefundHtlc((Commitment, MicroTari, String)),
+    FundMultisig((MicroTari, u8, Vec<CommsPublicKey>, MicroTari, String)),
+    SpendMultisig(
+        (
+            Commitment,
+            MicroTari,
+            CommsPublicKey,
+            Vec<CommsPublicKey>,
+            MicroTari,
+            String,
+        ),
+    ),
+    CosignMultisig((Commitment, MicroTari, CommsPublicKey)),
+    CancelMultisigSession(u64),
     CancelTransaction(TxId),
+    RetryTransaction(TxId),
     ImportUtxo(MicroTari, CommsPublicKey, String),
     SubmitTransaction((TxId, Transaction, MicroTari, MicroTari, String)),
     SetLowPowerMode,
     SetNormalPowerMode,
     ApplyEncryption(Box<Aes256Gcm>),
     RemoveEncryption,
-    GenerateCoinbaseTransaction(MicroTari, MicroTari, u64),
+    GenerateCoinbaseTransaction(MicroTari, MicroTari, u64, Vec<u8>),
     RestartTransactionProtocols,
     RestartBroadcastProtocols,
     GetNumConfirmationsRequired,
     SetNumConfirmationsRequired(u64),
+    SetTransactionResendPolicy(Duration, Option<u32>),
+    SetBroadcastRetryBackoff(f32, Duration),
+    SetTransactionRoutingMechanism(TransactionRoutingMechanism),
     ValidateTransactions(ValidationRetryStrategy),
+    GetTransactionLabels(TxId),
+    GetAllTransactionLabels,
+    SetTransactionLabels(TxId, Vec<String>),
+    EstimateFee(MicroTari, usize, Option<MicroTari>),
     #[cfg(feature = "test_harness")]
     CompletePendingOutboundTransaction(CompletedTransaction),
     #[cfg(feature = "test_harness")]
@@ -92,20 +129,87 @@ impl fmt::Display for TransactionServiceRequest {
             Self::SendTransaction((k, v, _, msg)) => {
                 f.write_str(&format!("SendTransaction (to {}, {}, {})", k, v, msg))
             },
+            Self::SendTransactionWithInputs((k, inputs, v, _, msg)) => f.write_str(&format!(
+                "SendTransactionWithInputs (to {}, {} inputs, {}, {})",
+                k,
+                inputs.len(),
+                v,
+                msg
+            )),
+            Self::SendTransactionWithStrategy((k, policy, v, _, msg)) => f.write_str(&format!(
+                "SendTransactionWithStrategy (to {}, {}, {}, {})",
+                k, policy, v, msg
+            )),
+            Self::SendTransactionBatch((recipients, _, msg)) => f.write_str(&format!(
+                "SendTransactionBatch (to {} recipient(s), {})",
+                recipients.len(),
+                msg
+            )),
+            Self::BurnTari((v, _, msg)) => f.write_str(&format!("BurnTari ({}, {})", v, msg)),
+            Self::SendOneSidedTransaction((k, v, _, msg)) => {
+                f.write_str(&format!("SendOneSidedTransaction (to {}, {}, {})", k, v, msg))
+            },
+            Self::SubmitOneSidedTransaction((id, k, _, v, _)) => {
+                f.write_str(&format!("SubmitOneSidedTransaction ({}, to {}, {})", id, k, v))
+            },
+            Self::ClaimOneSidedPayment((commitment, _, msg)) => {
+                f.write_str(&format!("ClaimOneSidedPayment ({}, {})", commitment.to_hex(), msg))
+            },
+            Self::LockHtlc((k, _, v, _, refund_height, _, msg)) => f.write_str(&format!(
+                "LockHtlc (to {}, {}, refund at {}, {})",
+                k, v, refund_height, msg
+            )),
+            Self::ClaimHtlc((commitment, _, _, msg)) => {
+                f.write_str(&format!("ClaimHtlc ({}, {})", commitment.to_hex(), msg))
+            },
+            Self::RefundHtlc((commitment, _, msg)) => {
+                f.write_str(&format!("RefundHtlc ({}, {})", commitment.to_hex(), msg))
+            },
+            Self::FundMultisig((v, threshold, members, _, msg)) => f.write_str(&format!(
+                "FundMultisig ({}, {}-of-{}, {})",
+                v,
+                threshold,
+                members.len() + 1,
+                msg
+            )),
+            Self::SpendMultisig((commitment, v, k, cosigners, _, msg)) => f.write_str(&format!(
+                "SpendMultisig ({}, {} to {}, {} cosigner(s), {})",
+                commitment.to_hex(),
+                v,
+                k,
+                cosigners.len(),
+                msg
+            )),
+            Self::CosignMultisig((commitment, v, k)) => {
+                f.write_str(&format!("CosignMultisig ({}, {} to {})", commitment.to_hex(), v, k))
+            },
+            Self::CancelMultisigSession(id) => f.write_str(&format!("CancelMultisigSession ({})", id)),
             Self::CancelTransaction(t) => f.write_str(&format!("CancelTransaction ({})", t)),
+            Self::RetryTransaction(t) => f.write_str(&format!("RetryTransaction ({})", t)),
             Self::ImportUtxo(v, k, msg) => f.write_str(&format!("ImportUtxo (from {}, {}, {})", k, v, msg)),
             Self::SubmitTransaction((id, _, _, _, _)) => f.write_str(&format!("SubmitTransaction ({})", id)),
             Self::SetLowPowerMode => f.write_str("SetLowPowerMode "),
             Self::SetNormalPowerMode => f.write_str("SetNormalPowerMode"),
             Self::ApplyEncryption(_) => f.write_str("ApplyEncryption"),
             Self::RemoveEncryption => f.write_str("RemoveEncryption"),
-            Self::GenerateCoinbaseTransaction(_, _, bh) => {
+            Self::GenerateCoinbaseTransaction(_, _, bh, _) => {
                 f.write_str(&format!("GenerateCoinbaseTransaction (Blockheight {})", bh))
             },
             Self::RestartTransactionProtocols => f.write_str("RestartTransactionProtocols"),
             Self::RestartBroadcastProtocols => f.write_str("RestartBroadcastProtocols"),
             Self::GetNumConfirmationsRequired => f.write_str("GetNumConfirmationsRequired"),
             Self::SetNumConfirmationsRequired(_) => f.write_str("SetNumConfirmationsRequired"),
+            Self::SetTransactionResendPolicy(period, limit) => f.write_str(&format!(
+                "SetTransactionResendPolicy({:?}, {})",
+                period,
+                limit.map_or_else(|| "unlimited".to_string(), |l| l.to_string())
+            )),
+            Self::SetBroadcastRetryBackoff(factor, max_delay) => {
+                f.write_str(&format!("SetBroadcastRetryBackoff({}, {:?})", factor, max_delay))
+            },
+            Self::SetTransactionRoutingMechanism(mechanism) => {
+                f.write_str(&format!("SetTransactionRoutingMechanism({})", mechanism))
+            },
             #[cfg(feature = "test_harness")]
             Self::CompletePendingOutboundTransaction(tx) => {
                 f.write_str(&format!("CompletePendingOutboundTransaction ({})", tx.tx_id))
@@ -122,6 +226,14 @@ impl fmt::Display for TransactionServiceRequest {
             Self::BroadcastTransaction(id) => f.write_str(&format!("BroadcastTransaction ({})", id)),
             Self::GetAnyTransaction(t) => f.write_str(&format!("GetAnyTransaction({})", t)),
             TransactionServiceRequest::ValidateTransactions(t) => f.write_str(&format!("ValidateTransaction({:?})", t)),
+            Self::GetTransactionLabels(t) => f.write_str(&format!("GetTransactionLabels({})", t)),
+            Self::GetAllTransactionLabels => f.write_str("GetAllTransactionLabels"),
+            Self::SetTransactionLabels(t, labels) => {
+                f.write_str(&format!("SetTransactionLabels({}, {})", t, labels.join(", ")))
+            },
+            Self::EstimateFee(amount, num_outputs, _) => {
+                f.write_str(&format!("EstimateFee({}, {} outputs)", amount, num_outputs))
+            },
         }
     }
 }
@@ -130,7 +242,9 @@ impl fmt::Display for TransactionServiceRequest {
 #[derive(Debug)]
 pub enum TransactionServiceResponse {
     TransactionSent(TxId),
+    TransactionsSent(Vec<TxId>),
     TransactionCancelled,
+    TransactionRetried(TransactionRetry),
     PendingInboundTransactions(HashMap<u64, InboundTransaction>),
     PendingOutboundTransactions(HashMap<u64, OutboundTransaction>),
     CompletedTransactions(HashMap<u64, CompletedTransaction>),
@@ -147,7 +261,17 @@ pub enum TransactionServiceResponse {
     AnyTransaction(Box<Option<WalletTransaction>>),
     NumConfirmationsRequired(u64),
     NumConfirmationsSet,
+    TransactionResendPolicySet,
+    BroadcastRetryBackoffSet,
+    TransactionRoutingMechanismSet,
     ValidationStarted(u64),
+    TransactionLabels(Vec<String>),
+    AllTransactionLabels(HashMap<TxId, Vec<String>>),
+    TransactionLabelsSet,
+    FeeEstimate(FeeEstimate),
+    MultisigSessionStarted(u64),
+    MultisigCosignApproved,
+    MultisigSessionCancelled,
     #[cfg(feature = "test_harness")]
     CompletedPendingTransaction,
     #[cfg(feature = "test_harness")]
@@ -160,6 +284,17 @@ pub enum TransactionServiceResponse {
     TransactionBroadcast,
 }
 
+/// What was done to retry a transaction that is stuck
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransactionRetry {
+    /// The pending outbound transaction was sent to its recipient again
+    Resent,
+    /// A broadcast of the completed transaction to the base node was started
+    BroadcastStarted,
+    /// The completed transaction is already being broadcast to the base node, which resubmits it until it is mined
+    BroadcastInProgress,
+}
+
 /// Events that can be published on the Text Message Service Event Stream
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub enum TransactionEvent {
@@ -182,6 +317,14 @@ pub enum TransactionEvent {
     TransactionValidationAborted(u64),
     TransactionValidationDelayed(u64),
     TransactionBaseNodeConnectionProblem(u64),
+    /// A multisig funding or spending session started by this wallet has produced its transaction
+    MultisigSessionCompleted(u64, TxId),
+    /// A multisig session started by this wallet has failed, the reason is given
+    MultisigSessionFailed(u64, String),
+    /// Another participant has asked this wallet to co-sign the spend of a multisig output
+    MultisigSignRequestReceived(u64),
+    /// This wallet has sent its partial signature for the spend of a multisig output
+    MultisigPartialSignatureSent(u64),
     Error(String),
 }
 
@@ -234,6 +377,349 @@ impl TransactionServiceHandle {
         }
     }
 
+    /// Send a transaction that spends exactly the unspent outputs with the `inputs` commitments, instead of letting the
+    /// output manager select the outputs
+    pub async fn send_transaction_with_inputs(
+        &mut self,
+        dest_pubkey: CommsPublicKey,
+        inputs: Vec<Commitment>,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<TxId, TransactionServiceError>
+    {
+        match self
+            .handle
+            .call(TransactionServiceRequest::SendTransactionWithInputs((
+                dest_pubkey,
+                inputs,
+                amount,
+                fee_per_gram,
+                message,
+            )))
+            .await??
+        {
+            TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Send a transaction whose inputs are chosen by the output manager with the given UTXO selection strategy instead
+    /// of the configured one
+    pub async fn send_transaction_with_strategy(
+        &mut self,
+        dest_pubkey: CommsPublicKey,
+        policy: UtxoSelectionPolicy,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<TxId, TransactionServiceError>
+    {
+        match self
+            .handle
+            .call(TransactionServiceRequest::SendTransactionWithStrategy((
+                dest_pubkey,
+                policy,
+                amount,
+                fee_per_gram,
+                message,
+            )))
+            .await??
+        {
+            TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Send `message` and the paired amount to each of the `recipients` in one transaction with a kernel for each
+    /// recipient. The TxIds of the recipients are returned in the same order, the first one being the TxId of the
+    /// transaction as a whole.
+    pub async fn send_transaction_batch(
+        &mut self,
+        recipients: Vec<(CommsPublicKey, MicroTari)>,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<Vec<TxId>, TransactionServiceError>
+    {
+        match self
+            .handle
+            .call(TransactionServiceRequest::SendTransactionBatch((
+                recipients,
+                fee_per_gram,
+                message,
+            )))
+            .await??
+        {
+            TransactionServiceResponse::TransactionsSent(tx_ids) => Ok(tx_ids),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Permanently destroy `amount` of this wallet's funds with a provable burn transaction
+    pub async fn burn_tari(
+        &mut self,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<TxId, TransactionServiceError>
+    {
+        match self
+            .handle
+            .call(TransactionServiceRequest::BurnTari((amount, fee_per_gram, message)))
+            .await??
+        {
+            TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Pay `amount` to `dest_pubkey` with a transaction that is built and broadcast without waiting for the
+    /// recipient's wallet to reply. The recipient learns how to spend the output when it next receives its messages.
+    pub async fn send_one_sided_transaction(
+        &mut self,
+        dest_pubkey: CommsPublicKey,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<TxId, TransactionServiceError>
+    {
+        match self
+            .handle
+            .call(TransactionServiceRequest::SendOneSidedTransaction((
+                dest_pubkey,
+                amount,
+                fee_per_gram,
+                message,
+            )))
+            .await??
+        {
+            TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Broadcast a one-sided transaction paying `amount` to `dest_pubkey` that was signed elsewhere, e.g. by an offline
+    /// wallet, and send it to the recipient like `send_one_sided_transaction` does
+    pub async fn submit_one_sided_transaction(
+        &mut self,
+        tx_id: TxId,
+        dest_pubkey: CommsPublicKey,
+        transaction: Transaction,
+        amount: MicroTari,
+        message: String,
+    ) -> Result<TxId, TransactionServiceError>
+    {
+        match self
+            .handle
+            .call(TransactionServiceRequest::SubmitOneSidedTransaction((
+                tx_id,
+                dest_pubkey,
+                Box::new(transaction),
+                amount,
+                message,
+            )))
+            .await??
+        {
+            TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Claim the one-sided payment output with the given commitment, which is locked to a one-time key of this wallet,
+    /// by spending it to an output of this wallet
+    pub async fn claim_one_sided_payment(
+        &mut self,
+        commitment: Commitment,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<TxId, TransactionServiceError>
+    {
+        match self
+            .handle
+            .call(TransactionServiceRequest::ClaimOneSidedPayment((
+                commitment,
+                fee_per_gram,
+                message,
+            )))
+            .await??
+        {
+            TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Lock `amount` in a hash time-locked output for an atomic swap. `dest_pubkey` can claim the output by revealing
+    /// the preimage of `hash_lock` and signing with `claim_public_key`, its HTLC public key, otherwise this wallet can
+    /// refund it from `refund_height` onwards. The output is sent to `dest_pubkey` like a one-sided payment.
+    pub async fn lock_htlc(
+        &mut self,
+        dest_pubkey: CommsPublicKey,
+        claim_public_key: PublicKey,
+        amount: MicroTari,
+        hash_lock: [u8; 32],
+        refund_height: u64,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<TxId, TransactionServiceError>
+    {
+        match self
+            .handle
+            .call(TransactionServiceRequest::LockHtlc((
+                dest_pubkey,
+                claim_public_key,
+                amount,
+                hash_lock,
+                refund_height,
+                fee_per_gram,
+                message,
+            )))
+            .await??
+        {
+            TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Claim the hash time-locked output with the given commitment by revealing the preimage of its hash lock
+    pub async fn claim_htlc(
+        &mut self,
+        commitment: Commitment,
+        preimage: Vec<u8>,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<TxId, TransactionServiceError>
+    {
+        match self
+            .handle
+            .call(TransactionServiceRequest::ClaimHtlc((
+                commitment,
+                preimage,
+                fee_per_gram,
+                message,
+            )))
+            .await??
+        {
+            TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Refund the hash time-locked output with the given commitment, which was locked by this wallet. The transaction
+    /// is only accepted by the base node once the refund height has been reached.
+    pub async fn refund_htlc(
+        &mut self,
+        commitment: Commitment,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<TxId, TransactionServiceError>
+    {
+        match self
+            .handle
+            .call(TransactionServiceRequest::RefundHtlc((
+                commitment,
+                fee_per_gram,
+                message,
+            )))
+            .await??
+        {
+            TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Lock `amount` in an m-of-n multisig output shared by this wallet and the `members`, of which `threshold` have
+    /// to sign to spend it. The multisig public keys of the members are requested over the network first, so this only
+    /// starts the session: `MultisigSessionCompleted` or `MultisigSessionFailed` is published once it ends.
+    pub async fn fund_multisig(
+        &mut self,
+        amount: MicroTari,
+        threshold: u8,
+        members: Vec<CommsPublicKey>,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<u64, TransactionServiceError>
+    {
+        match self
+            .handle
+            .call(TransactionServiceRequest::FundMultisig((
+                amount,
+                threshold,
+                members,
+                fee_per_gram,
+                message,
+            )))
+            .await??
+        {
+            TransactionServiceResponse::MultisigSessionStarted(session_id) => Ok(session_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Pay `amount` from the multisig output with the given commitment to `dest_pubkey`, with the `cosigners` signing
+    /// along with this wallet. Any change is locked back to the same participants. Like `fund_multisig`, this only
+    /// starts the signing session.
+    pub async fn spend_multisig(
+        &mut self,
+        commitment: Commitment,
+        amount: MicroTari,
+        dest_pubkey: CommsPublicKey,
+        cosigners: Vec<CommsPublicKey>,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<u64, TransactionServiceError>
+    {
+        match self
+            .handle
+            .call(TransactionServiceRequest::SpendMultisig((
+                commitment,
+                amount,
+                dest_pubkey,
+                cosigners,
+                fee_per_gram,
+                message,
+            )))
+            .await??
+        {
+            TransactionServiceResponse::MultisigSessionStarted(session_id) => Ok(session_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Approve co-signing a payment of `amount` to `dest_pubkey` from the multisig output with the given commitment.
+    /// The approval is used by the first matching sign request, whether it has already been received or not.
+    pub async fn cosign_multisig(
+        &mut self,
+        commitment: Commitment,
+        amount: MicroTari,
+        dest_pubkey: CommsPublicKey,
+    ) -> Result<(), TransactionServiceError>
+    {
+        match self
+            .handle
+            .call(TransactionServiceRequest::CosignMultisig((
+                commitment,
+                amount,
+                dest_pubkey,
+            )))
+            .await??
+        {
+            TransactionServiceResponse::MultisigCosignApproved => Ok(()),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Abandon a multisig session started by this wallet, releasing the outputs of a spend that is still being signed
+    pub async fn cancel_multisig_session(&mut self, session_id: u64) -> Result<(), TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::CancelMultisigSession(session_id))
+            .await??
+        {
+            TransactionServiceResponse::MultisigSessionCancelled => Ok(()),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn cancel_transaction(&mut self, tx_id: TxId) -> Result<(), TransactionServiceError> {
         match self
             .handle
@@ -245,6 +731,19 @@ impl TransactionServiceHandle {
         }
     }
 
+    /// Retry a transaction that is stuck: a pending outbound transaction is sent to its recipient again, and a
+    /// completed transaction that is not mined yet is broadcast to the base node if it is not being broadcast already
+    pub async fn retry_transaction(&mut self, tx_id: TxId) -> Result<TransactionRetry, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::RetryTransaction(tx_id))
+            .await??
+        {
+            TransactionServiceResponse::TransactionRetried(retry) => Ok(retry),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn get_pending_inbound_transactions(
         &mut self,
     ) -> Result<HashMap<u64, InboundTransaction>, TransactionServiceError> {
@@ -353,6 +852,71 @@ impl TransactionServiceHandle {
         }
     }
 
+    /// The labels of the transaction, in alphabetical order
+    pub async fn get_transaction_labels(&mut self, tx_id: TxId) -> Result<Vec<String>, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::GetTransactionLabels(tx_id))
+            .await??
+        {
+            TransactionServiceResponse::TransactionLabels(labels) => Ok(labels),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// The labels of all the transactions that have any
+    pub async fn get_all_transaction_labels(&mut self) -> Result<HashMap<TxId, Vec<String>>, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::GetAllTransactionLabels)
+            .await??
+        {
+            TransactionServiceResponse::AllTransactionLabels(labels) => Ok(labels),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Replace the labels of the transaction. The labels are trimmed, and empty and duplicate labels are dropped.
+    pub async fn set_transaction_labels(
+        &mut self,
+        tx_id: TxId,
+        labels: Vec<String>,
+    ) -> Result<(), TransactionServiceError>
+    {
+        match self
+            .handle
+            .call(TransactionServiceRequest::SetTransactionLabels(tx_id, labels))
+            .await??
+        {
+            TransactionServiceResponse::TransactionLabelsSet => Ok(()),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Estimate the fee of sending `amount` split over `num_outputs` outputs, including the inputs that would be
+    /// selected and the change output. If `fee_per_gram` is not given, the fee per gram suggested by the base node
+    /// is used; the suggestion is returned either way.
+    pub async fn estimate_fee(
+        &mut self,
+        amount: MicroTari,
+        num_outputs: usize,
+        fee_per_gram: Option<MicroTari>,
+    ) -> Result<FeeEstimate, TransactionServiceError>
+    {
+        match self
+            .handle
+            .call(TransactionServiceRequest::EstimateFee(
+                amount,
+                num_outputs,
+                fee_per_gram,
+            ))
+            .await??
+        {
+            TransactionServiceResponse::FeeEstimate(estimate) => Ok(estimate),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn set_base_node_public_key(
         &mut self,
         public_key: CommsPublicKey,
@@ -468,12 +1032,77 @@ impl TransactionServiceHandle {
         }
     }
 
+    /// Set how often a pending transaction is resent to its counterparty and how many times it may be resent in
+    /// total. A `limit` of `None` resends until the transaction expires.
+    pub async fn set_transaction_resend_policy(
+        &mut self,
+        period: Duration,
+        limit: Option<u32>,
+    ) -> Result<(), TransactionServiceError>
+    {
+        match self
+            .handle
+            .call(TransactionServiceRequest::SetTransactionResendPolicy(period, limit))
+            .await??
+        {
+            TransactionServiceResponse::TransactionResendPolicySet => Ok(()),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Set the factor the broadcast retry delay grows by after each failed attempt to reach the base node, and the
+    /// delay it is capped at.
+    pub async fn set_broadcast_retry_backoff(
+        &mut self,
+        factor: f32,
+        max_delay: Duration,
+    ) -> Result<(), TransactionServiceError>
+    {
+        match self
+            .handle
+            .call(TransactionServiceRequest::SetBroadcastRetryBackoff(factor, max_delay))
+            .await??
+        {
+            TransactionServiceResponse::BroadcastRetryBackoffSet => Ok(()),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Set whether transaction messages are sent directly, via store-and-forward, or both
+    pub async fn set_transaction_routing_mechanism(
+        &mut self,
+        mechanism: TransactionRoutingMechanism,
+    ) -> Result<(), TransactionServiceError>
+    {
+        match self
+            .handle
+            .call(TransactionServiceRequest::SetTransactionRoutingMechanism(mechanism))
+            .await??
+        {
+            TransactionServiceResponse::TransactionRoutingMechanismSet => Ok(()),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn generate_coinbase_transaction(
         &mut self,
         rewards: MicroTari,
         fees: MicroTari,
         block_height: u64,
     ) -> Result<Transaction, TransactionServiceError>
+    {
+        self.generate_coinbase_transaction_with_extra(rewards, fees, block_height, Vec::new())
+            .await
+    }
+
+    /// Generate a coinbase transaction whose output carries the given extra data, e.g. a mining pool identifier
+    pub async fn generate_coinbase_transaction_with_extra(
+        &mut self,
+        rewards: MicroTari,
+        fees: MicroTari,
+        block_height: u64,
+        extra: Vec<u8>,
+    ) -> Result<Transaction, TransactionServiceError>
     {
         match self
             .handle
@@ -481,6 +1110,7 @@ impl TransactionServiceHandle {
                 rewards,
                 fees,
                 block_height,
+                extra,
             ))
             .await??
         {
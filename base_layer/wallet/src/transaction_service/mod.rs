@@ -23,6 +23,8 @@
 pub mod config;
 pub mod error;
 pub mod handle;
+pub mod multisig;
+pub mod offline_signing;
 pub mod protocols;
 pub mod service;
 pub mod storage;
@@ -158,6 +160,19 @@ where T: TransactionBackend
             .map(map_decode::<proto::TransactionCancelledMessage>)
             .filter_map(ok_or_skip_result)
     }
+
+    fn multisig_stream(&self) -> impl Stream<Item = DomainMessage<proto::MultisigMessage>> {
+        trace!(
+            target: LOG_TARGET,
+            "Subscription '{}' for topic '{:?}' created.",
+            SUBSCRIPTION_LABEL,
+            TariMessageType::Multisig
+        );
+        self.subscription_factory
+            .get_subscription(TariMessageType::Multisig, SUBSCRIPTION_LABEL)
+            .map(map_decode::<proto::MultisigMessage>)
+            .filter_map(ok_or_skip_result)
+    }
 }
 
 impl<T> ServiceInitializer for TransactionServiceInitializer<T>
@@ -172,6 +187,7 @@ where T: TransactionBackend + 'static
         let transaction_finalized_stream = self.transaction_finalized_stream();
         let base_node_response_stream = self.base_node_response_stream();
         let transaction_cancelled_stream = self.transaction_cancelled_stream();
+        let multisig_stream = self.multisig_stream();
 
         let (publisher, _) = broadcast::channel(200);
 
@@ -203,6 +219,7 @@ where T: TransactionBackend + 'static
                 transaction_finalized_stream,
                 base_node_response_stream,
                 transaction_cancelled_stream,
+                multisig_stream,
                 output_manager_service,
                 outbound_message_service,
                 connectivity_manager,
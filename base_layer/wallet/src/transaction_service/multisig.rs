@@ -0,0 +1,826 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! The messages and session state of the protocol that funds and spends m-of-n multisig outputs.
+//!
+//! Funding: the funding wallet sends a key request to every other member of the new lock, collects the multisig
+//! public keys they reply with, and then pays the amount into an output locked to all of them. The output is sent to
+//! every member like a one-sided payment, so that each of them holds it.
+//!
+//! Spending: the spending wallet prepares the spending transaction and sends a sign request describing it to
+//! `threshold - 1` co-signers. The request carries the keys of the outputs and the offset of the transaction, so that
+//! each co-signer checks that it pays the amount to the destination that it approved, and that the kernel excess its
+//! signature is bound to follows from them. Each co-signer that has approved the spend replies with the commitment to a
+//! fresh nonce. Once all the commitments are in, the spending wallet sends them to the co-signers, which reply with
+//! their nonce, and once all the nonces are in, it sends the nonces of all the signers. Each co-signer checks them
+//! against the commitments, replies with its partial signature, and the spending wallet combines them into the witness
+//! of the input and finalizes the transaction.
+
+use crate::{output_manager_service::handle::MultisigSpend, transaction_service::error::TransactionServiceError};
+use std::{
+    collections::HashMap,
+    convert::{TryFrom, TryInto},
+};
+use tari_comms::types::CommsPublicKey;
+use tari_core::transactions::{
+    multisig::{nonce_commitment, MultisigError, MultisigLock, MultisigWitness},
+    one_sided::OneSidedPayment,
+    tari_amount::MicroTari,
+    transaction::UnblindedOutput,
+    transaction_protocol::proto,
+    types::{Commitment, CryptoFactories, PrivateKey, PublicKey, Signature},
+};
+use tari_crypto::{
+    commitment::HomomorphicCommitmentFactory,
+    keys::PublicKey as PublicKeyTrait,
+    tari_utilities::ByteArray,
+};
+
+/// A message of a multisig session, see the module documentation for the order in which they are exchanged
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultisigSessionMessage {
+    pub session_id: u64,
+    pub message: MultisigMessage,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MultisigMessage {
+    KeyRequest,
+    PublicKey(PublicKey),
+    SignRequest(Box<MultisigSignRequest>),
+    NonceCommitment {
+        public_key: PublicKey,
+        commitment: Vec<u8>,
+    },
+    NonceCommitments(Vec<(PublicKey, Vec<u8>)>),
+    Nonce {
+        public_key: PublicKey,
+        public_nonce: PublicKey,
+    },
+    SigningRound {
+        nonces: Vec<(PublicKey, PublicKey)>,
+    },
+    PartialSignature(Signature),
+}
+
+/// The spend of a multisig output that a co-signer is asked to sign
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultisigSignRequest {
+    pub lock: MultisigLock,
+    pub commitment: Commitment,
+    pub kernel_excess: Commitment,
+    pub amount: MicroTari,
+    pub destination: CommsPublicKey,
+    pub fee: MicroTari,
+    pub message: String,
+    /// The ephemeral secret key of the one-sided payment to the destination, from which the output paying it is
+    /// derived
+    pub ephemeral_secret_key: PrivateKey,
+    /// The value and spending key of the change output, if any
+    pub change: Option<(MicroTari, PrivateKey)>,
+    /// The offset of the spending transaction
+    pub offset: PrivateKey,
+}
+
+impl MultisigSignRequest {
+    /// Checks the spend against the multisig output being spent, as held by this wallet: its outputs are the payment of
+    /// `amount` to `destination` and the change, which add up to the value of the output less the fee, and the kernel
+    /// excess is the one of a transaction with exactly this input, these outputs and the offset.
+    pub fn check_spend(&self, input: &UnblindedOutput, factories: &CryptoFactories) -> Result<(), String> {
+        if input.features.multisig.as_ref() != Some(&self.lock) ||
+            factories
+                .commitment
+                .commit_value(&input.spending_key, input.value.into()) !=
+                self.commitment
+        {
+            return Err("The spend is not of the multisig output held by this wallet".to_string());
+        }
+        let change_value = self.change.as_ref().map(|(value, _)| *value).unwrap_or_default();
+        let spent = self
+            .amount
+            .checked_add(change_value)
+            .and_then(|total| total.checked_add(self.fee));
+        if spent != Some(input.value) {
+            return Err("The amount, change and fee of the spend do not add up to the value of the output".to_string());
+        }
+
+        let payment = OneSidedPayment::new(&self.ephemeral_secret_key, &self.destination).map_err(|e| e.to_string())?;
+        let excess = self
+            .change
+            .iter()
+            .fold(payment.spending_key() - &input.spending_key, |excess, (_, key)| {
+                &excess + key
+            });
+        let excess = &excess - &self.offset;
+        if Commitment::from_public_key(&PublicKey::from_secret_key(&excess)) != self.kernel_excess {
+            return Err("The kernel excess does not follow from the outputs of the spend".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+impl TryFrom<proto::MultisigMessage> for MultisigSessionMessage {
+    type Error = String;
+
+    fn try_from(message: proto::MultisigMessage) -> Result<Self, Self::Error> {
+        use proto::multisig_message::Message;
+
+        let public_key = |bytes: &[u8]| PublicKey::from_bytes(bytes).map_err(|err| err.to_string());
+        let inner = match message.message.ok_or_else(|| "Multisig message is empty".to_string())? {
+            Message::KeyRequest(_) => MultisigMessage::KeyRequest,
+            Message::PublicKey(key) => MultisigMessage::PublicKey(public_key(&key)?),
+            Message::SignRequest(request) => MultisigMessage::SignRequest(Box::new(request.try_into()?)),
+            Message::NonceCommitment(commitment) => MultisigMessage::NonceCommitment {
+                public_key: public_key(&commitment.public_key)?,
+                commitment: commitment.commitment,
+            },
+            Message::NonceCommitments(commitments) => MultisigMessage::NonceCommitments(
+                commitments
+                    .commitments
+                    .into_iter()
+                    .map(|c| Ok((public_key(&c.public_key)?, c.commitment)))
+                    .collect::<Result<_, String>>()?,
+            ),
+            Message::Nonce(nonce) => MultisigMessage::Nonce {
+                public_key: public_key(&nonce.public_key)?,
+                public_nonce: public_key(&nonce.public_nonce)?,
+            },
+            Message::SigningRound(round) => MultisigMessage::SigningRound {
+                nonces: round
+                    .nonces
+                    .into_iter()
+                    .map(|n| Ok((public_key(&n.public_key)?, public_key(&n.public_nonce)?)))
+                    .collect::<Result<_, String>>()?,
+            },
+            Message::PartialSignature(signature) => {
+                MultisigMessage::PartialSignature(signature.try_into().map_err(|err| format!("{}", err))?)
+            },
+        };
+
+        Ok(Self {
+            session_id: message.session_id,
+            message: inner,
+        })
+    }
+}
+
+impl From<MultisigSessionMessage> for proto::MultisigMessage {
+    fn from(message: MultisigSessionMessage) -> Self {
+        use proto::multisig_message::Message;
+
+        let inner = match message.message {
+            MultisigMessage::KeyRequest => Message::KeyRequest(proto::MultisigKeyRequest {}),
+            MultisigMessage::PublicKey(key) => Message::PublicKey(key.to_vec()),
+            MultisigMessage::SignRequest(request) => Message::SignRequest((*request).into()),
+            MultisigMessage::NonceCommitment { public_key, commitment } => {
+                Message::NonceCommitment(proto::MultisigNonceCommitment {
+                    public_key: public_key.to_vec(),
+                    commitment,
+                })
+            },
+            MultisigMessage::NonceCommitments(commitments) => {
+                Message::NonceCommitments(proto::MultisigNonceCommitments {
+                    commitments: commitments
+                        .into_iter()
+                        .map(|(public_key, commitment)| proto::MultisigNonceCommitment {
+                            public_key: public_key.to_vec(),
+                            commitment,
+                        })
+                        .collect(),
+                })
+            },
+            MultisigMessage::Nonce {
+                public_key,
+                public_nonce,
+            } => Message::Nonce(proto::MultisigNonce {
+                public_key: public_key.to_vec(),
+                public_nonce: public_nonce.to_vec(),
+            }),
+            MultisigMessage::SigningRound { nonces } => Message::SigningRound(proto::MultisigSigningRound {
+                nonces: nonces
+                    .into_iter()
+                    .map(|(public_key, public_nonce)| proto::MultisigNonce {
+                        public_key: public_key.to_vec(),
+                        public_nonce: public_nonce.to_vec(),
+                    })
+                    .collect(),
+            }),
+            MultisigMessage::PartialSignature(signature) => Message::PartialSignature(signature.into()),
+        };
+
+        Self {
+            session_id: message.session_id,
+            message: Some(inner),
+        }
+    }
+}
+
+impl TryFrom<proto::MultisigSignRequest> for MultisigSignRequest {
+    type Error = String;
+
+    fn try_from(request: proto::MultisigSignRequest) -> Result<Self, Self::Error> {
+        let commitment = |c: Option<_>| -> Result<Commitment, String> {
+            c.ok_or_else(|| "Multisig sign request is missing a commitment".to_string())
+                .and_then(|c| Commitment::try_from(c).map_err(|err| format!("{}", err)))
+        };
+        let private_key = |bytes: &[u8]| PrivateKey::from_bytes(bytes).map_err(|err| err.to_string());
+        let change = if request.change_spending_key.is_empty() {
+            None
+        } else {
+            Some((request.change_value.into(), private_key(&request.change_spending_key)?))
+        };
+
+        Ok(Self {
+            lock: request
+                .lock
+                .ok_or_else(|| "Multisig sign request is missing the lock".to_string())?
+                .try_into()?,
+            commitment: commitment(request.commitment)?,
+            kernel_excess: commitment(request.kernel_excess)?,
+            amount: request.amount.into(),
+            destination: CommsPublicKey::from_bytes(&request.destination).map_err(|err| err.to_string())?,
+            fee: request.fee.into(),
+            message: request.message,
+            ephemeral_secret_key: private_key(&request.ephemeral_secret_key)?,
+            change,
+            offset: private_key(&request.offset)?,
+        })
+    }
+}
+
+impl From<MultisigSignRequest> for proto::MultisigSignRequest {
+    fn from(request: MultisigSignRequest) -> Self {
+        let (change_value, change_spending_key) = match request.change {
+            Some((value, key)) => (value.into(), key.to_vec()),
+            None => (0, Vec::new()),
+        };
+
+        Self {
+            lock: Some(request.lock.into()),
+            commitment: Some(request.commitment.into()),
+            kernel_excess: Some(request.kernel_excess.into()),
+            amount: request.amount.into(),
+            destination: request.destination.to_vec(),
+            fee: request.fee.into(),
+            message: request.message,
+            ephemeral_secret_key: request.ephemeral_secret_key.to_vec(),
+            change_value,
+            change_spending_key,
+            offset: request.offset.to_vec(),
+        }
+    }
+}
+
+/// A multisig lock being funded by this wallet, waiting for the multisig public keys of the other members
+pub struct MultisigFundingSession {
+    pub amount: MicroTari,
+    pub threshold: u8,
+    pub fee_per_gram: MicroTari,
+    pub message: String,
+    /// The multisig public key of this wallet
+    pub public_key: PublicKey,
+    /// The other members and their multisig public keys, once received
+    pub members: HashMap<CommsPublicKey, Option<PublicKey>>,
+}
+
+impl MultisigFundingSession {
+    /// Record the multisig public key of a member. Returns the keys of all the participants, including this wallet,
+    /// once the keys of all members are known.
+    pub fn add_public_key(
+        &mut self,
+        session_id: u64,
+        member: &CommsPublicKey,
+        public_key: PublicKey,
+    ) -> Result<Option<Vec<PublicKey>>, TransactionServiceError>
+    {
+        match self.members.get_mut(member) {
+            Some(key) => *key = Some(public_key),
+            None => {
+                return Err(TransactionServiceError::InvalidMultisigMessage(
+                    session_id,
+                    "Public key received from a wallet that is not a member".to_string(),
+                ))
+            },
+        }
+        if self.members.values().any(Option::is_none) {
+            return Ok(None);
+        }
+
+        let mut public_keys = self.members.values().flatten().cloned().collect::<Vec<_>>();
+        public_keys.push(self.public_key.clone());
+        Ok(Some(public_keys))
+    }
+}
+
+/// A spend of a multisig output started by this wallet, which collects the nonce commitments, the nonces and then the
+/// partial signatures of its co-signers
+pub struct MultisigSpendingSession {
+    pub spend: MultisigSpend,
+    pub request: MultisigSignRequest,
+    pub cosigners: Vec<CommsPublicKey>,
+    /// The multisig public key and public nonce of this wallet
+    pub public_key: PublicKey,
+    pub public_nonce: PublicKey,
+    /// The multisig public keys and nonce commitments of the co-signers that have replied
+    pub nonce_commitments: HashMap<CommsPublicKey, (PublicKey, Vec<u8>)>,
+    /// The public nonces of the co-signers that have revealed them
+    pub nonces: HashMap<CommsPublicKey, PublicKey>,
+    /// The signers and their aggregate nonce, once all the nonces are in
+    pub signing_round: Option<(Vec<u8>, PublicKey)>,
+    pub partial_signatures: HashMap<CommsPublicKey, Signature>,
+}
+
+impl MultisigSpendingSession {
+    /// Record the nonce commitment of a co-signer. Returns true once the commitments of all co-signers are in.
+    pub fn add_nonce_commitment(
+        &mut self,
+        session_id: u64,
+        cosigner: &CommsPublicKey,
+        public_key: PublicKey,
+        commitment: Vec<u8>,
+    ) -> Result<bool, TransactionServiceError>
+    {
+        let invalid = |reason: &str| TransactionServiceError::InvalidMultisigMessage(session_id, reason.to_string());
+        if self.nonce_commitments.len() == self.cosigners.len() || self.nonce_commitments.contains_key(cosigner) {
+            // A repeated message, e.g. received both directly and by Store and Forward
+            return Ok(false);
+        }
+        if !self.cosigners.contains(cosigner) {
+            return Err(invalid(
+                "Nonce commitment received from a wallet that is not a co-signer",
+            ));
+        }
+        if self.request.lock.index_of(&public_key).is_none() ||
+            public_key == self.public_key ||
+            self.nonce_commitments.values().any(|(key, _)| key == &public_key)
+        {
+            return Err(invalid(
+                "Nonce commitment received for a key that is not a distinct participant",
+            ));
+        }
+        self.nonce_commitments
+            .insert(cosigner.clone(), (public_key, commitment));
+
+        Ok(self.nonce_commitments.len() == self.cosigners.len())
+    }
+
+    /// The multisig public keys and nonce commitments of all the signers, including this wallet
+    pub fn all_nonce_commitments(&self) -> Vec<(PublicKey, Vec<u8>)> {
+        self.nonce_commitments
+            .values()
+            .cloned()
+            .chain(std::iter::once((
+                self.public_key.clone(),
+                nonce_commitment(&self.public_key, &self.public_nonce),
+            )))
+            .collect()
+    }
+
+    /// Check the nonce of a co-signer against its commitment and record it. Returns true once the nonces of all
+    /// co-signers are in.
+    pub fn add_nonce(
+        &mut self,
+        session_id: u64,
+        cosigner: &CommsPublicKey,
+        public_key: PublicKey,
+        public_nonce: PublicKey,
+    ) -> Result<bool, TransactionServiceError>
+    {
+        let invalid = |reason: &str| TransactionServiceError::InvalidMultisigMessage(session_id, reason.to_string());
+        if self.signing_round.is_some() || self.nonces.contains_key(cosigner) {
+            return Ok(false);
+        }
+        if self.nonce_commitments.len() != self.cosigners.len() {
+            return Err(invalid("Nonce received before all co-signers committed to their nonce"));
+        }
+        let (committed_key, commitment) = self
+            .nonce_commitments
+            .get(cosigner)
+            .ok_or_else(|| invalid("Nonce received from a wallet that is not a co-signer"))?;
+        if committed_key != &public_key || &nonce_commitment(&public_key, &public_nonce) != commitment {
+            return Err(invalid("Nonce does not match the commitment of the co-signer"));
+        }
+        self.nonces.insert(cosigner.clone(), public_nonce);
+
+        Ok(self.nonces.len() == self.cosigners.len())
+    }
+
+    /// Fix the signers and their aggregate nonce, once all the nonces are in. Returns the multisig public keys and
+    /// public nonces of all the signers, to be sent to the co-signers.
+    pub fn start_signing_round(&mut self) -> Result<Vec<(PublicKey, PublicKey)>, TransactionServiceError> {
+        let mut nonces = self
+            .nonces
+            .iter()
+            .map(|(cosigner, nonce)| {
+                self.nonce_commitments
+                    .get(cosigner)
+                    .map(|(key, _)| (key.clone(), nonce.clone()))
+                    .ok_or(TransactionServiceError::InvalidStateError)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        nonces.push((self.public_key.clone(), self.public_nonce.clone()));
+        self.signing_round = Some(signing_round(&self.request.lock, &nonces)?);
+
+        Ok(nonces)
+    }
+
+    /// Verify and record the partial signature of a co-signer. Returns true once the partial signatures of all
+    /// co-signers are in.
+    pub fn add_partial_signature(
+        &mut self,
+        session_id: u64,
+        cosigner: &CommsPublicKey,
+        signature: Signature,
+    ) -> Result<bool, TransactionServiceError>
+    {
+        let (signers, aggregate_nonce) = self.signing_round.as_ref().ok_or_else(|| {
+            TransactionServiceError::InvalidMultisigMessage(
+                session_id,
+                "Partial signature received before the signing round".to_string(),
+            )
+        })?;
+        if self.partial_signatures.contains_key(cosigner) {
+            return Ok(false);
+        }
+        let (public_key, _) = self.nonce_commitments.get(cosigner).ok_or_else(|| {
+            TransactionServiceError::InvalidMultisigMessage(
+                session_id,
+                "Partial signature received from a wallet that is not a signer".to_string(),
+            )
+        })?;
+        self.request.lock.verify_partial_signature(
+            &signature,
+            public_key,
+            signers,
+            aggregate_nonce,
+            &self.request.commitment,
+            &self.request.kernel_excess,
+        )?;
+        self.partial_signatures.insert(cosigner.clone(), signature);
+
+        Ok(self.partial_signatures.len() == self.cosigners.len())
+    }
+
+    /// Combine the partial signatures of the co-signers and the partial signature of this wallet into the witness of
+    /// the multisig input
+    pub fn witness(&self, own_partial_signature: Signature) -> Result<MultisigWitness, TransactionServiceError> {
+        let (signers, _) = self
+            .signing_round
+            .as_ref()
+            .ok_or(TransactionServiceError::InvalidStateError)?;
+        let mut partial_signatures = self.partial_signatures.values().cloned().collect::<Vec<_>>();
+        partial_signatures.push(own_partial_signature);
+
+        Ok(self.request.lock.aggregate(
+            signers.clone(),
+            &partial_signatures,
+            &self.request.commitment,
+            self.request.kernel_excess.clone(),
+        )?)
+    }
+}
+
+/// A sign request received by this wallet
+pub struct MultisigCosigningSession {
+    /// The wallet that is spending the output
+    pub origin: CommsPublicKey,
+    pub request: MultisigSignRequest,
+    /// The multisig public key and public nonce of this wallet, once it has approved the request and replied with the
+    /// commitment to its nonce
+    pub own_nonce: Option<(PublicKey, PublicKey)>,
+    /// The multisig public keys and nonce commitments of all the signers, once received
+    pub nonce_commitments: Option<Vec<(PublicKey, Vec<u8>)>>,
+}
+
+impl MultisigCosigningSession {
+    /// Whether this wallet has approved the request
+    pub fn is_approved(&self) -> bool {
+        self.own_nonce.is_some()
+    }
+
+    /// Record the nonce commitments of all the signers, which must include the commitment of this wallet. Returns the
+    /// multisig public key and public nonce of this wallet, to be revealed now that all the signers have committed, or
+    /// None for a repeated message.
+    pub fn add_nonce_commitments(
+        &mut self,
+        session_id: u64,
+        commitments: Vec<(PublicKey, Vec<u8>)>,
+    ) -> Result<Option<(PublicKey, PublicKey)>, TransactionServiceError>
+    {
+        let invalid = |reason: &str| TransactionServiceError::InvalidMultisigMessage(session_id, reason.to_string());
+        let (public_key, public_nonce) = self
+            .own_nonce
+            .clone()
+            .ok_or_else(|| invalid("Nonce commitments received for a spend that has not been approved"))?;
+        if self.nonce_commitments.is_some() {
+            return Ok(None);
+        }
+        let lock = &self.request.lock;
+        let mut signers = commitments
+            .iter()
+            .map(|(key, _)| lock.index_of(key))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| invalid("Nonce commitment received for a key that is not a participant"))?;
+        signers.sort_unstable();
+        signers.dedup();
+        if signers.len() != commitments.len() || signers.len() != lock.threshold as usize {
+            return Err(invalid(
+                "Nonce commitments received for the wrong number of distinct signers",
+            ));
+        }
+        if !commitments.contains(&(public_key.clone(), nonce_commitment(&public_key, &public_nonce))) {
+            return Err(invalid(
+                "Nonce commitments received without the commitment of this wallet",
+            ));
+        }
+        self.nonce_commitments = Some(commitments);
+
+        Ok(Some((public_key, public_nonce)))
+    }
+
+    /// Check the nonces of the signing round against the commitments of the signers. Returns the signers and their
+    /// aggregate nonce.
+    pub fn check_signing_round(
+        &self,
+        session_id: u64,
+        nonces: &[(PublicKey, PublicKey)],
+    ) -> Result<(Vec<u8>, PublicKey), TransactionServiceError>
+    {
+        let invalid = |reason: &str| TransactionServiceError::InvalidMultisigMessage(session_id, reason.to_string());
+        let commitments = self
+            .nonce_commitments
+            .as_ref()
+            .ok_or_else(|| invalid("Signing round received before the nonce commitments"))?;
+        let matches_commitment =
+            |(key, nonce): &(PublicKey, PublicKey)| commitments.contains(&(key.clone(), nonce_commitment(key, nonce)));
+        if nonces.len() != commitments.len() || !nonces.iter().all(matches_commitment) {
+            return Err(invalid(
+                "Signing round nonces do not match the commitments of the signers",
+            ));
+        }
+
+        Ok(signing_round(&self.request.lock, nonces)?)
+    }
+}
+
+/// The lock indices of the signers with the given multisig public keys and public nonces, in ascending order, and the
+/// sum of their nonces
+fn signing_round(
+    lock: &MultisigLock,
+    nonces: &[(PublicKey, PublicKey)],
+) -> Result<(Vec<u8>, PublicKey), MultisigError>
+{
+    let mut signers = nonces
+        .iter()
+        .map(|(key, _)| lock.index_of(key).ok_or(MultisigError::NotAParticipant))
+        .collect::<Result<Vec<_>, _>>()?;
+    signers.sort_unstable();
+    let mut public_nonces = nonces.iter().map(|(_, nonce)| nonce);
+    let first = public_nonces.next().ok_or(MultisigError::InvalidSigners)?;
+    let aggregate_nonce = public_nonces.fold(first.clone(), |sum, nonce| &sum + nonce);
+
+    Ok((signers, aggregate_nonce))
+}
+
+/// A spend that the user of this wallet has agreed to co-sign
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultisigApproval {
+    pub commitment: Commitment,
+    pub amount: MicroTari,
+    pub destination: CommsPublicKey,
+}
+
+impl MultisigApproval {
+    pub fn matches(&self, request: &MultisigSignRequest) -> bool {
+        self.commitment == request.commitment &&
+            self.amount == request.amount &&
+            self.destination == request.destination
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::OsRng;
+    use tari_core::transactions::{helpers::create_test_multisig_lock, transaction::OutputFeatures};
+    use tari_crypto::keys::SecretKey;
+
+    fn random_public_key() -> PublicKey {
+        PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng))
+    }
+
+    /// A multisig output of 10,000 uT and a request to pay 1,000 uT from it, with 8,900 uT of change
+    fn test_spend(lock: MultisigLock) -> (UnblindedOutput, MultisigSignRequest) {
+        let input = UnblindedOutput::new(
+            MicroTari::from(10_000),
+            PrivateKey::random(&mut OsRng),
+            Some(OutputFeatures::with_multisig_lock(lock.clone())),
+        );
+        let destination = random_public_key();
+        let ephemeral_secret_key = PrivateKey::random(&mut OsRng);
+        let payment = OneSidedPayment::new(&ephemeral_secret_key, &destination).unwrap();
+        let change_key = PrivateKey::random(&mut OsRng);
+        let offset = PrivateKey::random(&mut OsRng);
+        let excess = &(&(payment.spending_key() + &change_key) - &input.spending_key) - &offset;
+        let factories = CryptoFactories::default();
+        let request = MultisigSignRequest {
+            lock,
+            commitment: factories
+                .commitment
+                .commit_value(&input.spending_key, input.value.into()),
+            kernel_excess: Commitment::from_public_key(&PublicKey::from_secret_key(&excess)),
+            amount: MicroTari::from(1_000),
+            destination,
+            fee: MicroTari::from(100),
+            message: "Payroll".to_string(),
+            ephemeral_secret_key,
+            change: Some((MicroTari::from(8_900), change_key)),
+            offset,
+        };
+        (input, request)
+    }
+
+    #[test]
+    fn messages_round_trip() {
+        let (lock, _) = create_test_multisig_lock();
+        let (_, request) = test_spend(lock);
+        let mut without_change = request.clone();
+        without_change.change = None;
+        let messages = vec![
+            MultisigMessage::KeyRequest,
+            MultisigMessage::PublicKey(random_public_key()),
+            MultisigMessage::SignRequest(Box::new(request)),
+            MultisigMessage::SignRequest(Box::new(without_change)),
+            MultisigMessage::NonceCommitment {
+                public_key: random_public_key(),
+                commitment: vec![1; 32],
+            },
+            MultisigMessage::NonceCommitments(vec![
+                (random_public_key(), vec![1; 32]),
+                (random_public_key(), vec![2; 32]),
+            ]),
+            MultisigMessage::Nonce {
+                public_key: random_public_key(),
+                public_nonce: random_public_key(),
+            },
+            MultisigMessage::SigningRound {
+                nonces: vec![
+                    (random_public_key(), random_public_key()),
+                    (random_public_key(), random_public_key()),
+                ],
+            },
+            MultisigMessage::PartialSignature(Signature::new(random_public_key(), PrivateKey::random(&mut OsRng))),
+        ];
+
+        for message in messages {
+            let message = MultisigSessionMessage {
+                session_id: 42,
+                message,
+            };
+            let decoded = MultisigSessionMessage::try_from(proto::MultisigMessage::from(message.clone())).unwrap();
+            assert_eq!(decoded, message);
+        }
+
+        assert!(MultisigSessionMessage::try_from(proto::MultisigMessage {
+            session_id: 1,
+            message: None,
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn the_funding_session_waits_for_all_members() {
+        let (alice, bob) = (random_public_key(), random_public_key());
+        let mut session = MultisigFundingSession {
+            amount: MicroTari::from(10_000),
+            threshold: 2,
+            fee_per_gram: MicroTari::from(5),
+            message: "Treasury".to_string(),
+            public_key: random_public_key(),
+            members: vec![(alice.clone(), None), (bob.clone(), None)].into_iter().collect(),
+        };
+
+        assert!(session
+            .add_public_key(1, &random_public_key(), random_public_key())
+            .is_err());
+        assert!(session
+            .add_public_key(1, &alice, random_public_key())
+            .unwrap()
+            .is_none());
+        let public_keys = session.add_public_key(1, &bob, random_public_key()).unwrap().unwrap();
+        let lock = MultisigLock::new(session.threshold, public_keys).unwrap();
+        assert_eq!(lock.public_keys.len(), 3);
+        assert!(lock.index_of(&session.public_key).is_some());
+    }
+
+    #[test]
+    fn co_signers_check_the_outputs_of_the_spend() {
+        let (lock, _) = create_test_multisig_lock();
+        let (input, request) = test_spend(lock);
+        let factories = CryptoFactories::default();
+        assert_eq!(request.check_spend(&input, &factories), Ok(()));
+
+        // The payment goes to a different wallet than the one in the request
+        let mut redirected = request.clone();
+        redirected.destination = random_public_key();
+        assert!(redirected.check_spend(&input, &factories).is_err());
+        // ...pays more than the request, which leaves part of the value unaccounted for
+        let mut overpaid = request.clone();
+        overpaid.amount = MicroTari::from(2_000);
+        assert!(overpaid.check_spend(&input, &factories).is_err());
+        // ...or has other outputs than the ones in the request
+        let mut other_outputs = request.clone();
+        other_outputs.change = Some((MicroTari::from(8_900), PrivateKey::random(&mut OsRng)));
+        assert!(other_outputs.check_spend(&input, &factories).is_err());
+        let mut other_kernel = request.clone();
+        other_kernel.kernel_excess = factories.commitment.commit_value(&PrivateKey::random(&mut OsRng), 0);
+        assert!(other_kernel.check_spend(&input, &factories).is_err());
+        // ...and it must spend the output held by this wallet
+        let (other_input, _) = test_spend(request.lock.clone());
+        assert!(request.check_spend(&other_input, &factories).is_err());
+    }
+
+    #[test]
+    fn co_signers_reveal_their_nonce_after_all_signers_committed() {
+        let (lock, secrets) = create_test_multisig_lock();
+        let (_, request) = test_spend(lock.clone());
+        let own_key = lock.public_keys[1].clone();
+        let own_nonce = random_public_key();
+        let spender_nonce = random_public_key();
+        let own_commitment = (own_key.clone(), nonce_commitment(&own_key, &own_nonce));
+        let spender_commitment = (
+            lock.public_keys[0].clone(),
+            nonce_commitment(&lock.public_keys[0], &spender_nonce),
+        );
+        let mut session = MultisigCosigningSession {
+            origin: random_public_key(),
+            request,
+            own_nonce: None,
+            nonce_commitments: None,
+        };
+
+        // Commitments are only accepted once the spend has been approved
+        assert!(session
+            .add_nonce_commitments(1, vec![spender_commitment.clone(), own_commitment.clone()])
+            .is_err());
+        session.own_nonce = Some((own_key.clone(), own_nonce.clone()));
+        assert!(session.is_approved());
+        // ...and must include the commitment of this wallet, for exactly the threshold of signers
+        assert!(session
+            .add_nonce_commitments(1, vec![
+                spender_commitment.clone(),
+                (own_key.clone(), nonce_commitment(&own_key, &random_public_key())),
+            ])
+            .is_err());
+        assert!(session
+            .add_nonce_commitments(1, vec![spender_commitment.clone()])
+            .is_err());
+        assert_eq!(
+            session
+                .add_nonce_commitments(1, vec![spender_commitment.clone(), own_commitment.clone()])
+                .unwrap(),
+            Some((own_key.clone(), own_nonce.clone()))
+        );
+        assert_eq!(
+            session
+                .add_nonce_commitments(1, vec![spender_commitment, own_commitment])
+                .unwrap(),
+            None
+        );
+
+        // The spender cannot change its nonce after seeing the nonce of this wallet
+        let nonces = vec![
+            (lock.public_keys[0].clone(), spender_nonce.clone()),
+            (own_key.clone(), own_nonce.clone()),
+        ];
+        let (signers, aggregate_nonce) = session.check_signing_round(1, &nonces).unwrap();
+        assert_eq!(signers, vec![0, 1]);
+        assert_eq!(aggregate_nonce, &spender_nonce + &own_nonce);
+        let changed = vec![
+            (lock.public_keys[0].clone(), PublicKey::from_secret_key(&secrets[0])),
+            (own_key, own_nonce),
+        ];
+        assert!(session.check_signing_round(1, &changed).is_err());
+        assert!(session.check_signing_round(1, &nonces[..1]).is_err());
+    }
+}
@@ -0,0 +1,134 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! The files exchanged by a watch-only wallet and the offline wallet that holds its spending keys. The watch-only
+//! wallet exports an [UnsignedTransactionBundle] of outputs to spend, the offline wallet signs a one-sided payment
+//! spending them into a [SignedTransactionBundle], and the watch-only wallet broadcasts the signed transaction.
+
+use crate::{output_manager_service::TxId, storage::database::WatchedOutput};
+use serde::{Deserialize, Serialize};
+use tari_comms::types::CommsPublicKey;
+use tari_core::transactions::{
+    fee::Fee,
+    tari_amount::MicroTari,
+    transaction::{Transaction, TransactionOutput},
+};
+
+/// A one-sided payment that a watch-only wallet prepared for the offline wallet to sign
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnsignedTransactionBundle {
+    pub recipient: CommsPublicKey,
+    pub amount: MicroTari,
+    pub fee_per_gram: MicroTari,
+    pub message: String,
+    /// The watched outputs to spend, whose spending keys the offline wallet recovers by rewinding their range proofs
+    pub inputs: Vec<TransactionOutput>,
+}
+
+/// A one-sided payment signed by the offline wallet, ready to be broadcast by the watch-only wallet. It holds no keys:
+/// the recipient derives the keys of its output itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignedTransactionBundle {
+    pub tx_id: TxId,
+    pub recipient: CommsPublicKey,
+    pub amount: MicroTari,
+    pub message: String,
+    pub transaction: Transaction,
+}
+
+/// Select the unspent watched outputs to spend on a one-sided payment of `amount`, largest first, until they cover the
+/// amount and the fee of a transaction with a recipient and a change output. Returns `None` when there are not enough
+/// unspent watched outputs that can be signed for offline.
+pub fn select_watched_outputs(
+    outputs: &[WatchedOutput],
+    amount: MicroTari,
+    fee_per_gram: MicroTari,
+    fee_calc: &Fee,
+) -> Option<Vec<TransactionOutput>>
+{
+    let mut candidates = outputs
+        .iter()
+        .filter(|o| o.spent_at.is_none())
+        .filter_map(|o| o.output.as_ref().map(|output| (o.value, output)))
+        .collect::<Vec<_>>();
+    candidates.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let mut selected = Vec::new();
+    let mut total = MicroTari::from(0);
+    for (value, output) in candidates {
+        selected.push(output.clone());
+        total += value;
+        let fee = fee_calc.calculate(fee_per_gram, 1, selected.len(), 2, Fee::default_output_metadata_size(2));
+        if total >= amount + fee {
+            return Some(selected);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::Utc;
+    use rand::rngs::OsRng;
+    use tari_core::transactions::{
+        transaction::UnblindedOutput,
+        types::{CryptoFactories, PrivateKey},
+    };
+    use tari_crypto::keys::SecretKey;
+
+    fn watched_output(value: u64, spent: bool, with_output: bool) -> WatchedOutput {
+        let output = UnblindedOutput::new(MicroTari::from(value), PrivateKey::random(&mut OsRng), None)
+            .as_transaction_output(&CryptoFactories::new(32))
+            .unwrap();
+        WatchedOutput {
+            hash: Vec::new(),
+            value: MicroTari::from(value),
+            detected_at: Utc::now().naive_utc(),
+            spent_at: if spent { Some(Utc::now().naive_utc()) } else { None },
+            output: if with_output { Some(output) } else { None },
+        }
+    }
+
+    #[test]
+    fn the_largest_unspent_outputs_are_selected() {
+        let outputs = vec![
+            watched_output(1_000, false, true),
+            watched_output(50_000, true, true),
+            watched_output(40_000, false, false),
+            watched_output(20_000, false, true),
+            watched_output(5_000, false, true),
+        ];
+        let fee_per_gram = MicroTari::from(5);
+
+        let selected =
+            select_watched_outputs(&outputs, MicroTari::from(10_000), fee_per_gram, &Fee::default()).unwrap();
+        assert_eq!(selected, vec![outputs[3].output.clone().unwrap()]);
+
+        let selected =
+            select_watched_outputs(&outputs, MicroTari::from(22_000), fee_per_gram, &Fee::default()).unwrap();
+        assert_eq!(selected.len(), 2);
+
+        // The spent output and the one without its data on the chain cannot be spent
+        assert!(select_watched_outputs(&outputs, MicroTari::from(26_000), fee_per_gram, &Fee::default()).is_none());
+    }
+}
@@ -59,6 +59,8 @@ where TBackend: TransactionBackend + 'static
     timeout_update_receiver: Option<broadcast::Receiver<Duration>>,
     base_node_update_receiver: Option<broadcast::Receiver<CommsPublicKey>>,
     first_rejection: bool,
+    /// The number of consecutive failures to reach the base node, which the retry delay backs off with
+    num_connection_failures: u32,
 }
 
 impl<TBackend> TransactionBroadcastProtocol<TBackend>
@@ -82,6 +84,7 @@ where TBackend: TransactionBackend + 'static
             timeout_update_receiver: Some(timeout_update_receiver),
             base_node_update_receiver: Some(base_node_update_receiver),
             first_rejection: false,
+            num_connection_failures: 0,
         }
     }
 
@@ -110,7 +113,11 @@ where TBackend: TransactionBackend + 'static
                 .map_err(|e| TransactionServiceProtocolError::new(self.tx_id, TransactionServiceError::from(e)))?;
             let mut connection: Option<PeerConnection> = None;
 
-            let delay = delay_for(self.timeout);
+            let delay = delay_for(
+                self.resources
+                    .config
+                    .broadcast_retry_delay(self.timeout, self.num_connection_failures),
+            );
 
             debug!(
                 target: LOG_TARGET,
@@ -124,6 +131,7 @@ where TBackend: TransactionBackend + 'static
                         },
                         Err(e) => {
                             info!(target: LOG_TARGET, "Problem connecting to base node: {} for Transaction Broadcast Protocol (TxId: {})", e, self.tx_id);
+                            self.num_connection_failures += 1;
                             let _ = self
                             .resources
                             .event_publisher
@@ -169,6 +177,7 @@ where TBackend: TransactionBackend + 'static
                                 "Transaction Broadcast protocol (TxId: {}) Base Node Public key updated to {:?}", self.tx_id, self.base_node_public_key
                             );
                             self.first_rejection = false;
+                            self.num_connection_failures = 0;
                             continue;
                         },
                         Err(e) => {
@@ -239,10 +248,12 @@ where TBackend: TransactionBackend + 'static
                 Ok(c) => c,
                 Err(e) => {
                     warn!(target: LOG_TARGET, "Problem establishing RPC connection: {}", e);
+                    self.num_connection_failures += 1;
                     delay.await;
                     continue;
                 },
             };
+            self.num_connection_failures = 0;
 
             let delay = delay_for(self.timeout);
             loop {
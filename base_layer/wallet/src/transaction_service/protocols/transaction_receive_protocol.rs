@@ -266,6 +266,7 @@ where TBackend: TransactionBackend + 'static
         let mut timeout_delay = delay_for(timeout_duration).fuse();
 
         // check to see if a resend is due
+        let mut send_count = inbound_tx.send_count;
         let resend = match inbound_tx.last_send_timestamp {
             None => true,
             Some(timestamp) => {
@@ -279,7 +280,8 @@ where TBackend: TransactionBackend + 'static
                             TransactionServiceError::ConversionError("duration::OutOfRangeError".to_string()),
                         )
                     })?;
-                elapsed_time > self.resources.config.transaction_resend_period
+                elapsed_time > self.resources.config.transaction_resend_period &&
+                    self.resources.config.can_resend(send_count)
             },
         };
 
@@ -302,6 +304,7 @@ where TBackend: TransactionBackend + 'static
                 .increment_send_count(self.id)
                 .await
                 .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+            send_count += 1;
         }
 
         let mut shutdown = self.resources.shutdown_signal.clone();
@@ -335,6 +338,10 @@ where TBackend: TransactionBackend + 'static
                         }
                     },
                     () = resend_timeout => {
+                        if !self.resources.config.can_resend(send_count) {
+                            continue;
+                        }
+                        send_count += 1;
                         match send_transaction_reply(
                             inbound_tx.clone(),
                             self.resources.outbound_message_service.clone(),
@@ -55,6 +55,8 @@ use tari_core::transactions::{
 };
 use tari_p2p::tari_message::TariMessageType;
 use tokio::time::delay_for;
+use tracing::info_span;
+use tracing_futures::Instrument;
 
 const LOG_TARGET: &str = "wallet::transaction_service::protocols::send_protocol";
 const LOG_TARGET_STRESS: &str = "stress_test::send_protocol";
@@ -69,14 +71,15 @@ pub struct TransactionSendProtocol<TBackend>
 where TBackend: TransactionBackend + 'static
 {
     id: u64,
-    dest_pubkey: CommsPublicKey,
-    amount: MicroTari,
+    // The public key and amount of each recipient, in the order of the sender protocol's TxIds
+    recipients: Vec<(CommsPublicKey, MicroTari)>,
     message: String,
     sender_protocol: SenderTransactionProtocol,
     stage: TransactionSendProtocolStage,
     resources: TransactionServiceResources<TBackend>,
     transaction_reply_receiver: Option<Receiver<(CommsPublicKey, RecipientSignedMessage)>>,
     cancellation_receiver: Option<oneshot::Receiver<()>>,
+    resend_receiver: Option<Receiver<()>>,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -88,20 +91,49 @@ where TBackend: TransactionBackend + 'static
         resources: TransactionServiceResources<TBackend>,
         transaction_reply_receiver: Receiver<(CommsPublicKey, RecipientSignedMessage)>,
         cancellation_receiver: oneshot::Receiver<()>,
+        resend_receiver: Receiver<()>,
         dest_pubkey: CommsPublicKey,
         amount: MicroTari,
         message: String,
         sender_protocol: SenderTransactionProtocol,
         stage: TransactionSendProtocolStage,
     ) -> Self
+    {
+        Self::new_to_many(
+            id,
+            resources,
+            transaction_reply_receiver,
+            cancellation_receiver,
+            resend_receiver,
+            vec![(dest_pubkey, amount)],
+            message,
+            sender_protocol,
+            stage,
+        )
+    }
+
+    /// A protocol for a transaction with several recipients. The replies of all the recipients are received on
+    /// `transaction_reply_receiver`, and the transaction is completed once each of them has replied. The `recipients`
+    /// are in the order of the sender protocol's TxIds, and `id` is the first of those.
+    pub fn new_to_many(
+        id: u64,
+        resources: TransactionServiceResources<TBackend>,
+        transaction_reply_receiver: Receiver<(CommsPublicKey, RecipientSignedMessage)>,
+        cancellation_receiver: oneshot::Receiver<()>,
+        resend_receiver: Receiver<()>,
+        recipients: Vec<(CommsPublicKey, MicroTari)>,
+        message: String,
+        sender_protocol: SenderTransactionProtocol,
+        stage: TransactionSendProtocolStage,
+    ) -> Self
     {
         Self {
             id,
             resources,
             transaction_reply_receiver: Some(transaction_reply_receiver),
             cancellation_receiver: Some(cancellation_receiver),
-            dest_pubkey,
-            amount,
+            resend_receiver: Some(resend_receiver),
+            recipients,
             message,
             sender_protocol,
             stage,
@@ -109,7 +141,17 @@ where TBackend: TransactionBackend + 'static
     }
 
     /// Execute the Transaction Send Protocol as an async task.
-    pub async fn execute(mut self) -> Result<u64, TransactionServiceProtocolError> {
+    pub async fn execute(self) -> Result<u64, TransactionServiceProtocolError> {
+        let span = info_span!(
+            "transaction_send_protocol",
+            tx_id = self.id,
+            destination = %self.recipients[0].0,
+            recipients = self.recipients.len()
+        );
+        self.execute_protocol().instrument(span).await
+    }
+
+    async fn execute_protocol(mut self) -> Result<u64, TransactionServiceProtocolError> {
         info!(
             target: LOG_TARGET,
             "Starting Transaction Send protocol for TxId: {} at Stage {:?}", self.id, self.stage
@@ -128,6 +170,21 @@ where TBackend: TransactionBackend + 'static
         Ok(self.id)
     }
 
+    /// The TxIds of the recipients, in the same order as `recipients`. The first one is the id of this protocol.
+    fn tx_ids(&self) -> Result<Vec<u64>, TransactionServiceProtocolError> {
+        let tx_ids = self
+            .sender_protocol
+            .get_tx_ids()
+            .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+        if tx_ids.len() != self.recipients.len() || tx_ids.first() != Some(&self.id) {
+            return Err(TransactionServiceProtocolError::new(
+                self.id,
+                TransactionServiceError::InvalidStateError,
+            ));
+        }
+        Ok(tx_ids)
+    }
+
     async fn initial_send_transaction(&mut self) -> Result<(), TransactionServiceProtocolError> {
         if !self.sender_protocol.is_single_round_message_ready() {
             error!(target: LOG_TARGET, "Sender Transaction Protocol is in an invalid state");
@@ -137,85 +194,109 @@ where TBackend: TransactionBackend + 'static
             ));
         }
 
-        let msg = self
+        let msgs = self
             .sender_protocol
-            .build_single_round_message()
+            .build_single_round_messages()
             .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
-        let tx_id = msg.tx_id;
-
-        if tx_id != self.id {
+        let tx_ids = self.tx_ids()?;
+        if msgs.iter().map(|m| m.tx_id).ne(tx_ids.iter().copied()) {
             return Err(TransactionServiceProtocolError::new(
                 self.id,
                 TransactionServiceError::InvalidStateError,
             ));
         }
 
-        let SendResult {
-            direct_send_result,
-            store_and_forward_send_result,
-        } = self.send_transaction(msg).await?;
+        // The transaction can only be completed if every recipient gets its message, so stop at the first failure
+        let mut send_results = Vec::with_capacity(msgs.len());
+        for (msg, (dest_pubkey, _)) in msgs.iter().zip(self.recipients.clone()) {
+            let send_result = self.send_transaction(msg.clone(), dest_pubkey).await?;
+            let sent = send_result.is_sent();
+            send_results.push(send_result);
+            if !sent {
+                break;
+            }
+        }
+        let all_sent = send_results.len() == msgs.len() && send_results.iter().all(SendResult::is_sent);
 
-        if direct_send_result || store_and_forward_send_result {
+        if all_sent {
             self.resources
                 .output_manager_service
                 .confirm_pending_transaction(self.id)
                 .await
                 .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
 
-            let fee = self
-                .sender_protocol
-                .get_fee_amount()
-                .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
-            let outbound_tx = OutboundTransaction::new(
-                tx_id,
-                self.dest_pubkey.clone(),
-                self.amount,
-                fee,
-                self.sender_protocol.clone(),
-                TransactionStatus::Pending,
-                self.message.clone(),
-                Utc::now().naive_utc(),
-                direct_send_result,
-            );
-            info!(
-                target: LOG_TARGET,
-                "Pending Outbound Transaction TxId: {:?} added. Waiting for Reply or Cancellation", self.id,
-            );
-            self.resources
-                .db
-                .add_pending_outbound_transaction(outbound_tx.tx_id, outbound_tx)
-                .await
-                .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+            for ((msg, (dest_pubkey, amount)), send_result) in
+                msgs.iter().zip(self.recipients.iter()).zip(send_results.iter())
+            {
+                let outbound_tx = OutboundTransaction::new(
+                    msg.tx_id,
+                    dest_pubkey.clone(),
+                    *amount,
+                    msg.metadata.fee,
+                    self.sender_protocol.clone(),
+                    TransactionStatus::Pending,
+                    self.message.clone(),
+                    Utc::now().naive_utc(),
+                    send_result.direct_send_result,
+                );
+                info!(
+                    target: LOG_TARGET,
+                    "Pending Outbound Transaction TxId: {:?} added. Waiting for Reply or Cancellation", msg.tx_id,
+                );
+                self.resources
+                    .db
+                    .add_pending_outbound_transaction(outbound_tx.tx_id, outbound_tx)
+                    .await
+                    .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
 
-            self.resources
-                .db
-                .increment_send_count(self.id)
-                .await
-                .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+                self.resources
+                    .db
+                    .increment_send_count(msg.tx_id)
+                    .await
+                    .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+            }
         }
 
-        let _ = self
-            .resources
-            .event_publisher
-            .send(Arc::new(TransactionEvent::TransactionDirectSendResult(
-                self.id,
-                direct_send_result,
-            )));
-        let _ = self
-            .resources
-            .event_publisher
-            .send(Arc::new(TransactionEvent::TransactionStoreForwardSendResult(
-                self.id,
-                store_and_forward_send_result,
-            )));
+        for (msg, send_result) in msgs.iter().zip(send_results.iter()) {
+            let _ = self
+                .resources
+                .event_publisher
+                .send(Arc::new(TransactionEvent::TransactionDirectSendResult(
+                    msg.tx_id,
+                    send_result.direct_send_result,
+                )));
+            let _ = self
+                .resources
+                .event_publisher
+                .send(Arc::new(TransactionEvent::TransactionStoreForwardSendResult(
+                    msg.tx_id,
+                    send_result.store_and_forward_send_result,
+                )));
+        }
 
-        if !direct_send_result && !store_and_forward_send_result {
+        if !all_sent {
             error!(
                 target: LOG_TARGET,
                 "Failed to Send Transaction (TxId: {}) both Directly or via Store and Forward. Pending Transaction \
                  will be cancelled",
                 self.id
             );
+            // The recipients that did get their message can not complete the transaction without the others
+            let num_sent = send_results.len() - 1;
+            for (msg, (dest_pubkey, _)) in msgs.iter().zip(self.recipients.iter()).take(num_sent) {
+                let _ = send_transaction_cancelled_message(
+                    msg.tx_id,
+                    dest_pubkey.clone(),
+                    self.resources.outbound_message_service.clone(),
+                )
+                .await
+                .map_err(|e| {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Error sending Transaction Cancelled (TxId: {}) message: {:?}", msg.tx_id, e
+                    )
+                });
+            }
             if let Err(e) = self.resources.output_manager_service.cancel_transaction(self.id).await {
                 warn!(
                     target: LOG_TARGET,
@@ -234,6 +315,7 @@ where TBackend: TransactionBackend + 'static
     async fn wait_for_reply(&mut self) -> Result<(), TransactionServiceProtocolError> {
         // Waiting  for Transaction Reply
         let tx_id = self.id;
+        let tx_ids = self.tx_ids()?;
         let mut receiver = self
             .transaction_reply_receiver
             .take()
@@ -245,6 +327,14 @@ where TBackend: TransactionBackend + 'static
             .ok_or_else(|| TransactionServiceProtocolError::new(self.id, TransactionServiceError::InvalidStateError))?
             .fuse();
 
+        let mut resend_receiver = self
+            .resend_receiver
+            .take()
+            .ok_or_else(|| TransactionServiceProtocolError::new(self.id, TransactionServiceError::InvalidStateError))?
+            .fuse();
+
+        // With several recipients each has a pending outbound transaction, the first one's is used for the transaction
+        // as a whole
         let mut outbound_tx = self
             .resources
             .db
@@ -287,6 +377,7 @@ where TBackend: TransactionBackend + 'static
         let mut timeout_delay = delay_for(timeout_duration).fuse();
 
         // check to see if a resend is due
+        let mut send_count = outbound_tx.send_count;
         let resend = match outbound_tx.last_send_timestamp {
             None => true,
             Some(timestamp) => {
@@ -300,67 +391,70 @@ where TBackend: TransactionBackend + 'static
                             TransactionServiceError::ConversionError("duration::OutOfRangeError".to_string()),
                         )
                     })?;
-                elapsed_time > self.resources.config.transaction_resend_period
+                elapsed_time > self.resources.config.transaction_resend_period &&
+                    self.resources.config.can_resend(send_count)
             },
         };
 
         if resend {
-            if let Err(e) = self
-                .send_transaction(
-                    outbound_tx
-                        .sender_protocol
-                        .get_single_round_message()
-                        .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?,
-                )
-                .await
-            {
-                warn!(
-                    target: LOG_TARGET,
-                    "Error resending Transaction (TxId: {}): {:?}", self.id, e
-                );
+            for (msg, dest_pubkey) in self.pending_messages(&outbound_tx.sender_protocol)? {
+                if let Err(e) = self.send_transaction(msg.clone(), dest_pubkey).await {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Error resending Transaction (TxId: {}): {:?}", msg.tx_id, e
+                    );
+                }
+                self.resources
+                    .db
+                    .increment_send_count(msg.tx_id)
+                    .await
+                    .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
             }
-            self.resources
-                .db
-                .increment_send_count(self.id)
-                .await
-                .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+            send_count += 1;
         }
 
         let mut shutdown = self.resources.shutdown_signal.clone();
-        #[allow(unused_assignments)]
-        let mut reply = None;
         loop {
             let mut resend_timeout = delay_for(self.resources.config.transaction_resend_period).fuse();
             futures::select! {
                 (spk, rr) = receiver.select_next_some() => {
                     let rr_tx_id = rr.tx_id;
-                    reply = Some(rr);
+                    let recipient = tx_ids.iter().position(|id| *id == rr_tx_id).map(|i| &self.recipients[i].0);
 
-                    if outbound_tx.destination_public_key != spk {
-                        warn!(
+                    match recipient {
+                        None => warn!(target: LOG_TARGET, "Transaction Reply does not have the correct TxId"),
+                        Some(dest_pubkey) if *dest_pubkey != spk => warn!(
                             target: LOG_TARGET,
                             "Transaction Reply did not come from the expected Public Key"
-                        );
-                    } else if !outbound_tx.sender_protocol.check_tx_id(rr_tx_id) {
-                        warn!(target: LOG_TARGET, "Transaction Reply does not have the correct TxId");
-                    } else {
-                        break;
+                        ),
+                        Some(_) => {
+                            // The same reply can arrive both directly and via store and forward, and neither a
+                            // duplicate nor an invalid reply should abort the transaction for the other recipients
+                            if let Err(e) = outbound_tx
+                                .sender_protocol
+                                .add_single_recipient_info(rr, &self.resources.factories.range_proof)
+                            {
+                                warn!(
+                                    target: LOG_TARGET,
+                                    "Transaction Reply for TX_ID = {} could not be accepted: {:?}", rr_tx_id, e
+                                );
+                                continue;
+                            }
+                            if outbound_tx.sender_protocol.is_finalizing() {
+                                break;
+                            }
+                            info!(
+                                target: LOG_TARGET,
+                                "Transaction Recipient Reply for TX_ID = {} received, waiting for other recipients",
+                                rr_tx_id
+                            );
+                        },
                     }
                 },
                 result = cancellation_receiver => {
                     if result.is_ok() {
                         info!(target: LOG_TARGET, "Cancelling Transaction Send Protocol (TxId: {})", self.id);
-                        let _ = send_transaction_cancelled_message(self.id,self.dest_pubkey.clone(), self.resources.outbound_message_service.clone(), ).await.map_err(|e| {
-                            warn!(
-                                target: LOG_TARGET,
-                                "Error sending Transaction Cancelled (TxId: {}) message: {:?}", self.id, e
-                            )
-                        });
-                        self.resources
-                            .db
-                            .increment_send_count(self.id)
-                            .await
-                            .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+                        self.send_cancellations().await?;
                         return Err(TransactionServiceProtocolError::new(
                             self.id,
                             TransactionServiceError::TransactionCancelled,
@@ -368,19 +462,15 @@ where TBackend: TransactionBackend + 'static
                     }
                 },
                 () = resend_timeout => {
-                    if let Err(e) = self.send_transaction(outbound_tx.sender_protocol.get_single_round_message().map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?).await {
-                        warn!(
-                            target: LOG_TARGET,
-                            "Error resending Transaction (TxId: {}): {:?}", self.id, e
-                        );
-                    } else {
-                        self.resources
-                            .db
-                            .increment_send_count(self.id)
-                            .await
-                            .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+                    if self.resources.config.can_resend(send_count) {
+                        self.resend_transaction(&outbound_tx).await?;
+                        send_count += 1;
                     }
                 },
+                _ = resend_receiver.select_next_some() => {
+                    info!(target: LOG_TARGET, "Resending Transaction (TxId: {}) on request", self.id);
+                    self.resend_transaction(&outbound_tx).await?;
+                },
                 () = timeout_delay => {
                     return self.timeout_transaction().await;
                 }
@@ -391,14 +481,12 @@ where TBackend: TransactionBackend + 'static
             }
         }
 
-        let recipient_reply = reply.ok_or_else(|| {
-            TransactionServiceProtocolError::new(self.id, TransactionServiceError::TransactionCancelled)
-        })?;
-
-        outbound_tx
+        // The fee and amount of the transaction as a whole, rather than the first recipient's share
+        let fee = outbound_tx
             .sender_protocol
-            .add_single_recipient_info(recipient_reply, &self.resources.factories.range_proof)
+            .get_fee_amount()
             .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+        let amount = self.recipients.iter().map(|(_, amount)| *amount).sum::<MicroTari>();
 
         outbound_tx
             .sender_protocol
@@ -420,12 +508,13 @@ where TBackend: TransactionBackend + 'static
             .get_transaction()
             .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
 
+        // The wallet records the transaction once, under the TxId of the first recipient
         let completed_transaction = CompletedTransaction::new(
             tx_id,
             self.resources.node_identity.public_key().clone(),
             outbound_tx.destination_public_key.clone(),
-            outbound_tx.amount,
-            outbound_tx.fee,
+            amount,
+            fee,
             tx.clone(),
             TransactionStatus::Completed,
             outbound_tx.message.clone(),
@@ -434,9 +523,15 @@ where TBackend: TransactionBackend + 'static
             None,
         );
 
+        // The pending rows of the other recipients are removed in the same db transaction, so none of them are left
+        // behind to be restarted
         self.resources
             .db
-            .complete_outbound_transaction(tx_id, completed_transaction.clone())
+            .complete_multi_recipient_outbound_transaction(
+                tx_id,
+                completed_transaction.clone(),
+                tx_ids.iter().skip(1).cloned().collect(),
+            )
             .await
             .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
         info!(
@@ -448,16 +543,18 @@ where TBackend: TransactionBackend + 'static
             "Transaction Recipient Reply for TX_ID = {} received", tx_id,
         );
 
-        send_finalized_transaction_message(
-            tx_id,
-            tx.clone(),
-            self.dest_pubkey.clone(),
-            self.resources.outbound_message_service.clone(),
-            self.resources.config.direct_send_timeout,
-            self.resources.config.transaction_routing_mechanism,
-        )
-        .await
-        .map_err(|e| TransactionServiceProtocolError::new(self.id, e))?;
+        for (recipient_tx_id, (dest_pubkey, _)) in tx_ids.iter().zip(self.recipients.iter()) {
+            send_finalized_transaction_message(
+                *recipient_tx_id,
+                tx.clone(),
+                dest_pubkey.clone(),
+                self.resources.outbound_message_service.clone(),
+                self.resources.config.direct_send_timeout,
+                self.resources.config.transaction_routing_mechanism,
+            )
+            .await
+            .map_err(|e| TransactionServiceProtocolError::new(self.id, e))?;
+        }
 
         self.resources
             .db
@@ -481,13 +578,83 @@ where TBackend: TransactionBackend + 'static
         Ok(())
     }
 
+    /// The messages of the recipients that have not replied yet, with their public keys
+    fn pending_messages(
+        &self,
+        sender_protocol: &SenderTransactionProtocol,
+    ) -> Result<Vec<(SingleRoundSenderData, CommsPublicKey)>, TransactionServiceProtocolError>
+    {
+        let pending_tx_ids = sender_protocol
+            .get_pending_recipient_tx_ids()
+            .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+        let msgs = sender_protocol
+            .get_single_round_messages()
+            .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+        Ok(msgs
+            .into_iter()
+            .zip(self.recipients.iter())
+            .filter(|(msg, _)| pending_tx_ids.contains(&msg.tx_id))
+            .map(|(msg, (dest_pubkey, _))| (msg, dest_pubkey.clone()))
+            .collect())
+    }
+
+    /// Send the pending transaction again to each recipient that has not replied yet, counting the sends that
+    /// succeeded
+    async fn resend_transaction(
+        &mut self,
+        outbound_tx: &OutboundTransaction,
+    ) -> Result<(), TransactionServiceProtocolError>
+    {
+        for (msg, dest_pubkey) in self.pending_messages(&outbound_tx.sender_protocol)? {
+            if let Err(e) = self.send_transaction(msg.clone(), dest_pubkey).await {
+                warn!(
+                    target: LOG_TARGET,
+                    "Error resending Transaction (TxId: {}): {:?}", msg.tx_id, e
+                );
+            } else {
+                self.resources
+                    .db
+                    .increment_send_count(msg.tx_id)
+                    .await
+                    .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Let every recipient know that the transaction is cancelled
+    async fn send_cancellations(&mut self) -> Result<(), TransactionServiceProtocolError> {
+        for (tx_id, (dest_pubkey, _)) in self.tx_ids()?.into_iter().zip(self.recipients.iter()) {
+            let _ = send_transaction_cancelled_message(
+                tx_id,
+                dest_pubkey.clone(),
+                self.resources.outbound_message_service.clone(),
+            )
+            .await
+            .map_err(|e| {
+                warn!(
+                    target: LOG_TARGET,
+                    "Error sending Transaction Cancelled (TxId: {}) message: {:?}", tx_id, e
+                )
+            });
+            self.resources
+                .db
+                .increment_send_count(tx_id)
+                .await
+                .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+        }
+        Ok(())
+    }
+
     /// Attempt to send the transaction to the recipient either directly, via Store-and-forward or both as per config
     /// setting. If the selected sending mechanism fail to send the transaction will be cancelled.
     /// # Argumentswallet_sync_with_base_node
     /// `msg`: The transaction data message to be sent
+    /// `dest_pubkey`: The public key of the recipient the message is for
     async fn send_transaction(
         &mut self,
         msg: SingleRoundSenderData,
+        dest_pubkey: CommsPublicKey,
     ) -> Result<SendResult, TransactionServiceProtocolError>
     {
         let mut result = SendResult {
@@ -497,10 +664,12 @@ where TBackend: TransactionBackend + 'static
 
         match self.resources.config.transaction_routing_mechanism {
             TransactionRoutingMechanism::DirectOnly | TransactionRoutingMechanism::DirectAndStoreAndForward => {
-                result = self.send_transaction_direct(msg.clone()).await?;
+                result = self.send_transaction_direct(msg.clone(), dest_pubkey).await?;
             },
             TransactionRoutingMechanism::StoreAndForwardOnly => {
-                result.store_and_forward_send_result = self.send_transaction_store_and_forward(msg.clone()).await?;
+                result.store_and_forward_send_result = self
+                    .send_transaction_store_and_forward(msg.clone(), dest_pubkey)
+                    .await?;
             },
         };
 
@@ -511,9 +680,11 @@ where TBackend: TransactionBackend + 'static
     /// the transaction will be cancelled.
     /// # Argumentswallet_sync_with_base_node
     /// `msg`: The transaction data message to be sent
+    /// `dest_pubkey`: The public key of the recipient the message is for
     async fn send_transaction_direct(
         &mut self,
         msg: SingleRoundSenderData,
+        dest_pubkey: CommsPublicKey,
     ) -> Result<SendResult, TransactionServiceProtocolError>
     {
         let proto_message = proto::TransactionSenderMessage::single(msg.clone().into());
@@ -522,14 +693,14 @@ where TBackend: TransactionBackend + 'static
 
         info!(
             target: LOG_TARGET,
-            "Attempting to Send Transaction (TxId: {}) to recipient with Public Key: {}", self.id, self.dest_pubkey,
+            "Attempting to Send Transaction (TxId: {}) to recipient with Public Key: {}", msg.tx_id, dest_pubkey,
         );
 
         match self
             .resources
             .outbound_message_service
             .send_direct(
-                self.dest_pubkey.clone(),
+                dest_pubkey.clone(),
                 OutboundDomainMessage::new(TariMessageType::SenderPartialTransaction, proto_message.clone()),
             )
             .await
@@ -538,8 +709,8 @@ where TBackend: TransactionBackend + 'static
                 SendMessageResponse::Queued(send_states) => {
                     if wait_on_dial(
                         send_states,
-                        self.id,
-                        self.dest_pubkey.clone(),
+                        msg.tx_id,
+                        dest_pubkey.clone(),
                         "Transaction",
                         self.resources.config.direct_send_timeout,
                     )
@@ -555,40 +726,46 @@ where TBackend: TransactionBackend + 'static
                         target: LOG_TARGET,
                         "Direct Send result was {}. Sending SAF for TxId: {} to recipient with Public Key: {}",
                         direct_send_result,
-                        self.id,
-                        self.dest_pubkey,
+                        msg.tx_id,
+                        dest_pubkey,
                     );
-                    store_and_forward_send_result = self.send_transaction_store_and_forward(msg.clone()).await?;
+                    store_and_forward_send_result = self
+                        .send_transaction_store_and_forward(msg.clone(), dest_pubkey.clone())
+                        .await?;
                 },
                 SendMessageResponse::Failed(err) => {
                     warn!(
                         target: LOG_TARGET,
-                        "Transaction Send Direct for TxID {} failed: {}", self.id, err
+                        "Transaction Send Direct for TxID {} failed: {}", msg.tx_id, err
                     );
                     debug!(
                         target: LOG_TARGET_STRESS,
-                        "Transaction Send Direct for TxID {} failed: {}", self.id, err
+                        "Transaction Send Direct for TxID {} failed: {}", msg.tx_id, err
                     );
-                    store_and_forward_send_result = self.send_transaction_store_and_forward(msg.clone()).await?;
+                    store_and_forward_send_result = self
+                        .send_transaction_store_and_forward(msg.clone(), dest_pubkey.clone())
+                        .await?;
                 },
                 SendMessageResponse::PendingDiscovery(rx) => {
                     let _ = self
                         .resources
                         .event_publisher
-                        .send(Arc::new(TransactionEvent::TransactionDiscoveryInProgress(self.id)));
-                    store_and_forward_send_result = self.send_transaction_store_and_forward(msg.clone()).await?;
+                        .send(Arc::new(TransactionEvent::TransactionDiscoveryInProgress(msg.tx_id)));
+                    store_and_forward_send_result = self
+                        .send_transaction_store_and_forward(msg.clone(), dest_pubkey.clone())
+                        .await?;
                     // now wait for discovery to complete
                     match rx.await {
                         Ok(send_msg_response) => {
                             if let SendMessageResponse::Queued(send_states) = send_msg_response {
                                 debug!(
                                     target: LOG_TARGET,
-                                    "Discovery of {} completed for TxID: {}", self.dest_pubkey, self.id
+                                    "Discovery of {} completed for TxID: {}", dest_pubkey, msg.tx_id
                                 );
                                 direct_send_result = wait_on_dial(
                                     send_states,
-                                    self.id,
-                                    self.dest_pubkey.clone(),
+                                    msg.tx_id,
+                                    dest_pubkey.clone(),
                                     "Transaction",
                                     self.resources.config.direct_send_timeout,
                                 )
@@ -598,7 +775,7 @@ where TBackend: TransactionBackend + 'static
                         Err(e) => {
                             warn!(
                                 target: LOG_TARGET,
-                                "Error waiting for Discovery while sending message to TxId: {} {:?}", self.id, e
+                                "Error waiting for Discovery while sending message to TxId: {} {:?}", msg.tx_id, e
                             );
                         },
                     }
@@ -619,22 +796,24 @@ where TBackend: TransactionBackend + 'static
     /// Contains all the logic to send the transaction to the recipient via store and forward
     /// # Arguments
     /// `msg`: The transaction data message to be sent
-    /// 'send_events': A bool indicating whether we should send events during the operation or not.
+    /// `dest_pubkey`: The public key of the recipient the message is for
     async fn send_transaction_store_and_forward(
         &mut self,
         msg: SingleRoundSenderData,
+        dest_pubkey: CommsPublicKey,
     ) -> Result<bool, TransactionServiceProtocolError>
     {
         if self.resources.config.transaction_routing_mechanism == TransactionRoutingMechanism::DirectOnly {
             return Ok(false);
         }
+        let tx_id = msg.tx_id;
         let proto_message = proto::TransactionSenderMessage::single(msg.into());
         match self
             .resources
             .outbound_message_service
             .closest_broadcast(
-                NodeId::from_public_key(&self.dest_pubkey),
-                OutboundEncryption::EncryptFor(Box::new(self.dest_pubkey.clone())),
+                NodeId::from_public_key(&dest_pubkey),
+                OutboundEncryption::EncryptFor(Box::new(dest_pubkey.clone())),
                 vec![],
                 OutboundDomainMessage::new(TariMessageType::SenderPartialTransaction, proto_message),
             )
@@ -649,14 +828,14 @@ where TBackend: TransactionBackend + 'static
                         target: LOG_TARGET,
                         "Transaction (TxId: {}) Send to Neighbours for Store and Forward successful with Message \
                          Tags: {:?}",
-                        self.id,
+                        tx_id,
                         successful_sends[0],
                     );
                     debug!(
                         target: LOG_TARGET_STRESS,
                         "Transaction (TxId: {}) Send to Neighbours for Store and Forward successful with Message \
                          Tags: {:?}",
-                        self.id,
+                        tx_id,
                         successful_sends[0],
                     );
                     Ok(true)
@@ -665,13 +844,13 @@ where TBackend: TransactionBackend + 'static
                         target: LOG_TARGET,
                         "Transaction Send to Neighbours for Store and Forward for TX_ID: {} was unsuccessful and no \
                          messages were sent",
-                        self.id
+                        tx_id
                     );
                     debug!(
                         target: LOG_TARGET_STRESS,
                         "Transaction Send to Neighbours for Store and Forward for TX_ID: {} was unsuccessful and no \
                          messages were sent",
-                        self.id
+                        tx_id
                     );
                     Ok(false)
                 } else {
@@ -679,13 +858,13 @@ where TBackend: TransactionBackend + 'static
                         target: LOG_TARGET,
                         "Transaction Send to Neighbours for Store and Forward for TX_ID: {} timed out and was \
                          unsuccessful. Some message might still be sent.",
-                        self.id
+                        tx_id
                     );
                     debug!(
                         target: LOG_TARGET_STRESS,
                         "Transaction Send to Neighbours for Store and Forward for TX_ID: {} timed out and was \
                          unsuccessful. Some message might still be sent.",
-                        self.id
+                        tx_id
                     );
                     Ok(false)
                 }
@@ -695,24 +874,24 @@ where TBackend: TransactionBackend + 'static
                     target: LOG_TARGET,
                     "Transaction Send to Neighbours for Store and Forward for TX_ID: {} was unsuccessful and no \
                      messages were sent",
-                    self.id
+                    tx_id
                 );
                 debug!(
                     target: LOG_TARGET_STRESS,
                     "Transaction Send to Neighbours for Store and Forward for TX_ID: {} was unsuccessful and no \
                      messages were sent",
-                    self.id
+                    tx_id
                 );
                 Ok(false)
             },
             Err(e) => {
                 warn!(
                     target: LOG_TARGET,
-                    "Transaction Send (TxId: {}) to neighbours for Store and Forward failed: {:?}", self.id, e
+                    "Transaction Send (TxId: {}) to neighbours for Store and Forward failed: {:?}", tx_id, e
                 );
                 debug!(
                     target: LOG_TARGET_STRESS,
-                    "Transaction Send (TxId: {}) to neighbours for Store and Forward failed: {:?}", self.id, e
+                    "Transaction Send (TxId: {}) to neighbours for Store and Forward failed: {:?}", tx_id, e
                 );
                 Ok(false)
             },
@@ -724,35 +903,17 @@ where TBackend: TransactionBackend + 'static
             target: LOG_TARGET,
             "Cancelling Transaction Send Protocol (TxId: {}) due to timeout after no counterparty response", self.id
         );
-        let _ = send_transaction_cancelled_message(
-            self.id,
-            self.dest_pubkey.clone(),
-            self.resources.outbound_message_service.clone(),
-        )
-        .await
-        .map_err(|e| {
-            warn!(
-                target: LOG_TARGET,
-                "Error sending Transaction Cancelled (TxId: {}) message: {:?}", self.id, e
-            )
-        });
-        self.resources
-            .db
-            .increment_send_count(self.id)
-            .await
-            .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
+        self.send_cancellations().await?;
 
-        self.resources
-            .db
-            .cancel_pending_transaction(self.id)
-            .await
-            .map_err(|e| {
+        for tx_id in self.tx_ids()? {
+            self.resources.db.cancel_pending_transaction(tx_id).await.map_err(|e| {
                 warn!(
                     target: LOG_TARGET,
                     "Pending Transaction does not exist and could not be cancelled: {:?}", e
                 );
                 TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e))
             })?;
+        }
 
         self.resources
             .output_manager_service
@@ -760,21 +921,23 @@ where TBackend: TransactionBackend + 'static
             .await
             .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
 
-        let _ = self
-            .resources
-            .event_publisher
-            .send(Arc::new(TransactionEvent::TransactionCancelled(self.id)))
-            .map_err(|e| {
-                trace!(
-                    target: LOG_TARGET,
-                    "Error sending event because there are no subscribers: {:?}",
-                    e
-                );
-                TransactionServiceProtocolError::new(
-                    self.id,
-                    TransactionServiceError::BroadcastSendError(format!("{:?}", e)),
-                )
-            });
+        for tx_id in self.tx_ids()? {
+            let _ = self
+                .resources
+                .event_publisher
+                .send(Arc::new(TransactionEvent::TransactionCancelled(tx_id)))
+                .map_err(|e| {
+                    trace!(
+                        target: LOG_TARGET,
+                        "Error sending event because there are no subscribers: {:?}",
+                        e
+                    );
+                    TransactionServiceProtocolError::new(
+                        self.id,
+                        TransactionServiceError::BroadcastSendError(format!("{:?}", e)),
+                    )
+                });
+        }
 
         info!(
             target: LOG_TARGET,
@@ -792,3 +955,9 @@ struct SendResult {
     direct_send_result: bool,
     store_and_forward_send_result: bool,
 }
+
+impl SendResult {
+    fn is_sent(&self) -> bool {
+        self.direct_send_result || self.store_and_forward_send_result
+    }
+}
@@ -21,11 +21,30 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::{
-    output_manager_service::{handle::OutputManagerHandle, TxId},
+    output_manager_service::{
+        handle::{HtlcSigningRequest, MultisigSigningRequest, OutputManagerHandle},
+        utxo_selection::UtxoSelectionPolicy,
+        TxId,
+    },
     transaction_service::{
         config::TransactionServiceConfig,
         error::{TransactionServiceError, TransactionServiceProtocolError},
-        handle::{TransactionEvent, TransactionEventSender, TransactionServiceRequest, TransactionServiceResponse},
+        handle::{
+            TransactionEvent,
+            TransactionEventSender,
+            TransactionRetry,
+            TransactionServiceRequest,
+            TransactionServiceResponse,
+        },
+        multisig::{
+            MultisigApproval,
+            MultisigCosigningSession,
+            MultisigFundingSession,
+            MultisigMessage,
+            MultisigSessionMessage,
+            MultisigSignRequest,
+            MultisigSpendingSession,
+        },
         protocols::{
             transaction_broadcast_protocol::TransactionBroadcastProtocol,
             transaction_coinbase_monitoring_protocol::TransactionCoinbaseMonitoringProtocol,
@@ -35,10 +54,11 @@ use crate::{
         },
         storage::{
             database::{TransactionBackend, TransactionDatabase},
-            models::{CompletedTransaction, TransactionDirection, TransactionStatus},
+            models::{CompletedTransaction, TransactionDirection, TransactionStatus, WalletTransaction},
         },
         tasks::{
-            send_finalized_transaction::send_finalized_transaction_message,
+            send_finalized_transaction::{send_finalized_transaction_message, send_one_sided_payment_message},
+            send_multisig_message::send_multisig_message,
             send_transaction_cancelled::send_transaction_cancelled_message,
             send_transaction_reply::send_transaction_reply,
         },
@@ -69,12 +89,21 @@ use tari_core::transactions::{tari_amount::uT, types::BlindingFactor};
 use tari_core::{
     proto::base_node as base_node_proto,
     transactions::{
+        htlc::HashTimeLock,
+        multisig::{nonce_commitment, MultisigError, MultisigLock, MAX_MULTISIG_PARTICIPANTS},
+        one_sided::OneSidedPayment,
+        script::{ScriptWitness, StackItem},
         tari_amount::MicroTari,
-        transaction::Transaction,
+        transaction::{KernelFeatures, Transaction, UnblindedOutput},
         transaction_protocol::{proto, recipient::RecipientSignedMessage, sender::TransactionSenderMessage},
-        types::{CryptoFactories, PrivateKey},
+        types::{Commitment, CryptoFactories, PrivateKey, PublicKey},
     },
 };
+use tari_crypto::{
+    commitment::HomomorphicCommitmentFactory,
+    keys::SecretKey as SecretKeyTrait,
+    tari_utilities::{hex::Hex, ByteArray},
+};
 use tari_p2p::domain_message::DomainMessage;
 use tari_service_framework::{reply_channel, reply_channel::Receiver};
 use tari_shutdown::ShutdownSignal;
@@ -104,6 +133,7 @@ pub struct TransactionService<
     BNResponseStream,
     TBackend,
     TTxCancelledStream,
+    TMultisigStream,
 > where TBackend: TransactionBackend + 'static
 {
     config: TransactionServiceConfig,
@@ -114,6 +144,7 @@ pub struct TransactionService<
     transaction_finalized_stream: Option<TTxFinalizedStream>,
     base_node_response_stream: Option<BNResponseStream>,
     transaction_cancelled_stream: Option<TTxCancelledStream>,
+    multisig_stream: Option<TMultisigStream>,
     request_stream: Option<
         reply_channel::Receiver<TransactionServiceRequest, Result<TransactionServiceResponse, TransactionServiceError>>,
     >,
@@ -124,6 +155,7 @@ pub struct TransactionService<
     pending_transaction_reply_senders: HashMap<TxId, Sender<(CommsPublicKey, RecipientSignedMessage)>>,
     base_node_response_senders: HashMap<u64, (TxId, Sender<base_node_proto::BaseNodeServiceResponse>)>,
     send_transaction_cancellation_senders: HashMap<u64, oneshot::Sender<()>>,
+    send_transaction_resend_senders: HashMap<u64, Sender<()>>,
     finalized_transaction_senders: HashMap<u64, Sender<(CommsPublicKey, TxId, Transaction)>>,
     receiver_transaction_cancellation_senders: HashMap<u64, oneshot::Sender<()>>,
     active_transaction_broadcast_protocols: HashSet<u64>,
@@ -131,17 +163,38 @@ pub struct TransactionService<
     timeout_update_publisher: broadcast::Sender<Duration>,
     base_node_update_publisher: broadcast::Sender<CommsPublicKey>,
     power_mode: PowerMode,
+    multisig_funding_sessions: HashMap<u64, MultisigFundingSession>,
+    multisig_spending_sessions: HashMap<u64, MultisigSpendingSession>,
+    multisig_cosigning_sessions: HashMap<u64, MultisigCosigningSession>,
+    multisig_approvals: Vec<MultisigApproval>,
 }
 
 #[allow(clippy::too_many_arguments)]
-impl<TTxStream, TTxReplyStream, TTxFinalizedStream, BNResponseStream, TBackend, TTxCancelledStream>
-    TransactionService<TTxStream, TTxReplyStream, TTxFinalizedStream, BNResponseStream, TBackend, TTxCancelledStream>
+impl<
+        TTxStream,
+        TTxReplyStream,
+        TTxFinalizedStream,
+        BNResponseStream,
+        TBackend,
+        TTxCancelledStream,
+        TMultisigStream,
+    >
+    TransactionService<
+        TTxStream,
+        TTxReplyStream,
+        TTxFinalizedStream,
+        BNResponseStream,
+        TBackend,
+        TTxCancelledStream,
+        TMultisigStream,
+    >
 where
     TTxStream: Stream<Item = DomainMessage<proto::TransactionSenderMessage>>,
     TTxReplyStream: Stream<Item = DomainMessage<proto::RecipientSignedMessage>>,
     TTxFinalizedStream: Stream<Item = DomainMessage<proto::TransactionFinalizedMessage>>,
     BNResponseStream: Stream<Item = DomainMessage<base_node_proto::BaseNodeServiceResponse>>,
     TTxCancelledStream: Stream<Item = DomainMessage<proto::TransactionCancelledMessage>>,
+    TMultisigStream: Stream<Item = DomainMessage<proto::MultisigMessage>>,
     TBackend: TransactionBackend + 'static,
 {
     pub fn new(
@@ -156,6 +209,7 @@ where
         transaction_finalized_stream: TTxFinalizedStream,
         base_node_response_stream: BNResponseStream,
         transaction_cancelled_stream: TTxCancelledStream,
+        multisig_stream: TMultisigStream,
         output_manager_service: OutputManagerHandle,
         outbound_message_service: OutboundMessageRequester,
         connectivity_manager: ConnectivityRequester,
@@ -191,6 +245,7 @@ where
             transaction_finalized_stream: Some(transaction_finalized_stream),
             base_node_response_stream: Some(base_node_response_stream),
             transaction_cancelled_stream: Some(transaction_cancelled_stream),
+            multisig_stream: Some(multisig_stream),
             request_stream: Some(request_stream),
             event_publisher,
             node_identity,
@@ -199,6 +254,7 @@ where
             pending_transaction_reply_senders: HashMap::new(),
             base_node_response_senders: HashMap::new(),
             send_transaction_cancellation_senders: HashMap::new(),
+            send_transaction_resend_senders: HashMap::new(),
             finalized_transaction_senders: HashMap::new(),
             receiver_transaction_cancellation_senders: HashMap::new(),
             active_transaction_broadcast_protocols: HashSet::new(),
@@ -206,6 +262,10 @@ where
             timeout_update_publisher,
             base_node_update_publisher,
             power_mode: PowerMode::Normal,
+            multisig_funding_sessions: HashMap::new(),
+            multisig_spending_sessions: HashMap::new(),
+            multisig_cosigning_sessions: HashMap::new(),
+            multisig_approvals: Vec::new(),
         }
     }
 
@@ -247,6 +307,12 @@ where
             .expect("Transaction Service initialized without transaction_cancelled_stream")
             .fuse();
         pin_mut!(transaction_cancelled_stream);
+        let multisig_stream = self
+            .multisig_stream
+            .take()
+            .expect("Transaction Service initialized without multisig_stream")
+            .fuse();
+        pin_mut!(multisig_stream);
 
         let mut shutdown = self.resources.shutdown_signal.clone();
 
@@ -342,7 +408,11 @@ where
                     let (origin_public_key, inner_msg) = msg.clone().into_origin_and_inner();
                     trace!(target: LOG_TARGET, "Handling Transaction Finalized Message, Trace: {}",
                     msg.dht_header.message_tag.as_value());
-                    let result = self.accept_finalized_transaction(origin_public_key, inner_msg, ).await;
+                    let result = self.accept_finalized_transaction(
+                        origin_public_key,
+                        inner_msg,
+                        &mut transaction_broadcast_protocol_handles,
+                    ).await;
 
                     match result {
                         Err(TransactionServiceError::TransactionDoesNotExistError) => {
@@ -380,6 +450,25 @@ where
                         warn!(target: LOG_TARGET, "Error handing Transaction Cancelled Message: {:?}", e);
                     }
                 }
+                // Incoming multisig session messages from the Comms layer
+                msg = multisig_stream.select_next_some() => {
+                    let (origin_public_key, inner_msg) = msg.clone().into_origin_and_inner();
+                    trace!(target: LOG_TARGET, "Handling Multisig message, Trace: {}", msg.dht_header.message_tag);
+                    match self.handle_multisig_message(
+                        origin_public_key,
+                        inner_msg,
+                        &mut transaction_broadcast_protocol_handles,
+                    ).await {
+                        Err(TransactionServiceError::MultisigSessionNotFound(session_id)) => {
+                            trace!(target: LOG_TARGET, "Unable to handle incoming Multisig message for session {} as \
+                            it does not exist. This usually means the message was a repeated message from Store and \
+                            Forward, Trace: {}", session_id, msg.dht_header.message_tag);
+                        },
+                        Err(e) => warn!(target: LOG_TARGET, "Error handling Multisig message: {:?}, Trace: {}", e,
+                            msg.dht_header.message_tag),
+                        Ok(_) => (),
+                    }
+                }
                 join_result = send_transaction_protocol_handles.select_next_some() => {
                     trace!(target: LOG_TARGET, "Send Protocol for Transaction has ended with result {:?}", join_result);
                     match join_result {
@@ -458,6 +547,46 @@ where
             TransactionServiceRequest::SendTransaction((dest_pubkey, amount, fee_per_gram, message)) => self
                 .send_transaction(
                     dest_pubkey,
+                    None,
+                    None,
+                    amount,
+                    fee_per_gram,
+                    message,
+                    send_transaction_join_handles,
+                    transaction_broadcast_join_handles,
+                )
+                .await
+                .map(TransactionServiceResponse::TransactionSent),
+            TransactionServiceRequest::SendTransactionWithInputs((
+                dest_pubkey,
+                inputs,
+                amount,
+                fee_per_gram,
+                message,
+            )) => self
+                .send_transaction(
+                    dest_pubkey,
+                    Some(inputs),
+                    None,
+                    amount,
+                    fee_per_gram,
+                    message,
+                    send_transaction_join_handles,
+                    transaction_broadcast_join_handles,
+                )
+                .await
+                .map(TransactionServiceResponse::TransactionSent),
+            TransactionServiceRequest::SendTransactionWithStrategy((
+                dest_pubkey,
+                policy,
+                amount,
+                fee_per_gram,
+                message,
+            )) => self
+                .send_transaction(
+                    dest_pubkey,
+                    None,
+                    Some(policy),
                     amount,
                     fee_per_gram,
                     message,
@@ -466,10 +595,132 @@ where
                 )
                 .await
                 .map(TransactionServiceResponse::TransactionSent),
+            TransactionServiceRequest::SendTransactionBatch((recipients, fee_per_gram, message)) => self
+                .send_transaction_batch(
+                    recipients,
+                    fee_per_gram,
+                    message,
+                    send_transaction_join_handles,
+                    transaction_broadcast_join_handles,
+                )
+                .await
+                .map(TransactionServiceResponse::TransactionsSent),
+            TransactionServiceRequest::BurnTari((amount, fee_per_gram, message)) => self
+                .burn_tari(amount, fee_per_gram, message, transaction_broadcast_join_handles)
+                .await
+                .map(TransactionServiceResponse::TransactionSent),
+            TransactionServiceRequest::SendOneSidedTransaction((dest_pubkey, amount, fee_per_gram, message)) => self
+                .send_one_sided_transaction(
+                    dest_pubkey,
+                    amount,
+                    fee_per_gram,
+                    message,
+                    transaction_broadcast_join_handles,
+                )
+                .await
+                .map(TransactionServiceResponse::TransactionSent),
+            TransactionServiceRequest::SubmitOneSidedTransaction((
+                tx_id,
+                dest_pubkey,
+                transaction,
+                amount,
+                message,
+            )) => self
+                .submit_one_sided_transaction(
+                    tx_id,
+                    dest_pubkey,
+                    *transaction,
+                    amount,
+                    None,
+                    message,
+                    transaction_broadcast_join_handles,
+                )
+                .await
+                .map(TransactionServiceResponse::TransactionSent),
+            TransactionServiceRequest::ClaimOneSidedPayment((commitment, fee_per_gram, message)) => self
+                .claim_one_sided_payment(commitment, fee_per_gram, message, transaction_broadcast_join_handles)
+                .await
+                .map(TransactionServiceResponse::TransactionSent),
+            TransactionServiceRequest::LockHtlc((
+                dest_pubkey,
+                claim_public_key,
+                amount,
+                hash_lock,
+                refund_height,
+                fee_per_gram,
+                message,
+            )) => self
+                .lock_htlc(
+                    dest_pubkey,
+                    claim_public_key,
+                    amount,
+                    hash_lock,
+                    refund_height,
+                    fee_per_gram,
+                    message,
+                    transaction_broadcast_join_handles,
+                )
+                .await
+                .map(TransactionServiceResponse::TransactionSent),
+            TransactionServiceRequest::ClaimHtlc((commitment, preimage, fee_per_gram, message)) => self
+                .spend_htlc(
+                    commitment,
+                    Some(preimage),
+                    fee_per_gram,
+                    message,
+                    transaction_broadcast_join_handles,
+                )
+                .await
+                .map(TransactionServiceResponse::TransactionSent),
+            TransactionServiceRequest::RefundHtlc((commitment, fee_per_gram, message)) => self
+                .spend_htlc(
+                    commitment,
+                    None,
+                    fee_per_gram,
+                    message,
+                    transaction_broadcast_join_handles,
+                )
+                .await
+                .map(TransactionServiceResponse::TransactionSent),
+            TransactionServiceRequest::FundMultisig((amount, threshold, members, fee_per_gram, message)) => self
+                .fund_multisig(amount, threshold, members, fee_per_gram, message)
+                .await
+                .map(TransactionServiceResponse::MultisigSessionStarted),
+            TransactionServiceRequest::SpendMultisig((
+                commitment,
+                amount,
+                dest_pubkey,
+                cosigners,
+                fee_per_gram,
+                message,
+            )) => self
+                .spend_multisig(
+                    commitment,
+                    amount,
+                    dest_pubkey,
+                    cosigners,
+                    fee_per_gram,
+                    message,
+                    transaction_broadcast_join_handles,
+                )
+                .await
+                .map(TransactionServiceResponse::MultisigSessionStarted),
+            TransactionServiceRequest::CosignMultisig((commitment, amount, dest_pubkey)) => self
+                .cosign_multisig(commitment, amount, dest_pubkey)
+                .await
+                .map(|_| TransactionServiceResponse::MultisigCosignApproved),
+            TransactionServiceRequest::CancelMultisigSession(session_id) => self
+                .cancel_multisig_session(session_id)
+                .await
+                .map(|_| TransactionServiceResponse::MultisigSessionCancelled),
             TransactionServiceRequest::CancelTransaction(tx_id) => self
                 .cancel_transaction(tx_id)
                 .await
                 .map(|_| TransactionServiceResponse::TransactionCancelled),
+            TransactionServiceRequest::RetryTransaction(tx_id) => self
+                .retry_transaction(tx_id, send_transaction_join_handles, transaction_broadcast_join_handles)
+                .await
+                .map(TransactionServiceResponse::TransactionRetried),
             TransactionServiceRequest::GetPendingInboundTransactions => {
                 Ok(TransactionServiceResponse::PendingInboundTransactions(
                     self.db.get_pending_inbound_transactions().await?,
@@ -519,8 +770,8 @@ where
                 .submit_transaction(transaction_broadcast_join_handles, tx_id, tx, fee, amount, message)
                 .await
                 .map(|_| TransactionServiceResponse::TransactionSubmitted),
-            TransactionServiceRequest::GenerateCoinbaseTransaction(reward, fees, block_height) => self
-                .generate_coinbase_transaction(reward, fees, block_height, coinbase_monitoring_join_handles)
+            TransactionServiceRequest::GenerateCoinbaseTransaction(reward, fees, block_height, extra) => self
+                .generate_coinbase_transaction(reward, fees, block_height, extra, coinbase_monitoring_join_handles)
                 .await
                 .map(|tx| TransactionServiceResponse::CoinbaseTransactionGenerated(Box::new(tx))),
             #[cfg(feature = "test_harness")]
@@ -588,89 +839,1309 @@ where
                 self.resources.config.num_confirmations_required = number;
                 Ok(TransactionServiceResponse::NumConfirmationsSet)
             },
-            TransactionServiceRequest::ValidateTransactions(retry_strategy) => self
-                .start_transaction_validation_protocol(retry_strategy, transaction_validation_join_handles)
-                .await
-                .map(TransactionServiceResponse::ValidationStarted),
-        }
+            TransactionServiceRequest::SetTransactionResendPolicy(period, limit) => {
+                self.resources.config.transaction_resend_period = period;
+                self.resources.config.transaction_resend_limit = limit;
+                Ok(TransactionServiceResponse::TransactionResendPolicySet)
+            },
+            TransactionServiceRequest::SetBroadcastRetryBackoff(factor, max_delay) => {
+                self.resources.config.broadcast_retry_backoff_factor = factor.max(1.0);
+                self.resources.config.broadcast_retry_max_delay = max_delay;
+                Ok(TransactionServiceResponse::BroadcastRetryBackoffSet)
+            },
+            TransactionServiceRequest::SetTransactionRoutingMechanism(mechanism) => {
+                self.resources.config.transaction_routing_mechanism = mechanism;
+                Ok(TransactionServiceResponse::TransactionRoutingMechanismSet)
+            },
+            TransactionServiceRequest::ValidateTransactions(retry_strategy) => self
+                .start_transaction_validation_protocol(retry_strategy, transaction_validation_join_handles)
+                .await
+                .map(TransactionServiceResponse::ValidationStarted),
+            TransactionServiceRequest::GetTransactionLabels(tx_id) => Ok(
+                TransactionServiceResponse::TransactionLabels(self.db.get_transaction_labels(tx_id).await?),
+            ),
+            TransactionServiceRequest::GetAllTransactionLabels => Ok(TransactionServiceResponse::AllTransactionLabels(
+                self.db.get_all_transaction_labels().await?,
+            )),
+            TransactionServiceRequest::SetTransactionLabels(tx_id, labels) => self
+                .set_transaction_labels(tx_id, labels)
+                .await
+                .map(|_| TransactionServiceResponse::TransactionLabelsSet),
+            TransactionServiceRequest::EstimateFee(amount, num_outputs, fee_per_gram) => {
+                Ok(TransactionServiceResponse::FeeEstimate(
+                    self.output_manager_service
+                        .estimate_fee(amount, num_outputs, fee_per_gram)
+                        .await?,
+                ))
+            },
+        }
+    }
+
+    /// Sends a new transaction to a recipient
+    /// # Arguments
+    /// 'dest_pubkey': The Comms pubkey of the recipient node
+    /// 'inputs': The commitments of the outputs to spend, or None to let the output manager select them
+    /// 'policy': The UTXO selection strategy the output manager uses, or None for the configured one
+    /// 'amount': The amount of Tari to send to the recipient
+    /// 'fee_per_gram': The amount of fee per transaction gram to be included in transaction
+    pub async fn send_transaction(
+        &mut self,
+        dest_pubkey: CommsPublicKey,
+        inputs: Option<Vec<Commitment>>,
+        policy: Option<UtxoSelectionPolicy>,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+        join_handles: &mut FuturesUnordered<JoinHandle<Result<u64, TransactionServiceProtocolError>>>,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<u64, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<TxId, TransactionServiceError>
+    {
+        // If we're paying ourselves, let's complete and submit the transaction immediately
+        if self.node_identity.public_key() == &dest_pubkey {
+            if inputs.is_some() {
+                return Err(TransactionServiceError::SelectedOutputsForPayToSelf);
+            }
+            debug!(
+                target: LOG_TARGET,
+                "Received transaction with spend-to-self transaction"
+            );
+
+            let (tx_id, fee, transaction) = self
+                .output_manager_service
+                .create_pay_to_self_transaction(amount, fee_per_gram, None, message.clone())
+                .await?;
+
+            // Notify that the transaction was successfully resolved.
+            let _ = self
+                .event_publisher
+                .send(Arc::new(TransactionEvent::TransactionCompletedImmediately(tx_id)));
+
+            self.submit_transaction(
+                transaction_broadcast_join_handles,
+                tx_id,
+                transaction,
+                fee,
+                amount,
+                message,
+            )
+            .await?;
+
+            return Ok(tx_id);
+        }
+
+        let sender_protocol = match (inputs, policy) {
+            (Some(inputs), _) => {
+                self.output_manager_service
+                    .prepare_transaction_to_send_with_inputs(inputs, amount, fee_per_gram, None, message.clone())
+                    .await?
+            },
+            (None, Some(policy)) => {
+                self.output_manager_service
+                    .prepare_transaction_to_send_with_strategy(policy, amount, fee_per_gram, None, message.clone())
+                    .await?
+            },
+            (None, None) => {
+                self.output_manager_service
+                    .prepare_transaction_to_send(amount, fee_per_gram, None, message.clone())
+                    .await?
+            },
+        };
+
+        let tx_id = sender_protocol.get_tx_id()?;
+
+        let (tx_reply_sender, tx_reply_receiver) = mpsc::channel(100);
+        let (cancellation_sender, cancellation_receiver) = oneshot::channel();
+        self.pending_transaction_reply_senders.insert(tx_id, tx_reply_sender);
+
+        self.send_transaction_cancellation_senders
+            .insert(tx_id, cancellation_sender);
+        let (resend_sender, resend_receiver) = mpsc::channel(1);
+        self.send_transaction_resend_senders.insert(tx_id, resend_sender);
+        let protocol = TransactionSendProtocol::new(
+            tx_id,
+            self.resources.clone(),
+            tx_reply_receiver,
+            cancellation_receiver,
+            resend_receiver,
+            dest_pubkey,
+            amount,
+            message,
+            sender_protocol,
+            TransactionSendProtocolStage::Initial,
+        );
+
+        let join_handle = tokio::spawn(protocol.execute());
+        join_handles.push(join_handle);
+
+        Ok(tx_id)
+    }
+
+    /// Sends a single transaction that pays each of the recipients in the batch, returning the TxIds of the recipients
+    /// in the same order. The transaction has a kernel for each recipient, so that every recipient can complete its
+    /// part with the standard single round protocol, while the inputs and the change are shared. The output manager
+    /// selects enough inputs to cover the total amount and the fee of every kernel before anything is sent.
+    /// # Arguments
+    /// 'recipients': The public key and amount of each recipient
+    /// 'fee_per_gram': The amount of fee per transaction gram to be included in transaction
+    /// 'message': The message that is sent to every recipient
+    pub async fn send_transaction_batch(
+        &mut self,
+        recipients: Vec<(CommsPublicKey, MicroTari)>,
+        fee_per_gram: MicroTari,
+        message: String,
+        join_handles: &mut FuturesUnordered<JoinHandle<Result<u64, TransactionServiceProtocolError>>>,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<u64, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<Vec<TxId>, TransactionServiceError>
+    {
+        if recipients.is_empty() {
+            return Err(TransactionServiceError::EmptyRecipientBatch);
+        }
+        if recipients.len() == 1 {
+            let (dest_pubkey, amount) = recipients[0].clone();
+            let tx_id = self
+                .send_transaction(
+                    dest_pubkey,
+                    None,
+                    None,
+                    amount,
+                    fee_per_gram,
+                    message,
+                    join_handles,
+                    transaction_broadcast_join_handles,
+                )
+                .await?;
+            return Ok(vec![tx_id]);
+        }
+        if recipients
+            .iter()
+            .any(|(dest_pubkey, _)| self.node_identity.public_key() == dest_pubkey)
+        {
+            return Err(TransactionServiceError::PayToSelfInBatch);
+        }
+
+        let amounts = recipients.iter().map(|(_, amount)| *amount).collect();
+        let sender_protocol = self
+            .output_manager_service
+            .prepare_transaction_to_send_to_many(amounts, fee_per_gram, None, message.clone())
+            .await?;
+
+        let tx_ids = sender_protocol.get_tx_ids()?;
+        let tx_id = tx_ids[0];
+
+        // The replies of all the recipients are handled by the one protocol
+        let (tx_reply_sender, tx_reply_receiver) = mpsc::channel(100);
+        for id in tx_ids.iter() {
+            self.pending_transaction_reply_senders
+                .insert(*id, tx_reply_sender.clone());
+        }
+        let (cancellation_sender, cancellation_receiver) = oneshot::channel();
+        self.send_transaction_cancellation_senders
+            .insert(tx_id, cancellation_sender);
+        let (resend_sender, resend_receiver) = mpsc::channel(1);
+        self.send_transaction_resend_senders.insert(tx_id, resend_sender);
+        let protocol = TransactionSendProtocol::new_to_many(
+            tx_id,
+            self.resources.clone(),
+            tx_reply_receiver,
+            cancellation_receiver,
+            resend_receiver,
+            recipients,
+            message,
+            sender_protocol,
+            TransactionSendProtocolStage::Initial,
+        );
+
+        let join_handle = tokio::spawn(protocol.execute());
+        join_handles.push(join_handle);
+        debug!(
+            target: LOG_TARGET,
+            "Transaction (TxId: {}) sent to {} recipients: {:?}",
+            tx_id,
+            tx_ids.len(),
+            tx_ids
+        );
+
+        Ok(tx_ids)
+    }
+
+    /// Creates a transaction that permanently destroys funds and submits it immediately, as no other party is involved
+    /// # Arguments
+    /// 'amount': The amount of Tari to burn
+    /// 'fee_per_gram': The amount of fee per transaction gram to be included in transaction
+    pub async fn burn_tari(
+        &mut self,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<u64, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<TxId, TransactionServiceError>
+    {
+        let (tx_id, fee, transaction) = self
+            .output_manager_service
+            .create_burn_transaction(amount, fee_per_gram, message.clone())
+            .await?;
+
+        let _ = self
+            .event_publisher
+            .send(Arc::new(TransactionEvent::TransactionCompletedImmediately(tx_id)));
+
+        self.submit_transaction(
+            transaction_broadcast_join_handles,
+            tx_id,
+            transaction,
+            fee,
+            amount,
+            message,
+        )
+        .await?;
+
+        Ok(tx_id)
+    }
+
+    /// Pays the recipient without waiting for their wallet to reply. The whole transaction, including the recipient's
+    /// output, is built here and broadcast straight away. The keys of the output are derived from the recipient's
+    /// public key, so only the transaction is sent to the recipient, who finds its output in it with its own secret key
+    /// and claims it once it is mined.
+    /// # Arguments
+    /// 'dest_pubkey': The Comms pubkey of the recipient node
+    /// 'amount': The amount of Tari to send to the recipient
+    /// 'fee_per_gram': The amount of fee per transaction gram to be included in transaction
+    pub async fn send_one_sided_transaction(
+        &mut self,
+        dest_pubkey: CommsPublicKey,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<u64, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<TxId, TransactionServiceError>
+    {
+        if self.node_identity.public_key() == &dest_pubkey {
+            return Err(TransactionServiceError::OneSidedPaymentToSelf);
+        }
+
+        let (tx_id, _, transaction, output) = self
+            .output_manager_service
+            .create_one_sided_transaction(dest_pubkey.clone(), amount, fee_per_gram, None, message.clone())
+            .await?;
+
+        self.submit_one_sided_transaction(
+            tx_id,
+            dest_pubkey,
+            transaction,
+            output.value,
+            None,
+            message,
+            transaction_broadcast_join_handles,
+        )
+        .await
+    }
+
+    /// Record a finalized one-sided transaction paying `amount` to the recipient, send the transaction to them and
+    /// broadcast it. The transaction may have been built by another wallet that owns its inputs. Only an output with a
+    /// hash time lock or multisig lock, which the recipient cannot derive, is sent along as `locked_output`.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn submit_one_sided_transaction(
+        &mut self,
+        tx_id: TxId,
+        dest_pubkey: CommsPublicKey,
+        transaction: Transaction,
+        amount: MicroTari,
+        locked_output: Option<UnblindedOutput>,
+        message: String,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<u64, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<TxId, TransactionServiceError>
+    {
+        if self.node_identity.public_key() == &dest_pubkey {
+            return Err(TransactionServiceError::OneSidedPaymentToSelf);
+        }
+        let fee = transaction.body.get_total_fee();
+
+        self.db
+            .insert_completed_transaction(
+                tx_id,
+                CompletedTransaction::new(
+                    tx_id,
+                    self.node_identity.public_key().clone(),
+                    dest_pubkey.clone(),
+                    amount,
+                    fee,
+                    transaction.clone(),
+                    TransactionStatus::Completed,
+                    message.clone(),
+                    Utc::now().naive_utc(),
+                    TransactionDirection::Outbound,
+                    None,
+                ),
+            )
+            .await?;
+
+        let _ = self
+            .event_publisher
+            .send(Arc::new(TransactionEvent::TransactionCompletedImmediately(tx_id)));
+
+        tokio::spawn(send_one_sided_payment_message(
+            tx_id,
+            transaction,
+            locked_output,
+            message,
+            dest_pubkey,
+            self.resources.outbound_message_service.clone(),
+            self.resources.config.direct_send_timeout,
+            self.resources.config.transaction_routing_mechanism,
+        ));
+
+        self.complete_send_transaction_protocol(Ok(tx_id), transaction_broadcast_join_handles)
+            .await;
+
+        Ok(tx_id)
+    }
+
+    /// Locks `amount` in a hash time-locked output for an atomic swap and sends it to `dest_pubkey` like a one-sided
+    /// payment. `dest_pubkey` can claim the output with the preimage of `hash_lock` and its HTLC key
+    /// `claim_public_key`, otherwise this wallet can refund it with its own HTLC key once `refund_height` is reached.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn lock_htlc(
+        &mut self,
+        dest_pubkey: CommsPublicKey,
+        claim_public_key: PublicKey,
+        amount: MicroTari,
+        hash_lock: [u8; 32],
+        refund_height: u64,
+        fee_per_gram: MicroTari,
+        message: String,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<u64, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<TxId, TransactionServiceError>
+    {
+        if self.node_identity.public_key() == &dest_pubkey {
+            return Err(TransactionServiceError::OneSidedPaymentToSelf);
+        }
+
+        let refund_public_key = self.output_manager_service.get_htlc_public_key().await?;
+        let htlc = HashTimeLock::new(hash_lock, claim_public_key, refund_public_key, refund_height);
+        let (tx_id, _, transaction, output) = self
+            .output_manager_service
+            .create_htlc_transaction(amount, htlc, fee_per_gram, message.clone())
+            .await?;
+
+        self.submit_one_sided_transaction(
+            tx_id,
+            dest_pubkey,
+            transaction,
+            amount,
+            Some(output),
+            message,
+            transaction_broadcast_join_handles,
+        )
+        .await
+    }
+
+    /// Spends the hash time-locked output with the given commitment to this wallet. With a `preimage` the output is
+    /// claimed, which requires this wallet to hold the claim key; without one it is refunded, which requires the refund
+    /// key. The witness is signed with the HTLC key of the wallet and commits to the kernel of the spending
+    /// transaction.
+    pub async fn spend_htlc(
+        &mut self,
+        commitment: Commitment,
+        preimage: Option<Vec<u8>>,
+        fee_per_gram: MicroTari,
+        message: String,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<u64, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<TxId, TransactionServiceError>
+    {
+        let htlc_public_key = self.output_manager_service.get_htlc_public_key().await?;
+        let htlc = self
+            .output_manager_service
+            .get_htlc_outputs()
+            .await?
+            .into_iter()
+            .find(|o| {
+                self.resources
+                    .factories
+                    .commitment
+                    .commit_value(&o.spending_key, o.value.into()) ==
+                    commitment
+            })
+            .and_then(|o| o.features.htlc)
+            .filter(|htlc| {
+                if preimage.is_some() {
+                    htlc.claim_public_key == htlc_public_key
+                } else {
+                    htlc.refund_public_key == htlc_public_key
+                }
+            })
+            .ok_or_else(|| TransactionServiceError::HtlcNotFound(commitment.to_hex()))?;
+        if let Some(preimage) = &preimage {
+            htlc.check_preimage(preimage)?;
+        }
+
+        let mut stp = self
+            .output_manager_service
+            .prepare_htlc_spend(commitment.clone(), fee_per_gram, message.clone())
+            .await?;
+        let tx_id = stp.get_tx_id()?;
+        let amount = stp.get_amount_to_self()?;
+        let fee = stp.get_fee_amount()?;
+
+        let result = match stp.get_kernel_excess() {
+            Ok(kernel_excess) => self
+                .output_manager_service
+                .sign_htlc_witness(HtlcSigningRequest {
+                    htlc,
+                    preimage,
+                    commitment: commitment.clone(),
+                    kernel_excess,
+                })
+                .await
+                .map_err(TransactionServiceError::from)
+                .and_then(|witness| {
+                    stp.add_htlc_witness(&commitment, witness)?;
+                    stp.finalize(KernelFeatures::empty(), &self.resources.factories)?;
+                    Ok(stp.take_transaction()?)
+                }),
+            Err(e) => Err(e.into()),
+        };
+        let tx = match result {
+            Ok(tx) => tx,
+            Err(e) => {
+                self.output_manager_service.cancel_transaction(tx_id).await?;
+                return Err(e);
+            },
+        };
+
+        self.submit_transaction(transaction_broadcast_join_handles, tx_id, tx, fee, amount, message)
+            .await?;
+
+        Ok(tx_id)
+    }
+
+    /// Claims the one-sided payment output with the given commitment by spending it to this wallet. The witness is
+    /// signed with the one-time key that the output is locked to, which is derived from the node identity key.
+    pub async fn claim_one_sided_payment(
+        &mut self,
+        commitment: Commitment,
+        fee_per_gram: MicroTari,
+        message: String,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<u64, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<TxId, TransactionServiceError>
+    {
+        let secret_key = self.node_identity.secret_key().clone();
+        let script = self
+            .output_manager_service
+            .get_unspent_outputs()
+            .await?
+            .into_iter()
+            .find(|o| {
+                self.resources
+                    .factories
+                    .commitment
+                    .commit_value(&o.spending_key, o.value.into()) ==
+                    commitment
+            })
+            .and_then(|o| o.features.script)
+            .ok_or_else(|| TransactionServiceError::OneSidedPaymentNotFound(commitment.to_hex()))?;
+        let payment = OneSidedPayment::from_script(&script, &secret_key)?;
+
+        let mut stp = self
+            .output_manager_service
+            .prepare_one_sided_payment_claim(commitment.clone(), fee_per_gram, message.clone())
+            .await?;
+        let tx_id = stp.get_tx_id()?;
+        let amount = stp.get_amount_to_self()?;
+        let fee = stp.get_fee_amount()?;
+
+        let nonce = PrivateKey::random(&mut OsRng);
+        let result = stp
+            .get_kernel_excess()
+            .map_err(TransactionServiceError::from)
+            .and_then(|excess| {
+                let signature = script.sign(payment.one_time_secret_key(&secret_key), nonce, &commitment, &excess)?;
+                stp.add_script_witness(&commitment, ScriptWitness {
+                    kernel_excess: excess,
+                    stack: vec![StackItem::Signature(signature)],
+                })?;
+                stp.finalize(KernelFeatures::empty(), &self.resources.factories)?;
+                Ok(stp.take_transaction()?)
+            });
+        let tx = match result {
+            Ok(tx) => tx,
+            Err(e) => {
+                self.output_manager_service.cancel_transaction(tx_id).await?;
+                return Err(e);
+            },
+        };
+
+        self.submit_transaction(transaction_broadcast_join_handles, tx_id, tx, fee, amount, message)
+            .await?;
+
+        Ok(tx_id)
+    }
+
+    /// Start funding a multisig lock shared by this wallet and the `members`, by asking each member for its multisig
+    /// public key. The lock is funded once all of them have replied, see `accept_multisig_public_key`.
+    pub async fn fund_multisig(
+        &mut self,
+        amount: MicroTari,
+        threshold: u8,
+        members: Vec<CommsPublicKey>,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<u64, TransactionServiceError>
+    {
+        let participants = members.len() + 1;
+        if participants > MAX_MULTISIG_PARTICIPANTS {
+            return Err(MultisigError::TooManyParticipants(participants).into());
+        }
+        if threshold == 0 || threshold as usize > participants {
+            return Err(MultisigError::InvalidThreshold {
+                threshold,
+                participants,
+            }
+            .into());
+        }
+        let unique_members = members.iter().collect::<HashSet<_>>();
+        if members.is_empty() ||
+            unique_members.len() != members.len() ||
+            unique_members.contains(self.node_identity.public_key())
+        {
+            return Err(MultisigError::InvalidParticipants.into());
+        }
+
+        let public_key = self.output_manager_service.get_multisig_public_key().await?;
+        let session_id = OsRng.next_u64();
+        for member in &members {
+            tokio::spawn(send_multisig_message(
+                MultisigSessionMessage {
+                    session_id,
+                    message: MultisigMessage::KeyRequest,
+                },
+                member.clone(),
+                self.resources.outbound_message_service.clone(),
+            ));
+        }
+        self.multisig_funding_sessions
+            .insert(session_id, MultisigFundingSession {
+                amount,
+                threshold,
+                fee_per_gram,
+                message,
+                public_key,
+                members: members.into_iter().map(|member| (member, None)).collect(),
+            });
+
+        Ok(session_id)
+    }
+
+    /// Start spending the multisig output with the given commitment, by preparing the spending transaction and asking
+    /// the `cosigners` to sign it. Exactly `threshold - 1` co-signers are needed, as this wallet signs as well.
+    pub async fn spend_multisig(
+        &mut self,
+        commitment: Commitment,
+        amount: MicroTari,
+        dest_pubkey: CommsPublicKey,
+        cosigners: Vec<CommsPublicKey>,
+        fee_per_gram: MicroTari,
+        message: String,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<u64, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<u64, TransactionServiceError>
+    {
+        if self.node_identity.public_key() == &dest_pubkey {
+            return Err(TransactionServiceError::OneSidedPaymentToSelf);
+        }
+        let lock = self
+            .output_manager_service
+            .get_multisig_outputs()
+            .await?
+            .into_iter()
+            .find(|o| {
+                self.resources
+                    .factories
+                    .commitment
+                    .commit_value(&o.spending_key, o.value.into()) ==
+                    commitment
+            })
+            .and_then(|o| o.features.multisig)
+            .ok_or_else(|| TransactionServiceError::MultisigOutputNotFound(commitment.to_hex()))?;
+        let public_key = self.output_manager_service.get_multisig_public_key().await?;
+        if lock.index_of(&public_key).is_none() {
+            return Err(MultisigError::NotAParticipant.into());
+        }
+        if cosigners.len() + 1 != lock.threshold as usize {
+            return Err(MultisigError::InvalidSignerCount {
+                expected: lock.threshold as usize - 1,
+                got: cosigners.len(),
+            }
+            .into());
+        }
+        let unique_cosigners = cosigners.iter().collect::<HashSet<_>>();
+        if unique_cosigners.len() != cosigners.len() || unique_cosigners.contains(self.node_identity.public_key()) {
+            return Err(MultisigError::InvalidSigners.into());
+        }
+
+        let spend = self
+            .output_manager_service
+            .prepare_multisig_spend(
+                commitment.clone(),
+                dest_pubkey.clone(),
+                amount,
+                fee_per_gram,
+                message.clone(),
+            )
+            .await?;
+        let tx_id = spend.stp.get_tx_id()?;
+        let session_id = OsRng.next_u64();
+        let request = match spend.stp.get_kernel_excess().and_then(|kernel_excess| {
+            Ok(MultisigSignRequest {
+                lock,
+                commitment,
+                kernel_excess,
+                amount,
+                destination: dest_pubkey,
+                fee: spend.stp.get_fee_amount()?,
+                message,
+                ephemeral_secret_key: spend.ephemeral_secret_key.clone(),
+                change: spend
+                    .change_output
+                    .as_ref()
+                    .map(|change| (change.value, change.spending_key.clone())),
+                offset: spend.offset.clone(),
+            })
+        }) {
+            Ok(request) => request,
+            Err(e) => {
+                self.output_manager_service.cancel_transaction(tx_id).await?;
+                return Err(e.into());
+            },
+        };
+        let public_nonce = match self.output_manager_service.create_multisig_nonce(session_id).await {
+            Ok(nonce) => nonce,
+            Err(e) => {
+                self.output_manager_service.cancel_transaction(tx_id).await?;
+                return Err(e.into());
+            },
+        };
+
+        for cosigner in &cosigners {
+            tokio::spawn(send_multisig_message(
+                MultisigSessionMessage {
+                    session_id,
+                    message: MultisigMessage::SignRequest(Box::new(request.clone())),
+                },
+                cosigner.clone(),
+                self.resources.outbound_message_service.clone(),
+            ));
+        }
+        let no_cosigners = cosigners.is_empty();
+        self.multisig_spending_sessions
+            .insert(session_id, MultisigSpendingSession {
+                spend,
+                request,
+                cosigners,
+                public_key,
+                public_nonce,
+                nonce_commitments: HashMap::new(),
+                nonces: HashMap::new(),
+                signing_round: None,
+                partial_signatures: HashMap::new(),
+            });
+
+        // A 1-of-n lock is signed by this wallet alone
+        if no_cosigners {
+            self.start_multisig_signing_round(session_id, transaction_broadcast_join_handles)
+                .await?;
+        }
+
+        Ok(session_id)
+    }
+
+    /// Approve co-signing a spend, either for a sign request that has already been received or for the first matching
+    /// one that arrives later
+    pub async fn cosign_multisig(
+        &mut self,
+        commitment: Commitment,
+        amount: MicroTari,
+        dest_pubkey: CommsPublicKey,
+    ) -> Result<(), TransactionServiceError>
+    {
+        let approval = MultisigApproval {
+            commitment,
+            amount,
+            destination: dest_pubkey,
+        };
+        let pending_session = self
+            .multisig_cosigning_sessions
+            .iter()
+            .find(|(_, session)| !session.is_approved() && approval.matches(&session.request))
+            .map(|(session_id, _)| *session_id);
+        match pending_session {
+            Some(session_id) => self.approve_multisig_sign_request(session_id).await,
+            None => {
+                self.multisig_approvals.push(approval);
+                Ok(())
+            },
+        }
+    }
+
+    /// Abandon a multisig session started by this wallet. The outputs of a spend that is still being signed are
+    /// released.
+    pub async fn cancel_multisig_session(&mut self, session_id: u64) -> Result<(), TransactionServiceError> {
+        if self.multisig_funding_sessions.remove(&session_id).is_some() {
+            return Ok(());
+        }
+        let session = self
+            .multisig_spending_sessions
+            .remove(&session_id)
+            .ok_or(TransactionServiceError::MultisigSessionNotFound(session_id))?;
+        self.output_manager_service
+            .cancel_transaction(session.spend.stp.get_tx_id()?)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Handle a message of a multisig session, either one started by this wallet or one it takes part in
+    pub async fn handle_multisig_message(
+        &mut self,
+        source_pubkey: CommsPublicKey,
+        message: proto::MultisigMessage,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<u64, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<(), TransactionServiceError>
+    {
+        let message: MultisigSessionMessage = message
+            .try_into()
+            .map_err(TransactionServiceError::InvalidMessageError)?;
+        let session_id = message.session_id;
+
+        match message.message {
+            MultisigMessage::KeyRequest => {
+                let public_key = self.output_manager_service.get_multisig_public_key().await?;
+                tokio::spawn(send_multisig_message(
+                    MultisigSessionMessage {
+                        session_id,
+                        message: MultisigMessage::PublicKey(public_key),
+                    },
+                    source_pubkey,
+                    self.resources.outbound_message_service.clone(),
+                ));
+                Ok(())
+            },
+            MultisigMessage::PublicKey(public_key) => {
+                self.accept_multisig_public_key(
+                    session_id,
+                    source_pubkey,
+                    public_key,
+                    transaction_broadcast_join_handles,
+                )
+                .await
+            },
+            MultisigMessage::SignRequest(request) => {
+                self.accept_multisig_sign_request(session_id, source_pubkey, *request)
+                    .await
+            },
+            MultisigMessage::NonceCommitment { public_key, commitment } => {
+                let session = self
+                    .multisig_spending_sessions
+                    .get_mut(&session_id)
+                    .ok_or(TransactionServiceError::MultisigSessionNotFound(session_id))?;
+                if session.add_nonce_commitment(session_id, &source_pubkey, public_key, commitment)? {
+                    let commitments = session.all_nonce_commitments();
+                    for cosigner in &session.cosigners {
+                        tokio::spawn(send_multisig_message(
+                            MultisigSessionMessage {
+                                session_id,
+                                message: MultisigMessage::NonceCommitments(commitments.clone()),
+                            },
+                            cosigner.clone(),
+                            self.resources.outbound_message_service.clone(),
+                        ));
+                    }
+                }
+                Ok(())
+            },
+            MultisigMessage::NonceCommitments(commitments) => {
+                self.accept_multisig_nonce_commitments(session_id, source_pubkey, commitments)
+            },
+            MultisigMessage::Nonce {
+                public_key,
+                public_nonce,
+            } => {
+                let session = self
+                    .multisig_spending_sessions
+                    .get_mut(&session_id)
+                    .ok_or(TransactionServiceError::MultisigSessionNotFound(session_id))?;
+                if session.add_nonce(session_id, &source_pubkey, public_key, public_nonce)? {
+                    self.start_multisig_signing_round(session_id, transaction_broadcast_join_handles)
+                        .await?;
+                }
+                Ok(())
+            },
+            MultisigMessage::SigningRound { nonces } => {
+                self.accept_multisig_signing_round(session_id, source_pubkey, nonces)
+                    .await
+            },
+            MultisigMessage::PartialSignature(signature) => {
+                let session = self
+                    .multisig_spending_sessions
+                    .get_mut(&session_id)
+                    .ok_or(TransactionServiceError::MultisigSessionNotFound(session_id))?;
+                match session.add_partial_signature(session_id, &source_pubkey, signature) {
+                    Ok(true) => {
+                        self.complete_multisig_spend(session_id, transaction_broadcast_join_handles)
+                            .await
+                    },
+                    Ok(false) => Ok(()),
+                    // The signers are fixed by now, so the session cannot complete without the misbehaving signer
+                    Err(e @ TransactionServiceError::MultisigError(_)) => {
+                        Err(self.fail_multisig_spend(session_id, e).await)
+                    },
+                    Err(e) => Err(e),
+                }
+            },
+        }
+    }
+
+    /// Record the multisig public key of a member of a lock being funded, and fund the lock once the keys of all
+    /// members are in
+    async fn accept_multisig_public_key(
+        &mut self,
+        session_id: u64,
+        source_pubkey: CommsPublicKey,
+        public_key: PublicKey,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<u64, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<(), TransactionServiceError>
+    {
+        let session = self
+            .multisig_funding_sessions
+            .get_mut(&session_id)
+            .ok_or(TransactionServiceError::MultisigSessionNotFound(session_id))?;
+        let public_keys = match session.add_public_key(session_id, &source_pubkey, public_key)? {
+            Some(public_keys) => public_keys,
+            None => return Ok(()),
+        };
+        let session = self
+            .multisig_funding_sessions
+            .remove(&session_id)
+            .ok_or(TransactionServiceError::MultisigSessionNotFound(session_id))?;
+
+        let result = self
+            .fund_multisig_lock(session, public_keys, transaction_broadcast_join_handles)
+            .await;
+        self.publish_multisig_session_result(session_id, result)
+    }
+
+    /// Pay into an output locked to the participants of a funding session, and send the output to every member like a
+    /// one-sided payment
+    async fn fund_multisig_lock(
+        &mut self,
+        session: MultisigFundingSession,
+        public_keys: Vec<PublicKey>,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<u64, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<TxId, TransactionServiceError>
+    {
+        let lock = MultisigLock::new(session.threshold, public_keys)?;
+        let (tx_id, _, transaction, output) = self
+            .output_manager_service
+            .create_multisig_transaction(session.amount, lock, session.fee_per_gram, session.message.clone())
+            .await?;
+
+        // The transaction is recorded as a payment to the first member, the others are sent the output as well
+        let mut members = session.members.into_iter().map(|(member, _)| member);
+        let first_member = members.next().ok_or(TransactionServiceError::InvalidStateError)?;
+        for member in members {
+            tokio::spawn(send_one_sided_payment_message(
+                tx_id,
+                transaction.clone(),
+                Some(output.clone()),
+                session.message.clone(),
+                member,
+                self.resources.outbound_message_service.clone(),
+                self.resources.config.direct_send_timeout,
+                self.resources.config.transaction_routing_mechanism,
+            ));
+        }
+
+        self.submit_one_sided_transaction(
+            tx_id,
+            first_member,
+            transaction,
+            session.amount,
+            Some(output),
+            session.message,
+            transaction_broadcast_join_handles,
+        )
+        .await
+    }
+
+    /// Record a sign request for a multisig output held by this wallet once its outputs and kernel have been checked,
+    /// and reply with a nonce commitment if the spend has already been approved
+    async fn accept_multisig_sign_request(
+        &mut self,
+        session_id: u64,
+        source_pubkey: CommsPublicKey,
+        request: MultisigSignRequest,
+    ) -> Result<(), TransactionServiceError>
+    {
+        if self.multisig_cosigning_sessions.contains_key(&session_id) {
+            // A repeated message, e.g. received both directly and by Store and Forward
+            return Ok(());
+        }
+        let public_key = self.output_manager_service.get_multisig_public_key().await?;
+        if request.lock.index_of(&public_key).is_none() {
+            return Err(MultisigError::NotAParticipant.into());
+        }
+        let output = self
+            .output_manager_service
+            .get_multisig_outputs()
+            .await?
+            .into_iter()
+            .find(|o| {
+                o.features.multisig.as_ref() == Some(&request.lock) &&
+                    self.resources
+                        .factories
+                        .commitment
+                        .commit_value(&o.spending_key, o.value.into()) ==
+                        request.commitment
+            })
+            .ok_or_else(|| TransactionServiceError::MultisigOutputNotFound(request.commitment.to_hex()))?;
+        request
+            .check_spend(&output, &self.resources.factories)
+            .map_err(|reason| TransactionServiceError::InvalidMultisigMessage(session_id, reason))?;
+
+        info!(
+            target: LOG_TARGET,
+            "Multisig sign request (Session: {}) received to pay {} to {} with a fee of {}",
+            session_id,
+            request.amount,
+            request.destination,
+            request.fee
+        );
+        let approval = self.multisig_approvals.iter().position(|a| a.matches(&request));
+        self.multisig_cosigning_sessions
+            .insert(session_id, MultisigCosigningSession {
+                origin: source_pubkey,
+                request,
+                own_nonce: None,
+                nonce_commitments: None,
+            });
+        let _ = self
+            .event_publisher
+            .send(Arc::new(TransactionEvent::MultisigSignRequestReceived(session_id)));
+
+        if let Some(position) = approval {
+            self.multisig_approvals.remove(position);
+            self.approve_multisig_sign_request(session_id).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Reply to an approved sign request with the multisig public key of this wallet and the commitment to a fresh
+    /// nonce
+    async fn approve_multisig_sign_request(&mut self, session_id: u64) -> Result<(), TransactionServiceError> {
+        let public_key = self.output_manager_service.get_multisig_public_key().await?;
+        let public_nonce = self.output_manager_service.create_multisig_nonce(session_id).await?;
+        let session = self
+            .multisig_cosigning_sessions
+            .get_mut(&session_id)
+            .ok_or(TransactionServiceError::MultisigSessionNotFound(session_id))?;
+        let commitment = nonce_commitment(&public_key, &public_nonce);
+        session.own_nonce = Some((public_key.clone(), public_nonce));
+
+        tokio::spawn(send_multisig_message(
+            MultisigSessionMessage {
+                session_id,
+                message: MultisigMessage::NonceCommitment { public_key, commitment },
+            },
+            session.origin.clone(),
+            self.resources.outbound_message_service.clone(),
+        ));
+
+        Ok(())
+    }
+
+    /// Reveal the nonce of this wallet once the spending wallet has sent the nonce commitments of all the signers
+    fn accept_multisig_nonce_commitments(
+        &mut self,
+        session_id: u64,
+        source_pubkey: CommsPublicKey,
+        commitments: Vec<(PublicKey, Vec<u8>)>,
+    ) -> Result<(), TransactionServiceError>
+    {
+        let session = self
+            .multisig_cosigning_sessions
+            .get_mut(&session_id)
+            .ok_or(TransactionServiceError::MultisigSessionNotFound(session_id))?;
+        if session.origin != source_pubkey {
+            return Err(TransactionServiceError::InvalidMultisigMessage(
+                session_id,
+                "Nonce commitments received from a wallet that did not send the sign request".to_string(),
+            ));
+        }
+        if let Some((public_key, public_nonce)) = session.add_nonce_commitments(session_id, commitments)? {
+            tokio::spawn(send_multisig_message(
+                MultisigSessionMessage {
+                    session_id,
+                    message: MultisigMessage::Nonce {
+                        public_key,
+                        public_nonce,
+                    },
+                },
+                session.origin.clone(),
+                self.resources.outbound_message_service.clone(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Sign an approved spend once the spending wallet has sent the nonces of all the signers, which must match their
+    /// commitments
+    async fn accept_multisig_signing_round(
+        &mut self,
+        session_id: u64,
+        source_pubkey: CommsPublicKey,
+        nonces: Vec<(PublicKey, PublicKey)>,
+    ) -> Result<(), TransactionServiceError>
+    {
+        let (signers, aggregate_nonce) = match self.multisig_cosigning_sessions.get(&session_id) {
+            None => return Err(TransactionServiceError::MultisigSessionNotFound(session_id)),
+            Some(session) if session.origin != source_pubkey => {
+                return Err(TransactionServiceError::InvalidMultisigMessage(
+                    session_id,
+                    "Signing round received from a wallet that did not send the sign request".to_string(),
+                ))
+            },
+            Some(session) if !session.is_approved() => {
+                return Err(TransactionServiceError::InvalidMultisigMessage(
+                    session_id,
+                    "Signing round received for a spend that has not been approved".to_string(),
+                ))
+            },
+            Some(session) => session.check_signing_round(session_id, &nonces)?,
+        };
+        let session = self
+            .multisig_cosigning_sessions
+            .remove(&session_id)
+            .ok_or(TransactionServiceError::MultisigSessionNotFound(session_id))?;
+
+        let signature = self
+            .output_manager_service
+            .sign_multisig(MultisigSigningRequest {
+                session_id,
+                lock: session.request.lock,
+                signers,
+                aggregate_nonce,
+                commitment: session.request.commitment,
+                kernel_excess: session.request.kernel_excess,
+            })
+            .await?;
+        tokio::spawn(send_multisig_message(
+            MultisigSessionMessage {
+                session_id,
+                message: MultisigMessage::PartialSignature(signature),
+            },
+            session.origin,
+            self.resources.outbound_message_service.clone(),
+        ));
+        let _ = self
+            .event_publisher
+            .send(Arc::new(TransactionEvent::MultisigPartialSignatureSent(session_id)));
+
+        Ok(())
     }
 
-    /// Sends a new transaction to a recipient
-    /// # Arguments
-    /// 'dest_pubkey': The Comms pubkey of the recipient node
-    /// 'amount': The amount of Tari to send to the recipient
-    /// 'fee_per_gram': The amount of fee per transaction gram to be included in transaction
-    pub async fn send_transaction(
+    /// Fix the signers and aggregate nonce of a spend once all the nonces are in, and send the nonces to the co-signers
+    async fn start_multisig_signing_round(
         &mut self,
-        dest_pubkey: CommsPublicKey,
-        amount: MicroTari,
-        fee_per_gram: MicroTari,
-        message: String,
-        join_handles: &mut FuturesUnordered<JoinHandle<Result<u64, TransactionServiceProtocolError>>>,
+        session_id: u64,
         transaction_broadcast_join_handles: &mut FuturesUnordered<
             JoinHandle<Result<u64, TransactionServiceProtocolError>>,
         >,
-    ) -> Result<TxId, TransactionServiceError>
+    ) -> Result<(), TransactionServiceError>
     {
-        // If we're paying ourselves, let's complete and submit the transaction immediately
-        if self.node_identity.public_key() == &dest_pubkey {
-            debug!(
-                target: LOG_TARGET,
-                "Received transaction with spend-to-self transaction"
-            );
+        let session = self
+            .multisig_spending_sessions
+            .get_mut(&session_id)
+            .ok_or(TransactionServiceError::MultisigSessionNotFound(session_id))?;
+        let nonces = session.start_signing_round()?;
+        for cosigner in &session.cosigners {
+            tokio::spawn(send_multisig_message(
+                MultisigSessionMessage {
+                    session_id,
+                    message: MultisigMessage::SigningRound { nonces: nonces.clone() },
+                },
+                cosigner.clone(),
+                self.resources.outbound_message_service.clone(),
+            ));
+        }
 
-            let (tx_id, fee, transaction) = self
-                .output_manager_service
-                .create_pay_to_self_transaction(amount, fee_per_gram, None, message.clone())
+        if session.cosigners.is_empty() {
+            self.complete_multisig_spend(session_id, transaction_broadcast_join_handles)
                 .await?;
+        }
 
-            // Notify that the transaction was successfully resolved.
-            let _ = self
-                .event_publisher
-                .send(Arc::new(TransactionEvent::TransactionCompletedImmediately(tx_id)));
-
-            self.submit_transaction(
-                transaction_broadcast_join_handles,
-                tx_id,
-                transaction,
-                fee,
-                amount,
-                message,
-            )
-            .await?;
+        Ok(())
+    }
 
-            return Ok(tx_id);
+    /// Combine the partial signatures of a spend into the witness of its input, and submit the finalized transaction.
+    /// The outputs of the spend are released if it cannot be completed.
+    async fn complete_multisig_spend(
+        &mut self,
+        session_id: u64,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<u64, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<(), TransactionServiceError>
+    {
+        let session = self
+            .multisig_spending_sessions
+            .remove(&session_id)
+            .ok_or(TransactionServiceError::MultisigSessionNotFound(session_id))?;
+        let tx_id = session.spend.stp.get_tx_id()?;
+
+        let result = self
+            .finalize_multisig_spend(session_id, session, transaction_broadcast_join_handles)
+            .await;
+        if result.is_err() {
+            if let Err(e) = self.output_manager_service.cancel_transaction(tx_id).await {
+                warn!(
+                    target: LOG_TARGET,
+                    "Could not cancel multisig spend (TxId: {}): {:?}", tx_id, e
+                );
+            }
         }
+        self.publish_multisig_session_result(session_id, result)
+    }
 
-        let sender_protocol = self
+    async fn finalize_multisig_spend(
+        &mut self,
+        session_id: u64,
+        session: MultisigSpendingSession,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<u64, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<TxId, TransactionServiceError>
+    {
+        let (signers, aggregate_nonce) = session
+            .signing_round
+            .clone()
+            .ok_or(TransactionServiceError::InvalidStateError)?;
+        let own_partial_signature = self
             .output_manager_service
-            .prepare_transaction_to_send(amount, fee_per_gram, None, message.clone())
+            .sign_multisig(MultisigSigningRequest {
+                session_id,
+                lock: session.request.lock.clone(),
+                signers,
+                aggregate_nonce,
+                commitment: session.request.commitment.clone(),
+                kernel_excess: session.request.kernel_excess.clone(),
+            })
             .await?;
+        let witness = session.witness(own_partial_signature)?;
+
+        let MultisigSpendingSession {
+            spend,
+            request,
+            cosigners,
+            ..
+        } = session;
+        let mut stp = spend.stp;
+        stp.add_multisig_witness(&request.commitment, witness)?;
+        stp.finalize(KernelFeatures::empty(), &self.resources.factories)?;
+        let tx_id = stp.get_tx_id()?;
+        let transaction = stp.take_transaction()?;
+
+        // The change is locked to the same participants, so the co-signers are sent it as well. Members that did not
+        // sign only learn of it from the chain. A co-signer that is also the recipient would drop the second message
+        // of the same transaction, so it is only sent the payment.
+        if let Some(change_output) = spend.change_output {
+            for cosigner in cosigners.into_iter().filter(|c| c != &request.destination) {
+                tokio::spawn(send_one_sided_payment_message(
+                    tx_id,
+                    transaction.clone(),
+                    Some(change_output.clone()),
+                    request.message.clone(),
+                    cosigner,
+                    self.resources.outbound_message_service.clone(),
+                    self.resources.config.direct_send_timeout,
+                    self.resources.config.transaction_routing_mechanism,
+                ));
+            }
+        }
 
-        let tx_id = sender_protocol.get_tx_id()?;
-
-        let (tx_reply_sender, tx_reply_receiver) = mpsc::channel(100);
-        let (cancellation_sender, cancellation_receiver) = oneshot::channel();
-        self.pending_transaction_reply_senders.insert(tx_id, tx_reply_sender);
-
-        self.send_transaction_cancellation_senders
-            .insert(tx_id, cancellation_sender);
-        let protocol = TransactionSendProtocol::new(
+        self.submit_one_sided_transaction(
             tx_id,
-            self.resources.clone(),
-            tx_reply_receiver,
-            cancellation_receiver,
-            dest_pubkey,
-            amount,
-            message,
-            sender_protocol,
-            TransactionSendProtocolStage::Initial,
-        );
+            request.destination,
+            transaction,
+            spend.recipient_output.value,
+            None,
+            request.message,
+            transaction_broadcast_join_handles,
+        )
+        .await
+    }
 
-        let join_handle = tokio::spawn(protocol.execute());
-        join_handles.push(join_handle);
+    /// Abandon a spend that cannot be completed, releasing its outputs
+    async fn fail_multisig_spend(
+        &mut self,
+        session_id: u64,
+        error: TransactionServiceError,
+    ) -> TransactionServiceError
+    {
+        if let Some(session) = self.multisig_spending_sessions.remove(&session_id) {
+            if let Ok(tx_id) = session.spend.stp.get_tx_id() {
+                if let Err(e) = self.output_manager_service.cancel_transaction(tx_id).await {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Could not cancel multisig spend (TxId: {}): {:?}", tx_id, e
+                    );
+                }
+            }
+        }
+        let _ = self
+            .event_publisher
+            .send(Arc::new(TransactionEvent::MultisigSessionFailed(
+                session_id,
+                error.to_string(),
+            )));
+        error
+    }
 
-        Ok(tx_id)
+    fn publish_multisig_session_result(
+        &self,
+        session_id: u64,
+        result: Result<TxId, TransactionServiceError>,
+    ) -> Result<(), TransactionServiceError>
+    {
+        let event = match &result {
+            Ok(tx_id) => TransactionEvent::MultisigSessionCompleted(session_id, *tx_id),
+            Err(e) => TransactionEvent::MultisigSessionFailed(session_id, e.to_string()),
+        };
+        let _ = self.event_publisher.send(Arc::new(event));
+        result.map(|_| ())
     }
 
     /// Accept the public reply from a recipient and apply the reply to the relevant transaction protocol
@@ -814,8 +2285,9 @@ where
     {
         match join_result {
             Ok(id) => {
-                let _ = self.pending_transaction_reply_senders.remove(&id);
+                self.remove_send_transaction_reply_senders(id);
                 let _ = self.send_transaction_cancellation_senders.remove(&id);
+                let _ = self.send_transaction_resend_senders.remove(&id);
                 let completed_tx = match self.db.get_completed_transaction(id).await {
                     Ok(v) => v,
                     Err(e) => {
@@ -843,8 +2315,9 @@ where
                 );
             },
             Err(TransactionServiceProtocolError { id, error }) => {
-                let _ = self.pending_transaction_reply_senders.remove(&id);
+                self.remove_send_transaction_reply_senders(id);
                 let _ = self.send_transaction_cancellation_senders.remove(&id);
+                let _ = self.send_transaction_resend_senders.remove(&id);
                 if let TransactionServiceError::Shutdown = error {
                     return;
                 }
@@ -859,45 +2332,67 @@ where
         }
     }
 
-    /// Cancel a pending transaction
+    /// Cancel a pending transaction. An outbound transaction with several recipients is cancelled as a whole, using the
+    /// TxId of any of its recipients.
     async fn cancel_transaction(&mut self, tx_id: TxId) -> Result<(), TransactionServiceError> {
-        self.db.cancel_pending_transaction(tx_id).await.map_err(|e| {
-            warn!(
-                target: LOG_TARGET,
-                "Pending Transaction does not exist and could not be cancelled: {:?}", e
-            );
-            e
-        })?;
+        let tx_ids = match self.db.get_pending_outbound_transaction(tx_id).await {
+            Ok(outbound_tx) => outbound_tx.sender_protocol.get_tx_ids().unwrap_or_else(|_| vec![tx_id]),
+            Err(_) => vec![tx_id],
+        };
+        let tx_id = tx_ids[0];
+
+        for id in tx_ids.iter() {
+            self.db.cancel_pending_transaction(*id).await.map_err(|e| {
+                warn!(
+                    target: LOG_TARGET,
+                    "Pending Transaction does not exist and could not be cancelled: {:?}", e
+                );
+                e
+            })?;
+        }
 
         self.output_manager_service.cancel_transaction(tx_id).await?;
 
         if let Some(cancellation_sender) = self.send_transaction_cancellation_senders.remove(&tx_id) {
             let _ = cancellation_sender.send(());
         }
-        let _ = self.pending_transaction_reply_senders.remove(&tx_id);
+        let _ = self.send_transaction_resend_senders.remove(&tx_id);
+        for id in tx_ids.iter() {
+            let _ = self.pending_transaction_reply_senders.remove(id);
+        }
 
         if let Some(cancellation_sender) = self.receiver_transaction_cancellation_senders.remove(&tx_id) {
             let _ = cancellation_sender.send(());
         }
         let _ = self.finalized_transaction_senders.remove(&tx_id);
 
-        let _ = self
-            .event_publisher
-            .send(Arc::new(TransactionEvent::TransactionCancelled(tx_id)))
-            .map_err(|e| {
-                trace!(
-                    target: LOG_TARGET,
-                    "Error sending event because there are no subscribers: {:?}",
+        for id in tx_ids {
+            let _ = self
+                .event_publisher
+                .send(Arc::new(TransactionEvent::TransactionCancelled(id)))
+                .map_err(|e| {
+                    trace!(
+                        target: LOG_TARGET,
+                        "Error sending event because there are no subscribers: {:?}",
+                        e
+                    );
                     e
-                );
-                e
-            });
+                });
+        }
 
         info!(target: LOG_TARGET, "Pending Transaction (TxId: {}) cancelled", tx_id);
 
         Ok(())
     }
 
+    /// Remove the reply senders of a finished Send Transaction protocol. A transaction with several recipients has a
+    /// sender for each of them, all feeding the one protocol, so those whose protocol has gone are removed as well.
+    fn remove_send_transaction_reply_senders(&mut self, tx_id: TxId) {
+        let _ = self.pending_transaction_reply_senders.remove(&tx_id);
+        self.pending_transaction_reply_senders
+            .retain(|_, sender| !sender.is_closed());
+    }
+
     /// Handle a Transaction Cancelled message received from the Comms layer
     pub async fn handle_transaction_cancelled_message(
         &mut self,
@@ -931,26 +2426,46 @@ where
     ) -> Result<(), TransactionServiceError>
     {
         let outbound_txs = self.db.get_pending_outbound_transactions().await?;
-        for (tx_id, tx) in outbound_txs {
+        for (tx_id, tx) in outbound_txs.iter() {
+            let tx_id = *tx_id;
+            let tx_ids = tx.sender_protocol.get_tx_ids().unwrap_or_else(|_| vec![tx_id]);
+            // A transaction with several recipients has a pending transaction for each, and is restarted from the first
+            if tx_ids[0] != tx_id {
+                continue;
+            }
             if !self.pending_transaction_reply_senders.contains_key(&tx_id) {
                 debug!(
                     target: LOG_TARGET,
                     "Restarting listening for Reply for Pending Outbound Transaction TxId: {}", tx_id
                 );
+                let recipients = tx_ids
+                    .iter()
+                    .map(|id| {
+                        outbound_txs
+                            .get(id)
+                            .map(|t| (t.destination_public_key.clone(), t.amount))
+                            .ok_or(TransactionServiceError::TransactionDoesNotExistError)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
                 let (tx_reply_sender, tx_reply_receiver) = mpsc::channel(100);
                 let (cancellation_sender, cancellation_receiver) = oneshot::channel();
-                self.pending_transaction_reply_senders.insert(tx_id, tx_reply_sender);
+                for id in tx_ids.iter() {
+                    self.pending_transaction_reply_senders
+                        .insert(*id, tx_reply_sender.clone());
+                }
                 self.send_transaction_cancellation_senders
                     .insert(tx_id, cancellation_sender);
-                let protocol = TransactionSendProtocol::new(
+                let (resend_sender, resend_receiver) = mpsc::channel(1);
+                self.send_transaction_resend_senders.insert(tx_id, resend_sender);
+                let protocol = TransactionSendProtocol::new_to_many(
                     tx_id,
                     self.resources.clone(),
                     tx_reply_receiver,
                     cancellation_receiver,
-                    tx.destination_public_key,
-                    tx.amount,
-                    tx.message,
-                    tx.sender_protocol,
+                    resend_receiver,
+                    recipients,
+                    tx.message.clone(),
+                    tx.sender_protocol.clone(),
                     TransactionSendProtocolStage::WaitForReply,
                 );
 
@@ -1080,6 +2595,9 @@ where
         &mut self,
         source_pubkey: CommsPublicKey,
         finalized_transaction: proto::TransactionFinalizedMessage,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<u64, TransactionServiceProtocolError>>,
+        >,
     ) -> Result<(), TransactionServiceError>
     {
         let tx_id = finalized_transaction.tx_id;
@@ -1098,7 +2616,21 @@ where
             })?;
 
         let sender = match self.finalized_transaction_senders.get_mut(&tx_id) {
-            None => return Err(TransactionServiceError::TransactionDoesNotExistError),
+            None => {
+                return match finalized_transaction.one_sided_payment {
+                    Some(payment) => {
+                        self.accept_one_sided_payment(
+                            source_pubkey,
+                            tx_id,
+                            transaction,
+                            payment,
+                            transaction_broadcast_join_handles,
+                        )
+                        .await
+                    },
+                    None => Err(TransactionServiceError::TransactionDoesNotExistError),
+                };
+            },
             Some(s) => s,
         };
 
@@ -1110,6 +2642,128 @@ where
         Ok(())
     }
 
+    /// Add the output of a one-sided payment to the wallet, once it is verified to be paid by the transaction, and
+    /// monitor the transaction until it is mined
+    async fn accept_one_sided_payment(
+        &mut self,
+        source_pubkey: CommsPublicKey,
+        tx_id: TxId,
+        transaction: Transaction,
+        payment: proto::OneSidedPayment,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<u64, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<(), TransactionServiceError>
+    {
+        // The payment is sent both directly and by Store and Forward, so it is usually received more than once
+        if self.db.transaction_exists(tx_id).await? {
+            debug!(
+                target: LOG_TARGET,
+                "One-sided payment (TxId: {}) has already been received", tx_id
+            );
+            return Ok(());
+        }
+
+        let output = if payment.spending_key.is_empty() {
+            self.find_one_sided_payment_output(&transaction)?
+        } else {
+            self.find_locked_output(&transaction, &payment)?
+        };
+        let amount = output.value;
+
+        self.output_manager_service
+            .accept_one_sided_output(tx_id, output)
+            .await?;
+
+        let completed_tx = CompletedTransaction::new(
+            tx_id,
+            source_pubkey,
+            self.node_identity.public_key().clone(),
+            amount,
+            transaction.body.get_total_fee(),
+            transaction,
+            TransactionStatus::Completed,
+            payment.message,
+            Utc::now().naive_utc(),
+            TransactionDirection::Inbound,
+            None,
+        );
+        self.db
+            .insert_completed_transaction(tx_id, completed_tx.clone())
+            .await?;
+        info!(
+            target: LOG_TARGET,
+            "One-sided payment (TxId: {}) of {} received", tx_id, amount
+        );
+
+        let _ = self
+            .event_publisher
+            .send(Arc::new(TransactionEvent::ReceivedFinalizedTransaction(tx_id)));
+
+        self.broadcast_completed_transaction(completed_tx, transaction_broadcast_join_handles)
+            .await
+    }
+
+    /// Find the output of the transaction that pays this wallet, by deriving the keys of the one-sided payment
+    /// outputs with the node identity key
+    fn find_one_sided_payment_output(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<UnblindedOutput, TransactionServiceError>
+    {
+        let secret_key = self.node_identity.secret_key();
+        transaction
+            .body
+            .outputs()
+            .iter()
+            .find_map(|output| {
+                let script = output.features.script.as_ref()?;
+                OneSidedPayment::from_script(script, secret_key)
+                    .and_then(|payment| payment.recover_output(output, &self.resources.factories))
+                    .ok()
+            })
+            .ok_or_else(|| {
+                TransactionServiceError::InvalidMessageError(
+                    "The transaction has no one-sided payment output for this wallet".to_string(),
+                )
+            })
+    }
+
+    /// Find the hash time-locked or multisig output whose spending key and amount were sent along with the
+    /// transaction. The spending key of any other output is known to the sender, who could spend the output, so such
+    /// an output is not accepted.
+    fn find_locked_output(
+        &self,
+        transaction: &Transaction,
+        payment: &proto::OneSidedPayment,
+    ) -> Result<UnblindedOutput, TransactionServiceError>
+    {
+        let spending_key = PrivateKey::from_bytes(&payment.spending_key).map_err(|_| {
+            TransactionServiceError::InvalidMessageError("One-sided payment has an invalid spending key".to_string())
+        })?;
+        let amount = MicroTari::from(payment.amount);
+        let commitment = self
+            .resources
+            .factories
+            .commitment
+            .commit_value(&spending_key, amount.into());
+        let features = match transaction.body.outputs().iter().find(|o| o.commitment == commitment) {
+            Some(output) => output.features.clone(),
+            None => {
+                return Err(TransactionServiceError::InvalidMessageError(
+                    "One-sided payment output is not in the transaction".to_string(),
+                ))
+            },
+        };
+        if !features.is_hash_time_locked() && !features.is_multisig_locked() {
+            return Err(TransactionServiceError::InvalidMessageError(
+                "The spending key of an output without a hash time lock or multisig lock was sent".to_string(),
+            ));
+        }
+
+        Ok(UnblindedOutput::new(amount, spending_key, Some(features)))
+    }
+
     /// Handle the final clean up after a Send Transaction protocol completes
     async fn complete_receive_transaction_protocol(
         &mut self,
@@ -1212,6 +2866,30 @@ where
         Ok(())
     }
 
+    /// Replace the labels of a known transaction. Labels are trimmed and empty or duplicate labels are dropped, so an
+    /// empty list clears the labels of the transaction.
+    async fn set_transaction_labels(
+        &mut self,
+        tx_id: TxId,
+        labels: Vec<String>,
+    ) -> Result<(), TransactionServiceError>
+    {
+        if self.db.get_any_transaction(tx_id).await?.is_none() {
+            return Err(TransactionServiceError::TransactionDoesNotExistError);
+        }
+
+        let mut labels = labels
+            .into_iter()
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<_>>();
+        labels.sort();
+        labels.dedup();
+
+        self.db.set_transaction_labels(tx_id, labels).await?;
+        Ok(())
+    }
+
     /// Add a base node public key to the list that will be used to broadcast transactions and monitor the base chain
     /// for the presence of spendable outputs. If this is the first time the base node public key is set do the initial
     /// mempool broadcast
@@ -1408,6 +3086,49 @@ where
         Ok(())
     }
 
+    /// Retry a transaction that is stuck. A pending outbound transaction is sent to its recipient again by its send
+    /// protocol, which is restarted first if it is not running. A completed transaction that is not mined is
+    /// broadcast to the base node, unless a broadcast protocol is already resubmitting it.
+    async fn retry_transaction(
+        &mut self,
+        tx_id: TxId,
+        send_transaction_join_handles: &mut FuturesUnordered<JoinHandle<Result<u64, TransactionServiceProtocolError>>>,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<u64, TransactionServiceProtocolError>>,
+        >,
+    ) -> Result<TransactionRetry, TransactionServiceError>
+    {
+        match self.db.get_any_transaction(tx_id).await? {
+            Some(WalletTransaction::PendingOutbound(outbound_tx)) if !outbound_tx.cancelled => {
+                // A transaction with several recipients is sent again by the protocol of its first TxId
+                let tx_id = outbound_tx.sender_protocol.get_tx_id()?;
+                if !self.send_transaction_resend_senders.contains_key(&tx_id) {
+                    self.restart_all_send_transaction_protocols(send_transaction_join_handles)
+                        .await?;
+                }
+                if let Some(resend_sender) = self.send_transaction_resend_senders.get_mut(&tx_id) {
+                    // The channel is only full when a resend has been requested that the protocol has not made yet
+                    let _ = resend_sender.try_send(());
+                }
+                Ok(TransactionRetry::Resent)
+            },
+            Some(WalletTransaction::Completed(completed_tx))
+                if !completed_tx.cancelled &&
+                    (completed_tx.status == TransactionStatus::Completed ||
+                        completed_tx.status == TransactionStatus::Broadcast) =>
+            {
+                if self.active_transaction_broadcast_protocols.contains(&tx_id) {
+                    return Ok(TransactionRetry::BroadcastInProgress);
+                }
+                self.broadcast_completed_transaction(completed_tx, transaction_broadcast_join_handles)
+                    .await?;
+                Ok(TransactionRetry::BroadcastStarted)
+            }
+            Some(_) => Err(TransactionServiceError::TransactionCannotBeRetried),
+            None => Err(TransactionServiceError::TransactionDoesNotExistError),
+        }
+    }
+
     /// Go through all completed transactions that have not yet been broadcast and broadcast all of them to the base
     /// node.
     async fn broadcast_all_completed_transactions(
@@ -1571,6 +3292,7 @@ where
         reward: MicroTari,
         fees: MicroTari,
         block_height: u64,
+        extra: Vec<u8>,
         coinbase_monitoring_protocol_join_handles: &mut FuturesUnordered<
             JoinHandle<Result<u64, TransactionServiceProtocolError>>,
         >,
@@ -1601,7 +3323,7 @@ where
                 let tx_id = OsRng.next_u64();
                 let tx = self
                     .output_manager_service
-                    .get_coinbase_transaction(tx_id, reward, fees, block_height)
+                    .get_coinbase_transaction_with_extra(tx_id, reward, fees, block_height, extra)
                     .await?;
 
                 // Cancel existing unmined coinbase transactions for this blockheight
@@ -1927,7 +3649,7 @@ where
         fake_oms.add_output(uo).await?;
 
         let mut stp = fake_oms
-            .prepare_transaction_to_send(amount, MicroTari::from(25), None, "".to_string())
+            .prepare_transaction_to_send(None, None, amount, MicroTari::from(25), None, "".to_string())
             .await?;
 
         let msg = stp.build_single_round_message()?;
@@ -1984,7 +3706,6 @@ where
     #[cfg(feature = "test_harness")]
     pub async fn finalize_received_test_transaction(&mut self, tx_id: TxId) -> Result<(), TransactionServiceError> {
         use tari_core::transactions::{transaction::KernelBuilder, types::Signature};
-        use tari_crypto::commitment::HomomorphicCommitmentFactory;
 
         let factories = CryptoFactories::default();
 
@@ -70,6 +70,15 @@ pub trait TransactionBackend: Send + Sync + Clone {
         tx_id: TxId,
         completed_transaction: CompletedTransaction,
     ) -> Result<(), TransactionStorageError>;
+    /// Complete an outbound transaction that was sent to several recipients, this operation must delete the
+    /// `OutboundTransaction`s with the provided `TxId`s and insert the provided `CompletedTransaction` under the first
+    /// of them, in a single db transaction.
+    fn complete_multi_recipient_outbound_transaction(
+        &self,
+        tx_id: TxId,
+        completed_transaction: CompletedTransaction,
+        other_tx_ids: Vec<TxId>,
+    ) -> Result<(), TransactionStorageError>;
     /// Complete inbound transaction, this operation must delete the `InboundTransaction` with the provided
     /// `TxId` and insert the provided `CompletedTransaction` into `CompletedTransactions`.
     fn complete_inbound_transaction(
@@ -140,6 +149,8 @@ pub enum DbKey {
     CancelledPendingOutboundTransaction(TxId),
     CancelledPendingInboundTransaction(TxId),
     AnyTransaction(TxId),
+    TransactionLabels(TxId),
+    AllTransactionLabels,
 }
 
 #[derive(Debug)]
@@ -151,12 +162,16 @@ pub enum DbValue {
     PendingInboundTransactions(HashMap<TxId, InboundTransaction>),
     CompletedTransactions(HashMap<TxId, CompletedTransaction>),
     WalletTransaction(Box<WalletTransaction>),
+    TransactionLabels(Vec<String>),
+    AllTransactionLabels(HashMap<TxId, Vec<String>>),
 }
 
 pub enum DbKeyValuePair {
     PendingOutboundTransaction(TxId, Box<OutboundTransaction>),
     PendingInboundTransaction(TxId, Box<InboundTransaction>),
     CompletedTransaction(TxId, Box<CompletedTransaction>),
+    /// Replaces all the labels of the transaction
+    TransactionLabels(TxId, Vec<String>),
 }
 
 pub enum WriteOperation {
@@ -557,6 +572,25 @@ where T: TransactionBackend + 'static
             .and_then(|inner_result| inner_result)
     }
 
+    /// This method moves the `PendingOutboundTransaction`s of a transaction that was sent to several recipients to
+    /// the `CompleteTransaction` collection, as one `CompletedTransaction` under the TxId of the first recipient.
+    pub async fn complete_multi_recipient_outbound_transaction(
+        &self,
+        tx_id: TxId,
+        transaction: CompletedTransaction,
+        other_tx_ids: Vec<TxId>,
+    ) -> Result<(), TransactionStorageError>
+    {
+        let db_clone = self.db.clone();
+
+        tokio::task::spawn_blocking(move || {
+            db_clone.complete_multi_recipient_outbound_transaction(tx_id, transaction, other_tx_ids)
+        })
+        .await
+        .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))
+        .and_then(|inner_result| inner_result)
+    }
+
     /// This method moves a `PendingInboundTransaction` to the `CompleteTransaction` collection.
     pub async fn complete_inbound_transaction(
         &self,
@@ -744,6 +778,51 @@ where T: TransactionBackend + 'static
         Ok(())
     }
 
+    /// The labels of the transaction, in alphabetical order
+    pub async fn get_transaction_labels(&self, tx_id: TxId) -> Result<Vec<String>, TransactionStorageError> {
+        let db_clone = self.db.clone();
+        let key = DbKey::TransactionLabels(tx_id);
+        let t = tokio::task::spawn_blocking(move || match db_clone.fetch(&key) {
+            Ok(None) => Ok(Vec::new()),
+            Ok(Some(DbValue::TransactionLabels(labels))) => Ok(labels),
+            Ok(Some(other)) => unexpected_result(key, other),
+            Err(e) => log_error(key, e),
+        })
+        .await
+        .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(t)
+    }
+
+    /// The labels of all the transactions that have any
+    pub async fn get_all_transaction_labels(&self) -> Result<HashMap<TxId, Vec<String>>, TransactionStorageError> {
+        let db_clone = self.db.clone();
+        let t = tokio::task::spawn_blocking(move || match db_clone.fetch(&DbKey::AllTransactionLabels) {
+            Ok(None) => Ok(HashMap::new()),
+            Ok(Some(DbValue::AllTransactionLabels(labels))) => Ok(labels),
+            Ok(Some(other)) => unexpected_result(DbKey::AllTransactionLabels, other),
+            Err(e) => log_error(DbKey::AllTransactionLabels, e),
+        })
+        .await
+        .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(t)
+    }
+
+    /// Replace the labels of the transaction, removing them all if `labels` is empty
+    pub async fn set_transaction_labels(
+        &self,
+        tx_id: TxId,
+        labels: Vec<String>,
+    ) -> Result<(), TransactionStorageError>
+    {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || {
+            db_clone.write(WriteOperation::Insert(DbKeyValuePair::TransactionLabels(tx_id, labels)))
+        })
+        .await
+        .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(())
+    }
+
     pub async fn set_transaction_mined_height(
         &self,
         tx_id: TxId,
@@ -782,6 +861,8 @@ impl Display for DbKey {
                 f.write_str(&"Cancelled Pending Inbound Transaction".to_string())
             },
             DbKey::AnyTransaction(_) => f.write_str(&"Any Transaction".to_string()),
+            DbKey::TransactionLabels(_) => f.write_str(&"Transaction Labels".to_string()),
+            DbKey::AllTransactionLabels => f.write_str(&"All Transaction Labels".to_string()),
         }
     }
 }
@@ -796,6 +877,8 @@ impl Display for DbValue {
             DbValue::PendingInboundTransactions(_) => f.write_str(&"All Pending Inbound Transactions".to_string()),
             DbValue::CompletedTransactions(_) => f.write_str(&"All Complete Transactions".to_string()),
             DbValue::WalletTransaction(_) => f.write_str(&"Any Wallet Transaction".to_string()),
+            DbValue::TransactionLabels(_) => f.write_str(&"Transaction Labels".to_string()),
+            DbValue::AllTransactionLabels(_) => f.write_str(&"All Transaction Labels".to_string()),
         }
     }
 }
@@ -22,7 +22,7 @@
 
 use crate::{
     output_manager_service::TxId,
-    schema::{completed_transactions, inbound_transactions, outbound_transactions},
+    schema::{completed_transactions, inbound_transactions, outbound_transactions, transaction_labels},
     storage::sqlite_utilities::WalletDbConnection,
     transaction_service::{
         error::TransactionStorageError,
@@ -68,9 +68,15 @@ pub struct TransactionServiceSqliteDatabase {
 
 impl TransactionServiceSqliteDatabase {
     pub fn new(database_connection: WalletDbConnection, cipher: Option<Aes256Gcm>) -> Self {
+        Self::with_shared_cipher(database_connection, Arc::new(RwLock::new(cipher)))
+    }
+
+    /// A backend that uses the cipher of the wallet backend, which encrypts and re-encrypts the values of all of the
+    /// services in the database at once
+    pub fn with_shared_cipher(database_connection: WalletDbConnection, cipher: Arc<RwLock<Option<Aes256Gcm>>>) -> Self {
         Self {
             database_connection,
-            cipher: Arc::new(RwLock::new(cipher)),
+            cipher,
         }
     }
 
@@ -102,6 +108,9 @@ impl TransactionServiceSqliteDatabase {
 
                 c.commit(&(*conn))?;
             },
+            DbKeyValuePair::TransactionLabels(k, v) => {
+                TransactionLabelSql::set_for_transaction(k, &v, &(*conn))?;
+            },
         }
         Ok(())
     }
@@ -190,9 +199,43 @@ impl TransactionServiceSqliteDatabase {
                 }
             },
             DbKey::AnyTransaction(_) => Err(TransactionStorageError::OperationNotSupported),
+            DbKey::TransactionLabels(k) => {
+                TransactionLabelSql::set_for_transaction(k, &[], &(*conn))?;
+                Ok(None)
+            },
+            DbKey::AllTransactionLabels => Err(TransactionStorageError::OperationNotSupported),
         }
     }
 
+    /// Move the `OutboundTransaction` with the provided `TxId` to the completed transactions
+    fn complete_outbound(
+        &self,
+        tx_id: TxId,
+        completed_transaction: CompletedTransaction,
+        conn: &SqliteConnection,
+    ) -> Result<(), TransactionStorageError>
+    {
+        if CompletedTransactionSql::find_by_cancelled(tx_id, false, conn).is_ok() {
+            return Err(TransactionStorageError::TransactionAlreadyExists);
+        }
+
+        match OutboundTransactionSql::find_by_cancelled(tx_id, false, conn) {
+            Ok(v) => {
+                let mut completed_tx_sql = CompletedTransactionSql::try_from(completed_transaction)?;
+                self.encrypt_if_necessary(&mut completed_tx_sql)?;
+                v.delete(conn)?;
+                completed_tx_sql.commit(conn)?;
+            },
+            Err(TransactionStorageError::DieselError(DieselError::NotFound)) => {
+                return Err(TransactionStorageError::ValueNotFound(
+                    DbKey::PendingOutboundTransaction(tx_id),
+                ))
+            },
+            Err(e) => return Err(e),
+        };
+        Ok(())
+    }
+
     fn decrypt_if_necessary<T: Encryptable<Aes256Gcm>>(&self, o: &mut T) -> Result<(), TransactionStorageError> {
         let cipher = acquire_read_lock!(self.cipher);
         if let Some(cipher) = cipher.as_ref() {
@@ -366,6 +409,20 @@ impl TransactionBackend for TransactionServiceSqliteDatabase {
                     Err(e) => return Err(e),
                 }
             },
+            DbKey::TransactionLabels(t) => Some(DbValue::TransactionLabels(
+                TransactionLabelSql::find_by_tx_id(*t, &(*conn))?
+                    .into_iter()
+                    .map(|l| l.label)
+                    .collect(),
+            )),
+            DbKey::AllTransactionLabels => {
+                let mut result: HashMap<TxId, Vec<String>> = HashMap::new();
+                for l in TransactionLabelSql::index(&(*conn))? {
+                    result.entry(l.tx_id as u64).or_default().push(l.label);
+                }
+
+                Some(DbValue::AllTransactionLabels(result))
+            },
         };
 
         Ok(result)
@@ -399,6 +456,8 @@ impl TransactionBackend for TransactionServiceSqliteDatabase {
                     InboundTransactionSql::find(*k, &(*conn)).is_ok() ||
                     OutboundTransactionSql::find(*k, &(*conn)).is_ok()
             },
+            DbKey::TransactionLabels(k) => !TransactionLabelSql::find_by_tx_id(*k, &(*conn))?.is_empty(),
+            DbKey::AllTransactionLabels => false,
         };
 
         Ok(result)
@@ -451,26 +510,32 @@ impl TransactionBackend for TransactionServiceSqliteDatabase {
     ) -> Result<(), TransactionStorageError>
     {
         let conn = self.database_connection.acquire_lock();
+        self.complete_outbound(tx_id, completed_transaction, &conn)
+    }
 
-        if CompletedTransactionSql::find_by_cancelled(tx_id, false, &(*conn)).is_ok() {
-            return Err(TransactionStorageError::TransactionAlreadyExists);
-        }
-
-        match OutboundTransactionSql::find_by_cancelled(tx_id, false, &(*conn)) {
-            Ok(v) => {
-                let mut completed_tx_sql = CompletedTransactionSql::try_from(completed_transaction)?;
-                self.encrypt_if_necessary(&mut completed_tx_sql)?;
-                v.delete(&(*conn))?;
-                completed_tx_sql.commit(&(*conn))?;
-            },
-            Err(TransactionStorageError::DieselError(DieselError::NotFound)) => {
-                return Err(TransactionStorageError::ValueNotFound(
-                    DbKey::PendingOutboundTransaction(tx_id),
-                ))
-            },
-            Err(e) => return Err(e),
-        };
-        Ok(())
+    fn complete_multi_recipient_outbound_transaction(
+        &self,
+        tx_id: u64,
+        completed_transaction: CompletedTransaction,
+        other_tx_ids: Vec<u64>,
+    ) -> Result<(), TransactionStorageError>
+    {
+        let conn = self.database_connection.acquire_lock();
+        conn.transaction::<_, TransactionStorageError, _>(|| {
+            self.complete_outbound(tx_id, completed_transaction, &conn)?;
+            for other_tx_id in other_tx_ids {
+                match OutboundTransactionSql::find_by_cancelled(other_tx_id, false, &(*conn)) {
+                    Ok(v) => v.delete(&(*conn))?,
+                    Err(TransactionStorageError::DieselError(DieselError::NotFound)) => {
+                        return Err(TransactionStorageError::ValueNotFound(
+                            DbKey::PendingOutboundTransaction(other_tx_id),
+                        ))
+                    },
+                    Err(e) => return Err(e),
+                }
+            }
+            Ok(())
+        })
     }
 
     fn complete_inbound_transaction(
@@ -670,61 +735,14 @@ impl TransactionBackend for TransactionServiceSqliteDatabase {
     }
 
     fn apply_encryption(&self, cipher: Aes256Gcm) -> Result<(), TransactionStorageError> {
+        let conn = self.database_connection.acquire_lock();
         let mut current_cipher = acquire_write_lock!(self.cipher);
 
         if (*current_cipher).is_some() {
             return Err(TransactionStorageError::AlreadyEncrypted);
         }
 
-        let conn = self.database_connection.acquire_lock();
-
-        let mut inbound_txs = InboundTransactionSql::index(&conn)?;
-        // If the db is already encrypted then the very first output we try to encrypt will fail.
-        for tx in inbound_txs.iter_mut() {
-            // Test if this transaction is encrypted or not to avoid a double encryption.
-            let _ = InboundTransaction::try_from(tx.clone()).map_err(|_| {
-                error!(
-                    target: LOG_TARGET,
-                    "Could not convert Inbound Transaction from database version, it might already be encrypted"
-                );
-                TransactionStorageError::AlreadyEncrypted
-            })?;
-            tx.encrypt(&cipher)
-                .map_err(|_| TransactionStorageError::AeadError("Encryption Error".to_string()))?;
-            tx.update_encryption(&conn)?;
-        }
-
-        let mut outbound_txs = OutboundTransactionSql::index(&conn)?;
-        // If the db is already encrypted then the very first output we try to encrypt will fail.
-        for tx in outbound_txs.iter_mut() {
-            // Test if this transaction is encrypted or not to avoid a double encryption.
-            let _ = OutboundTransaction::try_from(tx.clone()).map_err(|_| {
-                error!(
-                    target: LOG_TARGET,
-                    "Could not convert Inbound Transaction from database version, it might already be encrypted"
-                );
-                TransactionStorageError::AlreadyEncrypted
-            })?;
-            tx.encrypt(&cipher)
-                .map_err(|_| TransactionStorageError::AeadError("Encryption Error".to_string()))?;
-            tx.update_encryption(&conn)?;
-        }
-
-        let mut completed_txs = CompletedTransactionSql::index(&conn)?;
-        // If the db is already encrypted then the very first output we try to encrypt will fail.
-        for tx in completed_txs.iter_mut() {
-            // Test if this transaction is encrypted or not to avoid a double encryption.
-            let _ = CompletedTransaction::try_from(tx.clone()).map_err(|_| {
-                error!(
-                    target: LOG_TARGET,
-                    "Could not convert Inbound Transaction from database version, it might already be encrypted"
-                );
-                TransactionStorageError::AlreadyEncrypted
-            })?;
-            tx.encrypt(&cipher)
-                .map_err(|_| TransactionStorageError::AeadError("Encryption Error".to_string()))?;
-            tx.update_encryption(&conn)?;
-        }
+        conn.transaction::<_, TransactionStorageError, _>(|| encrypt_transactions(&cipher, &conn))?;
 
         (*current_cipher) = Some(cipher);
 
@@ -732,6 +750,7 @@ impl TransactionBackend for TransactionServiceSqliteDatabase {
     }
 
     fn remove_encryption(&self) -> Result<(), TransactionStorageError> {
+        let conn = self.database_connection.acquire_lock();
         let mut current_cipher = acquire_write_lock!(self.cipher);
 
         let cipher = if let Some(cipher) = (*current_cipher).clone().take() {
@@ -739,30 +758,7 @@ impl TransactionBackend for TransactionServiceSqliteDatabase {
         } else {
             return Ok(());
         };
-        let conn = self.database_connection.acquire_lock();
-
-        let mut inbound_txs = InboundTransactionSql::index(&conn)?;
-
-        for tx in inbound_txs.iter_mut() {
-            tx.decrypt(&cipher)
-                .map_err(|_| TransactionStorageError::AeadError("Decryption Error".to_string()))?;
-            tx.update_encryption(&conn)?;
-        }
-
-        let mut outbound_txs = OutboundTransactionSql::index(&conn)?;
-
-        for tx in outbound_txs.iter_mut() {
-            tx.decrypt(&cipher)
-                .map_err(|_| TransactionStorageError::AeadError("Decryption Error".to_string()))?;
-            tx.update_encryption(&conn)?;
-        }
-
-        let mut completed_txs = CompletedTransactionSql::index(&conn)?;
-        for tx in completed_txs.iter_mut() {
-            tx.decrypt(&cipher)
-                .map_err(|_| TransactionStorageError::AeadError("Decryption Error".to_string()))?;
-            tx.update_encryption(&conn)?;
-        }
+        conn.transaction::<_, TransactionStorageError, _>(|| decrypt_transactions(&cipher, &conn))?;
 
         // Now that all the decryption has been completed we can safely remove the cipher fully
         let _ = (*current_cipher).take();
@@ -941,6 +937,137 @@ impl TransactionBackend for TransactionServiceSqliteDatabase {
     }
 }
 
+/// Encrypt the inbound, outbound and completed transactions, which must not be encrypted yet
+pub(crate) fn encrypt_transactions(cipher: &Aes256Gcm, conn: &SqliteConnection) -> Result<(), TransactionStorageError> {
+    let mut inbound_txs = InboundTransactionSql::index(conn)?;
+    // If the db is already encrypted then the very first output we try to encrypt will fail.
+    for tx in inbound_txs.iter_mut() {
+        // Test if this transaction is encrypted or not to avoid a double encryption.
+        let _ = InboundTransaction::try_from(tx.clone()).map_err(|_| {
+            error!(
+                target: LOG_TARGET,
+                "Could not convert Inbound Transaction from database version, it might already be encrypted"
+            );
+            TransactionStorageError::AlreadyEncrypted
+        })?;
+        tx.encrypt(cipher)
+            .map_err(|_| TransactionStorageError::AeadError("Encryption Error".to_string()))?;
+        tx.update_encryption(conn)?;
+    }
+
+    let mut outbound_txs = OutboundTransactionSql::index(conn)?;
+    // If the db is already encrypted then the very first output we try to encrypt will fail.
+    for tx in outbound_txs.iter_mut() {
+        // Test if this transaction is encrypted or not to avoid a double encryption.
+        let _ = OutboundTransaction::try_from(tx.clone()).map_err(|_| {
+            error!(
+                target: LOG_TARGET,
+                "Could not convert Inbound Transaction from database version, it might already be encrypted"
+            );
+            TransactionStorageError::AlreadyEncrypted
+        })?;
+        tx.encrypt(cipher)
+            .map_err(|_| TransactionStorageError::AeadError("Encryption Error".to_string()))?;
+        tx.update_encryption(conn)?;
+    }
+
+    let mut completed_txs = CompletedTransactionSql::index(conn)?;
+    // If the db is already encrypted then the very first output we try to encrypt will fail.
+    for tx in completed_txs.iter_mut() {
+        // Test if this transaction is encrypted or not to avoid a double encryption.
+        let _ = CompletedTransaction::try_from(tx.clone()).map_err(|_| {
+            error!(
+                target: LOG_TARGET,
+                "Could not convert Inbound Transaction from database version, it might already be encrypted"
+            );
+            TransactionStorageError::AlreadyEncrypted
+        })?;
+        tx.encrypt(cipher)
+            .map_err(|_| TransactionStorageError::AeadError("Encryption Error".to_string()))?;
+        tx.update_encryption(conn)?;
+    }
+
+    Ok(())
+}
+
+/// Decrypt the inbound, outbound and completed transactions
+pub(crate) fn decrypt_transactions(cipher: &Aes256Gcm, conn: &SqliteConnection) -> Result<(), TransactionStorageError> {
+    let mut inbound_txs = InboundTransactionSql::index(conn)?;
+
+    for tx in inbound_txs.iter_mut() {
+        tx.decrypt(cipher)
+            .map_err(|_| TransactionStorageError::AeadError("Decryption Error".to_string()))?;
+        tx.update_encryption(conn)?;
+    }
+
+    let mut outbound_txs = OutboundTransactionSql::index(conn)?;
+
+    for tx in outbound_txs.iter_mut() {
+        tx.decrypt(cipher)
+            .map_err(|_| TransactionStorageError::AeadError("Decryption Error".to_string()))?;
+        tx.update_encryption(conn)?;
+    }
+
+    let mut completed_txs = CompletedTransactionSql::index(conn)?;
+    for tx in completed_txs.iter_mut() {
+        tx.decrypt(cipher)
+            .map_err(|_| TransactionStorageError::AeadError("Decryption Error".to_string()))?;
+        tx.update_encryption(conn)?;
+    }
+
+    Ok(())
+}
+
+/// A label that the user gave a transaction. A transaction can have any number of labels.
+#[derive(Clone, Debug, Queryable, Insertable, PartialEq)]
+#[table_name = "transaction_labels"]
+struct TransactionLabelSql {
+    tx_id: i64,
+    label: String,
+}
+
+impl TransactionLabelSql {
+    /// All the labels, ordered by transaction and then alphabetically
+    pub fn index(conn: &SqliteConnection) -> Result<Vec<TransactionLabelSql>, TransactionStorageError> {
+        Ok(transaction_labels::table
+            .order((transaction_labels::tx_id.asc(), transaction_labels::label.asc()))
+            .load::<TransactionLabelSql>(conn)?)
+    }
+
+    pub fn find_by_tx_id(
+        tx_id: TxId,
+        conn: &SqliteConnection,
+    ) -> Result<Vec<TransactionLabelSql>, TransactionStorageError>
+    {
+        Ok(transaction_labels::table
+            .filter(transaction_labels::tx_id.eq(tx_id as i64))
+            .order(transaction_labels::label.asc())
+            .load::<TransactionLabelSql>(conn)?)
+    }
+
+    /// Replace the labels of the transaction with `labels`, ignoring any duplicates
+    pub fn set_for_transaction(
+        tx_id: TxId,
+        labels: &[String],
+        conn: &SqliteConnection,
+    ) -> Result<(), TransactionStorageError>
+    {
+        conn.transaction::<_, TransactionStorageError, _>(|| {
+            diesel::delete(transaction_labels::table.filter(transaction_labels::tx_id.eq(tx_id as i64)))
+                .execute(conn)?;
+            for label in labels {
+                diesel::replace_into(transaction_labels::table)
+                    .values(TransactionLabelSql {
+                        tx_id: tx_id as i64,
+                        label: label.clone(),
+                    })
+                    .execute(conn)?;
+            }
+            Ok(())
+        })
+    }
+}
+
 #[derive(Clone, Debug, Queryable, Insertable, PartialEq)]
 #[table_name = "inbound_transactions"]
 struct InboundTransactionSql {
@@ -1697,7 +1824,7 @@ mod test {
     use crate::{
         storage::sqlite_utilities::WalletDbConnection,
         transaction_service::storage::{
-            database::{DbKey, TransactionBackend},
+            database::{DbKey, DbKeyValuePair, DbValue, TransactionBackend, WriteOperation},
             models::{
                 CompletedTransaction,
                 InboundTransaction,
@@ -2295,4 +2422,132 @@ mod test {
         assert!(db3.fetch(&DbKey::PendingOutboundTransactions).is_ok());
         assert!(db3.fetch(&DbKey::CompletedTransactions).is_ok());
     }
+
+    #[test]
+    fn test_complete_multi_recipient_outbound_transaction() {
+        let db_name = format!("{}.sqlite3", string(8).as_str());
+        let temp_dir = tempdir().unwrap();
+        let db_folder = temp_dir.path().to_str().unwrap().to_string();
+        let db_path = format!("{}{}", db_folder, db_name);
+
+        embed_migrations!("./migrations");
+        let conn = SqliteConnection::establish(&db_path).unwrap_or_else(|_| panic!("Error connecting to {}", db_path));
+
+        embedded_migrations::run_with_output(&conn, &mut std::io::stdout()).expect("Migration failed");
+
+        for tx_id in 1..=3u64 {
+            let outbound_tx = OutboundTransaction {
+                tx_id,
+                destination_public_key: PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+                amount: MicroTari::from(100),
+                fee: MicroTari::from(10),
+                sender_protocol: SenderTransactionProtocol::new_placeholder(),
+                status: TransactionStatus::Pending,
+                message: "Yo!".to_string(),
+                timestamp: Utc::now().naive_utc(),
+                cancelled: false,
+                direct_send_success: false,
+                send_count: 0,
+                last_send_timestamp: None,
+            };
+            OutboundTransactionSql::try_from(outbound_tx)
+                .unwrap()
+                .commit(&conn)
+                .unwrap();
+        }
+
+        let completed_tx = CompletedTransaction {
+            tx_id: 1,
+            source_public_key: PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+            destination_public_key: PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+            amount: MicroTari::from(300),
+            fee: MicroTari::from(30),
+            transaction: Transaction::new(vec![], vec![], vec![], PrivateKey::random(&mut OsRng)),
+            status: TransactionStatus::Completed,
+            message: "Yo!".to_string(),
+            timestamp: Utc::now().naive_utc(),
+            cancelled: false,
+            direction: TransactionDirection::Outbound,
+            coinbase_block_height: None,
+            send_count: 0,
+            last_send_timestamp: None,
+            valid: true,
+            confirmations: None,
+            mined_height: None,
+        };
+
+        let db = TransactionServiceSqliteDatabase::new(WalletDbConnection::new(conn, None), None);
+        let pending_outbound =
+            |db: &TransactionServiceSqliteDatabase| match db.fetch(&DbKey::PendingOutboundTransactions).unwrap() {
+                Some(DbValue::PendingOutboundTransactions(txs)) => txs.len(),
+                _ => panic!("Should be the pending outbound transactions"),
+            };
+
+        // Nothing is completed or removed if one of the pending transactions is missing
+        assert!(db
+            .complete_multi_recipient_outbound_transaction(1, completed_tx.clone(), vec![2, 4])
+            .is_err());
+        assert_eq!(pending_outbound(&db), 3);
+        assert!(db.fetch(&DbKey::CompletedTransaction(1)).unwrap().is_none());
+
+        db.complete_multi_recipient_outbound_transaction(1, completed_tx, vec![2, 3])
+            .unwrap();
+        assert_eq!(pending_outbound(&db), 0);
+        assert!(matches!(
+            db.fetch(&DbKey::CompletedTransaction(1)),
+            Ok(Some(DbValue::CompletedTransaction(_)))
+        ));
+    }
+
+    #[test]
+    fn test_transaction_labels() {
+        let db_name = format!("{}.sqlite3", string(8).as_str());
+        let temp_dir = tempdir().unwrap();
+        let db_folder = temp_dir.path().to_str().unwrap().to_string();
+        let db_path = format!("{}{}", db_folder, db_name);
+
+        embed_migrations!("./migrations");
+        let conn = SqliteConnection::establish(&db_path).unwrap_or_else(|_| panic!("Error connecting to {}", db_path));
+
+        embedded_migrations::run_with_output(&conn, &mut std::io::stdout()).expect("Migration failed");
+
+        let db = TransactionServiceSqliteDatabase::new(WalletDbConnection::new(conn, None), None);
+        let labels = |db: &TransactionServiceSqliteDatabase, tx_id| match db.fetch(&DbKey::TransactionLabels(tx_id)) {
+            Ok(Some(DbValue::TransactionLabels(labels))) => labels,
+            _ => panic!("Should be the labels of the transaction"),
+        };
+        assert!(labels(&db, 1).is_empty());
+        assert!(!db.contains(&DbKey::TransactionLabels(1)).unwrap());
+
+        db.write(WriteOperation::Insert(DbKeyValuePair::TransactionLabels(1, vec![
+            "rent".to_string(),
+            "home".to_string(),
+            "rent".to_string(),
+        ])))
+        .unwrap();
+        db.write(WriteOperation::Insert(DbKeyValuePair::TransactionLabels(2, vec![
+            "salary".to_string(),
+        ])))
+        .unwrap();
+        assert_eq!(labels(&db, 1), vec!["home".to_string(), "rent".to_string()]);
+        assert!(db.contains(&DbKey::TransactionLabels(1)).unwrap());
+
+        // Setting the labels replaces the previous ones
+        db.write(WriteOperation::Insert(DbKeyValuePair::TransactionLabels(1, vec![
+            "groceries".to_string(),
+        ])))
+        .unwrap();
+        match db.fetch(&DbKey::AllTransactionLabels) {
+            Ok(Some(DbValue::AllTransactionLabels(all))) => {
+                assert_eq!(all.len(), 2);
+                assert_eq!(all[&1], vec!["groceries".to_string()]);
+                assert_eq!(all[&2], vec!["salary".to_string()]);
+            },
+            _ => panic!("Should be the labels of all the transactions"),
+        }
+
+        db.write(WriteOperation::Remove(DbKey::TransactionLabels(1))).unwrap();
+        assert!(labels(&db, 1).is_empty());
+        assert_eq!(labels(&db, 2), vec!["salary".to_string()]);
+    }
 }
@@ -21,6 +21,7 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 pub mod send_finalized_transaction;
+pub mod send_multisig_message;
 pub mod send_transaction_cancelled;
 pub mod send_transaction_reply;
 pub mod start_transaction_validation_and_broadcast_protocols;
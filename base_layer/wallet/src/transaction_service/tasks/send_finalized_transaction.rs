@@ -34,7 +34,11 @@ use tari_comms_dht::{
     domain_message::OutboundDomainMessage,
     outbound::{OutboundEncryption, OutboundMessageRequester, SendMessageResponse},
 };
-use tari_core::transactions::{transaction::Transaction, transaction_protocol::proto};
+use tari_core::transactions::{
+    transaction::{Transaction, UnblindedOutput},
+    transaction_protocol::proto,
+};
+use tari_crypto::tari_utilities::ByteArray;
 use tari_p2p::tari_message::TariMessageType;
 
 const LOG_TARGET: &str = "wallet::transaction_service::tasks::send_finalized_transaction";
@@ -44,6 +48,73 @@ pub async fn send_finalized_transaction_message(
     tx_id: TxId,
     transaction: Transaction,
     destination_public_key: CommsPublicKey,
+    outbound_message_service: OutboundMessageRequester,
+    direct_send_timeout: Duration,
+    transaction_routing_mechanism: TransactionRoutingMechanism,
+) -> Result<(), TransactionServiceError>
+{
+    let finalized_transaction_message = proto::TransactionFinalizedMessage {
+        tx_id,
+        transaction: Some(transaction.into()),
+        one_sided_payment: None,
+    };
+    send_transaction_finalized_message(
+        tx_id,
+        finalized_transaction_message,
+        destination_public_key,
+        outbound_message_service,
+        direct_send_timeout,
+        transaction_routing_mechanism,
+    )
+    .await
+}
+
+/// Send a transaction that was built without the recipient to the recipient. The recipient derives the keys of a
+/// one-sided payment output itself, so only the spending key and amount of a `locked_output` that it shares with the
+/// sender are sent along.
+#[allow(clippy::too_many_arguments)]
+pub async fn send_one_sided_payment_message(
+    tx_id: TxId,
+    transaction: Transaction,
+    locked_output: Option<UnblindedOutput>,
+    message: String,
+    destination_public_key: CommsPublicKey,
+    outbound_message_service: OutboundMessageRequester,
+    direct_send_timeout: Duration,
+    transaction_routing_mechanism: TransactionRoutingMechanism,
+) -> Result<(), TransactionServiceError>
+{
+    let finalized_transaction_message = proto::TransactionFinalizedMessage {
+        tx_id,
+        transaction: Some(transaction.into()),
+        one_sided_payment: Some(match locked_output {
+            Some(output) => proto::OneSidedPayment {
+                spending_key: output.spending_key.to_vec(),
+                amount: output.value.into(),
+                message,
+            },
+            None => proto::OneSidedPayment {
+                spending_key: Vec::new(),
+                amount: 0,
+                message,
+            },
+        }),
+    };
+    send_transaction_finalized_message(
+        tx_id,
+        finalized_transaction_message,
+        destination_public_key,
+        outbound_message_service,
+        direct_send_timeout,
+        transaction_routing_mechanism,
+    )
+    .await
+}
+
+async fn send_transaction_finalized_message(
+    tx_id: TxId,
+    finalized_transaction_message: proto::TransactionFinalizedMessage,
+    destination_public_key: CommsPublicKey,
     mut outbound_message_service: OutboundMessageRequester,
     direct_send_timeout: Duration,
     transaction_routing_mechanism: TransactionRoutingMechanism,
@@ -53,7 +124,7 @@ pub async fn send_finalized_transaction_message(
         TransactionRoutingMechanism::DirectOnly | TransactionRoutingMechanism::DirectAndStoreAndForward => {
             send_finalized_transaction_message_direct(
                 tx_id,
-                transaction,
+                finalized_transaction_message,
                 destination_public_key,
                 outbound_message_service,
                 direct_send_timeout,
@@ -62,14 +133,10 @@ pub async fn send_finalized_transaction_message(
             .await?;
         },
         TransactionRoutingMechanism::StoreAndForwardOnly => {
-            let finalized_transaction_message = proto::TransactionFinalizedMessage {
-                tx_id,
-                transaction: Some(transaction.clone().into()),
-            };
             let store_and_forward_send_result = send_transaction_finalized_message_store_and_forward(
                 tx_id,
                 destination_public_key,
-                finalized_transaction_message.clone(),
+                finalized_transaction_message,
                 &mut outbound_message_service,
             )
             .await?;
@@ -84,17 +151,13 @@ pub async fn send_finalized_transaction_message(
 
 pub async fn send_finalized_transaction_message_direct(
     tx_id: TxId,
-    transaction: Transaction,
+    finalized_transaction_message: proto::TransactionFinalizedMessage,
     destination_public_key: CommsPublicKey,
     mut outbound_message_service: OutboundMessageRequester,
     direct_send_timeout: Duration,
     transaction_routing_mechanism: TransactionRoutingMechanism,
 ) -> Result<(), TransactionServiceError>
 {
-    let finalized_transaction_message = proto::TransactionFinalizedMessage {
-        tx_id,
-        transaction: Some(transaction.clone().into()),
-    };
     let mut store_and_forward_send_result = false;
     let mut direct_send_result = false;
     match outbound_message_service
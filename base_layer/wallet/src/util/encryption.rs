@@ -20,20 +20,193 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use crate::error::WalletStorageError;
 use aes_gcm::{
-    aead::{generic_array::GenericArray, Aead, Error as AeadError},
+    aead::{generic_array::GenericArray, Aead, Error as AeadError, NewAead},
     Aes256Gcm,
 };
+use digest::Digest;
 use rand::{rngs::OsRng, RngCore};
+use std::{fmt, str::FromStr};
+use tari_crypto::{
+    common::Blake256,
+    tari_utilities::hex::{from_hex, to_hex},
+};
 
 pub const AES_NONCE_BYTES: usize = 12;
 pub const AES_KEY_BYTES: usize = 32;
+pub const KDF_SALT_BYTES: usize = 16;
+/// The least memory a passphrase KDF may use, below which it is cheap to guess passphrases on a GPU
+pub const MIN_KDF_MEMORY_KIB: u32 = 8 * 1024;
+pub const MIN_KDF_ITERATIONS: u32 = 1;
+pub const MAX_KDF_PARALLELISM: u32 = 16;
 
 pub trait Encryptable<C> {
     fn encrypt(&mut self, cipher: &C) -> Result<(), AeadError>;
     fn decrypt(&mut self, cipher: &C) -> Result<(), AeadError>;
 }
 
+/// The cipher for the given passphrase, with which the wallet database and its backups are encrypted
+pub fn passphrase_cipher(passphrase: &str) -> Aes256Gcm {
+    let passphrase_hash = Blake256::new().chain(passphrase.as_bytes()).result();
+    let key = GenericArray::from_slice(passphrase_hash.as_slice());
+    Aes256Gcm::new(key)
+}
+
+/// The Argon2id costs of deriving the cipher of the wallet database from its passphrase
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KdfParameters {
+    /// The memory cost in KiB
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl KdfParameters {
+    /// Check that the parameters are within the bounds a stored KDF must keep to, so that a wallet is never encrypted
+    /// with a KDF that is cheap to brute-force or that Argon2id cannot be run with
+    pub fn validate(&self) -> Result<(), WalletStorageError> {
+        if self.memory_kib < MIN_KDF_MEMORY_KIB {
+            return Err(WalletStorageError::InvalidKdfParameters(format!(
+                "the memory cost must be at least {} KiB",
+                MIN_KDF_MEMORY_KIB
+            )));
+        }
+        if self.iterations < MIN_KDF_ITERATIONS {
+            return Err(WalletStorageError::InvalidKdfParameters(format!(
+                "there must be at least {} iteration",
+                MIN_KDF_ITERATIONS
+            )));
+        }
+        if self.parallelism < 1 || self.parallelism > MAX_KDF_PARALLELISM {
+            return Err(WalletStorageError::InvalidKdfParameters(format!(
+                "the parallelism must be between 1 and {}",
+                MAX_KDF_PARALLELISM
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for KdfParameters {
+    fn default() -> Self {
+        Self {
+            memory_kib: 64 * 1024,
+            iterations: 3,
+            parallelism: 1,
+        }
+    }
+}
+
+/// How the cipher of an encrypted wallet database was derived from its passphrase. It is stored unencrypted in the
+/// database, so that the cipher can be derived again when the wallet is unlocked.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PassphraseKdf {
+    /// A single Blake256 hash of the passphrase, which wallets that were encrypted before the KDF was stored use
+    Blake256,
+    Argon2id {
+        parameters: KdfParameters,
+        salt: Vec<u8>,
+    },
+}
+
+impl PassphraseKdf {
+    /// Argon2id with the given parameters and a new random salt
+    pub fn argon2id(parameters: KdfParameters) -> Self {
+        let mut salt = vec![0u8; KDF_SALT_BYTES];
+        OsRng.fill_bytes(&mut salt);
+        PassphraseKdf::Argon2id { parameters, salt }
+    }
+
+    pub fn cipher(&self, passphrase: &str) -> Result<Aes256Gcm, WalletStorageError> {
+        match self {
+            PassphraseKdf::Blake256 => Ok(passphrase_cipher(passphrase)),
+            PassphraseKdf::Argon2id { parameters, salt } => {
+                let config = argon2::Config {
+                    variant: argon2::Variant::Argon2id,
+                    version: argon2::Version::Version13,
+                    mem_cost: parameters.memory_kib,
+                    time_cost: parameters.iterations,
+                    lanes: parameters.parallelism,
+                    thread_mode: argon2::ThreadMode::from_threads(parameters.parallelism),
+                    hash_length: AES_KEY_BYTES as u32,
+                    ..Default::default()
+                };
+                let key = argon2::hash_raw(passphrase.as_bytes(), salt, &config)
+                    .map_err(|e| WalletStorageError::KdfError(e.to_string()))?;
+                Ok(Aes256Gcm::new(GenericArray::from_slice(&key)))
+            },
+        }
+    }
+
+    /// Whether a passphrase can be guessed more cheaply than if the cipher was derived with `parameters`, in which case
+    /// the wallet should be encrypted again with those
+    pub fn is_weaker_than(&self, parameters: &KdfParameters) -> bool {
+        match self {
+            PassphraseKdf::Blake256 => true,
+            PassphraseKdf::Argon2id {
+                parameters: current, ..
+            } => current.memory_kib < parameters.memory_kib || current.iterations < parameters.iterations,
+        }
+    }
+}
+
+/// The KDF is stored as `argon2id$m=<memory_kib>,t=<iterations>,p=<parallelism>$<hex salt>`, or `blake256`
+impl fmt::Display for PassphraseKdf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PassphraseKdf::Blake256 => f.write_str("blake256"),
+            PassphraseKdf::Argon2id { parameters, salt } => write!(
+                f,
+                "argon2id$m={},t={},p={}${}",
+                parameters.memory_kib,
+                parameters.iterations,
+                parameters.parallelism,
+                to_hex(salt)
+            ),
+        }
+    }
+}
+
+impl FromStr for PassphraseKdf {
+    type Err = WalletStorageError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || WalletStorageError::ConversionError(format!("Invalid passphrase KDF: {}", s));
+        if s == "blake256" {
+            return Ok(PassphraseKdf::Blake256);
+        }
+        let mut parts = s.split('$');
+        if parts.next() != Some("argon2id") {
+            return Err(invalid());
+        }
+        // Every parameter must be given, in the order they are written in, so that a truncated or altered KDF is
+        // never read as a KDF with other parameters
+        let mut values = parts.next().ok_or_else(invalid)?.split(',');
+        let mut value = |key: &str| {
+            values
+                .next()
+                .and_then(|parameter| parameter.strip_prefix(key))
+                .and_then(|value| value.strip_prefix('='))
+                .and_then(|value| value.parse::<u32>().ok())
+                .ok_or_else(invalid)
+        };
+        let parameters = KdfParameters {
+            memory_kib: value("m")?,
+            iterations: value("t")?,
+            parallelism: value("p")?,
+        };
+        if values.next().is_some() {
+            return Err(invalid());
+        }
+        let salt = from_hex(parts.next().ok_or_else(invalid)?)?;
+        if salt.len() != KDF_SALT_BYTES || parts.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(PassphraseKdf::Argon2id { parameters, salt })
+    }
+}
+
 pub fn decrypt_bytes_integral_nonce(cipher: &Aes256Gcm, ciphertext: Vec<u8>) -> Result<Vec<u8>, AeadError> {
     if ciphertext.len() < AES_NONCE_BYTES {
         return Err(AeadError);
@@ -55,7 +228,19 @@ pub fn encrypt_bytes_integral_nonce(cipher: &Aes256Gcm, plaintext: Vec<u8>) -> R
 
 #[cfg(test)]
 mod test {
-    use crate::util::encryption::{decrypt_bytes_integral_nonce, encrypt_bytes_integral_nonce};
+    use crate::{
+        error::WalletStorageError,
+        util::encryption::{
+            decrypt_bytes_integral_nonce,
+            encrypt_bytes_integral_nonce,
+            KdfParameters,
+            PassphraseKdf,
+            KDF_SALT_BYTES,
+            MAX_KDF_PARALLELISM,
+            MIN_KDF_ITERATIONS,
+            MIN_KDF_MEMORY_KIB,
+        },
+    };
     use aes_gcm::{
         aead::{generic_array::GenericArray, NewAead},
         Aes256Gcm,
@@ -70,4 +255,92 @@ mod test {
         let decrypted_text = decrypt_bytes_integral_nonce(&cipher, cipher_text).unwrap();
         assert_eq!(decrypted_text, plaintext);
     }
+
+    #[test]
+    fn test_passphrase_kdf() {
+        let parameters = KdfParameters {
+            memory_kib: 64,
+            iterations: 1,
+            parallelism: 1,
+        };
+        let kdf = PassphraseKdf::argon2id(parameters);
+        assert_eq!(kdf.to_string().parse::<PassphraseKdf>().unwrap(), kdf);
+        assert_eq!("blake256".parse::<PassphraseKdf>().unwrap(), PassphraseKdf::Blake256);
+        assert!("argon2id$m=64,t=1$zz".parse::<PassphraseKdf>().is_err());
+        let salt = "00".repeat(KDF_SALT_BYTES);
+        assert!(format!("argon2id$m=64,t=1,p=1${}", salt)
+            .parse::<PassphraseKdf>()
+            .is_ok());
+        // Every parameter is required, and the salt must have its full length
+        for invalid in &[
+            format!("argon2id$m=64,t=1${}", salt),
+            format!("argon2id$t=1,p=1${}", salt),
+            format!("argon2id$m=64,t=1,p=1,p=2${}", salt),
+            format!("argon2id$p=1,t=1,m=64${}", salt),
+            format!("argon2id$m=64,t=1,p=${}", salt),
+            format!("argon2id$m=64,t=1,p=1${}", &salt[2..]),
+            format!("argon2id$m=64,t=1,p=1${}00", salt),
+            format!("argon2id$m=64,t=1,p=1${}$", salt),
+        ] {
+            assert!(invalid.parse::<PassphraseKdf>().is_err(), "{}", invalid);
+        }
+        assert!("scrypt$n=1$00".parse::<PassphraseKdf>().is_err());
+
+        // The same passphrase and salt derive the same cipher
+        let plaintext = b"The quick brown fox was annoying".to_vec();
+        let cipher_text = encrypt_bytes_integral_nonce(&kdf.cipher("passphrase").unwrap(), plaintext.clone()).unwrap();
+        let decrypted_text =
+            decrypt_bytes_integral_nonce(&kdf.cipher("passphrase").unwrap(), cipher_text.clone()).unwrap();
+        assert_eq!(decrypted_text, plaintext);
+        assert!(decrypt_bytes_integral_nonce(&kdf.cipher("wrong").unwrap(), cipher_text).is_err());
+
+        assert!(PassphraseKdf::Blake256.is_weaker_than(&parameters));
+        assert!(!kdf.is_weaker_than(&parameters));
+        assert!(kdf.is_weaker_than(&KdfParameters {
+            memory_kib: 128,
+            ..parameters
+        }));
+        assert!(!kdf.is_weaker_than(&KdfParameters {
+            parallelism: 4,
+            ..parameters
+        }));
+    }
+
+    #[test]
+    fn test_kdf_parameter_bounds() {
+        assert!(KdfParameters::default().validate().is_ok());
+        let minimum = KdfParameters {
+            memory_kib: MIN_KDF_MEMORY_KIB,
+            iterations: MIN_KDF_ITERATIONS,
+            parallelism: 1,
+        };
+        assert!(minimum.validate().is_ok());
+        for parameters in &[
+            KdfParameters {
+                memory_kib: 1,
+                ..minimum
+            },
+            KdfParameters {
+                memory_kib: MIN_KDF_MEMORY_KIB - 1,
+                ..minimum
+            },
+            KdfParameters {
+                iterations: 0,
+                ..minimum
+            },
+            KdfParameters {
+                parallelism: 0,
+                ..minimum
+            },
+            KdfParameters {
+                parallelism: MAX_KDF_PARALLELISM + 1,
+                ..minimum
+            },
+        ] {
+            assert!(matches!(
+                parameters.validate(),
+                Err(WalletStorageError::InvalidKdfParameters(_))
+            ));
+        }
+    }
 }
@@ -22,8 +22,14 @@
 
 use crate::{
     base_node_service::{config::BaseNodeServiceConfig, handle::BaseNodeServiceHandle, BaseNodeServiceInitializer},
-    contacts_service::{handle::ContactsServiceHandle, storage::database::ContactsBackend, ContactsServiceInitializer},
+    contacts_service::{
+        config::ContactsServiceConfig,
+        handle::ContactsServiceHandle,
+        storage::database::ContactsBackend,
+        ContactsServiceInitializer,
+    },
     error::WalletError,
+    events::{WalletEvent, WalletEventPublisher, WalletEventReceiver, WalletEventSender, WALLET_EVENT_BUFFER_SIZE},
     output_manager_service::{
         config::OutputManagerServiceConfig,
         handle::OutputManagerHandle,
@@ -31,6 +37,7 @@ use crate::{
         OutputManagerServiceInitializer,
         TxId,
     },
+    scheduler_service::{config::SchedulerServiceConfig, handle::SchedulerServiceHandle, SchedulerServiceInitializer},
     storage::database::{WalletBackend, WalletDatabase},
     transaction_service::{
         config::TransactionServiceConfig,
@@ -38,14 +45,12 @@ use crate::{
         storage::database::TransactionBackend,
         TransactionServiceInitializer,
     },
-};
-use aes_gcm::{
-    aead::{generic_array::GenericArray, NewAead},
-    Aes256Gcm,
+    util::encryption::{KdfParameters, PassphraseKdf},
 };
 use digest::Digest;
+use futures::{stream::Fuse, StreamExt};
 use log::*;
-use std::{marker::PhantomData, sync::Arc};
+use std::{marker::PhantomData, path::PathBuf, sync::Arc};
 use tari_comms::{
     multiaddr::Multiaddr,
     peer_manager::{NodeId, Peer, PeerFeatures, PeerFlags},
@@ -58,7 +63,7 @@ use tari_core::{
     consensus::Network,
     transactions::{
         tari_amount::MicroTari,
-        transaction::{OutputFeatures, UnblindedOutput},
+        transaction::OutputFeatures,
         types::{CryptoFactories, PrivateKey},
     },
 };
@@ -72,10 +77,14 @@ use tari_p2p::{
     comms_connector::pubsub_connector,
     initialization,
     initialization::{CommsConfig, P2pInitializer},
+    services::liveness::{LivenessConfig, LivenessHandle, LivenessInitializer},
 };
 use tari_service_framework::StackBuilder;
 use tari_shutdown::ShutdownSignal;
-use tokio::runtime;
+use tokio::{
+    runtime,
+    sync::{broadcast, Mutex},
+};
 
 const LOG_TARGET: &str = "wallet";
 
@@ -89,6 +98,9 @@ pub struct WalletConfig {
     pub rate_limit: usize,
     pub network: Network,
     pub base_node_service_config: BaseNodeServiceConfig,
+    pub contacts_service_config: ContactsServiceConfig,
+    /// The Argon2id costs of deriving the cipher of the wallet database from its passphrase
+    pub passphrase_kdf: KdfParameters,
 }
 
 impl WalletConfig {
@@ -113,6 +125,8 @@ impl WalletConfig {
             rate_limit: rate_limit.unwrap_or_else(|| 50),
             network,
             base_node_service_config: base_node_service_config.unwrap_or_default(),
+            contacts_service_config: ContactsServiceConfig::default(),
+            passphrase_kdf: KdfParameters::default(),
         }
     }
 }
@@ -134,10 +148,16 @@ where
     pub transaction_service: TransactionServiceHandle,
     pub contacts_service: ContactsServiceHandle,
     pub base_node_service: BaseNodeServiceHandle,
+    pub liveness_service: LivenessHandle,
+    pub scheduler_service: SchedulerServiceHandle,
     pub db: WalletDatabase<T>,
     pub factories: CryptoFactories,
     #[cfg(feature = "test_harness")]
     pub transaction_backend: U,
+    passphrase_kdf: KdfParameters,
+    event_sender: WalletEventSender,
+    /// The base node every wallet service is set to. The lock is held while the base node is switched.
+    base_node_peer: Arc<Mutex<Option<Peer>>>,
     _u: PhantomData<U>,
     _v: PhantomData<V>,
     _w: PhantomData<W>,
@@ -164,10 +184,12 @@ where
         db.set_comms_secret_key(config.comms_config.node_identity.secret_key().clone())
             .await?;
         let bn_service_db = db.clone();
+        let scheduler_service_db = db.clone();
         #[cfg(feature = "test_harness")]
         let transaction_backend_handle = transaction_backend.clone();
 
         let factories = config.clone().factories;
+        let passphrase_kdf = config.passphrase_kdf;
         let (publisher, subscription_factory) =
             pubsub_connector(runtime::Handle::current(), config.buffer_size, config.rate_limit);
         let peer_message_subscription_factory = Arc::new(subscription_factory);
@@ -193,6 +215,7 @@ where
             config.buffer_size,
             config.rate_limit
         );
+        let event_publisher_shutdown_signal = shutdown_signal.clone();
         let stack = StackBuilder::new(shutdown_signal)
             .add_initializer(P2pInitializer::new(config.comms_config, publisher))
             .add_initializer(OutputManagerServiceInitializer::new(
@@ -203,15 +226,27 @@ where
             ))
             .add_initializer(TransactionServiceInitializer::new(
                 config.transaction_service_config.unwrap_or_default(),
-                peer_message_subscription_factory,
+                peer_message_subscription_factory.clone(),
                 transaction_backend,
                 node_identity.clone(),
                 factories.clone(),
             ))
-            .add_initializer(ContactsServiceInitializer::new(contacts_backend))
+            .add_initializer(ContactsServiceInitializer::new(
+                config.contacts_service_config,
+                contacts_backend,
+            ))
             .add_initializer(BaseNodeServiceInitializer::new(
                 config.base_node_service_config,
                 bn_service_db,
+            ))
+            .add_initializer(SchedulerServiceInitializer::new(
+                SchedulerServiceConfig::default(),
+                scheduler_service_db,
+            ))
+            // Pings are only sent on request, to measure the latency of connected peers and the liveness of contacts
+            .add_initializer(LivenessInitializer::new(
+                LivenessConfig::default(),
+                peer_message_subscription_factory,
             ));
 
         let mut handles = stack.build().await?;
@@ -228,6 +263,21 @@ where
         let store_and_forward_requester = dht.store_and_forward_requester();
 
         let base_node_service_handle = handles.expect_handle::<BaseNodeServiceHandle>();
+        let liveness_handle = handles.expect_handle::<LivenessHandle>();
+        let scheduler_service_handle = handles.expect_handle::<SchedulerServiceHandle>();
+
+        let (event_sender, _) = broadcast::channel(WALLET_EVENT_BUFFER_SIZE);
+        let event_publisher = WalletEventPublisher::new(
+            transaction_service_handle.get_event_stream_fused(),
+            output_manager_handle.get_event_stream_fused(),
+            base_node_service_handle.get_event_stream_fused(),
+            comms.connectivity().get_event_subscription().fuse(),
+            contacts_handle.get_liveness_event_stream_fused(),
+            output_manager_handle.clone(),
+            event_sender.clone(),
+            event_publisher_shutdown_signal,
+        );
+        tokio::spawn(event_publisher.run());
 
         Ok(Wallet {
             comms,
@@ -237,10 +287,15 @@ where
             transaction_service: transaction_service_handle,
             contacts_service: contacts_handle,
             base_node_service: base_node_service_handle,
+            liveness_service: liveness_handle,
+            scheduler_service: scheduler_service_handle,
             db,
             factories,
             #[cfg(feature = "test_harness")]
             transaction_backend: transaction_backend_handle,
+            passphrase_kdf,
+            event_sender,
+            base_node_peer: Arc::new(Mutex::new(None)),
             _u: PhantomData,
             _v: PhantomData,
             _w: PhantomData,
@@ -253,6 +308,11 @@ where
         self.comms.clone().wait_until_shutdown().await;
     }
 
+    /// The events of all the wallet services, and the changes to the balance of the wallet, as one stream
+    pub fn get_event_stream_fused(&self) -> Fuse<WalletEventReceiver> {
+        self.event_sender.subscribe().fuse()
+    }
+
     /// This function will set the base_node that the wallet uses to broadcast transactions, monitor outputs, and
     /// monitor the base node state.
     pub async fn set_base_node_peer(
@@ -277,12 +337,65 @@ where
             String::new(),
         );
 
+        self.set_base_node(peer).await
+    }
+
+    /// Switch the transaction service, the output manager and the base node service (and with it the UTXO scanners,
+    /// which follow the base node service) to the given base node. Either every service is switched or, if one of
+    /// them could not be, the services are set back to the previous base node and the error is returned. Once
+    /// switched, the connection to the previous base node is closed, which ends any RPC session still open to it,
+    /// and a `WalletEvent::BaseNodeChanged` is published.
+    pub async fn set_base_node(&mut self, peer: Peer) -> Result<(), WalletError> {
+        let base_node_peer = self.base_node_peer.clone();
+        let mut current_peer = base_node_peer.lock().await;
+
         self.comms.peer_manager().add_peer(peer.clone()).await?;
         self.comms
             .connectivity()
             .add_managed_peers(vec![peer.node_id.clone()])
             .await?;
 
+        if let Err(e) = self.set_base_node_for_services(&peer).await {
+            warn!(
+                target: LOG_TARGET,
+                "Could not switch the wallet services to base node {}: {}", peer.node_id, e
+            );
+            if let Some(previous_peer) = current_peer.as_ref() {
+                if let Err(e) = self.set_base_node_for_services(previous_peer).await {
+                    error!(
+                        target: LOG_TARGET,
+                        "Could not set the wallet services back to base node {}: {}", previous_peer.node_id, e
+                    );
+                }
+            }
+            if current_peer.as_ref().map(|p| &p.node_id) != Some(&peer.node_id) {
+                let _ = self.comms.connectivity().remove_peer(peer.node_id.clone()).await;
+            }
+            return Err(e);
+        }
+
+        if let Some(previous_peer) = current_peer.take() {
+            if previous_peer.node_id != peer.node_id {
+                self.disconnect_base_node(previous_peer.node_id).await;
+            }
+        }
+        *current_peer = Some(peer.clone());
+
+        info!(target: LOG_TARGET, "Wallet base node set to {}", peer.node_id);
+        // Sending only fails when there are no subscribers, which is not an error
+        let _ = self
+            .event_sender
+            .send(Arc::new(WalletEvent::BaseNodeChanged(Box::new(peer))));
+
+        Ok(())
+    }
+
+    /// The base node that the wallet services were last switched to, if one has been set
+    pub async fn get_base_node_peer(&self) -> Option<Peer> {
+        self.base_node_peer.lock().await.clone()
+    }
+
+    async fn set_base_node_for_services(&mut self, peer: &Peer) -> Result<(), WalletError> {
         self.transaction_service
             .set_base_node_public_key(peer.public_key.clone())
             .await?;
@@ -291,11 +404,39 @@ where
             .set_base_node_public_key(peer.public_key.clone())
             .await?;
 
-        self.base_node_service.set_base_node_peer(peer).await?;
+        self.base_node_service.set_base_node_peer(peer.clone()).await?;
 
         Ok(())
     }
 
+    /// Stop maintaining a connection to a previous base node and close the connection if it is open, so that the RPC
+    /// sessions on it end instead of running against a base node that the wallet no longer uses
+    async fn disconnect_base_node(&mut self, node_id: NodeId) {
+        let mut connectivity = self.comms.connectivity();
+        if let Err(e) = connectivity.remove_peer(node_id.clone()).await {
+            warn!(
+                target: LOG_TARGET,
+                "Could not stop managing the connection to previous base node {}: {}", node_id, e
+            );
+        }
+        match connectivity.get_connection(node_id.clone()).await {
+            Ok(Some(mut conn)) => {
+                debug!(target: LOG_TARGET, "Disconnecting from previous base node {}", node_id);
+                if let Err(e) = conn.disconnect().await {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Could not disconnect from previous base node {}: {}", node_id, e
+                    );
+                }
+            },
+            Ok(None) => {},
+            Err(e) => warn!(
+                target: LOG_TARGET,
+                "Could not get the connection to previous base node {}: {}", node_id, e
+            ),
+        }
+    }
+
     /// Import an external spendable UTXO into the wallet. The output will be added to the Output Manager and made
     /// spendable. A faux incoming transaction will be created to provide a record of the event. The TxId of the
     /// generated transaction is returned.
@@ -308,9 +449,10 @@ where
         message: String,
     ) -> Result<TxId, WalletError>
     {
-        let unblinded_output = UnblindedOutput::new(amount, spending_key.clone(), Some(features));
-
-        self.output_manager_service.add_output(unblinded_output.clone()).await?;
+        let commitment = self
+            .output_manager_service
+            .import_output(spending_key.clone(), amount, features)
+            .await?;
 
         let tx_id = self
             .transaction_service
@@ -320,10 +462,7 @@ where
         info!(
             target: LOG_TARGET,
             "UTXO (Commitment: {}) imported into wallet",
-            unblinded_output
-                .as_transaction_input(&self.factories.commitment, OutputFeatures::default())
-                .commitment
-                .to_hex()
+            commitment.to_hex()
         );
 
         Ok(tx_id)
@@ -384,25 +523,71 @@ where
     }
 
     /// Apply encryption to all the Wallet db backends. The Wallet backend will test if the db's are already encrypted
-    /// in which case this will fail.
+    /// in which case this will fail. The backends of the services share the cipher of the Wallet backend, which
+    /// encrypts their values along with its own.
     pub async fn apply_encryption(&mut self, passphrase: String) -> Result<(), WalletError> {
         debug!(target: LOG_TARGET, "Applying wallet encryption.");
-        let passphrase_hash = Blake256::new().chain(passphrase.as_bytes()).result().to_vec();
-        let key = GenericArray::from_slice(passphrase_hash.as_slice());
-        let cipher = Aes256Gcm::new(key);
+        let kdf = PassphraseKdf::argon2id(self.passphrase_kdf);
+        let cipher = kdf.cipher(&passphrase)?;
+
+        // The KDF is only used once the db is encrypted, so it is stored first in case the encryption is interrupted
+        self.db.set_passphrase_kdf(kdf).await?;
+        self.db.apply_encryption(cipher).await?;
+        Ok(())
+    }
+
+    /// Check that `passphrase` is the one the Wallet db is encrypted with. Fails with `IncorrectPassword` if it is
+    /// not.
+    pub async fn verify_passphrase(&self, passphrase: String) -> Result<(), WalletError> {
+        let cipher = self.passphrase_kdf().await?.cipher(&passphrase)?;
+        self.db.verify_cipher(cipher).await?;
+        Ok(())
+    }
+
+    /// How the cipher of the Wallet db is derived from its passphrase. Wallets that were encrypted before the KDF was
+    /// stored use a plain Blake256 hash.
+    async fn passphrase_kdf(&self) -> Result<PassphraseKdf, WalletError> {
+        Ok(self.db.get_passphrase_kdf().await?.unwrap_or(PassphraseKdf::Blake256))
+    }
+
+    /// Re-encrypt the Wallet db with a cipher derived from `new_passphrase` with a new salt and the configured
+    /// Argon2id costs. The values of every backend and the new KDF are written in a single db transaction, so the
+    /// wallet is never left encrypted with two passphrases, and the cipher that the backends share is swapped before
+    /// the services can write anything else.
+    pub async fn change_passphrase(
+        &mut self,
+        old_passphrase: String,
+        new_passphrase: String,
+    ) -> Result<(), WalletError>
+    {
+        debug!(target: LOG_TARGET, "Changing the wallet passphrase.");
+        self.verify_passphrase(old_passphrase).await?;
+        let kdf = PassphraseKdf::argon2id(self.passphrase_kdf);
+        let cipher = kdf.cipher(&new_passphrase)?;
 
-        self.db.apply_encryption(cipher.clone()).await?;
-        self.output_manager_service.apply_encryption(cipher.clone()).await?;
-        self.transaction_service.apply_encryption(cipher).await?;
+        self.db.rotate_cipher(cipher, kdf).await?;
         Ok(())
     }
 
+    /// Write a backup of the Wallet db, encrypted under a cipher derived from `passphrase` with the wallet's Argon2id
+    /// costs, to `backup_path`. The services can keep running while the backup is made. Returns the hex checksum of
+    /// the backup file.
+    pub async fn create_backup(&self, backup_path: PathBuf, passphrase: String) -> Result<String, WalletError> {
+        debug!(
+            target: LOG_TARGET,
+            "Creating a wallet backup at {}",
+            backup_path.display()
+        );
+        Ok(self
+            .db
+            .create_backup(backup_path, passphrase, self.passphrase_kdf)
+            .await?)
+    }
+
     /// Remove encryption from all the Wallet db backends. If any backends do not have encryption applied then this will
     /// fail
     pub async fn remove_encryption(&mut self) -> Result<(), WalletError> {
         self.db.remove_encryption().await?;
-        self.output_manager_service.remove_encryption().await?;
-        self.transaction_service.remove_encryption().await?;
         Ok(())
     }
 
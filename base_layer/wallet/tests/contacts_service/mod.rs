@@ -22,14 +22,24 @@
 
 use crate::support::utils::random_string;
 use rand::rngs::OsRng;
+use std::time::Duration;
+use tari_comms::peer_manager::NodeId;
 use tari_core::transactions::types::PublicKey;
 use tari_crypto::keys::PublicKey as PublicKeyTrait;
-use tari_service_framework::StackBuilder;
+use tari_p2p::services::liveness::{
+    mock::{create_p2p_liveness_mock, LivenessMockState},
+    LivenessEvent,
+    LivenessRequest,
+    Metadata,
+    PingPongEvent,
+};
+use tari_service_framework::{RegisterHandle, StackBuilder};
 use tari_shutdown::Shutdown;
 use tari_wallet::{
     contacts_service::{
+        config::ContactsServiceConfig,
         error::{ContactsServiceError, ContactsServiceStorageError},
-        handle::ContactsServiceHandle,
+        handle::{ContactOnlineStatus, ContactsServiceHandle},
         storage::{
             database::{Contact, ContactsBackend, ContactsDatabase, DbKey},
             memory_db::ContactsServiceMemoryDatabase,
@@ -45,18 +55,24 @@ use tokio::runtime::Runtime;
 pub fn setup_contacts_service<T: ContactsBackend + 'static>(
     runtime: &mut Runtime,
     backend: T,
-) -> (ContactsServiceHandle, Shutdown)
+    config: ContactsServiceConfig,
+) -> (ContactsServiceHandle, LivenessMockState, Shutdown)
 {
     let shutdown = Shutdown::new();
+    let (liveness_handle, liveness_mock, _) = create_p2p_liveness_mock(100);
+    let liveness_mock_state = liveness_mock.get_mock_state();
+    runtime.spawn(liveness_mock.run());
+
     let fut = StackBuilder::new(shutdown.to_signal())
-        .add_initializer(ContactsServiceInitializer::new(backend))
+        .add_initializer(RegisterHandle::new(liveness_handle))
+        .add_initializer(ContactsServiceInitializer::new(config, backend))
         .build();
 
     let handles = runtime.block_on(fut).expect("Service initialization failed");
 
     let contacts_api = handles.expect_handle::<ContactsServiceHandle>();
 
-    (contacts_api, shutdown)
+    (contacts_api, liveness_mock_state, shutdown)
 }
 
 #[test]
@@ -109,7 +125,8 @@ pub fn test_memory_database_crud() {
 
 pub fn test_contacts_service<T: ContactsBackend + 'static>(backend: T) {
     let mut runtime = Runtime::new().unwrap();
-    let (mut contacts_service, _shutdown) = setup_contacts_service(&mut runtime, backend);
+    let (mut contacts_service, _, _shutdown) =
+        setup_contacts_service(&mut runtime, backend, ContactsServiceConfig::default());
 
     let mut contacts = Vec::new();
     for i in 0..5 {
@@ -184,3 +201,64 @@ fn contacts_service_sqlite_db() {
     let connection = run_migration_and_create_sqlite_connection(&db_path).unwrap();
     test_contacts_service(ContactsServiceSqliteDatabase::new(connection));
 }
+
+#[test]
+fn contacts_that_answer_pings_are_shown_online() {
+    let mut runtime = Runtime::new().unwrap();
+    let config = ContactsServiceConfig {
+        liveness_check_interval: Duration::from_millis(100),
+        online_period: Duration::from_secs(60),
+    };
+    let (mut contacts_service, liveness_mock_state, _shutdown) =
+        setup_contacts_service(&mut runtime, ContactsServiceMemoryDatabase::new(), config);
+
+    let mut contacts = Vec::new();
+    for _ in 0..2 {
+        let (_secret_key, public_key) = PublicKey::random_keypair(&mut OsRng);
+        let contact = Contact {
+            alias: random_string(8),
+            public_key,
+        };
+        runtime
+            .block_on(contacts_service.upsert_contact(contact.clone()))
+            .unwrap();
+        contacts.push(contact);
+    }
+    let online_node_id = NodeId::from_public_key(&contacts[0].public_key);
+
+    runtime.block_on(async {
+        tokio::time::delay_for(Duration::from_millis(300)).await;
+    });
+    let pinged = liveness_mock_state
+        .take_calls()
+        .into_iter()
+        .filter_map(|call| match call {
+            LivenessRequest::SendPing(node_id) => Some(node_id),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert!(pinged.contains(&online_node_id));
+    assert!(pinged.contains(&NodeId::from_public_key(&contacts[1].public_key)));
+
+    runtime
+        .block_on(
+            liveness_mock_state.publish_event(LivenessEvent::ReceivedPong(Box::new(PingPongEvent::new(
+                online_node_id,
+                Some(42),
+                Metadata::new(),
+            )))),
+        )
+        .unwrap();
+    runtime.block_on(async {
+        tokio::time::delay_for(Duration::from_millis(100)).await;
+    });
+
+    let liveness = runtime.block_on(contacts_service.get_contacts_liveness()).unwrap();
+    assert_eq!(liveness.len(), 2);
+    assert_eq!(liveness[0].public_key, contacts[0].public_key);
+    assert_eq!(liveness[0].online_status, ContactOnlineStatus::Online);
+    assert_eq!(liveness[0].latency, Some(42));
+    assert!(liveness[0].last_seen.is_some());
+    assert_eq!(liveness[1].online_status, ContactOnlineStatus::NeverSeen);
+    assert_eq!(liveness[1].last_seen, None);
+}
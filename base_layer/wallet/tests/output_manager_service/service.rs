@@ -39,8 +39,12 @@ use tari_comms::{
 use tari_core::{
     base_node::rpc::BaseNodeWalletRpcServer,
     consensus::{ConsensusConstantsBuilder, Network},
+    mempool::{FeePerGramPercentiles, MempoolFeeStats},
     transactions::{
         fee::Fee,
+        htlc::{generate_preimage, hash_preimage, HashTimeLock, HTLC_WITNESS_WEIGHT},
+        multisig::MultisigLock,
+        one_sided::OneSidedPayment,
         tari_amount::{uT, MicroTari},
         transaction::{KernelFeatures, OutputFeatures, Transaction, UnblindedOutput},
         transaction_protocol::{
@@ -48,11 +52,15 @@ use tari_core::{
             sender::TransactionSenderMessage,
             single_receiver::SingleReceiverTransactionProtocol,
         },
-        types::{CryptoFactories, PrivateKey},
+        types::{CryptoFactories, PrivateKey, PublicKey},
         SenderTransactionProtocol,
     },
 };
-use tari_crypto::{hash::blake2::Blake256, keys::SecretKey};
+use tari_crypto::{
+    commitment::HomomorphicCommitmentFactory,
+    hash::blake2::Blake256,
+    keys::{PublicKey as PublicKeyTrait, SecretKey},
+};
 use tari_service_framework::reply_channel;
 use tari_shutdown::Shutdown;
 use tari_wallet::{
@@ -60,20 +68,29 @@ use tari_wallet::{
     output_manager_service::{
         config::OutputManagerServiceConfig,
         error::{OutputManagerError, OutputManagerStorageError},
-        handle::{OutputManagerEvent, OutputManagerHandle},
+        handle::{HtlcSigningRequest, MultisigSigningRequest, OutputManagerEvent, OutputManagerHandle},
         protocols::txo_validation_protocol::TxoValidationType,
-        service::OutputManagerService,
+        service::{suggested_fee_per_gram, OutputManagerService},
         storage::{
-            database::{DbKey, DbKeyValuePair, DbValue, OutputManagerBackend, OutputManagerDatabase, WriteOperation},
+            database::{
+                DbKey,
+                DbKeyValuePair,
+                DbValue,
+                KeyManagerState,
+                OutputManagerBackend,
+                OutputManagerDatabase,
+                WriteOperation,
+            },
             memory_db::OutputManagerMemoryDatabase,
             models::DbUnblindedOutput,
             sqlite_db::OutputManagerSqliteDatabase,
         },
+        utxo_selection::UtxoSelectionPolicy,
         TxId,
     },
     storage::sqlite_utilities::run_migration_and_create_sqlite_connection,
-    transaction_service::handle::TransactionServiceHandle,
-    types::ValidationRetryStrategy,
+    transaction_service::{handle::TransactionServiceHandle, multisig::MultisigSignRequest},
+    types::{ValidationRetryStrategy, DEFAULT_FEE_PER_GRAM},
 };
 use tempfile::tempdir;
 use tokio::{
@@ -314,21 +331,24 @@ fn fee_estimate<T: Clone + OutputManagerBackend + 'static>(backend: T) {
     // minimum fee
     let fee_per_gram = MicroTari::from(1);
     let fee = runtime
-        .block_on(oms.fee_estimate(MicroTari::from(100), fee_per_gram, 1, 1))
-        .unwrap();
+        .block_on(oms.estimate_fee(MicroTari::from(100), 1, Some(fee_per_gram)))
+        .unwrap()
+        .fee;
     assert_eq!(fee, MicroTari::from(100));
 
     let fee_per_gram = MicroTari::from(25);
     for outputs in 1..5 {
         let fee = runtime
-            .block_on(oms.fee_estimate(MicroTari::from(100), fee_per_gram, 1, outputs))
-            .unwrap();
-        assert_eq!(fee, Fee::calculate(fee_per_gram, 1, 1, outputs as usize));
+            .block_on(oms.estimate_fee(MicroTari::from(100), outputs, Some(fee_per_gram)))
+            .unwrap()
+            .fee;
+        // A kernel for each recipient, and the change output
+        assert_eq!(fee, Fee::default().calculate(fee_per_gram, outputs, 1, outputs + 1, 0));
     }
 
     // not enough funds
     let err = runtime
-        .block_on(oms.fee_estimate(MicroTari::from(2750), fee_per_gram, 1, 1))
+        .block_on(oms.estimate_fee(MicroTari::from(2750), 1, Some(fee_per_gram)))
         .unwrap_err();
     assert!(matches!(err, OutputManagerError::NotEnoughFunds));
 }
@@ -338,6 +358,89 @@ fn fee_estimate_memory_db() {
     fee_estimate(OutputManagerMemoryDatabase::new());
 }
 
+#[test]
+fn estimate_fee_accounts_for_inputs_and_change() {
+    let factories = CryptoFactories::default();
+    let mut runtime = Runtime::new().unwrap();
+    let (mut oms, _shutdown, _, _, _, _, _) =
+        setup_output_manager_service(&mut runtime, OutputManagerMemoryDatabase::new(), true);
+
+    let (_, uo) = make_input(&mut OsRng.clone(), MicroTari::from(3000), &factories.commitment);
+    runtime.block_on(oms.add_output(uo)).unwrap();
+    let (_, uo) = make_input(&mut OsRng.clone(), MicroTari::from(5000), &factories.commitment);
+    runtime.block_on(oms.add_output(uo)).unwrap();
+
+    // The base node reports no mempool fee stats, so the wallet default is suggested and used
+    let estimate = runtime
+        .block_on(oms.estimate_fee(MicroTari::from(1000), 1, None))
+        .unwrap();
+    assert_eq!(estimate.suggested_fee_per_gram, DEFAULT_FEE_PER_GRAM);
+    assert_eq!(estimate.fee_per_gram, DEFAULT_FEE_PER_GRAM);
+    assert_eq!(estimate.num_inputs, 1);
+    assert!(estimate.has_change_output);
+    assert_eq!(estimate.num_outputs, 2);
+    assert_eq!(estimate.weight, Fee::default().calculate_weight(1, 1, 2, 0));
+    assert_eq!(estimate.fee, Fee::default().calculate(DEFAULT_FEE_PER_GRAM, 1, 1, 2, 0));
+
+    // Spending more than the largest output needs both of them
+    let fee_per_gram = MicroTari::from(10);
+    let estimate = runtime
+        .block_on(oms.estimate_fee(MicroTari::from(6000), 2, Some(fee_per_gram)))
+        .unwrap();
+    assert_eq!(estimate.fee_per_gram, fee_per_gram);
+    assert_eq!(estimate.num_inputs, 2);
+    assert_eq!(estimate.num_outputs, 3);
+    // Each recipient has a kernel of its own
+    assert_eq!(estimate.fee, Fee::default().calculate(fee_per_gram, 2, 2, 3, 0));
+
+    // The minimum fee applies
+    let estimate = runtime
+        .block_on(oms.estimate_fee(MicroTari::from(100), 1, Some(MicroTari::from(1))))
+        .unwrap();
+    assert_eq!(estimate.fee, MicroTari::from(100));
+
+    let err = runtime
+        .block_on(oms.estimate_fee(MicroTari::from(8000), 1, None))
+        .unwrap_err();
+    assert!(matches!(err, OutputManagerError::NotEnoughFunds));
+
+    // The estimate is the fee that sending pays
+    let estimate = runtime
+        .block_on(oms.estimate_fee(MicroTari::from(1000), 1, Some(fee_per_gram)))
+        .unwrap();
+    let stp = runtime
+        .block_on(oms.prepare_transaction_to_send(MicroTari::from(1000), fee_per_gram, None, "".to_string()))
+        .unwrap();
+    assert_eq!(stp.get_fee_amount().unwrap(), estimate.fee);
+}
+
+#[test]
+fn suggested_fee_per_gram_follows_the_mempool() {
+    assert_eq!(suggested_fee_per_gram(None), DEFAULT_FEE_PER_GRAM);
+    assert_eq!(
+        suggested_fee_per_gram(Some(&MempoolFeeStats::default())),
+        DEFAULT_FEE_PER_GRAM
+    );
+
+    let mut stats = MempoolFeeStats {
+        tx_count: 10,
+        total_weight: 1000,
+        fee_per_gram_percentiles: FeePerGramPercentiles {
+            p10: 5,
+            p25: 10,
+            p50: 40,
+            p75: 60,
+            p90: 100,
+        },
+        ..Default::default()
+    };
+    assert_eq!(suggested_fee_per_gram(Some(&stats)), MicroTari::from(60));
+
+    // A pool of free transactions still suggests a non-zero fee per gram
+    stats.fee_per_gram_percentiles = FeePerGramPercentiles::default();
+    assert_eq!(suggested_fee_per_gram(Some(&stats)), MicroTari::from(1));
+}
+
 pub fn setup_oms_with_bn_state<T: OutputManagerBackend + 'static>(
     runtime: &mut Runtime,
     backend: T,
@@ -348,6 +451,28 @@ pub fn setup_oms_with_bn_state<T: OutputManagerBackend + 'static>(
     TransactionServiceHandle,
     BaseNodeServiceHandle,
 )
+{
+    let config = OutputManagerServiceConfig {
+        base_node_query_timeout: Duration::from_secs(10),
+        max_utxo_query_size: 2,
+        peer_dial_retry_timeout: Duration::from_secs(5),
+        ..Default::default()
+    };
+    setup_oms_with_config_and_bn_state(runtime, backend, config, height, None)
+}
+
+pub fn setup_oms_with_config_and_bn_state<T: OutputManagerBackend + 'static>(
+    runtime: &mut Runtime,
+    backend: T,
+    config: OutputManagerServiceConfig,
+    height: Option<u64>,
+    mempool_fee_stats: Option<MempoolFeeStats>,
+) -> (
+    OutputManagerHandle,
+    Shutdown,
+    TransactionServiceHandle,
+    BaseNodeServiceHandle,
+)
 {
     let shutdown = Shutdown::new();
     let factories = CryptoFactories::default();
@@ -367,6 +492,7 @@ pub fn setup_oms_with_bn_state<T: OutputManagerBackend + 'static>(
     let base_node_service_handle = BaseNodeServiceHandle::new(sender, event_publisher_bns);
     let mut mock_base_node_service = MockBaseNodeService::new(receiver_bns, shutdown.to_signal());
     mock_base_node_service.set_base_node_state(height);
+    mock_base_node_service.set_mempool_fee_stats(mempool_fee_stats);
     runtime.spawn(mock_base_node_service.run());
 
     let (connectivity_manager, connectivity_mock) = create_connectivity_mock();
@@ -375,12 +501,7 @@ pub fn setup_oms_with_bn_state<T: OutputManagerBackend + 'static>(
 
     let output_manager_service = runtime
         .block_on(OutputManagerService::new(
-            OutputManagerServiceConfig {
-                base_node_query_timeout: Duration::from_secs(10),
-                max_utxo_query_size: 2,
-                peer_dial_retry_timeout: Duration::from_secs(5),
-                ..Default::default()
-            },
+            config,
             ts_handle.clone(),
             oms_request_receiver,
             OutputManagerDatabase::new(backend),
@@ -404,6 +525,73 @@ pub fn setup_oms_with_bn_state<T: OutputManagerBackend + 'static>(
     )
 }
 
+#[test]
+fn consolidating_dust_outputs() {
+    let mut runtime = Runtime::new().unwrap();
+    let config = OutputManagerServiceConfig {
+        dust_consolidation_threshold: Some(MicroTari::from(2000)),
+        dust_consolidation_fee_per_gram: MicroTari::from(10),
+        dust_consolidation_min_outputs: 3,
+        ..Default::default()
+    };
+    let busy_mempool = MempoolFeeStats {
+        tx_count: 10,
+        fee_per_gram_percentiles: FeePerGramPercentiles {
+            p75: 25,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (mut oms, _shutdown, _, _) = setup_oms_with_config_and_bn_state(
+        &mut runtime,
+        OutputManagerMemoryDatabase::new(),
+        config.clone(),
+        Some(6),
+        Some(busy_mempool),
+    );
+    for value in &[1000, 1500, 1800, 50_000] {
+        let uo = UnblindedOutput::new(MicroTari::from(*value), PrivateKey::random(&mut OsRng), None);
+        runtime.block_on(oms.add_output(uo)).unwrap();
+    }
+
+    // The mempool is paying more than the consolidation fee per gram
+    assert_eq!(runtime.block_on(oms.consolidate_dust()).unwrap(), None);
+    assert_eq!(runtime.block_on(oms.get_unspent_outputs()).unwrap().len(), 4);
+
+    let (mut oms, _shutdown, _, _) = setup_oms_with_config_and_bn_state(
+        &mut runtime,
+        OutputManagerMemoryDatabase::new(),
+        config,
+        Some(6),
+        Some(MempoolFeeStats::default()),
+    );
+    for value in &[1000, 1500, 50_000] {
+        let uo = UnblindedOutput::new(MicroTari::from(*value), PrivateKey::random(&mut OsRng), None);
+        runtime.block_on(oms.add_output(uo)).unwrap();
+    }
+
+    // Two dust outputs are not enough
+    assert_eq!(runtime.block_on(oms.consolidate_dust()).unwrap(), None);
+
+    let uo = UnblindedOutput::new(MicroTari::from(1800), PrivateKey::random(&mut OsRng), None);
+    runtime.block_on(oms.add_output(uo)).unwrap();
+    let tx_id = runtime.block_on(oms.consolidate_dust()).unwrap();
+    assert!(tx_id.is_some());
+
+    let unspent = runtime.block_on(oms.get_unspent_outputs()).unwrap();
+    assert_eq!(unspent.len(), 1);
+    assert_eq!(unspent[0].value, MicroTari::from(50_000));
+    let pending = runtime.block_on(oms.get_pending_transactions()).unwrap();
+    let outputs = &pending[&tx_id.unwrap()];
+    assert_eq!(outputs.outputs_to_be_spent.len(), 3);
+    assert_eq!(outputs.outputs_to_be_received.len(), 1);
+    let fee = Fee::default().calculate(MicroTari::from(10), 1, 3, 1, 0);
+    assert_eq!(
+        outputs.outputs_to_be_received[0].unblinded_output.value,
+        MicroTari::from(4300) - fee
+    );
+}
+
 #[test]
 fn test_utxo_selection_no_chain_metadata_memory_db() {
     test_utxo_selection_no_chain_metadata(OutputManagerMemoryDatabase::new());
@@ -456,7 +644,10 @@ fn test_utxo_selection_no_chain_metadata<T: Clone + OutputManagerBackend + 'stat
     }
 
     // test that we can get a fee estimate with no chain metadata
-    let fee = runtime.block_on(oms.fee_estimate(amount, fee_per_gram, 1, 2)).unwrap();
+    let fee = runtime
+        .block_on(oms.estimate_fee(amount, 1, Some(fee_per_gram)))
+        .unwrap()
+        .fee;
     assert_eq!(fee, MicroTari::from(300));
 
     // test if a fee estimate would be possible with pending funds included
@@ -464,14 +655,14 @@ fn test_utxo_selection_no_chain_metadata<T: Clone + OutputManagerBackend + 'stat
     // so instead of returning "not enough funds", return "funds pending"
     let spendable_amount = (3..=10).sum::<u64>() * amount;
     let err = runtime
-        .block_on(oms.fee_estimate(spendable_amount, fee_per_gram, 1, 2))
+        .block_on(oms.estimate_fee(spendable_amount, 1, Some(fee_per_gram)))
         .unwrap_err();
     assert!(matches!(err, OutputManagerError::FundsPending));
 
     // test not enough funds
     let broke_amount = spendable_amount + MicroTari::from(2000);
     let err = runtime
-        .block_on(oms.fee_estimate(broke_amount, fee_per_gram, 1, 2))
+        .block_on(oms.estimate_fee(broke_amount, 1, Some(fee_per_gram)))
         .unwrap_err();
     assert!(matches!(err, OutputManagerError::NotEnoughFunds));
 
@@ -522,14 +713,17 @@ fn test_utxo_selection_with_chain_metadata<T: Clone + OutputManagerBackend + 'st
     assert_eq!(utxos.len(), 10);
 
     // test fee estimates
-    let fee = runtime.block_on(oms.fee_estimate(amount, fee_per_gram, 1, 2)).unwrap();
+    let fee = runtime
+        .block_on(oms.estimate_fee(amount, 1, Some(fee_per_gram)))
+        .unwrap()
+        .fee;
     assert_eq!(fee, MicroTari::from(310));
 
     // test fee estimates are maturity aware
     // even though we have utxos for the fee, they can't be spent because they are not mature yet
     let spendable_amount = (1..=6).sum::<u64>() * amount;
     let err = runtime
-        .block_on(oms.fee_estimate(spendable_amount, fee_per_gram, 1, 2))
+        .block_on(oms.estimate_fee(spendable_amount, 1, Some(fee_per_gram)))
         .unwrap_err();
     assert!(matches!(err, OutputManagerError::NotEnoughFunds));
 
@@ -579,6 +773,160 @@ fn test_utxo_selection_with_chain_metadata<T: Clone + OutputManagerBackend + 'st
     }
 }
 
+#[test]
+fn sending_transaction_with_selection_strategy_memory_db() {
+    sending_transaction_with_selection_strategy(OutputManagerMemoryDatabase::new());
+}
+
+fn sending_transaction_with_selection_strategy<T: Clone + OutputManagerBackend + 'static>(backend: T) {
+    let mut runtime = Runtime::new().unwrap();
+    let (mut oms, _shutdown, _, _) = setup_oms_with_bn_state(&mut runtime, backend, Some(6));
+
+    let amount = MicroTari::from(5000);
+    let fee_per_gram = MicroTari::from(10);
+    let exact = amount + Fee::default().calculate(fee_per_gram, 1, 1, 1, 0);
+    for value in &[
+        MicroTari::from(1000),
+        MicroTari::from(2000),
+        exact,
+        MicroTari::from(20_000),
+    ] {
+        let uo = UnblindedOutput::new(*value, PrivateKey::random(&mut OsRng), None);
+        runtime.block_on(oms.add_output(uo)).unwrap();
+    }
+
+    // Avoiding change spends the output that covers the amount and fee exactly
+    runtime
+        .block_on(oms.prepare_transaction_to_send_with_strategy(
+            UtxoSelectionPolicy::AvoidChange,
+            amount,
+            fee_per_gram,
+            None,
+            "".to_string(),
+        ))
+        .unwrap();
+    let utxos = runtime.block_on(oms.get_unspent_outputs()).unwrap();
+    assert_eq!(utxos.len(), 3);
+    assert!(!utxos.iter().any(|u| u.value == exact));
+
+    // Largest first spends the single large output
+    runtime
+        .block_on(oms.prepare_transaction_to_send_with_strategy(
+            UtxoSelectionPolicy::LargestFirst,
+            amount,
+            fee_per_gram,
+            None,
+            "".to_string(),
+        ))
+        .unwrap();
+    let utxos = runtime.block_on(oms.get_unspent_outputs()).unwrap();
+    assert_eq!(utxos.len(), 2);
+    assert!(!utxos.iter().any(|u| u.value == MicroTari::from(20_000)));
+
+    // What is left can't cover the amount until the change of the last transaction is confirmed
+    let err = runtime
+        .block_on(oms.prepare_transaction_to_send_with_strategy(
+            UtxoSelectionPolicy::SmallestFirst,
+            amount,
+            fee_per_gram,
+            None,
+            "".to_string(),
+        ))
+        .unwrap_err();
+    assert!(matches!(err, OutputManagerError::FundsPending));
+}
+
+#[test]
+fn sending_transaction_with_selected_inputs_memory_db() {
+    sending_transaction_with_selected_inputs(OutputManagerMemoryDatabase::new());
+}
+
+#[test]
+fn sending_transaction_with_selected_inputs_sqlite_db() {
+    let db_name = format!("{}.sqlite3", random_string(8).as_str());
+    let db_tempdir = tempdir().unwrap();
+    let db_folder = db_tempdir.path().to_str().unwrap().to_string();
+    let db_path = format!("{}/{}", db_folder, db_name);
+    let connection = run_migration_and_create_sqlite_connection(&db_path).unwrap();
+
+    sending_transaction_with_selected_inputs(OutputManagerSqliteDatabase::new(connection, None));
+}
+
+fn sending_transaction_with_selected_inputs<T: Clone + OutputManagerBackend + 'static>(backend: T) {
+    let factories = CryptoFactories::default();
+    let mut runtime = Runtime::new().unwrap();
+
+    // setup with chain metadata at a height of 6
+    let (mut oms, _shutdown, _, _) = setup_oms_with_bn_state(&mut runtime, backend, Some(6));
+
+    let amount = MicroTari::from(1000);
+    let fee_per_gram = MicroTari::from(10);
+    let mut inputs = Vec::new();
+    for i in &[1, 2, 3, 10] {
+        let (input, uo) = make_input_with_features(
+            &mut OsRng.clone(),
+            *i * amount,
+            &factories.commitment,
+            Some(OutputFeatures::with_maturity(*i)),
+        );
+        runtime.block_on(oms.add_output(uo)).unwrap();
+        inputs.push(input.commitment);
+    }
+
+    // an output that is not mature yet cannot be selected
+    let err = runtime
+        .block_on(oms.prepare_transaction_to_send_with_inputs(
+            vec![inputs[3].clone()],
+            amount,
+            fee_per_gram,
+            None,
+            "".to_string(),
+        ))
+        .unwrap_err();
+    assert!(matches!(err, OutputManagerError::SelectedOutputNotSpendable(_)));
+
+    // the selected outputs must cover the amount and fee
+    let err = runtime
+        .block_on(oms.prepare_transaction_to_send_with_inputs(
+            vec![inputs[0].clone()],
+            amount,
+            fee_per_gram,
+            None,
+            "".to_string(),
+        ))
+        .unwrap_err();
+    assert!(matches!(err, OutputManagerError::NotEnoughFunds));
+
+    // exactly the selected outputs are spent, even though the smallest output would be selected otherwise
+    let stp = runtime
+        .block_on(oms.prepare_transaction_to_send_with_inputs(
+            vec![inputs[1].clone(), inputs[2].clone()],
+            4 * amount,
+            fee_per_gram,
+            None,
+            "".to_string(),
+        ))
+        .unwrap();
+    assert!(stp.get_tx_id().is_ok());
+
+    let utxos = runtime.block_on(oms.get_unspent_outputs()).unwrap();
+    assert_eq!(utxos.len(), 2);
+    assert!(utxos.iter().any(|u| u.value == amount));
+    assert!(utxos.iter().any(|u| u.value == 10 * amount));
+
+    // an output that is already encumbered cannot be selected again
+    let err = runtime
+        .block_on(oms.prepare_transaction_to_send_with_inputs(
+            vec![inputs[2].clone()],
+            amount,
+            fee_per_gram,
+            None,
+            "".to_string(),
+        ))
+        .unwrap_err();
+    assert!(matches!(err, OutputManagerError::SelectedOutputNotSpendable(_)));
+}
+
 #[test]
 fn fee_estimate_sqlite_db() {
     let db_name = format!("{}.sqlite3", random_string(8).as_str());
@@ -657,7 +1005,7 @@ fn send_no_change<T: OutputManagerBackend + 'static>(backend: T) {
     let (mut oms, _shutdown, _, _, _, _, _) = setup_output_manager_service(&mut runtime, backend, true);
 
     let fee_per_gram = MicroTari::from(20);
-    let fee_without_change = Fee::calculate(fee_per_gram, 1, 2, 1);
+    let fee_without_change = Fee::default().calculate(fee_per_gram, 1, 2, 1, 0);
     let key1 = PrivateKey::random(&mut OsRng);
     let value1 = 500;
     runtime
@@ -737,7 +1085,7 @@ fn send_not_enough_for_change<T: OutputManagerBackend + 'static>(backend: T) {
     let (mut oms, _shutdown, _, _, _, _, _) = setup_output_manager_service(&mut runtime, backend, true);
 
     let fee_per_gram = MicroTari::from(20);
-    let fee_without_change = Fee::calculate(fee_per_gram, 1, 2, 1);
+    let fee_without_change = Fee::default().calculate(fee_per_gram, 1, 2, 1, 0);
     let key1 = PrivateKey::random(&mut OsRng);
     let value1 = 500;
     runtime
@@ -996,6 +1344,67 @@ fn test_get_balance_sqlite_db() {
     test_get_balance(OutputManagerSqliteDatabase::new(connection, None));
 }
 
+fn import_output<T: OutputManagerBackend + 'static>(backend: T) {
+    let factories = CryptoFactories::default();
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut oms, _shutdown, _, _, _, _, _) = setup_output_manager_service(&mut runtime, backend, true);
+
+    let spending_key = PrivateKey::random(&mut OsRng);
+    let value = MicroTari::from(5000);
+    let commitment = runtime
+        .block_on(oms.import_output(spending_key.clone(), value, OutputFeatures::default()))
+        .unwrap();
+    assert_eq!(
+        commitment,
+        factories.commitment.commit_value(&spending_key, value.into())
+    );
+
+    let balance = runtime.block_on(oms.get_balance()).unwrap();
+    assert_eq!(balance.available_balance, value);
+
+    // The imported output can be spent
+    runtime
+        .block_on(oms.prepare_transaction_to_send(MicroTari::from(1000), MicroTari::from(20), None, "".to_string()))
+        .unwrap();
+
+    let result = runtime.block_on(oms.import_output(spending_key, value, OutputFeatures::default()));
+    assert!(matches!(
+        result,
+        Err(OutputManagerError::OutputManagerStorageError(
+            OutputManagerStorageError::DuplicateOutput
+        ))
+    ));
+
+    let result = runtime.block_on(oms.import_output(
+        PrivateKey::random(&mut OsRng),
+        MicroTari::from(0),
+        OutputFeatures::default(),
+    ));
+    assert!(matches!(result, Err(OutputManagerError::InvalidImportedOutput(_))));
+
+    let (_, public_key) = PublicKey::random_keypair(&mut OsRng);
+    let features = OutputFeatures::with_multisig_lock(MultisigLock::new(1, vec![public_key]).unwrap());
+    let result = runtime.block_on(oms.import_output(PrivateKey::random(&mut OsRng), value, features));
+    assert!(matches!(result, Err(OutputManagerError::InvalidImportedOutput(_))));
+}
+
+#[test]
+fn import_output_memory_db() {
+    import_output(OutputManagerMemoryDatabase::new());
+}
+
+#[test]
+fn import_output_sqlite_db() {
+    let db_name = format!("{}.sqlite3", random_string(8).as_str());
+    let db_tempdir = tempdir().unwrap();
+    let db_folder = db_tempdir.path().to_str().unwrap().to_string();
+    let db_path = format!("{}/{}", db_folder, db_name);
+    let connection = run_migration_and_create_sqlite_connection(&db_path).unwrap();
+
+    import_output(OutputManagerSqliteDatabase::new(connection, None));
+}
+
 fn test_confirming_received_output<T: OutputManagerBackend + 'static>(backend: T) {
     let mut runtime = Runtime::new().unwrap();
 
@@ -1044,6 +1453,52 @@ fn test_confirming_received_output_sqlite_db() {
     test_confirming_received_output(OutputManagerSqliteDatabase::new(connection, None));
 }
 
+#[test]
+fn rewinding_outputs_skips_their_spend_keys() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let master_key = PrivateKey::random(&mut OsRng);
+    let with_master_key = |backend: &OutputManagerMemoryDatabase| {
+        backend
+            .write(WriteOperation::Insert(DbKeyValuePair::KeyManagerState(
+                KeyManagerState {
+                    master_key: master_key.clone(),
+                    branch_seed: "".to_string(),
+                    primary_key_index: 0,
+                },
+            )))
+            .unwrap();
+    };
+
+    let backend = OutputManagerMemoryDatabase::new();
+    with_master_key(&backend);
+    let (mut oms, _shutdown, _, _, _, _, _) = setup_output_manager_service(&mut runtime, backend, true);
+    let outputs = (0..3)
+        .map(|_| {
+            let (_tx_id, sender_message) = generate_sender_transaction_message(MicroTari::from(5000));
+            let rtp = runtime.block_on(oms.get_recipient_transaction(sender_message)).unwrap();
+            match rtp.state {
+                RecipientState::Finalized(s) => s.output,
+                RecipientState::Failed(_) => panic!("Should not be in Failed state"),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // A wallet restored from the same master key finds the outputs and continues after their spend keys
+    let restored_backend = OutputManagerMemoryDatabase::new();
+    with_master_key(&restored_backend);
+    let (mut restored_oms, _restored_shutdown, _, _, _, _, _) =
+        setup_output_manager_service(&mut runtime, restored_backend.clone(), true);
+    let rewound = runtime.block_on(restored_oms.rewind_outputs(outputs)).unwrap();
+    assert_eq!(rewound.len(), 3);
+
+    if let DbValue::KeyManagerState(km) = restored_backend.fetch(&DbKey::KeyManagerState).unwrap().unwrap() {
+        assert_eq!(km.primary_key_index, 3);
+    } else {
+        panic!("No Key Manager set");
+    }
+}
+
 fn sending_transaction_with_short_term_clear<T: Clone + OutputManagerBackend + 'static>(backend: T) {
     let factories = CryptoFactories::default();
     let mut runtime = Runtime::new().unwrap();
@@ -1143,7 +1598,7 @@ fn coin_split_with_change<T: Clone + OutputManagerBackend + 'static>(backend: T)
         .unwrap();
     assert_eq!(coin_split_tx.body.inputs().len(), 2);
     assert_eq!(coin_split_tx.body.outputs().len(), split_count + 1);
-    assert_eq!(fee, Fee::calculate(fee_per_gram, 1, 2, split_count + 1));
+    assert_eq!(fee, Fee::default().calculate(fee_per_gram, 1, 2, split_count + 1, 0));
     assert_eq!(amount, val2 + val3);
 }
 
@@ -1170,7 +1625,7 @@ fn coin_split_no_change<T: Clone + OutputManagerBackend + 'static>(backend: T) {
 
     let fee_per_gram = MicroTari::from(25);
     let split_count = 15;
-    let fee = Fee::calculate(fee_per_gram, 1, 3, 15);
+    let fee = Fee::default().calculate(fee_per_gram, 1, 3, 15, 0);
     let val1 = 4_000 * uT;
     let val2 = 5_000 * uT;
     let val3 = 6_000 * uT + fee;
@@ -1186,7 +1641,7 @@ fn coin_split_no_change<T: Clone + OutputManagerBackend + 'static>(backend: T) {
         .unwrap();
     assert_eq!(coin_split_tx.body.inputs().len(), 3);
     assert_eq!(coin_split_tx.body.outputs().len(), split_count);
-    assert_eq!(fee, Fee::calculate(fee_per_gram, 1, 3, split_count));
+    assert_eq!(fee, Fee::default().calculate(fee_per_gram, 1, 3, split_count, 0));
     assert_eq!(amount, val1 + val2 + val3);
 }
 
@@ -1206,6 +1661,363 @@ fn coin_split_no_change_sqlite_db() {
     coin_split_no_change(OutputManagerSqliteDatabase::new(connection, None));
 }
 
+fn burn_funds<T: Clone + OutputManagerBackend + 'static>(backend: T) {
+    let factories = CryptoFactories::default();
+    let mut runtime = Runtime::new().unwrap();
+    let (mut oms, _shutdown, _, _, _, _, _) = setup_output_manager_service(&mut runtime, backend, true);
+
+    let val = 10_000 * uT;
+    let (_ti, uo) = make_input(&mut OsRng.clone(), val, &factories.commitment);
+    assert!(runtime.block_on(oms.add_output(uo)).is_ok());
+
+    let fee_per_gram = MicroTari::from(25);
+    let burn_amount = 4_000 * uT;
+    let (tx_id, fee, burn_tx): (TxId, MicroTari, Transaction) = runtime
+        .block_on(oms.create_burn_transaction(burn_amount, fee_per_gram, "Burn".to_string()))
+        .unwrap();
+    assert_eq!(burn_tx.body.get_total_fee(), fee);
+    assert_eq!(burn_tx.body.inputs().len(), 1);
+    assert_eq!(
+        burn_tx.body.outputs().len(),
+        1,
+        "Only the change output should be created"
+    );
+    assert_eq!(fee, Fee::default().calculate(fee_per_gram, 1, 1, 1, 0));
+    assert_eq!(burn_tx.body.get_total_burned(), burn_amount);
+    assert!(burn_tx.body.kernels()[0].is_burn());
+    assert!(burn_tx.validate_internal_consistency(&factories, None).is_ok());
+
+    let balance = runtime.block_on(oms.get_balance()).unwrap();
+    assert_eq!(balance.available_balance, MicroTari::from(0));
+    assert_eq!(balance.pending_incoming_balance, val - burn_amount - fee);
+
+    runtime
+        .block_on(oms.confirm_transaction(tx_id, burn_tx.body.inputs().clone(), burn_tx.body.outputs().clone()))
+        .unwrap();
+    let balance = runtime.block_on(oms.get_balance()).unwrap();
+    assert_eq!(balance.available_balance, val - burn_amount - fee);
+}
+
+#[test]
+fn burn_funds_memory_db() {
+    burn_funds(OutputManagerMemoryDatabase::new());
+}
+
+#[test]
+fn burn_funds_sqlite_db() {
+    let db_name = format!("{}.sqlite3", random_string(8).as_str());
+    let db_tempdir = tempdir().unwrap();
+    let db_folder = db_tempdir.path().to_str().unwrap().to_string();
+    let db_path = format!("{}/{}", db_folder, db_name);
+    let connection = run_migration_and_create_sqlite_connection(&db_path).unwrap();
+
+    burn_funds(OutputManagerSqliteDatabase::new(connection, None));
+}
+
+fn one_sided_payment<T: Clone + OutputManagerBackend + 'static>(backend: T) {
+    let factories = CryptoFactories::default();
+    let mut runtime = Runtime::new().unwrap();
+    let (mut oms, _shutdown, _, _, _, _, _) = setup_output_manager_service(&mut runtime, backend, true);
+
+    let val = 10_000 * uT;
+    let (_ti, uo) = make_input(&mut OsRng.clone(), val, &factories.commitment);
+    assert!(runtime.block_on(oms.add_output(uo)).is_ok());
+
+    let (recipient_secret, recipient_public_key) = PublicKey::random_keypair(&mut OsRng);
+    let fee_per_gram = MicroTari::from(25);
+    let amount = 4_000 * uT;
+    let (tx_id, fee, tx, recipient_output) = runtime
+        .block_on(oms.create_one_sided_transaction(
+            recipient_public_key,
+            amount,
+            fee_per_gram,
+            None,
+            "One-sided".to_string(),
+        ))
+        .unwrap();
+    assert_eq!(tx.body.inputs().len(), 1);
+    assert_eq!(tx.body.outputs().len(), 2);
+    assert_eq!(recipient_output.value, amount);
+    let recipient_commitment = factories
+        .commitment
+        .commit_value(&recipient_output.spending_key, amount.into());
+    assert!(tx.validate_internal_consistency(&factories, None).is_ok());
+
+    // The recipient finds its output from the script and its own secret key alone
+    let script = recipient_output.features.script.clone().unwrap();
+    let payment = OneSidedPayment::from_script(&script, &recipient_secret).unwrap();
+    assert_eq!(payment.spending_key(), &recipient_output.spending_key);
+    let output = tx
+        .body
+        .outputs()
+        .iter()
+        .find(|o| o.commitment == recipient_commitment)
+        .unwrap();
+    let recovered = payment.recover_output(output, &factories).unwrap();
+    assert_eq!(recovered.value, amount);
+    assert!(OneSidedPayment::from_script(&script, &PrivateKey::random(&mut OsRng)).is_err());
+
+    // Only the change comes back to the sender
+    let balance = runtime.block_on(oms.get_balance()).unwrap();
+    assert_eq!(balance.available_balance, MicroTari::from(0));
+    assert_eq!(balance.pending_incoming_balance, val - amount - fee);
+
+    runtime
+        .block_on(oms.accept_one_sided_output(tx_id + 1, recipient_output))
+        .unwrap();
+    // The script-locked output is not part of the balance until it is claimed
+    let balance = runtime.block_on(oms.get_balance()).unwrap();
+    assert_eq!(balance.pending_incoming_balance, val - amount - fee);
+}
+
+#[test]
+fn one_sided_payment_memory_db() {
+    one_sided_payment(OutputManagerMemoryDatabase::new());
+}
+
+#[test]
+fn one_sided_payment_sqlite_db() {
+    let db_name = format!("{}.sqlite3", random_string(8).as_str());
+    let db_tempdir = tempdir().unwrap();
+    let db_folder = db_tempdir.path().to_str().unwrap().to_string();
+    let db_path = format!("{}/{}", db_folder, db_name);
+    let connection = run_migration_and_create_sqlite_connection(&db_path).unwrap();
+
+    one_sided_payment(OutputManagerSqliteDatabase::new(connection, None));
+}
+
+fn htlc_lock_and_refund<T: Clone + OutputManagerBackend + 'static>(backend: T) {
+    let factories = CryptoFactories::default();
+    let mut runtime = Runtime::new().unwrap();
+    let (mut oms, _shutdown, _, _, _, _, _) = setup_output_manager_service(&mut runtime, backend, true);
+
+    let val = 10_000 * uT;
+    let (_ti, uo) = make_input(&mut OsRng.clone(), val, &factories.commitment);
+    assert!(runtime.block_on(oms.add_output(uo)).is_ok());
+
+    // The wallet refunds with the key of its HTLC branch
+    let refund_public_key = runtime.block_on(oms.get_htlc_public_key()).unwrap();
+    let claim_public_key = PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng));
+    let htlc = HashTimeLock::new(
+        hash_preimage(&generate_preimage()),
+        claim_public_key,
+        refund_public_key,
+        10,
+    );
+
+    let fee_per_gram = MicroTari::from(25);
+    let amount = 4_000 * uT;
+    let (tx_id, fee, tx, htlc_output) = runtime
+        .block_on(oms.create_htlc_transaction(amount, htlc.clone(), fee_per_gram, "Swap".to_string()))
+        .unwrap();
+    assert_eq!(htlc_output.features.htlc, Some(htlc.clone()));
+    assert!(tx.validate_internal_consistency(&factories, None).is_ok());
+
+    // The locked output is kept for the refund, but is not part of the balance
+    let balance = runtime.block_on(oms.get_balance()).unwrap();
+    assert_eq!(balance.pending_incoming_balance, val - amount - fee);
+    runtime
+        .block_on(oms.confirm_transaction(tx_id, tx.body.inputs().clone(), tx.body.outputs().clone()))
+        .unwrap();
+    let balance = runtime.block_on(oms.get_balance()).unwrap();
+    assert_eq!(balance.available_balance, val - amount - fee);
+    let htlcs = runtime.block_on(oms.get_htlc_outputs()).unwrap();
+    assert_eq!(htlcs.len(), 1);
+    assert_eq!(htlcs[0].value, amount);
+
+    // It is never selected to fund other transactions
+    assert!(matches!(
+        runtime.block_on(oms.prepare_transaction_to_send(val - fee, fee_per_gram, None, "".to_string())),
+        Err(OutputManagerError::NotEnoughFunds)
+    ));
+
+    let commitment = factories
+        .commitment
+        .commit_value(&htlc_output.spending_key, amount.into());
+    let mut stp = runtime
+        .block_on(oms.prepare_htlc_spend(commitment.clone(), fee_per_gram, "Refund".to_string()))
+        .unwrap();
+    // The fee pays for the witness as well
+    assert_eq!(
+        stp.get_fee_amount().unwrap(),
+        Fee::default().calculate(fee_per_gram, 1, 1, 1, 0) + HTLC_WITNESS_WEIGHT * fee_per_gram
+    );
+    let witness = runtime
+        .block_on(oms.sign_htlc_witness(HtlcSigningRequest {
+            htlc: htlc.clone(),
+            preimage: None,
+            commitment: commitment.clone(),
+            kernel_excess: stp.get_kernel_excess().unwrap(),
+        }))
+        .unwrap();
+    assert!(htlc.verify_witness(&witness, &commitment).is_ok());
+    stp.add_htlc_witness(&commitment, witness).unwrap();
+    stp.finalize(KernelFeatures::empty(), &factories).unwrap();
+    let tx = stp.take_transaction().unwrap();
+    assert_eq!(tx.body.inputs()[0].min_spendable_height(), 10);
+    assert!(tx.validate_internal_consistency(&factories, None).is_ok());
+    assert!(runtime.block_on(oms.get_htlc_outputs()).unwrap().is_empty());
+}
+
+#[test]
+fn htlc_lock_and_refund_memory_db() {
+    htlc_lock_and_refund(OutputManagerMemoryDatabase::new());
+}
+
+#[test]
+fn htlc_lock_and_refund_sqlite_db() {
+    let db_name = format!("{}.sqlite3", random_string(8).as_str());
+    let db_tempdir = tempdir().unwrap();
+    let db_folder = db_tempdir.path().to_str().unwrap().to_string();
+    let db_path = format!("{}/{}", db_folder, db_name);
+    let connection = run_migration_and_create_sqlite_connection(&db_path).unwrap();
+
+    htlc_lock_and_refund(OutputManagerSqliteDatabase::new(connection, None));
+}
+
+fn multisig_lock_and_spend<T: Clone + OutputManagerBackend + 'static>(backend: T) {
+    let factories = CryptoFactories::default();
+    let mut runtime = Runtime::new().unwrap();
+    let (mut oms, _shutdown, _, _, _, _, _) = setup_output_manager_service(&mut runtime, backend, true);
+
+    let val = 10_000 * uT;
+    let (_ti, uo) = make_input(&mut OsRng.clone(), val, &factories.commitment);
+    assert!(runtime.block_on(oms.add_output(uo)).is_ok());
+
+    // A 2-of-3 lock shared by this wallet and two others
+    let public_key = runtime.block_on(oms.get_multisig_public_key()).unwrap();
+    let (cosigner_secret, cosigner_public_key) = PublicKey::random_keypair(&mut OsRng);
+    let other_public_key = PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng));
+    let lock = MultisigLock::new(2, vec![
+        public_key.clone(),
+        cosigner_public_key.clone(),
+        other_public_key,
+    ])
+    .unwrap();
+
+    let fee_per_gram = MicroTari::from(25);
+    let amount = 6_000 * uT;
+    let (tx_id, fee, tx, multisig_output) = runtime
+        .block_on(oms.create_multisig_transaction(amount, lock.clone(), fee_per_gram, "Treasury".to_string()))
+        .unwrap();
+    assert_eq!(multisig_output.features.multisig, Some(lock.clone()));
+    assert!(tx.validate_internal_consistency(&factories, None).is_ok());
+    runtime
+        .block_on(oms.confirm_transaction(tx_id, tx.body.inputs().clone(), tx.body.outputs().clone()))
+        .unwrap();
+
+    // The shared output is not part of the balance and is never selected to fund other transactions
+    let balance = runtime.block_on(oms.get_balance()).unwrap();
+    assert_eq!(balance.available_balance, val - amount - fee);
+    assert_eq!(runtime.block_on(oms.get_multisig_outputs()).unwrap().len(), 1);
+    assert!(matches!(
+        runtime.block_on(oms.prepare_transaction_to_send(val - fee, fee_per_gram, None, "".to_string())),
+        Err(OutputManagerError::NotEnoughFunds)
+    ));
+
+    let commitment = factories
+        .commitment
+        .commit_value(&multisig_output.spending_key, amount.into());
+    let spend_amount = 2_000 * uT;
+    let destination = PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng));
+    let spend = runtime
+        .block_on(oms.prepare_multisig_spend(
+            commitment.clone(),
+            destination.clone(),
+            spend_amount,
+            fee_per_gram,
+            "Payroll".to_string(),
+        ))
+        .unwrap();
+    assert_eq!(spend.recipient_output.value, spend_amount);
+    let change_output = spend.change_output.clone().unwrap();
+    assert_eq!(change_output.features.multisig, Some(lock.clone()));
+    let kernel_excess = spend.stp.get_kernel_excess().unwrap();
+
+    // A co-signer holding the output can check the outputs and kernel of the spend from the sign request
+    let mut sign_request = MultisigSignRequest {
+        lock: lock.clone(),
+        commitment: commitment.clone(),
+        kernel_excess: kernel_excess.clone(),
+        amount: spend_amount,
+        destination,
+        fee: spend.stp.get_fee_amount().unwrap(),
+        message: "Payroll".to_string(),
+        ephemeral_secret_key: spend.ephemeral_secret_key.clone(),
+        change: Some((change_output.value, change_output.spending_key.clone())),
+        offset: spend.offset.clone(),
+    };
+    assert_eq!(sign_request.check_spend(&multisig_output, &factories), Ok(()));
+    sign_request.destination = PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng));
+    assert!(sign_request.check_spend(&multisig_output, &factories).is_err());
+
+    let session_id = 7;
+    let public_nonce = runtime.block_on(oms.create_multisig_nonce(session_id)).unwrap();
+    let (cosigner_nonce, cosigner_public_nonce) = PublicKey::random_keypair(&mut OsRng);
+    let mut signers = vec![
+        lock.index_of(&public_key).unwrap(),
+        lock.index_of(&cosigner_public_key).unwrap(),
+    ];
+    signers.sort_unstable();
+    let aggregate_nonce = &public_nonce + &cosigner_public_nonce;
+    let request = MultisigSigningRequest {
+        session_id,
+        lock: lock.clone(),
+        signers: signers.clone(),
+        aggregate_nonce: aggregate_nonce.clone(),
+        commitment: commitment.clone(),
+        kernel_excess: kernel_excess.clone(),
+    };
+    let partial_signature = runtime.block_on(oms.sign_multisig(request.clone())).unwrap();
+    let cosigner_partial_signature = lock
+        .partial_sign(
+            &cosigner_secret,
+            &cosigner_nonce,
+            &signers,
+            &aggregate_nonce,
+            &commitment,
+            &kernel_excess,
+        )
+        .unwrap();
+    let witness = lock
+        .aggregate(
+            signers,
+            &[partial_signature, cosigner_partial_signature],
+            &commitment,
+            kernel_excess,
+        )
+        .unwrap();
+
+    // The nonce of a signing session can only be used once
+    assert!(matches!(
+        runtime.block_on(oms.sign_multisig(request)),
+        Err(OutputManagerError::MultisigNonceNotFound(7))
+    ));
+
+    let mut stp = spend.stp;
+    stp.add_multisig_witness(&commitment, witness).unwrap();
+    stp.finalize(KernelFeatures::empty(), &factories).unwrap();
+    let tx = stp.take_transaction().unwrap();
+    assert!(tx.validate_internal_consistency(&factories, None).is_ok());
+    assert!(runtime.block_on(oms.get_multisig_outputs()).unwrap().is_empty());
+}
+
+#[test]
+fn multisig_lock_and_spend_memory_db() {
+    multisig_lock_and_spend(OutputManagerMemoryDatabase::new());
+}
+
+#[test]
+fn multisig_lock_and_spend_sqlite_db() {
+    let db_name = format!("{}.sqlite3", random_string(8).as_str());
+    let db_tempdir = tempdir().unwrap();
+    let db_folder = db_tempdir.path().to_str().unwrap().to_string();
+    let db_path = format!("{}/{}", db_folder, db_name);
+    let connection = run_migration_and_create_sqlite_connection(&db_path).unwrap();
+
+    multisig_lock_and_spend(OutputManagerSqliteDatabase::new(connection, None));
+}
+
 fn handle_coinbase<T: Clone + OutputManagerBackend + 'static>(backend: T) {
     let mut runtime = Runtime::new().unwrap();
     let factories = CryptoFactories::default();
@@ -1842,3 +2654,55 @@ fn test_txo_validation_base_node_not_synced() {
     assert_eq!(outputs.len(), 1);
     assert!(outputs.iter().any(|o| o == &unspent_output1));
 }
+
+#[test]
+fn test_imported_relative_time_locked_output_gets_mined_height() {
+    let factories = CryptoFactories::default();
+
+    let mut runtime = Runtime::new().unwrap();
+    let backend = OutputManagerMemoryDatabase::new();
+    let db = OutputManagerDatabase::new(backend.clone());
+
+    let (mut oms, _shutdown, _ts, _mock_rpc_server, server_node_identity, rpc_service_state, _connectivity_mock_state) =
+        setup_output_manager_service(&mut runtime, backend, true);
+    let mut event_stream = oms.get_event_stream_fused();
+
+    let key = PrivateKey::random(&mut OsRng);
+    let features = OutputFeatures::with_relative_maturity(10);
+    let output = UnblindedOutput::new(MicroTari::from(1000), key.clone(), Some(features.clone()));
+    rpc_service_state.set_utxos(vec![output.as_transaction_output(&factories).unwrap()]);
+    rpc_service_state.set_utxos_mined_height(42);
+
+    runtime
+        .block_on(oms.set_base_node_public_key(server_node_identity.public_key().clone()))
+        .unwrap();
+    runtime
+        .block_on(oms.import_output(key, MicroTari::from(1000), features))
+        .unwrap();
+
+    runtime.block_on(async {
+        let mut delay = delay_for(Duration::from_secs(60)).fuse();
+        let mut success = false;
+        loop {
+            futures::select! {
+                event = event_stream.select_next_some() => {
+                    if let Ok(msg) = event {
+                        if let OutputManagerEvent::TxoValidationSuccess(_, TxoValidationType::Unspent) = (*msg).clone() {
+                            success = true;
+                            break;
+                        }
+                    }
+                },
+                () = delay => {
+                    break;
+                },
+            }
+        }
+        assert!(success, "Did not receive validation success event");
+    });
+
+    // The height is the one reported by the base node, not the height of the chain tip
+    let outputs = runtime.block_on(db.get_unspent_outputs()).unwrap();
+    assert_eq!(outputs.len(), 1);
+    assert_eq!(outputs[0].mined_height, Some(42));
+}
@@ -30,11 +30,15 @@ use diesel::result::{DatabaseErrorKind, Error::DatabaseError};
 use rand::{rngs::OsRng, RngCore};
 use std::time::Duration;
 use tari_core::transactions::{
+    asset::AssetOutputFeatures,
     tari_amount::MicroTari,
     transaction::OutputFeatures,
-    types::{CryptoFactories, PrivateKey},
+    types::{CryptoFactories, PrivateKey, PublicKey},
+};
+use tari_crypto::{
+    commitment::HomomorphicCommitmentFactory,
+    keys::{PublicKey as PublicKeyTrait, SecretKey},
 };
-use tari_crypto::{commitment::HomomorphicCommitmentFactory, keys::SecretKey};
 use tari_wallet::{
     output_manager_service::{
         error::OutputManagerStorageError,
@@ -617,3 +621,86 @@ pub async fn test_no_duplicate_outputs<T: OutputManagerBackend + 'static>(backen
     let outputs = db.get_unspent_outputs().await.unwrap();
     assert_eq!(outputs.len(), 1);
 }
+
+#[tokio_macros::test]
+pub async fn test_relative_time_locked_outputs_memory_db() {
+    test_relative_time_locked_outputs(OutputManagerMemoryDatabase::new()).await;
+}
+
+#[tokio_macros::test]
+pub async fn test_relative_time_locked_outputs_sqlite_db() {
+    let db_name = format!("{}.sqlite3", random_string(8).as_str());
+    let temp_dir = tempdir().unwrap();
+    let db_folder = temp_dir.path().to_str().unwrap().to_string();
+    let connection = run_migration_and_create_sqlite_connection(&format!("{}/{}", db_folder, db_name)).unwrap();
+
+    test_relative_time_locked_outputs(OutputManagerSqliteDatabase::new(connection, None)).await;
+}
+
+pub async fn test_relative_time_locked_outputs<T: OutputManagerBackend + 'static>(backend: T) {
+    let factories = CryptoFactories::default();
+    let db = OutputManagerDatabase::new(backend);
+
+    let (_ti, mut uo) = make_input(&mut OsRng, MicroTari::from(1000), &factories.commitment);
+    uo.features = OutputFeatures::with_relative_maturity(10);
+    let uo = DbUnblindedOutput::from_unblinded_output(uo, &factories).unwrap();
+    db.add_unspent_output(uo.clone()).await.unwrap();
+
+    // The output is time-locked until its mined height is known
+    assert!(!uo.is_spendable_at(1_000));
+    assert_eq!(db.get_timelocked_outputs(1_000).await.unwrap().len(), 1);
+
+    db.set_output_mined_height(uo.commitment.clone(), 5).await.unwrap();
+    let outputs = db.get_unspent_outputs().await.unwrap();
+    assert_eq!(outputs[0].mined_height, Some(5));
+    assert_eq!(outputs[0].unblinded_output.features.relative_maturity, 10);
+    assert!(!outputs[0].is_spendable_at(14));
+    assert!(outputs[0].is_spendable_at(15));
+    assert_eq!(db.get_timelocked_outputs(14).await.unwrap().len(), 1);
+    assert_eq!(db.get_timelocked_outputs(15).await.unwrap().len(), 0);
+}
+
+#[tokio_macros::test]
+pub async fn test_asset_outputs_memory_db() {
+    test_asset_outputs(OutputManagerMemoryDatabase::new()).await;
+}
+
+#[tokio_macros::test]
+pub async fn test_asset_outputs_sqlite_db() {
+    let db_name = format!("{}.sqlite3", random_string(8).as_str());
+    let temp_dir = tempdir().unwrap();
+    let db_folder = temp_dir.path().to_str().unwrap().to_string();
+    let connection = run_migration_and_create_sqlite_connection(&format!("{}/{}", db_folder, db_name)).unwrap();
+
+    test_asset_outputs(OutputManagerSqliteDatabase::new(connection, None)).await;
+}
+
+pub async fn test_asset_outputs<T: OutputManagerBackend + 'static>(backend: T) {
+    let factories = CryptoFactories::default();
+    let db = OutputManagerDatabase::new(backend);
+
+    let (_ti, plain) = make_input(&mut OsRng, MicroTari::from(1000), &factories.commitment);
+    db.add_unspent_output(DbUnblindedOutput::from_unblinded_output(plain, &factories).unwrap())
+        .await
+        .unwrap();
+
+    let (_, issuer) = PublicKey::random_keypair(&mut OsRng);
+    let asset = AssetOutputFeatures::new(issuer, b"gold".to_vec(), 7);
+    let (_ti, mut token) = make_input(&mut OsRng, MicroTari::from(100), &factories.commitment);
+    token.features = OutputFeatures::with_asset(asset.clone());
+    db.add_unspent_output(DbUnblindedOutput::from_unblinded_output(token, &factories).unwrap())
+        .await
+        .unwrap();
+
+    let outputs = db.get_unspent_outputs().await.unwrap();
+    assert_eq!(outputs.len(), 2);
+    let stored = outputs
+        .iter()
+        .find_map(|o| o.unblinded_output.features.asset.clone())
+        .expect("The asset features should be stored");
+    assert_eq!(stored, asset);
+
+    // Token outputs do not count towards the balance available to fund transactions
+    let balance = db.get_balance(None).await.unwrap();
+    assert_eq!(balance.available_balance, MicroTari::from(1000));
+}
@@ -35,6 +35,7 @@ use tari_core::{
         base_node::{
             ChainMetadata,
             FetchMatchingUtxos,
+            FetchUtxosByCommitment,
             FetchUtxosResponse,
             Signatures as SignaturesProto,
             TipInfoResponse,
@@ -43,13 +44,14 @@ use tari_core::{
             TxQueryResponse as TxQueryResponseProto,
             TxSubmissionResponse as TxSubmissionResponseProto,
         },
+        mempool::FeeStatsResponse,
         types::{
             Signature as SignatureProto,
             Transaction as TransactionProto,
             TransactionOutput as TransactionOutputProto,
         },
     },
-    tari_utilities::Hashable,
+    tari_utilities::{ByteArray, Hashable},
     transactions::{
         transaction::{Transaction, TransactionOutput},
         types::Signature,
@@ -83,11 +85,13 @@ pub struct BaseNodeWalletRpcMockState {
     submit_transaction_response: Arc<Mutex<TxSubmissionResponse>>,
     transaction_query_response: Arc<Mutex<TxQueryResponse>>,
     tip_info_response: Arc<Mutex<TipInfoResponse>>,
+    fee_stats_response: Arc<Mutex<FeeStatsResponse>>,
     fetch_utxos_calls: Arc<Mutex<Vec<Vec<Vec<u8>>>>>,
     response_delay: Arc<Mutex<Option<Duration>>>,
     rpc_status_error: Arc<Mutex<Option<RpcStatus>>>,
     synced: Arc<Mutex<bool>>,
     utxos: Arc<Mutex<Vec<TransactionOutput>>>,
+    utxos_mined_height: Arc<Mutex<u64>>,
 }
 
 #[allow(clippy::mutex_atomic)]
@@ -119,11 +123,13 @@ impl BaseNodeWalletRpcMockState {
                 }),
                 is_synced: true,
             })),
+            fee_stats_response: Arc::new(Mutex::new(FeeStatsResponse::default())),
             fetch_utxos_calls: Arc::new(Mutex::new(Vec::new())),
             response_delay: Arc::new(Mutex::new(None)),
             rpc_status_error: Arc::new(Mutex::new(None)),
             synced: Arc::new(Mutex::new(true)),
             utxos: Arc::new(Mutex::new(Vec::new())),
+            utxos_mined_height: Arc::new(Mutex::new(0)),
         }
     }
 
@@ -132,6 +138,11 @@ impl BaseNodeWalletRpcMockState {
         *lock = response;
     }
 
+    pub fn set_fee_stats_response(&self, response: FeeStatsResponse) {
+        let mut lock = acquire_lock!(self.fee_stats_response);
+        *lock = response;
+    }
+
     pub fn set_submit_transaction_response(&self, response: TxSubmissionResponse) {
         let mut lock = acquire_lock!(self.submit_transaction_response);
         *lock = response;
@@ -157,6 +168,12 @@ impl BaseNodeWalletRpcMockState {
         *lock = synced;
     }
 
+    /// This method sets the height that all UTXOs are reported to be mined at
+    pub fn set_utxos_mined_height(&self, height: u64) {
+        let mut lock = acquire_lock!(self.utxos_mined_height);
+        *lock = height;
+    }
+
     /// This method sets the contents of the UTXO set against which the queries will be made
     pub fn set_utxos(&self, utxos: Vec<TransactionOutput>) {
         let mut lock = acquire_lock!(self.utxos);
@@ -439,7 +456,42 @@ impl BaseNodeWalletService for BaseNodeWalletRpcMockService {
         }
 
         let sync_lock = acquire_lock!(self.state.synced);
+        let mined_height = *acquire_lock!(self.state.utxos_mined_height);
+        Ok(Response::new(FetchUtxosResponse {
+            mined_heights: vec![mined_height; result.len()],
+            outputs: result,
+            is_synced: *sync_lock,
+        }))
+    }
+
+    async fn fetch_utxos_by_commitment(
+        &self,
+        request: Request<FetchUtxosByCommitment>,
+    ) -> Result<Response<FetchUtxosResponse>, RpcStatus>
+    {
+        let delay_lock = (*acquire_lock!(self.state.response_delay));
+        if let Some(delay) = delay_lock {
+            delay_for(delay).await;
+        }
+
+        let message = request.into_message();
+        let utxos = (*acquire_lock!(self.state.utxos)).clone();
+        let result = message
+            .commitments
+            .iter()
+            .filter_map(|commitment| utxos.iter().find(|o| o.commitment.as_bytes() == commitment.as_slice()))
+            .map(|output| TransactionOutputProto::from(output.clone()))
+            .collect::<Vec<_>>();
+
+        let status_lock = acquire_lock!(self.state.rpc_status_error);
+        if let Some(status) = (*status_lock).clone() {
+            return Err(status);
+        }
+
+        let sync_lock = acquire_lock!(self.state.synced);
+        let mined_height = *acquire_lock!(self.state.utxos_mined_height);
         Ok(Response::new(FetchUtxosResponse {
+            mined_heights: vec![mined_height; result.len()],
             outputs: result,
             is_synced: *sync_lock,
         }))
@@ -462,6 +514,22 @@ impl BaseNodeWalletService for BaseNodeWalletRpcMockService {
 
         Ok(Response::new(tip_info_response_lock.clone()))
     }
+
+    async fn get_mempool_fee_stats(&self, _request: Request<()>) -> Result<Response<FeeStatsResponse>, RpcStatus> {
+        let delay_lock = (*acquire_lock!(self.state.response_delay));
+        if let Some(delay) = delay_lock {
+            delay_for(delay).await;
+        }
+
+        let status_lock = acquire_lock!(self.state.rpc_status_error);
+        if let Some(status) = (*status_lock).clone() {
+            return Err(status);
+        }
+
+        let fee_stats_response_lock = acquire_lock!(self.state.fee_stats_response);
+
+        Ok(Response::new(fee_stats_response_lock.clone()))
+    }
 }
 
 #[cfg(test)]
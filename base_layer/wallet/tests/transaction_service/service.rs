@@ -95,6 +95,7 @@ use tari_wallet::{
     },
     output_manager_service::{
         config::OutputManagerServiceConfig,
+        error::OutputManagerError,
         handle::OutputManagerHandle,
         service::OutputManagerService,
         storage::{
@@ -111,7 +112,8 @@ use tari_wallet::{
     test_utils::make_transaction_database,
     transaction_service::{
         config::TransactionServiceConfig,
-        handle::{TransactionEvent, TransactionServiceHandle},
+        error::TransactionServiceError,
+        handle::{TransactionEvent, TransactionRetry, TransactionServiceHandle},
         service::TransactionService,
         storage::{
             database::{DbKeyValuePair, TransactionBackend, TransactionDatabase, WriteOperation},
@@ -278,6 +280,8 @@ pub fn setup_transaction_service_no_comms_and_oms_backend<
     let (tx_finalized_sender, tx_finalized_receiver) = mpsc::channel(20);
     let (base_node_response_sender, base_node_response_receiver) = mpsc::channel(20);
     let (tx_cancelled_sender, tx_cancelled_receiver) = mpsc::channel(20);
+    // None of these tests take part in multisig sessions
+    let (_, multisig_receiver) = mpsc::channel::<DomainMessage<proto::MultisigMessage>>(20);
 
     let outbound_mock_state = mock_outbound_service.get_state();
     runtime.spawn(mock_outbound_service.run());
@@ -357,6 +361,7 @@ pub fn setup_transaction_service_no_comms_and_oms_backend<
         tx_finalized_receiver,
         base_node_response_receiver,
         tx_cancelled_receiver,
+        multisig_receiver,
         output_manager_service_handle.clone(),
         outbound_message_requester,
         connectivity_manager,
@@ -1125,6 +1130,7 @@ fn finalize_tx_with_incorrect_pubkey() {
     let finalized_transaction_message = proto::TransactionFinalizedMessage {
         tx_id: recipient_reply.tx_id,
         transaction: Some(tx.clone().into()),
+        one_sided_payment: None,
     };
 
     runtime
@@ -1248,6 +1254,7 @@ fn finalize_tx_with_missing_output() {
     let finalized_transaction_message = proto::TransactionFinalizedMessage {
         tx_id: recipient_reply.tx_id,
         transaction: Some(Transaction::new(vec![], vec![], vec![], PrivateKey::random(&mut OsRng)).into()),
+        one_sided_payment: None,
     };
 
     runtime
@@ -1587,6 +1594,44 @@ fn test_set_num_confirmations() {
     }
 }
 
+#[test]
+fn send_transaction_batch_is_validated_before_sending() {
+    let factories = CryptoFactories::default();
+    let mut runtime = Runtime::new().unwrap();
+
+    let db_name = format!("{}.sqlite3", random_string(8).as_str());
+    let temp_dir = tempdir().unwrap();
+    let db_folder = temp_dir.path().to_str().unwrap().to_string();
+    let connection = run_migration_and_create_sqlite_connection(&format!("{}/{}", db_folder, db_name)).unwrap();
+
+    let backend = TransactionServiceSqliteDatabase::new(connection, None);
+
+    let (mut ts, _, _, _, _, _, _, _, _, _shutdown, _, _, _) =
+        setup_transaction_service_no_comms(&mut runtime, factories, backend, None);
+
+    let result = runtime.block_on(ts.send_transaction_batch(vec![], 20 * uT, "Payroll".to_string()));
+    assert!(matches!(result, Err(TransactionServiceError::EmptyRecipientBatch)));
+
+    let recipients = vec![
+        (PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)), 1000 * uT),
+        (PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)), 2000 * uT),
+    ];
+    let result = runtime.block_on(ts.send_transaction_batch(recipients, 20 * uT, "Payroll".to_string()));
+    assert!(
+        matches!(
+            result,
+            Err(TransactionServiceError::OutputManagerError(
+                OutputManagerError::NotEnoughFunds
+            ))
+        ),
+        "Batch should be rejected when the wallet cannot cover it"
+    );
+    assert!(runtime
+        .block_on(ts.get_pending_outbound_transactions())
+        .unwrap()
+        .is_empty());
+}
+
 #[test]
 #[ignore = "test is flaky"]
 fn test_transaction_cancellation() {
@@ -2335,7 +2380,7 @@ fn test_restarting_transaction_protocols() {
     let bob = TestParams::new(&mut OsRng);
     let (utxo, input) = make_input(&mut OsRng, MicroTari(2000), &factories.commitment);
     let mut builder = SenderTransactionProtocol::builder(1);
-    let fee = Fee::calculate(MicroTari(20), 1, 1, 1);
+    let fee = Fee::default().calculate(MicroTari(20), 1, 1, 1, 0);
     builder
         .with_lock_height(0)
         .with_fee_per_gram(MicroTari(20))
@@ -2459,6 +2504,7 @@ fn test_restarting_transaction_protocols() {
     let finalized_transaction_message = proto::TransactionFinalizedMessage {
         tx_id,
         transaction: Some(tx.into()),
+        one_sided_payment: None,
     };
 
     runtime
@@ -3415,6 +3461,151 @@ fn test_transaction_resending() {
     assert_eq!(alice_finalize_message.tx_id, tx_id);
 }
 
+#[test]
+fn test_retrying_a_pending_transaction_resends_it() {
+    let factories = CryptoFactories::default();
+    let mut runtime = Runtime::new().unwrap();
+
+    let bob_node_identity =
+        NodeIdentity::random(&mut OsRng, get_next_memory_address(), PeerFeatures::COMMUNICATION_NODE).unwrap();
+
+    let db_name = format!("{}.sqlite3", random_string(8).as_str());
+    let temp_dir = tempdir().unwrap();
+    let db_folder = temp_dir.path().to_str().unwrap().to_string();
+    let connection = run_migration_and_create_sqlite_connection(&format!("{}/{}", db_folder, db_name)).unwrap();
+
+    let (mut alice_ts, mut alice_output_manager, alice_outbound_service, _, _, _, _, _, _, _shutdown, _, _, _) =
+        setup_transaction_service_no_comms(
+            &mut runtime,
+            factories.clone(),
+            TransactionServiceSqliteDatabase::new(connection, None),
+            None,
+        );
+
+    let (_utxo, uo) = make_input(&mut OsRng, 250000 * uT, &factories.commitment);
+    runtime.block_on(alice_output_manager.add_output(uo)).unwrap();
+
+    let tx_id = runtime
+        .block_on(alice_ts.send_transaction(
+            bob_node_identity.public_key().clone(),
+            10000 * uT,
+            100 * uT,
+            "Testing Message".to_string(),
+        ))
+        .unwrap();
+
+    for i in 0..=12 {
+        match runtime
+            .block_on(alice_ts.get_pending_outbound_transactions())
+            .unwrap()
+            .remove(&tx_id)
+        {
+            None => (),
+            Some(_) => break,
+        }
+        runtime.block_on(async { delay_for(Duration::from_secs(5)).await });
+        if i >= 12 {
+            panic!("Pending outbound transaction should have been added by now");
+        }
+    }
+    let _ = alice_outbound_service.take_calls();
+
+    // The resend period is far off, so the transaction is only sent again because of the retry
+    assert_eq!(
+        runtime.block_on(alice_ts.retry_transaction(tx_id)).unwrap(),
+        TransactionRetry::Resent
+    );
+    alice_outbound_service
+        .wait_call_count(2, Duration::from_secs(60))
+        .expect("Alice call wait 1");
+    for _ in 0..2 {
+        let call = alice_outbound_service.pop_call().unwrap();
+        if let TransactionSenderMessage::Single(data) = try_decode_sender_message(call.1.to_vec()).unwrap() {
+            assert_eq!(data.tx_id, tx_id);
+        } else {
+            panic!("Should be a Single Transaction Sender Message")
+        }
+    }
+
+    assert!(matches!(
+        runtime.block_on(alice_ts.retry_transaction(tx_id + 1)),
+        Err(TransactionServiceError::TransactionDoesNotExistError)
+    ));
+
+    runtime.block_on(alice_ts.cancel_transaction(tx_id)).unwrap();
+    assert!(matches!(
+        runtime.block_on(alice_ts.retry_transaction(tx_id)),
+        Err(TransactionServiceError::TransactionCannotBeRetried)
+    ));
+}
+
+#[test]
+fn test_transaction_resend_limit() {
+    let factories = CryptoFactories::default();
+    let mut runtime = Runtime::new().unwrap();
+
+    let bob_node_identity =
+        NodeIdentity::random(&mut OsRng, get_next_memory_address(), PeerFeatures::COMMUNICATION_NODE).unwrap();
+
+    let db_name = format!("{}.sqlite3", random_string(8).as_str());
+    let temp_dir = tempdir().unwrap();
+    let db_folder = temp_dir.path().to_str().unwrap().to_string();
+    let connection = run_migration_and_create_sqlite_connection(&format!("{}/{}", db_folder, db_name)).unwrap();
+
+    let (mut alice_ts, mut alice_output_manager, alice_outbound_service, _, _, _, _, _, _, _shutdown, _, _, _) =
+        setup_transaction_service_no_comms(
+            &mut runtime,
+            factories.clone(),
+            TransactionServiceSqliteDatabase::new(connection, None),
+            None,
+        );
+
+    // Resend every second but never more than once
+    runtime
+        .block_on(alice_ts.set_transaction_resend_policy(Duration::from_secs(1), Some(1)))
+        .unwrap();
+
+    let (_utxo, uo) = make_input(&mut OsRng, 250000 * uT, &factories.commitment);
+    runtime.block_on(alice_output_manager.add_output(uo)).unwrap();
+
+    let tx_id = runtime
+        .block_on(alice_ts.send_transaction(
+            bob_node_identity.public_key().clone(),
+            10000 * uT,
+            100 * uT,
+            "Testing Message".to_string(),
+        ))
+        .unwrap();
+
+    alice_outbound_service
+        .wait_call_count(1, Duration::from_secs(30))
+        .expect("Alice call wait 1");
+    runtime.block_on(async { delay_for(Duration::from_secs(5)).await });
+    let calls = alice_outbound_service.take_calls();
+    assert!(!calls.is_empty());
+    for call in calls {
+        if let TransactionSenderMessage::Single(data) = try_decode_sender_message(call.1.to_vec()).unwrap() {
+            assert_eq!(data.tx_id, tx_id);
+        } else {
+            panic!("Should be a Single Transaction Sender Message")
+        }
+    }
+
+    // The limit has been reached, so the transaction is not resent again
+    assert!(alice_outbound_service
+        .wait_call_count(1, Duration::from_secs(5))
+        .is_err());
+
+    // A retry requested by the user is not subject to the limit
+    assert_eq!(
+        runtime.block_on(alice_ts.retry_transaction(tx_id)).unwrap(),
+        TransactionRetry::Resent
+    );
+    alice_outbound_service
+        .wait_call_count(1, Duration::from_secs(30))
+        .expect("Alice call wait 2");
+}
+
 #[test]
 fn test_resend_on_startup() {
     // Test that messages are resent on startup if enough time has passed
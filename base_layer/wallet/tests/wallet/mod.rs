@@ -35,11 +35,6 @@ use tari_p2p::initialization::CommsConfig;
 use tari_shutdown::{Shutdown, ShutdownSignal};
 
 use crate::support::comms_and_services::get_next_memory_address;
-use aes_gcm::{
-    aead::{generic_array::GenericArray, NewAead},
-    Aes256Gcm,
-};
-use digest::Digest;
 use futures::{FutureExt, StreamExt};
 use std::path::Path;
 use tari_common_types::chain_metadata::ChainMetadata;
@@ -47,16 +42,17 @@ use tari_core::{
     consensus::Network,
     transactions::{tari_amount::uT, transaction::UnblindedOutput, types::PrivateKey},
 };
-use tari_crypto::common::Blake256;
 use tari_p2p::{transport::TransportType, DEFAULT_DNS_SEED_RESOLVER};
 use tari_wallet::{
+    base_node_service::handle::BaseNodeEvent,
     contacts_service::storage::{database::Contact, memory_db::ContactsServiceMemoryDatabase},
     error::{WalletError, WalletStorageError},
+    events::WalletEvent,
     output_manager_service::storage::memory_db::OutputManagerMemoryDatabase,
     storage::{
         database::{DbKeyValuePair, WalletBackend, WalletDatabase, WriteOperation},
         memory_db::WalletMemoryDatabase,
-        sqlite_db::WalletSqliteDatabase,
+        sqlite_db::{get_passphrase_kdf, WalletSqliteDatabase},
         sqlite_utilities::{
             initialize_sqlite_database_backends,
             partial_wallet_backup,
@@ -65,6 +61,7 @@ use tari_wallet::{
     },
     test_utils::make_transaction_database,
     transaction_service::{config::TransactionServiceConfig, handle::TransactionEvent},
+    util::encryption::PassphraseKdf,
     wallet::WalletConfig,
     Wallet,
     WalletSqlite,
@@ -116,6 +113,11 @@ async fn create_wallet(
         peer_seeds: Default::default(),
         dns_seeds: Default::default(),
         dns_seeds_use_dnssec: false,
+        outbound_only: false,
+        auto_update_public_address: false,
+        allow_unsigned_identities: true,
+        noise_rekey_interval: None,
+        noise_rekey_max_bytes: None,
     };
 
     let sql_database_path = comms_config
@@ -125,7 +127,7 @@ async fn create_wallet(
         .with_extension("sqlite3");
 
     let (wallet_backend, transaction_backend, output_manager_backend, contacts_backend) =
-        initialize_sqlite_database_backends(sql_database_path, passphrase).unwrap();
+        initialize_sqlite_database_backends(sql_database_path, passphrase, Default::default()).unwrap();
 
     let transaction_service_config = TransactionServiceConfig {
         resend_response_cooldown: Duration::from_secs(1),
@@ -304,12 +306,10 @@ async fn test_wallet() {
         panic!("Should not be able to instantiate encrypted wallet without cipher");
     }
 
-    let passphrase_hash = Blake256::new()
-        .chain("wrong passphrase".to_string().as_bytes())
-        .result()
-        .to_vec();
-    let key = GenericArray::from_slice(passphrase_hash.as_slice());
-    let cipher = Aes256Gcm::new(key);
+    let kdf = get_passphrase_kdf(&connection.acquire_lock()).unwrap().unwrap();
+    assert!(matches!(kdf, PassphraseKdf::Argon2id { .. }));
+
+    let cipher = kdf.cipher("wrong passphrase").unwrap();
     let result = WalletSqliteDatabase::new(connection.clone(), Some(cipher));
 
     if let Err(err) = result {
@@ -318,12 +318,7 @@ async fn test_wallet() {
         panic!("Should not be able to instantiate encrypted wallet without cipher");
     }
 
-    let passphrase_hash = Blake256::new()
-        .chain("It's turtles all the way down".to_string().as_bytes())
-        .result()
-        .to_vec();
-    let key = GenericArray::from_slice(passphrase_hash.as_slice());
-    let cipher = Aes256Gcm::new(key);
+    let cipher = kdf.cipher("It's turtles all the way down").unwrap();
     let db = WalletSqliteDatabase::new(connection, Some(cipher)).expect("Should be able to instantiate db with cipher");
     drop(db);
 
@@ -597,6 +592,11 @@ async fn test_import_utxo() {
         peer_seeds: Default::default(),
         dns_seeds: Default::default(),
         dns_seeds_use_dnssec: false,
+        outbound_only: false,
+        auto_update_public_address: false,
+        allow_unsigned_identities: true,
+        noise_rekey_interval: None,
+        noise_rekey_max_bytes: None,
     };
     let config = WalletConfig::new(
         comms_config,
@@ -647,6 +647,118 @@ async fn test_import_utxo() {
     assert_eq!(completed_tx.amount, 20000 * uT);
 }
 
+#[tokio_macros::test]
+async fn test_wallet_event_stream() {
+    let shutdown = Shutdown::new();
+    let factories = CryptoFactories::default();
+    let temp_dir = tempdir().unwrap();
+    let alice_identity =
+        NodeIdentity::random(&mut OsRng, get_next_memory_address(), PeerFeatures::COMMUNICATION_NODE).unwrap();
+    let base_node_identity =
+        NodeIdentity::random(&mut OsRng, get_next_memory_address(), PeerFeatures::COMMUNICATION_NODE).unwrap();
+
+    let mut alice_wallet = create_wallet(
+        alice_identity,
+        &temp_dir.path(),
+        "alice_db",
+        factories,
+        shutdown.to_signal(),
+        None,
+    )
+    .await;
+    let mut event_stream = alice_wallet.get_event_stream_fused();
+
+    alice_wallet
+        .set_base_node_peer(
+            base_node_identity.public_key().clone(),
+            get_next_memory_address().to_string(),
+        )
+        .await
+        .unwrap();
+
+    let mut delay = delay_for(Duration::from_secs(30)).fuse();
+    let mut base_node_peer_set = false;
+    loop {
+        futures::select! {
+            event = event_stream.select_next_some() => {
+                if let WalletEvent::BaseNode(event) = &*event.unwrap() {
+                    if let BaseNodeEvent::BaseNodePeerSet(peer) = &**event {
+                        assert_eq!(&peer.public_key, base_node_identity.public_key());
+                        base_node_peer_set = true;
+                        break;
+                    }
+                }
+            },
+            () = delay => break,
+        }
+    }
+    assert!(base_node_peer_set);
+}
+
+#[tokio_macros::test]
+async fn test_switching_base_node() {
+    let shutdown = Shutdown::new();
+    let factories = CryptoFactories::default();
+    let temp_dir = tempdir().unwrap();
+    let alice_identity =
+        NodeIdentity::random(&mut OsRng, get_next_memory_address(), PeerFeatures::COMMUNICATION_NODE).unwrap();
+    let base_node_identity1 =
+        NodeIdentity::random(&mut OsRng, get_next_memory_address(), PeerFeatures::COMMUNICATION_NODE).unwrap();
+    let base_node_identity2 =
+        NodeIdentity::random(&mut OsRng, get_next_memory_address(), PeerFeatures::COMMUNICATION_NODE).unwrap();
+
+    let mut alice_wallet = create_wallet(
+        alice_identity,
+        &temp_dir.path(),
+        "alice_db",
+        factories,
+        shutdown.to_signal(),
+        None,
+    )
+    .await;
+    assert!(alice_wallet.get_base_node_peer().await.is_none());
+    let mut event_stream = alice_wallet.get_event_stream_fused();
+
+    alice_wallet
+        .set_base_node(create_peer(
+            base_node_identity1.public_key().clone(),
+            get_next_memory_address(),
+        ))
+        .await
+        .unwrap();
+    alice_wallet
+        .set_base_node(create_peer(
+            base_node_identity2.public_key().clone(),
+            get_next_memory_address(),
+        ))
+        .await
+        .unwrap();
+
+    let mut delay = delay_for(Duration::from_secs(30)).fuse();
+    let mut base_nodes_changed = Vec::new();
+    loop {
+        futures::select! {
+            event = event_stream.select_next_some() => {
+                if let WalletEvent::BaseNodeChanged(peer) = &*event.unwrap() {
+                    base_nodes_changed.push(peer.public_key.clone());
+                    if base_nodes_changed.len() == 2 {
+                        break;
+                    }
+                }
+            },
+            () = delay => break,
+        }
+    }
+    assert_eq!(base_nodes_changed, vec![
+        base_node_identity1.public_key().clone(),
+        base_node_identity2.public_key().clone()
+    ]);
+    assert_eq!(
+        &alice_wallet.get_base_node_peer().await.unwrap().public_key,
+        base_node_identity2.public_key()
+    );
+}
+
 #[cfg(feature = "test_harness")]
 #[tokio_macros::test]
 async fn test_data_generation() {
@@ -680,6 +792,11 @@ async fn test_data_generation() {
         peer_seeds: Default::default(),
         dns_seeds: Default::default(),
         dns_seeds_use_dnssec: false,
+        outbound_only: false,
+        auto_update_public_address: false,
+        allow_unsigned_identities: true,
+        noise_rekey_interval: None,
+        noise_rekey_max_bytes: None,
     };
 
     let config = WalletConfig::new(comms_config, factories, None, None, Network::Stibbons, None, None, None);
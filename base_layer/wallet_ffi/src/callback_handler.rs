@@ -71,7 +71,7 @@ use tari_wallet::{
 const LOG_TARGET: &str = "wallet::transaction_service::callback_handler";
 
 #[derive(Clone, Copy)]
-enum CallbackValidationResults {
+pub(crate) enum CallbackValidationResults {
     Success,           // 0
     Aborted,           // 1
     Failure,           // 2
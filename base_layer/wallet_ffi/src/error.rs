@@ -54,6 +54,8 @@ pub enum InterfaceError {
     InvalidEmojiId,
     #[error("Comms Private Key is not present while Db appears to be encrypted which should not happen")]
     MissingCommsPrivateKey,
+    #[error("The version `{0}` of the wallet event callbacks is not supported")]
+    UnsupportedCallbacksVersion(u32),
 }
 
 /// This struct is meant to hold an error for use by FFI client applications. The error has an integer code and string
@@ -92,6 +94,10 @@ impl From<InterfaceError> for LibWalletError {
                 code: 7,
                 message: format!("{:?}", v),
             },
+            InterfaceError::UnsupportedCallbacksVersion(_) => Self {
+                code: 8,
+                message: format!("{:?}", v),
+            },
         }
     }
 }
@@ -276,6 +282,10 @@ impl From<WalletError> for LibWalletError {
                 code: 428,
                 message: format!("{:?}", w),
             },
+            WalletError::WalletStorageError(WalletStorageError::InvalidKdfParameters(_)) => Self {
+                code: 429,
+                message: format!("{:?}", w),
+            },
             // This is the catch all error code. Any error that is not explicitly mapped above will be given this code
             _ => Self {
                 code: 999,
@@ -0,0 +1,507 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! # Wallet Event Callbacks
+//! The EventCallbackHandler calls the callbacks of a `TariWalletEventCallbacks` struct for the events of the wallet
+//! event stream and for the receipt of store-and-forward messages, so that a client application is told about changes
+//! to the wallet state instead of having to poll for them. Every callback is optional.
+//!
+//! ## Versions
+//! The client sets `version` to the version of the struct that it was built against. Later versions only add
+//! callbacks at the end of the struct. The struct is read with the layout of the version it is set to, so the shorter
+//! struct of a client built against an earlier version remains valid, and the callbacks it lacks are not called.
+//!
+//! Version 1 has these callbacks:
+//!
+//! `callback_validation_complete` - Called when a validation of the outputs or transactions of the wallet completes,
+//! with the `ValidationKind`, the request key of the validation and the `CallbackValidationResults` of it
+//!
+//! `callback_base_node_changed` - Called with the public key of the base node once every wallet service has switched
+//! to it. The public key must be freed with `public_key_destroy`.
+//!
+//! `callback_base_node_state_changed` - Called when the state of the base node is updated, with the height of its
+//! chain tip (0 if not known), its `BaseNodeSyncState` and its `BaseNodeOnlineState`
+//!
+//! `callback_connectivity_status_changed` - Called when the connectivity of the wallet changes, with the
+//! `ConnectivityStatusKind` and the number of connected peers
+//!
+//! `callback_balance_changed` - Called when the balance changes, with the available, pending incoming, pending
+//! outgoing and time-locked balances in microTari
+//!
+//! `callback_contact_liveness_updated` - Called when a contact is seen or its online status changes, with the public
+//! key of the contact and its `ContactStatusKind`. The public key must be freed with `public_key_destroy`.
+//!
+//! `callback_saf_messages_received` - Called when store-and-forward messages were received for the wallet
+
+use crate::{callback_handler::CallbackValidationResults, error::InterfaceError};
+use futures::{stream::Fuse, StreamExt};
+use libc::{c_uchar, c_uint, c_ulonglong};
+use log::*;
+use std::{ptr, sync::Arc};
+use tari_comms::{connectivity::ConnectivityEvent, types::CommsPublicKey};
+use tari_comms_dht::event::{DhtEvent, DhtEventReceiver};
+use tari_shutdown::ShutdownSignal;
+use tari_wallet::{
+    base_node_service::{
+        handle::BaseNodeEvent,
+        service::{BaseNodeState, OnlineState},
+    },
+    contacts_service::handle::{ContactOnlineStatus, ContactsLivenessEvent},
+    events::{WalletEvent, WalletEventReceiver},
+    output_manager_service::{handle::OutputManagerEvent, protocols::txo_validation_protocol::TxoValidationType},
+    transaction_service::handle::TransactionEvent,
+};
+
+const LOG_TARGET: &str = "wallet_ffi::event_callbacks";
+
+/// The callbacks for the events of the wallet, in the layout of the latest version. See the module documentation for
+/// when each one is called.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct TariWalletEventCallbacks {
+    pub version: c_uint,
+    pub callback_validation_complete: Option<unsafe extern "C" fn(c_uchar, c_ulonglong, c_uchar)>,
+    pub callback_base_node_changed: Option<unsafe extern "C" fn(*mut CommsPublicKey)>,
+    pub callback_base_node_state_changed: Option<unsafe extern "C" fn(c_ulonglong, c_uchar, c_uchar)>,
+    pub callback_connectivity_status_changed: Option<unsafe extern "C" fn(c_uchar, c_uint)>,
+    pub callback_balance_changed: Option<unsafe extern "C" fn(c_ulonglong, c_ulonglong, c_ulonglong, c_ulonglong)>,
+    pub callback_contact_liveness_updated: Option<unsafe extern "C" fn(*mut CommsPublicKey, c_uchar)>,
+    pub callback_saf_messages_received: Option<unsafe extern "C" fn()>,
+}
+
+/// The layout of version 1 of `TariWalletEventCallbacks`, which is all that a client built against version 1 allocates
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct TariWalletEventCallbacksV1 {
+    version: c_uint,
+    callback_validation_complete: Option<unsafe extern "C" fn(c_uchar, c_ulonglong, c_uchar)>,
+    callback_base_node_changed: Option<unsafe extern "C" fn(*mut CommsPublicKey)>,
+    callback_base_node_state_changed: Option<unsafe extern "C" fn(c_ulonglong, c_uchar, c_uchar)>,
+    callback_connectivity_status_changed: Option<unsafe extern "C" fn(c_uchar, c_uint)>,
+    callback_balance_changed: Option<unsafe extern "C" fn(c_ulonglong, c_ulonglong, c_ulonglong, c_ulonglong)>,
+    callback_contact_liveness_updated: Option<unsafe extern "C" fn(*mut CommsPublicKey, c_uchar)>,
+    callback_saf_messages_received: Option<unsafe extern "C" fn()>,
+}
+
+impl From<TariWalletEventCallbacksV1> for TariWalletEventCallbacks {
+    fn from(callbacks: TariWalletEventCallbacksV1) -> Self {
+        Self {
+            version: callbacks.version,
+            callback_validation_complete: callbacks.callback_validation_complete,
+            callback_base_node_changed: callbacks.callback_base_node_changed,
+            callback_base_node_state_changed: callbacks.callback_base_node_state_changed,
+            callback_connectivity_status_changed: callbacks.callback_connectivity_status_changed,
+            callback_balance_changed: callbacks.callback_balance_changed,
+            callback_contact_liveness_updated: callbacks.callback_contact_liveness_updated,
+            callback_saf_messages_received: callbacks.callback_saf_messages_received,
+        }
+    }
+}
+
+impl TariWalletEventCallbacks {
+    /// Reads the callbacks struct of the version that it is set to, so that the struct of a client built against an
+    /// earlier version is not read past its end. The callbacks added by later versions are None.
+    ///
+    /// # Safety
+    /// `callbacks` must point to a struct of the version that its `version` field is set to
+    pub unsafe fn read(callbacks: *const TariWalletEventCallbacks) -> Result<Self, InterfaceError> {
+        let version = ptr::read(callbacks as *const c_uint);
+        match version {
+            1 => Ok(ptr::read(callbacks as *const TariWalletEventCallbacksV1).into()),
+            _ => Err(InterfaceError::UnsupportedCallbacksVersion(version)),
+        }
+    }
+}
+
+/// What a completed validation validated
+#[derive(Clone, Copy)]
+pub enum ValidationKind {
+    UnspentOutputs, // 0
+    SpentOutputs,   // 1
+    InvalidOutputs, // 2
+    Transactions,   // 3
+}
+
+impl From<TxoValidationType> for ValidationKind {
+    fn from(validation_type: TxoValidationType) -> Self {
+        match validation_type {
+            TxoValidationType::Unspent => Self::UnspentOutputs,
+            TxoValidationType::Spent => Self::SpentOutputs,
+            TxoValidationType::Invalid => Self::InvalidOutputs,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum BaseNodeSyncState {
+    Unknown, // 0
+    Syncing, // 1
+    Synced,  // 2
+}
+
+#[derive(Clone, Copy)]
+pub enum BaseNodeOnlineState {
+    Connecting, // 0
+    Online,     // 1
+    Offline,    // 2
+}
+
+#[derive(Clone, Copy)]
+pub enum ConnectivityStatusKind {
+    Initializing, // 0
+    Online,       // 1
+    Degraded,     // 2
+    Offline,      // 3
+}
+
+#[derive(Clone, Copy)]
+pub enum ContactStatusKind {
+    Online,    // 0
+    Offline,   // 1
+    NeverSeen, // 2
+}
+
+pub struct EventCallbackHandler {
+    callbacks: TariWalletEventCallbacks,
+    wallet_event_stream: Fuse<WalletEventReceiver>,
+    dht_event_stream: Fuse<DhtEventReceiver>,
+    shutdown_signal: ShutdownSignal,
+    replaced_signal: ShutdownSignal,
+}
+
+impl EventCallbackHandler {
+    /// The handler stops when the wallet shuts down, or when `replaced_signal` is triggered because other callbacks
+    /// were set
+    pub fn new(
+        callbacks: TariWalletEventCallbacks,
+        wallet_event_stream: Fuse<WalletEventReceiver>,
+        dht_event_stream: Fuse<DhtEventReceiver>,
+        shutdown_signal: ShutdownSignal,
+        replaced_signal: ShutdownSignal,
+    ) -> Self
+    {
+        Self {
+            callbacks,
+            wallet_event_stream,
+            dht_event_stream,
+            shutdown_signal,
+            replaced_signal,
+        }
+    }
+
+    pub async fn start(self) {
+        let Self {
+            callbacks,
+            mut wallet_event_stream,
+            mut dht_event_stream,
+            mut shutdown_signal,
+            mut replaced_signal,
+        } = self;
+
+        info!(target: LOG_TARGET, "Wallet event callback handler starting");
+        loop {
+            futures::select! {
+                result = wallet_event_stream.select_next_some() => match result {
+                    Ok(event) => {
+                        trace!(target: LOG_TARGET, "Wallet event callback handler event {:?}", event);
+                        call_wallet_event_callback(&callbacks, &event);
+                    },
+                    Err(e) => debug!(target: LOG_TARGET, "Lagging read on wallet event stream: {}", e),
+                },
+                result = dht_event_stream.select_next_some() => match result {
+                    Ok(event) => {
+                        if let DhtEvent::StoreAndForwardMessagesReceived = *event {
+                            if let Some(callback) = callbacks.callback_saf_messages_received {
+                                unsafe { callback() };
+                            }
+                        }
+                    },
+                    Err(e) => debug!(target: LOG_TARGET, "Lagging read on DHT event stream: {}", e),
+                },
+                complete => break,
+                _ = shutdown_signal => break,
+                _ = replaced_signal => break,
+            }
+        }
+        info!(target: LOG_TARGET, "Wallet event callback handler stopped");
+    }
+}
+
+fn call_wallet_event_callback(callbacks: &TariWalletEventCallbacks, event: &WalletEvent) {
+    match event {
+        WalletEvent::Transaction(event) => {
+            let result = match &**event {
+                TransactionEvent::TransactionValidationSuccess(id) => Some((*id, CallbackValidationResults::Success)),
+                TransactionEvent::TransactionValidationFailure(id) => Some((*id, CallbackValidationResults::Failure)),
+                TransactionEvent::TransactionValidationAborted(id) => Some((*id, CallbackValidationResults::Aborted)),
+                TransactionEvent::TransactionValidationDelayed(id) => {
+                    Some((*id, CallbackValidationResults::BaseNodeNotInSync))
+                },
+                // The other transaction events are called back through the callbacks given to `wallet_create`
+                _ => None,
+            };
+            if let Some((request_key, result)) = result {
+                validation_complete(callbacks, ValidationKind::Transactions, request_key, result);
+            }
+        },
+        WalletEvent::OutputManager(event) => {
+            let result = match &**event {
+                OutputManagerEvent::TxoValidationSuccess(id, t) => Some((*id, *t, CallbackValidationResults::Success)),
+                OutputManagerEvent::TxoValidationFailure(id, t) => Some((*id, *t, CallbackValidationResults::Failure)),
+                OutputManagerEvent::TxoValidationAborted(id, t) => Some((*id, *t, CallbackValidationResults::Aborted)),
+                OutputManagerEvent::TxoValidationDelayed(id, t) => {
+                    Some((*id, *t, CallbackValidationResults::BaseNodeNotInSync))
+                },
+                _ => None,
+            };
+            if let Some((request_key, validation_type, result)) = result {
+                validation_complete(callbacks, validation_type.into(), request_key, result);
+            }
+        },
+        WalletEvent::BaseNode(event) => {
+            if let BaseNodeEvent::BaseNodeStateChanged(state) = &**event {
+                base_node_state_changed(callbacks, state);
+            }
+        },
+        WalletEvent::BaseNodeChanged(peer) => {
+            if let Some(callback) = callbacks.callback_base_node_changed {
+                let public_key = Box::into_raw(Box::new(peer.public_key.clone()));
+                unsafe { callback(public_key) };
+            }
+        },
+        WalletEvent::Connectivity(event) => connectivity_changed(callbacks, event),
+        WalletEvent::BalanceChanged(balance) => {
+            if let Some(callback) = callbacks.callback_balance_changed {
+                unsafe {
+                    callback(
+                        u64::from(balance.available_balance),
+                        u64::from(balance.pending_incoming_balance),
+                        u64::from(balance.pending_outgoing_balance),
+                        balance.time_locked_balance.map(u64::from).unwrap_or(0),
+                    )
+                };
+            }
+        },
+        WalletEvent::Contacts(event) => {
+            let ContactsLivenessEvent::StatusUpdated(data) = &**event;
+            if let Some(callback) = callbacks.callback_contact_liveness_updated {
+                let status = match data.online_status {
+                    ContactOnlineStatus::Online => ContactStatusKind::Online,
+                    ContactOnlineStatus::Offline => ContactStatusKind::Offline,
+                    ContactOnlineStatus::NeverSeen => ContactStatusKind::NeverSeen,
+                };
+                let public_key = Box::into_raw(Box::new(data.public_key.clone()));
+                unsafe { callback(public_key, status as c_uchar) };
+            }
+        },
+    }
+}
+
+fn validation_complete(
+    callbacks: &TariWalletEventCallbacks,
+    kind: ValidationKind,
+    request_key: u64,
+    result: CallbackValidationResults,
+)
+{
+    if let Some(callback) = callbacks.callback_validation_complete {
+        unsafe { callback(kind as c_uchar, request_key, result as c_uchar) };
+    }
+}
+
+fn base_node_state_changed(callbacks: &TariWalletEventCallbacks, state: &BaseNodeState) {
+    if let Some(callback) = callbacks.callback_base_node_state_changed {
+        let height = state
+            .chain_metadata
+            .as_ref()
+            .map(|metadata| metadata.height_of_longest_chain())
+            .unwrap_or(0);
+        let sync_state = match state.is_synced {
+            None => BaseNodeSyncState::Unknown,
+            Some(false) => BaseNodeSyncState::Syncing,
+            Some(true) => BaseNodeSyncState::Synced,
+        };
+        let online_state = match state.online {
+            OnlineState::Connecting => BaseNodeOnlineState::Connecting,
+            OnlineState::Online => BaseNodeOnlineState::Online,
+            OnlineState::Offline => BaseNodeOnlineState::Offline,
+        };
+        unsafe { callback(height, sync_state as c_uchar, online_state as c_uchar) };
+    }
+}
+
+fn connectivity_changed(callbacks: &TariWalletEventCallbacks, event: &Arc<ConnectivityEvent>) {
+    if let Some(callback) = callbacks.callback_connectivity_status_changed {
+        let (status, num_peers) = match **event {
+            ConnectivityEvent::ConnectivityStateInitialized => (ConnectivityStatusKind::Initializing, 0),
+            ConnectivityEvent::ConnectivityStateOnline(n) => (ConnectivityStatusKind::Online, n),
+            ConnectivityEvent::ConnectivityStateDegraded(n) => (ConnectivityStatusKind::Degraded, n),
+            ConnectivityEvent::ConnectivityStateOffline => (ConnectivityStatusKind::Offline, 0),
+            // Only changes to the connectivity status are called back
+            _ => return,
+        };
+        unsafe { callback(status as c_uchar, num_peers as c_uint) };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::{
+        sync::{Arc, Mutex},
+        thread,
+        time::Duration,
+    };
+    use tari_core::transactions::tari_amount::MicroTari;
+    use tari_shutdown::Shutdown;
+    use tari_wallet::output_manager_service::service::Balance;
+    use tokio::{runtime::Runtime, sync::broadcast};
+
+    #[derive(Default)]
+    struct CallbackState {
+        pub validations: Vec<(u8, u64, u8)>,
+        pub connectivity: Vec<(u8, u32)>,
+        pub available_balance: u64,
+        pub saf_messages_received: u32,
+    }
+
+    lazy_static! {
+        static ref CALLBACK_STATE: Mutex<CallbackState> = Mutex::new(CallbackState::default());
+    }
+
+    unsafe extern "C" fn validation_complete_callback(kind: c_uchar, request_key: c_ulonglong, result: c_uchar) {
+        CALLBACK_STATE
+            .lock()
+            .unwrap()
+            .validations
+            .push((kind, request_key, result));
+    }
+
+    unsafe extern "C" fn connectivity_status_callback(status: c_uchar, num_peers: c_uint) {
+        CALLBACK_STATE.lock().unwrap().connectivity.push((status, num_peers));
+    }
+
+    unsafe extern "C" fn balance_callback(available: c_ulonglong, _: c_ulonglong, _: c_ulonglong, _: c_ulonglong) {
+        CALLBACK_STATE.lock().unwrap().available_balance = available;
+    }
+
+    unsafe extern "C" fn saf_messages_received_callback() {
+        CALLBACK_STATE.lock().unwrap().saf_messages_received += 1;
+    }
+
+    #[test]
+    fn test_event_callbacks() {
+        let mut runtime = Runtime::new().unwrap();
+        let (wallet_event_sender, wallet_event_receiver) = broadcast::channel(20);
+        let (dht_event_sender, dht_event_receiver) = broadcast::channel(20);
+        let shutdown = Shutdown::new();
+        let replaced = Shutdown::new();
+
+        let callbacks = TariWalletEventCallbacks {
+            version: 1,
+            callback_validation_complete: Some(validation_complete_callback),
+            callback_base_node_changed: None,
+            callback_base_node_state_changed: None,
+            callback_connectivity_status_changed: Some(connectivity_status_callback),
+            callback_balance_changed: Some(balance_callback),
+            callback_contact_liveness_updated: None,
+            callback_saf_messages_received: Some(saf_messages_received_callback),
+        };
+        let handler = EventCallbackHandler::new(
+            callbacks,
+            wallet_event_receiver.fuse(),
+            dht_event_receiver.fuse(),
+            shutdown.to_signal(),
+            replaced.to_signal(),
+        );
+        runtime.spawn(handler.start());
+
+        let events = vec![
+            WalletEvent::Transaction(Arc::new(TransactionEvent::TransactionValidationSuccess(1))),
+            WalletEvent::OutputManager(Arc::new(OutputManagerEvent::TxoValidationAborted(
+                2,
+                TxoValidationType::Spent,
+            ))),
+            WalletEvent::Connectivity(Arc::new(ConnectivityEvent::ConnectivityStateOnline(3))),
+            WalletEvent::BalanceChanged(Balance {
+                available_balance: MicroTari::from(1000),
+                time_locked_balance: None,
+                pending_incoming_balance: MicroTari::from(0),
+                pending_outgoing_balance: MicroTari::from(0),
+            }),
+        ];
+        for event in events {
+            wallet_event_sender.send(Arc::new(event)).unwrap();
+        }
+        dht_event_sender
+            .send(Arc::new(DhtEvent::StoreAndForwardMessagesReceived))
+            .unwrap();
+
+        thread::sleep(Duration::from_secs(2));
+
+        let lock = CALLBACK_STATE.lock().unwrap();
+        assert_eq!(lock.validations, vec![
+            (
+                ValidationKind::Transactions as u8,
+                1,
+                CallbackValidationResults::Success as u8
+            ),
+            (
+                ValidationKind::SpentOutputs as u8,
+                2,
+                CallbackValidationResults::Aborted as u8
+            ),
+        ]);
+        assert_eq!(lock.connectivity, vec![(ConnectivityStatusKind::Online as u8, 3)]);
+        assert_eq!(lock.available_balance, 1000);
+        assert_eq!(lock.saf_messages_received, 1);
+    }
+
+    #[test]
+    fn test_read_event_callbacks() {
+        let mut callbacks = TariWalletEventCallbacksV1 {
+            version: 1,
+            callback_validation_complete: Some(validation_complete_callback),
+            callback_base_node_changed: None,
+            callback_base_node_state_changed: None,
+            callback_connectivity_status_changed: None,
+            callback_balance_changed: None,
+            callback_contact_liveness_updated: None,
+            callback_saf_messages_received: Some(saf_messages_received_callback),
+        };
+        let pointer = &callbacks as *const TariWalletEventCallbacksV1 as *const TariWalletEventCallbacks;
+        let read = unsafe { TariWalletEventCallbacks::read(pointer) }.unwrap();
+        assert_eq!(read.version, 1);
+        assert!(read.callback_validation_complete.is_some());
+        assert!(read.callback_balance_changed.is_none());
+        assert!(read.callback_saf_messages_received.is_some());
+
+        for version in &[0, 2] {
+            callbacks.version = *version;
+            let pointer = &callbacks as *const TariWalletEventCallbacksV1 as *const TariWalletEventCallbacks;
+            match unsafe { TariWalletEventCallbacks::read(pointer) } {
+                Err(InterfaceError::UnsupportedCallbacksVersion(v)) => assert_eq!(v, *version),
+                _ => panic!("Version {} should not be supported", version),
+            }
+        }
+    }
+}
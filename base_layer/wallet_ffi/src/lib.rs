@@ -121,12 +121,14 @@ extern crate lazy_static;
 mod callback_handler;
 mod enums;
 mod error;
+mod event_callbacks;
 mod tasks;
 
 use crate::{
     callback_handler::CallbackHandler,
     enums::SeedWordPushResult,
     error::{InterfaceError, TransactionError},
+    event_callbacks::EventCallbackHandler,
     tasks::recovery_event_monitoring,
 };
 use core::ptr;
@@ -223,8 +225,10 @@ const LOG_TARGET: &str = "wallet_ffi";
 
 pub type TariTransportType = tari_p2p::transport::TransportType;
 pub type TariPublicKey = tari_comms::types::CommsPublicKey;
+pub type TariWalletEventCallbacks = event_callbacks::TariWalletEventCallbacks;
 pub type TariPrivateKey = tari_comms::types::CommsSecretKey;
 pub type TariCommsConfig = tari_p2p::initialization::CommsConfig;
+pub type TariPassphraseKdf = tari_wallet::util::encryption::KdfParameters;
 pub type TariExcess = tari_core::transactions::types::Commitment;
 pub type TariExcessPublicNonce = tari_crypto::ristretto::RistrettoPublicKey;
 pub type TariExcessSignature = tari_crypto::ristretto::RistrettoSecretKey;
@@ -255,6 +259,8 @@ pub struct TariWallet {
     wallet: WalletSqlite,
     runtime: Runtime,
     shutdown: Shutdown,
+    /// Stops the handler of the wallet event callbacks that are set, when other callbacks are set
+    event_callbacks_shutdown: Option<Shutdown>,
 }
 
 /// -------------------------------- Strings ------------------------------------------------ ///
@@ -2771,6 +2777,11 @@ pub unsafe extern "C" fn comms_config_create(
                         peer_seeds: Default::default(),
                         dns_seeds: Default::default(),
                         dns_seeds_use_dnssec: true,
+                        outbound_only: false,
+                        auto_update_public_address: false,
+                        allow_unsigned_identities: true,
+                        noise_rekey_interval: None,
+                        noise_rekey_max_bytes: None,
                     };
 
                     Box::into_raw(Box::new(config))
@@ -2861,6 +2872,69 @@ pub unsafe extern "C" fn comms_config_destroy(wc: *mut TariCommsConfig) {
 
 /// ---------------------------------------------------------------------------------------------- ///
 
+/// ---------------------------------- PassphraseKdf ---------------------------------------------///
+
+/// Creates a TariPassphraseKdf, the Argon2id costs of deriving the cipher of the wallet databases from the passphrase.
+/// A wallet whose cipher was derived with lower costs is encrypted again with these when it is created.
+///
+/// ## Arguments
+/// `memory_kib` - The memory cost in KiB, which must be at least 8192
+/// `iterations` - The number of iterations, which must be at least 1
+/// `parallelism` - The number of lanes, which must be between 1 and 16
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may not be null. Functions
+/// as an out parameter.
+///
+/// ## Returns
+/// `*mut TariPassphraseKdf` - Returns a pointer to a TariPassphraseKdf, or ptr::null_mut() if the costs are out of
+/// bounds
+///
+/// # Safety
+/// The ```passphrase_kdf_destroy``` method must be called when finished with a TariPassphraseKdf to prevent a memory
+/// leak
+#[no_mangle]
+pub unsafe extern "C" fn passphrase_kdf_create(
+    memory_kib: c_uint,
+    iterations: c_uint,
+    parallelism: c_uint,
+    error_out: *mut c_int,
+) -> *mut TariPassphraseKdf
+{
+    let mut error = 0;
+    ptr::swap(error_out, &mut error as *mut c_int);
+    let parameters = TariPassphraseKdf {
+        memory_kib,
+        iterations,
+        parallelism,
+    };
+    match parameters.validate() {
+        Ok(_) => Box::into_raw(Box::new(parameters)),
+        Err(e) => {
+            error = LibWalletError::from(WalletError::WalletStorageError(e)).code;
+            ptr::swap(error_out, &mut error as *mut c_int);
+            ptr::null_mut()
+        },
+    }
+}
+
+/// Frees memory for a TariPassphraseKdf
+///
+/// ## Arguments
+/// `kdf` - The TariPassphraseKdf pointer
+///
+/// ## Returns
+/// `()` - Does not return a value, equivalent to void in C
+///
+/// # Safety
+/// None
+#[no_mangle]
+pub unsafe extern "C" fn passphrase_kdf_destroy(kdf: *mut TariPassphraseKdf) {
+    if !kdf.is_null() {
+        Box::from_raw(kdf);
+    }
+}
+
+/// ---------------------------------------------------------------------------------------------- ///
+
 /// ------------------------------------- Wallet -------------------------------------------------///
 
 unsafe fn init_logging(log_path: *const c_char, num_rolling_log_files: c_uint, size_per_log_file_bytes: c_uint) {
@@ -2926,6 +3000,8 @@ unsafe fn init_logging(log_path: *const c_char, num_rolling_log_files: c_uint, s
 /// `passphrase` - An optional string that represents the passphrase used to
 /// encrypt/decrypt the databases for this wallet. If it is left Null no encryption is used. If the databases have been
 /// encrypted then the correct passphrase is required or this function will fail.
+/// `passphrase_kdf` - An optional TariPassphraseKdf with the Argon2id costs of deriving the cipher of the databases
+/// from the passphrase. If it is left Null the default costs are used.
 /// `callback_received_transaction` - The callback function pointer matching the
 /// function signature. This will be called when an inbound transaction is received.
 /// `callback_received_transaction_reply` - The callback function pointer matching the function signature. This will be
@@ -2971,6 +3047,7 @@ pub unsafe extern "C" fn wallet_create(
     num_rolling_log_files: c_uint,
     size_per_log_file_bytes: c_uint,
     passphrase: *const c_char,
+    passphrase_kdf: *mut TariPassphraseKdf,
     callback_received_transaction: unsafe extern "C" fn(*mut TariPendingInboundTransaction),
     callback_received_transaction_reply: unsafe extern "C" fn(*mut TariCompletedTransaction),
     callback_received_finalized_transaction: unsafe extern "C" fn(*mut TariCompletedTransaction),
@@ -3010,6 +3087,12 @@ pub unsafe extern "C" fn wallet_create(
         None
     };
 
+    let kdf_parameters = if passphrase_kdf.is_null() {
+        TariPassphraseKdf::default()
+    } else {
+        *passphrase_kdf
+    };
+
     let mut runtime = match Runtime::new() {
         Ok(r) => r,
         Err(e) => {
@@ -3028,7 +3111,7 @@ pub unsafe extern "C" fn wallet_create(
 
     debug!(target: LOG_TARGET, "Running Wallet database migrations");
     let (wallet_backend, transaction_backend, output_manager_backend, contacts_backend) =
-        match initialize_sqlite_database_backends(sql_database_path, passphrase_option) {
+        match initialize_sqlite_database_backends(sql_database_path, passphrase_option, kdf_parameters) {
             Ok((w, t, o, c)) => (w, t, o, c),
             Err(e) => {
                 error = LibWalletError::from(WalletError::WalletStorageError(e)).code;
@@ -3073,20 +3156,23 @@ pub unsafe extern "C" fn wallet_create(
 
     let shutdown = Shutdown::new();
 
+    let mut wallet_config = WalletConfig::new(
+        (*config).clone(),
+        factories,
+        Some(TransactionServiceConfig {
+            direct_send_timeout: (*config).dht.discovery_request_timeout,
+            ..Default::default()
+        }),
+        None,
+        Network::Stibbons,
+        None,
+        None,
+        None,
+    );
+    wallet_config.passphrase_kdf = kdf_parameters;
+
     w = runtime.block_on(Wallet::new(
-        WalletConfig::new(
-            (*config).clone(),
-            factories,
-            Some(TransactionServiceConfig {
-                direct_send_timeout: (*config).dht.discovery_request_timeout,
-                ..Default::default()
-            }),
-            None,
-            Network::Stibbons,
-            None,
-            None,
-            None,
-        ),
+        wallet_config,
         wallet_backend,
         transaction_backend.clone(),
         output_manager_backend,
@@ -3139,6 +3225,7 @@ pub unsafe extern "C" fn wallet_create(
                 wallet: w,
                 runtime,
                 shutdown,
+                event_callbacks_shutdown: None,
             };
 
             Box::into_raw(Box::new(tari_wallet))
@@ -3151,6 +3238,67 @@ pub unsafe extern "C" fn wallet_create(
     }
 }
 
+/// Sets the callbacks that are called for the events of the wallet, replacing the callbacks set before. These are in
+/// addition to the callbacks given to `wallet_create`.
+///
+/// ## Arguments
+/// `wallet` - The TariWallet pointer
+/// `callbacks` - The pointer to a TariWalletEventCallbacks struct, whose `version` is the version of the struct that
+/// the client was built against. Only the fields of that version are read, so a client built against an earlier
+/// version passes its own, shorter struct. Any of its callbacks may be null. The struct is copied, so it can be freed
+/// after this call.
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may not be null. Functions
+/// as an out parameter.
+///
+/// ## Returns
+/// `bool` - Returns if the callbacks were set. An unsupported `version` sets error code 8.
+///
+/// # Safety
+/// The callbacks are called from a thread of the wallet, so they must be safe to call from any thread
+#[no_mangle]
+pub unsafe extern "C" fn wallet_set_event_callbacks(
+    wallet: *mut TariWallet,
+    callbacks: *const TariWalletEventCallbacks,
+    error_out: *mut c_int,
+) -> bool
+{
+    let mut error = 0;
+    ptr::swap(error_out, &mut error as *mut c_int);
+    if wallet.is_null() {
+        error = LibWalletError::from(InterfaceError::NullError("wallet".to_string())).code;
+        ptr::swap(error_out, &mut error as *mut c_int);
+        return false;
+    }
+    if callbacks.is_null() {
+        error = LibWalletError::from(InterfaceError::NullError("callbacks".to_string())).code;
+        ptr::swap(error_out, &mut error as *mut c_int);
+        return false;
+    }
+    let callbacks = match TariWalletEventCallbacks::read(callbacks) {
+        Ok(callbacks) => callbacks,
+        Err(e) => {
+            error = LibWalletError::from(e).code;
+            ptr::swap(error_out, &mut error as *mut c_int);
+            return false;
+        },
+    };
+
+    if let Some(mut previous) = (*wallet).event_callbacks_shutdown.take() {
+        let _ = previous.trigger();
+    }
+    let replaced = Shutdown::new();
+    let handler = EventCallbackHandler::new(
+        callbacks,
+        (*wallet).wallet.get_event_stream_fused(),
+        (*wallet).wallet.dht_service.subscribe_dht_events().fuse(),
+        (*wallet).wallet.comms.shutdown_signal(),
+        replaced.to_signal(),
+    );
+    (*wallet).runtime.spawn(handler.start());
+    (*wallet).event_callbacks_shutdown = Some(replaced);
+    true
+}
+
 /// Signs a message using the public key of the TariWallet
 ///
 /// ## Arguments
@@ -3893,8 +4041,8 @@ pub unsafe extern "C" fn wallet_send_transaction(
 /// `wallet` - The TariWallet pointer
 /// `amount` - The amount
 /// `fee_per_gram` - The fee per gram
-/// `num_kernels` - The number of transaction kernels
-/// `num_outputs` - The number of outputs
+/// `num_outputs` - The number of recipients, each of which has an output and a kernel. The change output is added if
+/// one is needed.
 /// `error_out` - Pointer to an int which will be modified to an error code should one occur, may not be null. Functions
 /// as an out parameter.
 ///
@@ -3908,7 +4056,6 @@ pub unsafe extern "C" fn wallet_get_fee_estimate(
     wallet: *mut TariWallet,
     amount: c_ulonglong,
     fee_per_gram: c_ulonglong,
-    num_kernels: c_ulonglong,
     num_outputs: c_ulonglong,
     error_out: *mut c_int,
 ) -> c_ulonglong
@@ -3923,13 +4070,12 @@ pub unsafe extern "C" fn wallet_get_fee_estimate(
 
     match (*wallet)
         .runtime
-        .block_on((*wallet).wallet.output_manager_service.fee_estimate(
+        .block_on((*wallet).wallet.output_manager_service.estimate_fee(
             MicroTari::from(amount),
-            MicroTari::from(fee_per_gram),
-            num_kernels,
-            num_outputs,
+            num_outputs as usize,
+            Some(MicroTari::from(fee_per_gram)),
         )) {
-        Ok(fee) => fee.into(),
+        Ok(estimate) => estimate.fee.into(),
         Err(e) => {
             error = LibWalletError::from(WalletError::OutputManagerError(e)).code;
             ptr::swap(error_out, &mut error as *mut c_int);
@@ -6101,6 +6247,22 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_passphrase_kdf() {
+        unsafe {
+            let mut error = 0;
+            let error_ptr = &mut error as *mut c_int;
+            let kdf = passphrase_kdf_create(16 * 1024, 2, 1, error_ptr);
+            assert_eq!(error, 0);
+            assert_eq!((*kdf).memory_kib, 16 * 1024);
+            passphrase_kdf_destroy(kdf);
+
+            let kdf = passphrase_kdf_create(1, 2, 1, error_ptr);
+            assert_ne!(error, 0);
+            assert!(kdf.is_null());
+        }
+    }
+
     #[test]
     fn test_transport_type_tor() {
         unsafe {
@@ -6312,6 +6474,7 @@ mod test {
                 2,
                 10000,
                 ptr::null(),
+                ptr::null_mut(),
                 received_tx_callback,
                 received_tx_reply_callback,
                 received_tx_finalized_callback,
@@ -6360,6 +6523,7 @@ mod test {
                 0,
                 0,
                 ptr::null(),
+                ptr::null_mut(),
                 received_tx_callback_bob,
                 received_tx_reply_callback_bob,
                 received_tx_finalized_callback_bob,
@@ -6424,7 +6588,7 @@ mod test {
             }
 
             // empty wallet
-            let fee = wallet_get_fee_estimate(alice_wallet, 100, 1, 1, 1, error_ptr);
+            let fee = wallet_get_fee_estimate(alice_wallet, 100, 1, 1, error_ptr);
             assert_eq!(fee, 0);
             assert_eq!(error, 101);
 
@@ -6432,21 +6596,22 @@ mod test {
             assert_eq!(generated, true);
 
             // minimum fee
-            let fee = wallet_get_fee_estimate(alice_wallet, 100, 1, 1, 1, error_ptr);
+            let fee = wallet_get_fee_estimate(alice_wallet, 100, 1, 1, error_ptr);
             assert_eq!(fee, 100);
             assert_eq!(error, 0);
 
             for outputs in 1..5 {
-                let fee = wallet_get_fee_estimate(alice_wallet, 100, 25, 1, outputs, error_ptr);
+                let fee = wallet_get_fee_estimate(alice_wallet, 100, 25, outputs, error_ptr);
+                // A kernel for each recipient, and the change output
                 assert_eq!(
                     MicroTari::from(fee),
-                    Fee::calculate(MicroTari::from(25), 1, 1, outputs as usize)
+                    Fee::default().calculate(MicroTari::from(25), outputs as usize, 1, outputs as usize + 1, 0)
                 );
                 assert_eq!(error, 0);
             }
 
             // not enough funds
-            let fee = wallet_get_fee_estimate(alice_wallet, 1_000_000_000, 2_500, 1, 1, error_ptr);
+            let fee = wallet_get_fee_estimate(alice_wallet, 1_000_000_000, 2_500, 1, error_ptr);
             assert_eq!(fee, 0);
             assert_eq!(error, 101);
 
@@ -6652,7 +6817,7 @@ mod test {
                 .unwrap();
 
             // test "funds pending" when pending incoming would cover
-            let fee = wallet_get_fee_estimate(alice_wallet, pre_balance.available_balance.into(), 25, 1, 2, error_ptr);
+            let fee = wallet_get_fee_estimate(alice_wallet, pre_balance.available_balance.into(), 25, 1, error_ptr);
             assert_eq!(fee, 0);
             assert_eq!(error, 115);
 
@@ -6910,6 +7075,7 @@ mod test {
                 0,
                 0,
                 ptr::null(),
+                ptr::null_mut(),
                 received_tx_callback,
                 received_tx_reply_callback,
                 received_tx_finalized_callback,
@@ -7019,6 +7185,7 @@ mod test {
                 0,
                 0,
                 ptr::null(),
+                ptr::null_mut(),
                 received_tx_callback,
                 received_tx_reply_callback,
                 received_tx_finalized_callback,
@@ -7067,6 +7234,7 @@ mod test {
                 0,
                 0,
                 ptr::null(),
+                ptr::null_mut(),
                 received_tx_callback,
                 received_tx_reply_callback,
                 received_tx_finalized_callback,
@@ -7096,6 +7264,7 @@ mod test {
                 0,
                 0,
                 wrong_passphrase_const_str,
+                ptr::null_mut(),
                 received_tx_callback,
                 received_tx_reply_callback,
                 received_tx_finalized_callback,
@@ -7120,6 +7289,7 @@ mod test {
                 0,
                 0,
                 passphrase_const_str,
+                ptr::null_mut(),
                 received_tx_callback,
                 received_tx_reply_callback,
                 received_tx_finalized_callback,
@@ -7164,6 +7334,7 @@ mod test {
                 0,
                 0,
                 ptr::null(),
+                ptr::null_mut(),
                 received_tx_callback,
                 received_tx_reply_callback,
                 received_tx_finalized_callback,
@@ -7231,6 +7402,7 @@ mod test {
                 0,
                 0,
                 ptr::null(),
+                ptr::null_mut(),
                 received_tx_callback,
                 received_tx_reply_callback,
                 received_tx_finalized_callback,
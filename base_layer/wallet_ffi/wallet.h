@@ -45,6 +45,8 @@ struct ByteVector;
 
 struct TariCommsConfig;
 
+struct TariPassphraseKdf;
+
 struct TariPrivateKey;
 
 struct TariWallet;
@@ -400,6 +402,18 @@ void comms_config_set_secret_key(struct TariCommsConfig *comms_config, struct Ta
 // Frees memory for a TariCommsConfig
 void comms_config_destroy(struct TariCommsConfig *wc);
 
+/// -------------------------------- PassphraseKdf ----------------------------------------------- //
+
+// Creates the Argon2id costs of deriving the cipher of the wallet databases from the passphrase. The memory cost in
+// KiB must be at least 8192, the iterations at least 1 and the parallelism between 1 and 16, or null is returned.
+struct TariPassphraseKdf *passphrase_kdf_create(unsigned int memory_kib,
+                                                unsigned int iterations,
+                                                unsigned int parallelism,
+                                                int* error_out);
+
+// Frees memory for a TariPassphraseKdf
+void passphrase_kdf_destroy(struct TariPassphraseKdf *kdf);
+
 /// -------------------------------- TariWallet ----------------------------------------------- //
 
 /// Creates a TariWallet
@@ -415,6 +429,8 @@ void comms_config_destroy(struct TariCommsConfig *wc);
 /// `passphrase` - An optional string that represents the passphrase used to
 /// encrypt/decrypt the databases for this wallet. If it is left Null no encryption is used. If the databases have been
 /// encrypted then the correct passphrase is required or this function will fail.
+/// `passphrase_kdf` - An optional TariPassphraseKdf with the Argon2id costs of deriving the cipher of the databases
+/// from the passphrase. If it is left Null the default costs are used.
 /// `callback_received_transaction` - The callback function pointer matching the
 /// function signature. This will be called when an inbound transaction is received.
 /// `callback_received_transaction_reply` - The callback function pointer matching the function signature. This will be
@@ -466,6 +482,7 @@ struct TariWallet *wallet_create(struct TariWalletConfig *config,
                                     unsigned int num_rolling_log_files,
                                     unsigned int size_per_log_file_bytes,
                                     const char *passphrase,
+                                    struct TariPassphraseKdf *passphrase_kdf,
                                     void (*callback_received_transaction)(struct TariPendingInboundTransaction*),
                                     void (*callback_received_transaction_reply)(struct TariCompletedTransaction*),
                                     void (*callback_received_finalized_transaction)(struct TariCompletedTransaction*),
@@ -482,6 +499,39 @@ struct TariWallet *wallet_create(struct TariWalletConfig *config,
                                     void (*callback_saf_message_received)(),
                                     int* error_out);
 
+/// The callbacks for the events of the wallet, any of which may be null. `version` must be set to the version of the
+/// struct that the client was built against, which is currently 1. Later versions only add callbacks at the end, and
+/// only the fields of the given version are read, so the struct of an earlier version remains valid.
+///
+/// `callback_validation_complete` - Called when a validation completes, with the kind of validation, the request key
+/// and the CallbackValidationResults of it. The kind is 0 for unspent outputs, 1 for spent outputs, 2 for invalid
+/// outputs and 3 for transactions.
+/// `callback_base_node_changed` - Called with the public key of the base node once every wallet service has switched
+/// to it. The public key must be freed with `public_key_destroy`.
+/// `callback_base_node_state_changed` - Called with the chain tip height of the base node (0 if not known), its sync
+/// state (0 unknown, 1 syncing, 2 synced) and its online state (0 connecting, 1 online, 2 offline)
+/// `callback_connectivity_status_changed` - Called with the connectivity status of the wallet (0 initializing,
+/// 1 online, 2 degraded, 3 offline) and the number of connected peers
+/// `callback_balance_changed` - Called with the available, pending incoming, pending outgoing and time-locked balances
+/// in microTari
+/// `callback_contact_liveness_updated` - Called with the public key of a contact and its status (0 online, 1 offline,
+/// 2 never seen). The public key must be freed with `public_key_destroy`.
+/// `callback_saf_messages_received` - Called when store-and-forward messages were received for the wallet
+struct TariWalletEventCallbacks {
+    unsigned int version;
+    void (*callback_validation_complete)(unsigned char, unsigned long long, unsigned char);
+    void (*callback_base_node_changed)(struct TariPublicKey*);
+    void (*callback_base_node_state_changed)(unsigned long long, unsigned char, unsigned char);
+    void (*callback_connectivity_status_changed)(unsigned char, unsigned int);
+    void (*callback_balance_changed)(unsigned long long, unsigned long long, unsigned long long, unsigned long long);
+    void (*callback_contact_liveness_updated)(struct TariPublicKey*, unsigned char);
+    void (*callback_saf_messages_received)();
+};
+
+// Sets the callbacks for the events of the wallet, replacing any callbacks set before. The struct is copied. An
+// unsupported version sets error code 8.
+bool wallet_set_event_callbacks(struct TariWallet *wallet, const struct TariWalletEventCallbacks *callbacks, int* error_out);
+
 // Signs a message
 char* wallet_sign_message(struct TariWallet *wallet, const char* msg, int* error_out);
 
@@ -510,7 +560,7 @@ unsigned long long wallet_get_pending_incoming_balance(struct TariWallet *wallet
 unsigned long long wallet_get_pending_outgoing_balance(struct TariWallet *wallet,int* error_out);
 
 // Get a fee estimate from a TariWallet for a given amount
-unsigned long long wallet_get_fee_estimate(struct TariWallet *wallet, unsigned long long amount, unsigned long long fee_per_gram, unsigned long long num_kernels, unsigned long long num_outputs, int* error_out);
+unsigned long long wallet_get_fee_estimate(struct TariWallet *wallet, unsigned long long amount, unsigned long long fee_per_gram, unsigned long long num_outputs, int* error_out);
 
 // Get the number of mining confirmations by the wallet transaction service
 unsigned long long wallet_get_num_confirmations_required(struct TariWallet *wallet, int* error_out);
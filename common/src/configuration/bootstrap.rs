@@ -99,7 +99,8 @@ pub struct ConfigBootstrap {
     /// Create and save new node identity if one doesn't exist
     #[structopt(long, alias("create_id"))]
     pub create_id: bool,
-    /// Run in daemon mode, with no interface
+    /// Run in daemon mode, with no interface and without prompting for input, e.g. under systemd or in a container.
+    /// Missing configuration files are created with their defaults, and SIGTERM shuts the application down cleanly.
     #[structopt(short, long, alias("daemon"))]
     pub daemon_mode: bool,
     /// This will rebuild the db, adding block for block in
@@ -111,9 +112,15 @@ pub struct ConfigBootstrap {
     /// Single input command
     #[structopt(long)]
     pub command: Option<String>,
+    /// Print the results of the input file or the single input command as JSON, one object per line
+    #[structopt(long = "json")]
+    pub json_output: bool,
     /// This will clean out the orphans db at startup
     #[structopt(long, alias("clean_orphans_db"))]
     pub clean_orphans_db: bool,
+    /// Compact the blockchain database at startup, reclaiming the disk space freed by pruning
+    #[structopt(long, alias("compact_db"))]
+    pub compact_db: bool,
     /// Supply the password for the console wallet
     #[structopt(long)]
     pub password: Option<String>,
@@ -129,6 +136,19 @@ pub struct ConfigBootstrap {
     /// Supply the optional file name to save the wallet seed words into
     #[structopt(long, alias("seed_words_file_name"), parse(from_os_str))]
     pub seed_words_file_name: Option<PathBuf>,
+    /// The named wallet profile to open. Each profile has its own wallet database under the data directory.
+    #[structopt(long)]
+    pub profile: Option<String>,
+    /// Create a watch-only wallet from the view key exported by another wallet. A watch-only wallet shows the balance
+    /// of the other wallet, but has no keys with which to spend it.
+    #[structopt(long, alias("watch_only"))]
+    pub watch_only: Option<String>,
+    /// Write an encrypted backup of the console wallet database to this file and exit
+    #[structopt(long, parse(from_os_str))]
+    pub backup: Option<PathBuf>,
+    /// Restore the console wallet database from an encrypted backup file before opening it
+    #[structopt(long, alias("restore_backup"), parse(from_os_str))]
+    pub restore_backup: Option<PathBuf>,
     /// Wallet notify script
     #[structopt(long, alias("notify"))]
     pub wallet_notify: Option<PathBuf>,
@@ -152,12 +172,18 @@ impl Default for ConfigBootstrap {
             rebuild_db: false,
             input_file: None,
             command: None,
+            json_output: false,
             clean_orphans_db: false,
+            compact_db: false,
             password: None,
             change_password: false,
             recovery: false,
             seed_words: None,
             seed_words_file_name: None,
+            profile: None,
+            watch_only: None,
+            backup: None,
+            restore_backup: None,
             wallet_notify: None,
             miner_max_blocks: None,
             miner_min_diff: None,
@@ -212,7 +238,7 @@ impl ConfigBootstrap {
         }
 
         if !self.config.exists() {
-            let install = if !self.init {
+            let install = if !self.init && !self.daemon_mode {
                 prompt("Config file does not exist. We can create a default one for you now, or you can say 'no' here, \
                 and generate a customised one at https://config.tari.com.\n\
                 Would you like to try the default configuration (Y/n)?")
@@ -230,7 +256,7 @@ impl ConfigBootstrap {
         }
 
         if !self.log_config.exists() {
-            let install = if !self.init {
+            let install = if !self.init && !self.daemon_mode {
                 prompt("Logging configuration file does not exist. Would you like to create a new one (Y/n)?")
             } else {
                 true
@@ -334,10 +360,19 @@ mod test {
             "no-config-file-created",
             "--command",
             "no-command-provided",
+            "--json",
             "--seed-words-file-name",
             "no-seed-words-file-name-provided",
             "--seed-words",
             "purse soup tornado success arch expose submit",
+            "--profile",
+            "business",
+            "--watch-only",
+            "no-view-key-provided",
+            "--backup",
+            "no-backup-file-created",
+            "--restore-backup",
+            "no-backup-file-provided",
         ])
         .expect("failed to process arguments");
         assert!(bootstrap.init);
@@ -348,6 +383,7 @@ mod test {
         assert_eq!(bootstrap.log_config.to_str(), Some("no-log-config-file-created"));
         assert_eq!(bootstrap.config.to_str(), Some("no-config-file-created"));
         assert_eq!(bootstrap.command.unwrap(), "no-command-provided");
+        assert!(bootstrap.json_output);
         assert_eq!(
             bootstrap.seed_words_file_name.unwrap().to_str(),
             Some("no-seed-words-file-name-provided")
@@ -356,6 +392,13 @@ mod test {
             bootstrap.seed_words.unwrap().as_str(),
             "purse soup tornado success arch expose submit"
         );
+        assert_eq!(bootstrap.profile.unwrap(), "business");
+        assert_eq!(bootstrap.watch_only.unwrap(), "no-view-key-provided");
+        assert_eq!(bootstrap.backup.unwrap().to_str(), Some("no-backup-file-created"));
+        assert_eq!(
+            bootstrap.restore_backup.unwrap().to_str(),
+            Some("no-backup-file-provided")
+        );
 
         // Test command line argument aliases
         let bootstrap = ConfigBootstrap::from_iter_safe(vec![
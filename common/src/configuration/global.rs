@@ -26,11 +26,12 @@ use super::ConfigurationError;
 use config::{Config, ConfigError, Environment};
 use multiaddr::Multiaddr;
 use std::{
+    collections::HashMap,
     convert::TryInto,
     fmt::{Display, Formatter, Result as FormatResult},
     net::SocketAddr,
     num::{NonZeroU16, TryFromIntError},
-    path::PathBuf,
+    path::{Path, PathBuf},
     str::FromStr,
     time::Duration,
 };
@@ -54,6 +55,16 @@ pub struct GlobalConfig {
     pub listnener_liveness_max_sessions: usize,
     pub listener_liveness_allowlist_cidrs: Vec<String>,
     pub rpc_max_simultaneous_sessions: Option<usize>,
+    pub dht_num_neighbouring_nodes: usize,
+    pub dht_num_random_nodes: usize,
+    pub dht_propagation_factor: usize,
+    pub dht_broadcast_factor: usize,
+    pub dht_closest_min_additional_peers: usize,
+    pub dht_discovery_max_send_attempts: usize,
+    pub auto_update_public_address: bool,
+    pub allow_unsigned_peer_identities: bool,
+    pub noise_rekey_interval: Option<u64>,
+    pub noise_rekey_max_bytes: Option<u64>,
     pub data_dir: PathBuf,
     pub db_type: DatabaseType,
     pub db_config: LMDBConfig,
@@ -61,6 +72,8 @@ pub struct GlobalConfig {
     pub orphan_db_clean_out_threshold: usize,
     pub pruning_horizon: u64,
     pub pruned_mode_cleanup_interval: u64,
+    pub emission_schedule: Option<EmissionScheduleConfig>,
+    pub genesis_block_file: Option<PathBuf>,
     pub core_threads: Option<usize>,
     pub max_threads: Option<usize>,
     pub base_node_identity_file: PathBuf,
@@ -68,6 +81,10 @@ pub struct GlobalConfig {
     pub grpc_enabled: bool,
     pub grpc_base_node_address: SocketAddr,
     pub grpc_console_wallet_address: SocketAddr,
+    pub stratum_enabled: bool,
+    pub stratum_address: SocketAddr,
+    pub stratum_initial_share_difficulty: u64,
+    pub stratum_target_share_interval: u64,
     pub peer_seeds: Vec<String>,
     pub dns_seeds: Vec<String>,
     pub dns_seeds_name_server: SocketAddr,
@@ -96,24 +113,67 @@ pub struct GlobalConfig {
     pub transaction_direct_send_timeout: Duration,
     pub transaction_broadcast_send_timeout: Duration,
     pub transaction_routing_mechanism: String,
+    pub transaction_resend_period: Duration,
+    /// The number of times a pending transaction is resent to its counterparty, or unlimited if not set
+    pub transaction_resend_limit: Option<u32>,
+    pub transaction_broadcast_retry_backoff_factor: f32,
+    pub transaction_broadcast_retry_max_delay: Duration,
+    pub utxo_selection_strategy: String,
     pub transaction_num_confirmations_required: u64,
     pub console_wallet_password: Option<String>,
+    /// The Argon2id memory cost in KiB of deriving the cipher of the wallet database from its password, if set
+    pub wallet_passphrase_kdf_memory_kib: Option<u32>,
+    /// The Argon2id number of iterations of deriving the cipher of the wallet database from its password, if set
+    pub wallet_passphrase_kdf_iterations: Option<u32>,
+    /// The Argon2id parallelism of deriving the cipher of the wallet database from its password, if set
+    pub wallet_passphrase_kdf_parallelism: Option<u32>,
     pub wallet_command_send_wait_stage: String,
     pub wallet_command_send_wait_timeout: u64,
     pub wallet_base_node_service_peers: Vec<String>,
     pub wallet_base_node_service_refresh_interval: u64,
     pub wallet_base_node_service_request_max_age: u64,
+    pub wallet_outbound_only: bool,
+    pub wallet_otlp_endpoint: Option<String>,
+    pub wallet_health_address: Option<SocketAddr>,
     pub prevent_fee_gt_amount: bool,
-    pub monerod_url: String,
+    /// Outputs below this value in uT are periodically consolidated by the wallet, if set
+    pub wallet_dust_consolidation_threshold: Option<u64>,
+    pub wallet_dust_consolidation_interval: Duration,
+    pub wallet_dust_consolidation_fee_per_gram: u64,
+    pub wallet_dust_consolidation_min_outputs: usize,
+    /// Where the wallet keeps its master key, "Software" or "Ledger"
+    pub wallet_key_manager_backend: String,
+    /// Run the wallet over Tor as set in `[wallet.tor]`, instead of over the base node transport, if set
+    pub wallet_tor: Option<WalletTorConfig>,
+    pub monerod_urls: Vec<String>,
     pub monerod_username: String,
     pub monerod_password: String,
     pub monerod_use_auth: bool,
     pub proxy_host_address: SocketAddr,
     pub proxy_submit_to_origin: bool,
     pub force_sync_peers: Vec<String>,
+    pub assume_valid_height: Option<u64>,
+    pub assume_valid_hash: Option<String>,
     pub wait_for_initial_sync_at_startup: bool,
     pub max_randomx_vms: usize,
+    pub randomx_mode: RandomXMode,
+    pub base_node_otlp_endpoint: Option<String>,
+    pub base_node_health_address: Option<SocketAddr>,
     pub console_wallet_notify_file: Option<PathBuf>,
+    /// The URL that the console wallet posts transaction events to, as JSON objects, if set
+    pub console_wallet_notify_webhook: Option<String>,
+    /// Key bindings of the console wallet UI, mapping an action name to the key that triggers it
+    pub console_wallet_key_bindings: HashMap<String, String>,
+    /// The theme of the console wallet UI, mapping `preset` to the name of a preset and style names to colors
+    pub console_wallet_theme: HashMap<String, String>,
+    /// Lock the console wallet UI after it has been idle for this long, if set
+    pub console_wallet_idle_lock_timeout: Option<Duration>,
+    /// The HTTP endpoint that the console wallet fetches the price of one Tari in a fiat currency from, if set
+    pub console_wallet_price_feed_url: Option<String>,
+    /// The JSON pointer to the price in the responses of the price feed, or None if they are bare numbers
+    pub console_wallet_price_feed_pointer: Option<String>,
+    /// The fiat currency that the price feed quotes the price in
+    pub console_wallet_price_feed_currency: String,
     pub auto_ping_interval: u64,
     pub blocks_behind_before_considered_lagging: u64,
     pub flood_ban_max_msg_count: usize,
@@ -240,6 +300,20 @@ fn convert_node_config(network: Network, cfg: Config) -> Result<GlobalConfig, Co
         .get_int(&key)
         .map_err(|e| ConfigurationError::new(&key, &e.to_string()))? as u64;
 
+    // Custom emission schedule for test networks
+    let emission_schedule = emission_schedule_config(&cfg, network, &net_str)?;
+
+    // Custom genesis block for private networks
+    let key = config_string("base_node", &net_str, "genesis_block_file");
+    let genesis_block_file =
+        optional(cfg.get_str(&key).map(PathBuf::from)).map_err(|e| ConfigurationError::new(&key, &e.to_string()))?;
+    if genesis_block_file.is_some() && network != Network::LocalNet {
+        return Err(ConfigurationError::new(
+            &key,
+            "A custom genesis block can only be used on localnet",
+        ));
+    }
+
     // Thread counts
     let key = config_string("base_node", &net_str, "core_threads");
     let core_threads =
@@ -255,6 +329,28 @@ fn convert_node_config(network: Network, cfg: Config) -> Result<GlobalConfig, Co
         .map_err(|e| ConfigurationError::new(&key, &e.to_string()))?
         .unwrap_or(2) as usize;
 
+    let key = config_string("base_node", &net_str, "randomx_mode");
+    let randomx_mode = optional(cfg.get_str(&key))
+        .map_err(|e| ConfigurationError::new(&key, &e.to_string()))?
+        .map(|mode| mode.parse())
+        .transpose()?
+        .unwrap_or_default();
+
+    // OTLP collector that the spans of the base node are exported to
+    let key = config_string("base_node", &net_str, "otlp_endpoint");
+    let base_node_otlp_endpoint =
+        optional(cfg.get_str(&key)).map_err(|e| ConfigurationError::new(&key, &e.to_string()))?;
+
+    // Address of the HTTP server for the `/health` and `/ready` checks of the base node
+    let key = config_string("base_node", &net_str, "health_address");
+    let base_node_health_address = optional(cfg.get_str(&key))
+        .map_err(|e| ConfigurationError::new(&key, &e.to_string()))?
+        .map(|addr| {
+            addr.parse::<SocketAddr>()
+                .map_err(|e| ConfigurationError::new(&key, &e.to_string()))
+        })
+        .transpose()?;
+
     // Base node identity path
     let key = config_string("base_node", &net_str, "base_node_identity_file");
     let base_node_identity_file = cfg
@@ -315,14 +411,44 @@ fn convert_node_config(network: Network, cfg: Config) -> Result<GlobalConfig, Co
                 .map_err(|e| ConfigurationError::new(&key, &e.to_string()))
         })?;
 
-    let key = config_string("base_node", &net_str, "grpc_console_wallet_address");
-    let grpc_console_wallet_address = cfg
-        .get_str(&key)
-        .map_err(|e| ConfigurationError::new(&key, &e.to_string()))
-        .and_then(|addr| {
-            addr.parse::<SocketAddr>()
+    // The console wallet gRPC address can be set in the wallet section, which takes precedence over the base node
+    // section
+    let key = "wallet.grpc_address";
+    let grpc_console_wallet_address = match optional(cfg.get_str(key))? {
+        Some(addr) => addr
+            .parse::<SocketAddr>()
+            .map_err(|e| ConfigurationError::new(key, &e.to_string()))?,
+        None => {
+            let key = config_string("base_node", &net_str, "grpc_console_wallet_address");
+            cfg.get_str(&key)
                 .map_err(|e| ConfigurationError::new(&key, &e.to_string()))
-        })?;
+                .and_then(|addr| {
+                    addr.parse::<SocketAddr>()
+                        .map_err(|e| ConfigurationError::new(&key, &e.to_string()))
+                })?
+        },
+    };
+
+    let key = config_string("base_node", &net_str, "stratum_enabled");
+    let stratum_enabled = optional(cfg.get_bool(&key))?.unwrap_or(false);
+
+    let key = config_string("base_node", &net_str, "stratum_address");
+    let stratum_address = optional(cfg.get_str(&key))?
+        .unwrap_or_else(|| "127.0.0.1:18144".to_string())
+        .parse::<SocketAddr>()
+        .map_err(|e| ConfigurationError::new(&key, &e.to_string()))?;
+
+    let key = config_string("base_node", &net_str, "stratum_initial_share_difficulty");
+    let stratum_initial_share_difficulty = optional(cfg.get_int(&key))?.unwrap_or(10_000) as u64;
+    if stratum_initial_share_difficulty == 0 {
+        return Err(ConfigurationError::new(
+            &key,
+            "The share difficulty must be greater than zero",
+        ));
+    }
+
+    let key = config_string("base_node", &net_str, "stratum_target_share_interval");
+    let stratum_target_share_interval = optional(cfg.get_int(&key))?.unwrap_or(15) as u64;
 
     // Peer and DNS seeds
     let key = config_string("base_node", &net_str, "peer_seeds");
@@ -385,6 +511,18 @@ fn convert_node_config(network: Network, cfg: Config) -> Result<GlobalConfig, Co
     )?
     .unwrap_or_default();
 
+    // Blocks buried below this trusted block skip range proof verification during block sync
+    let key = config_string("base_node", &net_str, "assume_valid_height");
+    let assume_valid_height = optional(cfg.get_int(&key))?.map(|h| h as u64);
+    let hash_key = config_string("base_node", &net_str, "assume_valid_hash");
+    let assume_valid_hash = optional(cfg.get_str(&hash_key))?;
+    if assume_valid_height.is_some() != assume_valid_hash.is_some() {
+        return Err(ConfigurationError::new(
+            &key,
+            "assume_valid_height and assume_valid_hash must be set together",
+        ));
+    }
+
     // Liveness auto ping interval
     let key = config_string("base_node", &net_str, "auto_ping_interval");
     let auto_ping_interval = match cfg.get_int(&key) {
@@ -452,10 +590,44 @@ fn convert_node_config(network: Network, cfg: Config) -> Result<GlobalConfig, Co
         .get_bool(&key)
         .map_err(|e| ConfigurationError::new(&key, &e.to_string()))?;
 
+    let key = "wallet.dust_consolidation.threshold";
+    let wallet_dust_consolidation_threshold = optional(cfg.get_int(key))?.map(|v| v as u64);
+
+    let key = "wallet.dust_consolidation.interval";
+    let wallet_dust_consolidation_interval =
+        Duration::from_secs(optional(cfg.get_int(key))?.unwrap_or(21600).max(1) as u64);
+
+    let key = "wallet.dust_consolidation.fee_per_gram";
+    let wallet_dust_consolidation_fee_per_gram = optional(cfg.get_int(key))?.unwrap_or(5) as u64;
+
+    let key = "wallet.dust_consolidation.min_outputs";
+    let wallet_dust_consolidation_min_outputs = optional(cfg.get_int(key))?.unwrap_or(10) as usize;
+
+    let key = "wallet.key_manager_backend";
+    let wallet_key_manager_backend = optional(cfg.get_str(key))?.unwrap_or_else(|| "Software".to_string());
+
+    let wallet_tor = wallet_tor_config(&cfg, &data_dir)?;
+
     let key = "wallet.transaction_routing_mechanism";
     let transaction_routing_mechanism =
         optional(cfg.get_str(key))?.unwrap_or_else(|| "DirectAndStoreAndForward".to_string());
 
+    let key = "wallet.transaction_resend_period";
+    let transaction_resend_period = Duration::from_secs(optional(cfg.get_int(key))?.unwrap_or(3600).max(1) as u64);
+
+    let key = "wallet.transaction_resend_limit";
+    let transaction_resend_limit = optional(cfg.get_int(key))?.map(|v| v.max(0) as u32);
+
+    let key = "wallet.transaction_broadcast_retry_backoff_factor";
+    let transaction_broadcast_retry_backoff_factor = optional(cfg.get_float(key))?.unwrap_or(1.0).max(1.0) as f32;
+
+    let key = "wallet.transaction_broadcast_retry_max_delay";
+    let transaction_broadcast_retry_max_delay =
+        Duration::from_secs(optional(cfg.get_int(key))?.unwrap_or(600).max(0) as u64);
+
+    let key = "wallet.utxo_selection_strategy";
+    let utxo_selection_strategy = optional(cfg.get_str(key))?.unwrap_or_else(|| "Default".to_string());
+
     let key = "wallet.command_send_wait_stage";
     let wallet_command_send_wait_stage = optional(cfg.get_str(key))?.unwrap_or_else(|| "Broadcast".to_string());
 
@@ -475,9 +647,76 @@ fn convert_node_config(network: Network, cfg: Config) -> Result<GlobalConfig, Co
     let key = "wallet.password";
     let console_wallet_password = optional(cfg.get_str(key))?;
 
+    let kdf_parameter = |key: &str| -> Result<Option<u32>, ConfigurationError> {
+        optional(cfg.get_int(key))?
+            .map(|v| {
+                if v > 0 && v <= u32::MAX as i64 {
+                    Ok(v as u32)
+                } else {
+                    Err(ConfigurationError::new(key, "Must be a positive 32-bit integer"))
+                }
+            })
+            .transpose()
+    };
+    let wallet_passphrase_kdf_memory_kib = kdf_parameter("wallet.passphrase_kdf.memory_kib")?;
+    let wallet_passphrase_kdf_iterations = kdf_parameter("wallet.passphrase_kdf.iterations")?;
+    let wallet_passphrase_kdf_parallelism = kdf_parameter("wallet.passphrase_kdf.parallelism")?;
+
     let key = "wallet.notify";
     let console_wallet_notify_file = optional(cfg.get_str(key))?.map(PathBuf::from);
 
+    let key = "wallet.notify_webhook";
+    let console_wallet_notify_webhook = optional(cfg.get_str(key))?;
+
+    let key = "wallet.ui.keys";
+    let console_wallet_key_bindings = optional(cfg.get_table(key))?
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(action, value)| {
+            value
+                .into_str()
+                .map(|key_name| (action.clone(), key_name))
+                .map_err(|e| ConfigurationError::new(&format!("{}.{}", key, action), &e.to_string()))
+        })
+        .collect::<Result<HashMap<_, _>, _>>()?;
+
+    let key = "wallet.ui.theme";
+    let console_wallet_theme = optional(cfg.get_table(key))?
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(style, value)| {
+            value
+                .into_str()
+                .map(|color| (style.clone(), color))
+                .map_err(|e| ConfigurationError::new(&format!("{}.{}", key, style), &e.to_string()))
+        })
+        .collect::<Result<HashMap<_, _>, _>>()?;
+
+    let key = "wallet.ui.idle_lock_timeout";
+    let console_wallet_idle_lock_timeout = optional(cfg.get_int(key))?
+        .filter(|secs| *secs > 0)
+        .map(|secs| Duration::from_secs(secs as u64));
+
+    let key = "wallet.price_feed.url";
+    let console_wallet_price_feed_url = optional(cfg.get_str(key))?;
+
+    let key = "wallet.price_feed.pointer";
+    let console_wallet_price_feed_pointer = optional(cfg.get_str(key))?;
+
+    let key = "wallet.price_feed.currency";
+    let console_wallet_price_feed_currency = optional(cfg.get_str(key))?.unwrap_or_else(|| "USD".to_string());
+
+    let key = "wallet.otlp_endpoint";
+    let wallet_otlp_endpoint = optional(cfg.get_str(key))?;
+
+    let key = "wallet.health_address";
+    let wallet_health_address = optional(cfg.get_str(key))?
+        .map(|addr| {
+            addr.parse::<SocketAddr>()
+                .map_err(|e| ConfigurationError::new(key, &e.to_string()))
+        })
+        .transpose()?;
+
     let key = "wallet.base_node_service_refresh_interval";
     let wallet_base_node_service_refresh_interval = match cfg.get_int(key) {
         Ok(seconds) => seconds as u64,
@@ -492,6 +731,9 @@ fn convert_node_config(network: Network, cfg: Config) -> Result<GlobalConfig, Co
         Err(e) => return Err(ConfigurationError::new(&key, &e.to_string())),
     };
 
+    let key = "wallet.outbound_only";
+    let wallet_outbound_only = optional(cfg.get_bool(key))?.unwrap_or(false);
+
     let key = "common.liveness_max_sessions";
     let liveness_max_sessions = cfg
         .get_int(key)
@@ -518,6 +760,46 @@ fn convert_node_config(network: Network, cfg: Config) -> Result<GlobalConfig, Co
             )),
         })?;
 
+    let key = "common.dht_num_neighbouring_nodes";
+    let dht_num_neighbouring_nodes = optional(cfg.get_int(key))?.map(|v| v as usize).unwrap_or(8);
+
+    let key = "common.dht_num_random_nodes";
+    let dht_num_random_nodes = optional(cfg.get_int(key))?.map(|v| v as usize).unwrap_or(4);
+
+    let key = "common.dht_propagation_factor";
+    let dht_propagation_factor = optional(cfg.get_int(key))?.map(|v| v as usize).unwrap_or(4);
+
+    let key = "common.dht_broadcast_factor";
+    let dht_broadcast_factor = optional(cfg.get_int(key))?.map(|v| v as usize).unwrap_or(8);
+
+    let key = "common.dht_closest_min_additional_peers";
+    let dht_closest_min_additional_peers = optional(cfg.get_int(key))?.map(|v| v as usize).unwrap_or(2);
+
+    let key = "common.dht_discovery_max_send_attempts";
+    let dht_discovery_max_send_attempts = optional(cfg.get_int(key))?.map(|v| v as usize).unwrap_or(1);
+
+    let key = "common.auto_update_public_address";
+    let auto_update_public_address = optional(cfg.get_bool(key))?.unwrap_or(false);
+
+    let key = "common.allow_unsigned_peer_identities";
+    let allow_unsigned_peer_identities = optional(cfg.get_bool(key))?.unwrap_or(true);
+
+    let key = "common.noise_rekey_interval";
+    let noise_rekey_interval = match optional(cfg.get_int(key))? {
+        Some(secs) if secs <= 0 => {
+            return Err(ConfigurationError::new(key, "Must be a positive number of seconds"));
+        },
+        secs => secs.map(|secs| secs as u64),
+    };
+
+    let key = "common.noise_rekey_max_bytes";
+    let noise_rekey_max_bytes = match optional(cfg.get_int(key))? {
+        Some(max_bytes) if max_bytes <= 0 => {
+            return Err(ConfigurationError::new(key, "Must be a positive number of bytes"));
+        },
+        max_bytes => max_bytes.map(|max_bytes| max_bytes as u64),
+    };
+
     let key = "common.buffer_size_base_node";
     let buffer_size_base_node = cfg
         .get_int(&key)
@@ -557,9 +839,17 @@ fn convert_node_config(network: Network, cfg: Config) -> Result<GlobalConfig, Co
     );
 
     let key = config_string("merge_mining_proxy", &net_str, "monerod_url");
-    let monerod_url = cfg
-        .get_str(&key)
-        .map_err(|e| ConfigurationError::new(&key, &e.to_string()))?;
+    // The monerod URL can be a single URL, an array of URLs to fail over between, or a comma separated list
+    let monerod_urls = match cfg.get_array(&key) {
+        Ok(urls) => urls.into_iter().map(|v| v.into_str().unwrap()).collect::<Vec<_>>(),
+        Err(..) => match cfg.get_str(&key) {
+            Ok(s) => s.split(',').map(|v| v.trim().to_string()).collect(),
+            Err(err) => return Err(ConfigurationError::new(&key, &err.to_string())),
+        },
+    };
+    if monerod_urls.is_empty() || monerod_urls.iter().any(|url| url.is_empty()) {
+        return Err(ConfigurationError::new(&key, "At least one monerod URL is required"));
+    }
 
     let key = config_string("merge_mining_proxy", &net_str, "monerod_use_auth");
     let monerod_use_auth = cfg
@@ -603,6 +893,16 @@ fn convert_node_config(network: Network, cfg: Config) -> Result<GlobalConfig, Co
         listnener_liveness_max_sessions: liveness_max_sessions,
         listener_liveness_allowlist_cidrs: liveness_allowlist_cidrs,
         rpc_max_simultaneous_sessions,
+        dht_num_neighbouring_nodes,
+        dht_num_random_nodes,
+        dht_propagation_factor,
+        dht_broadcast_factor,
+        dht_closest_min_additional_peers,
+        dht_discovery_max_send_attempts,
+        auto_update_public_address,
+        allow_unsigned_peer_identities,
+        noise_rekey_interval,
+        noise_rekey_max_bytes,
         data_dir,
         db_type,
         db_config,
@@ -610,6 +910,8 @@ fn convert_node_config(network: Network, cfg: Config) -> Result<GlobalConfig, Co
         orphan_db_clean_out_threshold,
         pruning_horizon,
         pruned_mode_cleanup_interval,
+        emission_schedule,
+        genesis_block_file,
         core_threads,
         max_threads,
         base_node_identity_file,
@@ -617,6 +919,10 @@ fn convert_node_config(network: Network, cfg: Config) -> Result<GlobalConfig, Co
         grpc_enabled,
         grpc_base_node_address,
         grpc_console_wallet_address,
+        stratum_enabled,
+        stratum_address,
+        stratum_initial_share_difficulty,
+        stratum_target_share_interval,
         peer_seeds,
         dns_seeds,
         dns_seeds_name_server,
@@ -645,24 +951,53 @@ fn convert_node_config(network: Network, cfg: Config) -> Result<GlobalConfig, Co
         transaction_direct_send_timeout,
         transaction_broadcast_send_timeout,
         transaction_routing_mechanism,
+        transaction_resend_period,
+        transaction_resend_limit,
+        transaction_broadcast_retry_backoff_factor,
+        transaction_broadcast_retry_max_delay,
+        utxo_selection_strategy,
         transaction_num_confirmations_required,
         console_wallet_password,
+        wallet_passphrase_kdf_memory_kib,
+        wallet_passphrase_kdf_iterations,
+        wallet_passphrase_kdf_parallelism,
         wallet_command_send_wait_stage,
         wallet_command_send_wait_timeout,
         wallet_base_node_service_peers,
         wallet_base_node_service_refresh_interval,
         wallet_base_node_service_request_max_age,
+        wallet_outbound_only,
+        wallet_otlp_endpoint,
+        wallet_health_address,
         prevent_fee_gt_amount,
+        wallet_dust_consolidation_threshold,
+        wallet_dust_consolidation_interval,
+        wallet_dust_consolidation_fee_per_gram,
+        wallet_dust_consolidation_min_outputs,
+        wallet_key_manager_backend,
+        wallet_tor,
         proxy_host_address,
         proxy_submit_to_origin,
-        monerod_url,
+        monerod_urls,
         monerod_username,
         monerod_password,
         monerod_use_auth,
         force_sync_peers,
+        assume_valid_height,
+        assume_valid_hash,
         wait_for_initial_sync_at_startup,
         max_randomx_vms,
+        randomx_mode,
+        base_node_otlp_endpoint,
+        base_node_health_address,
         console_wallet_notify_file,
+        console_wallet_notify_webhook,
+        console_wallet_key_bindings,
+        console_wallet_theme,
+        console_wallet_idle_lock_timeout,
+        console_wallet_price_feed_url,
+        console_wallet_price_feed_pointer,
+        console_wallet_price_feed_currency,
         auto_ping_interval,
         blocks_behind_before_considered_lagging,
         flood_ban_max_msg_count,
@@ -727,6 +1062,14 @@ fn network_transport_config(cfg: &Config, network: &str) -> Result<CommsTranspor
                 .get::<NonZeroU16>(&key)
                 .map_err(|err| ConfigurationError::new(&key, &err.to_string()))?;
 
+            let key = config_string("base_node", network, "tor_socks_auth");
+            let socks_auth = match get_conf_str(&key).ok() {
+                Some(auth_str) => auth_str
+                    .parse()
+                    .map_err(|err: String| ConfigurationError::new(&key, &err))?,
+                None => SocksAuthentication::None,
+            };
+
             let key = config_string("base_node", network, "tor_socks_address_override");
             let socks_address_override = match get_conf_str(&key).ok() {
                 Some(addr) => Some(
@@ -740,6 +1083,7 @@ fn network_transport_config(cfg: &Config, network: &str) -> Result<CommsTranspor
                 control_server_address,
                 auth,
                 socks_address_override,
+                socks_auth,
                 forward_address,
                 onion_port,
             })
@@ -815,6 +1159,184 @@ impl Display for Network {
     }
 }
 
+//---------------------------------------------      RandomX mode         ------------------------------------------//
+/// How RandomX hashes are calculated when verifying Monero merge mined blocks
+#[derive(Clone, Debug, PartialEq, Copy)]
+pub enum RandomXMode {
+    /// Only the 256MB cache is kept for each seed hash. Hashing is several times slower, but no dataset has to be
+    /// built when the seed hash changes.
+    Light,
+    /// The full 2GB dataset is built and kept for each seed hash, which makes hashing fast once it has been built
+    Fast,
+}
+
+impl Default for RandomXMode {
+    fn default() -> Self {
+        Self::Fast
+    }
+}
+
+impl FromStr for RandomXMode {
+    type Err = ConfigurationError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "light" => Ok(Self::Light),
+            "fast" => Ok(Self::Fast),
+            invalid => Err(ConfigurationError::new(
+                "randomx_mode",
+                &format!("Invalid RandomX mode: {}", invalid),
+            )),
+        }
+    }
+}
+
+impl Display for RandomXMode {
+    fn fmt(&self, f: &mut Formatter) -> FormatResult {
+        let msg = match self {
+            Self::Light => "light",
+            Self::Fast => "fast",
+        };
+        f.write_str(msg)
+    }
+}
+
+//---------------------------------------------   Emission schedule       ------------------------------------------//
+/// A custom emission curve for a network, replacing the one in its consensus constants. Amounts are in microTari.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmissionScheduleConfig {
+    /// The reward of the genesis block
+    pub initial: u64,
+    /// The decay of the block reward, as a list of powers of two. See `EmissionSchedule` in `tari_core`.
+    pub decay: Vec<u64>,
+    /// The constant block reward once the decaying reward drops below it
+    pub tail: u64,
+}
+
+fn emission_schedule_config(
+    cfg: &Config,
+    network: Network,
+    net_str: &str,
+) -> Result<Option<EmissionScheduleConfig>, ConfigurationError>
+{
+    let non_negative = |key: &str, value: i64| -> Result<u64, ConfigurationError> {
+        value
+            .try_into()
+            .map_err(|_| ConfigurationError::new(key, "Emission schedule values can not be negative"))
+    };
+
+    let key = config_string("base_node", net_str, "emission_initial");
+    let initial = match optional(cfg.get_int(&key)).map_err(|e| ConfigurationError::new(&key, &e.to_string()))? {
+        Some(initial) => non_negative(&key, initial)?,
+        None => return Ok(None),
+    };
+    if network == Network::MainNet {
+        return Err(ConfigurationError::new(
+            &key,
+            "A custom emission schedule can not be used on mainnet",
+        ));
+    }
+
+    let key = config_string("base_node", net_str, "emission_decay");
+    let decay = cfg
+        .get_array(&key)
+        .and_then(|values| values.into_iter().map(|v| v.into_int()).collect::<Result<Vec<_>, _>>())
+        .map_err(|e| ConfigurationError::new(&key, &e.to_string()))?
+        .into_iter()
+        .map(|d| non_negative(&key, d))
+        .collect::<Result<Vec<_>, _>>()?;
+    if decay.iter().any(|d| *d >= 64) {
+        return Err(ConfigurationError::new(&key, "All decay values must be less than 64"));
+    }
+
+    let key = config_string("base_node", net_str, "emission_tail");
+    let tail = cfg
+        .get_int(&key)
+        .map_err(|e| ConfigurationError::new(&key, &e.to_string()))?;
+    let tail = non_negative(&key, tail)?;
+
+    Ok(Some(EmissionScheduleConfig { initial, decay, tail }))
+}
+
+//---------------------------------------------       Wallet Tor          ------------------------------------------//
+/// How the wallet runs over Tor. The wallet connects to the Tor control port to create its hidden service, launching
+/// Tor first if configured to, and otherwise only connects out through the Tor SOCKS5 proxy if falling back is allowed.
+#[derive(Debug, Clone)]
+pub struct WalletTorConfig {
+    /// The address of the Tor control port, which a launched Tor process also listens on
+    pub control_address: Multiaddr,
+    pub control_auth: TorControlAuthentication,
+    /// The address of the Tor SOCKS5 proxy. If not set, the address reported by the control port is used for the
+    /// hidden service and 127.0.0.1:9050 when falling back to SOCKS5.
+    pub socks_address: Option<Multiaddr>,
+    pub socks_auth: SocksAuthentication,
+    /// Launch Tor when nothing is listening on the control port
+    pub launch: bool,
+    /// The Tor executable that is launched
+    pub tor_executable: PathBuf,
+    /// The data directory of a launched Tor process
+    pub data_dir: PathBuf,
+    /// Connect to peers through the SOCKS5 proxy, without a hidden service, when the control port cannot be used
+    pub socks5_fallback: bool,
+}
+
+fn wallet_tor_config(cfg: &Config, data_dir: &Path) -> Result<Option<WalletTorConfig>, ConfigurationError> {
+    let key = "wallet.tor.enabled";
+    if !optional(cfg.get_bool(key))?.unwrap_or(false) {
+        return Ok(None);
+    }
+
+    let get_multiaddr = |key: &str| -> Result<Option<Multiaddr>, ConfigurationError> {
+        optional(cfg.get_str(key))?
+            .map(|addr| addr.parse::<Multiaddr>())
+            .transpose()
+            .map_err(|e| ConfigurationError::new(key, &e.to_string()))
+    };
+
+    let key = "wallet.tor.control_address";
+    let control_address = get_multiaddr(key)?.unwrap_or_else(|| "/ip4/127.0.0.1/tcp/9051".parse().unwrap());
+
+    let key = "wallet.tor.control_auth";
+    let control_auth = match optional(cfg.get_str(key))? {
+        Some(auth) => auth.parse().map_err(|e: String| ConfigurationError::new(key, &e))?,
+        None => TorControlAuthentication::None,
+    };
+
+    let key = "wallet.tor.socks_address";
+    let socks_address = get_multiaddr(key)?;
+
+    let key = "wallet.tor.socks_auth";
+    let socks_auth = match optional(cfg.get_str(key))? {
+        Some(auth) => auth.parse().map_err(|e: String| ConfigurationError::new(key, &e))?,
+        None => SocksAuthentication::None,
+    };
+
+    let key = "wallet.tor.launch";
+    let launch = optional(cfg.get_bool(key))?.unwrap_or(false);
+
+    let key = "wallet.tor.tor_executable";
+    let tor_executable = optional(cfg.get_str(key))?.unwrap_or_else(|| "tor".to_string()).into();
+
+    let key = "wallet.tor.data_dir";
+    let tor_data_dir = optional(cfg.get_str(key))?
+        .map(PathBuf::from)
+        .unwrap_or_else(|| data_dir.join("wallet_tor"));
+
+    let key = "wallet.tor.socks5_fallback";
+    let socks5_fallback = optional(cfg.get_bool(key))?.unwrap_or(true);
+
+    Ok(Some(WalletTorConfig {
+        control_address,
+        control_auth,
+        socks_address,
+        socks_auth,
+        launch,
+        tor_executable,
+        data_dir: tor_data_dir,
+        socks5_fallback,
+    }))
+}
+
 //---------------------------------------------      Database type        ------------------------------------------//
 #[derive(Debug, Clone)]
 pub enum DatabaseType {
@@ -864,6 +1386,8 @@ impl FromStr for TorControlAuthentication {
 pub enum SocksAuthentication {
     None,
     UsernamePassword(String, String),
+    /// Use random credentials for each connection so that Tor isolates each stream onto a separate circuit
+    StreamIsolation,
 }
 
 impl FromStr for SocksAuthentication {
@@ -873,6 +1397,7 @@ impl FromStr for SocksAuthentication {
         let (auth_type, maybe_value) = parse_key_value(s, '=');
         match auth_type.as_str() {
             "none" => Ok(SocksAuthentication::None),
+            "stream_isolation" => Ok(SocksAuthentication::StreamIsolation),
             "username_password" => {
                 let (username, password) = maybe_value
                     .and_then(|value| {
@@ -907,6 +1432,8 @@ pub enum CommsTransport {
         /// The address of the control server
         control_server_address: Multiaddr,
         socks_address_override: Option<Multiaddr>,
+        /// The authentication to use for the tor SOCKS proxy
+        socks_auth: SocksAuthentication,
         /// The address used to receive proxied traffic from the tor proxy to the Tari node. This port must be
         /// available
         forward_address: Multiaddr,
@@ -920,3 +1447,48 @@ pub enum CommsTransport {
         listener_address: Multiaddr,
     },
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn emission_config(initial: i64, decay: Vec<i64>, tail: i64) -> Config {
+        let mut cfg = Config::new();
+        cfg.set("base_node.stibbons.emission_initial", initial).unwrap();
+        cfg.set("base_node.stibbons.emission_decay", decay).unwrap();
+        cfg.set("base_node.stibbons.emission_tail", tail).unwrap();
+        cfg
+    }
+
+    #[test]
+    fn emission_schedule() {
+        let cfg = emission_config(1_000_000, vec![10, 20], 100);
+        assert_eq!(
+            emission_schedule_config(&cfg, Network::Stibbons, "stibbons").unwrap(),
+            Some(EmissionScheduleConfig {
+                initial: 1_000_000,
+                decay: vec![10, 20],
+                tail: 100,
+            })
+        );
+        assert_eq!(
+            emission_schedule_config(&Config::new(), Network::Stibbons, "stibbons").unwrap(),
+            None
+        );
+
+        let cfg = emission_config(1_000_000, vec![10, 20], 100);
+        assert!(emission_schedule_config(&cfg, Network::MainNet, "stibbons").is_err());
+        let cfg = emission_config(1_000_000, vec![10, 64], 100);
+        assert!(emission_schedule_config(&cfg, Network::Stibbons, "stibbons").is_err());
+    }
+
+    #[test]
+    fn emission_schedule_rejects_negative_values() {
+        let cfg = emission_config(-1, vec![10, 20], 100);
+        assert!(emission_schedule_config(&cfg, Network::Stibbons, "stibbons").is_err());
+        let cfg = emission_config(1_000_000, vec![10, -20], 100);
+        assert!(emission_schedule_config(&cfg, Network::Stibbons, "stibbons").is_err());
+        let cfg = emission_config(1_000_000, vec![10, 20], -100);
+        assert!(emission_schedule_config(&cfg, Network::Stibbons, "stibbons").is_err());
+    }
+}
@@ -43,6 +43,16 @@ pub mod utils;
 pub mod writer;
 
 pub use bootstrap::ConfigBootstrap;
-pub use global::{CommsTransport, DatabaseType, GlobalConfig, Network, SocksAuthentication, TorControlAuthentication};
+pub use global::{
+    CommsTransport,
+    DatabaseType,
+    EmissionScheduleConfig,
+    GlobalConfig,
+    Network,
+    RandomXMode,
+    SocksAuthentication,
+    TorControlAuthentication,
+    WalletTorConfig,
+};
 pub use loader::ConfigurationError;
 pub use utils::{default_config, install_default_config_file, load_configuration};
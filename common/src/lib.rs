@@ -86,11 +86,21 @@ pub use configuration::error::ConfigError;
 pub mod dir_utils;
 pub use configuration::{
     bootstrap::{install_configuration, ConfigBootstrap},
-    global::{CommsTransport, DatabaseType, GlobalConfig, Network, SocksAuthentication, TorControlAuthentication},
+    global::{
+        CommsTransport,
+        DatabaseType,
+        EmissionScheduleConfig,
+        GlobalConfig,
+        Network,
+        RandomXMode,
+        SocksAuthentication,
+        TorControlAuthentication,
+        WalletTorConfig,
+    },
     loader::{ConfigLoader, ConfigPath, ConfigurationError, DefaultConfigLoader, NetworkConfigPath},
     utils::{default_config, install_default_config_file, load_configuration},
 };
-pub use logging::initialize_logging;
+pub use logging::{initialize_logging, log_deserializers, JsonEncoder};
 
 pub const DEFAULT_CONFIG: &str = "config/config.toml";
 pub const DEFAULT_BASE_NODE_LOG_CONFIG: &str = "config/log4rs_base_node.yml";
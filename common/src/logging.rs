@@ -21,21 +21,35 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 //
 
+mod json;
+pub use json::{JsonEncoder, JsonEncoderDeserializer, JSON_ENCODER_KIND};
+
+use log4rs::file::Deserializers;
 use std::{fs, fs::File, io::Write, path::Path};
 
 /// Set up application-level logging using the Log4rs configuration file specified in
+///
+/// In addition to the standard log4rs components, the configuration file may use the `json` encoder kind to write
+/// structured JSON log records.
 pub fn initialize_logging(config_file: &Path) -> bool {
     println!(
         "Initializing logging according to {:?}",
         config_file.to_str().unwrap_or("[??]")
     );
-    if let Err(e) = log4rs::init_file(config_file, Default::default()) {
+    if let Err(e) = log4rs::init_file(config_file, log_deserializers()) {
         println!("We couldn't load a logging configuration file. {}", e.to_string());
         return false;
     }
     true
 }
 
+/// The log4rs deserializers for all the components that may be used in a Tari logging configuration file
+pub fn log_deserializers() -> Deserializers {
+    let mut deserializers = Deserializers::default();
+    deserializers.insert(JSON_ENCODER_KIND, JsonEncoderDeserializer);
+    deserializers
+}
+
 /// Installs a new default logfile configuration, copied from `log4rs_sample_base_node.yml` to the given path.
 pub fn install_default_base_node_logfile_config(path: &Path) -> Result<(), std::io::Error> {
     let source = include_str!("../logging/log4rs_sample_base_node.yml");
@@ -0,0 +1,181 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+
+//! A log4rs encoder that writes every log record as a single line of JSON, so that logs can be ingested by log
+//! aggregators such as ELK or Loki without having to parse the free-form message with a regex.
+//!
+//! Well-known identifiers that appear in a message, such as `TxId: 123` or `node_id=abcd`, are also extracted into a
+//! `fields` object under a canonical name (`tx_id`, `peer_id`). Select it in a log4rs configuration file with:
+//!
+//! ```yaml
+//! encoder:
+//!   kind: json
+//! ```
+
+use chrono::{SecondsFormat, Utc};
+use log::Record;
+use log4rs::{
+    encode::{Encode, Write},
+    file::{Deserialize, Deserializers},
+};
+use serde_json::{json, Map, Value};
+use std::{error::Error, io::Write as _};
+
+/// The log4rs encoder kind under which the [JsonEncoder] is registered
+pub const JSON_ENCODER_KIND: &str = "json";
+
+/// Canonical field names and the spellings that are used for them in log messages
+const KNOWN_FIELDS: &[(&str, &[&str])] = &[
+    ("tx_id", &["TxId", "tx_id"]),
+    ("peer_id", &["NodeId", "node_id", "peer_node_id", "peer_id"]),
+];
+
+/// Encodes log records as single-line JSON objects containing the timestamp, level, target, thread, message and any
+/// well-known fields found in the message
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonEncoder;
+
+impl JsonEncoder {
+    fn to_json(&self, record: &Record) -> Value {
+        let message = record.args().to_string();
+        let fields = extract_fields(&message);
+        let thread = std::thread::current();
+        json!({
+            "timestamp": Utc::now().to_rfc3339_opts(SecondsFormat::Micros, true),
+            "level": record.level().to_string(),
+            "target": record.target(),
+            "thread": thread.name().unwrap_or("<unnamed>"),
+            "message": message,
+            "fields": fields,
+        })
+    }
+}
+
+impl Encode for JsonEncoder {
+    fn encode(&self, w: &mut dyn Write, record: &Record) -> Result<(), Box<dyn Error + Sync + Send>> {
+        serde_json::to_writer(&mut *w, &self.to_json(record))?;
+        w.write_all(b"\n")?;
+        Ok(())
+    }
+}
+
+/// Deserializes the `json` encoder kind from a log4rs configuration file
+pub struct JsonEncoderDeserializer;
+
+impl Deserialize for JsonEncoderDeserializer {
+    type Config = serde::de::IgnoredAny;
+    type Trait = dyn Encode;
+
+    fn deserialize(
+        &self,
+        _config: Self::Config,
+        _deserializers: &Deserializers,
+    ) -> Result<Box<dyn Encode>, Box<dyn Error + Sync + Send>>
+    {
+        Ok(Box::new(JsonEncoder))
+    }
+}
+
+/// Extract the well-known fields that appear as `Key: value` or `key=value` in a log message. The first occurrence of
+/// a field wins.
+fn extract_fields(message: &str) -> Map<String, Value> {
+    let mut fields = Map::new();
+    for (name, spellings) in KNOWN_FIELDS {
+        let value = spellings.iter().find_map(|spelling| find_value(message, spelling));
+        if let Some(value) = value {
+            fields.insert((*name).to_string(), Value::String(value.to_string()));
+        }
+    }
+    fields
+}
+
+fn find_value<'a>(message: &'a str, key: &str) -> Option<&'a str> {
+    let mut search_from = 0;
+    while let Some(pos) = message[search_from..].find(key) {
+        let start = search_from + pos;
+        let end = start + key.len();
+        search_from = end;
+        // Only match whole keys, e.g. `node_id` must not match inside `peer_node_id`
+        let is_boundary = message[..start]
+            .chars()
+            .next_back()
+            .map(|c| !c.is_alphanumeric() && c != '_')
+            .unwrap_or(true);
+        if !is_boundary {
+            continue;
+        }
+        let rest = &message[end..];
+        let rest = if let Some(rest) = rest.strip_prefix('=') {
+            rest
+        } else if let Some(rest) = rest.strip_prefix(':') {
+            rest.trim_start()
+        } else {
+            continue;
+        };
+        let value_len = rest
+            .find(|c: char| c.is_whitespace() || c == ',' || c == ')' || c == ']' || c == ';')
+            .unwrap_or_else(|| rest.len());
+        let value = rest[..value_len].trim_end_matches('.');
+        if !value.is_empty() {
+            return Some(value);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use log::Level;
+
+    #[test]
+    fn it_extracts_known_fields() {
+        let fields = extract_fields("Transaction (TxId: 1234) sent to peer_node_id=abcd, retrying.");
+        assert_eq!(fields.get("tx_id").unwrap(), "1234");
+        assert_eq!(fields.get("peer_id").unwrap(), "abcd");
+
+        let fields = extract_fields("Received reply for tx_id: 42.");
+        assert_eq!(fields.get("tx_id").unwrap(), "42");
+        assert!(fields.get("peer_id").is_none());
+
+        // Only whole keys followed by a separator are matched
+        let fields = extract_fields("No TxIds here, node_identity=xyz, NodeId");
+        assert!(fields.is_empty());
+    }
+
+    #[test]
+    fn it_encodes_records_as_json() {
+        let json = JsonEncoder.to_json(
+            &Record::builder()
+                .args(format_args!("Cancelled TxId: 7"))
+                .level(Level::Info)
+                .target("wallet::transaction_service")
+                .build(),
+        );
+        assert_eq!(json["level"], "INFO");
+        assert_eq!(json["target"], "wallet::transaction_service");
+        assert_eq!(json["message"], "Cancelled TxId: 7");
+        assert_eq!(json["fields"]["tx_id"], "7");
+        assert!(json["timestamp"].as_str().unwrap().ends_with('Z'));
+    }
+}
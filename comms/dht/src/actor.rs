@@ -48,7 +48,16 @@ use log::*;
 use std::{cmp, fmt, fmt::Display, sync::Arc};
 use tari_comms::{
     connectivity::{ConnectivityError, ConnectivityRequester, ConnectivitySelection},
-    peer_manager::{NodeId, NodeIdentity, PeerFeatures, PeerManager, PeerManagerError, PeerQuery, PeerQuerySortBy},
+    peer_manager::{
+        node_id::NodeDistance,
+        NodeId,
+        NodeIdentity,
+        PeerFeatures,
+        PeerManager,
+        PeerManagerError,
+        PeerQuery,
+        PeerQuerySortBy,
+    },
 };
 use tari_shutdown::ShutdownSignal;
 use tari_utilities::message_format::{MessageFormat, MessageFormatError};
@@ -107,6 +116,8 @@ pub enum DhtRequest {
     SelectPeers(BroadcastStrategy, oneshot::Sender<Vec<NodeId>>),
     GetMetadata(DhtMetadataKey, oneshot::Sender<Result<Option<Vec<u8>>, DhtActorError>>),
     SetMetadata(DhtMetadataKey, Vec<u8>, oneshot::Sender<Result<(), DhtActorError>>),
+    /// Compute the network region around the given `NodeId` from the peers known to this node
+    GetNetworkRegion(NodeId, oneshot::Sender<Result<NetworkRegion, DhtActorError>>),
 }
 
 impl Display for DhtRequest {
@@ -120,10 +131,34 @@ impl Display for DhtRequest {
             SetMetadata(key, value, _) => {
                 f.write_str(&format!("SetMetadata (key={}, value={} bytes)", key, value.len()))
             },
+            GetNetworkRegion(node_id, _) => f.write_str(&format!("GetNetworkRegion (node_id={})", node_id)),
         }
     }
 }
 
+/// The network region around a `NodeId`, as computed by this node using the `num_neighbouring_nodes` closest known
+/// base nodes and clients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkRegion {
+    /// The `NodeId` at the centre of the region
+    pub node_id: NodeId,
+    /// The number of nearest neighbours of each peer type ([PeerFeatures::COMMUNICATION_NODE] and
+    /// [PeerFeatures::COMMUNICATION_CLIENT]) that make up the region
+    pub size: usize,
+    /// The distance from `node_id` of the furthest base node within the region
+    pub node_threshold: NodeDistance,
+    /// The distance from `node_id` of the furthest client within the region
+    pub client_threshold: NodeDistance,
+}
+
+impl NetworkRegion {
+    /// Returns true if the given `NodeId` falls within this network region, otherwise false
+    pub fn contains(&self, node_id: &NodeId) -> bool {
+        let dist = self.node_id.distance(node_id);
+        dist <= self.node_threshold || dist <= self.client_threshold
+    }
+}
+
 #[derive(Clone)]
 pub struct DhtRequester {
     sender: mpsc::Sender<DhtRequest>,
@@ -172,6 +207,15 @@ impl DhtRequester {
         self.sender.send(DhtRequest::SetMetadata(key, bytes, reply_tx)).await?;
         reply_rx.await.map_err(|_| DhtActorError::ReplyCanceled)?
     }
+
+    /// Returns the network region around the given `NodeId`, as seen by this node.
+    pub async fn get_network_region(&mut self, node_id: NodeId) -> Result<NetworkRegion, DhtActorError> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.sender
+            .send(DhtRequest::GetNetworkRegion(node_id, reply_tx))
+            .await?;
+        reply_rx.await.map_err(|_| DhtActorError::ReplyCanceled)?
+    }
 }
 
 pub struct DhtActor {
@@ -332,9 +376,38 @@ impl DhtActor {
                     Ok(())
                 })
             },
+            GetNetworkRegion(node_id, reply_tx) => {
+                let peer_manager = Arc::clone(&self.peer_manager);
+                let size = self.config.num_neighbouring_nodes;
+                Box::pin(async move {
+                    let result = Self::calc_network_region(&peer_manager, node_id, size).await;
+                    let _ = reply_tx.send(result);
+                    Ok(())
+                })
+            },
         }
     }
 
+    async fn calc_network_region(
+        peer_manager: &PeerManager,
+        node_id: NodeId,
+        size: usize,
+    ) -> Result<NetworkRegion, DhtActorError>
+    {
+        let node_threshold = peer_manager
+            .calc_region_threshold(&node_id, size, PeerFeatures::COMMUNICATION_NODE)
+            .await?;
+        let client_threshold = peer_manager
+            .calc_region_threshold(&node_id, size, PeerFeatures::COMMUNICATION_CLIENT)
+            .await?;
+        Ok(NetworkRegion {
+            node_id,
+            size,
+            node_threshold,
+            client_threshold,
+        })
+    }
+
     async fn broadcast_join(
         node_identity: Arc<NodeIdentity>,
         mut outbound_requester: OutboundMessageRequester,
@@ -414,8 +487,12 @@ impl DhtActor {
                         .chain(candidates.iter())
                         .cloned()
                         .collect::<Vec<_>>();
-                    // If we don't have enough connections, let's select some more disconnected peers (at least 2)
-                    let n = cmp::max(config.broadcast_factor.saturating_sub(candidates.len()), 2);
+                    // If we don't have enough connections, let's select some more disconnected peers (at least
+                    // `closest_min_additional_peers`)
+                    let n = cmp::max(
+                        config.broadcast_factor.saturating_sub(candidates.len()),
+                        config.closest_min_additional_peers,
+                    );
                     let additional = Self::select_closest_peers_for_propagation(
                         &peer_manager,
                         &closest_request.node_id,
@@ -807,6 +884,58 @@ mod test {
         assert_eq!(peers.len(), 1);
     }
 
+    #[tokio_macros::test_basic]
+    async fn get_network_region() {
+        let node_identity = make_node_identity();
+        let peer_manager = build_peer_manager();
+        let nodes = (0..6).map(|_| make_node_identity()).collect::<Vec<_>>();
+        for node in &nodes {
+            peer_manager.add_peer(node.to_peer()).await.unwrap();
+        }
+        let client = make_client_identity();
+        peer_manager.add_peer(client.to_peer()).await.unwrap();
+
+        let (connectivity_manager, mock) = create_connectivity_mock();
+        mock.spawn();
+        let (out_tx, _) = mpsc::channel(1);
+        let (actor_tx, actor_rx) = mpsc::channel(1);
+        let mut requester = DhtRequester::new(actor_tx);
+        let outbound_requester = OutboundMessageRequester::new(out_tx);
+        let shutdown = Shutdown::new();
+        let actor = DhtActor::new(
+            DhtConfig {
+                num_neighbouring_nodes: 3,
+                ..Default::default()
+            },
+            db_connection().await,
+            Arc::clone(&node_identity),
+            peer_manager,
+            connectivity_manager,
+            outbound_requester,
+            actor_rx,
+            shutdown.to_signal(),
+        );
+
+        actor.spawn();
+
+        let region = requester
+            .get_network_region(node_identity.node_id().clone())
+            .await
+            .unwrap();
+        assert_eq!(region.size, 3);
+        assert_eq!(&region.node_id, node_identity.node_id());
+
+        let mut dists = nodes
+            .iter()
+            .map(|n| node_identity.node_id().distance(n.node_id()))
+            .collect::<Vec<_>>();
+        dists.sort();
+        assert_eq!(region.node_threshold, dists[2]);
+        // Less than `size` clients are known, so all clients are considered to be in the region
+        assert_eq!(region.client_threshold, NodeDistance::max_distance());
+        assert!(region.contains(client.node_id()));
+    }
+
     #[tokio_macros::test_basic]
     async fn get_and_set_metadata() {
         let node_identity = make_node_identity();
@@ -120,11 +120,21 @@ impl DhtBuilder {
         self
     }
 
+    pub fn with_closest_min_additional_peers(mut self, n: usize) -> Self {
+        self.config.closest_min_additional_peers = n;
+        self
+    }
+
     pub fn with_discovery_timeout(mut self, timeout: Duration) -> Self {
         self.config.discovery_request_timeout = timeout;
         self
     }
 
+    pub fn with_discovery_max_send_attempts(mut self, max_attempts: usize) -> Self {
+        self.config.discovery_max_send_attempts = max_attempts;
+        self
+    }
+
     pub fn enable_auto_join(mut self) -> Self {
         self.config.auto_join = true;
         self
@@ -42,6 +42,10 @@ pub struct DhtConfig {
     /// Send to this many peers when using the propagate strategy
     /// Default: 4
     pub propagation_factor: usize,
+    /// When using the closest strategy (and `connected_only` is false), select at least this many additional
+    /// known closest peers that are not currently connected.
+    /// Default: 2
+    pub closest_min_additional_peers: usize,
     /// The amount of seconds added to the current time (Utc) which will then be used to check if the message has
     /// expired or not when processing the message
     /// Default: 10800
@@ -80,6 +84,9 @@ pub struct DhtConfig {
     /// The duration to wait for a peer discovery to complete before giving up.
     /// Default: 2 minutes
     pub discovery_request_timeout: Duration,
+    /// The maximum number of times to attempt to send a discovery message before failing the discovery request.
+    /// Default: 1
+    pub discovery_max_send_attempts: usize,
     /// Set to true to automatically broadcast a join message when ready, otherwise false. Default: false
     pub auto_join: bool,
     /// The minimum time between sending a Join message to the network. Joins are only sent when the node establishes
@@ -152,6 +159,7 @@ impl Default for DhtConfig {
             num_random_nodes: 4,
             propagation_factor: 4,
             broadcast_factor: 8,
+            closest_min_additional_peers: 2,
             outbound_buffer_size: 20,
             saf_num_closest_nodes: 10,
             saf_max_returned_messages: 50,
@@ -165,6 +173,7 @@ impl Default for DhtConfig {
             msg_hash_cache_ttl: Duration::from_secs(5 * 60),
             database_url: DbConnectionUrl::Memory,
             discovery_request_timeout: Duration::from_secs(2 * 60),
+            discovery_max_send_attempts: 1,
             connectivity_update_interval: Duration::from_secs(2 * 60),
             connectivity_random_pool_refresh: Duration::from_secs(2 * 60 * 60),
             auto_join: false,
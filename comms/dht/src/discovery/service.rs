@@ -35,6 +35,7 @@ use futures::{
 use log::*;
 use rand::{rngs::OsRng, RngCore};
 use std::{
+    cmp,
     collections::HashMap,
     sync::Arc,
     time::{Duration, Instant},
@@ -292,9 +293,29 @@ impl DhtDiscoveryService {
     ) -> Result<(), DhtDiscoveryError>
     {
         let nonce = OsRng.next_u64();
-        if let Err(err) = self.send_discover(nonce, destination, dest_pubkey.clone()).await {
-            let _ = reply_tx.send(Err(err));
-            return Ok(());
+        let max_attempts = cmp::max(1, self.config.discovery_max_send_attempts);
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            match self
+                .send_discover(nonce, destination.clone(), dest_pubkey.clone())
+                .await
+            {
+                Ok(_) => break,
+                Err(err) if attempts < max_attempts => {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Failed to send discovery message (attempt {} of {}): {}. Retrying...",
+                        attempts,
+                        max_attempts,
+                        err
+                    );
+                },
+                Err(err) => {
+                    let _ = reply_tx.send(Err(err));
+                    return Ok(());
+                },
+            }
         }
 
         let inflight_count = self.inflight_discoveries.len();
@@ -127,7 +127,7 @@ mod macros;
 mod test_utils;
 
 mod actor;
-pub use actor::{DhtActorError, DhtRequest, DhtRequester};
+pub use actor::{DhtActorError, DhtRequest, DhtRequester, NetworkRegion};
 
 mod builder;
 pub use builder::DhtBuilder;
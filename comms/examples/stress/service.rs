@@ -64,7 +64,7 @@ pub fn start_service(
         "Node credentials are {}::{} (local_listening_addr='{}')",
         node_identity.public_key().to_hex(),
         node_identity.public_address(),
-        comms_node.listening_address(),
+        comms_node.listening_address().unwrap(),
     );
 
     let service = StressTestService::new(request_rx, comms_node, protocol_notif, inbound_rx, outbound_tx);
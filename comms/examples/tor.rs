@@ -81,13 +81,13 @@ async fn run() -> Result<(), Error> {
         "Node 1 is '{}' with address '{}' (local_listening_addr='{}')",
         node_identity1.node_id().short_str(),
         node_identity1.public_address(),
-        comms_node1.listening_address(),
+        comms_node1.listening_address().unwrap(),
     );
     println!(
         "Node 2 is '{}' with address '{}' (local_listening_addr='{}')",
         node_identity2.node_id().short_str(),
         node_identity2.public_address(),
-        comms_node2.listening_address(),
+        comms_node2.listening_address().unwrap(),
     );
 
     // Let's add node 2 as a peer to node 1
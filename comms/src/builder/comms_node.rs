@@ -30,7 +30,7 @@ use crate::{
     },
     connectivity::{ConnectivityEventRx, ConnectivityManager, ConnectivityRequest, ConnectivityRequester},
     multiaddr::Multiaddr,
-    noise::NoiseConfig,
+    noise::{NoiseConfig, RekeyConfig},
     peer_manager::{NodeIdentity, PeerManager},
     protocol::{
         ProtocolExtension,
@@ -41,7 +41,7 @@ use crate::{
         Protocols,
     },
     tor,
-    transports::Transport,
+    transports::{RemoteAddress, Transport},
     CommsBuilder,
     Substream,
 };
@@ -111,6 +111,11 @@ impl UnspawnedCommsNode {
         self
     }
 
+    /// Returns true if this comms instance is configured to run in outbound-only mode
+    pub fn is_outbound_only(&self) -> bool {
+        self.builder.connection_manager_config.outbound_only
+    }
+
     /// Set the tor hidden service controller to associate with this comms instance
     pub fn with_hidden_service_controller(mut self, hidden_service_ctl: tor::HiddenServiceController) -> Self {
         self.builder.hidden_service_ctl = Some(hidden_service_ctl);
@@ -139,7 +144,7 @@ impl UnspawnedCommsNode {
     pub async fn spawn_with_transport<TTransport>(self, transport: TTransport) -> Result<CommsNode, CommsBuilderError>
     where
         TTransport: Transport + Unpin + Send + Sync + Clone + 'static,
-        TTransport::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin + 'static,
+        TTransport::Output: AsyncRead + AsyncWrite + RemoteAddress + Send + Sync + Unpin + 'static,
     {
         let UnspawnedCommsNode {
             builder,
@@ -162,6 +167,11 @@ impl UnspawnedCommsNode {
             ..
         } = builder;
 
+        let is_outbound_only = connection_manager_config.outbound_only;
+        if is_outbound_only && hidden_service_ctl.is_some() {
+            return Err(CommsBuilderError::HiddenServiceNotSupportedInOutboundOnlyMode);
+        }
+
         //---------------------------------- Connectivity Manager --------------------------------------------//
         let connectivity_manager = ConnectivityManager {
             config: connectivity_config,
@@ -188,7 +198,10 @@ impl UnspawnedCommsNode {
 
         //---------------------------------- Connection Manager --------------------------------------------//
 
-        let noise_config = NoiseConfig::new(node_identity.clone());
+        let noise_config = NoiseConfig::new(node_identity.clone()).with_rekey_config(RekeyConfig {
+            interval: connection_manager_config.noise_rekey_interval,
+            max_bytes: connection_manager_config.noise_rekey_max_bytes,
+        });
 
         let mut connection_manager = ConnectionManager::new(
             connection_manager_config,
@@ -224,19 +237,28 @@ impl UnspawnedCommsNode {
             node_identity.node_id()
         );
 
-        let listening_addr = Self::wait_listening(connection_manager_event_subscription).await?;
         let mut hidden_service = None;
-        if let Some(mut ctl) = hidden_service_ctl {
-            ctl.set_proxied_addr(listening_addr.clone());
-            let hs = ctl.create_hidden_service().await?;
-            node_identity.set_public_address(hs.get_onion_address());
-            hidden_service = Some(hs);
-        }
-        info!(
-            target: LOG_TARGET,
-            "Your node's public address is '{}'",
-            node_identity.public_address()
-        );
+        let listening_addr = if is_outbound_only {
+            info!(
+                target: LOG_TARGET,
+                "Your node is running in outbound-only mode and does not advertise a public address"
+            );
+            None
+        } else {
+            let listening_addr = Self::wait_listening(connection_manager_event_subscription).await?;
+            if let Some(mut ctl) = hidden_service_ctl {
+                ctl.set_proxied_addr(listening_addr.clone());
+                let hs = ctl.create_hidden_service().await?;
+                node_identity.set_public_address(hs.get_onion_address());
+                hidden_service = Some(hs);
+            }
+            info!(
+                target: LOG_TARGET,
+                "Your node's public address is '{}'",
+                node_identity.public_address()
+            );
+            Some(listening_addr)
+        };
 
         Ok(CommsNode {
             shutdown_signal,
@@ -293,8 +315,8 @@ pub struct CommsNode {
     node_identity: Arc<NodeIdentity>,
     /// Shared PeerManager instance
     peer_manager: Arc<PeerManager>,
-    /// The resolved Ip-Tcp listening address.
-    listening_addr: Multiaddr,
+    /// The resolved Ip-Tcp listening address. This is `None` if the node is running in outbound-only mode.
+    listening_addr: Option<Multiaddr>,
     /// `Some` if the comms node is configured to run via a hidden service, otherwise `None`
     hidden_service: Option<tor::HiddenService>,
     /// The 'reciprocal' shutdown signals for each comms service
@@ -327,9 +349,9 @@ impl CommsNode {
         &self.node_identity
     }
 
-    /// Return the Ip/Tcp address that this node is listening on
-    pub fn listening_address(&self) -> &Multiaddr {
-        &self.listening_addr
+    /// Return the Ip/Tcp address that this node is listening on, or None if the node is running in outbound-only mode
+    pub fn listening_address(&self) -> Option<&Multiaddr> {
+        self.listening_addr.as_ref()
     }
 
     /// Return the Ip/Tcp address that this node is listening on
@@ -52,7 +52,7 @@ use crate::{
     types::CommsDatabase,
 };
 use futures::channel::mpsc;
-use std::{fs::File, sync::Arc};
+use std::{fs::File, sync::Arc, time::Duration};
 use tari_shutdown::ShutdownSignal;
 use tokio::sync::broadcast;
 
@@ -121,6 +121,43 @@ impl CommsBuilder {
         self
     }
 
+    /// Run comms in outbound-only mode. No listener is started and no address is advertised to peers. Peers can then
+    /// only reach this node over connections that it has established, or via store and forward.
+    pub fn outbound_only(mut self) -> Self {
+        self.connection_manager_config.outbound_only = true;
+        self
+    }
+
+    /// Update this node's advertised public address when the majority of peers that this node connects to observe a
+    /// different public IP address, e.g. after the node's IP address has changed.
+    pub fn auto_update_public_address(mut self) -> Self {
+        self.connection_manager_config.auto_update_public_address = true;
+        self
+    }
+
+    /// Set whether to accept the unsigned identities of peers that run a version that does not sign its identity
+    /// yet. This is true by default, so that those peers can still connect during the upgrade.
+    pub fn allow_unsigned_identities(mut self, allow: bool) -> Self {
+        self.connection_manager_config.allow_unsigned_identities = allow;
+        self
+    }
+
+    /// The minimum number of peers that must observe a new public IP address for this node before the advertised
+    /// address is updated. This has no effect unless `auto_update_public_address` is set.
+    pub fn with_public_address_min_observations(mut self, min_observations: usize) -> Self {
+        self.connection_manager_config.public_address_min_observations = min_observations;
+        self
+    }
+
+    /// Periodically rotate the session keys of each connection. Keys are rotated after `interval` has elapsed or
+    /// `max_bytes` have been sent, whichever comes first. Rekeying is transparent to protocols. Keys are only
+    /// rotated on connections to peers that advertise support for rekeying.
+    pub fn with_noise_rekey(mut self, interval: Option<Duration>, max_bytes: Option<u64>) -> Self {
+        self.connection_manager_config.noise_rekey_interval = interval;
+        self.connection_manager_config.noise_rekey_max_bytes = max_bytes;
+        self
+    }
+
     pub fn with_listener_address(mut self, listener_address: Multiaddr) -> Self {
         self.connection_manager_config.listener_address = listener_address;
         self
@@ -143,6 +180,13 @@ impl CommsBuilder {
         self
     }
 
+    /// The maximum number of outbound dials that will be attempted at the same time. Once this limit is reached,
+    /// further dial requests are queued until an in-progress dial completes.
+    pub fn with_max_simultaneous_outbound_dials(mut self, max_simultaneous_outbound_dials: usize) -> Self {
+        self.connection_manager_config.max_simultaneous_outbound_dials = max_simultaneous_outbound_dials;
+        self
+    }
+
     /// The number of dial attempts to make before giving up.
     pub fn with_max_dial_attempts(mut self, max_dial_attempts: usize) -> Self {
         self.connection_manager_config.max_dial_attempts = max_dial_attempts;
@@ -105,7 +105,7 @@ async fn spawn_node(
         .await
         .unwrap();
 
-    unpack_enum!(Protocol::Memory(_port) = comms_node.listening_address().iter().next().unwrap());
+    unpack_enum!(Protocol::Memory(_port) = comms_node.listening_address().unwrap().iter().next().unwrap());
 
     (comms_node, inbound_rx, outbound_tx, messaging_events_sender)
 }
@@ -374,3 +374,51 @@ where
     let mut uniq = HashSet::new();
     iter.into_iter().all(move |x| uniq.insert(x))
 }
+
+#[runtime::test_basic]
+async fn outbound_only_node() {
+    let mut shutdown = Shutdown::new();
+    let (comms_node1, _, _, _) = spawn_node(Protocols::new(), shutdown.to_signal()).await;
+
+    let node_identity2 = build_node_identity(PeerFeatures::COMMUNICATION_CLIENT);
+    let comms_node2 = CommsBuilder::new()
+        .outbound_only()
+        .with_shutdown_signal(shutdown.to_signal())
+        .with_peer_storage(HashmapDatabase::new(), None)
+        .with_node_identity(node_identity2.clone())
+        .build()
+        .unwrap()
+        .spawn_with_transport(MemoryTransport)
+        .await
+        .unwrap();
+    assert!(comms_node2.listening_address().is_none());
+
+    let node_identity1 = comms_node1.node_identity();
+    comms_node2
+        .peer_manager()
+        .add_peer(node_identity1.to_peer())
+        .await
+        .unwrap();
+
+    let mut conn_man_events1 = comms_node1.subscribe_connection_manager_events();
+    let _conn = comms_node2
+        .connectivity()
+        .dial_peer(node_identity1.node_id().clone())
+        .await
+        .unwrap();
+
+    let next_event = conn_man_events1.next().await.unwrap().unwrap();
+    unpack_enum!(ConnectionManagerEvent::PeerConnected(_conn) = &*next_event);
+
+    // The outbound-only node is added to the peer list without any addresses
+    let peer = comms_node1
+        .peer_manager()
+        .find_by_node_id(node_identity2.node_id())
+        .await
+        .unwrap();
+    assert!(peer.addresses.is_empty());
+
+    shutdown.trigger().unwrap();
+    comms_node1.wait_until_shutdown().await;
+    comms_node2.wait_until_shutdown().await;
+}
@@ -25,6 +25,7 @@ use crate::{
     connection_manager::error::ConnectionManagerError,
     multiaddr::{Multiaddr, Protocol},
     multiplexing::Yamux,
+    noise::{RekeySupport, NOISE_REKEY_CAPABILITY},
     peer_manager::{NodeId, NodeIdentity, Peer, PeerFeatures, PeerFlags},
     proto::identity::PeerIdentityMsg,
     protocol,
@@ -34,6 +35,7 @@ use crate::{
 };
 use futures::StreamExt;
 use log::*;
+use std::iter;
 use tari_crypto::tari_utilities::ByteArray;
 
 const LOG_TARGET: &str = "comms::connection_manager::common";
@@ -47,6 +49,8 @@ pub async fn perform_identity_exchange<'p, P: IntoIterator<Item = &'p ProtocolId
     direction: ConnectionDirection,
     our_supported_protocols: P,
     user_agent: String,
+    advertise_public_address: bool,
+    observed_address: Option<&Multiaddr>,
 ) -> Result<PeerIdentityMsg, ConnectionManagerError>
 {
     let mut control = muxer.get_yamux_control();
@@ -64,12 +68,29 @@ pub async fn perform_identity_exchange<'p, P: IntoIterator<Item = &'p ProtocolId
         "{} substream opened to peer. Performing identity exchange.", direction
     );
 
-    let peer_identity =
-        protocol::identity_exchange(node_identity, direction, our_supported_protocols, user_agent, stream).await?;
+    let peer_identity = protocol::identity_exchange(
+        node_identity,
+        direction,
+        our_supported_protocols
+            .into_iter()
+            .chain(iter::once(&NOISE_REKEY_CAPABILITY)),
+        user_agent,
+        advertise_public_address,
+        observed_address,
+        stream,
+    )
+    .await?;
 
     Ok(peer_identity)
 }
 
+/// Enables the rotation of the outgoing noise session key if the peer has advertised that it supports it
+pub fn enable_rekey_if_supported(rekey_support: &RekeySupport, their_supported_protocols: &[ProtocolId]) {
+    if their_supported_protocols.contains(&NOISE_REKEY_CAPABILITY) {
+        rekey_support.set_supported_by_peer();
+    }
+}
+
 /// Validate the node id against the given public key. Returns true if this is a valid base node
 /// node id, otherwise false.
 pub fn is_valid_base_node_node_id(node_id: &NodeId, public_key: &CommsPublicKey) -> bool {
@@ -83,8 +104,11 @@ pub fn is_valid_base_node_node_id(node_id: &NodeId, public_key: &CommsPublicKey)
 ///
 /// The following process is used to validate the peer:
 /// 1. Check the offered node identity is a valid base node identity (TODO: This won't work for DAN nodes)
+/// 1. Check that the identity is signed by the peer's authenticated public key. An unsigned identity from a peer
+/// running an earlier version is accepted if `allow_unsigned_identity` is true, a badly signed one never is.
 /// 1. Check if we know the peer, if so, is the peer banned, if so, return an error
-/// 1. Check that the offered addresses are valid
+/// 1. Check that the offered addresses are valid. An inbound client peer may offer no addresses if it is running in
+/// outbound-only mode, in which case any previously known addresses for the peer are cleared.
 /// 1. Update or add the peer, returning it's NodeId
 ///
 /// If the `allow_test_addrs` parameter is true, loopback, local link and other addresses normally not considered valid
@@ -96,6 +120,7 @@ pub async fn validate_and_add_peer_from_peer_identity(
     mut peer_identity: PeerIdentityMsg,
     dialed_addr: Option<&Multiaddr>,
     allow_test_addrs: bool,
+    allow_unsigned_identity: bool,
 ) -> Result<(NodeId, Vec<ProtocolId>), ConnectionManagerError>
 {
     // let peer_manager = peer_manager.inner();
@@ -107,6 +132,20 @@ pub async fn validate_and_add_peer_from_peer_identity(
         return Err(ConnectionManagerError::PeerIdentityInvalidNodeId);
     }
 
+    // The peer must have signed the identity it claims with the key it authenticated with
+    if peer_identity.identity_signature.is_empty() {
+        if !allow_unsigned_identity {
+            return Err(ConnectionManagerError::PeerIdentityInvalidSignature);
+        }
+        debug!(
+            target: LOG_TARGET,
+            "Peer '{}' sent an unsigned identity, which is accepted until every peer signs its identity",
+            peer_node_id.short_str()
+        );
+    } else if !protocol::verify_peer_identity_signature(&authenticated_public_key, &peer_identity) {
+        return Err(ConnectionManagerError::PeerIdentityInvalidSignature);
+    }
+
     let addresses = peer_identity
         .addresses
         .into_iter()
@@ -116,7 +155,9 @@ pub async fn validate_and_add_peer_from_peer_identity(
     // TODO: #banheuristic
     validate_peer_addresses(&addresses, allow_test_addrs)?;
 
-    if addresses.is_empty() {
+    let features = PeerFeatures::from_bits_truncate(peer_identity.features);
+    let is_outbound_only_client = dialed_addr.is_none() && features.is_client();
+    if addresses.is_empty() && !is_outbound_only_client {
         return Err(ConnectionManagerError::PeerIdentityNoValidAddresses);
     }
 
@@ -142,7 +183,7 @@ pub async fn validate_and_add_peer_from_peer_identity(
             if let Some(addr) = dialed_addr {
                 peer.addresses.mark_successful_connection_attempt(addr);
             }
-            peer.features = PeerFeatures::from_bits_truncate(peer_identity.features);
+            peer.features = features;
             peer.supported_protocols = supported_protocols.clone();
             peer.user_agent = peer_identity.user_agent;
             peer
@@ -158,7 +199,7 @@ pub async fn validate_and_add_peer_from_peer_identity(
                 peer_node_id.clone(),
                 addresses.into(),
                 PeerFlags::empty(),
-                PeerFeatures::from_bits_truncate(peer_identity.features),
+                features,
                 supported_protocols.clone(),
                 peer_identity.user_agent,
             );
@@ -284,6 +325,10 @@ fn validate_tcp_port(expected_tcp: Protocol) -> Result<(), ConnectionManagerErro
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::{
+        runtime,
+        test_utils::{node_identity::build_node_identity, test_node::build_peer_manager},
+    };
     use multiaddr::multiaddr;
 
     #[test]
@@ -341,4 +386,40 @@ mod test {
             validate_address(addr, true).unwrap_err();
         }
     }
+
+    #[runtime::test_basic]
+    async fn validate_unsigned_peer_identity() {
+        let peer_manager = build_peer_manager();
+        let node_identity = build_node_identity(PeerFeatures::COMMUNICATION_NODE);
+        let identity = PeerIdentityMsg {
+            node_id: node_identity.node_id().to_vec(),
+            addresses: vec![node_identity.public_address().to_string()],
+            features: node_identity.features().bits(),
+            supported_protocols: Vec::new(),
+            user_agent: String::new(),
+            observed_address: String::new(),
+            identity_signature: Vec::new(),
+        };
+        let validate = |identity: PeerIdentityMsg, allow_unsigned_identity: bool| {
+            validate_and_add_peer_from_peer_identity(
+                &peer_manager,
+                None,
+                node_identity.public_key().clone(),
+                identity,
+                None,
+                true,
+                allow_unsigned_identity,
+            )
+        };
+
+        // An unsigned identity from a peer running an earlier version is only accepted during the transition
+        validate(identity.clone(), false).await.unwrap_err();
+        validate(identity.clone(), true).await.unwrap();
+
+        // A bad signature is never accepted
+        let mut badly_signed = identity;
+        badly_signed.identity_signature = vec![1, 2, 3];
+        validate(badly_signed.clone(), true).await.unwrap_err();
+        validate(badly_signed, false).await.unwrap_err();
+    }
 }
@@ -22,6 +22,7 @@
 
 use crate::{
     connection_manager::{error::ConnectionManagerError, peer_connection::PeerConnection},
+    multiaddr::Multiaddr,
     peer_manager::Peer,
 };
 use futures::channel::oneshot;
@@ -37,6 +38,8 @@ pub struct DialState {
     cancel_signal: ShutdownSignal,
     /// Reply channel for a connection result
     pub reply_tx: oneshot::Sender<Result<PeerConnection, ConnectionManagerError>>,
+    /// The address of this node as observed by the peer, if the peer reported one
+    observed_address: Option<Multiaddr>,
 }
 
 impl DialState {
@@ -52,6 +55,7 @@ impl DialState {
             attempts: 0,
             reply_tx,
             cancel_signal,
+            observed_address: None,
         }
     }
 
@@ -69,4 +73,14 @@ impl DialState {
     pub fn num_attempts(&self) -> usize {
         self.attempts
     }
+
+    pub fn set_observed_address(&mut self, observed_address: Option<Multiaddr>) -> &mut Self {
+        self.observed_address = observed_address;
+        self
+    }
+
+    /// Take the address of this node as observed by the peer, if any
+    pub fn take_observed_address(&mut self) -> Option<Multiaddr> {
+        self.observed_address.take()
+    }
 }
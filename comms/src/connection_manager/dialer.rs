@@ -28,6 +28,7 @@ use crate::{
         dial_state::DialState,
         manager::{ConnectionManagerConfig, ConnectionManagerEvent},
         peer_connection,
+        public_address::PublicAddressObserver,
         wire_mode::WireMode,
     },
     multiaddr::Multiaddr,
@@ -35,7 +36,7 @@ use crate::{
     noise::{NoiseConfig, NoiseSocket},
     peer_manager::{NodeId, NodeIdentity, Peer, PeerFeatures, PeerManager},
     protocol::ProtocolId,
-    transports::Transport,
+    transports::{RemoteAddress, Transport},
     types::CommsPublicKey,
 };
 use futures::{
@@ -52,16 +53,25 @@ use futures::{
     StreamExt,
 };
 use log::*;
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{
+    cmp,
+    collections::{HashMap, VecDeque},
+    iter,
+    sync::Arc,
+    time::Duration,
+};
 use tari_crypto::tari_utilities::hex::Hex;
 use tari_shutdown::{Shutdown, ShutdownSignal};
 use tokio::time;
 
 const LOG_TARGET: &str = "comms::connection_manager::dialer";
+/// The number of most recent public address observations that are retained
+const MAX_PUBLIC_ADDRESS_OBSERVATIONS: usize = 20;
 
-type DialResult<TSocket> = Result<(NoiseSocket<TSocket>, Multiaddr), ConnectionManagerError>;
+type DialResult<TSocket> = Result<(NoiseSocket<TSocket>, Multiaddr, Option<Multiaddr>), ConnectionManagerError>;
 type DialFuturesUnordered =
     FuturesUnordered<BoxFuture<'static, (DialState, Result<PeerConnection, ConnectionManagerError>)>>;
+type DialReplyTx = oneshot::Sender<Result<PeerConnection, ConnectionManagerError>>;
 
 #[derive(Debug)]
 pub(crate) enum DialerRequest {
@@ -83,14 +93,16 @@ pub struct Dialer<TTransport, TBackoff> {
     cancel_signals: HashMap<NodeId, Shutdown>,
     conn_man_notifier: mpsc::Sender<ConnectionManagerEvent>,
     shutdown: Option<ShutdownSignal>,
-    pending_dial_requests: HashMap<NodeId, Vec<oneshot::Sender<Result<PeerConnection, ConnectionManagerError>>>>,
+    pending_dial_requests: HashMap<NodeId, Vec<DialReplyTx>>,
+    dial_queue: VecDeque<(Box<Peer>, DialReplyTx)>,
     our_supported_protocols: Vec<ProtocolId>,
+    public_address_observer: Option<PublicAddressObserver>,
 }
 
 impl<TTransport, TBackoff> Dialer<TTransport, TBackoff>
 where
     TTransport: Transport + Unpin + Send + Sync + Clone + 'static,
-    TTransport::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin + 'static,
+    TTransport::Output: AsyncRead + AsyncWrite + RemoteAddress + Send + Sync + Unpin + 'static,
     TBackoff: Backoff + Send + Sync + 'static,
 {
     #[allow(clippy::too_many_arguments)]
@@ -106,6 +118,15 @@ where
         shutdown: ShutdownSignal,
     ) -> Self
     {
+        let public_address_observer = if config.auto_update_public_address && !config.outbound_only {
+            Some(PublicAddressObserver::new(
+                config.public_address_min_observations,
+                MAX_PUBLIC_ADDRESS_OBSERVATIONS,
+            ))
+        } else {
+            None
+        };
+
         Self {
             config,
             node_identity,
@@ -118,7 +139,9 @@ where
             conn_man_notifier,
             shutdown: Some(shutdown),
             pending_dial_requests: Default::default(),
+            dial_queue: VecDeque::new(),
             our_supported_protocols: Vec::new(),
+            public_address_observer,
         }
     }
 
@@ -140,6 +163,7 @@ where
                 request = self.request_rx.select_next_some() => self.handle_request(&mut pending_dials, request),
                 (dial_state, dial_result) = pending_dials.select_next_some() => {
                     self.handle_dial_result(dial_state, dial_result).await;
+                    self.process_dial_queue(&mut pending_dials);
                 }
                 _ = shutdown => {
                     info!(target: LOG_TARGET, "Connection dialer shutting down because the shutdown signal was received");
@@ -161,6 +185,12 @@ where
                 if let Some(mut s) = self.cancel_signals.remove(&peer_id) {
                     let _ = s.trigger();
                 }
+                if let Some(pos) = self.dial_queue.iter().position(|(peer, _)| peer.node_id == peer_id) {
+                    if let Some((_, reply_tx)) = self.dial_queue.remove(pos) {
+                        let _ = reply_tx.send(Err(ConnectionManagerError::DialCancelled));
+                    }
+                    self.reply_to_pending_requests(&peer_id, Err(ConnectionManagerError::DialCancelled));
+                }
             },
         }
     }
@@ -169,12 +199,44 @@ where
         self.cancel_signals.contains_key(node_id)
     }
 
+    fn is_queued_dial(&self, node_id: &NodeId) -> bool {
+        self.dial_queue.iter().any(|(peer, _)| peer.node_id == *node_id)
+    }
+
+    fn max_simultaneous_dials(&self) -> usize {
+        cmp::max(1, self.config.max_simultaneous_outbound_dials)
+    }
+
+    /// Start queued dials while there is capacity to do so
+    fn process_dial_queue(&mut self, pending_dials: &mut DialFuturesUnordered) {
+        while pending_dials.len() < self.max_simultaneous_dials() {
+            match self.dial_queue.pop_front() {
+                Some((peer, reply_tx)) => {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Starting queued dial for peer '{}' ({} dial(s) remaining in queue)",
+                        peer.node_id.short_str(),
+                        self.dial_queue.len()
+                    );
+                    self.start_dial(pending_dials, peer, reply_tx);
+                },
+                None => break,
+            }
+        }
+    }
+
     fn cancel_all_dials(&mut self) {
         debug!(
             target: LOG_TARGET,
-            "Cancelling {} pending dial(s)",
-            self.cancel_signals.len()
+            "Cancelling {} pending dial(s) and {} queued dial(s)",
+            self.cancel_signals.len(),
+            self.dial_queue.len()
         );
+        let queued = self.dial_queue.drain(..).collect::<Vec<_>>();
+        for (peer, reply_tx) in queued {
+            let _ = reply_tx.send(Err(ConnectionManagerError::DialCancelled));
+            self.reply_to_pending_requests(&peer.node_id, Err(ConnectionManagerError::DialCancelled));
+        }
         self.cancel_signals.drain().for_each(|(_, mut signal)| {
             log_if_error_fmt!(
                 level: warn,
@@ -187,10 +249,11 @@ where
 
     async fn handle_dial_result(
         &mut self,
-        dial_state: DialState,
+        mut dial_state: DialState,
         dial_result: Result<PeerConnection, ConnectionManagerError>,
     )
     {
+        let observed_address = dial_state.take_observed_address();
         let DialState { peer, reply_tx, .. } = dial_state;
 
         let node_id = peer.node_id.clone();
@@ -202,6 +265,9 @@ where
         match &dial_result {
             Ok(conn) => {
                 debug!(target: LOG_TARGET, "Successfully dialed peer '{}'", peer_id_short_str);
+                if let Some(addr) = observed_address {
+                    self.handle_observed_address(node_id.clone(), addr);
+                }
                 self.notify_connection_manager(ConnectionManagerEvent::PeerConnected(conn.clone()))
                     .await
             },
@@ -225,6 +291,39 @@ where
         let _ = reply_tx.send(dial_result);
     }
 
+    /// Record the address that a dialed peer observed for this node, updating this node's public address if the
+    /// majority of peers agree that it has changed.
+    fn handle_observed_address(&mut self, peer_node_id: NodeId, observed_address: Multiaddr) {
+        let observer = match self.public_address_observer.as_mut() {
+            Some(observer) => observer,
+            None => return,
+        };
+
+        if let Err(err) =
+            common::validate_peer_addresses(iter::once(&observed_address), self.config.allow_test_addresses)
+        {
+            debug!(
+                target: LOG_TARGET,
+                "Peer '{}' reported an invalid observed address '{}': {}",
+                peer_node_id.short_str(),
+                observed_address,
+                err
+            );
+            return;
+        }
+
+        let current_address = self.node_identity.public_address();
+        if let Some(new_address) = observer.observe(peer_node_id, &observed_address, &current_address) {
+            info!(
+                target: LOG_TARGET,
+                "Peers report that this node's public address has changed. Updating public address from '{}' to '{}'",
+                current_address,
+                new_address
+            );
+            self.node_identity.set_public_address(new_address);
+        }
+    }
+
     pub async fn notify_connection_manager(&mut self, event: ConnectionManagerEvent) {
         log_if_error!(
             target: LOG_TARGET,
@@ -258,15 +357,31 @@ where
         &mut self,
         pending_dials: &mut DialFuturesUnordered,
         peer: Box<Peer>,
-        reply_tx: oneshot::Sender<Result<PeerConnection, ConnectionManagerError>>,
+        reply_tx: DialReplyTx,
     )
     {
-        if self.is_pending_dial(&peer.node_id) {
+        if self.is_pending_dial(&peer.node_id) || self.is_queued_dial(&peer.node_id) {
             let entry = self.pending_dial_requests.entry(peer.node_id).or_insert_with(Vec::new);
             entry.push(reply_tx);
             return;
         }
 
+        if pending_dials.len() >= self.max_simultaneous_dials() {
+            debug!(
+                target: LOG_TARGET,
+                "Maximum number of simultaneous dials ({}) reached. Queuing dial for peer '{}' ({} dial(s) queued)",
+                self.max_simultaneous_dials(),
+                peer.node_id.short_str(),
+                self.dial_queue.len() + 1
+            );
+            self.dial_queue.push_back((peer, reply_tx));
+            return;
+        }
+
+        self.start_dial(pending_dials, peer, reply_tx);
+    }
+
+    fn start_dial(&mut self, pending_dials: &mut DialFuturesUnordered, peer: Box<Peer>, reply_tx: DialReplyTx) {
         let transport = self.transport.clone();
         let dial_cancel = Shutdown::new();
         let cancel_signal = dial_cancel.to_signal();
@@ -283,15 +398,17 @@ where
         let user_agent = self.config.user_agent.clone();
         let noise_config = self.noise_config.clone();
         let allow_test_addresses = self.config.allow_test_addresses;
+        let allow_unsigned_identities = self.config.allow_unsigned_identities;
+        let outbound_only = self.config.outbound_only;
 
         let dial_fut = async move {
-            let (dial_state, dial_result) =
+            let (mut dial_state, dial_result) =
                 Self::dial_peer_with_retry(dial_state, noise_config, transport, backoff, max_attempts).await;
 
             let cancel_signal = dial_state.get_cancel_signal();
 
             match dial_result {
-                Ok((socket, addr)) => {
+                Ok((socket, addr, remote_address)) => {
                     let authenticated_public_key =
                         match Self::check_authenticated_public_key(&socket, &dial_state.peer.public_key) {
                             Ok(pk) => pk,
@@ -305,14 +422,21 @@ where
                         node_identity,
                         socket,
                         addr,
+                        remote_address,
                         authenticated_public_key,
                         conn_man_notifier,
                         supported_protocols,
                         user_agent,
                         allow_test_addresses,
+                        allow_unsigned_identities,
+                        outbound_only,
                         cancel_signal,
                     )
-                    .await;
+                    .await
+                    .map(|(conn, observed_address)| {
+                        dial_state.set_observed_address(observed_address);
+                        conn
+                    });
 
                     (dial_state, result)
                 },
@@ -345,16 +469,20 @@ where
         node_identity: Arc<NodeIdentity>,
         socket: NoiseSocket<TTransport::Output>,
         dialed_addr: Multiaddr,
+        remote_address: Option<Multiaddr>,
         authenticated_public_key: CommsPublicKey,
         conn_man_notifier: mpsc::Sender<ConnectionManagerEvent>,
         our_supported_protocols: Vec<ProtocolId>,
         user_agent: String,
         allow_test_addresses: bool,
+        allow_unsigned_identities: bool,
+        outbound_only: bool,
         cancel_signal: ShutdownSignal,
-    ) -> Result<PeerConnection, ConnectionManagerError>
+    ) -> Result<(PeerConnection, Option<Multiaddr>), ConnectionManagerError>
     {
         static CONNECTION_DIRECTION: ConnectionDirection = ConnectionDirection::Outbound;
 
+        let rekey_support = socket.rekey_support();
         let mut muxer = Yamux::upgrade_connection(socket, CONNECTION_DIRECTION)
             .await
             .map_err(|err| ConnectionManagerError::YamuxUpgradeFailure(err.to_string()))?;
@@ -373,6 +501,8 @@ where
             CONNECTION_DIRECTION,
             &our_supported_protocols,
             user_agent,
+            !outbound_only,
+            remote_address.as_ref(),
         )
         .await?;
         if cancel_signal.is_terminated() {
@@ -388,6 +518,7 @@ where
             features
         );
         trace!(target: LOG_TARGET, "{:?}", peer_identity);
+        let observed_address = peer_identity.observed_address.parse::<Multiaddr>().ok();
 
         // Check if we know the peer and if it is banned
         let known_peer = common::find_unbanned_peer(&peer_manager, &authenticated_public_key).await?;
@@ -399,8 +530,10 @@ where
             peer_identity,
             Some(&dialed_addr),
             allow_test_addresses,
+            allow_unsigned_identities,
         )
         .await?;
+        common::enable_rekey_if_supported(&rekey_support, &their_supported_protocols);
 
         if cancel_signal.is_terminated() {
             muxer.get_yamux_control().close().await?;
@@ -414,7 +547,7 @@ where
             peer_node_id.short_str()
         );
 
-        peer_connection::create(
+        let conn = peer_connection::create(
             muxer,
             dialed_addr,
             peer_node_id,
@@ -423,7 +556,9 @@ where
             conn_man_notifier,
             our_supported_protocols,
             their_supported_protocols,
-        )
+        )?;
+
+        Ok((conn, observed_address))
     }
 
     async fn dial_peer_with_retry(
@@ -456,9 +591,9 @@ where
                 _ = delay => {
                     debug!(target: LOG_TARGET, "[Attempt {}] Connecting to peer '{}'", current_state.num_attempts(), current_state.peer.node_id.short_str());
                     match Self::dial_peer(current_state, &noise_config, &current_transport).await {
-                        (state, Ok((socket, addr))) => {
+                        (state, Ok((socket, addr, remote_address))) => {
                             debug!(target: LOG_TARGET, "Dial succeeded for peer '{}' after {} attempt(s)", state.peer.node_id.short_str(), state.num_attempts());
-                            break (state, Ok((socket, addr)));
+                            break (state, Ok((socket, addr, remote_address)));
                         },
                         // Inflight dial was cancelled
                         (state, Err(ConnectionManagerError::DialCancelled)) => break (state, Err(ConnectionManagerError::DialCancelled)),
@@ -491,7 +626,7 @@ where
         transport: &TTransport,
     ) -> (
         DialState,
-        Result<(NoiseSocket<TTransport::Output>, Multiaddr), ConnectionManagerError>,
+        Result<(NoiseSocket<TTransport::Output>, Multiaddr, Option<Multiaddr>), ConnectionManagerError>,
     )
     {
         let mut addr_iter = dial_state.peer.addresses.iter();
@@ -516,6 +651,8 @@ where
                             target: LOG_TARGET,
                             "Socket established on '{}'. Performing noise upgrade protocol", address
                         );
+                        // The address of the peer as this node sees it, which is reported to the peer
+                        let remote_address = socket.remote_address();
 
                         socket
                             .write(&[WireMode::Comms as u8])
@@ -528,13 +665,15 @@ where
                         )
                         .await
                         .map_err(|_| ConnectionManagerError::NoiseProtocolTimeout)??;
-                        Result::<_, ConnectionManagerError>::Ok(noise_socket)
+                        Result::<_, ConnectionManagerError>::Ok((noise_socket, remote_address))
                     };
 
                     pin_mut!(dial_fut);
                     let either = future::select(dial_fut, cancel_signal.clone()).await;
                     match either {
-                        Either::Left((Ok(noise_socket), _)) => Ok((noise_socket, address.clone())),
+                        Either::Left((Ok((noise_socket, remote_address)), _)) => {
+                            Ok((noise_socket, address.clone(), remote_address))
+                        },
                         Either::Left((Err(err), _)) => {
                             debug!(
                                 target: LOG_TARGET,
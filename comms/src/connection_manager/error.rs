@@ -66,6 +66,8 @@ pub enum ConnectionManagerError {
     IncomingListenerStreamClosed,
     #[error("The peer offered a NodeId that failed to validate against it's public key")]
     PeerIdentityInvalidNodeId,
+    #[error("The peer identity signature did not verify against the peer's public key")]
+    PeerIdentityInvalidSignature,
     #[error("Peer is banned, denying connection")]
     PeerBanned,
     #[error("Unable to parse any of the network addresses offered by the connecting peer")]
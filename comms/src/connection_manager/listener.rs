@@ -201,6 +201,7 @@ where
         let config = self.config.clone();
         let our_supported_protocols = self.our_supported_protocols.clone();
         let allow_test_addresses = self.config.allow_test_addresses;
+        let allow_unsigned_identities = self.config.allow_unsigned_identities;
         let liveness_session_count = self.liveness_session_count.clone();
         let user_agent = self.config.user_agent.clone();
         let shutdown_signal = self.shutdown_signal.clone();
@@ -219,6 +220,7 @@ where
                         our_supported_protocols,
                         user_agent,
                         allow_test_addresses,
+                        allow_unsigned_identities,
                     )
                     .await;
 
@@ -300,6 +302,7 @@ where
         our_supported_protocols: Vec<ProtocolId>,
         user_agent: String,
         allow_test_addresses: bool,
+        allow_unsigned_identities: bool,
     ) -> Result<PeerConnection, ConnectionManagerError>
     {
         static CONNECTION_DIRECTION: ConnectionDirection = ConnectionDirection::Inbound;
@@ -322,6 +325,7 @@ where
         // Check if we know the peer and if it is banned
         let known_peer = common::find_unbanned_peer(&peer_manager, &authenticated_public_key).await?;
 
+        let rekey_support = noise_socket.rekey_support();
         let mut muxer = Yamux::upgrade_connection(noise_socket, CONNECTION_DIRECTION)
             .await
             .map_err(|err| ConnectionManagerError::YamuxUpgradeFailure(err.to_string()))?;
@@ -338,6 +342,9 @@ where
             CONNECTION_DIRECTION,
             &our_supported_protocols,
             user_agent,
+            // The listener is only started if this node is reachable
+            true,
+            Some(&peer_addr),
         )
         .await?;
 
@@ -357,8 +364,10 @@ where
             peer_identity,
             None,
             allow_test_addresses,
+            allow_unsigned_identities,
         )
         .await?;
+        common::enable_rekey_if_supported(&rekey_support, &their_supported_protocols);
 
         debug!(
             target: LOG_TARGET,
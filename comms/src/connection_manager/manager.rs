@@ -34,7 +34,7 @@ use crate::{
     peer_manager::{NodeId, NodeIdentity},
     protocol::{ProtocolEvent, ProtocolId, Protocols},
     runtime,
-    transports::Transport,
+    transports::{RemoteAddress, Transport},
     types::DEFAULT_LISTENER_ADDRESS,
     PeerManager,
 };
@@ -101,6 +101,9 @@ pub struct ConnectionManagerConfig {
     pub listener_address: Multiaddr,
     /// The number of dial attempts to make before giving up. Default: 3
     pub max_dial_attempts: usize,
+    /// The maximum number of outbound dials that will be attempted at the same time. Once this limit is reached,
+    /// further dial requests are queued until an in-progress dial completes. Default: 20
+    pub max_simultaneous_outbound_dials: usize,
     /// The maximum number of connection tasks that will be spawned at the same time. Once this limit is reached, peers
     /// attempting to connect will have to wait for another connection attempt to complete. Default: 20
     pub max_simultaneous_inbound_connects: usize,
@@ -115,6 +118,27 @@ pub struct ConnectionManagerConfig {
     pub liveness_cidr_allowlist: Vec<cidr::AnyIpCidr>,
     /// The user agent string for this node
     pub user_agent: String,
+    /// Set to true to never start the listener. The node relies only on outbound connections (and store and forward)
+    /// for reachability and does not advertise an address to peers. This is useful for wallets that are behind NAT.
+    /// Default: false
+    pub outbound_only: bool,
+    /// Set to true to update this node's advertised public address when the majority of peers that this node dials
+    /// report observing a different public IP address for this node. Only IP addresses are updated, the advertised
+    /// port is retained. Default: false
+    pub auto_update_public_address: bool,
+    /// Set to true to accept the unsigned identities of peers that run a version that does not sign its identity yet.
+    /// Identities that are signed must always have a valid signature. Set to false once every peer signs its
+    /// identity. Default: true
+    pub allow_unsigned_identities: bool,
+    /// The minimum number of distinct peers that must agree on a new public IP address before the advertised
+    /// address is updated. Default: 3
+    pub public_address_min_observations: usize,
+    /// Rotate the Noise session keys of a connection once this much time has elapsed since the last rotation.
+    /// Default: None (disabled)
+    pub noise_rekey_interval: Option<Duration>,
+    /// Rotate the Noise session keys of a connection once this many bytes have been sent since the last rotation.
+    /// Default: None (disabled)
+    pub noise_rekey_max_bytes: Option<u64>,
 }
 
 impl Default for ConnectionManagerConfig {
@@ -124,6 +148,7 @@ impl Default for ConnectionManagerConfig {
                 .parse()
                 .expect("DEFAULT_LISTENER_ADDRESS is malformed"),
             max_dial_attempts: 3,
+            max_simultaneous_outbound_dials: 20,
             max_simultaneous_inbound_connects: 20,
             #[cfg(not(test))]
             allow_test_addresses: false,
@@ -134,11 +159,18 @@ impl Default for ConnectionManagerConfig {
             time_to_first_byte: Duration::from_secs(7),
             liveness_cidr_allowlist: vec![cidr::AnyIpCidr::V4("127.0.0.1/32".parse().unwrap())],
             user_agent: Default::default(),
+            outbound_only: false,
+            auto_update_public_address: false,
+            allow_unsigned_identities: true,
+            public_address_min_observations: 3,
+            noise_rekey_interval: None,
+            noise_rekey_max_bytes: None,
         }
     }
 }
 
 pub struct ConnectionManager<TTransport, TBackoff> {
+    is_outbound_only: bool,
     request_rx: Fuse<mpsc::Receiver<ConnectionManagerRequest>>,
     internal_event_rx: Fuse<mpsc::Receiver<ConnectionManagerEvent>>,
     dialer_tx: mpsc::Sender<DialerRequest>,
@@ -156,7 +188,7 @@ pub struct ConnectionManager<TTransport, TBackoff> {
 impl<TTransport, TBackoff> ConnectionManager<TTransport, TBackoff>
 where
     TTransport: Transport + Unpin + Send + Sync + Clone + 'static,
-    TTransport::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin + 'static,
+    TTransport::Output: AsyncRead + AsyncWrite + RemoteAddress + Send + Sync + Unpin + 'static,
     TBackoff: Backoff + Send + Sync + 'static,
 {
     #[allow(clippy::too_many_arguments)]
@@ -175,6 +207,7 @@ where
         let (internal_event_tx, internal_event_rx) = mpsc::channel(EVENT_CHANNEL_SIZE);
 
         let (dialer_tx, dialer_rx) = mpsc::channel(DIALER_REQUEST_CHANNEL_SIZE);
+        let is_outbound_only = config.outbound_only;
 
         let listener = PeerListener::new(
             config.clone(),
@@ -199,6 +232,7 @@ where
         );
 
         Self {
+            is_outbound_only,
             shutdown_signal: Some(shutdown_signal),
             request_rx: request_rx.fuse(),
             peer_manager,
@@ -233,7 +267,14 @@ where
             .take()
             .expect("ConnectionManager initialized without a shutdown");
 
-        self.run_listener();
+        if self.is_outbound_only {
+            info!(
+                target: LOG_TARGET,
+                "Connection manager is running in outbound-only mode. Inbound connections will not be accepted."
+            );
+        } else {
+            self.run_listener();
+        }
         self.run_dialer();
 
         debug!(
@@ -296,6 +337,8 @@ where
                     );
                 }
             },
+            // The reply is dropped, there will never be a listening address in outbound-only mode
+            NotifyListening(_) if self.is_outbound_only => {},
             NotifyListening(reply) => match self.listener_address.as_ref() {
                 Some(addr) => {
                     let _ = reply.send(addr.clone());
@@ -43,6 +43,7 @@ mod peer_connection;
 pub use peer_connection::{ConnectionId, NegotiatedSubstream, PeerConnection, PeerConnectionRequest};
 
 mod liveness;
+mod public_address;
 mod wire_mode;
 
 #[cfg(test)]
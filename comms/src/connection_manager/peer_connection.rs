@@ -21,7 +21,7 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 #[cfg(feature = "rpc")]
-use crate::protocol::rpc::{NamedProtocolService, RpcClient, RpcClientBuilder, RpcError, RPC_MAX_FRAME_SIZE};
+use crate::protocol::rpc::{NamedProtocolService, RpcClient, RpcClientBuilder, RpcError};
 
 use super::{
     error::{ConnectionManagerError, PeerConnectionError},
@@ -222,7 +222,9 @@ impl PeerConnection {
             String::from_utf8_lossy(protocol),
             self.peer_node_id
         );
-        let framed = self.open_framed_substream(&protocol.into(), RPC_MAX_FRAME_SIZE).await?;
+        let framed = self
+            .open_framed_substream(&protocol.into(), builder.max_frame_size())
+            .await?;
         builder.connect(framed).await
     }
 
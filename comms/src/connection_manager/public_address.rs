@@ -0,0 +1,190 @@
+// Copyright 2019, The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::{
+    multiaddr::{Multiaddr, Protocol},
+    peer_manager::NodeId,
+};
+use log::*;
+use std::{collections::VecDeque, net::IpAddr};
+
+const LOG_TARGET: &str = "comms::connection_manager::public_address";
+
+/// Tracks the source addresses that peers report observing for this node's outbound connections, and determines
+/// if the majority of peers agree on a public IP address that differs from the one this node currently advertises.
+///
+/// Only the IP component of an observation is used. The observed port is the ephemeral port of the outbound
+/// connection and is unrelated to the port this node listens on, so the port (and any other components) of the
+/// currently advertised address is retained.
+pub struct PublicAddressObserver {
+    observations: VecDeque<(NodeId, IpAddr)>,
+    min_observations: usize,
+    max_observations: usize,
+}
+
+impl PublicAddressObserver {
+    pub fn new(min_observations: usize, max_observations: usize) -> Self {
+        Self {
+            observations: VecDeque::with_capacity(max_observations),
+            min_observations,
+            max_observations,
+        }
+    }
+
+    /// Record the address that `peer` observed for this node. Each peer has a single vote, which is replaced if it
+    /// reports again. Returns the new public address to advertise if a majority (of at least `min_observations`)
+    /// of peers agree on an IP address that differs from the IP address of `current_address`, otherwise None.
+    pub fn observe(
+        &mut self,
+        peer: NodeId,
+        observed_address: &Multiaddr,
+        current_address: &Multiaddr,
+    ) -> Option<Multiaddr>
+    {
+        let observed_ip = extract_ip(observed_address)?;
+        // Addresses without an IP component (e.g. onion addresses) are never updated
+        let current_ip = extract_ip(current_address)?;
+
+        if let Some(pos) = self.observations.iter().position(|(node_id, _)| *node_id == peer) {
+            self.observations.remove(pos);
+        }
+        if self.observations.len() >= self.max_observations {
+            self.observations.pop_front();
+        }
+        self.observations.push_back((peer, observed_ip));
+
+        let (ip, votes) = self.majority_ip()?;
+        if votes < self.min_observations || ip == current_ip {
+            return None;
+        }
+
+        debug!(
+            target: LOG_TARGET,
+            "{} of {} peer(s) observed this node's public IP as {} (currently advertising {})",
+            votes,
+            self.observations.len(),
+            ip,
+            current_ip
+        );
+        Some(replace_ip(current_address, ip))
+    }
+
+    /// Returns the IP address that more than half of the recorded observations agree on, along with the number of
+    /// votes for it.
+    fn majority_ip(&self) -> Option<(IpAddr, usize)> {
+        let total = self.observations.len();
+        self.observations
+            .iter()
+            .map(|(_, ip)| *ip)
+            .map(|ip| (ip, self.observations.iter().filter(|(_, i)| *i == ip).count()))
+            .find(|(_, votes)| *votes * 2 > total)
+    }
+}
+
+fn extract_ip(addr: &Multiaddr) -> Option<IpAddr> {
+    addr.iter().find_map(|p| match p {
+        Protocol::Ip4(ip) => Some(IpAddr::V4(ip)),
+        Protocol::Ip6(ip) => Some(IpAddr::V6(ip)),
+        _ => None,
+    })
+}
+
+fn replace_ip(addr: &Multiaddr, ip: IpAddr) -> Multiaddr {
+    addr.iter()
+        .map(|p| match p {
+            Protocol::Ip4(_) | Protocol::Ip6(_) => Protocol::from(ip),
+            p => p,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::node_id;
+    use multiaddr::multiaddr;
+
+    fn observed(ip: [u8; 4]) -> Multiaddr {
+        multiaddr!(Ip4(ip), Tcp(54321u16))
+    }
+
+    #[test]
+    fn it_updates_the_address_once_a_majority_agree() {
+        let current = multiaddr!(Ip4([1, 1, 1, 1]), Tcp(18189u16));
+        let mut observer = PublicAddressObserver::new(3, 10);
+
+        assert!(observer
+            .observe(node_id::random(), &observed([2, 2, 2, 2]), &current)
+            .is_none());
+        assert!(observer
+            .observe(node_id::random(), &observed([2, 2, 2, 2]), &current)
+            .is_none());
+        let new_addr = observer
+            .observe(node_id::random(), &observed([2, 2, 2, 2]), &current)
+            .unwrap();
+        assert_eq!(new_addr, multiaddr!(Ip4([2, 2, 2, 2]), Tcp(18189u16)));
+    }
+
+    #[test]
+    fn it_does_not_update_without_a_majority() {
+        let current = multiaddr!(Ip4([1, 1, 1, 1]), Tcp(18189u16));
+        let mut observer = PublicAddressObserver::new(2, 10);
+
+        assert!(observer
+            .observe(node_id::random(), &observed([2, 2, 2, 2]), &current)
+            .is_none());
+        assert!(observer
+            .observe(node_id::random(), &observed([3, 3, 3, 3]), &current)
+            .is_none());
+        assert!(observer
+            .observe(node_id::random(), &observed([4, 4, 4, 4]), &current)
+            .is_none());
+        assert!(observer
+            .observe(node_id::random(), &observed([2, 2, 2, 2]), &current)
+            .is_none());
+        // Confirms the current address
+        assert!(observer
+            .observe(node_id::random(), &observed([1, 1, 1, 1]), &current)
+            .is_none());
+    }
+
+    #[test]
+    fn it_counts_one_vote_per_peer() {
+        let current = multiaddr!(Ip4([1, 1, 1, 1]), Tcp(18189u16));
+        let mut observer = PublicAddressObserver::new(2, 10);
+        let peer = node_id::random();
+
+        assert!(observer
+            .observe(peer.clone(), &observed([2, 2, 2, 2]), &current)
+            .is_none());
+        assert!(observer.observe(peer, &observed([2, 2, 2, 2]), &current).is_none());
+    }
+
+    #[test]
+    fn it_ignores_addresses_without_an_ip() {
+        let current: Multiaddr = "/onion/aaimaq4ygg2iegci:1234".parse().unwrap();
+        let mut observer = PublicAddressObserver::new(1, 10);
+        assert!(observer
+            .observe(node_id::random(), &observed([2, 2, 2, 2]), &current)
+            .is_none());
+    }
+}
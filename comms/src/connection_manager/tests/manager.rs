@@ -29,7 +29,7 @@ use crate::{
         ConnectionManagerRequester,
         PeerConnectionError,
     },
-    noise::NoiseConfig,
+    noise::{NoiseConfig, NOISE_REKEY_CAPABILITY},
     peer_manager::{NodeId, Peer, PeerFeatures, PeerFlags, PeerManagerError},
     protocol::{ProtocolEvent, ProtocolId, Protocols, IDENTITY_PROTOCOL},
     runtime,
@@ -51,7 +51,7 @@ use futures::{
 use std::time::Duration;
 use tari_shutdown::Shutdown;
 use tari_test_utils::{collect_stream, unpack_enum};
-use tokio::{runtime::Handle, sync::broadcast};
+use tokio::{runtime::Handle, sync::broadcast, time};
 
 #[runtime::test_basic]
 async fn connect_to_nonexistent_peer() {
@@ -154,7 +154,11 @@ async fn dial_success() {
     let mut conn_out = conn_man1.dial_peer(node_identity2.node_id().clone()).await.unwrap();
     assert_eq!(conn_out.peer_node_id(), node_identity2.node_id());
     let peer2 = peer_manager1.find_by_node_id(conn_out.peer_node_id()).await.unwrap();
-    assert_eq!(peer2.supported_protocols, [&IDENTITY_PROTOCOL, &TEST_PROTO]);
+    assert_eq!(peer2.supported_protocols, [
+        &IDENTITY_PROTOCOL,
+        &TEST_PROTO,
+        &NOISE_REKEY_CAPABILITY
+    ]);
     assert_eq!(peer2.user_agent, "node2");
 
     let event = subscription2.next().await.unwrap().unwrap();
@@ -165,7 +169,11 @@ async fn dial_success() {
     assert_eq!(conn_in.peer_node_id(), node_identity1.node_id());
 
     let peer1 = peer_manager2.find_by_node_id(node_identity1.node_id()).await.unwrap();
-    assert_eq!(peer1.supported_protocols(), [&IDENTITY_PROTOCOL, &TEST_PROTO]);
+    assert_eq!(peer1.supported_protocols(), [
+        &IDENTITY_PROTOCOL,
+        &TEST_PROTO,
+        &NOISE_REKEY_CAPABILITY
+    ]);
     assert_eq!(peer1.user_agent, "node1");
 
     let err = conn_out
@@ -350,3 +358,70 @@ async fn dial_cancelled() {
     assert_eq!(&**node_id, node_identity2.node_id());
     unpack_enum!(ConnectionManagerError::DialCancelled = err);
 }
+
+#[tokio_macros::test_basic]
+async fn dial_queued_when_max_simultaneous_dials_reached() {
+    let mut shutdown = Shutdown::new();
+
+    let node_identity1 = build_node_identity(PeerFeatures::empty());
+    let node_identity2 = build_node_identity(PeerFeatures::empty());
+    let node_identity3 = build_node_identity(PeerFeatures::empty());
+
+    let peer_manager1 = build_peer_manager();
+
+    let mut conn_man1 = build_connection_manager(
+        {
+            let mut config = TestNodeConfig {
+                node_identity: node_identity1.clone(),
+                dial_backoff_duration: Duration::from_secs(100),
+                ..Default::default()
+            };
+            config.connection_manager_config.max_simultaneous_outbound_dials = 1;
+            config
+        },
+        peer_manager1.clone(),
+        Default::default(),
+        shutdown.to_signal(),
+    );
+
+    conn_man1.wait_until_listening().await.unwrap();
+
+    let mut subscription1 = conn_man1.get_event_subscription();
+
+    peer_manager1.add_peer(node_identity2.to_peer()).await.unwrap();
+    peer_manager1.add_peer(node_identity3.to_peer()).await.unwrap();
+
+    let dial_results = [node_identity2.node_id().clone(), node_identity3.node_id().clone()]
+        .iter()
+        .cloned()
+        .map(|node_id| {
+            let mut cm = conn_man1.clone();
+            task::spawn(async move { cm.dial_peer(node_id).await })
+        })
+        .collect::<Vec<_>>();
+
+    // Give the dial requests a chance to reach the dialer
+    time::delay_for(Duration::from_millis(100)).await;
+
+    // The second dial is queued behind the first. Cancelling it removes it from the queue.
+    conn_man1.cancel_dial(node_identity3.node_id().clone()).await.unwrap();
+    conn_man1.cancel_dial(node_identity2.node_id().clone()).await.unwrap();
+    for dial_result in dial_results {
+        let err = dial_result.await.unwrap().unwrap_err();
+        unpack_enum!(ConnectionManagerError::DialCancelled = err);
+    }
+
+    shutdown.trigger().unwrap();
+    drop(conn_man1);
+
+    let events1 = collect_stream!(subscription1, timeout = Duration::from_secs(5))
+        .into_iter()
+        .map(Result::unwrap)
+        .collect::<Vec<_>>();
+
+    // Only the in-progress dial publishes an event
+    assert_eq!(events1.len(), 1);
+    unpack_enum!(ConnectionManagerEvent::PeerConnectFailed(node_id, err) = &*events1[0]);
+    assert_eq!(&**node_id, node_identity2.node_id());
+    unpack_enum!(ConnectionManagerError::DialCancelled = err);
+}
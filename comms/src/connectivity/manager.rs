@@ -98,11 +98,16 @@ impl ConnectivityManager {
     }
 }
 
+/// The overall connectivity status of this node
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ConnectivityStatus {
+    /// The connectivity manager has started but has not yet determined the connectivity status
     Initializing,
+    /// The required number of nodes are connected. Contains the number of connected nodes.
     Online(usize),
+    /// Some, but fewer than the required number of nodes are connected. Contains the number of connected nodes.
     Degraded(usize),
+    /// No nodes are connected
     Offline,
 }
 
@@ -48,23 +48,90 @@ const LOG_TARGET: &str = "comms::connectivity::requester";
 pub type ConnectivityEventRx = broadcast::Receiver<Arc<ConnectivityEvent>>;
 pub type ConnectivityEventTx = broadcast::Sender<Arc<ConnectivityEvent>>;
 
+/// Events published by the connectivity manager. A subscription can be obtained by calling
+/// [ConnectivityRequester::get_event_subscription] or `CommsNode::subscribe_connectivity_events`.
+///
+/// Peer events (e.g. `PeerConnected`, `PeerDisconnected`, `PeerBanned`) are published whenever the state of a peer
+/// connection changes. `ConnectivityState*` events are published when the overall connectivity status transitions
+/// (see [ConnectivityEvent::connectivity_status]).
 #[derive(Debug, Clone)]
 pub enum ConnectivityEvent {
+    /// A peer disconnected
     PeerDisconnected(NodeId),
+    /// A managed peer (i.e. a peer added with [ConnectivityRequester::add_managed_peers]) disconnected
     ManagedPeerDisconnected(NodeId),
+    /// A new peer connection was established
     PeerConnected(PeerConnection),
+    /// A connection attempt to a peer failed
     PeerConnectFailed(NodeId),
+    /// A connection attempt to a managed peer failed
     ManagedPeerConnectFailed(NodeId),
+    /// A peer was banned
     PeerBanned(NodeId),
+    /// A peer has been marked as offline after failing to connect too many times
     PeerOffline(NodeId),
+    /// The connection to a peer is about to be closed
     PeerConnectionWillClose(NodeId, ConnectionDirection),
 
+    /// The connectivity manager has started
     ConnectivityStateInitialized,
+    /// Connectivity is online, with the given number of connected nodes
     ConnectivityStateOnline(usize),
+    /// Connectivity is degraded i.e. fewer nodes than required are connected
     ConnectivityStateDegraded(usize),
+    /// No nodes are connected
     ConnectivityStateOffline,
 }
 
+impl ConnectivityEvent {
+    /// Returns the `NodeId` of the peer this event refers to, or None if this is a connectivity state event
+    pub fn peer_node_id(&self) -> Option<&NodeId> {
+        use ConnectivityEvent::*;
+        match self {
+            PeerDisconnected(node_id) |
+            ManagedPeerDisconnected(node_id) |
+            PeerConnectFailed(node_id) |
+            ManagedPeerConnectFailed(node_id) |
+            PeerBanned(node_id) |
+            PeerOffline(node_id) |
+            PeerConnectionWillClose(node_id, _) => Some(node_id),
+            PeerConnected(conn) => Some(conn.peer_node_id()),
+            ConnectivityStateInitialized |
+            ConnectivityStateOnline(_) |
+            ConnectivityStateDegraded(_) |
+            ConnectivityStateOffline => None,
+        }
+    }
+
+    /// Returns true if this event indicates that the set of connected (or usable) peers may have changed. Failed
+    /// connection attempts are not included.
+    pub fn is_peer_state_change(&self) -> bool {
+        use ConnectivityEvent::*;
+        matches!(
+            self,
+            PeerDisconnected(_) |
+                ManagedPeerDisconnected(_) |
+                PeerConnected(_) |
+                PeerBanned(_) |
+                PeerOffline(_) |
+                PeerConnectionWillClose(_, _)
+        )
+    }
+
+    /// Returns the [ConnectivityStatus] that was transitioned to if this is a connectivity state event, otherwise
+    /// None.
+    pub fn connectivity_status(&self) -> Option<ConnectivityStatus> {
+        use ConnectivityEvent::*;
+        match self {
+            ConnectivityStateInitialized => Some(ConnectivityStatus::Initializing),
+            ConnectivityStateOnline(n) => Some(ConnectivityStatus::Online(*n)),
+            ConnectivityStateDegraded(n) => Some(ConnectivityStatus::Degraded(*n)),
+            ConnectivityStateOffline => Some(ConnectivityStatus::Offline),
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for ConnectivityEvent {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use ConnectivityEvent::*;
@@ -115,6 +182,7 @@ impl ConnectivityRequester {
         Self { sender, event_tx }
     }
 
+    /// Returns a subscription to [ConnectivityEvent]s. Events published before subscribing are not received.
     pub fn get_event_subscription(&self) -> ConnectivityEventRx {
         self.event_tx.subscribe()
     }
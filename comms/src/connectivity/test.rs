@@ -22,7 +22,7 @@
 use super::{
     config::ConnectivityConfig,
     connection_pool::ConnectionStatus,
-    manager::ConnectivityManager,
+    manager::{ConnectivityManager, ConnectivityStatus},
     requester::{ConnectivityEvent, ConnectivityRequester},
     selection::ConnectivitySelection,
 };
@@ -347,6 +347,33 @@ async fn ban_peer() {
     assert!(conn.is_none());
 }
 
+#[runtime::test_basic]
+async fn peer_and_status_events() {
+    let (_connectivity, mut event_stream, node_identity, peer_manager, cm_mock_state, _shutdown) =
+        setup_connectivity_manager(Default::default());
+    let peer = add_test_peers(&peer_manager, 1).await.pop().unwrap();
+    let (conn, _, _, _) = create_peer_connection_mock_pair(1, node_identity.to_peer(), peer.clone()).await;
+
+    let event = collect_stream!(event_stream, take = 1, timeout = Duration::from_secs(10))
+        .pop()
+        .unwrap()
+        .unwrap();
+    assert_eq!(event.connectivity_status(), Some(ConnectivityStatus::Initializing));
+    assert!(event.peer_node_id().is_none());
+    assert!(!event.is_peer_state_change());
+
+    cm_mock_state.publish_event(ConnectionManagerEvent::PeerConnected(conn));
+    let mut events = collect_stream!(event_stream, take = 2, timeout = Duration::from_secs(10));
+    let event = events.remove(0).unwrap();
+    assert_eq!(event.peer_node_id(), Some(&peer.node_id));
+    assert!(event.is_peer_state_change());
+    assert!(event.connectivity_status().is_none());
+
+    let event = events.remove(0).unwrap();
+    assert!(event.connectivity_status().unwrap().is_online());
+    assert!(event.peer_node_id().is_none());
+}
+
 #[runtime::test_basic]
 async fn peer_selection() {
     let config = ConnectivityConfig {
@@ -22,7 +22,8 @@
 
 use crate::compat::IoCompat;
 use futures::{AsyncRead, AsyncWrite};
-use tokio_util::codec::{Framed, LengthDelimitedCodec};
+use std::io;
+use tokio_util::codec::{Framed, LengthDelimitedCodec, LengthDelimitedCodecError};
 
 /// Tari comms canonical framing
 pub type CanonicalFraming<T> = Framed<IoCompat<T>, LengthDelimitedCodec>;
@@ -36,3 +37,11 @@ where T: AsyncRead + AsyncWrite + Unpin {
             .new_codec(),
     )
 }
+
+/// Returns true if the given IO error was caused by a frame that exceeds the maximum frame length, otherwise false.
+/// This applies to both inbound and outbound frames.
+pub fn is_max_frame_length_exceeded(err: &io::Error) -> bool {
+    err.get_ref()
+        .map(|e| e.is::<LengthDelimitedCodecError>())
+        .unwrap_or(false)
+}
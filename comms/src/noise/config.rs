@@ -34,7 +34,7 @@ use crate::{
 use futures::{AsyncRead, AsyncWrite};
 use log::*;
 use snow::{self, params::NoiseParams};
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use tari_crypto::tari_utilities::ByteArray;
 
 const LOG_TARGET: &str = "comms::noise";
@@ -46,6 +46,23 @@ pub(super) const NOISE_IX_PARAMETER: &str = "Noise_IX_25519_ChaChaPoly_BLAKE2b";
 pub struct NoiseConfig {
     node_identity: Arc<NodeIdentity>,
     parameters: NoiseParams,
+    rekey_config: RekeyConfig,
+}
+
+/// Determines when the outgoing session key of an established Noise session is rotated. A rekey is triggered by
+/// whichever limit is reached first. If neither limit is set, session keys are never rotated.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RekeyConfig {
+    /// Rotate the session key once this much time has elapsed since the last rekey
+    pub interval: Option<Duration>,
+    /// Rotate the session key once this many bytes have been sent since the last rekey
+    pub max_bytes: Option<u64>,
+}
+
+impl RekeyConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.interval.is_some() || self.max_bytes.is_some()
+    }
 }
 
 impl NoiseConfig {
@@ -55,9 +72,16 @@ impl NoiseConfig {
         Self {
             node_identity,
             parameters,
+            rekey_config: Default::default(),
         }
     }
 
+    /// Periodically rotate the session keys of upgraded sockets according to the given config
+    pub fn with_rekey_config(mut self, rekey_config: RekeyConfig) -> Self {
+        self.rekey_config = rekey_config;
+        self
+    }
+
     /// Upgrades the given socket to using the noise protocol. The upgraded socket and the peer's static key
     /// is returned.
     pub async fn upgrade_socket<TSocket>(
@@ -88,7 +112,7 @@ impl NoiseConfig {
         let handshake = Handshake::new(socket, handshake_state);
         let socket = handshake.handshake_1rt().await.map_err(NoiseError::HandshakeFailed)?;
 
-        Ok(socket)
+        Ok(socket.with_rekey_config(self.rekey_config))
     }
 }
 
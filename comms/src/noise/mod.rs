@@ -27,6 +27,12 @@ mod crypto_resolver;
 mod error;
 mod socket;
 
-pub use config::NoiseConfig;
+pub use config::{NoiseConfig, RekeyConfig};
 pub use error::NoiseError;
-pub use socket::NoiseSocket;
+pub use socket::{NoiseSocket, RekeySupport};
+
+use crate::protocol::ProtocolId;
+
+/// Advertised in the supported protocols of the peer identity to signal that this node supports the rotation of noise
+/// session keys. No substream is opened for this protocol.
+pub static NOISE_REKEY_CAPABILITY: ProtocolId = ProtocolId::from_static(b"/tari/noise/rekey/1.0.0");
@@ -33,10 +33,15 @@ use std::{
     convert::TryInto,
     io,
     pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     task::{Context, Poll},
+    time::Instant,
 };
 // use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
-use crate::types::CommsPublicKey;
+use crate::{noise::config::RekeyConfig, types::CommsPublicKey};
 use futures::{io::Error, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tari_crypto::tari_utilities::ByteArray;
 
@@ -48,6 +53,12 @@ const MAX_PAYLOAD_LENGTH: usize = u16::max_value() as usize; // 65535
 // encrypted messages include a tag along with the payload.
 const MAX_WRITE_BUFFER_LENGTH: usize = u16::max_value() as usize - 16; // 65519
 
+/// A zero frame length, which is sent before a control frame. Data frames always include the 16 byte tag, so their
+/// length is never zero.
+const CONTROL_FRAME_MARKER: [u8; 2] = [0, 0];
+/// The payload of the control frame that announces that the sender has rotated its outgoing session key
+const REKEY_CONTROL_PAYLOAD: &[u8] = &[1];
+
 /// Collection of buffers used for buffering data during the various read/write states of a
 /// NoiseSocket
 struct NoiseBuffers {
@@ -86,10 +97,18 @@ impl ::std::fmt::Debug for NoiseBuffers {
 enum ReadState {
     /// Initial State
     Init,
-    /// Read frame length
-    ReadFrameLen { buf: [u8; 2], offset: usize },
+    /// Read frame length. `is_control` is true once a control frame marker has been read.
+    ReadFrameLen {
+        buf: [u8; 2],
+        offset: usize,
+        is_control: bool,
+    },
     /// Read encrypted frame
-    ReadFrame { frame_len: u16, offset: usize },
+    ReadFrame {
+        frame_len: u16,
+        offset: usize,
+        is_control: bool,
+    },
     /// Copy decrypted frame to provided buffer
     CopyDecryptedFrame { decrypted_len: usize, offset: usize },
     /// End of file reached, result indicated if EOF was expected or not
@@ -105,6 +124,8 @@ enum WriteState {
     Init,
     /// Buffer provided data
     BufferData { offset: usize },
+    /// Write the marker of a control frame to the wire, followed by the control frame
+    WriteControlFrameMarker { frame_len: u16, offset: usize },
     /// Write frame length to the wire
     WriteFrameLen {
         frame_len: u16,
@@ -121,11 +142,71 @@ enum WriteState {
     EncryptionError(snow::Error),
 }
 
+/// Tracks when the outgoing session key should next be rotated
+#[derive(Debug)]
+struct RekeyState {
+    config: RekeyConfig,
+    bytes_since_rekey: u64,
+    last_rekey: Instant,
+    peer_support: RekeySupport,
+}
+
+impl RekeyState {
+    fn new(config: RekeyConfig) -> Self {
+        Self {
+            config,
+            bytes_since_rekey: 0,
+            last_rekey: Instant::now(),
+            peer_support: RekeySupport::default(),
+        }
+    }
+
+    fn is_due(&self) -> bool {
+        if !self.peer_support.is_supported() {
+            return false;
+        }
+        let bytes_exceeded = self.config.max_bytes.map(|max| self.bytes_since_rekey >= max);
+        let interval_elapsed = self
+            .config
+            .interval
+            .map(|interval| self.last_rekey.elapsed() >= interval);
+        bytes_exceeded.unwrap_or(false) || interval_elapsed.unwrap_or(false)
+    }
+
+    fn reset(&mut self) {
+        self.bytes_since_rekey = 0;
+        self.last_rekey = Instant::now();
+    }
+}
+
+/// Records whether the remote supports session key rotation, which is only known once the peer has sent its
+/// identity. The outgoing session key is never rotated before the remote is known to support it, because a remote
+/// running an earlier version could not decrypt the connection after the rotation.
+#[derive(Debug, Clone, Default)]
+pub struct RekeySupport(Arc<AtomicBool>);
+
+impl RekeySupport {
+    /// Record that the remote supports session key rotation
+    pub fn set_supported_by_peer(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_supported(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
 /// A Noise session with a remote
 ///
 /// Encrypts data to be written to and decrypts data that is read from the underlying socket using
 /// the noise protocol. This is done by wrapping noise payloads in u16 (big endian) length prefix
 /// frames.
+///
+/// Once in transport mode and once the remote is known to support it, the outgoing session key may be rotated
+/// according to the [RekeyConfig]. Before rotating its key, the sender sends a control frame, a zero frame length
+/// followed by an encrypted rekey payload. The receiver rotates its incoming key once it has decrypted this frame.
+/// Data frames never have a zero frame length, so control frames cannot be mistaken for data and rekeying is
+/// transparent to the reader and writer of the socket.
 #[derive(Debug)]
 pub struct NoiseSocket<TSocket> {
     socket: TSocket,
@@ -133,6 +214,7 @@ pub struct NoiseSocket<TSocket> {
     buffers: Box<NoiseBuffers>,
     read_state: ReadState,
     write_state: WriteState,
+    rekey_state: RekeyState,
 }
 
 impl<TSocket> NoiseSocket<TSocket> {
@@ -143,9 +225,21 @@ impl<TSocket> NoiseSocket<TSocket> {
             buffers: Box::new(NoiseBuffers::new()),
             read_state: ReadState::Init,
             write_state: WriteState::Init,
+            rekey_state: RekeyState::new(Default::default()),
         }
     }
 
+    pub(super) fn with_rekey_config(mut self, config: RekeyConfig) -> Self {
+        self.rekey_state = RekeyState::new(config);
+        self
+    }
+
+    /// The handle with which the rotation of the outgoing session key is enabled once the remote is known to
+    /// support it
+    pub fn rekey_support(&self) -> RekeySupport {
+        self.rekey_state.peer_support.clone()
+    }
+
     /// Get the raw remote static key
     pub fn get_remote_static(&self) -> Option<&[u8]> {
         self.state.get_remote_static()
@@ -250,11 +344,16 @@ where TSocket: AsyncRead + Unpin
             trace!(target: LOG_TARGET, "NoiseSocket ReadState::{:?}", self.read_state);
             match self.read_state {
                 ReadState::Init => {
-                    self.read_state = ReadState::ReadFrameLen { buf: [0, 0], offset: 0 };
+                    self.read_state = ReadState::ReadFrameLen {
+                        buf: [0, 0],
+                        offset: 0,
+                        is_control: false,
+                    };
                 },
                 ReadState::ReadFrameLen {
                     ref mut buf,
                     ref mut offset,
+                    is_control,
                 } => {
                     match ready!(poll_read_u16frame_len(
                         &mut context,
@@ -262,13 +361,23 @@ where TSocket: AsyncRead + Unpin
                         buf,
                         offset
                     )) {
+                        Ok(Some(0)) if is_control => {
+                            return Poll::Ready(Err(io::Error::new(io::ErrorKind::InvalidData, "Empty control frame")));
+                        },
+                        Ok(Some(0)) => {
+                            // A control frame follows
+                            self.read_state = ReadState::ReadFrameLen {
+                                buf: [0, 0],
+                                offset: 0,
+                                is_control: true,
+                            };
+                        },
                         Ok(Some(frame_len)) => {
-                            // Empty Frame
-                            if frame_len == 0 {
-                                self.read_state = ReadState::Init;
-                            } else {
-                                self.read_state = ReadState::ReadFrame { frame_len, offset: 0 };
-                            }
+                            self.read_state = ReadState::ReadFrame {
+                                frame_len,
+                                offset: 0,
+                                is_control,
+                            };
                         },
                         Ok(None) => {
                             self.read_state = ReadState::Eof(Ok(()));
@@ -284,6 +393,7 @@ where TSocket: AsyncRead + Unpin
                 ReadState::ReadFrame {
                     frame_len,
                     ref mut offset,
+                    is_control,
                 } => {
                     match ready!(poll_read_exact(
                         &mut context,
@@ -296,6 +406,21 @@ where TSocket: AsyncRead + Unpin
                                 &self.buffers.read_encrypted[..(frame_len as usize)],
                                 &mut self.buffers.read_decrypted,
                             ) {
+                                Ok(decrypted_len) if is_control => {
+                                    if &self.buffers.read_decrypted[..decrypted_len] != REKEY_CONTROL_PAYLOAD {
+                                        return Poll::Ready(Err(io::Error::new(
+                                            io::ErrorKind::InvalidData,
+                                            "Unknown control frame",
+                                        )));
+                                    }
+                                    debug!(target: LOG_TARGET, "Remote rotated session key. Rekeying incoming.");
+                                    self.state.rekey_incoming();
+                                    self.read_state = ReadState::Init;
+                                },
+                                Ok(0) if self.state.is_transport() => {
+                                    // An empty data frame carries no data
+                                    self.read_state = ReadState::Init;
+                                },
                                 Ok(decrypted_len) => {
                                     self.read_state = ReadState::CopyDecryptedFrame {
                                         decrypted_len,
@@ -374,11 +499,34 @@ where TSocket: AsyncWrite + Unpin
             );
             match self.write_state {
                 WriteState::Init => {
-                    if buf.is_some() {
-                        self.write_state = WriteState::BufferData { offset: 0 };
-                    } else {
+                    if buf.is_none() {
                         return Poll::Ready(Ok(None));
                     }
+
+                    if self.state.is_transport() && self.rekey_state.is_due() {
+                        // Send a rekey control frame encrypted with the current key, and then rotate the outgoing
+                        // key. All subsequent frames are encrypted with the new key.
+                        match self
+                            .state
+                            .write_message(REKEY_CONTROL_PAYLOAD, &mut self.buffers.write_encrypted)
+                        {
+                            Ok(encrypted_len) => {
+                                debug!(target: LOG_TARGET, "Rotating outgoing session key");
+                                self.state.rekey_outgoing();
+                                self.rekey_state.reset();
+                                let frame_len = encrypted_len.try_into().expect("offset should be able to fit in u16");
+                                self.write_state = WriteState::WriteControlFrameMarker { frame_len, offset: 0 };
+                            },
+                            Err(e) => {
+                                warn!(target: LOG_TARGET, "Encryption Error: {}", e);
+                                let err = io::Error::new(io::ErrorKind::InvalidData, format!("EncryptionError: {}", e));
+                                self.write_state = WriteState::EncryptionError(e);
+                                return Poll::Ready(Err(err));
+                            },
+                        }
+                    } else {
+                        self.write_state = WriteState::BufferData { offset: 0 };
+                    }
                 },
                 WriteState::BufferData { ref mut offset } => {
                     let bytes_buffered = if let Some(buf) = buf {
@@ -397,7 +545,14 @@ where TSocket: AsyncWrite + Unpin
                         None
                     };
 
+                    if buf.is_none() && *offset == 0 && self.state.is_transport() {
+                        // Nothing to send, so no empty frame is sent
+                        self.write_state = WriteState::Flush;
+                        continue;
+                    }
+
                     if buf.is_none() || *offset == MAX_WRITE_BUFFER_LENGTH {
+                        self.rekey_state.bytes_since_rekey += *offset as u64;
                         match self.state.write_message(
                             &self.buffers.write_decrypted[..*offset],
                             &mut self.buffers.write_encrypted,
@@ -423,6 +578,29 @@ where TSocket: AsyncWrite + Unpin
                         return Poll::Ready(Ok(Some(bytes_buffered)));
                     }
                 },
+                WriteState::WriteControlFrameMarker {
+                    frame_len,
+                    ref mut offset,
+                } => match ready!(poll_write_all(
+                    &mut context,
+                    Pin::new(&mut self.socket),
+                    &CONTROL_FRAME_MARKER,
+                    offset
+                )) {
+                    Ok(()) => {
+                        self.write_state = WriteState::WriteFrameLen {
+                            frame_len,
+                            buf: u16::to_be_bytes(frame_len),
+                            offset: 0,
+                        };
+                    },
+                    Err(e) => {
+                        if e.kind() == io::ErrorKind::WriteZero {
+                            self.write_state = WriteState::Eof;
+                        }
+                        return Poll::Ready(Err(e));
+                    },
+                },
                 WriteState::WriteFrameLen {
                     frame_len,
                     ref buf,
@@ -618,6 +796,24 @@ impl NoiseState {
 
     proxy_state_method!(pub fn get_remote_static(&self) -> Option<&[u8]>);
 
+    pub fn is_transport(&self) -> bool {
+        matches!(self, NoiseState::TransportState(_))
+    }
+
+    /// Rotate the outgoing session key. This has no effect if the handshake has not completed.
+    pub fn rekey_outgoing(&mut self) {
+        if let NoiseState::TransportState(state) = self {
+            state.rekey_outgoing();
+        }
+    }
+
+    /// Rotate the incoming session key. This has no effect if the handshake has not completed.
+    pub fn rekey_incoming(&mut self) {
+        if let NoiseState::TransportState(state) = self {
+            state.rekey_incoming();
+        }
+    }
+
     pub fn into_transport_mode(self) -> Result<Self, snow::Error> {
         match self {
             NoiseState::HandshakeState(state) => Ok(NoiseState::TransportState(Box::new(state.into_transport_mode()?))),
@@ -644,7 +840,7 @@ mod test {
     use crate::{memsocket::MemorySocket, noise::config::NOISE_IX_PARAMETER, runtime};
     use futures::future::join;
     use snow::{params::NoiseParams, Builder, Error, Keypair};
-    use std::io;
+    use std::{io, time::Duration};
     use tokio::runtime::Runtime;
 
     async fn build_test_connection(
@@ -747,6 +943,139 @@ mod test {
         Ok(())
     }
 
+    #[runtime::test]
+    async fn rekey_after_max_bytes() -> io::Result<()> {
+        let ((_dialer_keypair, dialer), (_listener_keypair, listener)) = build_test_connection().await.unwrap();
+
+        let (a, b) = perform_handshake(dialer, listener).await?;
+        let rekey_config = RekeyConfig {
+            interval: None,
+            max_bytes: Some(10),
+        };
+        let mut a = a.with_rekey_config(rekey_config);
+        let mut b = b.with_rekey_config(rekey_config);
+        a.rekey_support().set_supported_by_peer();
+        b.rekey_support().set_supported_by_peer();
+
+        a.write_all(b"The Name of the Wind").await?;
+        a.flush().await?;
+        assert_eq!(a.rekey_state.bytes_since_rekey, 20);
+        a.write_all(b"The Wise Man's Fear").await?;
+        a.flush().await?;
+        // The rekey happened before the second frame was sent
+        assert_eq!(a.rekey_state.bytes_since_rekey, 19);
+
+        b.write_all(b"The Doors of Stone").await?;
+        b.flush().await?;
+        b.write_all(b"The Slow Regard of Silent Things").await?;
+        b.flush().await?;
+
+        let mut buf = [0; 20];
+        b.read_exact(&mut buf).await?;
+        assert_eq!(&buf, b"The Name of the Wind");
+        let mut buf = [0; 19];
+        b.read_exact(&mut buf).await?;
+        assert_eq!(&buf, b"The Wise Man's Fear");
+
+        let mut buf = [0; 18];
+        a.read_exact(&mut buf).await?;
+        assert_eq!(&buf, b"The Doors of Stone");
+        let mut buf = [0; 32];
+        a.read_exact(&mut buf).await?;
+        assert_eq!(&buf, b"The Slow Regard of Silent Things");
+
+        Ok(())
+    }
+
+    #[runtime::test]
+    async fn rekey_after_interval() -> io::Result<()> {
+        let ((_dialer_keypair, dialer), (_listener_keypair, listener)) = build_test_connection().await.unwrap();
+
+        let (a, mut b) = perform_handshake(dialer, listener).await?;
+        // Rekey before every frame
+        let mut a = a.with_rekey_config(RekeyConfig {
+            interval: Some(Duration::from_secs(0)),
+            max_bytes: None,
+        });
+        a.rekey_support().set_supported_by_peer();
+
+        for _ in 0..5 {
+            a.write_all(b"stormlight").await?;
+            a.flush().await?;
+        }
+        a.close().await?;
+
+        let mut buf = Vec::new();
+        b.read_to_end(&mut buf).await?;
+        assert_eq!(buf, b"stormlight".repeat(5));
+
+        Ok(())
+    }
+
+    #[runtime::test]
+    async fn no_rekey_without_peer_support() -> io::Result<()> {
+        let ((_dialer_keypair, dialer), (_listener_keypair, listener)) = build_test_connection().await.unwrap();
+
+        let (a, mut b) = perform_handshake(dialer, listener).await?;
+        let mut a = a.with_rekey_config(RekeyConfig {
+            interval: Some(Duration::from_secs(0)),
+            max_bytes: Some(10),
+        });
+
+        a.write_all(b"The Name of the Wind").await?;
+        a.flush().await?;
+        a.write_all(b"The Wise Man's Fear").await?;
+        a.flush().await?;
+        assert_eq!(a.rekey_state.bytes_since_rekey, 39);
+
+        let mut buf = [0; 39];
+        b.read_exact(&mut buf).await?;
+        assert_eq!(&buf[..], &b"The Name of the WindThe Wise Man's Fear"[..]);
+
+        Ok(())
+    }
+
+    #[runtime::test]
+    async fn empty_data_frame_is_not_a_rekey() -> io::Result<()> {
+        let ((_dialer_keypair, dialer), (_listener_keypair, listener)) = build_test_connection().await.unwrap();
+
+        let (mut a, mut b) = perform_handshake(dialer, listener).await?;
+
+        // Send an empty data frame, as a peer running an earlier version may do
+        let mut frame = [0u8; 16];
+        let frame_len = a.state.write_message(&[], &mut frame).unwrap();
+        a.socket.write_all(&u16::to_be_bytes(frame_len as u16)).await?;
+        a.socket.write_all(&frame[..frame_len]).await?;
+
+        a.write_all(b"stormlight").await?;
+        a.flush().await?;
+
+        let mut buf = [0; 10];
+        b.read_exact(&mut buf).await?;
+        assert_eq!(&buf, b"stormlight");
+
+        Ok(())
+    }
+
+    #[runtime::test]
+    async fn unknown_control_frame() -> io::Result<()> {
+        let ((_dialer_keypair, dialer), (_listener_keypair, listener)) = build_test_connection().await.unwrap();
+
+        let (mut a, mut b) = perform_handshake(dialer, listener).await?;
+
+        let mut frame = [0u8; 17];
+        let frame_len = a.state.write_message(&[2], &mut frame).unwrap();
+        a.socket.write_all(&CONTROL_FRAME_MARKER).await?;
+        a.socket.write_all(&u16::to_be_bytes(frame_len as u16)).await?;
+        a.socket.write_all(&frame[..frame_len]).await?;
+
+        let mut buf = [0; 1];
+        let err = b.read_exact(&mut buf).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        Ok(())
+    }
+
     #[test]
     fn u16_max_writes() -> io::Result<()> {
         // Current thread runtime stack overflows, so the full tokio runtime is used here
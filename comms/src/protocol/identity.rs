@@ -23,15 +23,19 @@ use crate::{
     compat::IoCompat,
     connection_manager::ConnectionDirection,
     message::MessageExt,
+    multiaddr::Multiaddr,
     peer_manager::NodeIdentity,
     proto::identity::PeerIdentityMsg,
     protocol::{ProtocolError, ProtocolId, ProtocolNegotiation},
+    types::CommsPublicKey,
+    utils::signature,
 };
 use futures::{AsyncRead, AsyncWrite, SinkExt, StreamExt};
 use log::*;
 use prost::Message;
+use rand::rngs::OsRng;
 use std::{io, time::Duration};
-use tari_crypto::tari_utilities::ByteArray;
+use tari_crypto::tari_utilities::{message_format::MessageFormat, ByteArray};
 use thiserror::Error;
 use tokio::time;
 use tokio_util::codec::{Framed, LengthDelimitedCodec};
@@ -44,6 +48,8 @@ pub async fn identity_exchange<'p, TSocket, P>(
     direction: ConnectionDirection,
     our_supported_protocols: P,
     user_agent: String,
+    advertise_public_address: bool,
+    observed_address: Option<&Multiaddr>,
     mut socket: TSocket,
 ) -> Result<PeerIdentityMsg, IdentityProtocolError>
 where
@@ -83,15 +89,25 @@ where
 
     let supported_protocols = our_supported_protocols.into_iter().map(|p| p.to_vec()).collect();
 
+    // An outbound-only node is not reachable, so it does not advertise an address
+    let addresses = if advertise_public_address {
+        vec![node_identity.public_address().to_string()]
+    } else {
+        Vec::new()
+    };
+
     // Send this node's identity
-    let msg_bytes = PeerIdentityMsg {
+    let mut msg = PeerIdentityMsg {
         node_id: node_identity.node_id().to_vec(),
-        addresses: vec![node_identity.public_address().to_string()],
+        addresses,
         features: node_identity.features().bits(),
         supported_protocols,
         user_agent,
-    }
-    .to_encoded_bytes();
+        observed_address: observed_address.map(ToString::to_string).unwrap_or_default(),
+        identity_signature: Vec::new(),
+    };
+    msg.identity_signature = sign_peer_identity(node_identity, &msg)?;
+    let msg_bytes = msg.to_encoded_bytes();
 
     sink.send(msg_bytes.into()).await?;
     sink.close().await?;
@@ -105,6 +121,48 @@ where
     Ok(identity_msg)
 }
 
+/// Returns the bytes that the identity signature commits to i.e. every field of the identity except the signature
+fn identity_signature_body(msg: &PeerIdentityMsg) -> Vec<u8> {
+    // Length-prefix each variable length field so that the fields cannot be ambiguously reinterpreted
+    fn append_field(body: &mut Vec<u8>, field: &[u8]) {
+        body.extend_from_slice(&(field.len() as u64).to_le_bytes());
+        body.extend_from_slice(field);
+    }
+
+    let mut body = Vec::new();
+    append_field(&mut body, &msg.node_id);
+    body.extend_from_slice(&msg.features.to_le_bytes());
+    body.extend_from_slice(&(msg.addresses.len() as u64).to_le_bytes());
+    for addr in &msg.addresses {
+        append_field(&mut body, addr.as_bytes());
+    }
+    body.extend_from_slice(&(msg.supported_protocols.len() as u64).to_le_bytes());
+    for protocol in &msg.supported_protocols {
+        append_field(&mut body, protocol);
+    }
+    append_field(&mut body, msg.user_agent.as_bytes());
+    append_field(&mut body, msg.observed_address.as_bytes());
+    body
+}
+
+fn sign_peer_identity(node_identity: &NodeIdentity, msg: &PeerIdentityMsg) -> Result<Vec<u8>, IdentityProtocolError> {
+    let signature = signature::sign(
+        &mut OsRng,
+        node_identity.secret_key().clone(),
+        identity_signature_body(msg),
+    )
+    .map_err(|err| IdentityProtocolError::SigningFailed(err.to_string()))?;
+    signature
+        .to_binary()
+        .map_err(|err| IdentityProtocolError::SigningFailed(err.to_string()))
+}
+
+/// Verify that the identity signature in the given `PeerIdentityMsg` was created by the given public key over the
+/// other fields of the message.
+pub fn verify_peer_identity_signature(public_key: &CommsPublicKey, msg: &PeerIdentityMsg) -> bool {
+    signature::verify(public_key, &msg.identity_signature, identity_signature_body(msg))
+}
+
 #[derive(Debug, Error, Clone)]
 pub enum IdentityProtocolError {
     #[error("IoError: {0}")]
@@ -119,6 +177,8 @@ pub enum IdentityProtocolError {
     PeerUnexpectedCloseConnection,
     #[error("Timeout waiting for peer to send identity information")]
     Timeout,
+    #[error("Failed to sign peer identity: {0}")]
+    SigningFailed(String),
 }
 
 impl From<time::Elapsed> for IdentityProtocolError {
@@ -177,6 +237,8 @@ mod test {
                 ConnectionDirection::Inbound,
                 &[],
                 Default::default(),
+                true,
+                Some(&"/memory/1234".parse().unwrap()),
                 in_sock,
             ),
             super::identity_exchange(
@@ -184,6 +246,8 @@ mod test {
                 ConnectionDirection::Outbound,
                 &[],
                 Default::default(),
+                true,
+                None,
                 out_sock,
             ),
         )
@@ -196,9 +260,105 @@ mod test {
         assert_eq!(identity1.node_id, node_identity1.node_id().to_vec());
         assert_eq!(identity1.features, node_identity1.features().bits());
         assert_eq!(identity1.addresses, vec![node_identity1.public_address().to_string()]);
+        assert_eq!(identity1.observed_address, "/memory/1234");
 
         assert_eq!(identity2.node_id, node_identity2.node_id().to_vec());
         assert_eq!(identity2.features, node_identity2.features().bits());
         assert_eq!(identity2.addresses, vec![node_identity2.public_address().to_string()]);
+        assert!(identity2.observed_address.is_empty());
+
+        // Each identity is signed by the sending node
+        assert!(super::verify_peer_identity_signature(
+            node_identity1.public_key(),
+            &identity1
+        ));
+        assert!(super::verify_peer_identity_signature(
+            node_identity2.public_key(),
+            &identity2
+        ));
+        assert!(!super::verify_peer_identity_signature(
+            node_identity2.public_key(),
+            &identity1
+        ));
+
+        // Tampering with the advertised addresses or features invalidates the signature
+        let mut spoofed = identity1.clone();
+        spoofed.addresses = vec!["/ip4/1.2.3.4/tcp/18189".to_string()];
+        assert!(!super::verify_peer_identity_signature(
+            node_identity1.public_key(),
+            &spoofed
+        ));
+        let mut spoofed = identity1.clone();
+        spoofed.features = PeerFeatures::COMMUNICATION_CLIENT.bits();
+        assert!(!super::verify_peer_identity_signature(
+            node_identity1.public_key(),
+            &spoofed
+        ));
+        let mut spoofed = identity1.clone();
+        spoofed.supported_protocols = vec![b"/tari/spoofed/1.0.0".to_vec()];
+        assert!(!super::verify_peer_identity_signature(
+            node_identity1.public_key(),
+            &spoofed
+        ));
+        let mut spoofed = identity1.clone();
+        spoofed.user_agent = "spoofed".to_string();
+        assert!(!super::verify_peer_identity_signature(
+            node_identity1.public_key(),
+            &spoofed
+        ));
+        let mut spoofed = identity1;
+        spoofed.observed_address = "/ip4/1.2.3.4/tcp/18189".to_string();
+        assert!(!super::verify_peer_identity_signature(
+            node_identity1.public_key(),
+            &spoofed
+        ));
+    }
+
+    #[runtime::test_basic]
+    async fn identity_exchange_without_public_address() {
+        let transport = MemoryTransport;
+        let addr = "/memory/0".parse().unwrap();
+        let (mut listener, addr) = transport.listen(addr).unwrap().await.unwrap();
+
+        let (out_sock, in_sock) = future::join(transport.dial(addr).unwrap(), listener.next()).await;
+
+        let out_sock = out_sock.unwrap();
+        let in_sock = in_sock.unwrap().map(|(f, _)| f).unwrap().await.unwrap();
+
+        let node_identity1 = build_node_identity(PeerFeatures::COMMUNICATION_NODE);
+        let node_identity2 = build_node_identity(PeerFeatures::COMMUNICATION_CLIENT);
+
+        let (result1, result2) = future::join(
+            super::identity_exchange(
+                &node_identity1,
+                ConnectionDirection::Inbound,
+                &[],
+                Default::default(),
+                true,
+                None,
+                in_sock,
+            ),
+            super::identity_exchange(
+                &node_identity2,
+                ConnectionDirection::Outbound,
+                &[],
+                Default::default(),
+                false,
+                None,
+                out_sock,
+            ),
+        )
+        .await;
+
+        let identity2 = result1.unwrap();
+        let identity1 = result2.unwrap();
+
+        assert_eq!(identity1.addresses, vec![node_identity1.public_address().to_string()]);
+        // Node 2 is outbound-only, so it does not advertise its address
+        assert!(identity2.addresses.is_empty());
+        assert!(super::verify_peer_identity_signature(
+            node_identity2.public_key(),
+            &identity2
+        ));
     }
 }
@@ -30,12 +30,17 @@ pub struct MessagingConfig {
     /// Inbound/outbound substreams are closed independently, and they may be reopened in the future once closed.
     /// (default: 8 mins)
     pub inactivity_timeout: Option<Duration>,
+    /// The maximum size of a single message frame in bytes. Inbound messages that exceed this size cause the inbound
+    /// substream to be closed, and outbound messages that exceed it fail to send.
+    /// (default: 8 MiB)
+    pub max_frame_length: usize,
 }
 
 impl Default for MessagingConfig {
     fn default() -> Self {
         Self {
             inactivity_timeout: Some(Duration::from_secs(8 * 60)),
+            max_frame_length: 8 * 1_024 * 1_024,
         }
     }
 }
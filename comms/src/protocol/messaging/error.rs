@@ -49,4 +49,6 @@ pub enum MessagingProtocolError {
     SenderError(#[from] mpsc::SendError),
     #[error("Stream closed due to inactivity")]
     Inactivity,
+    #[error("Message of {size} bytes exceeds the maximum frame length of {max_frame_length} bytes")]
+    MessageTooLarge { size: usize, max_frame_length: usize },
 }
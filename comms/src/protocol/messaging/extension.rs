@@ -20,7 +20,7 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use super::MessagingProtocol;
+use super::{MessagingConfig, MessagingProtocol};
 use crate::{
     bounded_executor::BoundedExecutor,
     message::InboundMessage,
@@ -41,11 +41,22 @@ use tower::Service;
 pub struct MessagingProtocolExtension<TInPipe, TOutPipe, TOutReq> {
     event_tx: MessagingEventSender,
     pipeline: pipeline::Config<TInPipe, TOutPipe, TOutReq>,
+    config: MessagingConfig,
 }
 
 impl<TInPipe, TOutPipe, TOutReq> MessagingProtocolExtension<TInPipe, TOutPipe, TOutReq> {
     pub fn new(event_tx: MessagingEventSender, pipeline: pipeline::Config<TInPipe, TOutPipe, TOutReq>) -> Self {
-        Self { event_tx, pipeline }
+        Self {
+            event_tx,
+            pipeline,
+            config: Default::default(),
+        }
+    }
+
+    /// Sets the configuration used by the messaging protocol. If not set, `MessagingConfig::default()` is used.
+    pub fn with_messaging_config(mut self, config: MessagingConfig) -> Self {
+        self.config = config;
+        self
     }
 }
 
@@ -67,7 +78,7 @@ where
         let (inbound_message_tx, inbound_message_rx) = mpsc::channel(consts::INBOUND_MESSAGE_BUFFER_SIZE);
 
         let messaging = MessagingProtocol::new(
-            Default::default(),
+            self.config,
             context.connectivity(),
             proto_rx,
             messaging_request_rx,
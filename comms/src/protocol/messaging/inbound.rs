@@ -22,6 +22,7 @@
 
 use crate::{
     common::rate_limit::RateLimit,
+    framing,
     message::InboundMessage,
     peer_manager::NodeId,
     protocol::messaging::{MessagingEvent, MessagingProtocol},
@@ -40,6 +41,7 @@ pub struct InboundMessaging {
     rate_limit_capacity: usize,
     rate_limit_restock_interval: Duration,
     inactivity_timeout: Option<Duration>,
+    max_frame_length: usize,
 }
 
 impl InboundMessaging {
@@ -50,6 +52,7 @@ impl InboundMessaging {
         rate_limit_capacity: usize,
         rate_limit_restock_interval: Duration,
         inactivity_timeout: Option<Duration>,
+        max_frame_length: usize,
     ) -> Self
     {
         Self {
@@ -59,6 +62,7 @@ impl InboundMessaging {
             rate_limit_capacity,
             rate_limit_restock_interval,
             inactivity_timeout,
+            max_frame_length,
         }
     }
 
@@ -71,7 +75,7 @@ impl InboundMessaging {
             peer.short_str()
         );
 
-        let (mut sink, stream) = MessagingProtocol::framed(socket).split();
+        let (mut sink, stream) = MessagingProtocol::framed(socket, self.max_frame_length).split();
 
         if let Err(err) = sink.close().await {
             debug!(
@@ -117,6 +121,16 @@ impl InboundMessaging {
 
                     let _ = self.messaging_events_tx.send(Arc::new(event));
                 },
+                Ok(Err(err)) if framing::is_max_frame_length_exceeded(&err) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Peer '{}' sent a message that exceeds the maximum frame length of {} bytes. Closing inbound \
+                         messaging substream.",
+                        peer.short_str(),
+                        self.max_frame_length
+                    );
+                    break;
+                },
                 Ok(Err(err)) => {
                     error!(
                         target: LOG_TARGET,
@@ -49,6 +49,7 @@ pub struct OutboundMessaging {
     messaging_events_tx: mpsc::Sender<MessagingEvent>,
     peer_node_id: NodeId,
     inactivity_timeout: Option<Duration>,
+    max_frame_length: usize,
 }
 
 impl OutboundMessaging {
@@ -58,6 +59,7 @@ impl OutboundMessaging {
         request_rx: mpsc::UnboundedReceiver<OutboundMessage>,
         peer_node_id: NodeId,
         inactivity_timeout: Option<Duration>,
+        max_frame_length: usize,
     ) -> Self
     {
         Self {
@@ -66,6 +68,7 @@ impl OutboundMessaging {
             messaging_events_tx,
             peer_node_id,
             inactivity_timeout,
+            max_frame_length,
         }
     }
 
@@ -214,7 +217,7 @@ impl OutboundMessaging {
         );
         let substream = substream.stream;
 
-        let (sink, _) = MessagingProtocol::framed(substream).split();
+        let (sink, _) = MessagingProtocol::framed(substream, self.max_frame_length).split();
 
         let Self {
             request_rx,
@@ -56,7 +56,6 @@ const INTERNAL_MESSAGING_EVENT_CHANNEL_SIZE: usize = 150;
 /// The maximum amount of inbound messages to accept within the `RATE_LIMIT_RESTOCK_INTERVAL` window
 const RATE_LIMIT_CAPACITY: usize = 10;
 const RATE_LIMIT_RESTOCK_INTERVAL: Duration = Duration::from_millis(100);
-const MAX_FRAME_LENGTH: usize = 8 * 1_024 * 1_024;
 
 pub type MessagingEventSender = broadcast::Sender<Arc<MessagingEvent>>;
 pub type MessagingEventReceiver = broadcast::Receiver<Arc<MessagingEvent>>;
@@ -81,6 +80,8 @@ pub enum SendFailReason {
     Dropped,
     #[error("Message could not send after {0} attempt(s)")]
     MaxRetriesReached(usize),
+    #[error("Message exceeds the maximum frame length")]
+    MessageTooLarge,
 }
 
 #[derive(Debug)]
@@ -189,9 +190,14 @@ impl MessagingProtocol {
     }
 
     #[inline]
-    pub fn framed<TSubstream>(socket: TSubstream) -> Framed<IoCompat<TSubstream>, LengthDelimitedCodec>
-    where TSubstream: AsyncRead + AsyncWrite + Unpin {
-        framing::canonical(socket, MAX_FRAME_LENGTH)
+    pub fn framed<TSubstream>(
+        socket: TSubstream,
+        max_frame_length: usize,
+    ) -> Framed<IoCompat<TSubstream>, LengthDelimitedCodec>
+    where
+        TSubstream: AsyncRead + AsyncWrite + Unpin,
+    {
+        framing::canonical(socket, max_frame_length)
     }
 
     fn handle_connectivity_event(&mut self, event: &ConnectivityEvent) {
@@ -248,7 +254,22 @@ impl MessagingProtocol {
         Ok(())
     }
 
-    async fn send_message(&mut self, out_msg: OutboundMessage) -> Result<(), MessagingProtocolError> {
+    async fn send_message(&mut self, mut out_msg: OutboundMessage) -> Result<(), MessagingProtocolError> {
+        if out_msg.body.len() > self.config.max_frame_length {
+            let size = out_msg.body.len();
+            out_msg.reply_fail(SendFailReason::MessageTooLarge);
+            let _ = self
+                .messaging_events_tx
+                .send(Arc::new(MessagingEvent::SendMessageFailed(
+                    out_msg,
+                    SendFailReason::MessageTooLarge,
+                )));
+            return Err(MessagingProtocolError::MessageTooLarge {
+                size,
+                max_frame_length: self.config.max_frame_length,
+            });
+        }
+
         let peer_node_id = out_msg.peer_node_id.clone();
         let sender = loop {
             match self.active_queues.entry(peer_node_id.clone()) {
@@ -265,6 +286,7 @@ impl MessagingProtocol {
                         self.internal_messaging_event_tx.clone(),
                         peer_node_id.clone(),
                         self.config.inactivity_timeout,
+                        self.config.max_frame_length,
                     );
                     break entry.insert(sender);
                 },
@@ -293,11 +315,18 @@ impl MessagingProtocol {
         events_tx: mpsc::Sender<MessagingEvent>,
         peer_node_id: NodeId,
         inactivity_timeout: Option<Duration>,
+        max_frame_length: usize,
     ) -> mpsc::UnboundedSender<OutboundMessage>
     {
         let (msg_tx, msg_rx) = mpsc::unbounded();
-        let outbound_messaging =
-            OutboundMessaging::new(connectivity, events_tx, msg_rx, peer_node_id, inactivity_timeout);
+        let outbound_messaging = OutboundMessaging::new(
+            connectivity,
+            events_tx,
+            msg_rx,
+            peer_node_id,
+            inactivity_timeout,
+            max_frame_length,
+        );
         task::spawn(outbound_messaging.run());
         msg_tx
     }
@@ -312,6 +341,7 @@ impl MessagingProtocol {
             RATE_LIMIT_CAPACITY,
             RATE_LIMIT_RESTOCK_INTERVAL,
             self.config.inactivity_timeout,
+            self.config.max_frame_length,
         );
         task::spawn(inbound_messaging.run(substream));
     }
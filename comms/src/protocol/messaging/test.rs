@@ -34,7 +34,7 @@ use crate::{
     net_address::MultiaddressesWithStats,
     peer_manager::{NodeId, NodeIdentity, Peer, PeerFeatures, PeerFlags, PeerManager},
     protocol::{
-        messaging::{inbound::InboundMessaging, SendFailReason},
+        messaging::{inbound::InboundMessaging, MessagingConfig, SendFailReason},
         ProtocolEvent,
         ProtocolNotification,
     },
@@ -144,7 +144,7 @@ async fn new_inbound_substream_handling() {
         .unwrap();
 
     let stream_theirs = muxer_theirs.incoming_mut().next().await.unwrap();
-    let mut framed_theirs = MessagingProtocol::framed(stream_theirs);
+    let mut framed_theirs = MessagingProtocol::framed(stream_theirs, MessagingConfig::default().max_frame_length);
 
     framed_theirs.send(TEST_MSG1.clone()).await.unwrap();
 
@@ -184,7 +184,7 @@ async fn send_message_request() {
 
     // Check that node got the message
     let stream = peer_conn_mock2.next_incoming_substream().await.unwrap();
-    let mut framed = MessagingProtocol::framed(stream);
+    let mut framed = MessagingProtocol::framed(stream, MessagingConfig::default().max_frame_length);
     let msg = framed.next().await.unwrap().unwrap();
     assert_eq!(msg, TEST_MSG1);
 
@@ -301,7 +301,7 @@ async fn many_concurrent_send_message_requests() {
 
     // Check that the node got the messages
     let stream = peer_conn_mock2.next_incoming_substream().await.unwrap();
-    let mut framed = MessagingProtocol::framed(stream);
+    let mut framed = MessagingProtocol::framed(stream, MessagingConfig::default().max_frame_length);
     let messages = collect_stream!(framed, take = NUM_MSGS, timeout = Duration::from_secs(10));
     assert_eq!(messages.len(), NUM_MSGS);
 
@@ -374,12 +374,13 @@ async fn inactivity_timeout() {
             10,
             Duration::from_millis(100),
             Some(Duration::from_millis(5)),
+            MessagingConfig::default().max_frame_length,
         )
         .run(socket_in),
     );
 
     // Write messages for 5 milliseconds
-    let mut framed = MessagingProtocol::framed(socket_out);
+    let mut framed = MessagingProtocol::framed(socket_out, MessagingConfig::default().max_frame_length);
     for _ in 0..5u8 {
         framed.send(Bytes::from_static(b"some message")).await.unwrap();
         time::delay_for(Duration::from_millis(1)).await;
@@ -392,3 +393,38 @@ async fn inactivity_timeout() {
 
     let _ = collect_stream!(inbound_msg_rx, take = 5, timeout = Duration::from_secs(10));
 }
+
+#[runtime::test_basic]
+async fn inbound_max_frame_length_exceeded() {
+    let node_identity = build_node_identity(PeerFeatures::COMMUNICATION_CLIENT);
+    let (inbound_msg_tx, mut inbound_msg_rx) = mpsc::channel(5);
+    let (events_tx, _) = broadcast::channel(1);
+
+    let (socket_in, socket_out) = MemorySocket::new_pair();
+
+    let inbound_handle = task::spawn(
+        InboundMessaging::new(
+            node_identity.node_id().clone(),
+            inbound_msg_tx,
+            events_tx,
+            10,
+            Duration::from_millis(100),
+            None,
+            16,
+        )
+        .run(socket_in),
+    );
+
+    let mut framed = MessagingProtocol::framed(socket_out, MessagingConfig::default().max_frame_length);
+    framed.send(Bytes::from_static(b"small")).await.unwrap();
+    framed
+        .send(Bytes::from_static(b"this message exceeds the maximum frame length"))
+        .await
+        .unwrap();
+
+    // The inbound substream is closed once the oversized frame is read
+    inbound_handle.await.unwrap();
+    let msgs = collect_stream!(inbound_msg_rx, timeout = Duration::from_secs(10));
+    assert_eq!(msgs.len(), 1);
+    assert_eq!(msgs[0].body, Bytes::from_static(b"small"));
+}
@@ -212,6 +212,8 @@ impl Buf for BodyBytes {
     }
 }
 
+/// A server-side stream of response messages. Messages are sent to the client as they are produced, subject to
+/// backpressure from the client.
 #[derive(Debug)]
 pub struct Streaming<T> {
     inner: mpsc::Receiver<Result<T, RpcStatus>>,
@@ -252,6 +254,8 @@ impl<T: prost::Message + 'static> IntoBody for Streaming<T> {
     }
 }
 
+/// A client-side stream of response messages. At most `stream_buffer_size` messages are buffered, after which the
+/// client stops reading from the substream until the consumer catches up.
 #[derive(Debug)]
 pub struct ClientStreaming<T> {
     inner: mpsc::Receiver<Result<Response<Bytes>, RpcStatus>>,
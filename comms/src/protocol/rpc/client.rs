@@ -33,6 +33,7 @@ use crate::{
         Response,
         RpcError,
         RpcStatus,
+        RPC_MAX_FRAME_SIZE,
     },
     runtime::task,
 };
@@ -57,12 +58,15 @@ use std::{
 };
 use tokio::time;
 use tower::{Service, ServiceExt};
+use tracing::debug_span;
+use tracing_futures::Instrument;
 
 const LOG_TARGET: &str = "comms::rpc::client";
 
 #[derive(Clone)]
 pub struct RpcClient {
     connector: ClientConnector,
+    max_frame_size: usize,
 }
 
 impl RpcClient {
@@ -76,12 +80,16 @@ impl RpcClient {
     {
         let (request_tx, request_rx) = mpsc::channel(1);
         let connector = ClientConnector { inner: request_tx };
+        let max_frame_size = config.max_frame_size;
         let (ready_tx, ready_rx) = oneshot::channel();
         task::spawn(RpcClientWorker::new(config, request_rx, framed, ready_tx).run());
         ready_rx
             .await
             .expect("ready_rx oneshot is never dropped without a reply")?;
-        Ok(Self { connector })
+        Ok(Self {
+            connector,
+            max_frame_size,
+        })
     }
 
     /// Perform a single request and single response
@@ -91,14 +99,18 @@ impl RpcClient {
         R: prost::Message + Default + std::fmt::Debug,
         M: Into<RpcMethod>,
     {
+        let method = method.into();
         let req_bytes = request.to_encoded_bytes();
-        let request = BaseRequest::new(method.into(), req_bytes.into());
+        let request = BaseRequest::new(method, req_bytes.into());
 
-        let mut resp = self.call_inner(request).await?;
-        let resp = resp.next().await.ok_or_else(|| RpcError::ServerClosedRequest)??;
-        let resp = R::decode(resp.into_message())?;
-
-        Ok(resp)
+        async move {
+            let mut resp = self.call_inner(request).await?;
+            let resp = resp.next().await.ok_or_else(|| RpcError::ServerClosedRequest)??;
+            let resp = R::decode(resp.into_message())?;
+            Result::<_, RpcError>::Ok(resp)
+        }
+        .instrument(debug_span!("rpc::request_response", method = method.id()))
+        .await
     }
 
     /// Perform a single request and streaming response
@@ -108,10 +120,14 @@ impl RpcClient {
         R: prost::Message + Default,
         M: Into<RpcMethod>,
     {
+        let method = method.into();
         let req_bytes = request.to_encoded_bytes();
-        let request = BaseRequest::new(method.into(), req_bytes.into());
+        let request = BaseRequest::new(method, req_bytes.into());
 
-        let resp = self.call_inner(request).await?;
+        let resp = self
+            .call_inner(request)
+            .instrument(debug_span!("rpc::server_streaming", method = method.id()))
+            .await?;
 
         Ok(ClientStreaming::new(resp))
     }
@@ -131,6 +147,13 @@ impl RpcClient {
         request: BaseRequest<Bytes>,
     ) -> Result<mpsc::Receiver<Result<Response<Bytes>, RpcStatus>>, RpcError>
     {
+        // Requests that cannot be sent in a single frame are rejected here rather than failing the session
+        if request.message.len() > self.max_frame_size {
+            return Err(RpcError::RequestTooLarge {
+                size: request.message.len(),
+                max_frame_size: self.max_frame_size,
+            });
+        }
         let svc = self.connector.ready_and().await?;
         let resp = svc.call(request).await?;
         Ok(resp)
@@ -194,6 +217,28 @@ where TClient: From<RpcClient> + NamedProtocolService
         self
     }
 
+    /// Set the maximum number of streamed response messages that are buffered by the client before the consumer
+    /// reads them. Once the buffer is full, the client stops reading from the substream which applies backpressure to
+    /// the server.
+    /// Default: 10
+    pub fn with_stream_buffer_size(mut self, size: usize) -> Self {
+        self.config.stream_buffer_size = size;
+        self
+    }
+
+    /// Set the maximum size of a single frame sent or received by this client. Responses that exceed this size cause
+    /// the session to fail with `RpcError::MaxFrameSizeExceeded`.
+    /// Default: [RPC_MAX_FRAME_SIZE](super::RPC_MAX_FRAME_SIZE)
+    pub fn with_max_frame_size(mut self, size: usize) -> Self {
+        self.config.max_frame_size = size;
+        self
+    }
+
+    /// Returns the maximum frame size that should be used when framing the substream for this client
+    pub fn max_frame_size(&self) -> usize {
+        self.config.max_frame_size
+    }
+
     /// Negotiates and establishes a session to the peer's RPC service
     pub async fn connect<TSubstream>(self, framed: CanonicalFraming<TSubstream>) -> Result<TClient, RpcError>
     where TSubstream: AsyncRead + AsyncWrite + Unpin + Send + 'static {
@@ -206,6 +251,8 @@ pub struct RpcClientConfig {
     pub deadline: Option<Duration>,
     pub deadline_grace_period: Duration,
     pub handshake_timeout: Duration,
+    pub stream_buffer_size: usize,
+    pub max_frame_size: usize,
 }
 
 impl RpcClientConfig {
@@ -226,6 +273,8 @@ impl Default for RpcClientConfig {
             deadline: Some(Duration::from_secs(30)),
             deadline_grace_period: Duration::from_secs(10),
             handshake_timeout: Duration::from_secs(30),
+            stream_buffer_size: 10,
+            max_frame_size: RPC_MAX_FRAME_SIZE,
         }
     }
 }
@@ -379,7 +428,9 @@ where TSubstream: AsyncRead + AsyncWrite + Unpin + Send
         let start = Instant::now();
         self.framed.send(req.to_encoded_bytes().into()).await?;
 
-        let (mut response_tx, response_rx) = mpsc::channel(1);
+        // The response channel is bounded by the stream buffer size. If the consumer is not keeping up, sending to the
+        // channel will wait, and we stop reading from the substream until there is capacity.
+        let (mut response_tx, response_rx) = mpsc::channel(self.config.stream_buffer_size);
         if reply.send(response_rx).is_err() {
             debug!(target: LOG_TARGET, "Client request was cancelled.");
             response_tx.close_channel();
@@ -404,7 +455,19 @@ where TSubstream: AsyncRead + AsyncWrite + Unpin + Send
                         latency
                     );
                     self.latency = Some(latency);
-                    proto::rpc::RpcResponse::decode(resp)?
+                    let resp = proto::rpc::RpcResponse::decode(resp)?;
+                    // Responses for a previous request that was abandoned (timed out or dropped by the consumer) may
+                    // still arrive. These are discarded.
+                    if resp.request_id != u32::from(request_id) {
+                        debug!(
+                            target: LOG_TARGET,
+                            "Ignoring stale response for request {} while waiting for request {}",
+                            resp.request_id,
+                            request_id
+                        );
+                        continue;
+                    }
+                    resp
                 },
                 Ok(Some(Err(err))) => {
                     return Err(err.into());
@@ -430,17 +493,25 @@ where TSubstream: AsyncRead + AsyncWrite + Unpin + Send
 
             match Self::convert_to_result(resp) {
                 Ok(resp) => {
-                    // The consumer may drop the receiver before all responses are received.
-                    // We just ignore that as we still want obey the protocol and receive messages until the FIN flag or
-                    // the connection is dropped
                     let is_finished = resp.is_finished();
-                    if !response_tx.is_closed() {
-                        let _ = response_tx.send(Ok(resp)).await;
-                    }
                     if is_finished {
+                        if !response_tx.is_closed() {
+                            let _ = response_tx.send(Ok(resp)).await;
+                        }
                         response_tx.close_channel();
                         break;
                     }
+                    // The consumer dropped the stream before it completed, we give up on the request. The server
+                    // abandons the request once it receives the next request on this session.
+                    if response_tx.send(Ok(resp)).await.is_err() {
+                        debug!(
+                            target: LOG_TARGET,
+                            "Request {} (method={}) was dropped by the consumer before the stream completed",
+                            request_id,
+                            method
+                        );
+                        break;
+                    }
                 },
                 Err(err) => {
                     debug!(target: LOG_TARGET, "Remote service returned error: {}", err);
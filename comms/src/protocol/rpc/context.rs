@@ -28,7 +28,11 @@ use crate::{
     PeerManager,
 };
 use async_trait::async_trait;
-use std::{fmt, sync::Arc};
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 /// Abstraction of the comms backend calls provided to RPC services.
 #[async_trait]
@@ -79,17 +83,42 @@ impl RpcCommsProvider for RpcCommsBackend {
 pub struct RequestContext {
     backend: Box<dyn RpcCommsProvider>,
     node_id: NodeId,
+    deadline: Option<Instant>,
 }
 
 impl RequestContext {
     pub(super) fn new(node_id: NodeId, backend: Box<dyn RpcCommsProvider>) -> Self {
-        Self { node_id, backend }
+        Self {
+            node_id,
+            backend,
+            deadline: None,
+        }
+    }
+
+    /// Sets the client deadline for this request, relative to now.
+    pub(super) fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(Instant::now() + deadline);
+        self
     }
 
     pub fn peer_node_id(&self) -> &NodeId {
         &self.node_id
     }
 
+    /// Returns the time remaining before the client deadline is reached, or None if the request has no deadline.
+    /// Long-running handlers can use this to abandon work that the client will no longer wait for.
+    pub fn remaining_time(&self) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Returns true if the client deadline for this request has passed
+    pub fn is_deadline_exceeded(&self) -> bool {
+        self.deadline
+            .map(|deadline| Instant::now() >= deadline)
+            .unwrap_or(false)
+    }
+
     pub(crate) async fn fetch_peer(&self) -> Result<Peer, RpcError> {
         self.backend.fetch_peer(&self.node_id).await
     }
@@ -107,6 +136,7 @@ impl fmt::Debug for RequestContext {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RequestContext")
             .field("node_id", &self.node_id)
+            .field("deadline", &self.deadline)
             .field("backend", &"dyn RpcCommsProvider")
             .finish()
     }
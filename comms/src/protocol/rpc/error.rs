@@ -23,6 +23,7 @@
 use super::{handshake::RpcHandshakeError, server::RpcServerError, RpcStatus};
 use crate::{
     connectivity::ConnectivityError,
+    framing,
     peer_manager::PeerManagerError,
     proto::rpc as rpc_proto,
     PeerConnectionError,
@@ -36,7 +37,11 @@ pub enum RpcError {
     #[error("Failed to decode message: {0}")]
     DecodeError(#[from] DecodeError),
     #[error("IO Error: {0}")]
-    Io(#[from] io::Error),
+    Io(io::Error),
+    #[error("A frame exceeded the maximum frame size")]
+    MaxFrameSizeExceeded,
+    #[error("Request of {size} bytes exceeds the maximum frame size of {max_frame_size} bytes")]
+    RequestTooLarge { size: usize, max_frame_size: usize },
     #[error("The client connection is closed")]
     ClientClosed,
     #[error("Request failed: {0}")]
@@ -61,6 +66,16 @@ pub enum RpcError {
     UnknownError(#[from] anyhow::Error),
 }
 
+impl From<io::Error> for RpcError {
+    fn from(err: io::Error) -> Self {
+        if framing::is_max_frame_length_exceeded(&err) {
+            RpcError::MaxFrameSizeExceeded
+        } else {
+            RpcError::Io(err)
+        }
+    }
+}
+
 impl RpcError {
     pub fn client_internal_error<T: ToString>(err: T) -> Self {
         RpcError::ClientInternalError(err.to_string())
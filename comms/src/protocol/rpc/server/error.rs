@@ -20,7 +20,7 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use crate::protocol::rpc::handshake::RpcHandshakeError;
+use crate::{framing, protocol::rpc::handshake::RpcHandshakeError};
 use futures::channel::oneshot;
 use prost::DecodeError;
 use std::io;
@@ -30,7 +30,9 @@ pub enum RpcServerError {
     #[error("Failed to decode message: {0}")]
     DecodeError(#[from] DecodeError),
     #[error("IO Error: {0}")]
-    Io(#[from] io::Error),
+    Io(io::Error),
+    #[error("The peer sent a frame that exceeds the maximum frame size")]
+    MaxFrameSizeExceeded,
     #[error("Maximum number of RPC sessions reached")]
     MaximumSessionsReached,
     #[error("Internal service request canceled")]
@@ -41,6 +43,16 @@ pub enum RpcServerError {
     ProtocolServiceNotFound(String),
 }
 
+impl From<io::Error> for RpcServerError {
+    fn from(err: io::Error) -> Self {
+        if framing::is_max_frame_length_exceeded(&err) {
+            RpcServerError::MaxFrameSizeExceeded
+        } else {
+            RpcServerError::Io(err)
+        }
+    }
+}
+
 impl From<oneshot::Canceled> for RpcServerError {
     fn from(_: oneshot::Canceled) -> Self {
         RpcServerError::RequestCanceled
@@ -53,7 +53,8 @@ use crate::{
     protocol::{ProtocolEvent, ProtocolId, ProtocolNotification, ProtocolNotificationRx},
     Bytes,
 };
-use futures::{channel::mpsc, AsyncRead, AsyncWrite, Sink, SinkExt, StreamExt};
+use bytes::BytesMut;
+use futures::{channel::mpsc, future, future::Either, AsyncRead, AsyncWrite, Sink, SinkExt, Stream, StreamExt};
 use log::*;
 use prost::Message;
 use std::{
@@ -148,6 +149,7 @@ impl Default for RpcServer {
 #[derive(Clone)]
 pub struct RpcServerBuilder {
     maximum_simultaneous_sessions: Option<usize>,
+    maximum_frame_size: usize,
     minimum_client_deadline: Duration,
     handshake_timeout: Duration,
     shutdown_signal: OptionalShutdownSignal,
@@ -168,6 +170,14 @@ impl RpcServerBuilder {
         self
     }
 
+    /// Set the maximum size of a single inbound or outbound frame. Inbound requests that exceed this size cause the
+    /// session to be closed, and responses that exceed it are replaced with an error response.
+    /// Default: [RPC_MAX_FRAME_SIZE](super::RPC_MAX_FRAME_SIZE)
+    pub fn with_maximum_frame_size(mut self, size: usize) -> Self {
+        self.maximum_frame_size = size;
+        self
+    }
+
     pub fn with_minimum_client_deadline(mut self, deadline: Duration) -> Self {
         self.minimum_client_deadline = deadline;
         self
@@ -192,6 +202,7 @@ impl Default for RpcServerBuilder {
     fn default() -> Self {
         Self {
             maximum_simultaneous_sessions: Some(1000),
+            maximum_frame_size: RPC_MAX_FRAME_SIZE,
             minimum_client_deadline: Duration::from_secs(1),
             handshake_timeout: Duration::from_secs(15),
             shutdown_signal: Default::default(),
@@ -310,7 +321,7 @@ where
                     node_id
                 );
 
-                let framed = framing::canonical(substream, RPC_MAX_FRAME_SIZE);
+                let framed = framing::canonical(substream, self.config.maximum_frame_size);
                 match self.try_initiate_service(notification.protocol, node_id, framed).await {
                     Ok(_) => {},
                     Err(err) => {
@@ -413,31 +424,72 @@ where
     async fn run(&mut self) -> Result<(), RpcServerError> {
         let (mut sink, stream) = self.framed.take().unwrap().split();
         let mut stream = stream.fuse().take_until(self.shutdown_signal.clone());
+        let mut next_frame = None;
+
+        loop {
+            let frame = match next_frame.take() {
+                Some(frame) => frame,
+                None => match stream.next().await {
+                    Some(result) => result?,
+                    None => break,
+                },
+            };
 
-        while let Some(result) = stream.next().await {
             let start = Instant::now();
-            if let Err(err) = self.handle(&mut sink, result?.freeze()).await {
-                sink.close().await?;
-                return Err(err);
+            match self.handle(&mut sink, &mut stream, frame.freeze()).await {
+                Ok(CallOutcome::Completed) => {
+                    debug!(target: LOG_TARGET, "RPC request completed in {:.0?}", start.elapsed());
+                },
+                Ok(CallOutcome::Cancelled(Some(frame))) => {
+                    debug!(
+                        target: LOG_TARGET,
+                        "(Peer = `{}`) RPC request cancelled by the client after {:.0?}",
+                        self.node_id,
+                        start.elapsed()
+                    );
+                    next_frame = Some(frame);
+                },
+                Ok(CallOutcome::Cancelled(None)) => {
+                    debug!(
+                        target: LOG_TARGET,
+                        "(Peer = `{}`) Client closed the session while a request was in progress", self.node_id
+                    );
+                    break;
+                },
+                Err(err) => {
+                    sink.close().await?;
+                    return Err(err);
+                },
             }
-            debug!(target: LOG_TARGET, "RPC request completed in {:.0?}", start.elapsed());
         }
 
         sink.close().await?;
         Ok(())
     }
 
-    fn create_request_context(&self) -> RequestContext {
-        RequestContext::new(self.node_id.clone(), Box::new(self.comms_provider.clone()))
+    fn create_request_context(&self, deadline: Duration) -> RequestContext {
+        RequestContext::new(self.node_id.clone(), Box::new(self.comms_provider.clone())).with_deadline(deadline)
     }
 
-    async fn handle<W>(&mut self, sink: &mut W, mut request: Bytes) -> Result<(), RpcServerError>
-    where W: Sink<Bytes, Error = io::Error> + Unpin {
+    /// Handles a single request, writing the response(s) to the sink. The client may give up on a request before it
+    /// has completed, either by sending its next request or by closing the session. In that case, the service call is
+    /// dropped (cancelling any in-progress work) and `CallOutcome::Cancelled` is returned.
+    async fn handle<W, R>(
+        &mut self,
+        sink: &mut W,
+        stream: &mut R,
+        mut request: Bytes,
+    ) -> Result<CallOutcome, RpcServerError>
+    where
+        W: Sink<Bytes, Error = io::Error> + Unpin,
+        R: Stream<Item = Result<BytesMut, io::Error>> + Unpin,
+    {
         let decoded_msg = proto::rpc::RpcRequest::decode(&mut request)?;
 
         let request_id = decoded_msg.request_id;
         let method = decoded_msg.method.into();
         let deadline = Duration::from_secs(decoded_msg.deadline);
+        let max_frame_size = self.config.maximum_frame_size;
 
         // The client side deadline MUST be greater or equal to the minimum_client_deadline
         if deadline < self.config.minimum_client_deadline {
@@ -457,7 +509,7 @@ where
                 message: status.details_bytes(),
             };
             sink.send(bad_request.to_encoded_bytes().into()).await?;
-            return Ok(());
+            return Ok(CallOutcome::Completed);
         }
 
         debug!(
@@ -465,25 +517,38 @@ where
             "[Peer=`{}`] Got request {}", self.node_id, decoded_msg
         );
 
-        let req = Request::with_context(self.create_request_context(), method, decoded_msg.message.into());
+        let req = Request::with_context(
+            self.create_request_context(deadline),
+            method,
+            decoded_msg.message.into(),
+        );
 
-        let service_result = time::timeout(deadline, self.service.call(req)).await;
-        let service_result = match service_result {
-            Ok(v) => v,
-            Err(_) => {
+        let service_call = time::timeout(deadline, self.service.call(req));
+        futures::pin_mut!(service_call);
+        let service_result = match future::select(service_call, stream.next()).await {
+            Either::Left((Ok(v), _)) => v,
+            Either::Left((Err(_), _)) => {
                 warn!(
                     target: LOG_TARGET,
                     "RPC service was not able to complete within the deadline ({:.0?}). Request aborted.", deadline
                 );
-                return Ok(());
+                return Ok(CallOutcome::Completed);
             },
+            Either::Right((frame, _)) => return Self::client_cancelled(request_id, frame),
         };
 
         match service_result {
             Ok(body) => {
                 let mut message = body.into_message();
                 loop {
-                    match time::timeout(deadline, message.next()).await {
+                    let next_item = time::timeout(deadline, message.next());
+                    futures::pin_mut!(next_item);
+                    let next_item = match future::select(next_item, stream.next()).await {
+                        Either::Left((next_item, _)) => next_item,
+                        Either::Right((frame, _)) => return Self::client_cancelled(request_id, frame),
+                    };
+
+                    match next_item {
                         Ok(Some(msg)) => {
                             let resp = match msg {
                                 Ok(msg) => {
@@ -510,8 +575,23 @@ where
                                 },
                             };
 
-                            if !send_response_checked(sink, request_id, resp).await? {
-                                break;
+                            // A client that has stopped reading the stream may leave us waiting indefinitely for
+                            // capacity, so the client deadline also applies to sending each message
+                            match time::timeout(deadline, send_response_checked(sink, request_id, resp, max_frame_size))
+                                .await
+                            {
+                                Ok(is_sent) => {
+                                    if !is_sent? {
+                                        break;
+                                    }
+                                },
+                                Err(_) => {
+                                    debug!(
+                                        target: LOG_TARGET,
+                                        "Client did not accept the next message within the deadline ({:.0?})", deadline
+                                    );
+                                    break;
+                                },
                             }
                         },
                         Ok(None) => break,
@@ -539,27 +619,52 @@ where
             },
         }
 
-        Ok(())
+        Ok(CallOutcome::Completed)
+    }
+
+    fn client_cancelled(
+        request_id: u32,
+        frame: Option<Result<BytesMut, io::Error>>,
+    ) -> Result<CallOutcome, RpcServerError>
+    {
+        debug!(
+            target: LOG_TARGET,
+            "Client gave up on request {} before it completed. Abandoning the request.", request_id
+        );
+        match frame {
+            Some(frame) => Ok(CallOutcome::Cancelled(Some(frame?))),
+            None => Ok(CallOutcome::Cancelled(None)),
+        }
     }
 }
 
-/// Sends an RpcResponse on the given Sink. If the size of the message exceeds the RPC_MAX_FRAME_SIZE, an error is
+/// The result of handling a single RPC request
+enum CallOutcome {
+    /// The request was handled to completion
+    Completed,
+    /// The client gave up on the request. This contains the next request frame sent by the client, or None if the
+    /// client closed the session.
+    Cancelled(Option<BytesMut>),
+}
+
+/// Sends an RpcResponse on the given Sink. If the size of the message exceeds `max_frame_size`, an error is
 /// returned to the client and false is returned from this function, otherwise the message is sent and true is returned
 #[inline]
 async fn send_response_checked<S>(
     sink: &mut S,
     request_id: u32,
     resp: proto::rpc::RpcResponse,
+    max_frame_size: usize,
 ) -> Result<bool, S::Error>
 where
     S: Sink<Bytes> + Unpin,
 {
     match resp.to_encoded_bytes() {
-        buf if buf.len() > RPC_MAX_FRAME_SIZE => {
+        buf if buf.len() > max_frame_size => {
             let msg = format!(
                 "This node tried to return a message that exceeds the maximum frame size. Max = {:.4} MiB, Got = \
                  {:.4} MiB",
-                RPC_MAX_FRAME_SIZE as f32 / (1024.0 * 1024.0),
+                max_frame_size as f32 / (1024.0 * 1024.0),
                 buf.len() as f32 / (1024.0 * 1024.0)
             );
             warn!(target: LOG_TARGET, "{}", msg);
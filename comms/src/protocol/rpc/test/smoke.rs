@@ -202,6 +202,66 @@ async fn request_reponse_errors_and_streaming() // a.k.a  smoke test
     server_hnd.await.unwrap().unwrap();
 }
 
+#[runtime::test_basic]
+async fn streaming_backpressure() {
+    let greetings = &["Sawubona", "Jambo", "Bonjour", "Hello", "Molo", "Olá"];
+    let (socket, _, _, _shutdown) = setup(GreetingService::new(greetings), 1).await;
+
+    let framed = framing::canonical(socket, 1024);
+    let mut client = GreetingClient::builder()
+        .with_stream_buffer_size(0)
+        .connect(framed)
+        .await
+        .unwrap();
+
+    let mut resp = client.get_greetings(greetings.len() as u32).await.unwrap();
+    let mut received = Vec::new();
+    while let Some(greeting) = resp.next().await {
+        // Slow consumer - the client will not read further messages until this one is consumed
+        time::delay_for(Duration::from_millis(10)).await;
+        received.push(greeting.unwrap());
+    }
+    assert_eq!(received, greetings);
+
+    // Check that the session is still usable after the stream has completed
+    let resp = client
+        .say_hello(SayHelloRequest {
+            name: "Bob".to_string(),
+            language: 3,
+        })
+        .await
+        .unwrap();
+    assert_eq!(resp.greeting, "Hello Bob");
+}
+
+#[runtime::test_basic]
+async fn client_abandons_stream() {
+    let greetings = &["Sawubona", "Jambo", "Bonjour", "Hello", "Molo", "Olá"];
+    let (socket, _, _, _shutdown) = setup(GreetingService::new(greetings), 1).await;
+
+    let framed = framing::canonical(socket, 1024);
+    let mut client = GreetingClient::builder()
+        .with_stream_buffer_size(0)
+        .connect(framed)
+        .await
+        .unwrap();
+
+    let mut resp = client.get_greetings(greetings.len() as u32).await.unwrap();
+    let first = resp.next().await.unwrap().unwrap();
+    assert_eq!(first, "Sawubona");
+    drop(resp);
+
+    // The server abandons the stream and responds to the next request. Any remaining stream messages are discarded.
+    let resp = client
+        .say_hello(SayHelloRequest {
+            name: "Alice".to_string(),
+            language: 4,
+        })
+        .await
+        .unwrap();
+    assert_eq!(resp.greeting, "Molo Alice");
+}
+
 #[runtime::test_basic]
 async fn response_too_big() {
     let (socket, _, _, _shutdown) = setup(GreetingService::new(&[]), 1).await;
@@ -223,6 +283,37 @@ async fn response_too_big() {
     let _ = client.reply_with_msg_of_size(max_size as u64).await.unwrap();
 }
 
+#[runtime::test_basic]
+async fn request_too_big() {
+    let (socket, _, _, _shutdown) = setup(GreetingService::new(&["Sawubona", "Jambo"]), 1).await;
+
+    let framed = framing::canonical(socket, 1024);
+    let mut client = GreetingClient::builder()
+        .with_max_frame_size(1024)
+        .connect(framed)
+        .await
+        .unwrap();
+
+    let err = client
+        .say_hello(SayHelloRequest {
+            name: iter::repeat('a').take(1025).collect(),
+            language: 0,
+        })
+        .await
+        .unwrap_err();
+    assert!(matches!(err, RpcError::RequestTooLarge { size, max_frame_size: 1024 } if size > 1024));
+
+    // The request was rejected before being sent so the session is still usable
+    let resp = client
+        .say_hello(SayHelloRequest {
+            name: "Alice".to_string(),
+            language: 1,
+        })
+        .await
+        .unwrap();
+    assert_eq!(resp.greeting, "Jambo Alice");
+}
+
 #[runtime::test_basic]
 async fn server_shutdown_after_connect() {
     let (socket, _, _, mut shutdown) = setup(GreetingService::new(&[]), 1).await;
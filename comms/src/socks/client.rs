@@ -22,9 +22,10 @@
 
 // Acknowledgement to @sticnarf for tokio-socks on which this code is based
 use super::error::SocksError;
-use data_encoding::BASE32;
+use data_encoding::{BASE32, HEXLOWER};
 use futures::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use multiaddr::{Multiaddr, Protocol};
+use rand::{rngs::OsRng, RngCore};
 use std::{
     borrow::Cow,
     net::{Ipv4Addr, Ipv6Addr},
@@ -35,17 +36,38 @@ pub type Result<T> = std::result::Result<T, SocksError>;
 /// Authentication methods
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Authentication {
+    /// No authentication
     None,
+    /// Username and password authentication
     Password(String, String),
+    /// Username and password authentication using randomly generated credentials for each connection. The Tor proxy
+    /// isolates streams that use different SOCKS credentials onto separate circuits (IsolateSOCKSAuth), so this
+    /// prevents connections from being linked by a shared circuit.
+    StreamIsolation,
 }
 
 impl Authentication {
     fn id(&self) -> u8 {
         match self {
-            Authentication::Password(_, _) => 0x02,
+            Authentication::Password(_, _) | Authentication::StreamIsolation => 0x02,
             Authentication::None => 0x00,
         }
     }
+
+    /// Returns the credentials to use for a single connection. `StreamIsolation` is resolved to a random username and
+    /// password, all other methods are returned as is.
+    fn for_connection(self) -> Self {
+        match self {
+            Authentication::StreamIsolation => {
+                let mut buf = [0u8; 16];
+                OsRng.fill_bytes(&mut buf);
+                let username = HEXLOWER.encode(&buf[..8]);
+                let password = HEXLOWER.encode(&buf[8..]);
+                Authentication::Password(username, password)
+            },
+            auth => auth,
+        }
+    }
 }
 
 impl Default for Authentication {
@@ -82,7 +104,10 @@ where TSocket: AsyncRead + AsyncWrite + Unpin
         }
     }
 
+    /// Set the authentication method used by this client. If `Authentication::StreamIsolation` is given, random
+    /// credentials are generated for this client.
     pub fn with_authentication(&mut self, auth: Authentication) -> Result<&mut Self> {
+        let auth = auth.for_connection();
         Self::validate_auth(&auth)?;
         self.protocol.set_authentication(auth);
         Ok(self)
@@ -127,7 +152,7 @@ where TSocket: AsyncRead + AsyncWrite + Unpin
 
     fn validate_auth(auth: &Authentication) -> Result<()> {
         match auth {
-            Authentication::None => {},
+            Authentication::None | Authentication::StreamIsolation => {},
             Authentication::Password(username, password) => {
                 let username_len = username.as_bytes().len();
                 if username_len < 1 || username_len > 255 {
@@ -325,7 +350,7 @@ where TSocket: AsyncRead + AsyncWrite + Unpin
                 self.buf[1..3].copy_from_slice(&[1, 0x00]);
                 self.len = 3;
             },
-            Authentication::Password { .. } => {
+            Authentication::Password(_, _) | Authentication::StreamIsolation => {
                 self.buf[1..4].copy_from_slice(&[2, 0x00, 0x02]);
                 self.len = 4;
             },
@@ -353,6 +378,9 @@ where TSocket: AsyncRead + AsyncWrite + Unpin
                 self.buf[(3 + username_len)..self.len].copy_from_slice(password_bytes);
             },
             Authentication::None => unreachable!(),
+            Authentication::StreamIsolation => {
+                unreachable!("StreamIsolation is resolved to password authentication in with_authentication")
+            },
         }
     }
 
@@ -458,3 +486,26 @@ where TSocket: AsyncRead + AsyncWrite + Unpin
         Ok(self.len - self.ptr)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stream_isolation_generates_unique_credentials() {
+        let a = Authentication::StreamIsolation.for_connection();
+        let b = Authentication::StreamIsolation.for_connection();
+        match (&a, &b) {
+            (Authentication::Password(a_user, a_pass), Authentication::Password(b_user, b_pass)) => {
+                assert_ne!(a_user, b_user);
+                assert_ne!(a_pass, b_pass);
+            },
+            _ => panic!("StreamIsolation did not resolve to password authentication"),
+        }
+        assert_eq!(a.id(), 0x02);
+
+        let auth = Authentication::Password("user".to_string(), "pass".to_string());
+        assert_eq!(auth.clone().for_connection(), auth);
+        assert_eq!(Authentication::None.for_connection(), Authentication::None);
+    }
+}
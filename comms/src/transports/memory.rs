@@ -26,7 +26,7 @@
 use crate::{
     memsocket,
     memsocket::{MemoryListener, MemorySocket},
-    transports::Transport,
+    transports::{RemoteAddress, Transport},
 };
 use futures::{future, stream::Stream, Future};
 use multiaddr::{Multiaddr, Protocol};
@@ -81,6 +81,13 @@ impl Transport for MemoryTransport {
     }
 }
 
+impl RemoteAddress for MemorySocket {
+    fn remote_address(&self) -> Option<Multiaddr> {
+        // A memory socket does not know the port of the socket at its remote end
+        None
+    }
+}
+
 fn parse_addr(addr: &Multiaddr) -> io::Result<u16> {
     let mut iter = addr.iter();
 
@@ -62,3 +62,10 @@ pub trait Transport {
     /// Connect (dial) to the given multiaddr
     fn dial(&self, addr: Multiaddr) -> Result<Self::DialFuture, Self::Error>;
 }
+
+/// A connected socket that knows the address of the remote end of its connection
+pub trait RemoteAddress {
+    /// The address that this node sees for the remote end of the connection, or None if it is not known or is not
+    /// the address of the peer itself e.g. because the connection goes through a proxy
+    fn remote_address(&self) -> Option<Multiaddr>;
+}
@@ -76,7 +76,7 @@ impl SocksTransport {
         client
             .connect(&dest_addr)
             .await
-            .map(|(socket, _)| socket)
+            .map(|(socket, _)| socket.into_proxied())
             .map_err(|err| io::Error::new(io::ErrorKind::Other, err))
     }
 }
@@ -20,7 +20,7 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use super::{dns::DnsResolver, Transport};
+use super::{dns::DnsResolver, RemoteAddress, Transport};
 use crate::{
     transports::dns::{DnsResolverRef, SystemDnsResolver},
     utils::multiaddr::socketaddr_to_multiaddr,
@@ -205,11 +205,32 @@ impl Stream for TcpInbound {
 /// `futures-rs` AsyncRead/Write
 pub struct TcpSocket {
     inner: TcpStream,
+    /// True if the stream is connected to a proxy rather than to the peer
+    is_proxied: bool,
 }
 
 impl TcpSocket {
     pub fn new(stream: TcpStream) -> Self {
-        Self { inner: stream }
+        Self {
+            inner: stream,
+            is_proxied: false,
+        }
+    }
+
+    /// Marks this socket as connected to the peer through a proxy, so that the proxy address is not reported as the
+    /// address of the peer
+    pub fn into_proxied(mut self) -> Self {
+        self.is_proxied = true;
+        self
+    }
+}
+
+impl RemoteAddress for TcpSocket {
+    fn remote_address(&self) -> Option<Multiaddr> {
+        if self.is_proxied {
+            return None;
+        }
+        self.inner.peer_addr().ok().map(|addr| socketaddr_to_multiaddr(&addr))
     }
 }
 
@@ -235,7 +256,7 @@ impl AsyncRead for TcpSocket {
 
 impl From<TcpStream> for TcpSocket {
     fn from(stream: TcpStream) -> Self {
-        Self { inner: stream }
+        Self::new(stream)
     }
 }
 
@@ -258,4 +279,18 @@ mod test {
         assert_eq!(tcp.ttl, Some(789));
         assert_eq!(tcp.keepalive, Some(Some(Duration::from_millis(100))));
     }
+
+    #[crate::runtime::test_basic]
+    async fn remote_address() {
+        let tcp = TcpTransport::new();
+        let (_listener, addr) = tcp
+            .listen("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+            .unwrap()
+            .await
+            .unwrap();
+        let socket = tcp.dial(addr.clone()).unwrap().await.unwrap();
+        assert_eq!(socket.remote_address(), Some(addr));
+        // A proxied socket is connected to the proxy, not to the peer
+        assert_eq!(socket.into_proxied().remote_address(), None);
+    }
 }
@@ -5,6 +5,7 @@ use crate::{
     lmdb_store::error::LMDBError,
 };
 use lmdb_zero::{
+    copy,
     db,
     error::{self, LmdbResultExt},
     open,
@@ -339,6 +340,15 @@ impl LMDBStore {
         Ok(())
     }
 
+    /// Writes a copy of the environment to the directory at `path`, which must exist and be empty. Free pages are left
+    /// out of the copy, so it is only as large as the data that it holds.
+    pub fn copy_compacted<P: AsRef<Path>>(&self, path: P) -> Result<(), LMDBError> {
+        let path = path.as_ref().to_str().ok_or_else(|| LMDBError::InvalidPath)?;
+        debug!(target: LOG_TARGET, "({}) Writing compacted copy to {}", self.path, path);
+        self.env.copy(path, copy::COMPACT)?;
+        Ok(())
+    }
+
     pub fn log_info(&self) {
         match self.env.info() {
             Err(e) => warn!(